@@ -9,17 +9,35 @@ use clap::{ArgAction, Args, Parser, Subcommand};
 use tracing::{info, warn};
 use tracing_subscriber::prelude::*;
 
+use videnoa_core::benchmark::{run_benchmark, AutoModelSource, BenchmarkSpec, BenchmarkStore};
+use videnoa_core::chunking;
 use videnoa_core::config::{config_path, data_dir, initialize_data_dir, AppConfig};
-use videnoa_core::executor::SequentialExecutor;
+use videnoa_core::executor::{set_default_node_timeout, SequentialExecutor};
 use videnoa_core::graph::PipelineGraph;
+use videnoa_core::jellyfin::resolve_server_refs;
+use videnoa_core::job_logs;
+use videnoa_core::lint::lint_graph;
 use videnoa_core::logging::{
     self, FileSinkPlan, LoggingInitOptions, PanicHookInstallPlan, RuntimeLogMode,
     DEFAULT_LOG_FILTER,
 };
+use videnoa_core::model_registry::{ModelRegistry, ModelType};
 use videnoa_core::nodes::compile_context::VideoCompileContext;
+use videnoa_core::nodes::external_command::set_allowed_commands;
+use videnoa_core::nodes::http_request::resolve_auth_secrets;
 use videnoa_core::registry::{register_all_nodes, NodeRegistry};
+use videnoa_core::runtime::memory::{set_memory_budget, MemoryBudget};
+use videnoa_core::runtime::session_pool::{session_pool, spawn_warmup};
+use videnoa_core::secrets::SecretStore;
+use videnoa_core::services::NodeServices;
 use videnoa_core::types::PortData;
-use videnoa_core::server::{app_router_with_static, app_state_with_config};
+use videnoa_core::vapoursynth_export::{export_vapoursynth, SCRIPT_FILE_EXTENSION};
+use videnoa_core::server::{
+    api_router, app_router_with_static, app_state_with_config, spawn_community_preset_sync,
+    spawn_config_watcher, spawn_job_retention, spawn_log_size_limit, spawn_models_watcher,
+    spawn_performance_sampler, spawn_power_manager,
+};
+use videnoa_core::worker::WorkerOptions;
 
 #[derive(Parser)]
 #[command(
@@ -56,11 +74,31 @@ struct Cli {
 
     #[arg(long)]
     data_dir: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Serve the HTTP API only, without the embedded web frontend"
+    )]
+    headless: bool,
+
+    #[arg(
+        long,
+        help = "Reject requests that start jobs or change config or files"
+    )]
+    read_only: bool,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     Run(RunArgs),
+    /// Execute jobs dispatched by a remote videnoa server.
+    Worker(WorkerArgs),
+    /// Measure super-resolution model speed on this machine for `model_path: auto`.
+    Bench(BenchArgs),
+    /// Convert a workflow into a VapourSynth script that uses vs-mlrt.
+    Export(ExportArgs),
+    /// Check a workflow without running it.
+    Validate(ValidateArgs),
 }
 
 #[derive(Args)]
@@ -77,14 +115,102 @@ struct RunArgs {
         help = "Pass parameters to WorkflowInput nodes (repeatable, e.g. --param key=value)"
     )]
     params: Vec<String>,
+    #[arg(
+        long,
+        help = "Process the input in scene-aligned chunks, resuming from <output>.chunks.json if present"
+    )]
+    chunked: bool,
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = chunking::DEFAULT_TARGET_CHUNK_SECS,
+        requires = "chunked",
+        help = "Target chunk length for --chunked"
+    )]
+    chunk_secs: f64,
+}
+
+#[derive(Args)]
+struct WorkerArgs {
+    #[arg(long, value_name = "URL", help = "API server to claim jobs from")]
+    server: String,
+    #[arg(long, help = "Name shown in the server's worker list (defaults to the host name)")]
+    name: Option<String>,
+    #[arg(
+        long,
+        value_name = "TOKEN",
+        help = "Bearer token matching the server's workers.token_secret (or VIDENOA_WORKER_TOKEN)"
+    )]
+    token: Option<String>,
+    #[arg(long, default_value_t = 5, help = "Seconds between claim attempts when idle")]
+    poll_interval_secs: u64,
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    #[arg(
+        long = "model",
+        value_name = "FILE",
+        help = "Model to benchmark (repeatable; defaults to every downloaded super-resolution model)"
+    )]
+    models: Vec<PathBuf>,
+    #[arg(long, help = "Model scale, for models missing from the catalog")]
+    scale: Option<u32>,
+    #[arg(long, default_value_t = 1920, help = "Input frame width")]
+    width: u32,
+    #[arg(long, default_value_t = 1080, help = "Input frame height")]
+    height: u32,
+    #[arg(long, default_value_t = 10, help = "Frames timed per model")]
+    frames: usize,
+    #[arg(long, default_value = "cuda", help = "Inference backend (cuda or tensorrt)")]
+    backend: String,
+    #[arg(long, default_value_t = 0, help = "Tile size (0 = whole frame)")]
+    tile_size: u32,
+}
+
+#[derive(Args)]
+struct ExportArgs {
+    #[arg(help = "Path to workflow JSON file")]
+    workflow: PathBuf,
+    #[arg(
+        short = 'o',
+        long,
+        help = "Script to write (defaults to the workflow path with a .vpy extension)"
+    )]
+    output: Option<PathBuf>,
+    #[arg(
+        long = "param",
+        value_name = "KEY=VALUE",
+        help = "Pass parameters to WorkflowInput nodes (repeatable, e.g. --param key=value)"
+    )]
+    params: Vec<String>,
+}
+
+#[derive(Args)]
+struct ValidateArgs {
+    #[arg(help = "Path to workflow JSON file")]
+    workflow: PathBuf,
+    #[arg(
+        long = "param",
+        value_name = "KEY=VALUE",
+        help = "Pass parameters to WorkflowInput nodes (repeatable, e.g. --param key=value)"
+    )]
+    params: Vec<String>,
+    #[arg(
+        long,
+        help = "Also report likely mistakes that do not stop the workflow from running"
+    )]
+    lint: bool,
 }
 
 pub async fn run_from_env() -> Result<()> {
     let cli = Cli::parse();
-    let mode = if cli.command.is_some() {
-        RuntimeLogMode::Cli
-    } else {
-        RuntimeLogMode::Server
+    let mode = match cli.command {
+        Some(Commands::Run(_))
+        | Some(Commands::Bench(_))
+        | Some(Commands::Export(_))
+        | Some(Commands::Validate(_)) => RuntimeLogMode::Cli,
+        Some(Commands::Worker(_)) | None => RuntimeLogMode::Server,
     };
     let resolved_data_dir = data_dir(cli.data_dir.as_deref());
 
@@ -100,9 +226,30 @@ pub async fn run_from_env() -> Result<()> {
 
     match cli.command {
         Some(Commands::Run(run)) => {
-            run_workflow(run.workflow, run.input, run.output, run.params).await
+            run_workflow(
+                run.workflow,
+                run.input,
+                run.output,
+                run.params,
+                run.chunked.then_some(run.chunk_secs),
+                &resolved_data_dir,
+            )
+            .await
+        }
+        Some(Commands::Worker(worker)) => run_worker(worker, resolved_data_dir).await,
+        Some(Commands::Bench(bench)) => run_bench(bench, &resolved_data_dir),
+        Some(Commands::Export(export)) => run_export(export, &resolved_data_dir),
+        Some(Commands::Validate(validate)) => run_validate(validate, &resolved_data_dir),
+        None => {
+            run_server(
+                cli.port,
+                cli.host,
+                resolved_data_dir,
+                cli.headless,
+                cli.read_only,
+            )
+            .await
         }
-        None => run_server(cli.port, cli.host, resolved_data_dir).await,
     }
 }
 
@@ -149,12 +296,20 @@ fn init_logging(
         );
     }
 
+    // Logging starts before the config is formally loaded, so read just the
+    // retention setting here and fall back to defaults on any error.
+    let retention_files = data_dir
+        .and_then(|dir| AppConfig::load_from_path(&config_path(dir)).ok())
+        .map(|config| config.logging.retention_files)
+        .unwrap_or(logging::DEFAULT_LOG_RETENTION_FILES);
+
     let init_options = LoggingInitOptions {
         mode,
         data_dir: data_dir.map(Path::to_path_buf),
         verbose,
         cli_log_filter: cli_log_filter.map(ToString::to_string),
         rust_log_env: std::env::var("RUST_LOG").ok(),
+        retention_files,
         ..Default::default()
     };
     let init_plan = logging::compose_logging_init_plan(&init_options);
@@ -177,7 +332,8 @@ fn init_logging(
                         .with_ansi(false)
                         .with_writer(logging::redacting_make_writer(ready.appender))
                         .with_filter(file_env_filter),
-                );
+                )
+                .with(job_logs::job_log_layer().with_filter(job_logs::job_log_targets()));
 
             if let Err(error) = tracing::subscriber::set_global_default(subscriber) {
                 eprintln!(
@@ -194,11 +350,13 @@ fn init_logging(
             let reason = fallback.reason;
 
             let console_env_filter = parse_env_filter_with_fallback(&console_filter, "console");
-            let subscriber = tracing_subscriber::registry().with(
-                tracing_subscriber::fmt::layer()
-                    .with_writer(std::io::stderr)
-                    .with_filter(console_env_filter),
-            );
+            let subscriber = tracing_subscriber::registry()
+                .with(
+                    tracing_subscriber::fmt::layer()
+                        .with_writer(std::io::stderr)
+                        .with_filter(console_env_filter),
+                )
+                .with(job_logs::job_log_layer().with_filter(job_logs::job_log_targets()));
 
             if let Err(error) = tracing::subscriber::set_global_default(subscriber) {
                 eprintln!(
@@ -268,6 +426,8 @@ async fn run_server(
     port_override: Option<u16>,
     host_override: Option<String>,
     data_dir: PathBuf,
+    headless: bool,
+    read_only: bool,
 ) -> Result<()> {
     if let Err(e) = initialize_data_dir(&data_dir) {
         warn!(error = %e, "Failed to initialize data directory");
@@ -287,27 +447,43 @@ async fn run_server(
     let host = host_override.unwrap_or_else(|| config.server.host.clone());
 
     let state = app_state_with_config(config, cfg_path, data_dir);
-
-    #[cfg(not(debug_assertions))]
-    {
-        info!("Serving embedded frontend assets");
+    if read_only {
+        info!("Read-only mode — rejecting requests that change anything");
+        state.force_read_only();
     }
-
-    #[cfg(debug_assertions)]
-    let static_path = {
-        use std::path::Path;
-        let dir = Path::new("web/dist");
-        if dir.is_dir() {
-            Some(dir)
-        } else {
-            info!("web/dist/ not found — serving API only (run `cd web && npm run build` first)");
-            None
+    let _config_watcher = spawn_config_watcher(&state);
+    let _models_watcher = spawn_models_watcher(&state);
+    let _job_retention = spawn_job_retention(&state);
+    let _log_size_limit = spawn_log_size_limit(&state);
+    let _performance_sampler = spawn_performance_sampler(&state);
+    let _community_preset_sync = spawn_community_preset_sync(&state);
+    let _power_manager = spawn_power_manager(&state);
+
+    let app = if headless {
+        info!("Headless mode — serving API only");
+        api_router(state)
+    } else {
+        #[cfg(not(debug_assertions))]
+        {
+            info!("Serving embedded frontend assets");
         }
-    };
-    #[cfg(not(debug_assertions))]
-    let static_path: Option<&std::path::Path> = None;
 
-    let app = app_router_with_static(state, static_path);
+        #[cfg(debug_assertions)]
+        let static_path = {
+            use std::path::Path;
+            let dir = Path::new("web/dist");
+            if dir.is_dir() {
+                Some(dir)
+            } else {
+                info!("web/dist/ not found — serving API only (run `cd web && npm run build` first)");
+                None
+            }
+        };
+        #[cfg(not(debug_assertions))]
+        let static_path: Option<&std::path::Path> = None;
+
+        app_router_with_static(state, static_path)
+    };
 
     let addr = format!("{host}:{port}");
     info!(%addr, "Starting videnoa server");
@@ -317,6 +493,187 @@ async fn run_server(
     Ok(())
 }
 
+async fn run_worker(args: WorkerArgs, data_dir: PathBuf) -> Result<()> {
+    if let Err(e) = initialize_data_dir(&data_dir) {
+        warn!(error = %e, "Failed to initialize data directory");
+    }
+    let config = match AppConfig::load_from_path(&config_path(&data_dir)) {
+        Ok(config) => config,
+        Err(err) => {
+            warn!(error = %err, "Failed to load config file, using defaults");
+            AppConfig::default()
+        }
+    };
+
+    let name = args
+        .name
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .unwrap_or_else(|| "videnoa-worker".to_string());
+    let token = args
+        .token
+        .or_else(|| std::env::var("VIDENOA_WORKER_TOKEN").ok());
+
+    set_allowed_commands(config.commands.allowed.clone());
+    set_default_node_timeout(config.jobs.node_timeout_secs);
+    set_memory_budget(MemoryBudget::from_config(&config.memory));
+    session_pool().set_capacity(config.performance.session_cache_size);
+    spawn_warmup(
+        config.performance.warmup.clone(),
+        config.paths.models_dir.clone(),
+        config.paths.trt_cache_dir.clone(),
+    );
+
+    let auto_models = auto_models(&config, &data_dir);
+    videnoa_core::worker::run_worker(WorkerOptions {
+        server_url: args.server,
+        name,
+        token,
+        trt_cache_dir: config.paths.trt_cache_dir,
+        auto_models,
+        plugins_dir: config.paths.plugins_dir,
+        profiling_enabled: config.performance.profiling_enabled,
+        torrents: config.torrents,
+        poll_interval: std::time::Duration::from_secs(args.poll_interval_secs.max(1)),
+    })
+    .await
+}
+
+/// Candidate models and the benchmark cache for `model_path: auto`.
+fn auto_models(config: &AppConfig, data_dir: &Path) -> AutoModelSource {
+    let benchmarks = BenchmarkStore::open(data_dir)
+        .inspect_err(|err| warn!(error = %err, "Benchmark cache unavailable"))
+        .ok();
+    AutoModelSource {
+        models_dir: config.paths.models_dir.clone(),
+        benchmarks,
+    }
+}
+
+fn run_bench(args: BenchArgs, data_dir: &Path) -> Result<()> {
+    let config = AppConfig::load_from_path(&config_path(data_dir)).unwrap_or_default();
+    let models_dir = config.paths.models_dir.clone();
+    let mut registry = ModelRegistry::with_builtin_models(models_dir.clone());
+    registry.discover()?;
+    let catalog_scale = |path: &Path| {
+        let filename = path.file_name()?.to_str()?;
+        registry
+            .list()
+            .iter()
+            .find(|entry| entry.filename == filename)
+            .and_then(|entry| entry.scale)
+    };
+
+    let models: Vec<PathBuf> = if args.models.is_empty() {
+        registry
+            .list_by_type(ModelType::SuperResolution)
+            .into_iter()
+            .map(|entry| models_dir.join(&entry.filename))
+            .filter(|path| path.is_file())
+            .collect()
+    } else {
+        args.models.clone()
+    };
+    if models.is_empty() {
+        bail!(
+            "no downloaded super-resolution models in {}",
+            models_dir.display()
+        );
+    }
+
+    let store = BenchmarkStore::open(data_dir)?;
+    println!(
+        "Benchmarking {} model(s) at {}x{} on {}",
+        models.len(),
+        args.width,
+        args.height,
+        args.backend
+    );
+    for model_path in models {
+        let Some(scale) = args.scale.or_else(|| catalog_scale(&model_path)) else {
+            bail!(
+                "cannot tell the scale of {}; pass --scale",
+                model_path.display()
+            );
+        };
+        let spec = BenchmarkSpec {
+            model_path: model_path.clone(),
+            scale,
+            backend: args.backend.clone(),
+            width: args.width,
+            height: args.height,
+            tile_size: args.tile_size,
+            frames: args.frames,
+            trt_cache_dir: Some(config.paths.trt_cache_dir.clone()),
+        };
+        match run_benchmark(&spec) {
+            Ok(record) => {
+                store.record(&record)?;
+                println!("  {:<48} {:>8.2} fps", record.model, record.fps);
+            }
+            Err(err) => println!("  {:<48} failed: {err:#}", model_path.display()),
+        }
+    }
+    Ok(())
+}
+
+/// Reads a workflow file, fills in `--param` values and validates it.
+fn load_validated_workflow(
+    workflow: &Path,
+    param_args: &[String],
+    registry: &NodeRegistry,
+) -> Result<PipelineGraph> {
+    let json_str = std::fs::read_to_string(workflow)
+        .with_context(|| format!("Failed to read workflow file: {}", workflow.display()))?;
+    let workflow_value: serde_json::Value = serde_json::from_str(&json_str)
+        .with_context(|| format!("Failed to parse workflow JSON: {}", workflow.display()))?;
+
+    let mut params = HashMap::new();
+    for item in param_args {
+        let (key, value) = item
+            .split_once('=')
+            .with_context(|| format!("invalid --param format '{}' (expected KEY=VALUE)", item))?;
+        params.insert(key.to_string(), value.to_string());
+    }
+    let workflow_value =
+        inject_params_into_workflow_input(&unwrap_workflow(workflow_value), &params)?;
+    let graph: PipelineGraph =
+        serde_json::from_value(workflow_value).context("Failed to parse workflow JSON")?;
+    graph
+        .validate(registry)
+        .context("Workflow validation failed")?;
+    Ok(graph)
+}
+
+fn run_export(args: ExportArgs, data_dir: &Path) -> Result<()> {
+    let registry = build_registry(data_dir);
+    let graph = load_validated_workflow(&args.workflow, &args.params, &registry)?;
+
+    let script = export_vapoursynth(&graph, &registry)?;
+    let output = args
+        .output
+        .unwrap_or_else(|| args.workflow.with_extension(SCRIPT_FILE_EXTENSION));
+    std::fs::write(&output, script)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+    info!("VapourSynth script written to {}", output.display());
+    Ok(())
+}
+
+fn run_validate(args: ValidateArgs, data_dir: &Path) -> Result<()> {
+    let registry = build_registry(data_dir);
+    let graph = load_validated_workflow(&args.workflow, &args.params, &registry)?;
+    println!("{}: valid", args.workflow.display());
+
+    if args.lint {
+        let warnings = lint_graph(&graph, &registry);
+        for warning in &warnings {
+            println!("  warning: {warning}");
+        }
+        println!("{} lint warning(s)", warnings.len());
+    }
+    Ok(())
+}
+
 fn format_duration(secs: f64) -> String {
     let total = secs.round() as u64;
     let h = total / 3600;
@@ -347,7 +704,7 @@ fn print_progress(
         let percent = fraction * 100.0;
         let filled = (fraction * PROGRESS_BAR_WIDTH as f64).round() as usize;
         let empty = PROGRESS_BAR_WIDTH.saturating_sub(filled);
-        let bar: String = "█".repeat(filled) + &"░".repeat(empty);
+        let bar: String = "█".repeat(filled) + "░".repeat(empty).as_str();
 
         let input_total = total_input.unwrap_or(total);
 
@@ -492,6 +849,7 @@ fn inject_params_into_workflow_input(
 const KNOWN_FLAGS: &[&str] = &[
     "--input", "-i", "--output", "-o", "--param", "--help", "-h",
     "--version", "-V", "--verbose", "--log-filter", "--port", "--host", "--data-dir",
+    "--chunked", "--chunk-secs",
 ];
 
 fn parse_dynamic_args(args: &[String], workflow_ports: &[String]) -> HashMap<String, String> {
@@ -519,6 +877,8 @@ async fn run_workflow(
     input: Option<PathBuf>,
     output: Option<PathBuf>,
     raw_params: Vec<String>,
+    chunk_secs: Option<f64>,
+    data_dir: &Path,
 ) -> Result<()> {
     if !workflow_path.exists() {
         bail!("Workflow file does not exist: {}", workflow_path.display());
@@ -575,17 +935,17 @@ async fn run_workflow(
         all_params.insert(key.to_string(), value.to_string());
     }
 
-    let workflow_value = inject_params_into_workflow_input(&workflow_value, &all_params)?;
-
-    let graph: PipelineGraph = serde_json::from_value(workflow_value)
-        .with_context(|| format!("Failed to parse workflow JSON: {}", workflow_path.display()))?;
-
-    let registry = build_registry();
+    let registry = build_registry(data_dir);
 
-    info!("Validating workflow...");
-    graph
-        .validate(&registry)
-        .context("Workflow validation failed")?;
+    if let Some(target_chunk_secs) = chunk_secs {
+        return run_workflow_chunked(
+            &workflow_value,
+            &all_params,
+            target_chunk_secs,
+            &registry,
+            data_dir,
+        );
+    }
 
     if !all_params.is_empty() {
         info!(
@@ -594,18 +954,7 @@ async fn run_workflow(
         );
     }
 
-    let compile_ctx = VideoCompileContext::default();
-    let (_frames_written, progress_callback) = make_progress_callback();
-
-    info!("Executing workflow...");
-    let outputs = SequentialExecutor::execute_with_context(
-        &graph,
-        &registry,
-        Some(&compile_ctx),
-        Some(progress_callback),
-        None,
-    )
-    .context("Workflow execution failed")?;
+    let outputs = execute_workflow_value(&workflow_value, &all_params, &registry, data_dir)?;
 
     eprintln!();
     info!("Workflow completed successfully");
@@ -623,11 +972,130 @@ async fn run_workflow(
     Ok(())
 }
 
-fn build_registry() -> NodeRegistry {
+fn execute_workflow_value(
+    workflow_value: &serde_json::Value,
+    params: &HashMap<String, String>,
+    registry: &NodeRegistry,
+    data_dir: &Path,
+) -> Result<HashMap<String, HashMap<String, PortData>>> {
+    let workflow_value = inject_params_into_workflow_input(workflow_value, params)?;
+
+    let mut graph: PipelineGraph =
+        serde_json::from_value(workflow_value).context("Failed to parse workflow JSON")?;
+
+    info!("Validating workflow...");
+    graph
+        .validate(registry)
+        .context("Workflow validation failed")?;
+
+    let secrets = SecretStore::new(data_dir);
+    secrets
+        .resolve_graph_refs(&mut graph)
+        .context("Failed to resolve workflow secrets")?;
+
+    let config = AppConfig::load_from_path(&config_path(data_dir)).unwrap_or_default();
+    resolve_server_refs(&mut graph, &config.jellyfin, &secrets)
+        .context("Failed to resolve Jellyfin servers")?;
+    resolve_auth_secrets(&mut graph, &secrets)
+        .context("Failed to resolve node auth secrets")?;
+    let compile_ctx = VideoCompileContext::default()
+        .with_auto_models(auto_models(&config, data_dir))
+        .with_services(
+            NodeServices::default()
+                .with_secrets(Arc::new(secrets))
+                .with_torrents_config(config.torrents.clone()),
+        );
+    let (_frames_written, progress_callback) = make_progress_callback();
+
+    info!("Executing workflow...");
+    SequentialExecutor::execute_with_context(
+        &graph,
+        registry,
+        Some(&compile_ctx),
+        Some(progress_callback),
+        None,
+    )
+    .context("Workflow execution failed")
+}
+
+/// Run the workflow once per chunk of `input`, recording each finished chunk
+/// in the manifest next to `output` so a rerun after an interruption only
+/// processes what is left.
+fn run_workflow_chunked(
+    workflow_value: &serde_json::Value,
+    params: &HashMap<String, String>,
+    target_chunk_secs: f64,
+    registry: &NodeRegistry,
+    data_dir: &Path,
+) -> Result<()> {
+    if target_chunk_secs.is_nan() || target_chunk_secs < chunking::MIN_CHUNK_SECS {
+        bail!("--chunk-secs must be at least {}", chunking::MIN_CHUNK_SECS);
+    }
+    let source = params
+        .get("input")
+        .map(PathBuf::from)
+        .context("--chunked requires --input")?;
+    let output = params
+        .get("output")
+        .map(PathBuf::from)
+        .context("--chunked requires --output")?;
+
+    let mut manifest = chunking::prepare_chunked_run(
+        &source,
+        &output,
+        target_chunk_secs,
+        chunking::DEFAULT_SCENE_THRESHOLD,
+    )?;
+    let total = manifest.chunks.len();
+    let skipped = manifest.completed_count();
+    if skipped > 0 {
+        info!(
+            "Resuming from {}: {skipped}/{total} chunks already done",
+            chunking::manifest_path(&output).display()
+        );
+    }
+
+    for index in 0..total {
+        let chunk = &manifest.chunks[index];
+        if chunk.is_done() {
+            continue;
+        }
+        info!(
+            "Processing chunk {}/{total} ({:.1}s-{:.1}s)",
+            index + 1,
+            chunk.start_secs,
+            chunk.end_secs
+        );
+
+        let mut chunk_params = params.clone();
+        chunk_params.insert("input".to_string(), chunk.source.display().to_string());
+        chunk_params.insert("output".to_string(), chunk.output.display().to_string());
+
+        execute_workflow_value(workflow_value, &chunk_params, registry, data_dir)
+            .with_context(|| format!("chunk {}/{total} failed", index + 1))?;
+        eprintln!();
+        manifest.mark_completed(index)?;
+    }
+
+    chunking::finalize_chunked_run(&manifest)?;
+    info!(
+        "Workflow completed successfully: {} ({total} chunks)",
+        output.display()
+    );
+    Ok(())
+}
+
+fn build_registry(data_dir: &Path) -> NodeRegistry {
     let mut registry = NodeRegistry::new();
 
     register_all_nodes(&mut registry);
 
+    let config = AppConfig::load_from_path(&config_path(data_dir)).unwrap_or_default();
+    set_allowed_commands(config.commands.allowed);
+    set_default_node_timeout(config.jobs.node_timeout_secs);
+    set_memory_budget(MemoryBudget::from_config(&config.memory));
+    registry.load_plugins(&config.paths.plugins_dir);
+
     registry
 }
 
@@ -639,6 +1107,15 @@ fn format_port_data(data: &PortData) -> String {
         PortData::Bool(v) => format!("{}", v),
         PortData::Path(v) => format!("{}", v.display()),
         PortData::Metadata(_) => "<MediaMetadata>".to_string(),
+        PortData::Json(v) => v.to_string(),
+        PortData::List(items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(format_port_data)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
     }
 }
 
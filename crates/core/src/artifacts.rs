@@ -0,0 +1,245 @@
+//! Files a finished job wrote, kept with the job so the history still shows
+//! what it produced after a restart.
+//!
+//! Every file written by a `VideoOutput` node is recorded with its size and,
+//! when ffprobe can read it, the duration, bitrate, codec and frame size of
+//! the encode. Paths are where the encoder wrote the file; post actions that
+//! move it record the new location in their own results.
+//!
+//! Source/output frame pairs saved for an output (`compare_every_mins`) are
+//! listed with its artifact.
+//!
+//! [`EncodeStats`] sums these up per job, to answer "how big did the file
+//! get, and how fast was it made" after every run.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::graph::PipelineGraph;
+use crate::nodes::comparison::{read_comparison_frames, ComparisonFrame};
+use crate::probe::probe_media;
+use crate::types::PortData;
+
+const VIDEO_OUTPUT_NODE_TYPE: &str = "VideoOutput";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JobArtifact {
+    /// The `VideoOutput` node that wrote the file.
+    pub node_id: String,
+    pub path: PathBuf,
+    pub size_bytes: Option<u64>,
+    pub duration_secs: Option<f64>,
+    /// Overall bitrate in bits per second.
+    pub bit_rate: Option<u64>,
+    pub video_codec: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Set when the file was scored against its source, e.g. by an
+    /// experiment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vmaf: Option<f64>,
+    /// Before/after frame pairs saved next to the file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comparison_frames: Vec<ComparisonFrame>,
+}
+
+/// Encode results of a whole job, derived from its artifacts.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EncodeStats {
+    /// Size of all output files; unset when any size is unknown.
+    pub output_bytes: Option<u64>,
+    /// Length of the longest output.
+    pub duration_secs: Option<f64>,
+    /// `output_bytes` over `duration_secs`, in bits per second.
+    pub average_bit_rate: Option<u64>,
+    /// Seconds of video produced per second the job ran, like ffmpeg's
+    /// `speed=2.5x`.
+    pub speed: Option<f64>,
+    /// Lowest VMAF score among the scored outputs.
+    pub vmaf: Option<f64>,
+}
+
+impl EncodeStats {
+    /// Stats for `artifacts` of a job that ran for `elapsed_secs`; `None`
+    /// when the job wrote no files.
+    pub fn from_artifacts(artifacts: &[JobArtifact], elapsed_secs: Option<f64>) -> Option<Self> {
+        if artifacts.is_empty() {
+            return None;
+        }
+        let output_bytes = artifacts
+            .iter()
+            .map(|artifact| artifact.size_bytes)
+            .sum::<Option<u64>>();
+        let duration_secs = artifacts
+            .iter()
+            .filter_map(|artifact| artifact.duration_secs)
+            .reduce(f64::max);
+        let average_bit_rate = output_bytes
+            .zip(duration_secs)
+            .filter(|(_, duration)| *duration > 0.0)
+            .map(|(bytes, duration)| (bytes as f64 * 8.0 / duration).round() as u64);
+        let speed = duration_secs
+            .zip(elapsed_secs)
+            .filter(|(_, elapsed)| *elapsed > 0.0)
+            .map(|(duration, elapsed)| duration / elapsed);
+        let vmaf = artifacts
+            .iter()
+            .filter_map(|artifact| artifact.vmaf)
+            .reduce(f64::min);
+        Some(Self {
+            output_bytes,
+            duration_secs,
+            average_bit_rate,
+            speed,
+            vmaf,
+        })
+    }
+}
+
+/// Artifacts for the files written by `graph`'s `VideoOutput` nodes, ordered
+/// by node id. Runs ffprobe on each file, so call it off the async runtime.
+pub fn collect_artifacts(
+    graph: &PipelineGraph,
+    outputs_by_node: &HashMap<String, HashMap<String, PortData>>,
+) -> Vec<JobArtifact> {
+    let mut artifacts: Vec<JobArtifact> = graph
+        .nodes()
+        .filter(|node| node.node_type == VIDEO_OUTPUT_NODE_TYPE)
+        .filter_map(
+            |node| match outputs_by_node.get(&node.id)?.get("output_path")? {
+                PortData::Path(path) => Some(describe_file(&node.id, path)),
+                _ => None,
+            },
+        )
+        .collect();
+    artifacts.sort_by(|a, b| a.node_id.cmp(&b.node_id));
+    artifacts
+}
+
+fn describe_file(node_id: &str, path: &Path) -> JobArtifact {
+    let mut artifact = JobArtifact {
+        node_id: node_id.to_string(),
+        path: path.to_path_buf(),
+        size_bytes: fs::metadata(path).ok().map(|meta| meta.len()),
+        duration_secs: None,
+        bit_rate: None,
+        video_codec: None,
+        width: None,
+        height: None,
+        vmaf: None,
+        comparison_frames: read_comparison_frames(path),
+    };
+
+    match probe_media(path) {
+        Ok(probe) => {
+            artifact.size_bytes = probe.format.size_bytes.or(artifact.size_bytes);
+            artifact.duration_secs = probe.format.duration_secs;
+            artifact.bit_rate = probe.format.bit_rate;
+            let video = probe.streams.into_iter().find(|stream| {
+                stream
+                    .video
+                    .as_ref()
+                    .is_some_and(|video| !video.attached_pic)
+            });
+            if let Some(stream) = video {
+                artifact.video_codec = stream.codec_name;
+                artifact.width = stream.video.as_ref().map(|video| video.width);
+                artifact.height = stream.video.as_ref().map(|video| video.height);
+            }
+        }
+        Err(err) => {
+            debug!(path = %path.display(), error = %format!("{err:#}"), "Could not probe job output");
+        }
+    }
+    artifact
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_artifacts_records_video_outputs() {
+        let dir = tempfile::tempdir().unwrap();
+        let written = dir.path().join("ep01.mkv");
+        fs::write(&written, b"not really a video").unwrap();
+
+        let graph: PipelineGraph = serde_json::from_value(serde_json::json!({
+            "nodes": [
+                {"id": "out_b", "node_type": "VideoOutput", "params": {}},
+                {"id": "out_a", "node_type": "VideoOutput", "params": {}},
+                {"id": "print", "node_type": "Print", "params": {}}
+            ],
+            "connections": []
+        }))
+        .unwrap();
+        let outputs_by_node = HashMap::from([
+            (
+                "out_b".to_string(),
+                HashMap::from([(
+                    "output_path".to_string(),
+                    PortData::Path(dir.path().join("missing.mkv")),
+                )]),
+            ),
+            (
+                "out_a".to_string(),
+                HashMap::from([("output_path".to_string(), PortData::Path(written.clone()))]),
+            ),
+            (
+                "print".to_string(),
+                HashMap::from([("output_path".to_string(), PortData::Path(written.clone()))]),
+            ),
+        ]);
+
+        let artifacts = collect_artifacts(&graph, &outputs_by_node);
+        assert_eq!(artifacts.len(), 2);
+        assert_eq!(artifacts[0].node_id, "out_a");
+        assert_eq!(artifacts[0].path, written);
+        assert_eq!(artifacts[0].size_bytes, Some(18));
+        assert!(artifacts[0].duration_secs.is_none());
+        assert!(artifacts[0].comparison_frames.is_empty());
+        assert_eq!(artifacts[1].node_id, "out_b");
+        assert!(artifacts[1].size_bytes.is_none());
+    }
+
+    #[test]
+    fn test_encode_stats_from_artifacts() {
+        let artifact = |size_bytes, duration_secs, vmaf| JobArtifact {
+            node_id: "out".to_string(),
+            path: PathBuf::from("/out/ep01.mkv"),
+            size_bytes,
+            duration_secs,
+            bit_rate: None,
+            video_codec: None,
+            width: None,
+            height: None,
+            vmaf,
+            comparison_frames: Vec::new(),
+        };
+
+        let stats = EncodeStats::from_artifacts(
+            &[
+                artifact(Some(300_000_000), Some(1200.0), Some(96.5)),
+                artifact(Some(100_000_000), Some(600.0), Some(93.0)),
+            ],
+            Some(480.0),
+        )
+        .unwrap();
+        assert_eq!(stats.output_bytes, Some(400_000_000));
+        assert_eq!(stats.duration_secs, Some(1200.0));
+        assert_eq!(stats.average_bit_rate, Some(2_666_667));
+        assert_eq!(stats.speed, Some(2.5));
+        assert_eq!(stats.vmaf, Some(93.0));
+
+        let unknown =
+            EncodeStats::from_artifacts(&[artifact(None, Some(60.0), None)], None).unwrap();
+        assert!(unknown.output_bytes.is_none());
+        assert!(unknown.average_bit_rate.is_none());
+        assert!(unknown.speed.is_none());
+        assert!(EncodeStats::from_artifacts(&[], Some(1.0)).is_none());
+    }
+}
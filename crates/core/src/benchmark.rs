@@ -0,0 +1,623 @@
+//! Per-machine model benchmarks and `auto` model selection.
+//!
+//! `videnoa bench` measures how fast each downloaded super-resolution model
+//! runs on this machine and stores the result in `benchmarks.db` under the
+//! data directory. A `SuperResolution` node with `model_path: auto` picks a
+//! model from those numbers when the workflow is compiled. Models without a
+//! stored benchmark are sampled on a few frames first, and the result is
+//! stored for the next run.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::model_registry::{ModelRegistry, ModelType};
+use crate::node::{ExecutionContext, FrameProcessor, Node};
+use crate::nodes::backend::InferenceBackend;
+use crate::nodes::super_res::SuperResNode;
+use crate::types::{Frame, PortData};
+
+pub const BENCHMARKS_DB_FILE: &str = "benchmarks.db";
+/// `model_path` value that asks for automatic model selection.
+pub const AUTO_MODEL: &str = "auto";
+pub const AUTO_PREFERENCE_OPTIONS: [&str; 2] = ["quality", "speed"];
+/// Frames timed when a model is sampled during auto selection.
+const SAMPLE_FRAMES: usize = 3;
+/// Frame size assumed when the input size is not known at compile time.
+pub const DEFAULT_BENCHMARK_SIZE: (u32, u32) = (1920, 1080);
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct BenchmarkRecord {
+    /// Model file name inside the models directory.
+    pub model: String,
+    pub backend: String,
+    pub width: u32,
+    pub height: u32,
+    pub tile_size: u32,
+    /// Input frames processed per second.
+    pub fps: f64,
+    pub measured_at: DateTime<Utc>,
+}
+
+impl BenchmarkRecord {
+    /// Throughput expected at another input size, assuming the cost grows
+    /// with the pixel count.
+    pub fn fps_at(&self, width: u32, height: u32) -> f64 {
+        let measured = self.width as f64 * self.height as f64;
+        let target = width as f64 * height as f64;
+        if measured <= 0.0 || target <= 0.0 {
+            return self.fps;
+        }
+        self.fps * measured / target
+    }
+}
+
+/// SQLite table of benchmark results, one row per model, backend, input size
+/// and tile size.
+#[derive(Debug, Clone)]
+pub struct BenchmarkStore {
+    db_path: PathBuf,
+}
+
+impl BenchmarkStore {
+    pub fn open(data_dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(data_dir).with_context(|| {
+            format!(
+                "failed to create data directory for benchmarks db: {}",
+                data_dir.display()
+            )
+        })?;
+        let store = Self {
+            db_path: data_dir.join(BENCHMARKS_DB_FILE),
+        };
+        store.with_connection(|conn| {
+            conn.execute_batch(
+                "CREATE TABLE IF NOT EXISTS model_benchmarks (
+                    model TEXT NOT NULL,
+                    backend TEXT NOT NULL,
+                    width INTEGER NOT NULL,
+                    height INTEGER NOT NULL,
+                    tile_size INTEGER NOT NULL,
+                    fps REAL NOT NULL,
+                    measured_at TEXT NOT NULL,
+                    PRIMARY KEY (model, backend, width, height, tile_size)
+                 );",
+            )
+            .context("failed to initialize benchmarks schema")
+        })?;
+        Ok(store)
+    }
+
+    pub fn record(&self, record: &BenchmarkRecord) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO model_benchmarks
+                    (model, backend, width, height, tile_size, fps, measured_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    record.model,
+                    record.backend,
+                    record.width,
+                    record.height,
+                    record.tile_size,
+                    record.fps,
+                    record.measured_at.to_rfc3339(),
+                ],
+            )
+            .with_context(|| format!("failed to store benchmark for {}", record.model))?;
+            Ok(())
+        })
+    }
+
+    /// All results, newest first.
+    pub fn list(&self) -> Result<Vec<BenchmarkRecord>> {
+        self.query("ORDER BY measured_at DESC", params![])
+    }
+
+    /// Newest result for a model with the given backend and tile size.
+    pub fn latest(
+        &self,
+        model: &str,
+        backend: &str,
+        tile_size: u32,
+    ) -> Result<Option<BenchmarkRecord>> {
+        Ok(self
+            .query(
+                "WHERE model = ?1 AND backend = ?2 AND tile_size = ?3
+                 ORDER BY measured_at DESC LIMIT 1",
+                params![model, backend, tile_size],
+            )?
+            .pop())
+    }
+
+    fn query(&self, clause: &str, args: &[&dyn rusqlite::ToSql]) -> Result<Vec<BenchmarkRecord>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT model, backend, width, height, tile_size, fps, measured_at
+                 FROM model_benchmarks {clause}"
+            ))?;
+            let rows = stmt.query_map(args, |row| {
+                Ok((
+                    BenchmarkRecord {
+                        model: row.get(0)?,
+                        backend: row.get(1)?,
+                        width: row.get(2)?,
+                        height: row.get(3)?,
+                        tile_size: row.get(4)?,
+                        fps: row.get(5)?,
+                        measured_at: DateTime::<Utc>::MIN_UTC,
+                    },
+                    row.get::<_, String>(6)?,
+                ))
+            })?;
+            let mut records = Vec::new();
+            for row in rows {
+                let (mut record, measured_at) = row?;
+                match DateTime::parse_from_rfc3339(&measured_at) {
+                    Ok(ts) => record.measured_at = ts.with_timezone(&Utc),
+                    Err(err) => {
+                        warn!(model = %record.model, error = %err, "Skipping benchmark with invalid timestamp");
+                        continue;
+                    }
+                }
+                records.push(record);
+            }
+            Ok(records)
+        })
+    }
+
+    fn with_connection<T>(&self, op: impl FnOnce(&Connection) -> Result<T>) -> Result<T> {
+        let conn = Connection::open(&self.db_path)
+            .with_context(|| format!("failed to open benchmarks db: {}", self.db_path.display()))?;
+        conn.busy_timeout(std::time::Duration::from_secs(5))
+            .context("failed to set benchmarks db busy timeout")?;
+        op(&conn)
+    }
+}
+
+/// What to measure in [`run_benchmark`].
+#[derive(Debug, Clone)]
+pub struct BenchmarkSpec {
+    pub model_path: PathBuf,
+    pub scale: u32,
+    pub backend: String,
+    pub width: u32,
+    pub height: u32,
+    pub tile_size: u32,
+    /// Timed frames, after one untimed warm-up frame.
+    pub frames: usize,
+    pub trt_cache_dir: Option<PathBuf>,
+}
+
+/// Loads the model into a `SuperResolution` node and times it on synthetic
+/// frames. The warm-up frame absorbs TensorRT engine builds and first-run
+/// allocations.
+pub fn run_benchmark(spec: &BenchmarkSpec) -> Result<BenchmarkRecord> {
+    let model = spec
+        .model_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .with_context(|| format!("invalid model path: {}", spec.model_path.display()))?
+        .to_string();
+
+    let mut node = SuperResNode::new();
+    if let Some(dir) = &spec.trt_cache_dir {
+        node.set_trt_cache_dir(dir.clone());
+    }
+    let inputs = HashMap::from([
+        (
+            "model_path".to_string(),
+            PortData::Path(spec.model_path.clone()),
+        ),
+        ("scale".to_string(), PortData::Int(spec.scale as i64)),
+        (
+            "tile_size".to_string(),
+            PortData::Int(spec.tile_size as i64),
+        ),
+        ("backend".to_string(), PortData::Str(spec.backend.clone())),
+    ]);
+    let ctx = ExecutionContext::default();
+    node.execute(&inputs, &ctx)
+        .with_context(|| format!("failed to load {model} for benchmarking"))?;
+
+    let (w, h) = (spec.width as usize, spec.height as usize);
+    let data: Vec<u8> = (0..w * h * 3).map(|i| (i % 251) as u8).collect();
+    let frame = || Frame::CpuRgb {
+        data: data.clone(),
+        width: spec.width,
+        height: spec.height,
+        bit_depth: 8,
+    };
+
+    node.process_frame(frame(), &ctx)?;
+    let frames = spec.frames.max(1);
+    let started = Instant::now();
+    for _ in 0..frames {
+        node.process_frame(frame(), &ctx)?;
+    }
+    let elapsed = started.elapsed().as_secs_f64().max(f64::EPSILON);
+
+    Ok(BenchmarkRecord {
+        model,
+        backend: InferenceBackend::from_str_lossy(&spec.backend).to_string(),
+        width: spec.width,
+        height: spec.height,
+        tile_size: spec.tile_size,
+        fps: frames as f64 / elapsed,
+        measured_at: Utc::now(),
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoPreference {
+    /// The slowest model that still meets the target fps. Heavier models
+    /// are assumed to give better results.
+    Quality,
+    /// The fastest model.
+    Speed,
+}
+
+impl AutoPreference {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "quality" => Some(Self::Quality),
+            "speed" => Some(Self::Speed),
+            _ => None,
+        }
+    }
+}
+
+/// Picks from `(model, estimated fps)` pairs. Models below `target_fps` are
+/// only considered when none reaches it; then the fastest one wins.
+pub fn pick_model(
+    candidates: &[(String, f64)],
+    target_fps: Option<f64>,
+    preference: AutoPreference,
+) -> Option<&str> {
+    let by_fps = |a: &&(String, f64), b: &&(String, f64)| a.1.total_cmp(&b.1);
+    let meeting: Vec<&(String, f64)> = candidates
+        .iter()
+        .filter(|(_, fps)| target_fps.is_none_or(|target| *fps >= target))
+        .collect();
+    let picked = if meeting.is_empty() {
+        candidates.iter().max_by(by_fps)
+    } else {
+        match preference {
+            AutoPreference::Quality => meeting.into_iter().min_by(by_fps),
+            AutoPreference::Speed => meeting.into_iter().max_by(by_fps),
+        }
+    };
+    picked.map(|(model, _)| model.as_str())
+}
+
+/// The node settings auto selection has to satisfy.
+#[derive(Debug, Clone)]
+pub struct AutoRequest {
+    pub scale: u32,
+    pub backend: String,
+    pub tile_size: u32,
+    /// Input frame size.
+    pub width: u32,
+    pub height: u32,
+    pub target_fps: Option<f64>,
+    pub preference: AutoPreference,
+}
+
+impl AutoRequest {
+    /// Reads `scale`, `tile_size`, `backend`, `auto_target_fps` and
+    /// `auto_preference` from `SuperResolution` inputs. A zero frame size
+    /// falls back to [`DEFAULT_BENCHMARK_SIZE`].
+    pub fn from_inputs(
+        inputs: &HashMap<String, PortData>,
+        width: u32,
+        height: u32,
+    ) -> Result<Self> {
+        let int = |name: &str, default: i64| match inputs.get(name) {
+            Some(PortData::Int(value)) if *value >= 0 => Ok(*value as u32),
+            Some(PortData::Int(value)) => bail!("{name} must not be negative, got {value}"),
+            Some(_) => bail!("{name} must be an Int"),
+            None => Ok(default as u32),
+        };
+        let backend = match inputs.get("backend") {
+            Some(PortData::Str(value)) => InferenceBackend::from_str_lossy(value),
+            _ => InferenceBackend::default(),
+        };
+        let target_fps = match inputs.get("auto_target_fps") {
+            Some(PortData::Float(fps)) if *fps > 0.0 => Some(*fps),
+            Some(PortData::Float(fps)) if *fps == 0.0 => None,
+            Some(PortData::Float(fps)) => bail!("auto_target_fps must not be negative, got {fps}"),
+            Some(_) => bail!("auto_target_fps must be a Float"),
+            None => None,
+        };
+        let preference = match inputs.get("auto_preference") {
+            Some(PortData::Str(value)) => AutoPreference::parse(value).with_context(|| {
+                format!(
+                    "auto_preference must be one of {}, got '{value}'",
+                    AUTO_PREFERENCE_OPTIONS.join(", ")
+                )
+            })?,
+            Some(_) => bail!("auto_preference must be a Str"),
+            None => AutoPreference::Quality,
+        };
+        let (width, height) = if width == 0 || height == 0 {
+            DEFAULT_BENCHMARK_SIZE
+        } else {
+            (width, height)
+        };
+        Ok(Self {
+            scale: int("scale", 4)?,
+            backend: backend.to_string(),
+            tile_size: int("tile_size", 0)?,
+            width,
+            height,
+            target_fps,
+            preference,
+        })
+    }
+}
+
+/// Where `model_path: auto` looks for candidate models and cached
+/// benchmarks.
+#[derive(Debug, Clone)]
+pub struct AutoModelSource {
+    pub models_dir: PathBuf,
+    /// `None` samples candidates on every run.
+    pub benchmarks: Option<BenchmarkStore>,
+}
+
+impl Default for AutoModelSource {
+    fn default() -> Self {
+        Self {
+            models_dir: PathBuf::from("models"),
+            benchmarks: None,
+        }
+    }
+}
+
+impl AutoModelSource {
+    /// Chooses a downloaded super-resolution model for `request`, sampling
+    /// models that have no stored benchmark yet.
+    pub fn select(&self, request: &AutoRequest, trt_cache_dir: Option<&Path>) -> Result<PathBuf> {
+        self.select_with(request, |model_path| {
+            run_benchmark(&BenchmarkSpec {
+                model_path: model_path.to_path_buf(),
+                scale: request.scale,
+                backend: request.backend.clone(),
+                width: request.width,
+                height: request.height,
+                tile_size: request.tile_size,
+                frames: SAMPLE_FRAMES,
+                trt_cache_dir: trt_cache_dir.map(Path::to_path_buf),
+            })
+        })
+    }
+
+    fn select_with(
+        &self,
+        request: &AutoRequest,
+        mut sample: impl FnMut(&Path) -> Result<BenchmarkRecord>,
+    ) -> Result<PathBuf> {
+        let mut registry = ModelRegistry::with_builtin_models(self.models_dir.clone());
+        registry.discover()?;
+        let models: Vec<String> = registry
+            .list_by_type(ModelType::SuperResolution)
+            .into_iter()
+            .filter(|entry| entry.scale == Some(request.scale))
+            .map(|entry| entry.filename.clone())
+            .filter(|filename| self.models_dir.join(filename).is_file())
+            .collect();
+        if models.is_empty() {
+            bail!(
+                "auto model selection: no downloaded {}x super-resolution model in {}",
+                request.scale,
+                self.models_dir.display()
+            );
+        }
+
+        let mut candidates = Vec::with_capacity(models.len());
+        for model in models {
+            let stored = match &self.benchmarks {
+                Some(store) => store.latest(&model, &request.backend, request.tile_size)?,
+                None => None,
+            };
+            let record = match stored {
+                Some(record) => record,
+                None => {
+                    info!(%model, "Sampling model speed for auto selection");
+                    match sample(&self.models_dir.join(&model)) {
+                        Ok(record) => {
+                            if let Some(store) = &self.benchmarks {
+                                store.record(&record)?;
+                            }
+                            record
+                        }
+                        Err(err) => {
+                            warn!(%model, error = %format!("{err:#}"), "Skipping model that failed to run");
+                            continue;
+                        }
+                    }
+                }
+            };
+            candidates.push((model, record.fps_at(request.width, request.height)));
+        }
+
+        let picked = pick_model(&candidates, request.target_fps, request.preference)
+            .context("auto model selection: no candidate model could run")?;
+        if let Some(target) = request.target_fps {
+            if candidates.iter().all(|(_, fps)| *fps < target) {
+                warn!(
+                    target_fps = target,
+                    model = picked,
+                    "No model reaches the target fps; using the fastest"
+                );
+            }
+        }
+        info!(
+            model = picked,
+            ?candidates,
+            "Auto-selected super-resolution model"
+        );
+        Ok(self.models_dir.join(picked))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tempdir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("videnoa_benchmark_{name}_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn record(model: &str, width: u32, fps: f64) -> BenchmarkRecord {
+        BenchmarkRecord {
+            model: model.to_string(),
+            backend: "cuda".to_string(),
+            width,
+            height: 720,
+            tile_size: 0,
+            fps,
+            measured_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_store_roundtrip_and_latest() {
+        let dir = tempdir("store");
+        let store = BenchmarkStore::open(&dir).unwrap();
+        store.record(&record("a.onnx", 1280, 10.0)).unwrap();
+        // Same key replaces the previous result.
+        store.record(&record("a.onnx", 1280, 12.0)).unwrap();
+        store.record(&record("b.onnx", 1280, 30.0)).unwrap();
+
+        assert_eq!(store.list().unwrap().len(), 2);
+        let latest = store.latest("a.onnx", "cuda", 0).unwrap().unwrap();
+        assert_eq!(latest.fps, 12.0);
+        assert!(store.latest("a.onnx", "tensorrt", 0).unwrap().is_none());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_fps_at_scales_with_pixel_count() {
+        let measured = record("a.onnx", 1280, 20.0);
+        assert!((measured.fps_at(640, 360) - 80.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pick_model_by_preference_and_target() {
+        let candidates = vec![
+            ("heavy.onnx".to_string(), 8.0),
+            ("medium.onnx".to_string(), 24.0),
+            ("light.onnx".to_string(), 60.0),
+        ];
+        let quality = AutoPreference::Quality;
+        assert_eq!(pick_model(&candidates, None, quality), Some("heavy.onnx"));
+        assert_eq!(
+            pick_model(&candidates, Some(20.0), quality),
+            Some("medium.onnx")
+        );
+        assert_eq!(
+            pick_model(&candidates, Some(20.0), AutoPreference::Speed),
+            Some("light.onnx")
+        );
+        // Nothing reaches 100 fps: fall back to the fastest.
+        assert_eq!(
+            pick_model(&candidates, Some(100.0), quality),
+            Some("light.onnx")
+        );
+        assert_eq!(pick_model(&[], None, quality), None);
+    }
+
+    #[test]
+    fn test_auto_request_from_inputs() {
+        let inputs = HashMap::from([
+            ("scale".to_string(), PortData::Int(2)),
+            ("backend".to_string(), PortData::Str("TensorRT".to_string())),
+            ("auto_target_fps".to_string(), PortData::Float(24.0)),
+            (
+                "auto_preference".to_string(),
+                PortData::Str("speed".to_string()),
+            ),
+        ]);
+        let request = AutoRequest::from_inputs(&inputs, 0, 0).unwrap();
+        assert_eq!(request.scale, 2);
+        assert_eq!(request.backend, "tensorrt");
+        assert_eq!((request.width, request.height), DEFAULT_BENCHMARK_SIZE);
+        assert_eq!(request.target_fps, Some(24.0));
+        assert_eq!(request.preference, AutoPreference::Speed);
+
+        let bad = HashMap::from([(
+            "auto_preference".to_string(),
+            PortData::Str("fastest".to_string()),
+        )]);
+        let err = AutoRequest::from_inputs(&bad, 640, 360).unwrap_err();
+        assert!(err.to_string().contains("auto_preference"), "{err}");
+    }
+
+    #[test]
+    fn test_select_samples_missing_models_once() {
+        let data_dir = tempdir("select");
+        let models_dir = data_dir.join("models");
+        std::fs::create_dir_all(&models_dir).unwrap();
+        for name in ["2x_Fast.onnx", "2x_Slow.onnx", "4x_Other.onnx"] {
+            std::fs::write(models_dir.join(name), b"data").unwrap();
+        }
+        let source = AutoModelSource {
+            models_dir: models_dir.clone(),
+            benchmarks: Some(BenchmarkStore::open(&data_dir).unwrap()),
+        };
+        let request = AutoRequest {
+            scale: 2,
+            backend: "cuda".to_string(),
+            tile_size: 0,
+            width: 1280,
+            height: 720,
+            target_fps: Some(20.0),
+            preference: AutoPreference::Quality,
+        };
+
+        let mut sampled = Vec::new();
+        let mut sample = |path: &Path| {
+            let model = path.file_name().unwrap().to_str().unwrap().to_string();
+            sampled.push(model.clone());
+            let fps = if model.contains("Fast") { 50.0 } else { 10.0 };
+            Ok(record(&model, 1280, fps))
+        };
+        let picked = source.select_with(&request, &mut sample).unwrap();
+        assert_eq!(picked, models_dir.join("2x_Fast.onnx"));
+        sampled.sort();
+        assert_eq!(sampled, ["2x_Fast.onnx", "2x_Slow.onnx"]);
+
+        // Second run uses the stored numbers; at 640x360 both reach 20 fps.
+        let request = AutoRequest {
+            width: 640,
+            height: 360,
+            ..request
+        };
+        let picked = source
+            .select_with(&request, |_| bail!("should not sample again"))
+            .unwrap();
+        assert_eq!(picked, models_dir.join("2x_Slow.onnx"));
+
+        let err = source
+            .select_with(
+                &AutoRequest {
+                    scale: 3,
+                    ..request
+                },
+                |_| bail!("unused"),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("no downloaded 3x"), "{err}");
+        std::fs::remove_dir_all(&data_dir).ok();
+    }
+}
@@ -0,0 +1,523 @@
+//! Chunked processing of long sources.
+//!
+//! A source is stream-copied into scene-aligned chunks, each chunk is run
+//! through the workflow separately and the results are concatenated without
+//! re-encoding. Progress is recorded in a manifest next to the output
+//! (`<output>.chunks.json`, with chunk files in `<output>.chunks/`) so an
+//! interrupted run picks up where it stopped.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+pub const DEFAULT_TARGET_CHUNK_SECS: f64 = 300.0;
+pub const MIN_CHUNK_SECS: f64 = 10.0;
+pub const DEFAULT_SCENE_THRESHOLD: f64 = 0.3;
+/// How far (as a fraction of the target length) a cut may move to land on a
+/// scene change.
+const SCENE_SNAP_WINDOW: f64 = 0.25;
+
+/// Pick cut points roughly every `target_secs`, moving each one to the
+/// nearest scene change inside the snap window. Cuts closer than
+/// [`MIN_CHUNK_SECS`] to the previous cut or the end are dropped.
+pub fn plan_chunk_boundaries(duration_secs: f64, scene_cuts: &[f64], target_secs: f64) -> Vec<f64> {
+    if duration_secs <= 0.0 || target_secs <= 0.0 {
+        return Vec::new();
+    }
+
+    let window = target_secs * SCENE_SNAP_WINDOW;
+    let mut boundaries: Vec<f64> = Vec::new();
+    let mut ideal = target_secs;
+    while ideal < duration_secs {
+        let cut = scene_cuts
+            .iter()
+            .copied()
+            .filter(|cut| (cut - ideal).abs() <= window)
+            .min_by(|a, b| (a - ideal).abs().total_cmp(&(b - ideal).abs()))
+            .unwrap_or(ideal);
+
+        let previous = boundaries.last().copied().unwrap_or(0.0);
+        if cut - previous >= MIN_CHUNK_SECS && duration_secs - cut >= MIN_CHUNK_SECS {
+            boundaries.push(cut);
+        }
+        ideal += target_secs;
+    }
+    boundaries
+}
+
+/// `(start, end)` spans covering `[0, duration]` split at `boundaries`.
+pub fn chunk_spans(duration_secs: f64, boundaries: &[f64]) -> Vec<(f64, f64)> {
+    let mut points = Vec::with_capacity(boundaries.len() + 2);
+    points.push(0.0);
+    points.extend_from_slice(boundaries);
+    points.push(duration_secs);
+    points.windows(2).map(|pair| (pair[0], pair[1])).collect()
+}
+
+pub fn probe_duration_secs(path: &Path) -> Result<f64> {
+    let output = crate::runtime::command_for("ffprobe")
+        .args(["-v", "error", "-show_entries", "format=duration", "-of"])
+        .arg("default=noprint_wrappers=1:nokey=1")
+        .arg(path)
+        .output()
+        .context("failed to launch ffprobe")?;
+    if !output.status.success() {
+        bail!(
+            "ffprobe failed for {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .with_context(|| format!("ffprobe reported no duration for {}", path.display()))
+}
+
+/// Scene change timestamps from ffmpeg's `scene` score.
+pub fn detect_scene_cuts(path: &Path, threshold: f64) -> Result<Vec<f64>> {
+    let output = crate::runtime::command_for("ffmpeg")
+        .args(["-hide_banner", "-nostdin", "-i"])
+        .arg(path)
+        .args(["-an", "-sn", "-dn", "-vf"])
+        .arg(format!("select='gt(scene,{threshold})',showinfo"))
+        .args(["-f", "null", "-"])
+        .output()
+        .context("failed to launch ffmpeg")?;
+    if !output.status.success() {
+        bail!(
+            "ffmpeg scene detection failed for {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(parse_showinfo_pts_times(&String::from_utf8_lossy(
+        &output.stderr,
+    )))
+}
+
+fn parse_showinfo_pts_times(stderr: &str) -> Vec<f64> {
+    stderr
+        .lines()
+        .filter(|line| line.contains("Parsed_showinfo"))
+        .filter_map(|line| {
+            let rest = &line[line.find("pts_time:")? + "pts_time:".len()..];
+            rest.split_whitespace().next()?.parse().ok()
+        })
+        .collect()
+}
+
+/// Stream-copy `source` into segments at `boundaries`. ffmpeg cuts on the
+/// keyframe at or after each boundary, so no re-encoding happens here.
+pub fn split_source(source: &Path, boundaries: &[f64], dir: &Path) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let ext = source
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("mkv");
+    let pattern = dir.join(format!("source_%04d.{ext}"));
+
+    let mut command = crate::runtime::command_for("ffmpeg");
+    command
+        .args(["-hide_banner", "-nostdin", "-y", "-v", "error", "-i"])
+        .arg(source)
+        .args(["-map", "0", "-c", "copy", "-f", "segment"]);
+    if !boundaries.is_empty() {
+        let times: Vec<String> = boundaries.iter().map(|t| format!("{t:.3}")).collect();
+        command.args(["-segment_times", &times.join(",")]);
+    }
+    let output = command
+        .args(["-reset_timestamps", "1"])
+        .arg(&pattern)
+        .output()
+        .context("failed to launch ffmpeg")?;
+    if !output.status.success() {
+        bail!(
+            "ffmpeg failed to split {}: {}",
+            source.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let mut chunks: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("source_"))
+        })
+        .collect();
+    chunks.sort();
+    if chunks.is_empty() {
+        bail!("ffmpeg produced no chunks for {}", source.display());
+    }
+    Ok(chunks)
+}
+
+/// Join processed chunks with the concat demuxer, copying every stream.
+pub fn concat_chunks(chunks: &[PathBuf], dir: &Path, output: &Path) -> Result<()> {
+    let list_path = dir.join("concat.txt");
+    let list = chunks
+        .iter()
+        .map(|chunk| {
+            let path = fs::canonicalize(chunk).unwrap_or_else(|_| chunk.clone());
+            format!("file '{}'\n", path.to_string_lossy().replace('\'', "'\\''"))
+        })
+        .collect::<String>();
+    fs::write(&list_path, list)
+        .with_context(|| format!("failed to write {}", list_path.display()))?;
+
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+
+    let result = crate::runtime::command_for("ffmpeg")
+        .args([
+            "-hide_banner",
+            "-nostdin",
+            "-y",
+            "-v",
+            "error",
+            "-f",
+            "concat",
+            "-safe",
+            "0",
+            "-i",
+        ])
+        .arg(&list_path)
+        .args(["-map", "0", "-c", "copy"])
+        .arg(output)
+        .output()
+        .context("failed to launch ffmpeg")?;
+    if !result.status.success() {
+        bail!(
+            "ffmpeg failed to concatenate chunks into {}: {}",
+            output.display(),
+            String::from_utf8_lossy(&result.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+const MANIFEST_VERSION: u32 = 1;
+
+/// Record of a chunked run, rewritten after every finished chunk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkManifest {
+    pub version: u32,
+    pub source: PathBuf,
+    pub source_len: u64,
+    pub source_modified: Option<DateTime<Utc>>,
+    pub output: PathBuf,
+    pub target_chunk_secs: f64,
+    pub chunks: Vec<ManifestChunk>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestChunk {
+    pub index: usize,
+    pub start_secs: f64,
+    pub end_secs: f64,
+    pub source: PathBuf,
+    pub output: PathBuf,
+    pub completed: bool,
+}
+
+impl ManifestChunk {
+    /// Completed and its processed file is still on disk.
+    pub fn is_done(&self) -> bool {
+        self.completed && self.output.is_file()
+    }
+}
+
+pub fn manifest_path(output: &Path) -> PathBuf {
+    with_suffix(output, ".chunks.json")
+}
+
+pub fn work_dir(output: &Path) -> PathBuf {
+    with_suffix(output, ".chunks")
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn source_fingerprint(source: &Path) -> Result<(u64, Option<DateTime<Utc>>)> {
+    let meta =
+        fs::metadata(source).with_context(|| format!("failed to stat {}", source.display()))?;
+    Ok((meta.len(), meta.modified().ok().map(DateTime::<Utc>::from)))
+}
+
+impl ChunkManifest {
+    pub fn load(output: &Path) -> Result<Option<Self>> {
+        let path = manifest_path(output);
+        let raw = match fs::read(&path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to read {}", path.display()))
+            }
+        };
+        serde_json::from_slice(&raw)
+            .map(Some)
+            .with_context(|| format!("failed to parse {}", path.display()))
+    }
+
+    /// Write via a temp file and rename so a crash never leaves a torn manifest.
+    pub fn save(&self) -> Result<()> {
+        let path = manifest_path(&self.output);
+        let tmp = with_suffix(&path, ".tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &path).with_context(|| format!("failed to replace {}", path.display()))
+    }
+
+    pub fn mark_completed(&mut self, index: usize) -> Result<()> {
+        let chunk = self
+            .chunks
+            .get_mut(index)
+            .with_context(|| format!("no chunk {index} in manifest"))?;
+        if !chunk.output.is_file() {
+            bail!(
+                "chunk {index} finished without writing {}",
+                chunk.output.display()
+            );
+        }
+        chunk.completed = true;
+        self.save()
+    }
+
+    pub fn completed_count(&self) -> usize {
+        self.chunks.iter().filter(|chunk| chunk.is_done()).count()
+    }
+
+    /// Whether this manifest was written for the same source and chunk size,
+    /// with every chunk source still present.
+    fn matches(&self, source: &Path, target_chunk_secs: f64) -> bool {
+        let Ok((len, modified)) = source_fingerprint(source) else {
+            return false;
+        };
+        self.version == MANIFEST_VERSION
+            && self.source == source
+            && self.source_len == len
+            && self.source_modified == modified
+            && self.target_chunk_secs == target_chunk_secs
+            && self.chunks.iter().all(|chunk| chunk.source.is_file())
+    }
+}
+
+/// Load a matching manifest for `output`, or split `source` afresh and write
+/// a new one.
+pub fn prepare_chunked_run(
+    source: &Path,
+    output: &Path,
+    target_chunk_secs: f64,
+    scene_threshold: f64,
+) -> Result<ChunkManifest> {
+    match ChunkManifest::load(output) {
+        Ok(Some(manifest)) if manifest.matches(source, target_chunk_secs) => {
+            info!(
+                manifest = %manifest_path(output).display(),
+                completed = manifest.completed_count(),
+                total = manifest.chunks.len(),
+                "Resuming chunked run"
+            );
+            return Ok(manifest);
+        }
+        Ok(Some(_)) => info!(
+            manifest = %manifest_path(output).display(),
+            "Ignoring chunk manifest written for a different source or chunk size"
+        ),
+        Ok(None) => {}
+        Err(err) => warn!(error = %format!("{err:#}"), "Ignoring unreadable chunk manifest"),
+    }
+
+    let dir = work_dir(output);
+    if dir.exists() {
+        fs::remove_dir_all(&dir).with_context(|| format!("failed to clear {}", dir.display()))?;
+    }
+
+    let (source_len, source_modified) = source_fingerprint(source)?;
+    let duration = probe_duration_secs(source)?;
+    let scene_cuts = detect_scene_cuts(source, scene_threshold).unwrap_or_else(|err| {
+        warn!(error = %format!("{err:#}"), "Scene detection failed; cutting at fixed intervals");
+        Vec::new()
+    });
+    let boundaries = plan_chunk_boundaries(duration, &scene_cuts, target_chunk_secs);
+    let sources = split_source(source, &boundaries, &dir)?;
+    let spans = chunk_spans(duration, &boundaries);
+
+    let output_ext = output
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("mkv");
+    let chunks = sources
+        .into_iter()
+        .enumerate()
+        .map(|(index, chunk_source)| {
+            let (start_secs, end_secs) = spans.get(index).copied().unwrap_or_default();
+            ManifestChunk {
+                index,
+                start_secs,
+                end_secs,
+                source: chunk_source,
+                output: dir.join(format!("processed_{index:04}.{output_ext}")),
+                completed: false,
+            }
+        })
+        .collect();
+
+    let manifest = ChunkManifest {
+        version: MANIFEST_VERSION,
+        source: source.to_path_buf(),
+        source_len,
+        source_modified,
+        output: output.to_path_buf(),
+        target_chunk_secs,
+        chunks,
+    };
+    manifest.save()?;
+    Ok(manifest)
+}
+
+/// Concatenate every processed chunk into the output, then drop the
+/// manifest and work directory.
+pub fn finalize_chunked_run(manifest: &ChunkManifest) -> Result<()> {
+    if let Some(chunk) = manifest.chunks.iter().find(|chunk| !chunk.is_done()) {
+        bail!("chunk {} has not completed", chunk.index);
+    }
+
+    let dir = work_dir(&manifest.output);
+    let outputs: Vec<PathBuf> = manifest
+        .chunks
+        .iter()
+        .map(|chunk| chunk.output.clone())
+        .collect();
+    concat_chunks(&outputs, &dir, &manifest.output)?;
+
+    fs::remove_file(manifest_path(&manifest.output)).with_context(|| {
+        format!(
+            "failed to remove {}",
+            manifest_path(&manifest.output).display()
+        )
+    })?;
+    if let Err(err) = fs::remove_dir_all(&dir) {
+        warn!(error = %err, dir = %dir.display(), "Failed to remove chunk work directory");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boundaries_snap_to_nearby_scene_cuts() {
+        let boundaries = plan_chunk_boundaries(1000.0, &[95.0, 180.0, 312.5, 700.0], 300.0);
+        // 300 snaps to 312.5, 600 has no cut within 75s, 900 stays put.
+        assert_eq!(boundaries, vec![312.5, 600.0, 900.0]);
+    }
+
+    #[test]
+    fn short_tail_and_short_sources_are_not_split() {
+        assert!(plan_chunk_boundaries(305.0, &[], 300.0).is_empty());
+        assert!(plan_chunk_boundaries(0.0, &[], 300.0).is_empty());
+        assert_eq!(
+            chunk_spans(650.0, &[300.0, 600.0]),
+            vec![(0.0, 300.0), (300.0, 600.0), (600.0, 650.0)]
+        );
+    }
+
+    #[test]
+    fn parse_showinfo_reads_pts_times() {
+        let stderr = "[Parsed_showinfo_1 @ 0x1] n:   0 pts:  12012 pts_time:12.012 duration:1001\n\
+                      frame=  10 fps=0.0\n\
+                      [Parsed_showinfo_1 @ 0x1] n:   1 pts:  90090 pts_time:90.09 duration:1001\n";
+        assert_eq!(parse_showinfo_pts_times(stderr), vec![12.012, 90.09]);
+    }
+
+    fn manifest_with_chunks(root: &Path) -> (PathBuf, ChunkManifest) {
+        let source = root.join("source.mkv");
+        fs::write(&source, b"source").unwrap();
+        let output = root.join("out.mkv");
+        let dir = work_dir(&output);
+        fs::create_dir_all(&dir).unwrap();
+        let (source_len, source_modified) = source_fingerprint(&source).unwrap();
+        let chunks = (0..2)
+            .map(|index| {
+                let chunk_source = dir.join(format!("source_{index:04}.mkv"));
+                fs::write(&chunk_source, b"chunk").unwrap();
+                ManifestChunk {
+                    index,
+                    start_secs: index as f64 * 300.0,
+                    end_secs: (index + 1) as f64 * 300.0,
+                    source: chunk_source,
+                    output: dir.join(format!("processed_{index:04}.mkv")),
+                    completed: false,
+                }
+            })
+            .collect();
+        let manifest = ChunkManifest {
+            version: MANIFEST_VERSION,
+            source: source.clone(),
+            source_len,
+            source_modified,
+            output,
+            target_chunk_secs: 300.0,
+            chunks,
+        };
+        manifest.save().unwrap();
+        (source, manifest)
+    }
+
+    #[test]
+    fn manifest_paths_sit_next_to_output() {
+        let output = Path::new("/media/out/movie.mkv");
+        assert_eq!(
+            manifest_path(output),
+            PathBuf::from("/media/out/movie.mkv.chunks.json")
+        );
+        assert_eq!(
+            work_dir(output),
+            PathBuf::from("/media/out/movie.mkv.chunks")
+        );
+    }
+
+    #[test]
+    fn resume_reuses_manifest_and_keeps_completed_chunks() {
+        let root = tempfile::tempdir().unwrap();
+        let (source, mut manifest) = manifest_with_chunks(root.path());
+
+        assert!(
+            manifest.mark_completed(0).is_err(),
+            "output not written yet"
+        );
+        fs::write(&manifest.chunks[0].output, b"done").unwrap();
+        manifest.mark_completed(0).unwrap();
+
+        // No ffmpeg involved: a matching manifest is returned as-is.
+        let resumed = prepare_chunked_run(&source, &manifest.output, 300.0, 0.3).unwrap();
+        assert_eq!(resumed, manifest);
+        assert_eq!(resumed.completed_count(), 1);
+        assert!(!resumed.chunks[1].is_done());
+    }
+
+    #[test]
+    fn manifest_for_changed_source_does_not_match() {
+        let root = tempfile::tempdir().unwrap();
+        let (source, manifest) = manifest_with_chunks(root.path());
+        assert!(manifest.matches(&source, 300.0));
+        assert!(!manifest.matches(&source, 120.0));
+
+        fs::write(&source, b"a different source").unwrap();
+        assert!(!manifest.matches(&source, 300.0));
+    }
+}
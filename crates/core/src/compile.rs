@@ -1,20 +1,29 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
 
 use anyhow::{anyhow, bail, Context, Result};
 use petgraph::stable_graph::NodeIndex;
 
 use crate::debug_event::{build_print_debug_value_event, NodeDebugEventCallback};
-use crate::executor::{clone_port_data, port_data_from_json};
-use crate::graph::PipelineGraph;
-use crate::node::{ExecutionContext, FrameProcessor, Node};
+use crate::executor::{
+    clone_port_data, execute_node, insert_connected_input, node_timeout, port_data_from_json,
+    step_after, trace_finish, trace_start,
+};
+use crate::graph::{PipelineGraph, PortConnection};
+use crate::node::{ExecutionContext, FrameProcessor, Node, NodeDebugSink};
+use crate::node_error::NodeFailure;
 use crate::registry::NodeRegistry;
+use crate::services::NodeServices;
+use crate::step::Stepper;
 use crate::streaming_executor::{FrameInterpolator, FrameSink, PipelineStage};
+use crate::trace::JobTrace;
 use crate::types::{Frame, PortData, PortType};
 
+type FrameDecoder = Box<dyn Iterator<Item = Result<Frame>> + Send>;
+
 /// Compiled pipeline ready for `StreamingExecutor::execute_pipeline_stages()`.
 pub struct CompiledPipeline {
-    pub decoder: Box<dyn Iterator<Item = Result<Frame>> + Send>,
+    pub decoder: FrameDecoder,
     pub stages: Vec<PipelineStage>,
     pub encoder: Box<dyn FrameSink>,
     /// Total number of **input** frames (from the decoder / source probe).
@@ -22,6 +31,9 @@ pub struct CompiledPipeline {
     /// Total number of **output** frames after interpolation expansion.
     /// Equals `total_frames` when no interpolator is present.
     pub total_output_frames: Option<u64>,
+    /// Param outputs of every node executed while compiling, e.g. the
+    /// source and output paths.
+    pub outputs_by_node: HashMap<String, HashMap<String, PortData>>,
 }
 
 impl fmt::Debug for CompiledPipeline {
@@ -77,6 +89,27 @@ pub trait CompileContext {
         None
     }
 
+    /// Where nodes send debug events while they run; see
+    /// [`ExecutionContext::emit_debug`].
+    fn debug_sink(&self) -> Option<NodeDebugSink> {
+        None
+    }
+
+    /// Services handed to nodes through [`ExecutionContext::services`].
+    fn services(&self) -> NodeServices {
+        NodeServices::default()
+    }
+
+    /// The job's execution trace, if one is being recorded.
+    fn trace(&self) -> Option<JobTrace> {
+        None
+    }
+
+    /// Pauses the job after each node when it runs in step mode.
+    fn stepper(&self) -> Option<Stepper> {
+        None
+    }
+
     /// Create one or more streaming stages for a processing node.
     ///
     /// The default implementation preserves the original one-node -> one-stage
@@ -105,9 +138,13 @@ pub trait CompileContext {
 /// `StreamingExecutor::execute_pipeline_stages()`.
 ///
 /// The function walks the graph in topological order, validates that it
-/// represents a linear VideoFrames pipeline (no fan-out / fan-in), resolves
-/// parameter inputs for every node, and categorises nodes into source,
-/// processing stages, and sink.
+/// represents a linear VideoFrames pipeline (no fan-out, and fan-in only into
+/// list ports), resolves parameter inputs for every node, and categorises
+/// nodes into source, processing stages, and sink.
+///
+/// One node may join several sources through a list port, e.g. `Concat`.
+/// Its segments are decoded one after the other, in connection-index order,
+/// and the node itself adds no stage.
 pub fn compile_graph(
     graph: &PipelineGraph,
     registry: &NodeRegistry,
@@ -129,8 +166,9 @@ pub fn compile_graph_with_debug_hook(
     }
 
     validate_linear_topology(graph, registry, &execution_order)?;
+    let joiner_idx = find_joiner(graph, registry, &execution_order)?;
 
-    let mut source_idx: Option<NodeIndex> = None;
+    let mut source_indices: Vec<NodeIndex> = Vec::new();
     let mut sink_idx: Option<NodeIndex> = None;
     let mut processing_order: Vec<NodeIndex> = Vec::new();
 
@@ -139,12 +177,7 @@ pub fn compile_graph_with_debug_hook(
         let outgoing_vf = count_video_frames_edges(graph, node_idx, Direction::Outgoing);
 
         if incoming_vf == 0 && outgoing_vf > 0 {
-            if source_idx.is_some() {
-                bail!(
-                    "multiple source nodes detected — compile_graph only supports linear pipelines"
-                );
-            }
-            source_idx = Some(node_idx);
+            source_indices.push(node_idx);
         } else if incoming_vf > 0 && outgoing_vf == 0 {
             if sink_idx.is_some() {
                 bail!(
@@ -152,16 +185,30 @@ pub fn compile_graph_with_debug_hook(
                 );
             }
             sink_idx = Some(node_idx);
-        } else if incoming_vf > 0 && outgoing_vf > 0 {
+        } else if incoming_vf > 0 && outgoing_vf > 0 && Some(node_idx) != joiner_idx {
             processing_order.push(node_idx);
         }
     }
 
-    let source_idx =
-        source_idx.ok_or_else(|| anyhow!("no source node found in VideoFrames pipeline"))?;
+    if source_indices.is_empty() {
+        bail!("no source node found in VideoFrames pipeline");
+    }
+    let segment_sources = match joiner_idx {
+        Some(joiner_idx) => joined_sources(graph, joiner_idx, &source_indices)?,
+        None if source_indices.len() > 1 => {
+            bail!("multiple source nodes detected — compile_graph only supports linear pipelines")
+        }
+        None => source_indices,
+    };
     let sink_idx = sink_idx.ok_or_else(|| anyhow!("no sink node found in VideoFrames pipeline"))?;
 
-    let exec_ctx = ExecutionContext::default();
+    let mut exec_ctx = ExecutionContext {
+        debug_sink: ctx.debug_sink(),
+        services: ctx.services(),
+        trace: ctx.trace(),
+        stepper: ctx.stepper(),
+        ..Default::default()
+    };
     let mut outputs_by_node: HashMap<String, HashMap<String, PortData>> = HashMap::new();
 
     for &node_idx in &execution_order {
@@ -171,7 +218,7 @@ pub fn compile_graph_with_debug_hook(
             continue;
         }
         let instance = graph.node(node_idx);
-        let mut node = registry
+        let node = registry
             .create(&instance.node_type, instance.params.clone())
             .with_context(|| {
                 format!(
@@ -180,39 +227,87 @@ pub fn compile_graph_with_debug_hook(
                 )
             })?;
         let inputs = resolve_inputs(graph, registry, node_idx, &outputs_by_node)?;
-        let node_outputs = node
-            .execute(&inputs, &exec_ctx)
-            .with_context(|| format!("execution failed for param node '{}'", instance.id))?;
+        exec_ctx.node_id = instance.id.clone();
+        let timeout = node_timeout(instance, node.as_ref())?;
+        let span = trace_start(&exec_ctx, instance, &inputs);
+        let result = execute_node(node, inputs, &exec_ctx, timeout)
+            .with_context(|| NodeFailure::new("param node", instance));
+        trace_finish(span, &result);
+        let node_outputs = result?;
         emit_print_debug_event(
             &instance.id,
             &instance.node_type,
             &node_outputs,
             &mut node_debug_callback,
         );
+        step_after(&exec_ctx, instance, &node_outputs)?;
         outputs_by_node.insert(instance.id.clone(), node_outputs);
     }
 
-    let source_instance = graph.node(source_idx);
-    let mut source_node = registry
-        .create(&source_instance.node_type, source_instance.params.clone())
-        .with_context(|| {
-            format!(
-                "failed to instantiate source node '{}' of type '{}'",
-                source_instance.id, source_instance.node_type
-            )
-        })?;
-    let source_inputs = resolve_inputs(graph, registry, source_idx, &outputs_by_node)?;
-    let source_outputs = source_node
-        .execute(&source_inputs, &exec_ctx)
-        .with_context(|| format!("execution failed for source node '{}'", source_instance.id))?;
-    emit_print_debug_event(
-        &source_instance.id,
-        &source_instance.node_type,
-        &source_outputs,
-        &mut node_debug_callback,
-    );
-    let (decoder, total_frames) = ctx.create_decoder(source_node.as_mut(), &source_outputs)?;
-    outputs_by_node.insert(source_instance.id.clone(), source_outputs);
+    let mut segments: Vec<FrameDecoder> = Vec::new();
+    let mut total_frames: Option<u64> = Some(0);
+    for &source_idx in &segment_sources {
+        let source_instance = graph.node(source_idx);
+        let mut source_node = registry
+            .create(&source_instance.node_type, source_instance.params.clone())
+            .with_context(|| {
+                format!(
+                    "failed to instantiate source node '{}' of type '{}'",
+                    source_instance.id, source_instance.node_type
+                )
+            })?;
+        let source_inputs = resolve_inputs(graph, registry, source_idx, &outputs_by_node)?;
+        let span = trace_start(&exec_ctx, source_instance, &source_inputs);
+        let result = source_node
+            .execute(&source_inputs, &exec_ctx)
+            .with_context(|| NodeFailure::new("source node", source_instance));
+        trace_finish(span, &result);
+        let source_outputs = result?;
+        emit_print_debug_event(
+            &source_instance.id,
+            &source_instance.node_type,
+            &source_outputs,
+            &mut node_debug_callback,
+        );
+        step_after(&exec_ctx, source_instance, &source_outputs)?;
+        let (decoder, segment_frames) =
+            ctx.create_decoder(source_node.as_mut(), &source_outputs)?;
+        outputs_by_node.insert(source_instance.id.clone(), source_outputs);
+        segments.push(decoder);
+        total_frames = total_frames.zip(segment_frames).map(|(a, b)| a + b);
+    }
+    let decoder: FrameDecoder = if segments.len() == 1 {
+        segments.pop().expect("one segment")
+    } else {
+        Box::new(ConcatDecoder::new(segments))
+    };
+
+    if let Some(joiner_idx) = joiner_idx {
+        let instance = graph.node(joiner_idx);
+        let mut node = registry
+            .create(&instance.node_type, instance.params.clone())
+            .with_context(|| {
+                format!(
+                    "failed to instantiate node '{}' of type '{}'",
+                    instance.id, instance.node_type
+                )
+            })?;
+        let inputs = resolve_inputs(graph, registry, joiner_idx, &outputs_by_node)?;
+        let span = trace_start(&exec_ctx, instance, &inputs);
+        let result = node
+            .execute(&inputs, &exec_ctx)
+            .with_context(|| NodeFailure::new("node", instance));
+        trace_finish(span, &result);
+        let outputs = result?;
+        emit_print_debug_event(
+            &instance.id,
+            &instance.node_type,
+            &outputs,
+            &mut node_debug_callback,
+        );
+        step_after(&exec_ctx, instance, &outputs)?;
+        outputs_by_node.insert(instance.id.clone(), outputs);
+    }
 
     let mut stages: Vec<PipelineStage> = Vec::new();
 
@@ -227,15 +322,19 @@ pub fn compile_graph_with_debug_hook(
                 )
             })?;
         let inputs = resolve_inputs(graph, registry, node_idx, &outputs_by_node)?;
-        let outputs = node
+        let span = trace_start(&exec_ctx, instance, &inputs);
+        let result = node
             .execute(&inputs, &exec_ctx)
-            .with_context(|| format!("execution failed for node '{}'", instance.id))?;
+            .with_context(|| NodeFailure::new("node", instance));
+        trace_finish(span, &result);
+        let outputs = result?;
         emit_print_debug_event(
             &instance.id,
             &instance.node_type,
             &outputs,
             &mut node_debug_callback,
         );
+        step_after(&exec_ctx, instance, &outputs)?;
         outputs_by_node.insert(instance.id.clone(), outputs);
 
         let is_interpolator = ctx.is_interpolator_type(&instance.node_type);
@@ -269,6 +368,7 @@ pub fn compile_graph_with_debug_hook(
                 if let Ok(pd) = port_data_from_json(&PortType::Path, value)
                     .or_else(|_| port_data_from_json(&PortType::Str, value))
                     .or_else(|_| port_data_from_json(&PortType::Int, value))
+                    .or_else(|_| port_data_from_json(&PortType::Float, value))
                     .or_else(|_| port_data_from_json(&PortType::Bool, value))
                 {
                     fallback.insert(key.clone(), pd);
@@ -304,6 +404,7 @@ pub fn compile_graph_with_debug_hook(
         encoder,
         total_frames,
         total_output_frames,
+        outputs_by_node,
     })
 }
 
@@ -334,17 +435,18 @@ fn has_video_frames_ports(
 }
 
 /// Validate that the VideoFrames sub-graph is strictly linear: every node has
-/// at most 1 incoming VF edge and at most 1 outgoing VF edge.
+/// at most 1 outgoing VF edge, and at most 1 incoming VF edge unless they all
+/// go into list ports.
 fn validate_linear_topology(
     graph: &PipelineGraph,
-    _registry: &NodeRegistry,
+    registry: &NodeRegistry,
     execution_order: &[NodeIndex],
 ) -> Result<()> {
     for &node_idx in execution_order {
         let incoming_vf = count_video_frames_edges(graph, node_idx, Direction::Incoming);
         let outgoing_vf = count_video_frames_edges(graph, node_idx, Direction::Outgoing);
 
-        if incoming_vf > 1 {
+        if incoming_vf > 1 && !joins_video_frames(graph, registry, node_idx, true)? {
             let instance = graph.node(node_idx);
             bail!(
                 "node '{}' has {} incoming VideoFrames edges — \
@@ -366,6 +468,154 @@ fn validate_linear_topology(
     Ok(())
 }
 
+/// Whether VideoFrames connections into the node go into one of its list
+/// ports. With `all`, every such connection must.
+fn joins_video_frames(
+    graph: &PipelineGraph,
+    registry: &NodeRegistry,
+    node_idx: NodeIndex,
+    all: bool,
+) -> Result<bool> {
+    let instance = graph.node(node_idx);
+    let list_ports = registry
+        .create(&instance.node_type, instance.params.clone())
+        .with_context(|| {
+            format!(
+                "failed to instantiate node '{}' of type '{}'",
+                instance.id, instance.node_type
+            )
+        })?
+        .list_input_ports();
+    let mut video_frames = graph
+        .connections_to(node_idx)
+        .into_iter()
+        .filter(|(_, conn)| conn.port_type == PortType::VideoFrames);
+    let into_list =
+        |(_, conn): (NodeIndex, &PortConnection)| list_ports.contains(&conn.target_port);
+    Ok(if all {
+        video_frames.all(into_list)
+    } else {
+        video_frames.any(into_list)
+    })
+}
+
+/// The node that joins the VideoFrames of several sources through a list
+/// port, if there is one. Only one is supported per pipeline.
+fn find_joiner(
+    graph: &PipelineGraph,
+    registry: &NodeRegistry,
+    execution_order: &[NodeIndex],
+) -> Result<Option<NodeIndex>> {
+    let mut joiner: Option<NodeIndex> = None;
+    for &node_idx in execution_order {
+        if !joins_video_frames(graph, registry, node_idx, false)? {
+            continue;
+        }
+        if let Some(other) = joiner {
+            bail!(
+                "nodes '{}' and '{}' both join VideoFrames segments — \
+                 compile_graph supports one join per pipeline",
+                graph.node(other).id,
+                graph.node(node_idx).id
+            );
+        }
+        joiner = Some(node_idx);
+    }
+    Ok(joiner)
+}
+
+/// Sources feeding the joiner, one per connection in index order. Segments
+/// must come straight from source nodes, and every source must feed the
+/// joiner.
+fn joined_sources(
+    graph: &PipelineGraph,
+    joiner_idx: NodeIndex,
+    source_indices: &[NodeIndex],
+) -> Result<Vec<NodeIndex>> {
+    let joiner_id = &graph.node(joiner_idx).id;
+    let segments: Vec<NodeIndex> = graph
+        .connections_to(joiner_idx)
+        .into_iter()
+        .filter(|(_, conn)| conn.port_type == PortType::VideoFrames)
+        .map(|(source_idx, _)| source_idx)
+        .collect();
+    for source_idx in &segments {
+        if !source_indices.contains(source_idx) {
+            bail!(
+                "node '{}' feeds '{}' but is not a source node — \
+                 joined segments must come straight from sources",
+                graph.node(*source_idx).id,
+                joiner_id
+            );
+        }
+    }
+    for source_idx in source_indices {
+        if !segments.contains(source_idx) {
+            bail!(
+                "source node '{}' does not feed '{}' — \
+                 compile_graph only supports one stream after a join",
+                graph.node(*source_idx).id,
+                joiner_id
+            );
+        }
+    }
+    Ok(segments)
+}
+
+/// Frames of several decoders, one after the other. Every segment must have
+/// the frame size of the first.
+struct ConcatDecoder {
+    segments: VecDeque<FrameDecoder>,
+    segment: usize,
+    dimensions: Option<(u32, u32)>,
+}
+
+impl ConcatDecoder {
+    fn new(segments: Vec<FrameDecoder>) -> Self {
+        Self {
+            segments: segments.into(),
+            segment: 0,
+            dimensions: None,
+        }
+    }
+}
+
+impl Iterator for ConcatDecoder {
+    type Item = Result<Frame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let frame = match self.segments.front_mut()?.next() {
+                Some(Ok(frame)) => frame,
+                Some(Err(err)) => {
+                    return Some(Err(err.context(format!("segment {}", self.segment))))
+                }
+                None => {
+                    self.segments.pop_front();
+                    self.segment += 1;
+                    continue;
+                }
+            };
+            let (width, height) = frame.dimensions();
+            match self.dimensions {
+                None => self.dimensions = Some((width, height)),
+                Some((expected_width, expected_height))
+                    if (expected_width, expected_height) != (width, height) =>
+                {
+                    self.segments.clear();
+                    return Some(Err(anyhow!(
+                        "segment {} is {width}x{height}, but the first segment is \
+                         {expected_width}x{expected_height}; joined segments must have the same frame size",
+                        self.segment
+                    )));
+                }
+                Some(_) => {}
+            }
+            return Some(Ok(frame));
+        }
+    }
+}
+
 /// Count VideoFrames-typed edges in the given direction for a node.
 fn count_video_frames_edges(
     graph: &PipelineGraph,
@@ -400,6 +650,7 @@ fn resolve_inputs(
             )
         })?;
     let input_port_defs = node.input_ports();
+    let list_ports = node.list_input_ports();
     let mut inputs: HashMap<String, PortData> = HashMap::new();
 
     for (source_idx, connection) in graph.connections_to(node_idx) {
@@ -422,7 +673,12 @@ fn resolve_inputs(
             )
         })?;
 
-        inputs.insert(connection.target_port.clone(), clone_port_data(data));
+        insert_connected_input(
+            &mut inputs,
+            &list_ports,
+            &connection.target_port,
+            clone_port_data(data),
+        );
     }
 
     for input_port in input_port_defs {
@@ -941,6 +1197,9 @@ mod tests {
         registry.register("mock_processor", |_| Ok(Box::new(MockProcessorNode)));
         registry.register("mock_interpolator", |_| Ok(Box::new(MockInterpolatorNode)));
         registry.register("mock_sink", |_| Ok(Box::new(MockSinkNode)));
+        registry.register("Concat", |_| {
+            Ok(Box::new(crate::nodes::concat::ConcatNode::new()))
+        });
 
         registry.register("int_source", |_| {
             Ok(Box::new(IntOnlyNode {
@@ -1022,6 +1281,7 @@ mod tests {
                     source_port: "frames".to_string(),
                     target_port: "frames".to_string(),
                     port_type: PortType::VideoFrames,
+                    index: None,
                 },
                 "processor",
             )
@@ -1033,6 +1293,7 @@ mod tests {
                     source_port: "frames".to_string(),
                     target_port: "frames".to_string(),
                     port_type: PortType::VideoFrames,
+                    index: None,
                 },
                 "sink",
             )
@@ -1095,6 +1356,7 @@ mod tests {
                     source_port: "frames".to_string(),
                     target_port: "frames".to_string(),
                     port_type: PortType::VideoFrames,
+                    index: None,
                 },
                 "processor",
             )
@@ -1106,6 +1368,7 @@ mod tests {
                     source_port: "frames".to_string(),
                     target_port: "frames".to_string(),
                     port_type: PortType::VideoFrames,
+                    index: None,
                 },
                 "interpolator",
             )
@@ -1117,6 +1380,7 @@ mod tests {
                     source_port: "frames".to_string(),
                     target_port: "frames".to_string(),
                     port_type: PortType::VideoFrames,
+                    index: None,
                 },
                 "sink",
             )
@@ -1179,6 +1443,7 @@ mod tests {
                     source_port: "frames".to_string(),
                     target_port: "frames".to_string(),
                     port_type: PortType::VideoFrames,
+                    index: None,
                 },
                 "proc_a",
             )
@@ -1190,6 +1455,7 @@ mod tests {
                     source_port: "frames".to_string(),
                     target_port: "frames".to_string(),
                     port_type: PortType::VideoFrames,
+                    index: None,
                 },
                 "proc_b",
             )
@@ -1201,6 +1467,7 @@ mod tests {
                     source_port: "frames".to_string(),
                     target_port: "frames".to_string(),
                     port_type: PortType::VideoFrames,
+                    index: None,
                 },
                 "sink",
             )
@@ -1250,6 +1517,7 @@ mod tests {
                     source_port: "out".to_string(),
                     target_port: "in".to_string(),
                     port_type: PortType::Int,
+                    index: None,
                 },
                 "proc",
             )
@@ -1261,6 +1529,7 @@ mod tests {
                     source_port: "out".to_string(),
                     target_port: "in".to_string(),
                     port_type: PortType::Int,
+                    index: None,
                 },
                 "snk",
             )
@@ -1326,6 +1595,7 @@ mod tests {
                     source_port: "frames".to_string(),
                     target_port: "frames".to_string(),
                     port_type: PortType::VideoFrames,
+                    index: None,
                 },
                 "sink",
             )
@@ -1389,6 +1659,7 @@ mod tests {
                     source_port: "value".to_string(),
                     target_port: "value".to_string(),
                     port_type: PortType::Str,
+                    index: None,
                 },
                 "print_source",
             )
@@ -1400,6 +1671,7 @@ mod tests {
                     source_port: "value".to_string(),
                     target_port: "value".to_string(),
                     port_type: PortType::Str,
+                    index: None,
                 },
                 "print_processing",
             )
@@ -1411,6 +1683,7 @@ mod tests {
                     source_port: "value".to_string(),
                     target_port: "value".to_string(),
                     port_type: PortType::Str,
+                    index: None,
                 },
                 "print_sink",
             )
@@ -1423,6 +1696,7 @@ mod tests {
                     source_port: "frames".to_string(),
                     target_port: "frames".to_string(),
                     port_type: PortType::VideoFrames,
+                    index: None,
                 },
                 "print_processing",
             )
@@ -1434,6 +1708,7 @@ mod tests {
                     source_port: "frames".to_string(),
                     target_port: "frames".to_string(),
                     port_type: PortType::VideoFrames,
+                    index: None,
                 },
                 "print_sink",
             )
@@ -1469,4 +1744,126 @@ mod tests {
             .iter()
             .all(|event| event.value_preview == "preview-value"));
     }
+
+    fn segment_connection(index: u32) -> PortConnection {
+        PortConnection {
+            source_port: "frames".to_string(),
+            target_port: "segments".to_string(),
+            port_type: PortType::VideoFrames,
+            index: Some(index),
+        }
+    }
+
+    fn build_concat_graph() -> PipelineGraph {
+        let mut graph = PipelineGraph::new();
+        for (id, node_type) in [
+            ("intro", "mock_source"),
+            ("main", "mock_source"),
+            ("concat", "Concat"),
+            ("processor", "mock_processor"),
+            ("sink", "mock_sink"),
+        ] {
+            graph
+                .add_node(NodeInstance {
+                    id: id.to_string(),
+                    node_type: node_type.to_string(),
+                    params: HashMap::new(),
+                })
+                .unwrap();
+        }
+        let frames = || PortConnection {
+            source_port: "frames".to_string(),
+            target_port: "frames".to_string(),
+            port_type: PortType::VideoFrames,
+            index: None,
+        };
+        graph
+            .add_connection("main", segment_connection(1), "concat")
+            .unwrap();
+        graph
+            .add_connection("intro", segment_connection(0), "concat")
+            .unwrap();
+        graph
+            .add_connection("concat", frames(), "processor")
+            .unwrap();
+        graph.add_connection("processor", frames(), "sink").unwrap();
+        graph
+    }
+
+    #[test]
+    fn test_compile_concat_joins_sources_in_index_order() {
+        let registry = build_video_registry();
+        let compile_ctx = MockCompileContext::new(3);
+        let graph = build_concat_graph();
+        graph
+            .validate(&registry)
+            .expect("concat graph should validate");
+
+        let compiled =
+            compile_graph(&graph, &registry, &compile_ctx).expect("concat graph should compile");
+
+        assert_eq!(compiled.stages.len(), 1, "Concat adds no stage");
+        assert_eq!(compiled.total_frames, Some(6));
+        let frames: Vec<Frame> = compiled.decoder.map(Result::unwrap).collect();
+        assert_eq!(frames.len(), 6);
+        assert!(compiled.outputs_by_node.contains_key("intro"));
+        assert!(compiled.outputs_by_node.contains_key("main"));
+    }
+
+    #[test]
+    fn test_compile_concat_rejects_source_outside_join() {
+        let registry = build_video_registry();
+        let compile_ctx = MockCompileContext::new(3);
+        let mut graph = build_concat_graph();
+        graph
+            .add_node(NodeInstance {
+                id: "stray".to_string(),
+                node_type: "mock_source".to_string(),
+                params: HashMap::new(),
+            })
+            .unwrap();
+        graph
+            .add_connection(
+                "stray",
+                PortConnection {
+                    source_port: "frames".to_string(),
+                    target_port: "frames".to_string(),
+                    port_type: PortType::VideoFrames,
+                    index: None,
+                },
+                "processor",
+            )
+            .unwrap();
+
+        let err = compile_graph(&graph, &registry, &compile_ctx)
+            .expect_err("a source outside the join should be rejected");
+        assert!(err.to_string().contains("fan-in"), "got: {err}");
+    }
+
+    #[test]
+    fn test_concat_decoder_rejects_mismatched_frame_size() {
+        let segment = |width: u32, count: usize| -> FrameDecoder {
+            let frames: Vec<Result<Frame>> = (0..count)
+                .map(|_| {
+                    Ok(Frame::CpuRgb {
+                        data: vec![0; width as usize * 3],
+                        width,
+                        height: 1,
+                        bit_depth: 8,
+                    })
+                })
+                .collect();
+            Box::new(frames.into_iter())
+        };
+
+        let mut decoder = ConcatDecoder::new(vec![segment(2, 2), segment(2, 1), segment(4, 1)]);
+        for _ in 0..3 {
+            assert!(decoder.next().unwrap().is_ok());
+        }
+        let Some(Err(err)) = decoder.next() else {
+            panic!("a 4x1 frame after 2x1 ones should be an error");
+        };
+        assert!(err.to_string().contains("segment 2 is 4x1"), "got: {err}");
+        assert!(decoder.next().is_none());
+    }
 }
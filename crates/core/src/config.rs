@@ -7,6 +7,9 @@ use serde::{Deserialize, Serialize};
 
 const CONFIG_FILE_NAME: &str = "config.toml";
 const ENV_DATA_DIR: &str = "VIDENOA_DATA_DIR";
+/// File next to the desktop executable that turns on portable mode.
+pub const PORTABLE_MARKER_FILE: &str = "videnoa.portable";
+const PORTABLE_DATA_DIR_NAME: &str = "data";
 pub const FALLBACK_LOCALE: &str = "en";
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -16,15 +19,34 @@ pub struct AppConfig {
     pub server: ServerConfig,
     pub locale: String,
     pub performance: PerformanceConfig,
+    pub logging: LoggingConfig,
+    pub redaction: RedactionConfig,
+    pub workers: WorkersConfig,
+    pub jobs: JobsConfig,
+    pub commands: CommandsConfig,
+    pub torrents: TorrentsConfig,
+    pub memory: MemoryConfig,
+    pub workflows: WorkflowsConfig,
+    pub presets: PresetsConfig,
+    pub jellyfin: JellyfinConfig,
+    pub power: PowerConfig,
+    pub updates: UpdatesConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(default)]
 pub struct PathsConfig {
     pub models_dir: PathBuf,
+    /// More directories scanned for `.onnx` files, after `models_dir`.
+    /// Downloads still go to `models_dir`.
+    pub extra_models_dirs: Vec<PathBuf>,
     pub trt_cache_dir: PathBuf,
     pub presets_dir: PathBuf,
     pub workflows_dir: PathBuf,
+    pub plugins_dir: PathBuf,
+    /// Directories whose files `POST /api/probe` may read. Empty disables
+    /// the endpoint.
+    pub media_roots: Vec<PathBuf>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -32,12 +54,56 @@ pub struct PathsConfig {
 pub struct ServerConfig {
     pub port: u16,
     pub host: String,
+    /// Reject every request that would start a job or change config or
+    /// files, so the server can be shown as a status dashboard.
+    pub read_only: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(default)]
 pub struct PerformanceConfig {
     pub profiling_enabled: bool,
+    /// Idle ONNX Runtime sessions kept for later jobs; `0` disables the cache.
+    pub session_cache_size: usize,
+    /// Models loaded into the session cache at server start.
+    pub warmup: Vec<WarmupModel>,
+    /// Seconds between samples of the performance series while profiling.
+    pub sample_interval_secs: u64,
+    /// Hours of performance samples kept in the jobs database; `0` keeps all.
+    pub series_retention_hours: u64,
+}
+
+/// A model to build a session for, and run once, at server start.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct WarmupModel {
+    /// Model file; relative paths are resolved against `paths.models_dir`.
+    pub model: PathBuf,
+    /// `cuda` or `tensorrt`.
+    pub backend: String,
+    /// Input size of the warm-up run, so TensorRT builds its engine for it.
+    pub height: usize,
+    pub width: usize,
+}
+
+impl Default for WarmupModel {
+    fn default() -> Self {
+        Self {
+            model: PathBuf::new(),
+            backend: "cuda".to_string(),
+            height: 1080,
+            width: 1920,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct LoggingConfig {
+    /// Number of daily log files kept by the rolling file sink.
+    pub retention_files: usize,
+    /// Upper bound for the combined size of rotated log files; `0` disables the cap.
+    pub max_total_size_mb: u64,
 }
 
 impl Default for AppConfig {
@@ -47,6 +113,18 @@ impl Default for AppConfig {
             server: ServerConfig::default(),
             locale: FALLBACK_LOCALE.to_string(),
             performance: PerformanceConfig::default(),
+            logging: LoggingConfig::default(),
+            redaction: RedactionConfig::default(),
+            workers: WorkersConfig::default(),
+            jobs: JobsConfig::default(),
+            commands: CommandsConfig::default(),
+            torrents: TorrentsConfig::default(),
+            memory: MemoryConfig::default(),
+            workflows: WorkflowsConfig::default(),
+            presets: PresetsConfig::default(),
+            jellyfin: JellyfinConfig::default(),
+            power: PowerConfig::default(),
+            updates: UpdatesConfig::default(),
         }
     }
 }
@@ -63,13 +141,31 @@ pub fn normalize_supported_locale(locale: &str) -> String {
     }
 }
 
+impl PathsConfig {
+    /// `models_dir` followed by `extra_models_dirs`, in search order.
+    pub fn models_dirs(&self) -> impl Iterator<Item = &PathBuf> {
+        std::iter::once(&self.models_dir).chain(&self.extra_models_dirs)
+    }
+
+    /// Where the model file `filename` is, looking in [`Self::models_dirs`]
+    /// order.
+    pub fn find_model_file(&self, filename: &str) -> Option<PathBuf> {
+        self.models_dirs()
+            .map(|dir| dir.join(filename))
+            .find(|path| path.is_file())
+    }
+}
+
 impl Default for PathsConfig {
     fn default() -> Self {
         Self {
             models_dir: PathBuf::from("models"),
+            extra_models_dirs: Vec::new(),
             trt_cache_dir: PathBuf::from("trt_cache"),
             presets_dir: PathBuf::from("presets"),
             workflows_dir: PathBuf::from("data/workflows"),
+            plugins_dir: PathBuf::from("plugins"),
+            media_roots: Vec::new(),
         }
     }
 }
@@ -79,6 +175,7 @@ impl Default for ServerConfig {
         Self {
             port: 3000,
             host: "0.0.0.0".to_string(),
+            read_only: false,
         }
     }
 }
@@ -87,10 +184,252 @@ impl Default for PerformanceConfig {
     fn default() -> Self {
         Self {
             profiling_enabled: false,
+            session_cache_size: crate::runtime::session_pool::DEFAULT_SESSION_CACHE_SIZE,
+            warmup: Vec::new(),
+            sample_interval_secs: 5,
+            series_retention_hours: 24,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RedactionConfig {
+    /// Extra parameter/field names whose values are always masked.
+    pub param_names: Vec<String>,
+    /// Regular expressions whose matches are masked in logs and job records.
+    pub patterns: Vec<String>,
+}
+
+impl RedactionConfig {
+    pub fn compile(&self) -> Result<crate::logging::RedactionRules> {
+        crate::logging::RedactionRules::compile(&self.param_names, &self.patterns)
+            .context("invalid redaction pattern")
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobDispatchMode {
+    /// Jobs execute inside this process.
+    #[default]
+    Local,
+    /// Jobs wait in the queue until a registered remote worker claims them.
+    Remote,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct WorkersConfig {
+    pub dispatch: JobDispatchMode,
+    /// Name of a stored secret holding the bearer token workers must present.
+    /// Worker endpoints are disabled when unset.
+    pub token_secret: Option<String>,
+    /// Workers that miss heartbeats for this long are dropped and their
+    /// claimed jobs are returned to the queue.
+    pub heartbeat_timeout_secs: u64,
+}
+
+impl Default for WorkersConfig {
+    fn default() -> Self {
+        Self {
+            dispatch: JobDispatchMode::Local,
+            token_secret: None,
+            heartbeat_timeout_secs: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct JobsConfig {
+    /// Finished jobs kept in the history; older ones are deleted. `0` keeps all.
+    pub retention_max_jobs: usize,
+    /// Finished jobs older than this many days are deleted. `0` keeps all.
+    pub retention_max_age_days: u64,
+    /// Jobs that may run at once per resource class their nodes need (see
+    /// `ResourceClass`). A job takes a slot in every class it uses; only one
+    /// job at a time gets the GPU. `0` makes a class share the GPU slot.
+    /// Read at startup.
+    pub max_cpu_jobs: usize,
+    pub max_network_jobs: usize,
+    pub max_disk_jobs: usize,
+    /// Seconds a node may run before the job fails, for nodes without a
+    /// `timeout_secs` param of their own. `0` means no limit.
+    pub node_timeout_secs: u64,
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        Self {
+            retention_max_jobs: 0,
+            retention_max_age_days: 0,
+            max_cpu_jobs: 2,
+            max_network_jobs: 4,
+            max_disk_jobs: 3,
+            node_timeout_secs: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueEmptyAction {
+    #[default]
+    None,
+    Suspend,
+    Shutdown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct PowerConfig {
+    /// Keep the machine from sleeping while jobs are queued or running.
+    pub prevent_sleep: bool,
+    /// What to do once the last job finishes and the queue stays empty for
+    /// a minute.
+    pub when_queue_empty: QueueEmptyAction,
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            prevent_sleep: true,
+            when_queue_empty: QueueEmptyAction::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    /// Full releases only.
+    #[default]
+    Stable,
+    /// Pre-releases as well.
+    Beta,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct UpdatesConfig {
+    pub channel: UpdateChannel,
+    /// `http(s)://` or `file://` URL of a GitHub releases list.
+    pub releases_url: String,
+}
+
+impl Default for UpdatesConfig {
+    fn default() -> Self {
+        Self {
+            channel: UpdateChannel::Stable,
+            releases_url: "https://api.github.com/repos/ControlNet/videnoa/releases".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct CommandsConfig {
+    /// Binaries the `ExternalCommand` node may run, matched exactly against
+    /// the node's `command`. Empty disables the node.
+    pub allowed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct TorrentsConfig {
+    /// Lets the `TorrentInput` node download magnet links and `.torrent`
+    /// files. Off by default.
+    pub enabled: bool,
+    /// Where torrents are downloaded; empty uses `videnoa/torrents` in the
+    /// system temp directory.
+    pub download_dir: PathBuf,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct MemoryConfig {
+    /// Host RAM the pipelines may hold in frame buffers and sessions before
+    /// decoding is throttled. `0` uses 80% of physical memory.
+    pub host_budget_mb: u64,
+    /// VRAM budget for sessions and tile batches; tiles shrink to stay
+    /// within it. `0` disables the limit.
+    pub vram_budget_mb: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct WorkflowsConfig {
+    /// Previous versions kept per saved workflow; `0` disables the history.
+    pub keep_versions: usize,
+}
+
+impl Default for WorkflowsConfig {
+    fn default() -> Self {
+        Self { keep_versions: 20 }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct PresetsConfig {
+    /// `http(s)://` or `file://` URL of a community preset index; empty
+    /// disables community presets.
+    pub community_index_url: String,
+    /// Hours between syncs of the community index.
+    pub sync_interval_hours: u64,
+}
+
+impl Default for PresetsConfig {
+    fn default() -> Self {
+        Self {
+            community_index_url: String::new(),
+            sync_interval_hours: 24,
         }
     }
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct JellyfinConfig {
+    /// Servers the Jellyfin endpoints and `JellyfinVideo` nodes can refer to
+    /// by name instead of taking a URL and API key.
+    pub servers: Vec<JellyfinServerConfig>,
+    /// Server used when a request or node names neither a server nor a URL.
+    /// Empty means there is no default.
+    pub default_server: String,
+}
+
+impl JellyfinConfig {
+    pub fn server(&self, name: &str) -> Option<&JellyfinServerConfig> {
+        self.servers.iter().find(|server| server.name == name)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct JellyfinServerConfig {
+    pub name: String,
+    pub url: String,
+    /// Name of the stored secret holding the server's API key.
+    pub api_key_secret: String,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            retention_files: crate::logging::DEFAULT_LOG_RETENTION_FILES,
+            max_total_size_mb: 512,
+        }
+    }
+}
+
+impl LoggingConfig {
+    pub fn max_total_size_bytes(&self) -> Option<u64> {
+        (self.max_total_size_mb > 0).then(|| self.max_total_size_mb.saturating_mul(1024 * 1024))
+    }
+}
+
 impl AppConfig {
     pub fn load_from_path(path: &Path) -> Result<Self> {
         if !path.exists() {
@@ -139,6 +478,31 @@ pub fn data_dir(cli_override: Option<&Path>) -> PathBuf {
     PathBuf::from("data")
 }
 
+/// The directory of `exe` when a [`PORTABLE_MARKER_FILE`] sits next to it.
+/// `VIDENOA_DATA_DIR` takes precedence, so it turns portable mode off.
+pub fn portable_root(exe: &Path) -> Option<PathBuf> {
+    if env::var_os(ENV_DATA_DIR).is_some() {
+        return None;
+    }
+    let root = exe.parent()?;
+    root.join(PORTABLE_MARKER_FILE)
+        .is_file()
+        .then(|| root.to_path_buf())
+}
+
+/// Data directory of a portable install at `root`: the path written in the
+/// marker file, relative to `root`, or `<root>/data` when the marker is
+/// empty.
+pub fn portable_data_dir(root: &Path) -> PathBuf {
+    let configured = fs::read_to_string(root.join(PORTABLE_MARKER_FILE)).unwrap_or_default();
+    let configured = configured.trim();
+    if configured.is_empty() {
+        root.join(PORTABLE_DATA_DIR_NAME)
+    } else {
+        resolve_relative_to(root, Path::new(configured))
+    }
+}
+
 /// Returns the path to config.toml within the given data directory.
 pub fn config_path(data_dir: &Path) -> PathBuf {
     data_dir.join(CONFIG_FILE_NAME)
@@ -188,11 +552,14 @@ mod tests {
         assert_eq!(cfg.paths.trt_cache_dir, PathBuf::from("trt_cache"));
         assert_eq!(cfg.paths.presets_dir, PathBuf::from("presets"));
         assert_eq!(cfg.paths.workflows_dir, PathBuf::from("data/workflows"));
+        assert_eq!(cfg.paths.plugins_dir, PathBuf::from("plugins"));
 
         assert_eq!(cfg.server.port, 3000);
         assert_eq!(cfg.server.host, "0.0.0.0");
         assert_eq!(cfg.locale, "en");
         assert!(!cfg.performance.profiling_enabled);
+        assert_eq!(cfg.logging.retention_files, 14);
+        assert_eq!(cfg.logging.max_total_size_mb, 512);
     }
 
     #[test]
@@ -256,6 +623,25 @@ mod tests {
         assert_eq!(result, PathBuf::from("data"));
     }
 
+    #[test]
+    fn portable_data_dir_reads_marker_file() {
+        let root = unique_temp_dir();
+        fs::create_dir_all(&root).expect("create temp dir");
+        let marker = root.join(PORTABLE_MARKER_FILE);
+
+        fs::write(&marker, "").expect("write marker");
+        assert_eq!(portable_data_dir(&root), root.join("data"));
+
+        fs::write(&marker, "profiles/anime\n").expect("write marker");
+        assert_eq!(portable_data_dir(&root), root.join("profiles/anime"));
+
+        let absolute = env::temp_dir().join("videnoa-elsewhere");
+        fs::write(&marker, absolute.to_string_lossy().as_bytes()).expect("write marker");
+        assert_eq!(portable_data_dir(&root), absolute);
+
+        fs::remove_dir_all(&root).ok();
+    }
+
     #[test]
     fn config_path_is_data_dir_join_config_toml() {
         let result = config_path(Path::new("/data"));
@@ -39,8 +39,9 @@ pub fn build_print_debug_value_event(
 pub fn format_port_data_preview(value: &PortData, max_chars: usize) -> (String, bool) {
     let raw = match value {
         PortData::Metadata(metadata) => format!(
-            "MediaMetadata(source_path={}, audio_streams={}, subtitle_streams={}, attachment_streams={}, chapters={}, global_metadata={}, container_format={})",
+            "MediaMetadata(source_path={}, video_streams={}, audio_streams={}, subtitle_streams={}, attachment_streams={}, chapters={}, global_metadata={}, container_format={})",
             metadata.source_path.display(),
+            metadata.video_streams.len(),
             metadata.audio_streams.len(),
             metadata.subtitle_streams.len(),
             metadata.attachment_streams.len(),
@@ -53,6 +54,8 @@ pub fn format_port_data_preview(value: &PortData, max_chars: usize) -> (String,
         PortData::Str(v) => v.clone(),
         PortData::Bool(v) => v.to_string(),
         PortData::Path(v) => v.display().to_string(),
+        PortData::Json(v) => v.to_string(),
+        PortData::List(items) => format!("List(len={})", items.len()),
     };
 
     truncate_preview(&raw, max_chars)
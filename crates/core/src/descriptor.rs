@@ -5,26 +5,49 @@
 //! node editor. They are a **separate data path** from the runtime
 //! `Node::input_ports()`/`output_ports()` — the runtime trait is
 //! unchanged.
+//!
+//! Display names, descriptions and port labels carry translation keys and
+//! are localized from the bundled tables in [`crate::node_i18n`].
 
 use serde::Serialize;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct NodeDescriptor {
     pub node_type: String,
     pub display_name: String,
-    /// "input", "processing", "output", "utility"
+    /// One-line summary shown in the palette.
+    pub description: String,
+    /// Translation key of `display_name`, `node.<node_type>.title`.
+    pub label_key: String,
+    /// Translation key of `description`, `node.<node_type>.description`.
+    pub description_key: String,
+    /// "input", "processing", "output", "utility", "workflow"; the palette
+    /// groups nodes by it.
     pub category: String,
     /// Hex color, e.g. "#F97316"
     pub accent_color: String,
     /// Icon name, e.g. "microscope", "film"
     pub icon: String,
+    /// Still settling: params may change between releases. The palette
+    /// badges it.
+    pub experimental: bool,
+    /// Kept only so existing workflows keep running. The palette hides it
+    /// and the editor points at `replaced_by`.
+    pub deprecated: bool,
+    /// Node type to use instead of this one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replaced_by: Option<String>,
     pub inputs: Vec<PortDescriptor>,
     pub outputs: Vec<PortDescriptor>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct PortDescriptor {
     pub name: String,
+    /// Human-readable name; falls back to `name`.
+    pub label: String,
+    /// Translation key of `label`, `port.<name>`.
+    pub label_key: String,
     /// "VideoFrames", "Metadata", "Int", "Str", etc.
     pub port_type: String,
     /// "stream" or "param"
@@ -34,6 +57,8 @@ pub struct PortDescriptor {
     /// "model_selector", "enum", "path_picker", etc.
     pub ui_hint: Option<String>,
     pub enum_options: Option<Vec<String>>,
+    /// Accepts any number of connections, ordered by their `to_index`.
+    pub list: bool,
     /// For future Constant node
     pub dynamic_type_param: Option<String>,
 }
@@ -49,6 +74,7 @@ fn stream(name: &str, port_type: &str) -> PortDescriptor {
         ui_hint: None,
         enum_options: None,
         dynamic_type_param: None,
+        ..Default::default()
     }
 }
 
@@ -63,6 +89,7 @@ fn param_required(name: &str, port_type: &str) -> PortDescriptor {
         ui_hint: None,
         enum_options: None,
         dynamic_type_param: None,
+        ..Default::default()
     }
 }
 
@@ -77,15 +104,67 @@ fn param_opt(name: &str, port_type: &str, default: serde_json::Value) -> PortDes
         ui_hint: None,
         enum_options: None,
         dynamic_type_param: None,
+        ..Default::default()
+    }
+}
+
+/// Pre/post-processing params shared by the inference nodes, see
+/// [`ModelPreprocess`](crate::nodes::model_preprocess::ModelPreprocess).
+fn model_preprocess_params() -> Vec<PortDescriptor> {
+    use crate::nodes::model_preprocess::{
+        CHANNEL_ORDER_OPTIONS, OUTPUT_RANGE_OPTIONS, VALUE_RANGE_OPTIONS,
+    };
+    let options = |values: &[&str]| Some(values.iter().map(|v| v.to_string()).collect());
+    vec![
+        PortDescriptor {
+            enum_options: options(&VALUE_RANGE_OPTIONS),
+            ..param_opt("value_range", "Str", serde_json::json!("auto"))
+        },
+        PortDescriptor {
+            enum_options: options(&OUTPUT_RANGE_OPTIONS),
+            ..param_opt("output_range", "Str", serde_json::json!("same"))
+        },
+        PortDescriptor {
+            enum_options: options(&CHANNEL_ORDER_OPTIONS),
+            ..param_opt("channel_order", "Str", serde_json::json!("rgb"))
+        },
+        param_opt("pad_align", "Int", serde_json::json!(0)),
+    ]
+}
+
+/// Static-region skipping params shared by the model nodes, see `crate::roi`.
+fn static_region_params() -> Vec<PortDescriptor> {
+    vec![
+        param_opt("skip_static_regions", "Bool", serde_json::json!(false)),
+        param_opt(
+            "static_threshold",
+            "Int",
+            serde_json::json!(crate::roi::DEFAULT_STATIC_THRESHOLD),
+        ),
+    ]
+}
+
+/// Returns descriptors for all registered node types, in English.
+pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
+    localized_node_descriptors(crate::config::FALLBACK_LOCALE)
+}
+
+/// Returns descriptors for all registered node types with names,
+/// descriptions and port labels in `locale` where a translation exists.
+/// Descriptors exported by loaded plugins are appended at the end.
+pub fn localized_node_descriptors(locale: &str) -> Vec<NodeDescriptor> {
+    let mut descriptors = builtin_node_descriptors();
+    descriptors.extend(crate::plugin::plugin_node_descriptors());
+    for descriptor in &mut descriptors {
+        crate::node_i18n::localize_descriptor(descriptor, locale);
     }
+    descriptors
 }
 
-/// Returns descriptors for all registered node types.
-///
 /// Port data is hardcoded to match the runtime `Node` implementations.
 /// Stream ports (VideoFrames in/out) are listed separately from param
 /// ports (which correspond to `Node::input_ports()` / `output_ports()`).
-pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
+fn builtin_node_descriptors() -> Vec<NodeDescriptor> {
     vec![
         // ---------------------------------------------------------------
         // 1. VideoInput
@@ -93,12 +172,29 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
         NodeDescriptor {
             node_type: "VideoInput".to_string(),
             display_name: "Video Input".to_string(),
+            description: "Decodes a video file into frames and metadata.".to_string(),
             category: "input".to_string(),
             accent_color: "#A855F7".to_string(),
             icon: "file-video".to_string(),
             inputs: vec![
                 // param: from VideoInputNode::input_ports()
                 param_required("path", "Path"),
+                PortDescriptor {
+                    enum_options: Some(vec![
+                        "none".to_string(),
+                        "auto".to_string(),
+                        "nvdec".to_string(),
+                        "qsv".to_string(),
+                        "vaapi".to_string(),
+                        "videotoolbox".to_string(),
+                    ]),
+                    ..param_opt("hwaccel", "Str", serde_json::json!("none"))
+                },
+                param_opt("video_stream", "Int", serde_json::json!(-1)),
+                param_opt("audio_stream", "Int", serde_json::json!(-1)),
+                param_opt("start_frame", "Int", serde_json::json!(0)),
+                param_opt("end_frame", "Int", serde_json::json!(-1)),
+                param_opt("segments", "Str", serde_json::json!("")),
             ],
             outputs: vec![
                 // stream
@@ -110,6 +206,7 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                     ..param_required("source_path", "Path")
                 },
             ],
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // 2. SuperResolution
@@ -117,6 +214,7 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
         NodeDescriptor {
             node_type: "SuperResolution".to_string(),
             display_name: "Super Resolution".to_string(),
+            description: "Upscales frames with a super-resolution model.".to_string(),
             category: "processing".to_string(),
             accent_color: "#F97316".to_string(),
             icon: "microscope".to_string(),
@@ -135,11 +233,28 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                     enum_options: Some(vec!["cuda".to_string(), "tensorrt".to_string()]),
                     ..param_opt("backend", "Str", serde_json::json!("cuda"))
                 },
-            ],
+            ]
+            .into_iter()
+            .chain(model_preprocess_params())
+            .chain([
+                param_opt("auto_target_fps", "Float", serde_json::json!(0.0)),
+                PortDescriptor {
+                    enum_options: Some(
+                        crate::benchmark::AUTO_PREFERENCE_OPTIONS
+                            .iter()
+                            .map(|v| v.to_string())
+                            .collect(),
+                    ),
+                    ..param_opt("auto_preference", "Str", serde_json::json!("quality"))
+                },
+            ])
+            .chain(static_region_params())
+            .collect(),
             outputs: vec![
                 // stream
                 stream("frames", "VideoFrames"),
             ],
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // 3. FrameInterpolation
@@ -147,6 +262,7 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
         NodeDescriptor {
             node_type: "FrameInterpolation".to_string(),
             display_name: "Frame Interpolation".to_string(),
+            description: "Synthesizes in-between frames to raise the frame rate.".to_string(),
             category: "processing".to_string(),
             accent_color: "#06B6D4".to_string(),
             icon: "film".to_string(),
@@ -166,6 +282,164 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                 },
             ],
             outputs: vec![stream("frames", "VideoFrames")],
+            ..Default::default()
+        },
+        // ---------------------------------------------------------------
+        // Python
+        // ---------------------------------------------------------------
+        NodeDescriptor {
+            node_type: "Python".to_string(),
+            display_name: "Python".to_string(),
+            description: "Processes frames with a Python script.".to_string(),
+            category: "processing".to_string(),
+            accent_color: "#F97316".to_string(),
+            icon: "terminal".to_string(),
+            inputs: vec![
+                // stream
+                stream("frames", "VideoFrames"),
+                // param: from PythonNode::input_ports()
+                param_required("script", "Path"),
+                param_opt("python", "Str", serde_json::json!("python3")),
+                param_opt("scale", "Int", serde_json::json!(1)),
+                param_opt("params_json", "Str", serde_json::json!("{}")),
+            ],
+            outputs: vec![stream("frames", "VideoFrames")],
+            experimental: true,
+            ..Default::default()
+        },
+        // ---------------------------------------------------------------
+        // FfmpegFilter
+        // ---------------------------------------------------------------
+        NodeDescriptor {
+            node_type: "FfmpegFilter".to_string(),
+            display_name: "FFmpeg Filter".to_string(),
+            description: "Applies an FFmpeg filter graph to the frames.".to_string(),
+            category: "processing".to_string(),
+            accent_color: "#22C55E".to_string(),
+            icon: "sliders-horizontal".to_string(),
+            inputs: vec![
+                // stream
+                stream("frames", "VideoFrames"),
+                // param: from FfmpegFilterNode::input_ports()
+                param_required("filter", "Str"),
+            ],
+            outputs: vec![stream("frames", "VideoFrames")],
+            ..Default::default()
+        },
+        // ---------------------------------------------------------------
+        // GrainSynthesis
+        // ---------------------------------------------------------------
+        NodeDescriptor {
+            node_type: "GrainSynthesis".to_string(),
+            display_name: "Grain Synthesis".to_string(),
+            description: "Adds synthetic film grain.".to_string(),
+            category: "processing".to_string(),
+            accent_color: "#A8A29E".to_string(),
+            icon: "sparkles".to_string(),
+            inputs: vec![
+                // stream
+                stream("frames", "VideoFrames"),
+                // param: from GrainSynthesisNode::input_ports()
+                param_opt("intensity", "Float", serde_json::json!(0.03)),
+                param_opt("grain_size", "Float", serde_json::json!(1.0)),
+                param_opt("chroma", "Float", serde_json::json!(0.2)),
+                param_opt("seed", "Int", serde_json::json!(0)),
+            ],
+            outputs: vec![stream("frames", "VideoFrames")],
+            ..Default::default()
+        },
+        // ---------------------------------------------------------------
+        // PreviewTap
+        // ---------------------------------------------------------------
+        NodeDescriptor {
+            node_type: "PreviewTap".to_string(),
+            display_name: "Preview Tap".to_string(),
+            description: "Publishes a live JPEG preview of the frames passing through.".to_string(),
+            category: "processing".to_string(),
+            accent_color: "#38BDF8".to_string(),
+            icon: "eye".to_string(),
+            inputs: vec![
+                // stream
+                stream("frames", "VideoFrames"),
+                // param: from PreviewTapNode::input_ports()
+                param_opt("interval_secs", "Float", serde_json::json!(2.0)),
+                param_opt("max_width", "Int", serde_json::json!(480)),
+            ],
+            outputs: vec![stream("frames", "VideoFrames")],
+            ..Default::default()
+        },
+        // ---------------------------------------------------------------
+        // RegionEnhance
+        // ---------------------------------------------------------------
+        NodeDescriptor {
+            node_type: "RegionEnhance".to_string(),
+            display_name: "Region Enhance".to_string(),
+            description: "Enhances masked regions, such as faces, with a separate model."
+                .to_string(),
+            category: "processing".to_string(),
+            accent_color: "#EC4899".to_string(),
+            icon: "scan-face".to_string(),
+            inputs: vec![
+                // stream
+                stream("frames", "VideoFrames"),
+                // param: from RegionEnhanceNode::input_ports()
+                PortDescriptor {
+                    ui_hint: Some("model_selector".to_string()),
+                    ..param_required("mask_model_path", "Path")
+                },
+                PortDescriptor {
+                    ui_hint: Some("model_selector".to_string()),
+                    ..param_required("model_path", "Path")
+                },
+                PortDescriptor {
+                    ui_hint: Some("model_selector".to_string()),
+                    ..param_opt("region_model_path", "Path", serde_json::json!(""))
+                },
+                param_opt("scale", "Int", serde_json::json!(4)),
+                param_opt("strength", "Float", serde_json::json!(1.0)),
+                param_opt("region_strength", "Float", serde_json::json!(1.0)),
+                param_opt("threshold", "Float", serde_json::json!(0.5)),
+                param_opt("feather", "Int", serde_json::json!(8)),
+                param_opt("tile_size", "Int", serde_json::json!(0)),
+                PortDescriptor {
+                    enum_options: Some(vec!["cuda".to_string(), "tensorrt".to_string()]),
+                    ..param_opt("backend", "Str", serde_json::json!("cuda"))
+                },
+            ],
+            outputs: vec![stream("frames", "VideoFrames")],
+            ..Default::default()
+        },
+        // ---------------------------------------------------------------
+        // ModelInference
+        // ---------------------------------------------------------------
+        NodeDescriptor {
+            node_type: "ModelInference".to_string(),
+            display_name: "Model Inference".to_string(),
+            description: "Runs an arbitrary ONNX model on each frame.".to_string(),
+            category: "processing".to_string(),
+            accent_color: "#8B5CF6".to_string(),
+            icon: "brain".to_string(),
+            inputs: vec![
+                // stream
+                stream("frames", "VideoFrames"),
+                // param: from ModelInferenceNode::input_ports(); the model's
+                // extra inputs are added by the editor from model inspection
+                PortDescriptor {
+                    ui_hint: Some("model_selector".to_string()),
+                    ..param_required("model_path", "Path")
+                },
+                param_opt("scale", "Int", serde_json::json!(1)),
+                PortDescriptor {
+                    enum_options: Some(vec!["cuda".to_string(), "tensorrt".to_string()]),
+                    ..param_opt("backend", "Str", serde_json::json!("cuda"))
+                },
+            ]
+            .into_iter()
+            .chain(model_preprocess_params())
+            .chain(static_region_params())
+            .collect(),
+            outputs: vec![stream("frames", "VideoFrames")],
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // 4. VideoOutput
@@ -173,6 +447,7 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
         NodeDescriptor {
             node_type: "VideoOutput".to_string(),
             display_name: "Video Output".to_string(),
+            description: "Encodes frames into a video file.".to_string(),
             category: "output".to_string(),
             accent_color: "#10B981".to_string(),
             icon: "hard-drive".to_string(),
@@ -194,6 +469,21 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                 param_required("width", "Int"),
                 param_required("height", "Int"),
                 param_required("fps", "Str"),
+                PortDescriptor {
+                    enum_options: Some(vec!["direct".to_string(), "temp_rename".to_string()]),
+                    ..param_opt("output_strategy", "Str", serde_json::json!("direct"))
+                },
+                param_opt("temp_suffix", "Str", serde_json::json!(".part")),
+                PortDescriptor {
+                    enum_options: Some(vec!["append".to_string(), "before_extension".to_string()]),
+                    ..param_opt("temp_suffix_position", "Str", serde_json::json!("append"))
+                },
+                param_opt("verify_output", "Bool", serde_json::json!(false)),
+                PortDescriptor {
+                    enum_options: Some(vec!["none".to_string(), "sha256".to_string()]),
+                    ..param_opt("checksum", "Str", serde_json::json!("none"))
+                },
+                param_opt("compare_every_mins", "Float", serde_json::json!(0.0)),
             ],
             outputs: vec![
                 // param: from VideoOutputNode::output_ports()
@@ -202,6 +492,7 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                     ..param_required("output_path", "Path")
                 },
             ],
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // 5. Resize
@@ -209,6 +500,7 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
         NodeDescriptor {
             node_type: "Resize".to_string(),
             display_name: "Resize".to_string(),
+            description: "Scales frames to a fixed width and height.".to_string(),
             category: "processing".to_string(),
             accent_color: "#3B82F6".to_string(),
             icon: "scaling".to_string(),
@@ -224,10 +516,12 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                 },
             ],
             outputs: vec![stream("frames", "VideoFrames")],
+            ..Default::default()
         },
         NodeDescriptor {
             node_type: "Rescale".to_string(),
             display_name: "Rescale".to_string(),
+            description: "Scales frames by a factor.".to_string(),
             category: "processing".to_string(),
             accent_color: "#3B82F6".to_string(),
             icon: "scaling".to_string(),
@@ -240,6 +534,21 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                 },
             ],
             outputs: vec![stream("frames", "VideoFrames")],
+            ..Default::default()
+        },
+        NodeDescriptor {
+            node_type: "Concat".to_string(),
+            display_name: "Concat".to_string(),
+            description: "Joins video segments one after the other.".to_string(),
+            category: "processing".to_string(),
+            accent_color: "#3B82F6".to_string(),
+            icon: "film".to_string(),
+            inputs: vec![PortDescriptor {
+                list: true,
+                ..stream("segments", "VideoFrames")
+            }],
+            outputs: vec![stream("frames", "VideoFrames")],
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // 6. ColorSpace
@@ -247,6 +556,7 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
         NodeDescriptor {
             node_type: "ColorSpace".to_string(),
             display_name: "Color Space".to_string(),
+            description: "Describes the color matrix, range and transfer for encoding.".to_string(),
             category: "processing".to_string(),
             accent_color: "#EAB308".to_string(),
             icon: "palette".to_string(),
@@ -275,6 +585,7 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                     ..param_required("config", "Str")
                 },
             ],
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // 7. SceneDetect
@@ -282,6 +593,7 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
         NodeDescriptor {
             node_type: "SceneDetect".to_string(),
             display_name: "Scene Detect".to_string(),
+            description: "Flags frames that start a new scene.".to_string(),
             category: "processing".to_string(),
             accent_color: "#EF4444".to_string(),
             icon: "scissors".to_string(),
@@ -300,20 +612,165 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                     ..param_required("is_scene_change", "Bool")
                 },
             ],
+            ..Default::default()
+        },
+        // ---------------------------------------------------------------
+        // ---------------------------------------------------------------
+        NodeDescriptor {
+            node_type: "AnalyzeSource".to_string(),
+            display_name: "Analyze Source".to_string(),
+            description: "Samples a video and reports noise, banding and upscaling.".to_string(),
+            category: "utility".to_string(),
+            accent_color: "#6366F1".to_string(),
+            icon: "activity".to_string(),
+            inputs: vec![
+                param_required("path", "Path"),
+                param_opt("sample_count", "Int", serde_json::json!(8)),
+            ],
+            outputs: vec![
+                param_required("report", "Json"),
+                param_required("noise_level", "Float"),
+                param_required("banding", "Float"),
+                param_required("interlaced", "Bool"),
+                param_required("estimated_height", "Int"),
+                param_required("upscaled", "Bool"),
+            ],
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // ---------------------------------------------------------------
         NodeDescriptor {
             node_type: "Downloader".to_string(),
             display_name: "Downloader".to_string(),
+            description: "Downloads a file from a URL.".to_string(),
             category: "input".to_string(),
             accent_color: "#A855F7".to_string(),
             icon: "download".to_string(),
-            inputs: vec![param_required("url", "Str")],
+            inputs: vec![
+                param_required("url", "Str"),
+                param_opt("sha256", "Str", serde_json::json!("")),
+                param_opt("max_bytes_per_sec", "Int", serde_json::json!(0)),
+                param_opt("resume", "Bool", serde_json::json!(true)),
+            ],
             outputs: vec![PortDescriptor {
                 direction: "param".to_string(),
                 ..param_required("path", "Path")
             }],
+            ..Default::default()
+        },
+        // ---------------------------------------------------------------
+        // TorrentInput
+        // ---------------------------------------------------------------
+        NodeDescriptor {
+            node_type: "TorrentInput".to_string(),
+            display_name: "Torrent Input".to_string(),
+            description: "Downloads a torrent and outputs its largest video file.".to_string(),
+            category: "input".to_string(),
+            accent_color: "#A855F7".to_string(),
+            icon: "magnet".to_string(),
+            inputs: vec![
+                param_required("source", "Str"),
+                param_opt("timeout_secs", "Int", serde_json::json!(0)),
+            ],
+            outputs: vec![param_required("path", "Path")],
+            ..Default::default()
+        },
+        // ---------------------------------------------------------------
+        // RemoteFetch
+        // ---------------------------------------------------------------
+        NodeDescriptor {
+            node_type: "RemoteFetch".to_string(),
+            display_name: "Remote Fetch".to_string(),
+            description: "Copies a file from S3, WebDAV or SFTP storage.".to_string(),
+            category: "input".to_string(),
+            accent_color: "#A855F7".to_string(),
+            icon: "cloud-download".to_string(),
+            inputs: vec![
+                param_required("url", "Str"),
+                param_opt("endpoint", "Str", serde_json::json!("")),
+                param_opt("region", "Str", serde_json::json!("us-east-1")),
+                param_opt("auth_secret", "Str", serde_json::json!("")),
+            ],
+            outputs: vec![param_required("path", "Path")],
+            ..Default::default()
+        },
+        // ---------------------------------------------------------------
+        // RemoteUpload
+        // ---------------------------------------------------------------
+        NodeDescriptor {
+            node_type: "RemoteUpload".to_string(),
+            display_name: "Remote Upload".to_string(),
+            description: "Copies a file to S3, WebDAV or SFTP storage.".to_string(),
+            category: "output".to_string(),
+            accent_color: "#10B981".to_string(),
+            icon: "cloud-upload".to_string(),
+            inputs: vec![
+                param_required("path", "Path"),
+                param_required("url", "Str"),
+                param_opt("endpoint", "Str", serde_json::json!("")),
+                param_opt("region", "Str", serde_json::json!("us-east-1")),
+                param_opt("auth_secret", "Str", serde_json::json!("")),
+            ],
+            outputs: vec![param_required("url", "Str")],
+            ..Default::default()
+        },
+        // ---------------------------------------------------------------
+        // StreamInput
+        // ---------------------------------------------------------------
+        NodeDescriptor {
+            node_type: "StreamInput".to_string(),
+            display_name: "Stream Input".to_string(),
+            description: "Reads frames from a live stream.".to_string(),
+            category: "input".to_string(),
+            accent_color: "#A855F7".to_string(),
+            icon: "radio".to_string(),
+            inputs: vec![
+                // param: from StreamInputNode::input_ports()
+                param_required("url", "Str"),
+                param_opt("protocol_options", "Str", serde_json::json!("")),
+                param_opt("max_reconnects", "Int", serde_json::json!(10)),
+                param_opt("reconnect_delay_secs", "Int", serde_json::json!(2)),
+            ],
+            outputs: vec![
+                // stream
+                stream("frames", "VideoFrames"),
+                // param: from StreamInputNode::output_ports()
+                stream("metadata", "Metadata"),
+                PortDescriptor {
+                    direction: "param".to_string(),
+                    ..param_required("source_url", "Str")
+                },
+            ],
+            ..Default::default()
+        },
+        // ---------------------------------------------------------------
+        // VapourSynthInput
+        // ---------------------------------------------------------------
+        NodeDescriptor {
+            node_type: "VapourSynthInput".to_string(),
+            display_name: "VapourSynth Input".to_string(),
+            description: "Reads frames from a VapourSynth script.".to_string(),
+            category: "input".to_string(),
+            accent_color: "#A855F7".to_string(),
+            icon: "file-code".to_string(),
+            inputs: vec![
+                // param: from VapourSynthInputNode::input_ports()
+                param_required("script_path", "Path"),
+                param_opt("script_args", "Str", serde_json::json!("")),
+                param_opt("audio_source", "Path", serde_json::json!("")),
+            ],
+            outputs: vec![
+                // stream
+                stream("frames", "VideoFrames"),
+                // param: from VapourSynthInputNode::output_ports()
+                stream("metadata", "Metadata"),
+                PortDescriptor {
+                    direction: "param".to_string(),
+                    ..param_required("source_path", "Path")
+                },
+            ],
+            experimental: true,
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // 9. StreamOutput
@@ -321,12 +778,13 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
         NodeDescriptor {
             node_type: "StreamOutput".to_string(),
             display_name: "Stream Output".to_string(),
+            description: "Publishes frames to a live stream.".to_string(),
             category: "output".to_string(),
             accent_color: "#10B981".to_string(),
             icon: "radio".to_string(),
             inputs: vec![
                 // param: from StreamOutputNode::input_ports()
-                param_required("url", "Str"),
+                param_opt("url", "Str", serde_json::json!("")),
                 PortDescriptor {
                     enum_options: Some(vec!["libx265".to_string(), "libx264".to_string()]),
                     ..param_opt("codec", "Str", serde_json::json!("libx264"))
@@ -341,6 +799,11 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                     ..param_opt("format", "Str", serde_json::json!("flv"))
                 },
                 param_opt("source_url", "Str", serde_json::json!("")),
+                PortDescriptor {
+                    enum_options: Some(vec!["push".to_string(), "hls".to_string()]),
+                    ..param_opt("mode", "Str", serde_json::json!("push"))
+                },
+                param_opt("stream_id", "Str", serde_json::json!("")),
             ],
             outputs: vec![
                 // param: from StreamOutputNode::output_ports()
@@ -349,24 +812,34 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                     ..param_required("output_url", "Str")
                 },
             ],
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // ---------------------------------------------------------------
         NodeDescriptor {
             node_type: "JellyfinVideo".to_string(),
             display_name: "Jellyfin Video".to_string(),
+            description: "Resolves a Jellyfin library item to a video file.".to_string(),
             category: "input".to_string(),
             accent_color: "#A855F7".to_string(),
             icon: "tv".to_string(),
             inputs: vec![
-                param_required("jellyfin_url", "Str"),
-                param_required("api_key", "Str"),
+                PortDescriptor {
+                    ui_hint: Some("jellyfin_server".to_string()),
+                    ..param_opt("server", "Str", serde_json::json!(""))
+                },
+                param_opt("jellyfin_url", "Str", serde_json::json!("")),
+                param_opt("api_key", "Str", serde_json::json!("")),
                 param_required("item_id", "Str"),
+                param_opt("skip_min_width", "Int", serde_json::json!(0)),
+                param_opt("skip_min_height", "Int", serde_json::json!(0)),
+                param_opt("skip_codecs", "Str", serde_json::json!("")),
             ],
             outputs: vec![PortDescriptor {
                 direction: "param".to_string(),
                 ..param_required("video_url", "Str")
             }],
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // 11. Constant
@@ -374,6 +847,7 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
         NodeDescriptor {
             node_type: "Constant".to_string(),
             display_name: "Constant".to_string(),
+            description: "Provides a fixed value to other nodes.".to_string(),
             category: "utility".to_string(),
             accent_color: "#6366F1".to_string(),
             icon: "hash".to_string(),
@@ -394,12 +868,14 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                 dynamic_type_param: Some("type".to_string()),
                 ..param_required("value", "Int")
             }],
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // ---------------------------------------------------------------
         NodeDescriptor {
             node_type: "Print".to_string(),
             display_name: "Print".to_string(),
+            description: "Logs a value while the workflow runs.".to_string(),
             category: "utility".to_string(),
             accent_color: "#6366F1".to_string(),
             icon: "hash".to_string(),
@@ -423,12 +899,14 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                 dynamic_type_param: Some("value_type".to_string()),
                 ..param_required("value", "Str")
             }],
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // ---------------------------------------------------------------
         NodeDescriptor {
             node_type: "PathDivider".to_string(),
             display_name: "Path Divider".to_string(),
+            description: "Splits a path into its directory, stem and extension.".to_string(),
             category: "utility".to_string(),
             accent_color: "#6366F1".to_string(),
             icon: "split".to_string(),
@@ -451,12 +929,14 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                     ..param_required("file_extension", "Str")
                 },
             ],
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // ---------------------------------------------------------------
         NodeDescriptor {
             node_type: "PathJoiner".to_string(),
             display_name: "Path Joiner".to_string(),
+            description: "Joins a parent directory, sub-path and file name.".to_string(),
             category: "utility".to_string(),
             accent_color: "#6366F1".to_string(),
             icon: "split".to_string(),
@@ -469,12 +949,14 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                 direction: "param".to_string(),
                 ..param_required("path", "Path")
             }],
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // ---------------------------------------------------------------
         NodeDescriptor {
             node_type: "StringTemplate".to_string(),
             display_name: "String Template".to_string(),
+            description: "Fills a text template from its inputs.".to_string(),
             category: "utility".to_string(),
             accent_color: "#6366F1".to_string(),
             icon: "braces".to_string(),
@@ -487,12 +969,14 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                 direction: "param".to_string(),
                 ..param_required("value", "Str")
             }],
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // ---------------------------------------------------------------
         NodeDescriptor {
             node_type: "StringReplace".to_string(),
             display_name: "String Replace".to_string(),
+            description: "Replaces text within a string.".to_string(),
             category: "utility".to_string(),
             accent_color: "#6366F1".to_string(),
             icon: "replace".to_string(),
@@ -505,12 +989,14 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                 direction: "param".to_string(),
                 ..param_required("output", "Str")
             }],
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // ---------------------------------------------------------------
         NodeDescriptor {
             node_type: "TypeConversion".to_string(),
             display_name: "Type Conversion".to_string(),
+            description: "Converts a value between types.".to_string(),
             category: "utility".to_string(),
             accent_color: "#6366F1".to_string(),
             icon: "arrow-left-right".to_string(),
@@ -522,6 +1008,7 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                         "Str".to_string(),
                         "Bool".to_string(),
                         "Path".to_string(),
+                        "Json".to_string(),
                     ]),
                     ..param_opt("input_type", "Str", serde_json::json!("Int"))
                 },
@@ -532,6 +1019,7 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                         "Str".to_string(),
                         "Bool".to_string(),
                         "Path".to_string(),
+                        "Json".to_string(),
                     ]),
                     ..param_opt("output_type", "Str", serde_json::json!("Int"))
                 },
@@ -545,23 +1033,284 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                 dynamic_type_param: Some("output_type".to_string()),
                 ..param_required("value", "Int")
             }],
+            ..Default::default()
+        },
+        NodeDescriptor {
+            node_type: "JsonExtract".to_string(),
+            display_name: "JSON Extract".to_string(),
+            description: "Reads one value out of JSON by path.".to_string(),
+            category: "utility".to_string(),
+            accent_color: "#6366F1".to_string(),
+            icon: "braces".to_string(),
+            inputs: vec![
+                param_required("json", "Json"),
+                param_opt("path", "Str", serde_json::json!("")),
+                PortDescriptor {
+                    enum_options: Some(vec![
+                        "Int".to_string(),
+                        "Float".to_string(),
+                        "Str".to_string(),
+                        "Bool".to_string(),
+                        "Path".to_string(),
+                        "Json".to_string(),
+                    ]),
+                    ..param_opt("output_type", "Str", serde_json::json!("Json"))
+                },
+            ],
+            outputs: vec![PortDescriptor {
+                dynamic_type_param: Some("output_type".to_string()),
+                ..param_required("value", "Json")
+            }],
+            ..Default::default()
+        },
+        // ---------------------------------------------------------------
+        // ---------------------------------------------------------------
+        NodeDescriptor {
+            node_type: "Script".to_string(),
+            display_name: "Script".to_string(),
+            description: "Computes outputs with a Rhai script.".to_string(),
+            category: "utility".to_string(),
+            accent_color: "#6366F1".to_string(),
+            icon: "code".to_string(),
+            inputs: vec![
+                param_required("script", "Str"),
+                PortDescriptor {
+                    enum_options: Some(vec![
+                        "Int".to_string(),
+                        "Float".to_string(),
+                        "Str".to_string(),
+                        "Bool".to_string(),
+                        "Path".to_string(),
+                    ]),
+                    ..param_opt("input_type", "Str", serde_json::json!("Str"))
+                },
+                PortDescriptor {
+                    enum_options: Some(vec![
+                        "Int".to_string(),
+                        "Float".to_string(),
+                        "Str".to_string(),
+                        "Bool".to_string(),
+                        "Path".to_string(),
+                    ]),
+                    ..param_opt("output_type", "Str", serde_json::json!("Str"))
+                },
+                PortDescriptor {
+                    required: false,
+                    dynamic_type_param: Some("input_type".to_string()),
+                    ..param_required("a", "Str")
+                },
+                PortDescriptor {
+                    required: false,
+                    dynamic_type_param: Some("input_type".to_string()),
+                    ..param_required("b", "Str")
+                },
+                PortDescriptor {
+                    required: false,
+                    dynamic_type_param: Some("input_type".to_string()),
+                    ..param_required("c", "Str")
+                },
+            ],
+            outputs: vec![PortDescriptor {
+                dynamic_type_param: Some("output_type".to_string()),
+                ..param_required("value", "Str")
+            }],
+            ..Default::default()
+        },
+        // ---------------------------------------------------------------
+        // ---------------------------------------------------------------
+        NodeDescriptor {
+            node_type: "Expression".to_string(),
+            display_name: "Expression".to_string(),
+            description: "Computes a value from an arithmetic or logic expression.".to_string(),
+            category: "utility".to_string(),
+            accent_color: "#6366F1".to_string(),
+            icon: "calculator".to_string(),
+            inputs: vec![
+                param_required("expression", "Str"),
+                PortDescriptor {
+                    enum_options: Some(vec![
+                        "Int".to_string(),
+                        "Float".to_string(),
+                        "Str".to_string(),
+                        "Bool".to_string(),
+                    ]),
+                    ..param_opt("output_type", "Str", serde_json::json!("Float"))
+                },
+                PortDescriptor {
+                    required: false,
+                    ..param_required("x", "Float")
+                },
+                PortDescriptor {
+                    required: false,
+                    ..param_required("y", "Float")
+                },
+                PortDescriptor {
+                    required: false,
+                    ..param_required("i", "Int")
+                },
+                PortDescriptor {
+                    required: false,
+                    ..param_required("j", "Int")
+                },
+                PortDescriptor {
+                    required: false,
+                    ..param_required("s", "Str")
+                },
+                PortDescriptor {
+                    required: false,
+                    ..param_required("t", "Str")
+                },
+            ],
+            outputs: vec![PortDescriptor {
+                direction: "param".to_string(),
+                dynamic_type_param: Some("output_type".to_string()),
+                ..param_required("value", "Float")
+            }],
+            ..Default::default()
+        },
+        // ---------------------------------------------------------------
+        // ---------------------------------------------------------------
+        NodeDescriptor {
+            node_type: "DateTime".to_string(),
+            display_name: "Date/Time".to_string(),
+            description: "Outputs the current date and time as formatted text.".to_string(),
+            category: "utility".to_string(),
+            accent_color: "#6366F1".to_string(),
+            icon: "clock".to_string(),
+            inputs: vec![
+                param_opt("format", "Str", serde_json::json!("%Y-%m-%d_%H-%M-%S")),
+                param_opt("utc", "Bool", serde_json::json!(false)),
+            ],
+            outputs: vec![
+                PortDescriptor {
+                    direction: "param".to_string(),
+                    ..param_required("value", "Str")
+                },
+                PortDescriptor {
+                    direction: "param".to_string(),
+                    ..param_required("unix", "Int")
+                },
+            ],
+            ..Default::default()
+        },
+        // ---------------------------------------------------------------
+        // ---------------------------------------------------------------
+        NodeDescriptor {
+            node_type: "Uuid".to_string(),
+            display_name: "UUID".to_string(),
+            description: "Outputs a new random UUID.".to_string(),
+            category: "utility".to_string(),
+            accent_color: "#6366F1".to_string(),
+            icon: "fingerprint".to_string(),
+            inputs: vec![param_opt("simple", "Bool", serde_json::json!(false))],
+            outputs: vec![PortDescriptor {
+                direction: "param".to_string(),
+                ..param_required("value", "Str")
+            }],
+            ..Default::default()
+        },
+        // ---------------------------------------------------------------
+        // ---------------------------------------------------------------
+        NodeDescriptor {
+            node_type: "HostInfo".to_string(),
+            display_name: "Host Info".to_string(),
+            description: "Outputs the hostname, GPU name and videnoa version.".to_string(),
+            category: "utility".to_string(),
+            accent_color: "#6366F1".to_string(),
+            icon: "server".to_string(),
+            inputs: vec![param_opt("device_id", "Int", serde_json::json!(0))],
+            outputs: vec![
+                PortDescriptor {
+                    direction: "param".to_string(),
+                    ..param_required("hostname", "Str")
+                },
+                PortDescriptor {
+                    direction: "param".to_string(),
+                    ..param_required("gpu_name", "Str")
+                },
+                PortDescriptor {
+                    direction: "param".to_string(),
+                    ..param_required("version", "Str")
+                },
+            ],
+            ..Default::default()
+        },
+        // ---------------------------------------------------------------
+        // ---------------------------------------------------------------
+        NodeDescriptor {
+            node_type: "ExternalCommand".to_string(),
+            display_name: "External Command".to_string(),
+            description: "Runs an external program.".to_string(),
+            category: "utility".to_string(),
+            accent_color: "#6366F1".to_string(),
+            icon: "terminal".to_string(),
+            inputs: vec![
+                param_required("command", "Str"),
+                param_opt("args", "Str", serde_json::json!("")),
+                PortDescriptor {
+                    required: false,
+                    ..param_required("input", "Path")
+                },
+                param_opt("output_extension", "Str", serde_json::json!("")),
+                param_opt("timeout_secs", "Int", serde_json::json!(300)),
+            ],
+            outputs: vec![
+                param_required("exit_code", "Int"),
+                param_required("stdout", "Str"),
+                param_required("stderr", "Str"),
+                param_required("output_path", "Path"),
+            ],
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // ---------------------------------------------------------------
         NodeDescriptor {
             node_type: "HttpRequest".to_string(),
             display_name: "HTTP Request".to_string(),
+            description: "Sends an HTTP request and returns the response.".to_string(),
             category: "utility".to_string(),
             accent_color: "#6366F1".to_string(),
             icon: "globe".to_string(),
             inputs: vec![
-                param_opt("method", "Str", serde_json::json!("GET")),
+                PortDescriptor {
+                    enum_options: Some(vec![
+                        "GET".to_string(),
+                        "POST".to_string(),
+                        "PUT".to_string(),
+                        "PATCH".to_string(),
+                        "DELETE".to_string(),
+                        "HEAD".to_string(),
+                    ]),
+                    ..param_opt("method", "Str", serde_json::json!("GET"))
+                },
                 param_required("url", "Str"),
                 param_opt("headers_json", "Str", serde_json::json!("{}")),
+                PortDescriptor {
+                    required: false,
+                    ..param_required("headers", "Json")
+                },
                 param_opt("body", "Str", serde_json::json!("")),
+                PortDescriptor {
+                    required: false,
+                    ..param_required("json_body", "Json")
+                },
+                PortDescriptor {
+                    enum_options: Some(vec![
+                        "none".to_string(),
+                        "bearer".to_string(),
+                        "basic".to_string(),
+                    ]),
+                    ..param_opt("auth", "Str", serde_json::json!("none"))
+                },
+                param_opt("auth_secret", "Str", serde_json::json!("")),
                 param_opt("timeout_ms", "Int", serde_json::json!(30000)),
                 param_opt("max_retries", "Int", serde_json::json!(2)),
                 param_opt("retry_backoff_ms", "Int", serde_json::json!(250)),
+                param_opt(
+                    "retry_on_status",
+                    "Str",
+                    serde_json::json!("429,502,503,504"),
+                ),
                 param_opt("max_response_bytes", "Int", serde_json::json!(1048576)),
             ],
             outputs: vec![
@@ -585,35 +1334,45 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                     direction: "param".to_string(),
                     ..param_required("content_type", "Str")
                 },
+                PortDescriptor {
+                    direction: "param".to_string(),
+                    ..param_required("response_json", "Json")
+                },
             ],
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // ---------------------------------------------------------------
         NodeDescriptor {
             node_type: "WorkflowInput".to_string(),
             display_name: "Workflow Input".to_string(),
+            description: "Declares the inputs of a reusable workflow.".to_string(),
             category: "workflow".to_string(),
             accent_color: "#EAB308".to_string(),
             icon: "arrow-down-to-line".to_string(),
             inputs: vec![],
             outputs: vec![],
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // ---------------------------------------------------------------
         NodeDescriptor {
             node_type: "WorkflowOutput".to_string(),
             display_name: "Workflow Output".to_string(),
+            description: "Declares the outputs of a reusable workflow.".to_string(),
             category: "workflow".to_string(),
             accent_color: "#EAB308".to_string(),
             icon: "arrow-up-from-line".to_string(),
             inputs: vec![],
             outputs: vec![],
+            ..Default::default()
         },
         // ---------------------------------------------------------------
         // ---------------------------------------------------------------
         NodeDescriptor {
             node_type: "Workflow".to_string(),
             display_name: "Workflow".to_string(),
+            description: "Runs another workflow as a single node.".to_string(),
             category: "workflow".to_string(),
             accent_color: "#EAB308".to_string(),
             icon: "workflow".to_string(),
@@ -622,6 +1381,7 @@ pub fn all_node_descriptors() -> Vec<NodeDescriptor> {
                 ..param_required("workflow_path", "WorkflowPath")
             }],
             outputs: vec![],
+            ..Default::default()
         },
     ]
 }
@@ -633,7 +1393,7 @@ mod tests {
     #[test]
     fn test_all_node_descriptors_count() {
         let descs = all_node_descriptors();
-        assert_eq!(descs.len(), 22);
+        assert_eq!(descs.len(), 42);
     }
 
     #[test]
@@ -642,7 +1402,7 @@ mod tests {
         let mut types: Vec<&str> = descs.iter().map(|d| d.node_type.as_str()).collect();
         types.sort();
         types.dedup();
-        assert_eq!(types.len(), 22);
+        assert_eq!(types.len(), 42);
     }
 
     #[test]
@@ -696,7 +1456,7 @@ mod tests {
         let vi = descs.iter().find(|d| d.node_type == "VideoInput").unwrap();
         assert_eq!(vi.display_name, "Video Input");
         assert_eq!(vi.category, "input");
-        assert_eq!(vi.inputs.len(), 1);
+        assert_eq!(vi.inputs.len(), 7);
         assert_eq!(vi.outputs.len(), 3);
     }
 
@@ -709,10 +1469,19 @@ mod tests {
             .unwrap();
         assert_eq!(sr.display_name, "Super Resolution");
         assert_eq!(sr.category, "processing");
-        assert_eq!(sr.inputs.len(), 6);
+        assert_eq!(sr.inputs.len(), 14);
         assert_eq!(sr.outputs.len(), 1);
         let backend = sr.inputs.iter().find(|p| p.name == "backend").unwrap();
         assert!(backend.enum_options.is_some());
+        let range = sr.inputs.iter().find(|p| p.name == "value_range").unwrap();
+        assert_eq!(range.default_value, Some(serde_json::json!("auto")));
+        assert_eq!(range.enum_options.as_ref().map(Vec::len), Some(4));
+        let preference = sr
+            .inputs
+            .iter()
+            .find(|p| p.name == "auto_preference")
+            .unwrap();
+        assert_eq!(preference.default_value, Some(serde_json::json!("quality")));
     }
 
     #[test]
@@ -731,6 +1500,29 @@ mod tests {
         assert!(output.required);
     }
 
+    #[test]
+    fn test_lifecycle_flags() {
+        let descs = all_node_descriptors();
+        let python = descs.iter().find(|d| d.node_type == "Python").unwrap();
+        assert!(python.experimental && !python.deprecated);
+
+        for desc in &descs {
+            if let Some(replacement) = &desc.replaced_by {
+                assert!(desc.deprecated, "{} has a replacement", desc.node_type);
+                let target = descs
+                    .iter()
+                    .find(|d| &d.node_type == replacement)
+                    .unwrap_or_else(|| panic!("unknown replacement {replacement}"));
+                assert!(!target.deprecated, "{replacement} is deprecated too");
+            }
+        }
+
+        let json = serde_json::to_value(python).unwrap();
+        assert_eq!(json["experimental"], true);
+        assert_eq!(json["deprecated"], false);
+        assert!(json.get("replaced_by").is_none());
+    }
+
     #[test]
     fn test_directions_valid() {
         let descs = all_node_descriptors();
@@ -1,16 +1,34 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::Duration;
 
 use anyhow::{anyhow, bail, Context, Result};
 
 use crate::compile::{compile_graph_with_debug_hook, CompileContext};
 use crate::debug_event::{build_print_debug_value_event, NodeDebugEventCallback};
-use crate::graph::PipelineGraph;
-use crate::node::ExecutionContext;
+use crate::graph::{NodeInstance, PipelineGraph};
+use crate::job_phase::{phase_reporter, with_phase_reporter};
+use crate::node::{ExecutionContext, Node};
+use crate::node_error::NodeFailure;
 use crate::registry::NodeRegistry;
 use crate::streaming_executor::{FrameSink, StreamingExecutor, DEFAULT_BUFFER_SIZE};
+use crate::trace::NodeSpan;
 use crate::types::{Chapter, Frame, MediaMetadata, PortData, PortType, StreamInfo};
 
+/// Node param limiting how long the node may run, in seconds. `0` lifts the
+/// limit, including the default from [`set_default_node_timeout`].
+pub const NODE_TIMEOUT_PARAM: &str = "timeout_secs";
+
+static DEFAULT_NODE_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(0);
+
+/// Replace the process-wide limit for nodes without a `timeout_secs` param.
+/// `0` means no limit.
+pub fn set_default_node_timeout(secs: u64) {
+    DEFAULT_NODE_TIMEOUT_SECS.store(secs, Ordering::Relaxed);
+}
+
 impl FrameSink for Box<dyn FrameSink> {
     fn write_frame(&mut self, frame: &Frame) -> Result<()> {
         (**self).write_frame(frame)
@@ -98,15 +116,27 @@ impl SequentialExecutor {
                 }
             }
 
-            return Ok(HashMap::new());
+            return Ok(compiled.outputs_by_node);
         }
 
         let mut outputs_by_node: HashMap<String, HashMap<String, PortData>> = HashMap::new();
-        let ctx = ExecutionContext::default();
+        let mut ctx = ExecutionContext {
+            debug_sink: compile_ctx.and_then(|compile_ctx| compile_ctx.debug_sink()),
+            services: compile_ctx
+                .map(|compile_ctx| compile_ctx.services())
+                .unwrap_or_default(),
+            trace: compile_ctx.and_then(|compile_ctx| compile_ctx.trace()),
+            stepper: compile_ctx.and_then(|compile_ctx| compile_ctx.stepper()),
+            ..Default::default()
+        };
 
         for node_idx in execution_order {
             let instance = graph.node(node_idx);
-            let mut node = registry
+            ctx.services
+                .check_cancelled()
+                .with_context(|| format!("stopped before node '{}'", instance.id))?;
+            ctx.node_id = instance.id.clone();
+            let node = registry
                 .create(&instance.node_type, instance.params.clone())
                 .with_context(|| {
                     format!(
@@ -116,6 +146,7 @@ impl SequentialExecutor {
                 })?;
 
             let input_port_defs = node.input_ports();
+            let list_ports = node.list_input_ports();
             let mut inputs: HashMap<String, PortData> = HashMap::new();
 
             for (source_idx, connection) in graph.connections_to(node_idx) {
@@ -132,7 +163,12 @@ impl SequentialExecutor {
                     )
                 })?;
 
-                inputs.insert(connection.target_port.clone(), clone_port_data(data));
+                insert_connected_input(
+                    &mut inputs,
+                    &list_ports,
+                    &connection.target_port,
+                    clone_port_data(data),
+                );
             }
 
             for input_port in input_port_defs {
@@ -164,9 +200,12 @@ impl SequentialExecutor {
                 }
             }
 
-            let node_outputs = node
-                .execute(&inputs, &ctx)
-                .with_context(|| format!("execution failed for node '{}'", instance.id))?;
+            let timeout = node_timeout(instance, node.as_ref())?;
+            let span = trace_start(&ctx, instance, &inputs);
+            let result = execute_node(node, inputs, &ctx, timeout)
+                .with_context(|| NodeFailure::new("node", instance));
+            trace_finish(span, &result);
+            let node_outputs = result?;
 
             emit_print_debug_event(
                 &instance.id,
@@ -174,6 +213,7 @@ impl SequentialExecutor {
                 &node_outputs,
                 &mut node_debug_callback,
             );
+            step_after(&ctx, instance, &node_outputs)?;
 
             outputs_by_node.insert(instance.id.clone(), node_outputs);
         }
@@ -202,15 +242,23 @@ impl SequentialExecutor {
         let execution_order = graph.execution_order()?;
 
         let mut outputs_by_node: HashMap<String, HashMap<String, PortData>> = HashMap::new();
-        let ctx = ExecutionContext {
+        let mut ctx = ExecutionContext {
             executing_workflows: outer_ctx.executing_workflows.clone(),
             nesting_depth: outer_ctx.nesting_depth,
+            debug_sink: outer_ctx.debug_sink.clone(),
+            services: outer_ctx.services.clone(),
+            trace: outer_ctx.trace.clone(),
+            stepper: outer_ctx.stepper.clone(),
             ..Default::default()
         };
 
         for node_idx in execution_order {
             let instance = graph.node(node_idx);
-            let mut node = registry
+            ctx.services
+                .check_cancelled()
+                .with_context(|| format!("stopped before node '{}'", instance.id))?;
+            ctx.node_id = instance.id.clone();
+            let node = registry
                 .create(&instance.node_type, instance.params.clone())
                 .with_context(|| {
                     format!(
@@ -220,6 +268,7 @@ impl SequentialExecutor {
                 })?;
 
             let input_port_defs = node.input_ports();
+            let list_ports = node.list_input_ports();
             let mut inputs: HashMap<String, PortData> = HashMap::new();
 
             if node.node_type() == "WorkflowInput" {
@@ -242,7 +291,12 @@ impl SequentialExecutor {
                     )
                 })?;
 
-                inputs.insert(connection.target_port.clone(), clone_port_data(data));
+                insert_connected_input(
+                    &mut inputs,
+                    &list_ports,
+                    &connection.target_port,
+                    clone_port_data(data),
+                );
             }
 
             for input_port in input_port_defs {
@@ -274,9 +328,12 @@ impl SequentialExecutor {
                 }
             }
 
-            let node_outputs = node
-                .execute(&inputs, &ctx)
-                .with_context(|| format!("execution failed for node '{}'", instance.id))?;
+            let timeout = node_timeout(instance, node.as_ref())?;
+            let span = trace_start(&ctx, instance, &inputs);
+            let result = execute_node(node, inputs, &ctx, timeout)
+                .with_context(|| NodeFailure::new("node", instance));
+            trace_finish(span, &result);
+            let node_outputs = result?;
 
             emit_print_debug_event(
                 &instance.id,
@@ -284,6 +341,7 @@ impl SequentialExecutor {
                 &node_outputs,
                 &mut node_debug_callback,
             );
+            step_after(&ctx, instance, &node_outputs)?;
 
             outputs_by_node.insert(instance.id.clone(), node_outputs);
         }
@@ -292,6 +350,100 @@ impl SequentialExecutor {
     }
 }
 
+/// The time limit for running `instance`: its `timeout_secs` param, else
+/// the process-wide default. Nodes with a `timeout_secs` input of their own
+/// (e.g. `ExternalCommand`) enforce it themselves and get no limit here.
+pub(crate) fn node_timeout(instance: &NodeInstance, node: &dyn Node) -> Result<Option<Duration>> {
+    if node
+        .input_ports()
+        .iter()
+        .any(|port| port.name == NODE_TIMEOUT_PARAM)
+    {
+        return Ok(None);
+    }
+    let secs = match instance.params.get(NODE_TIMEOUT_PARAM) {
+        Some(value) => value.as_u64().with_context(|| {
+            format!(
+                "node '{}': {NODE_TIMEOUT_PARAM} must be a non-negative integer, got {value}",
+                instance.id
+            )
+        })?,
+        None => DEFAULT_NODE_TIMEOUT_SECS.load(Ordering::Relaxed),
+    };
+    Ok((secs > 0).then(|| Duration::from_secs(secs)))
+}
+
+/// Runs `node`, giving up after `timeout`. A node that overruns is cancelled
+/// through its context and left to finish on its own thread, so a node that
+/// never checks for cancellation cannot hang the job.
+pub(crate) fn execute_node(
+    mut node: Box<dyn Node>,
+    inputs: HashMap<String, PortData>,
+    ctx: &ExecutionContext,
+    timeout: Option<Duration>,
+) -> Result<HashMap<String, PortData>> {
+    let Some(timeout) = timeout else {
+        return node.execute(&inputs, ctx);
+    };
+
+    let cancel = ctx.services.cancel_token().child_token();
+    let node_ctx = ExecutionContext {
+        services: ctx.services.clone().with_cancel_token(cancel.clone()),
+        ..ctx.clone()
+    };
+    let reporter = phase_reporter();
+    let node_span = tracing::Span::current();
+    let (tx, rx) = mpsc::channel();
+    std::thread::Builder::new()
+        .name(format!("node-{}", ctx.node_id))
+        .spawn(move || {
+            let _entered = node_span.enter();
+            let result = with_phase_reporter(reporter, || node.execute(&inputs, &node_ctx));
+            let _ = tx.send(result);
+        })
+        .context("failed to start node thread")?;
+
+    match rx.recv_timeout(timeout) {
+        Ok(result) => result,
+        Err(RecvTimeoutError::Timeout) => {
+            cancel.cancel();
+            bail!("timed out after {}s", timeout.as_secs())
+        }
+        Err(RecvTimeoutError::Disconnected) => bail!("node panicked"),
+    }
+}
+
+/// Record in the job's trace, if any, that `instance` is starting.
+pub(crate) fn trace_start(
+    ctx: &ExecutionContext,
+    instance: &NodeInstance,
+    inputs: &HashMap<String, PortData>,
+) -> Option<NodeSpan> {
+    ctx.trace
+        .as_ref()
+        .map(|trace| trace.start_node(instance, inputs))
+}
+
+/// In step mode, publish `instance`'s outputs and wait for the next step.
+pub(crate) fn step_after(
+    ctx: &ExecutionContext,
+    instance: &NodeInstance,
+    outputs: &HashMap<String, PortData>,
+) -> Result<()> {
+    let Some(stepper) = &ctx.stepper else {
+        return Ok(());
+    };
+    stepper
+        .pause_after(instance, outputs, &ctx.services)
+        .with_context(|| format!("stopped after node '{}'", instance.id))
+}
+
+pub(crate) fn trace_finish(span: Option<NodeSpan>, result: &Result<HashMap<String, PortData>>) {
+    if let Some(span) = span {
+        span.finish(result.as_ref());
+    }
+}
+
 fn emit_print_debug_event(
     node_id: &str,
     node_type: &str,
@@ -337,12 +489,54 @@ pub fn port_data_from_json(port_type: &PortType, value: &serde_json::Value) -> R
             .as_str()
             .map(|v| PortData::Path(PathBuf::from(v)))
             .ok_or_else(|| anyhow!("expected string JSON value for path")),
+        PortType::Json => Ok(PortData::Json(value.clone())),
         PortType::Metadata => bail!("metadata default values are not supported"),
         PortType::Model => bail!("model default values are not supported"),
         PortType::VideoFrames => bail!("video frame default values are not supported"),
     }
 }
 
+/// Inverse of [`port_data_from_json`] for the scalar port types. Lists become
+/// arrays. Metadata has no JSON form and yields `None`, as does a list
+/// holding it.
+pub fn port_data_to_json(data: &PortData) -> Option<serde_json::Value> {
+    match data {
+        PortData::Metadata(_) => None,
+        PortData::Int(value) => Some(serde_json::json!(value)),
+        PortData::Float(value) => Some(serde_json::json!(value)),
+        PortData::Str(value) => Some(serde_json::json!(value)),
+        PortData::Bool(value) => Some(serde_json::json!(value)),
+        PortData::Path(value) => Some(serde_json::json!(value.to_string_lossy())),
+        PortData::Json(value) => Some(value.clone()),
+        PortData::List(items) => items
+            .iter()
+            .map(port_data_to_json)
+            .collect::<Option<Vec<_>>>()
+            .map(serde_json::Value::Array),
+    }
+}
+
+/// Store the value of a connection into `port`. Connections into a list port
+/// are collected into a `PortData::List`, in the order they are inserted.
+pub fn insert_connected_input(
+    inputs: &mut HashMap<String, PortData>,
+    list_ports: &[String],
+    port: &str,
+    data: PortData,
+) {
+    if !list_ports.iter().any(|name| name == port) {
+        inputs.insert(port.to_string(), data);
+        return;
+    }
+    match inputs
+        .entry(port.to_string())
+        .or_insert_with(|| PortData::List(Vec::new()))
+    {
+        PortData::List(items) => items.push(data),
+        other => *other = PortData::List(vec![data]),
+    }
+}
+
 pub fn clone_port_data(data: &PortData) -> PortData {
     match data {
         PortData::Metadata(metadata) => PortData::Metadata(clone_media_metadata(metadata)),
@@ -351,12 +545,19 @@ pub fn clone_port_data(data: &PortData) -> PortData {
         PortData::Str(value) => PortData::Str(value.clone()),
         PortData::Bool(value) => PortData::Bool(*value),
         PortData::Path(value) => PortData::Path(value.clone()),
+        PortData::Json(value) => PortData::Json(value.clone()),
+        PortData::List(items) => PortData::List(items.iter().map(clone_port_data).collect()),
     }
 }
 
 fn clone_media_metadata(metadata: &MediaMetadata) -> MediaMetadata {
     MediaMetadata {
         source_path: metadata.source_path.clone(),
+        video_streams: metadata
+            .video_streams
+            .iter()
+            .map(clone_stream_info)
+            .collect(),
         audio_streams: metadata
             .audio_streams
             .iter()
@@ -685,6 +886,60 @@ mod tests {
         }
     }
 
+    /// Logs one line and returns.
+    struct LoggingNode;
+
+    impl Node for LoggingNode {
+        fn node_type(&self) -> &str {
+            "logging"
+        }
+
+        fn input_ports(&self) -> Vec<PortDefinition> {
+            vec![]
+        }
+
+        fn output_ports(&self) -> Vec<PortDefinition> {
+            vec![]
+        }
+
+        fn execute(
+            &mut self,
+            _inputs: &HashMap<String, PortData>,
+            _ctx: &ExecutionContext,
+        ) -> Result<HashMap<String, PortData>> {
+            tracing::info!("hello from the node thread");
+            Ok(HashMap::new())
+        }
+    }
+
+    /// Waits until its context is cancelled.
+    struct WaitForCancelNode;
+
+    impl Node for WaitForCancelNode {
+        fn node_type(&self) -> &str {
+            "wait_for_cancel"
+        }
+
+        fn input_ports(&self) -> Vec<PortDefinition> {
+            vec![]
+        }
+
+        fn output_ports(&self) -> Vec<PortDefinition> {
+            vec![]
+        }
+
+        fn execute(
+            &mut self,
+            _inputs: &HashMap<String, PortData>,
+            ctx: &ExecutionContext,
+        ) -> Result<HashMap<String, PortData>> {
+            while !ctx.services.is_cancelled() {
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            Ok(HashMap::new())
+        }
+    }
+
     fn build_registry() -> NodeRegistry {
         let mut registry = NodeRegistry::new();
 
@@ -709,6 +964,8 @@ mod tests {
         registry.register("WorkflowInput", |_| Ok(Box::new(WorkflowInputNode)));
         registry.register("Print", |_| Ok(Box::new(PrintDebugNode)));
         registry.register("string_output", |_| Ok(Box::new(StringOutputNode)));
+        registry.register("wait_for_cancel", |_| Ok(Box::new(WaitForCancelNode)));
+        registry.register("logging", |_| Ok(Box::new(LoggingNode)));
 
         registry
     }
@@ -743,6 +1000,7 @@ mod tests {
                         source_port: "out".to_string(),
                         target_port: "value".to_string(),
                         port_type: PortType::Int,
+                        index: None,
                     },
                     "probe",
                 )
@@ -794,6 +1052,7 @@ mod tests {
                         source_port: "out".to_string(),
                         target_port: "value".to_string(),
                         port_type: PortType::Int,
+                        index: None,
                     },
                     "probe",
                 )
@@ -853,6 +1112,7 @@ mod tests {
                     source_port: "out".to_string(),
                     target_port: "in".to_string(),
                     port_type: PortType::Int,
+                    index: None,
                 },
                 "process",
             )
@@ -864,6 +1124,7 @@ mod tests {
                     source_port: "out".to_string(),
                     target_port: "in".to_string(),
                     port_type: PortType::Int,
+                    index: None,
                 },
                 "output",
             )
@@ -942,6 +1203,134 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_cancelled_job_stops_before_next_node() {
+        let registry = build_registry();
+        let mut graph = PipelineGraph::new();
+        graph
+            .add_node(NodeInstance {
+                id: "input".to_string(),
+                node_type: "input".to_string(),
+                params: HashMap::from([(String::from("value"), serde_json::json!(1))]),
+            })
+            .expect("input node should be added");
+
+        let cancel = tokio_util::sync::CancellationToken::new();
+        let ctx = ExecutionContext {
+            services: crate::services::NodeServices::default().with_cancel_token(cancel.clone()),
+            ..Default::default()
+        };
+        assert!(
+            SequentialExecutor::execute_with_params(&graph, &registry, HashMap::new(), &ctx)
+                .is_ok()
+        );
+
+        cancel.cancel();
+        let err = SequentialExecutor::execute_with_params(&graph, &registry, HashMap::new(), &ctx)
+            .err()
+            .expect("cancelled job should not run nodes");
+        assert!(
+            format!("{err:#}").contains("stopped before node 'input': job was cancelled"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn test_node_timeout_param_fails_the_node() {
+        let registry = build_registry();
+        let mut graph = PipelineGraph::new();
+        graph
+            .add_node(NodeInstance {
+                id: "wait".to_string(),
+                node_type: "wait_for_cancel".to_string(),
+                params: HashMap::from([(NODE_TIMEOUT_PARAM.to_string(), serde_json::json!(1))]),
+            })
+            .expect("wait node should be added");
+
+        let started = std::time::Instant::now();
+        let err = SequentialExecutor::execute(&graph, &registry)
+            .err()
+            .expect("node should time out");
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(
+            format!("{err:#}"),
+            "execution failed for node 'wait': timed out after 1s"
+        );
+    }
+
+    #[test]
+    fn test_timed_node_logs_reach_the_job_log() {
+        use crate::job_logs::{job_log_hub, job_log_layer, job_log_targets};
+        use tracing_subscriber::prelude::*;
+
+        // The node runs on its own thread, which only sees the global
+        // subscriber; a scoped one would not observe its events.
+        let _ = tracing::subscriber::set_global_default(
+            tracing_subscriber::registry().with(job_log_layer().with_filter(job_log_targets())),
+        );
+
+        let registry = build_registry();
+        let mut graph = PipelineGraph::new();
+        graph
+            .add_node(NodeInstance {
+                id: "log".to_string(),
+                node_type: "logging".to_string(),
+                params: HashMap::from([(NODE_TIMEOUT_PARAM.to_string(), serde_json::json!(30))]),
+            })
+            .expect("logging node should be added");
+
+        let job_id = format!("node-thread-{}", uuid::Uuid::new_v4());
+        let _capture = job_log_hub().begin_capture(&job_id, None);
+        tracing::info_span!("job", job_id = %job_id).in_scope(|| {
+            SequentialExecutor::execute(&graph, &registry).expect("logging node should run")
+        });
+
+        let lines = job_log_hub().tail(&job_id, 10).expect("capture active");
+        assert!(
+            lines
+                .iter()
+                .any(|line| line.message == "hello from the node thread"),
+            "{lines:?}"
+        );
+    }
+
+    #[test]
+    fn test_node_timeout_resolution() {
+        let instance = |params: HashMap<String, serde_json::Value>| NodeInstance {
+            id: "n".to_string(),
+            node_type: "output".to_string(),
+            params,
+        };
+        let timeout = |value: serde_json::Value| {
+            node_timeout(
+                &instance(HashMap::from([(NODE_TIMEOUT_PARAM.to_string(), value)])),
+                &OutputNode,
+            )
+        };
+
+        assert_eq!(
+            timeout(serde_json::json!(30)).unwrap(),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(timeout(serde_json::json!(0)).unwrap(), None);
+        assert!(timeout(serde_json::json!(-1)).is_err());
+        assert!(timeout(serde_json::json!("30")).is_err());
+
+        // A node with its own timeout_secs input handles it itself.
+        let command = crate::nodes::external_command::ExternalCommandNode::new();
+        assert_eq!(
+            node_timeout(
+                &instance(HashMap::from([(
+                    NODE_TIMEOUT_PARAM.to_string(),
+                    serde_json::json!(30)
+                )])),
+                &command
+            )
+            .unwrap(),
+            None
+        );
+    }
+
     #[test]
     fn test_print_node_emits_debug_event() {
         let registry = build_registry();
@@ -976,6 +1365,7 @@ mod tests {
                     source_port: "value".to_string(),
                     target_port: "value".to_string(),
                     port_type: PortType::Str,
+                    index: None,
                 },
                 "print_1",
             )
@@ -987,6 +1377,7 @@ mod tests {
                     source_port: "value".to_string(),
                     target_port: "value".to_string(),
                     port_type: PortType::Str,
+                    index: None,
                 },
                 "sink",
             )
@@ -1068,6 +1459,7 @@ mod tests {
                     source_port: "out".to_string(),
                     target_port: "in".to_string(),
                     port_type: PortType::Int,
+                    index: None,
                 },
                 "process",
             )
@@ -1079,6 +1471,7 @@ mod tests {
                     source_port: "out".to_string(),
                     target_port: "in".to_string(),
                     port_type: PortType::Int,
+                    index: None,
                 },
                 "output",
             )
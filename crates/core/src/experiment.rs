@@ -0,0 +1,241 @@
+//! Model A/B experiments.
+//!
+//! An experiment runs one source clip through several workflow variants,
+//! then scores each result with VMAF. The reference for every variant is the
+//! source upscaled with lanczos to that variant's output size, so the score
+//! shows how far a model moves away from a plain resize. Outputs and the
+//! `report.json` artifact live in `experiments/<job id>/` under the data
+//! directory.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::nodes::video_input::{extract_metadata, run_ffprobe};
+
+pub const EXPERIMENTS_DIR_NAME: &str = "experiments";
+pub const REPORT_FILE_NAME: &str = "report.json";
+/// Resize used to build the VMAF reference from the source.
+pub const BASELINE_SCALER: &str = "lanczos";
+const SUPER_RESOLUTION_NODE_TYPE: &str = "SuperResolution";
+
+pub fn experiment_dir(data_dir: &Path, experiment_id: &str) -> PathBuf {
+    data_dir.join(EXPERIMENTS_DIR_NAME).join(experiment_id)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExperimentReport {
+    pub experiment_id: String,
+    pub source: PathBuf,
+    pub baseline: String,
+    pub created_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub variants: Vec<VariantResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VariantResult {
+    pub name: String,
+    pub job_id: String,
+    pub output: PathBuf,
+    /// Final status of the variant's job, e.g. `completed` or `failed`.
+    pub status: String,
+    pub error: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Wall-clock time of the variant's job.
+    pub elapsed_secs: Option<f64>,
+    /// Mean VMAF against the upscaled source, 0–100.
+    pub vmaf: Option<f64>,
+}
+
+impl ExperimentReport {
+    pub fn save(&self, dir: &Path) -> Result<PathBuf> {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+        let path = dir.join(REPORT_FILE_NAME);
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(&path, json).with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(path)
+    }
+
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(REPORT_FILE_NAME);
+        let json = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("invalid report {}", path.display()))
+    }
+}
+
+/// File name for a variant's output: its position plus the name reduced to
+/// characters that are safe on every file system.
+pub fn variant_output_name(index: usize, name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{:02}_{slug}.mkv", index + 1)
+}
+
+/// Sets `model_path` on every `SuperResolution` node of a workflow JSON.
+/// Fails when the workflow has no such node.
+pub fn override_model_path(workflow: &mut serde_json::Value, model_path: &str) -> Result<()> {
+    let nodes = workflow
+        .get_mut("nodes")
+        .and_then(serde_json::Value::as_array_mut)
+        .context("workflow has no nodes array")?;
+    let mut replaced = 0;
+    for node in nodes {
+        if node.get("node_type").and_then(serde_json::Value::as_str)
+            != Some(SUPER_RESOLUTION_NODE_TYPE)
+        {
+            continue;
+        }
+        let Some(params) = node
+            .as_object_mut()
+            .map(|node| {
+                node.entry("params")
+                    .or_insert_with(|| serde_json::json!({}))
+            })
+            .and_then(serde_json::Value::as_object_mut)
+        else {
+            bail!("SuperResolution node params must be an object");
+        };
+        params.insert(
+            "model_path".to_string(),
+            serde_json::Value::String(model_path.to_string()),
+        );
+        replaced += 1;
+    }
+    if replaced == 0 {
+        bail!("workflow has no SuperResolution node to set model_path on");
+    }
+    Ok(())
+}
+
+/// Filtergraph scoring input 0 (the variant) against input 1 (the source),
+/// resized to `width`x`height` at `fps`.
+pub fn vmaf_filtergraph(width: u32, height: u32, fps: f64) -> String {
+    format!(
+        "[0:v]format=yuv420p,setpts=PTS-STARTPTS[dist];\
+         [1:v]scale={width}:{height}:flags={BASELINE_SCALER},fps={fps},format=yuv420p,\
+         setpts=PTS-STARTPTS[ref];[dist][ref]libvmaf"
+    )
+}
+
+/// Reads the pooled score from libvmaf's `VMAF score: 93.41` log line.
+pub fn parse_vmaf_score(stderr: &str) -> Option<f64> {
+    stderr
+        .lines()
+        .rev()
+        .find_map(|line| line.split("VMAF score:").nth(1))
+        .and_then(|score| score.trim().parse().ok())
+}
+
+/// Probes `output` and scores it against `source`. Returns the output size
+/// and the VMAF score.
+pub fn score_variant(source: &Path, output: &Path) -> Result<(u32, u32, f64)> {
+    let (info, _) = extract_metadata(&run_ffprobe(output)?, output)?;
+    let result = crate::runtime::command_for("ffmpeg")
+        .args(["-hide_banner", "-nostats", "-i"])
+        .arg(output)
+        .arg("-i")
+        .arg(source)
+        .arg("-lavfi")
+        .arg(vmaf_filtergraph(info.width, info.height, info.fps))
+        .args(["-f", "null", "-"])
+        .output()
+        .context("failed to launch ffmpeg")?;
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    if !result.status.success() {
+        bail!(
+            "VMAF scoring failed for {}: {}",
+            output.display(),
+            stderr.lines().last().unwrap_or_default()
+        );
+    }
+    let score = parse_vmaf_score(&stderr)
+        .with_context(|| format!("ffmpeg printed no VMAF score for {}", output.display()))?;
+    Ok((info.width, info.height, score))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_override_model_path_sets_every_super_resolution_node() {
+        let mut workflow = serde_json::json!({
+            "nodes": [
+                {"id": "in", "node_type": "VideoInput", "params": {}},
+                {"id": "sr1", "node_type": "SuperResolution", "params": {"model_path": "models/a.onnx"}},
+                {"id": "sr2", "node_type": "SuperResolution"},
+            ],
+            "connections": []
+        });
+        override_model_path(&mut workflow, "models/b.onnx").unwrap();
+        assert_eq!(
+            workflow["nodes"][1]["params"]["model_path"],
+            "models/b.onnx"
+        );
+        assert_eq!(
+            workflow["nodes"][2]["params"]["model_path"],
+            "models/b.onnx"
+        );
+        assert!(workflow["nodes"][0]["params"].get("model_path").is_none());
+
+        let mut no_sr = serde_json::json!({"nodes": [], "connections": []});
+        assert!(override_model_path(&mut no_sr, "models/b.onnx").is_err());
+    }
+
+    #[test]
+    fn test_parse_vmaf_score_and_filtergraph() {
+        let stderr = "frame=  48 fps=12\n[Parsed_libvmaf_6 @ 0x5581] VMAF score: 93.412870\n";
+        assert_eq!(parse_vmaf_score(stderr), Some(93.41287));
+        assert_eq!(parse_vmaf_score("no score here"), None);
+
+        let graph = vmaf_filtergraph(3840, 2160, 23.976);
+        assert!(graph.contains("scale=3840:2160:flags=lanczos,fps=23.976"));
+        assert!(graph.ends_with("[dist][ref]libvmaf"));
+    }
+
+    #[test]
+    fn test_variant_output_name_and_report_roundtrip() {
+        assert_eq!(
+            variant_output_name(0, "ESRGAN x4/anime"),
+            "01_ESRGAN_x4_anime.mkv"
+        );
+
+        let dir = std::env::temp_dir().join(format!("videnoa_experiment_{}", uuid::Uuid::new_v4()));
+        let report = ExperimentReport {
+            experiment_id: "exp".to_string(),
+            source: PathBuf::from("/videos/clip.mkv"),
+            baseline: BASELINE_SCALER.to_string(),
+            created_at: Utc::now(),
+            completed_at: None,
+            variants: vec![VariantResult {
+                name: "a".to_string(),
+                job_id: "job-a".to_string(),
+                output: dir.join("01_a.mkv"),
+                status: "completed".to_string(),
+                error: None,
+                width: Some(3840),
+                height: Some(2160),
+                elapsed_secs: Some(12.5),
+                vmaf: Some(91.0),
+            }],
+        };
+        let path = report.save(&dir).unwrap();
+        assert_eq!(path, dir.join(REPORT_FILE_NAME));
+        assert_eq!(ExperimentReport::load(&dir).unwrap(), report);
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use anyhow::{anyhow, bail, Context, Result};
 use petgraph::algo::toposort;
@@ -8,7 +8,8 @@ use petgraph::Direction;
 use serde::de::Error as DeError;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::node::PortDefinition;
+use crate::node::{PortDefinition, ResourceClass};
+use crate::post_actions::PostAction;
 use crate::registry::NodeRegistry;
 use crate::types::PortType;
 
@@ -18,12 +19,214 @@ pub struct WorkflowInterface {
     pub outputs: Vec<WorkflowPort>,
 }
 
+impl WorkflowInterface {
+    /// Coerce submitted params to the declared input types in place, then
+    /// check them against the declared constraints. An input with no
+    /// submitted value, no default and no entry in `preset` (values already
+    /// set on the WorkflowInput node) is reported as missing.
+    pub fn validate_params(
+        &self,
+        params: &mut HashMap<String, serde_json::Value>,
+        preset: &HashMap<String, serde_json::Value>,
+    ) -> Vec<ParamError> {
+        let mut errors = Vec::new();
+        for port in &self.inputs {
+            let Some(value) = params.get_mut(&port.name) else {
+                if port.default_value.is_none() && !preset.contains_key(&port.name) {
+                    errors.push(ParamError::new(&port.name, "a value is required"));
+                }
+                continue;
+            };
+            if let Err(message) = port
+                .coerce_value(value)
+                .and_then(|()| port.check_value(value))
+            {
+                errors.push(ParamError::new(&port.name, message));
+            }
+        }
+        errors
+    }
+
+    /// Declared type of each input whose `port_type` names a known type.
+    pub fn input_types(&self) -> HashMap<String, PortType> {
+        self.inputs
+            .iter()
+            .filter_map(|port| Some((port.name.clone(), port.parsed_port_type()?)))
+            .collect()
+    }
+}
+
+/// A submitted param that does not fit the workflow interface.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParamError {
+    pub param: String,
+    pub message: String,
+}
+
+impl ParamError {
+    fn new(param: &str, message: impl Into<String>) -> Self {
+        Self {
+            param: param.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for ParamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "param '{}': {}", self.param, self.message)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WorkflowPort {
     pub name: String,
     pub port_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default_value: Option<serde_json::Value>,
+    /// Help text shown next to the field in the run dialog.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Allowed values; the run dialog renders a select.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enum_options: Option<Vec<serde_json::Value>>,
+    /// Inclusive bounds for Int and Float inputs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    /// How the run dialog should pick a Path input.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_picker: Option<FilePickerHint>,
+}
+
+impl WorkflowPort {
+    pub fn parsed_port_type(&self) -> Option<PortType> {
+        serde_json::from_value(serde_json::Value::String(self.port_type.clone())).ok()
+    }
+
+    /// Convert `value` to the JSON shape of the declared type, accepting
+    /// numeric and boolean strings and whole floats for Int inputs.
+    pub fn coerce_value(&self, value: &mut serde_json::Value) -> std::result::Result<(), String> {
+        use serde_json::Value;
+
+        let Some(port_type) = self.parsed_port_type() else {
+            return Ok(());
+        };
+        let coerced = match port_type {
+            PortType::Int => match &*value {
+                Value::Number(number) if number.is_i64() => return Ok(()),
+                Value::Number(number) => number
+                    .as_f64()
+                    .filter(|float| float.fract() == 0.0 && float.abs() < i64::MAX as f64)
+                    .map(|float| Value::from(float as i64)),
+                Value::String(raw) => raw.trim().parse::<i64>().ok().map(Value::from),
+                _ => None,
+            },
+            PortType::Float => match &*value {
+                Value::Number(_) => return Ok(()),
+                Value::String(raw) => raw
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|float| float.is_finite())
+                    .map(Value::from),
+                _ => None,
+            },
+            PortType::Bool => match &*value {
+                Value::Bool(_) => return Ok(()),
+                Value::String(raw) => match raw.trim().to_ascii_lowercase().as_str() {
+                    "true" => Some(Value::Bool(true)),
+                    "false" => Some(Value::Bool(false)),
+                    _ => None,
+                },
+                _ => None,
+            },
+            PortType::Str | PortType::Path | PortType::WorkflowPath => match &*value {
+                Value::String(_) => return Ok(()),
+                _ => None,
+            },
+            PortType::VideoFrames | PortType::Metadata | PortType::Model | PortType::Json => {
+                return Ok(())
+            }
+        };
+
+        match coerced {
+            Some(coerced) => {
+                *value = coerced;
+                Ok(())
+            }
+            None => Err(format!("expected {}, got {value}", self.port_type)),
+        }
+    }
+
+    /// Check `value` against this port's enum, range and extension
+    /// constraints. Values of an unexpected JSON type are left to the
+    /// consuming node.
+    pub fn check_value(&self, value: &serde_json::Value) -> std::result::Result<(), String> {
+        if let Some(options) = &self.enum_options {
+            if !options.contains(value) {
+                let allowed = options
+                    .iter()
+                    .map(|option| option.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                return Err(format!("must be one of {allowed}, got {value}"));
+            }
+        }
+
+        if let Some(number) = value.as_f64() {
+            if let Some(min) = self.min {
+                if number < min {
+                    return Err(format!("must be at least {min}, got {value}"));
+                }
+            }
+            if let Some(max) = self.max {
+                if number > max {
+                    return Err(format!("must be at most {max}, got {value}"));
+                }
+            }
+        }
+
+        if let (Some(picker), Some(path)) = (&self.file_picker, value.as_str()) {
+            if picker.mode != FilePickerMode::Directory && !picker.extensions.is_empty() {
+                let extension = std::path::Path::new(path)
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .unwrap_or_default()
+                    .to_ascii_lowercase();
+                let matches = picker.extensions.iter().any(|allowed| {
+                    allowed
+                        .trim_start_matches('.')
+                        .eq_ignore_ascii_case(&extension)
+                });
+                if !matches {
+                    return Err(format!(
+                        "expected a file ending in {}",
+                        picker.extensions.join(", ")
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilePickerHint {
+    pub mode: FilePickerMode,
+    /// Accepted extensions, e.g. `["mkv", "mp4"]`. Empty accepts any file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilePickerMode {
+    Open,
+    Save,
+    Directory,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -38,6 +241,10 @@ pub struct PortConnection {
     pub source_port: String,
     pub target_port: String,
     pub port_type: PortType,
+    /// Position among the connections into a list port; unset for other
+    /// ports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<u32>,
 }
 
 #[derive(Debug, Clone)]
@@ -45,6 +252,8 @@ pub struct PipelineGraph {
     graph: StableDiGraph<NodeInstance, PortConnection>,
     node_ids: HashMap<String, NodeIndex>,
     pub interface: Option<WorkflowInterface>,
+    /// File actions the server runs once a job of this workflow succeeds.
+    pub post_actions: Vec<PostAction>,
 }
 
 impl PipelineGraph {
@@ -53,6 +262,7 @@ impl PipelineGraph {
             graph: StableDiGraph::new(),
             node_ids: HashMap::new(),
             interface: None,
+            post_actions: Vec::new(),
         }
     }
 
@@ -125,8 +335,61 @@ impl PipelineGraph {
         injected
     }
 
+    /// The resource classes running this workflow needs: each node's
+    /// [`crate::node::Node::resource_class`], plus the GPU when a `hwaccel` or `codec`
+    /// input asks for hardware decoding or an NVENC encoder, or comes in over
+    /// a connection and so can't be known up front.
+    pub fn resource_classes(&self, registry: &NodeRegistry) -> Result<BTreeSet<ResourceClass>> {
+        let mut classes = BTreeSet::new();
+        for idx in self.graph.node_indices() {
+            let instance = self.node(idx);
+            let node = registry
+                .create(&instance.node_type, instance.params.clone())
+                .with_context(|| {
+                    format!(
+                        "failed to instantiate node '{}' of type '{}'",
+                        instance.id, instance.node_type
+                    )
+                })?;
+            classes.insert(node.resource_class());
+
+            let connected = |port: &str| {
+                self.connections_to(idx)
+                    .iter()
+                    .any(|(_, connection)| connection.target_port == port)
+            };
+            let param = |port: &str| instance.params.get(port).and_then(|value| value.as_str());
+            let hardware = connected("hwaccel")
+                || connected("codec")
+                || param("hwaccel").is_some_and(|accel| accel != "none")
+                || param("codec").is_some_and(|codec| codec.contains("nvenc"));
+            if hardware {
+                classes.insert(ResourceClass::Gpu);
+            }
+        }
+        Ok(classes)
+    }
+
+    /// Values already set on WorkflowInput nodes, keyed by port name.
+    pub fn workflow_input_values(&self) -> HashMap<String, serde_json::Value> {
+        self.nodes()
+            .filter(|node| node.node_type == "WorkflowInput")
+            .flat_map(|node| node.params.iter())
+            .filter(|(key, _)| {
+                !matches!(
+                    key.as_str(),
+                    "ports" | "interface_inputs" | "interface_outputs"
+                )
+            })
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
     pub fn validate(&self, registry: &NodeRegistry) -> Result<()> {
         self.execution_order()?;
+        for action in &self.post_actions {
+            action.validate().context("invalid post action")?;
+        }
 
         let definitions = self.collect_port_definitions(registry)?;
 
@@ -147,11 +410,11 @@ impl PipelineGraph {
             let source_outputs = &definitions
                 .get(&source_idx)
                 .expect("source node should be present")
-                .1;
+                .outputs;
             let target_inputs = &definitions
                 .get(&target_idx)
                 .expect("target node should be present")
-                .0;
+                .inputs;
 
             let source_port = source_outputs
                 .iter()
@@ -203,7 +466,11 @@ impl PipelineGraph {
             }
         }
 
-        for (idx, (input_ports, _)) in &definitions {
+        for (idx, ports) in &definitions {
+            self.validate_fan_in(*idx, &ports.list_inputs)?;
+        }
+
+        for (idx, ports) in &definitions {
             let has_vf_edge = self
                 .graph
                 .edges_directed(*idx, petgraph::Direction::Incoming)
@@ -225,7 +492,7 @@ impl PipelineGraph {
 
             let node = self.node(*idx);
             let has_param = |name: &str| -> bool { node.params.contains_key(name) };
-            for input in input_ports {
+            for input in &ports.inputs {
                 if input.required
                     && input.default_value.is_none()
                     && !connected_inputs.contains(&input.name)
@@ -253,11 +520,24 @@ impl PipelineGraph {
             .expect("node index should be valid")
     }
 
+    pub fn nodes(&self) -> impl Iterator<Item = &NodeInstance> {
+        self.graph.node_weights()
+    }
+
+    pub fn nodes_mut(&mut self) -> impl Iterator<Item = &mut NodeInstance> {
+        self.graph.node_weights_mut()
+    }
+
+    /// Connections into `idx`. Those into the same list port come in index
+    /// order.
     pub fn connections_to(&self, idx: NodeIndex) -> Vec<(NodeIndex, &PortConnection)> {
-        self.graph
+        let mut connections: Vec<_> = self
+            .graph
             .edges_directed(idx, Direction::Incoming)
             .map(|edge| (edge.source(), edge.weight()))
-            .collect()
+            .collect();
+        connections.sort_by_key(|(_, connection)| connection.index);
+        connections
     }
 
     pub fn connections_from(&self, node_idx: NodeIndex) -> Vec<(NodeIndex, &PortConnection)> {
@@ -267,10 +547,66 @@ impl PipelineGraph {
             .collect()
     }
 
+    /// A port takes one connection unless it is a list port. Connections into
+    /// a list port need distinct indices; a lone one may leave it unset.
+    fn validate_fan_in(&self, idx: NodeIndex, list_inputs: &[String]) -> Result<()> {
+        let node = self.node(idx);
+        let mut by_port: HashMap<&str, Vec<Option<u32>>> = HashMap::new();
+        for (_, connection) in self.connections_to(idx) {
+            by_port
+                .entry(connection.target_port.as_str())
+                .or_default()
+                .push(connection.index);
+        }
+
+        for (port, indices) in by_port {
+            if !list_inputs.iter().any(|name| name == port) {
+                if indices.len() > 1 {
+                    bail!(
+                        "node '{}' input port '{}' has {} connections but accepts one",
+                        node.id,
+                        port,
+                        indices.len()
+                    );
+                }
+                if indices[0].is_some() {
+                    bail!(
+                        "node '{}' input port '{}' is not a list port; its connection cannot have an index",
+                        node.id,
+                        port
+                    );
+                }
+                continue;
+            }
+            if indices.len() == 1 {
+                continue;
+            }
+            let mut seen = HashSet::new();
+            for index in indices {
+                let Some(index) = index else {
+                    bail!(
+                        "node '{}' list port '{}' has several connections, so each needs an index",
+                        node.id,
+                        port
+                    );
+                };
+                if !seen.insert(index) {
+                    bail!(
+                        "node '{}' list port '{}' has more than one connection at index {}",
+                        node.id,
+                        port,
+                        index
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn collect_port_definitions(
         &self,
         registry: &NodeRegistry,
-    ) -> Result<HashMap<NodeIndex, (Vec<PortDefinition>, Vec<PortDefinition>)>> {
+    ) -> Result<HashMap<NodeIndex, NodePorts>> {
         let mut definitions = HashMap::new();
 
         for idx in self.graph.node_indices() {
@@ -284,13 +620,26 @@ impl PipelineGraph {
                     )
                 })?;
 
-            definitions.insert(idx, (node.input_ports(), node.output_ports()));
+            definitions.insert(
+                idx,
+                NodePorts {
+                    inputs: node.input_ports(),
+                    outputs: node.output_ports(),
+                    list_inputs: node.list_input_ports(),
+                },
+            );
         }
 
         Ok(definitions)
     }
 }
 
+struct NodePorts {
+    inputs: Vec<PortDefinition>,
+    outputs: Vec<PortDefinition>,
+    list_inputs: Vec<String>,
+}
+
 impl Default for PipelineGraph {
     fn default() -> Self {
         Self::new()
@@ -303,6 +652,8 @@ struct PipelineGraphSerde {
     connections: Vec<PipelineConnectionSerde>,
     #[serde(skip_serializing_if = "Option::is_none")]
     interface: Option<WorkflowInterface>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    post_actions: Vec<PostAction>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -312,6 +663,8 @@ struct PipelineConnectionSerde {
     to_node: String,
     to_port: String,
     port_type: PortType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    to_index: Option<u32>,
 }
 
 impl Serialize for PipelineGraph {
@@ -340,6 +693,7 @@ impl Serialize for PipelineGraph {
                     to_node,
                     to_port: weight.target_port.clone(),
                     port_type: weight.port_type.clone(),
+                    to_index: weight.index,
                 }
             })
             .collect();
@@ -350,6 +704,7 @@ impl Serialize for PipelineGraph {
                 .then_with(|| a.from_port.cmp(&b.from_port))
                 .then_with(|| a.to_node.cmp(&b.to_node))
                 .then_with(|| a.to_port.cmp(&b.to_port))
+                .then_with(|| a.to_index.cmp(&b.to_index))
                 .then_with(|| {
                     port_type_sort_key(&a.port_type).cmp(&port_type_sort_key(&b.port_type))
                 })
@@ -359,6 +714,7 @@ impl Serialize for PipelineGraph {
             nodes,
             connections,
             interface: self.interface.clone(),
+            post_actions: self.post_actions.clone(),
         }
         .serialize(serializer)
     }
@@ -372,6 +728,7 @@ impl<'de> Deserialize<'de> for PipelineGraph {
         let serialized = PipelineGraphSerde::deserialize(deserializer)?;
         let mut graph = PipelineGraph::new();
         graph.interface = serialized.interface;
+        graph.post_actions = serialized.post_actions;
 
         for node in serialized.nodes {
             graph.add_node(node).map_err(D::Error::custom)?;
@@ -385,6 +742,7 @@ impl<'de> Deserialize<'de> for PipelineGraph {
                         source_port: connection.from_port,
                         target_port: connection.to_port,
                         port_type: connection.port_type,
+                        index: connection.to_index,
                     },
                     &connection.to_node,
                 )
@@ -406,6 +764,7 @@ fn port_type_sort_key(port_type: &PortType) -> u8 {
         PortType::Bool => 6,
         PortType::Path => 7,
         PortType::WorkflowPath => 8,
+        PortType::Json => 9,
     }
 }
 
@@ -519,6 +878,7 @@ mod tests {
                     source_port: "out".to_string(),
                     target_port: "in".to_string(),
                     port_type: PortType::Int,
+                    index: None,
                 },
                 "b",
             )
@@ -530,6 +890,7 @@ mod tests {
                     source_port: "out".to_string(),
                     target_port: "in".to_string(),
                     port_type: PortType::Int,
+                    index: None,
                 },
                 "c",
             )
@@ -541,6 +902,7 @@ mod tests {
                     source_port: "out".to_string(),
                     target_port: "in".to_string(),
                     port_type: PortType::Int,
+                    index: None,
                 },
                 "a",
             )
@@ -590,6 +952,7 @@ mod tests {
                     source_port: "out".to_string(),
                     target_port: "in".to_string(),
                     port_type: PortType::Str,
+                    index: None,
                 },
                 "sink",
             )
@@ -601,6 +964,72 @@ mod tests {
         assert!(err.to_string().contains("incompatible port types"));
     }
 
+    #[test]
+    fn test_validate_fan_in_only_into_list_ports() {
+        let registry = build_default_registry();
+        let graph_with = |connections: serde_json::Value| -> PipelineGraph {
+            serde_json::from_value(serde_json::json!({
+                "nodes": [
+                    {"id": "a", "node_type": "VideoInput", "params": {"path": "/tmp/a.mkv"}},
+                    {"id": "b", "node_type": "VideoInput", "params": {"path": "/tmp/b.mkv"}},
+                    {"id": "concat", "node_type": "Concat", "params": {}},
+                    {"id": "out", "node_type": "VideoOutput", "params": {"output_path": "/tmp/out.mkv"}}
+                ],
+                "connections": connections
+            }))
+            .expect("graph should deserialize")
+        };
+        let frames = |from: &str, to: &str, to_port: &str, to_index: Option<u32>| {
+            serde_json::json!({
+                "from_node": from,
+                "from_port": "frames",
+                "to_node": to,
+                "to_port": to_port,
+                "port_type": "VideoFrames",
+                "to_index": to_index
+            })
+        };
+
+        let joined = graph_with(serde_json::json!([
+            frames("b", "concat", "segments", Some(1)),
+            frames("a", "concat", "segments", Some(0)),
+            frames("concat", "out", "frames", None)
+        ]));
+        joined
+            .validate(&registry)
+            .expect("indexed segments should validate");
+        let concat_idx = joined.node_ids["concat"];
+        let sources: Vec<&str> = joined
+            .connections_to(concat_idx)
+            .into_iter()
+            .map(|(idx, _)| joined.node(idx).id.as_str())
+            .collect();
+        assert_eq!(sources, ["a", "b"]);
+
+        let same_index = graph_with(serde_json::json!([
+            frames("a", "concat", "segments", Some(0)),
+            frames("b", "concat", "segments", Some(0))
+        ]));
+        let err = same_index.validate(&registry).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("more than one connection at index 0"));
+
+        let unindexed = graph_with(serde_json::json!([
+            frames("a", "concat", "segments", None),
+            frames("b", "concat", "segments", Some(1))
+        ]));
+        let err = unindexed.validate(&registry).unwrap_err();
+        assert!(err.to_string().contains("each needs an index"));
+
+        let not_list = graph_with(serde_json::json!([
+            frames("a", "out", "frames", None),
+            frames("b", "out", "frames", None)
+        ]));
+        let err = not_list.validate(&registry).unwrap_err();
+        assert!(err.to_string().contains("accepts one"));
+    }
+
     #[test]
     fn test_validate_rejects_legacy_downloader_video_path_output_port() {
         let registry = build_default_registry();
@@ -627,6 +1056,7 @@ mod tests {
                     source_port: "video_path".to_string(),
                     target_port: "path".to_string(),
                     port_type: PortType::Path,
+                    index: None,
                 },
                 "video_input",
             )
@@ -670,6 +1100,7 @@ mod tests {
                     source_port: "value".to_string(),
                     target_port: "value".to_string(),
                     port_type: PortType::Str,
+                    index: None,
                 },
                 "print",
             )
@@ -729,6 +1160,7 @@ mod tests {
                     source_port: "out".to_string(),
                     target_port: "in".to_string(),
                     port_type: PortType::Int,
+                    index: None,
                 },
                 "sink",
             )
@@ -777,6 +1209,7 @@ mod tests {
                     source_port: "out".to_string(),
                     target_port: "in".to_string(),
                     port_type: PortType::Int,
+                    index: None,
                 },
                 "b",
             )
@@ -788,6 +1221,7 @@ mod tests {
                     source_port: "out".to_string(),
                     target_port: "in".to_string(),
                     port_type: PortType::Int,
+                    index: None,
                 },
                 "c",
             )
@@ -834,6 +1268,7 @@ mod tests {
                     source_port: "out".to_string(),
                     target_port: "in".to_string(),
                     port_type: PortType::Int,
+                    index: None,
                 },
                 "b",
             )
@@ -845,6 +1280,7 @@ mod tests {
                     source_port: "out".to_string(),
                     target_port: "in".to_string(),
                     port_type: PortType::Int,
+                    index: None,
                 },
                 "c",
             )
@@ -895,4 +1331,157 @@ mod tests {
             "non-WorkflowInput nodes should remain unchanged"
         );
     }
+
+    #[test]
+    fn test_resource_classes_aggregate_nodes_and_hardware_params() {
+        fn node(id: &str, node_type: &str, params: serde_json::Value) -> NodeInstance {
+            NodeInstance {
+                id: id.to_string(),
+                node_type: node_type.to_string(),
+                params: serde_json::from_value(params).unwrap(),
+            }
+        }
+        let registry = build_default_registry();
+        let remux = || {
+            let mut graph = PipelineGraph::new();
+            graph
+                .add_node(node(
+                    "in",
+                    "VideoInput",
+                    serde_json::json!({"path": "/a.mkv"}),
+                ))
+                .unwrap();
+            graph
+                .add_node(node(
+                    "out",
+                    "VideoOutput",
+                    serde_json::json!({"codec": "libx265"}),
+                ))
+                .unwrap();
+            graph
+        };
+        let classes = |graph: &PipelineGraph| -> Vec<ResourceClass> {
+            graph
+                .resource_classes(&registry)
+                .unwrap()
+                .into_iter()
+                .collect()
+        };
+        assert!(classes(&PipelineGraph::new()).is_empty());
+        assert_eq!(classes(&remux()), [ResourceClass::Disk]);
+
+        let mut with_model = remux();
+        with_model
+            .add_node(node("sr", "SuperResolution", serde_json::json!({})))
+            .unwrap();
+        with_model
+            .add_node(node("fetch", "HttpRequest", serde_json::json!({})))
+            .unwrap();
+        assert_eq!(
+            classes(&with_model),
+            [
+                ResourceClass::Gpu,
+                ResourceClass::Network,
+                ResourceClass::Disk
+            ]
+        );
+
+        let mut nvenc = remux();
+        nvenc.nodes_mut().for_each(|node| {
+            node.params
+                .insert("codec".to_string(), serde_json::json!("hevc_nvenc"));
+        });
+        assert_eq!(classes(&nvenc), [ResourceClass::Gpu, ResourceClass::Disk]);
+
+        let mut hw_decode = remux();
+        hw_decode.nodes_mut().for_each(|node| {
+            node.params
+                .insert("hwaccel".to_string(), serde_json::json!("nvdec"));
+        });
+        assert!(classes(&hw_decode).contains(&ResourceClass::Gpu));
+
+        let mut connected_codec = remux();
+        connected_codec
+            .add_node(node(
+                "codec",
+                "Constant",
+                serde_json::json!({"type": "Str", "value": "libx264"}),
+            ))
+            .unwrap();
+        connected_codec
+            .add_connection(
+                "codec",
+                PortConnection {
+                    source_port: "value".to_string(),
+                    target_port: "codec".to_string(),
+                    port_type: PortType::Str,
+                    index: None,
+                },
+                "out",
+            )
+            .unwrap();
+        assert_eq!(
+            classes(&connected_codec),
+            [ResourceClass::Cpu, ResourceClass::Gpu, ResourceClass::Disk]
+        );
+
+        let mut unknown = PipelineGraph::new();
+        unknown
+            .add_node(node("p", "SomePluginNode", serde_json::json!({})))
+            .unwrap();
+        assert!(unknown.resource_classes(&registry).is_err());
+    }
+
+    #[test]
+    fn test_workflow_interface_validate_params() {
+        let interface: WorkflowInterface = serde_json::from_value(serde_json::json!({
+            "inputs": [
+                {"name": "scale", "port_type": "Int", "enum_options": [2, 4]},
+                {"name": "crf", "port_type": "Int", "min": 0, "max": 51},
+                {
+                    "name": "output",
+                    "port_type": "Path",
+                    "file_picker": {"mode": "save", "extensions": ["mkv", ".mp4"]}
+                },
+                {"name": "dir", "port_type": "Path", "file_picker": {"mode": "directory"}}
+            ],
+            "outputs": []
+        }))
+        .unwrap();
+
+        let mut valid = HashMap::from([
+            ("scale".to_string(), serde_json::json!("4")),
+            ("crf".to_string(), serde_json::json!(18.0)),
+            ("output".to_string(), serde_json::json!("/tmp/out.MP4")),
+        ]);
+        let preset = HashMap::from([("dir".to_string(), serde_json::json!("/tmp"))]);
+        assert!(interface.validate_params(&mut valid, &preset).is_empty());
+        assert_eq!(valid["scale"], serde_json::json!(4));
+        assert_eq!(valid["crf"], serde_json::json!(18));
+
+        let mut invalid = HashMap::from([
+            ("scale".to_string(), serde_json::json!(3)),
+            ("crf".to_string(), serde_json::json!("high")),
+            ("output".to_string(), serde_json::json!("/tmp/out.avi")),
+        ]);
+        let errors: Vec<String> = interface
+            .validate_params(&mut invalid, &HashMap::new())
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                "param 'scale': must be one of 2, 4, got 3",
+                "param 'crf': expected Int, got \"high\"",
+                "param 'output': expected a file ending in mkv, .mp4",
+                "param 'dir': a value is required",
+            ]
+        );
+
+        let mut out_of_range = HashMap::from([("crf".to_string(), serde_json::json!(52))]);
+        let errors = interface.validate_params(&mut out_of_range, &HashMap::new());
+        assert_eq!(errors[1].param, "crf");
+        assert_eq!(errors[1].message, "must be at most 51, got 52");
+    }
 }
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use anyhow::{bail, Context, Result};
@@ -5,6 +6,15 @@ use reqwest::header::{HeaderMap, HeaderValue};
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+use crate::config::JellyfinConfig;
+use crate::graph::PipelineGraph;
+use crate::secrets::SecretStore;
+
+/// `JellyfinVideo` param naming a server from `[[jellyfin.servers]]`.
+pub const SERVER_PARAM: &str = "server";
+
+const SERVER_NAME_MAX_LEN: usize = 32;
+
 /// Basic server information returned by `GET /System/Info`.
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "PascalCase")]
@@ -42,6 +52,12 @@ pub struct ItemQuery {
     pub fields: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub recursive: Option<bool>,
+    /// Comma-separated sort keys, e.g. `SortName` or `DateCreated`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_by: Option<String>,
+    /// `Ascending` or `Descending`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort_order: Option<String>,
 }
 
 /// Paginated response from `GET /Items`.
@@ -50,6 +66,8 @@ pub struct ItemQuery {
 pub struct ItemsResponse {
     pub items: Vec<MediaItem>,
     pub total_record_count: u64,
+    #[serde(default)]
+    pub start_index: u64,
 }
 
 /// A Jellyfin media item (movie, episode, series, etc.).
@@ -65,6 +83,113 @@ pub struct MediaItem {
     pub season_name: Option<String>,
     pub index_number: Option<u32>,
     pub overview: Option<String>,
+    /// Image type (`Primary`, `Backdrop`, …) → tag of the item's images.
+    #[serde(default)]
+    pub image_tags: HashMap<String, String>,
+    /// Only filled in when requested with the `MediaStreams` field.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub media_streams: Vec<MediaStream>,
+}
+
+impl MediaItem {
+    /// The item's first video stream.
+    pub fn video_stream(&self) -> Option<&MediaStream> {
+        self.media_streams
+            .iter()
+            .find(|stream| stream.type_ == "Video")
+    }
+}
+
+/// A video, audio or subtitle stream of a media item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct MediaStream {
+    #[serde(rename = "Type")]
+    pub type_: String,
+    pub codec: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub bit_depth: Option<u32>,
+}
+
+/// Targets that make processing an item unnecessary. An item is skipped
+/// when its video stream meets every target that is set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SkipRule {
+    /// `0` means any width.
+    pub min_width: u32,
+    /// `0` means any height.
+    pub min_height: u32,
+    /// Codecs as reported by Jellyfin, e.g. `hevc` or `av1`; empty means any.
+    pub codecs: Vec<String>,
+}
+
+impl SkipRule {
+    /// Rule from the `skip_*` params of a `JellyfinVideo` node.
+    pub fn from_params(params: &HashMap<String, serde_json::Value>) -> Self {
+        let number = |name: &str| {
+            params
+                .get(name)
+                .and_then(|value| value.as_u64())
+                .map_or(0, |value| value.min(u32::MAX as u64) as u32)
+        };
+        let codecs = params
+            .get("skip_codecs")
+            .and_then(|value| value.as_str())
+            .map(parse_codec_list)
+            .unwrap_or_default();
+        Self {
+            min_width: number("skip_min_width"),
+            min_height: number("skip_min_height"),
+            codecs,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min_width == 0 && self.min_height == 0 && self.codecs.is_empty()
+    }
+
+    /// Why `item` needs no processing, or `None` when it does.
+    pub fn check(&self, item: &MediaItem) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+        let video = item.video_stream()?;
+        let width = video.width.unwrap_or(0);
+        let height = video.height.unwrap_or(0);
+        let codec = video.codec.as_deref().unwrap_or("unknown");
+        if width < self.min_width || height < self.min_height {
+            return None;
+        }
+        if !self.codecs.is_empty()
+            && !self
+                .codecs
+                .iter()
+                .any(|target| target.eq_ignore_ascii_case(codec))
+        {
+            return None;
+        }
+        Some(format!(
+            "source is {width}x{height} {codec}, which already meets the target"
+        ))
+    }
+}
+
+/// Splits a comma-separated codec list such as `"hevc, av1"`.
+pub fn parse_codec_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|codec| codec.trim().to_ascii_lowercase())
+        .filter(|codec| !codec.is_empty())
+        .collect()
+}
+
+/// An image fetched from `GET /Items/{id}/Images/{type}`.
+#[derive(Debug, Clone)]
+pub struct ItemImage {
+    pub content_type: String,
+    pub bytes: Vec<u8>,
 }
 
 /// Authenticated Jellyfin REST API client.
@@ -185,7 +310,7 @@ impl JellyfinClient {
         let resp = self
             .client
             .get(url)
-            .query(&[("Ids", item_id), ("Fields", "Path,Overview")])
+            .query(&[("Ids", item_id), ("Fields", "Path,Overview,MediaStreams")])
             .send()
             .await
             .with_context(|| format!("failed to fetch item {item_id}"))?;
@@ -207,6 +332,49 @@ impl JellyfinClient {
             .with_context(|| format!("item not found: {item_id}"))
     }
 
+    /// `GET /Items/{id}/Images/{type}` — fetch an item image scaled down to
+    /// `max_width`. `None` when the item has no image of that type.
+    pub async fn get_item_image(
+        &self,
+        item_id: &str,
+        image_type: &str,
+        max_width: u32,
+    ) -> Result<Option<ItemImage>> {
+        let url = self.url(&format!("/Items/{item_id}/Images/{image_type}"))?;
+        let resp = self
+            .client
+            .get(url)
+            .query(&[("maxWidth", max_width)])
+            .send()
+            .await
+            .with_context(|| format!("failed to fetch {image_type} image of item {item_id}"))?;
+
+        if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !resp.status().is_success() {
+            bail!(
+                "Jellyfin /Items/{item_id}/Images/{image_type} returned HTTP {}",
+                resp.status().as_u16()
+            );
+        }
+
+        let content_type = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("application/octet-stream")
+            .to_string();
+        let bytes = resp
+            .bytes()
+            .await
+            .context("failed to read image response")?;
+        Ok(Some(ItemImage {
+            content_type,
+            bytes: bytes.to_vec(),
+        }))
+    }
+
     /// Resolve item ID → local filesystem path via `MediaItem.Path`.
     pub async fn get_item_file_path(&self, item_id: &str) -> Result<PathBuf> {
         let item = self.get_item(item_id).await?;
@@ -237,6 +405,151 @@ impl JellyfinClient {
     }
 }
 
+pub fn validate_server_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > SERVER_NAME_MAX_LEN {
+        bail!("Jellyfin server name must be 1-{SERVER_NAME_MAX_LEN} characters");
+    }
+    if !name
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-'))
+    {
+        bail!("Jellyfin server name may only contain letters, digits, '_' and '-'");
+    }
+    Ok(())
+}
+
+/// Secret that holds the API key of a server saved through the API.
+pub fn server_api_key_secret(name: &str) -> String {
+    format!("jellyfin.{name}")
+}
+
+/// Base URL and API key of a configured server.
+#[derive(Debug, Clone)]
+pub struct ServerCredentials {
+    pub url: String,
+    pub api_key: String,
+}
+
+/// Look up a configured server and decrypt its API key.
+pub fn server_credentials(
+    config: &JellyfinConfig,
+    secrets: &SecretStore,
+    name: &str,
+) -> Result<ServerCredentials> {
+    let server = config
+        .server(name)
+        .with_context(|| format!("unknown Jellyfin server: {name}"))?;
+    let api_key = secrets.get(&server.api_key_secret)?.with_context(|| {
+        format!(
+            "API key secret '{}' of Jellyfin server '{name}' is not set",
+            server.api_key_secret
+        )
+    })?;
+    Ok(ServerCredentials {
+        url: server.url.clone(),
+        api_key,
+    })
+}
+
+/// Fill `jellyfin_url` and `api_key` of every `JellyfinVideo` node that
+/// names a configured server, or that has no URL while a default server is
+/// set. Like secret references, this only runs on the copy handed to the
+/// executor.
+pub fn resolve_server_refs(
+    graph: &mut PipelineGraph,
+    config: &JellyfinConfig,
+    secrets: &SecretStore,
+) -> Result<()> {
+    for node in graph.nodes_mut() {
+        if node.node_type != "JellyfinVideo" {
+            continue;
+        }
+        let param = |name: &str| {
+            node.params
+                .get(name)
+                .and_then(|value| value.as_str())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+        let name = match param(SERVER_PARAM) {
+            Some(name) => name,
+            None if param("jellyfin_url").is_none() && !config.default_server.is_empty() => {
+                config.default_server.as_str()
+            }
+            None => continue,
+        };
+        let name = name.to_string();
+        let credentials = server_credentials(config, secrets, &name)
+            .with_context(|| format!("failed to resolve Jellyfin server for node '{}'", node.id))?;
+        node.params.insert(
+            "jellyfin_url".to_string(),
+            serde_json::Value::String(credentials.url),
+        );
+        node.params.insert(
+            "api_key".to_string(),
+            serde_json::Value::String(credentials.api_key),
+        );
+    }
+    Ok(())
+}
+
+/// Checks the `JellyfinVideo` nodes that have skip rules and returns why the
+/// job can be skipped, if one of their items already meets its rule. Runs
+/// after server references are resolved, so the nodes carry a URL and key.
+/// `item_id` may come from a param or from a `WorkflowInput` port.
+pub async fn skip_reason(
+    graph: &PipelineGraph,
+    job_params: Option<&HashMap<String, serde_json::Value>>,
+) -> Result<Option<String>> {
+    for idx in graph.execution_order()? {
+        let node = graph.node(idx);
+        if node.node_type != "JellyfinVideo" {
+            continue;
+        }
+        let rule = SkipRule::from_params(&node.params);
+        if rule.is_empty() {
+            continue;
+        }
+
+        let input = |port: &str| -> Option<String> {
+            let value = node.params.get(port).or_else(|| {
+                let (source, connection) = graph
+                    .connections_to(idx)
+                    .into_iter()
+                    .find(|(_, connection)| connection.target_port == port)?;
+                let source = graph.node(source);
+                if source.node_type != "WorkflowInput" {
+                    return None;
+                }
+                job_params
+                    .and_then(|params| params.get(&connection.source_port))
+                    .or_else(|| source.params.get(&connection.source_port))
+            })?;
+            value
+                .as_str()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(ToOwned::to_owned)
+        };
+        let (Some(url), Some(api_key), Some(item_id)) =
+            (input("jellyfin_url"), input("api_key"), input("item_id"))
+        else {
+            bail!(
+                "node '{}' has skip rules but its Jellyfin URL, API key or item id is not known before the run",
+                node.id
+            );
+        };
+
+        let item = JellyfinClient::new(&url, &api_key)?
+            .get_item(&item_id)
+            .await?;
+        if let Some(reason) = rule.check(&item) {
+            return Ok(Some(format!("Jellyfin item {item_id}: {reason}")));
+        }
+    }
+    Ok(None)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -285,6 +598,8 @@ mod tests {
             start_index: Some(0),
             fields: Some("Path,Overview".to_string()),
             recursive: Some(true),
+            sort_by: Some("SortName".to_string()),
+            sort_order: Some("Descending".to_string()),
         };
 
         let qs = serde_qs_manual(&query);
@@ -293,6 +608,8 @@ mod tests {
         assert!(qs.contains("Limit=20"), "got: {qs}");
         assert!(qs.contains("StartIndex=0"), "got: {qs}");
         assert!(qs.contains("Recursive=true"), "got: {qs}");
+        assert!(qs.contains("SortBy=SortName"), "got: {qs}");
+        assert!(qs.contains("SortOrder=Descending"), "got: {qs}");
     }
 
     #[test]
@@ -358,7 +675,8 @@ mod tests {
                     "SeriesName": "My Anime",
                     "SeasonName": "Season 1",
                     "IndexNumber": 1,
-                    "Overview": "The adventure begins."
+                    "Overview": "The adventure begins.",
+                    "ImageTags": {"Primary": "tag001"}
                 },
                 {
                     "Id": "item002",
@@ -386,6 +704,10 @@ mod tests {
         assert_eq!(ep1.season_name.as_deref(), Some("Season 1"));
         assert_eq!(ep1.index_number, Some(1));
         assert_eq!(ep1.overview.as_deref(), Some("The adventure begins."));
+        assert_eq!(
+            ep1.image_tags.get("Primary").map(String::as_str),
+            Some("tag001")
+        );
 
         let ep2 = &resp.items[1];
         assert_eq!(ep2.index_number, Some(2));
@@ -447,4 +769,117 @@ mod tests {
             .collect::<Vec<_>>()
             .join("&")
     }
+
+    #[test]
+    fn test_resolve_server_refs_fills_credentials() {
+        use crate::config::JellyfinServerConfig;
+        use crate::graph::NodeInstance;
+
+        let dir = tempfile::tempdir().unwrap();
+        let secrets = SecretStore::new(dir.path());
+        secrets.put("jellyfin.anime", "anime-key").unwrap();
+        let mut config = JellyfinConfig {
+            servers: vec![JellyfinServerConfig {
+                name: "anime".to_string(),
+                url: "http://anime.local:8096".to_string(),
+                api_key_secret: "jellyfin.anime".to_string(),
+            }],
+            default_server: String::new(),
+        };
+
+        let mut graph = PipelineGraph::new();
+        graph
+            .add_node(NodeInstance {
+                id: "source".to_string(),
+                node_type: "JellyfinVideo".to_string(),
+                params: HashMap::from([
+                    ("server".to_string(), serde_json::json!("anime")),
+                    ("item_id".to_string(), serde_json::json!("abc")),
+                ]),
+            })
+            .unwrap();
+        resolve_server_refs(&mut graph, &config, &secrets).unwrap();
+        let node = graph.nodes_mut().next().unwrap();
+        assert_eq!(node.params["jellyfin_url"], "http://anime.local:8096");
+        assert_eq!(node.params["api_key"], "anime-key");
+
+        node.params
+            .insert("server".to_string(), serde_json::json!("movies"));
+        let err = resolve_server_refs(&mut graph, &config, &secrets).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("unknown Jellyfin server: movies"),
+            "{err:#}"
+        );
+
+        // Without a server or URL the default server is used; an explicit
+        // URL is left alone.
+        let mut graph = PipelineGraph::new();
+        for (id, url) in [("default", None), ("explicit", Some("http://other:8096"))] {
+            let mut params = HashMap::from([("item_id".to_string(), serde_json::json!("abc"))]);
+            if let Some(url) = url {
+                params.insert("jellyfin_url".to_string(), serde_json::json!(url));
+                params.insert("api_key".to_string(), serde_json::json!("other-key"));
+            }
+            graph
+                .add_node(NodeInstance {
+                    id: id.to_string(),
+                    node_type: "JellyfinVideo".to_string(),
+                    params,
+                })
+                .unwrap();
+        }
+        config.default_server = "anime".to_string();
+        resolve_server_refs(&mut graph, &config, &secrets).unwrap();
+        let keys: HashMap<String, serde_json::Value> = graph
+            .nodes_mut()
+            .map(|node| (node.id.clone(), node.params["api_key"].clone()))
+            .collect();
+        assert_eq!(keys["default"], "anime-key");
+        assert_eq!(keys["explicit"], "other-key");
+    }
+
+    #[test]
+    fn test_validate_server_name() {
+        assert!(validate_server_name("anime-1").is_ok());
+        assert!(validate_server_name("").is_err());
+        assert!(validate_server_name("has space").is_err());
+        assert!(validate_server_name("a.b").is_err());
+    }
+
+    #[test]
+    fn test_skip_rule_checks_resolution_and_codec() {
+        let item: MediaItem = serde_json::from_value(serde_json::json!({
+            "Id": "ep1",
+            "Name": "Episode 1",
+            "Type": "Episode",
+            "MediaStreams": [
+                {"Type": "Audio", "Codec": "aac"},
+                {"Type": "Video", "Codec": "hevc", "Width": 3840, "Height": 2160}
+            ]
+        }))
+        .unwrap();
+
+        let params = HashMap::from([
+            ("skip_min_height".to_string(), serde_json::json!(2160)),
+            ("skip_codecs".to_string(), serde_json::json!("HEVC, av1")),
+        ]);
+        let rule = SkipRule::from_params(&params);
+        assert_eq!(rule.codecs, ["hevc", "av1"]);
+        assert_eq!(
+            rule.check(&item).as_deref(),
+            Some("source is 3840x2160 hevc, which already meets the target")
+        );
+
+        let higher = SkipRule {
+            min_width: 7680,
+            ..rule.clone()
+        };
+        assert_eq!(higher.check(&item), None);
+        let other_codec = SkipRule {
+            codecs: vec!["av1".to_string()],
+            ..rule
+        };
+        assert_eq!(other_codec.check(&item), None);
+        assert_eq!(SkipRule::default().check(&item), None);
+    }
 }
@@ -0,0 +1,490 @@
+//! Per-job log capture.
+//!
+//! Every job runs inside a `job` tracing span carrying a `job_id` field.
+//! [`JobLogLayer`] routes events emitted under that span (including ffmpeg
+//! stderr forwarded by the decode/encode threads) into the process-wide
+//! [`JobLogHub`], which keeps a bounded in-memory ring for live viewers and
+//! mirrors each line into a size-capped JSON-lines file under
+//! `<data_dir>/logs/jobs/` so logs survive job completion and restarts.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use anyhow::{Context as _, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::logging::redact_sensitive_text;
+
+/// Name of the span that marks all work belonging to one job.
+pub const JOB_LOG_SPAN_NAME: &str = "job";
+/// Sub-directory of the log directory holding per-job log files.
+pub const DEFAULT_JOB_LOG_DIR_NAME: &str = "jobs";
+pub const DEFAULT_JOB_LOG_RING_CAPACITY: usize = 2000;
+pub const DEFAULT_JOB_LOG_FILE_MAX_BYTES: u64 = 8 * 1024 * 1024;
+
+const JOB_LOG_CHANNEL_CAPACITY: usize = 256;
+const JOB_LOG_FILE_TRUNCATED_MESSAGE: &str =
+    "job log file size limit reached; further lines are kept in memory only";
+
+static JOB_LOG_HUB: OnceLock<JobLogHub> = OnceLock::new();
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobLogLine {
+    pub timestamp: DateTime<Utc>,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+#[derive(Debug)]
+struct JobLogFile {
+    file: fs::File,
+    written_bytes: u64,
+    max_bytes: u64,
+    truncated: bool,
+}
+
+#[derive(Debug)]
+struct JobLogCapture {
+    ring: Mutex<VecDeque<JobLogLine>>,
+    ring_capacity: usize,
+    file: Mutex<Option<JobLogFile>>,
+    sender: broadcast::Sender<JobLogLine>,
+}
+
+/// Process-wide registry of jobs whose logs are currently being captured.
+#[derive(Debug, Default)]
+pub struct JobLogHub {
+    captures: Mutex<HashMap<String, Arc<JobLogCapture>>>,
+}
+
+/// Ends the capture for a job when dropped.
+#[derive(Debug)]
+pub struct JobLogCaptureGuard {
+    job_id: String,
+}
+
+/// Tracing layer that forwards events emitted inside a `job` span to the hub.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JobLogLayer;
+
+struct JobSpanId(String);
+
+#[derive(Default)]
+struct JobIdVisitor {
+    job_id: Option<String>,
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+pub fn job_log_hub() -> &'static JobLogHub {
+    JOB_LOG_HUB.get_or_init(JobLogHub::default)
+}
+
+pub fn job_log_layer() -> JobLogLayer {
+    JobLogLayer
+}
+
+/// Filter for the job log layer: info and above everywhere, plus the ffmpeg
+/// stderr targets which are only emitted at debug level.
+pub fn job_log_targets() -> Targets {
+    Targets::new()
+        .with_default(Level::INFO)
+        .with_target("ort", Level::WARN)
+        .with_target("ffmpeg_stderr", Level::DEBUG)
+        .with_target("ffmpeg_encode_stderr", Level::DEBUG)
+        .with_target("ffmpeg_stream_stderr", Level::DEBUG)
+        .with_target("ffmpeg_filter_stderr", Level::DEBUG)
+}
+
+/// Location of the persisted log file for `job_id` under `data_dir`.
+pub fn job_log_file_path(data_dir: &Path, job_id: &str) -> PathBuf {
+    data_dir
+        .join(crate::logging::DEFAULT_LOG_DIR_NAME)
+        .join(DEFAULT_JOB_LOG_DIR_NAME)
+        .join(format!("{job_id}.jsonl"))
+}
+
+/// Read the last `tail` lines from a persisted job log file.
+pub fn read_job_log_file_tail(path: &Path, tail: usize) -> Result<Vec<JobLogLine>> {
+    let file = fs::File::open(path)
+        .with_context(|| format!("failed to open job log file: {}", path.display()))?;
+
+    let mut lines = VecDeque::with_capacity(tail.min(DEFAULT_JOB_LOG_RING_CAPACITY));
+    for raw in BufReader::new(file).lines() {
+        let raw =
+            raw.with_context(|| format!("failed to read job log file: {}", path.display()))?;
+        let Ok(line) = serde_json::from_str::<JobLogLine>(&raw) else {
+            continue;
+        };
+        if tail == 0 {
+            continue;
+        }
+        if lines.len() == tail {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    Ok(lines.into_iter().collect())
+}
+
+impl JobLogHub {
+    /// Start capturing logs for `job_id`, optionally mirroring them to `log_file`.
+    pub fn begin_capture(&self, job_id: &str, log_file: Option<&Path>) -> JobLogCaptureGuard {
+        let file = log_file.and_then(|path| open_job_log_file(path).ok());
+        let (sender, _rx) = broadcast::channel(JOB_LOG_CHANNEL_CAPACITY);
+        let capture = Arc::new(JobLogCapture {
+            ring: Mutex::new(VecDeque::new()),
+            ring_capacity: DEFAULT_JOB_LOG_RING_CAPACITY,
+            file: Mutex::new(file),
+            sender,
+        });
+
+        self.lock_captures().insert(job_id.to_string(), capture);
+
+        JobLogCaptureGuard {
+            job_id: job_id.to_string(),
+        }
+    }
+
+    pub fn end_capture(&self, job_id: &str) {
+        let removed = self.lock_captures().remove(job_id);
+        if let Some(capture) = removed {
+            let mut file = capture.file.lock().unwrap_or_else(|p| p.into_inner());
+            if let Some(open) = file.as_mut() {
+                let _ = open.file.flush();
+            }
+            *file = None;
+        }
+    }
+
+    pub fn is_capturing(&self, job_id: &str) -> bool {
+        self.lock_captures().contains_key(job_id)
+    }
+
+    /// Last `tail` lines of an active capture, or `None` when the job is not
+    /// being captured.
+    pub fn tail(&self, job_id: &str, tail: usize) -> Option<Vec<JobLogLine>> {
+        let capture = self.capture(job_id)?;
+        let ring = capture.ring.lock().unwrap_or_else(|p| p.into_inner());
+        let skip = ring.len().saturating_sub(tail);
+        Some(ring.iter().skip(skip).cloned().collect())
+    }
+
+    pub fn subscribe(&self, job_id: &str) -> Option<broadcast::Receiver<JobLogLine>> {
+        self.capture(job_id)
+            .map(|capture| capture.sender.subscribe())
+    }
+
+    pub fn append(&self, job_id: &str, line: JobLogLine) {
+        let Some(capture) = self.capture(job_id) else {
+            return;
+        };
+
+        {
+            let mut ring = capture.ring.lock().unwrap_or_else(|p| p.into_inner());
+            if ring.len() == capture.ring_capacity {
+                ring.pop_front();
+            }
+            ring.push_back(line.clone());
+        }
+
+        {
+            // Never log from here: this runs inside the tracing dispatch.
+            let mut file = capture.file.lock().unwrap_or_else(|p| p.into_inner());
+            if let Some(open) = file.as_mut() {
+                if open.append(&line).is_err() {
+                    *file = None;
+                }
+            }
+        }
+
+        let _ = capture.sender.send(line);
+    }
+
+    fn capture(&self, job_id: &str) -> Option<Arc<JobLogCapture>> {
+        self.lock_captures().get(job_id).cloned()
+    }
+
+    fn lock_captures(&self) -> std::sync::MutexGuard<'_, HashMap<String, Arc<JobLogCapture>>> {
+        self.captures.lock().unwrap_or_else(|p| p.into_inner())
+    }
+}
+
+impl Drop for JobLogCaptureGuard {
+    fn drop(&mut self) {
+        job_log_hub().end_capture(&self.job_id);
+    }
+}
+
+impl JobLogFile {
+    fn append(&mut self, line: &JobLogLine) -> std::io::Result<()> {
+        if self.truncated {
+            return Ok(());
+        }
+
+        let mut encoded = serde_json::to_string(line).map_err(std::io::Error::other)?;
+        encoded.push('\n');
+
+        if self.written_bytes + encoded.len() as u64 > self.max_bytes {
+            self.truncated = true;
+            let marker = JobLogLine {
+                timestamp: Utc::now(),
+                level: Level::WARN.to_string(),
+                target: module_path!().to_string(),
+                message: JOB_LOG_FILE_TRUNCATED_MESSAGE.to_string(),
+            };
+            let mut marker = serde_json::to_string(&marker).map_err(std::io::Error::other)?;
+            marker.push('\n');
+            self.file.write_all(marker.as_bytes())?;
+            return self.file.flush();
+        }
+
+        self.file.write_all(encoded.as_bytes())?;
+        self.written_bytes += encoded.len() as u64;
+        Ok(())
+    }
+}
+
+fn open_job_log_file(path: &Path) -> std::io::Result<JobLogFile> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    let written_bytes = file.metadata().map(|m| m.len()).unwrap_or(0);
+
+    Ok(JobLogFile {
+        file,
+        written_bytes,
+        max_bytes: DEFAULT_JOB_LOG_FILE_MAX_BYTES,
+        truncated: false,
+    })
+}
+
+impl<S> Layer<S> for JobLogLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != JOB_LOG_SPAN_NAME {
+            return;
+        }
+
+        let mut visitor = JobIdVisitor::default();
+        attrs.record(&mut visitor);
+
+        if let (Some(job_id), Some(span)) = (visitor.job_id, ctx.span(id)) {
+            span.extensions_mut().insert(JobSpanId(job_id));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let Some(scope) = ctx.event_scope(event) else {
+            return;
+        };
+        let Some(job_id) = scope
+            .from_root()
+            .find_map(|span| span.extensions().get::<JobSpanId>().map(|id| id.0.clone()))
+        else {
+            return;
+        };
+
+        let hub = job_log_hub();
+        if !hub.is_capturing(&job_id) {
+            return;
+        }
+
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let message = if visitor.fields.is_empty() {
+            visitor.message
+        } else if visitor.message.is_empty() {
+            visitor.fields
+        } else {
+            format!("{} {}", visitor.message, visitor.fields)
+        };
+
+        let metadata = event.metadata();
+        hub.append(
+            &job_id,
+            JobLogLine {
+                timestamp: Utc::now(),
+                level: metadata.level().to_string(),
+                target: metadata.target().to_string(),
+                message: redact_sensitive_text(&message),
+            },
+        );
+    }
+}
+
+impl Visit for JobIdVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "job_id" {
+            self.job_id = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "job_id" {
+            self.job_id = Some(format!("{value:?}").trim_matches('"').to_string());
+        }
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.push_field(field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        } else {
+            self.push_field(field.name(), &format!("{value:?}"));
+        }
+    }
+}
+
+impl MessageVisitor {
+    fn push_field(&mut self, name: &str, value: &str) {
+        if !self.fields.is_empty() {
+            self.fields.push(' ');
+        }
+        let _ = write!(self.fields, "{name}={value}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    use tracing_subscriber::prelude::*;
+
+    fn with_job_log_subscriber(op: impl FnOnce()) {
+        let subscriber =
+            tracing_subscriber::registry().with(job_log_layer().with_filter(job_log_targets()));
+        tracing::subscriber::with_default(subscriber, op);
+    }
+
+    #[test]
+    fn layer_captures_only_events_inside_job_span() {
+        let job_id = format!("capture-{}", uuid::Uuid::new_v4());
+        let _guard = job_log_hub().begin_capture(&job_id, None);
+
+        with_job_log_subscriber(|| {
+            tracing::info!("outside any job");
+            let span = tracing::info_span!("job", job_id = %job_id);
+            let _entered = span.enter();
+            tracing::info!(frames = 12, "decoded frames");
+            tracing::debug!(target: "ffmpeg_stderr", "frame=12 fps=24");
+            tracing::debug!("filtered debug line");
+        });
+
+        let lines = job_log_hub().tail(&job_id, 10).expect("capture active");
+        let messages: Vec<&str> = lines.iter().map(|line| line.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec!["decoded frames frames=12", "frame=12 fps=24"]
+        );
+        assert_eq!(lines[1].target, "ffmpeg_stderr");
+    }
+
+    #[test]
+    fn layer_propagates_job_span_into_spawned_threads() {
+        let job_id = format!("thread-{}", uuid::Uuid::new_v4());
+        let _guard = job_log_hub().begin_capture(&job_id, None);
+
+        with_job_log_subscriber(|| {
+            let span = tracing::info_span!("job", job_id = %job_id);
+            let _entered = span.enter();
+            let current = tracing::Span::current();
+            let dispatch = tracing::dispatcher::get_default(|d| d.clone());
+            std::thread::spawn(move || {
+                tracing::dispatcher::with_default(&dispatch, || {
+                    let _entered = current.enter();
+                    tracing::warn!("from worker thread");
+                });
+            })
+            .join()
+            .expect("join worker");
+        });
+
+        let lines = job_log_hub().tail(&job_id, 10).expect("capture active");
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].level, "WARN");
+        assert_eq!(lines[0].message, "from worker thread");
+    }
+
+    #[test]
+    fn captured_lines_are_redacted_and_mirrored_to_file() {
+        let data_dir = tempdir().expect("tempdir");
+        let job_id = format!("file-{}", uuid::Uuid::new_v4());
+        let path = job_log_file_path(data_dir.path(), &job_id);
+
+        {
+            let _guard = job_log_hub().begin_capture(&job_id, Some(&path));
+            with_job_log_subscriber(|| {
+                let span = tracing::info_span!("job", job_id = %job_id);
+                let _entered = span.enter();
+                for index in 0..5 {
+                    tracing::info!("line {index} api_key=hunter2");
+                }
+            });
+        }
+
+        assert!(!job_log_hub().is_capturing(&job_id));
+        let lines = read_job_log_file_tail(&path, 2).expect("read job log tail");
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].message.starts_with("line 3 "));
+        assert!(lines[1].message.starts_with("line 4 "));
+        assert!(lines.iter().all(|line| !line.message.contains("hunter2")));
+    }
+
+    #[test]
+    fn ring_buffer_is_bounded() {
+        let job_id = format!("ring-{}", uuid::Uuid::new_v4());
+        let _guard = job_log_hub().begin_capture(&job_id, None);
+
+        for index in 0..(DEFAULT_JOB_LOG_RING_CAPACITY + 5) {
+            job_log_hub().append(
+                &job_id,
+                JobLogLine {
+                    timestamp: Utc::now(),
+                    level: "INFO".to_string(),
+                    target: "test".to_string(),
+                    message: index.to_string(),
+                },
+            );
+        }
+
+        let lines = job_log_hub()
+            .tail(&job_id, usize::MAX)
+            .expect("capture active");
+        assert_eq!(lines.len(), DEFAULT_JOB_LOG_RING_CAPACITY);
+        assert_eq!(lines[0].message, "5");
+    }
+}
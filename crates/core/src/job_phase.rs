@@ -0,0 +1,105 @@
+//! Coarse phases of a running job.
+//!
+//! Long silent stretches, above all TensorRT engine builds, make a job look
+//! hung. Code deep inside the pipeline (session loading, decoder start-up,
+//! encoder shutdown) calls [`report_phase`]; whoever runs the job installs a
+//! reporter with [`with_phase_reporter`] to turn the phases into progress
+//! events. The reporter is per thread; pipeline stages that run on their own
+//! threads take it along via [`phase_reporter`].
+
+use std::cell::RefCell;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobPhase {
+    /// Reading a model and creating its inference session.
+    LoadingModel,
+    /// TensorRT is building (or loading from its cache) the model's engine.
+    BuildingEngine,
+    /// Waiting for the first decoded frame.
+    Decoding,
+    /// Frames are flowing through the pipeline.
+    Processing,
+    /// All frames are written; the encoder is flushing.
+    Encoding,
+    /// Finishing the output container: joining chunks, verification and
+    /// moving the file into place.
+    Muxing,
+}
+
+pub type PhaseReporter = Arc<dyn Fn(JobPhase) + Send + Sync>;
+
+thread_local! {
+    static REPORTER: RefCell<Option<PhaseReporter>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with `reporter` receiving the phases reported on this thread,
+/// then restores the previous reporter.
+pub fn with_phase_reporter<R>(reporter: Option<PhaseReporter>, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<PhaseReporter>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            REPORTER.with(|slot| *slot.borrow_mut() = previous);
+        }
+    }
+
+    let _restore = Restore(REPORTER.with(|slot| slot.replace(reporter)));
+    f()
+}
+
+/// The reporter installed on this thread, for handing to worker threads.
+pub fn phase_reporter() -> Option<PhaseReporter> {
+    REPORTER.with(|slot| slot.borrow().clone())
+}
+
+/// Reports `phase` to this thread's reporter; a no-op without one.
+pub fn report_phase(phase: JobPhase) {
+    if let Some(reporter) = phase_reporter() {
+        reporter(phase);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[test]
+    fn test_reporter_is_scoped_to_thread_and_closure() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let reporter: PhaseReporter = Arc::new(move |phase| sink.lock().unwrap().push(phase));
+
+        report_phase(JobPhase::Decoding);
+        with_phase_reporter(Some(reporter), || {
+            report_phase(JobPhase::LoadingModel);
+            // Not inherited by other threads unless handed over.
+            std::thread::spawn(|| report_phase(JobPhase::Encoding))
+                .join()
+                .unwrap();
+            let handed = phase_reporter();
+            std::thread::spawn(move || {
+                with_phase_reporter(handed, || report_phase(JobPhase::Muxing))
+            })
+            .join()
+            .unwrap();
+        });
+        report_phase(JobPhase::Processing);
+
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![JobPhase::LoadingModel, JobPhase::Muxing]
+        );
+        assert!(phase_reporter().is_none());
+        assert_eq!(
+            serde_json::to_value(JobPhase::BuildingEngine).unwrap(),
+            "building_engine"
+        );
+    }
+}
@@ -1,19 +1,41 @@
 //! Core crate for shared videnoa types.
 
+pub mod artifacts;
+pub mod benchmark;
+pub mod chunking;
 pub mod compile;
 pub mod config;
 pub mod debug_event;
 pub mod descriptor;
 pub mod executor;
+pub mod experiment;
 pub mod graph;
 pub mod jellyfin;
+pub mod job_logs;
+pub mod job_phase;
+pub mod lint;
 pub mod logging;
 pub mod model_inspect;
 pub mod model_registry;
+pub mod monitor;
 pub mod node;
+pub mod node_error;
+pub mod node_i18n;
 pub mod nodes;
+pub mod plugin;
+pub mod post_actions;
+pub mod power;
+pub mod probe;
 pub mod registry;
+pub mod roi;
 pub mod runtime;
+pub mod secrets;
 pub mod server;
+pub mod services;
+pub mod step;
 pub mod streaming_executor;
+pub mod trace;
 pub mod types;
+pub mod updates;
+pub mod vapoursynth_export;
+pub mod worker;
@@ -0,0 +1,418 @@
+//! Workflow linting.
+//!
+//! Unlike [`PipelineGraph::validate`], which rejects graphs that cannot run,
+//! the lint pass reports graphs that run but probably do not do what their
+//! author meant: nodes whose results are thrown away, workflow inputs nobody
+//! reads, pointless or lossy type conversions, and param values that are
+//! legal but unusual. Warnings never block a job.
+
+use std::collections::{HashSet, VecDeque};
+
+use petgraph::stable_graph::NodeIndex;
+use serde::{Deserialize, Serialize};
+
+use crate::graph::PipelineGraph;
+use crate::registry::NodeRegistry;
+use crate::types::PortType;
+
+/// Nodes whose only effect is the data on their outputs. One whose outputs
+/// never reach a node with side effects does nothing.
+const PURE_NODE_TYPES: &[&str] = &[
+    "VideoInput",
+    "StreamInput",
+    "VapourSynthInput",
+    "JellyfinVideo",
+    "SuperResolution",
+    "FrameInterpolation",
+    "Python",
+    "FfmpegFilter",
+    "GrainSynthesis",
+    "RegionEnhance",
+    "ModelInference",
+    "Resize",
+    "Rescale",
+    "ColorSpace",
+    "Concat",
+    "SceneDetect",
+    "AnalyzeSource",
+    "WorkflowInput",
+    "Constant",
+    "PathDivider",
+    "PathJoiner",
+    "StringTemplate",
+    "StringReplace",
+    "TypeConversion",
+    "JsonExtract",
+    "DateTime",
+    "Uuid",
+    "HostInfo",
+    "Script",
+    "Expression",
+];
+
+/// Nodes run for the values they produce, so each unconnected output is
+/// probably a missing connection.
+const RESULT_NODE_TYPES: &[&str] = &["WorkflowInput", "Downloader"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintCode {
+    /// The node's outputs never reach a node with side effects.
+    DeadNode,
+    /// An output that is the point of the node is not connected.
+    UnusedOutput,
+    /// A type conversion that does nothing or feeds another conversion.
+    RedundantConversion,
+    /// A type conversion that can lose information or fail at run time.
+    NarrowingConversion,
+    /// A legal param value that is rarely what was meant.
+    SuspiciousParam,
+}
+
+impl LintCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::DeadNode => "dead_node",
+            Self::UnusedOutput => "unused_output",
+            Self::RedundantConversion => "redundant_conversion",
+            Self::NarrowingConversion => "narrowing_conversion",
+            Self::SuspiciousParam => "suspicious_param",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LintWarning {
+    pub code: LintCode,
+    pub node_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<String>,
+    pub message: String,
+}
+
+impl std::fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: node '{}'", self.code.as_str(), self.node_id)?;
+        if let Some(port) = &self.port {
+            write!(f, " port '{port}'")?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Lints a graph that already passed validation. Warnings come in
+/// execution order.
+pub fn lint_graph(graph: &PipelineGraph, registry: &NodeRegistry) -> Vec<LintWarning> {
+    let Ok(order) = graph.execution_order() else {
+        return Vec::new();
+    };
+
+    let live = live_nodes(graph, &order);
+    let mut warnings = Vec::new();
+    for &idx in &order {
+        let node = graph.node(idx);
+        let warn = |code, port: Option<&str>, message: String| LintWarning {
+            code,
+            node_id: node.id.clone(),
+            port: port.map(str::to_string),
+            message,
+        };
+
+        if !live.contains(&idx) {
+            warnings.push(warn(
+                LintCode::DeadNode,
+                None,
+                "its outputs never reach an output, command or other node with side effects"
+                    .to_string(),
+            ));
+            continue;
+        }
+
+        if RESULT_NODE_TYPES.contains(&node.node_type.as_str()) {
+            let connected: HashSet<&str> = graph
+                .connections_from(idx)
+                .into_iter()
+                .map(|(_, connection)| connection.source_port.as_str())
+                .collect();
+            let outputs = registry
+                .create(&node.node_type, node.params.clone())
+                .map(|node| node.output_ports())
+                .unwrap_or_default();
+            for port in outputs {
+                if !connected.contains(port.name.as_str()) {
+                    warnings.push(warn(
+                        LintCode::UnusedOutput,
+                        Some(&port.name),
+                        "output is not connected to anything".to_string(),
+                    ));
+                }
+            }
+        }
+
+        match node.node_type.as_str() {
+            "TypeConversion" => {
+                let input_type = param_port_type(node, "input_type");
+                let output_type = param_port_type(node, "output_type");
+                if input_type == output_type {
+                    warnings.push(warn(
+                        LintCode::RedundantConversion,
+                        None,
+                        format!("converts {input_type:?} to itself"),
+                    ));
+                } else if is_narrowing(&input_type, &output_type) {
+                    warnings.push(warn(
+                        LintCode::NarrowingConversion,
+                        None,
+                        format!("converting {input_type:?} to {output_type:?} can lose information or fail at run time"),
+                    ));
+                }
+                let chained = graph
+                    .connections_to(idx)
+                    .into_iter()
+                    .any(|(source, _)| graph.node(source).node_type == "TypeConversion");
+                if chained {
+                    warnings.push(warn(
+                        LintCode::RedundantConversion,
+                        None,
+                        "converts the result of another TypeConversion; one conversion is enough"
+                            .to_string(),
+                    ));
+                }
+            }
+            "VideoOutput" => {
+                if node.params.get("crf").and_then(|v| v.as_i64()) == Some(0) {
+                    warnings.push(warn(
+                        LintCode::SuspiciousParam,
+                        Some("crf"),
+                        "crf 0 is lossless and produces very large files".to_string(),
+                    ));
+                }
+                let fixed_fps = node.params.contains_key("fps")
+                    && !graph
+                        .connections_to(idx)
+                        .iter()
+                        .any(|(_, connection)| connection.target_port == "fps");
+                if fixed_fps && interpolated_upstream(graph, idx) {
+                    warnings.push(warn(
+                        LintCode::SuspiciousParam,
+                        Some("fps"),
+                        "a fixed fps ignores the frame rate change of the FrameInterpolation upstream"
+                            .to_string(),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    warnings
+}
+
+/// Nodes from which a node with side effects is reachable, including the
+/// side-effect nodes themselves.
+fn live_nodes(graph: &PipelineGraph, order: &[NodeIndex]) -> HashSet<NodeIndex> {
+    let mut live: HashSet<NodeIndex> = order
+        .iter()
+        .copied()
+        .filter(|&idx| !PURE_NODE_TYPES.contains(&graph.node(idx).node_type.as_str()))
+        .collect();
+    let mut queue: VecDeque<NodeIndex> = live.iter().copied().collect();
+    while let Some(idx) = queue.pop_front() {
+        for (source, _) in graph.connections_to(idx) {
+            if live.insert(source) {
+                queue.push_back(source);
+            }
+        }
+    }
+    live
+}
+
+/// Whether frames reaching `idx` pass through a `FrameInterpolation` node.
+fn interpolated_upstream(graph: &PipelineGraph, idx: NodeIndex) -> bool {
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::from([idx]);
+    while let Some(current) = queue.pop_front() {
+        for (source, connection) in graph.connections_to(current) {
+            if connection.port_type != PortType::VideoFrames || !seen.insert(source) {
+                continue;
+            }
+            if graph.node(source).node_type == "FrameInterpolation" {
+                return true;
+            }
+            queue.push_back(source);
+        }
+    }
+    false
+}
+
+/// A `TypeConversion` type param; the node defaults both to `Int`.
+fn param_port_type(node: &crate::graph::NodeInstance, name: &str) -> PortType {
+    node.params
+        .get(name)
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+        .unwrap_or(PortType::Int)
+}
+
+fn is_narrowing(from: &PortType, to: &PortType) -> bool {
+    matches!(
+        (from, to),
+        (PortType::Float, PortType::Int)
+            | (
+                PortType::Str,
+                PortType::Int | PortType::Float | PortType::Bool
+            )
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::build_default_registry;
+
+    fn lint(workflow: serde_json::Value) -> Vec<(LintCode, String, Option<String>)> {
+        let graph: PipelineGraph = serde_json::from_value(workflow).unwrap();
+        let mut warnings: Vec<_> = lint_graph(&graph, &build_default_registry())
+            .into_iter()
+            .map(|w| (w.code, w.node_id, w.port))
+            .collect();
+        // Independent nodes have no fixed execution order.
+        warnings.sort_by(|a, b| (&a.1, &a.2).cmp(&(&b.1, &b.2)));
+        warnings
+    }
+
+    fn connection(
+        from: &str,
+        from_port: &str,
+        to: &str,
+        to_port: &str,
+        port_type: &str,
+    ) -> serde_json::Value {
+        serde_json::json!({
+            "from_node": from, "from_port": from_port,
+            "to_node": to, "to_port": to_port, "port_type": port_type
+        })
+    }
+
+    #[test]
+    fn test_lint_reports_dead_branches_and_unused_inputs() {
+        let warnings = lint(serde_json::json!({
+            "nodes": [
+                {"id": "in", "node_type": "WorkflowInput", "params": {"ports": [
+                    {"name": "name", "port_type": "Str"},
+                    {"name": "unused", "port_type": "Str"}
+                ]}},
+                {"id": "template", "node_type": "StringTemplate", "params": {"num_input": 1, "template": "{0}"}},
+                {"id": "print", "node_type": "Print", "params": {}},
+                {"id": "orphan", "node_type": "PathJoiner", "params": {"parent_path": "/tmp"}}
+            ],
+            "connections": [
+                connection("in", "name", "template", "str0", "Str"),
+                connection("template", "value", "print", "value", "Str")
+            ]
+        }));
+
+        assert_eq!(
+            warnings,
+            vec![
+                (
+                    LintCode::UnusedOutput,
+                    "in".to_string(),
+                    Some("unused".to_string())
+                ),
+                (LintCode::DeadNode, "orphan".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_reports_conversions() {
+        let warnings = lint(serde_json::json!({
+            "nodes": [
+                {"id": "value", "node_type": "Constant", "params": {"type": "Float", "value": 1.5}},
+                {"id": "to_int", "node_type": "TypeConversion", "params": {"input_type": "Float", "output_type": "Int"}},
+                {"id": "to_str", "node_type": "TypeConversion", "params": {"input_type": "Int", "output_type": "Str"}},
+                {"id": "same", "node_type": "TypeConversion", "params": {"input_type": "Str", "output_type": "Str"}},
+                {"id": "print", "node_type": "Print", "params": {}}
+            ],
+            "connections": [
+                connection("value", "value", "to_int", "value", "Float"),
+                connection("to_int", "value", "to_str", "value", "Int"),
+                connection("to_str", "value", "same", "value", "Str"),
+                connection("same", "value", "print", "value", "Str")
+            ]
+        }));
+
+        assert_eq!(
+            warnings,
+            vec![
+                (LintCode::RedundantConversion, "same".to_string(), None),
+                (LintCode::RedundantConversion, "same".to_string(), None),
+                (LintCode::NarrowingConversion, "to_int".to_string(), None),
+                (LintCode::RedundantConversion, "to_str".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_reports_suspicious_output_params() {
+        let warnings = lint(serde_json::json!({
+            "nodes": [
+                {"id": "input", "node_type": "VideoInput", "params": {"path": "/in.mkv"}},
+                {"id": "fi", "node_type": "FrameInterpolation", "params": {"model_path": "rife.onnx"}},
+                {"id": "output", "node_type": "VideoOutput", "params": {
+                    "output_path": "/out.mkv", "crf": 0, "fps": "24000/1001"
+                }}
+            ],
+            "connections": [
+                connection("input", "frames", "fi", "frames", "VideoFrames"),
+                connection("fi", "frames", "output", "frames", "VideoFrames"),
+                connection("input", "source_path", "output", "source_path", "Path")
+            ]
+        }));
+
+        assert_eq!(
+            warnings,
+            vec![
+                (
+                    LintCode::SuspiciousParam,
+                    "output".to_string(),
+                    Some("crf".to_string())
+                ),
+                (
+                    LintCode::SuspiciousParam,
+                    "output".to_string(),
+                    Some("fps".to_string())
+                ),
+            ]
+        );
+
+        let clean = lint(serde_json::json!({
+            "nodes": [
+                {"id": "input", "node_type": "VideoInput", "params": {"path": "/in.mkv"}},
+                {"id": "output", "node_type": "VideoOutput", "params": {"output_path": "/out.mkv", "crf": 18}}
+            ],
+            "connections": [
+                connection("input", "frames", "output", "frames", "VideoFrames"),
+                connection("input", "source_path", "output", "source_path", "Path")
+            ]
+        }));
+        assert!(clean.is_empty(), "{clean:?}");
+    }
+
+    #[test]
+    fn test_lint_warning_display() {
+        let warning = LintWarning {
+            code: LintCode::UnusedOutput,
+            node_id: "in".to_string(),
+            port: Some("unused".to_string()),
+            message: "output is not connected to anything".to_string(),
+        };
+        assert_eq!(
+            warning.to_string(),
+            "unused_output: node 'in' port 'unused': output is not connected to anything"
+        );
+        assert_eq!(
+            serde_json::to_value(&warning).unwrap()["code"],
+            "unused_output"
+        );
+    }
+}
@@ -7,7 +7,7 @@ use std::{
     path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, AtomicU64, Ordering},
-        Mutex, OnceLock,
+        Arc, Mutex, OnceLock, RwLock,
     },
     thread,
 };
@@ -36,6 +36,7 @@ static PANIC_HOOK_INSTALL_LOCK: Mutex<()> = Mutex::new(());
 static PANIC_HOOK_CRASH_DIR: OnceLock<PathBuf> = OnceLock::new();
 static PANIC_HOOK_WRITE_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
 static PANIC_ARTIFACT_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+static CUSTOM_REDACTION_RULES: RwLock<Option<Arc<RedactionRules>>> = RwLock::new(None);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RuntimeLogMode {
@@ -267,9 +268,7 @@ pub fn install_panic_hook(data_dir: Option<&Path>) -> PanicHookInstallPlan {
         };
     };
 
-    let crash_dir = data_dir
-        .join(DEFAULT_LOG_DIR_NAME)
-        .join(DEFAULT_CRASH_DIR_NAME);
+    let crash_dir = crash_dir_for(data_dir);
     if let Err(error) = fs::create_dir_all(&crash_dir) {
         return PanicHookInstallPlan::Fallback {
             attempted_crash_dir: Some(crash_dir),
@@ -433,9 +432,105 @@ fn is_ffmpeg_target(target: &str) -> bool {
     FFMPEG_DEBUG_TARGETS.contains(&target)
 }
 
+/// User-configured redaction rules layered on top of the built-in
+/// key heuristics: extra parameter names treated as sensitive, and regexes
+/// whose matches are masked wherever they appear.
+#[derive(Debug, Clone, Default)]
+pub struct RedactionRules {
+    param_names: Vec<String>,
+    patterns: Vec<regex::Regex>,
+}
+
+impl RedactionRules {
+    pub fn compile(param_names: &[String], patterns: &[String]) -> Result<Self, regex::Error> {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| regex::Regex::new(pattern))
+            .collect::<Result<Vec<_>, _>>()?;
+        let param_names = param_names
+            .iter()
+            .map(|name| name.trim().to_ascii_lowercase())
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        Ok(Self {
+            param_names,
+            patterns,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.param_names.is_empty() && self.patterns.is_empty()
+    }
+}
+
+/// Replace the process-wide custom redaction rules.
+pub fn set_redaction_rules(rules: RedactionRules) {
+    let rules = (!rules.is_empty()).then(|| Arc::new(rules));
+    *CUSTOM_REDACTION_RULES
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = rules;
+}
+
+fn custom_redaction_rules() -> Option<Arc<RedactionRules>> {
+    CUSTOM_REDACTION_RULES
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
 pub fn redact_sensitive_text(input: &str) -> String {
+    let rules = custom_redaction_rules();
+    redact_sensitive_text_with_rules(input, rules.as_deref())
+}
+
+fn redact_sensitive_text_with_rules(input: &str, rules: Option<&RedactionRules>) -> String {
     let with_redacted_userinfo = redact_url_credentials(input);
-    redact_sensitive_assignments(with_redacted_userinfo.as_str())
+    let redacted = redact_sensitive_assignments(with_redacted_userinfo.as_str(), rules);
+
+    match rules {
+        Some(rules) => rules.patterns.iter().fold(redacted, |text, pattern| {
+            pattern
+                .replace_all(&text, REDACTION_PLACEHOLDER)
+                .into_owned()
+        }),
+        None => redacted,
+    }
+}
+
+/// Mask values of sensitive keys in a JSON document and apply text redaction
+/// to every remaining string value.
+pub fn redact_json_value(value: &mut serde_json::Value) {
+    let rules = custom_redaction_rules();
+    redact_json_value_with_rules(value, rules.as_deref());
+}
+
+fn redact_json_value_with_rules(value: &mut serde_json::Value, rules: Option<&RedactionRules>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, entry) in map.iter_mut() {
+                if is_sensitive_key_with_rules(&key.to_ascii_lowercase(), rules) {
+                    if !entry.is_null() {
+                        *entry = serde_json::Value::String(REDACTION_PLACEHOLDER.to_string());
+                    }
+                } else {
+                    redact_json_value_with_rules(entry, rules);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                redact_json_value_with_rules(item, rules);
+            }
+        }
+        serde_json::Value::String(text) => {
+            let redacted = redact_sensitive_text_with_rules(text, rules);
+            if redacted != *text {
+                *text = redacted;
+            }
+        }
+        _ => {}
+    }
 }
 
 fn redact_url_credentials(input: &str) -> String {
@@ -474,7 +569,7 @@ fn redact_url_credentials(input: &str) -> String {
     output
 }
 
-fn redact_sensitive_assignments(input: &str) -> String {
+fn redact_sensitive_assignments(input: &str, rules: Option<&RedactionRules>) -> String {
     let bytes = input.as_bytes();
     let mut output = String::with_capacity(input.len());
     let mut cursor = 0usize;
@@ -495,7 +590,7 @@ fn redact_sensitive_assignments(input: &str) -> String {
 
             if key_start < index {
                 let key = input[key_start..index].to_ascii_lowercase();
-                if is_sensitive_key(key.as_str()) {
+                if is_sensitive_key_with_rules(key.as_str(), rules) {
                     let mut value_start = index + 1;
                     while value_start < bytes.len() && bytes[value_start].is_ascii_whitespace() {
                         value_start += 1;
@@ -572,6 +667,11 @@ fn find_unquoted_value_end(input: &str, bytes: &[u8], start: usize) -> usize {
     }
 }
 
+fn is_sensitive_key_with_rules(key: &str, rules: Option<&RedactionRules>) -> bool {
+    is_sensitive_key(key)
+        || rules.is_some_and(|rules| rules.param_names.iter().any(|name| name == key))
+}
+
 fn is_sensitive_key(key: &str) -> bool {
     if key == "key" || key == "pwd" || key == "passwd" || key == "authorization" {
         return true;
@@ -690,6 +790,113 @@ fn write_panic_artifact_file(
     Ok(artifact_path)
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LogFileEntry {
+    pub name: String,
+    pub size_bytes: u64,
+    pub modified_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Whether `name` looks like a file produced by the rolling file sink.
+pub fn is_rotated_log_file_name(name: &str) -> bool {
+    !name.contains(['/', '\\'])
+        && name.starts_with(&format!("{DEFAULT_LOG_FILE_PREFIX}."))
+        && name.ends_with(&format!(".{DEFAULT_LOG_FILE_SUFFIX}"))
+}
+
+/// List rotated log files in `log_dir`, newest first.
+pub fn list_log_files(log_dir: &Path) -> io::Result<Vec<LogFileEntry>> {
+    list_files_matching(log_dir, is_rotated_log_file_name)
+}
+
+/// Whether `name` looks like an artifact written by the panic hook.
+pub fn is_crash_artifact_file_name(name: &str) -> bool {
+    !name.contains(['/', '\\']) && name.starts_with("panic-") && name.ends_with(".log")
+}
+
+/// Directory the panic hook writes crash artifacts into for `data_dir`.
+pub fn crash_dir_for(data_dir: &Path) -> PathBuf {
+    data_dir
+        .join(DEFAULT_LOG_DIR_NAME)
+        .join(DEFAULT_CRASH_DIR_NAME)
+}
+
+/// List panic crash artifacts in `crash_dir`, newest first.
+pub fn list_crash_artifacts(crash_dir: &Path) -> io::Result<Vec<LogFileEntry>> {
+    list_files_matching(crash_dir, is_crash_artifact_file_name)
+}
+
+fn list_files_matching(dir: &Path, matches: fn(&str) -> bool) -> io::Result<Vec<LogFileEntry>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+
+    let mut files = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let Ok(name) = entry.file_name().into_string() else {
+            continue;
+        };
+        if !matches(&name) {
+            continue;
+        }
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() {
+            continue;
+        }
+        files.push(LogFileEntry {
+            name,
+            size_bytes: metadata.len(),
+            modified_at: metadata.modified().ok().map(chrono::DateTime::from),
+        });
+    }
+
+    files.sort_by(|a, b| {
+        b.modified_at
+            .cmp(&a.modified_at)
+            .then_with(|| b.name.cmp(&a.name))
+    });
+    Ok(files)
+}
+
+/// Read the last `tail` lines of a rotated log file.
+pub fn read_log_file_tail(log_dir: &Path, name: &str, tail: usize) -> io::Result<Vec<String>> {
+    if !is_rotated_log_file_name(name) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("not a log file name: {name}"),
+        ));
+    }
+
+    let raw = fs::read(log_dir.join(name))?;
+    let text = String::from_utf8_lossy(&raw);
+    let lines: Vec<&str> = text.lines().collect();
+    let skip = lines.len().saturating_sub(tail);
+    Ok(lines[skip..].iter().map(|line| line.to_string()).collect())
+}
+
+/// Delete the oldest rotated log files until their combined size fits in
+/// `max_total_bytes`. The newest file is the active sink and is always kept.
+/// Returns the names of deleted files.
+pub fn enforce_log_dir_size_limit(log_dir: &Path, max_total_bytes: u64) -> io::Result<Vec<String>> {
+    let files = list_log_files(log_dir)?;
+    let mut total: u64 = files.iter().map(|file| file.size_bytes).sum();
+    let mut removed = Vec::new();
+
+    for file in files.iter().skip(1).rev() {
+        if total <= max_total_bytes {
+            break;
+        }
+        fs::remove_file(log_dir.join(&file.name))?;
+        total = total.saturating_sub(file.size_bytes);
+        removed.push(file.name.clone());
+    }
+
+    Ok(removed)
+}
+
 fn panic_payload_to_string(payload: &(dyn Any + Send)) -> String {
     if let Some(payload) = payload.downcast_ref::<&str>() {
         (*payload).to_string()
@@ -873,6 +1080,106 @@ mod tests {
         }
     }
 
+    #[test]
+    fn custom_redaction_rules_mask_named_params_and_patterns() {
+        let rules = RedactionRules::compile(
+            &["Jellyfin_Auth".to_string()],
+            &[r"sk-[A-Za-z0-9]{8,}".to_string()],
+        )
+        .expect("compile rules");
+
+        let redacted = redact_sensitive_text_with_rules(
+            "jellyfin_auth=abcdef url=http://h/ key=sk-ABCDEFGH123 ok=1",
+            Some(&rules),
+        );
+        assert_eq!(
+            redacted,
+            format!(
+                "jellyfin_auth={REDACTION_PLACEHOLDER} url=http://h/ key={REDACTION_PLACEHOLDER} ok=1"
+            )
+        );
+        assert_eq!(
+            redact_sensitive_text_with_rules("note sk-ABCDEFGH123 here", Some(&rules)),
+            format!("note {REDACTION_PLACEHOLDER} here")
+        );
+        assert_eq!(
+            redact_sensitive_text_with_rules("jellyfin_auth=abcdef", None),
+            "jellyfin_auth=abcdef"
+        );
+    }
+
+    #[test]
+    fn custom_redaction_rules_reject_invalid_regex() {
+        assert!(RedactionRules::compile(&[], &["(".to_string()]).is_err());
+    }
+
+    #[test]
+    fn json_redaction_masks_sensitive_keys_and_nested_strings() {
+        let rules =
+            RedactionRules::compile(&["jellyfin_server_id".to_string()], &[]).expect("compile");
+        let mut value = serde_json::json!({
+            "api_key": "abc",
+            "jellyfin_server_id": 42,
+            "input": "/videos/a.mkv",
+            "nested": [{"url": "http://user:pw@host/x"}],
+            "token": null,
+        });
+
+        redact_json_value_with_rules(&mut value, Some(&rules));
+
+        assert_eq!(value["api_key"], REDACTION_PLACEHOLDER);
+        assert_eq!(value["jellyfin_server_id"], REDACTION_PLACEHOLDER);
+        assert_eq!(value["input"], "/videos/a.mkv");
+        assert_eq!(
+            value["nested"][0]["url"],
+            format!("http://{REDACTION_PLACEHOLDER}@host/x")
+        );
+        assert!(value["token"].is_null());
+    }
+
+    #[test]
+    fn log_dir_size_limit_removes_oldest_files_but_keeps_newest() {
+        let dir = tempdir().expect("tempdir");
+        let base = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+        for (index, name) in [
+            "videnoa.2026-01-01.log",
+            "videnoa.2026-01-02.log",
+            "videnoa.2026-01-03.log",
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let path = dir.path().join(name);
+            stdfs::write(&path, vec![b'x'; 100]).expect("write log file");
+            let file = stdfs::File::options()
+                .write(true)
+                .open(&path)
+                .expect("open log file");
+            file.set_modified(base + std::time::Duration::from_secs(index as u64 * 60))
+                .expect("set mtime");
+        }
+        stdfs::write(dir.path().join("unrelated.txt"), vec![b'x'; 1000]).expect("write");
+
+        let removed = enforce_log_dir_size_limit(dir.path(), 150).expect("enforce limit");
+        assert_eq!(
+            removed,
+            vec!["videnoa.2026-01-01.log", "videnoa.2026-01-02.log"]
+        );
+
+        let remaining = list_log_files(dir.path()).expect("list logs");
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].name, "videnoa.2026-01-03.log");
+        assert!(dir.path().join("unrelated.txt").exists());
+    }
+
+    #[test]
+    fn rotated_log_file_name_rejects_paths() {
+        assert!(is_rotated_log_file_name("videnoa.2026-01-01.log"));
+        assert!(!is_rotated_log_file_name("../videnoa.2026-01-01.log"));
+        assert!(!is_rotated_log_file_name("other.2026-01-01.log"));
+        assert!(!is_rotated_log_file_name("videnoa.2026-01-01.txt"));
+    }
+
     #[test]
     fn file_sink_wires_retention_override() {
         let data_dir = tempdir().expect("tempdir");
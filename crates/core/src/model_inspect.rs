@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use anyhow::{Context, Result};
@@ -42,6 +43,8 @@ pub struct ModelInspection {
     pub param_count: u64,
     /// Number of graph nodes (operations).
     pub op_count: usize,
+    /// Free-form `metadata_props` key/value pairs written by the exporter.
+    pub metadata: BTreeMap<String, String>,
 }
 
 /// Map ONNX `TensorProto.DataType` enum value to a human-readable string.
@@ -165,6 +168,12 @@ pub fn inspect_onnx_bytes(bytes: &[u8]) -> Result<ModelInspection> {
 
     let op_count = graph.node.len();
 
+    let metadata = model
+        .metadata_props
+        .into_iter()
+        .map(|entry| (entry.key, entry.value))
+        .collect();
+
     Ok(ModelInspection {
         ir_version: model.ir_version,
         opset_version,
@@ -178,6 +187,7 @@ pub fn inspect_onnx_bytes(bytes: &[u8]) -> Result<ModelInspection> {
         nodes,
         param_count,
         op_count,
+        metadata,
     })
 }
 
@@ -296,6 +306,10 @@ mod tests {
             model_version: 1,
             doc_string: "Test model".into(),
             graph: Some(graph),
+            metadata_props: vec![StringStringEntryProto {
+                key: "architecture".into(),
+                value: "ESRGAN".into(),
+            }],
         };
 
         model.encode_to_vec()
@@ -313,6 +327,10 @@ mod tests {
         assert_eq!(info.domain, "test.domain");
         assert_eq!(info.model_version, 1);
         assert_eq!(info.doc_string, "Test model");
+        assert_eq!(
+            info.metadata.get("architecture").map(String::as_str),
+            Some("ESRGAN")
+        );
 
         // 2 graph inputs (A and B)
         assert_eq!(info.inputs.len(), 2);
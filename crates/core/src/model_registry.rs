@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
@@ -6,7 +7,9 @@ use std::time::Duration;
 use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
+
+use crate::model_inspect::inspect_onnx;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum ModelType {
@@ -44,6 +47,122 @@ pub struct ModelEntry {
     /// Input format: "standard" (single RGB input), "concatenated" (single 7-ch input for RIFE v4.22+),
     /// or "three_input" (three separate tensors for RIFE v4.6/v4.7).
     pub input_format: String,
+    /// Architecture family, e.g. "ESRGAN", "Compact", "RIFE".
+    #[serde(default)]
+    pub architecture: Option<String>,
+    /// Content the model was trained on, e.g. "anime", "photo".
+    #[serde(default)]
+    pub training_domain: Option<String>,
+    /// SPDX license identifier.
+    #[serde(default)]
+    pub license: Option<String>,
+    /// Project page the model comes from.
+    #[serde(default)]
+    pub source_url: Option<String>,
+    /// Tile size that fits typical GPU memory; `None` means full-frame.
+    #[serde(default)]
+    pub recommended_tile_size: Option<u32>,
+}
+
+/// Metadata a user can declare for a discovered model, either in a sidecar
+/// `<stem>.json` next to the `.onnx` file or as ONNX `metadata_props` with the
+/// same keys (values as strings). Unset fields keep what discovery guessed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ModelMetadata {
+    pub name: Option<String>,
+    pub model_type: Option<ModelType>,
+    pub description: Option<String>,
+    pub scale: Option<u32>,
+    pub architecture: Option<String>,
+    pub training_domain: Option<String>,
+    pub license: Option<String>,
+    pub source_url: Option<String>,
+    pub recommended_tile_size: Option<u32>,
+    pub normalization_range: Option<(f32, f32)>,
+    pub pad_align: Option<u32>,
+    pub is_fp16: Option<bool>,
+    pub input_format: Option<String>,
+}
+
+impl ModelMetadata {
+    /// Reads the keys this struct knows from ONNX `metadata_props`. Other
+    /// keys are exporter-specific and ignored; unparsable values are skipped.
+    pub fn from_onnx_props(props: &BTreeMap<String, String>) -> Self {
+        let text = |key: &str| props.get(key).filter(|v| !v.is_empty()).cloned();
+        let number = |key: &str| props.get(key).and_then(|v| v.trim().parse().ok());
+        Self {
+            name: text("name"),
+            model_type: props.get("model_type").and_then(|v| match v.as_str() {
+                "SuperResolution" => Some(ModelType::SuperResolution),
+                "FrameInterpolation" => Some(ModelType::FrameInterpolation),
+                _ => None,
+            }),
+            description: text("description"),
+            scale: number("scale"),
+            architecture: text("architecture"),
+            training_domain: text("training_domain"),
+            license: text("license"),
+            source_url: text("source_url"),
+            recommended_tile_size: number("recommended_tile_size"),
+            normalization_range: props.get("normalization_range").and_then(|v| {
+                let (lo, hi) = v.split_once(',')?;
+                Some((lo.trim().parse().ok()?, hi.trim().parse().ok()?))
+            }),
+            pad_align: number("pad_align"),
+            is_fp16: props.get("is_fp16").and_then(|v| v.trim().parse().ok()),
+            input_format: text("input_format"),
+        }
+    }
+
+    fn apply(self, entry: &mut ModelEntry) {
+        fn set<T>(field: &mut T, value: Option<T>) {
+            if let Some(value) = value {
+                *field = value;
+            }
+        }
+        fn set_opt<T>(field: &mut Option<T>, value: Option<T>) {
+            if value.is_some() {
+                *field = value;
+            }
+        }
+        set(&mut entry.name, self.name);
+        set(&mut entry.model_type, self.model_type);
+        set(&mut entry.description, self.description);
+        set_opt(&mut entry.scale, self.scale);
+        set_opt(&mut entry.architecture, self.architecture);
+        set_opt(&mut entry.training_domain, self.training_domain);
+        set_opt(&mut entry.license, self.license);
+        set_opt(&mut entry.source_url, self.source_url);
+        set_opt(&mut entry.recommended_tile_size, self.recommended_tile_size);
+        set(&mut entry.normalization_range, self.normalization_range);
+        set(&mut entry.pad_align, self.pad_align);
+        set(&mut entry.is_fp16, self.is_fp16);
+        set(&mut entry.input_format, self.input_format);
+    }
+}
+
+/// Scale factor from names like `4x-UltraSharp`, `4xNomos8k` or
+/// `RealESRGAN_x4plus`.
+fn scale_from_filename(stem: &str) -> Option<u32> {
+    stem.split(|c: char| !c.is_ascii_alphanumeric())
+        .find_map(|token| {
+            let token = token.to_ascii_lowercase();
+            let digits_before_x = token
+                .split_once('x')
+                .filter(|(digits, _)| !digits.is_empty())
+                .map(|(digits, _)| digits);
+            let digits_after_x = token.strip_prefix('x').map(|rest| {
+                let end = rest
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(rest.len());
+                &rest[..end]
+            });
+            digits_before_x
+                .or(digits_after_x)
+                .and_then(|digits| digits.parse().ok())
+                .filter(|scale| (1..=16).contains(scale))
+        })
 }
 
 fn builtin_catalog() -> Vec<ModelEntry> {
@@ -62,6 +181,11 @@ fn builtin_catalog() -> Vec<ModelEntry> {
             description: "RealESRGAN x4 anime-optimized model (6-block variant, 17.9 MB)".into(),
             is_fp16: false,
             input_format: "standard".into(),
+            architecture: Some("ESRGAN".into()),
+            training_domain: Some("anime".into()),
+            license: Some("BSD-3-Clause".into()),
+            source_url: Some("https://github.com/xinntao/Real-ESRGAN".into()),
+            recommended_tile_size: Some(512),
         },
         ModelEntry {
             name: "AnimeJaNai_V3_L1_Sharp_HD_x2_FP16".into(),
@@ -77,6 +201,11 @@ fn builtin_catalog() -> Vec<ModelEntry> {
             description: "AnimeJaNai V3 L1 Sharp HD 2x FP16 — Compact architecture, optimized for anime".into(),
             is_fp16: true,
             input_format: "standard".into(),
+            architecture: Some("Compact".into()),
+            training_domain: Some("anime".into()),
+            license: Some("CC-BY-NC-SA-4.0".into()),
+            source_url: Some("https://github.com/the-database/mpv-upscale-2x_animejanai".into()),
+            recommended_tile_size: None,
         },
         ModelEntry {
             name: "RIFE_v4.26".into(),
@@ -92,12 +221,26 @@ fn builtin_catalog() -> Vec<ModelEntry> {
             description: "RIFE v4.26 frame interpolation — concatenated 7-channel input format".into(),
             is_fp16: false,
             input_format: "concatenated".into(),
+            architecture: Some("RIFE".into()),
+            training_domain: None,
+            license: Some("MIT".into()),
+            source_url: Some("https://github.com/hzwer/Practical-RIFE".into()),
+            recommended_tile_size: None,
         },
     ]
 }
 
+/// Catalog entry for a built-in model file, if `filename` is one.
+pub fn builtin_entry(filename: &str) -> Option<ModelEntry> {
+    builtin_catalog()
+        .into_iter()
+        .find(|entry| entry.filename == filename)
+}
+
 pub struct ModelRegistry {
     models_dir: PathBuf,
+    /// Searched after `models_dir`; downloads always go to `models_dir`.
+    extra_dirs: Vec<PathBuf>,
     entries: Vec<ModelEntry>,
 }
 
@@ -105,6 +248,7 @@ impl ModelRegistry {
     pub fn new(models_dir: PathBuf) -> Self {
         Self {
             models_dir,
+            extra_dirs: Vec::new(),
             entries: Vec::new(),
         }
     }
@@ -112,12 +256,39 @@ impl ModelRegistry {
     pub fn with_builtin_models(models_dir: PathBuf) -> Self {
         Self {
             models_dir,
+            extra_dirs: Vec::new(),
             entries: builtin_catalog(),
         }
     }
 
+    pub fn with_extra_dirs(mut self, extra_dirs: Vec<PathBuf>) -> Self {
+        self.extra_dirs = extra_dirs;
+        self
+    }
+
+    /// `models_dir` followed by the extra directories, in search order.
+    pub fn dirs(&self) -> impl Iterator<Item = &PathBuf> {
+        std::iter::once(&self.models_dir).chain(&self.extra_dirs)
+    }
+
+    /// Where `filename` is on disk, looking in [`Self::dirs`] order.
+    pub fn find_file(&self, filename: &str) -> Option<PathBuf> {
+        self.dirs()
+            .map(|dir| dir.join(filename))
+            .find(|path| path.is_file())
+    }
+
+    /// Scan every model directory for `.onnx` files. When two directories
+    /// hold the same filename, the first one wins.
     pub fn discover(&mut self) -> Result<()> {
-        let dir = &self.models_dir;
+        let dirs: Vec<PathBuf> = self.dirs().cloned().collect();
+        for dir in &dirs {
+            self.discover_dir(dir)?;
+        }
+        Ok(())
+    }
+
+    fn discover_dir(&mut self, dir: &Path) -> Result<()> {
         if !dir.exists() {
             return Ok(());
         }
@@ -163,13 +334,13 @@ impl ModelRegistry {
                 "standard".to_string()
             };
 
-            self.entries.push(ModelEntry {
+            let mut discovered = ModelEntry {
+                scale: scale_from_filename(&name),
                 name,
                 model_type: ModelType::SuperResolution,
                 filename,
                 url: None,
                 sha256: None,
-                scale: None,
                 input_names: Vec::new(),
                 output_names: Vec::new(),
                 normalization_range: (0.0, 1.0),
@@ -177,7 +348,37 @@ impl ModelRegistry {
                 description: "Discovered model (metadata unknown)".into(),
                 is_fp16,
                 input_format,
-            });
+                architecture: None,
+                training_domain: None,
+                license: None,
+                source_url: None,
+                recommended_tile_size: None,
+            };
+
+            // Embedded metadata first, so the sidecar can correct it.
+            match inspect_onnx(&path) {
+                Ok(inspection) => {
+                    discovered.input_names =
+                        inspection.inputs.iter().map(|t| t.name.clone()).collect();
+                    discovered.output_names =
+                        inspection.outputs.iter().map(|t| t.name.clone()).collect();
+                    ModelMetadata::from_onnx_props(&inspection.metadata).apply(&mut discovered);
+                }
+                Err(err) => {
+                    debug!(filename = %discovered.filename, error = %err, "Cannot read ONNX metadata")
+                }
+            }
+            let sidecar = path.with_extension("json");
+            if sidecar.is_file() {
+                match read_sidecar(&sidecar) {
+                    Ok(metadata) => metadata.apply(&mut discovered),
+                    Err(err) => {
+                        warn!(path = %sidecar.display(), error = %err, "Ignoring model sidecar")
+                    }
+                }
+            }
+
+            self.entries.push(discovered);
         }
 
         Ok(())
@@ -200,12 +401,15 @@ impl ModelRegistry {
 
     pub fn is_downloaded(&self, name: &str) -> bool {
         self.get(name)
-            .map(|e| self.models_dir.join(&e.filename).is_file())
-            .unwrap_or(false)
+            .is_some_and(|e| self.find_file(&e.filename).is_some())
     }
 
+    /// Where the model is, or where a download would put it.
     pub fn model_path(&self, name: &str) -> Option<PathBuf> {
-        self.get(name).map(|e| self.models_dir.join(&e.filename))
+        self.get(name).map(|e| {
+            self.find_file(&e.filename)
+                .unwrap_or_else(|| self.models_dir.join(&e.filename))
+        })
     }
 
     pub fn download(&self, name: &str) -> Result<PathBuf> {
@@ -218,78 +422,13 @@ impl ModelRegistry {
             .as_deref()
             .with_context(|| format!("No download URL for model: {name}"))?;
 
-        fs::create_dir_all(&self.models_dir).with_context(|| {
-            format!(
-                "Failed to create models directory: {}",
-                self.models_dir.display()
-            )
-        })?;
-
-        let final_path = self.models_dir.join(&entry.filename);
-        let tmp_path = self.models_dir.join(format!("{}.part", entry.filename));
-
-        info!(model = %name, url = %url, "Downloading model");
-
-        let client = reqwest::blocking::Client::builder()
-            .connect_timeout(Duration::from_secs(15))
-            .timeout(Duration::from_secs(30 * 60))
-            .build()
-            .context("Failed to build HTTP client for model download")?;
-
-        let mut response = client
-            .get(url)
-            .send()
-            .with_context(|| format!("Failed to start download for model {name}"))?;
-
-        if !response.status().is_success() {
-            let _ = fs::remove_file(&tmp_path);
-            bail!(
-                "Download request for model {name} returned HTTP {}",
-                response.status().as_u16()
-            );
-        }
-
-        let mut tmp_file = fs::File::create(&tmp_path)
-            .with_context(|| format!("Failed to create temp file: {}", tmp_path.display()))?;
-
-        if let Err(err) = response
-            .copy_to(&mut tmp_file)
-            .with_context(|| format!("Failed while downloading model {name} from {url}"))
-        {
-            let _ = fs::remove_file(&tmp_path);
-            return Err(err);
-        }
-
-        if let Err(err) = tmp_file
-            .sync_all()
-            .with_context(|| format!("Failed to flush temp file: {}", tmp_path.display()))
-        {
-            let _ = fs::remove_file(&tmp_path);
-            return Err(err);
-        }
-
-        if let Some(expected_hash) = &entry.sha256 {
-            info!(model = %name, "Verifying SHA256 hash");
-            let actual_hash = sha256_file(&tmp_path)?;
-            if actual_hash != *expected_hash {
-                let _ = fs::remove_file(&tmp_path);
-                bail!("SHA256 mismatch for {name}: expected {expected_hash}, got {actual_hash}");
-            }
-            info!(model = %name, "Hash verified OK");
-        } else {
-            warn!(model = %name, "No SHA256 hash configured — skipping verification");
-        }
-
-        fs::rename(&tmp_path, &final_path).with_context(|| {
-            format!(
-                "Failed to move {} → {}",
-                tmp_path.display(),
-                final_path.display()
-            )
-        })?;
-
-        info!(model = %name, path = %final_path.display(), "Download complete");
-        Ok(final_path)
+        download_model_file(
+            &self.models_dir,
+            name,
+            &entry.filename,
+            url,
+            entry.sha256.as_deref(),
+        )
     }
 
     pub fn to_json(&self) -> Result<String> {
@@ -308,7 +447,95 @@ impl ModelRegistry {
     }
 }
 
-fn sha256_file(path: &Path) -> Result<String> {
+/// Downloads `url` to `<models_dir>/<filename>` through a `.part` file,
+/// checking the SHA-256 when one is known.
+pub fn download_model_file(
+    models_dir: &Path,
+    name: &str,
+    filename: &str,
+    url: &str,
+    sha256: Option<&str>,
+) -> Result<PathBuf> {
+    fs::create_dir_all(models_dir).with_context(|| {
+        format!(
+            "Failed to create models directory: {}",
+            models_dir.display()
+        )
+    })?;
+
+    let final_path = models_dir.join(filename);
+    let tmp_path = models_dir.join(format!("{filename}.part"));
+
+    info!(model = %name, url = %url, "Downloading model");
+
+    let client = reqwest::blocking::Client::builder()
+        .connect_timeout(Duration::from_secs(15))
+        .timeout(Duration::from_secs(30 * 60))
+        .build()
+        .context("Failed to build HTTP client for model download")?;
+
+    let mut response = client
+        .get(url)
+        .send()
+        .with_context(|| format!("Failed to start download for model {name}"))?;
+
+    if !response.status().is_success() {
+        let _ = fs::remove_file(&tmp_path);
+        bail!(
+            "Download request for model {name} returned HTTP {}",
+            response.status().as_u16()
+        );
+    }
+
+    let mut tmp_file = fs::File::create(&tmp_path)
+        .with_context(|| format!("Failed to create temp file: {}", tmp_path.display()))?;
+
+    if let Err(err) = response
+        .copy_to(&mut tmp_file)
+        .with_context(|| format!("Failed while downloading model {name} from {url}"))
+    {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(err);
+    }
+
+    if let Err(err) = tmp_file
+        .sync_all()
+        .with_context(|| format!("Failed to flush temp file: {}", tmp_path.display()))
+    {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(err);
+    }
+
+    if let Some(expected_hash) = sha256 {
+        info!(model = %name, "Verifying SHA256 hash");
+        let actual_hash = sha256_file(&tmp_path)?;
+        if actual_hash != expected_hash {
+            let _ = fs::remove_file(&tmp_path);
+            bail!("SHA256 mismatch for {name}: expected {expected_hash}, got {actual_hash}");
+        }
+        info!(model = %name, "Hash verified OK");
+    } else {
+        warn!(model = %name, "No SHA256 hash configured — skipping verification");
+    }
+
+    fs::rename(&tmp_path, &final_path).with_context(|| {
+        format!(
+            "Failed to move {} → {}",
+            tmp_path.display(),
+            final_path.display()
+        )
+    })?;
+
+    info!(model = %name, path = %final_path.display(), "Download complete");
+    Ok(final_path)
+}
+
+fn read_sidecar(path: &Path) -> Result<ModelMetadata> {
+    let json = fs::read_to_string(path)?;
+    serde_json::from_str(&json).context("invalid model metadata JSON")
+}
+
+pub(crate) fn sha256_file(path: &Path) -> Result<String> {
     let mut file =
         fs::File::open(path).with_context(|| format!("Cannot open {}", path.display()))?;
     let mut hasher = Sha256::new();
@@ -484,6 +711,72 @@ mod tests {
         cleanup(&dir);
     }
 
+    #[test]
+    fn test_discover_applies_sidecar_metadata() {
+        let dir = tempdir();
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("4x-UltraSharp.onnx"), b"data").unwrap();
+        fs::write(dir.join("2x_Plain.onnx"), b"data").unwrap();
+        fs::write(
+            dir.join("2x_Plain.json"),
+            r#"{"architecture": "SPAN", "training_domain": "photo", "license": "CC0-1.0",
+                "source_url": "https://example.com/span", "recommended_tile_size": 256,
+                "normalization_range": [0.0, 255.0]}"#,
+        )
+        .unwrap();
+        let mut reg = ModelRegistry::new(dir.clone());
+        reg.discover().unwrap();
+
+        let sharp = reg.get("4x-UltraSharp").unwrap();
+        assert_eq!(sharp.scale, Some(4));
+        assert_eq!(sharp.architecture, None);
+
+        let plain = reg.get("2x_Plain").unwrap();
+        assert_eq!(plain.scale, Some(2));
+        assert_eq!(plain.architecture.as_deref(), Some("SPAN"));
+        assert_eq!(plain.training_domain.as_deref(), Some("photo"));
+        assert_eq!(plain.license.as_deref(), Some("CC0-1.0"));
+        assert_eq!(plain.recommended_tile_size, Some(256));
+        assert_eq!(plain.normalization_range, (0.0, 255.0));
+        cleanup(&dir);
+    }
+
+    #[test]
+    fn test_metadata_from_onnx_props() {
+        let props: BTreeMap<String, String> = [
+            ("scale", "2"),
+            ("architecture", "Compact"),
+            ("model_type", "FrameInterpolation"),
+            ("normalization_range", "0, 255"),
+            ("is_fp16", "true"),
+            ("recommended_tile_size", "big"),
+            ("onnx_exporter", "torch"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+        let metadata = ModelMetadata::from_onnx_props(&props);
+        assert_eq!(metadata.scale, Some(2));
+        assert_eq!(metadata.architecture.as_deref(), Some("Compact"));
+        assert_eq!(metadata.model_type, Some(ModelType::FrameInterpolation));
+        assert_eq!(metadata.normalization_range, Some((0.0, 255.0)));
+        assert_eq!(metadata.is_fp16, Some(true));
+        assert_eq!(metadata.recommended_tile_size, None);
+    }
+
+    #[test]
+    fn test_scale_from_filename() {
+        assert_eq!(scale_from_filename("4x-UltraSharp"), Some(4));
+        assert_eq!(scale_from_filename("4xNomos8k_span"), Some(4));
+        assert_eq!(scale_from_filename("RealESRGAN_x4plus_anime_6B"), Some(4));
+        assert_eq!(
+            scale_from_filename("the_database_AnimeJaNaiV3L1_sharp_HD_x2_fp16_op17"),
+            Some(2)
+        );
+        assert_eq!(scale_from_filename("rife_v4.26"), None);
+        assert_eq!(scale_from_filename("MyCustomModel"), None);
+    }
+
     #[test]
     fn test_discover_nonexistent_dir() {
         let dir = std::env::temp_dir().join("videnoa_test_nonexistent_dir_xyz");
@@ -492,6 +785,32 @@ mod tests {
         assert_eq!(reg.list().len(), 3);
     }
 
+    #[test]
+    fn test_discover_scans_extra_dirs() {
+        let dir = tempdir();
+        let extra = dir.join("extra");
+        fs::create_dir_all(&extra).unwrap();
+        fs::write(dir.join("2x_Shared.onnx"), b"data").unwrap();
+        fs::write(extra.join("2x_Shared.onnx"), b"other").unwrap();
+        fs::write(extra.join("4x_Extra.onnx"), b"data").unwrap();
+
+        let mut reg = ModelRegistry::new(dir.clone()).with_extra_dirs(vec![extra.clone()]);
+        reg.discover().unwrap();
+        assert_eq!(reg.list().len(), 2);
+        assert_eq!(
+            reg.model_path("2x_Shared"),
+            Some(dir.join("2x_Shared.onnx")),
+            "models_dir comes first"
+        );
+        assert_eq!(
+            reg.model_path("4x_Extra"),
+            Some(extra.join("4x_Extra.onnx"))
+        );
+        assert!(reg.is_downloaded("4x_Extra"));
+        assert!(reg.find_file("missing.onnx").is_none());
+        cleanup(&dir);
+    }
+
     #[test]
     fn test_discover_ignores_non_onnx() {
         let dir = tempdir();
@@ -0,0 +1,193 @@
+//! Live monitor of running jobs.
+//!
+//! The server wraps each local job's encoder in a [`MonitorSink`]. While
+//! anyone watches the job's [`JobMonitor`], the sink keeps a small JPEG of
+//! the frame being written, about once every [`MONITOR_INTERVAL`]. Nothing
+//! is written to disk, and a job nobody watches encodes no previews at all.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio::sync::watch;
+
+use crate::nodes::preview_tap::{FramePreview, FramePreviewer};
+use crate::streaming_executor::FrameSink;
+use crate::types::Frame;
+
+/// How often a watched job's monitor frame is refreshed.
+pub const MONITOR_INTERVAL: Duration = Duration::from_secs(1);
+/// Width monitor frames are scaled down to, in pixels.
+pub const MONITOR_MAX_WIDTH: u32 = 320;
+/// Boundary between the parts of an MJPEG stream.
+pub const MJPEG_BOUNDARY: &str = "videnoa-frame";
+
+/// Latest monitor frame of one job. Clones share the frame.
+#[derive(Clone)]
+pub struct JobMonitor {
+    frames: watch::Sender<Option<Arc<FramePreview>>>,
+}
+
+impl JobMonitor {
+    pub fn new() -> Self {
+        Self {
+            frames: watch::Sender::new(None),
+        }
+    }
+
+    /// Receives each new frame. The job keeps encoding frames while any
+    /// receiver is alive.
+    pub fn subscribe(&self) -> watch::Receiver<Option<Arc<FramePreview>>> {
+        self.frames.subscribe()
+    }
+
+    pub fn is_watched(&self) -> bool {
+        self.frames.receiver_count() > 0
+    }
+
+    pub fn latest(&self) -> Option<Arc<FramePreview>> {
+        self.frames.borrow().clone()
+    }
+
+    pub fn publish(&self, preview: FramePreview) {
+        self.frames.send_replace(Some(Arc::new(preview)));
+    }
+}
+
+impl Default for JobMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Passes frames on to `inner`, sampling them for a [`JobMonitor`].
+pub struct MonitorSink<S> {
+    inner: S,
+    monitor: JobMonitor,
+    previewer: FramePreviewer,
+}
+
+impl<S: FrameSink> MonitorSink<S> {
+    pub fn new(inner: S, monitor: JobMonitor) -> Self {
+        let mut previewer = FramePreviewer::new(MONITOR_INTERVAL, MONITOR_MAX_WIDTH);
+        let publisher = monitor.clone();
+        previewer.set_sink(Arc::new(move |preview| publisher.publish(preview)));
+        Self {
+            inner,
+            monitor,
+            previewer,
+        }
+    }
+}
+
+impl<S: FrameSink> FrameSink for MonitorSink<S> {
+    fn write_frame(&mut self, frame: &Frame) -> Result<()> {
+        if self.monitor.is_watched() {
+            // A frame that can't be previewed must not fail the job.
+            if let Err(err) = self.previewer.offer(frame) {
+                tracing::debug!(error = %err, "Skipped monitor frame");
+            }
+        }
+        self.inner.write_frame(frame)
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.inner.finish()
+    }
+}
+
+/// One part of a `multipart/x-mixed-replace` MJPEG stream.
+pub fn mjpeg_part(jpeg: &[u8]) -> Vec<u8> {
+    let mut part = format!(
+        "--{MJPEG_BOUNDARY}\r\nContent-Type: image/jpeg\r\nContent-Length: {}\r\n\r\n",
+        jpeg.len()
+    )
+    .into_bytes();
+    part.extend_from_slice(jpeg);
+    part.extend_from_slice(b"\r\n");
+    part
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct CountingSink {
+        frames: usize,
+        finished: bool,
+    }
+
+    impl FrameSink for CountingSink {
+        fn write_frame(&mut self, _frame: &Frame) -> Result<()> {
+            self.frames += 1;
+            Ok(())
+        }
+
+        fn finish(&mut self) -> Result<()> {
+            self.finished = true;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_monitor_sink_passes_frames_through() {
+        let monitor = JobMonitor::new();
+        let mut sink = MonitorSink::new(
+            CountingSink {
+                frames: 0,
+                finished: false,
+            },
+            monitor.clone(),
+        );
+        let frame = Frame::CpuRgb {
+            data: vec![0; 4 * 2 * 3],
+            width: 4,
+            height: 2,
+            bit_depth: 8,
+        };
+        sink.write_frame(&frame).unwrap();
+        assert!(!monitor.is_watched());
+
+        let receiver = monitor.subscribe();
+        assert!(monitor.is_watched());
+        // Broken frames are skipped by the monitor, not the encoder.
+        sink.write_frame(&Frame::CpuRgb {
+            data: vec![0; 5],
+            width: 4,
+            height: 2,
+            bit_depth: 8,
+        })
+        .unwrap();
+        sink.write_frame(&frame).unwrap();
+        sink.finish().unwrap();
+        assert_eq!(sink.inner.frames, 3);
+        assert!(sink.inner.finished);
+
+        drop(receiver);
+        assert!(!monitor.is_watched());
+    }
+
+    #[test]
+    fn test_monitor_publishes_latest_frame() {
+        let monitor = JobMonitor::new();
+        let mut receiver = monitor.subscribe();
+        assert!(monitor.latest().is_none());
+
+        monitor.publish(FramePreview {
+            frame_index: 12,
+            width: 2,
+            height: 1,
+            jpeg: vec![0xFF, 0xD8],
+        });
+        assert!(receiver.has_changed().unwrap());
+        let frame = receiver.borrow_and_update().clone().unwrap();
+        assert_eq!(frame.frame_index, 12);
+        assert_eq!(monitor.latest().unwrap().jpeg, vec![0xFF, 0xD8]);
+
+        let part = mjpeg_part(&frame.jpeg);
+        let header =
+            format!("--{MJPEG_BOUNDARY}\r\nContent-Type: image/jpeg\r\nContent-Length: 2\r\n\r\n");
+        assert!(part.starts_with(header.as_bytes()));
+        assert!(part.ends_with(&[0xFF, 0xD8, b'\r', b'\n']));
+    }
+}
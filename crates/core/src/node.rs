@@ -1,8 +1,13 @@
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use anyhow::Result;
 
+use crate::debug_event::{format_port_data_preview, NodeDebugValueEvent, PRINT_PREVIEW_MAX_CHARS};
+use crate::services::NodeServices;
+use crate::step::Stepper;
+use crate::trace::JobTrace;
 use crate::types::{Frame, PortData, PortType};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -13,12 +18,24 @@ pub struct PortDefinition {
     pub default_value: Option<serde_json::Value>,
 }
 
-#[derive(Default)]
+/// Receives debug events while a node is still running, e.g. download
+/// progress. A server job forwards them to the job's WebSocket.
+pub type NodeDebugSink = Arc<dyn Fn(NodeDebugValueEvent) + Send + Sync>;
+
+#[derive(Clone, Default)]
 pub struct ExecutionContext {
     pub total_frames: Option<u64>,
     pub current_frame: u64,
     pub executing_workflows: HashSet<PathBuf>,
     pub nesting_depth: u32,
+    /// Id of the node being executed, set by the executor.
+    pub node_id: String,
+    pub debug_sink: Option<NodeDebugSink>,
+    pub services: NodeServices,
+    /// Where executors record node start and end events, if anywhere.
+    pub trace: Option<JobTrace>,
+    /// Set in step mode; executors pause after each node.
+    pub stepper: Option<Stepper>,
 }
 
 impl ExecutionContext {
@@ -30,6 +47,44 @@ impl ExecutionContext {
 
         Some((self.current_frame as f32 / total as f32).clamp(0.0, 1.0))
     }
+
+    /// Send a status line for the running node to `debug_sink`, if any.
+    pub fn emit_debug(&self, node_type: &str, message: impl Into<String>) {
+        let Some(sink) = &self.debug_sink else {
+            return;
+        };
+        let (value_preview, truncated) =
+            format_port_data_preview(&PortData::Str(message.into()), PRINT_PREVIEW_MAX_CHARS);
+        sink(NodeDebugValueEvent {
+            node_id: self.node_id.clone(),
+            node_type: node_type.to_string(),
+            value_preview,
+            truncated,
+            preview_max_chars: PRINT_PREVIEW_MAX_CHARS,
+        });
+    }
+}
+
+/// What a node mostly waits on while it runs. The server gives each class
+/// its own concurrency limit, so a job only queues behind jobs that need
+/// the same resources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ResourceClass {
+    Cpu,
+    Gpu,
+    Network,
+    Disk,
+}
+
+impl ResourceClass {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Cpu => "cpu",
+            Self::Gpu => "gpu",
+            Self::Network => "network",
+            Self::Disk => "disk",
+        }
+    }
 }
 
 /// Core node trait that all nodes implement.
@@ -37,6 +92,18 @@ pub trait Node: Send + Sync {
     fn node_type(&self) -> &str;
     fn input_ports(&self) -> Vec<PortDefinition>;
     fn output_ports(&self) -> Vec<PortDefinition>;
+    /// Input ports that accept any number of connections. Each connection
+    /// carries an index, and the node receives their values as a
+    /// `PortData::List` in index order.
+    fn list_input_ports(&self) -> Vec<String> {
+        Vec::new()
+    }
+    /// The resource this node needs. Nodes that don't declare one count as
+    /// GPU work, so a plugin or new node never runs beside a GPU job by
+    /// accident.
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Gpu
+    }
     fn execute(
         &mut self,
         inputs: &HashMap<String, PortData>,
@@ -0,0 +1,240 @@
+//! Structured description of why a job failed.
+//!
+//! Executors wrap a node's error in a [`NodeFailure`] context naming the
+//! node. When the job fails, [`NodeError::from_error`] finds that context in
+//! the error chain and sorts the cause into a coarse [`ErrorCategory`] with a
+//! hint on what to check, so the UI can show more than the raw message.
+
+use std::fmt;
+use std::io::ErrorKind;
+
+use serde::{Deserialize, Serialize};
+
+use crate::graph::NodeInstance;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// Reading or writing files.
+    Io,
+    /// Loading or running a model.
+    Model,
+    /// Decoding, filtering or encoding with FFmpeg.
+    Encode,
+    /// Talking to another machine.
+    Network,
+    Other,
+}
+
+/// A failed job's error, attributed to the node it came from when known.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeError {
+    pub node_id: Option<String>,
+    pub node_type: Option<String>,
+    pub category: ErrorCategory,
+    /// The error without the "execution failed for node" prefix.
+    pub message: String,
+    pub hint: Option<String>,
+}
+
+/// Error context naming the node whose execution failed. Displays as the
+/// plain message the executors have always used.
+#[derive(Debug, Clone)]
+pub struct NodeFailure {
+    node_id: Option<String>,
+    node_type: String,
+    label: String,
+}
+
+impl NodeFailure {
+    /// `kind` describes the node's role, e.g. `node` or `source node`.
+    pub fn new(kind: &str, instance: &NodeInstance) -> Self {
+        Self {
+            node_id: Some(instance.id.clone()),
+            node_type: instance.node_type.clone(),
+            label: format!("execution failed for {kind} '{}'", instance.id),
+        }
+    }
+
+    /// A streaming stage, known only by its node type.
+    pub fn stage(kind: &str, node_type: &str) -> Self {
+        Self {
+            node_id: None,
+            node_type: node_type.to_string(),
+            label: format!("{kind} stage '{node_type}' failed"),
+        }
+    }
+}
+
+impl fmt::Display for NodeFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.label)
+    }
+}
+
+impl NodeError {
+    pub fn from_error(err: &anyhow::Error) -> Self {
+        let failure = err.downcast_ref::<NodeFailure>();
+        let mut chain = err.chain().map(ToString::to_string).collect::<Vec<_>>();
+        if let Some(failure) = failure {
+            if let Some(at) = chain.iter().position(|part| *part == failure.label) {
+                if at + 1 < chain.len() {
+                    chain.drain(..=at);
+                }
+            }
+        }
+        let message = chain.join(": ");
+
+        let io_kind = err
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<std::io::Error>())
+            .map(std::io::Error::kind);
+        let is_network = err
+            .chain()
+            .any(|cause| cause.downcast_ref::<reqwest::Error>().is_some());
+        let (category, hint) = classify(&message.to_lowercase(), io_kind, is_network);
+
+        Self {
+            node_id: failure.and_then(|failure| failure.node_id.clone()),
+            node_type: failure.map(|failure| failure.node_type.clone()),
+            category,
+            message,
+            hint: hint.map(str::to_string),
+        }
+    }
+}
+
+const MODEL_WORDS: [&str; 6] = ["onnx", "model", "tensorrt", "session", "cuda", "inference"];
+const ENCODE_WORDS: [&str; 7] = [
+    "ffmpeg", "encode", "decode", "nvenc", "codec", "muxing", "filter",
+];
+const NETWORK_WORDS: [&str; 5] = ["connect", "dns", "http", "tls", "download"];
+const IO_WORDS: [&str; 6] = [
+    "no such file",
+    "permission denied",
+    "not found",
+    "no space left",
+    "read-only file system",
+    "failed to create",
+];
+
+fn classify(
+    message: &str,
+    io_kind: Option<ErrorKind>,
+    is_network: bool,
+) -> (ErrorCategory, Option<&'static str>) {
+    let has = |words: &[&str]| words.iter().any(|word| message.contains(word));
+
+    if message.contains("timed out after") {
+        return (
+            ErrorCategory::Other,
+            Some("The node ran past its time limit. Raise its timeout_secs param or jobs.node_timeout_secs."),
+        );
+    }
+    if is_network || (io_kind.is_none() && has(&NETWORK_WORDS)) {
+        return (
+            ErrorCategory::Network,
+            Some("Check the URL and credentials, and that this machine can reach the host."),
+        );
+    }
+    match io_kind {
+        Some(ErrorKind::NotFound) => {
+            return (
+                ErrorCategory::Io,
+                Some("A file or folder does not exist. Check the paths the node reads and writes."),
+            )
+        }
+        Some(ErrorKind::PermissionDenied) => {
+            return (
+                ErrorCategory::Io,
+                Some("The server user may not access this path. Check its permissions."),
+            )
+        }
+        _ => {}
+    }
+    if has(&MODEL_WORDS) {
+        return (
+            ErrorCategory::Model,
+            Some("Check that the model file exists and fits the node. For TensorRT errors, try the CUDA backend or clear the engine cache."),
+        );
+    }
+    if has(&ENCODE_WORDS) {
+        return (
+            ErrorCategory::Encode,
+            Some("Check the codec and encoder settings. NVENC codecs need an NVIDIA GPU with a free encoder session."),
+        );
+    }
+    if io_kind.is_some() || has(&IO_WORDS) {
+        return (
+            ErrorCategory::Io,
+            Some("Check the paths the node reads and writes, and the free disk space."),
+        );
+    }
+    (ErrorCategory::Other, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use anyhow::Context;
+
+    use super::*;
+
+    fn instance(id: &str, node_type: &str) -> NodeInstance {
+        NodeInstance {
+            id: id.to_string(),
+            node_type: node_type.to_string(),
+            params: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_node_error_names_the_failed_node() {
+        let err = Err::<(), _>(std::io::Error::from(ErrorKind::NotFound))
+            .context("failed to open /media/in.mkv")
+            .with_context(|| NodeFailure::new("node", &instance("input", "VideoInput")))
+            .context("workflow failed")
+            .unwrap_err();
+        assert_eq!(
+            format!("{err:#}"),
+            "workflow failed: execution failed for node 'input': failed to open /media/in.mkv: entity not found"
+        );
+
+        let details = NodeError::from_error(&err);
+        assert_eq!(details.node_id.as_deref(), Some("input"));
+        assert_eq!(details.node_type.as_deref(), Some("VideoInput"));
+        assert_eq!(details.category, ErrorCategory::Io);
+        assert_eq!(
+            details.message,
+            "failed to open /media/in.mkv: entity not found"
+        );
+        assert!(details.hint.unwrap().contains("does not exist"));
+    }
+
+    #[test]
+    fn test_node_error_categories() {
+        let category = |message: &str| {
+            let err = Err::<(), _>(anyhow::anyhow!(message.to_string()))
+                .with_context(|| NodeFailure::stage("processor", "SuperResolution"))
+                .unwrap_err();
+            let details = NodeError::from_error(&err);
+            assert_eq!(details.node_id, None);
+            assert_eq!(details.node_type.as_deref(), Some("SuperResolution"));
+            details.category
+        };
+        assert_eq!(category("failed to load ONNX model"), ErrorCategory::Model);
+        assert_eq!(
+            category("ffmpeg exited with status 1"),
+            ErrorCategory::Encode
+        );
+        assert_eq!(category("connection refused"), ErrorCategory::Network);
+        assert_eq!(category("No space left on device"), ErrorCategory::Io);
+        assert_eq!(category("value out of range"), ErrorCategory::Other);
+
+        let plain = NodeError::from_error(&anyhow::anyhow!("chunk 3 failed"));
+        assert_eq!(plain.node_id, None);
+        assert_eq!(plain.node_type, None);
+        assert_eq!(plain.message, "chunk 3 failed");
+    }
+}
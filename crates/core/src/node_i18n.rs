@@ -0,0 +1,172 @@
+//! Bundled translations for node descriptors.
+//!
+//! Each supported locale has a flat JSON table under `locales/nodes/` that
+//! maps translation keys to text: `node.<node_type>.title`,
+//! `node.<node_type>.description` and `port.<name>`. English node names and
+//! descriptions live in the descriptors themselves; the English table only
+//! holds port labels. A missing entry falls back to English, then to the
+//! untranslated value.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::config::{normalize_supported_locale, FALLBACK_LOCALE};
+use crate::descriptor::NodeDescriptor;
+
+const BUNDLED_TABLES: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/nodes/en.json")),
+    ("zh-CN", include_str!("../locales/nodes/zh-CN.json")),
+];
+
+type Table = HashMap<String, String>;
+
+fn tables() -> &'static HashMap<&'static str, Table> {
+    static TABLES: OnceLock<HashMap<&'static str, Table>> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        BUNDLED_TABLES
+            .iter()
+            .map(|(locale, json)| {
+                let table = serde_json::from_str(json)
+                    .unwrap_or_else(|e| panic!("invalid node translations for {locale}: {e}"));
+                (*locale, table)
+            })
+            .collect()
+    })
+}
+
+/// Looks `key` up in the table of `locale` only.
+pub fn translate(locale: &str, key: &str) -> Option<&'static str> {
+    tables()
+        .get(locale)
+        .and_then(|table| table.get(key))
+        .map(String::as_str)
+}
+
+/// Fills in the translation keys of `descriptor` and replaces its display
+/// name, description and port labels with their `locale` translations.
+pub fn localize_descriptor(descriptor: &mut NodeDescriptor, locale: &str) {
+    descriptor.label_key = format!("node.{}.title", descriptor.node_type);
+    descriptor.description_key = format!("node.{}.description", descriptor.node_type);
+    if let Some(title) = translate(locale, &descriptor.label_key) {
+        descriptor.display_name = title.to_string();
+    }
+    if let Some(description) = translate(locale, &descriptor.description_key) {
+        descriptor.description = description.to_string();
+    }
+
+    for port in descriptor
+        .inputs
+        .iter_mut()
+        .chain(descriptor.outputs.iter_mut())
+    {
+        port.label_key = format!("port.{}", port.name);
+        port.label = translate(locale, &port.label_key)
+            .or_else(|| translate(FALLBACK_LOCALE, &port.label_key))
+            .unwrap_or(&port.name)
+            .to_string();
+    }
+}
+
+/// Picks the supported locale the client prefers most from an
+/// `Accept-Language` header, or `None` when it names none of them.
+pub fn negotiate_locale(accept_language: &str) -> Option<String> {
+    let mut ranges: Vec<(&str, f32)> = accept_language
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let tag = parts.next()?.trim();
+            let quality = parts
+                .find_map(|param| param.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+            (!tag.is_empty() && tag != "*" && quality > 0.0).then_some((tag, quality))
+        })
+        .collect();
+    // Stable, so equal weights keep the header order.
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    ranges.into_iter().find_map(|(tag, _)| {
+        let lower = tag.to_ascii_lowercase();
+        (lower.starts_with("en") || lower.starts_with("zh"))
+            .then(|| normalize_supported_locale(tag))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::descriptor::localized_node_descriptors;
+
+    #[test]
+    fn test_zh_cn_covers_builtin_descriptors() {
+        for descriptor in localized_node_descriptors("zh-CN") {
+            assert!(
+                translate("zh-CN", &descriptor.label_key).is_some(),
+                "missing {}",
+                descriptor.label_key
+            );
+            assert!(
+                translate("zh-CN", &descriptor.description_key).is_some(),
+                "missing {}",
+                descriptor.description_key
+            );
+            for port in descriptor.inputs.iter().chain(&descriptor.outputs) {
+                assert!(
+                    translate("zh-CN", &port.label_key).is_some(),
+                    "missing {}",
+                    port.label_key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_localize_descriptor_falls_back_to_english() {
+        let super_res = |locale| {
+            localized_node_descriptors(locale)
+                .into_iter()
+                .find(|d| d.node_type == "SuperResolution")
+                .unwrap()
+        };
+
+        let en = super_res("en");
+        assert_eq!(en.display_name, "Super Resolution");
+        assert_eq!(en.label_key, "node.SuperResolution.title");
+        let tile = en.inputs.iter().find(|p| p.name == "tile_size").unwrap();
+        assert_eq!(tile.label_key, "port.tile_size");
+        assert_eq!(tile.label, "Tile size");
+
+        let zh = super_res("zh-CN");
+        assert_eq!(zh.display_name, "超分辨率");
+        assert_eq!(zh.description, "使用超分辨率模型放大画面。");
+        let tile = zh.inputs.iter().find(|p| p.name == "tile_size").unwrap();
+        assert_eq!(tile.label, "分块大小");
+
+        let unknown = super_res("fr");
+        assert_eq!(unknown.display_name, "Super Resolution");
+        let fps = unknown
+            .inputs
+            .iter()
+            .find(|p| p.name == "auto_target_fps")
+            .unwrap();
+        assert_eq!(fps.label, "Auto target FPS");
+    }
+
+    #[test]
+    fn test_negotiate_locale_honours_quality_order() {
+        assert_eq!(
+            negotiate_locale("zh-CN,zh;q=0.9,en;q=0.8").as_deref(),
+            Some("zh-CN")
+        );
+        assert_eq!(
+            negotiate_locale("fr-FR, en-US;q=0.5, zh;q=0.7").as_deref(),
+            Some("zh-CN")
+        );
+        assert_eq!(negotiate_locale("de, en-GB").as_deref(), Some("en"));
+        assert_eq!(
+            negotiate_locale("zh-TW;q=0, en;q=0.1").as_deref(),
+            Some("en")
+        );
+        assert_eq!(negotiate_locale("fr, *"), None);
+        assert_eq!(negotiate_locale(""), None);
+    }
+}
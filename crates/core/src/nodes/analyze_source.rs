@@ -0,0 +1,775 @@
+//! Source analysis: samples frames from a video and estimates how much noise,
+//! banding and combing it carries, and whether it was upscaled from a lower
+//! native resolution.
+//!
+//! Every metric works on the luma plane of a handful of frames spread across
+//! the file. The numbers are heuristics meant for routing a workflow (pick a
+//! denoiser, skip upscaling, deinterlace first), not for grading a source.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use tracing::info;
+
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
+use crate::probe::{probe_media, VideoDetails};
+use crate::types::{PortData, PortType};
+
+pub const DEFAULT_SAMPLE_COUNT: i64 = 8;
+const MAX_SAMPLE_COUNT: i64 = 64;
+
+/// Heights a source is commonly produced at before being upscaled.
+pub const CANDIDATE_HEIGHTS: &[u32] = &[360, 480, 540, 576, 720, 810, 864, 900, 960, 1080, 1440];
+/// A candidate explains the frame when resizing through it loses at most this
+/// share of the detail lost at the lowest candidate.
+const NATIVE_ERROR_RATIO: f64 = 0.1;
+/// Frames whose lowest-candidate round trip differs by less than this many
+/// luma levels are too flat to say anything about resolution.
+const MIN_DETAIL_ERROR: f64 = 0.5;
+/// Estimated heights below this share of the real height count as upscaled.
+const UPSCALED_HEIGHT_RATIO: f64 = 0.9;
+
+/// Pixels with a gradient above this are edges and left out of the noise
+/// estimate.
+const NOISE_EDGE_THRESHOLD: i32 = 24;
+
+const BANDING_BLOCK: usize = 8;
+/// A block is flat when its luma range is at most this many levels.
+const BANDING_FLAT_RANGE: u8 = 1;
+/// Adjacent flat blocks whose means differ by this much form a band edge.
+const BANDING_STEP_MIN: f64 = 0.75;
+const BANDING_STEP_MAX: f64 = 4.0;
+/// Frames with less than this share of flat block pairs score zero banding.
+const BANDING_MIN_FLAT_SHARE: f64 = 0.05;
+
+/// Minimum luma difference between a row and both of its neighbours to count
+/// as a vertical edge for the combing metric.
+const COMB_EDGE_THRESHOLD: i32 = 12;
+/// Share of combed edge pixels above which a source is reported interlaced.
+const COMBING_THRESHOLD: f64 = 0.15;
+
+/// 8-bit luma plane of one sampled frame.
+pub struct LumaPlane {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<u8>,
+}
+
+impl LumaPlane {
+    fn at(&self, x: usize, y: usize) -> i32 {
+        i32::from(self.data[y * self.width + x])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SourceReport {
+    pub path: PathBuf,
+    pub width: u32,
+    pub height: u32,
+    pub sampled_frames: usize,
+    /// Estimated standard deviation of the noise, in 8-bit luma levels.
+    pub noise_level: f64,
+    /// 0–1 likelihood that smooth areas show visible banding.
+    pub banding: f64,
+    /// Share of vertical edges that alternate line by line.
+    pub combing: f64,
+    /// True when the stream is flagged interlaced or the frames are combed.
+    pub interlaced: bool,
+    /// Lowest height that still reproduces the frames' detail.
+    pub estimated_height: u32,
+    pub upscaled: bool,
+}
+
+pub struct AnalyzeSourceNode;
+
+impl AnalyzeSourceNode {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for AnalyzeSourceNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node for AnalyzeSourceNode {
+    fn node_type(&self) -> &str {
+        "AnalyzeSource"
+    }
+
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Disk
+    }
+
+    fn input_ports(&self) -> Vec<PortDefinition> {
+        vec![
+            PortDefinition {
+                name: "path".to_string(),
+                port_type: PortType::Path,
+                required: true,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "sample_count".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(DEFAULT_SAMPLE_COUNT)),
+            },
+        ]
+    }
+
+    fn output_ports(&self) -> Vec<PortDefinition> {
+        let output = |name: &str, port_type: PortType| PortDefinition {
+            name: name.to_string(),
+            port_type,
+            required: true,
+            default_value: None,
+        };
+        vec![
+            output("report", PortType::Json),
+            output("noise_level", PortType::Float),
+            output("banding", PortType::Float),
+            output("interlaced", PortType::Bool),
+            output("estimated_height", PortType::Int),
+            output("upscaled", PortType::Bool),
+        ]
+    }
+
+    fn execute(
+        &mut self,
+        inputs: &HashMap<String, PortData>,
+        _ctx: &ExecutionContext,
+    ) -> Result<HashMap<String, PortData>> {
+        let path = match inputs.get("path") {
+            Some(PortData::Path(p)) => p.clone(),
+            Some(PortData::Str(s)) => PathBuf::from(s),
+            _ => bail!("AnalyzeSource requires input port 'path' of type Path"),
+        };
+        let sample_count = match inputs.get("sample_count") {
+            Some(PortData::Int(n)) => *n,
+            Some(_) => bail!("AnalyzeSource input 'sample_count' must be Int"),
+            None => DEFAULT_SAMPLE_COUNT,
+        };
+        if !(1..=MAX_SAMPLE_COUNT).contains(&sample_count) {
+            bail!("AnalyzeSource sample_count must be between 1 and {MAX_SAMPLE_COUNT}");
+        }
+
+        let report = analyze_source(&path, sample_count as usize)?;
+        info!(
+            path = %path.display(),
+            noise = report.noise_level,
+            banding = report.banding,
+            interlaced = report.interlaced,
+            estimated_height = report.estimated_height,
+            "source analyzed"
+        );
+
+        let mut outputs = HashMap::new();
+        outputs.insert(
+            "report".to_string(),
+            PortData::Json(serde_json::to_value(&report)?),
+        );
+        outputs.insert(
+            "noise_level".to_string(),
+            PortData::Float(report.noise_level),
+        );
+        outputs.insert("banding".to_string(), PortData::Float(report.banding));
+        outputs.insert("interlaced".to_string(), PortData::Bool(report.interlaced));
+        outputs.insert(
+            "estimated_height".to_string(),
+            PortData::Int(i64::from(report.estimated_height)),
+        );
+        outputs.insert("upscaled".to_string(), PortData::Bool(report.upscaled));
+        Ok(outputs)
+    }
+}
+
+/// Probes `path`, decodes `sample_count` evenly spaced frames of its primary
+/// video stream and analyzes them.
+pub fn analyze_source(path: &Path, sample_count: usize) -> Result<SourceReport> {
+    let probe = probe_media(path)?;
+    let (index, video) = probe
+        .streams
+        .iter()
+        .find_map(|stream| {
+            stream
+                .video
+                .as_ref()
+                .filter(|video| !video.attached_pic)
+                .map(|video| (stream.index, video))
+        })
+        .with_context(|| format!("no video stream found in {}", path.display()))?;
+
+    let timestamps = sample_timestamps(probe.format.duration_secs, sample_count);
+    let mut planes = Vec::with_capacity(timestamps.len());
+    for timestamp in timestamps {
+        planes.push(extract_luma_plane(path, index, video, timestamp)?);
+    }
+    Ok(analyze_planes(path, video, &planes))
+}
+
+/// Midpoints of `count` equal slices of the file. Without a known duration
+/// only the first frame is sampled.
+fn sample_timestamps(duration_secs: Option<f64>, count: usize) -> Vec<f64> {
+    match duration_secs {
+        Some(duration) if duration > 0.0 => (0..count)
+            .map(|i| duration * (i as f64 + 0.5) / count as f64)
+            .collect(),
+        _ => vec![0.0],
+    }
+}
+
+fn extract_luma_plane(
+    path: &Path,
+    stream_index: usize,
+    video: &VideoDetails,
+    timestamp: f64,
+) -> Result<LumaPlane> {
+    let output = crate::runtime::command_for("ffmpeg")
+        .args(["-v", "error", "-ss", &format!("{timestamp:.3}"), "-i"])
+        .arg(path)
+        .args([
+            "-map",
+            &format!("0:{stream_index}"),
+            "-frames:v",
+            "1",
+            "-f",
+            "rawvideo",
+            "-pix_fmt",
+            "gray",
+            "-",
+        ])
+        .stdin(Stdio::null())
+        .output()
+        .context("failed to launch ffmpeg")?;
+    if !output.status.success() {
+        bail!(
+            "ffmpeg failed to decode a frame at {timestamp:.3}s from {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let (width, height) = (video.width as usize, video.height as usize);
+    if output.stdout.len() < width * height {
+        bail!(
+            "ffmpeg returned no frame at {timestamp:.3}s from {}",
+            path.display()
+        );
+    }
+    let mut data = output.stdout;
+    data.truncate(width * height);
+    Ok(LumaPlane {
+        width,
+        height,
+        data,
+    })
+}
+
+/// Combines per-frame metrics into a report. Noise uses the median so one
+/// grainy shot does not dominate; the estimated height uses the most detailed
+/// frame.
+pub fn analyze_planes(path: &Path, video: &VideoDetails, planes: &[LumaPlane]) -> SourceReport {
+    let mut noise: Vec<f64> = planes.iter().map(estimate_noise).collect();
+    noise.sort_by(f64::total_cmp);
+    let noise_level = noise.get(noise.len() / 2).copied().unwrap_or(0.0);
+    let banding = mean(planes.iter().map(banding_score));
+    let combing = mean(planes.iter().map(combing_score));
+    let estimated_height = planes
+        .iter()
+        .filter_map(|plane| estimate_native_height(plane, CANDIDATE_HEIGHTS))
+        .max()
+        .unwrap_or(video.height);
+
+    SourceReport {
+        path: path.to_path_buf(),
+        width: video.width,
+        height: video.height,
+        sampled_frames: planes.len(),
+        noise_level,
+        banding,
+        combing,
+        interlaced: video.interlaced || combing > COMBING_THRESHOLD,
+        estimated_height,
+        upscaled: f64::from(estimated_height) < f64::from(video.height) * UPSCALED_HEIGHT_RATIO,
+    }
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> f64 {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    if count == 0 {
+        0.0
+    } else {
+        sum / count as f64
+    }
+}
+
+/// Noise standard deviation using Immerkær's Laplacian-difference estimator,
+/// skipping edge pixels so texture is not mistaken for noise.
+pub fn estimate_noise(plane: &LumaPlane) -> f64 {
+    if plane.width < 3 || plane.height < 3 {
+        return 0.0;
+    }
+    let mut sum = 0.0;
+    let mut count = 0usize;
+    for y in 1..plane.height - 1 {
+        for x in 1..plane.width - 1 {
+            let gx = (plane.at(x + 1, y) - plane.at(x - 1, y)).abs();
+            let gy = (plane.at(x, y + 1) - plane.at(x, y - 1)).abs();
+            if gx + gy > NOISE_EDGE_THRESHOLD {
+                continue;
+            }
+            let laplacian = plane.at(x - 1, y - 1) - 2 * plane.at(x, y - 1)
+                + plane.at(x + 1, y - 1)
+                - 2 * plane.at(x - 1, y)
+                + 4 * plane.at(x, y)
+                - 2 * plane.at(x + 1, y)
+                + plane.at(x - 1, y + 1)
+                - 2 * plane.at(x, y + 1)
+                + plane.at(x + 1, y + 1);
+            sum += f64::from(laplacian.abs());
+            count += 1;
+        }
+    }
+    if count == 0 {
+        return 0.0;
+    }
+    (std::f64::consts::PI / 2.0).sqrt() * sum / (6.0 * count as f64)
+}
+
+/// Share of adjacent flat blocks separated by a small, hard luma step — the
+/// staircase left when a smooth gradient is quantized without dithering.
+pub fn banding_score(plane: &LumaPlane) -> f64 {
+    let cols = plane.width / BANDING_BLOCK;
+    let rows = plane.height / BANDING_BLOCK;
+    if cols < 2 || rows < 2 {
+        return 0.0;
+    }
+    let blocks: Vec<Option<f64>> = (0..rows)
+        .flat_map(|by| (0..cols).map(move |bx| (bx, by)))
+        .map(|(bx, by)| flat_block_mean(plane, bx, by))
+        .collect();
+
+    // Counted per direction: a horizontal gradient only steps between
+    // horizontal neighbours, and its vertical pairs would dilute the score.
+    let mut flat_pairs = [0usize; 2];
+    let mut steps = [0usize; 2];
+    for by in 0..rows {
+        for bx in 0..cols {
+            let Some(mean) = blocks[by * cols + bx] else {
+                continue;
+            };
+            let neighbours = [
+                (bx + 1 < cols).then(|| blocks[by * cols + bx + 1]),
+                (by + 1 < rows).then(|| blocks[(by + 1) * cols + bx]),
+            ];
+            for (direction, neighbour) in neighbours.into_iter().enumerate() {
+                let Some(Some(neighbour)) = neighbour else {
+                    continue;
+                };
+                flat_pairs[direction] += 1;
+                let diff = (mean - neighbour).abs();
+                if (BANDING_STEP_MIN..=BANDING_STEP_MAX).contains(&diff) {
+                    steps[direction] += 1;
+                }
+            }
+        }
+    }
+    let total_pairs = [(cols - 1) * rows, cols * (rows - 1)];
+    (0..2)
+        .filter(|&d| flat_pairs[d] as f64 >= total_pairs[d] as f64 * BANDING_MIN_FLAT_SHARE)
+        .map(|d| steps[d] as f64 / flat_pairs[d] as f64)
+        .fold(0.0, f64::max)
+}
+
+fn flat_block_mean(plane: &LumaPlane, bx: usize, by: usize) -> Option<f64> {
+    let mut min = u8::MAX;
+    let mut max = u8::MIN;
+    let mut sum = 0u32;
+    for y in by * BANDING_BLOCK..(by + 1) * BANDING_BLOCK {
+        let row = &plane.data[y * plane.width + bx * BANDING_BLOCK..][..BANDING_BLOCK];
+        for &v in row {
+            min = min.min(v);
+            max = max.max(v);
+            sum += u32::from(v);
+        }
+    }
+    (max - min <= BANDING_FLAT_RANGE)
+        .then(|| f64::from(sum) / (BANDING_BLOCK * BANDING_BLOCK) as f64)
+}
+
+/// Share of vertical edges where a line differs from both neighbours in the
+/// same direction while the neighbours agree — the comb pattern of two
+/// fields captured at different times.
+pub fn combing_score(plane: &LumaPlane) -> f64 {
+    if plane.height < 3 {
+        return 0.0;
+    }
+    let mut edges = 0usize;
+    let mut combed = 0usize;
+    for y in 1..plane.height - 1 {
+        for x in 0..plane.width {
+            let above = plane.at(x, y - 1);
+            let here = plane.at(x, y);
+            let below = plane.at(x, y + 1);
+            let up = here - above;
+            let down = here - below;
+            if up.abs().max(down.abs()) <= COMB_EDGE_THRESHOLD {
+                continue;
+            }
+            edges += 1;
+            let same_direction = up.signum() == down.signum();
+            if same_direction
+                && up.abs().min(down.abs()) > COMB_EDGE_THRESHOLD
+                && (above - below).abs() * 2 < up.abs().min(down.abs())
+            {
+                combed += 1;
+            }
+        }
+    }
+    if edges == 0 {
+        0.0
+    } else {
+        combed as f64 / edges as f64
+    }
+}
+
+/// Lowest of `candidates` the frame can be descaled to without losing
+/// detail. Returns `None` for frames too flat to judge and the frame's own
+/// height when no candidate fits.
+pub fn estimate_native_height(plane: &LumaPlane, candidates: &[u32]) -> Option<u32> {
+    let source: Vec<f32> = plane.data.iter().map(|&v| f32::from(v)).collect();
+    let errors: Vec<(u32, f64)> = candidates
+        .iter()
+        .copied()
+        .filter(|&h| (h as usize) < plane.height)
+        .map(|h| (h, descale_error(&source, plane.width, plane.height, h)))
+        .collect();
+    let &(_, lowest_error) = errors.first()?;
+    if lowest_error < MIN_DETAIL_ERROR {
+        return None;
+    }
+    Some(
+        errors
+            .iter()
+            .find(|(_, error)| *error <= lowest_error * NATIVE_ERROR_RATIO)
+            .map_or(plane.height as u32, |&(h, _)| h),
+    )
+}
+
+/// Mean absolute difference between the frame and its best bilinear
+/// reconstruction from `target_height` (keeping the aspect ratio).
+///
+/// Like the `descale` VapourSynth plugin, this inverts the upscale with least
+/// squares instead of a plain downscale, so a frame bilinearly upscaled from
+/// exactly that height comes back almost unchanged.
+fn descale_error(source: &[f32], width: usize, height: usize, target_height: u32) -> f64 {
+    let target_height = target_height as usize;
+    let target_width = ((width * target_height) as f64 / height as f64)
+        .round()
+        .max(1.0) as usize;
+
+    let horizontal = Descaler::new(target_width, width);
+    let mut rows = vec![0.0f32; target_width * height];
+    for y in 0..height {
+        let line = &source[y * width..(y + 1) * width];
+        rows[y * target_width..(y + 1) * target_width].copy_from_slice(&horizontal.solve(line));
+    }
+    let vertical = Descaler::new(target_height, height);
+    let mut down = vec![0.0f32; target_width * target_height];
+    let mut column = vec![0.0f32; height];
+    for x in 0..target_width {
+        for (y, value) in column.iter_mut().enumerate() {
+            *value = rows[y * target_width + x];
+        }
+        for (y, value) in vertical.solve(&column).into_iter().enumerate() {
+            down[y * target_width + x] = value;
+        }
+    }
+
+    let up = resize(&down, target_width, target_height, width, height);
+    let total: f64 = source
+        .iter()
+        .zip(&up)
+        .map(|(a, b)| f64::from((a - b).abs()))
+        .sum();
+    total / source.len() as f64
+}
+
+/// Least-squares inverse of a 1-D bilinear upscale from `low` to `high`
+/// samples, via a banded Cholesky factorization of the normal equations.
+struct Descaler {
+    taps: Vec<Vec<(usize, f32)>>,
+    /// Lower band of the Cholesky factor: `factor[j][d]` is `L[j][j - d]`.
+    factor: Vec<Vec<f64>>,
+    bandwidth: usize,
+}
+
+impl Descaler {
+    fn new(low: usize, high: usize) -> Self {
+        let taps = resize_weights(low, high);
+        let bandwidth = taps
+            .iter()
+            .map(|t| t.last().map_or(0, |l| l.0) - t.first().map_or(0, |f| f.0))
+            .max()
+            .unwrap_or(0);
+
+        // Normal matrix U^T U, stored as its lower band.
+        let mut normal = vec![vec![0.0f64; bandwidth + 1]; low];
+        for t in &taps {
+            for &(j, wj) in t {
+                for &(k, wk) in t {
+                    if k <= j {
+                        normal[j][j - k] += f64::from(wj) * f64::from(wk);
+                    }
+                }
+            }
+        }
+
+        let mut factor = vec![vec![0.0f64; bandwidth + 1]; low];
+        for j in 0..low {
+            for d in (0..=bandwidth.min(j)).rev() {
+                let k = j - d;
+                let mut sum = normal[j][d];
+                for m in 1..=bandwidth.min(k) {
+                    if d + m <= bandwidth {
+                        sum -= factor[j][d + m] * factor[k][m];
+                    }
+                }
+                factor[j][d] = if d == 0 {
+                    sum.max(1e-9).sqrt()
+                } else {
+                    sum / factor[k][0]
+                };
+            }
+        }
+        Self {
+            taps,
+            factor,
+            bandwidth,
+        }
+    }
+
+    fn solve(&self, high: &[f32]) -> Vec<f32> {
+        let low = self.factor.len();
+        let mut x = vec![0.0f64; low];
+        for (t, &value) in self.taps.iter().zip(high) {
+            for &(j, w) in t {
+                x[j] += f64::from(w) * f64::from(value);
+            }
+        }
+        // Forward substitution with L, then back substitution with L^T.
+        for j in 0..low {
+            for d in 1..=self.bandwidth.min(j) {
+                x[j] -= self.factor[j][d] * x[j - d];
+            }
+            x[j] /= self.factor[j][0];
+        }
+        for j in (0..low).rev() {
+            for d in 1..=self.bandwidth.min(low - 1 - j) {
+                x[j] -= self.factor[j + d][d] * x[j + d];
+            }
+            x[j] /= self.factor[j][0];
+        }
+        x.into_iter().map(|v| v as f32).collect()
+    }
+}
+
+/// Separable linear resize whose triangle filter widens when downscaling, so
+/// it averages instead of skipping pixels.
+pub(crate) fn resize(
+    src: &[f32],
+    src_w: usize,
+    src_h: usize,
+    dst_w: usize,
+    dst_h: usize,
+) -> Vec<f32> {
+    let horizontal = resize_weights(src_w, dst_w);
+    let mut rows = vec![0.0f32; dst_w * src_h];
+    for y in 0..src_h {
+        let line = &src[y * src_w..(y + 1) * src_w];
+        for (x, taps) in horizontal.iter().enumerate() {
+            rows[y * dst_w + x] = taps.iter().map(|&(i, w)| line[i] * w).sum();
+        }
+    }
+    let vertical = resize_weights(src_h, dst_h);
+    let mut out = vec![0.0f32; dst_w * dst_h];
+    for (y, taps) in vertical.iter().enumerate() {
+        for x in 0..dst_w {
+            out[y * dst_w + x] = taps.iter().map(|&(i, w)| rows[i * dst_w + x] * w).sum();
+        }
+    }
+    out
+}
+
+fn resize_weights(src_len: usize, dst_len: usize) -> Vec<Vec<(usize, f32)>> {
+    let scale = src_len as f64 / dst_len as f64;
+    let support = scale.max(1.0);
+    (0..dst_len)
+        .map(|i| {
+            let center = (i as f64 + 0.5) * scale - 0.5;
+            let first = (center - support).floor().max(0.0) as usize;
+            let last = ((center + support).ceil() as usize).min(src_len - 1);
+            let mut taps: Vec<(usize, f32)> = (first..=last)
+                .map(|j| (j, (1.0 - (j as f64 - center).abs() / support).max(0.0)))
+                .filter(|&(_, w)| w > 0.0)
+                .map(|(j, w)| (j, w as f32))
+                .collect();
+            let total: f32 = taps.iter().map(|&(_, w)| w).sum();
+            if total > 0.0 {
+                for tap in &mut taps {
+                    tap.1 /= total;
+                }
+            } else {
+                let nearest = center.round().clamp(0.0, (src_len - 1) as f64) as usize;
+                taps = vec![(nearest, 1.0)];
+            }
+            taps
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random luma values.
+    fn noise_values(len: usize, seed: u32) -> impl Iterator<Item = f32> {
+        let mut state = seed;
+        (0..len).map(move |_| {
+            state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (state >> 24) as f32
+        })
+    }
+
+    fn plane_from(width: usize, height: usize, values: &[f32]) -> LumaPlane {
+        LumaPlane {
+            width,
+            height,
+            data: values
+                .iter()
+                .map(|v| v.round().clamp(0.0, 255.0) as u8)
+                .collect(),
+        }
+    }
+
+    fn video(width: u32, height: u32, interlaced: bool) -> VideoDetails {
+        VideoDetails {
+            width,
+            height,
+            interlaced,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_noise_estimate_tracks_added_noise() {
+        let clean = plane_from(64, 64, &vec![128.0; 64 * 64]);
+        assert_eq!(estimate_noise(&clean), 0.0);
+
+        let noisy: Vec<f32> = noise_values(64 * 64, 7)
+            .map(|v| 128.0 + (v - 127.5) / 16.0)
+            .collect();
+        let light = estimate_noise(&plane_from(64, 64, &noisy));
+        let noisier: Vec<f32> = noise_values(64 * 64, 7)
+            .map(|v| 128.0 + (v - 127.5) / 8.0)
+            .collect();
+        let heavy = estimate_noise(&plane_from(64, 64, &noisier));
+        assert!(light > 1.0, "light noise estimate {light}");
+        assert!(heavy > light * 1.5, "heavy {heavy} vs light {light}");
+    }
+
+    #[test]
+    fn test_banding_score_flags_quantized_gradient() {
+        let (w, h) = (128, 64);
+        // One luma level every 16 columns: flat plateaus with hard steps.
+        let banded: Vec<f32> = (0..w * h).map(|i| 60.0 + ((i % w) / 16) as f32).collect();
+        let score = banding_score(&plane_from(w, h, &banded));
+        assert!(score > 0.3, "banded gradient scored {score}");
+
+        let flat = plane_from(w, h, &vec![60.0; w * h]);
+        assert_eq!(banding_score(&flat), 0.0);
+
+        let textured: Vec<f32> = noise_values(w * h, 3).collect();
+        assert_eq!(banding_score(&plane_from(w, h, &textured)), 0.0);
+    }
+
+    #[test]
+    fn test_combing_score_separates_fields() {
+        let (w, h) = (64, 64);
+        // Odd lines show an object shifted 8 pixels from the even lines.
+        let combed: Vec<f32> = (0..w * h)
+            .map(|i| {
+                let (x, y) = (i % w, i / w);
+                let shift = if y % 2 == 0 { 0 } else { 8 };
+                if (16 + shift..40 + shift).contains(&x) {
+                    200.0
+                } else {
+                    40.0
+                }
+            })
+            .collect();
+        assert!(combing_score(&plane_from(w, h, &combed)) > 0.5);
+
+        let progressive: Vec<f32> = (0..w * h)
+            .map(|i| {
+                if (16..48).contains(&(i / w)) {
+                    200.0
+                } else {
+                    40.0
+                }
+            })
+            .collect();
+        assert_eq!(combing_score(&plane_from(w, h, &progressive)), 0.0);
+    }
+
+    #[test]
+    fn test_estimate_native_height_detects_upscale() {
+        let candidates = [24, 48, 64];
+        let native: Vec<f32> = noise_values(96 * 96, 11).collect();
+        assert_eq!(
+            estimate_native_height(&plane_from(96, 96, &native), &candidates),
+            Some(96)
+        );
+
+        let small: Vec<f32> = noise_values(48 * 48, 11).collect();
+        let upscaled = resize(&small, 48, 48, 96, 96);
+        assert_eq!(
+            estimate_native_height(&plane_from(96, 96, &upscaled), &candidates),
+            Some(48)
+        );
+
+        let flat = plane_from(96, 96, &vec![90.0; 96 * 96]);
+        assert_eq!(estimate_native_height(&flat, &candidates), None);
+    }
+
+    #[test]
+    fn test_analyze_planes_builds_report() {
+        let small: Vec<f32> = noise_values(48 * 48, 5).collect();
+        let upscaled = resize(&small, 48, 48, 96, 96);
+        let planes = vec![plane_from(96, 96, &upscaled)];
+        let report = analyze_planes(Path::new("/videos/a.mkv"), &video(96, 96, true), &planes);
+        assert_eq!(report.sampled_frames, 1);
+        assert!(report.interlaced);
+        // The default candidates are all above this tiny frame.
+        assert_eq!(report.estimated_height, 96);
+        assert!(!report.upscaled);
+
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["height"], 96);
+        assert_eq!(json["interlaced"], true);
+    }
+
+    #[test]
+    fn test_sample_timestamps_spread_over_duration() {
+        assert_eq!(
+            sample_timestamps(Some(40.0), 4),
+            vec![5.0, 15.0, 25.0, 35.0]
+        );
+        assert_eq!(sample_timestamps(None, 4), vec![0.0]);
+    }
+}
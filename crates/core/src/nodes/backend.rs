@@ -15,11 +15,14 @@ use ort::{
 };
 use tracing::{debug, error, info, warn};
 
+use crate::job_phase::{report_phase, JobPhase};
+use crate::runtime::memory::{memory_governor, MemoryKind, MemoryReservation};
+
 /// Inference backend selection.
 ///
 /// Default is `Cuda`. `Tensorrt` requires TensorRT runtime libraries (`libnvinfer.so.10` or `nvinfer.dll`)
 /// to be installed; if unavailable, the session falls back to CUDA EP automatically.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub enum InferenceBackend {
     #[default]
     Cuda,
@@ -122,6 +125,7 @@ pub fn build_session(config: &SessionConfig<'_>) -> Result<Session> {
 
             let cache_path = cache_dir.to_string_lossy().to_string();
             let before = cache_stats(cache_dir);
+            report_phase(JobPhase::BuildingEngine);
             let started = Instant::now();
 
             debug!(
@@ -244,6 +248,20 @@ pub fn build_session(config: &SessionConfig<'_>) -> Result<Session> {
     Ok(session)
 }
 
+/// Weights plus workspace a loaded session holds, as a multiple of the model
+/// file size.
+const SESSION_VRAM_FACTOR: u64 = 3;
+
+/// Records the VRAM a session built from `model_path` is expected to hold
+/// with the memory governor. Keep the reservation alive as long as the
+/// session.
+pub fn reserve_session_memory(model_path: &Path) -> MemoryReservation {
+    let model_bytes = std::fs::metadata(model_path)
+        .map(|meta| meta.len())
+        .unwrap_or(0);
+    memory_governor().reserve(MemoryKind::Vram, model_bytes * SESSION_VRAM_FACTOR)
+}
+
 /// Format: `{compute_capability}_{model_hash}_{input_h}x{input_w}`
 pub fn trt_cache_key(
     compute_capability: &str,
@@ -7,7 +7,7 @@ use std::collections::HashMap;
 
 use anyhow::Result;
 
-use crate::node::{ExecutionContext, FrameProcessor, Node, PortDefinition};
+use crate::node::{ExecutionContext, FrameProcessor, Node, PortDefinition, ResourceClass};
 use crate::types::{Frame, PortData, PortType};
 
 #[derive(Debug, Clone)]
@@ -80,6 +80,10 @@ impl Node for ColorSpaceNode {
         "ColorSpace"
     }
 
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
     fn input_ports(&self) -> Vec<PortDefinition> {
         vec![
             PortDefinition {
@@ -0,0 +1,200 @@
+//! Before/after frame pairs saved next to a finished output.
+//!
+//! With `compare_every_mins` set on a `VideoOutput` node, the encoder grabs
+//! the source frame and the output frame at the same timestamp every that
+//! many minutes of content once the file is written, and saves both as PNG
+//! into `<output>.compare/`. The pairs are listed with the job's artifacts,
+//! so quality can be audited at several points without scrubbing two files.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Appended to the output path to name the directory holding its pairs.
+pub const COMPARISON_DIR_SUFFIX: &str = ".compare";
+/// Pairs saved per output at most; later timestamps are left out.
+pub const MAX_COMPARISON_PAIRS: usize = 100;
+
+const ORIGINAL_SUFFIX: &str = "-original.png";
+const PROCESSED_SUFFIX: &str = "-processed.png";
+
+/// One source frame and the output frame at the same point of the content.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComparisonFrame {
+    /// Timestamp of both frames, in seconds from the start.
+    pub time_secs: u64,
+    pub original: PathBuf,
+    pub processed: PathBuf,
+}
+
+/// Where the pairs of `output_path` are saved.
+pub fn comparison_dir_path(output_path: &Path) -> PathBuf {
+    let mut name = output_path.as_os_str().to_owned();
+    name.push(COMPARISON_DIR_SUFFIX);
+    PathBuf::from(name)
+}
+
+/// Timestamps to compare `duration_secs` of content at: every `every_secs`,
+/// or the middle of the content when it is shorter than that.
+pub fn comparison_times(duration_secs: f64, every_secs: u64) -> Vec<u64> {
+    if every_secs == 0 || !duration_secs.is_finite() || duration_secs < 1.0 {
+        return Vec::new();
+    }
+    let times = (1..)
+        .map(|step| step * every_secs)
+        .take_while(|time| (*time as f64) < duration_secs)
+        .take(MAX_COMPARISON_PAIRS)
+        .collect::<Vec<_>>();
+    if times.is_empty() {
+        return vec![(duration_secs / 2.0) as u64];
+    }
+    times
+}
+
+/// Extracts the frame at each of `times` from `source_path` and
+/// `output_path` into a fresh [`comparison_dir_path`].
+pub fn save_comparison_frames(
+    source_path: &Path,
+    output_path: &Path,
+    times: &[u64],
+) -> Result<Vec<ComparisonFrame>> {
+    let dir = comparison_dir_path(output_path);
+    if dir.exists() {
+        fs::remove_dir_all(&dir).with_context(|| format!("failed to clear {}", dir.display()))?;
+    }
+    fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+
+    times
+        .iter()
+        .map(|&time_secs| {
+            let stem = timestamp_name(time_secs);
+            let frame = ComparisonFrame {
+                time_secs,
+                original: dir.join(format!("{stem}{ORIGINAL_SUFFIX}")),
+                processed: dir.join(format!("{stem}{PROCESSED_SUFFIX}")),
+            };
+            extract_frame(source_path, time_secs, &frame.original)?;
+            extract_frame(output_path, time_secs, &frame.processed)?;
+            Ok(frame)
+        })
+        .collect()
+}
+
+/// The complete pairs saved for `output_path`, by timestamp.
+pub fn read_comparison_frames(output_path: &Path) -> Vec<ComparisonFrame> {
+    let dir = comparison_dir_path(output_path);
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut frames = entries
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            let stem = name.strip_suffix(ORIGINAL_SUFFIX)?;
+            let time_secs = parse_timestamp_name(stem)?;
+            let processed = dir.join(format!("{stem}{PROCESSED_SUFFIX}"));
+            processed.is_file().then(|| ComparisonFrame {
+                time_secs,
+                original: dir.join(&name),
+                processed,
+            })
+        })
+        .collect::<Vec<_>>();
+    frames.sort_by_key(|frame| frame.time_secs);
+    frames
+}
+
+/// `01h02m03s` for 3723 seconds.
+fn timestamp_name(time_secs: u64) -> String {
+    format!(
+        "{:02}h{:02}m{:02}s",
+        time_secs / 3600,
+        time_secs / 60 % 60,
+        time_secs % 60
+    )
+}
+
+fn parse_timestamp_name(name: &str) -> Option<u64> {
+    let (hours, rest) = name.split_once('h')?;
+    let (minutes, rest) = rest.split_once('m')?;
+    let seconds = rest.strip_suffix('s')?;
+    let (hours, minutes, seconds) = (
+        hours.parse::<u64>().ok()?,
+        minutes.parse::<u64>().ok()?,
+        seconds.parse::<u64>().ok()?,
+    );
+    Some(hours * 3600 + minutes * 60 + seconds)
+}
+
+fn extract_frame(video: &Path, time_secs: u64, image: &Path) -> Result<()> {
+    let result = crate::runtime::command_for("ffmpeg")
+        .args(["-hide_banner", "-nostdin", "-v", "error", "-y", "-ss"])
+        .arg(time_secs.to_string())
+        .arg("-i")
+        .arg(video)
+        .args(["-map", "0:v:0", "-frames:v", "1"])
+        .arg(image)
+        .stdin(Stdio::null())
+        .output()
+        .context("failed to launch ffmpeg to extract a comparison frame")?;
+    if !result.status.success() || !image.is_file() {
+        bail!(
+            "failed to extract the frame at {}s of {}: {}",
+            time_secs,
+            video.display(),
+            String::from_utf8_lossy(&result.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comparison_times() {
+        assert_eq!(comparison_times(1500.0, 600), vec![600, 1200]);
+        assert_eq!(comparison_times(1200.0, 600), vec![600]);
+        assert_eq!(comparison_times(90.0, 600), vec![45]);
+        assert!(comparison_times(0.5, 600).is_empty());
+        assert!(comparison_times(1500.0, 0).is_empty());
+        assert_eq!(comparison_times(1_000_000.0, 1).len(), MAX_COMPARISON_PAIRS);
+    }
+
+    #[test]
+    fn test_read_comparison_frames_pairs_files_by_timestamp() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("ep01.mkv");
+        assert!(read_comparison_frames(&output).is_empty());
+
+        let pairs = comparison_dir_path(&output);
+        assert_eq!(pairs, dir.path().join("ep01.mkv.compare"));
+        fs::create_dir_all(&pairs).unwrap();
+        for name in [
+            "01h02m03s-original.png",
+            "01h02m03s-processed.png",
+            "00h10m00s-original.png",
+            "00h10m00s-processed.png",
+            // Incomplete pair and stray files are skipped.
+            "00h20m00s-original.png",
+            "notes.txt",
+        ] {
+            fs::write(pairs.join(name), b"png").unwrap();
+        }
+
+        let frames = read_comparison_frames(&output);
+        assert_eq!(
+            frames
+                .iter()
+                .map(|frame| frame.time_secs)
+                .collect::<Vec<_>>(),
+            vec![600, 3723]
+        );
+        assert_eq!(frames[1].original, pairs.join("01h02m03s-original.png"));
+        assert_eq!(frames[1].processed, pairs.join("01h02m03s-processed.png"));
+        assert_eq!(timestamp_name(3723), "01h02m03s");
+    }
+}
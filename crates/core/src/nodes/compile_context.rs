@@ -7,17 +7,37 @@ use std::sync::Arc;
 
 use anyhow::{anyhow, bail, Context, Result};
 
+use crate::benchmark::{AutoModelSource, AutoRequest, AUTO_MODEL};
 use crate::compile::CompileContext;
-use crate::node::{ExecutionContext, FrameProcessor, Node, PortDefinition};
+use crate::monitor::{JobMonitor, MonitorSink};
+use crate::node::{ExecutionContext, FrameProcessor, Node, NodeDebugSink, PortDefinition};
+use crate::roi::{RoiConfig, RoiProcessor};
+use crate::services::NodeServices;
+use crate::step::Stepper;
 use crate::streaming_executor::{FrameInterpolator, FrameSink, PipelineStage};
+use crate::trace::JobTrace;
 use crate::types::{Frame, PortData};
 
+use crate::nodes::ffmpeg_filter::{FfmpegFilterNode, FFMPEG_FILTER_NODE_TYPE};
 use crate::nodes::frame_interpolation::{
     FrameInterpolationNode, FrameInterpolationPostprocess, ModelFormat,
 };
+use crate::nodes::grain_synthesis::{GrainSynthesisNode, GRAIN_SYNTHESIS_NODE_TYPE};
+use crate::nodes::model_inference::{ModelInferenceNode, MODEL_INFERENCE_NODE_TYPE};
+use crate::nodes::preview_tap::{FramePreviewSink, PreviewTapNode, PREVIEW_TAP_NODE_TYPE};
+use crate::nodes::python_node::{PythonNode, PYTHON_NODE_TYPE};
+use crate::nodes::region_enhance::{RegionEnhanceNode, REGION_ENHANCE_NODE_TYPE};
+use crate::nodes::stream_input::{probe_stream, LiveStreamConfig, LiveStreamDecoder};
+use crate::nodes::stream_output::{stream_encoder_config_from_inputs, StreamEncoder};
 use crate::nodes::super_res::{SuperResNode, SuperResPostprocess};
-use crate::nodes::video_input::{extract_metadata, run_ffprobe, VideoDecoder};
-use crate::nodes::video_output::{EncoderConfig, VideoEncoder};
+use crate::nodes::vapoursynth_input::{probe_script, VapourSynthConfig, VapourSynthDecoder};
+use crate::nodes::video_input::{
+    extract_metadata_for_stream, open_decoder, parse_segments, run_ffprobe, stream_index_input,
+    HwAccel, VideoStreamInfo,
+};
+use crate::nodes::video_output::{
+    output_strategy_from_inputs, EncoderConfig, OutputChecks, VideoEncoder,
+};
 
 pub struct VideoCompileContext {
     output_width: Cell<u32>,
@@ -28,10 +48,25 @@ pub struct VideoCompileContext {
     previous_node_type: RefCell<Option<String>>,
     accumulated_stages: RefCell<Vec<PipelineStage>>,
     source_path: RefCell<Option<PathBuf>>,
+    /// The encoder may copy audio, subtitles and attachments from
+    /// `source_path`. Off for live feeds, for partial decodes (whose streams
+    /// no longer line up with the video) and for scripts without an audio
+    /// source.
+    copy_source_streams: Cell<bool>,
+    /// Source audio stream chosen on `VideoInput`; `None` copies them all.
+    audio_stream: Cell<Option<usize>>,
     pending_superres_emit_tensor: RefCell<Option<Arc<AtomicBool>>>,
     previous_superres_fp16: Cell<bool>,
     pending_fi_emit_tensor: RefCell<Option<Arc<AtomicBool>>>,
     trt_cache_dir: PathBuf,
+    streams_dir: PathBuf,
+    auto_models: AutoModelSource,
+    debug_sink: Option<NodeDebugSink>,
+    services: NodeServices,
+    trace: Option<JobTrace>,
+    stepper: Option<Stepper>,
+    frame_preview_sink: Option<FramePreviewSink>,
+    monitor: Option<JobMonitor>,
 }
 
 impl VideoCompileContext {
@@ -45,16 +80,91 @@ impl VideoCompileContext {
             previous_node_type: RefCell::new(None),
             accumulated_stages: RefCell::new(Vec::new()),
             source_path: RefCell::new(None),
+            copy_source_streams: Cell::new(false),
+            audio_stream: Cell::new(None),
             pending_superres_emit_tensor: RefCell::new(None),
             previous_superres_fp16: Cell::new(false),
             pending_fi_emit_tensor: RefCell::new(None),
             trt_cache_dir,
+            streams_dir: PathBuf::from("streams"),
+            auto_models: AutoModelSource::default(),
+            debug_sink: None,
+            services: NodeServices::default(),
+            trace: None,
+            stepper: None,
+            frame_preview_sink: None,
+            monitor: None,
+        }
+    }
+
+    /// Directory HLS `StreamOutput` nodes write their playlists under.
+    pub fn with_streams_dir(mut self, streams_dir: PathBuf) -> Self {
+        self.streams_dir = streams_dir;
+        self
+    }
+
+    /// Models and benchmarks `SuperResolution` chooses from when its
+    /// `model_path` is `auto`.
+    pub fn with_auto_models(mut self, auto_models: AutoModelSource) -> Self {
+        self.auto_models = auto_models;
+        self
+    }
+
+    /// Receiver for debug events emitted by param nodes while they run.
+    pub fn with_debug_sink(mut self, debug_sink: NodeDebugSink) -> Self {
+        self.debug_sink = Some(debug_sink);
+        self
+    }
+
+    /// The job's services, handed to every node it runs.
+    pub fn with_services(mut self, services: NodeServices) -> Self {
+        self.services = services;
+        self
+    }
+
+    /// Record the nodes the job runs into `trace`.
+    pub fn with_trace(mut self, trace: JobTrace) -> Self {
+        self.trace = Some(trace);
+        self
+    }
+
+    /// Pause after each node the job runs.
+    pub fn with_stepper(mut self, stepper: Stepper) -> Self {
+        self.stepper = Some(stepper);
+        self
+    }
+
+    /// Receiver for the previews `PreviewTap` nodes publish.
+    pub fn with_frame_preview_sink(mut self, sink: FramePreviewSink) -> Self {
+        self.frame_preview_sink = Some(sink);
+        self
+    }
+
+    /// Sample the frames written to the job's output for `monitor`.
+    pub fn with_monitor(mut self, monitor: JobMonitor) -> Self {
+        self.monitor = Some(monitor);
+        self
+    }
+
+    fn monitored(&self, encoder: Box<dyn FrameSink>) -> Box<dyn FrameSink> {
+        match &self.monitor {
+            Some(monitor) => Box::new(MonitorSink::new(encoder, monitor.clone())),
+            None => encoder,
         }
     }
 
     fn create_superres_node(&self, inputs: &HashMap<String, PortData>) -> Result<SuperResNode> {
         let mut node = SuperResNode::new();
         node.set_trt_cache_dir(self.trt_cache_dir.clone());
+        if is_auto_model(inputs) {
+            let request =
+                AutoRequest::from_inputs(inputs, self.output_width.get(), self.output_height.get())
+                    .context("SuperResolution")?;
+            let model = self
+                .auto_models
+                .select(&request, Some(&self.trt_cache_dir))?;
+            node.set_model_override(model);
+        }
         node.execute(inputs, &ExecutionContext::default())
             .context("failed to initialize SuperResolution node")?;
         Ok(node)
@@ -79,6 +189,24 @@ impl VideoCompileContext {
         self.output_height
             .set(self.output_height.get().saturating_mul(scale));
 
+        if let Some(roi) = RoiConfig::from_inputs(inputs)? {
+            // Tiles are composited on RGB frames, so neither neighbour may
+            // hand over tensors.
+            self.pending_superres_emit_tensor.replace(None);
+            self.previous_superres_fp16.set(false);
+            self.pending_fi_emit_tensor.replace(None);
+            self.previous_node_type
+                .replace(Some("SuperResolution".to_string()));
+            let stage = SuperResSingleStage {
+                inner: node,
+                emit_tensor: Arc::new(AtomicBool::new(false)),
+            };
+            return Ok(vec![PipelineStage::Processor(Box::new(RoiProcessor::new(
+                Box::new(stage),
+                roi,
+            )))]);
+        }
+
         let fi_to_sr =
             should_enable_fi_to_sr_passthrough(self.previous_node_type.borrow().as_deref());
         if fi_to_sr {
@@ -93,7 +221,11 @@ impl VideoCompileContext {
         self.previous_superres_fp16.set(node.is_fp16());
         self.pending_fi_emit_tensor.replace(None);
 
-        if should_use_superres_micro_stages(node.is_fp16(), node.tile_size()) {
+        if should_use_superres_micro_stages(
+            node.is_fp16(),
+            node.tile_size(),
+            node.preprocess().is_identity(),
+        ) {
             let micro = node
                 .into_micro_stages()
                 .ok_or_else(|| anyhow!("failed to build SuperResolution micro-stages"))?;
@@ -184,6 +316,192 @@ impl VideoCompileContext {
         Ok(take_stages(&self.accumulated_stages))
     }
 
+    fn create_python_processor(
+        &self,
+        inputs: &HashMap<String, PortData>,
+    ) -> Result<Box<dyn FrameProcessor>> {
+        let mut node = PythonNode::new();
+        node.execute(inputs, &ExecutionContext::default())
+            .context("failed to start Python node")?;
+        let scale = node.scale();
+        self.output_width
+            .set(self.output_width.get().saturating_mul(scale));
+        self.output_height
+            .set(self.output_height.get().saturating_mul(scale));
+
+        // The script needs RGB frames, so neighbouring inference stages must
+        // not hand tensors across it.
+        self.pending_superres_emit_tensor.replace(None);
+        self.previous_superres_fp16.set(false);
+        self.pending_fi_emit_tensor.replace(None);
+        self.previous_node_type
+            .replace(Some(PYTHON_NODE_TYPE.to_string()));
+        Ok(Box::new(node))
+    }
+
+    fn create_ffmpeg_filter_processor(
+        &self,
+        inputs: &HashMap<String, PortData>,
+    ) -> Result<Box<dyn FrameProcessor>> {
+        let mut node = FfmpegFilterNode::new();
+        node.execute(inputs, &ExecutionContext::default())?;
+        let (width, height) = node.dry_run(
+            self.output_width.get(),
+            self.output_height.get(),
+            &self.output_fps_string(),
+        )?;
+        self.output_width.set(width);
+        self.output_height.set(height);
+
+        // ffmpeg works on RGB frames, same as the Python node.
+        self.pending_superres_emit_tensor.replace(None);
+        self.previous_superres_fp16.set(false);
+        self.pending_fi_emit_tensor.replace(None);
+        self.previous_node_type
+            .replace(Some(FFMPEG_FILTER_NODE_TYPE.to_string()));
+        Ok(Box::new(node))
+    }
+
+    fn create_grain_processor(
+        &self,
+        inputs: &HashMap<String, PortData>,
+    ) -> Result<Box<dyn FrameProcessor>> {
+        let mut node = GrainSynthesisNode::new();
+        node.execute(inputs, &ExecutionContext::default())?;
+
+        // Grain is added to RGB frames, so tensors cannot pass through it.
+        self.pending_superres_emit_tensor.replace(None);
+        self.previous_superres_fp16.set(false);
+        self.pending_fi_emit_tensor.replace(None);
+        self.previous_node_type
+            .replace(Some(GRAIN_SYNTHESIS_NODE_TYPE.to_string()));
+        Ok(Box::new(node))
+    }
+
+    fn create_preview_tap_processor(
+        &self,
+        inputs: &HashMap<String, PortData>,
+    ) -> Result<Box<dyn FrameProcessor>> {
+        let mut node = PreviewTapNode::new();
+        node.execute(inputs, &ExecutionContext::default())?;
+        if let Some(sink) = &self.frame_preview_sink {
+            node.set_sink(Arc::clone(sink));
+        }
+
+        // Previews are taken from RGB frames, so tensors cannot pass through.
+        self.pending_superres_emit_tensor.replace(None);
+        self.previous_superres_fp16.set(false);
+        self.pending_fi_emit_tensor.replace(None);
+        self.previous_node_type
+            .replace(Some(PREVIEW_TAP_NODE_TYPE.to_string()));
+        Ok(Box::new(node))
+    }
+
+    fn create_region_enhance_processor(
+        &self,
+        inputs: &HashMap<String, PortData>,
+    ) -> Result<Box<dyn FrameProcessor>> {
+        let mut node = RegionEnhanceNode::new();
+        node.set_trt_cache_dir(self.trt_cache_dir.clone());
+        node.execute(inputs, &ExecutionContext::default())
+            .context("failed to initialize RegionEnhance node")?;
+        let scale = node.scale();
+        self.output_width
+            .set(self.output_width.get().saturating_mul(scale));
+        self.output_height
+            .set(self.output_height.get().saturating_mul(scale));
+
+        // Compositing happens on RGB frames, so tensors cannot pass through.
+        self.pending_superres_emit_tensor.replace(None);
+        self.previous_superres_fp16.set(false);
+        self.pending_fi_emit_tensor.replace(None);
+        self.previous_node_type
+            .replace(Some(REGION_ENHANCE_NODE_TYPE.to_string()));
+        Ok(Box::new(node))
+    }
+
+    fn create_model_inference_processor(
+        &self,
+        inputs: &HashMap<String, PortData>,
+    ) -> Result<Box<dyn FrameProcessor>> {
+        let mut node = ModelInferenceNode::new();
+        node.set_trt_cache_dir(self.trt_cache_dir.clone());
+        node.execute(inputs, &ExecutionContext::default())
+            .context("failed to initialize ModelInference node")?;
+        let scale = node.scale();
+        self.output_width
+            .set(self.output_width.get().saturating_mul(scale));
+        self.output_height
+            .set(self.output_height.get().saturating_mul(scale));
+
+        // Generic models take and return RGB frames.
+        self.pending_superres_emit_tensor.replace(None);
+        self.previous_superres_fp16.set(false);
+        self.pending_fi_emit_tensor.replace(None);
+        self.previous_node_type
+            .replace(Some(MODEL_INFERENCE_NODE_TYPE.to_string()));
+        match RoiConfig::from_inputs(inputs)? {
+            Some(roi) => Ok(Box::new(RoiProcessor::new(Box::new(node), roi))),
+            None => Ok(Box::new(node)),
+        }
+    }
+
+    /// Reset the per-pipeline output shape to match a freshly opened source.
+    fn reset_for_source(&self, video_info: &VideoStreamInfo, total_frames: Option<u64>) {
+        let (fps_num, fps_den) = fps_to_rational(video_info.fps);
+        self.output_width.set(video_info.width);
+        self.output_height.set(video_info.height);
+        self.output_fps_num.set(fps_num);
+        self.output_fps_den.set(fps_den);
+        self.total_output_frames.set(total_frames);
+        self.previous_node_type.replace(None);
+        self.pending_superres_emit_tensor.replace(None);
+        self.previous_superres_fp16.set(false);
+        self.pending_fi_emit_tensor.replace(None);
+    }
+
+    /// Decoder for a `StreamInput` source: unbounded, reconnecting, and with
+    /// no local file for the encoder to copy other streams from.
+    fn create_live_decoder(
+        &self,
+        outputs: &HashMap<String, PortData>,
+    ) -> Result<LiveStreamDecoder> {
+        let config = LiveStreamConfig::from_outputs(outputs)?;
+        let video_info = probe_stream(&config.url, &config.input_args)
+            .context("failed to probe input stream")?;
+        let source = PathBuf::from(&config.url);
+        let decoder = LiveStreamDecoder::new(config, video_info.clone())?;
+
+        self.source_path.replace(Some(source));
+        self.copy_source_streams.set(false);
+        self.audio_stream.set(None);
+        self.reset_for_source(&video_info, None);
+
+        Ok(decoder)
+    }
+
+    /// Decoder for a `VapourSynthInput` source. Other streams are copied from
+    /// the node's `audio_source` when one is set.
+    fn create_vapoursynth_decoder(
+        &self,
+        outputs: &HashMap<String, PortData>,
+    ) -> Result<(VapourSynthDecoder, Option<u64>)> {
+        let config = VapourSynthConfig::from_outputs(outputs)?;
+        let info = probe_script(&config.script, &config.script_args)
+            .context("failed to probe VapourSynth script")?;
+        let decoder = VapourSynthDecoder::new(&config, &info)
+            .context("failed to create VapourSynth decoder")?;
+        let total_frames = Some(info.frames);
+
+        self.copy_source_streams.set(config.audio_source.is_some());
+        self.source_path
+            .replace(Some(config.audio_source.unwrap_or(config.script)));
+        self.audio_stream.set(None);
+        self.reset_for_source(&info.stream_info(), total_frames);
+
+        Ok((decoder, total_frames))
+    }
+
     fn output_fps_string(&self) -> String {
         let num = self.output_fps_num.get().max(1);
         let den = self.output_fps_den.get().max(1);
@@ -203,9 +521,17 @@ impl CompileContext for VideoCompileContext {
         node: &mut dyn Node,
         outputs: &HashMap<String, PortData>,
     ) -> Result<(Box<dyn Iterator<Item = Result<Frame>> + Send>, Option<u64>)> {
+        if node.node_type() == "stream_input" {
+            let decoder = self.create_live_decoder(outputs)?;
+            return Ok((Box::new(decoder), None));
+        }
+        if node.node_type() == "vapoursynth_input" {
+            let (decoder, total_frames) = self.create_vapoursynth_decoder(outputs)?;
+            return Ok((Box::new(decoder), total_frames));
+        }
         if node.node_type() != "video_input" && node.node_type() != "VideoInput" {
             bail!(
-                "expected VideoInput source node, got '{}'",
+                "expected VideoInput, StreamInput or VapourSynthInput source node, got '{}'",
                 node.node_type()
             );
         }
@@ -217,27 +543,42 @@ impl CompileContext for VideoCompileContext {
         };
 
         let probe = run_ffprobe(&source_path).context("failed to probe input video")?;
+        let video_stream = stream_index_input(outputs, "video_stream")?;
         let (video_info, _metadata) =
-            extract_metadata(&probe, &source_path).context("failed to parse input metadata")?;
+            extract_metadata_for_stream(&probe, &source_path, video_stream)
+                .context("failed to parse input metadata")?;
 
-        let (fps_num, fps_den) = fps_to_rational(video_info.fps);
-        let total_frames = estimate_total_frames(&source_path, video_info.fps);
+        let hwaccel = match outputs.get("hwaccel") {
+            Some(PortData::Str(value)) => HwAccel::parse(value).unwrap_or_default(),
+            _ => HwAccel::None,
+        };
+        let segments = match outputs.get("segments") {
+            Some(PortData::Str(value)) => parse_segments(value)?,
+            _ => Vec::new(),
+        };
 
-        let decoder = VideoDecoder::new(&source_path, &video_info, Some("none"))
+        let mut total_frames = estimate_total_frames(&source_path, video_info.fps);
+        if !segments.is_empty() {
+            tracing::info!(
+                segments = segments.len(),
+                "decoding part of the source; its audio and subtitles are not copied"
+            );
+            total_frames = segments
+                .iter()
+                .map(|range| range.frame_count(total_frames))
+                .sum();
+        }
+
+        let decoder = open_decoder(&source_path, &video_info, hwaccel, &segments)
             .context("failed to create video decoder")?;
 
         self.source_path.replace(Some(source_path));
-        self.output_width.set(video_info.width);
-        self.output_height.set(video_info.height);
-        self.output_fps_num.set(fps_num);
-        self.output_fps_den.set(fps_den);
-        self.total_output_frames.set(total_frames);
-        self.previous_node_type.replace(None);
-        self.pending_superres_emit_tensor.replace(None);
-        self.previous_superres_fp16.set(false);
-        self.pending_fi_emit_tensor.replace(None);
+        self.copy_source_streams.set(segments.is_empty());
+        self.audio_stream
+            .set(stream_index_input(outputs, "audio_stream")?);
+        self.reset_for_source(&video_info, total_frames);
 
-        Ok((Box::new(decoder), total_frames))
+        Ok((decoder, total_frames))
     }
 
     fn create_encoder(
@@ -245,8 +586,28 @@ impl CompileContext for VideoCompileContext {
         node: &mut dyn Node,
         outputs: &HashMap<String, PortData>,
     ) -> Result<Box<dyn FrameSink>> {
+        let width = self.output_width.get();
+        let height = self.output_height.get();
+        if node.node_type() == "stream_output" {
+            if width == 0 || height == 0 {
+                bail!("output resolution is not initialized");
+            }
+            let config = stream_encoder_config_from_inputs(
+                outputs,
+                width,
+                height,
+                &self.output_fps_string(),
+                8,
+                &self.streams_dir,
+            )?;
+            let encoder = StreamEncoder::new(&config).context("failed to create stream encoder")?;
+            return Ok(self.monitored(Box::new(encoder)));
+        }
         if node.node_type() != "video_output" && node.node_type() != "VideoOutput" {
-            bail!("expected VideoOutput sink node, got '{}'", node.node_type());
+            bail!(
+                "expected VideoOutput or StreamOutput sink node, got '{}'",
+                node.node_type()
+            );
         }
 
         let source_path = self
@@ -274,8 +635,6 @@ impl CompileContext for VideoCompileContext {
             _ => "yuv420p10le".to_string(),
         };
 
-        let width = self.output_width.get();
-        let height = self.output_height.get();
         if width == 0 || height == 0 {
             bail!("output resolution is not initialized");
         }
@@ -293,10 +652,14 @@ impl CompileContext for VideoCompileContext {
             cq_value: None,
             nvenc_preset: None,
             x265_preset: None,
+            copy_source_streams: self.copy_source_streams.get(),
+            audio_stream: self.audio_stream.get(),
+            output_strategy: output_strategy_from_inputs(outputs)?,
+            checks: OutputChecks::from_inputs(outputs)?,
         };
 
         let encoder = VideoEncoder::new(&config).context("failed to create video encoder")?;
-        Ok(Box::new(encoder))
+        Ok(self.monitored(Box::new(encoder)))
     }
 
     fn create_processor(
@@ -304,6 +667,24 @@ impl CompileContext for VideoCompileContext {
         node: Box<dyn Node>,
         inputs: &HashMap<String, PortData>,
     ) -> Result<Box<dyn FrameProcessor>> {
+        if node.node_type() == PYTHON_NODE_TYPE {
+            return self.create_python_processor(inputs);
+        }
+        if node.node_type() == FFMPEG_FILTER_NODE_TYPE {
+            return self.create_ffmpeg_filter_processor(inputs);
+        }
+        if node.node_type() == GRAIN_SYNTHESIS_NODE_TYPE {
+            return self.create_grain_processor(inputs);
+        }
+        if node.node_type() == PREVIEW_TAP_NODE_TYPE {
+            return self.create_preview_tap_processor(inputs);
+        }
+        if node.node_type() == REGION_ENHANCE_NODE_TYPE {
+            return self.create_region_enhance_processor(inputs);
+        }
+        if node.node_type() == MODEL_INFERENCE_NODE_TYPE {
+            return self.create_model_inference_processor(inputs);
+        }
         if node.node_type() != "SuperResolution" {
             bail!(
                 "unsupported processor node '{}' in VideoCompileContext",
@@ -395,6 +776,22 @@ impl CompileContext for VideoCompileContext {
         self.total_output_frames.get()
     }
 
+    fn debug_sink(&self) -> Option<NodeDebugSink> {
+        self.debug_sink.clone()
+    }
+
+    fn services(&self) -> NodeServices {
+        self.services.clone()
+    }
+
+    fn trace(&self) -> Option<JobTrace> {
+        self.trace.clone()
+    }
+
+    fn stepper(&self) -> Option<Stepper> {
+        self.stepper.clone()
+    }
+
     fn create_stages(
         &self,
         node: Box<dyn Node>,
@@ -575,6 +972,14 @@ impl FrameProcessor for FIPostprocessStage {
     }
 }
 
+fn is_auto_model(inputs: &HashMap<String, PortData>) -> bool {
+    match inputs.get("model_path") {
+        Some(PortData::Path(path)) => path == Path::new(AUTO_MODEL),
+        Some(PortData::Str(value)) => value == AUTO_MODEL,
+        _ => false,
+    }
+}
+
 fn read_positive_u32(inputs: &HashMap<String, PortData>, key: &str, default: u32) -> Result<u32> {
     match inputs.get(key) {
         Some(PortData::Int(value)) => {
@@ -588,8 +993,12 @@ fn read_positive_u32(inputs: &HashMap<String, PortData>, key: &str, default: u32
     }
 }
 
-fn should_use_superres_micro_stages(is_fp16_model: bool, tile_size: u32) -> bool {
-    is_fp16_model && tile_size == 0
+fn should_use_superres_micro_stages(
+    is_fp16_model: bool,
+    tile_size: u32,
+    identity_preprocess: bool,
+) -> bool {
+    is_fp16_model && tile_size == 0 && identity_preprocess
 }
 
 fn should_enable_sr_to_fi_passthrough(
@@ -702,7 +1111,7 @@ mod tests {
     use super::*;
 
     fn superres_stage_count(is_fp16_model: bool, tile_size: u32) -> usize {
-        if should_use_superres_micro_stages(is_fp16_model, tile_size) {
+        if should_use_superres_micro_stages(is_fp16_model, tile_size, true) {
             3
         } else {
             1
@@ -722,6 +1131,19 @@ mod tests {
         assert_eq!(superres_stage_count(false, 0), 1);
         assert_eq!(superres_stage_count(true, 64), 1);
         assert_eq!(superres_stage_count(true, 0), 3);
+        // Value-range or channel-order conversion runs in the single stage.
+        assert!(!should_use_superres_micro_stages(true, 0, false));
+    }
+
+    #[test]
+    fn test_is_auto_model() {
+        let path = |p: &str| HashMap::from([("model_path".to_string(), PortData::Path(p.into()))]);
+        assert!(is_auto_model(&path("auto")));
+        assert!(!is_auto_model(&path("models/auto.onnx")));
+        assert!(is_auto_model(&HashMap::from([(
+            "model_path".to_string(),
+            PortData::Str("auto".to_string()),
+        )])));
     }
 
     #[test]
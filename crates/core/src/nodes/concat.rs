@@ -0,0 +1,59 @@
+//! Concat node: joins several VideoFrames segments into one stream.
+//!
+//! Segments connect to the `segments` list port, and their connection
+//! indices set the order. The joining itself happens in `compile_graph`,
+//! which decodes the segments one after the other; this node only marks the
+//! join.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
+use crate::types::PortData;
+
+pub const CONCAT_NODE_TYPE: &str = "Concat";
+
+pub struct ConcatNode;
+
+impl ConcatNode {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for ConcatNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node for ConcatNode {
+    fn node_type(&self) -> &str {
+        CONCAT_NODE_TYPE
+    }
+
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
+    fn input_ports(&self) -> Vec<PortDefinition> {
+        vec![]
+    }
+
+    fn output_ports(&self) -> Vec<PortDefinition> {
+        vec![]
+    }
+
+    fn list_input_ports(&self) -> Vec<String> {
+        vec!["segments".to_string()]
+    }
+
+    fn execute(
+        &mut self,
+        _inputs: &HashMap<String, PortData>,
+        _ctx: &ExecutionContext,
+    ) -> Result<HashMap<String, PortData>> {
+        Ok(HashMap::new())
+    }
+}
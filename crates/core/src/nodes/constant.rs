@@ -8,7 +8,7 @@ use std::path::PathBuf;
 
 use anyhow::{anyhow, bail, Result};
 
-use crate::node::{ExecutionContext, Node, PortDefinition};
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
 use crate::types::{PortData, PortType};
 
 pub struct ConstantNode {
@@ -39,6 +39,10 @@ impl Node for ConstantNode {
         "Constant"
     }
 
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
     fn input_ports(&self) -> Vec<PortDefinition> {
         vec![
             PortDefinition {
@@ -155,6 +159,8 @@ fn port_data_kind(data: &PortData) -> &'static str {
         PortData::Bool(_) => "Bool",
         PortData::Path(_) => "Path",
         PortData::Metadata(_) => "Metadata",
+        PortData::Json(_) => "Json",
+        PortData::List(_) => "List",
     }
 }
 
@@ -0,0 +1,157 @@
+//! DateTime node: the time the node runs, formatted as a string.
+//!
+//! `format` takes chrono `strftime` specifiers, e.g. `%Y-%m-%d_%H-%M-%S`
+//! for a file-name-safe timestamp. The time is local unless `utc` is set.
+//! `unix` carries the same instant as seconds since the epoch.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Local, TimeZone, Utc};
+
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
+use crate::types::{PortData, PortType};
+
+pub const DATE_TIME_NODE_TYPE: &str = "DateTime";
+
+pub const DEFAULT_DATE_TIME_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+pub struct DateTimeNode;
+
+impl DateTimeNode {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for DateTimeNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node for DateTimeNode {
+    fn node_type(&self) -> &str {
+        DATE_TIME_NODE_TYPE
+    }
+
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
+    fn input_ports(&self) -> Vec<PortDefinition> {
+        vec![
+            PortDefinition {
+                name: "format".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!(DEFAULT_DATE_TIME_FORMAT)),
+            },
+            PortDefinition {
+                name: "utc".to_string(),
+                port_type: PortType::Bool,
+                required: false,
+                default_value: Some(serde_json::json!(false)),
+            },
+        ]
+    }
+
+    fn output_ports(&self) -> Vec<PortDefinition> {
+        vec![
+            PortDefinition {
+                name: "value".to_string(),
+                port_type: PortType::Str,
+                required: true,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "unix".to_string(),
+                port_type: PortType::Int,
+                required: true,
+                default_value: None,
+            },
+        ]
+    }
+
+    fn execute(
+        &mut self,
+        inputs: &HashMap<String, PortData>,
+        _ctx: &ExecutionContext,
+    ) -> Result<HashMap<String, PortData>> {
+        let format = match inputs.get("format") {
+            Some(PortData::Str(value)) => value.as_str(),
+            Some(_) => bail!("DateTime: input 'format' must be Str"),
+            None => DEFAULT_DATE_TIME_FORMAT,
+        };
+        let utc = match inputs.get("utc") {
+            Some(PortData::Bool(value)) => *value,
+            Some(_) => bail!("DateTime: input 'utc' must be Bool"),
+            None => false,
+        };
+
+        let now = Utc::now();
+        let value = if utc {
+            format_date_time(&now, format)?
+        } else {
+            format_date_time(&now.with_timezone(&Local), format)?
+        };
+
+        Ok(HashMap::from([
+            ("value".to_string(), PortData::Str(value)),
+            ("unix".to_string(), PortData::Int(now.timestamp())),
+        ]))
+    }
+}
+
+fn format_date_time<Tz: TimeZone>(time: &DateTime<Tz>, format: &str) -> Result<String>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let mut value = String::new();
+    if write!(value, "{}", time.format(format)).is_err() {
+        bail!("DateTime: invalid format '{format}'");
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_date_time() {
+        let time = Utc.with_ymd_and_hms(2024, 3, 9, 7, 5, 30).unwrap();
+        assert_eq!(
+            format_date_time(&time, DEFAULT_DATE_TIME_FORMAT).unwrap(),
+            "2024-03-09_07-05-30"
+        );
+        assert_eq!(format_date_time(&time, "%Y%m%d").unwrap(), "20240309");
+
+        let err = format_date_time(&time, "%Q").unwrap_err();
+        assert!(
+            err.to_string().contains("invalid format '%Q'"),
+            "got: {err}"
+        );
+    }
+
+    #[test]
+    fn test_date_time_node_outputs() {
+        let mut node = DateTimeNode::new();
+        let inputs = HashMap::from([
+            ("format".to_string(), PortData::Str("%Y".to_string())),
+            ("utc".to_string(), PortData::Bool(true)),
+        ]);
+        let before = Utc::now();
+        let outputs = node.execute(&inputs, &ExecutionContext::default()).unwrap();
+
+        let Some(PortData::Str(year)) = outputs.get("value") else {
+            panic!("value should be Str");
+        };
+        assert_eq!(year.len(), 4);
+        let Some(PortData::Int(unix)) = outputs.get("unix") else {
+            panic!("unix should be Int");
+        };
+        assert!(*unix >= before.timestamp());
+    }
+}
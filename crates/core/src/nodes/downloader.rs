@@ -1,23 +1,40 @@
+//! Downloader node: fetches a URL into the local download cache.
+//!
+//! Bodies are streamed into a `.part` file named after the URL. With
+//! `resume` on, a part left behind by a failed attempt or an earlier job is
+//! continued with a `Range` request. A non-empty `sha256` is checked before
+//! the part is moved into place, and `max_bytes_per_sec` caps the transfer
+//! rate. Progress is reported through [`ExecutionContext::emit_debug`].
+
 use std::collections::HashMap;
 use std::fs;
-use std::io::Write;
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, bail, Context, Result};
 use sha2::{Digest, Sha256};
 use tracing::debug;
 use url::Url;
 
-use crate::node::{ExecutionContext, Node, PortDefinition};
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
 use crate::types::{PortData, PortType};
 
 pub struct DownloaderNode;
 
-const DOWNLOAD_CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+pub const DOWNLOADER_NODE_TYPE: &str = "Downloader";
+
 const DOWNLOAD_REQUEST_TIMEOUT: Duration = Duration::from_secs(30 * 60);
 const DOWNLOAD_MAX_ATTEMPTS: usize = 3;
 const DOWNLOAD_RETRY_BACKOFF_MS: u64 = 250;
+const DOWNLOAD_CHUNK_SIZE: usize = 64 * 1024;
+const DOWNLOAD_PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+struct DownloadOptions {
+    expected_sha256: Option<String>,
+    max_bytes_per_sec: u64,
+    resume: bool,
+}
 
 impl DownloaderNode {
     pub fn new() -> Self {
@@ -36,13 +53,37 @@ impl Node for DownloaderNode {
         "downloader"
     }
 
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Network
+    }
+
     fn input_ports(&self) -> Vec<PortDefinition> {
-        vec![PortDefinition {
-            name: "url".to_string(),
-            port_type: PortType::Str,
-            required: true,
-            default_value: None,
-        }]
+        vec![
+            PortDefinition {
+                name: "url".to_string(),
+                port_type: PortType::Str,
+                required: true,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "sha256".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("")),
+            },
+            PortDefinition {
+                name: "max_bytes_per_sec".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(0)),
+            },
+            PortDefinition {
+                name: "resume".to_string(),
+                port_type: PortType::Bool,
+                required: false,
+                default_value: Some(serde_json::json!(true)),
+            },
+        ]
     }
 
     fn output_ports(&self) -> Vec<PortDefinition> {
@@ -57,17 +98,18 @@ impl Node for DownloaderNode {
     fn execute(
         &mut self,
         inputs: &HashMap<String, PortData>,
-        _ctx: &ExecutionContext,
+        ctx: &ExecutionContext,
     ) -> Result<HashMap<String, PortData>> {
         let url_raw = match inputs.get("url") {
             Some(PortData::Str(value)) => value,
             _ => bail!("missing or invalid 'url' input (expected Str)"),
         };
+        let options = download_options_from_inputs(inputs)?;
 
         let parsed_url = parse_http_url(url_raw)?;
         let redacted = redacted_url_for_display(&parsed_url);
         debug!(url = %redacted, "downloading URL to local path");
-        let final_path = download_to_file(&parsed_url, &redacted)?;
+        let final_path = download_to_file(&parsed_url, &redacted, &options, ctx)?;
 
         let mut outputs = HashMap::new();
         outputs.insert("path".to_string(), PortData::Path(final_path));
@@ -75,6 +117,39 @@ impl Node for DownloaderNode {
     }
 }
 
+fn download_options_from_inputs(inputs: &HashMap<String, PortData>) -> Result<DownloadOptions> {
+    let expected_sha256 = match inputs.get("sha256") {
+        Some(PortData::Str(value)) if value.trim().is_empty() => None,
+        Some(PortData::Str(value)) => {
+            let value = value.trim().to_ascii_lowercase();
+            if value.len() != 64 || !value.chars().all(|ch| ch.is_ascii_hexdigit()) {
+                bail!("Downloader: 'sha256' must be 64 hex digits");
+            }
+            Some(value)
+        }
+        Some(_) => bail!("Downloader: input 'sha256' must be Str"),
+        None => None,
+    };
+    let max_bytes_per_sec = match inputs.get("max_bytes_per_sec") {
+        Some(PortData::Int(value)) if *value >= 0 => *value as u64,
+        Some(PortData::Int(value)) => {
+            bail!("Downloader: max_bytes_per_sec must be >= 0, got {value}")
+        }
+        Some(_) => bail!("Downloader: input 'max_bytes_per_sec' must be Int"),
+        None => 0,
+    };
+    let resume = match inputs.get("resume") {
+        Some(PortData::Bool(value)) => *value,
+        Some(_) => bail!("Downloader: input 'resume' must be Bool"),
+        None => true,
+    };
+    Ok(DownloadOptions {
+        expected_sha256,
+        max_bytes_per_sec,
+        resume,
+    })
+}
+
 fn parse_http_url(raw: &str) -> Result<Url> {
     let parsed = Url::parse(raw).with_context(|| {
         format!(
@@ -94,15 +169,16 @@ fn parse_http_url(raw: &str) -> Result<Url> {
     }
 }
 
-fn download_to_file(url: &Url, redacted_url: &str) -> Result<PathBuf> {
-    let client = reqwest::blocking::Client::builder()
-        .connect_timeout(DOWNLOAD_CONNECT_TIMEOUT)
-        .timeout(DOWNLOAD_REQUEST_TIMEOUT)
-        .build()
-        .context("failed to build HTTP client for downloader")?;
+fn download_to_file(
+    url: &Url,
+    redacted_url: &str,
+    options: &DownloadOptions,
+    ctx: &ExecutionContext,
+) -> Result<PathBuf> {
+    let client = ctx.services.http_client()?;
 
     for attempt in 1..=DOWNLOAD_MAX_ATTEMPTS {
-        match download_once(&client, url, redacted_url) {
+        match download_once(client, url, redacted_url, options, ctx) {
             Ok(final_path) => return Ok(final_path),
             Err(attempt_error) => {
                 let DownloadAttemptError { retryable, error } = attempt_error;
@@ -117,7 +193,7 @@ fn download_to_file(url: &Url, redacted_url: &str) -> Result<PathBuf> {
                     );
 
                     let backoff_ms = DOWNLOAD_RETRY_BACKOFF_MS.saturating_mul(attempt as u64);
-                    std::thread::sleep(Duration::from_millis(backoff_ms));
+                    ctx.services.sleep(Duration::from_millis(backoff_ms))?;
                     continue;
                 }
 
@@ -146,8 +222,34 @@ fn download_once(
     client: &reqwest::blocking::Client,
     url: &Url,
     redacted_url: &str,
+    options: &DownloadOptions,
+    ctx: &ExecutionContext,
 ) -> std::result::Result<PathBuf, DownloadAttemptError> {
-    let mut response = client.get(url.as_str()).send().map_err(|err| {
+    let tmp_path = partial_path_for_url(url);
+    if let Some(parent_dir) = tmp_path.parent() {
+        fs::create_dir_all(parent_dir)
+            .with_context(|| {
+                format!(
+                    "failed to create downloader cache dir: {}",
+                    parent_dir.display()
+                )
+            })
+            .map_err(DownloadAttemptError::fatal)?;
+    }
+
+    let resume_from = if options.resume {
+        fs::metadata(&tmp_path).map(|meta| meta.len()).unwrap_or(0)
+    } else {
+        cleanup_file_if_exists(&tmp_path);
+        0
+    };
+
+    let mut request = client.get(url.as_str()).timeout(DOWNLOAD_REQUEST_TIMEOUT);
+    if resume_from > 0 {
+        debug!(url = %redacted_url, offset = resume_from, "resuming partial download");
+        request = request.header(reqwest::header::RANGE, format!("bytes={resume_from}-"));
+    }
+    let mut response = request.send().map_err(|err| {
         let wrapped = anyhow!("failed to start download from {redacted_url}");
         if is_retryable_reqwest_error(&err) {
             DownloadAttemptError::retryable(wrapped)
@@ -156,6 +258,14 @@ fn download_once(
         }
     })?;
 
+    if resume_from > 0 && response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        // The part no longer matches what the server has; start over.
+        cleanup_file_if_exists(&tmp_path);
+        return Err(DownloadAttemptError::retryable(anyhow!(
+            "server rejected resume offset {resume_from} for {redacted_url}"
+        )));
+    }
+
     if !response.status().is_success() {
         let status = response.status();
         let wrapped = anyhow!(
@@ -170,32 +280,48 @@ fn download_once(
         return Err(DownloadAttemptError::fatal(wrapped));
     }
 
-    let (final_path, tmp_path) =
-        destination_paths_for_url_and_headers(url, Some(response.headers()));
-    if let Some(parent_dir) = final_path.parent() {
-        fs::create_dir_all(parent_dir)
-            .with_context(|| {
-                format!(
-                    "failed to create downloader cache dir: {}",
-                    parent_dir.display()
-                )
-            })
-            .map_err(DownloadAttemptError::fatal)?;
+    let append = resume_from > 0 && response.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+    if append && content_range_start(response.headers()) != Some(resume_from) {
+        cleanup_file_if_exists(&tmp_path);
+        return Err(DownloadAttemptError::retryable(anyhow!(
+            "server answered resume of {redacted_url} with an unexpected range"
+        )));
     }
+    let offset = if append { resume_from } else { 0 };
 
-    cleanup_file_if_exists(&tmp_path);
+    let (final_path, _) = destination_paths_for_url_and_headers(url, Some(response.headers()));
 
-    let mut tmp_file = fs::File::create(&tmp_path)
+    let mut tmp_file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(&tmp_path)
         .with_context(|| format!("failed to create temp file: {}", tmp_path.display()))
         .map_err(DownloadAttemptError::fatal)?;
     let mut tmp_guard = TempFileCleanupGuard::new(&tmp_path);
+    if options.resume {
+        // Keep whatever arrived so the next attempt can pick up from it.
+        tmp_guard.disarm();
+    }
 
-    response.copy_to(&mut tmp_file).map_err(|err| {
-        let wrapped = anyhow!("failed while reading HTTP body from {redacted_url}");
-        if is_retryable_reqwest_error(&err) {
-            DownloadAttemptError::retryable(wrapped)
-        } else {
-            DownloadAttemptError::fatal(wrapped)
+    let total_len = response.content_length().map(|len| len + offset);
+    let mut progress = DownloadProgress::new(ctx, offset, total_len);
+    copy_body(
+        &mut response,
+        &mut tmp_file,
+        options.max_bytes_per_sec,
+        &mut progress,
+    )
+    .map_err(|err| match err {
+        CopyError::Read => DownloadAttemptError::retryable(anyhow!(
+            "failed while reading HTTP body from {redacted_url}"
+        )),
+        CopyError::Write(err) => DownloadAttemptError::fatal(
+            anyhow!(err).context(format!("failed to write temp file: {}", tmp_path.display())),
+        ),
+        CopyError::Cancelled => {
+            DownloadAttemptError::fatal(anyhow!("download from {redacted_url} was cancelled"))
         }
     })?;
 
@@ -211,6 +337,18 @@ fn download_once(
 
     drop(tmp_file);
 
+    if let Some(expected) = &options.expected_sha256 {
+        let actual = crate::model_registry::sha256_file(&tmp_path)
+            .with_context(|| format!("failed to hash {}", tmp_path.display()))
+            .map_err(DownloadAttemptError::fatal)?;
+        if &actual != expected {
+            cleanup_file_if_exists(&tmp_path);
+            return Err(DownloadAttemptError::fatal(anyhow!(
+                "sha256 mismatch for {redacted_url}: expected {expected}, got {actual}"
+            )));
+        }
+    }
+
     fs::rename(&tmp_path, &final_path)
         .with_context(|| {
             format!(
@@ -222,9 +360,108 @@ fn download_once(
         .map_err(DownloadAttemptError::fatal)?;
 
     tmp_guard.disarm();
+    progress.finish();
     Ok(final_path)
 }
 
+enum CopyError {
+    Read,
+    Write(std::io::Error),
+    Cancelled,
+}
+
+/// Stream `reader` into `writer` in chunks, sleeping as needed to stay under
+/// `max_bytes_per_sec` (0 = unlimited). Stops when the job is cancelled.
+fn copy_body(
+    reader: &mut impl Read,
+    writer: &mut impl Write,
+    max_bytes_per_sec: u64,
+    progress: &mut DownloadProgress<'_>,
+) -> std::result::Result<(), CopyError> {
+    let started = Instant::now();
+    let mut copied = 0_u64;
+    let mut buf = vec![0u8; DOWNLOAD_CHUNK_SIZE];
+    loop {
+        if progress.ctx.services.is_cancelled() {
+            return Err(CopyError::Cancelled);
+        }
+        let read_count = match reader.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(read_count) => read_count,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(_) => return Err(CopyError::Read),
+        };
+        writer
+            .write_all(&buf[..read_count])
+            .map_err(CopyError::Write)?;
+        copied += read_count as u64;
+        progress.advance(read_count as u64);
+
+        if max_bytes_per_sec > 0 {
+            let due = Duration::from_secs_f64(copied as f64 / max_bytes_per_sec as f64);
+            if let Some(wait) = due.checked_sub(started.elapsed()) {
+                if progress.ctx.services.sleep(wait).is_err() {
+                    return Err(CopyError::Cancelled);
+                }
+            }
+        }
+    }
+}
+
+struct DownloadProgress<'a> {
+    ctx: &'a ExecutionContext,
+    downloaded: u64,
+    total: Option<u64>,
+    last_report: Option<Instant>,
+}
+
+impl<'a> DownloadProgress<'a> {
+    fn new(ctx: &'a ExecutionContext, downloaded: u64, total: Option<u64>) -> Self {
+        Self {
+            ctx,
+            downloaded,
+            total,
+            last_report: None,
+        }
+    }
+
+    fn advance(&mut self, bytes: u64) {
+        self.downloaded += bytes;
+        if self
+            .last_report
+            .is_some_and(|at| at.elapsed() < DOWNLOAD_PROGRESS_INTERVAL)
+        {
+            return;
+        }
+        self.last_report = Some(Instant::now());
+        let message = match self.total {
+            Some(total) if total > 0 => format!(
+                "downloaded {} of {} bytes ({:.0}%)",
+                self.downloaded,
+                total,
+                self.downloaded as f64 * 100.0 / total as f64
+            ),
+            _ => format!("downloaded {} bytes", self.downloaded),
+        };
+        self.ctx.emit_debug(DOWNLOADER_NODE_TYPE, message);
+    }
+
+    fn finish(&self) {
+        self.ctx.emit_debug(
+            DOWNLOADER_NODE_TYPE,
+            format!("download complete ({} bytes)", self.downloaded),
+        );
+    }
+}
+
+/// Start offset of a `Content-Range: bytes START-END/TOTAL` header.
+fn content_range_start(headers: &reqwest::header::HeaderMap) -> Option<u64> {
+    let raw = headers.get(reqwest::header::CONTENT_RANGE)?.to_str().ok()?;
+    let range = raw.trim().strip_prefix("bytes ")?;
+    let (start, _) = range.split_once('-')?;
+    start.trim().parse().ok()
+}
+
 fn is_retryable_status(status: reqwest::StatusCode) -> bool {
     status.as_u16() == 408 || status.as_u16() == 429 || status.is_server_error()
 }
@@ -289,19 +526,19 @@ fn destination_paths_for_url_and_headers(
     let digest_hex = format!("{digest:x}");
     let filename = choose_download_filename(url, &digest_hex, response_headers);
 
-    let final_path = std::env::temp_dir()
-        .join("videnoa")
-        .join("downloads")
-        .join(filename);
-    let tmp_path = final_path.with_extension(format!(
-        "{}part",
-        final_path
-            .extension()
-            .map(|ext| format!("{}.", ext.to_string_lossy()))
-            .unwrap_or_default()
-    ));
-
-    (final_path, tmp_path)
+    let final_path = downloads_dir().join(filename);
+    (final_path, partial_path_for_url(url))
+}
+
+fn downloads_dir() -> PathBuf {
+    std::env::temp_dir().join("videnoa").join("downloads")
+}
+
+/// The in-progress file for `url`. It depends only on the URL, so a later
+/// attempt can find it before any response headers are known.
+fn partial_path_for_url(url: &Url) -> PathBuf {
+    let digest = Sha256::digest(url.as_str().as_bytes());
+    downloads_dir().join(format!("{digest:x}.part"))
 }
 
 fn choose_download_filename(
@@ -495,7 +732,7 @@ fn decode_rfc8187_filename(raw: &str) -> Option<String> {
     }
 }
 
-fn percent_decode(raw: &str) -> Option<Vec<u8>> {
+pub(crate) fn percent_decode(raw: &str) -> Option<Vec<u8>> {
     let bytes = raw.as_bytes();
     let mut out = Vec::with_capacity(bytes.len());
     let mut idx = 0usize;
@@ -683,7 +920,7 @@ mod tests {
         assert_eq!(node.node_type(), "downloader");
 
         let input_ports = node.input_ports();
-        assert_eq!(input_ports.len(), 1);
+        assert_eq!(input_ports.len(), 4);
         assert_eq!(input_ports[0].name, "url");
         assert_eq!(input_ports[0].port_type, PortType::Str);
         assert!(input_ports[0].required);
@@ -919,6 +1156,7 @@ mod tests {
         let mut node = DownloaderNode::new();
         let mut inputs = HashMap::new();
         inputs.insert("url".to_string(), PortData::Str(url.clone()));
+        inputs.insert("resume".to_string(), PortData::Bool(false));
 
         let err = node
             .execute(&inputs, &ExecutionContext::default())
@@ -1048,4 +1286,143 @@ mod tests {
             ".part file should be cleaned after retry exhaustion"
         );
     }
+
+    #[test]
+    fn test_execute_resumes_part_file_with_range_request() {
+        let id = unique_id();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+            let mut buffer = [0u8; 4096];
+            let read_count = stream.read(&mut buffer).unwrap();
+            let request = String::from_utf8_lossy(&buffer[..read_count]).to_ascii_lowercase();
+            assert!(request.contains("range: bytes=5-"), "request: {request}");
+            let body = b" world";
+            let headers = format!(
+                "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 5-10/11\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(headers.as_bytes()).unwrap();
+            stream.write_all(body).unwrap();
+        });
+        let url = format!("http://{addr}/resume/{id}.mkv");
+
+        cleanup_url_paths(&url);
+        let (_, tmp_path) = destination_paths_for_url_and_headers(&Url::parse(&url).unwrap(), None);
+        fs::create_dir_all(tmp_path.parent().unwrap()).unwrap();
+        fs::write(&tmp_path, b"hello").unwrap();
+
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let events_for_sink = Arc::clone(&events);
+        let ctx = ExecutionContext {
+            node_id: "dl".to_string(),
+            debug_sink: Some(Arc::new(move |event| {
+                events_for_sink.lock().unwrap().push(event);
+            })),
+            ..Default::default()
+        };
+        let inputs = HashMap::from([
+            ("url".to_string(), PortData::Str(url.clone())),
+            (
+                "sha256".to_string(),
+                PortData::Str(format!("{:x}", Sha256::digest(b"hello world"))),
+            ),
+        ]);
+
+        let outputs = DownloaderNode::new()
+            .execute(&inputs, &ctx)
+            .expect("resumed download should succeed");
+        server_handle.join().unwrap();
+
+        let Some(PortData::Path(output_path)) = outputs.get("path") else {
+            panic!("expected path Path output");
+        };
+        assert_eq!(fs::read(output_path).unwrap(), b"hello world");
+        assert!(!tmp_path.exists(), ".part file should be moved into place");
+
+        let events = events.lock().unwrap();
+        assert!(events
+            .iter()
+            .all(|event| event.node_id == "dl" && event.node_type == DOWNLOADER_NODE_TYPE));
+        assert!(events
+            .iter()
+            .any(|event| event.value_preview.contains("11 bytes (100%)")));
+        assert!(events
+            .iter()
+            .any(|event| event.value_preview.contains("download complete (11 bytes)")));
+
+        cleanup_url_paths(&url);
+    }
+
+    #[test]
+    fn test_execute_sha256_mismatch_fails_and_removes_part_file() {
+        let id = unique_id();
+        let (base_url, request_count, server_handle) =
+            spawn_sequence_server(vec![ServerResponse::Success(b"payload".to_vec())]);
+        let url = format!("{base_url}/checksum/{id}.mp4");
+
+        cleanup_url_paths(&url);
+
+        let inputs = HashMap::from([
+            ("url".to_string(), PortData::Str(url.clone())),
+            ("sha256".to_string(), PortData::Str("0".repeat(64))),
+        ]);
+        let err = DownloaderNode::new()
+            .execute(&inputs, &ExecutionContext::default())
+            .err()
+            .expect("checksum mismatch should fail");
+        server_handle.join().unwrap();
+
+        assert!(err.to_string().contains("sha256 mismatch"), "error: {err}");
+        assert_eq!(request_count.load(Ordering::SeqCst), 1);
+        let (final_path, tmp_path) =
+            destination_paths_for_url_and_headers(&Url::parse(&url).unwrap(), None);
+        assert!(!final_path.exists());
+        assert!(!tmp_path.exists());
+    }
+
+    #[test]
+    fn test_download_options_validate_inputs() {
+        let options = download_options_from_inputs(&HashMap::new()).unwrap();
+        assert!(options.resume);
+        assert_eq!(options.max_bytes_per_sec, 0);
+        assert!(options.expected_sha256.is_none());
+
+        let bad_hash = HashMap::from([("sha256".to_string(), PortData::Str("abc".to_string()))]);
+        assert!(download_options_from_inputs(&bad_hash).is_err());
+        let negative_rate = HashMap::from([("max_bytes_per_sec".to_string(), PortData::Int(-1))]);
+        assert!(download_options_from_inputs(&negative_rate).is_err());
+    }
+
+    #[test]
+    fn test_copy_body_respects_rate_limit() {
+        let ctx = ExecutionContext::default();
+        let mut progress = DownloadProgress::new(&ctx, 0, None);
+        let body = vec![7u8; 2048];
+        let mut out = Vec::new();
+        let started = Instant::now();
+        assert!(copy_body(&mut body.as_slice(), &mut out, 8192, &mut progress).is_ok());
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert_eq!(out, body);
+        assert_eq!(progress.downloaded, 2048);
+    }
+
+    #[test]
+    fn test_copy_body_stops_when_cancelled() {
+        let cancel = tokio_util::sync::CancellationToken::new();
+        let ctx = ExecutionContext {
+            services: crate::services::NodeServices::default().with_cancel_token(cancel.clone()),
+            ..Default::default()
+        };
+        cancel.cancel();
+        let mut progress = DownloadProgress::new(&ctx, 0, None);
+        let mut out = Vec::new();
+        assert!(matches!(
+            copy_body(&mut [7u8; 16].as_slice(), &mut out, 0, &mut progress),
+            Err(CopyError::Cancelled)
+        ));
+        assert!(out.is_empty());
+    }
 }
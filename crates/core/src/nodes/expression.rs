@@ -0,0 +1,295 @@
+//! `Expression` node: evaluates one arithmetic or logic expression.
+//!
+//! The expression sees the Float inputs `x` and `y`, the Int inputs `i` and
+//! `j`, and the Str inputs `s` and `t` (`()` when not connected), e.g.
+//! `clamp(i * x, 16, 3840)` for a scaled width. It is evaluated with the
+//! `Script` node's sandboxed Rhai engine, but only as an expression:
+//! statements, loops and function definitions are rejected. Mixing Int and
+//! Float yields Float; an Int output rounds it to the nearest integer.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Result};
+use rhai::{Dynamic, Scope, FLOAT, INT};
+
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
+use crate::nodes::script::sandboxed_engine;
+use crate::types::{PortData, PortType};
+
+pub const EXPRESSION_NODE_TYPE: &str = "Expression";
+
+const EXPRESSION_INPUTS: [(&str, PortType); 6] = [
+    ("x", PortType::Float),
+    ("y", PortType::Float),
+    ("i", PortType::Int),
+    ("j", PortType::Int),
+    ("s", PortType::Str),
+    ("t", PortType::Str),
+];
+
+pub struct ExpressionNode {
+    output_type: PortType,
+}
+
+impl ExpressionNode {
+    pub fn new() -> Self {
+        Self {
+            output_type: PortType::Float,
+        }
+    }
+
+    pub fn from_params(params: &HashMap<String, serde_json::Value>) -> Result<Self> {
+        let mut node = Self::new();
+        if let Some(value) = params.get("output_type") {
+            node.output_type = parse_output_type(value.as_str())?;
+        }
+        Ok(node)
+    }
+}
+
+impl Default for ExpressionNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node for ExpressionNode {
+    fn node_type(&self) -> &str {
+        EXPRESSION_NODE_TYPE
+    }
+
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
+    fn input_ports(&self) -> Vec<PortDefinition> {
+        let mut ports = vec![
+            PortDefinition {
+                name: "expression".to_string(),
+                port_type: PortType::Str,
+                required: true,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "output_type".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!(output_type_name(&self.output_type))),
+            },
+        ];
+        for (name, port_type) in EXPRESSION_INPUTS {
+            ports.push(PortDefinition {
+                name: name.to_string(),
+                port_type,
+                required: false,
+                default_value: None,
+            });
+        }
+        ports
+    }
+
+    fn output_ports(&self) -> Vec<PortDefinition> {
+        vec![PortDefinition {
+            name: "value".to_string(),
+            port_type: self.output_type.clone(),
+            required: true,
+            default_value: None,
+        }]
+    }
+
+    fn execute(
+        &mut self,
+        inputs: &HashMap<String, PortData>,
+        _ctx: &ExecutionContext,
+    ) -> Result<HashMap<String, PortData>> {
+        let expression = match inputs.get("expression") {
+            Some(PortData::Str(value)) => value.as_str(),
+            Some(_) => bail!("Expression: input 'expression' must be Str"),
+            None => bail!("Expression: input 'expression' is required"),
+        };
+        if let Some(PortData::Str(value)) = inputs.get("output_type") {
+            self.output_type = parse_output_type(Some(value))?;
+        }
+
+        let mut scope = Scope::new();
+        for (name, _) in EXPRESSION_INPUTS {
+            let value = match inputs.get(name) {
+                None => Dynamic::UNIT,
+                Some(PortData::Int(value)) => Dynamic::from_int(*value),
+                Some(PortData::Float(value)) => Dynamic::from_float(*value),
+                Some(PortData::Str(value)) => Dynamic::from(value.clone()),
+                Some(_) => bail!("Expression: input '{name}' must be Int, Float or Str"),
+            };
+            scope.push_dynamic(name, value);
+        }
+
+        let mut engine = sandboxed_engine();
+        engine.register_fn("clamp", |value: INT, min: INT, max: INT| {
+            value.clamp(min, max.max(min))
+        });
+        engine.register_fn("clamp", |value: FLOAT, min: FLOAT, max: FLOAT| {
+            value.clamp(min, max.max(min))
+        });
+        engine.register_fn("clamp", |value: FLOAT, min: INT, max: INT| {
+            value.clamp(min as FLOAT, max.max(min) as FLOAT)
+        });
+        let result = engine
+            .eval_expression_with_scope::<Dynamic>(&mut scope, expression)
+            .map_err(|err| anyhow!("Expression: {err}"))?;
+        let value = dynamic_to_output(result, &self.output_type)?;
+
+        Ok(HashMap::from([("value".to_string(), value)]))
+    }
+}
+
+fn dynamic_to_output(value: Dynamic, output_type: &PortType) -> Result<PortData> {
+    let type_name = value.type_name();
+    let mismatch = || {
+        anyhow!(
+            "Expression: expected {} result, got {type_name}",
+            output_type_name(output_type)
+        )
+    };
+
+    match output_type {
+        PortType::Int => {
+            if let Ok(value) = value.as_int() {
+                return Ok(PortData::Int(value));
+            }
+            let value = value.as_float().map_err(|_| mismatch())?;
+            if !value.is_finite() || value.abs() >= i64::MAX as f64 {
+                bail!("Expression: result {value} does not fit an Int");
+            }
+            Ok(PortData::Int(value.round() as i64))
+        }
+        PortType::Float => value
+            .as_float()
+            .or_else(|_| value.as_int().map(|v| v as f64))
+            .map(PortData::Float)
+            .map_err(|_| mismatch()),
+        PortType::Bool => value.as_bool().map(PortData::Bool).map_err(|_| mismatch()),
+        _ => {
+            if value.is_unit() {
+                Err(mismatch())
+            } else if value.is_string() {
+                value
+                    .into_string()
+                    .map(PortData::Str)
+                    .map_err(|_| mismatch())
+            } else {
+                Ok(PortData::Str(value.to_string()))
+            }
+        }
+    }
+}
+
+fn parse_output_type(value: Option<&str>) -> Result<PortType> {
+    match value {
+        Some("Int") => Ok(PortType::Int),
+        Some("Float") => Ok(PortType::Float),
+        Some("Str") => Ok(PortType::Str),
+        Some("Bool") => Ok(PortType::Bool),
+        Some(other) => {
+            bail!(
+                "Expression: unsupported output_type '{other}', expected one of Int|Float|Str|Bool"
+            )
+        }
+        None => bail!("Expression: param 'output_type' must be a string type name"),
+    }
+}
+
+fn output_type_name(port_type: &PortType) -> &'static str {
+    match port_type {
+        PortType::Int => "Int",
+        PortType::Str => "Str",
+        PortType::Bool => "Bool",
+        _ => "Float",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(
+        expression: &str,
+        output_type: &str,
+        inputs: Vec<(&str, PortData)>,
+    ) -> Result<PortData> {
+        let params = HashMap::from([("output_type".to_string(), serde_json::json!(output_type))]);
+        let mut node = ExpressionNode::from_params(&params)?;
+        let mut inputs: HashMap<String, PortData> = inputs
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect();
+        inputs.insert(
+            "expression".to_string(),
+            PortData::Str(expression.to_string()),
+        );
+        let mut outputs = node.execute(&inputs, &ExecutionContext::default())?;
+        Ok(outputs.remove("value").unwrap())
+    }
+
+    #[test]
+    fn test_expression_scales_and_clamps_width() {
+        let value = eval(
+            "clamp(i * x, 16, 3840)",
+            "Int",
+            vec![("i", PortData::Int(1920)), ("x", PortData::Float(1.5))],
+        )
+        .unwrap();
+        assert!(matches!(value, PortData::Int(2880)));
+
+        let value = eval(
+            "clamp(i * x, 16, 3840)",
+            "Int",
+            vec![("i", PortData::Int(1920)), ("x", PortData::Float(4.0))],
+        )
+        .unwrap();
+        assert!(matches!(value, PortData::Int(3840)));
+
+        let value = eval("i * 2 / 3", "Float", vec![("i", PortData::Int(1080))]).unwrap();
+        assert!(matches!(value, PortData::Float(v) if v == 720.0));
+    }
+
+    #[test]
+    fn test_expression_logic_and_strings() {
+        let value = eval(
+            "i >= 1080 && s == \"anime\"",
+            "Bool",
+            vec![
+                ("i", PortData::Int(1080)),
+                ("s", PortData::Str("anime".to_string())),
+            ],
+        )
+        .unwrap();
+        assert!(matches!(value, PortData::Bool(true)));
+
+        let value = eval(
+            "if x > 1.0 { s + \"_up\" } else { s }",
+            "Str",
+            vec![
+                ("x", PortData::Float(2.0)),
+                ("s", PortData::Str("ep01".to_string())),
+            ],
+        )
+        .unwrap();
+        assert!(matches!(value, PortData::Str(v) if v == "ep01_up"));
+    }
+
+    #[test]
+    fn test_expression_rejects_statements_and_bad_results() {
+        let Err(err) = eval("let a = 1; a", "Int", vec![]) else {
+            panic!("statements are not expressions");
+        };
+        assert!(err.to_string().starts_with("Expression:"), "got: {err}");
+
+        let Err(err) = eval("s", "Int", vec![("s", PortData::Str("x".to_string()))]) else {
+            panic!("a string is not an Int");
+        };
+        assert!(
+            err.to_string().contains("expected Int result"),
+            "got: {err}"
+        );
+    }
+}
@@ -0,0 +1,480 @@
+//! ExternalCommand node: runs an allow-listed binary with templated arguments.
+//!
+//! `args` is split like a shell command line (whitespace separated, with single
+//! or double quotes grouping words) and the placeholders `{input}`, `{output}`
+//! and `{temp_dir}` are replaced in every argument. The command runs without a
+//! shell, with stdin closed, a minimal environment and a per-run temp directory
+//! as its working directory. The temp directory comes from the job's scratch
+//! space and is removed when the job ends. Only commands listed in
+//! `commands.allowed` in the config may run.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
+use crate::services::NodeServices;
+use crate::types::{PortData, PortType};
+
+const DEFAULT_TIMEOUT_SECS: i64 = 300;
+const MIN_TIMEOUT_SECS: i64 = 1;
+const MAX_TIMEOUT_SECS: i64 = 86_400;
+const MAX_CAPTURED_BYTES: u64 = 1_048_576;
+/// Environment variables passed through to the command; everything else is
+/// cleared.
+const INHERITED_ENV_VARS: [&str; 7] = [
+    "PATH",
+    "HOME",
+    "LANG",
+    "TMPDIR",
+    "TEMP",
+    "TMP",
+    "SYSTEMROOT",
+];
+
+static ALLOWED_COMMANDS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// Replace the process-wide list of commands the node may run.
+pub fn set_allowed_commands(commands: Vec<String>) {
+    *ALLOWED_COMMANDS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = commands;
+}
+
+/// Whether `command` is listed in `commands.allowed`. Other nodes that
+/// shell out to a helper binary check it here too.
+pub(crate) fn is_allowed_command(command: &str) -> bool {
+    ALLOWED_COMMANDS
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .iter()
+        .any(|allowed| allowed == command)
+}
+
+pub struct ExternalCommandNode {
+    /// Overrides the process-wide allow-list when set.
+    allowed: Option<Vec<String>>,
+}
+
+impl ExternalCommandNode {
+    pub fn new() -> Self {
+        Self { allowed: None }
+    }
+
+    fn is_command_allowed(&self, command: &str) -> bool {
+        match &self.allowed {
+            Some(allowed) => allowed.iter().any(|allowed| allowed == command),
+            None => is_allowed_command(command),
+        }
+    }
+}
+
+impl Default for ExternalCommandNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node for ExternalCommandNode {
+    fn node_type(&self) -> &str {
+        "ExternalCommand"
+    }
+
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
+    fn input_ports(&self) -> Vec<PortDefinition> {
+        vec![
+            PortDefinition {
+                name: "command".to_string(),
+                port_type: PortType::Str,
+                required: true,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "args".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("")),
+            },
+            PortDefinition {
+                name: "input".to_string(),
+                port_type: PortType::Path,
+                required: false,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "output_extension".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("")),
+            },
+            PortDefinition {
+                name: "timeout_secs".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(DEFAULT_TIMEOUT_SECS)),
+            },
+        ]
+    }
+
+    fn output_ports(&self) -> Vec<PortDefinition> {
+        vec![
+            PortDefinition {
+                name: "exit_code".to_string(),
+                port_type: PortType::Int,
+                required: true,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "stdout".to_string(),
+                port_type: PortType::Str,
+                required: true,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "stderr".to_string(),
+                port_type: PortType::Str,
+                required: true,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "output_path".to_string(),
+                port_type: PortType::Path,
+                required: true,
+                default_value: None,
+            },
+        ]
+    }
+
+    fn execute(
+        &mut self,
+        inputs: &HashMap<String, PortData>,
+        ctx: &ExecutionContext,
+    ) -> Result<HashMap<String, PortData>> {
+        let command = match inputs.get("command") {
+            Some(PortData::Str(value)) if !value.trim().is_empty() => value.trim().to_string(),
+            Some(PortData::Str(_)) | None => bail!("ExternalCommand: input 'command' is required"),
+            Some(_) => bail!("ExternalCommand: input 'command' must be Str"),
+        };
+        if !self.is_command_allowed(&command) {
+            bail!("ExternalCommand: command '{command}' is not in commands.allowed in the config");
+        }
+
+        let args_template = read_optional_str(inputs, "args")?.unwrap_or_default();
+        let input = match inputs.get("input") {
+            Some(PortData::Path(path)) => Some(path.clone()),
+            Some(PortData::Str(path)) => Some(PathBuf::from(path)),
+            Some(_) => bail!("ExternalCommand: input 'input' must be Path"),
+            None => None,
+        };
+        let extension = read_optional_str(inputs, "output_extension")?.unwrap_or_default();
+        let timeout_secs = match inputs.get("timeout_secs") {
+            Some(PortData::Int(value)) => *value,
+            Some(_) => bail!("ExternalCommand: input 'timeout_secs' must be Int"),
+            None => DEFAULT_TIMEOUT_SECS,
+        };
+        if !(MIN_TIMEOUT_SECS..=MAX_TIMEOUT_SECS).contains(&timeout_secs) {
+            bail!(
+                "ExternalCommand: timeout_secs must be between {MIN_TIMEOUT_SECS} and {MAX_TIMEOUT_SECS}, got {timeout_secs}"
+            );
+        }
+
+        let temp_dir = ctx.services.temp_dir("cmd")?;
+        let output_path = temp_dir.join(output_file_name(&extension));
+
+        let placeholders = HashMap::from([
+            (
+                "input",
+                input
+                    .as_deref()
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+            ),
+            ("output", output_path.to_string_lossy().into_owned()),
+            ("temp_dir", temp_dir.to_string_lossy().into_owned()),
+        ]);
+        let args = split_args(&args_template)?
+            .into_iter()
+            .map(|arg| render_placeholders(&arg, &placeholders))
+            .collect::<Result<Vec<_>>>()?;
+
+        let run = run_command(
+            &command,
+            &args,
+            &temp_dir,
+            Duration::from_secs(timeout_secs as u64),
+            &ctx.services,
+        )
+        .inspect_err(|_| {
+            let _ = std::fs::remove_dir_all(&temp_dir);
+        })?;
+
+        Ok(HashMap::from([
+            ("exit_code".to_string(), PortData::Int(run.exit_code)),
+            ("stdout".to_string(), PortData::Str(run.stdout)),
+            ("stderr".to_string(), PortData::Str(run.stderr)),
+            ("output_path".to_string(), PortData::Path(output_path)),
+        ]))
+    }
+}
+
+fn read_optional_str(inputs: &HashMap<String, PortData>, key: &str) -> Result<Option<String>> {
+    match inputs.get(key) {
+        Some(PortData::Str(value)) => Ok(Some(value.clone())),
+        Some(_) => bail!("ExternalCommand: input '{key}' must be Str"),
+        None => Ok(None),
+    }
+}
+
+fn output_file_name(extension: &str) -> String {
+    let extension = extension.trim().trim_start_matches('.');
+    if extension.is_empty() {
+        "output".to_string()
+    } else {
+        format!("output.{extension}")
+    }
+}
+
+/// Split a command line into words. Whitespace separates words; single and
+/// double quotes group them and are removed. No other shell syntax applies.
+fn split_args(raw: &str) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+
+    for ch in raw.chars() {
+        match quote {
+            Some(q) if ch == q => quote = None,
+            Some(_) => current.push(ch),
+            None if ch == '"' || ch == '\'' => {
+                quote = Some(ch);
+                in_word = true;
+            }
+            None if ch.is_whitespace() => {
+                if in_word {
+                    args.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            None => {
+                current.push(ch);
+                in_word = true;
+            }
+        }
+    }
+
+    if quote.is_some() {
+        bail!("ExternalCommand: unterminated quote in args");
+    }
+    if in_word {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+fn render_placeholders(arg: &str, values: &HashMap<&str, String>) -> Result<String> {
+    let mut rendered = String::with_capacity(arg.len());
+    let mut rest = arg;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| anyhow!("ExternalCommand: unclosed placeholder in '{arg}'"))?;
+        let name = &after[..end];
+        let value = values
+            .get(name)
+            .ok_or_else(|| anyhow!("ExternalCommand: unknown placeholder '{{{name}}}'"))?;
+        rendered.push_str(value);
+        rest = &after[end + 1..];
+    }
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+struct CommandRun {
+    exit_code: i64,
+    stdout: String,
+    stderr: String,
+}
+
+fn run_command(
+    command: &str,
+    args: &[String],
+    working_dir: &Path,
+    timeout: Duration,
+    services: &NodeServices,
+) -> Result<CommandRun> {
+    let mut cmd = Command::new(command);
+    cmd.args(args)
+        .current_dir(working_dir)
+        .env_clear()
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    for name in INHERITED_ENV_VARS {
+        if let Some(value) = std::env::var_os(name) {
+            cmd.env(name, value);
+        }
+    }
+
+    let mut child = cmd
+        .spawn()
+        .with_context(|| format!("ExternalCommand: failed to start '{command}'"))?;
+    let stdout = child.stdout.take().map(capture_stream);
+    let stderr = child.stderr.take().map(capture_stream);
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!(
+                "ExternalCommand: '{command}' timed out after {}s",
+                timeout.as_secs()
+            );
+        }
+        if services.is_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!("ExternalCommand: '{command}' was cancelled");
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+
+    let collect = |handle: Option<std::thread::JoinHandle<String>>| {
+        handle
+            .and_then(|handle| handle.join().ok())
+            .unwrap_or_default()
+    };
+    Ok(CommandRun {
+        // Processes killed by a signal have no exit code.
+        exit_code: status.code().map(i64::from).unwrap_or(-1),
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    })
+}
+
+/// Read a child pipe on its own thread, keeping at most `MAX_CAPTURED_BYTES`
+/// and discarding the rest so the child never blocks on a full pipe.
+fn capture_stream(stream: impl Read + Send + 'static) -> std::thread::JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut stream = stream;
+        let mut captured = Vec::new();
+        let _ = stream
+            .by_ref()
+            .take(MAX_CAPTURED_BYTES)
+            .read_to_end(&mut captured);
+        let _ = std::io::copy(&mut stream, &mut std::io::sink());
+        String::from_utf8_lossy(&captured).into_owned()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_node() -> ExternalCommandNode {
+        ExternalCommandNode {
+            allowed: Some(vec!["sh".to_string()]),
+        }
+    }
+
+    fn inputs(command: &str, args: &str) -> HashMap<String, PortData> {
+        HashMap::from([
+            ("command".to_string(), PortData::Str(command.to_string())),
+            ("args".to_string(), PortData::Str(args.to_string())),
+        ])
+    }
+
+    #[test]
+    fn test_split_args_handles_quotes() {
+        assert_eq!(
+            split_args(r#"-i {input} -vf "scale=1280:720" '' -y"#).unwrap(),
+            vec!["-i", "{input}", "-vf", "scale=1280:720", "", "-y"]
+        );
+        assert!(split_args("\"open").is_err());
+    }
+
+    #[test]
+    fn test_render_placeholders() {
+        let values = HashMap::from([("input", "/a.mkv".to_string())]);
+        assert_eq!(
+            render_placeholders("--in={input}", &values).unwrap(),
+            "--in=/a.mkv"
+        );
+        assert!(render_placeholders("{nope}", &values).is_err());
+        assert!(render_placeholders("{input", &values).is_err());
+    }
+
+    #[test]
+    fn test_rejects_commands_outside_allow_list() {
+        let mut node = test_node();
+        let err = node
+            .execute(&inputs("rm", "-rf /tmp/x"), &ExecutionContext::default())
+            .err()
+            .expect("rm is not allowed");
+        assert!(err.to_string().contains("not in commands.allowed"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_captures_exit_code_streams_and_output_path() {
+        let mut node = test_node();
+        let mut inputs = inputs(
+            "sh",
+            r#"-c "echo done > {output}; echo warn >&2; echo {input}; exit 3""#,
+        );
+        inputs.insert(
+            "input".to_string(),
+            PortData::Path(PathBuf::from("/media/in.mkv")),
+        );
+        inputs.insert(
+            "output_extension".to_string(),
+            PortData::Str(".txt".to_string()),
+        );
+
+        let ctx = ExecutionContext::default();
+        let outputs = node.execute(&inputs, &ctx).unwrap();
+
+        assert!(matches!(outputs.get("exit_code"), Some(PortData::Int(3))));
+        assert!(matches!(outputs.get("stderr"), Some(PortData::Str(s)) if s == "warn\n"));
+        assert!(matches!(outputs.get("stdout"), Some(PortData::Str(s)) if s == "/media/in.mkv\n"));
+        let Some(PortData::Path(output_path)) = outputs.get("output_path") else {
+            panic!("expected output path");
+        };
+        assert_eq!(output_path.extension().unwrap(), "txt");
+        assert_eq!(std::fs::read_to_string(output_path).unwrap(), "done\n");
+
+        // The temp dir is part of the job's scratch space.
+        drop(ctx);
+        assert!(!output_path.parent().unwrap().exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_times_out_long_running_commands() {
+        let mut node = test_node();
+        let mut inputs = inputs("sh", r#"-c "sleep 5""#);
+        inputs.insert("timeout_secs".to_string(), PortData::Int(1));
+
+        let started = Instant::now();
+        let err = node
+            .execute(&inputs, &ExecutionContext::default())
+            .err()
+            .expect("sleep should time out");
+        assert!(err.to_string().contains("timed out after 1s"));
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+}
@@ -0,0 +1,564 @@
+//! FfmpegFilter node: runs every frame through a raw FFmpeg `-vf` filtergraph.
+//!
+//! The filter string is handed to a long-lived `ffmpeg` process that reads
+//! raw RGB frames on stdin and writes PPM images on stdout, so each frame
+//! comes back with its own dimensions. Before the pipeline starts, a dry run
+//! pushes one black frame through a throwaway process: it rejects filters
+//! FFmpeg cannot parse and learns the output size for the encoder.
+//!
+//! Only filters that emit exactly one frame per input frame without holding
+//! frames back are supported (`scale`, `crop`, `eq`, `hqdn3d`, `unsharp`,
+//! `drawtext`, ...). Filters that change the frame count, such as `fps` or
+//! `minterpolate`, fail the dry run.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::process::{Child, ChildStdin, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::node::{ExecutionContext, FrameProcessor, Node, PortDefinition, ResourceClass};
+use crate::types::{Frame, PortData, PortType};
+
+pub const FFMPEG_FILTER_NODE_TYPE: &str = "FfmpegFilter";
+const DRY_RUN_TIMEOUT: Duration = Duration::from_secs(30);
+const FRAME_TIMEOUT: Duration = Duration::from_secs(120);
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+const STDERR_TAIL_LINES: usize = 20;
+
+pub struct FfmpegFilterNode {
+    filter: String,
+    fps: String,
+    process: Option<FilterProcess>,
+}
+
+impl FfmpegFilterNode {
+    pub fn new() -> Self {
+        Self {
+            filter: String::new(),
+            fps: "24000/1001".to_string(),
+            process: None,
+        }
+    }
+
+    /// Runs the filter once on a black `width`x`height` frame and returns the
+    /// size of the frame it produces.
+    pub fn dry_run(&mut self, width: u32, height: u32, fps: &str) -> Result<(u32, u32)> {
+        if self.filter.is_empty() {
+            bail!("FfmpegFilter: filter is required");
+        }
+        self.fps = fps.to_string();
+
+        let black = Frame::CpuRgb {
+            data: vec![0; width as usize * height as usize * 3],
+            width,
+            height,
+            bit_depth: 8,
+        };
+        let mut process = FilterProcess::spawn(&self.filter, &self.fps, &black)?;
+        let out = process
+            .round_trip(black, DRY_RUN_TIMEOUT)
+            .context("FfmpegFilter: dry run failed")?;
+        Ok(frame_size(&out))
+    }
+}
+
+impl Default for FfmpegFilterNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node for FfmpegFilterNode {
+    fn node_type(&self) -> &str {
+        FFMPEG_FILTER_NODE_TYPE
+    }
+
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
+    fn input_ports(&self) -> Vec<PortDefinition> {
+        vec![PortDefinition {
+            name: "filter".to_string(),
+            port_type: PortType::Str,
+            required: true,
+            default_value: None,
+        }]
+    }
+
+    fn output_ports(&self) -> Vec<PortDefinition> {
+        vec![]
+    }
+
+    fn execute(
+        &mut self,
+        inputs: &HashMap<String, PortData>,
+        _ctx: &ExecutionContext,
+    ) -> Result<HashMap<String, PortData>> {
+        let filter = match inputs.get("filter") {
+            Some(PortData::Str(filter)) => filter.trim(),
+            Some(_) => bail!("FfmpegFilter: filter must be a Str"),
+            None => bail!("FfmpegFilter: filter is required"),
+        };
+        if filter.is_empty() {
+            bail!("FfmpegFilter: filter is required");
+        }
+        self.filter = filter.to_string();
+        self.process = None;
+        Ok(HashMap::new())
+    }
+}
+
+impl FrameProcessor for FfmpegFilterNode {
+    fn process_frame(&mut self, frame: Frame, _ctx: &ExecutionContext) -> Result<Frame> {
+        // The real process starts on the first frame so it can match the
+        // decoder's bit depth.
+        if self.process.is_none() {
+            self.process = Some(FilterProcess::spawn(&self.filter, &self.fps, &frame)?);
+        }
+        let process = self.process.as_mut().expect("process started above");
+        process.round_trip(frame, FRAME_TIMEOUT)
+    }
+}
+
+fn frame_size(frame: &Frame) -> (u32, u32) {
+    match frame {
+        Frame::CpuRgb { width, height, .. } => (*width, *height),
+        _ => (0, 0),
+    }
+}
+
+/// `-pix_fmt` values for a frame's layout on the way in and out.
+fn pixel_formats(bit_depth: u8) -> (&'static str, &'static str) {
+    if bit_depth > 8 {
+        ("rgb48le", "rgb48be")
+    } else {
+        ("rgb24", "rgb24")
+    }
+}
+
+fn build_filter_args(
+    filter: &str,
+    width: u32,
+    height: u32,
+    fps: &str,
+    bit_depth: u8,
+) -> Vec<String> {
+    let (in_pix_fmt, out_pix_fmt) = pixel_formats(bit_depth);
+    [
+        "-hide_banner",
+        "-v",
+        "error",
+        "-f",
+        "rawvideo",
+        "-pix_fmt",
+        in_pix_fmt,
+        "-s",
+        &format!("{width}x{height}"),
+        "-r",
+        fps,
+        "-i",
+        "pipe:0",
+        "-vf",
+        filter,
+        "-vsync",
+        "passthrough",
+        "-f",
+        "image2pipe",
+        "-c:v",
+        "ppm",
+        "-pix_fmt",
+        out_pix_fmt,
+        "pipe:1",
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect()
+}
+
+struct FilterProcess {
+    child: Child,
+    stdin: Option<BufWriter<ChildStdin>>,
+    // Wrapped so the node stays `Sync`; only `round_trip` ever locks it.
+    frames: Mutex<Receiver<Result<PpmImage>>>,
+    stderr_tail: Arc<Mutex<VecDeque<String>>>,
+    stderr_thread: Option<JoinHandle<()>>,
+    input: (u32, u32, u8),
+}
+
+impl FilterProcess {
+    fn spawn(filter: &str, fps: &str, first_frame: &Frame) -> Result<Self> {
+        let Frame::CpuRgb {
+            width,
+            height,
+            bit_depth,
+            ..
+        } = *first_frame
+        else {
+            bail!("FfmpegFilter: only CPU RGB frames are supported");
+        };
+
+        let args = build_filter_args(filter, width, height, fps, bit_depth);
+        tracing::debug!(cmd = %format!("ffmpeg {}", args.join(" ")), "launching FFmpeg filter");
+        let mut child = crate::runtime::command_for("ffmpeg")
+            .args(&args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("FfmpegFilter: failed to launch ffmpeg — is it installed?")?;
+
+        let stderr_tail = Arc::new(Mutex::new(VecDeque::new()));
+        let stderr_thread = child.stderr.take().map(|stderr| {
+            let tail = Arc::clone(&stderr_tail);
+            let stderr_span = tracing::Span::current();
+            std::thread::spawn(move || {
+                let _entered = stderr_span.enter();
+                for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                    tracing::debug!(target: "ffmpeg_filter_stderr", "{line}");
+                    let mut tail = tail.lock().unwrap_or_else(|e| e.into_inner());
+                    if tail.len() == STDERR_TAIL_LINES {
+                        tail.pop_front();
+                    }
+                    tail.push_back(line);
+                }
+            })
+        });
+
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow!("FfmpegFilter: ffmpeg stdout is unavailable"))?;
+        let (tx, frames) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            loop {
+                match read_ppm(&mut reader) {
+                    Ok(Some(image)) => {
+                        if tx.send(Ok(image)).is_err() {
+                            return;
+                        }
+                    }
+                    Ok(None) => return,
+                    Err(err) => {
+                        let _ = tx.send(Err(err));
+                        return;
+                    }
+                }
+            }
+        });
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow!("FfmpegFilter: ffmpeg stdin is unavailable"))?;
+        Ok(Self {
+            child,
+            stdin: Some(BufWriter::new(stdin)),
+            frames: Mutex::new(frames),
+            stderr_tail,
+            stderr_thread,
+            input: (width, height, bit_depth),
+        })
+    }
+
+    fn round_trip(&mut self, frame: Frame, timeout: Duration) -> Result<Frame> {
+        let Frame::CpuRgb {
+            data,
+            width,
+            height,
+            bit_depth,
+        } = frame
+        else {
+            bail!("FfmpegFilter: only CPU RGB frames are supported");
+        };
+        if (width, height, bit_depth) != self.input {
+            bail!(
+                "FfmpegFilter: frame format changed mid-stream ({}x{} {}-bit, expected {}x{} {}-bit)",
+                width,
+                height,
+                bit_depth,
+                self.input.0,
+                self.input.1,
+                self.input.2
+            );
+        }
+
+        let stdin = self
+            .stdin
+            .as_mut()
+            .ok_or_else(|| anyhow!("FfmpegFilter: ffmpeg stdin is closed"))?;
+        if let Err(err) = stdin.write_all(&data).and_then(|()| stdin.flush()) {
+            return Err(self.failure(&format!("failed to send frame: {err}")));
+        }
+
+        let received = self
+            .frames
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .recv_timeout(timeout);
+        let image = match received {
+            Ok(Ok(image)) => image,
+            Ok(Err(err)) => return Err(self.failure(&err.to_string())),
+            Err(RecvTimeoutError::Timeout) => bail!(
+                "FfmpegFilter: no frame back from ffmpeg after {}s; the filter must emit \
+                 exactly one frame per input frame",
+                timeout.as_secs()
+            ),
+            Err(RecvTimeoutError::Disconnected) => {
+                return Err(self.failure("ffmpeg exited without returning a frame"))
+            }
+        };
+
+        // The output pixel format mirrors the input, so the bit depth carries over.
+        let (out_width, out_height) = (image.width, image.height);
+        Ok(Frame::CpuRgb {
+            data: image.into_le_data(),
+            width: out_width,
+            height: out_height,
+            bit_depth,
+        })
+    }
+
+    /// Builds an error carrying the last lines ffmpeg wrote to stderr.
+    fn failure(&mut self, message: &str) -> anyhow::Error {
+        self.shutdown();
+        if let Some(thread) = self.stderr_thread.take() {
+            let _ = thread.join();
+        }
+        let tail = self.stderr_tail.lock().unwrap_or_else(|e| e.into_inner());
+        if tail.is_empty() {
+            anyhow!("FfmpegFilter: {message}")
+        } else {
+            let lines: Vec<&str> = tail.iter().map(String::as_str).collect();
+            anyhow!("FfmpegFilter: {message}: {}", lines.join("; "))
+        }
+    }
+
+    /// Closes stdin so ffmpeg flushes and exits, killing it after a grace period.
+    fn shutdown(&mut self) {
+        self.stdin.take();
+        let deadline = Instant::now() + SHUTDOWN_GRACE;
+        while Instant::now() < deadline {
+            match self.child.try_wait() {
+                Ok(Some(_)) | Err(_) => return,
+                Ok(None) => std::thread::sleep(Duration::from_millis(50)),
+            }
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl Drop for FilterProcess {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+struct PpmImage {
+    width: u32,
+    height: u32,
+    max_value: u32,
+    data: Vec<u8>,
+}
+
+impl PpmImage {
+    /// PPM stores 16-bit samples big-endian; frames carry them little-endian.
+    fn into_le_data(self) -> Vec<u8> {
+        let mut data = self.data;
+        if self.max_value > 255 {
+            for sample in data.chunks_exact_mut(2) {
+                sample.swap(0, 1);
+            }
+        }
+        data
+    }
+}
+
+/// Reads one binary (`P6`) PPM image, or `None` at a clean end of stream.
+fn read_ppm(reader: &mut impl BufRead) -> Result<Option<PpmImage>> {
+    if reader.fill_buf()?.is_empty() {
+        return Ok(None);
+    }
+
+    let magic = read_ppm_token(reader)?;
+    if magic != "P6" {
+        bail!("unexpected image header '{magic}' from ffmpeg");
+    }
+    let mut number = |name: &str| -> Result<u32> {
+        let token = read_ppm_token(reader)?;
+        token
+            .parse()
+            .with_context(|| format!("invalid PPM {name} '{token}'"))
+    };
+    let width = number("width")?;
+    let height = number("height")?;
+    let max_value = number("max value")?;
+    if max_value == 0 || max_value > 65535 {
+        bail!("invalid PPM max value {max_value}");
+    }
+
+    let bytes_per_sample = if max_value > 255 { 2 } else { 1 };
+    let mut data = vec![0u8; width as usize * height as usize * 3 * bytes_per_sample];
+    reader
+        .read_exact(&mut data)
+        .context("image from ffmpeg is truncated")?;
+    Ok(Some(PpmImage {
+        width,
+        height,
+        max_value,
+        data,
+    }))
+}
+
+/// Reads a whitespace-delimited header token, skipping `#` comments. The
+/// single whitespace byte that ends the token is consumed.
+fn read_ppm_token(reader: &mut impl BufRead) -> Result<String> {
+    let mut token = String::new();
+    let mut byte = [0u8; 1];
+    loop {
+        reader
+            .read_exact(&mut byte)
+            .context("image header from ffmpeg is truncated")?;
+        match byte[0] {
+            b'#' if token.is_empty() => {
+                let mut comment = Vec::new();
+                reader.read_until(b'\n', &mut comment)?;
+            }
+            b if b.is_ascii_whitespace() => {
+                if !token.is_empty() {
+                    return Ok(token);
+                }
+            }
+            b => token.push(b as char),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn ffmpeg_available() -> bool {
+        crate::runtime::command_for("ffmpeg")
+            .arg("-version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    }
+
+    fn node_with_filter(filter: &str) -> FfmpegFilterNode {
+        let mut node = FfmpegFilterNode::new();
+        let inputs = HashMap::from([("filter".to_string(), PortData::Str(filter.to_string()))]);
+        node.execute(&inputs, &ExecutionContext::default()).unwrap();
+        node
+    }
+
+    #[test]
+    fn test_read_ppm_frames() {
+        let mut bytes = b"P6\n# from ffmpeg\n2 1\n255\n".to_vec();
+        bytes.extend([1, 2, 3, 4, 5, 6]);
+        bytes.extend(b"P6 1 1 65535\n");
+        bytes.extend([0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+        let mut reader = Cursor::new(bytes);
+
+        let image = read_ppm(&mut reader).unwrap().unwrap();
+        assert_eq!((image.width, image.height, image.max_value), (2, 1, 255));
+        assert_eq!(image.into_le_data(), vec![1, 2, 3, 4, 5, 6]);
+
+        let image = read_ppm(&mut reader).unwrap().unwrap();
+        assert_eq!((image.width, image.height, image.max_value), (1, 1, 65535));
+        assert_eq!(
+            image.into_le_data(),
+            vec![0x02, 0x01, 0x04, 0x03, 0x06, 0x05]
+        );
+
+        assert!(read_ppm(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_read_ppm_rejects_truncated_image() {
+        let mut reader = Cursor::new(b"P6\n2 2\n255\n\x00\x00".to_vec());
+        let err = read_ppm(&mut reader)
+            .err()
+            .expect("short pixel data should fail");
+        assert!(err.to_string().contains("truncated"));
+
+        let mut reader = Cursor::new(b"P5\n1 1\n255\n\x00".to_vec());
+        assert!(read_ppm(&mut reader).is_err());
+    }
+
+    #[test]
+    fn test_build_filter_args() {
+        let args = build_filter_args("scale=1280:-2,eq=gamma=1.1", 1920, 1080, "24000/1001", 10);
+        let pos = |flag: &str| args.iter().position(|arg| arg == flag).unwrap();
+        assert_eq!(args[pos("-s") + 1], "1920x1080");
+        assert_eq!(args[pos("-r") + 1], "24000/1001");
+        assert_eq!(args[pos("-vf") + 1], "scale=1280:-2,eq=gamma=1.1");
+        assert_eq!(args[pos("-pix_fmt") + 1], "rgb48le");
+        assert_eq!(args.last().unwrap(), "pipe:1");
+    }
+
+    #[test]
+    fn test_ffmpeg_filter_requires_filter() {
+        let mut node = FfmpegFilterNode::new();
+        let inputs = HashMap::from([("filter".to_string(), PortData::Str("  ".to_string()))]);
+        let err = node
+            .execute(&inputs, &ExecutionContext::default())
+            .err()
+            .expect("empty filter should be rejected");
+        assert!(err.to_string().contains("filter is required"));
+    }
+
+    #[test]
+    fn test_ffmpeg_filter_dry_run_and_process() {
+        if !ffmpeg_available() {
+            return;
+        }
+        let mut node = node_with_filter("scale=4:2");
+        assert_eq!(node.dry_run(8, 4, "25").unwrap(), (4, 2));
+
+        let frame = Frame::CpuRgb {
+            data: vec![200; 8 * 4 * 3],
+            width: 8,
+            height: 4,
+            bit_depth: 8,
+        };
+        let out = node
+            .process_frame(frame, &ExecutionContext::default())
+            .unwrap();
+        match out {
+            Frame::CpuRgb {
+                data,
+                width,
+                height,
+                bit_depth,
+            } => {
+                assert_eq!((width, height, bit_depth), (4, 2, 8));
+                assert_eq!(data.len(), 4 * 2 * 3);
+            }
+            _ => panic!("expected RGB frame"),
+        }
+    }
+
+    #[test]
+    fn test_ffmpeg_filter_dry_run_reports_bad_filter() {
+        if !ffmpeg_available() {
+            return;
+        }
+        let mut node = node_with_filter("definitely_not_a_filter=1");
+        let err = node
+            .dry_run(8, 4, "25")
+            .expect_err("unknown filter should fail the dry run");
+        assert!(format!("{err:#}").contains("FfmpegFilter"));
+    }
+}
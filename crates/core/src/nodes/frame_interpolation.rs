@@ -24,10 +24,11 @@ use ort::{
 use tracing::debug;
 
 use crate::node::{ExecutionContext, FrameProcessor, Node, PortDefinition};
+use crate::runtime::session_pool::{acquire_session, SessionLease};
 use crate::streaming_executor::FrameInterpolator;
 use crate::types::{Frame, PortData, PortType};
 
-use crate::nodes::backend::{build_session, InferenceBackend, SessionConfig};
+use crate::nodes::backend::{InferenceBackend, SessionConfig};
 
 const PAD_ALIGN: usize = 32;
 
@@ -57,6 +58,8 @@ pub enum ModelFormat {
 /// - e.g. 2× → t=0.5; 3× → t=0.333, 0.667; 4× → t=0.25, 0.5, 0.75
 pub struct FrameInterpolationNode {
     session: Option<Arc<Mutex<Session>>>,
+    /// Returns `session` to the session pool when dropped.
+    session_lease: Option<SessionLease>,
     multiplier: u32,
     backend: InferenceBackend,
     use_iobinding: bool,
@@ -81,6 +84,7 @@ impl FrameInterpolationNode {
     pub fn new() -> Self {
         Self {
             session: None,
+            session_lease: None,
             multiplier: 2,
             backend: InferenceBackend::default(),
             use_iobinding: true,
@@ -125,6 +129,7 @@ impl FrameInterpolationNode {
             preprocess: FrameInterpolationPreprocess { nchw_buf: None },
             inference: FrameInterpolationInference {
                 session,
+                _session_lease: self.session_lease,
                 use_iobinding: self.use_iobinding,
                 concat_buf: self.concat_buf,
                 multiplier: self.multiplier,
@@ -430,15 +435,16 @@ impl Node for FrameInterpolationNode {
             trt_cache_dir: self.trt_cache_dir.as_deref(),
         };
 
-        let session = build_session(&config)?;
+        let (session, lease) = acquire_session(&config)?;
 
-        self.model_format = detect_model_format(&session);
+        self.model_format = detect_model_format(&session.lock().unwrap());
         debug!(
             format = ?self.model_format,
             "Detected RIFE model format"
         );
 
-        self.session = Some(Arc::new(Mutex::new(session)));
+        self.session = Some(session);
+        self.session_lease = Some(lease);
         debug!("RIFE model loaded successfully");
 
         Ok(HashMap::new())
@@ -534,6 +540,7 @@ impl FrameProcessor for FrameInterpolationPreprocess {
 
 pub struct FrameInterpolationInference {
     session: Arc<Mutex<Session>>,
+    _session_lease: Option<SessionLease>,
     use_iobinding: bool,
     concat_buf: Option<Array4<f32>>,
     multiplier: u32,
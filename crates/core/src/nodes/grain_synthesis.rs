@@ -0,0 +1,426 @@
+//! GrainSynthesis node: overlays synthetic film grain on RGB frames.
+//!
+//! Upscaling models smooth away the grain of the source. This node puts a
+//! controllable amount back after inference. Grain is Gaussian-like noise,
+//! fresh for every frame, that can be made coarser with `grain_size` and
+//! tinted per channel with `chroma`. It is strongest in the midtones and fades
+//! towards black and white, like real film grain. The pattern only depends on
+//! `seed` and the frame number, so reruns give identical output.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+
+use crate::node::{ExecutionContext, FrameProcessor, Node, PortDefinition, ResourceClass};
+use crate::types::{Frame, PortData, PortType};
+
+pub const GRAIN_SYNTHESIS_NODE_TYPE: &str = "GrainSynthesis";
+const MAX_INTENSITY: f64 = 0.5;
+const MAX_GRAIN_SIZE: f64 = 8.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GrainParams {
+    /// Grain standard deviation as a fraction of full range.
+    pub intensity: f32,
+    /// Grain cell size in pixels; 1.0 is per-pixel grain.
+    pub grain_size: f32,
+    /// Strength of independent per-channel grain relative to the shared
+    /// (monochrome) grain.
+    pub chroma: f32,
+    pub seed: u64,
+}
+
+impl Default for GrainParams {
+    fn default() -> Self {
+        Self {
+            intensity: 0.03,
+            grain_size: 1.0,
+            chroma: 0.2,
+            seed: 0,
+        }
+    }
+}
+
+pub struct GrainSynthesisNode {
+    params: GrainParams,
+    frame_index: u64,
+}
+
+impl GrainSynthesisNode {
+    pub fn new() -> Self {
+        Self {
+            params: GrainParams::default(),
+            frame_index: 0,
+        }
+    }
+}
+
+impl Default for GrainSynthesisNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node for GrainSynthesisNode {
+    fn node_type(&self) -> &str {
+        GRAIN_SYNTHESIS_NODE_TYPE
+    }
+
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
+    fn input_ports(&self) -> Vec<PortDefinition> {
+        let defaults = GrainParams::default();
+        vec![
+            PortDefinition {
+                name: "intensity".to_string(),
+                port_type: PortType::Float,
+                required: false,
+                default_value: Some(serde_json::json!(defaults.intensity)),
+            },
+            PortDefinition {
+                name: "grain_size".to_string(),
+                port_type: PortType::Float,
+                required: false,
+                default_value: Some(serde_json::json!(defaults.grain_size)),
+            },
+            PortDefinition {
+                name: "chroma".to_string(),
+                port_type: PortType::Float,
+                required: false,
+                default_value: Some(serde_json::json!(defaults.chroma)),
+            },
+            PortDefinition {
+                name: "seed".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(defaults.seed)),
+            },
+        ]
+    }
+
+    fn output_ports(&self) -> Vec<PortDefinition> {
+        vec![]
+    }
+
+    fn execute(
+        &mut self,
+        inputs: &HashMap<String, PortData>,
+        _ctx: &ExecutionContext,
+    ) -> Result<HashMap<String, PortData>> {
+        let float_input = |name: &str, default: f32, max: f64| -> Result<f32> {
+            let value = match inputs.get(name) {
+                Some(PortData::Float(value)) => *value,
+                Some(PortData::Int(value)) => *value as f64,
+                Some(_) => bail!("GrainSynthesis: {name} must be a Float"),
+                None => return Ok(default),
+            };
+            if !(0.0..=max).contains(&value) {
+                bail!("GrainSynthesis: {name} must be between 0 and {max}, got {value}");
+            }
+            Ok(value as f32)
+        };
+
+        let defaults = GrainParams::default();
+        let mut params = GrainParams {
+            intensity: float_input("intensity", defaults.intensity, MAX_INTENSITY)?,
+            grain_size: float_input("grain_size", defaults.grain_size, MAX_GRAIN_SIZE)?,
+            chroma: float_input("chroma", defaults.chroma, 1.0)?,
+            seed: defaults.seed,
+        };
+        if params.grain_size < 1.0 {
+            bail!(
+                "GrainSynthesis: grain_size must be at least 1, got {}",
+                params.grain_size
+            );
+        }
+        match inputs.get("seed") {
+            Some(PortData::Int(seed)) => params.seed = *seed as u64,
+            Some(_) => bail!("GrainSynthesis: seed must be an Int"),
+            None => {}
+        }
+
+        self.params = params;
+        self.frame_index = 0;
+        Ok(HashMap::new())
+    }
+}
+
+impl FrameProcessor for GrainSynthesisNode {
+    fn process_frame(&mut self, frame: Frame, _ctx: &ExecutionContext) -> Result<Frame> {
+        let Frame::CpuRgb {
+            mut data,
+            width,
+            height,
+            bit_depth,
+        } = frame
+        else {
+            bail!("GrainSynthesis: only CPU RGB frames are supported");
+        };
+
+        let frame_index = self.frame_index;
+        self.frame_index += 1;
+        if self.params.intensity > 0.0 {
+            apply_grain(
+                &mut data,
+                width as usize,
+                height as usize,
+                bit_depth,
+                &self.params,
+                frame_index,
+            )?;
+        }
+
+        Ok(Frame::CpuRgb {
+            data,
+            width,
+            height,
+            bit_depth,
+        })
+    }
+}
+
+/// Adds grain in place to packed RGB data: 8-bit, or `rgb48le` (full 16-bit
+/// range, as decoded) when `bit_depth > 8`.
+pub fn apply_grain(
+    data: &mut [u8],
+    width: usize,
+    height: usize,
+    bit_depth: u8,
+    params: &GrainParams,
+    frame_index: u64,
+) -> Result<()> {
+    let bytes_per_sample = if bit_depth > 8 { 2 } else { 1 };
+    let expected = width * height * 3 * bytes_per_sample;
+    if data.len() != expected {
+        bail!(
+            "GrainSynthesis: frame data length mismatch: expected {expected}, got {}",
+            data.len()
+        );
+    }
+
+    let max_value = if bit_depth > 8 { 65535.0 } else { 255.0 };
+    let field = GrainField::new(width, height, params, frame_index);
+    let mut noise = [0f32; 4];
+
+    for y in 0..height {
+        for x in 0..width {
+            field.sample(x, y, &mut noise);
+            let pixel = (y * width + x) * 3;
+
+            let mut rgb = [0f32; 3];
+            for (c, value) in rgb.iter_mut().enumerate() {
+                *value = read_sample(data, pixel + c, bytes_per_sample) as f32 / max_value;
+            }
+            let luma = 0.2126 * rgb[0] + 0.7152 * rgb[1] + 0.0722 * rgb[2];
+            // 1.0 at mid grey, 0.25 at black and white.
+            let weight = 0.25 + 3.0 * luma * (1.0 - luma);
+            let amount = params.intensity * weight;
+
+            for (c, value) in rgb.iter().enumerate() {
+                let grain = noise[0] + params.chroma * noise[c + 1];
+                let out = ((value + grain * amount) * max_value)
+                    .round()
+                    .clamp(0.0, max_value);
+                write_sample(data, pixel + c, bytes_per_sample, out as u16);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_sample(data: &[u8], index: usize, bytes_per_sample: usize) -> u16 {
+    if bytes_per_sample == 2 {
+        u16::from_le_bytes([data[index * 2], data[index * 2 + 1]])
+    } else {
+        data[index] as u16
+    }
+}
+
+fn write_sample(data: &mut [u8], index: usize, bytes_per_sample: usize, value: u16) {
+    if bytes_per_sample == 2 {
+        data[index * 2..index * 2 + 2].copy_from_slice(&value.to_le_bytes());
+    } else {
+        data[index] = value as u8;
+    }
+}
+
+/// Noise on a grid of `grain_size` cells, bilinearly interpolated so coarse
+/// grain has soft edges. Each cell holds four values: shared grain and one
+/// per channel.
+struct GrainField {
+    cells: Vec<[f32; 4]>,
+    grid_w: usize,
+    grid_h: usize,
+    inv_size: f32,
+}
+
+impl GrainField {
+    fn new(width: usize, height: usize, params: &GrainParams, frame_index: u64) -> Self {
+        let size = params.grain_size.max(1.0);
+        let grid_w = (width as f32 / size).ceil() as usize + 1;
+        let grid_h = (height as f32 / size).ceil() as usize + 1;
+        let frame_seed = splitmix64(params.seed ^ splitmix64(frame_index));
+
+        // Interpolation between cells lowers the variance; scale it back up so
+        // `intensity` means roughly the same thing at every grain size.
+        let gain = if size > 1.0 { 1.5 } else { 1.0 };
+        let cells = (0..grid_w * grid_h)
+            .map(|cell| {
+                let mut state = frame_seed ^ (cell as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+                let mut cell_noise = [0f32; 4];
+                for value in &mut cell_noise {
+                    *value = gaussian(&mut state) * gain;
+                }
+                cell_noise
+            })
+            .collect();
+
+        Self {
+            cells,
+            grid_w,
+            grid_h,
+            inv_size: 1.0 / size,
+        }
+    }
+
+    fn sample(&self, x: usize, y: usize, out: &mut [f32; 4]) {
+        if self.inv_size >= 1.0 {
+            *out = self.cells[y * self.grid_w + x];
+            return;
+        }
+
+        let gx = x as f32 * self.inv_size;
+        let gy = y as f32 * self.inv_size;
+        let x0 = (gx as usize).min(self.grid_w - 2);
+        let y0 = (gy as usize).min(self.grid_h - 2);
+        let fx = gx - x0 as f32;
+        let fy = gy - y0 as f32;
+
+        let top = y0 * self.grid_w + x0;
+        let bottom = top + self.grid_w;
+        for (i, value) in out.iter_mut().enumerate() {
+            let t = self.cells[top][i] * (1.0 - fx) + self.cells[top + 1][i] * fx;
+            let b = self.cells[bottom][i] * (1.0 - fx) + self.cells[bottom + 1][i] * fx;
+            *value = t * (1.0 - fy) + b * fy;
+        }
+    }
+}
+
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// Approximately standard normal: the sum of four uniforms, rescaled.
+fn gaussian(state: &mut u64) -> f32 {
+    let mut sum = 0.0f32;
+    for _ in 0..4 {
+        *state = splitmix64(*state);
+        sum += (*state >> 40) as f32 / (1u64 << 24) as f32;
+    }
+    // Four U(0,1) have mean 2 and variance 1/3.
+    (sum - 2.0) * 3f32.sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grey_frame(width: u32, height: u32) -> Frame {
+        Frame::CpuRgb {
+            data: vec![128; (width * height * 3) as usize],
+            width,
+            height,
+            bit_depth: 8,
+        }
+    }
+
+    fn node(pairs: Vec<(&str, PortData)>) -> Result<GrainSynthesisNode> {
+        let mut node = GrainSynthesisNode::new();
+        let inputs: HashMap<String, PortData> =
+            pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect();
+        node.execute(&inputs, &ExecutionContext::default())?;
+        Ok(node)
+    }
+
+    fn frame_data(frame: Frame) -> Vec<u8> {
+        match frame {
+            Frame::CpuRgb { data, .. } => data,
+            _ => panic!("expected RGB frame"),
+        }
+    }
+
+    #[test]
+    fn test_grain_strength_follows_intensity() {
+        let mut node = node(vec![("intensity", PortData::Float(0.05))]).unwrap();
+        let data = frame_data(
+            node.process_frame(grey_frame(64, 64), &ExecutionContext::default())
+                .unwrap(),
+        );
+
+        let mean = data.iter().map(|&v| v as f64).sum::<f64>() / data.len() as f64;
+        let std = (data.iter().map(|&v| (v as f64 - mean).powi(2)).sum::<f64>()
+            / data.len() as f64)
+            .sqrt();
+        assert!((mean - 128.0).abs() < 1.5, "mean {mean}");
+        // 5% of 255 with a little chroma grain on top.
+        assert!((10.0..17.0).contains(&std), "std {std}");
+    }
+
+    #[test]
+    fn test_grain_is_deterministic_and_changes_per_frame() {
+        let params = || {
+            vec![
+                ("seed", PortData::Int(7)),
+                ("grain_size", PortData::Float(2.5)),
+            ]
+        };
+        let ctx = ExecutionContext::default();
+        let mut a = node(params()).unwrap();
+        let mut b = node(params()).unwrap();
+
+        let a0 = frame_data(a.process_frame(grey_frame(32, 16), &ctx).unwrap());
+        let b0 = frame_data(b.process_frame(grey_frame(32, 16), &ctx).unwrap());
+        let a1 = frame_data(a.process_frame(grey_frame(32, 16), &ctx).unwrap());
+        assert_eq!(a0, b0);
+        assert_ne!(a0, a1);
+    }
+
+    #[test]
+    fn test_grain_zero_intensity_and_16bit() {
+        let ctx = ExecutionContext::default();
+        let mut off = node(vec![("intensity", PortData::Float(0.0))]).unwrap();
+        let data = frame_data(off.process_frame(grey_frame(8, 8), &ctx).unwrap());
+        assert!(data.iter().all(|&v| v == 128));
+
+        let mut node = node(vec![]).unwrap();
+        let frame = Frame::CpuRgb {
+            data: [32768u16.to_le_bytes(); 8 * 8 * 3].concat(),
+            width: 8,
+            height: 8,
+            bit_depth: 10,
+        };
+        let data = frame_data(node.process_frame(frame, &ctx).unwrap());
+        let samples: Vec<u16> = data
+            .chunks_exact(2)
+            .map(|s| u16::from_le_bytes([s[0], s[1]]))
+            .collect();
+        let mean = samples.iter().map(|&v| v as f64).sum::<f64>() / samples.len() as f64;
+        assert!((mean - 32768.0).abs() < 1000.0, "mean {mean}");
+        assert!(samples.iter().any(|&v| v != 32768));
+    }
+
+    #[test]
+    fn test_grain_rejects_out_of_range_params() {
+        for (name, value) in [("intensity", 0.9), ("grain_size", 0.5), ("chroma", -0.1)] {
+            let err = node(vec![(name, PortData::Float(value))])
+                .err()
+                .expect("out-of-range value should be rejected");
+            assert!(err.to_string().contains(name), "{err}");
+        }
+    }
+}
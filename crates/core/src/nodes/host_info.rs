@@ -0,0 +1,160 @@
+//! HostInfo node: where and with what an encode ran.
+//!
+//! Outputs the machine's hostname, the name of the GPU at `device_id` (as
+//! reported by `nvidia-smi`) and the videnoa version, for recording
+//! provenance in output names or webhook payloads. A value that can't be
+//! found is an empty string rather than an error.
+
+use std::collections::HashMap;
+use std::process::Command;
+
+use anyhow::{bail, Result};
+
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
+use crate::types::{PortData, PortType};
+
+pub const HOST_INFO_NODE_TYPE: &str = "HostInfo";
+
+pub struct HostInfoNode;
+
+impl HostInfoNode {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for HostInfoNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node for HostInfoNode {
+    fn node_type(&self) -> &str {
+        HOST_INFO_NODE_TYPE
+    }
+
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
+    fn input_ports(&self) -> Vec<PortDefinition> {
+        vec![PortDefinition {
+            name: "device_id".to_string(),
+            port_type: PortType::Int,
+            required: false,
+            default_value: Some(serde_json::json!(0)),
+        }]
+    }
+
+    fn output_ports(&self) -> Vec<PortDefinition> {
+        ["hostname", "gpu_name", "version"]
+            .into_iter()
+            .map(|name| PortDefinition {
+                name: name.to_string(),
+                port_type: PortType::Str,
+                required: true,
+                default_value: None,
+            })
+            .collect()
+    }
+
+    fn execute(
+        &mut self,
+        inputs: &HashMap<String, PortData>,
+        _ctx: &ExecutionContext,
+    ) -> Result<HashMap<String, PortData>> {
+        let device_id = match inputs.get("device_id") {
+            Some(PortData::Int(value)) if *value >= 0 => *value,
+            Some(PortData::Int(value)) => bail!("HostInfo: device_id must be >= 0, got {value}"),
+            Some(_) => bail!("HostInfo: input 'device_id' must be Int"),
+            None => 0,
+        };
+
+        Ok(HashMap::from([
+            (
+                "hostname".to_string(),
+                PortData::Str(hostname().unwrap_or_default()),
+            ),
+            (
+                "gpu_name".to_string(),
+                PortData::Str(gpu_name(device_id).unwrap_or_default()),
+            ),
+            (
+                "version".to_string(),
+                PortData::Str(env!("CARGO_PKG_VERSION").to_string()),
+            ),
+        ]))
+    }
+}
+
+#[cfg(unix)]
+fn hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    if unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) } != 0 {
+        return None;
+    }
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    let name = String::from_utf8_lossy(&buf[..len]).trim().to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+#[cfg(not(unix))]
+fn hostname() -> Option<String> {
+    std::env::var("COMPUTERNAME")
+        .ok()
+        .filter(|name| !name.trim().is_empty())
+}
+
+fn gpu_name(device_id: i64) -> Option<String> {
+    let output = Command::new("nvidia-smi")
+        .args([
+            "--query-gpu=name",
+            "--format=csv,noheader",
+            &format!("--id={device_id}"),
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_gpu_name(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_gpu_name(stdout: &str) -> Option<String> {
+    stdout
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gpu_name() {
+        assert_eq!(
+            parse_gpu_name("NVIDIA GeForce RTX 4090\n").as_deref(),
+            Some("NVIDIA GeForce RTX 4090")
+        );
+        assert_eq!(parse_gpu_name("\n  \n"), None);
+    }
+
+    #[test]
+    fn test_host_info_outputs() {
+        let mut node = HostInfoNode::new();
+        let outputs = node
+            .execute(&HashMap::new(), &ExecutionContext::default())
+            .unwrap();
+        assert!(
+            matches!(outputs.get("version"), Some(PortData::Str(v)) if v == env!("CARGO_PKG_VERSION"))
+        );
+        assert!(matches!(outputs.get("hostname"), Some(PortData::Str(_))));
+        assert!(matches!(outputs.get("gpu_name"), Some(PortData::Str(_))));
+
+        let inputs = HashMap::from([("device_id".to_string(), PortData::Int(-1))]);
+        assert!(node.execute(&inputs, &ExecutionContext::default()).is_err());
+    }
+}
@@ -1,15 +1,39 @@
+//! HttpRequest node: sends one HTTP request and returns the response.
+//!
+//! Headers come from `headers_json` text and the `headers` Json port, and
+//! the body from `body` text or the `json_body` Json port. `auth` adds a
+//! bearer or basic `Authorization` header from the stored secret named by
+//! `auth_secret`; like Jellyfin servers, the secret is looked up by
+//! [`resolve_auth_secrets`] on the copy of the workflow handed to the
+//! executor, so it never appears in saved workflows. A secret that wasn't
+//! filled in that way is read from the job's secret store at run time.
+//! Requests go through the shared client from `NodeServices`. Transport
+//! errors and the statuses in `retry_on_status` are retried with a linear
+//! backoff, which a cancelled job cuts short.
+
 use std::collections::HashMap;
 use std::io::Read;
 use std::time::Duration;
 
 use anyhow::{anyhow, bail, Context, Result};
-use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
 use reqwest::Method;
 use url::Url;
 
-use crate::node::{ExecutionContext, Node, PortDefinition};
+use crate::graph::PipelineGraph;
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
+use crate::nodes::remote_storage::{REMOTE_FETCH_NODE_TYPE, REMOTE_UPLOAD_NODE_TYPE};
+use crate::secrets::SecretStore;
+use crate::services::NodeServices;
 use crate::types::{PortData, PortType};
 
+pub const HTTP_REQUEST_NODE_TYPE: &str = "HttpRequest";
+
+/// Input that [`resolve_auth_secrets`] fills with the value of `auth_secret`.
+pub const AUTH_CREDENTIALS_PARAM: &str = "auth_credentials";
+
 pub struct HttpRequestNode;
 
 const DEFAULT_TIMEOUT_MS: i64 = 30_000;
@@ -28,7 +52,7 @@ const DEFAULT_MAX_RESPONSE_BYTES: i64 = 1_048_576;
 const MIN_MAX_RESPONSE_BYTES: i64 = 1;
 const MAX_MAX_RESPONSE_BYTES: i64 = 16_777_216;
 
-const CONNECT_TIMEOUT_CAP_MS: i64 = 15_000;
+const DEFAULT_RETRY_ON_STATUS: &str = "429,502,503,504";
 
 impl HttpRequestNode {
     pub fn new() -> Self {
@@ -44,7 +68,11 @@ impl Default for HttpRequestNode {
 
 impl Node for HttpRequestNode {
     fn node_type(&self) -> &str {
-        "HttpRequest"
+        HTTP_REQUEST_NODE_TYPE
+    }
+
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Network
     }
 
     fn input_ports(&self) -> Vec<PortDefinition> {
@@ -67,12 +95,42 @@ impl Node for HttpRequestNode {
                 required: false,
                 default_value: Some(serde_json::json!("{}")),
             },
+            PortDefinition {
+                name: "headers".to_string(),
+                port_type: PortType::Json,
+                required: false,
+                default_value: None,
+            },
             PortDefinition {
                 name: "body".to_string(),
                 port_type: PortType::Str,
                 required: false,
                 default_value: Some(serde_json::json!("")),
             },
+            PortDefinition {
+                name: "json_body".to_string(),
+                port_type: PortType::Json,
+                required: false,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "auth".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("none")),
+            },
+            PortDefinition {
+                name: "auth_secret".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("")),
+            },
+            PortDefinition {
+                name: AUTH_CREDENTIALS_PARAM.to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: None,
+            },
             PortDefinition {
                 name: "timeout_ms".to_string(),
                 port_type: PortType::Int,
@@ -91,6 +149,12 @@ impl Node for HttpRequestNode {
                 required: false,
                 default_value: Some(serde_json::json!(DEFAULT_RETRY_BACKOFF_MS)),
             },
+            PortDefinition {
+                name: "retry_on_status".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!(DEFAULT_RETRY_ON_STATUS)),
+            },
             PortDefinition {
                 name: "max_response_bytes".to_string(),
                 port_type: PortType::Int,
@@ -132,13 +196,19 @@ impl Node for HttpRequestNode {
                 required: true,
                 default_value: None,
             },
+            PortDefinition {
+                name: "response_json".to_string(),
+                port_type: PortType::Json,
+                required: true,
+                default_value: None,
+            },
         ]
     }
 
     fn execute(
         &mut self,
         inputs: &HashMap<String, PortData>,
-        _ctx: &ExecutionContext,
+        ctx: &ExecutionContext,
     ) -> Result<HashMap<String, PortData>> {
         let method = parse_method(inputs)?;
         let raw_url = parse_required_str(inputs, "url")?;
@@ -147,14 +217,43 @@ impl Node for HttpRequestNode {
 
         let headers_json = parse_optional_str(inputs, "headers_json", "{}");
         let headers_json_context = sanitize_headers_json_for_context(headers_json.as_str());
-        let headers = parse_headers_json(headers_json.as_str()).with_context(|| {
+        let mut headers = parse_headers_json(headers_json.as_str()).with_context(|| {
             format!(
                 "HttpRequest invalid headers_json for {}: {}",
                 redacted_url, headers_json_context
             )
         })?;
+        match inputs.get("headers") {
+            None | Some(PortData::Json(serde_json::Value::Null)) => {}
+            Some(PortData::Json(value)) => {
+                let extra = parse_header_object(value, "headers")
+                    .with_context(|| format!("HttpRequest invalid headers for {}", redacted_url))?;
+                headers.extend(extra);
+            }
+            Some(_) => bail!("HttpRequest input 'headers' must be Json"),
+        }
+        if let Some(authorization) = parse_auth(inputs, &ctx.services)? {
+            headers.insert(AUTHORIZATION, authorization);
+        }
 
-        let body = parse_optional_str(inputs, "body", "");
+        let mut body = parse_optional_str(inputs, "body", "");
+        let mut body_context = body.clone();
+        match inputs.get("json_body") {
+            None | Some(PortData::Json(serde_json::Value::Null)) => {}
+            Some(PortData::Json(value)) => {
+                if !body.is_empty() {
+                    bail!("HttpRequest takes either body or json_body, not both");
+                }
+                body = value.to_string();
+                let mut redacted = value.clone();
+                crate::logging::redact_json_value(&mut redacted);
+                body_context = redacted.to_string();
+                if !headers.contains_key(CONTENT_TYPE) {
+                    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+                }
+            }
+            Some(_) => bail!("HttpRequest input 'json_body' must be Json"),
+        }
         let timeout_ms = parse_clamped_i64(
             inputs,
             "timeout_ms",
@@ -176,6 +275,11 @@ impl Node for HttpRequestNode {
             MIN_RETRY_BACKOFF_MS,
             MAX_RETRY_BACKOFF_MS,
         );
+        let retry_on_status = parse_status_list(&parse_optional_str(
+            inputs,
+            "retry_on_status",
+            DEFAULT_RETRY_ON_STATUS,
+        ))?;
         let max_response_bytes = parse_clamped_i64(
             inputs,
             "max_response_bytes",
@@ -185,13 +289,7 @@ impl Node for HttpRequestNode {
         ) as usize;
 
         let request_timeout = Duration::from_millis(timeout_ms as u64);
-        let connect_timeout = Duration::from_millis(timeout_ms.min(CONNECT_TIMEOUT_CAP_MS) as u64);
-
-        let client = reqwest::blocking::Client::builder()
-            .connect_timeout(connect_timeout)
-            .timeout(request_timeout)
-            .build()
-            .context("failed to build HTTP client for HttpRequest")?;
+        let client = ctx.services.http_client()?;
 
         let max_attempts = (max_retries as usize).saturating_add(1);
         let request_context = sanitized_context(format!(
@@ -199,24 +297,40 @@ impl Node for HttpRequestNode {
             method.as_str(),
             redacted_url,
             headers_json_context,
-            body
+            body_context
         ));
+        let backoff = |attempt: usize| {
+            let delay_ms = (retry_backoff_ms as u64).saturating_mul(attempt as u64);
+            ctx.services.sleep(Duration::from_millis(delay_ms))
+        };
 
         for attempt in 1..=max_attempts {
+            ctx.services.check_cancelled()?;
             match execute_once(
-                &client,
-                method.clone(),
-                &url,
+                client
+                    .request(method.clone(), url.as_str())
+                    .timeout(request_timeout),
                 headers.clone(),
                 body.clone(),
                 max_response_bytes,
                 &request_context,
             ) {
-                Ok(outputs) => return Ok(outputs),
+                Ok(outputs) => {
+                    // The last attempt returns its response whatever the
+                    // status, so the workflow can still branch on `ok`.
+                    let status = match outputs.get("status_code") {
+                        Some(PortData::Int(status)) => *status,
+                        _ => 0,
+                    };
+                    if attempt < max_attempts && retry_on_status.contains(&status) {
+                        backoff(attempt)?;
+                        continue;
+                    }
+                    return Ok(outputs);
+                }
                 Err(attempt_error) => {
                     if attempt_error.retryable && attempt < max_attempts {
-                        let delay_ms = (retry_backoff_ms as u64).saturating_mul(attempt as u64);
-                        std::thread::sleep(Duration::from_millis(delay_ms));
+                        backoff(attempt)?;
                         continue;
                     }
 
@@ -243,15 +357,12 @@ impl Node for HttpRequestNode {
 }
 
 fn execute_once(
-    client: &reqwest::blocking::Client,
-    method: Method,
-    url: &Url,
+    mut request: reqwest::blocking::RequestBuilder,
     headers: HeaderMap,
     body: String,
     max_response_bytes: usize,
     request_context: &str,
 ) -> std::result::Result<HashMap<String, PortData>, RequestAttemptError> {
-    let mut request = client.request(method, url.as_str());
     if !headers.is_empty() {
         request = request.headers(headers);
     }
@@ -292,6 +403,8 @@ fn execute_once(
             RequestAttemptError::fatal(wrapped)
         })?;
 
+    // Null when the body isn't JSON, so the port always has a value.
+    let response_json = serde_json::from_str(&response_body).unwrap_or(serde_json::Value::Null);
     let outputs = HashMap::from([
         (
             "status_code".to_string(),
@@ -301,6 +414,7 @@ fn execute_once(
         ("response_body".to_string(), PortData::Str(response_body)),
         ("response_url".to_string(), PortData::Str(response_url)),
         ("content_type".to_string(), PortData::Str(content_type)),
+        ("response_json".to_string(), PortData::Json(response_json)),
     ]);
 
     Ok(outputs)
@@ -358,10 +472,13 @@ fn parse_http_url(raw: &str) -> Result<Url> {
 fn parse_headers_json(raw: &str) -> Result<HeaderMap> {
     let parsed: serde_json::Value = serde_json::from_str(raw)
         .with_context(|| sanitized_context(format!("headers_json is not valid JSON: {raw}")))?;
+    parse_header_object(&parsed, "headers_json")
+}
 
+fn parse_header_object(parsed: &serde_json::Value, port: &str) -> Result<HeaderMap> {
     let object = parsed
         .as_object()
-        .ok_or_else(|| anyhow!("headers_json must be a JSON object"))?;
+        .ok_or_else(|| anyhow!("{port} must be a JSON object"))?;
 
     let mut headers = HeaderMap::new();
     for (key, value) in object {
@@ -379,6 +496,95 @@ fn parse_headers_json(raw: &str) -> Result<HeaderMap> {
     Ok(headers)
 }
 
+/// The `Authorization` header for `auth`, built from the resolved
+/// `auth_credentials` (or the `auth_secret` read from `services`): the
+/// token for `bearer`, `username:password` for `basic`.
+fn parse_auth(
+    inputs: &HashMap<String, PortData>,
+    services: &NodeServices,
+) -> Result<Option<HeaderValue>> {
+    let auth = parse_optional_str(inputs, "auth", "none")
+        .trim()
+        .to_ascii_lowercase();
+    if auth.is_empty() || auth == "none" {
+        return Ok(None);
+    }
+    if auth != "bearer" && auth != "basic" {
+        bail!("HttpRequest unsupported auth '{auth}' (expected none/bearer/basic)");
+    }
+
+    let mut credentials = parse_optional_str(inputs, AUTH_CREDENTIALS_PARAM, "");
+    if credentials.is_empty() {
+        let secret = parse_optional_str(inputs, "auth_secret", "");
+        let secret = secret.trim();
+        if secret.is_empty() {
+            bail!("HttpRequest auth '{auth}' needs auth_secret");
+        }
+        credentials = services.secret(secret).with_context(|| {
+            format!("HttpRequest auth secret '{secret}' was not resolved from the secret store")
+        })?;
+    }
+
+    let value = if auth == "bearer" {
+        format!("Bearer {}", credentials.trim())
+    } else {
+        if !credentials.contains(':') {
+            bail!("HttpRequest basic auth secret must be 'username:password'");
+        }
+        format!("Basic {}", BASE64.encode(credentials))
+    };
+    let mut value = HeaderValue::from_str(&value)
+        .map_err(|_| anyhow!("HttpRequest auth secret is not a valid header value"))?;
+    value.set_sensitive(true);
+    Ok(Some(value))
+}
+
+fn parse_status_list(raw: &str) -> Result<Vec<i64>> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|code| !code.is_empty())
+        .map(|code| match code.parse::<i64>() {
+            Ok(status) if (100..=599).contains(&status) => Ok(status),
+            _ => bail!("HttpRequest invalid retry_on_status entry '{code}'"),
+        })
+        .collect()
+}
+
+/// Node types whose `auth_secret` is resolved by [`resolve_auth_secrets`].
+const AUTH_SECRET_NODE_TYPES: &[&str] = &[
+    HTTP_REQUEST_NODE_TYPE,
+    REMOTE_FETCH_NODE_TYPE,
+    REMOTE_UPLOAD_NODE_TYPE,
+];
+
+/// Fill `auth_credentials` of every `HttpRequest`, `RemoteFetch` and
+/// `RemoteUpload` node that names an `auth_secret`. Like secret references,
+/// this only runs on the copy handed to the executor.
+pub fn resolve_auth_secrets(graph: &mut PipelineGraph, secrets: &SecretStore) -> Result<()> {
+    for node in graph.nodes_mut() {
+        if !AUTH_SECRET_NODE_TYPES.contains(&node.node_type.as_str()) {
+            continue;
+        }
+        let Some(name) = node
+            .params
+            .get("auth_secret")
+            .and_then(|value| value.as_str())
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        else {
+            continue;
+        };
+        let value = secrets
+            .get(name)?
+            .with_context(|| format!("auth secret '{name}' of node '{}' is not set", node.id))?;
+        node.params.insert(
+            AUTH_CREDENTIALS_PARAM.to_string(),
+            serde_json::Value::String(value),
+        );
+    }
+    Ok(())
+}
+
 fn read_response_body_limited(
     response: &mut reqwest::blocking::Response,
     max_response_bytes: usize,
@@ -474,6 +680,8 @@ mod tests {
     use std::net::{TcpListener, TcpStream};
     use std::thread;
 
+    use crate::nodes::test_http::spawn_recording_server;
+
     fn spawn_single_response_server(raw_response: String) -> (String, thread::JoinHandle<()>) {
         let listener = TcpListener::bind("127.0.0.1:0").expect("bind test server");
         let addr = listener.local_addr().expect("local addr");
@@ -530,14 +738,14 @@ mod tests {
         assert_eq!(node.node_type(), "HttpRequest");
 
         let input_ports = node.input_ports();
-        assert_eq!(input_ports.len(), 8);
+        assert_eq!(input_ports.len(), 14);
         assert_eq!(input_ports[0].name, "method");
         assert_eq!(input_ports[0].port_type, PortType::Str);
         assert_eq!(input_ports[1].name, "url");
         assert_eq!(input_ports[1].port_type, PortType::Str);
 
         let output_ports = node.output_ports();
-        assert_eq!(output_ports.len(), 5);
+        assert_eq!(output_ports.len(), 6);
         assert_eq!(output_ports[0].name, "status_code");
         assert_eq!(output_ports[0].port_type, PortType::Int);
         assert_eq!(output_ports[1].name, "ok");
@@ -548,6 +756,8 @@ mod tests {
         assert_eq!(output_ports[3].port_type, PortType::Str);
         assert_eq!(output_ports[4].name, "content_type");
         assert_eq!(output_ports[4].port_type, PortType::Str);
+        assert_eq!(output_ports[5].name, "response_json");
+        assert_eq!(output_ports[5].port_type, PortType::Json);
     }
 
     #[test]
@@ -758,4 +968,171 @@ mod tests {
             "error should redact URL secrets: {msg}"
         );
     }
+
+    #[test]
+    fn test_http_request_auth_json_body_and_headers() {
+        let response = "HTTP/1.1 201 Created\r\nContent-Type: application/json\r\nContent-Length: 10\r\nConnection: close\r\n\r\n{\"id\": 42}".to_string();
+        let (addr, server_handle) = spawn_recording_server(vec![response]);
+        let base_url = format!("http://{addr}");
+
+        let inputs = HashMap::from([
+            ("method".to_string(), PortData::Str("POST".to_string())),
+            ("url".to_string(), PortData::Str(format!("{base_url}/jobs"))),
+            (
+                "headers".to_string(),
+                PortData::Json(serde_json::json!({"X-Source": "videnoa"})),
+            ),
+            (
+                "json_body".to_string(),
+                PortData::Json(serde_json::json!({"file": "ep01.mkv"})),
+            ),
+            ("auth".to_string(), PortData::Str("bearer".to_string())),
+            ("auth_secret".to_string(), PortData::Str("hook".to_string())),
+            (
+                AUTH_CREDENTIALS_PARAM.to_string(),
+                PortData::Str("tok-123".to_string()),
+            ),
+        ]);
+
+        let outputs = run_node_with_inputs(inputs).expect("request should succeed");
+        let requests = server_handle.join().expect("server thread join");
+        let request = requests[0].to_ascii_lowercase();
+
+        assert!(request.starts_with("post /jobs"), "request: {request}");
+        assert!(request.contains("authorization: bearer tok-123"));
+        assert!(request.contains("x-source: videnoa"));
+        assert!(request.contains("content-type: application/json"));
+        assert!(request.ends_with(r#"{"file":"ep01.mkv"}"#));
+        assert_eq!(expect_int(&outputs, "status_code"), 201);
+        assert!(matches!(
+            outputs.get("response_json"),
+            Some(PortData::Json(json)) if json["id"] == 42
+        ));
+    }
+
+    #[test]
+    fn test_http_request_basic_auth_and_auth_errors() {
+        let inputs = |auth: &str, credentials: Option<&str>| {
+            let mut inputs = HashMap::from([
+                (
+                    "url".to_string(),
+                    PortData::Str("http://127.0.0.1:1/".to_string()),
+                ),
+                ("auth".to_string(), PortData::Str(auth.to_string())),
+                ("auth_secret".to_string(), PortData::Str("hook".to_string())),
+            ]);
+            if let Some(credentials) = credentials {
+                inputs.insert(
+                    AUTH_CREDENTIALS_PARAM.to_string(),
+                    PortData::Str(credentials.to_string()),
+                );
+            }
+            inputs
+        };
+
+        let header = parse_auth(
+            &inputs("basic", Some("user:pass")),
+            &NodeServices::default(),
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(header.to_str().unwrap(), "Basic dXNlcjpwYXNz");
+        assert!(header.is_sensitive());
+
+        // An unfilled secret is read from the job's secret store.
+        struct Store;
+        impl crate::services::SecretResolver for Store {
+            fn resolve_secret(&self, name: &str) -> Result<Option<String>> {
+                Ok((name == "hook").then(|| "from-store".to_string()))
+            }
+        }
+        let services = NodeServices::default().with_secrets(std::sync::Arc::new(Store));
+        let header = parse_auth(&inputs("bearer", None), &services)
+            .unwrap()
+            .unwrap();
+        assert_eq!(header.to_str().unwrap(), "Bearer from-store");
+
+        let err = run_node_with_inputs(inputs("bearer", None))
+            .err()
+            .expect("unresolved secret should fail");
+        assert_eq!(
+            err.to_string(),
+            "HttpRequest auth secret 'hook' was not resolved from the secret store"
+        );
+        let err = run_node_with_inputs(inputs("basic", Some("no-colon")))
+            .err()
+            .expect("basic auth needs a colon");
+        assert!(err.to_string().contains("username:password"), "{err}");
+        let err = run_node_with_inputs(inputs("digest", Some("x")))
+            .err()
+            .expect("unknown auth should fail");
+        assert!(
+            err.to_string().contains("unsupported auth 'digest'"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_http_request_retries_listed_statuses() {
+        let unavailable =
+            "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                .to_string();
+        let ok = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nOK".to_string();
+        let (addr, server_handle) = spawn_recording_server(vec![unavailable.clone(), ok]);
+        let base_url = format!("http://{addr}");
+
+        let inputs = HashMap::from([
+            ("url".to_string(), PortData::Str(base_url.clone())),
+            ("retry_backoff_ms".to_string(), PortData::Int(0)),
+        ]);
+        let outputs = run_node_with_inputs(inputs).expect("retry should succeed");
+        assert_eq!(server_handle.join().expect("server thread join").len(), 2);
+        assert_eq!(expect_int(&outputs, "status_code"), 200);
+
+        // Once retries run out, the last response is returned as is.
+        let (addr, server_handle) = spawn_recording_server(vec![unavailable]);
+        let base_url = format!("http://{addr}");
+        let inputs = HashMap::from([
+            ("url".to_string(), PortData::Str(base_url)),
+            ("max_retries".to_string(), PortData::Int(0)),
+        ]);
+        let outputs = run_node_with_inputs(inputs).expect("503 should return outputs");
+        server_handle.join().expect("server thread join");
+        assert_eq!(expect_int(&outputs, "status_code"), 503);
+        assert!(!expect_bool(&outputs, "ok"));
+
+        assert!(parse_status_list("429, 503").is_ok());
+        assert!(parse_status_list("").unwrap().is_empty());
+        assert!(parse_status_list("5xx").is_err());
+    }
+
+    #[test]
+    fn test_resolve_auth_secrets_fills_credentials() {
+        use crate::graph::NodeInstance;
+
+        let dir = tempfile::tempdir().unwrap();
+        let secrets = SecretStore::new(dir.path());
+        secrets.put("hook", "tok-123").unwrap();
+
+        let node = |id: &str, secret: &str| NodeInstance {
+            id: id.to_string(),
+            node_type: HTTP_REQUEST_NODE_TYPE.to_string(),
+            params: HashMap::from([("auth_secret".to_string(), serde_json::json!(secret))]),
+        };
+        let mut graph = PipelineGraph::new();
+        let notify = graph.add_node(node("notify", "hook")).unwrap();
+        let plain = graph.add_node(node("plain", "")).unwrap();
+
+        resolve_auth_secrets(&mut graph, &secrets).unwrap();
+        assert_eq!(graph.node(notify).params[AUTH_CREDENTIALS_PARAM], "tok-123");
+        assert!(!graph
+            .node(plain)
+            .params
+            .contains_key(AUTH_CREDENTIALS_PARAM));
+
+        let mut graph = PipelineGraph::new();
+        graph.add_node(node("notify", "missing")).unwrap();
+        let err = resolve_auth_secrets(&mut graph, &secrets).unwrap_err();
+        assert!(err.to_string().contains("'missing'"), "{err}");
+    }
 }
@@ -3,7 +3,8 @@ use std::collections::HashMap;
 use anyhow::{bail, Context, Result};
 use url::Url;
 
-use crate::node::{ExecutionContext, Node, PortDefinition};
+use crate::jellyfin::SERVER_PARAM;
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
 use crate::types::{PortData, PortType};
 
 pub struct JellyfinVideoNode;
@@ -26,7 +27,24 @@ impl JellyfinVideoNode {
         Ok(value.to_string())
     }
 
-    fn build_download_url(base_url: &str, item_id: &str, api_key: &str) -> Result<Url> {
+    /// Checks that a `server` reference was resolved into a URL and API key
+    /// before execution; that happens in the server and the CLI, which have
+    /// the config and the secret store.
+    fn check_server_resolved(inputs: &HashMap<String, PortData>) -> Result<()> {
+        let server = match inputs.get(SERVER_PARAM) {
+            Some(PortData::Str(s)) if !s.trim().is_empty() => s.trim(),
+            _ => return Ok(()),
+        };
+        let resolved = ["jellyfin_url", "api_key"].iter().all(
+            |name| matches!(inputs.get(*name), Some(PortData::Str(s)) if !s.trim().is_empty()),
+        );
+        if !resolved {
+            bail!("Jellyfin server '{server}' was not resolved from the config");
+        }
+        Ok(())
+    }
+
+    pub(crate) fn build_download_url(base_url: &str, item_id: &str, api_key: &str) -> Result<Url> {
         let mut url = Url::parse(base_url).context("invalid Jellyfin base URL")?;
 
         {
@@ -50,18 +68,28 @@ impl Node for JellyfinVideoNode {
         "jellyfin_video"
     }
 
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Network
+    }
+
     fn input_ports(&self) -> Vec<PortDefinition> {
         vec![
+            PortDefinition {
+                name: SERVER_PARAM.to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: None,
+            },
             PortDefinition {
                 name: "jellyfin_url".to_string(),
                 port_type: PortType::Str,
-                required: true,
+                required: false,
                 default_value: None,
             },
             PortDefinition {
                 name: "api_key".to_string(),
                 port_type: PortType::Str,
-                required: true,
+                required: false,
                 default_value: None,
             },
             PortDefinition {
@@ -70,6 +98,26 @@ impl Node for JellyfinVideoNode {
                 required: true,
                 default_value: None,
             },
+            // Skip rules are checked by the server before the job starts,
+            // see `jellyfin::skip_reason`.
+            PortDefinition {
+                name: "skip_min_width".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(0)),
+            },
+            PortDefinition {
+                name: "skip_min_height".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(0)),
+            },
+            PortDefinition {
+                name: "skip_codecs".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("")),
+            },
         ]
     }
 
@@ -87,6 +135,7 @@ impl Node for JellyfinVideoNode {
         inputs: &HashMap<String, PortData>,
         _ctx: &ExecutionContext,
     ) -> Result<HashMap<String, PortData>> {
+        Self::check_server_resolved(inputs)?;
         let jellyfin_url = Self::required_str(inputs, "jellyfin_url")?;
         let api_key = Self::required_str(inputs, "api_key")?;
         let item_id = Self::required_str(inputs, "item_id")?;
@@ -124,12 +173,16 @@ mod tests {
         assert_eq!(node.node_type(), "jellyfin_video");
 
         let inputs = node.input_ports();
-        assert_eq!(inputs.len(), 3);
-        assert_eq!(inputs[0].name, "jellyfin_url");
-        assert_eq!(inputs[0].port_type, PortType::Str);
-        assert!(inputs[0].required);
-        assert_eq!(inputs[1].name, "api_key");
-        assert_eq!(inputs[2].name, "item_id");
+        assert_eq!(inputs.len(), 7);
+        assert_eq!(inputs[0].name, "server");
+        assert!(!inputs[0].required);
+        assert_eq!(inputs[1].name, "jellyfin_url");
+        assert_eq!(inputs[1].port_type, PortType::Str);
+        assert!(!inputs[1].required);
+        assert_eq!(inputs[2].name, "api_key");
+        assert_eq!(inputs[3].name, "item_id");
+        assert!(inputs[3].required);
+        assert!(inputs[4..].iter().all(|port| !port.required));
 
         let outputs = node.output_ports();
         assert_eq!(outputs.len(), 1);
@@ -196,6 +249,21 @@ mod tests {
         assert!(err.to_string().contains("item_id"));
     }
 
+    #[test]
+    fn test_execute_rejects_unresolved_server() {
+        let mut node = JellyfinVideoNode;
+        let ctx = ExecutionContext::default();
+        let mut inputs = HashMap::new();
+        inputs.insert("server".to_string(), PortData::Str("anime".to_string()));
+        inputs.insert("item_id".to_string(), PortData::Str("abc123".to_string()));
+
+        let err = node
+            .execute(&inputs, &ctx)
+            .err()
+            .expect("unresolved server should fail");
+        assert!(err.to_string().contains("'anime' was not resolved"));
+    }
+
     #[test]
     fn test_execute_rejects_invalid_base_url() {
         let mut node = JellyfinVideoNode;
@@ -0,0 +1,269 @@
+//! JsonExtract node: reads one value out of a Json input by path.
+//!
+//! Paths are dot-separated keys with `[n]` for array items, e.g.
+//! `streams[0].width`. An empty path selects the whole input. The value is
+//! converted to `output_type`; `Str` takes strings as they are and writes
+//! anything else as JSON text.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
+use crate::types::{PortData, PortType};
+
+pub const JSON_EXTRACT_NODE_TYPE: &str = "JsonExtract";
+
+pub struct JsonExtractNode {
+    output_type: PortType,
+}
+
+impl JsonExtractNode {
+    pub fn new() -> Self {
+        Self {
+            output_type: PortType::Json,
+        }
+    }
+
+    pub fn from_params(params: &HashMap<String, serde_json::Value>) -> Result<Self> {
+        let output_type = match params.get("output_type") {
+            None => PortType::Json,
+            Some(serde_json::Value::String(raw)) => parse_output_type(raw)?,
+            Some(_) => bail!("JsonExtract: param 'output_type' must be a string type name"),
+        };
+        Ok(Self { output_type })
+    }
+}
+
+impl Default for JsonExtractNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node for JsonExtractNode {
+    fn node_type(&self) -> &str {
+        JSON_EXTRACT_NODE_TYPE
+    }
+
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
+    fn input_ports(&self) -> Vec<PortDefinition> {
+        vec![
+            PortDefinition {
+                name: "json".to_string(),
+                port_type: PortType::Json,
+                required: true,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "path".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("")),
+            },
+            PortDefinition {
+                name: "output_type".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!(output_type_name(&self.output_type))),
+            },
+        ]
+    }
+
+    fn output_ports(&self) -> Vec<PortDefinition> {
+        vec![PortDefinition {
+            name: "value".to_string(),
+            port_type: self.output_type.clone(),
+            required: true,
+            default_value: None,
+        }]
+    }
+
+    fn execute(
+        &mut self,
+        inputs: &HashMap<String, PortData>,
+        _ctx: &ExecutionContext,
+    ) -> Result<HashMap<String, PortData>> {
+        if let Some(raw) = inputs.get("output_type") {
+            let PortData::Str(raw) = raw else {
+                bail!("JsonExtract: input 'output_type' must be Str");
+            };
+            self.output_type = parse_output_type(raw)?;
+        }
+        let json = match inputs.get("json") {
+            Some(PortData::Json(json)) => json,
+            Some(_) => bail!("JsonExtract: input 'json' must be Json"),
+            None => bail!("JsonExtract: input 'json' is required"),
+        };
+        let path = match inputs.get("path") {
+            Some(PortData::Str(path)) => path.as_str(),
+            Some(_) => bail!("JsonExtract: input 'path' must be Str"),
+            None => "",
+        };
+
+        let value = extract_json_path(json, path)?
+            .ok_or_else(|| anyhow!("JsonExtract: no value at path '{path}'"))?;
+        let converted = convert_json(value, &self.output_type)
+            .map_err(|err| anyhow!("JsonExtract: value at path '{path}' {err}"))?;
+        Ok(HashMap::from([("value".to_string(), converted)]))
+    }
+}
+
+/// The value at `path` in `json`, or `None` when a key or index is missing.
+/// Fails only when the path itself is malformed.
+pub fn extract_json_path<'a>(
+    json: &'a serde_json::Value,
+    path: &str,
+) -> Result<Option<&'a serde_json::Value>> {
+    let mut current = json;
+    let path = path.trim();
+    if path.is_empty() {
+        return Ok(Some(current));
+    }
+    for segment in path.split('.') {
+        let (key, indices) = match segment.find('[') {
+            Some(start) => (&segment[..start], &segment[start..]),
+            None => (segment, ""),
+        };
+        if !key.is_empty() {
+            let Some(next) = current.get(key) else {
+                return Ok(None);
+            };
+            current = next;
+        } else if indices.is_empty() {
+            bail!("JsonExtract: empty key in path '{path}'");
+        }
+
+        let mut rest = indices;
+        while !rest.is_empty() {
+            let close = rest
+                .find(']')
+                .filter(|_| rest.starts_with('['))
+                .ok_or_else(|| anyhow!("JsonExtract: malformed index in path '{path}'"))?;
+            let index: usize = rest[1..close]
+                .trim()
+                .parse()
+                .map_err(|_| anyhow!("JsonExtract: malformed index in path '{path}'"))?;
+            let Some(next) = current.get(index) else {
+                return Ok(None);
+            };
+            current = next;
+            rest = &rest[close + 1..];
+        }
+    }
+    Ok(Some(current))
+}
+
+fn convert_json(value: &serde_json::Value, output_type: &PortType) -> Result<PortData> {
+    let mismatch = || anyhow!("is not {}: {value}", output_type_name(output_type));
+    Ok(match output_type {
+        PortType::Int => PortData::Int(value.as_i64().ok_or_else(mismatch)?),
+        PortType::Float => PortData::Float(value.as_f64().ok_or_else(mismatch)?),
+        PortType::Bool => PortData::Bool(value.as_bool().ok_or_else(mismatch)?),
+        PortType::Str => PortData::Str(match value {
+            serde_json::Value::String(text) => text.clone(),
+            other => other.to_string(),
+        }),
+        PortType::Path => PortData::Path(PathBuf::from(value.as_str().ok_or_else(mismatch)?)),
+        _ => PortData::Json(value.clone()),
+    })
+}
+
+fn parse_output_type(raw: &str) -> Result<PortType> {
+    match raw {
+        "Int" => Ok(PortType::Int),
+        "Float" => Ok(PortType::Float),
+        "Str" => Ok(PortType::Str),
+        "Bool" => Ok(PortType::Bool),
+        "Path" => Ok(PortType::Path),
+        "Json" => Ok(PortType::Json),
+        other => bail!(
+            "JsonExtract: unsupported output_type '{other}', expected one of Int|Float|Str|Bool|Path|Json"
+        ),
+    }
+}
+
+fn output_type_name(port_type: &PortType) -> &'static str {
+    match port_type {
+        PortType::Int => "Int",
+        PortType::Float => "Float",
+        PortType::Str => "Str",
+        PortType::Bool => "Bool",
+        PortType::Path => "Path",
+        _ => "Json",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(
+        json: serde_json::Value,
+        path: &str,
+        output_type: &str,
+    ) -> Result<HashMap<String, PortData>> {
+        let params = HashMap::from([("output_type".to_string(), serde_json::json!(output_type))]);
+        let mut node = JsonExtractNode::from_params(&params)?;
+        let inputs = HashMap::from([
+            ("json".to_string(), PortData::Json(json)),
+            ("path".to_string(), PortData::Str(path.to_string())),
+        ]);
+        node.execute(&inputs, &ExecutionContext::default())
+    }
+
+    #[test]
+    fn test_extract_json_path() {
+        let json = serde_json::json!({
+            "streams": [{"width": 1920, "tags": {"title": "Main"}}],
+            "matrix": [[1, 2], [3, 4]]
+        });
+
+        let value = |path| extract_json_path(&json, path).unwrap().cloned();
+        assert_eq!(value("streams[0].width"), Some(serde_json::json!(1920)));
+        assert_eq!(
+            value("streams[0].tags.title"),
+            Some(serde_json::json!("Main"))
+        );
+        assert_eq!(value("matrix[1][0]"), Some(serde_json::json!(3)));
+        assert_eq!(value(""), Some(json.clone()));
+        assert_eq!(value("streams[1].width"), None);
+        assert_eq!(value("format.duration"), None);
+        assert!(extract_json_path(&json, "streams[x]").is_err());
+        assert!(extract_json_path(&json, "streams..width").is_err());
+    }
+
+    #[test]
+    fn test_json_extract_converts_to_output_type() {
+        let json = serde_json::json!({"width": 1920, "name": "ep01", "hdr": false});
+
+        let outputs = run(json.clone(), "width", "Int").unwrap();
+        assert!(matches!(outputs.get("value"), Some(PortData::Int(1920))));
+        let outputs = run(json.clone(), "width", "Str").unwrap();
+        assert!(matches!(outputs.get("value"), Some(PortData::Str(v)) if v == "1920"));
+        let outputs = run(json.clone(), "name", "Str").unwrap();
+        assert!(matches!(outputs.get("value"), Some(PortData::Str(v)) if v == "ep01"));
+        let outputs = run(json.clone(), "", "Json").unwrap();
+        assert!(matches!(outputs.get("value"), Some(PortData::Json(v)) if *v == json));
+
+        let Err(err) = run(json.clone(), "name", "Int") else {
+            panic!("a string is not an Int");
+        };
+        assert!(err.to_string().contains("is not Int"), "got: {err}");
+        let Err(err) = run(json, "missing", "Json") else {
+            panic!("a missing key should fail");
+        };
+        assert!(err.to_string().contains("no value at path 'missing'"));
+
+        let node = JsonExtractNode::from_params(&HashMap::from([(
+            "output_type".to_string(),
+            serde_json::json!("Bool"),
+        )]))
+        .unwrap();
+        assert_eq!(node.output_ports()[0].port_type, PortType::Bool);
+    }
+}
@@ -1,22 +1,45 @@
+pub mod analyze_source;
 pub mod backend;
 pub mod color_space;
+pub mod comparison;
 pub mod compile_context;
+pub mod concat;
 pub mod constant;
+pub mod date_time;
 pub mod downloader;
+pub mod expression;
+pub mod external_command;
+pub mod ffmpeg_filter;
 pub mod frame_interpolation;
+pub mod grain_synthesis;
+pub mod host_info;
 pub mod http_request;
 pub mod jellyfin_video;
+pub mod json_extract;
+pub mod model_inference;
+pub mod model_preprocess;
 pub mod path_divider;
 pub mod path_joiner;
+pub mod preview_tap;
 pub mod print;
+pub mod python_node;
+pub mod region_enhance;
+pub mod remote_storage;
 pub mod rescale;
 pub mod resize;
 pub mod scene_detect;
+pub mod script;
+pub mod stream_input;
 pub mod stream_output;
 pub mod string_replace;
 pub mod string_template;
 pub mod super_res;
+#[cfg(test)]
+mod test_http;
+pub mod torrent_input;
 pub mod type_conversion;
+pub mod uuid_generator;
+pub mod vapoursynth_input;
 pub mod video_input;
 pub mod video_output;
 pub mod workflow_io;
@@ -0,0 +1,760 @@
+//! ModelInference node: runs an arbitrary image-to-image ONNX model.
+//!
+//! SuperResolution and FrameInterpolation are written for specific model
+//! families. This node only relies on the graph signature from
+//! [`model_inspect`](crate::model_inspect). The first 4-D float input gets
+//! the frame (NCHW, 1 or 3 channels), and the first 4-D float output becomes
+//! the new frame. A 1-channel output is written back as grayscale. Every other
+//! model input becomes a param port named after it, and its scalar value is
+//! broadcast to the input's shape. This covers colorization, denoisers with a
+//! noise-level input and segmentation models without writing a new node.
+//! Value range, channel order and padding are set through
+//! [`ModelPreprocess`]; frames are packed in `0..1`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+
+use anyhow::{bail, Context, Result};
+use half::f16;
+use ndarray::Array4;
+use ort::value::{DynValue, Tensor};
+use tracing::debug;
+
+use crate::model_inspect::{inspect_onnx, ModelInspection, TensorInfo};
+use crate::node::{ExecutionContext, FrameProcessor, Node, PortDefinition};
+use crate::runtime::session_pool::{acquire_session, SessionLease, SharedSession};
+use crate::types::{Frame, PortData, PortType};
+
+use crate::nodes::backend::{InferenceBackend, SessionConfig};
+use crate::nodes::model_preprocess::ModelPreprocess;
+use crate::nodes::resize::{resize_bilinear, to_rgb8};
+
+pub const MODEL_INFERENCE_NODE_TYPE: &str = "ModelInference";
+
+/// Ports every `ModelInference` node has; model inputs may not reuse them.
+const STATIC_PORTS: [&str; 7] = [
+    "model_path",
+    "scale",
+    "backend",
+    "value_range",
+    "output_range",
+    "channel_order",
+    "pad_align",
+];
+/// Range frames are packed in before [`ModelPreprocess`] remaps them.
+const NATIVE_RANGE: (f32, f32) = (0.0, 1.0);
+
+/// How a model's inputs and outputs map onto the node.
+#[derive(Debug, Clone)]
+pub struct ModelIo {
+    pub image_input: TensorInfo,
+    pub image_output: TensorInfo,
+    /// Remaining model inputs, exposed as param ports.
+    pub extra_inputs: Vec<TensorInfo>,
+}
+
+impl ModelIo {
+    pub fn from_inspection(inspection: &ModelInspection) -> Result<Self> {
+        let image_input = inspection
+            .inputs
+            .iter()
+            .find(|t| is_image_tensor(t))
+            .context("ModelInference: model has no 4-D float input to feed frames into")?;
+        let image_output = inspection
+            .outputs
+            .iter()
+            .find(|t| is_image_tensor(t))
+            .context("ModelInference: model has no 4-D float output to read frames from")?;
+        for tensor in [image_input, image_output] {
+            if !matches!(tensor.shape[1], 1 | 3 | -1) {
+                bail!(
+                    "ModelInference: '{}' has {} channels, only 1 or 3 are supported",
+                    tensor.name,
+                    tensor.shape[1]
+                );
+            }
+        }
+
+        let mut extra_inputs = Vec::new();
+        for tensor in &inspection.inputs {
+            if tensor.name == image_input.name {
+                continue;
+            }
+            if scalar_port_type(&tensor.data_type).is_none() {
+                bail!(
+                    "ModelInference: input '{}' has unsupported type {}",
+                    tensor.name,
+                    tensor.data_type
+                );
+            }
+            if STATIC_PORTS.contains(&tensor.name.as_str()) {
+                bail!(
+                    "ModelInference: input '{}' clashes with a built-in param",
+                    tensor.name
+                );
+            }
+            extra_inputs.push(tensor.clone());
+        }
+
+        Ok(Self {
+            image_input: image_input.clone(),
+            image_output: image_output.clone(),
+            extra_inputs,
+        })
+    }
+
+    /// Param ports for the extra model inputs.
+    pub fn extra_ports(&self) -> Vec<PortDefinition> {
+        self.extra_inputs
+            .iter()
+            .filter_map(|t| {
+                Some(PortDefinition {
+                    name: t.name.clone(),
+                    port_type: scalar_port_type(&t.data_type)?,
+                    required: true,
+                    default_value: None,
+                })
+            })
+            .collect()
+    }
+
+    fn gray_input(&self) -> bool {
+        self.image_input.shape[1] == 1
+    }
+
+    /// `(height, width)` when the model only accepts one frame size.
+    fn fixed_input_size(&self) -> Option<(usize, usize)> {
+        let shape = &self.image_input.shape;
+        (shape[2] > 0 && shape[3] > 0).then(|| (shape[2] as usize, shape[3] as usize))
+    }
+}
+
+fn is_image_tensor(tensor: &TensorInfo) -> bool {
+    tensor.shape.len() == 4 && matches!(tensor.data_type.as_str(), "float32" | "float16")
+}
+
+fn scalar_port_type(data_type: &str) -> Option<PortType> {
+    match data_type {
+        "float32" | "float16" | "float64" => Some(PortType::Float),
+        "int32" | "int64" => Some(PortType::Int),
+        "bool" => Some(PortType::Bool),
+        _ => None,
+    }
+}
+
+/// Inspects a model once per file version. Nodes are instantiated several
+/// times per run (validation, input resolution, compilation).
+pub fn inspect_model_io(path: &Path) -> Result<Arc<ModelIo>> {
+    type Cache = Mutex<HashMap<PathBuf, (SystemTime, Arc<ModelIo>)>>;
+    static CACHE: OnceLock<Cache> = OnceLock::new();
+
+    let modified = std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .with_context(|| format!("ModelInference: cannot read {}", path.display()))?;
+    let cache = CACHE.get_or_init(Default::default);
+    if let Some((cached_at, io)) = cache.lock().unwrap().get(path) {
+        if *cached_at == modified {
+            return Ok(Arc::clone(io));
+        }
+    }
+
+    let io = Arc::new(ModelIo::from_inspection(&inspect_onnx(path)?)?);
+    cache
+        .lock()
+        .unwrap()
+        .insert(path.to_path_buf(), (modified, Arc::clone(&io)));
+    Ok(io)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ScalarValue {
+    Float(f64),
+    Int(i64),
+    Bool(bool),
+}
+
+pub struct ModelInferenceNode {
+    io: Option<Arc<ModelIo>>,
+    session: Option<SharedSession>,
+    /// Returns `session` to the session pool when dropped.
+    session_lease: Option<SessionLease>,
+    extra_values: Vec<(TensorInfo, ScalarValue)>,
+    scale: u32,
+    preprocess: ModelPreprocess,
+    trt_cache_dir: Option<PathBuf>,
+}
+
+impl ModelInferenceNode {
+    pub fn new() -> Self {
+        Self {
+            io: None,
+            session: None,
+            session_lease: None,
+            extra_values: Vec::new(),
+            scale: 1,
+            preprocess: ModelPreprocess::identity(NATIVE_RANGE, 1),
+            trt_cache_dir: None,
+        }
+    }
+
+    /// Reads the model named by `model_path` so the model's extra inputs are
+    /// known as ports. An unreadable model leaves only the built-in ports;
+    /// `execute` reports the actual error.
+    pub fn from_params(params: &HashMap<String, serde_json::Value>) -> Self {
+        let mut node = Self::new();
+        if let Some(path) = params
+            .get("model_path")
+            .and_then(|v| v.as_str())
+            .filter(|p| !p.is_empty())
+        {
+            match inspect_model_io(Path::new(path)) {
+                Ok(io) => node.io = Some(io),
+                Err(err) => {
+                    debug!(model = path, error = %err, "ModelInference: cannot inspect model")
+                }
+            }
+        }
+        node
+    }
+
+    pub fn set_trt_cache_dir(&mut self, dir: PathBuf) {
+        self.trt_cache_dir = Some(dir);
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    fn run_model(&mut self, rgb8: &[u8], width: usize, height: usize) -> Result<Vec<u8>> {
+        let io = Arc::clone(
+            self.io
+                .as_ref()
+                .context("ModelInference: model not loaded")?,
+        );
+        let mut session = self
+            .session
+            .as_ref()
+            .context("ModelInference: model not loaded — call execute() first")?
+            .lock()
+            .unwrap();
+
+        let preprocess = self.preprocess;
+        let align = preprocess.pad_align;
+        let (in_w, in_h, padded_w, padded_h) = match io.fixed_input_size() {
+            Some((fixed_h, fixed_w)) => (fixed_w, fixed_h, fixed_w, fixed_h),
+            None => (
+                width,
+                height,
+                width.div_ceil(align) * align,
+                height.div_ceil(align) * align,
+            ),
+        };
+        let resized;
+        let pixels = if (in_w, in_h) == (width, height) {
+            rgb8
+        } else {
+            resized = resize_bilinear(rgb8, width, height, in_w, in_h);
+            &resized
+        };
+
+        let mut input = frame_to_nchw(pixels, in_w, in_h, padded_w, padded_h, io.gray_input());
+        let channels = input.shape()[1];
+        if let Some(values) = input.as_slice_mut() {
+            preprocess.to_model(values, channels);
+        }
+        let mut feeds: Vec<(String, DynValue)> = Vec::with_capacity(1 + self.extra_values.len());
+        let image = if io.image_input.data_type == "float16" {
+            Tensor::from_array(input.mapv(f16::from_f32))?.into_dyn()
+        } else {
+            Tensor::from_array(input)?.into_dyn()
+        };
+        feeds.push((io.image_input.name.clone(), image));
+        for (tensor, value) in &self.extra_values {
+            feeds.push((tensor.name.clone(), scalar_tensor(tensor, *value)?));
+        }
+
+        let outputs = session.run(feeds)?;
+        let output = &outputs[io.image_output.name.as_str()];
+        let (shape, mut values): (Vec<usize>, Vec<f32>) = if io.image_output.data_type == "float16"
+        {
+            let view = output.try_extract_array::<f16>()?;
+            (
+                view.shape().to_vec(),
+                view.iter().map(|v| v.to_f32()).collect(),
+            )
+        } else {
+            let view = output.try_extract_array::<f32>()?;
+            (view.shape().to_vec(), view.iter().copied().collect())
+        };
+        if shape.len() != 4 || !matches!(shape[1], 1 | 3) {
+            bail!("ModelInference: unexpected output shape {shape:?}");
+        }
+        preprocess.from_model(&mut values, shape[1]);
+
+        // Output scale relative to the padded input; crop the padding away.
+        let (out_h, out_w) = (shape[2], shape[3]);
+        let crop_w = out_w * in_w / padded_w;
+        let crop_h = out_h * in_h / padded_h;
+        let rgb = nchw_to_rgb8(&values, shape[1], out_w, out_h, crop_w, crop_h);
+
+        let (target_w, target_h) = (width * self.scale as usize, height * self.scale as usize);
+        if (crop_w, crop_h) == (target_w, target_h) {
+            return Ok(rgb);
+        }
+        if io.fixed_input_size().is_some() {
+            return Ok(resize_bilinear(&rgb, crop_w, crop_h, target_w, target_h));
+        }
+        bail!(
+            "ModelInference: model turned {width}x{height} into {crop_w}x{crop_h}, \
+             but scale is {} — set scale to match the model",
+            self.scale
+        );
+    }
+}
+
+impl Default for ModelInferenceNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node for ModelInferenceNode {
+    fn node_type(&self) -> &str {
+        MODEL_INFERENCE_NODE_TYPE
+    }
+
+    fn input_ports(&self) -> Vec<PortDefinition> {
+        let mut ports = vec![
+            PortDefinition {
+                name: "model_path".to_string(),
+                port_type: PortType::Path,
+                required: true,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "scale".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(1)),
+            },
+            PortDefinition {
+                name: "backend".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("cuda")),
+            },
+            PortDefinition {
+                name: "value_range".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("auto")),
+            },
+            PortDefinition {
+                name: "output_range".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("same")),
+            },
+            PortDefinition {
+                name: "channel_order".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("rgb")),
+            },
+            PortDefinition {
+                name: "pad_align".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(0)),
+            },
+            PortDefinition {
+                name: "skip_static_regions".to_string(),
+                port_type: PortType::Bool,
+                required: false,
+                default_value: Some(serde_json::json!(false)),
+            },
+            PortDefinition {
+                name: "static_threshold".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(crate::roi::DEFAULT_STATIC_THRESHOLD)),
+            },
+        ];
+        if let Some(io) = &self.io {
+            ports.extend(io.extra_ports());
+        }
+        ports
+    }
+
+    fn output_ports(&self) -> Vec<PortDefinition> {
+        vec![]
+    }
+
+    fn execute(
+        &mut self,
+        inputs: &HashMap<String, PortData>,
+        _ctx: &ExecutionContext,
+    ) -> Result<HashMap<String, PortData>> {
+        let model_path = match inputs.get("model_path") {
+            Some(PortData::Path(p)) => p.clone(),
+            Some(_) => bail!("ModelInference: model_path must be a Path"),
+            None => bail!("ModelInference: model_path is required"),
+        };
+        self.scale = match inputs.get("scale") {
+            Some(PortData::Int(value)) if *value > 0 => *value as u32,
+            Some(PortData::Int(value)) => {
+                bail!("ModelInference: scale must be positive, got {value}")
+            }
+            Some(_) => bail!("ModelInference: scale must be an Int"),
+            None => 1,
+        };
+        self.preprocess = ModelPreprocess::from_inputs(
+            MODEL_INFERENCE_NODE_TYPE,
+            inputs,
+            &model_path,
+            NATIVE_RANGE,
+            1,
+        )?;
+
+        let io = inspect_model_io(&model_path)?;
+        let mut extra_values = Vec::with_capacity(io.extra_inputs.len());
+        for tensor in &io.extra_inputs {
+            let value = match (
+                scalar_port_type(&tensor.data_type),
+                inputs.get(&tensor.name),
+            ) {
+                (Some(PortType::Float), Some(PortData::Float(v))) => ScalarValue::Float(*v),
+                (Some(PortType::Float), Some(PortData::Int(v))) => ScalarValue::Float(*v as f64),
+                (Some(PortType::Int), Some(PortData::Int(v))) => ScalarValue::Int(*v),
+                (Some(PortType::Bool), Some(PortData::Bool(v))) => ScalarValue::Bool(*v),
+                (_, None) => bail!("ModelInference: no value for model input '{}'", tensor.name),
+                (_, Some(_)) => bail!(
+                    "ModelInference: model input '{}' expects {}",
+                    tensor.name,
+                    tensor.data_type
+                ),
+            };
+            extra_values.push((tensor.clone(), value));
+        }
+
+        let backend = match inputs.get("backend") {
+            Some(PortData::Str(b)) => InferenceBackend::from_str_lossy(b),
+            _ => InferenceBackend::default(),
+        };
+        debug!(
+            model = %model_path.display(),
+            scale = self.scale,
+            extra_inputs = extra_values.len(),
+            %backend,
+            "Loading ONNX model for ModelInference"
+        );
+        let (session, lease) = acquire_session(&SessionConfig {
+            model_path: &model_path,
+            backend: &backend,
+            trt_cache_dir: self.trt_cache_dir.as_deref(),
+        })?;
+
+        self.io = Some(io);
+        self.extra_values = extra_values;
+        self.session = Some(session);
+        self.session_lease = Some(lease);
+        Ok(HashMap::new())
+    }
+}
+
+impl FrameProcessor for ModelInferenceNode {
+    fn process_frame(&mut self, frame: Frame, _ctx: &ExecutionContext) -> Result<Frame> {
+        let Frame::CpuRgb {
+            data,
+            width,
+            height,
+            bit_depth,
+        } = frame
+        else {
+            bail!("ModelInference: only CPU RGB frames are supported");
+        };
+
+        let (w, h) = (width as usize, height as usize);
+        let rgb8 =
+            to_rgb8(&data, w, h, bit_depth).context("ModelInference: invalid input frame")?;
+        let out = self.run_model(&rgb8, w, h)?;
+        Ok(Frame::CpuRgb {
+            data: out,
+            width: width * self.scale,
+            height: height * self.scale,
+            bit_depth: 8,
+        })
+    }
+}
+
+/// Packs RGB24 into `[1, C, padded_h, padded_w]`, scaled to `0..1`.
+/// Padding repeats the last row and column; a 1-channel model gets luma.
+fn frame_to_nchw(
+    rgb8: &[u8],
+    width: usize,
+    height: usize,
+    padded_w: usize,
+    padded_h: usize,
+    gray: bool,
+) -> Array4<f32> {
+    let channels = if gray { 1 } else { 3 };
+    let norm = 1.0 / 255.0;
+    let mut arr = Array4::<f32>::zeros((1, channels, padded_h, padded_w));
+    for y in 0..padded_h {
+        let sy = y.min(height - 1);
+        for x in 0..padded_w {
+            let sx = x.min(width - 1);
+            let px = &rgb8[(sy * width + sx) * 3..][..3];
+            if gray {
+                let luma = 0.299 * px[0] as f32 + 0.587 * px[1] as f32 + 0.114 * px[2] as f32;
+                arr[[0, 0, y, x]] = luma * norm;
+            } else {
+                for c in 0..3 {
+                    arr[[0, c, y, x]] = px[c] as f32 * norm;
+                }
+            }
+        }
+    }
+    arr
+}
+
+/// Unpacks the top-left `crop_w×crop_h` of a `[1, C, H, W]` output in `0..1`
+/// into RGB24. A single channel is replicated to gray.
+fn nchw_to_rgb8(
+    values: &[f32],
+    channels: usize,
+    width: usize,
+    height: usize,
+    crop_w: usize,
+    crop_h: usize,
+) -> Vec<u8> {
+    let plane = width * height;
+    let mut out = Vec::with_capacity(crop_w * crop_h * 3);
+    for y in 0..crop_h {
+        for x in 0..crop_w {
+            let idx = y * width + x;
+            for c in 0..3 {
+                let channel = if channels == 1 { 0 } else { c };
+                let v = values[channel * plane + idx] * 255.0;
+                out.push(v.round().clamp(0.0, 255.0) as u8);
+            }
+        }
+    }
+    out
+}
+
+/// A tensor of the input's shape (dynamic dimensions as 1) filled with `value`.
+fn scalar_tensor(tensor: &TensorInfo, value: ScalarValue) -> Result<DynValue> {
+    let shape: Vec<i64> = tensor.shape.iter().map(|&d| d.max(1)).collect();
+    let len = shape.iter().product::<i64>() as usize;
+    let value = match (tensor.data_type.as_str(), value) {
+        ("float32", ScalarValue::Float(v)) => {
+            Tensor::from_array((shape, vec![v as f32; len]))?.into_dyn()
+        }
+        ("float16", ScalarValue::Float(v)) => {
+            Tensor::from_array((shape, vec![f16::from_f64(v); len]))?.into_dyn()
+        }
+        ("float64", ScalarValue::Float(v)) => Tensor::from_array((shape, vec![v; len]))?.into_dyn(),
+        ("int32", ScalarValue::Int(v)) => {
+            let v = i32::try_from(v).with_context(|| {
+                format!(
+                    "ModelInference: {v} does not fit int32 input '{}'",
+                    tensor.name
+                )
+            })?;
+            Tensor::from_array((shape, vec![v; len]))?.into_dyn()
+        }
+        ("int64", ScalarValue::Int(v)) => Tensor::from_array((shape, vec![v; len]))?.into_dyn(),
+        ("bool", ScalarValue::Bool(v)) => Tensor::from_array((shape, vec![v; len]))?.into_dyn(),
+        (data_type, value) => {
+            bail!(
+                "ModelInference: cannot feed {value:?} to {data_type} input '{}'",
+                tensor.name
+            )
+        }
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tensor(name: &str, data_type: &str, shape: &[i64]) -> TensorInfo {
+        TensorInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            shape: shape.to_vec(),
+        }
+    }
+
+    fn inspection(inputs: Vec<TensorInfo>, outputs: Vec<TensorInfo>) -> ModelInspection {
+        ModelInspection {
+            ir_version: 8,
+            opset_version: 17,
+            producer_name: String::new(),
+            producer_version: String::new(),
+            domain: String::new(),
+            model_version: 0,
+            doc_string: String::new(),
+            inputs,
+            outputs,
+            nodes: vec![],
+            param_count: 0,
+            op_count: 0,
+            metadata: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_model_io_maps_extra_inputs_to_ports() {
+        let io = ModelIo::from_inspection(&inspection(
+            vec![
+                tensor("noise_level", "float32", &[1]),
+                tensor("image", "float16", &[1, 3, -1, -1]),
+                tensor("steps", "int64", &[]),
+            ],
+            vec![tensor("out", "float16", &[1, 3, -1, -1])],
+        ))
+        .unwrap();
+
+        assert_eq!(io.image_input.name, "image");
+        assert_eq!(io.image_output.name, "out");
+        let ports = io.extra_ports();
+        let summary: Vec<(&str, &PortType, bool)> = ports
+            .iter()
+            .map(|p| (p.name.as_str(), &p.port_type, p.required))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("noise_level", &PortType::Float, true),
+                ("steps", &PortType::Int, true)
+            ]
+        );
+
+        let mut node = ModelInferenceNode::new();
+        node.io = Some(Arc::new(io));
+        let names: Vec<String> = node.input_ports().into_iter().map(|p| p.name).collect();
+        assert_eq!(
+            names,
+            [
+                "model_path",
+                "scale",
+                "backend",
+                "value_range",
+                "output_range",
+                "channel_order",
+                "pad_align",
+                "skip_static_regions",
+                "static_threshold",
+                "noise_level",
+                "steps"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_model_io_rejects_unusable_models() {
+        let image = || tensor("image", "float32", &[1, 3, -1, -1]);
+        let cases = [
+            (
+                vec![tensor("ids", "int64", &[1, 77])],
+                vec![image()],
+                "no 4-D float input",
+            ),
+            (
+                vec![image()],
+                vec![tensor("logits", "float32", &[1, 1000])],
+                "no 4-D float output",
+            ),
+            (
+                vec![tensor("image", "float32", &[1, 4, 64, 64])],
+                vec![image()],
+                "4 channels",
+            ),
+            (
+                vec![image(), tensor("prompt", "string", &[1])],
+                vec![image()],
+                "unsupported type",
+            ),
+            (
+                vec![image(), tensor("scale", "float32", &[1])],
+                vec![image()],
+                "clashes",
+            ),
+        ];
+        for (inputs, outputs, expected) in cases {
+            let err = ModelIo::from_inspection(&inspection(inputs, outputs)).unwrap_err();
+            assert!(err.to_string().contains(expected), "{expected}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_frame_to_nchw_pads_and_converts_to_gray() {
+        // 2x1 frame: red, white.
+        let rgb = [255, 0, 0, 255, 255, 255];
+        let arr = frame_to_nchw(&rgb, 2, 1, 4, 2, false);
+        assert_eq!(arr.shape(), &[1, 3, 2, 4]);
+        assert_eq!(arr[[0, 0, 0, 0]], 1.0);
+        assert_eq!(arr[[0, 1, 0, 0]], 0.0);
+        // Padding repeats the last column and row.
+        assert_eq!(arr[[0, 1, 1, 3]], 1.0);
+
+        let gray = frame_to_nchw(&rgb, 2, 1, 2, 1, true);
+        assert_eq!(gray.shape(), &[1, 1, 1, 2]);
+        assert!((gray[[0, 0, 0, 0]] - 0.299).abs() < 1e-5);
+        assert!((gray[[0, 0, 0, 1]] - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_nchw_to_rgb8_crops_and_replicates_gray() {
+        // 1-channel 3x2 output, cropped to 2x1.
+        let values = [0.0, 0.5, 1.0, 0.2, 0.2, 0.2];
+        let rgb = nchw_to_rgb8(&values, 1, 3, 2, 2, 1);
+        assert_eq!(rgb, [0, 0, 0, 128, 128, 128]);
+
+        // 3-channel 1x1 output with out-of-range values.
+        let rgb = nchw_to_rgb8(&[1.5, -0.2, 0.25], 3, 1, 1, 1, 1);
+        assert_eq!(rgb, [255, 0, 64]);
+    }
+
+    #[test]
+    fn test_model_inference_execute_validates_params() {
+        for (name, value, expected) in [
+            ("scale", PortData::Int(0), "scale must be positive"),
+            ("value_range", PortData::Str("0-2".into()), "value_range"),
+            (
+                "channel_order",
+                PortData::Str("rbg".into()),
+                "channel_order",
+            ),
+            ("pad_align", PortData::Int(-4), "pad_align"),
+        ] {
+            let mut inputs = HashMap::new();
+            inputs.insert(
+                "model_path".to_string(),
+                PortData::Path(PathBuf::from("missing.onnx")),
+            );
+            inputs.insert(name.to_string(), value);
+            let err = ModelInferenceNode::new()
+                .execute(&inputs, &ExecutionContext::default())
+                .err()
+                .expect("execute should fail");
+            assert!(err.to_string().contains(expected), "{name}: {err}");
+        }
+
+        let mut inputs = HashMap::new();
+        inputs.insert(
+            "model_path".to_string(),
+            PortData::Path(PathBuf::from("missing.onnx")),
+        );
+        let err = ModelInferenceNode::new()
+            .execute(&inputs, &ExecutionContext::default())
+            .err()
+            .expect("execute should fail");
+        assert!(err.to_string().contains("missing.onnx"), "{err}");
+    }
+}
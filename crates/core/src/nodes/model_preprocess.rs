@@ -0,0 +1,308 @@
+//! Pre/post-processing shared by the inference nodes.
+//!
+//! Models disagree on value range (`0-1`, `0-255`, `-1-1`), channel order and
+//! the multiple spatial dimensions must be padded to. Each node converts
+//! frames into tensors in its *native* range and leaves the rest to
+//! [`ModelPreprocess`]: it remaps native tensors into the model's input range
+//! before inference and the model's output range back afterwards. The `auto`
+//! settings come from the built-in model catalog when the file is known,
+//! otherwise the node's historical defaults apply.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use half::f16;
+
+use crate::model_registry::builtin_entry;
+use crate::types::PortData;
+
+pub const VALUE_RANGE_OPTIONS: [&str; 4] = ["auto", "0-1", "0-255", "-1-1"];
+pub const OUTPUT_RANGE_OPTIONS: [&str; 4] = ["same", "0-1", "0-255", "-1-1"];
+pub const CHANNEL_ORDER_OPTIONS: [&str; 2] = ["rgb", "bgr"];
+pub const MAX_PAD_ALIGN: i64 = 256;
+
+/// Tensor element types a [`ModelPreprocess`] can remap in place.
+pub trait Sample: Copy {
+    fn to_f32(self) -> f32;
+    fn from_f32(value: f32) -> Self;
+}
+
+impl Sample for f32 {
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+}
+
+impl Sample for f16 {
+    fn to_f32(self) -> f32 {
+        f16::to_f32(self)
+    }
+
+    fn from_f32(value: f32) -> Self {
+        f16::from_f32(value)
+    }
+}
+
+fn parse_range(value: &str) -> Option<(f32, f32)> {
+    match value {
+        "0-1" => Some((0.0, 1.0)),
+        "0-255" => Some((0.0, 255.0)),
+        "-1-1" => Some((-1.0, 1.0)),
+        _ => None,
+    }
+}
+
+/// How tensors are converted between a node and its model.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModelPreprocess {
+    /// Range of the tensors the node builds and reads back.
+    pub native: (f32, f32),
+    /// Range the model expects on its image input.
+    pub input_range: (f32, f32),
+    /// Range the model produces on its image output.
+    pub output_range: (f32, f32),
+    /// Model uses BGR plane order.
+    pub bgr: bool,
+    /// Spatial dimensions are padded to multiples of this.
+    pub pad_align: usize,
+}
+
+impl ModelPreprocess {
+    /// No conversion: the model works in the node's native range.
+    pub fn identity(native: (f32, f32), pad_align: usize) -> Self {
+        Self {
+            native,
+            input_range: native,
+            output_range: native,
+            bgr: false,
+            pad_align,
+        }
+    }
+
+    /// Reads `value_range`, `output_range`, `channel_order` and `pad_align`
+    /// from a node's inputs. Missing ports and `auto` (`0` for `pad_align`)
+    /// resolve from the catalog entry for `model_path`, then from
+    /// `native`/`default_pad_align`.
+    pub fn from_inputs(
+        node: &str,
+        inputs: &HashMap<String, PortData>,
+        model_path: &Path,
+        native: (f32, f32),
+        default_pad_align: usize,
+    ) -> Result<Self> {
+        let entry = model_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(builtin_entry);
+
+        let input_range = match str_input(node, inputs, "value_range")? {
+            None | Some("auto") => entry
+                .as_ref()
+                .map(|e| e.normalization_range)
+                .unwrap_or(native),
+            Some(value) => parse_range(value).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "{node}: value_range must be one of {}, got '{value}'",
+                    VALUE_RANGE_OPTIONS.join(", ")
+                )
+            })?,
+        };
+        let output_range = match str_input(node, inputs, "output_range")? {
+            None | Some("same") => input_range,
+            Some(value) => parse_range(value).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "{node}: output_range must be one of {}, got '{value}'",
+                    OUTPUT_RANGE_OPTIONS.join(", ")
+                )
+            })?,
+        };
+        let bgr = match str_input(node, inputs, "channel_order")? {
+            None | Some("rgb") => false,
+            Some("bgr") => true,
+            Some(value) => bail!(
+                "{node}: channel_order must be one of {}, got '{value}'",
+                CHANNEL_ORDER_OPTIONS.join(", ")
+            ),
+        };
+        let pad_align = match inputs.get("pad_align") {
+            None | Some(PortData::Int(0)) => entry
+                .as_ref()
+                .map(|e| e.pad_align as usize)
+                .unwrap_or(default_pad_align),
+            Some(PortData::Int(value)) if (1..=MAX_PAD_ALIGN).contains(value) => *value as usize,
+            Some(PortData::Int(value)) => {
+                bail!("{node}: pad_align must be between 0 (auto) and {MAX_PAD_ALIGN}, got {value}")
+            }
+            Some(_) => bail!("{node}: pad_align must be an Int"),
+        };
+
+        Ok(Self {
+            native,
+            input_range,
+            output_range,
+            bgr,
+            pad_align,
+        })
+    }
+
+    /// True when neither direction changes any value.
+    pub fn is_identity(&self) -> bool {
+        !self.bgr && self.input_range == self.native && self.output_range == self.native
+    }
+
+    /// Native → model input, for a `[1, C, H, W]` tensor.
+    pub fn to_model<T: Sample>(&self, data: &mut [T], channels: usize) {
+        remap(data, self.native, self.input_range, None);
+        if self.bgr {
+            swap_red_blue(data, channels);
+        }
+    }
+
+    /// Model output → native, for a `[1, C, H, W]` tensor. Remapped values
+    /// are clamped to the native range.
+    pub fn from_model<T: Sample>(&self, data: &mut [T], channels: usize) {
+        if self.bgr {
+            swap_red_blue(data, channels);
+        }
+        let clamp = (self.output_range != self.native).then_some(self.native);
+        remap(data, self.output_range, self.native, clamp);
+    }
+}
+
+fn str_input<'a>(
+    node: &str,
+    inputs: &'a HashMap<String, PortData>,
+    name: &str,
+) -> Result<Option<&'a str>> {
+    match inputs.get(name) {
+        Some(PortData::Str(value)) => Ok(Some(value.as_str())),
+        Some(_) => bail!("{node}: {name} must be a Str"),
+        None => Ok(None),
+    }
+}
+
+fn remap<T: Sample>(data: &mut [T], from: (f32, f32), to: (f32, f32), clamp: Option<(f32, f32)>) {
+    if from == to {
+        return;
+    }
+    let scale = (to.1 - to.0) / (from.1 - from.0);
+    for value in data.iter_mut() {
+        let mut v = (value.to_f32() - from.0) * scale + to.0;
+        if let Some((lo, hi)) = clamp {
+            v = v.clamp(lo, hi);
+        }
+        *value = T::from_f32(v);
+    }
+}
+
+fn swap_red_blue<T: Sample>(data: &mut [T], channels: usize) {
+    if channels != 3 {
+        return;
+    }
+    let plane = data.len() / 3;
+    let (red, rest) = data.split_at_mut(plane);
+    red.swap_with_slice(&mut rest[plane..]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inputs<const N: usize>(pairs: [(&str, PortData); N]) -> HashMap<String, PortData> {
+        pairs
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), value))
+            .collect()
+    }
+
+    #[test]
+    fn test_auto_resolves_from_catalog_then_defaults() {
+        let none = HashMap::new();
+        let known = ModelPreprocess::from_inputs(
+            "Test",
+            &none,
+            Path::new("/models/RealESRGAN_x4plus_anime_6B.onnx"),
+            (0.0, 1.0),
+            1,
+        )
+        .unwrap();
+        assert_eq!(known.input_range, (0.0, 255.0));
+        assert_eq!(known.output_range, (0.0, 255.0));
+        assert_eq!(known.pad_align, 4);
+        assert!(!known.is_identity());
+
+        let custom =
+            ModelPreprocess::from_inputs("Test", &none, Path::new("custom.onnx"), (0.0, 1.0), 8)
+                .unwrap();
+        assert_eq!(custom, ModelPreprocess::identity((0.0, 1.0), 8));
+        assert!(custom.is_identity());
+    }
+
+    #[test]
+    fn test_explicit_settings_and_validation() {
+        let settings = inputs([
+            ("value_range", PortData::Str("-1-1".into())),
+            ("output_range", PortData::Str("0-255".into())),
+            ("channel_order", PortData::Str("bgr".into())),
+            ("pad_align", PortData::Int(16)),
+        ]);
+        let pre =
+            ModelPreprocess::from_inputs("Test", &settings, Path::new("m.onnx"), (0.0, 1.0), 1)
+                .unwrap();
+        assert_eq!(pre.input_range, (-1.0, 1.0));
+        assert_eq!(pre.output_range, (0.0, 255.0));
+        assert!(pre.bgr);
+        assert_eq!(pre.pad_align, 16);
+
+        for (name, value, expected) in [
+            ("value_range", PortData::Str("0-2".into()), "value_range"),
+            ("output_range", PortData::Str("auto".into()), "output_range"),
+            (
+                "channel_order",
+                PortData::Str("rbg".into()),
+                "channel_order",
+            ),
+            ("pad_align", PortData::Int(512), "pad_align"),
+            ("pad_align", PortData::Int(-1), "pad_align"),
+        ] {
+            let err = ModelPreprocess::from_inputs(
+                "Test",
+                &inputs([(name, value)]),
+                Path::new("m.onnx"),
+                (0.0, 1.0),
+                1,
+            )
+            .unwrap_err();
+            assert!(err.to_string().contains(expected), "{name}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_remap_roundtrip_with_bgr_and_clamp() {
+        let pre = ModelPreprocess {
+            native: (0.0, 1.0),
+            input_range: (-1.0, 1.0),
+            output_range: (0.0, 255.0),
+            bgr: true,
+            pad_align: 1,
+        };
+        // One pixel per plane: R, G, B.
+        let mut data = [1.0f32, 0.5, 0.0];
+        pre.to_model(&mut data, 3);
+        assert_eq!(data, [-1.0, 0.0, 1.0]);
+
+        // Model answers in BGR 0..255, with an overshoot on blue.
+        let mut out = [300.0f32, 127.5, 0.0];
+        pre.from_model(&mut out, 3);
+        assert_eq!(out, [0.0, 0.5, 1.0]);
+
+        let mut half = [f16::from_f32(0.5)];
+        pre.to_model(&mut half, 1);
+        assert_eq!(half[0].to_f32(), 0.0);
+    }
+}
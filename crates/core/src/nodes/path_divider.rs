@@ -3,7 +3,7 @@ use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Result};
 
-use crate::node::{ExecutionContext, Node, PortDefinition};
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
 use crate::types::{PortData, PortType};
 
 pub struct PathDividerNode;
@@ -25,6 +25,10 @@ impl Node for PathDividerNode {
         "PathDivider"
     }
 
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
     fn input_ports(&self) -> Vec<PortDefinition> {
         vec![PortDefinition {
             name: "path".to_string(),
@@ -5,7 +5,7 @@ use std::path::PathBuf;
 
 use anyhow::{bail, Result};
 
-use crate::node::{ExecutionContext, Node, PortDefinition};
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
 use crate::types::{PortData, PortType};
 
 pub struct PathJoinerNode;
@@ -27,6 +27,10 @@ impl Node for PathJoinerNode {
         "PathJoiner"
     }
 
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
     fn input_ports(&self) -> Vec<PortDefinition> {
         vec![
             PortDefinition {
@@ -0,0 +1,367 @@
+//! PreviewTap node: publishes a small JPEG of the frames passing through it.
+//!
+//! Frames go through unchanged. Every `interval_secs` the node takes the
+//! current frame, scales it down to at most `max_width` pixels wide and
+//! encodes it to JPEG with ffmpeg on a background thread, so a slow encode
+//! never holds up the pipeline; frames arriving while an encode is still
+//! running are not previewed. The server shows the latest preview of a job
+//! at `/api/jobs/{id}/preview.jpg` and sends it over the job's WebSocket.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::node::{ExecutionContext, FrameProcessor, Node, PortDefinition, ResourceClass};
+use crate::nodes::resize::{resize_nearest, to_rgb8};
+use crate::types::{Frame, PortData, PortType};
+
+pub const PREVIEW_TAP_NODE_TYPE: &str = "PreviewTap";
+const DEFAULT_INTERVAL_SECS: f64 = 2.0;
+const DEFAULT_MAX_WIDTH: i64 = 480;
+const MAX_PREVIEW_WIDTH: i64 = 3840;
+
+/// A JPEG preview of one frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FramePreview {
+    /// Index of the frame among those that reached the node.
+    pub frame_index: u64,
+    pub width: u32,
+    pub height: u32,
+    pub jpeg: Vec<u8>,
+}
+
+/// Receives each preview a `PreviewTap` node publishes.
+pub type FramePreviewSink = Arc<dyn Fn(FramePreview) + Send + Sync>;
+
+/// Publishes throttled previews of the frames it is offered. Encoding runs
+/// on a background thread; frames offered meanwhile are skipped.
+pub struct FramePreviewer {
+    interval: Duration,
+    max_width: u32,
+    sink: Option<FramePreviewSink>,
+    frame_index: u64,
+    last_published: Option<Instant>,
+    encoding: Arc<AtomicBool>,
+}
+
+impl FramePreviewer {
+    pub fn new(interval: Duration, max_width: u32) -> Self {
+        Self {
+            interval,
+            max_width,
+            sink: None,
+            frame_index: 0,
+            last_published: None,
+            encoding: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Where previews go. Without a sink frames are not previewed.
+    pub fn set_sink(&mut self, sink: FramePreviewSink) {
+        self.sink = Some(sink);
+    }
+
+    /// Counts `frame` and, if a preview is due, starts encoding it. Only
+    /// CPU RGB frames are previewed.
+    pub fn offer(&mut self, frame: &Frame) -> Result<()> {
+        let frame_index = self.frame_index;
+        self.frame_index += 1;
+        let Some(sink) = self.sink.clone() else {
+            return Ok(());
+        };
+        let now = Instant::now();
+        if !self.preview_due(now) {
+            return Ok(());
+        }
+        let Frame::CpuRgb {
+            data,
+            width,
+            height,
+            bit_depth,
+        } = frame
+        else {
+            return Ok(());
+        };
+
+        let (rgb, width, height) = downscale(data, *width, *height, *bit_depth, self.max_width)?;
+        self.last_published = Some(now);
+        self.encoding.store(true, Ordering::Release);
+        let encoding = Arc::clone(&self.encoding);
+        std::thread::spawn(move || {
+            match encode_jpeg(&rgb, width, height) {
+                Ok(jpeg) => sink(FramePreview {
+                    frame_index,
+                    width,
+                    height,
+                    jpeg,
+                }),
+                Err(err) => tracing::warn!(error = %err, "failed to encode frame preview"),
+            }
+            encoding.store(false, Ordering::Release);
+        });
+        Ok(())
+    }
+
+    /// Whether a preview is due for a frame arriving at `now`.
+    fn preview_due(&self, now: Instant) -> bool {
+        self.last_published
+            .is_none_or(|last| now.duration_since(last) >= self.interval)
+            && !self.encoding.load(Ordering::Acquire)
+    }
+}
+
+pub struct PreviewTapNode {
+    previewer: FramePreviewer,
+}
+
+impl PreviewTapNode {
+    pub fn new() -> Self {
+        Self {
+            previewer: FramePreviewer::new(
+                Duration::from_secs_f64(DEFAULT_INTERVAL_SECS),
+                DEFAULT_MAX_WIDTH as u32,
+            ),
+        }
+    }
+
+    /// Where previews go. Without a sink frames only pass through.
+    pub fn set_sink(&mut self, sink: FramePreviewSink) {
+        self.previewer.set_sink(sink);
+    }
+}
+
+impl Default for PreviewTapNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node for PreviewTapNode {
+    fn node_type(&self) -> &str {
+        PREVIEW_TAP_NODE_TYPE
+    }
+
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
+    fn input_ports(&self) -> Vec<PortDefinition> {
+        vec![
+            PortDefinition {
+                name: "interval_secs".to_string(),
+                port_type: PortType::Float,
+                required: false,
+                default_value: Some(serde_json::json!(DEFAULT_INTERVAL_SECS)),
+            },
+            PortDefinition {
+                name: "max_width".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(DEFAULT_MAX_WIDTH)),
+            },
+        ]
+    }
+
+    fn output_ports(&self) -> Vec<PortDefinition> {
+        vec![]
+    }
+
+    fn execute(
+        &mut self,
+        inputs: &HashMap<String, PortData>,
+        _ctx: &ExecutionContext,
+    ) -> Result<HashMap<String, PortData>> {
+        let interval_secs = match inputs.get("interval_secs") {
+            Some(PortData::Float(value)) => *value,
+            Some(PortData::Int(value)) => *value as f64,
+            Some(_) => bail!("PreviewTap: interval_secs must be a Float"),
+            None => DEFAULT_INTERVAL_SECS,
+        };
+        if !interval_secs.is_finite() || interval_secs < 0.0 {
+            bail!("PreviewTap: interval_secs must be 0 or more, got {interval_secs}");
+        }
+        let max_width = match inputs.get("max_width") {
+            Some(PortData::Int(value)) => *value,
+            Some(_) => bail!("PreviewTap: max_width must be an Int"),
+            None => DEFAULT_MAX_WIDTH,
+        };
+        if !(16..=MAX_PREVIEW_WIDTH).contains(&max_width) {
+            bail!(
+                "PreviewTap: max_width must be between 16 and {MAX_PREVIEW_WIDTH}, got {max_width}"
+            );
+        }
+
+        let sink = self.previewer.sink.take();
+        self.previewer =
+            FramePreviewer::new(Duration::from_secs_f64(interval_secs), max_width as u32);
+        self.previewer.sink = sink;
+        Ok(HashMap::new())
+    }
+}
+
+impl FrameProcessor for PreviewTapNode {
+    fn process_frame(&mut self, frame: Frame, _ctx: &ExecutionContext) -> Result<Frame> {
+        self.previewer.offer(&frame)?;
+        Ok(frame)
+    }
+}
+
+/// 8-bit RGB copy of a packed RGB frame, at most `max_width` wide with the
+/// aspect ratio kept.
+fn downscale(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    bit_depth: u8,
+    max_width: u32,
+) -> Result<(Vec<u8>, u32, u32)> {
+    let rgb = to_rgb8(data, width as usize, height as usize, bit_depth)
+        .context("PreviewTap: invalid frame")?;
+    if width <= max_width {
+        return Ok((rgb, width, height));
+    }
+    let scaled_height =
+        ((u64::from(height) * u64::from(max_width)) / u64::from(width)).max(1) as u32;
+    let scaled = resize_nearest(
+        &rgb,
+        width as usize,
+        height as usize,
+        max_width as usize,
+        scaled_height as usize,
+    );
+    Ok((scaled, max_width, scaled_height))
+}
+
+/// Encodes 8-bit RGB data to a JPEG with ffmpeg.
+fn encode_jpeg(rgb: &[u8], width: u32, height: u32) -> Result<Vec<u8>> {
+    let mut child = crate::runtime::command_for("ffmpeg")
+        .args(["-v", "error", "-f", "rawvideo", "-pix_fmt", "rgb24", "-s"])
+        .arg(format!("{width}x{height}"))
+        .args([
+            "-i",
+            "-",
+            "-frames:v",
+            "1",
+            "-f",
+            "image2pipe",
+            "-c:v",
+            "mjpeg",
+            "-q:v",
+            "5",
+            "-",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("PreviewTap: failed to start ffmpeg")?;
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow!("PreviewTap: ffmpeg stdin is unavailable"))?;
+    let rgb = rgb.to_vec();
+    let writer = std::thread::spawn(move || stdin.write_all(&rgb));
+    let output = child
+        .wait_with_output()
+        .context("PreviewTap: failed to wait for ffmpeg")?;
+    let _ = writer.join();
+    if !output.status.success() || output.stdout.is_empty() {
+        bail!(
+            "PreviewTap: ffmpeg failed to encode the preview: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    fn node(pairs: Vec<(&str, PortData)>) -> Result<PreviewTapNode> {
+        let mut node = PreviewTapNode::new();
+        let inputs: HashMap<String, PortData> =
+            pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect();
+        node.execute(&inputs, &ExecutionContext::default())?;
+        Ok(node)
+    }
+
+    fn gradient_frame(width: u32, height: u32) -> Frame {
+        Frame::CpuRgb {
+            data: (0..width * height * 3).map(|i| (i % 251) as u8).collect(),
+            width,
+            height,
+            bit_depth: 8,
+        }
+    }
+
+    fn ffmpeg_available() -> bool {
+        crate::runtime::command_for("ffmpeg")
+            .arg("-version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    }
+
+    #[test]
+    fn test_preview_tap_rejects_bad_params() {
+        assert!(node(vec![("interval_secs", PortData::Float(-1.0))]).is_err());
+        assert!(node(vec![("max_width", PortData::Int(4))]).is_err());
+        assert!(node(vec![("max_width", PortData::Str("wide".into()))]).is_err());
+        assert!(node(vec![("interval_secs", PortData::Int(0))]).is_ok());
+    }
+
+    #[test]
+    fn test_downscale_keeps_aspect_ratio() {
+        let Frame::CpuRgb { data, .. } = gradient_frame(64, 36) else {
+            unreachable!()
+        };
+        let (rgb, width, height) = downscale(&data, 64, 36, 8, 32).unwrap();
+        assert_eq!((width, height), (32, 18));
+        assert_eq!(rgb.len(), 32 * 18 * 3);
+
+        let (rgb, width, height) = downscale(&data, 64, 36, 8, 480).unwrap();
+        assert_eq!((width, height), (64, 36));
+        assert_eq!(rgb, data);
+    }
+
+    #[test]
+    fn test_preview_tap_passes_frames_through_and_waits_for_interval() {
+        let (tx, rx) = mpsc::channel();
+        let tx = std::sync::Mutex::new(tx);
+        let mut node = node(vec![
+            ("interval_secs", PortData::Float(3600.0)),
+            ("max_width", PortData::Int(32)),
+        ])
+        .unwrap();
+        node.set_sink(Arc::new(move |preview| {
+            let _ = tx.lock().unwrap().send(preview);
+        }));
+
+        let ctx = ExecutionContext::default();
+        for _ in 0..3 {
+            let out = node.process_frame(gradient_frame(64, 36), &ctx).unwrap();
+            assert_eq!(out.byte_len(), 64 * 36 * 3);
+            assert!(!node.previewer.preview_due(Instant::now()));
+        }
+        assert!(node.previewer.last_published.is_some());
+
+        if !ffmpeg_available() {
+            return;
+        }
+        let preview = rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(preview.frame_index, 0);
+        assert_eq!((preview.width, preview.height), (32, 18));
+        assert!(preview.jpeg.starts_with(&[0xFF, 0xD8]));
+        assert!(rx.recv_timeout(Duration::from_millis(200)).is_err());
+    }
+}
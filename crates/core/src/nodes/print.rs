@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use anyhow::{anyhow, bail, Result};
 
-use crate::node::{ExecutionContext, Node, PortDefinition};
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
 use crate::types::{PortData, PortType};
 
 pub struct PrintNode {
@@ -37,6 +37,10 @@ impl Node for PrintNode {
         "Print"
     }
 
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
     fn input_ports(&self) -> Vec<PortDefinition> {
         vec![
             PortDefinition {
@@ -138,7 +142,9 @@ fn clone_port_data(value: &PortData) -> PortData {
         PortData::Str(v) => PortData::Str(v.clone()),
         PortData::Bool(v) => PortData::Bool(*v),
         PortData::Path(v) => PortData::Path(v.clone()),
-        PortData::Metadata(_) => unreachable!("metadata is not supported by Print value_type"),
+        PortData::Metadata(_) | PortData::Json(_) | PortData::List(_) => {
+            unreachable!("metadata, JSON and lists are not supported by Print value_type")
+        }
     }
 }
 
@@ -161,6 +167,8 @@ fn port_data_kind(data: &PortData) -> &'static str {
         PortData::Bool(_) => "Bool",
         PortData::Path(_) => "Path",
         PortData::Metadata(_) => "Metadata",
+        PortData::Json(_) => "Json",
+        PortData::List(_) => "List",
     }
 }
 
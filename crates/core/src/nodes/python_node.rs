@@ -0,0 +1,448 @@
+//! Python node: hands every frame to a managed Python subprocess.
+//!
+//! The subprocess speaks a small frame protocol over stdin/stdout:
+//!
+//! 1. The host writes one JSON line,
+//!    `{"protocol": "videnoa-frames", "version": 1, "params": {...}}`.
+//! 2. The script answers with one JSON line, `{"ready": true}` once its model
+//!    is loaded, or `{"error": "..."}` to abort the job.
+//! 3. For each frame the host writes a 12-byte header (`width`, `height`,
+//!    `channels` as little-endian u32, channels is always 3) followed by the
+//!    RGB24 pixels. The script replies in the same format.
+//! 4. The host closes stdin when the stream ends.
+//!
+//! Anything the script prints to stderr is forwarded to the job log.
+//! `scripts/videnoa_frames.py` implements the Python side.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::node::{ExecutionContext, FrameProcessor, Node, PortDefinition};
+use crate::types::{Frame, PortData, PortType};
+
+pub const PYTHON_NODE_TYPE: &str = "Python";
+const PROTOCOL_NAME: &str = "videnoa-frames";
+const PROTOCOL_VERSION: u32 = 1;
+const FRAME_HEADER_LEN: usize = 12;
+const RGB_CHANNELS: u32 = 3;
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+pub struct PythonNode {
+    python: String,
+    scale: u32,
+    process: Option<PythonProcess>,
+}
+
+impl PythonNode {
+    pub fn new() -> Self {
+        Self {
+            python: "python3".to_string(),
+            scale: 1,
+            process: None,
+        }
+    }
+
+    /// Output frames are `scale` times the size of the input frames.
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+}
+
+impl Default for PythonNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node for PythonNode {
+    fn node_type(&self) -> &str {
+        PYTHON_NODE_TYPE
+    }
+
+    fn input_ports(&self) -> Vec<PortDefinition> {
+        vec![
+            PortDefinition {
+                name: "script".to_string(),
+                port_type: PortType::Path,
+                required: true,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "python".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("python3")),
+            },
+            PortDefinition {
+                name: "scale".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(1)),
+            },
+            PortDefinition {
+                name: "params_json".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("{}")),
+            },
+        ]
+    }
+
+    fn output_ports(&self) -> Vec<PortDefinition> {
+        vec![]
+    }
+
+    /// Starts the subprocess and waits for its handshake.
+    fn execute(
+        &mut self,
+        inputs: &HashMap<String, PortData>,
+        _ctx: &ExecutionContext,
+    ) -> Result<HashMap<String, PortData>> {
+        let script = match inputs.get("script") {
+            Some(PortData::Path(path)) => path.clone(),
+            Some(PortData::Str(path)) => PathBuf::from(path),
+            Some(_) => bail!("Python: script must be a Path"),
+            None => bail!("Python: script is required"),
+        };
+        if let Some(PortData::Str(python)) = inputs.get("python") {
+            if !python.trim().is_empty() {
+                self.python = python.trim().to_string();
+            }
+        }
+        if let Some(PortData::Int(scale)) = inputs.get("scale") {
+            if *scale < 1 {
+                bail!("Python: scale must be >= 1, got {scale}");
+            }
+            self.scale = *scale as u32;
+        }
+        let params = match inputs.get("params_json") {
+            Some(PortData::Str(raw)) if !raw.trim().is_empty() => {
+                serde_json::from_str(raw).context("Python: params_json must be valid JSON")?
+            }
+            _ => serde_json::json!({}),
+        };
+
+        self.process = Some(PythonProcess::spawn(&self.python, &script, &params)?);
+        Ok(HashMap::new())
+    }
+}
+
+impl FrameProcessor for PythonNode {
+    fn process_frame(&mut self, frame: Frame, _ctx: &ExecutionContext) -> Result<Frame> {
+        let Frame::CpuRgb {
+            data,
+            width,
+            height,
+            bit_depth: 8,
+        } = frame
+        else {
+            bail!("Python: only 8-bit RGB frames are supported");
+        };
+        let process = self
+            .process
+            .as_mut()
+            .ok_or_else(|| anyhow!("Python: subprocess is not running"))?;
+
+        let (out_width, out_height, out_data) = process.round_trip(width, height, &data)?;
+        if out_width != width * self.scale || out_height != height * self.scale {
+            bail!(
+                "Python: expected a {}x{} frame back, got {out_width}x{out_height}",
+                width * self.scale,
+                height * self.scale
+            );
+        }
+
+        Ok(Frame::CpuRgb {
+            data: out_data,
+            width: out_width,
+            height: out_height,
+            bit_depth: 8,
+        })
+    }
+}
+
+struct PythonProcess {
+    child: Child,
+    stdin: Option<BufWriter<ChildStdin>>,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl PythonProcess {
+    fn spawn(python: &str, script: &Path, params: &serde_json::Value) -> Result<Self> {
+        if !script.is_file() {
+            bail!("Python: script not found: {}", script.display());
+        }
+
+        let mut child = Command::new(python)
+            .arg(script)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Python: failed to start '{python}'"))?;
+
+        if let Some(stderr) = child.stderr.take() {
+            let stderr_span = tracing::Span::current();
+            std::thread::spawn(move || {
+                let _entered = stderr_span.enter();
+                for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                    tracing::info!(target: "videnoa::python", "{line}");
+                }
+            });
+        }
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| anyhow!("Python: stdin is unavailable"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| anyhow!("Python: stdout is unavailable"))?;
+        let mut process = Self {
+            child,
+            stdin: Some(BufWriter::new(stdin)),
+            stdout: BufReader::new(stdout),
+        };
+        process.handshake(params)?;
+        Ok(process)
+    }
+
+    fn handshake(&mut self, params: &serde_json::Value) -> Result<()> {
+        let hello = serde_json::json!({
+            "protocol": PROTOCOL_NAME,
+            "version": PROTOCOL_VERSION,
+            "params": params,
+        });
+        let stdin = self.stdin()?;
+        writeln!(stdin, "{hello}").context("Python: failed to send handshake")?;
+        stdin.flush()?;
+
+        let mut line = String::new();
+        let read = self
+            .stdout
+            .read_line(&mut line)
+            .context("Python: failed to read handshake")?;
+        if read == 0 {
+            let status = self.child.wait().ok();
+            bail!("Python: script exited before the handshake ({status:?})");
+        }
+
+        let reply: serde_json::Value = serde_json::from_str(line.trim())
+            .with_context(|| format!("Python: invalid handshake reply: {}", line.trim()))?;
+        if let Some(error) = reply.get("error").and_then(|value| value.as_str()) {
+            bail!("Python: {error}");
+        }
+        if reply.get("ready").and_then(|value| value.as_bool()) != Some(true) {
+            bail!("Python: unexpected handshake reply: {}", line.trim());
+        }
+        Ok(())
+    }
+
+    fn stdin(&mut self) -> Result<&mut BufWriter<ChildStdin>> {
+        self.stdin
+            .as_mut()
+            .ok_or_else(|| anyhow!("Python: stdin is closed"))
+    }
+
+    fn round_trip(&mut self, width: u32, height: u32, data: &[u8]) -> Result<(u32, u32, Vec<u8>)> {
+        let expected = width as usize * height as usize * RGB_CHANNELS as usize;
+        if data.len() != expected {
+            bail!(
+                "Python: frame data length mismatch: got {}, expected {expected}",
+                data.len()
+            );
+        }
+
+        let stdin = self.stdin()?;
+        stdin.write_all(&encode_frame_header(width, height))?;
+        stdin.write_all(data)?;
+        stdin.flush().context("Python: failed to send frame")?;
+
+        let mut header = [0u8; FRAME_HEADER_LEN];
+        self.stdout
+            .read_exact(&mut header)
+            .context("Python: script stopped before returning a frame")?;
+        let (out_width, out_height, channels) = decode_frame_header(&header);
+        if channels != RGB_CHANNELS {
+            bail!("Python: expected {RGB_CHANNELS} channels, got {channels}");
+        }
+
+        let mut out = vec![0u8; out_width as usize * out_height as usize * RGB_CHANNELS as usize];
+        self.stdout
+            .read_exact(&mut out)
+            .context("Python: frame returned by script is truncated")?;
+        Ok((out_width, out_height, out))
+    }
+}
+
+impl Drop for PythonProcess {
+    fn drop(&mut self) {
+        // Closing stdin tells the script the stream is over.
+        self.stdin.take();
+        let deadline = Instant::now() + SHUTDOWN_GRACE;
+        while Instant::now() < deadline {
+            match self.child.try_wait() {
+                Ok(Some(_)) | Err(_) => return,
+                Ok(None) => std::thread::sleep(Duration::from_millis(50)),
+            }
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn encode_frame_header(width: u32, height: u32) -> [u8; FRAME_HEADER_LEN] {
+    let mut header = [0u8; FRAME_HEADER_LEN];
+    header[0..4].copy_from_slice(&width.to_le_bytes());
+    header[4..8].copy_from_slice(&height.to_le_bytes());
+    header[8..12].copy_from_slice(&RGB_CHANNELS.to_le_bytes());
+    header
+}
+
+fn decode_frame_header(header: &[u8; FRAME_HEADER_LEN]) -> (u32, u32, u32) {
+    let read = |range: std::ops::Range<usize>| {
+        u32::from_le_bytes(header[range].try_into().expect("4-byte slice"))
+    };
+    (read(0..4), read(4..8), read(8..12))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn python_available() -> bool {
+        Command::new("python3")
+            .arg("--version")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    }
+
+    fn write_script(name: &str, body: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("videnoa-python-node-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let helper_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../scripts");
+        let script = format!(
+            "import sys\nsys.path.insert(0, {:?})\nimport videnoa_frames\n{body}",
+            helper_dir.to_string_lossy()
+        );
+        std::fs::write(&path, script).unwrap();
+        path
+    }
+
+    fn start(script: PathBuf, scale: i64) -> Result<PythonNode> {
+        let mut node = PythonNode::new();
+        let inputs = HashMap::from([
+            ("script".to_string(), PortData::Path(script)),
+            ("scale".to_string(), PortData::Int(scale)),
+            (
+                "params_json".to_string(),
+                PortData::Str(r#"{"offset": 10}"#.to_string()),
+            ),
+        ]);
+        node.execute(&inputs, &ExecutionContext::default())?;
+        Ok(node)
+    }
+
+    #[test]
+    fn test_frame_header_roundtrip() {
+        let header = encode_frame_header(1920, 1080);
+        assert_eq!(decode_frame_header(&header), (1920, 1080, 3));
+    }
+
+    #[test]
+    fn test_python_node_processes_frames() {
+        if !python_available() {
+            return;
+        }
+        let script = write_script(
+            "offset.py",
+            "def process(frame, params):\n    \
+                 off = params['offset']\n    \
+                 return frame.replace(data=bytes((b + off) % 256 for b in frame.data))\n\
+             videnoa_frames.serve(process)\n",
+        );
+        let mut node = start(script, 1).unwrap();
+
+        let frame = Frame::CpuRgb {
+            data: vec![0, 1, 2, 250, 251, 252],
+            width: 2,
+            height: 1,
+            bit_depth: 8,
+        };
+        let out = node
+            .process_frame(frame, &ExecutionContext::default())
+            .unwrap();
+        match out {
+            Frame::CpuRgb {
+                data,
+                width,
+                height,
+                ..
+            } => {
+                assert_eq!((width, height), (2, 1));
+                assert_eq!(data, vec![10, 11, 12, 4, 5, 6]);
+            }
+            _ => panic!("expected RGB frame"),
+        }
+    }
+
+    #[test]
+    fn test_python_node_rejects_wrong_output_size() {
+        if !python_available() {
+            return;
+        }
+        let script = write_script(
+            "identity.py",
+            "videnoa_frames.serve(lambda frame, params: frame)\n",
+        );
+        let mut node = start(script, 2).unwrap();
+
+        let frame = Frame::CpuRgb {
+            data: vec![0; 12],
+            width: 2,
+            height: 2,
+            bit_depth: 8,
+        };
+        let err = node
+            .process_frame(frame, &ExecutionContext::default())
+            .err()
+            .expect("identity output should not match scale 2");
+        assert!(err.to_string().contains("expected a 4x4 frame"));
+    }
+
+    #[test]
+    fn test_python_node_reports_handshake_error() {
+        if !python_available() {
+            return;
+        }
+        let script = write_script(
+            "failing.py",
+            "def setup(params):\n    raise RuntimeError('model weights missing')\n\
+             videnoa_frames.serve(lambda frame, params: frame, setup=setup)\n",
+        );
+        let err = start(script, 1)
+            .err()
+            .expect("setup failure should abort the node");
+        assert!(err.to_string().contains("model weights missing"));
+    }
+
+    #[test]
+    fn test_python_node_requires_script() {
+        let mut node = PythonNode::new();
+        let err = node
+            .execute(&HashMap::new(), &ExecutionContext::default())
+            .err()
+            .expect("script is required");
+        assert!(err.to_string().contains("script is required"));
+    }
+}
@@ -0,0 +1,707 @@
+//! RegionEnhance node: masked, two-model super-resolution.
+//!
+//! A segmentation model (faces, line art, ...) marks regions of each frame.
+//! The frame is upscaled by `model_path` everywhere and, if set, by
+//! `region_model_path` as well. The two results are blended through the
+//! feathered mask. `strength` and `region_strength` blend each side with a
+//! plain bilinear upscale, so the enhancement can also just be toned down in
+//! one of the two areas with a single model.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use half::f16;
+use ndarray::Array4;
+use ort::value::Tensor;
+use tracing::debug;
+
+use crate::node::{ExecutionContext, FrameProcessor, Node, PortDefinition};
+use crate::runtime::session_pool::{acquire_session, SessionLease, SharedSession};
+use crate::types::{Frame, PortData, PortType};
+
+use crate::nodes::backend::{InferenceBackend, SessionConfig};
+use crate::nodes::resize::{resize_bilinear, to_rgb8};
+use crate::nodes::super_res::SuperResNode;
+
+pub const REGION_ENHANCE_NODE_TYPE: &str = "RegionEnhance";
+
+/// Long side of the frame fed to mask models with a dynamic input size.
+const MASK_LONG_SIDE: usize = 512;
+/// Segmentation backbones usually downsample by 32.
+const MASK_ALIGN: usize = 32;
+const MAX_FEATHER: i64 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RegionSettings {
+    /// Blend of `model_path` output over a plain upscale outside the mask.
+    pub strength: f32,
+    /// Blend of the region model output over a plain upscale inside the mask.
+    pub region_strength: f32,
+    /// Mask probability at or above which a pixel belongs to the region.
+    pub threshold: f32,
+    /// Radius in output pixels over which the mask edge is softened.
+    pub feather: usize,
+}
+
+impl Default for RegionSettings {
+    fn default() -> Self {
+        Self {
+            strength: 1.0,
+            region_strength: 1.0,
+            threshold: 0.5,
+            feather: 8,
+        }
+    }
+}
+
+struct MaskModel {
+    session: SharedSession,
+    _lease: SessionLease,
+    input_name: String,
+    output_name: String,
+    is_fp16: bool,
+    /// `(height, width)` when the model only accepts one input size.
+    fixed_size: Option<(usize, usize)>,
+}
+
+pub struct RegionEnhanceNode {
+    base: Option<SuperResNode>,
+    region: Option<SuperResNode>,
+    mask: Option<MaskModel>,
+    settings: RegionSettings,
+    scale: u32,
+    trt_cache_dir: Option<PathBuf>,
+}
+
+impl RegionEnhanceNode {
+    pub fn new() -> Self {
+        Self {
+            base: None,
+            region: None,
+            mask: None,
+            settings: RegionSettings::default(),
+            scale: 4,
+            trt_cache_dir: None,
+        }
+    }
+
+    pub fn set_trt_cache_dir(&mut self, dir: PathBuf) {
+        self.trt_cache_dir = Some(dir);
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    fn load_upscaler(
+        &self,
+        model_path: &Path,
+        inputs: &HashMap<String, PortData>,
+    ) -> Result<SuperResNode> {
+        let mut sr_inputs = HashMap::new();
+        sr_inputs.insert(
+            "model_path".to_string(),
+            PortData::Path(model_path.to_path_buf()),
+        );
+        sr_inputs.insert("scale".to_string(), PortData::Int(self.scale as i64));
+        for key in ["tile_size", "backend"] {
+            match inputs.get(key) {
+                Some(PortData::Int(value)) => {
+                    sr_inputs.insert(key.to_string(), PortData::Int(*value));
+                }
+                Some(PortData::Str(value)) => {
+                    sr_inputs.insert(key.to_string(), PortData::Str(value.clone()));
+                }
+                _ => {}
+            }
+        }
+
+        let mut node = SuperResNode::new();
+        if let Some(dir) = &self.trt_cache_dir {
+            node.set_trt_cache_dir(dir.clone());
+        }
+        node.execute(&sr_inputs, &ExecutionContext::default())
+            .with_context(|| format!("RegionEnhance: failed to load {}", model_path.display()))?;
+        Ok(node)
+    }
+
+    fn load_mask_model(
+        &self,
+        model_path: &Path,
+        inputs: &HashMap<String, PortData>,
+    ) -> Result<MaskModel> {
+        let backend = match inputs.get("backend") {
+            Some(PortData::Str(b)) => InferenceBackend::from_str_lossy(b),
+            _ => InferenceBackend::default(),
+        };
+        let (session_arc, lease) = acquire_session(&SessionConfig {
+            model_path,
+            backend: &backend,
+            trt_cache_dir: self.trt_cache_dir.as_deref(),
+        })
+        .with_context(|| {
+            format!(
+                "RegionEnhance: failed to load mask model {}",
+                model_path.display()
+            )
+        })?;
+        let session = session_arc.lock().unwrap();
+
+        let input_name = session.inputs()[0].name().to_string();
+        let output_name = session.outputs()[0].name().to_string();
+        let (is_fp16, fixed_size) = match session.inputs()[0].dtype() {
+            ort::value::ValueType::Tensor { ty, shape, .. } => {
+                let fixed = match shape.len() {
+                    4 if shape[2] > 0 && shape[3] > 0 => {
+                        Some((shape[2] as usize, shape[3] as usize))
+                    }
+                    _ => None,
+                };
+                (*ty == ort::tensor::TensorElementType::Float16, fixed)
+            }
+            _ => (false, None),
+        };
+
+        debug!(
+            model = %model_path.display(),
+            %input_name, %output_name, is_fp16, ?fixed_size,
+            "Loaded region mask model"
+        );
+
+        Ok(MaskModel {
+            session: Arc::clone(&session_arc),
+            _lease: lease,
+            input_name,
+            output_name,
+            is_fp16,
+            fixed_size,
+        })
+    }
+}
+
+impl Default for RegionEnhanceNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node for RegionEnhanceNode {
+    fn node_type(&self) -> &str {
+        REGION_ENHANCE_NODE_TYPE
+    }
+
+    fn input_ports(&self) -> Vec<PortDefinition> {
+        let defaults = RegionSettings::default();
+        vec![
+            PortDefinition {
+                name: "mask_model_path".to_string(),
+                port_type: PortType::Path,
+                required: true,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "model_path".to_string(),
+                port_type: PortType::Path,
+                required: true,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "region_model_path".to_string(),
+                port_type: PortType::Path,
+                required: false,
+                default_value: Some(serde_json::json!("")),
+            },
+            PortDefinition {
+                name: "scale".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(4)),
+            },
+            PortDefinition {
+                name: "strength".to_string(),
+                port_type: PortType::Float,
+                required: false,
+                default_value: Some(serde_json::json!(defaults.strength)),
+            },
+            PortDefinition {
+                name: "region_strength".to_string(),
+                port_type: PortType::Float,
+                required: false,
+                default_value: Some(serde_json::json!(defaults.region_strength)),
+            },
+            PortDefinition {
+                name: "threshold".to_string(),
+                port_type: PortType::Float,
+                required: false,
+                default_value: Some(serde_json::json!(defaults.threshold)),
+            },
+            PortDefinition {
+                name: "feather".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(defaults.feather)),
+            },
+            PortDefinition {
+                name: "tile_size".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(0)),
+            },
+            PortDefinition {
+                name: "backend".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("cuda")),
+            },
+        ]
+    }
+
+    fn output_ports(&self) -> Vec<PortDefinition> {
+        vec![]
+    }
+
+    fn execute(
+        &mut self,
+        inputs: &HashMap<String, PortData>,
+        _ctx: &ExecutionContext,
+    ) -> Result<HashMap<String, PortData>> {
+        let mask_model_path = path_input(inputs, "mask_model_path")?
+            .context("RegionEnhance: mask_model_path is required")?;
+        let model_path =
+            path_input(inputs, "model_path")?.context("RegionEnhance: model_path is required")?;
+        let region_model_path = path_input(inputs, "region_model_path")?;
+
+        let fraction_input = |name: &str, default: f32| -> Result<f32> {
+            let value = match inputs.get(name) {
+                Some(PortData::Float(value)) => *value,
+                Some(PortData::Int(value)) => *value as f64,
+                Some(_) => bail!("RegionEnhance: {name} must be a Float"),
+                None => return Ok(default),
+            };
+            if !(0.0..=1.0).contains(&value) {
+                bail!("RegionEnhance: {name} must be between 0 and 1, got {value}");
+            }
+            Ok(value as f32)
+        };
+
+        let defaults = RegionSettings::default();
+        let mut settings = RegionSettings {
+            strength: fraction_input("strength", defaults.strength)?,
+            region_strength: fraction_input("region_strength", defaults.region_strength)?,
+            threshold: fraction_input("threshold", defaults.threshold)?,
+            feather: defaults.feather,
+        };
+        match inputs.get("feather") {
+            Some(PortData::Int(value)) if (0..=MAX_FEATHER).contains(value) => {
+                settings.feather = *value as usize;
+            }
+            Some(PortData::Int(value)) => {
+                bail!("RegionEnhance: feather must be between 0 and {MAX_FEATHER}, got {value}")
+            }
+            Some(_) => bail!("RegionEnhance: feather must be an Int"),
+            None => {}
+        }
+        self.scale = match inputs.get("scale") {
+            Some(PortData::Int(value)) if *value > 0 => *value as u32,
+            Some(PortData::Int(value)) => {
+                bail!("RegionEnhance: scale must be positive, got {value}")
+            }
+            Some(_) => bail!("RegionEnhance: scale must be an Int"),
+            None => 4,
+        };
+        self.settings = settings;
+
+        self.mask = Some(self.load_mask_model(&mask_model_path, inputs)?);
+        self.base = Some(self.load_upscaler(&model_path, inputs)?);
+        self.region = match region_model_path {
+            Some(path) => Some(self.load_upscaler(&path, inputs)?),
+            None => None,
+        };
+
+        Ok(HashMap::new())
+    }
+}
+
+impl FrameProcessor for RegionEnhanceNode {
+    fn process_frame(&mut self, frame: Frame, ctx: &ExecutionContext) -> Result<Frame> {
+        let Frame::CpuRgb {
+            data,
+            width,
+            height,
+            bit_depth,
+        } = frame
+        else {
+            bail!("RegionEnhance: only CPU RGB frames are supported");
+        };
+        let (Some(base), Some(mask_model)) = (self.base.as_mut(), self.mask.as_mut()) else {
+            bail!("RegionEnhance: models not loaded — call execute() first");
+        };
+
+        let (w, h) = (width as usize, height as usize);
+        let rgb8 = to_rgb8(&data, w, h, bit_depth).context("RegionEnhance: invalid input frame")?;
+        let (mask, mask_w, mask_h) = mask_model.predict(&rgb8, w, h)?;
+
+        let region_out = match self.region.as_mut() {
+            Some(region) => Some(upscale(
+                region,
+                data.clone(),
+                width,
+                height,
+                bit_depth,
+                ctx,
+            )?),
+            None => None,
+        };
+        let base_out = upscale(base, data, width, height, bit_depth, ctx)?;
+        let (out_w, out_h) = (w * self.scale as usize, h * self.scale as usize);
+        if base_out.len() != out_w * out_h * 3
+            || region_out
+                .as_ref()
+                .is_some_and(|region| region.len() != base_out.len())
+        {
+            bail!(
+                "RegionEnhance: models must upscale by exactly {}x (scale)",
+                self.scale
+            );
+        }
+
+        let plain = if self.settings.strength < 1.0 || self.settings.region_strength < 1.0 {
+            Some(resize_bilinear(&rgb8, w, h, out_w, out_h))
+        } else {
+            None
+        };
+        let mask = build_region_mask(&mask, mask_w, mask_h, out_w, out_h, &self.settings);
+        let composited = composite(
+            &base_out,
+            region_out.as_deref(),
+            plain.as_deref(),
+            &mask,
+            &self.settings,
+        );
+
+        Ok(Frame::CpuRgb {
+            data: composited,
+            width: out_w as u32,
+            height: out_h as u32,
+            bit_depth: 8,
+        })
+    }
+}
+
+impl MaskModel {
+    /// Runs the segmentation model on an 8-bit RGB frame and returns its
+    /// first output channel as `(probabilities, width, height)`.
+    fn predict(
+        &mut self,
+        rgb8: &[u8],
+        width: usize,
+        height: usize,
+    ) -> Result<(Vec<f32>, usize, usize)> {
+        let (in_h, in_w) = self
+            .fixed_size
+            .unwrap_or_else(|| mask_input_size(width, height));
+        let resized;
+        let pixels = if (in_w, in_h) == (width, height) {
+            rgb8
+        } else {
+            resized = resize_bilinear(rgb8, width, height, in_w, in_h);
+            &resized
+        };
+
+        let mut input = Array4::<f32>::zeros((1, 3, in_h, in_w));
+        for (i, px) in pixels.chunks_exact(3).enumerate() {
+            let (y, x) = (i / in_w, i % in_w);
+            for c in 0..3 {
+                input[[0, c, y, x]] = px[c] as f32 / 255.0;
+            }
+        }
+
+        let values: Vec<f32>;
+        let shape: Vec<usize>;
+        let mut session = self.session.lock().unwrap();
+        if self.is_fp16 {
+            let tensor = Tensor::from_array(input.mapv(f16::from_f32))?;
+            let outputs = session.run(ort::inputs![self.input_name.as_str() => &tensor])?;
+            let view = outputs[self.output_name.as_str()].try_extract_array::<f16>()?;
+            shape = view.shape().to_vec();
+            values = view.iter().map(|v| v.to_f32()).collect();
+        } else {
+            let tensor = Tensor::from_array(input)?;
+            let outputs = session.run(ort::inputs![self.input_name.as_str() => &tensor])?;
+            let view = outputs[self.output_name.as_str()].try_extract_array::<f32>()?;
+            shape = view.shape().to_vec();
+            values = view.iter().copied().collect();
+        }
+
+        first_mask_channel(values, &shape)
+    }
+}
+
+/// Picks the first `H×W` plane of an `[N, C, H, W]`, `[N, H, W]` or `[H, W]`
+/// mask output.
+fn first_mask_channel(mut values: Vec<f32>, shape: &[usize]) -> Result<(Vec<f32>, usize, usize)> {
+    if shape.len() < 2 || shape.len() > 4 {
+        bail!("RegionEnhance: unsupported mask output shape {shape:?}");
+    }
+    let mask_h = shape[shape.len() - 2];
+    let mask_w = shape[shape.len() - 1];
+    if mask_h == 0 || mask_w == 0 {
+        bail!("RegionEnhance: mask model returned an empty mask");
+    }
+    values.truncate(mask_w * mask_h);
+    Ok((values, mask_w, mask_h))
+}
+
+/// Input size for mask models with a dynamic shape: the long side scaled to
+/// [`MASK_LONG_SIDE`], both sides rounded to [`MASK_ALIGN`].
+fn mask_input_size(width: usize, height: usize) -> (usize, usize) {
+    let ratio = MASK_LONG_SIDE as f64 / width.max(height).max(1) as f64;
+    let align = |v: usize| {
+        let scaled = (v as f64 * ratio / MASK_ALIGN as f64).round() as usize;
+        scaled.max(1) * MASK_ALIGN
+    };
+    (align(height), align(width))
+}
+
+fn upscale(
+    node: &mut SuperResNode,
+    data: Vec<u8>,
+    width: u32,
+    height: u32,
+    bit_depth: u8,
+    ctx: &ExecutionContext,
+) -> Result<Vec<u8>> {
+    let frame = Frame::CpuRgb {
+        data,
+        width,
+        height,
+        bit_depth,
+    };
+    match node.process_frame(frame, ctx)? {
+        Frame::CpuRgb { data, .. } => Ok(data),
+        _ => bail!("RegionEnhance: upscaler returned a non-RGB frame"),
+    }
+}
+
+/// Scales the model mask to the output size, thresholds it and softens the
+/// edge with a separable box blur of radius `feather`.
+pub fn build_region_mask(
+    probabilities: &[f32],
+    mask_w: usize,
+    mask_h: usize,
+    out_w: usize,
+    out_h: usize,
+    settings: &RegionSettings,
+) -> Vec<f32> {
+    let mut mask = vec![0.0f32; out_w * out_h];
+    for y in 0..out_h {
+        let sy = ((y as f32 + 0.5) * mask_h as f32 / out_h as f32 - 0.5).max(0.0);
+        let y0 = (sy as usize).min(mask_h - 1);
+        let y1 = (y0 + 1).min(mask_h - 1);
+        let fy = sy - y0 as f32;
+        for x in 0..out_w {
+            let sx = ((x as f32 + 0.5) * mask_w as f32 / out_w as f32 - 0.5).max(0.0);
+            let x0 = (sx as usize).min(mask_w - 1);
+            let x1 = (x0 + 1).min(mask_w - 1);
+            let fx = sx - x0 as f32;
+            let top =
+                probabilities[y0 * mask_w + x0] * (1.0 - fx) + probabilities[y0 * mask_w + x1] * fx;
+            let bottom =
+                probabilities[y1 * mask_w + x0] * (1.0 - fx) + probabilities[y1 * mask_w + x1] * fx;
+            let p = top * (1.0 - fy) + bottom * fy;
+            mask[y * out_w + x] = if p >= settings.threshold { 1.0 } else { 0.0 };
+        }
+    }
+
+    if settings.feather > 0 {
+        box_blur(&mut mask, out_w, out_h, settings.feather, true);
+        box_blur(&mut mask, out_w, out_h, settings.feather, false);
+    }
+    mask
+}
+
+fn box_blur(mask: &mut [f32], width: usize, height: usize, radius: usize, horizontal: bool) {
+    let (lines, len) = if horizontal {
+        (height, width)
+    } else {
+        (width, height)
+    };
+    let index = |line: usize, i: usize| {
+        if horizontal {
+            line * width + i
+        } else {
+            i * width + line
+        }
+    };
+
+    let mut line_buf = vec![0.0f32; len];
+    for line in 0..lines {
+        for (i, value) in line_buf.iter_mut().enumerate() {
+            *value = mask[index(line, i)];
+        }
+        let mut sum: f32 = line_buf[..radius.min(len - 1) + 1].iter().sum();
+        let mut count = radius.min(len - 1) + 1;
+        for i in 0..len {
+            mask[index(line, i)] = sum / count as f32;
+            if i + radius + 1 < len {
+                sum += line_buf[i + radius + 1];
+                count += 1;
+            }
+            if i >= radius {
+                sum -= line_buf[i - radius];
+                count -= 1;
+            }
+        }
+    }
+}
+
+/// Blends the upscaled frames through the mask. Where `region` is `None`
+/// the base output is used inside the mask too; `plain` is required when
+/// either strength is below 1.
+pub fn composite(
+    base: &[u8],
+    region: Option<&[u8]>,
+    plain: Option<&[u8]>,
+    mask: &[f32],
+    settings: &RegionSettings,
+) -> Vec<u8> {
+    let region = region.unwrap_or(base);
+    let mut out = Vec::with_capacity(base.len());
+    for (i, &m) in mask.iter().enumerate() {
+        for c in 0..3 {
+            let idx = i * 3 + c;
+            let plain_value = plain.map_or(base[idx] as f32, |p| p[idx] as f32);
+            let outside = plain_value + (base[idx] as f32 - plain_value) * settings.strength;
+            let inside =
+                plain_value + (region[idx] as f32 - plain_value) * settings.region_strength;
+            let value = outside + (inside - outside) * m;
+            out.push(value.round().clamp(0.0, 255.0) as u8);
+        }
+    }
+    out
+}
+
+fn path_input(inputs: &HashMap<String, PortData>, name: &str) -> Result<Option<PathBuf>> {
+    let path = match inputs.get(name) {
+        Some(PortData::Path(path)) => path.clone(),
+        Some(PortData::Str(path)) => PathBuf::from(path),
+        Some(_) => bail!("RegionEnhance: {name} must be a Path"),
+        None => return Ok(None),
+    };
+    Ok((!path.as_os_str().is_empty()).then_some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_enhance_requires_models() {
+        let mut node = RegionEnhanceNode::new();
+        let err = node
+            .execute(&HashMap::new(), &ExecutionContext::default())
+            .err()
+            .expect("execute should fail");
+        assert!(err.to_string().contains("mask_model_path"), "{err}");
+
+        let mut inputs = HashMap::new();
+        inputs.insert(
+            "mask_model_path".to_string(),
+            PortData::Path(PathBuf::from("face.onnx")),
+        );
+        inputs.insert("model_path".to_string(), PortData::Path(PathBuf::new()));
+        let err = node
+            .execute(&inputs, &ExecutionContext::default())
+            .err()
+            .expect("execute should fail");
+        assert!(err.to_string().contains("model_path is required"), "{err}");
+    }
+
+    #[test]
+    fn test_region_enhance_rejects_out_of_range_settings() {
+        for (name, value) in [
+            ("strength", PortData::Float(1.5)),
+            ("region_strength", PortData::Float(-0.1)),
+            ("threshold", PortData::Float(2.0)),
+            ("feather", PortData::Int(65)),
+            ("scale", PortData::Int(0)),
+        ] {
+            let mut inputs = HashMap::new();
+            inputs.insert(
+                "mask_model_path".to_string(),
+                PortData::Path(PathBuf::from("face.onnx")),
+            );
+            inputs.insert(
+                "model_path".to_string(),
+                PortData::Path(PathBuf::from("sr.onnx")),
+            );
+            inputs.insert(name.to_string(), value);
+            let err = RegionEnhanceNode::new()
+                .execute(&inputs, &ExecutionContext::default())
+                .err()
+                .expect("execute should fail");
+            assert!(err.to_string().contains(name), "{name}: {err}");
+        }
+    }
+
+    #[test]
+    fn test_build_region_mask_thresholds_and_feathers() {
+        // Left half of a 2x1 mask is a face, right half is background.
+        let probabilities = [0.9, 0.1];
+        let sharp = RegionSettings {
+            feather: 0,
+            ..RegionSettings::default()
+        };
+        let mask = build_region_mask(&probabilities, 2, 1, 8, 1, &sharp);
+        assert_eq!(mask, [1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0]);
+
+        let soft = RegionSettings {
+            feather: 2,
+            ..RegionSettings::default()
+        };
+        let mask = build_region_mask(&probabilities, 2, 1, 8, 1, &soft);
+        assert_eq!(mask[0], 1.0);
+        assert_eq!(mask[7], 0.0);
+        assert!(mask[3] > mask[4] && mask[4] > 0.0 && mask[3] < 1.0);
+        assert!(mask.windows(2).all(|pair| pair[0] >= pair[1]));
+    }
+
+    #[test]
+    fn test_composite_uses_region_model_and_strengths() {
+        let base = [100u8; 6];
+        let region = [200u8; 6];
+        let plain = [0u8; 6];
+        let mask = [1.0, 0.0];
+
+        let full = composite(
+            &base,
+            Some(&region),
+            None,
+            &mask,
+            &RegionSettings::default(),
+        );
+        assert_eq!(full, [200, 200, 200, 100, 100, 100]);
+
+        let toned_down = RegionSettings {
+            strength: 0.5,
+            region_strength: 0.25,
+            ..RegionSettings::default()
+        };
+        let blended = composite(&base, None, Some(&plain), &mask, &toned_down);
+        assert_eq!(blended, [25, 25, 25, 50, 50, 50]);
+    }
+
+    #[test]
+    fn test_mask_helpers() {
+        assert_eq!(mask_input_size(1920, 1080), (288, 512));
+        assert_eq!(mask_input_size(16, 16), (512, 512));
+
+        let (plane, w, h) =
+            first_mask_channel(vec![0.1, 0.2, 0.3, 0.4, 9.0, 9.0, 9.0, 9.0], &[1, 2, 2, 2])
+                .unwrap();
+        assert_eq!((plane, w, h), (vec![0.1, 0.2, 0.3, 0.4], 2, 2));
+        assert!(first_mask_channel(vec![0.0], &[1]).is_err());
+    }
+}
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use anyhow::{bail, Result};
 
-use crate::node::{ExecutionContext, FrameProcessor, Node, PortDefinition};
+use crate::node::{ExecutionContext, FrameProcessor, Node, PortDefinition, ResourceClass};
 use crate::types::{Frame, PortData, PortType};
 
 use super::resize::ResizeAlgorithm;
@@ -32,6 +32,10 @@ impl Node for RescaleNode {
         "Rescale"
     }
 
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
     fn input_ports(&self) -> Vec<PortDefinition> {
         vec![
             PortDefinition {
@@ -4,7 +4,7 @@ use std::collections::HashMap;
 
 use anyhow::{bail, Result};
 
-use crate::node::{ExecutionContext, FrameProcessor, Node, PortDefinition};
+use crate::node::{ExecutionContext, FrameProcessor, Node, PortDefinition, ResourceClass};
 use crate::types::{Frame, PortData, PortType};
 
 /// Supported resize algorithms.
@@ -50,6 +50,10 @@ impl Node for ResizeNode {
         "Resize"
     }
 
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
     fn input_ports(&self) -> Vec<PortDefinition> {
         vec![
             PortDefinition {
@@ -166,6 +170,23 @@ impl FrameProcessor for ResizeNode {
     }
 }
 
+/// 8-bit copy of packed RGB frame data; high bit depth frames are `rgb48le`.
+pub(crate) fn to_rgb8(data: &[u8], width: usize, height: usize, bit_depth: u8) -> Result<Vec<u8>> {
+    let bytes_per_sample = if bit_depth > 8 { 2 } else { 1 };
+    let expected_len = width * height * 3 * bytes_per_sample;
+    if data.len() != expected_len {
+        bail!(
+            "Frame data length mismatch: expected {expected_len}, got {}",
+            data.len()
+        );
+    }
+    if bytes_per_sample == 2 {
+        Ok(data.chunks_exact(2).map(|sample| sample[1]).collect())
+    } else {
+        Ok(data.to_vec())
+    }
+}
+
 /// Nearest-neighbor resize for 8-bit RGB24 data.
 pub(crate) fn resize_nearest(
     src: &[u8],
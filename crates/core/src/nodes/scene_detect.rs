@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use anyhow::{bail, Result};
 
-use crate::node::{ExecutionContext, Node, PortDefinition};
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
 use crate::types::{Frame, PortData, PortType};
 
 const DOWNSCALE_WIDTH: usize = 160;
@@ -45,6 +45,10 @@ impl Node for SceneDetectNode {
         "SceneDetect"
     }
 
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
     fn input_ports(&self) -> Vec<PortDefinition> {
         vec![PortDefinition {
             name: "threshold".to_string(),
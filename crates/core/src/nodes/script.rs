@@ -0,0 +1,373 @@
+//! `Script` node: runs a small Rhai script over scalar port values.
+//!
+//! The script sees the inputs `a`, `b` and `c` as variables (`()` when not
+//! connected) and its last expression becomes the `value` output. The engine
+//! is sandboxed: there is no file, network or process access, `import` and
+//! `eval` are disabled, and operation, depth and size limits stop runaway
+//! scripts. The host only adds pure string helpers for path mangling.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Result};
+use rhai::{Dynamic, Engine, Scope};
+
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
+use crate::types::{PortData, PortType};
+
+const SCRIPT_INPUTS: [&str; 3] = ["a", "b", "c"];
+const MAX_SCRIPT_OPERATIONS: u64 = 1_000_000;
+const MAX_SCRIPT_CALL_LEVELS: usize = 32;
+const MAX_SCRIPT_EXPR_DEPTH: usize = 64;
+const MAX_SCRIPT_STRING_BYTES: usize = 1024 * 1024;
+const MAX_SCRIPT_COLLECTION_LEN: usize = 10_000;
+
+pub struct ScriptNode {
+    input_type: PortType,
+    output_type: PortType,
+}
+
+impl ScriptNode {
+    pub fn new() -> Self {
+        Self {
+            input_type: PortType::Str,
+            output_type: PortType::Str,
+        }
+    }
+
+    pub fn from_params(params: &HashMap<String, serde_json::Value>) -> Result<Self> {
+        let mut node = Self::new();
+        if let Some(value) = params.get("input_type") {
+            node.input_type = parse_script_type(value.as_str(), "input_type")?;
+        }
+        if let Some(value) = params.get("output_type") {
+            node.output_type = parse_script_type(value.as_str(), "output_type")?;
+        }
+        Ok(node)
+    }
+}
+
+impl Default for ScriptNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node for ScriptNode {
+    fn node_type(&self) -> &str {
+        "Script"
+    }
+
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
+    fn input_ports(&self) -> Vec<PortDefinition> {
+        let mut ports = vec![
+            PortDefinition {
+                name: "script".to_string(),
+                port_type: PortType::Str,
+                required: true,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "input_type".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!(script_type_name(&self.input_type))),
+            },
+            PortDefinition {
+                name: "output_type".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!(script_type_name(&self.output_type))),
+            },
+        ];
+
+        for name in SCRIPT_INPUTS {
+            ports.push(PortDefinition {
+                name: name.to_string(),
+                port_type: self.input_type.clone(),
+                required: false,
+                default_value: None,
+            });
+        }
+
+        ports
+    }
+
+    fn output_ports(&self) -> Vec<PortDefinition> {
+        vec![PortDefinition {
+            name: "value".to_string(),
+            port_type: self.output_type.clone(),
+            required: true,
+            default_value: None,
+        }]
+    }
+
+    fn execute(
+        &mut self,
+        inputs: &HashMap<String, PortData>,
+        _ctx: &ExecutionContext,
+    ) -> Result<HashMap<String, PortData>> {
+        let script = match inputs.get("script") {
+            Some(PortData::Str(value)) => value.as_str(),
+            Some(_) => bail!("Script: input 'script' must be Str"),
+            None => bail!("Script: input 'script' is required"),
+        };
+        if let Some(PortData::Str(value)) = inputs.get("output_type") {
+            self.output_type = parse_script_type(Some(value), "output_type")?;
+        }
+
+        let mut scope = Scope::new();
+        for name in SCRIPT_INPUTS {
+            let value = match inputs.get(name) {
+                Some(data) => port_data_to_dynamic(data, name)?,
+                None => Dynamic::UNIT,
+            };
+            scope.push_dynamic(name, value);
+        }
+
+        let result = sandboxed_engine()
+            .eval_with_scope::<Dynamic>(&mut scope, script)
+            .map_err(|err| anyhow!("Script: {err}"))?;
+        let value = dynamic_to_port_data(result, &self.output_type)?;
+
+        Ok(HashMap::from([("value".to_string(), value)]))
+    }
+}
+
+/// Builds an engine with only the capabilities scripts are meant to have.
+pub(crate) fn sandboxed_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new());
+    engine.disable_symbol("eval");
+    engine.set_max_operations(MAX_SCRIPT_OPERATIONS);
+    engine.set_max_call_levels(MAX_SCRIPT_CALL_LEVELS);
+    engine.set_max_expr_depths(MAX_SCRIPT_EXPR_DEPTH, MAX_SCRIPT_EXPR_DEPTH);
+    engine.set_max_string_size(MAX_SCRIPT_STRING_BYTES);
+    engine.set_max_array_size(MAX_SCRIPT_COLLECTION_LEN);
+    engine.set_max_map_size(MAX_SCRIPT_COLLECTION_LEN);
+    engine.on_print(|text| tracing::info!(target: "videnoa::script", "{text}"));
+    engine.on_debug(|text, _source, _pos| tracing::debug!(target: "videnoa::script", "{text}"));
+
+    engine.register_fn("file_name", |path: &str| path_part(path, |p| p.file_name()));
+    engine.register_fn("file_stem", |path: &str| path_part(path, |p| p.file_stem()));
+    engine.register_fn("extension", |path: &str| path_part(path, |p| p.extension()));
+    engine.register_fn("parent", |path: &str| {
+        Path::new(path)
+            .parent()
+            .map(|parent| parent.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+    engine.register_fn("with_extension", |path: &str, ext: &str| {
+        Path::new(path)
+            .with_extension(ext)
+            .to_string_lossy()
+            .into_owned()
+    });
+    engine.register_fn("join_path", |base: &str, child: &str| {
+        Path::new(base).join(child).to_string_lossy().into_owned()
+    });
+    engine.register_fn("zero_pad", |value: i64, width: i64| {
+        format!("{value:0width$}", width = width.clamp(0, 64) as usize)
+    });
+
+    engine
+}
+
+fn path_part(path: &str, part: impl Fn(&Path) -> Option<&std::ffi::OsStr>) -> String {
+    part(Path::new(path))
+        .map(|value| value.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn parse_script_type(value: Option<&str>, key: &str) -> Result<PortType> {
+    match value {
+        Some("Int") => Ok(PortType::Int),
+        Some("Float") => Ok(PortType::Float),
+        Some("Str") => Ok(PortType::Str),
+        Some("Bool") => Ok(PortType::Bool),
+        Some("Path") => Ok(PortType::Path),
+        Some(other) => {
+            bail!("Script: unsupported {key} '{other}', expected one of Int|Float|Str|Bool|Path")
+        }
+        None => bail!("Script: param '{key}' must be a string type name"),
+    }
+}
+
+fn script_type_name(port_type: &PortType) -> &'static str {
+    match port_type {
+        PortType::Int => "Int",
+        PortType::Float => "Float",
+        PortType::Bool => "Bool",
+        PortType::Path => "Path",
+        _ => "Str",
+    }
+}
+
+fn port_data_to_dynamic(data: &PortData, name: &str) -> Result<Dynamic> {
+    Ok(match data {
+        PortData::Int(value) => Dynamic::from_int(*value),
+        PortData::Float(value) => Dynamic::from_float(*value),
+        PortData::Str(value) => Dynamic::from(value.clone()),
+        PortData::Bool(value) => Dynamic::from_bool(*value),
+        PortData::Path(value) => Dynamic::from(value.to_string_lossy().into_owned()),
+        PortData::Metadata(_) => bail!("Script: input '{name}' cannot be Metadata"),
+        PortData::Json(value) => Dynamic::from(value.to_string()),
+        PortData::List(items) => Dynamic::from_array(
+            items
+                .iter()
+                .map(|item| port_data_to_dynamic(item, name))
+                .collect::<Result<_>>()?,
+        ),
+    })
+}
+
+fn dynamic_to_port_data(value: Dynamic, output_type: &PortType) -> Result<PortData> {
+    let type_name = value.type_name();
+    let mismatch = || {
+        anyhow!(
+            "Script: expected {} result, got {type_name}",
+            script_type_name(output_type)
+        )
+    };
+
+    match output_type {
+        PortType::Int => value.as_int().map(PortData::Int).map_err(|_| mismatch()),
+        PortType::Float => value
+            .as_float()
+            .or_else(|_| value.as_int().map(|v| v as f64))
+            .map(PortData::Float)
+            .map_err(|_| mismatch()),
+        PortType::Bool => value.as_bool().map(PortData::Bool).map_err(|_| mismatch()),
+        PortType::Path => value
+            .into_string()
+            .map(|v| PortData::Path(PathBuf::from(v)))
+            .map_err(|_| mismatch()),
+        _ => {
+            if value.is_string() {
+                value
+                    .into_string()
+                    .map(PortData::Str)
+                    .map_err(|_| mismatch())
+            } else if value.is_unit() {
+                Err(mismatch())
+            } else {
+                Ok(PortData::Str(value.to_string()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(
+        params: &[(&str, serde_json::Value)],
+        inputs: Vec<(&str, PortData)>,
+    ) -> Result<PortData> {
+        let params: HashMap<String, serde_json::Value> = params
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect();
+        let mut node = ScriptNode::from_params(&params)?;
+        let inputs: HashMap<String, PortData> = inputs
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect();
+        let mut outputs = node.execute(&inputs, &ExecutionContext::default())?;
+        Ok(outputs.remove("value").expect("value output"))
+    }
+
+    #[test]
+    fn test_script_mangles_file_names() {
+        let value = run(
+            &[("output_type", serde_json::json!("Path"))],
+            vec![
+                (
+                    "script",
+                    PortData::Str(
+                        r#"join_path(parent(a), file_stem(a) + "_" + zero_pad(42, 3) + ".mkv")"#
+                            .to_string(),
+                    ),
+                ),
+                ("a", PortData::Str("/media/show/ep.mp4".to_string())),
+            ],
+        )
+        .unwrap();
+
+        match value {
+            PortData::Path(path) => assert_eq!(path, PathBuf::from("/media/show/ep_042.mkv")),
+            _ => panic!("expected path output"),
+        }
+    }
+
+    #[test]
+    fn test_script_numeric_logic_and_unset_inputs() {
+        let value = run(
+            &[
+                ("input_type", serde_json::json!("Int")),
+                ("output_type", serde_json::json!("Float")),
+            ],
+            vec![
+                (
+                    "script",
+                    PortData::Str("if c == () { a * b } else { c }".to_string()),
+                ),
+                ("a", PortData::Int(3)),
+                ("b", PortData::Int(4)),
+            ],
+        )
+        .unwrap();
+
+        match value {
+            PortData::Float(v) => assert_eq!(v, 12.0),
+            _ => panic!("expected float output"),
+        }
+    }
+
+    #[test]
+    fn test_script_reports_type_mismatch() {
+        let err = run(
+            &[("output_type", serde_json::json!("Int"))],
+            vec![("script", PortData::Str(r#""text""#.to_string()))],
+        )
+        .err()
+        .expect("string result should not convert to Int");
+        assert!(err.to_string().contains("expected Int result"));
+
+        let err = run(&[("output_type", serde_json::json!("Nope"))], vec![])
+            .err()
+            .expect("unknown type should be rejected");
+        assert!(err.to_string().contains("unsupported output_type"));
+    }
+
+    #[test]
+    fn test_script_sandbox_limits() {
+        let err = run(&[], vec![("script", PortData::Str("loop {}".to_string()))])
+            .err()
+            .expect("infinite loop should hit the operation limit");
+        assert!(err.to_string().contains("Too many operations"));
+
+        let err = run(
+            &[],
+            vec![(
+                "script",
+                PortData::Str(r#"import "os" as os; 1"#.to_string()),
+            )],
+        )
+        .err()
+        .expect("imports are disabled");
+        assert!(err.to_string().starts_with("Script:"));
+
+        let err = run(
+            &[],
+            vec![("script", PortData::Str(r#"eval("1")"#.to_string()))],
+        )
+        .err()
+        .expect("eval is disabled");
+        assert!(err.to_string().starts_with("Script:"));
+    }
+}
@@ -1,20 +1,27 @@
-//! StreamInput node: accepts a URL (HTTP, RTMP, etc.) as a video source.
+//! StreamInput node: reads a live feed (RTMP, RTSP, HLS, ...) as a video source.
 //!
 //! Similar to `VideoInputNode` but uses URL-based input instead of a file path.
-//! FFmpeg handles network protocol decoding natively, so `VideoDecoder` can be
-//! reused with the URL as the input path.
+//! FFmpeg handles network protocol decoding natively, so `VideoDecoder` is
+//! reused with the URL as the input path. [`LiveStreamDecoder`] wraps it and
+//! restarts FFmpeg when the feed drops, so the streaming executor keeps
+//! receiving frames for as long as the source is live.
 
 use std::collections::HashMap;
 use std::path::Path;
 use std::process::Stdio;
+use std::thread;
+use std::time::Duration;
 
 use anyhow::{bail, Context, Result};
-use tracing::debug;
+use tracing::{debug, info, warn};
 
-use crate::node::{ExecutionContext, Node, PortDefinition};
-use crate::types::{PortData, PortType};
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
+use crate::types::{Frame, PortData, PortType};
 
-use crate::nodes::video_input::{extract_metadata, FfprobeOutput};
+use crate::nodes::video_input::{extract_metadata, FfprobeOutput, VideoDecoder, VideoStreamInfo};
+
+pub const DEFAULT_MAX_RECONNECTS: i64 = 10;
+pub const DEFAULT_RECONNECT_DELAY_SECS: i64 = 2;
 
 /// Validate that a string looks like a plausible stream URL.
 ///
@@ -42,8 +49,31 @@ fn validate_stream_url(url: &str) -> Result<()> {
     Ok(())
 }
 
+/// FFmpeg input options for `url`: the user's `protocol_options`
+/// (whitespace-separated, e.g. `-rtsp_transport tcp`) plus HTTP reconnect
+/// flags for HLS / progressive HTTP sources.
+pub fn stream_input_args(url: &str, protocol_options: &str) -> Vec<String> {
+    let mut args: Vec<String> = Vec::new();
+    let lower = url.to_ascii_lowercase();
+    if lower.starts_with("http://") || lower.starts_with("https://") {
+        args.extend(
+            [
+                "-reconnect",
+                "1",
+                "-reconnect_streamed",
+                "1",
+                "-reconnect_delay_max",
+                "5",
+            ]
+            .map(String::from),
+        );
+    }
+    args.extend(protocol_options.split_whitespace().map(String::from));
+    args
+}
+
 /// Run ffprobe against a URL and parse the JSON output.
-fn run_ffprobe_url(url: &str) -> Result<FfprobeOutput> {
+fn run_ffprobe_url(url: &str, input_args: &[String]) -> Result<FfprobeOutput> {
     let output = crate::runtime::command_for("ffprobe")
         .args([
             "-v",
@@ -54,6 +84,7 @@ fn run_ffprobe_url(url: &str) -> Result<FfprobeOutput> {
             "-show_streams",
             "-show_chapters",
         ])
+        .args(input_args)
         .arg(url)
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -75,6 +106,162 @@ fn run_ffprobe_url(url: &str) -> Result<FfprobeOutput> {
     Ok(probe)
 }
 
+/// Probe a live source, returning the video stream layout decoded frames
+/// will have.
+pub fn probe_stream(url: &str, input_args: &[String]) -> Result<VideoStreamInfo> {
+    let probe = run_ffprobe_url(url, input_args)?;
+    let (video_info, _metadata) = extract_metadata(&probe, Path::new(url))?;
+    Ok(video_info)
+}
+
+#[derive(Debug, Clone)]
+pub struct LiveStreamConfig {
+    pub url: String,
+    pub input_args: Vec<String>,
+    /// Consecutive failed (re)connects tolerated before the stream is treated
+    /// as ended.
+    pub max_reconnects: u32,
+    pub reconnect_delay: Duration,
+}
+
+impl LiveStreamConfig {
+    /// Read the settings [`StreamInputNode`] passes through its outputs.
+    pub fn from_outputs(outputs: &HashMap<String, PortData>) -> Result<Self> {
+        let url = match outputs.get("source_url") {
+            Some(PortData::Str(url)) => url.clone(),
+            _ => bail!("StreamInput output 'source_url' is missing"),
+        };
+        let protocol_options = match outputs.get("protocol_options") {
+            Some(PortData::Str(value)) => value.as_str(),
+            _ => "",
+        };
+        let max_reconnects = match outputs.get("max_reconnects") {
+            Some(PortData::Int(value)) => *value,
+            _ => DEFAULT_MAX_RECONNECTS,
+        };
+        let reconnect_delay_secs = match outputs.get("reconnect_delay_secs") {
+            Some(PortData::Int(value)) => *value,
+            _ => DEFAULT_RECONNECT_DELAY_SECS,
+        };
+
+        Ok(Self {
+            input_args: stream_input_args(&url, protocol_options),
+            url,
+            max_reconnects: max_reconnects.clamp(0, u32::MAX as i64) as u32,
+            reconnect_delay: Duration::from_secs(reconnect_delay_secs.max(0) as u64),
+        })
+    }
+}
+
+/// Frame iterator over a live feed. When FFmpeg exits or errors, it waits
+/// `reconnect_delay` and starts it again; after `max_reconnects` consecutive
+/// attempts without a frame the stream is considered over and iteration
+/// ends, letting the encoder finalize its output.
+pub struct LiveStreamDecoder {
+    config: LiveStreamConfig,
+    info: VideoStreamInfo,
+    decoder: Option<VideoDecoder>,
+    failed_attempts: u32,
+    frames_decoded: u64,
+    done: bool,
+}
+
+impl LiveStreamDecoder {
+    pub fn new(config: LiveStreamConfig, info: VideoStreamInfo) -> Result<Self> {
+        let decoder = VideoDecoder::with_input_args(
+            Path::new(&config.url),
+            &info,
+            Some("none"),
+            &config.input_args,
+        )
+        .context("failed to start live stream decoder")?;
+        Ok(Self {
+            config,
+            info,
+            decoder: Some(decoder),
+            failed_attempts: 0,
+            frames_decoded: 0,
+            done: false,
+        })
+    }
+
+    fn reconnect(&mut self) -> Result<()> {
+        thread::sleep(self.config.reconnect_delay);
+        let info = probe_stream(&self.config.url, &self.config.input_args)?;
+        if (info.width, info.height) != (self.info.width, self.info.height) {
+            bail!(
+                "stream resolution changed from {}x{} to {}x{}",
+                self.info.width,
+                self.info.height,
+                info.width,
+                info.height
+            );
+        }
+        self.info = info;
+        self.decoder = Some(VideoDecoder::with_input_args(
+            Path::new(&self.config.url),
+            &self.info,
+            Some("none"),
+            &self.config.input_args,
+        )?);
+        Ok(())
+    }
+}
+
+impl Iterator for LiveStreamDecoder {
+    type Item = Result<Frame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let Some(decoder) = self.decoder.as_mut() else {
+                if self.failed_attempts >= self.config.max_reconnects {
+                    self.done = true;
+                    info!(
+                        url = %self.config.url,
+                        frames = self.frames_decoded,
+                        "live stream ended"
+                    );
+                    if self.frames_decoded == 0 {
+                        return Some(Err(anyhow::anyhow!(
+                            "no frames received from {}",
+                            self.config.url
+                        )));
+                    }
+                    return None;
+                }
+                self.failed_attempts += 1;
+                warn!(
+                    url = %self.config.url,
+                    attempt = self.failed_attempts,
+                    max = self.config.max_reconnects,
+                    "reconnecting to live stream"
+                );
+                if let Err(err) = self.reconnect() {
+                    warn!(url = %self.config.url, error = %format!("{err:#}"), "reconnect failed");
+                }
+                continue;
+            };
+
+            match decoder.next() {
+                Some(Ok(frame)) => {
+                    self.failed_attempts = 0;
+                    self.frames_decoded += 1;
+                    return Some(Ok(frame));
+                }
+                Some(Err(err)) => {
+                    warn!(url = %self.config.url, error = %format!("{err:#}"), "live stream read failed");
+                    self.decoder = None;
+                }
+                None => {
+                    debug!(url = %self.config.url, "live stream decoder reached EOF");
+                    self.decoder = None;
+                }
+            }
+        }
+        None
+    }
+}
+
 pub struct StreamInputNode;
 
 impl StreamInputNode {
@@ -94,6 +281,10 @@ impl Node for StreamInputNode {
         "stream_input"
     }
 
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Network
+    }
+
     fn input_ports(&self) -> Vec<PortDefinition> {
         vec![
             PortDefinition {
@@ -108,6 +299,18 @@ impl Node for StreamInputNode {
                 required: false,
                 default_value: Some(serde_json::json!("")),
             },
+            PortDefinition {
+                name: "max_reconnects".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(DEFAULT_MAX_RECONNECTS)),
+            },
+            PortDefinition {
+                name: "reconnect_delay_secs".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(DEFAULT_RECONNECT_DELAY_SECS)),
+            },
         ]
     }
 
@@ -140,8 +343,27 @@ impl Node for StreamInputNode {
 
         validate_stream_url(&url)?;
 
+        let protocol_options = match inputs.get("protocol_options") {
+            Some(PortData::Str(s)) => s.clone(),
+            _ => String::new(),
+        };
+        let max_reconnects = match inputs.get("max_reconnects") {
+            Some(PortData::Int(v)) if *v < 0 => {
+                bail!("max_reconnects must not be negative, got {v}")
+            }
+            Some(PortData::Int(v)) => *v,
+            _ => DEFAULT_MAX_RECONNECTS,
+        };
+        let reconnect_delay_secs = match inputs.get("reconnect_delay_secs") {
+            Some(PortData::Int(v)) if *v < 0 => {
+                bail!("reconnect_delay_secs must not be negative, got {v}")
+            }
+            Some(PortData::Int(v)) => *v,
+            _ => DEFAULT_RECONNECT_DELAY_SECS,
+        };
+
         debug!(url = %url, "running ffprobe on stream URL");
-        let probe = run_ffprobe_url(&url)?;
+        let probe = run_ffprobe_url(&url, &stream_input_args(&url, &protocol_options))?;
 
         // Use a synthetic path for metadata (stream URLs don't have local paths)
         let synthetic_path = Path::new(&url);
@@ -160,6 +382,17 @@ impl Node for StreamInputNode {
         let mut outputs = HashMap::new();
         outputs.insert("metadata".to_string(), PortData::Metadata(metadata));
         outputs.insert("source_url".to_string(), PortData::Str(url));
+        // Not ports: read back by the compile context to configure the
+        // live decoder (see `LiveStreamConfig::from_outputs`).
+        outputs.insert(
+            "protocol_options".to_string(),
+            PortData::Str(protocol_options),
+        );
+        outputs.insert("max_reconnects".to_string(), PortData::Int(max_reconnects));
+        outputs.insert(
+            "reconnect_delay_secs".to_string(),
+            PortData::Int(reconnect_delay_secs),
+        );
         Ok(outputs)
     }
 }
@@ -178,13 +411,15 @@ mod tests {
     fn test_input_ports() {
         let node = StreamInputNode::new();
         let ports = node.input_ports();
-        assert_eq!(ports.len(), 2);
+        assert_eq!(ports.len(), 4);
         assert_eq!(ports[0].name, "url");
         assert_eq!(ports[0].port_type, PortType::Str);
         assert!(ports[0].required);
         assert_eq!(ports[1].name, "protocol_options");
         assert_eq!(ports[1].port_type, PortType::Str);
         assert!(!ports[1].required);
+        assert_eq!(ports[2].name, "max_reconnects");
+        assert_eq!(ports[3].name, "reconnect_delay_secs");
     }
 
     #[test]
@@ -287,6 +522,51 @@ mod tests {
         assert!(msg.contains("empty"), "error: {msg}");
     }
 
+    #[test]
+    fn test_execute_rejects_negative_reconnects() {
+        let mut node = StreamInputNode::new();
+        let ctx = ExecutionContext::default();
+        let inputs = HashMap::from([
+            (
+                "url".to_string(),
+                PortData::Str("rtsp://camera.local:554/stream".to_string()),
+            ),
+            ("max_reconnects".to_string(), PortData::Int(-1)),
+        ]);
+        let msg = node.execute(&inputs, &ctx).err().unwrap().to_string();
+        assert!(msg.contains("max_reconnects"), "error: {msg}");
+    }
+
+    #[test]
+    fn test_stream_input_args_http_adds_reconnect_flags() {
+        let args = stream_input_args("https://cdn.example.com/live.m3u8", "");
+        assert!(args.windows(2).any(|w| w[0] == "-reconnect" && w[1] == "1"));
+        assert!(args
+            .windows(2)
+            .any(|w| w[0] == "-reconnect_streamed" && w[1] == "1"));
+    }
+
+    #[test]
+    fn test_stream_input_args_appends_protocol_options() {
+        let args = stream_input_args("rtsp://camera.local/stream", " -rtsp_transport  tcp ");
+        assert_eq!(args, vec!["-rtsp_transport", "tcp"]);
+    }
+
+    #[test]
+    fn test_live_stream_config_from_outputs_defaults() {
+        let outputs = HashMap::from([(
+            "source_url".to_string(),
+            PortData::Str("rtmp://live.example.com/app/key".to_string()),
+        )]);
+        let config = LiveStreamConfig::from_outputs(&outputs).unwrap();
+        assert_eq!(config.max_reconnects, DEFAULT_MAX_RECONNECTS as u32);
+        assert_eq!(
+            config.reconnect_delay,
+            Duration::from_secs(DEFAULT_RECONNECT_DELAY_SECS as u64)
+        );
+        assert!(config.input_args.is_empty());
+    }
+
     #[test]
     fn test_default_trait() {
         let node = StreamInputNode::default();
@@ -1,13 +1,43 @@
 use std::collections::HashMap;
 use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
 use std::process::{Child, ChildStdin, Stdio};
 use std::thread::{self, JoinHandle};
 
 use anyhow::{bail, Context, Result};
 use tracing::{debug, info};
 
-use crate::node::{ExecutionContext, Node, PortDefinition};
-use crate::types::{PortData, PortType};
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
+use crate::nodes::video_output::{nchw_f16_to_rgb, nchw_f32_to_rgb};
+use crate::streaming_executor::FrameSink;
+use crate::types::{Frame, PortData, PortType};
+
+/// Push frames to `url` (RTMP, SRT, UDP, ...).
+pub const STREAM_MODE_PUSH: &str = "push";
+/// Write an HLS playlist that the API server serves under `/api/streams/{id}/`.
+pub const STREAM_MODE_HLS: &str = "hls";
+pub const HLS_PLAYLIST_NAME: &str = "playlist.m3u8";
+const HLS_SEGMENT_SECS: u32 = 2;
+const HLS_LIST_SIZE: u32 = 6;
+
+/// Stream ids name a directory under the streams root and appear in URLs.
+pub fn validate_stream_id(stream_id: &str) -> Result<()> {
+    if stream_id.is_empty() || stream_id.len() > 64 {
+        bail!("stream_id must be 1-64 characters long");
+    }
+    if !stream_id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!("stream_id may only contain letters, digits, '-' and '_': '{stream_id}'");
+    }
+    Ok(())
+}
+
+/// Server path of the HLS playlist for `stream_id`.
+pub fn hls_playlist_url(stream_id: &str) -> String {
+    format!("/api/streams/{stream_id}/{HLS_PLAYLIST_NAME}")
+}
 
 fn validate_stream_url(url: &str) -> Result<()> {
     if url.is_empty() {
@@ -97,6 +127,26 @@ impl StreamEncoderConfig {
             args.push("no_duration_filesize".into());
         }
 
+        if self.format == "hls" {
+            let segment_pattern = Path::new(&self.url)
+                .with_file_name("segment_%05d.ts")
+                .to_string_lossy()
+                .into_owned();
+            args.extend([
+                // Keyframes on segment boundaries so every segment is playable.
+                "-force_key_frames".into(),
+                format!("expr:gte(t,n_forced*{HLS_SEGMENT_SECS})"),
+                "-hls_time".into(),
+                HLS_SEGMENT_SECS.to_string(),
+                "-hls_list_size".into(),
+                HLS_LIST_SIZE.to_string(),
+                "-hls_flags".into(),
+                "delete_segments+independent_segments".into(),
+                "-hls_segment_filename".into(),
+                segment_pattern,
+            ]);
+        }
+
         args.push(self.url.clone());
 
         args
@@ -117,6 +167,20 @@ pub struct StreamEncoder {
 
 impl StreamEncoder {
     pub fn new(config: &StreamEncoderConfig) -> Result<Self> {
+        if config.format == "hls" {
+            // Start from an empty directory so players never see segments
+            // from a previous run.
+            let dir = Path::new(&config.url)
+                .parent()
+                .context("HLS playlist path has no parent directory")?;
+            if dir.exists() {
+                std::fs::remove_dir_all(dir)
+                    .with_context(|| format!("failed to clear {}", dir.display()))?;
+            }
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+
         let args = config.build_ffmpeg_args();
         let frame_size = config.frame_size();
 
@@ -139,7 +203,9 @@ impl StreamEncoder {
             .ok_or_else(|| anyhow::anyhow!("failed to open ffmpeg stdin"))?;
 
         let stderr = child.stderr.take().expect("stderr should be piped");
+        let stderr_span = tracing::Span::current();
         let stderr_thread = thread::spawn(move || {
+            let _entered = stderr_span.enter();
             let reader = BufReader::new(stderr);
             for line in reader.lines() {
                 match line {
@@ -192,7 +258,7 @@ impl StreamEncoder {
         Ok(())
     }
 
-    pub fn finish(mut self) -> Result<()> {
+    pub fn finish(&mut self) -> Result<()> {
         drop(self.stdin.take());
 
         let status = self.child.wait().context("failed to wait for ffmpeg")?;
@@ -210,6 +276,35 @@ impl StreamEncoder {
     }
 }
 
+impl FrameSink for StreamEncoder {
+    fn write_frame(&mut self, frame: &Frame) -> Result<()> {
+        match frame {
+            Frame::CpuRgb { data, .. } => StreamEncoder::write_frame(self, data),
+            Frame::NchwF16 {
+                data,
+                height,
+                width,
+            } => {
+                let rgb = nchw_f16_to_rgb(data, *height as usize, *width as usize)?;
+                StreamEncoder::write_frame(self, &rgb)
+            }
+            Frame::NchwF32 {
+                data,
+                height,
+                width,
+            } => {
+                let rgb = nchw_f32_to_rgb(data, *height as usize, *width as usize)?;
+                StreamEncoder::write_frame(self, &rgb)
+            }
+            _ => bail!("unsupported Frame variant for stream encoding"),
+        }
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        StreamEncoder::finish(self)
+    }
+}
+
 impl Drop for StreamEncoder {
     fn drop(&mut self) {
         drop(self.stdin.take());
@@ -240,13 +335,17 @@ impl Node for StreamOutputNode {
         "stream_output"
     }
 
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Network
+    }
+
     fn input_ports(&self) -> Vec<PortDefinition> {
         vec![
             PortDefinition {
                 name: "url".to_string(),
                 port_type: PortType::Str,
-                required: true,
-                default_value: None,
+                required: false,
+                default_value: Some(serde_json::json!("")),
             },
             PortDefinition {
                 name: "codec".to_string(),
@@ -272,6 +371,18 @@ impl Node for StreamOutputNode {
                 required: false,
                 default_value: Some(serde_json::json!("")),
             },
+            PortDefinition {
+                name: "mode".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!(STREAM_MODE_PUSH)),
+            },
+            PortDefinition {
+                name: "stream_id".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("")),
+            },
         ]
     }
 
@@ -289,12 +400,26 @@ impl Node for StreamOutputNode {
         inputs: &HashMap<String, PortData>,
         _ctx: &ExecutionContext,
     ) -> Result<HashMap<String, PortData>> {
+        let mode = stream_mode(inputs)?;
         let url = match inputs.get("url") {
             Some(PortData::Str(u)) => u.clone(),
-            _ => bail!("missing or invalid 'url' input (expected Str)"),
+            _ => String::new(),
+        };
+        let stream_id = match inputs.get("stream_id") {
+            Some(PortData::Str(s)) => s.clone(),
+            _ => String::new(),
         };
 
-        validate_stream_url(&url)?;
+        let output_url = if mode == STREAM_MODE_HLS {
+            validate_stream_id(&stream_id)?;
+            hls_playlist_url(&stream_id)
+        } else {
+            if !inputs.contains_key("url") {
+                bail!("missing or invalid 'url' input (expected Str)");
+            }
+            validate_stream_url(&url)?;
+            url.clone()
+        };
 
         let codec = match inputs.get("codec") {
             Some(PortData::Str(s)) => s.clone(),
@@ -306,13 +431,18 @@ impl Node for StreamOutputNode {
             _ => "5M".to_string(),
         };
 
-        let format = match inputs.get("format") {
-            Some(PortData::Str(s)) if !s.is_empty() => s.clone(),
-            _ => detect_format_from_url(&url).unwrap_or("flv").to_string(),
+        let format = if mode == STREAM_MODE_HLS {
+            "hls".to_string()
+        } else {
+            match inputs.get("format") {
+                Some(PortData::Str(s)) if !s.is_empty() => s.clone(),
+                _ => detect_format_from_url(&url).unwrap_or("flv").to_string(),
+            }
         };
 
         debug!(
-            url = %url,
+            mode = %mode,
+            url = %output_url,
             codec = %codec,
             bitrate = %bitrate,
             format = %format,
@@ -320,21 +450,63 @@ impl Node for StreamOutputNode {
         );
 
         let mut outputs = HashMap::new();
-        outputs.insert("output_url".to_string(), PortData::Str(url));
+        outputs.insert("output_url".to_string(), PortData::Str(output_url));
+        // Not ports: read back by the compile context to build the encoder
+        // (see `stream_encoder_config_from_inputs`).
+        for (key, value) in [
+            ("mode", mode),
+            ("url", url),
+            ("stream_id", stream_id),
+            ("codec", codec),
+            ("bitrate", bitrate),
+            ("format", format),
+        ] {
+            outputs.insert(key.to_string(), PortData::Str(value));
+        }
         Ok(outputs)
     }
 }
 
+fn stream_mode(inputs: &HashMap<String, PortData>) -> Result<String> {
+    match inputs.get("mode") {
+        Some(PortData::Str(s)) if s == STREAM_MODE_PUSH || s.is_empty() => {
+            Ok(STREAM_MODE_PUSH.to_string())
+        }
+        Some(PortData::Str(s)) if s == STREAM_MODE_HLS => Ok(STREAM_MODE_HLS.to_string()),
+        Some(PortData::Str(s)) => {
+            bail!("unsupported stream mode '{s}' (expected '{STREAM_MODE_PUSH}' or '{STREAM_MODE_HLS}')")
+        }
+        _ => Ok(STREAM_MODE_PUSH.to_string()),
+    }
+}
+
+/// Build the encoder config for a StreamOutput node. In HLS mode the
+/// playlist is written to `<streams_dir>/<stream_id>/playlist.m3u8`.
 pub fn stream_encoder_config_from_inputs(
     inputs: &HashMap<String, PortData>,
     width: u32,
     height: u32,
     fps: &str,
     bit_depth: u8,
+    streams_dir: &Path,
 ) -> Result<StreamEncoderConfig> {
-    let url = match inputs.get("url") {
-        Some(PortData::Str(u)) => u.clone(),
-        _ => bail!("missing or invalid 'url' input"),
+    let mode = stream_mode(inputs)?;
+    let url = if mode == STREAM_MODE_HLS {
+        let stream_id = match inputs.get("stream_id") {
+            Some(PortData::Str(s)) => s.clone(),
+            _ => bail!("missing or invalid 'stream_id' input"),
+        };
+        validate_stream_id(&stream_id)?;
+        streams_dir
+            .join(&stream_id)
+            .join(HLS_PLAYLIST_NAME)
+            .to_string_lossy()
+            .into_owned()
+    } else {
+        match inputs.get("url") {
+            Some(PortData::Str(u)) => u.clone(),
+            _ => bail!("missing or invalid 'url' input"),
+        }
     };
 
     let codec = match inputs.get("codec") {
@@ -347,9 +519,13 @@ pub fn stream_encoder_config_from_inputs(
         _ => "5M".to_string(),
     };
 
-    let format = match inputs.get("format") {
-        Some(PortData::Str(s)) if !s.is_empty() => s.clone(),
-        _ => detect_format_from_url(&url).unwrap_or("flv").to_string(),
+    let format = if mode == STREAM_MODE_HLS {
+        "hls".to_string()
+    } else {
+        match inputs.get("format") {
+            Some(PortData::Str(s)) if !s.is_empty() => s.clone(),
+            _ => detect_format_from_url(&url).unwrap_or("flv").to_string(),
+        }
     };
 
     Ok(StreamEncoderConfig {
@@ -378,10 +554,12 @@ mod tests {
     fn test_input_ports() {
         let node = StreamOutputNode::new();
         let ports = node.input_ports();
-        assert_eq!(ports.len(), 5);
+        assert_eq!(ports.len(), 7);
 
         let names: Vec<&str> = ports.iter().map(|p| p.name.as_str()).collect();
         assert!(names.contains(&"url"));
+        assert!(names.contains(&"mode"));
+        assert!(names.contains(&"stream_id"));
         assert!(names.contains(&"codec"));
         assert!(names.contains(&"bitrate"));
         assert!(names.contains(&"format"));
@@ -392,7 +570,7 @@ mod tests {
             .filter(|p| p.required)
             .map(|p| p.name.as_str())
             .collect();
-        assert!(required.contains(&"url"));
+        assert!(!required.contains(&"url"), "url is unused in HLS mode");
         assert!(!required.contains(&"codec"));
         assert!(!required.contains(&"bitrate"));
         assert!(!required.contains(&"format"));
@@ -586,7 +764,9 @@ mod tests {
             PortData::Str("rtmp://live.example.com/app/key".to_string()),
         );
 
-        let config = stream_encoder_config_from_inputs(&inputs, 1920, 1080, "30/1", 8).unwrap();
+        let config =
+            stream_encoder_config_from_inputs(&inputs, 1920, 1080, "30/1", 8, Path::new("streams"))
+                .unwrap();
         assert_eq!(config.codec, "libx264");
         assert_eq!(config.bitrate, "5M");
         assert_eq!(config.format, "flv");
@@ -607,8 +787,15 @@ mod tests {
         inputs.insert("bitrate".to_string(), PortData::Str("10M".to_string()));
         inputs.insert("format".to_string(), PortData::Str("mpegts".to_string()));
 
-        let config =
-            stream_encoder_config_from_inputs(&inputs, 3840, 2160, "24000/1001", 10).unwrap();
+        let config = stream_encoder_config_from_inputs(
+            &inputs,
+            3840,
+            2160,
+            "24000/1001",
+            10,
+            Path::new("streams"),
+        )
+        .unwrap();
         assert_eq!(config.codec, "libx265");
         assert_eq!(config.bitrate, "10M");
         assert_eq!(config.format, "mpegts");
@@ -617,6 +804,69 @@ mod tests {
         assert_eq!(config.bit_depth, 10);
     }
 
+    #[test]
+    fn test_execute_hls_mode_reports_playlist_url() {
+        let mut node = StreamOutputNode::new();
+        let ctx = ExecutionContext::default();
+        let inputs = HashMap::from([
+            ("mode".to_string(), PortData::Str("hls".to_string())),
+            (
+                "stream_id".to_string(),
+                PortData::Str("living-room".to_string()),
+            ),
+        ]);
+        let outputs = node.execute(&inputs, &ctx).unwrap();
+        match outputs.get("output_url") {
+            Some(PortData::Str(url)) => {
+                assert_eq!(url, "/api/streams/living-room/playlist.m3u8")
+            }
+            _ => panic!("output_url should be a string"),
+        }
+    }
+
+    #[test]
+    fn test_execute_rejects_unknown_mode_and_bad_stream_id() {
+        let mut node = StreamOutputNode::new();
+        let ctx = ExecutionContext::default();
+
+        let inputs = HashMap::from([("mode".to_string(), PortData::Str("dash".to_string()))]);
+        let msg = node.execute(&inputs, &ctx).err().unwrap().to_string();
+        assert!(msg.contains("unsupported stream mode"), "error: {msg}");
+
+        let inputs = HashMap::from([
+            ("mode".to_string(), PortData::Str("hls".to_string())),
+            ("stream_id".to_string(), PortData::Str("../etc".to_string())),
+        ]);
+        let msg = node.execute(&inputs, &ctx).err().unwrap().to_string();
+        assert!(msg.contains("stream_id"), "error: {msg}");
+    }
+
+    #[test]
+    fn test_hls_config_writes_playlist_under_streams_dir() {
+        let inputs = HashMap::from([
+            ("mode".to_string(), PortData::Str("hls".to_string())),
+            ("stream_id".to_string(), PortData::Str("cam1".to_string())),
+        ]);
+        let config = stream_encoder_config_from_inputs(
+            &inputs,
+            1280,
+            720,
+            "30/1",
+            8,
+            Path::new("/data/streams"),
+        )
+        .unwrap();
+        assert_eq!(config.format, "hls");
+        assert_eq!(config.url, "/data/streams/cam1/playlist.m3u8");
+
+        let args = config.build_ffmpeg_args();
+        assert!(args.windows(2).any(
+            |w| w[0] == "-hls_segment_filename" && w[1] == "/data/streams/cam1/segment_%05d.ts"
+        ));
+        assert!(args.contains(&"-force_key_frames".to_string()));
+        assert_eq!(args.last().unwrap(), "/data/streams/cam1/playlist.m3u8");
+    }
+
     #[test]
     fn test_default_trait() {
         let node = StreamOutputNode::default();
@@ -1,10 +1,12 @@
 //! SuperResolution node: upscaling via `ort::Session` + CUDA/TensorRT EP.
 //!
 //! Supports both FP32 models (e.g. Real-ESRGAN, value range 0–255)
-//! and FP16 models (e.g. AnimeJaNai, value range 0–1).
+//! and FP16 models (e.g. AnimeJaNai, value range 0–1). Those are the native
+//! ranges of the two paths; models that want another range, BGR input or a
+//! different padding multiple are handled by [`ModelPreprocess`].
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 use anyhow::{bail, Context, Result};
@@ -14,19 +16,38 @@ use ndarray::{s, Array4};
 use ort::{session::Session, value::Tensor};
 use tracing::debug;
 
+use crate::benchmark::AUTO_MODEL;
 use crate::node::{ExecutionContext, FrameProcessor, Node, PortDefinition};
+use crate::runtime::memory::{memory_governor, MemoryKind, MemoryReservation};
+use crate::runtime::session_pool::{acquire_session, SessionLease};
 use crate::types::{Frame, PortData, PortType};
 
-use crate::nodes::backend::{build_session, InferenceBackend, SessionConfig};
+use crate::nodes::backend::{InferenceBackend, SessionConfig};
+use crate::nodes::model_preprocess::ModelPreprocess;
 
 /// Tile overlap in pixels per side — prevents seam artifacts between tiles.
 const DEFAULT_TILE_OVERLAP: usize = 16;
 
-/// Model requires spatial dimensions to be multiples of this.
+/// Smallest tile the VRAM budget may shrink tiling down to.
+const MIN_BUDGET_TILE: usize = 128;
+
+/// Rough VRAM per input pixel of a tile batch, per byte of tensor element:
+/// input, upscaled output and the intermediate feature maps.
+const TILE_VRAM_FACTOR: u64 = 64;
+
+/// Spatial dimensions are padded to multiples of this unless the model asks
+/// for another `pad_align`.
 const PAD_ALIGN: usize = 4;
 
+/// Native tensor range of the FP32 path.
+const FP32_RANGE: (f32, f32) = (0.0, 255.0);
+/// Native tensor range of the FP16 path.
+const FP16_RANGE: (f32, f32) = (0.0, 1.0);
+
 pub struct SuperResNode {
     session: Option<Arc<Mutex<Session>>>,
+    /// Returns `session` to the session pool when dropped.
+    session_lease: Option<SessionLease>,
     scale: u32,
     tile_size: u32,
     backend: InferenceBackend,
@@ -35,6 +56,9 @@ pub struct SuperResNode {
     input_name: Option<String>,
     output_name: Option<String>,
     is_fp16_model: bool,
+    preprocess: ModelPreprocess,
+    /// Model chosen for `model_path: auto` at compile time.
+    model_override: Option<PathBuf>,
     /// Reusable f32 NCHW buffer for FP32 path — avoids ~24 MB allocation per frame at 1080p.
     f32_nchw_buf: Option<Array4<f32>>,
     /// Reusable f16 NCHW buffer for FP16 path — avoids ~12 MB allocation per frame at 1080p.
@@ -48,6 +72,7 @@ impl SuperResNode {
     pub fn new() -> Self {
         Self {
             session: None,
+            session_lease: None,
             scale: 4,
             tile_size: 0,
             backend: InferenceBackend::default(),
@@ -56,6 +81,8 @@ impl SuperResNode {
             input_name: None,
             output_name: None,
             is_fp16_model: false,
+            preprocess: ModelPreprocess::identity(FP32_RANGE, PAD_ALIGN),
+            model_override: None,
             f32_nchw_buf: None,
             f16_nchw_buf: None,
             emit_tensor: false,
@@ -70,6 +97,11 @@ impl SuperResNode {
         self.trt_cache_dir = Some(dir);
     }
 
+    /// Loads `path` instead of the `model_path` input.
+    pub fn set_model_override(&mut self, path: PathBuf) {
+        self.model_override = Some(path);
+    }
+
     pub fn is_fp16(&self) -> bool {
         self.is_fp16_model
     }
@@ -77,6 +109,31 @@ impl SuperResNode {
     pub fn tile_size(&self) -> u32 {
         self.tile_size
     }
+
+    /// Tile size for a `height`×`width` frame, shrunk until one tile batch
+    /// fits the VRAM budget, together with the reservation for that batch.
+    fn budgeted_tile(&self, height: usize, width: usize) -> (usize, MemoryReservation) {
+        let element_bytes = if self.is_fp16_model { 2 } else { 4 };
+        let scale = self.scale.max(1) as u64;
+        let bytes_per_pixel = element_bytes * (1 + scale * scale) * TILE_VRAM_FACTOR;
+        let governor = memory_governor();
+        let requested = self.tile_size as usize;
+        let tile =
+            governor.fit_tile_size(requested, height, width, bytes_per_pixel, MIN_BUDGET_TILE);
+        if tile != requested {
+            debug!(
+                requested,
+                tile, "Shrinking super-resolution tiles to fit the VRAM budget"
+            );
+        }
+        let edge = |len: usize| if tile == 0 { len } else { tile.min(len) };
+        let bytes = (edge(height) * edge(width)) as u64 * bytes_per_pixel;
+        (tile, governor.reserve(MemoryKind::Vram, bytes))
+    }
+
+    pub fn preprocess(&self) -> &ModelPreprocess {
+        &self.preprocess
+    }
 }
 
 impl Default for SuperResNode {
@@ -103,14 +160,16 @@ impl SuperResNode {
     /// 2. **Inference** (GPU, ~53ms): `NchwF16 → NchwF16` — pad → session.run → unpad
     /// 3. **Postprocess** (CPU, ~30ms): `NchwF16 → CpuRgb` — f16→u8 ×255, CHW→HWC
     ///
-    /// Returns `None` if the model is FP32 or uses tiled inference (tile_size > 0),
-    /// in which case the caller should fall back to using the whole `SuperResNode`
-    /// as a single `FrameProcessor` stage.
+    /// Returns `None` if the model is FP32, uses tiled inference (tile_size > 0)
+    /// or needs value-range/channel-order conversion, in which case the caller
+    /// should fall back to using the whole `SuperResNode` as a single
+    /// `FrameProcessor` stage.
     pub fn into_micro_stages(self) -> Option<SuperResMicroStages> {
-        if !self.is_fp16_model || self.tile_size > 0 {
+        if !self.is_fp16_model || self.tile_size > 0 || !self.preprocess.is_identity() {
             return None;
         }
         let session = self.session?;
+        let session_lease = self.session_lease;
         let input_name = self.input_name?;
         let output_name = self.output_name?;
 
@@ -118,9 +177,11 @@ impl SuperResNode {
             preprocess: SuperResPreprocess { f16_nchw_buf: None },
             inference: SuperResInference {
                 session,
+                _session_lease: session_lease,
                 scale: self.scale as usize,
                 input_name,
                 output_name,
+                pad_align: self.preprocess.pad_align,
             },
             postprocess: SuperResPostprocess,
         })
@@ -315,13 +376,15 @@ impl FrameProcessor for SuperResPreprocess {
 
 /// Runs ORT/TensorRT FP16 inference: `NchwF16 → NchwF16`.
 ///
-/// Internally pads to `PAD_ALIGN` multiples, runs `session.run()`, and crops
+/// Internally pads to `pad_align` multiples, runs `session.run()`, and crops
 /// the output back to `(orig_h * scale, orig_w * scale)`.
 pub struct SuperResInference {
     session: Arc<Mutex<Session>>,
+    _session_lease: Option<SessionLease>,
     scale: usize,
     input_name: String,
     output_name: String,
+    pad_align: usize,
 }
 
 impl Node for SuperResInference {
@@ -361,7 +424,7 @@ impl FrameProcessor for SuperResInference {
         let input_arr = ndarray::ArrayD::from_shape_vec(ndarray::IxDyn(&[1, 3, h, w]), f16_vec)
             .context("SuperResInference: failed to reshape input")?;
 
-        let padded = pad_f16_nchw(&input_arr, h, w);
+        let padded = pad_f16_nchw(&input_arr, h, w, self.pad_align);
 
         let output_owned = {
             let mut session = self.session.lock().unwrap();
@@ -518,6 +581,54 @@ impl Node for SuperResNode {
                 required: false,
                 default_value: Some(serde_json::json!("cuda")),
             },
+            PortDefinition {
+                name: "value_range".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("auto")),
+            },
+            PortDefinition {
+                name: "output_range".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("same")),
+            },
+            PortDefinition {
+                name: "channel_order".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("rgb")),
+            },
+            PortDefinition {
+                name: "pad_align".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(0)),
+            },
+            PortDefinition {
+                name: "auto_target_fps".to_string(),
+                port_type: PortType::Float,
+                required: false,
+                default_value: Some(serde_json::json!(0.0)),
+            },
+            PortDefinition {
+                name: "auto_preference".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("quality")),
+            },
+            PortDefinition {
+                name: "skip_static_regions".to_string(),
+                port_type: PortType::Bool,
+                required: false,
+                default_value: Some(serde_json::json!(false)),
+            },
+            PortDefinition {
+                name: "static_threshold".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(crate::roi::DEFAULT_STATIC_THRESHOLD)),
+            },
         ]
     }
 
@@ -530,11 +641,15 @@ impl Node for SuperResNode {
         inputs: &HashMap<String, PortData>,
         _ctx: &ExecutionContext,
     ) -> Result<HashMap<String, PortData>> {
-        let model_path = match inputs.get("model_path") {
-            Some(PortData::Path(p)) => p.clone(),
-            Some(_) => bail!("model_path must be a Path"),
-            None => bail!("model_path is required"),
+        let model_path = match (&self.model_override, inputs.get("model_path")) {
+            (Some(p), _) => p.clone(),
+            (None, Some(PortData::Path(p))) => p.clone(),
+            (None, Some(_)) => bail!("model_path must be a Path"),
+            (None, None) => bail!("model_path is required"),
         };
+        if model_path == Path::new(AUTO_MODEL) {
+            bail!("SuperResolution: model_path 'auto' is only resolved when a video workflow is compiled");
+        }
 
         if let Some(PortData::Int(s)) = inputs.get("scale") {
             self.scale = *s as u32;
@@ -563,27 +678,62 @@ impl Node for SuperResNode {
             trt_cache_dir: self.trt_cache_dir.as_deref(),
         };
 
-        let session = build_session(&config)?;
+        let (session_arc, lease) = acquire_session(&config)?;
+        let session = session_arc.lock().unwrap();
 
         let input_name = session.inputs()[0].name().to_string();
         let output_name = session.outputs()[0].name().to_string();
-        let is_fp16 = match session.inputs()[0].dtype() {
+        let (is_fp16, fixed_size) = match session.inputs()[0].dtype() {
+            ort::value::ValueType::Tensor { ty, shape, .. } => (
+                *ty == ort::tensor::TensorElementType::Float16,
+                shape.len() == 4 && shape[2] > 0 && shape[3] > 0,
+            ),
+            _ => (false, false),
+        };
+        let output_fp16 = match session.outputs()[0].dtype() {
             ort::value::ValueType::Tensor { ty, .. } => {
                 *ty == ort::tensor::TensorElementType::Float16
             }
             _ => false,
         };
+        drop(session);
+        if is_fp16 != output_fp16 {
+            let dtype = |fp16: bool| if fp16 { "float16" } else { "float32" };
+            bail!(
+                "SuperResolution: model takes {} but returns {}; mixed-precision models \
+                 are not supported, use ModelInference instead",
+                dtype(is_fp16),
+                dtype(output_fp16)
+            );
+        }
+        if fixed_size {
+            bail!(
+                "SuperResolution: model only accepts a fixed input size; \
+                 use ModelInference, which resizes frames to fit"
+            );
+        }
+
+        let native = if is_fp16 { FP16_RANGE } else { FP32_RANGE };
+        let preprocess = ModelPreprocess::from_inputs(
+            "SuperResolution",
+            inputs,
+            &model_path,
+            native,
+            PAD_ALIGN,
+        )?;
 
         debug!(
-            %input_name, %output_name, is_fp16,
+            %input_name, %output_name, is_fp16, ?preprocess,
             "Detected model IO"
         );
 
         self.input_name = Some(input_name);
         self.output_name = Some(output_name);
         self.is_fp16_model = is_fp16;
+        self.preprocess = preprocess;
 
-        self.session = Some(Arc::new(Mutex::new(session)));
+        self.session = Some(session_arc);
+        self.session_lease = Some(lease);
         debug!("Model loaded successfully");
 
         Ok(HashMap::new())
@@ -608,20 +758,25 @@ impl FrameProcessor for SuperResNode {
                 bit_depth,
             } => {
                 let scale = self.scale as usize;
-                let tile_size = self.tile_size as usize;
+                let (tile_size, _tile_memory) = self.budgeted_tile(height as usize, width as usize);
                 let in_name = self.input_name.as_deref().unwrap_or("image.1");
                 let out_name = self.output_name.as_deref().unwrap_or("image");
 
+                let preprocess = self.preprocess;
+                let align = preprocess.pad_align;
+
                 if self.is_fp16_model {
-                    let (input_f16, orig_h, orig_w) = cpu_rgb_to_f16_nchw_into(
+                    let (mut input_f16, orig_h, orig_w) = cpu_rgb_to_f16_nchw_into(
                         &data,
                         width,
                         height,
                         bit_depth,
+                        align,
                         &mut self.f16_nchw_buf,
                     )?;
+                    remap_nchw(&mut input_f16, |v| preprocess.to_model(v, 3));
 
-                    let output_f16 = if tile_size > 0 {
+                    let mut output_f16 = if tile_size > 0 {
                         run_tiled_f16_inference(
                             &session_arc,
                             &input_f16,
@@ -631,6 +786,7 @@ impl FrameProcessor for SuperResNode {
                             scale,
                             in_name,
                             out_name,
+                            align,
                         )?
                     } else {
                         run_single_f16_inference(
@@ -643,6 +799,7 @@ impl FrameProcessor for SuperResNode {
                             out_name,
                         )?
                     };
+                    remap_nchw(&mut output_f16, |v| preprocess.from_model(v, 3));
 
                     let out_h = orig_h * scale;
                     let out_w = orig_w * scale;
@@ -671,15 +828,17 @@ impl FrameProcessor for SuperResNode {
                         })
                     }
                 } else {
-                    let (input_array, orig_h, orig_w) = cpu_rgb_to_nchw_into(
+                    let (mut input_array, orig_h, orig_w) = cpu_rgb_to_nchw_into(
                         &data,
                         width,
                         height,
                         bit_depth,
+                        align,
                         &mut self.f32_nchw_buf,
                     )?;
+                    remap_nchw(&mut input_array, |v| preprocess.to_model(v, 3));
 
-                    let output_array = if tile_size > 0 {
+                    let mut output_array = if tile_size > 0 {
                         run_tiled_inference(
                             &session_arc,
                             &input_array,
@@ -691,6 +850,7 @@ impl FrameProcessor for SuperResNode {
                             in_name,
                             out_name,
                             false,
+                            align,
                         )?
                     } else {
                         run_single_inference(
@@ -705,6 +865,7 @@ impl FrameProcessor for SuperResNode {
                             false,
                         )?
                     };
+                    remap_nchw(&mut output_array, |v| preprocess.from_model(v, 3));
 
                     let out_h = orig_h * scale;
                     let out_w = orig_w * scale;
@@ -724,16 +885,19 @@ impl FrameProcessor for SuperResNode {
                 height,
             } => {
                 let scale = self.scale as usize;
-                let tile_size = self.tile_size as usize;
-                let in_name = self.input_name.as_deref().unwrap_or("image.1");
-                let out_name = self.output_name.as_deref().unwrap_or("image");
                 let h = height as usize;
                 let w = width as usize;
+                let (tile_size, _tile_memory) = self.budgeted_tile(h, w);
+                let in_name = self.input_name.as_deref().unwrap_or("image.1");
+                let out_name = self.output_name.as_deref().unwrap_or("image");
+                let preprocess = self.preprocess;
+                let align = preprocess.pad_align;
 
                 if self.is_fp16_model {
-                    let input_f16 = nchw_f32_to_f16_padded(&data, h, w)?;
+                    let mut input_f16 = nchw_f32_to_f16_padded(&data, h, w, align)?;
+                    remap_nchw(&mut input_f16, |v| preprocess.to_model(v, 3));
 
-                    let output_f16 = if tile_size > 0 {
+                    let mut output_f16 = if tile_size > 0 {
                         run_tiled_f16_inference(
                             &session_arc,
                             &input_f16,
@@ -743,6 +907,7 @@ impl FrameProcessor for SuperResNode {
                             scale,
                             in_name,
                             out_name,
+                            align,
                         )?
                     } else {
                         run_single_f16_inference(
@@ -755,6 +920,7 @@ impl FrameProcessor for SuperResNode {
                             out_name,
                         )?
                     };
+                    remap_nchw(&mut output_f16, |v| preprocess.from_model(v, 3));
 
                     let out_h = h * scale;
                     let out_w = w * scale;
@@ -787,9 +953,10 @@ impl FrameProcessor for SuperResNode {
                     let rescaled: Vec<f32> = data.iter().map(|&v| v * 255.0).collect();
                     let arr = Array4::from_shape_vec((1, 3, h, w), rescaled)
                         .context("SuperResNode: failed to reshape NchwF32 input")?;
-                    let padded = pad_nchw(&arr, h, w);
+                    let mut padded = pad_nchw(&arr, h, w, align);
+                    remap_nchw(&mut padded, |v| preprocess.to_model(v, 3));
 
-                    let output_array = if tile_size > 0 {
+                    let mut output_array = if tile_size > 0 {
                         run_tiled_inference(
                             &session_arc,
                             &padded,
@@ -801,6 +968,7 @@ impl FrameProcessor for SuperResNode {
                             in_name,
                             out_name,
                             false,
+                            align,
                         )?
                     } else {
                         run_single_inference(
@@ -815,6 +983,7 @@ impl FrameProcessor for SuperResNode {
                             false,
                         )?
                     };
+                    remap_nchw(&mut output_array, |v| preprocess.from_model(v, 3));
 
                     let out_h = h * scale;
                     let out_w = w * scale;
@@ -833,6 +1002,20 @@ impl FrameProcessor for SuperResNode {
     }
 }
 
+/// Applies an in-place [`ModelPreprocess`] conversion to an owned NCHW array,
+/// making it contiguous first if needed. Identity conversions cost nothing.
+fn remap_nchw<T: Clone, D: ndarray::Dimension>(
+    arr: &mut ndarray::Array<T, D>,
+    remap: impl FnOnce(&mut [T]),
+) {
+    if arr.as_slice_mut().is_none() {
+        *arr = arr.as_standard_layout().into_owned();
+    }
+    if let Some(values) = arr.as_slice_mut() {
+        remap(values);
+    }
+}
+
 /// Convert interleaved HWC CPU RGB bytes → NCHW `[1,3,H,W]` float32 (0–255 range).
 ///
 /// Returns `(padded_array, original_h, original_w)`. The array is reflection-padded
@@ -844,7 +1027,7 @@ fn cpu_rgb_to_nchw(
     height: u32,
     bit_depth: u8,
 ) -> Result<(Array4<f32>, usize, usize)> {
-    cpu_rgb_to_nchw_into(data, width, height, bit_depth, &mut None)
+    cpu_rgb_to_nchw_into(data, width, height, bit_depth, PAD_ALIGN, &mut None)
 }
 
 fn cpu_rgb_to_nchw_into(
@@ -852,6 +1035,7 @@ fn cpu_rgb_to_nchw_into(
     width: u32,
     height: u32,
     bit_depth: u8,
+    align: usize,
     buf: &mut Option<Array4<f32>>,
 ) -> Result<(Array4<f32>, usize, usize)> {
     let h = height as usize;
@@ -955,7 +1139,7 @@ fn cpu_rgb_to_nchw_into(
         _ => bail!("Unsupported bit depth: {bit_depth} (expected 8..=16)"),
     };
 
-    let padded = pad_nchw(&nchw, h, w);
+    let padded = pad_nchw(&nchw, h, w, align);
     *buf = Some(nchw);
     Ok((padded, h, w))
 }
@@ -978,10 +1162,11 @@ fn quantize_high_bit_sample_to_u8(sample: u32, source_max: u32) -> u8 {
     (((clamped * 255) + source_max / 2) / source_max) as u8
 }
 
-/// Reflection-pad NCHW array so H and W are multiples of [`PAD_ALIGN`].
-fn pad_nchw(arr: &Array4<f32>, h: usize, w: usize) -> Array4<f32> {
-    let pad_h = (PAD_ALIGN - (h % PAD_ALIGN)) % PAD_ALIGN;
-    let pad_w = (PAD_ALIGN - (w % PAD_ALIGN)) % PAD_ALIGN;
+/// Reflection-pad NCHW array so H and W are multiples of `align`. Padding
+/// wider than the image repeats the edge.
+fn pad_nchw(arr: &Array4<f32>, h: usize, w: usize, align: usize) -> Array4<f32> {
+    let pad_h = pad_amount(h, align);
+    let pad_w = pad_amount(w, align);
 
     if pad_h == 0 && pad_w == 0 {
         return arr.clone();
@@ -996,7 +1181,7 @@ fn pad_nchw(arr: &Array4<f32>, h: usize, w: usize) -> Array4<f32> {
         .assign(&arr.slice(s![.., .., ..h, ..w]));
 
     for y in 0..pad_h {
-        let src_y = h - 1 - y;
+        let src_y = (h - 1).saturating_sub(y);
         for c in 0..3 {
             for x in 0..w {
                 padded[[0, c, h + y, x]] = arr[[0, c, src_y, x]];
@@ -1005,10 +1190,14 @@ fn pad_nchw(arr: &Array4<f32>, h: usize, w: usize) -> Array4<f32> {
     }
 
     for x in 0..pad_w {
-        let src_x = w - 1 - x;
+        let src_x = (w - 1).saturating_sub(x);
         for c in 0..3 {
             for y in 0..new_h {
-                let src_y = if y < h { y } else { h - 1 - (y - h) };
+                let src_y = if y < h {
+                    y
+                } else {
+                    (h - 1).saturating_sub(y - h)
+                };
                 padded[[0, c, y, w + x]] = arr[[0, c, src_y, src_x]];
             }
         }
@@ -1017,11 +1206,16 @@ fn pad_nchw(arr: &Array4<f32>, h: usize, w: usize) -> Array4<f32> {
     padded
 }
 
-fn pad_amount(dim: usize) -> usize {
-    (PAD_ALIGN - (dim % PAD_ALIGN)) % PAD_ALIGN
+fn pad_amount(dim: usize, align: usize) -> usize {
+    (align - (dim % align)) % align
 }
 
-fn nchw_f32_to_f16_padded(data: &[f32], h: usize, w: usize) -> Result<ndarray::ArrayD<f16>> {
+fn nchw_f32_to_f16_padded(
+    data: &[f32],
+    h: usize,
+    w: usize,
+    align: usize,
+) -> Result<ndarray::ArrayD<f16>> {
     let expected = 3 * h * w;
     if data.len() != expected {
         bail!(
@@ -1044,12 +1238,17 @@ fn nchw_f32_to_f16_padded(data: &[f32], h: usize, w: usize) -> Result<ndarray::A
         offset += len;
     }
 
-    Ok(pad_f16_nchw(&nchw, h, w))
+    Ok(pad_f16_nchw(&nchw, h, w, align))
 }
 
-fn pad_f16_nchw(arr: &ndarray::ArrayD<f16>, h: usize, w: usize) -> ndarray::ArrayD<f16> {
-    let pad_h = (PAD_ALIGN - (h % PAD_ALIGN)) % PAD_ALIGN;
-    let pad_w = (PAD_ALIGN - (w % PAD_ALIGN)) % PAD_ALIGN;
+fn pad_f16_nchw(
+    arr: &ndarray::ArrayD<f16>,
+    h: usize,
+    w: usize,
+    align: usize,
+) -> ndarray::ArrayD<f16> {
+    let pad_h = pad_amount(h, align);
+    let pad_w = pad_amount(w, align);
 
     if pad_h == 0 && pad_w == 0 {
         return arr.clone();
@@ -1064,7 +1263,7 @@ fn pad_f16_nchw(arr: &ndarray::ArrayD<f16>, h: usize, w: usize) -> ndarray::Arra
         .assign(&arr.slice(s![.., .., ..h, ..w]));
 
     for y in 0..pad_h {
-        let src_y = h - 1 - y;
+        let src_y = (h - 1).saturating_sub(y);
         for c in 0..3usize {
             for x in 0..w {
                 padded[[0, c, h + y, x]] = arr[[0, c, src_y, x]];
@@ -1073,10 +1272,14 @@ fn pad_f16_nchw(arr: &ndarray::ArrayD<f16>, h: usize, w: usize) -> ndarray::Arra
     }
 
     for x in 0..pad_w {
-        let src_x = w - 1 - x;
+        let src_x = (w - 1).saturating_sub(x);
         for c in 0..3usize {
             for y in 0..new_h {
-                let src_y = if y < h { y } else { h - 1 - (y - h) };
+                let src_y = if y < h {
+                    y
+                } else {
+                    (h - 1).saturating_sub(y - h)
+                };
                 padded[[0, c, y, w + x]] = arr[[0, c, src_y, src_x]];
             }
         }
@@ -1095,7 +1298,7 @@ fn cpu_rgb_to_f16_nchw(
     width: u32,
     height: u32,
 ) -> Result<(ndarray::ArrayD<f16>, usize, usize)> {
-    cpu_rgb_to_f16_nchw_into(data, width, height, 8, &mut None)
+    cpu_rgb_to_f16_nchw_into(data, width, height, 8, PAD_ALIGN, &mut None)
 }
 
 fn cpu_rgb_to_f16_nchw_into(
@@ -1103,6 +1306,7 @@ fn cpu_rgb_to_f16_nchw_into(
     width: u32,
     height: u32,
     bit_depth: u8,
+    align: usize,
     buf: &mut Option<ndarray::ArrayD<f16>>,
 ) -> Result<(ndarray::ArrayD<f16>, usize, usize)> {
     let h = height as usize;
@@ -1181,7 +1385,7 @@ fn cpu_rgb_to_f16_nchw_into(
         offset += len;
     }
 
-    let padded = pad_f16_nchw(&nchw, h, w);
+    let padded = pad_f16_nchw(&nchw, h, w, align);
     *buf = Some(nchw);
     Ok((padded, h, w))
 }
@@ -1361,6 +1565,7 @@ fn run_tiled_inference(
     input_name: &str,
     output_name: &str,
     is_fp16: bool,
+    align: usize,
 ) -> Result<Array4<f32>> {
     let out_h = orig_h * scale;
     let out_w = orig_w * scale;
@@ -1392,8 +1597,8 @@ fn run_tiled_inference(
             let tile_h = in_y1 - in_y0;
             let tile_w = in_x1 - in_x0;
 
-            let tile_pad_h = pad_amount(tile_h);
-            let tile_pad_w = pad_amount(tile_w);
+            let tile_pad_h = pad_amount(tile_h, align);
+            let tile_pad_w = pad_amount(tile_w, align);
 
             let tile_input = if tile_pad_h > 0 || tile_pad_w > 0 {
                 let raw_tile = input
@@ -1403,6 +1608,7 @@ fn run_tiled_inference(
                     &raw_tile.into_dimensionality::<ndarray::Ix4>()?,
                     tile_h,
                     tile_w,
+                    align,
                 )
             } else {
                 input
@@ -1499,6 +1705,7 @@ fn run_tiled_f16_inference(
     scale: usize,
     input_name: &str,
     output_name: &str,
+    align: usize,
 ) -> Result<ndarray::ArrayD<f16>> {
     let out_h = orig_h * scale;
     let out_w = orig_w * scale;
@@ -1530,8 +1737,8 @@ fn run_tiled_f16_inference(
             let tile_h = in_y1 - in_y0;
             let tile_w = in_x1 - in_x0;
 
-            let tile_pad_h = pad_amount(tile_h);
-            let tile_pad_w = pad_amount(tile_w);
+            let tile_pad_h = pad_amount(tile_h, align);
+            let tile_pad_w = pad_amount(tile_w, align);
 
             let raw_tile = input
                 .slice(s![.., .., in_y0..in_y1, in_x0..in_x1])
@@ -1539,7 +1746,7 @@ fn run_tiled_f16_inference(
                 .into_dyn();
 
             let tile_input = if tile_pad_h > 0 || tile_pad_w > 0 {
-                pad_f16_nchw(&raw_tile, tile_h, tile_w)
+                pad_f16_nchw(&raw_tile, tile_h, tile_w, align)
             } else {
                 raw_tile
             };
@@ -1659,14 +1866,14 @@ mod tests {
     #[test]
     fn test_pad_nchw_no_padding() {
         let arr = Array4::<f32>::ones((1, 3, 8, 8));
-        let padded = pad_nchw(&arr, 8, 8);
+        let padded = pad_nchw(&arr, 8, 8, PAD_ALIGN);
         assert_eq!(padded.shape(), &[1, 3, 8, 8]);
     }
 
     #[test]
     fn test_pad_nchw_needs_padding() {
         let arr = Array4::<f32>::ones((1, 3, 5, 6));
-        let padded = pad_nchw(&arr, 5, 6);
+        let padded = pad_nchw(&arr, 5, 6, PAD_ALIGN);
         assert_eq!(padded.shape(), &[1, 3, 8, 8]);
         assert_eq!(padded[[0, 0, 0, 0]], 1.0);
         assert_eq!(padded[[0, 0, 4, 5]], 1.0);
@@ -1675,6 +1882,20 @@ mod tests {
         assert_eq!(padded[[0, 0, 7, 0]], padded[[0, 0, 2, 0]]);
     }
 
+    #[test]
+    fn test_pad_wider_than_frame_repeats_edge() {
+        // 2x3 frame padded to a multiple of 8: reflection runs out of rows.
+        let arr = Array4::from_shape_fn((1, 3, 2, 3), |(_, _, y, x)| (y * 3 + x) as f32);
+        let padded = pad_nchw(&arr, 2, 3, 8);
+        assert_eq!(padded.shape(), &[1, 3, 8, 8]);
+        assert_eq!(padded[[0, 0, 1, 2]], 5.0);
+        assert_eq!(padded[[0, 0, 7, 7]], 0.0);
+
+        let f16_arr = arr.mapv(f16::from_f32).into_dyn();
+        let padded = pad_f16_nchw(&f16_arr, 2, 3, 8);
+        assert_eq!(padded.shape(), &[1, 3, 8, 8]);
+    }
+
     #[test]
     fn test_nchw_to_cpu_rgb_basic() {
         let mut arr = Array4::<f32>::zeros((1, 3, 2, 2));
@@ -1710,13 +1931,13 @@ mod tests {
 
     #[test]
     fn test_pad_amount() {
-        assert_eq!(pad_amount(4), 0);
-        assert_eq!(pad_amount(5), 3);
-        assert_eq!(pad_amount(6), 2);
-        assert_eq!(pad_amount(7), 1);
-        assert_eq!(pad_amount(8), 0);
-        assert_eq!(pad_amount(1080), 0);
-        assert_eq!(pad_amount(720), 0);
+        assert_eq!(pad_amount(4, PAD_ALIGN), 0);
+        assert_eq!(pad_amount(5, PAD_ALIGN), 3);
+        assert_eq!(pad_amount(6, PAD_ALIGN), 2);
+        assert_eq!(pad_amount(7, PAD_ALIGN), 1);
+        assert_eq!(pad_amount(8, PAD_ALIGN), 0);
+        assert_eq!(pad_amount(1080, PAD_ALIGN), 0);
+        assert_eq!(pad_amount(720, PAD_ALIGN), 0);
     }
 
     #[test]
@@ -1740,7 +1961,7 @@ mod tests {
         assert_eq!(node.node_type(), "SuperResolution");
 
         let inputs = node.input_ports();
-        assert_eq!(inputs.len(), 4);
+        assert_eq!(inputs.len(), 12);
         assert_eq!(inputs[0].name, "model_path");
         assert_eq!(inputs[0].port_type, PortType::Path);
         assert!(inputs[0].required);
@@ -1757,10 +1978,30 @@ mod tests {
         assert_eq!(inputs[3].port_type, PortType::Str);
         assert!(!inputs[3].required);
 
+        let preprocess: Vec<&str> = inputs[4..8].iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(
+            preprocess,
+            ["value_range", "output_range", "channel_order", "pad_align"]
+        );
+        assert_eq!(inputs[8].name, "auto_target_fps");
+        assert_eq!(inputs[8].port_type, PortType::Float);
+        assert_eq!(inputs[9].name, "auto_preference");
+
         let outputs = node.output_ports();
         assert!(outputs.is_empty());
     }
 
+    #[test]
+    fn test_super_res_rejects_unresolved_auto_model() {
+        let mut node = SuperResNode::new();
+        let inputs = HashMap::from([("model_path".to_string(), PortData::Path("auto".into()))]);
+        let err = node
+            .execute(&inputs, &ExecutionContext::default())
+            .err()
+            .expect("unresolved auto model should fail");
+        assert!(err.to_string().contains("'auto'"), "{err}");
+    }
+
     #[test]
     fn test_super_res_node_default_backend() {
         let node = SuperResNode::new();
@@ -1916,7 +2157,7 @@ mod tests {
             data.extend_from_slice(&1023u16.to_le_bytes());
         }
 
-        let (arr, h, w) = cpu_rgb_to_f16_nchw_into(&data, 2, 2, 10, &mut None).unwrap();
+        let (arr, h, w) = cpu_rgb_to_f16_nchw_into(&data, 2, 2, 10, PAD_ALIGN, &mut None).unwrap();
         assert_eq!(h, 2);
         assert_eq!(w, 2);
         assert_eq!(arr.shape(), &[1, 3, 4, 4]);
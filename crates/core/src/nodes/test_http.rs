@@ -0,0 +1,58 @@
+//! Minimal HTTP server for node tests that talk to a remote endpoint.
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+/// Answer one connection per response, in order, and hand back each raw
+/// request (headers and body). Returns the server's `host:port`.
+pub(crate) fn spawn_recording_server(
+    raw_responses: Vec<String>,
+) -> (String, thread::JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind test server");
+    let addr = listener.local_addr().expect("local addr");
+
+    let handle = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for raw_response in raw_responses {
+            let (mut stream, _) = listener.accept().expect("accept test client");
+            requests.push(read_request(&mut stream));
+            stream
+                .write_all(raw_response.as_bytes())
+                .expect("write response");
+            let _ = stream.flush();
+        }
+        requests
+    });
+
+    (addr.to_string(), handle)
+}
+
+/// Read one request, up to the end of its `Content-Length` body.
+pub(crate) fn read_request(stream: &mut TcpStream) -> String {
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 4096];
+    while let Ok(read_count) = stream.read(&mut buffer) {
+        if read_count == 0 {
+            break;
+        }
+        request.extend_from_slice(&buffer[..read_count]);
+        let text = String::from_utf8_lossy(&request).to_string();
+        if let Some(header_end) = text.find("\r\n\r\n") {
+            let content_length = text[..header_end]
+                .lines()
+                .find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("content-length")
+                        .then(|| value.trim().parse::<usize>().ok())?
+                })
+                .unwrap_or(0);
+            if request.len() >= header_end + 4 + content_length {
+                break;
+            }
+        }
+    }
+    String::from_utf8_lossy(&request).into_owned()
+}
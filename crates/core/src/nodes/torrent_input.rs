@@ -0,0 +1,451 @@
+//! TorrentInput node: downloads a torrent and outputs its largest video.
+//!
+//! `source` is a magnet link, an http(s) URL of a `.torrent` file or a local
+//! `.torrent` path. The download runs in an embedded BitTorrent client
+//! ([librqbit]) and lands in a directory per source under
+//! `torrents.download_dir`, so running the same source again continues where
+//! it stopped. Seeding stops once the download completes.
+//!
+//! The node is opt-in: it fails unless `torrents.enabled` is set in the
+//! config. The settings come from the job's
+//! [`NodeServices`](crate::services::NodeServices).
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use librqbit::{
+    AddTorrent, AddTorrentOptions, DhtSessionConfig, ManagedTorrent, Session, SessionOptions,
+};
+use sha2::{Digest, Sha256};
+use tokio_util::sync::CancellationToken;
+
+use crate::config::TorrentsConfig;
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
+use crate::types::{PortData, PortType};
+
+pub const TORRENT_INPUT_NODE_TYPE: &str = "TorrentInput";
+
+const MAX_TIMEOUT_SECS: i64 = 7 * 86_400;
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+const VIDEO_EXTENSIONS: [&str; 11] = [
+    "mkv", "mp4", "m4v", "avi", "mov", "webm", "ts", "m2ts", "wmv", "flv", "mpg",
+];
+
+pub struct TorrentInputNode;
+
+impl TorrentInputNode {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for TorrentInputNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node for TorrentInputNode {
+    fn node_type(&self) -> &str {
+        TORRENT_INPUT_NODE_TYPE
+    }
+
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Network
+    }
+
+    fn input_ports(&self) -> Vec<PortDefinition> {
+        vec![
+            PortDefinition {
+                name: "source".to_string(),
+                port_type: PortType::Str,
+                required: true,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "timeout_secs".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(0)),
+            },
+        ]
+    }
+
+    fn output_ports(&self) -> Vec<PortDefinition> {
+        vec![PortDefinition {
+            name: "path".to_string(),
+            port_type: PortType::Path,
+            required: true,
+            default_value: None,
+        }]
+    }
+
+    fn execute(
+        &mut self,
+        inputs: &HashMap<String, PortData>,
+        ctx: &ExecutionContext,
+    ) -> Result<HashMap<String, PortData>> {
+        let config = ctx.services.torrents_config();
+        if !config.enabled {
+            bail!("TorrentInput is disabled; set torrents.enabled = true in config.toml");
+        }
+
+        let source = match inputs.get("source") {
+            Some(PortData::Str(value)) => value.trim(),
+            _ => bail!("TorrentInput: missing or invalid 'source' input (expected Str)"),
+        };
+        validate_source(source)?;
+        let timeout = match inputs.get("timeout_secs") {
+            Some(PortData::Int(0)) | None => None,
+            Some(PortData::Int(secs)) if (1..=MAX_TIMEOUT_SECS).contains(secs) => {
+                Some(Duration::from_secs(*secs as u64))
+            }
+            Some(PortData::Int(secs)) => {
+                bail!("TorrentInput: timeout_secs must be 0..={MAX_TIMEOUT_SECS}, got {secs}")
+            }
+            Some(_) => bail!("TorrentInput: input 'timeout_secs' must be Int"),
+        };
+
+        let dir = download_dir_for_source(config, source);
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("TorrentInput: failed to create {}", dir.display()))?;
+        download(source, &dir, timeout, ctx)?;
+
+        let path = largest_video_file(&dir)?.with_context(|| {
+            format!(
+                "TorrentInput: the torrent has no video file (looked in {})",
+                dir.display()
+            )
+        })?;
+        Ok(HashMap::from([("path".to_string(), PortData::Path(path))]))
+    }
+}
+
+fn validate_source(source: &str) -> Result<()> {
+    if source.starts_with("magnet:?") {
+        return Ok(());
+    }
+    if source.starts_with("http://") || source.starts_with("https://") {
+        return Ok(());
+    }
+    let path = Path::new(source);
+    let is_torrent_file = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("torrent"));
+    if is_torrent_file && path.is_file() {
+        return Ok(());
+    }
+    bail!("TorrentInput: source must be a magnet link, an http(s) URL or a .torrent file")
+}
+
+/// A directory per source, so a re-run resumes the same download.
+fn download_dir_for_source(config: &TorrentsConfig, source: &str) -> PathBuf {
+    let root = if config.download_dir.as_os_str().is_empty() {
+        std::env::temp_dir().join("videnoa").join("torrents")
+    } else {
+        config.download_dir.clone()
+    };
+    let digest = format!("{:x}", Sha256::digest(source.as_bytes()));
+    root.join(&digest[..16])
+}
+
+/// Runs the download on a runtime of its own, since nodes execute on
+/// blocking threads, and polls it for cancellation, timeout and progress.
+fn download(
+    source: &str,
+    dir: &Path,
+    timeout: Option<Duration>,
+    ctx: &ExecutionContext,
+) -> Result<()> {
+    let add = if source.starts_with("magnet:") || source.contains("://") {
+        AddTorrent::from_url(source.to_string())
+    } else {
+        AddTorrent::from_local_filename(source)
+            .with_context(|| format!("TorrentInput: failed to read {source}"))?
+    };
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(2)
+        .thread_name("torrent-input")
+        .enable_all()
+        .build()
+        .context("TorrentInput: failed to start the torrent client runtime")?;
+
+    let cancel = CancellationToken::new();
+    let torrent: Arc<Mutex<Option<Arc<ManagedTorrent>>>> = Arc::default();
+    let (done_tx, done_rx) = mpsc::channel();
+    runtime.spawn(run_session(
+        add,
+        dir.to_path_buf(),
+        cancel.clone(),
+        torrent.clone(),
+        done_tx,
+    ));
+
+    let result = wait_for_download(&done_rx, &torrent, timeout, ctx);
+    cancel.cancel();
+    // Nodes may run where blocking on a runtime shutdown is not allowed.
+    runtime.shutdown_background();
+    result
+}
+
+async fn run_session(
+    add: AddTorrent<'static>,
+    dir: PathBuf,
+    cancel: CancellationToken,
+    torrent: Arc<Mutex<Option<Arc<ManagedTorrent>>>>,
+    done: mpsc::Sender<Result<()>>,
+) {
+    let result = async {
+        let session = Session::new_with_opts(
+            dir.clone(),
+            SessionOptions {
+                dht: Some(DhtSessionConfig {
+                    persistence: None,
+                    ..Default::default()
+                }),
+                cancellation_token: Some(cancel),
+                ..Default::default()
+            },
+        )
+        .await
+        .context("failed to start the torrent client")?;
+        let handle = session
+            .add_torrent(
+                add,
+                Some(AddTorrentOptions {
+                    output_folder: Some(dir.to_string_lossy().into_owned()),
+                    // Lets a re-run check and keep the pieces already on disk.
+                    overwrite: true,
+                    ..Default::default()
+                }),
+            )
+            .await
+            .context("failed to add the torrent")?
+            .into_handle()
+            .context("the torrent was not added")?;
+        *torrent
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(handle.clone());
+        handle.wait_until_completed().await?;
+        session.stop().await;
+        Ok(())
+    }
+    .await;
+    let _ = done.send(result);
+}
+
+fn wait_for_download(
+    done: &mpsc::Receiver<Result<()>>,
+    torrent: &Mutex<Option<Arc<ManagedTorrent>>>,
+    timeout: Option<Duration>,
+    ctx: &ExecutionContext,
+) -> Result<()> {
+    let started = Instant::now();
+    let mut last_report = started;
+    loop {
+        match done.recv_timeout(Duration::from_millis(200)) {
+            Ok(result) => return result.context("TorrentInput: download failed"),
+            Err(RecvTimeoutError::Disconnected) => {
+                bail!("TorrentInput: the torrent client stopped unexpectedly")
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+        if ctx.services.is_cancelled() {
+            bail!("TorrentInput: download was cancelled");
+        }
+        if let Some(timeout) = timeout.filter(|timeout| started.elapsed() >= *timeout) {
+            bail!(
+                "TorrentInput: download timed out after {}s",
+                timeout.as_secs()
+            );
+        }
+        if last_report.elapsed() >= PROGRESS_INTERVAL {
+            last_report = Instant::now();
+            let stats = torrent
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .as_ref()
+                .map(|handle| handle.stats());
+            let message = match stats {
+                Some(stats) if stats.total_bytes > 0 => format!(
+                    "downloaded {} of {} bytes",
+                    stats.progress_bytes, stats.total_bytes
+                ),
+                _ => "fetching torrent metadata".to_string(),
+            };
+            ctx.emit_debug(TORRENT_INPUT_NODE_TYPE, message);
+        }
+    }
+}
+
+fn is_video_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            VIDEO_EXTENSIONS
+                .iter()
+                .any(|video| video.eq_ignore_ascii_case(ext))
+        })
+}
+
+/// The largest file with a video extension anywhere under `dir`.
+fn largest_video_file(dir: &Path) -> Result<Option<PathBuf>> {
+    let mut best: Option<(u64, PathBuf)> = None;
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in fs::read_dir(&current)
+            .with_context(|| format!("failed to list {}", current.display()))?
+        {
+            let entry = entry?;
+            let path = entry.path();
+            let kind = entry.file_type()?;
+            if kind.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            if !kind.is_file() || !is_video_file(&path) {
+                continue;
+            }
+            let len = entry.metadata()?.len();
+            if best.as_ref().is_none_or(|(best_len, _)| len > *best_len) {
+                best = Some((len, path));
+            }
+        }
+    }
+    Ok(best.map(|(_, path)| path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::NodeServices;
+
+    #[test]
+    fn test_disabled_by_default() {
+        let mut node = TorrentInputNode::new();
+        let inputs = HashMap::from([(
+            "source".to_string(),
+            PortData::Str("magnet:?xt=urn:btih:abc".to_string()),
+        )]);
+        let err = node
+            .execute(&inputs, &ExecutionContext::default())
+            .err()
+            .expect("disabled node should fail");
+        assert!(err.to_string().contains("torrents.enabled"), "{err}");
+
+        let ctx = ExecutionContext {
+            services: NodeServices::default().with_torrents_config(TorrentsConfig {
+                enabled: true,
+                download_dir: PathBuf::new(),
+            }),
+            ..Default::default()
+        };
+        let inputs = HashMap::from([(
+            "source".to_string(),
+            PortData::Str("ftp://example.com/show.torrent".to_string()),
+        )]);
+        let err = node
+            .execute(&inputs, &ctx)
+            .err()
+            .expect("unsupported source should fail");
+        assert!(err.to_string().contains("magnet link"), "{err}");
+    }
+
+    #[test]
+    fn test_validate_source_and_download_dir() {
+        assert!(validate_source("magnet:?xt=urn:btih:abc").is_ok());
+        assert!(validate_source("https://example.com/show.torrent").is_ok());
+        assert!(validate_source("/nonexistent/show.torrent").is_err());
+        assert!(validate_source("ftp://example.com/show.torrent").is_err());
+
+        let config = TorrentsConfig {
+            enabled: true,
+            download_dir: PathBuf::from("/srv/torrents"),
+        };
+        let dir = download_dir_for_source(&config, "magnet:?xt=urn:btih:abc");
+        assert_eq!(dir.parent(), Some(Path::new("/srv/torrents")));
+        assert_eq!(
+            dir,
+            download_dir_for_source(&config, "magnet:?xt=urn:btih:abc")
+        );
+        assert_ne!(
+            dir,
+            download_dir_for_source(&config, "magnet:?xt=urn:btih:def")
+        );
+    }
+
+    #[test]
+    fn test_rerun_keeps_a_completed_download() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let video = source_dir.path().join("Episode 1.mkv");
+        let contents: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+        fs::write(&video, &contents).unwrap();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let torrent = runtime
+            .block_on(async {
+                librqbit::create_torrent(
+                    &video,
+                    librqbit::CreateTorrentOptions {
+                        name: None,
+                        trackers: Vec::new(),
+                        piece_length: Some(16_384),
+                    },
+                    &librqbit::spawn_utils::BlockingSpawner::new(1),
+                )
+                .await
+            })
+            .unwrap();
+        drop(runtime);
+        let torrent_file = source_dir.path().join("show.torrent");
+        fs::write(&torrent_file, torrent.as_bytes().unwrap()).unwrap();
+
+        // The pieces are already on disk, so no peer is needed to finish.
+        let config = TorrentsConfig {
+            enabled: true,
+            download_dir: tempfile::tempdir().unwrap().keep(),
+        };
+        let source = torrent_file.to_string_lossy().into_owned();
+        let dir = download_dir_for_source(&config, &source);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("Episode 1.mkv"), &contents).unwrap();
+
+        let ctx = ExecutionContext {
+            services: NodeServices::default().with_torrents_config(config),
+            ..Default::default()
+        };
+        let inputs = HashMap::from([
+            ("source".to_string(), PortData::Str(source)),
+            ("timeout_secs".to_string(), PortData::Int(60)),
+        ]);
+        let outputs = TorrentInputNode::new().execute(&inputs, &ctx).unwrap();
+        match outputs.get("path") {
+            Some(PortData::Path(path)) => assert_eq!(path, &dir.join("Episode 1.mkv")),
+            _ => panic!("TorrentInput should output a path"),
+        }
+        fs::remove_dir_all(dir.parent().unwrap()).ok();
+    }
+
+    #[test]
+    fn test_largest_video_file_skips_other_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let season = dir.path().join("Season 1");
+        fs::create_dir_all(&season).unwrap();
+        fs::write(dir.path().join("sample.mkv"), vec![0u8; 10]).unwrap();
+        fs::write(season.join("Episode 1.MKV"), vec![0u8; 100]).unwrap();
+        fs::write(season.join("extras.iso"), vec![0u8; 1000]).unwrap();
+        fs::write(season.join("Episode 1.MKV.part"), vec![0u8; 500]).unwrap();
+
+        assert_eq!(
+            largest_video_file(dir.path()).unwrap(),
+            Some(season.join("Episode 1.MKV"))
+        );
+
+        let empty = tempfile::tempdir().unwrap();
+        assert_eq!(largest_video_file(empty.path()).unwrap(), None);
+    }
+}
@@ -3,7 +3,7 @@ use std::path::PathBuf;
 
 use anyhow::{anyhow, bail, Result};
 
-use crate::node::{ExecutionContext, Node, PortDefinition};
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
 use crate::types::{PortData, PortType};
 
 pub struct TypeConversionNode {
@@ -56,6 +56,10 @@ impl Node for TypeConversionNode {
         "TypeConversion"
     }
 
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
     fn input_ports(&self) -> Vec<PortDefinition> {
         vec![
             PortDefinition {
@@ -114,7 +118,7 @@ fn parse_param_port_type(
 
     let value = value.as_str().ok_or_else(|| {
         anyhow!(
-            "TypeConversion: param '{key}' must be a string type name (Int|Float|Str|Bool|Path)"
+            "TypeConversion: param '{key}' must be a string type name (Int|Float|Str|Bool|Path|Json)"
         )
     })?;
 
@@ -149,8 +153,9 @@ fn parse_supported_type(raw: &str, key: &str) -> Result<PortType> {
         "Str" => Ok(PortType::Str),
         "Bool" => Ok(PortType::Bool),
         "Path" => Ok(PortType::Path),
+        "Json" => Ok(PortType::Json),
         other => bail!(
-            "TypeConversion: unsupported {key} '{other}', expected one of Int|Float|Str|Bool|Path"
+            "TypeConversion: unsupported {key} '{other}', expected one of Int|Float|Str|Bool|Path|Json"
         ),
     }
 }
@@ -162,6 +167,7 @@ fn port_type_name(port_type: &PortType) -> &'static str {
         PortType::Str => "Str",
         PortType::Bool => "Bool",
         PortType::Path => "Path",
+        PortType::Json => "Json",
         _ => "Unsupported",
     }
 }
@@ -174,6 +180,8 @@ fn port_data_kind(data: &PortData) -> &'static str {
         PortData::Bool(_) => "Bool",
         PortData::Path(_) => "Path",
         PortData::Metadata(_) => "Metadata",
+        PortData::Json(_) => "Json",
+        PortData::List(_) => "List",
     }
 }
 
@@ -371,6 +379,21 @@ fn convert_value(
             _ => Err(mismatch()),
         },
 
+        (PortType::Str, PortType::Json) => match value {
+            PortData::Str(v) => serde_json::from_str(v)
+                .map(PortData::Json)
+                .map_err(|e| anyhow!("TypeConversion: failed to parse Str as Json: {e}")),
+            _ => Err(mismatch()),
+        },
+        (PortType::Json, PortType::Str) => match value {
+            PortData::Json(v) => Ok(PortData::Str(v.to_string())),
+            _ => Err(mismatch()),
+        },
+        (PortType::Json, PortType::Json) => match value {
+            PortData::Json(v) => Ok(PortData::Json(v.clone())),
+            _ => Err(mismatch()),
+        },
+
         _ => bail!(
             "TypeConversion: unsupported conversion {} -> {}",
             port_type_name(input_type),
@@ -675,7 +698,7 @@ mod tests {
         };
         assert_eq!(
             err.to_string(),
-            "TypeConversion: unsupported input_type 'VideoFrames', expected one of Int|Float|Str|Bool|Path"
+            "TypeConversion: unsupported input_type 'VideoFrames', expected one of Int|Float|Str|Bool|Path|Json"
         );
     }
 
@@ -0,0 +1,103 @@
+//! Uuid node: a new random (v4) UUID each run.
+//!
+//! The UUID is written in the usual hyphenated form, or as 32 plain hex
+//! digits when `simple` is set.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
+use crate::types::{PortData, PortType};
+
+pub const UUID_NODE_TYPE: &str = "Uuid";
+
+pub struct UuidNode;
+
+impl UuidNode {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for UuidNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node for UuidNode {
+    fn node_type(&self) -> &str {
+        UUID_NODE_TYPE
+    }
+
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
+    fn input_ports(&self) -> Vec<PortDefinition> {
+        vec![PortDefinition {
+            name: "simple".to_string(),
+            port_type: PortType::Bool,
+            required: false,
+            default_value: Some(serde_json::json!(false)),
+        }]
+    }
+
+    fn output_ports(&self) -> Vec<PortDefinition> {
+        vec![PortDefinition {
+            name: "value".to_string(),
+            port_type: PortType::Str,
+            required: true,
+            default_value: None,
+        }]
+    }
+
+    fn execute(
+        &mut self,
+        inputs: &HashMap<String, PortData>,
+        _ctx: &ExecutionContext,
+    ) -> Result<HashMap<String, PortData>> {
+        let simple = match inputs.get("simple") {
+            Some(PortData::Bool(value)) => *value,
+            Some(_) => bail!("Uuid: input 'simple' must be Bool"),
+            None => false,
+        };
+
+        let uuid = uuid::Uuid::new_v4();
+        let value = if simple {
+            uuid.simple().to_string()
+        } else {
+            uuid.to_string()
+        };
+        Ok(HashMap::from([("value".to_string(), PortData::Str(value))]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(simple: bool) -> String {
+        let inputs = HashMap::from([("simple".to_string(), PortData::Bool(simple))]);
+        let mut outputs = UuidNode::new()
+            .execute(&inputs, &ExecutionContext::default())
+            .unwrap();
+        match outputs.remove("value") {
+            Some(PortData::Str(value)) => value,
+            _ => panic!("value should be Str"),
+        }
+    }
+
+    #[test]
+    fn test_uuid_node_formats() {
+        let hyphenated = run(false);
+        assert_eq!(hyphenated.len(), 36);
+        assert!(uuid::Uuid::parse_str(&hyphenated).is_ok());
+
+        let simple = run(true);
+        assert_eq!(simple.len(), 32);
+        assert!(simple.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(run(false), hyphenated);
+    }
+}
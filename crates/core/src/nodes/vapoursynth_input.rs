@@ -0,0 +1,483 @@
+//! VapourSynthInput node: uses a VapourSynth script as the video source.
+//!
+//! The script does the filtering; `vspipe` renders it to y4m and FFmpeg reads
+//! that pipe, so the rest of the pipeline sees the same RGB frames a
+//! `VideoInput` produces. The script's clip must have a constant format,
+//! size and frame rate.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Stdio};
+use std::thread;
+
+use anyhow::{bail, Context, Result};
+use tracing::debug;
+
+use crate::node::{ExecutionContext, Node, PortDefinition};
+use crate::nodes::video_input::{extract_metadata, run_ffprobe, VideoDecoder, VideoStreamInfo};
+use crate::types::{Frame, MediaMetadata, PortData, PortType};
+
+pub const VAPOURSYNTH_INPUT_NODE_TYPE: &str = "VapourSynthInput";
+
+/// Clip properties reported by `vspipe --info`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VapourSynthInfo {
+    pub width: u32,
+    pub height: u32,
+    pub frames: u64,
+    pub fps_num: u64,
+    pub fps_den: u64,
+    pub format_name: String,
+    pub bits: u8,
+}
+
+impl VapourSynthInfo {
+    /// Layout of the frames FFmpeg decodes from the y4m pipe.
+    pub fn stream_info(&self) -> VideoStreamInfo {
+        VideoStreamInfo {
+            stream_index: 0,
+            width: self.width,
+            height: self.height,
+            fps: self.fps_num as f64 / self.fps_den as f64,
+            codec_name: "vapoursynth".to_string(),
+            pix_fmt: self.format_name.to_ascii_lowercase(),
+            bit_depth: self.bits,
+        }
+    }
+}
+
+/// Parse the `Key: value` lines `vspipe --info` prints.
+pub fn parse_vspipe_info(text: &str) -> Result<VapourSynthInfo> {
+    let fields: HashMap<&str, &str> = text
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect();
+    let field = |key: &str| {
+        fields
+            .get(key)
+            .copied()
+            .with_context(|| format!("vspipe --info printed no '{key}'"))
+    };
+    let number = |key: &str| -> Result<u64> {
+        field(key)?
+            .parse()
+            .with_context(|| format!("vspipe --info '{key}' is not a number"))
+    };
+
+    let width = number("Width")? as u32;
+    let height = number("Height")? as u32;
+    if width == 0 || height == 0 {
+        bail!("VapourSynth clips with variable size are not supported");
+    }
+
+    // "24000/1001 (23.976 fps)"
+    let fps = field("FPS")?;
+    let (fps_num, fps_den) = fps
+        .split_whitespace()
+        .next()
+        .and_then(|ratio| ratio.split_once('/'))
+        .and_then(|(num, den)| Some((num.parse::<u64>().ok()?, den.parse::<u64>().ok()?)))
+        .with_context(|| format!("unrecognized vspipe FPS '{fps}'"))?;
+    if fps_num == 0 || fps_den == 0 {
+        bail!("VapourSynth clips with variable frame rate are not supported");
+    }
+
+    let format_name = field("Format Name")?.to_string();
+    if format_name.is_empty() || format_name.eq_ignore_ascii_case("variable") {
+        bail!("VapourSynth clips with variable format are not supported");
+    }
+
+    Ok(VapourSynthInfo {
+        width,
+        height,
+        frames: number("Frames")?,
+        fps_num,
+        fps_den,
+        format_name,
+        bits: number("Bits")?.clamp(8, 16) as u8,
+    })
+}
+
+/// `--arg` options for vspipe from whitespace-separated `key=value` pairs.
+pub fn vspipe_script_args(args: &str) -> Result<Vec<String>> {
+    let mut result = Vec::new();
+    for pair in args.split_whitespace() {
+        match pair.split_once('=') {
+            Some((key, _)) if !key.is_empty() => {
+                result.push("--arg".to_string());
+                result.push(pair.to_string());
+            }
+            _ => bail!("invalid VapourSynth script argument '{pair}', expected key=value"),
+        }
+    }
+    Ok(result)
+}
+
+/// Run `vspipe --info` on `script`.
+pub fn probe_script(script: &Path, script_args: &[String]) -> Result<VapourSynthInfo> {
+    let output = crate::runtime::command_for("vspipe")
+        .args(script_args)
+        .arg("--info")
+        .arg(script)
+        .arg("-")
+        .stdin(Stdio::null())
+        .output()
+        .context("failed to execute vspipe -- is VapourSynth installed?")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!(
+            "vspipe exited with status {}: {}",
+            output.status,
+            stderr.trim()
+        );
+    }
+
+    parse_vspipe_info(&String::from_utf8_lossy(&output.stdout))
+        .with_context(|| format!("failed to read clip info from {}", script.display()))
+}
+
+/// Settings [`VapourSynthInputNode`] passes through its outputs.
+#[derive(Debug, Clone)]
+pub struct VapourSynthConfig {
+    pub script: PathBuf,
+    pub script_args: Vec<String>,
+    /// File the encoder copies audio, subtitles and attachments from.
+    pub audio_source: Option<PathBuf>,
+}
+
+impl VapourSynthConfig {
+    pub fn from_outputs(outputs: &HashMap<String, PortData>) -> Result<Self> {
+        let script = match outputs.get("source_path") {
+            Some(PortData::Path(path)) => path.clone(),
+            _ => bail!("VapourSynthInput output 'source_path' is missing"),
+        };
+        let script_args = match outputs.get("script_args") {
+            Some(PortData::Str(value)) => vspipe_script_args(value)?,
+            _ => Vec::new(),
+        };
+        let audio_source = match outputs.get("audio_source") {
+            Some(PortData::Path(path)) if !path.as_os_str().is_empty() => Some(path.clone()),
+            _ => None,
+        };
+        Ok(Self {
+            script,
+            script_args,
+            audio_source,
+        })
+    }
+}
+
+/// Frame iterator over a VapourSynth script: `vspipe` writes y4m into an
+/// FFmpeg [`VideoDecoder`]. Kills vspipe on [`Drop`].
+pub struct VapourSynthDecoder {
+    vspipe: Child,
+    decoder: VideoDecoder,
+    _stderr_thread: Option<thread::JoinHandle<()>>,
+    done: bool,
+}
+
+impl VapourSynthDecoder {
+    pub fn new(config: &VapourSynthConfig, info: &VapourSynthInfo) -> Result<Self> {
+        let mut vspipe = crate::runtime::command_for("vspipe")
+            .args(&config.script_args)
+            .args(["-c", "y4m"])
+            .arg(&config.script)
+            .arg("-")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("failed to launch vspipe -- is VapourSynth installed?")?;
+
+        let stdout = vspipe.stdout.take().expect("stdout should be piped");
+        let stderr = vspipe.stderr.take().expect("stderr should be piped");
+        let stderr_span = tracing::Span::current();
+        let stderr_thread = thread::spawn(move || {
+            let _entered = stderr_span.enter();
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                if !line.is_empty() {
+                    debug!(target: "vspipe_stderr", "{}", line);
+                }
+            }
+        });
+
+        let decoder = match VideoDecoder::from_pipe(
+            Stdio::from(stdout),
+            &info.stream_info(),
+            &["-f".to_string(), "yuv4mpegpipe".to_string()],
+        ) {
+            Ok(decoder) => decoder,
+            Err(err) => {
+                let _ = vspipe.kill();
+                let _ = vspipe.wait();
+                return Err(err);
+            }
+        };
+
+        Ok(Self {
+            vspipe,
+            decoder,
+            _stderr_thread: Some(stderr_thread),
+            done: false,
+        })
+    }
+
+    /// Reports a script error once FFmpeg has seen the end of the pipe.
+    fn finish(&mut self) -> Result<()> {
+        let status = self.vspipe.wait().context("failed to wait for vspipe")?;
+        if !status.success() {
+            bail!("vspipe exited with status {}", status);
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for VapourSynthDecoder {
+    type Item = Result<Frame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.decoder.next() {
+            Some(Ok(frame)) => Some(Ok(frame)),
+            Some(Err(err)) => {
+                self.done = true;
+                Some(Err(err))
+            }
+            None => {
+                self.done = true;
+                self.finish().err().map(Err)
+            }
+        }
+    }
+}
+
+impl Drop for VapourSynthDecoder {
+    fn drop(&mut self) {
+        let _ = self.vspipe.kill();
+        let _ = self.vspipe.wait();
+        if let Some(handle) = self._stderr_thread.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+pub struct VapourSynthInputNode;
+
+impl VapourSynthInputNode {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for VapourSynthInputNode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Node for VapourSynthInputNode {
+    fn node_type(&self) -> &str {
+        "vapoursynth_input"
+    }
+
+    fn input_ports(&self) -> Vec<PortDefinition> {
+        vec![
+            PortDefinition {
+                name: "script_path".to_string(),
+                port_type: PortType::Path,
+                required: true,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "script_args".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("")),
+            },
+            PortDefinition {
+                name: "audio_source".to_string(),
+                port_type: PortType::Path,
+                required: false,
+                default_value: Some(serde_json::json!("")),
+            },
+        ]
+    }
+
+    fn output_ports(&self) -> Vec<PortDefinition> {
+        vec![
+            PortDefinition {
+                name: "metadata".to_string(),
+                port_type: PortType::Metadata,
+                required: true,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "source_path".to_string(),
+                port_type: PortType::Path,
+                required: true,
+                default_value: None,
+            },
+        ]
+    }
+
+    fn execute(
+        &mut self,
+        inputs: &HashMap<String, PortData>,
+        _ctx: &ExecutionContext,
+    ) -> Result<HashMap<String, PortData>> {
+        let script = match inputs.get("script_path") {
+            Some(PortData::Path(p)) => p.clone(),
+            Some(PortData::Str(s)) => PathBuf::from(s),
+            _ => bail!("missing or invalid 'script_path' input (expected Path)"),
+        };
+        if !script.is_file() {
+            bail!("VapourSynth script not found: {}", script.display());
+        }
+
+        let script_args = match inputs.get("script_args") {
+            Some(PortData::Str(s)) => s.clone(),
+            _ => String::new(),
+        };
+        let audio_source = match inputs.get("audio_source") {
+            Some(PortData::Path(p)) => p.clone(),
+            Some(PortData::Str(s)) => PathBuf::from(s),
+            _ => PathBuf::new(),
+        };
+
+        let info = probe_script(&script, &vspipe_script_args(&script_args)?)?;
+        debug!(
+            script = %script.display(),
+            width = info.width,
+            height = info.height,
+            frames = info.frames,
+            fps = %format!("{}/{}", info.fps_num, info.fps_den),
+            format = %info.format_name,
+            "VapourSynth script probed"
+        );
+
+        // Streams to copy come from the audio source; the script only
+        // provides video.
+        let metadata = if audio_source.as_os_str().is_empty() {
+            MediaMetadata {
+                source_path: script.clone(),
+                video_streams: Vec::new(),
+                audio_streams: Vec::new(),
+                subtitle_streams: Vec::new(),
+                attachment_streams: Vec::new(),
+                chapters: Vec::new(),
+                global_metadata: HashMap::new(),
+                container_format: "vapoursynth".to_string(),
+            }
+        } else {
+            let probe = run_ffprobe(&audio_source).context("failed to probe audio source")?;
+            extract_metadata(&probe, &audio_source)?.1
+        };
+
+        let mut outputs = HashMap::new();
+        outputs.insert("metadata".to_string(), PortData::Metadata(metadata));
+        outputs.insert("source_path".to_string(), PortData::Path(script));
+        // Not ports: read back by the compile context to start vspipe (see
+        // `VapourSynthConfig::from_outputs`).
+        outputs.insert("script_args".to_string(), PortData::Str(script_args));
+        outputs.insert("audio_source".to_string(), PortData::Path(audio_source));
+        Ok(outputs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INFO: &str = "Width: 1920\nHeight: 1080\nFrames: 34046\n\
+        FPS: 24000/1001 (23.976 fps)\nFormat Name: YUV420P10\nColor Family: YUV\n\
+        Alpha: No\nSample Type: Integer\nBits: 10\nSubSampling W: 1\nSubSampling H: 1\n";
+
+    #[test]
+    fn test_node_ports() {
+        let node = VapourSynthInputNode::new();
+        assert_eq!(node.node_type(), "vapoursynth_input");
+        let inputs = node.input_ports();
+        assert_eq!(inputs.len(), 3);
+        assert_eq!(inputs[0].name, "script_path");
+        assert!(inputs[0].required);
+        assert_eq!(inputs[1].name, "script_args");
+        assert_eq!(inputs[2].name, "audio_source");
+        assert!(!inputs[2].required);
+        let outputs = node.output_ports();
+        assert_eq!(outputs.len(), 2);
+        assert_eq!(outputs[1].name, "source_path");
+    }
+
+    #[test]
+    fn test_parse_vspipe_info() {
+        let info = parse_vspipe_info(INFO).unwrap();
+        assert_eq!(
+            info,
+            VapourSynthInfo {
+                width: 1920,
+                height: 1080,
+                frames: 34046,
+                fps_num: 24000,
+                fps_den: 1001,
+                format_name: "YUV420P10".to_string(),
+                bits: 10,
+            }
+        );
+        let stream = info.stream_info();
+        assert!((stream.fps - 23.976).abs() < 0.001);
+        assert_eq!(stream.pix_fmt, "yuv420p10");
+        assert_eq!(stream.bit_depth, 10);
+
+        let variable_fps = INFO.replace("24000/1001 (23.976 fps)", "0/0 (0.000 fps)");
+        assert!(parse_vspipe_info(&variable_fps).is_err());
+        let variable_size = INFO.replace("Width: 1920", "Width: 0");
+        assert!(parse_vspipe_info(&variable_size).is_err());
+        assert!(parse_vspipe_info("Width: 1920").is_err());
+    }
+
+    #[test]
+    fn test_vspipe_script_args() {
+        assert_eq!(
+            vspipe_script_args("src=/videos/ep01.mkv  strength=2").unwrap(),
+            vec!["--arg", "src=/videos/ep01.mkv", "--arg", "strength=2"]
+        );
+        assert!(vspipe_script_args("").unwrap().is_empty());
+        assert!(vspipe_script_args("novalue").is_err());
+        assert!(vspipe_script_args("=value").is_err());
+    }
+
+    #[test]
+    fn test_execute_rejects_missing_script() {
+        let mut node = VapourSynthInputNode::new();
+        let mut inputs = HashMap::new();
+        inputs.insert(
+            "script_path".to_string(),
+            PortData::Path(PathBuf::from("/nonexistent/filter.vpy")),
+        );
+        let err = node
+            .execute(&inputs, &ExecutionContext::default())
+            .err()
+            .expect("missing script should fail");
+        assert!(err.to_string().contains("script not found"));
+    }
+
+    #[test]
+    fn test_config_from_outputs() {
+        let mut outputs = HashMap::new();
+        outputs.insert(
+            "source_path".to_string(),
+            PortData::Path(PathBuf::from("/scripts/filter.vpy")),
+        );
+        outputs.insert("script_args".to_string(), PortData::Str("a=1".to_string()));
+        outputs.insert("audio_source".to_string(), PortData::Path(PathBuf::new()));
+        let config = VapourSynthConfig::from_outputs(&outputs).unwrap();
+        assert_eq!(config.script, PathBuf::from("/scripts/filter.vpy"));
+        assert_eq!(config.script_args, vec!["--arg", "a=1"]);
+        assert_eq!(config.audio_source, None);
+    }
+}
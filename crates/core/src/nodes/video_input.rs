@@ -2,12 +2,13 @@ use std::collections::HashMap;
 use std::io::{BufRead, BufReader, Read};
 use std::path::Path;
 use std::process::{Child, Stdio};
+use std::sync::OnceLock;
 use std::thread;
 
 use anyhow::{anyhow, bail, Context, Result};
 use tracing::{debug, warn};
 
-use crate::node::{ExecutionContext, Node, PortDefinition};
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
 use crate::types::{Chapter, Frame, MediaMetadata, PortData, PortType, StreamInfo};
 // ffprobe JSON model (serde)
 // ---------------------------------------------------------------------------
@@ -57,7 +58,7 @@ struct FfprobeFormat {
     tags: HashMap<String, String>,
 }
 
-fn parse_frame_rate(s: &str) -> Option<f64> {
+pub(crate) fn parse_frame_rate(s: &str) -> Option<f64> {
     let parts: Vec<&str> = s.split('/').collect();
     if parts.len() == 2 {
         let num: f64 = parts[0].parse().ok()?;
@@ -113,6 +114,19 @@ fn select_primary_video_stream(streams: &[FfprobeStream]) -> Option<&FfprobeStre
         })
 }
 
+fn stream_indices(streams: &[FfprobeStream], codec_type: &str) -> String {
+    let indices: Vec<String> = streams
+        .iter()
+        .filter(|stream| stream.codec_type.as_deref() == Some(codec_type))
+        .map(|stream| stream.index.to_string())
+        .collect();
+    if indices.is_empty() {
+        "none".to_string()
+    } else {
+        indices.join(", ")
+    }
+}
+
 fn is_interlaced(field_order: Option<&str>) -> bool {
     match field_order {
         Some(fo) => matches!(fo, "tt" | "bb" | "tb" | "bt"),
@@ -178,8 +192,30 @@ pub fn extract_metadata(
     probe: &FfprobeOutput,
     source_path: &Path,
 ) -> Result<(VideoStreamInfo, MediaMetadata)> {
-    let video_stream = select_primary_video_stream(&probe.streams)
-        .ok_or_else(|| anyhow!("no video stream found"))?;
+    extract_metadata_for_stream(probe, source_path, None)
+}
+
+/// Like [`extract_metadata`], but describes the video stream with ffprobe
+/// index `video_stream` instead of the primary one.
+pub fn extract_metadata_for_stream(
+    probe: &FfprobeOutput,
+    source_path: &Path,
+    video_stream: Option<usize>,
+) -> Result<(VideoStreamInfo, MediaMetadata)> {
+    let video_stream = match video_stream {
+        None => select_primary_video_stream(&probe.streams)
+            .ok_or_else(|| anyhow!("no video stream found"))?,
+        Some(index) => probe
+            .streams
+            .iter()
+            .find(|stream| stream.index == index && stream.codec_type.as_deref() == Some("video"))
+            .ok_or_else(|| {
+                anyhow!(
+                    "stream {index} is not a video stream (video streams: {})",
+                    stream_indices(&probe.streams, "video")
+                )
+            })?,
+    };
 
     if is_interlaced(video_stream.field_order.as_deref()) {
         bail!(
@@ -235,6 +271,7 @@ pub fn extract_metadata(
         bit_depth,
     };
 
+    let mut video_streams = Vec::new();
     let mut audio_streams = Vec::new();
     let mut subtitle_streams = Vec::new();
     let mut attachment_streams = Vec::new();
@@ -251,6 +288,7 @@ pub fn extract_metadata(
         };
 
         match codec_type {
+            "video" => video_streams.push(info),
             "audio" => audio_streams.push(info),
             "subtitle" => subtitle_streams.push(info),
             "attachment" => attachment_streams.push(info),
@@ -285,6 +323,7 @@ pub fn extract_metadata(
 
     let metadata = MediaMetadata {
         source_path: source_path.to_path_buf(),
+        video_streams,
         audio_streams,
         subtitle_streams,
         attachment_streams,
@@ -309,13 +348,55 @@ impl Node for VideoInputNode {
         "video_input"
     }
 
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Disk
+    }
+
     fn input_ports(&self) -> Vec<PortDefinition> {
-        vec![PortDefinition {
-            name: "path".to_string(),
-            port_type: PortType::Path,
-            required: true,
-            default_value: None,
-        }]
+        vec![
+            PortDefinition {
+                name: "path".to_string(),
+                port_type: PortType::Path,
+                required: true,
+                default_value: None,
+            },
+            PortDefinition {
+                name: "hwaccel".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("none")),
+            },
+            PortDefinition {
+                name: "video_stream".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(-1)),
+            },
+            PortDefinition {
+                name: "audio_stream".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(-1)),
+            },
+            PortDefinition {
+                name: "start_frame".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(0)),
+            },
+            PortDefinition {
+                name: "end_frame".to_string(),
+                port_type: PortType::Int,
+                required: false,
+                default_value: Some(serde_json::json!(-1)),
+            },
+            PortDefinition {
+                name: "segments".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("")),
+            },
+        ]
     }
 
     fn output_ports(&self) -> Vec<PortDefinition> {
@@ -345,13 +426,35 @@ impl Node for VideoInputNode {
             _ => bail!("missing or invalid 'path' input (expected Path)"),
         };
 
-        if !path.exists() {
+        if !is_remote_source(&path) && !path.exists() {
             bail!("input file does not exist: {}", path.display());
         }
 
+        let hwaccel = match inputs.get("hwaccel") {
+            Some(PortData::Str(value)) => HwAccel::parse(value).ok_or_else(|| {
+                anyhow!(
+                    "unsupported hwaccel '{value}', expected one of {}",
+                    HwAccel::OPTIONS.join("|")
+                )
+            })?,
+            Some(_) => bail!("invalid 'hwaccel' input (expected Str)"),
+            None => HwAccel::None,
+        };
+        let segments = segments_from_inputs(inputs)?;
+        let video_stream = stream_index_input(inputs, "video_stream")?;
+        let audio_stream = stream_index_input(inputs, "audio_stream")?;
+
         debug!(path = %path.display(), "running ffprobe");
         let probe = run_ffprobe(&path)?;
-        let (_video_info, metadata) = extract_metadata(&probe, &path)?;
+        let (_video_info, metadata) = extract_metadata_for_stream(&probe, &path, video_stream)?;
+        if let Some(index) = audio_stream {
+            if !metadata.audio_streams.iter().any(|s| s.index == index) {
+                bail!(
+                    "stream {index} is not an audio stream (audio streams: {})",
+                    stream_indices(&probe.streams, "audio")
+                );
+            }
+        }
 
         debug!(
             stream_index = _video_info.stream_index,
@@ -365,14 +468,364 @@ impl Node for VideoInputNode {
             subtitle_streams = metadata.subtitle_streams.len(),
             "video input probed"
         );
+        let stream_output = |index: Option<usize>| PortData::Int(index.map_or(-1, |i| i as i64));
 
         let mut outputs = HashMap::new();
         outputs.insert("metadata".to_string(), PortData::Metadata(metadata));
         outputs.insert("source_path".to_string(), PortData::Path(path));
+        // Not a port: read back by the compile context to pick the decoder.
+        outputs.insert("hwaccel".to_string(), PortData::Str(hwaccel.to_string()));
+        outputs.insert(
+            "segments".to_string(),
+            PortData::Str(format_segments(&segments)),
+        );
+        outputs.insert("video_stream".to_string(), stream_output(video_stream));
+        outputs.insert("audio_stream".to_string(), stream_output(audio_stream));
         Ok(outputs)
     }
 }
 
+/// A stream index param: an ffprobe stream index, or `None` for `-1`.
+pub fn stream_index_input(inputs: &HashMap<String, PortData>, name: &str) -> Result<Option<usize>> {
+    match inputs.get(name) {
+        Some(PortData::Int(-1)) | None => Ok(None),
+        Some(PortData::Int(value)) if *value >= 0 => Ok(Some(*value as usize)),
+        Some(PortData::Int(value)) => bail!("{name} must be -1 or a stream index, got {value}"),
+        Some(_) => bail!("invalid '{name}' input (expected Int)"),
+    }
+}
+
+/// Frame ranges selected by `segments`, or by `start_frame`/`end_frame` when
+/// no segments are given. Empty means the whole source.
+pub(crate) fn segments_from_inputs(inputs: &HashMap<String, PortData>) -> Result<Vec<FrameRange>> {
+    let int_input = |name: &str, default: i64| match inputs.get(name) {
+        Some(PortData::Int(value)) => Ok(*value),
+        Some(_) => bail!("invalid '{name}' input (expected Int)"),
+        None => Ok(default),
+    };
+    let start_frame = int_input("start_frame", 0)?;
+    let end_frame = int_input("end_frame", -1)?;
+    let segments = match inputs.get("segments") {
+        Some(PortData::Str(value)) => value.trim(),
+        Some(_) => bail!("invalid 'segments' input (expected Str)"),
+        None => "",
+    };
+
+    if !segments.is_empty() {
+        if start_frame != 0 || end_frame >= 0 {
+            bail!("set either 'segments' or 'start_frame'/'end_frame', not both");
+        }
+        return parse_segments(segments);
+    }
+    if start_frame < 0 {
+        bail!("start_frame must be >= 0, got {start_frame}");
+    }
+    let range = FrameRange {
+        start: start_frame as u64,
+        end: (end_frame >= 0).then_some(end_frame as u64),
+    };
+    validate_segments(&[range])?;
+    Ok(if range == FrameRange::ALL {
+        Vec::new()
+    } else {
+        vec![range]
+    })
+}
+
+/// Hardware decode selection for `VideoInput`.
+///
+/// `Auto` picks the first method FFmpeg reports in `ffmpeg -hwaccels`. Any
+/// method that is unavailable, or that fails before the first frame, falls
+/// back to software decoding.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HwAccel {
+    #[default]
+    None,
+    Auto,
+    Nvdec,
+    Qsv,
+    Vaapi,
+    VideoToolbox,
+}
+
+impl HwAccel {
+    pub const OPTIONS: [&'static str; 6] =
+        ["none", "auto", "nvdec", "qsv", "vaapi", "videotoolbox"];
+
+    /// Methods tried by `Auto`, fastest first.
+    const AUTO_ORDER: [HwAccel; 4] = [Self::Nvdec, Self::VideoToolbox, Self::Qsv, Self::Vaapi];
+
+    /// Parse from string (case-insensitive). `cuda` is accepted for `nvdec`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "" | "none" => Some(Self::None),
+            "auto" => Some(Self::Auto),
+            "nvdec" | "cuda" => Some(Self::Nvdec),
+            "qsv" => Some(Self::Qsv),
+            "vaapi" => Some(Self::Vaapi),
+            "videotoolbox" => Some(Self::VideoToolbox),
+            _ => None,
+        }
+    }
+
+    /// The `-hwaccel` value FFmpeg expects.
+    fn ffmpeg_name(self) -> Option<&'static str> {
+        match self {
+            Self::None | Self::Auto => None,
+            Self::Nvdec => Some("cuda"),
+            Self::Qsv => Some("qsv"),
+            Self::Vaapi => Some("vaapi"),
+            Self::VideoToolbox => Some("videotoolbox"),
+        }
+    }
+
+    /// The `-hwaccel` value to use given the methods this FFmpeg build
+    /// supports, or `None` for software decoding.
+    pub fn resolve(self, available: &[String]) -> Option<&'static str> {
+        let supported = |accel: HwAccel| {
+            accel
+                .ffmpeg_name()
+                .filter(|name| available.iter().any(|a| a == name))
+        };
+        match self {
+            Self::None => None,
+            Self::Auto => Self::AUTO_ORDER.into_iter().find_map(supported),
+            accel => {
+                let name = supported(accel);
+                if name.is_none() {
+                    warn!(hwaccel = %accel, "hardware decoder not supported by ffmpeg, decoding in software");
+                }
+                name
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for HwAccel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::None => "none",
+            Self::Auto => "auto",
+            Self::Nvdec => "nvdec",
+            Self::Qsv => "qsv",
+            Self::Vaapi => "vaapi",
+            Self::VideoToolbox => "videotoolbox",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Hardware acceleration methods listed by `ffmpeg -hwaccels`, probed once
+/// per process.
+pub fn available_hwaccels() -> &'static [String] {
+    static AVAILABLE: OnceLock<Vec<String>> = OnceLock::new();
+    AVAILABLE.get_or_init(|| {
+        let output = crate::runtime::command_for("ffmpeg")
+            .args(["-hide_banner", "-hwaccels"])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output();
+        match output {
+            Ok(output) if output.status.success() => {
+                parse_hwaccels(&String::from_utf8_lossy(&output.stdout))
+            }
+            _ => {
+                warn!("failed to list ffmpeg hardware decoders, decoding in software");
+                Vec::new()
+            }
+        }
+    })
+}
+
+fn parse_hwaccels(stdout: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .skip_while(|line| !line.starts_with("Hardware acceleration methods"))
+        .skip(1)
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Opens a decoder for the `segments` of `path` (the whole file when empty),
+/// played back to back, using `hwaccel` when FFmpeg supports it. The hardware
+/// decoder has to deliver its first frame; if it fails instead, every segment
+/// is decoded in software.
+pub fn open_decoder(
+    path: &Path,
+    info: &VideoStreamInfo,
+    hwaccel: HwAccel,
+    segments: &[FrameRange],
+) -> Result<Box<dyn Iterator<Item = Result<Frame>> + Send>> {
+    let mut segments = segments.to_vec();
+    if segments.is_empty() {
+        segments.push(FrameRange::ALL);
+    }
+    let first_range = segments.remove(0);
+
+    let mut accel = hwaccel.resolve(available_hwaccels());
+    let mut first: Option<Box<dyn Iterator<Item = Result<Frame>> + Send>> = None;
+    if let Some(name) = accel {
+        match VideoDecoder::for_range(path, info, Some(name), first_range) {
+            Ok(mut decoder) => match decoder.read_frame() {
+                Ok(Some(frame)) => {
+                    debug!(hwaccel = name, "hardware decode enabled");
+                    first = Some(Box::new(std::iter::once(Ok(frame)).chain(decoder)));
+                }
+                Ok(None) => {
+                    warn!(
+                        hwaccel = name,
+                        "hardware decoder produced no frames, decoding in software"
+                    );
+                }
+                Err(e) => {
+                    warn!(hwaccel = name, error = %e, "hardware decoder failed, decoding in software");
+                }
+            },
+            Err(e) => {
+                warn!(hwaccel = name, error = %e, "failed to start hardware decoder, decoding in software");
+            }
+        }
+        if first.is_none() {
+            accel = None;
+        }
+    }
+    let first = match first {
+        Some(first) => first,
+        None => Box::new(VideoDecoder::for_range(path, info, None, first_range)?),
+    };
+
+    // Later segments start their decoder only once the previous one is done.
+    let path = path.to_path_buf();
+    let info = info.clone();
+    let rest = segments.into_iter().flat_map(
+        move |range| -> Box<dyn Iterator<Item = Result<Frame>> + Send> {
+            match VideoDecoder::for_range(&path, &info, accel, range) {
+                Ok(decoder) => Box::new(decoder),
+                Err(e) => Box::new(std::iter::once(Err(e))),
+            }
+        },
+    );
+    Ok(Box::new(first.chain(rest)))
+}
+
+/// A half-open range of source frames, `start..end`. `end: None` runs to the
+/// last frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameRange {
+    pub start: u64,
+    pub end: Option<u64>,
+}
+
+impl FrameRange {
+    pub const ALL: FrameRange = FrameRange {
+        start: 0,
+        end: None,
+    };
+
+    /// Number of frames in the range, given the source's total when the range
+    /// is open-ended.
+    pub fn frame_count(&self, total_frames: Option<u64>) -> Option<u64> {
+        self.end
+            .or(total_frames)
+            .map(|end| end.saturating_sub(self.start))
+    }
+}
+
+impl std::fmt::Display for FrameRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.end {
+            Some(end) => write!(f, "{}-{}", self.start, end),
+            None => write!(f, "{}-", self.start),
+        }
+    }
+}
+
+/// Parses a segment list such as `"0-240, 1000-1240, 5000-"`. Segments are
+/// half-open frame ranges in playback order and must not overlap; only the
+/// last one may leave out its end.
+pub fn parse_segments(value: &str) -> Result<Vec<FrameRange>> {
+    let mut ranges: Vec<FrameRange> = Vec::new();
+    for part in value.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (start, end) = part
+            .split_once('-')
+            .ok_or_else(|| anyhow!("invalid segment '{part}', expected 'start-end'"))?;
+        let start: u64 = start
+            .trim()
+            .parse()
+            .with_context(|| format!("invalid start frame in segment '{part}'"))?;
+        let end = match end.trim() {
+            "" => None,
+            end => Some(
+                end.parse::<u64>()
+                    .with_context(|| format!("invalid end frame in segment '{part}'"))?,
+            ),
+        };
+        ranges.push(FrameRange { start, end });
+    }
+    validate_segments(&ranges)?;
+    Ok(ranges)
+}
+
+pub fn format_segments(ranges: &[FrameRange]) -> String {
+    ranges
+        .iter()
+        .map(FrameRange::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn validate_segments(ranges: &[FrameRange]) -> Result<()> {
+    let mut previous_end = 0u64;
+    for (index, range) in ranges.iter().enumerate() {
+        if range.end.is_some_and(|end| end <= range.start) {
+            bail!("segment {range} is empty: end frame must be after start frame");
+        }
+        if index > 0 && range.start < previous_end {
+            bail!("segment {range} overlaps or comes before the previous segment");
+        }
+        match range.end {
+            Some(end) => previous_end = end,
+            None if index + 1 < ranges.len() => {
+                bail!("only the last segment may be open-ended, got {range}")
+            }
+            None => {}
+        }
+    }
+    Ok(())
+}
+
+/// FFmpeg options that restrict decoding to `range`.
+///
+/// `-ss` before `-i` seeks to the keyframe before the start and then decodes
+/// and drops frames up to it, so the cut is frame-accurate. The seek lands half
+/// a frame early to absorb timestamp rounding; `setpts` then moves the first
+/// kept frame back to zero and `-frames:v` stops at the end of the range.
+fn range_args(range: FrameRange, fps: f64) -> Result<(Vec<String>, Vec<String>)> {
+    let mut input_args = Vec::new();
+    let mut output_args = Vec::new();
+    if range.start > 0 {
+        if !fps.is_finite() || fps <= 0.0 {
+            bail!("cannot seek to frame {}: unknown frame rate", range.start);
+        }
+        let seek_secs = (range.start as f64 - 0.5) / fps;
+        input_args.extend(["-ss".to_string(), format!("{seek_secs:.6}")]);
+        output_args.extend(["-vf".to_string(), "setpts=PTS-STARTPTS".to_string()]);
+    }
+    if let Some(count) = range.frame_count(None) {
+        output_args.extend(["-frames:v".to_string(), count.to_string()]);
+    }
+    Ok((input_args, output_args))
+}
+
+/// Whether `path` is an HTTP(S) URL (e.g. a Jellyfin download link) that
+/// FFmpeg reads directly rather than a local file.
+pub fn is_remote_source(path: &Path) -> bool {
+    let lower = path.to_string_lossy().to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
 /// Decodes video to raw RGB frames via FFmpeg subprocess, yielding one frame
 /// at a time. Uses `rgb24` for 8-bit, `rgb48le` for 10-bit+. Drains stderr in
 /// a background thread to prevent pipe deadlock. Kills FFmpeg on [`Drop`].
@@ -394,21 +847,24 @@ fn build_decoder_args(
     pix_fmt: &str,
     stream_index: usize,
     hwaccel: Option<&str>,
+    input_args: &[String],
+    output_args: &[String],
 ) -> Vec<String> {
     let mut args: Vec<String> = vec!["-nostdin".to_string()];
 
     // FFmpeg requires -hwaccel before -i
     if let Some(accel) = hwaccel {
-        if accel == "cuda" {
-            args.extend(["-hwaccel".to_string(), "cuda".to_string()]);
+        if matches!(accel, "cuda" | "qsv" | "vaapi" | "videotoolbox") {
+            args.extend(["-hwaccel".to_string(), accel.to_string()]);
         }
     }
 
+    args.extend(input_args.iter().cloned());
     args.push("-i".to_string());
     args.push(path.to_string_lossy().into_owned());
+    args.extend(["-map".to_string(), format!("0:{stream_index}")]);
+    args.extend(output_args.iter().cloned());
     args.extend([
-        "-map".to_string(),
-        format!("0:{stream_index}"),
         "-f".to_string(),
         "rawvideo".to_string(),
         "-pix_fmt".to_string(),
@@ -424,6 +880,54 @@ fn build_decoder_args(
 
 impl VideoDecoder {
     pub fn new(path: &Path, info: &VideoStreamInfo, hwaccel: Option<&str>) -> Result<Self> {
+        Self::with_input_args(path, info, hwaccel, &[])
+    }
+
+    /// Like [`VideoDecoder::new`], with extra FFmpeg options placed before
+    /// `-i` (protocol options for network sources).
+    pub fn with_input_args(
+        path: &Path,
+        info: &VideoStreamInfo,
+        hwaccel: Option<&str>,
+        input_args: &[String],
+    ) -> Result<Self> {
+        Self::launch(path, info, hwaccel, input_args, &[], None)
+    }
+
+    /// Decodes a stream another process writes into `input`. `input_args`
+    /// must name the container, since FFmpeg cannot probe a pipe by
+    /// extension (e.g. `-f yuv4mpegpipe`).
+    pub fn from_pipe(input: Stdio, info: &VideoStreamInfo, input_args: &[String]) -> Result<Self> {
+        Self::launch(
+            Path::new("pipe:0"),
+            info,
+            None,
+            input_args,
+            &[],
+            Some(input),
+        )
+    }
+
+    /// Decodes only the frames in `range`: FFmpeg seeks to the first frame and
+    /// stops after the last one.
+    pub fn for_range(
+        path: &Path,
+        info: &VideoStreamInfo,
+        hwaccel: Option<&str>,
+        range: FrameRange,
+    ) -> Result<Self> {
+        let (input_args, output_args) = range_args(range, info.fps)?;
+        Self::launch(path, info, hwaccel, &input_args, &output_args, None)
+    }
+
+    fn launch(
+        path: &Path,
+        info: &VideoStreamInfo,
+        hwaccel: Option<&str>,
+        input_args: &[String],
+        output_args: &[String],
+        stdin: Option<Stdio>,
+    ) -> Result<Self> {
         let (pix_fmt, bytes_per_pixel) = if info.bit_depth > 8 {
             ("rgb48le", 6usize)
         } else {
@@ -436,21 +940,35 @@ impl VideoDecoder {
             Some(other) => Some(other),
         };
 
-        let decode_args = build_decoder_args(path, pix_fmt, info.stream_index, hwaccel);
+        let decode_args = build_decoder_args(
+            path,
+            pix_fmt,
+            info.stream_index,
+            hwaccel,
+            input_args,
+            output_args,
+        );
 
-        if hwaccel == Some("cuda") {
-            debug!("NVDEC hardware decode enabled (hwaccel=cuda)");
+        if let Some(accel) = hwaccel {
+            debug!(hwaccel = accel, "launching FFmpeg with hardware decode");
         }
 
-        let mut child = crate::runtime::command_for("ffmpeg")
+        let mut command = crate::runtime::command_for("ffmpeg");
+        command
             .args(&decode_args)
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(stdin) = stdin {
+            command.stdin(stdin);
+        }
+        let mut child = command
             .spawn()
             .context("failed to launch ffmpeg — is it installed?")?;
 
         let stderr = child.stderr.take().expect("stderr should be piped");
+        let stderr_span = tracing::Span::current();
         let stderr_thread = thread::spawn(move || {
+            let _entered = stderr_span.enter();
             let reader = BufReader::new(stderr);
             for line in reader.lines() {
                 match line {
@@ -894,10 +1412,12 @@ mod tests {
         assert_eq!(node.node_type(), "video_input");
 
         let inputs = node.input_ports();
-        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs.len(), 7);
         assert_eq!(inputs[0].name, "path");
         assert_eq!(inputs[0].port_type, PortType::Path);
         assert!(inputs[0].required);
+        assert_eq!(inputs[1].name, "hwaccel");
+        assert!(!inputs[1].required);
 
         let outputs = node.output_ports();
         assert_eq!(outputs.len(), 2);
@@ -934,6 +1454,16 @@ mod tests {
             .contains("does not exist"));
     }
 
+    #[test]
+    fn test_is_remote_source() {
+        assert!(is_remote_source(Path::new(
+            "http://jellyfin.local:8096/Items/abc/Download?ApiKey=k"
+        )));
+        assert!(is_remote_source(Path::new("HTTPS://example.com/a.mkv")));
+        assert!(!is_remote_source(Path::new("/videos/http/a.mkv")));
+        assert!(!is_remote_source(Path::new("rtmp://live/key")));
+    }
+
     #[test]
     fn test_10bit_source_metadata() {
         let json = r#"{
@@ -1124,7 +1654,7 @@ mod tests {
     #[test]
     fn test_decoder_args_no_hwaccel() {
         let path = test_mkv_path();
-        let args = build_decoder_args(path.as_path(), "rgb24", 4, None);
+        let args = build_decoder_args(path.as_path(), "rgb24", 4, None, &[], &[]);
 
         assert!(!args.contains(&"-hwaccel".to_string()));
         let i_idx = args.iter().position(|a| a == "-i").unwrap();
@@ -1139,7 +1669,7 @@ mod tests {
     #[test]
     fn test_decoder_args_cuda_hwaccel() {
         let path = test_mkv_path();
-        let args = build_decoder_args(path.as_path(), "rgb48le", 2, Some("cuda"));
+        let args = build_decoder_args(path.as_path(), "rgb48le", 2, Some("cuda"), &[], &[]);
 
         let hwaccel_idx = args.iter().position(|a| a == "-hwaccel").unwrap();
         let i_idx = args.iter().position(|a| a == "-i").unwrap();
@@ -1155,7 +1685,7 @@ mod tests {
     #[test]
     fn test_decoder_args_none_string_hwaccel() {
         let path = test_mkv_path();
-        let args = build_decoder_args(path.as_path(), "rgb24", 0, Some("none"));
+        let args = build_decoder_args(path.as_path(), "rgb24", 0, Some("none"), &[], &[]);
 
         assert!(!args.contains(&"-hwaccel".to_string()));
     }
@@ -1163,13 +1693,169 @@ mod tests {
     #[test]
     fn test_decoder_args_unknown_hwaccel_ignored() {
         let path = test_mkv_path();
-        let args = build_decoder_args(path.as_path(), "rgb24", 7, Some("vulkan"));
+        let args = build_decoder_args(path.as_path(), "rgb24", 7, Some("vulkan"), &[], &[]);
 
         assert!(!args.contains(&"-hwaccel".to_string()));
         let map_idx = args.iter().position(|a| a == "-map").unwrap();
         assert_eq!(args[map_idx + 1], "0:7");
     }
 
+    #[test]
+    fn test_decoder_args_vaapi_hwaccel() {
+        let path = test_mkv_path();
+        let args = build_decoder_args(path.as_path(), "rgb24", 0, Some("vaapi"), &[], &[]);
+
+        let hwaccel_idx = args.iter().position(|a| a == "-hwaccel").unwrap();
+        assert_eq!(args[hwaccel_idx + 1], "vaapi");
+    }
+
+    #[test]
+    fn test_parse_hwaccels() {
+        let stdout = "Hardware acceleration methods:\nvdpau\ncuda\nvaapi\nqsv\n\n";
+        assert_eq!(
+            parse_hwaccels(stdout),
+            vec!["vdpau", "cuda", "vaapi", "qsv"]
+        );
+        assert!(parse_hwaccels("").is_empty());
+    }
+
+    #[test]
+    fn test_hwaccel_parse_and_resolve() {
+        assert_eq!(HwAccel::parse("NVDEC"), Some(HwAccel::Nvdec));
+        assert_eq!(HwAccel::parse("cuda"), Some(HwAccel::Nvdec));
+        assert_eq!(HwAccel::parse(""), Some(HwAccel::None));
+        assert_eq!(HwAccel::parse("vulkan"), None);
+        for option in HwAccel::OPTIONS {
+            assert_eq!(HwAccel::parse(option).unwrap().to_string(), option);
+        }
+
+        let available = vec!["vaapi".to_string(), "qsv".to_string()];
+        assert_eq!(HwAccel::Auto.resolve(&available), Some("qsv"));
+        assert_eq!(HwAccel::Vaapi.resolve(&available), Some("vaapi"));
+        assert_eq!(HwAccel::Nvdec.resolve(&available), None);
+        assert_eq!(HwAccel::None.resolve(&available), None);
+        assert_eq!(HwAccel::Auto.resolve(&[]), None);
+    }
+
+    #[test]
+    fn test_extract_metadata_for_selected_stream() {
+        let probe = parse_ffprobe_json(SAMPLE_FFPROBE_JSON.as_bytes()).unwrap();
+        let path = test_mkv_path();
+
+        let (info, metadata) = extract_metadata_for_stream(&probe, &path, Some(0)).unwrap();
+        assert_eq!(info.stream_index, 0);
+        assert_eq!(metadata.video_streams.len(), 1);
+        assert_eq!(metadata.video_streams[0].codec_name, "hevc");
+
+        let err = extract_metadata_for_stream(&probe, &path, Some(1))
+            .err()
+            .expect("audio stream cannot be decoded as video");
+        assert!(err.to_string().contains("video streams: 0"), "{err}");
+    }
+
+    #[test]
+    fn test_stream_index_input() {
+        let inputs = HashMap::from([
+            ("video_stream".to_string(), PortData::Int(-1)),
+            ("audio_stream".to_string(), PortData::Int(2)),
+            ("bad".to_string(), PortData::Int(-5)),
+        ]);
+        assert_eq!(stream_index_input(&inputs, "video_stream").unwrap(), None);
+        assert_eq!(
+            stream_index_input(&inputs, "audio_stream").unwrap(),
+            Some(2)
+        );
+        assert_eq!(stream_index_input(&inputs, "missing").unwrap(), None);
+        assert!(stream_index_input(&inputs, "bad").is_err());
+    }
+
+    #[test]
+    fn test_parse_segments() {
+        let ranges = parse_segments(" 0-240, 1000-1240,5000-").unwrap();
+        assert_eq!(
+            ranges,
+            vec![
+                FrameRange {
+                    start: 0,
+                    end: Some(240)
+                },
+                FrameRange {
+                    start: 1000,
+                    end: Some(1240)
+                },
+                FrameRange {
+                    start: 5000,
+                    end: None
+                },
+            ]
+        );
+        assert_eq!(format_segments(&ranges), "0-240,1000-1240,5000-");
+        assert!(parse_segments("").unwrap().is_empty());
+
+        for bad in ["10-10", "0-100,50-200", "0-,100-200", "abc", "5-x"] {
+            assert!(parse_segments(bad).is_err(), "{bad} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_segments_from_inputs() {
+        let inputs = |pairs: Vec<(&str, PortData)>| -> HashMap<String, PortData> {
+            pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect()
+        };
+
+        assert!(segments_from_inputs(&HashMap::new()).unwrap().is_empty());
+        assert_eq!(
+            segments_from_inputs(&inputs(vec![
+                ("start_frame", PortData::Int(48)),
+                ("end_frame", PortData::Int(96)),
+            ]))
+            .unwrap(),
+            vec![FrameRange {
+                start: 48,
+                end: Some(96)
+            }]
+        );
+        assert!(segments_from_inputs(&inputs(vec![
+            ("start_frame", PortData::Int(48)),
+            ("segments", PortData::Str("0-10".to_string())),
+        ]))
+        .is_err());
+        assert!(segments_from_inputs(&inputs(vec![("start_frame", PortData::Int(-1))])).is_err());
+    }
+
+    #[test]
+    fn test_range_args_seek_and_trim() {
+        let (input_args, output_args) = range_args(
+            FrameRange {
+                start: 240,
+                end: Some(480),
+            },
+            24.0,
+        )
+        .unwrap();
+        assert_eq!(input_args, vec!["-ss", "9.979167"]);
+        assert_eq!(
+            output_args,
+            vec!["-vf", "setpts=PTS-STARTPTS", "-frames:v", "240"]
+        );
+
+        let (input_args, output_args) = range_args(FrameRange::ALL, 24.0).unwrap();
+        assert!(input_args.is_empty() && output_args.is_empty());
+
+        let args = build_decoder_args(
+            test_mkv_path().as_path(),
+            "rgb24",
+            0,
+            None,
+            &["-ss".to_string(), "1.0".to_string()],
+            &["-frames:v".to_string(), "10".to_string()],
+        );
+        let ss_idx = args.iter().position(|a| a == "-ss").unwrap();
+        let i_idx = args.iter().position(|a| a == "-i").unwrap();
+        let frames_idx = args.iter().position(|a| a == "-frames:v").unwrap();
+        assert!(ss_idx < i_idx && i_idx < frames_idx);
+    }
+
     fn test_mkv_path() -> PathBuf {
         std::env::temp_dir().join("test.mkv")
     }
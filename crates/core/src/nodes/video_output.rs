@@ -14,10 +14,246 @@ use std::thread::{self, JoinHandle};
 use anyhow::{bail, Context, Result};
 use tracing::{debug, info, warn};
 
-use crate::node::{ExecutionContext, Node, PortDefinition};
+use crate::job_phase::{report_phase, JobPhase};
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
+use crate::nodes::comparison::{comparison_times, save_comparison_frames};
+use crate::nodes::video_input::parse_frame_rate;
 use crate::streaming_executor::FrameSink;
 use crate::types::{Frame, PortData, PortType};
 
+/// Segment length for `.m3u8` outputs.
+const HLS_VOD_SEGMENT_SECS: u32 = 4;
+pub const DEFAULT_TEMP_SUFFIX: &str = ".part";
+
+/// How the encoder gets its file to `output_path`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum OutputStrategy {
+    /// FFmpeg writes `output_path` directly.
+    #[default]
+    Direct,
+    /// FFmpeg writes a temp name in the same directory, which is renamed to
+    /// `output_path` once the encode succeeds. Media servers and torrent
+    /// clients watching the directory never see a half-written file.
+    TempRename {
+        suffix: String,
+        position: SuffixPosition,
+    },
+}
+
+/// Where [`OutputStrategy::TempRename`] puts its suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuffixPosition {
+    /// `ep01.mkv.part`: most scanners skip it, but FFmpeg needs the
+    /// container named explicitly.
+    Append,
+    /// `ep01.part.mkv`: keeps the real extension.
+    BeforeExtension,
+}
+
+impl OutputStrategy {
+    /// The file FFmpeg writes while encoding to `output_path`.
+    pub fn write_path(&self, output_path: &Path) -> PathBuf {
+        let (suffix, position) = match self {
+            OutputStrategy::Direct => return output_path.to_path_buf(),
+            OutputStrategy::TempRename { suffix, position } => (suffix, position),
+        };
+        let file_name = output_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let temp_name = match (position, output_path.extension()) {
+            (SuffixPosition::BeforeExtension, Some(ext)) => {
+                let stem = output_path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                format!("{stem}{suffix}.{}", ext.to_string_lossy())
+            }
+            _ => format!("{file_name}{suffix}"),
+        };
+        output_path.with_file_name(temp_name)
+    }
+}
+
+/// Reads `output_strategy`, `temp_suffix` and `temp_suffix_position`.
+pub fn output_strategy_from_inputs(inputs: &HashMap<String, PortData>) -> Result<OutputStrategy> {
+    let str_input = |name: &str, default: &str| match inputs.get(name) {
+        Some(PortData::Str(value)) => Ok(value.clone()),
+        Some(_) => bail!("invalid '{name}' input (expected Str)"),
+        None => Ok(default.to_string()),
+    };
+    let strategy = match str_input("output_strategy", "direct")?.as_str() {
+        "direct" => return Ok(OutputStrategy::Direct),
+        "temp_rename" => {
+            let suffix = str_input("temp_suffix", DEFAULT_TEMP_SUFFIX)?;
+            if suffix.is_empty() || suffix.contains(['/', '\\']) {
+                bail!("temp_suffix must be a non-empty file name suffix, got '{suffix}'");
+            }
+            let position = match str_input("temp_suffix_position", "append")?.as_str() {
+                "append" => SuffixPosition::Append,
+                "before_extension" => SuffixPosition::BeforeExtension,
+                other => bail!(
+                    "temp_suffix_position must be 'append' or 'before_extension', got '{other}'"
+                ),
+            };
+            OutputStrategy::TempRename { suffix, position }
+        }
+        other => bail!("output_strategy must be 'direct' or 'temp_rename', got '{other}'"),
+    };
+
+    if let Some(PortData::Path(output_path)) = inputs.get("output_path") {
+        let ext = output_path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        if ext == "m3u8" {
+            bail!("output_strategy 'temp_rename' does not apply to HLS (.m3u8) outputs");
+        }
+        if matches!(
+            strategy,
+            OutputStrategy::TempRename {
+                position: SuffixPosition::Append,
+                ..
+            }
+        ) && muxer_for_extension(&ext).is_none()
+        {
+            bail!(
+                "cannot tell the container of '{}' once a suffix is appended; \
+                 use temp_suffix_position 'before_extension'",
+                output_path.display()
+            );
+        }
+    }
+    Ok(strategy)
+}
+
+/// Checks run on the finished file before the encode counts as done.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OutputChecks {
+    /// Decode the whole file with `ffmpeg -f null` and fail on decode errors
+    /// or fewer frames than were encoded.
+    pub verify: bool,
+    /// Write `<output>.<algorithm>` next to the output, in `sha256sum`
+    /// format.
+    pub checksum: Option<ChecksumAlgorithm>,
+    /// Save source/output frame pairs this many seconds of content apart,
+    /// see [`crate::nodes::comparison`].
+    pub compare_every_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+    Sha256,
+}
+
+impl ChecksumAlgorithm {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Sha256 => "sha256",
+        }
+    }
+}
+
+impl OutputChecks {
+    /// Reads `verify_output`, `checksum` and `compare_every_mins`.
+    pub fn from_inputs(inputs: &HashMap<String, PortData>) -> Result<Self> {
+        let verify = match inputs.get("verify_output") {
+            Some(PortData::Bool(value)) => *value,
+            Some(_) => bail!("invalid 'verify_output' input (expected Bool)"),
+            None => false,
+        };
+        let checksum = match inputs.get("checksum") {
+            Some(PortData::Str(value)) => match value.as_str() {
+                "none" | "" => None,
+                "sha256" => Some(ChecksumAlgorithm::Sha256),
+                other => bail!("checksum must be 'none' or 'sha256', got '{other}'"),
+            },
+            Some(_) => bail!("invalid 'checksum' input (expected Str)"),
+            None => None,
+        };
+        let compare_every_mins = match inputs.get("compare_every_mins") {
+            Some(PortData::Float(value)) => *value,
+            Some(PortData::Int(value)) => *value as f64,
+            Some(_) => bail!("invalid 'compare_every_mins' input (expected Float)"),
+            None => 0.0,
+        };
+        if !compare_every_mins.is_finite() || compare_every_mins < 0.0 {
+            bail!("compare_every_mins must be 0 or more, got {compare_every_mins}");
+        }
+        let compare_every_secs =
+            (compare_every_mins > 0.0).then(|| ((compare_every_mins * 60.0).round() as u64).max(1));
+        Ok(Self {
+            verify,
+            checksum,
+            compare_every_secs,
+        })
+    }
+}
+
+/// Path of the checksum file written for `output_path`.
+pub fn checksum_sidecar_path(output_path: &Path, algorithm: ChecksumAlgorithm) -> PathBuf {
+    let mut name = output_path.as_os_str().to_owned();
+    name.push(".");
+    name.push(algorithm.extension());
+    PathBuf::from(name)
+}
+
+/// Decodes `path` to nowhere and fails if FFmpeg reports an error or decodes
+/// fewer than `expected_frames` video frames (a truncated file).
+fn verify_output_file(path: &Path, expected_frames: u64) -> Result<()> {
+    let result = crate::runtime::command_for("ffmpeg")
+        .args(["-hide_banner", "-nostats", "-v", "error", "-xerror", "-i"])
+        .arg(path)
+        .args(["-map", "0:v:0", "-f", "null", "-progress", "pipe:1", "-"])
+        .stdin(Stdio::null())
+        .output()
+        .context("failed to launch ffmpeg to verify the output")?;
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    if !result.status.success() || !stderr.trim().is_empty() {
+        bail!(
+            "output {} failed verification: {}",
+            path.display(),
+            stderr
+                .lines()
+                .next()
+                .unwrap_or("ffmpeg could not decode it")
+        );
+    }
+    let decoded = parse_progress_frames(&String::from_utf8_lossy(&result.stdout))
+        .with_context(|| format!("ffmpeg reported no frame count for {}", path.display()))?;
+    if decoded < expected_frames {
+        bail!(
+            "output {} is truncated: {decoded} of {expected_frames} frames decode",
+            path.display()
+        );
+    }
+    debug!(path = %path.display(), frames = decoded, "output verified");
+    Ok(())
+}
+
+/// Last `frame=` value of FFmpeg's `-progress` output.
+fn parse_progress_frames(progress: &str) -> Option<u64> {
+    progress
+        .lines()
+        .rev()
+        .find_map(|line| line.trim().strip_prefix("frame="))
+        .and_then(|frames| frames.trim().parse().ok())
+}
+
+/// FFmpeg muxer for an output extension, for temp names that hide it.
+fn muxer_for_extension(ext: &str) -> Option<&'static str> {
+    Some(match ext {
+        "mkv" | "mka" => "matroska",
+        "webm" => "webm",
+        "mp4" | "m4v" => "mp4",
+        "mov" => "mov",
+        "ts" | "m2ts" => "mpegts",
+        "avi" => "avi",
+        "flv" => "flv",
+        _ => return None,
+    })
+}
+
 #[derive(Debug, Clone)]
 pub struct EncoderConfig {
     /// Path to the original source file (for non-video stream muxing).
@@ -44,6 +280,14 @@ pub struct EncoderConfig {
     pub nvenc_preset: Option<String>,
     /// Software encoder preset (e.g. "medium", "slow", "veryslow" for x265/x264).
     pub x265_preset: Option<String>,
+    /// Copy audio, subtitles, attachments, metadata and chapters from
+    /// `source_path`. Off for live sources, which have no file to copy from.
+    pub copy_source_streams: bool,
+    /// With `copy_source_streams`, copy only this source audio stream (by
+    /// ffprobe index) instead of all of them.
+    pub audio_stream: Option<usize>,
+    pub output_strategy: OutputStrategy,
+    pub checks: OutputChecks,
 }
 
 impl EncoderConfig {
@@ -82,17 +326,45 @@ impl EncoderConfig {
             self.fps.clone(),
             "-i".into(),
             "pipe:0".into(),
-            "-i".into(),
-            self.source_path.to_string_lossy().into_owned(),
-            "-map".into(),
-            "0:v:0".into(),
-            "-map".into(),
-            "1".into(),
-            "-map".into(),
-            "-1:v".into(),
-            "-c:v".into(),
-            self.codec.clone(),
         ];
+        let is_hls = self.is_hls_output();
+        if self.copy_source_streams && is_hls {
+            // HLS carries no subtitle or attachment streams: keep the audio only.
+            let audio_map = match self.audio_stream {
+                Some(index) => format!("1:{index}"),
+                None => "1:a?".to_string(),
+            };
+            args.extend([
+                "-i".into(),
+                self.source_path.to_string_lossy().into_owned(),
+                "-map".into(),
+                "0:v:0".into(),
+                "-map".into(),
+                audio_map,
+            ]);
+        } else if self.copy_source_streams {
+            args.extend([
+                "-i".into(),
+                self.source_path.to_string_lossy().into_owned(),
+                "-map".into(),
+                "0:v:0".into(),
+                "-map".into(),
+                "1".into(),
+                "-map".into(),
+                "-1:v".into(),
+            ]);
+            if let Some(index) = self.audio_stream {
+                args.extend([
+                    "-map".into(),
+                    "-1:a".into(),
+                    "-map".into(),
+                    format!("1:{index}"),
+                ]);
+            }
+        } else {
+            args.extend(["-map".into(), "0:v:0".into()]);
+        }
+        args.extend(["-c:v".into(), self.codec.clone()]);
 
         if is_nvenc {
             let cq = self.cq_value.unwrap_or(20);
@@ -121,29 +393,98 @@ impl EncoderConfig {
             self.pixel_format.clone(),
             "-vf".into(),
             vf_filter,
-            "-c:a".into(),
-            "copy".into(),
-            "-c:s".into(),
-            "copy".into(),
-            "-c:t".into(),
-            "copy".into(),
-            "-map_metadata".into(),
-            "1".into(),
-            "-map_chapters".into(),
-            "1".into(),
-            "-copy_unknown".into(),
         ]);
+        if self.copy_source_streams && is_hls {
+            // Source audio is often FLAC/DTS/TrueHD, which browsers cannot play.
+            args.extend([
+                "-c:a".into(),
+                "aac".into(),
+                "-b:a".into(),
+                "192k".into(),
+                "-map_metadata".into(),
+                "1".into(),
+            ]);
+        } else if self.copy_source_streams {
+            args.extend([
+                "-c:a".into(),
+                "copy".into(),
+                "-c:s".into(),
+                "copy".into(),
+                "-c:t".into(),
+                "copy".into(),
+                "-map_metadata".into(),
+                "1".into(),
+                "-map_chapters".into(),
+                "1".into(),
+                "-copy_unknown".into(),
+            ]);
+        }
 
         if self.codec == "libx265" && self.pixel_format.contains("10") {
             args.push("-x265-params".into());
             args.push("profile=main10".into());
         }
 
-        args.push(self.output_path.to_string_lossy().into_owned());
+        if is_hls {
+            let segment_pattern = self
+                .output_path
+                .parent()
+                .unwrap_or_else(|| Path::new("."))
+                .join("segment_%05d.m4s");
+            args.extend([
+                "-f".into(),
+                "hls".into(),
+                "-hls_time".into(),
+                HLS_VOD_SEGMENT_SECS.to_string(),
+                "-hls_playlist_type".into(),
+                "event".into(),
+                "-hls_segment_type".into(),
+                "fmp4".into(),
+                "-hls_fmp4_init_filename".into(),
+                "init.mp4".into(),
+                "-hls_flags".into(),
+                "independent_segments".into(),
+                "-hls_segment_filename".into(),
+                segment_pattern.to_string_lossy().into_owned(),
+            ]);
+            if self.codec.contains("265") || self.codec.contains("hevc") {
+                // Apple players only accept HEVC tagged as hvc1.
+                args.extend(["-tag:v".into(), "hvc1".into()]);
+            }
+        }
+
+        if let OutputStrategy::TempRename {
+            position: SuffixPosition::Append,
+            ..
+        } = self.output_strategy
+        {
+            let ext = self
+                .output_path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+                .unwrap_or_default();
+            if let Some(muxer) = muxer_for_extension(&ext) {
+                args.extend(["-f".into(), muxer.into()]);
+            }
+        }
+        args.push(
+            self.output_strategy
+                .write_path(&self.output_path)
+                .to_string_lossy()
+                .into_owned(),
+        );
 
         args
     }
 
+    /// An `.m3u8` output path writes a growing fragmented-MP4 HLS playlist
+    /// that can be watched while the encode is still running.
+    pub fn is_hls_output(&self) -> bool {
+        self.output_path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("m3u8"))
+    }
+
     pub fn frame_size(&self) -> usize {
         let bytes_per_pixel: usize = if self.bit_depth > 8 { 6 } else { 3 };
         self.width as usize * self.height as usize * bytes_per_pixel
@@ -158,10 +499,21 @@ pub struct VideoEncoder {
     stderr_thread: Option<JoinHandle<()>>,
     frame_size: usize,
     output_path: PathBuf,
+    /// Differs from `output_path` with [`OutputStrategy::TempRename`].
+    write_path: PathBuf,
+    finished: bool,
+    checks: OutputChecks,
+    frames_written: u64,
+    /// Source to take comparison frames from, and the output frame rate;
+    /// `None` when the output does not line up with a source file.
+    comparison_source: Option<(PathBuf, f64)>,
 }
 
 impl VideoEncoder {
     pub fn new(config: &EncoderConfig) -> Result<Self> {
+        if config.output_strategy != OutputStrategy::Direct && config.is_hls_output() {
+            bail!("output_strategy 'temp_rename' does not apply to HLS (.m3u8) outputs");
+        }
         let args = config.build_ffmpeg_args();
         let frame_size = config.frame_size();
 
@@ -184,7 +536,9 @@ impl VideoEncoder {
             .ok_or_else(|| anyhow::anyhow!("failed to open ffmpeg stdin"))?;
 
         let stderr = child.stderr.take().expect("stderr should be piped");
+        let stderr_span = tracing::Span::current();
         let stderr_thread = thread::spawn(move || {
+            let _entered = stderr_span.enter();
             let reader = BufReader::new(stderr);
             for line in reader.lines() {
                 match line {
@@ -210,12 +564,27 @@ impl VideoEncoder {
             "FFmpeg encoder started"
         );
 
+        // Trimmed decodes and live feeds no longer line up with the source
+        // file, which is when its streams are not copied either.
+        let comparison_source = parse_frame_rate(&config.fps)
+            .filter(|_| {
+                config.checks.compare_every_secs.is_some()
+                    && config.copy_source_streams
+                    && !config.is_hls_output()
+            })
+            .map(|fps| (config.source_path.clone(), fps));
+
         Ok(Self {
             child,
             stdin: Some(stdin),
             stderr_thread: Some(stderr_thread),
             frame_size,
             output_path: config.output_path.clone(),
+            write_path: config.output_strategy.write_path(&config.output_path),
+            finished: false,
+            checks: config.checks.clone(),
+            frames_written: 0,
+            comparison_source,
         })
     }
 
@@ -237,6 +606,7 @@ impl VideoEncoder {
         stdin
             .write_all(data)
             .context("failed to write frame to ffmpeg stdin")?;
+        self.frames_written += 1;
 
         Ok(())
     }
@@ -255,9 +625,69 @@ impl VideoEncoder {
         }
 
         debug!("FFmpeg encoder finished successfully");
+        report_phase(JobPhase::Muxing);
 
         // Post-process MKV files: regenerate track statistics tags.
-        add_mkv_statistics_tags(&self.output_path);
+        add_mkv_statistics_tags(&self.write_path, &self.output_path);
+
+        if self.checks.verify {
+            verify_output_file(&self.write_path, self.frames_written)?;
+        }
+        let checksum = match self.checks.checksum {
+            Some(algorithm) => {
+                let digest = match algorithm {
+                    ChecksumAlgorithm::Sha256 => {
+                        crate::model_registry::sha256_file(&self.write_path)?
+                    }
+                };
+                Some((algorithm, digest))
+            }
+            None => None,
+        };
+
+        if self.write_path != self.output_path {
+            std::fs::rename(&self.write_path, &self.output_path).with_context(|| {
+                format!(
+                    "failed to rename {} to {}",
+                    self.write_path.display(),
+                    self.output_path.display()
+                )
+            })?;
+            debug!(
+                from = %self.write_path.display(),
+                to = %self.output_path.display(),
+                "output moved into place"
+            );
+        }
+        if let Some((algorithm, digest)) = checksum {
+            let sidecar = checksum_sidecar_path(&self.output_path, algorithm);
+            let file_name = self
+                .output_path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            std::fs::write(&sidecar, format!("{digest}  {file_name}\n"))
+                .with_context(|| format!("failed to write {}", sidecar.display()))?;
+            info!(path = %self.output_path.display(), %digest, "output checksum written");
+        }
+        if let Some(((source_path, fps), every_secs)) = self
+            .comparison_source
+            .as_ref()
+            .zip(self.checks.compare_every_secs)
+        {
+            // Comparison frames are an aid; failing to save them keeps the
+            // encode.
+            let times = comparison_times(self.frames_written as f64 / fps, every_secs);
+            match save_comparison_frames(source_path, &self.output_path, &times) {
+                Ok(frames) => {
+                    info!(path = %self.output_path.display(), pairs = frames.len(), "comparison frames saved");
+                }
+                Err(err) => {
+                    warn!(path = %self.output_path.display(), error = %format!("{err:#}"), "Failed to save comparison frames");
+                }
+            }
+        }
+        self.finished = true;
 
         Ok(())
     }
@@ -271,6 +701,10 @@ impl Drop for VideoEncoder {
         if let Some(handle) = self.stderr_thread.take() {
             let _ = handle.join();
         }
+        // A failed or cancelled encode leaves no partial temp file behind.
+        if !self.finished && self.write_path != self.output_path {
+            let _ = std::fs::remove_file(&self.write_path);
+        }
     }
 }
 
@@ -388,6 +822,10 @@ impl Node for VideoOutputNode {
         "video_output"
     }
 
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Disk
+    }
+
     fn input_ports(&self) -> Vec<PortDefinition> {
         vec![
             PortDefinition {
@@ -438,6 +876,42 @@ impl Node for VideoOutputNode {
                 required: true,
                 default_value: None,
             },
+            PortDefinition {
+                name: "output_strategy".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("direct")),
+            },
+            PortDefinition {
+                name: "temp_suffix".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!(DEFAULT_TEMP_SUFFIX)),
+            },
+            PortDefinition {
+                name: "temp_suffix_position".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("append")),
+            },
+            PortDefinition {
+                name: "verify_output".to_string(),
+                port_type: PortType::Bool,
+                required: false,
+                default_value: Some(serde_json::json!(false)),
+            },
+            PortDefinition {
+                name: "checksum".to_string(),
+                port_type: PortType::Str,
+                required: false,
+                default_value: Some(serde_json::json!("none")),
+            },
+            PortDefinition {
+                name: "compare_every_mins".to_string(),
+                port_type: PortType::Float,
+                required: false,
+                default_value: Some(serde_json::json!(0.0)),
+            },
         ]
     }
 
@@ -508,6 +982,8 @@ impl Node for VideoOutputNode {
         if !source_path.exists() {
             bail!("source file does not exist: {}", source_path.display());
         }
+        let output_strategy = output_strategy_from_inputs(inputs)?;
+        OutputChecks::from_inputs(inputs)?;
 
         debug!(
             source = %source_path.display(),
@@ -518,6 +994,7 @@ impl Node for VideoOutputNode {
             width = width,
             height = height,
             fps = %fps,
+            output_strategy = ?output_strategy,
             "video output config validated"
         );
 
@@ -584,6 +1061,10 @@ pub fn encoder_config_from_inputs(
         cq_value: None,
         nvenc_preset: None,
         x265_preset: None,
+        copy_source_streams: true,
+        audio_stream: None,
+        output_strategy: output_strategy_from_inputs(inputs)?,
+        checks: OutputChecks::from_inputs(inputs)?,
     })
 }
 
@@ -592,9 +1073,10 @@ pub fn encoder_config_from_inputs(
 /// statistics tags that FFmpeg does not produce.
 ///
 /// Degrades gracefully: logs a warning if mkvpropedit is not installed or
-/// the output is not an MKV file.
-fn add_mkv_statistics_tags(output_path: &Path) {
-    let ext = output_path
+/// the output is not an MKV file. The container is judged by `final_path`,
+/// since a temp name may hide the extension.
+fn add_mkv_statistics_tags(output_path: &Path, final_path: &Path) {
+    let ext = final_path
         .extension()
         .and_then(|e| e.to_str())
         .unwrap_or("");
@@ -633,7 +1115,7 @@ fn add_mkv_statistics_tags(output_path: &Path) {
     }
 }
 
-fn nchw_f16_to_rgb(data: &[u16], h: usize, w: usize) -> Result<Vec<u8>> {
+pub(crate) fn nchw_f16_to_rgb(data: &[u16], h: usize, w: usize) -> Result<Vec<u8>> {
     use half::f16;
     use half::slice::HalfFloatSliceExt;
 
@@ -650,7 +1132,7 @@ fn nchw_f16_to_rgb(data: &[u16], h: usize, w: usize) -> Result<Vec<u8>> {
     nchw_f32_to_rgb(&f32_buf, h, w)
 }
 
-fn nchw_f32_to_rgb(data: &[f32], h: usize, w: usize) -> Result<Vec<u8>> {
+pub(crate) fn nchw_f32_to_rgb(data: &[f32], h: usize, w: usize) -> Result<Vec<u8>> {
     let expected = 3 * h * w;
     anyhow::ensure!(
         data.len() == expected,
@@ -736,6 +1218,10 @@ mod tests {
             cq_value: None,
             nvenc_preset: None,
             x265_preset: None,
+            copy_source_streams: true,
+            audio_stream: None,
+            output_strategy: OutputStrategy::Direct,
+            checks: OutputChecks::default(),
         }
     }
 
@@ -821,6 +1307,159 @@ mod tests {
         assert_eq!(args.last().unwrap(), &test_output_path().to_string_lossy());
     }
 
+    #[test]
+    fn test_ffmpeg_args_without_source_streams() {
+        let mut config = default_config();
+        config.copy_source_streams = false;
+        let args = config.build_ffmpeg_args();
+
+        assert_eq!(args.iter().filter(|a| *a == "-i").count(), 1);
+        assert!(!args.contains(&test_source_path().to_string_lossy().to_string()));
+        assert!(args.windows(2).any(|w| w[0] == "-map" && w[1] == "0:v:0"));
+        assert!(!args.contains(&"-map_metadata".to_string()));
+        assert!(!args.contains(&"-c:a".to_string()));
+    }
+
+    #[test]
+    fn test_ffmpeg_args_with_selected_audio_stream() {
+        let mut config = default_config();
+        config.audio_stream = Some(2);
+        let args = config.build_ffmpeg_args();
+
+        let maps: Vec<&str> = args
+            .windows(2)
+            .filter(|w| w[0] == "-map")
+            .map(|w| w[1].as_str())
+            .collect();
+        assert_eq!(maps, vec!["0:v:0", "1", "-1:v", "-1:a", "1:2"]);
+    }
+
+    #[test]
+    fn test_ffmpeg_args_hls_output() {
+        let mut config = default_config();
+        config.output_path = PathBuf::from("/data/streams/ep1/playlist.m3u8");
+        assert!(config.is_hls_output());
+        let args = config.build_ffmpeg_args();
+
+        assert!(args.windows(2).any(|w| w[0] == "-map" && w[1] == "1:a?"));
+        assert!(!args.contains(&"-1:v".to_string()));
+        assert!(!args.contains(&"-c:s".to_string()));
+        assert!(args.windows(2).any(|w| w[0] == "-c:a" && w[1] == "aac"));
+        assert!(args.windows(2).any(|w| w[0] == "-f" && w[1] == "hls"));
+        assert!(args
+            .windows(2)
+            .any(|w| w[0] == "-hls_segment_type" && w[1] == "fmp4"));
+        assert!(args.windows(2).any(
+            |w| w[0] == "-hls_segment_filename" && w[1] == "/data/streams/ep1/segment_%05d.m4s"
+        ));
+        assert!(args.windows(2).any(|w| w[0] == "-tag:v" && w[1] == "hvc1"));
+        assert_eq!(args.last().unwrap(), "/data/streams/ep1/playlist.m3u8");
+    }
+
+    #[test]
+    fn test_output_strategy_write_path() {
+        let output = Path::new("/media/show/ep01.mkv");
+        assert_eq!(OutputStrategy::Direct.write_path(output), output);
+
+        let append = OutputStrategy::TempRename {
+            suffix: ".part".to_string(),
+            position: SuffixPosition::Append,
+        };
+        assert_eq!(
+            append.write_path(output),
+            PathBuf::from("/media/show/ep01.mkv.part")
+        );
+
+        let before_ext = OutputStrategy::TempRename {
+            suffix: ".tmp".to_string(),
+            position: SuffixPosition::BeforeExtension,
+        };
+        assert_eq!(
+            before_ext.write_path(output),
+            PathBuf::from("/media/show/ep01.tmp.mkv")
+        );
+        assert_eq!(
+            before_ext.write_path(Path::new("/media/show/ep01")),
+            PathBuf::from("/media/show/ep01.tmp")
+        );
+    }
+
+    #[test]
+    fn test_ffmpeg_args_temp_rename_output() {
+        let mut config = default_config();
+        config.output_strategy = OutputStrategy::TempRename {
+            suffix: ".part".to_string(),
+            position: SuffixPosition::Append,
+        };
+        let args = config.build_ffmpeg_args();
+        let expected = format!("{}.part", test_output_path().display());
+        assert_eq!(args.last().unwrap(), &expected);
+        assert!(args.windows(2).any(|w| w[0] == "-f" && w[1] == "matroska"));
+
+        config.output_strategy = OutputStrategy::TempRename {
+            suffix: ".part".to_string(),
+            position: SuffixPosition::BeforeExtension,
+        };
+        let args = config.build_ffmpeg_args();
+        let expected = test_output_path().with_file_name("output.part.mkv");
+        assert_eq!(args.last().unwrap(), &expected.to_string_lossy());
+        assert!(!args.windows(2).any(|w| w[0] == "-f" && w[1] == "matroska"));
+    }
+
+    #[test]
+    fn test_output_strategy_from_inputs() {
+        let mut inputs = HashMap::new();
+        assert_eq!(
+            output_strategy_from_inputs(&inputs).unwrap(),
+            OutputStrategy::Direct
+        );
+
+        inputs.insert(
+            "output_strategy".to_string(),
+            PortData::Str("temp_rename".to_string()),
+        );
+        inputs.insert(
+            "output_path".to_string(),
+            PortData::Path(PathBuf::from("/media/ep01.mp4")),
+        );
+        assert_eq!(
+            output_strategy_from_inputs(&inputs).unwrap(),
+            OutputStrategy::TempRename {
+                suffix: DEFAULT_TEMP_SUFFIX.to_string(),
+                position: SuffixPosition::Append,
+            }
+        );
+
+        inputs.insert(
+            "output_path".to_string(),
+            PortData::Path(PathBuf::from("/media/ep01.xyz")),
+        );
+        assert!(output_strategy_from_inputs(&inputs).is_err());
+        inputs.insert(
+            "temp_suffix_position".to_string(),
+            PortData::Str("before_extension".to_string()),
+        );
+        assert!(output_strategy_from_inputs(&inputs).is_ok());
+
+        inputs.insert(
+            "output_path".to_string(),
+            PortData::Path(PathBuf::from("/streams/playlist.m3u8")),
+        );
+        assert!(output_strategy_from_inputs(&inputs).is_err());
+
+        inputs.insert(
+            "output_path".to_string(),
+            PortData::Path(PathBuf::from("/media/ep01.mkv")),
+        );
+        inputs.insert("temp_suffix".to_string(), PortData::Str("a/b".to_string()));
+        assert!(output_strategy_from_inputs(&inputs).is_err());
+        inputs.insert(
+            "output_strategy".to_string(),
+            PortData::Str("copy".to_string()),
+        );
+        assert!(output_strategy_from_inputs(&inputs).is_err());
+    }
+
     #[test]
     fn test_ffmpeg_args_10bit_input() {
         let mut config = default_config();
@@ -887,7 +1526,7 @@ mod tests {
         let node = VideoOutputNode::new();
         let ports = node.input_ports();
 
-        assert_eq!(ports.len(), 8);
+        assert_eq!(ports.len(), 14);
 
         let names: Vec<&str> = ports.iter().map(|p| p.name.as_str()).collect();
         assert!(names.contains(&"source_path"));
@@ -898,6 +1537,7 @@ mod tests {
         assert!(names.contains(&"width"));
         assert!(names.contains(&"height"));
         assert!(names.contains(&"fps"));
+        assert!(names.contains(&"compare_every_mins"));
 
         let required: Vec<&str> = ports
             .iter()
@@ -1119,8 +1759,7 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_frame_sink_write_frame_cpu_rgb() {
+    fn mock_encoder(output_path: PathBuf, write_path: PathBuf) -> VideoEncoder {
         let cmd_name = if cfg!(windows) { "cmd" } else { "cat" };
         let mut command = std::process::Command::new(cmd_name);
         if cfg!(windows) {
@@ -1134,14 +1773,23 @@ mod tests {
             .expect("failed to spawn mock encoder process");
 
         let stdin = child.stdin.take().expect("mock child stdin must be piped");
-        let frame_size = 6usize;
-        let mut encoder = VideoEncoder {
+        VideoEncoder {
             child,
             stdin: Some(stdin),
             stderr_thread: None,
-            frame_size,
-            output_path: null_path(),
-        };
+            frame_size: 6,
+            output_path,
+            write_path,
+            finished: false,
+            checks: OutputChecks::default(),
+            frames_written: 0,
+            comparison_source: None,
+        }
+    }
+
+    #[test]
+    fn test_frame_sink_write_frame_cpu_rgb() {
+        let mut encoder = mock_encoder(null_path(), null_path());
 
         let frame = Frame::CpuRgb {
             data: vec![0, 1, 2, 3, 4, 5],
@@ -1154,6 +1802,83 @@ mod tests {
         FrameSink::finish(&mut encoder).expect("mock encoder should finish successfully");
     }
 
+    #[test]
+    fn test_temp_rename_moves_output_on_finish_and_cleans_up_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("ep01.mp4");
+        let temp = dir.path().join("ep01.mp4.part");
+
+        std::fs::write(&temp, b"encoded").unwrap();
+        let mut encoder = mock_encoder(output.clone(), temp.clone());
+        encoder
+            .finish()
+            .expect("mock encoder should finish successfully");
+        drop(encoder);
+        assert!(!temp.exists());
+        assert_eq!(std::fs::read(&output).unwrap(), b"encoded");
+
+        std::fs::write(&temp, b"partial").unwrap();
+        drop(mock_encoder(dir.path().join("ep02.mp4"), temp.clone()));
+        assert!(!temp.exists());
+        assert!(!dir.path().join("ep02.mp4").exists());
+    }
+
+    #[test]
+    fn test_checksum_sidecar_written_for_final_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("ep01.mp4");
+        let temp = dir.path().join("ep01.mp4.part");
+        std::fs::write(&temp, b"abc").unwrap();
+
+        let mut encoder = mock_encoder(output.clone(), temp);
+        encoder.checks.checksum = Some(ChecksumAlgorithm::Sha256);
+        encoder
+            .finish()
+            .expect("mock encoder should finish successfully");
+
+        let sidecar = checksum_sidecar_path(&output, ChecksumAlgorithm::Sha256);
+        assert_eq!(sidecar, dir.path().join("ep01.mp4.sha256"));
+        assert_eq!(
+            std::fs::read_to_string(sidecar).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  ep01.mp4\n"
+        );
+    }
+
+    #[test]
+    fn test_output_checks_from_inputs_and_progress_parsing() {
+        let mut inputs = HashMap::new();
+        assert_eq!(
+            OutputChecks::from_inputs(&inputs).unwrap(),
+            OutputChecks::default()
+        );
+        inputs.insert("verify_output".to_string(), PortData::Bool(true));
+        inputs.insert("checksum".to_string(), PortData::Str("sha256".to_string()));
+        assert_eq!(
+            OutputChecks::from_inputs(&inputs).unwrap(),
+            OutputChecks {
+                verify: true,
+                checksum: Some(ChecksumAlgorithm::Sha256),
+                compare_every_secs: None,
+            }
+        );
+        inputs.insert("compare_every_mins".to_string(), PortData::Float(2.5));
+        assert_eq!(
+            OutputChecks::from_inputs(&inputs)
+                .unwrap()
+                .compare_every_secs,
+            Some(150)
+        );
+        inputs.insert("compare_every_mins".to_string(), PortData::Float(-1.0));
+        assert!(OutputChecks::from_inputs(&inputs).is_err());
+        inputs.remove("compare_every_mins");
+        inputs.insert("checksum".to_string(), PortData::Str("md5".to_string()));
+        assert!(OutputChecks::from_inputs(&inputs).is_err());
+
+        let progress = "frame=24\nfps=0.0\nprogress=continue\nframe=48\nfps=96.0\nprogress=end\n";
+        assert_eq!(parse_progress_frames(progress), Some(48));
+        assert_eq!(parse_progress_frames("progress=end\n"), None);
+    }
+
     #[test]
     #[ignore]
     fn test_encode_decode_roundtrip() {
@@ -1192,6 +1917,10 @@ mod tests {
             cq_value: None,
             nvenc_preset: None,
             x265_preset: None,
+            copy_source_streams: true,
+            audio_stream: None,
+            output_strategy: OutputStrategy::Direct,
+            checks: OutputChecks::default(),
         };
 
         let mut encoder = VideoEncoder::new(&config).unwrap();
@@ -1231,13 +1960,13 @@ mod tests {
     #[test]
     fn test_add_mkv_statistics_tags_skips_non_mkv() {
         let tmp = tempfile::NamedTempFile::with_suffix(".mp4").unwrap();
-        add_mkv_statistics_tags(tmp.path());
+        add_mkv_statistics_tags(tmp.path(), tmp.path());
     }
 
     #[test]
     fn test_add_mkv_statistics_tags_handles_missing_tool() {
         let tmp = tempfile::NamedTempFile::with_suffix(".mkv").unwrap();
-        add_mkv_statistics_tags(tmp.path());
+        add_mkv_statistics_tags(tmp.path(), tmp.path());
     }
 
     fn test_source_path() -> PathBuf {
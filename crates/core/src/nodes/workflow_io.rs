@@ -9,9 +9,9 @@ use std::path::PathBuf;
 
 use anyhow::{bail, Context, Result};
 
-use crate::executor::{port_data_from_json, SequentialExecutor};
+use crate::executor::{port_data_from_json, port_data_to_json, SequentialExecutor};
 use crate::graph::PipelineGraph;
-use crate::node::{ExecutionContext, Node, PortDefinition};
+use crate::node::{ExecutionContext, Node, PortDefinition, ResourceClass};
 use crate::types::{PortData, PortType};
 
 // ─── WorkflowInputNode ──────────────────────────────────────────────────────
@@ -64,6 +64,10 @@ impl Node for WorkflowInputNode {
         "WorkflowInput"
     }
 
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
     fn input_ports(&self) -> Vec<PortDefinition> {
         vec![]
     }
@@ -155,6 +159,10 @@ impl Node for WorkflowOutputNode {
         "WorkflowOutput"
     }
 
+    fn resource_class(&self) -> ResourceClass {
+        ResourceClass::Cpu
+    }
+
     fn input_ports(&self) -> Vec<PortDefinition> {
         self.ports.clone()
     }
@@ -179,6 +187,29 @@ impl Node for WorkflowOutputNode {
     }
 }
 
+/// The values the graph's WorkflowOutput nodes collected during a run, as
+/// JSON keyed by port name. `None` when the graph has no WorkflowOutput node
+/// or the run produced no per-node outputs (video pipelines).
+pub fn collect_workflow_outputs(
+    graph: &PipelineGraph,
+    outputs_by_node: &HashMap<String, HashMap<String, PortData>>,
+) -> Option<HashMap<String, serde_json::Value>> {
+    let mut results = HashMap::new();
+    let mut found = false;
+    for node in graph.nodes().filter(|n| n.node_type == "WorkflowOutput") {
+        let Some(node_outputs) = outputs_by_node.get(&node.id) else {
+            continue;
+        };
+        found = true;
+        for (port_name, data) in node_outputs {
+            if let Some(value) = port_data_to_json(data) {
+                results.insert(port_name.clone(), value);
+            }
+        }
+    }
+    found.then_some(results)
+}
+
 // ─── WorkflowNode (nested execution) ────────────────────────────────────────
 
 /// Executes a nested workflow as a single node. Input ports map to the inner
@@ -293,6 +324,9 @@ impl Node for WorkflowNode {
         inner_ctx.executing_workflows = ctx.executing_workflows.clone();
         inner_ctx.executing_workflows.insert(path);
         inner_ctx.nesting_depth = ctx.nesting_depth + 1;
+        inner_ctx.services = ctx.services.clone();
+        inner_ctx.trace = ctx.trace.as_ref().map(|trace| trace.scoped(&ctx.node_id));
+        inner_ctx.stepper = ctx.stepper.clone();
 
         // Inject our inputs as params for the inner WorkflowInput node
         let mut inner_params = HashMap::new();
@@ -327,6 +361,7 @@ fn parse_port_type(s: &str) -> Option<PortType> {
         "Bool" => Some(PortType::Bool),
         "Path" => Some(PortType::Path),
         "WorkflowPath" => Some(PortType::WorkflowPath),
+        "Json" => Some(PortType::Json),
         _ => None,
     }
 }
@@ -1034,4 +1069,38 @@ mod tests {
 
         let _ = std::fs::remove_dir_all(&dir);
     }
+
+    #[test]
+    fn test_collect_workflow_outputs() {
+        let graph: PipelineGraph = serde_json::from_value(serde_json::json!({
+            "nodes": [
+                {"id": "wf_in", "node_type": "WorkflowInput", "params": {}},
+                {"id": "wf_out", "node_type": "WorkflowOutput", "params": {}}
+            ],
+            "connections": []
+        }))
+        .unwrap();
+
+        assert!(collect_workflow_outputs(&graph, &HashMap::new()).is_none());
+
+        let mut outputs_by_node = HashMap::new();
+        outputs_by_node.insert(
+            "wf_in".to_string(),
+            HashMap::from([("ignored".to_string(), PortData::Int(1))]),
+        );
+        outputs_by_node.insert(
+            "wf_out".to_string(),
+            HashMap::from([
+                ("count".to_string(), PortData::Int(3)),
+                (
+                    "path".to_string(),
+                    PortData::Path(PathBuf::from("/out/a.mkv")),
+                ),
+            ]),
+        );
+        let collected = collect_workflow_outputs(&graph, &outputs_by_node).unwrap();
+        assert_eq!(collected.len(), 2);
+        assert_eq!(collected["count"], 3);
+        assert_eq!(collected["path"], "/out/a.mkv");
+    }
 }
@@ -0,0 +1,607 @@
+//! Custom node plugins loaded from dynamic libraries.
+//!
+//! A plugin is a shared library (`.so`, `.dylib` or `.dll`) placed in the
+//! configured `plugins_dir`. It talks to the host over a small C ABI where all
+//! structured data is passed as NUL-terminated UTF-8 JSON, so plugins can be
+//! written in any language that can export C symbols:
+//!
+//! - `u32 videnoa_plugin_abi_version(void)` must return [`PLUGIN_ABI_VERSION`].
+//! - `const char *videnoa_plugin_manifest(void)` returns a [`PluginManifest`].
+//!   The string is owned by the plugin and must stay valid while it is loaded.
+//! - `char *videnoa_plugin_execute(const char *node_type, const char *request)`
+//!   runs one node. The request is `{"params": {...}, "inputs": {...}}` and the
+//!   response is either `{"outputs": {...}}` or `{"error": "..."}`.
+//! - `void videnoa_plugin_free(char *ptr)` releases a string returned by
+//!   `videnoa_plugin_execute`.
+//!
+//! `videnoa_plugin_execute` may be called from several threads at once.
+//! Plugin nodes only exchange scalar port values; `VideoFrames` ports are not
+//! supported over this ABI.
+
+use std::collections::HashMap;
+use std::ffi::{c_char, CStr, CString};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock, RwLock};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::descriptor::{NodeDescriptor, PortDescriptor};
+use crate::executor::{port_data_from_json, port_data_to_json};
+use crate::node::{ExecutionContext, Node, PortDefinition};
+use crate::registry::NodeRegistry;
+use crate::types::{PortData, PortType};
+
+/// Version of the plugin C ABI understood by this build. Libraries reporting a
+/// different version are skipped.
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+const ABI_VERSION_SYMBOL: &[u8] = b"videnoa_plugin_abi_version\0";
+const MANIFEST_SYMBOL: &[u8] = b"videnoa_plugin_manifest\0";
+const EXECUTE_SYMBOL: &[u8] = b"videnoa_plugin_execute\0";
+const FREE_SYMBOL: &[u8] = b"videnoa_plugin_free\0";
+
+type AbiVersionFn = unsafe extern "C" fn() -> u32;
+type ManifestFn = unsafe extern "C" fn() -> *const c_char;
+type ExecuteFn = unsafe extern "C" fn(*const c_char, *const c_char) -> *mut c_char;
+type FreeFn = unsafe extern "C" fn(*mut c_char);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginManifest {
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub nodes: Vec<PluginNodeManifest>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginNodeManifest {
+    pub node_type: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub experimental: bool,
+    #[serde(default)]
+    pub deprecated: bool,
+    #[serde(default)]
+    pub replaced_by: Option<String>,
+    #[serde(default = "default_plugin_category")]
+    pub category: String,
+    #[serde(default = "default_plugin_accent_color")]
+    pub accent_color: String,
+    #[serde(default = "default_plugin_icon")]
+    pub icon: String,
+    #[serde(default)]
+    pub inputs: Vec<PluginPortManifest>,
+    #[serde(default)]
+    pub outputs: Vec<PluginPortManifest>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginPortManifest {
+    pub name: String,
+    pub port_type: PortType,
+    #[serde(default = "default_true")]
+    pub required: bool,
+    #[serde(default)]
+    pub default_value: Option<serde_json::Value>,
+    #[serde(default)]
+    pub enum_options: Option<Vec<String>>,
+}
+
+fn default_plugin_category() -> String {
+    "utility".to_string()
+}
+
+fn default_plugin_accent_color() -> String {
+    "#64748B".to_string()
+}
+
+fn default_plugin_icon() -> String {
+    "puzzle".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Summary of a loaded plugin, as reported by `GET /api/plugins`.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct PluginInfo {
+    pub name: String,
+    pub version: String,
+    pub path: PathBuf,
+    pub node_types: Vec<String>,
+}
+
+struct LoadedPlugin {
+    info: PluginInfo,
+    descriptors: Vec<NodeDescriptor>,
+}
+
+fn loaded_plugin_list() -> &'static RwLock<Vec<LoadedPlugin>> {
+    static LOADED: OnceLock<RwLock<Vec<LoadedPlugin>>> = OnceLock::new();
+    LOADED.get_or_init(|| RwLock::new(Vec::new()))
+}
+
+/// Plugins loaded by this process so far.
+pub fn loaded_plugins() -> Vec<PluginInfo> {
+    loaded_plugin_list()
+        .read()
+        .map(|plugins| plugins.iter().map(|plugin| plugin.info.clone()).collect())
+        .unwrap_or_default()
+}
+
+/// Descriptors exported by loaded plugins, appended to the built-in ones.
+pub fn plugin_node_descriptors() -> Vec<NodeDescriptor> {
+    loaded_plugin_list()
+        .read()
+        .map(|plugins| {
+            plugins
+                .iter()
+                .flat_map(|plugin| plugin.descriptors.iter().cloned())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn remember_loaded_plugin(info: PluginInfo, descriptors: Vec<NodeDescriptor>) {
+    let Ok(mut plugins) = loaded_plugin_list().write() else {
+        return;
+    };
+    // The server, CLI and worker each build their own registry; keep one
+    // entry per library so descriptors are not listed twice.
+    plugins.retain(|plugin| plugin.info.path != info.path);
+    plugins.push(LoadedPlugin { info, descriptors });
+}
+
+/// An opened plugin library and the entry points resolved from it.
+pub struct PluginLibrary {
+    execute: ExecuteFn,
+    free: FreeFn,
+    // Declared last so the library is unloaded only after the function
+    // pointers above are gone.
+    _library: Option<libloading::Library>,
+}
+
+impl PluginLibrary {
+    /// Opens `path`, negotiates the ABI version and reads the manifest.
+    pub fn open(path: &Path) -> Result<(Self, PluginManifest)> {
+        // SAFETY: loading a library runs its initializers; plugins in
+        // `plugins_dir` are trusted the same way as the ONNX Runtime library.
+        let library = unsafe { libloading::Library::new(path) }
+            .with_context(|| format!("failed to load plugin {}", path.display()))?;
+
+        // SAFETY: the symbol types match the documented plugin ABI.
+        let (abi_version, manifest, execute, free) = unsafe {
+            let abi_version = library
+                .get::<AbiVersionFn>(ABI_VERSION_SYMBOL)
+                .context("missing videnoa_plugin_abi_version")?;
+            let abi_version = abi_version();
+            check_abi_version(abi_version)?;
+
+            let manifest_fn = library
+                .get::<ManifestFn>(MANIFEST_SYMBOL)
+                .context("missing videnoa_plugin_manifest")?;
+            let manifest_ptr = manifest_fn();
+            if manifest_ptr.is_null() {
+                bail!("plugin returned a null manifest");
+            }
+            let manifest = CStr::from_ptr(manifest_ptr)
+                .to_str()
+                .context("plugin manifest is not valid UTF-8")?
+                .to_string();
+
+            let execute = *library
+                .get::<ExecuteFn>(EXECUTE_SYMBOL)
+                .context("missing videnoa_plugin_execute")?;
+            let free = *library
+                .get::<FreeFn>(FREE_SYMBOL)
+                .context("missing videnoa_plugin_free")?;
+            (abi_version, manifest, execute, free)
+        };
+
+        let manifest = parse_manifest(&manifest)?;
+        info!(
+            plugin = %manifest.name,
+            version = %manifest.version,
+            abi_version,
+            path = %path.display(),
+            "loaded node plugin"
+        );
+
+        Ok((
+            Self {
+                execute,
+                free,
+                _library: Some(library),
+            },
+            manifest,
+        ))
+    }
+
+    fn call(&self, node_type: &str, request: &serde_json::Value) -> Result<serde_json::Value> {
+        let node_type = CString::new(node_type)?;
+        let request = CString::new(serde_json::to_string(request)?)?;
+
+        // SAFETY: both arguments are valid NUL-terminated strings for the
+        // duration of the call, and the returned pointer is handed back to the
+        // plugin's own free function.
+        let response = unsafe {
+            let ptr = (self.execute)(node_type.as_ptr(), request.as_ptr());
+            if ptr.is_null() {
+                bail!("plugin returned no response");
+            }
+            let response = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+            (self.free)(ptr);
+            response
+        };
+
+        serde_json::from_str(&response).context("plugin response is not valid JSON")
+    }
+}
+
+fn check_abi_version(version: u32) -> Result<()> {
+    if version != PLUGIN_ABI_VERSION {
+        bail!("plugin ABI version {version} is not supported (expected {PLUGIN_ABI_VERSION})");
+    }
+    Ok(())
+}
+
+fn parse_manifest(raw: &str) -> Result<PluginManifest> {
+    let manifest: PluginManifest =
+        serde_json::from_str(raw).context("plugin manifest is not valid JSON")?;
+    if manifest.name.trim().is_empty() {
+        bail!("plugin manifest has an empty name");
+    }
+    Ok(manifest)
+}
+
+fn is_plugin_file(path: &Path) -> bool {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase());
+    matches!(extension.as_deref(), Some("so" | "dylib" | "dll"))
+}
+
+/// Loads every plugin library in `plugins_dir` into `registry`.
+///
+/// A missing directory is not an error. Libraries that fail to load, report an
+/// unsupported ABI version or declare node types that are already registered
+/// are skipped with a warning.
+pub fn load_plugins(registry: &mut NodeRegistry, plugins_dir: &Path) -> Vec<PluginInfo> {
+    let entries = match std::fs::read_dir(plugins_dir) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && is_plugin_file(path))
+        .collect();
+    paths.sort();
+
+    let mut loaded = Vec::new();
+    for path in paths {
+        match PluginLibrary::open(&path) {
+            Ok((library, manifest)) => {
+                let (info, descriptors) =
+                    register_plugin(registry, Arc::new(library), manifest, &path);
+                remember_loaded_plugin(info.clone(), descriptors);
+                loaded.push(info);
+            }
+            Err(err) => warn!(path = %path.display(), error = %err, "skipping node plugin"),
+        }
+    }
+    loaded
+}
+
+/// Registers the nodes declared in `manifest` and returns their descriptors.
+fn register_plugin(
+    registry: &mut NodeRegistry,
+    library: Arc<PluginLibrary>,
+    manifest: PluginManifest,
+    path: &Path,
+) -> (PluginInfo, Vec<NodeDescriptor>) {
+    let existing: Vec<String> = registry
+        .list_node_types()
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+
+    let mut node_types = Vec::new();
+    let mut descriptors = Vec::new();
+    for node in manifest.nodes {
+        if existing.contains(&node.node_type) || node_types.contains(&node.node_type) {
+            warn!(
+                plugin = %manifest.name,
+                node_type = %node.node_type,
+                "plugin node type is already registered, skipping"
+            );
+            continue;
+        }
+        let uses_frames = node
+            .inputs
+            .iter()
+            .chain(node.outputs.iter())
+            .any(|port| port.port_type == PortType::VideoFrames);
+        if uses_frames {
+            warn!(
+                plugin = %manifest.name,
+                node_type = %node.node_type,
+                "plugin nodes cannot use VideoFrames ports, skipping"
+            );
+            continue;
+        }
+
+        descriptors.push(node_descriptor(&node));
+        let inputs: Vec<PortDefinition> = node.inputs.iter().map(port_definition).collect();
+        let outputs: Vec<PortDefinition> = node.outputs.iter().map(port_definition).collect();
+        let node_type = node.node_type.clone();
+        let library = Arc::clone(&library);
+        registry.register(&node.node_type, move |params| {
+            Ok(Box::new(PluginNode {
+                node_type: node_type.clone(),
+                inputs: inputs.clone(),
+                outputs: outputs.clone(),
+                params,
+                library: Arc::clone(&library),
+            }))
+        });
+        node_types.push(node.node_type);
+    }
+
+    let info = PluginInfo {
+        name: manifest.name,
+        version: manifest.version,
+        path: path.to_path_buf(),
+        node_types,
+    };
+    (info, descriptors)
+}
+
+fn port_definition(port: &PluginPortManifest) -> PortDefinition {
+    PortDefinition {
+        name: port.name.clone(),
+        port_type: port.port_type.clone(),
+        required: port.required,
+        default_value: port.default_value.clone(),
+    }
+}
+
+fn port_descriptor(port: &PluginPortManifest) -> PortDescriptor {
+    PortDescriptor {
+        name: port.name.clone(),
+        port_type: format!("{:?}", port.port_type),
+        direction: "param".to_string(),
+        required: port.required,
+        default_value: port.default_value.clone(),
+        ui_hint: port.enum_options.as_ref().map(|_| "enum".to_string()),
+        enum_options: port.enum_options.clone(),
+        dynamic_type_param: None,
+        ..Default::default()
+    }
+}
+
+fn node_descriptor(node: &PluginNodeManifest) -> NodeDescriptor {
+    NodeDescriptor {
+        node_type: node.node_type.clone(),
+        display_name: node
+            .display_name
+            .clone()
+            .unwrap_or_else(|| node.node_type.clone()),
+        description: node.description.clone().unwrap_or_default(),
+        category: node.category.clone(),
+        accent_color: node.accent_color.clone(),
+        icon: node.icon.clone(),
+        inputs: node.inputs.iter().map(port_descriptor).collect(),
+        outputs: node.outputs.iter().map(port_descriptor).collect(),
+        experimental: node.experimental,
+        deprecated: node.deprecated,
+        replaced_by: node.replaced_by.clone(),
+        ..Default::default()
+    }
+}
+
+/// A node whose `execute` is forwarded to a plugin library.
+struct PluginNode {
+    node_type: String,
+    inputs: Vec<PortDefinition>,
+    outputs: Vec<PortDefinition>,
+    params: HashMap<String, serde_json::Value>,
+    library: Arc<PluginLibrary>,
+}
+
+impl Node for PluginNode {
+    fn node_type(&self) -> &str {
+        &self.node_type
+    }
+
+    fn input_ports(&self) -> Vec<PortDefinition> {
+        self.inputs.clone()
+    }
+
+    fn output_ports(&self) -> Vec<PortDefinition> {
+        self.outputs.clone()
+    }
+
+    fn execute(
+        &mut self,
+        inputs: &HashMap<String, PortData>,
+        _ctx: &ExecutionContext,
+    ) -> Result<HashMap<String, PortData>> {
+        let inputs: serde_json::Map<String, serde_json::Value> = inputs
+            .iter()
+            .filter_map(|(name, data)| port_data_to_json(data).map(|value| (name.clone(), value)))
+            .collect();
+        let request = serde_json::json!({ "params": self.params, "inputs": inputs });
+
+        let response = self.library.call(&self.node_type, &request)?;
+        if let Some(error) = response.get("error").and_then(|error| error.as_str()) {
+            bail!("plugin node {} failed: {error}", self.node_type);
+        }
+        let outputs = response
+            .get("outputs")
+            .and_then(|outputs| outputs.as_object())
+            .ok_or_else(|| anyhow!("plugin node {} returned no outputs", self.node_type))?;
+
+        let mut result = HashMap::new();
+        for port in &self.outputs {
+            let Some(value) = outputs.get(&port.name) else {
+                if port.required {
+                    bail!(
+                        "plugin node {} did not return output '{}'",
+                        self.node_type,
+                        port.name
+                    );
+                }
+                continue;
+            };
+            let data = port_data_from_json(&port.port_type, value)
+                .with_context(|| format!("invalid value for output '{}'", port.name))?;
+            result.insert(port.name.clone(), data);
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe extern "C" fn upper_execute(
+        _node_type: *const c_char,
+        request: *const c_char,
+    ) -> *mut c_char {
+        let request: serde_json::Value =
+            serde_json::from_str(CStr::from_ptr(request).to_str().unwrap()).unwrap();
+        let text = request["inputs"]["text"].as_str().unwrap_or_default();
+        let response = if text.is_empty() {
+            serde_json::json!({ "error": "text is empty" })
+        } else {
+            let suffix = request["params"]["suffix"].as_str().unwrap_or_default();
+            serde_json::json!({ "outputs": { "text": format!("{}{suffix}", text.to_uppercase()) } })
+        };
+        CString::new(response.to_string()).unwrap().into_raw()
+    }
+
+    unsafe extern "C" fn free_string(ptr: *mut c_char) {
+        drop(CString::from_raw(ptr));
+    }
+
+    fn test_library() -> Arc<PluginLibrary> {
+        Arc::new(PluginLibrary {
+            execute: upper_execute,
+            free: free_string,
+            _library: None,
+        })
+    }
+
+    const MANIFEST: &str = r#"{
+        "name": "text-tools",
+        "version": "0.1.0",
+        "nodes": [
+            {
+                "node_type": "PluginUppercase",
+                "display_name": "Uppercase",
+                "deprecated": true,
+                "replaced_by": "PluginTitlecase",
+                "inputs": [{ "name": "text", "port_type": "Str" }],
+                "outputs": [{ "name": "text", "port_type": "Str" }]
+            },
+            {
+                "node_type": "PluginFrames",
+                "inputs": [{ "name": "frames", "port_type": "VideoFrames" }]
+            },
+            {
+                "node_type": "Print",
+                "inputs": [{ "name": "value", "port_type": "Str" }]
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_check_abi_version() {
+        assert!(check_abi_version(PLUGIN_ABI_VERSION).is_ok());
+        let err = check_abi_version(PLUGIN_ABI_VERSION + 1).unwrap_err();
+        assert!(err.to_string().contains("not supported"));
+    }
+
+    #[test]
+    fn test_parse_manifest_defaults() {
+        let manifest = parse_manifest(MANIFEST).unwrap();
+        assert_eq!(manifest.name, "text-tools");
+        let node = &manifest.nodes[0];
+        assert_eq!(node.category, "utility");
+        assert!(node.inputs[0].required);
+        assert_eq!(node.inputs[0].port_type, PortType::Str);
+
+        assert!(parse_manifest(r#"{"name": " "}"#).is_err());
+        assert!(parse_manifest("not json").is_err());
+    }
+
+    #[test]
+    fn test_register_plugin_skips_unsupported_and_duplicate_nodes() {
+        let mut registry = NodeRegistry::new();
+        registry.register("Print", |_| bail!("builtin"));
+        let manifest = parse_manifest(MANIFEST).unwrap();
+        let path = PathBuf::from("/plugins/libtext_tools_skip.so");
+
+        let (info, descriptors) = register_plugin(&mut registry, test_library(), manifest, &path);
+
+        assert_eq!(info.node_types, vec!["PluginUppercase".to_string()]);
+        assert_eq!(registry.list_node_types(), vec!["PluginUppercase", "Print"]);
+        assert_eq!(descriptors.len(), 1);
+        let descriptor = &descriptors[0];
+        assert_eq!(descriptor.display_name, "Uppercase");
+        assert!(descriptor.deprecated && !descriptor.experimental);
+        assert_eq!(descriptor.replaced_by.as_deref(), Some("PluginTitlecase"));
+        assert_eq!(descriptor.inputs[0].port_type, "Str");
+    }
+
+    #[test]
+    fn test_plugin_node_executes_through_library() {
+        let mut registry = NodeRegistry::new();
+        let manifest = parse_manifest(MANIFEST).unwrap();
+        let path = PathBuf::from("/plugins/libtext_tools_exec.so");
+        register_plugin(&mut registry, test_library(), manifest, &path);
+
+        let params = HashMap::from([("suffix".to_string(), serde_json::json!("!"))]);
+        let mut node = registry.create("PluginUppercase", params).unwrap();
+        let ctx = ExecutionContext::default();
+
+        let inputs = HashMap::from([("text".to_string(), PortData::Str("hello".to_string()))]);
+        let outputs = node.execute(&inputs, &ctx).unwrap();
+        match outputs.get("text") {
+            Some(PortData::Str(value)) => assert_eq!(value, "HELLO!"),
+            _ => panic!("expected string output"),
+        }
+
+        let inputs = HashMap::from([("text".to_string(), PortData::Str(String::new()))]);
+        let err = match node.execute(&inputs, &ctx) {
+            Ok(_) => panic!("empty text should fail"),
+            Err(err) => err,
+        };
+        assert!(err.to_string().contains("text is empty"));
+    }
+
+    #[test]
+    fn test_load_plugins_skips_invalid_libraries() {
+        let dir = std::env::temp_dir().join(format!("videnoa-plugins-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("libbroken.so"), b"not a library").unwrap();
+        std::fs::write(dir.join("readme.txt"), b"ignored").unwrap();
+
+        let mut registry = NodeRegistry::new();
+        assert!(load_plugins(&mut registry, &dir).is_empty());
+        assert!(load_plugins(&mut registry, &dir.join("missing")).is_empty());
+        assert!(registry.list_node_types().is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,422 @@
+//! File actions run by the server after a job succeeds.
+//!
+//! A workflow lists its actions in `post_actions`, and a job request can
+//! replace that list. Actions run in order on every file written by a
+//! `VideoOutput` node: a `move` or `copy` makes later actions apply to the
+//! new location, so `move` then `chmod` sets the mode of the moved file.
+//! `delete_source` runs once, after every output action has succeeded. The
+//! first failing action stops the rest; the job stays completed and the
+//! results record what happened.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::graph::PipelineGraph;
+use crate::types::PortData;
+
+const VIDEO_INPUT_NODE_TYPE: &str = "VideoInput";
+const VIDEO_OUTPUT_NODE_TYPE: &str = "VideoOutput";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case", deny_unknown_fields)]
+pub enum PostAction {
+    /// Moves the output. `to` is a directory (kept file name) when it exists
+    /// as one or ends with a separator, and the new file path otherwise.
+    Move { to: PathBuf },
+    /// Copies the output; later actions apply to the copy.
+    Copy { to: PathBuf },
+    /// Sets the permission bits, as octal, e.g. `"664"`. Unix only.
+    Chmod { mode: String },
+    /// Changes owner and/or group, by name or numeric id. Unix only.
+    Chown {
+        #[serde(default)]
+        user: Option<String>,
+        #[serde(default)]
+        group: Option<String>,
+    },
+    /// Removes the source video, or moves it into `trash_dir` when set.
+    DeleteSource {
+        #[serde(default)]
+        trash_dir: Option<PathBuf>,
+    },
+}
+
+impl PostAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PostAction::Move { .. } => "move",
+            PostAction::Copy { .. } => "copy",
+            PostAction::Chmod { .. } => "chmod",
+            PostAction::Chown { .. } => "chown",
+            PostAction::DeleteSource { .. } => "delete_source",
+        }
+    }
+
+    /// Checks the settings that can be checked before the job runs.
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            PostAction::Move { to } | PostAction::Copy { to } => {
+                if to.as_os_str().is_empty() {
+                    bail!("{}: 'to' must not be empty", self.name());
+                }
+            }
+            PostAction::Chmod { mode } => {
+                parse_mode(mode)?;
+            }
+            PostAction::Chown { user, group } => {
+                if user.is_none() && group.is_none() {
+                    bail!("chown: set 'user', 'group' or both");
+                }
+            }
+            PostAction::DeleteSource { .. } => {}
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of one action on one file, recorded on the job.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PostActionResult {
+    pub action: String,
+    /// File the action was applied to.
+    pub path: PathBuf,
+    /// Where the file ended up, for `move`, `copy` and `delete_source` with
+    /// a trash directory.
+    pub result_path: Option<PathBuf>,
+    pub error: Option<String>,
+}
+
+/// Files written by the workflow's `VideoOutput` nodes and the source read
+/// by its first `VideoInput`.
+pub fn job_files(
+    graph: &PipelineGraph,
+    outputs_by_node: &HashMap<String, HashMap<String, PortData>>,
+) -> (Vec<PathBuf>, Option<PathBuf>) {
+    let path_output = |node_type: &str, port: &str| -> Vec<PathBuf> {
+        let mut paths: Vec<(String, PathBuf)> = graph
+            .nodes()
+            .filter(|node| node.node_type == node_type)
+            .filter_map(|node| match outputs_by_node.get(&node.id)?.get(port)? {
+                PortData::Path(path) => Some((node.id.clone(), path.clone())),
+                _ => None,
+            })
+            .collect();
+        paths.sort();
+        paths.into_iter().map(|(_, path)| path).collect()
+    };
+    let outputs = path_output(VIDEO_OUTPUT_NODE_TYPE, "output_path");
+    let source = path_output(VIDEO_INPUT_NODE_TYPE, "source_path")
+        .into_iter()
+        .next();
+    (outputs, source)
+}
+
+/// Runs `actions` on each output, then the source actions.
+pub fn run_post_actions(
+    actions: &[PostAction],
+    outputs: &[PathBuf],
+    source: Option<&Path>,
+) -> Vec<PostActionResult> {
+    let mut results = Vec::new();
+    for output in outputs {
+        let mut current = output.clone();
+        for action in actions {
+            if matches!(action, PostAction::DeleteSource { .. }) {
+                continue;
+            }
+            let outcome = apply_to_output(action, &current);
+            let failed = outcome.is_err();
+            results.push(result_from(action, &current, outcome));
+            if failed {
+                return results;
+            }
+            if let Some(next) = results.last().and_then(|r| r.result_path.clone()) {
+                current = next;
+            }
+        }
+    }
+
+    for action in actions {
+        let PostAction::DeleteSource { trash_dir } = action else {
+            continue;
+        };
+        let Some(source) = source else {
+            results.push(PostActionResult {
+                action: action.name().to_string(),
+                path: PathBuf::new(),
+                result_path: None,
+                error: Some("the workflow has no VideoInput source".to_string()),
+            });
+            return results;
+        };
+        let outcome = delete_source(source, trash_dir.as_deref(), outputs);
+        results.push(result_from(action, source, outcome));
+    }
+    results
+}
+
+fn result_from(
+    action: &PostAction,
+    path: &Path,
+    outcome: Result<Option<PathBuf>>,
+) -> PostActionResult {
+    let (result_path, error) = match outcome {
+        Ok(result_path) => (result_path, None),
+        Err(err) => (None, Some(format!("{err:#}"))),
+    };
+    PostActionResult {
+        action: action.name().to_string(),
+        path: path.to_path_buf(),
+        result_path,
+        error,
+    }
+}
+
+fn apply_to_output(action: &PostAction, path: &Path) -> Result<Option<PathBuf>> {
+    match action {
+        PostAction::Move { to } => {
+            let target = target_path(path, to)?;
+            move_file(path, &target)?;
+            Ok(Some(target))
+        }
+        PostAction::Copy { to } => {
+            let target = target_path(path, to)?;
+            fs::copy(path, &target).with_context(|| {
+                format!("failed to copy {} to {}", path.display(), target.display())
+            })?;
+            Ok(Some(target))
+        }
+        PostAction::Chmod { mode } => {
+            set_mode(path, parse_mode(mode)?)?;
+            Ok(None)
+        }
+        PostAction::Chown { user, group } => {
+            chown(path, user.as_deref(), group.as_deref())?;
+            Ok(None)
+        }
+        PostAction::DeleteSource { .. } => Ok(None),
+    }
+}
+
+/// Resolves `to` against the file name of `path` and creates its parent.
+fn target_path(path: &Path, to: &Path) -> Result<PathBuf> {
+    let names_dir = to.is_dir() || to.as_os_str().to_string_lossy().ends_with(['/', '\\']);
+    let target = if names_dir {
+        let file_name = path
+            .file_name()
+            .with_context(|| format!("{} has no file name", path.display()))?;
+        to.join(file_name)
+    } else {
+        to.to_path_buf()
+    };
+    if let Some(parent) = target.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    Ok(target)
+}
+
+/// Renames, falling back to copy and delete across file systems.
+fn move_file(from: &Path, to: &Path) -> Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to)
+        .with_context(|| format!("failed to move {} to {}", from.display(), to.display()))?;
+    fs::remove_file(from).with_context(|| format!("failed to remove {}", from.display()))
+}
+
+fn delete_source(
+    source: &Path,
+    trash_dir: Option<&Path>,
+    outputs: &[PathBuf],
+) -> Result<Option<PathBuf>> {
+    if outputs.iter().any(|output| output == source) {
+        bail!(
+            "source {} is also an output; not deleting it",
+            source.display()
+        );
+    }
+    if !source.is_file() {
+        bail!("source {} does not exist", source.display());
+    }
+    match trash_dir {
+        Some(trash_dir) => {
+            fs::create_dir_all(trash_dir)
+                .with_context(|| format!("failed to create {}", trash_dir.display()))?;
+            let target = target_path(source, trash_dir)?;
+            move_file(source, &target)?;
+            Ok(Some(target))
+        }
+        None => {
+            fs::remove_file(source)
+                .with_context(|| format!("failed to delete {}", source.display()))?;
+            Ok(None)
+        }
+    }
+}
+
+fn parse_mode(mode: &str) -> Result<u32> {
+    let digits = mode.trim().trim_start_matches("0o");
+    match u32::from_str_radix(digits, 8) {
+        Ok(bits) if !digits.is_empty() && bits <= 0o7777 => Ok(bits),
+        _ => bail!("chmod: mode must be octal like \"664\", got '{mode}'"),
+    }
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+        .with_context(|| format!("failed to chmod {}", path.display()))
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: u32) -> Result<()> {
+    bail!("chmod is only supported on Unix")
+}
+
+#[cfg(unix)]
+fn chown(path: &Path, user: Option<&str>, group: Option<&str>) -> Result<()> {
+    let owner = match (user, group) {
+        (Some(user), Some(group)) => format!("{user}:{group}"),
+        (Some(user), None) => user.to_string(),
+        (None, Some(group)) => format!(":{group}"),
+        (None, None) => return Ok(()),
+    };
+    let output = std::process::Command::new("chown")
+        .arg(&owner)
+        .arg(path)
+        .output()
+        .context("failed to run chown")?;
+    if !output.status.success() {
+        bail!(
+            "chown {owner} {} failed: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn chown(_path: &Path, _user: Option<&str>, _group: Option<&str>) -> Result<()> {
+    bail!("chown is only supported on Unix")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_post_action_parsing_and_validation() {
+        let actions: Vec<PostAction> = serde_json::from_value(serde_json::json!([
+            {"action": "move", "to": "/library/Show/"},
+            {"action": "chmod", "mode": "664"},
+            {"action": "chown", "group": "jellyfin"},
+            {"action": "delete_source", "trash_dir": "/media/.trash"},
+        ]))
+        .unwrap();
+        assert_eq!(
+            actions[0],
+            PostAction::Move {
+                to: PathBuf::from("/library/Show/")
+            }
+        );
+        assert!(actions.iter().all(|action| action.validate().is_ok()));
+
+        assert!(PostAction::Chmod {
+            mode: "rw-r--r--".to_string()
+        }
+        .validate()
+        .is_err());
+        assert!(PostAction::Chown {
+            user: None,
+            group: None
+        }
+        .validate()
+        .is_err());
+        assert!(serde_json::from_value::<PostAction>(serde_json::json!({
+            "action": "rename", "to": "/x"
+        }))
+        .is_err());
+    }
+
+    #[test]
+    fn test_run_post_actions_moves_copies_and_recycles_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("ep01.mkv");
+        let source = dir.path().join("ep01_src.mkv");
+        fs::write(&output, b"out").unwrap();
+        fs::write(&source, b"src").unwrap();
+        let library = dir.path().join("library").join("Show");
+        let backup = dir.path().join("backup.mkv");
+        let trash = dir.path().join("trash");
+
+        let actions = vec![
+            PostAction::Move {
+                to: PathBuf::from(format!("{}/", library.display())),
+            },
+            PostAction::Copy { to: backup.clone() },
+            PostAction::DeleteSource {
+                trash_dir: Some(trash.clone()),
+            },
+        ];
+        let results = run_post_actions(&actions, std::slice::from_ref(&output), Some(&source));
+
+        assert!(results.iter().all(|r| r.error.is_none()), "{results:?}");
+        assert_eq!(results[0].result_path, Some(library.join("ep01.mkv")));
+        assert_eq!(results[1].path, library.join("ep01.mkv"));
+        assert_eq!(fs::read(&backup).unwrap(), b"out");
+        assert!(!output.exists());
+        assert!(!source.exists());
+        assert_eq!(fs::read(trash.join("ep01_src.mkv")).unwrap(), b"src");
+    }
+
+    #[test]
+    fn test_run_post_actions_stops_at_first_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("src.mkv");
+        fs::write(&source, b"src").unwrap();
+
+        let actions = vec![
+            PostAction::Copy {
+                to: dir.path().join("copy.mkv"),
+            },
+            PostAction::DeleteSource { trash_dir: None },
+        ];
+        let missing = dir.path().join("missing.mkv");
+        let results = run_post_actions(&actions, &[missing], Some(&source));
+        assert_eq!(results.len(), 1);
+        assert!(results[0].error.is_some());
+        assert!(source.exists());
+
+        // An in-place job must never delete its own output.
+        let results = run_post_actions(&actions[1..], std::slice::from_ref(&source), Some(&source));
+        assert!(results[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("also an output"));
+        assert!(source.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_chmod_sets_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let actions = vec![PostAction::Chmod {
+            mode: "640".to_string(),
+        }];
+        let results = run_post_actions(&actions, &[file.path().to_path_buf()], None);
+        assert!(results[0].error.is_none());
+        let mode = fs::metadata(file.path()).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+    }
+}
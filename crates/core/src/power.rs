@@ -0,0 +1,206 @@
+//! Keeps the machine awake while jobs run and optionally suspends or shuts
+//! it down once the queue is empty, for unattended overnight batches.
+//!
+//! Sleep is held off with the platform's own mechanism: `systemd-inhibit` on
+//! Linux, `caffeinate` on macOS and `SetThreadExecutionState` on Windows.
+//! The display may still turn off; only system sleep is blocked.
+
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+
+use crate::config::QueueEmptyAction;
+
+/// Blocks system sleep until dropped.
+pub struct SleepInhibitor {
+    #[cfg(unix)]
+    child: std::process::Child,
+    #[cfg(windows)]
+    _release: std::sync::mpsc::Sender<()>,
+}
+
+impl SleepInhibitor {
+    /// `reason` is shown by tools that list inhibitors, e.g.
+    /// `systemd-inhibit --list`.
+    #[cfg(target_os = "linux")]
+    pub fn acquire(reason: &str) -> Result<Self> {
+        let child = Command::new("systemd-inhibit")
+            .args([
+                "--what=idle:sleep",
+                "--who=Videnoa",
+                &format!("--why={reason}"),
+                "--mode=block",
+                "sleep",
+                "infinity",
+            ])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("failed to run systemd-inhibit")?;
+        Ok(Self { child })
+    }
+
+    #[cfg(target_os = "macos")]
+    pub fn acquire(_reason: &str) -> Result<Self> {
+        // `-w` ends the assertion if this process dies without dropping it.
+        let child = Command::new("caffeinate")
+            .args(["-i", "-w", &std::process::id().to_string()])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("failed to run caffeinate")?;
+        Ok(Self { child })
+    }
+
+    #[cfg(all(unix, not(any(target_os = "linux", target_os = "macos"))))]
+    pub fn acquire(_reason: &str) -> Result<Self> {
+        bail!("preventing sleep is not supported on this platform")
+    }
+
+    /// The execution state belongs to the calling thread, so a dedicated
+    /// thread holds it until the inhibitor is dropped.
+    #[cfg(windows)]
+    pub fn acquire(_reason: &str) -> Result<Self> {
+        use std::sync::mpsc;
+        use windows_sys::Win32::System::Power::{
+            SetThreadExecutionState, ES_CONTINUOUS, ES_SYSTEM_REQUIRED,
+        };
+
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let (ready_tx, ready_rx) = mpsc::channel::<bool>();
+        std::thread::Builder::new()
+            .name("sleep-inhibitor".to_string())
+            .spawn(move || {
+                let previous =
+                    unsafe { SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED) };
+                let _ = ready_tx.send(previous != 0);
+                // Returns once the sender is dropped.
+                let _ = release_rx.recv();
+                unsafe { SetThreadExecutionState(ES_CONTINUOUS) };
+            })
+            .context("failed to start sleep inhibitor thread")?;
+        if !ready_rx.recv().unwrap_or(false) {
+            bail!("SetThreadExecutionState failed");
+        }
+        Ok(Self {
+            _release: release_tx,
+        })
+    }
+}
+
+#[cfg(unix)]
+impl Drop for SleepInhibitor {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Suspend or shut down the machine. `QueueEmptyAction::None` does nothing.
+pub fn run_queue_empty_action(action: QueueEmptyAction) -> Result<()> {
+    let (program, args): (&str, &[&str]) = match action {
+        QueueEmptyAction::None => return Ok(()),
+        #[cfg(target_os = "linux")]
+        QueueEmptyAction::Suspend => ("systemctl", &["suspend"]),
+        #[cfg(target_os = "linux")]
+        QueueEmptyAction::Shutdown => ("systemctl", &["poweroff"]),
+        #[cfg(target_os = "macos")]
+        QueueEmptyAction::Suspend => ("pmset", &["sleepnow"]),
+        #[cfg(target_os = "macos")]
+        QueueEmptyAction::Shutdown => (
+            "osascript",
+            &["-e", "tell application \"System Events\" to shut down"],
+        ),
+        #[cfg(windows)]
+        QueueEmptyAction::Suspend => ("rundll32.exe", &["powrprof.dll,SetSuspendState", "0,1,0"]),
+        #[cfg(windows)]
+        QueueEmptyAction::Shutdown => ("shutdown", &["/s", "/t", "0"]),
+        #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+        QueueEmptyAction::Suspend | QueueEmptyAction::Shutdown => {
+            bail!("power actions are not supported on this platform")
+        }
+    };
+
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .with_context(|| format!("failed to run {program}"))?;
+    if !output.status.success() {
+        bail!(
+            "{program} exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Notices when a queue that had work has stayed empty for a grace period,
+/// so a job submitted right after the last one finishes cancels the action.
+#[derive(Debug)]
+pub struct QueueWatch {
+    grace: Duration,
+    had_work: bool,
+    empty_since: Option<Instant>,
+}
+
+impl QueueWatch {
+    pub fn new(grace: Duration) -> Self {
+        Self {
+            grace,
+            had_work: false,
+            empty_since: None,
+        }
+    }
+
+    /// Record whether jobs are queued or running at `now`. Returns `true`
+    /// once per busy period, when the queue has been empty for the grace
+    /// period after it.
+    pub fn observe(&mut self, busy: bool, now: Instant) -> bool {
+        if busy {
+            self.had_work = true;
+            self.empty_since = None;
+            return false;
+        }
+        if !self.had_work {
+            return false;
+        }
+        let empty_since = *self.empty_since.get_or_insert(now);
+        if now.duration_since(empty_since) < self.grace {
+            return false;
+        }
+        self.had_work = false;
+        self.empty_since = None;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_watch_fires_once_after_grace() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut watch = QueueWatch::new(Duration::from_secs(60));
+
+        assert!(!watch.observe(false, at(0)), "never busy");
+        assert!(!watch.observe(true, at(5)));
+        assert!(!watch.observe(false, at(10)));
+        assert!(!watch.observe(false, at(69)));
+        assert!(watch.observe(false, at(70)));
+        assert!(!watch.observe(false, at(200)), "fires once per busy period");
+
+        assert!(!watch.observe(true, at(300)));
+        assert!(!watch.observe(false, at(310)));
+        assert!(!watch.observe(true, at(350)), "new job within the grace");
+        assert!(!watch.observe(false, at(360)));
+        assert!(!watch.observe(false, at(400)));
+        assert!(watch.observe(false, at(420)));
+    }
+}
@@ -0,0 +1,584 @@
+//! Deep media probe for `POST /api/probe`.
+//!
+//! Runs ffprobe on a file and turns its JSON into typed streams, bitrates,
+//! HDR metadata and chapters, so the editor can show what a source holds
+//! before a workflow is built around it. HDR mastering display and content
+//! light level data live on frames, so the first video frame is read too.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MediaProbe {
+    pub path: PathBuf,
+    pub format: ProbeFormat,
+    pub streams: Vec<ProbeStream>,
+    pub chapters: Vec<ProbeChapter>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProbeFormat {
+    pub format_name: Option<String>,
+    pub format_long_name: Option<String>,
+    pub duration_secs: Option<f64>,
+    pub size_bytes: Option<u64>,
+    pub bit_rate: Option<u64>,
+    pub tags: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProbeStream {
+    pub index: usize,
+    /// `video`, `audio`, `subtitle`, `attachment` or `data`.
+    pub codec_type: String,
+    pub codec_name: Option<String>,
+    pub profile: Option<String>,
+    pub bit_rate: Option<u64>,
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub default: bool,
+    pub forced: bool,
+    /// Set for video streams.
+    pub video: Option<VideoDetails>,
+    /// Set for audio streams.
+    pub audio: Option<AudioDetails>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct VideoDetails {
+    pub width: u32,
+    pub height: u32,
+    pub pix_fmt: Option<String>,
+    pub bit_depth: Option<u8>,
+    pub frame_rate: Option<f64>,
+    pub frame_count: Option<u64>,
+    pub interlaced: bool,
+    pub attached_pic: bool,
+    pub color_range: Option<String>,
+    pub color_space: Option<String>,
+    pub color_primaries: Option<String>,
+    pub color_transfer: Option<String>,
+    /// Only set for HDR sources.
+    pub hdr: Option<HdrInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HdrInfo {
+    /// `hdr10`, `hlg` or `dolby_vision`.
+    pub format: String,
+    pub mastering_display: Option<MasteringDisplay>,
+    pub max_cll: Option<u32>,
+    pub max_fall: Option<u32>,
+    pub dolby_vision_profile: Option<u32>,
+}
+
+/// SMPTE ST 2086 mastering display, in cd/m².
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MasteringDisplay {
+    pub min_luminance: Option<f64>,
+    pub max_luminance: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AudioDetails {
+    pub channels: Option<u32>,
+    pub channel_layout: Option<String>,
+    pub sample_rate: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProbeChapter {
+    pub start_secs: Option<f64>,
+    pub end_secs: Option<f64>,
+    pub title: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// ffprobe JSON model
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+struct RawProbe {
+    #[serde(default)]
+    streams: Vec<RawStream>,
+    #[serde(default)]
+    chapters: Vec<RawChapter>,
+    format: Option<RawFormat>,
+    #[serde(default)]
+    frames: Vec<RawFrame>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawStream {
+    index: usize,
+    codec_type: Option<String>,
+    codec_name: Option<String>,
+    profile: Option<String>,
+    bit_rate: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    pix_fmt: Option<String>,
+    bits_per_raw_sample: Option<String>,
+    avg_frame_rate: Option<String>,
+    r_frame_rate: Option<String>,
+    nb_frames: Option<String>,
+    field_order: Option<String>,
+    color_range: Option<String>,
+    color_space: Option<String>,
+    color_primaries: Option<String>,
+    color_transfer: Option<String>,
+    channels: Option<u32>,
+    channel_layout: Option<String>,
+    sample_rate: Option<String>,
+    #[serde(default)]
+    disposition: HashMap<String, i64>,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+    #[serde(default)]
+    side_data_list: Vec<RawSideData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawFormat {
+    format_name: Option<String>,
+    format_long_name: Option<String>,
+    duration: Option<String>,
+    size: Option<String>,
+    bit_rate: Option<String>,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawChapter {
+    start_time: Option<String>,
+    end_time: Option<String>,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawFrame {
+    #[serde(default)]
+    side_data_list: Vec<RawSideData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawSideData {
+    side_data_type: Option<String>,
+    min_luminance: Option<String>,
+    max_luminance: Option<String>,
+    max_content: Option<u32>,
+    max_average: Option<u32>,
+    dv_profile: Option<u32>,
+}
+
+/// Probes `path` with ffprobe.
+pub fn probe_media(path: &Path) -> Result<MediaProbe> {
+    let streams = run_ffprobe_json(path, &["-show_format", "-show_streams", "-show_chapters"])?;
+    // Frame side data is best effort: a file without video has none.
+    let frames = run_ffprobe_json(
+        path,
+        &[
+            "-select_streams",
+            "v:0",
+            "-read_intervals",
+            "%+#1",
+            "-show_frames",
+            "-show_entries",
+            "frame=side_data_list",
+        ],
+    )
+    .ok();
+    parse_probe(path, &streams, frames.as_deref())
+}
+
+fn run_ffprobe_json(path: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let output = crate::runtime::command_for("ffprobe")
+        .args(["-v", "error", "-print_format", "json"])
+        .args(args)
+        .arg(path)
+        .output()
+        .context("failed to execute ffprobe — is FFmpeg installed?")?;
+    if !output.status.success() {
+        bail!(
+            "ffprobe could not read {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output.stdout)
+}
+
+/// Builds the probe from ffprobe's stream JSON and, optionally, the JSON of
+/// the first video frame.
+pub fn parse_probe(
+    path: &Path,
+    streams_json: &[u8],
+    frames_json: Option<&[u8]>,
+) -> Result<MediaProbe> {
+    let raw: RawProbe =
+        serde_json::from_slice(streams_json).context("failed to parse ffprobe JSON")?;
+    let frame_side_data: Vec<RawSideData> = frames_json
+        .and_then(|json| serde_json::from_slice::<RawProbe>(json).ok())
+        .and_then(|probe| probe.frames.into_iter().next())
+        .map(|frame| frame.side_data_list)
+        .unwrap_or_default();
+
+    let first_video = raw
+        .streams
+        .iter()
+        .find(|stream| stream.codec_type.as_deref() == Some("video"))
+        .map(|stream| stream.index);
+    let streams = raw
+        .streams
+        .into_iter()
+        .map(|stream| {
+            // `-select_streams v:0` frame data belongs to the first video stream.
+            let frame_data = if Some(stream.index) == first_video {
+                frame_side_data.as_slice()
+            } else {
+                &[]
+            };
+            convert_stream(stream, frame_data)
+        })
+        .collect();
+
+    let format = raw.format.map_or(
+        ProbeFormat {
+            format_name: None,
+            format_long_name: None,
+            duration_secs: None,
+            size_bytes: None,
+            bit_rate: None,
+            tags: HashMap::new(),
+        },
+        |format| ProbeFormat {
+            format_name: format.format_name,
+            format_long_name: format.format_long_name,
+            duration_secs: parse_num(format.duration.as_deref()),
+            size_bytes: parse_num(format.size.as_deref()),
+            bit_rate: parse_num(format.bit_rate.as_deref()),
+            tags: format.tags,
+        },
+    );
+
+    let chapters = raw
+        .chapters
+        .into_iter()
+        .map(|chapter| ProbeChapter {
+            start_secs: parse_num(chapter.start_time.as_deref()),
+            end_secs: parse_num(chapter.end_time.as_deref()),
+            title: chapter.tags.get("title").cloned(),
+        })
+        .collect();
+
+    Ok(MediaProbe {
+        path: path.to_path_buf(),
+        format,
+        streams,
+        chapters,
+    })
+}
+
+fn convert_stream(stream: RawStream, frame_side_data: &[RawSideData]) -> ProbeStream {
+    let codec_type = stream
+        .codec_type
+        .clone()
+        .unwrap_or_else(|| "unknown".to_string());
+    let flag = |key: &str| stream.disposition.get(key).is_some_and(|v| *v != 0);
+
+    let video = (codec_type == "video").then(|| {
+        let side_data = stream.side_data_list.iter().chain(frame_side_data);
+        VideoDetails {
+            width: stream.width.unwrap_or(0),
+            height: stream.height.unwrap_or(0),
+            bit_depth: parse_num(stream.bits_per_raw_sample.as_deref())
+                .or_else(|| stream.pix_fmt.as_deref().map(bit_depth_from_pix_fmt)),
+            pix_fmt: stream.pix_fmt.clone(),
+            frame_rate: stream
+                .avg_frame_rate
+                .as_deref()
+                .and_then(parse_rate)
+                .or_else(|| stream.r_frame_rate.as_deref().and_then(parse_rate)),
+            frame_count: parse_num(stream.nb_frames.as_deref()),
+            interlaced: matches!(
+                stream.field_order.as_deref(),
+                Some("tt" | "bb" | "tb" | "bt")
+            ),
+            attached_pic: flag("attached_pic"),
+            color_range: stream.color_range.clone(),
+            color_space: stream.color_space.clone(),
+            color_primaries: stream.color_primaries.clone(),
+            color_transfer: stream.color_transfer.clone(),
+            hdr: hdr_info(stream.color_transfer.as_deref(), side_data),
+        }
+    });
+    let audio = (codec_type == "audio").then(|| AudioDetails {
+        channels: stream.channels,
+        channel_layout: stream.channel_layout.clone(),
+        sample_rate: parse_num(stream.sample_rate.as_deref()),
+    });
+
+    ProbeStream {
+        index: stream.index,
+        codec_name: stream.codec_name,
+        profile: stream.profile,
+        bit_rate: parse_num(stream.bit_rate.as_deref()),
+        language: stream.tags.get("language").cloned(),
+        title: stream.tags.get("title").cloned(),
+        default: flag("default"),
+        forced: flag("forced"),
+        codec_type,
+        video,
+        audio,
+    }
+}
+
+fn hdr_info<'a>(
+    color_transfer: Option<&str>,
+    side_data: impl Iterator<Item = &'a RawSideData>,
+) -> Option<HdrInfo> {
+    let mut info = HdrInfo {
+        format: match color_transfer {
+            Some("smpte2084") => "hdr10",
+            Some("arib-std-b67") => "hlg",
+            _ => "",
+        }
+        .to_string(),
+        mastering_display: None,
+        max_cll: None,
+        max_fall: None,
+        dolby_vision_profile: None,
+    };
+    for data in side_data {
+        match data.side_data_type.as_deref() {
+            Some("Mastering display metadata") => {
+                info.mastering_display = Some(MasteringDisplay {
+                    min_luminance: data.min_luminance.as_deref().and_then(parse_rate),
+                    max_luminance: data.max_luminance.as_deref().and_then(parse_rate),
+                });
+            }
+            Some("Content light level metadata") => {
+                info.max_cll = data.max_content;
+                info.max_fall = data.max_average;
+            }
+            Some("DOVI configuration record") => {
+                info.dolby_vision_profile = data.dv_profile;
+                info.format = "dolby_vision".to_string();
+            }
+            _ => {}
+        }
+    }
+    (!info.format.is_empty()).then_some(info)
+}
+
+fn parse_num<T: std::str::FromStr>(value: Option<&str>) -> Option<T> {
+    value?.trim().parse().ok()
+}
+
+/// Parses `24000/1001`-style rationals and plain numbers.
+fn parse_rate(value: &str) -> Option<f64> {
+    match value.split_once('/') {
+        Some((num, den)) => {
+            let num: f64 = num.trim().parse().ok()?;
+            let den: f64 = den.trim().parse().ok()?;
+            (den != 0.0).then(|| num / den)
+        }
+        None => value.trim().parse().ok(),
+    }
+}
+
+fn bit_depth_from_pix_fmt(pix_fmt: &str) -> u8 {
+    ["16", "14", "12", "10"]
+        .iter()
+        .find(|depth| {
+            pix_fmt.contains(&format!("{depth}le"))
+                || pix_fmt.contains(&format!("{depth}be"))
+                || pix_fmt.ends_with(&format!("p{depth}"))
+        })
+        .and_then(|depth| depth.parse().ok())
+        .unwrap_or(8)
+}
+
+/// Canonical form of `path` when it lies inside one of `roots`. Symlinks are
+/// resolved first, so a link cannot lead out of a root.
+pub fn resolve_in_roots(path: &Path, roots: &[PathBuf]) -> Result<PathBuf> {
+    if roots.is_empty() {
+        bail!("no media roots are configured; set paths.media_roots");
+    }
+    let in_roots = |canonical: &Path| {
+        roots
+            .iter()
+            .filter_map(|root| root.canonicalize().ok())
+            .any(|root| canonical.starts_with(root))
+    };
+    let canonical = match path.canonicalize() {
+        Ok(canonical) => canonical,
+        // A missing path is reported as missing only inside the roots, so
+        // the error does not tell whether files exist outside them.
+        Err(err) => {
+            if !canonical_parent(path).is_some_and(|parent| in_roots(&parent)) {
+                bail!("{} is outside the configured media roots", path.display());
+            }
+            return Err(err).with_context(|| format!("{} does not exist", path.display()));
+        }
+    };
+    if !in_roots(&canonical) {
+        bail!("{} is outside the configured media roots", path.display());
+    }
+    Ok(canonical)
+}
+
+/// Canonical form of the nearest existing ancestor of `path`, joined with
+/// the rest of it. `None` when the rest steps up with `..`.
+fn canonical_parent(path: &Path) -> Option<PathBuf> {
+    let mut rest = Vec::new();
+    let mut ancestor = path;
+    let canonical = loop {
+        if let Ok(canonical) = ancestor.canonicalize() {
+            break canonical;
+        }
+        match ancestor.components().next_back()? {
+            std::path::Component::Normal(name) => rest.push(name),
+            _ => return None,
+        }
+        ancestor = ancestor.parent()?;
+    };
+    Some(
+        rest.into_iter()
+            .rev()
+            .fold(canonical, |path, name| path.join(name)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STREAMS_JSON: &str = r#"{
+        "streams": [
+            {"index": 0, "codec_type": "video", "codec_name": "hevc", "profile": "Main 10",
+             "width": 3840, "height": 2160, "pix_fmt": "yuv420p10le",
+             "avg_frame_rate": "24000/1001", "r_frame_rate": "24000/1001",
+             "color_range": "tv", "color_space": "bt2020nc", "color_primaries": "bt2020",
+             "color_transfer": "smpte2084", "field_order": "progressive",
+             "disposition": {"default": 1, "forced": 0}, "tags": {"BPS": "40000000"}},
+            {"index": 1, "codec_type": "audio", "codec_name": "eac3", "bit_rate": "640000",
+             "channels": 6, "channel_layout": "5.1(side)", "sample_rate": "48000",
+             "disposition": {"default": 1}, "tags": {"language": "jpn", "title": "Main"}},
+            {"index": 2, "codec_type": "subtitle", "codec_name": "ass",
+             "disposition": {"default": 0, "forced": 1}, "tags": {"language": "eng"}}
+        ],
+        "chapters": [
+            {"start_time": "0.000000", "end_time": "90.000000", "tags": {"title": "Opening"}}
+        ],
+        "format": {"format_name": "matroska,webm", "duration": "1420.5",
+                   "size": "7100000000", "bit_rate": "39985000", "tags": {"title": "Ep 1"}}
+    }"#;
+
+    const FRAMES_JSON: &str = r#"{
+        "frames": [{"side_data_list": [
+            {"side_data_type": "Mastering display metadata",
+             "min_luminance": "50/10000", "max_luminance": "10000000/10000"},
+            {"side_data_type": "Content light level metadata",
+             "max_content": 1000, "max_average": 400}
+        ]}]
+    }"#;
+
+    #[test]
+    fn test_parse_probe_streams_hdr_and_chapters() {
+        let probe = parse_probe(
+            Path::new("/media/ep01.mkv"),
+            STREAMS_JSON.as_bytes(),
+            Some(FRAMES_JSON.as_bytes()),
+        )
+        .unwrap();
+
+        assert_eq!(probe.format.duration_secs, Some(1420.5));
+        assert_eq!(probe.format.bit_rate, Some(39_985_000));
+        assert_eq!(probe.streams.len(), 3);
+
+        let video = probe.streams[0].video.as_ref().unwrap();
+        assert_eq!((video.width, video.height), (3840, 2160));
+        assert_eq!(video.bit_depth, Some(10));
+        assert!((video.frame_rate.unwrap() - 23.976).abs() < 0.001);
+        assert!(!video.interlaced);
+        let hdr = video.hdr.as_ref().unwrap();
+        assert_eq!(hdr.format, "hdr10");
+        assert_eq!(hdr.max_cll, Some(1000));
+        assert_eq!(hdr.max_fall, Some(400));
+        assert_eq!(
+            hdr.mastering_display,
+            Some(MasteringDisplay {
+                min_luminance: Some(0.005),
+                max_luminance: Some(1000.0),
+            })
+        );
+
+        let audio = &probe.streams[1];
+        assert_eq!(audio.bit_rate, Some(640_000));
+        assert_eq!(audio.language.as_deref(), Some("jpn"));
+        assert_eq!(audio.audio.as_ref().unwrap().sample_rate, Some(48000));
+        assert!(probe.streams[2].forced && !probe.streams[2].default);
+
+        assert_eq!(probe.chapters[0].title.as_deref(), Some("Opening"));
+        assert_eq!(probe.chapters[0].end_secs, Some(90.0));
+    }
+
+    #[test]
+    fn test_parse_probe_sdr_has_no_hdr_info() {
+        let json = r#"{"streams": [{"index": 0, "codec_type": "video", "width": 1920,
+            "height": 1080, "pix_fmt": "yuv420p", "color_transfer": "bt709"}],
+            "format": {}}"#;
+        let probe = parse_probe(Path::new("a.mkv"), json.as_bytes(), None).unwrap();
+        let video = probe.streams[0].video.as_ref().unwrap();
+        assert_eq!(video.bit_depth, Some(8));
+        assert!(video.hdr.is_none());
+    }
+
+    #[test]
+    fn test_resolve_in_roots() {
+        let root = tempfile::tempdir().unwrap();
+        let other = tempfile::tempdir().unwrap();
+        let inside = root.path().join("ep01.mkv");
+        let outside = other.path().join("secret.mkv");
+        std::fs::write(&inside, b"").unwrap();
+        std::fs::write(&outside, b"").unwrap();
+        let roots = vec![root.path().to_path_buf()];
+
+        assert_eq!(
+            resolve_in_roots(&inside, &roots).unwrap(),
+            inside.canonicalize().unwrap()
+        );
+        assert!(resolve_in_roots(&outside, &roots).is_err());
+        let escape = root
+            .path()
+            .join("..")
+            .join(other.path().file_name().unwrap());
+        assert!(resolve_in_roots(&escape.join("secret.mkv"), &roots).is_err());
+        assert!(resolve_in_roots(&inside, &[]).is_err());
+
+        let is_missing = |err: anyhow::Error| {
+            err.chain().any(|cause| {
+                cause
+                    .downcast_ref::<std::io::Error>()
+                    .is_some_and(|err| err.kind() == std::io::ErrorKind::NotFound)
+            })
+        };
+        let missing_inside = root.path().join("season1").join("ep02.mkv");
+        assert!(is_missing(
+            resolve_in_roots(&missing_inside, &roots).unwrap_err()
+        ));
+        let missing_outside = other.path().join("missing.mkv");
+        assert!(!is_missing(
+            resolve_in_roots(&missing_outside, &roots).unwrap_err()
+        ));
+        assert!(!is_missing(
+            resolve_in_roots(&escape.join("missing.mkv"), &roots).unwrap_err()
+        ));
+    }
+}
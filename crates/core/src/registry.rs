@@ -1,9 +1,12 @@
 use std::collections::HashMap;
 
+use std::path::Path;
+
 use anyhow::{anyhow, Result};
 
 use crate::node::Node;
 use crate::nodes::rescale::RescaleNode;
+use crate::plugin::PluginInfo;
 
 type NodeFactory =
     dyn Fn(HashMap<String, serde_json::Value>) -> Result<Box<dyn Node>> + Send + Sync;
@@ -45,6 +48,12 @@ impl NodeRegistry {
         node_types.sort_unstable();
         node_types
     }
+
+    /// Register the nodes of every plugin library found in `plugins_dir`.
+    /// See [`crate::plugin`] for the plugin ABI.
+    pub fn load_plugins(&mut self, plugins_dir: &Path) -> Vec<PluginInfo> {
+        crate::plugin::load_plugins(self, plugins_dir)
+    }
 }
 
 impl Default for NodeRegistry {
@@ -62,22 +71,43 @@ pub fn register_rescale_node(registry: &mut NodeRegistry) {
 /// The keys match the frontend `NodeTypeName` values so that workflow JSON
 /// round-trips cleanly between UI and backend.
 pub fn register_all_nodes(registry: &mut NodeRegistry) {
+    use crate::nodes::analyze_source::AnalyzeSourceNode;
     use crate::nodes::color_space::ColorSpaceNode;
+    use crate::nodes::concat::{ConcatNode, CONCAT_NODE_TYPE};
     use crate::nodes::constant::ConstantNode;
+    use crate::nodes::date_time::{DateTimeNode, DATE_TIME_NODE_TYPE};
     use crate::nodes::downloader::DownloaderNode;
+    use crate::nodes::expression::{ExpressionNode, EXPRESSION_NODE_TYPE};
+    use crate::nodes::external_command::ExternalCommandNode;
+    use crate::nodes::ffmpeg_filter::{FfmpegFilterNode, FFMPEG_FILTER_NODE_TYPE};
     use crate::nodes::frame_interpolation::FrameInterpolationNode;
+    use crate::nodes::grain_synthesis::{GrainSynthesisNode, GRAIN_SYNTHESIS_NODE_TYPE};
+    use crate::nodes::host_info::{HostInfoNode, HOST_INFO_NODE_TYPE};
     use crate::nodes::http_request::HttpRequestNode;
     use crate::nodes::jellyfin_video::JellyfinVideoNode;
+    use crate::nodes::json_extract::{JsonExtractNode, JSON_EXTRACT_NODE_TYPE};
+    use crate::nodes::model_inference::{ModelInferenceNode, MODEL_INFERENCE_NODE_TYPE};
     use crate::nodes::path_divider::PathDividerNode;
     use crate::nodes::path_joiner::PathJoinerNode;
+    use crate::nodes::preview_tap::{PreviewTapNode, PREVIEW_TAP_NODE_TYPE};
     use crate::nodes::print::PrintNode;
+    use crate::nodes::python_node::{PythonNode, PYTHON_NODE_TYPE};
+    use crate::nodes::region_enhance::{RegionEnhanceNode, REGION_ENHANCE_NODE_TYPE};
+    use crate::nodes::remote_storage::{
+        RemoteFetchNode, RemoteUploadNode, REMOTE_FETCH_NODE_TYPE, REMOTE_UPLOAD_NODE_TYPE,
+    };
     use crate::nodes::resize::ResizeNode;
     use crate::nodes::scene_detect::SceneDetectNode;
+    use crate::nodes::script::ScriptNode;
+    use crate::nodes::stream_input::StreamInputNode;
     use crate::nodes::stream_output::StreamOutputNode;
     use crate::nodes::string_replace::StringReplaceNode;
     use crate::nodes::string_template::StringTemplateNode;
     use crate::nodes::super_res::SuperResNode;
+    use crate::nodes::torrent_input::{TorrentInputNode, TORRENT_INPUT_NODE_TYPE};
     use crate::nodes::type_conversion::TypeConversionNode;
+    use crate::nodes::uuid_generator::{UuidNode, UUID_NODE_TYPE};
+    use crate::nodes::vapoursynth_input::{VapourSynthInputNode, VAPOURSYNTH_INPUT_NODE_TYPE};
     use crate::nodes::video_input::VideoInputNode;
     use crate::nodes::video_output::VideoOutputNode;
     use crate::nodes::workflow_io::{WorkflowInputNode, WorkflowNode, WorkflowOutputNode};
@@ -94,15 +124,43 @@ pub fn register_all_nodes(registry: &mut NodeRegistry) {
     registry.register("FrameInterpolation", |_params| {
         Ok(Box::new(FrameInterpolationNode::new()))
     });
+    registry.register(PYTHON_NODE_TYPE, |_params| Ok(Box::new(PythonNode::new())));
+    registry.register(FFMPEG_FILTER_NODE_TYPE, |_params| {
+        Ok(Box::new(FfmpegFilterNode::new()))
+    });
+    registry.register(GRAIN_SYNTHESIS_NODE_TYPE, |_params| {
+        Ok(Box::new(GrainSynthesisNode::new()))
+    });
+    registry.register(PREVIEW_TAP_NODE_TYPE, |_params| {
+        Ok(Box::new(PreviewTapNode::new()))
+    });
+    registry.register(REGION_ENHANCE_NODE_TYPE, |_params| {
+        Ok(Box::new(RegionEnhanceNode::new()))
+    });
+    registry.register(MODEL_INFERENCE_NODE_TYPE, |params| {
+        Ok(Box::new(ModelInferenceNode::from_params(&params)))
+    });
     registry.register("VideoOutput", |_params| {
         Ok(Box::new(VideoOutputNode::new()))
     });
     registry.register("Downloader", |_params| Ok(Box::new(DownloaderNode::new())));
+    registry.register(TORRENT_INPUT_NODE_TYPE, |_params| {
+        Ok(Box::new(TorrentInputNode::new()))
+    });
+    registry.register(REMOTE_FETCH_NODE_TYPE, |_params| {
+        Ok(Box::new(RemoteFetchNode::new()))
+    });
+    registry.register(REMOTE_UPLOAD_NODE_TYPE, |_params| {
+        Ok(Box::new(RemoteUploadNode::new()))
+    });
     registry.register("PathDivider", |_params| {
         Ok(Box::new(PathDividerNode::new()))
     });
     registry.register("PathJoiner", |_params| Ok(Box::new(PathJoinerNode::new())));
     registry.register("Print", |_params| Ok(Box::new(PrintNode::new())));
+    registry.register("AnalyzeSource", |_params| {
+        Ok(Box::new(AnalyzeSourceNode::new()))
+    });
     registry.register("StringTemplate", |params| {
         Ok(Box::new(StringTemplateNode::from_params(&params)))
     });
@@ -112,21 +170,47 @@ pub fn register_all_nodes(registry: &mut NodeRegistry) {
     registry.register("TypeConversion", |params| {
         Ok(Box::new(TypeConversionNode::from_params(&params)?))
     });
+    registry.register(JSON_EXTRACT_NODE_TYPE, |params| {
+        Ok(Box::new(JsonExtractNode::from_params(&params)?))
+    });
+    registry.register("ExternalCommand", |_params| {
+        Ok(Box::new(ExternalCommandNode::new()))
+    });
     registry.register("HttpRequest", |_params| {
         Ok(Box::new(HttpRequestNode::new()))
     });
+    registry.register("StreamInput", |_params| {
+        Ok(Box::new(StreamInputNode::new()))
+    });
+    registry.register(VAPOURSYNTH_INPUT_NODE_TYPE, |_params| {
+        Ok(Box::new(VapourSynthInputNode::new()))
+    });
     registry.register("StreamOutput", |_params| {
         Ok(Box::new(StreamOutputNode::new()))
     });
     registry.register("Resize", |_params| Ok(Box::new(ResizeNode::new())));
     register_rescale_node(registry);
     registry.register("ColorSpace", |_params| Ok(Box::new(ColorSpaceNode::new())));
+    registry.register(CONCAT_NODE_TYPE, |_params| Ok(Box::new(ConcatNode::new())));
     registry.register("SceneDetect", |_params| {
         Ok(Box::new(SceneDetectNode::new()))
     });
+    registry.register("Script", |params| {
+        Ok(Box::new(ScriptNode::from_params(&params)?))
+    });
+    registry.register(EXPRESSION_NODE_TYPE, |params| {
+        Ok(Box::new(ExpressionNode::from_params(&params)?))
+    });
     registry.register("Constant", |params| {
         Ok(Box::new(ConstantNode::from_params(&params)?))
     });
+    registry.register(DATE_TIME_NODE_TYPE, |_params| {
+        Ok(Box::new(DateTimeNode::new()))
+    });
+    registry.register(UUID_NODE_TYPE, |_params| Ok(Box::new(UuidNode::new())));
+    registry.register(HOST_INFO_NODE_TYPE, |_params| {
+        Ok(Box::new(HostInfoNode::new()))
+    });
     registry.register("WorkflowInput", |params| {
         Ok(Box::new(WorkflowInputNode::from_params(&params)))
     });
@@ -231,23 +315,43 @@ mod tests {
         register_all_nodes(&mut registry);
 
         let expected = vec![
+            "AnalyzeSource",
             "ColorSpace",
+            "Concat",
             "Constant",
+            "DateTime",
             "Downloader",
+            "Expression",
+            "ExternalCommand",
+            "FfmpegFilter",
             "FrameInterpolation",
+            "GrainSynthesis",
+            "HostInfo",
             "HttpRequest",
             "JellyfinVideo",
+            "JsonExtract",
+            "ModelInference",
             "PathDivider",
             "PathJoiner",
+            "PreviewTap",
             "Print",
+            "Python",
+            "RegionEnhance",
+            "RemoteFetch",
+            "RemoteUpload",
             "Rescale",
             "Resize",
             "SceneDetect",
+            "Script",
+            "StreamInput",
             "StreamOutput",
             "StringReplace",
             "StringTemplate",
             "SuperResolution",
+            "TorrentInput",
             "TypeConversion",
+            "Uuid",
+            "VapourSynthInput",
             "VideoInput",
             "VideoOutput",
             "Workflow",
@@ -259,17 +363,26 @@ mod tests {
     }
 
     #[test]
-    fn test_register_all_nodes_rejects_legacy_jellyfin_and_stream_input_types() {
+    fn test_register_all_nodes_rejects_legacy_jellyfin_input_type() {
         let mut registry = NodeRegistry::new();
         register_all_nodes(&mut registry);
 
-        for node_type in ["StreamInput", "JellyfinInput"] {
-            let err = match registry.create(node_type, HashMap::new()) {
-                Ok(_) => panic!("legacy aliases must stay rejected"),
-                Err(err) => err,
-            };
-            assert_eq!(err.to_string(), format!("unknown node type: {node_type}"));
-        }
+        let err = match registry.create("JellyfinInput", HashMap::new()) {
+            Ok(_) => panic!("legacy aliases must stay rejected"),
+            Err(err) => err,
+        };
+        assert_eq!(err.to_string(), "unknown node type: JellyfinInput");
+    }
+
+    #[test]
+    fn test_register_all_nodes_creates_stream_input() {
+        let mut registry = NodeRegistry::new();
+        register_all_nodes(&mut registry);
+
+        let node = registry
+            .create("StreamInput", HashMap::new())
+            .expect("stream input should be created");
+        assert_eq!(node.node_type(), "stream_input");
     }
 
     #[test]
@@ -0,0 +1,696 @@
+//! Static-region skipping for streaming processor stages.
+//!
+//! Anime often holds a background still while only a mouth or a hand moves.
+//! [`RoiProcessor`] wraps a processor stage, compares every frame with the
+//! input its cached output was produced from, and runs the processor only on
+//! the tiles that changed. The results are pasted into the cached output, so
+//! a mostly static frame costs a fraction of a full pass.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+use tracing::{info, warn};
+
+use crate::node::{ExecutionContext, FrameProcessor, Node, PortDefinition};
+use crate::types::{Frame, PortData};
+
+pub const DEFAULT_STATIC_THRESHOLD: i64 = 3;
+const TILE_SIZE: u32 = 32;
+/// Pixels of surrounding context handed to the processor around each changed
+/// region, so models see past the region's edge.
+const CONTEXT_PADDING: u32 = 16;
+/// Above this share of the frame, one full pass is cheaper than many crops.
+const MAX_CHANGED_SHARE: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoiConfig {
+    /// Largest per-sample difference, in 8-bit levels, a tile may have and
+    /// still count as unchanged. Absorbs compression noise.
+    pub threshold: u8,
+    pub tile_size: u32,
+    pub padding: u32,
+    pub max_changed_share: f64,
+}
+
+impl Default for RoiConfig {
+    fn default() -> Self {
+        Self {
+            threshold: DEFAULT_STATIC_THRESHOLD as u8,
+            tile_size: TILE_SIZE,
+            padding: CONTEXT_PADDING,
+            max_changed_share: MAX_CHANGED_SHARE,
+        }
+    }
+}
+
+impl RoiConfig {
+    /// Reads `skip_static_regions` and `static_threshold`. Returns `None` when
+    /// skipping is off.
+    pub fn from_inputs(inputs: &HashMap<String, PortData>) -> Result<Option<Self>> {
+        match inputs.get("skip_static_regions") {
+            None | Some(PortData::Bool(false)) => return Ok(None),
+            Some(PortData::Bool(true)) => {}
+            Some(_) => bail!("'skip_static_regions' must be Bool"),
+        }
+        let threshold = match inputs.get("static_threshold") {
+            None => DEFAULT_STATIC_THRESHOLD,
+            Some(PortData::Int(value)) => *value,
+            Some(_) => bail!("'static_threshold' must be Int"),
+        };
+        let threshold = u8::try_from(threshold)
+            .map_err(|_| anyhow::anyhow!("'static_threshold' must be between 0 and 255"))?;
+        Ok(Some(Self {
+            threshold,
+            ..Self::default()
+        }))
+    }
+}
+
+/// Tile-aligned pixel rectangle, `x1`/`y1` exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rect {
+    x0: u32,
+    y0: u32,
+    x1: u32,
+    y1: u32,
+}
+
+impl Rect {
+    fn area(&self) -> u64 {
+        u64::from(self.x1 - self.x0) * u64::from(self.y1 - self.y0)
+    }
+
+    fn padded(&self, padding: u32, width: u32, height: u32) -> Self {
+        Self {
+            x0: self.x0.saturating_sub(padding),
+            y0: self.y0.saturating_sub(padding),
+            x1: (self.x1 + padding).min(width),
+            y1: (self.y1 + padding).min(height),
+        }
+    }
+}
+
+struct RoiCache {
+    width: u32,
+    height: u32,
+    bit_depth: u8,
+    /// Input each output pixel was last produced from.
+    reference: Vec<u8>,
+    output: Vec<u8>,
+    output_bit_depth: u8,
+    scale: u32,
+}
+
+#[derive(Default)]
+struct RoiStats {
+    frames: u64,
+    full: u64,
+    partial: u64,
+    reused: u64,
+    tiles_total: u64,
+    tiles_processed: u64,
+}
+
+/// Wraps a processor that maps an RGB frame to an RGB frame scaled by a
+/// whole factor (1 for filters, 2 or 4 for upscalers) and reprocesses only
+/// changed tiles.
+///
+/// Falls back to full passes for non-RGB frames and turns itself off when the
+/// processor's output is not a whole-factor RGB frame or it rejects a crop.
+pub struct RoiProcessor {
+    inner: Box<dyn FrameProcessor>,
+    config: RoiConfig,
+    cache: Option<RoiCache>,
+    disabled: bool,
+    stats: RoiStats,
+}
+
+impl RoiProcessor {
+    pub fn new(inner: Box<dyn FrameProcessor>, config: RoiConfig) -> Self {
+        Self {
+            inner,
+            config,
+            cache: None,
+            disabled: false,
+            stats: RoiStats::default(),
+        }
+    }
+
+    fn disable(&mut self, reason: &str) {
+        warn!(
+            stage = self.inner.node_type(),
+            reason, "static region skipping disabled"
+        );
+        self.disabled = true;
+        self.cache = None;
+    }
+
+    fn process_full(
+        &mut self,
+        data: Vec<u8>,
+        width: u32,
+        height: u32,
+        bit_depth: u8,
+        ctx: &ExecutionContext,
+    ) -> Result<Frame> {
+        self.stats.full += 1;
+        let reference = data.clone();
+        let output = self.inner.process_frame(
+            Frame::CpuRgb {
+                data,
+                width,
+                height,
+                bit_depth,
+            },
+            ctx,
+        )?;
+        let Frame::CpuRgb {
+            data: out,
+            width: out_width,
+            height: out_height,
+            bit_depth: out_depth,
+        } = output
+        else {
+            self.disable("processor output is not an RGB frame");
+            return Ok(output);
+        };
+        let scale = out_width / width;
+        if scale == 0 || out_width != width * scale || out_height != height * scale {
+            self.disable("processor does not scale by a whole factor");
+        } else {
+            self.cache = Some(RoiCache {
+                width,
+                height,
+                bit_depth,
+                reference,
+                output: out.clone(),
+                output_bit_depth: out_depth,
+                scale,
+            });
+        }
+        Ok(Frame::CpuRgb {
+            data: out,
+            width: out_width,
+            height: out_height,
+            bit_depth: out_depth,
+        })
+    }
+
+    /// Runs the processor on `regions` and pastes them into the cache.
+    /// Returns `false` when the processor fails on a crop or its output does
+    /// not fit.
+    fn process_regions(&mut self, data: &[u8], regions: &[Rect], ctx: &ExecutionContext) -> bool {
+        let Some(cache) = self.cache.as_mut() else {
+            return false;
+        };
+        let pixel = pixel_bytes(cache.bit_depth);
+        let out_pixel = pixel_bytes(cache.output_bit_depth);
+        let scale = cache.scale;
+        for region in regions {
+            let padded = region.padded(self.config.padding, cache.width, cache.height);
+            let crop = copy_rect(data, cache.width, pixel, padded);
+            let output = self.inner.process_frame(
+                Frame::CpuRgb {
+                    data: crop,
+                    width: padded.x1 - padded.x0,
+                    height: padded.y1 - padded.y0,
+                    bit_depth: cache.bit_depth,
+                },
+                ctx,
+            );
+            let Ok(Frame::CpuRgb {
+                data: out,
+                width: out_width,
+                height: out_height,
+                bit_depth: out_depth,
+            }) = output
+            else {
+                return false;
+            };
+            if out_width != (padded.x1 - padded.x0) * scale
+                || out_height != (padded.y1 - padded.y0) * scale
+                || out_depth != cache.output_bit_depth
+            {
+                return false;
+            }
+
+            let row_bytes = ((region.x1 - region.x0) * scale) as usize * out_pixel;
+            let out_stride = (cache.width * scale) as usize * out_pixel;
+            let crop_stride = out_width as usize * out_pixel;
+            let crop_x = ((region.x0 - padded.x0) * scale) as usize * out_pixel;
+            for row in 0..(region.y1 - region.y0) * scale {
+                let crop_y = (region.y0 - padded.y0) * scale + row;
+                let src = crop_y as usize * crop_stride + crop_x;
+                let dst_y = region.y0 * scale + row;
+                let dst = dst_y as usize * out_stride + (region.x0 * scale) as usize * out_pixel;
+                cache.output[dst..dst + row_bytes].copy_from_slice(&out[src..src + row_bytes]);
+            }
+            paste_rect(&mut cache.reference, data, cache.width, pixel, *region);
+        }
+        true
+    }
+}
+
+impl Node for RoiProcessor {
+    fn node_type(&self) -> &str {
+        self.inner.node_type()
+    }
+
+    fn input_ports(&self) -> Vec<PortDefinition> {
+        self.inner.input_ports()
+    }
+
+    fn output_ports(&self) -> Vec<PortDefinition> {
+        self.inner.output_ports()
+    }
+
+    fn execute(
+        &mut self,
+        inputs: &HashMap<String, PortData>,
+        ctx: &ExecutionContext,
+    ) -> Result<HashMap<String, PortData>> {
+        self.inner.execute(inputs, ctx)
+    }
+}
+
+impl FrameProcessor for RoiProcessor {
+    fn process_frame(&mut self, frame: Frame, ctx: &ExecutionContext) -> Result<Frame> {
+        if self.disabled {
+            return self.inner.process_frame(frame, ctx);
+        }
+        let Frame::CpuRgb {
+            data,
+            width,
+            height,
+            bit_depth,
+        } = frame
+        else {
+            return self.inner.process_frame(frame, ctx);
+        };
+        self.stats.frames += 1;
+
+        let Some(cache) = self
+            .cache
+            .as_ref()
+            .filter(|c| c.width == width && c.height == height && c.bit_depth == bit_depth)
+        else {
+            return self.process_full(data, width, height, bit_depth, ctx);
+        };
+
+        let (cols, rows, changed) = changed_tiles(
+            &cache.reference,
+            &data,
+            width,
+            height,
+            bit_depth,
+            self.config.threshold,
+            self.config.tile_size,
+        );
+        self.stats.tiles_total += u64::from(cols * rows);
+        let changed_count = changed.iter().filter(|&&c| c).count() as u64;
+        let regions = changed_regions(&changed, cols, rows, self.config.tile_size, width, height);
+        let padded_area: u64 = regions
+            .iter()
+            .map(|r| r.padded(self.config.padding, width, height).area())
+            .sum();
+        if padded_area as f64 > self.config.max_changed_share * f64::from(width * height) {
+            self.stats.tiles_processed += u64::from(cols * rows);
+            return self.process_full(data, width, height, bit_depth, ctx);
+        }
+
+        if !regions.is_empty() {
+            if !self.process_regions(&data, &regions, ctx) {
+                self.disable("processor cannot process a cropped region");
+                return self.inner.process_frame(
+                    Frame::CpuRgb {
+                        data,
+                        width,
+                        height,
+                        bit_depth,
+                    },
+                    ctx,
+                );
+            }
+            self.stats.partial += 1;
+            self.stats.tiles_processed += changed_count;
+        } else {
+            self.stats.reused += 1;
+        }
+
+        let cache = self
+            .cache
+            .as_ref()
+            .expect("cache exists after a region pass");
+        Ok(Frame::CpuRgb {
+            data: cache.output.clone(),
+            width: width * cache.scale,
+            height: height * cache.scale,
+            bit_depth: cache.output_bit_depth,
+        })
+    }
+}
+
+impl Drop for RoiProcessor {
+    fn drop(&mut self) {
+        let stats = &self.stats;
+        if stats.frames == 0 {
+            return;
+        }
+        info!(
+            stage = self.inner.node_type(),
+            frames = stats.frames,
+            full = stats.full,
+            partial = stats.partial,
+            reused = stats.reused,
+            tiles_processed = stats.tiles_processed,
+            tiles_total = stats.tiles_total,
+            "Static region summary"
+        );
+    }
+}
+
+fn pixel_bytes(bit_depth: u8) -> usize {
+    if bit_depth > 8 {
+        6
+    } else {
+        3
+    }
+}
+
+/// Marks each tile whose samples differ from `reference` by more than
+/// `threshold` 8-bit levels. Returns the tile grid size and the row-major
+/// mask.
+fn changed_tiles(
+    reference: &[u8],
+    data: &[u8],
+    width: u32,
+    height: u32,
+    bit_depth: u8,
+    threshold: u8,
+    tile_size: u32,
+) -> (u32, u32, Vec<bool>) {
+    let cols = width.div_ceil(tile_size);
+    let rows = height.div_ceil(tile_size);
+    let pixel = pixel_bytes(bit_depth);
+    let stride = width as usize * pixel;
+    let mut changed = vec![false; (cols * rows) as usize];
+    for ty in 0..rows {
+        for tx in 0..cols {
+            let x0 = (tx * tile_size) as usize * pixel;
+            let x1 = ((tx + 1) * tile_size).min(width) as usize * pixel;
+            let y_end = ((ty + 1) * tile_size).min(height);
+            changed[(ty * cols + tx) as usize] = (ty * tile_size..y_end).any(|y| {
+                let start = y as usize * stride;
+                let a = &reference[start + x0..start + x1];
+                let b = &data[start + x0..start + x1];
+                if bit_depth > 8 {
+                    let limit = u16::from(threshold) * 257;
+                    a.chunks_exact(2).zip(b.chunks_exact(2)).any(|(a, b)| {
+                        let a = u16::from_le_bytes([a[0], a[1]]);
+                        let b = u16::from_le_bytes([b[0], b[1]]);
+                        a.abs_diff(b) > limit
+                    })
+                } else {
+                    a.iter().zip(b).any(|(a, b)| a.abs_diff(*b) > threshold)
+                }
+            });
+        }
+    }
+    (cols, rows, changed)
+}
+
+/// Bounding rectangles, in pixels, of the 4-connected groups of changed tiles.
+fn changed_regions(
+    changed: &[bool],
+    cols: u32,
+    rows: u32,
+    tile_size: u32,
+    width: u32,
+    height: u32,
+) -> Vec<Rect> {
+    let mut seen = vec![false; changed.len()];
+    let mut regions = Vec::new();
+    for start in 0..changed.len() {
+        if !changed[start] || seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        let mut stack = vec![start];
+        let (mut tx0, mut ty0, mut tx1, mut ty1) = (u32::MAX, u32::MAX, 0, 0);
+        while let Some(index) = stack.pop() {
+            let (tx, ty) = (index as u32 % cols, index as u32 / cols);
+            tx0 = tx0.min(tx);
+            ty0 = ty0.min(ty);
+            tx1 = tx1.max(tx);
+            ty1 = ty1.max(ty);
+            let neighbours = [
+                (tx > 0).then(|| index - 1),
+                (tx + 1 < cols).then(|| index + 1),
+                (ty > 0).then(|| index - cols as usize),
+                (ty + 1 < rows).then(|| index + cols as usize),
+            ];
+            for next in neighbours.into_iter().flatten() {
+                if changed[next] && !seen[next] {
+                    seen[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+        regions.push(Rect {
+            x0: tx0 * tile_size,
+            y0: ty0 * tile_size,
+            x1: ((tx1 + 1) * tile_size).min(width),
+            y1: ((ty1 + 1) * tile_size).min(height),
+        });
+    }
+    regions
+}
+
+fn copy_rect(data: &[u8], width: u32, pixel: usize, rect: Rect) -> Vec<u8> {
+    let stride = width as usize * pixel;
+    let row_bytes = (rect.x1 - rect.x0) as usize * pixel;
+    let mut out = Vec::with_capacity(row_bytes * (rect.y1 - rect.y0) as usize);
+    for y in rect.y0..rect.y1 {
+        let start = y as usize * stride + rect.x0 as usize * pixel;
+        out.extend_from_slice(&data[start..start + row_bytes]);
+    }
+    out
+}
+
+fn paste_rect(dst: &mut [u8], src: &[u8], width: u32, pixel: usize, rect: Rect) {
+    let stride = width as usize * pixel;
+    let row_bytes = (rect.x1 - rect.x0) as usize * pixel;
+    for y in rect.y0..rect.y1 {
+        let start = y as usize * stride + rect.x0 as usize * pixel;
+        dst[start..start + row_bytes].copy_from_slice(&src[start..start + row_bytes]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+
+    /// Nearest-neighbour 2x upscaler that brightens every sample by one
+    /// level and counts the pixels it processed.
+    struct Upscale2x {
+        pixels: Arc<AtomicU64>,
+    }
+
+    impl Node for Upscale2x {
+        fn node_type(&self) -> &str {
+            "Upscale2x"
+        }
+
+        fn input_ports(&self) -> Vec<PortDefinition> {
+            vec![]
+        }
+
+        fn output_ports(&self) -> Vec<PortDefinition> {
+            vec![]
+        }
+
+        fn execute(
+            &mut self,
+            _inputs: &HashMap<String, PortData>,
+            _ctx: &ExecutionContext,
+        ) -> Result<HashMap<String, PortData>> {
+            Ok(HashMap::new())
+        }
+    }
+
+    impl FrameProcessor for Upscale2x {
+        fn process_frame(&mut self, frame: Frame, _ctx: &ExecutionContext) -> Result<Frame> {
+            let Frame::CpuRgb {
+                data,
+                width,
+                height,
+                bit_depth,
+            } = frame
+            else {
+                bail!("expected RGB");
+            };
+            self.pixels
+                .fetch_add(u64::from(width * height), Ordering::SeqCst);
+            let (w, h) = (width as usize, height as usize);
+            let mut out = vec![0u8; w * h * 12];
+            for y in 0..h * 2 {
+                for x in 0..w * 2 {
+                    let src = ((y / 2) * w + x / 2) * 3;
+                    let dst = (y * w * 2 + x) * 3;
+                    for c in 0..3 {
+                        out[dst + c] = data[src + c].wrapping_add(1);
+                    }
+                }
+            }
+            Ok(Frame::CpuRgb {
+                data: out,
+                width: width * 2,
+                height: height * 2,
+                bit_depth,
+            })
+        }
+    }
+
+    fn rgb(width: u32, height: u32, data: Vec<u8>) -> Frame {
+        Frame::CpuRgb {
+            data,
+            width,
+            height,
+            bit_depth: 8,
+        }
+    }
+
+    fn frame_data(frame: Frame) -> Vec<u8> {
+        match frame {
+            Frame::CpuRgb { data, .. } => data,
+            _ => panic!("expected RGB"),
+        }
+    }
+
+    fn processor() -> (RoiProcessor, Arc<AtomicU64>) {
+        let pixels = Arc::new(AtomicU64::new(0));
+        let inner = Upscale2x {
+            pixels: Arc::clone(&pixels),
+        };
+        (
+            RoiProcessor::new(Box::new(inner), RoiConfig::default()),
+            pixels,
+        )
+    }
+
+    #[test]
+    fn test_static_frames_reuse_cached_output() {
+        let (mut roi, pixels) = processor();
+        let ctx = ExecutionContext::default();
+        let first = frame_data(
+            roi.process_frame(rgb(128, 128, vec![10; 128 * 128 * 3]), &ctx)
+                .unwrap(),
+        );
+        assert_eq!(pixels.load(Ordering::SeqCst), 128 * 128);
+
+        // Within the threshold: nothing is reprocessed.
+        let second = frame_data(
+            roi.process_frame(rgb(128, 128, vec![12; 128 * 128 * 3]), &ctx)
+                .unwrap(),
+        );
+        assert_eq!(pixels.load(Ordering::SeqCst), 128 * 128);
+        assert_eq!(first, second);
+        assert_eq!(roi.stats.reused, 1);
+    }
+
+    #[test]
+    fn test_changed_tile_is_reprocessed_and_composited() {
+        let (mut roi, pixels) = processor();
+        let ctx = ExecutionContext::default();
+        let (w, h) = (256u32, 256u32);
+        roi.process_frame(rgb(w, h, vec![10; (w * h * 3) as usize]), &ctx)
+            .unwrap();
+
+        let mut moved = vec![10u8; (w * h * 3) as usize];
+        for y in 100..110 {
+            for x in 40..50 {
+                let i = ((y * w + x) * 3) as usize;
+                moved[i..i + 3].copy_from_slice(&[200, 150, 100]);
+            }
+        }
+        let out = frame_data(roi.process_frame(rgb(w, h, moved.clone()), &ctx).unwrap());
+
+        let mut full = Upscale2x {
+            pixels: Arc::new(AtomicU64::new(0)),
+        };
+        let expected = frame_data(full.process_frame(rgb(w, h, moved), &ctx).unwrap());
+        assert_eq!(out, expected);
+        // One 32x32 tile plus 16 pixels of context on each side.
+        assert_eq!(pixels.load(Ordering::SeqCst), u64::from(w * h) + 64 * 64);
+        assert_eq!(roi.stats.partial, 1);
+    }
+
+    #[test]
+    fn test_large_change_and_new_size_run_full_pass() {
+        let (mut roi, pixels) = processor();
+        let ctx = ExecutionContext::default();
+        roi.process_frame(rgb(64, 64, vec![10; 64 * 64 * 3]), &ctx)
+            .unwrap();
+        roi.process_frame(rgb(64, 64, vec![90; 64 * 64 * 3]), &ctx)
+            .unwrap();
+        assert_eq!(pixels.load(Ordering::SeqCst), 2 * 64 * 64);
+
+        let out = roi
+            .process_frame(rgb(32, 32, vec![90; 32 * 32 * 3]), &ctx)
+            .unwrap();
+        assert!(matches!(
+            out,
+            Frame::CpuRgb {
+                width: 64,
+                height: 64,
+                ..
+            }
+        ));
+        assert_eq!(roi.stats.full, 3);
+    }
+
+    #[test]
+    fn test_changed_regions_groups_connected_tiles() {
+        #[rustfmt::skip]
+        let changed = [
+            true, true, false, false,
+            false, true, false, false,
+            false, false, false, true,
+        ];
+        let regions = changed_regions(&changed, 4, 3, 32, 120, 96);
+        assert_eq!(
+            regions,
+            vec![
+                Rect {
+                    x0: 0,
+                    y0: 0,
+                    x1: 64,
+                    y1: 64
+                },
+                Rect {
+                    x0: 96,
+                    y0: 64,
+                    x1: 120,
+                    y1: 96
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_roi_config_from_inputs() {
+        let mut inputs = HashMap::new();
+        assert_eq!(RoiConfig::from_inputs(&inputs).unwrap(), None);
+        inputs.insert("skip_static_regions".to_string(), PortData::Bool(true));
+        inputs.insert("static_threshold".to_string(), PortData::Int(6));
+        assert_eq!(
+            RoiConfig::from_inputs(&inputs).unwrap().unwrap().threshold,
+            6
+        );
+        inputs.insert("static_threshold".to_string(), PortData::Int(300));
+        assert!(RoiConfig::from_inputs(&inputs).is_err());
+    }
+}
@@ -0,0 +1,436 @@
+//! Process-wide memory governor.
+//!
+//! Frame buffers, tile batches and ONNX sessions take out a
+//! [`MemoryReservation`] for the host RAM or VRAM they hold; the reservation
+//! is returned when it is dropped. With the totals known, the decoder waits
+//! before reading more frames while host memory is over budget, and
+//! super-resolution shrinks its tiles to fit the VRAM budget, instead of the
+//! process growing until the OS kills it.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, LazyLock, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tracing::warn;
+
+use crate::config::MemoryConfig;
+
+/// Share of physical RAM used as the host budget when none is configured.
+const AUTO_HOST_BUDGET_SHARE: f64 = 0.8;
+/// Frames always admitted regardless of budget, so an interpolator holding
+/// one frame can still receive the next.
+const MIN_FRAMES_IN_FLIGHT: u64 = 2;
+/// Admit a frame anyway when nothing has been released for this long; the
+/// budget is then taken by memory the pipeline cannot free by draining.
+const STALL_TIMEOUT: Duration = Duration::from_secs(5);
+const WAIT_SLICE: Duration = Duration::from_millis(50);
+
+static GOVERNOR: LazyLock<MemoryGovernor> =
+    LazyLock::new(|| MemoryGovernor::new(MemoryBudget::default()));
+
+/// The governor shared by every pipeline in the process.
+pub fn memory_governor() -> &'static MemoryGovernor {
+    &GOVERNOR
+}
+
+/// Replace the process-wide budget, e.g. after the config changed.
+pub fn set_memory_budget(budget: MemoryBudget) {
+    GOVERNOR.set_budget(budget);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryKind {
+    Host,
+    Vram,
+}
+
+/// Limits in bytes; `None` means unlimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryBudget {
+    pub host_bytes: Option<u64>,
+    pub vram_bytes: Option<u64>,
+}
+
+impl Default for MemoryBudget {
+    fn default() -> Self {
+        Self::from_config(&MemoryConfig::default())
+    }
+}
+
+impl MemoryBudget {
+    pub fn from_config(config: &MemoryConfig) -> Self {
+        let mb = |value: u64| (value > 0).then(|| value.saturating_mul(1024 * 1024));
+        Self {
+            host_bytes: mb(config.host_budget_mb).or_else(|| {
+                total_host_memory().map(|total| (total as f64 * AUTO_HOST_BUDGET_SHARE) as u64)
+            }),
+            vram_bytes: mb(config.vram_budget_mb),
+        }
+    }
+
+    fn limit(&self, kind: MemoryKind) -> Option<u64> {
+        match kind {
+            MemoryKind::Host => self.host_bytes,
+            MemoryKind::Vram => self.vram_bytes,
+        }
+    }
+}
+
+/// Physical RAM from `/proc/meminfo`; `None` where that is unavailable.
+fn total_host_memory() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    parse_mem_total(&meminfo)
+}
+
+fn parse_mem_total(meminfo: &str) -> Option<u64> {
+    let line = meminfo.lines().find(|l| l.starts_with("MemTotal:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct MemoryUsage {
+    pub host_used_bytes: u64,
+    pub host_budget_bytes: Option<u64>,
+    pub vram_used_bytes: u64,
+    pub vram_budget_bytes: Option<u64>,
+    pub frames_in_flight: u64,
+}
+
+#[derive(Debug)]
+struct GovernorState {
+    budget: MemoryBudget,
+    host_used: u64,
+    vram_used: u64,
+    frames_in_flight: u64,
+    /// Bumped on every release, so waiters can tell a stall from progress.
+    releases: u64,
+}
+
+impl GovernorState {
+    fn used(&self, kind: MemoryKind) -> u64 {
+        match kind {
+            MemoryKind::Host => self.host_used,
+            MemoryKind::Vram => self.vram_used,
+        }
+    }
+
+    fn used_mut(&mut self, kind: MemoryKind) -> &mut u64 {
+        match kind {
+            MemoryKind::Host => &mut self.host_used,
+            MemoryKind::Vram => &mut self.vram_used,
+        }
+    }
+
+    fn fits(&self, kind: MemoryKind, bytes: u64) -> bool {
+        self.budget
+            .limit(kind)
+            .is_none_or(|limit| self.used(kind).saturating_add(bytes) <= limit)
+    }
+}
+
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<GovernorState>,
+    released: Condvar,
+}
+
+/// Tracks reserved host RAM and VRAM against a [`MemoryBudget`].
+#[derive(Debug, Clone)]
+pub struct MemoryGovernor {
+    shared: Arc<Shared>,
+}
+
+impl MemoryGovernor {
+    pub fn new(budget: MemoryBudget) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                state: Mutex::new(GovernorState {
+                    budget,
+                    host_used: 0,
+                    vram_used: 0,
+                    frames_in_flight: 0,
+                    releases: 0,
+                }),
+                released: Condvar::new(),
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, GovernorState> {
+        self.shared
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn set_budget(&self, budget: MemoryBudget) {
+        self.lock().budget = budget;
+        self.shared.released.notify_all();
+    }
+
+    pub fn budget(&self) -> MemoryBudget {
+        self.lock().budget
+    }
+
+    pub fn usage(&self) -> MemoryUsage {
+        let state = self.lock();
+        MemoryUsage {
+            host_used_bytes: state.host_used,
+            host_budget_bytes: state.budget.host_bytes,
+            vram_used_bytes: state.vram_used,
+            vram_budget_bytes: state.budget.vram_bytes,
+            frames_in_flight: state.frames_in_flight,
+        }
+    }
+
+    /// Bytes left before `kind` reaches its budget; `None` when unlimited.
+    pub fn available(&self, kind: MemoryKind) -> Option<u64> {
+        let state = self.lock();
+        state
+            .budget
+            .limit(kind)
+            .map(|limit| limit.saturating_sub(state.used(kind)))
+    }
+
+    /// Records `bytes` of `kind` without waiting. For memory that is
+    /// allocated either way, such as a loaded session.
+    pub fn reserve(&self, kind: MemoryKind, bytes: u64) -> MemoryReservation {
+        self.reserve_locked(&mut self.lock(), kind, bytes, false)
+    }
+
+    /// Records a frame buffer without waiting, e.g. for frames an
+    /// interpolator creates while it holds others.
+    pub fn reserve_frame(&self, bytes: u64) -> MemoryReservation {
+        self.reserve_locked(&mut self.lock(), MemoryKind::Host, bytes, true)
+    }
+
+    /// Reserves host memory for a decoded frame, waiting while the budget is
+    /// exhausted and earlier frames are still in the pipeline. Returns `None`
+    /// when `cancel` is set while waiting.
+    pub fn admit_frame(&self, bytes: u64, cancel: &AtomicBool) -> Option<MemoryReservation> {
+        let mut state = self.lock();
+        let mut last_releases = state.releases;
+        let mut last_progress = Instant::now();
+        loop {
+            if state.fits(MemoryKind::Host, bytes) || state.frames_in_flight < MIN_FRAMES_IN_FLIGHT
+            {
+                return Some(self.reserve_locked(&mut state, MemoryKind::Host, bytes, true));
+            }
+            if cancel.load(Ordering::SeqCst) {
+                return None;
+            }
+            if state.releases != last_releases {
+                last_releases = state.releases;
+                last_progress = Instant::now();
+            } else if last_progress.elapsed() >= STALL_TIMEOUT {
+                warn!(
+                    host_used = state.host_used,
+                    host_budget = state.budget.host_bytes,
+                    "Host memory over budget with no frames draining; admitting frame anyway"
+                );
+                return Some(self.reserve_locked(&mut state, MemoryKind::Host, bytes, true));
+            }
+            state = self
+                .shared
+                .released
+                .wait_timeout(state, WAIT_SLICE)
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .0;
+        }
+    }
+
+    /// Largest tile edge, starting at `requested` (`0` = whole frame) and
+    /// halving down to `min_tile`, whose estimated VRAM fits what is left of
+    /// the budget. Returns `requested` when VRAM is unlimited or it fits.
+    pub fn fit_tile_size(
+        &self,
+        requested: usize,
+        height: usize,
+        width: usize,
+        bytes_per_pixel: u64,
+        min_tile: usize,
+    ) -> usize {
+        let Some(available) = self.available(MemoryKind::Vram) else {
+            return requested;
+        };
+        let estimate =
+            |tile: usize| -> u64 { (tile.min(height) * tile.min(width)) as u64 * bytes_per_pixel };
+        let mut tile = if requested == 0 {
+            height.max(width)
+        } else {
+            requested
+        };
+        if estimate(tile) <= available {
+            return requested;
+        }
+        while estimate(tile) > available && tile / 2 >= min_tile {
+            tile /= 2;
+        }
+        tile
+    }
+
+    fn reserve_locked(
+        &self,
+        state: &mut GovernorState,
+        kind: MemoryKind,
+        bytes: u64,
+        frame: bool,
+    ) -> MemoryReservation {
+        *state.used_mut(kind) += bytes;
+        if frame {
+            state.frames_in_flight += 1;
+        }
+        MemoryReservation {
+            governor: self.clone(),
+            kind,
+            bytes,
+            frame,
+        }
+    }
+}
+
+/// Memory recorded with a [`MemoryGovernor`]; released on drop.
+#[derive(Debug)]
+pub struct MemoryReservation {
+    governor: MemoryGovernor,
+    kind: MemoryKind,
+    bytes: u64,
+    frame: bool,
+}
+
+impl MemoryReservation {
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Adjusts the reservation after the buffer it covers changed size,
+    /// e.g. a frame after upscaling.
+    pub fn resize(&mut self, bytes: u64) {
+        if bytes == self.bytes {
+            return;
+        }
+        let mut state = self.governor.lock();
+        let used = state.used_mut(self.kind);
+        *used = used.saturating_sub(self.bytes) + bytes;
+        if bytes < self.bytes {
+            state.releases += 1;
+            drop(state);
+            self.governor.shared.released.notify_all();
+        }
+        self.bytes = bytes;
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        let mut state = self.governor.lock();
+        let used = state.used_mut(self.kind);
+        *used = used.saturating_sub(self.bytes);
+        if self.frame {
+            state.frames_in_flight = state.frames_in_flight.saturating_sub(1);
+        }
+        state.releases += 1;
+        drop(state);
+        self.governor.shared.released.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn governor(host: Option<u64>, vram: Option<u64>) -> MemoryGovernor {
+        MemoryGovernor::new(MemoryBudget {
+            host_bytes: host,
+            vram_bytes: vram,
+        })
+    }
+
+    #[test]
+    fn test_reservations_are_released_and_resized() {
+        let governor = governor(Some(1000), None);
+        let mut frame = governor.reserve_frame(100);
+        let session = governor.reserve(MemoryKind::Vram, 500);
+        assert_eq!(governor.usage().host_used_bytes, 100);
+        assert_eq!(governor.usage().vram_used_bytes, 500);
+        assert_eq!(governor.usage().frames_in_flight, 1);
+        assert_eq!(governor.available(MemoryKind::Host), Some(900));
+        assert_eq!(governor.available(MemoryKind::Vram), None);
+
+        frame.resize(400);
+        assert_eq!(governor.usage().host_used_bytes, 400);
+        drop(frame);
+        drop(session);
+        assert_eq!(
+            governor.usage(),
+            MemoryUsage {
+                host_used_bytes: 0,
+                host_budget_bytes: Some(1000),
+                vram_used_bytes: 0,
+                vram_budget_bytes: None,
+                frames_in_flight: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_admit_frame_waits_for_release() {
+        let governor = governor(Some(250), None);
+        let cancel = AtomicBool::new(false);
+        let first = governor.admit_frame(100, &cancel).unwrap();
+        let second = governor.admit_frame(100, &cancel).unwrap();
+        // Over budget with two frames in flight: waits until one drains.
+        let releaser = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(100));
+            drop(first);
+        });
+        let started = Instant::now();
+        let third = governor.admit_frame(100, &cancel).unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(90));
+        releaser.join().unwrap();
+        assert_eq!(governor.usage().host_used_bytes, 200);
+        drop((second, third));
+    }
+
+    #[test]
+    fn test_admit_frame_stops_on_cancel() {
+        let governor = governor(Some(100), None);
+        let cancel = AtomicBool::new(false);
+        let _held = [
+            governor.admit_frame(100, &cancel).unwrap(),
+            governor.admit_frame(100, &cancel).unwrap(),
+        ];
+        cancel.store(true, Ordering::SeqCst);
+        assert!(governor.admit_frame(100, &cancel).is_none());
+    }
+
+    #[test]
+    fn test_fit_tile_size_halves_to_fit_vram() {
+        let unlimited = governor(None, None);
+        assert_eq!(unlimited.fit_tile_size(0, 1080, 1920, 1000, 128), 0);
+
+        let governor = governor(None, Some(600 * 600 * 10));
+        // Whole 1080p frame does not fit; 1920 -> 960 -> 480 does.
+        assert_eq!(governor.fit_tile_size(0, 1080, 1920, 10, 128), 480);
+        assert_eq!(governor.fit_tile_size(512, 1080, 1920, 10, 128), 512);
+        let _session = governor.reserve(MemoryKind::Vram, 600 * 600 * 10);
+        assert_eq!(governor.fit_tile_size(512, 1080, 1920, 10, 128), 128);
+    }
+
+    #[test]
+    fn test_budget_from_config() {
+        let budget = MemoryBudget::from_config(&MemoryConfig {
+            host_budget_mb: 2048,
+            vram_budget_mb: 0,
+        });
+        assert_eq!(budget.host_bytes, Some(2048 * 1024 * 1024));
+        assert_eq!(budget.vram_bytes, None);
+        assert_eq!(
+            parse_mem_total("MemTotal:       16314500 kB\nMemFree: 1 kB\n"),
+            Some(16_314_500 * 1024)
+        );
+    }
+}
@@ -6,6 +6,9 @@ use std::process::Command as ProcessCommand;
 
 use tracing::{info, warn};
 
+pub mod memory;
+pub mod session_pool;
+
 #[cfg(unix)]
 const ORT_LIB_NAME: &str = "libonnxruntime.so";
 #[cfg(windows)]
@@ -335,6 +338,17 @@ pub fn log_runtime_lib_status() {
     }
 }
 
+/// Load the ONNX Runtime library ORT will use (from `ORT_DYLIB_PATH`, or the
+/// platform default name) without committing an environment. Used by the
+/// readiness probe; loading is idempotent, so later sessions reuse it.
+pub fn probe_ort_runtime() -> anyhow::Result<PathBuf> {
+    let path = env::var_os("ORT_DYLIB_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(ORT_LIB_NAME));
+    ort::init_from(&path).map_err(|err| anyhow::anyhow!("{err}"))?;
+    Ok(path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
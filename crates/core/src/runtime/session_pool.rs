@@ -0,0 +1,544 @@
+//! Cache of ONNX Runtime sessions shared by jobs.
+//!
+//! Building a session is slow, and the first TensorRT engine build for a
+//! model can take minutes. Sessions are kept here when a job finishes and
+//! handed to the next node that loads the same model file with the same
+//! backend and engine cache directory. Precision is a property of the model
+//! file, and the TensorRT EP builds (or loads from its engine cache) the
+//! engine for a new input shape within the same session, so neither needs a
+//! key of its own.
+//!
+//! A session is leased to one node at a time: a second job loading the same
+//! model while the first still runs builds a session of its own. Up to
+//! `capacity` idle sessions are kept, the least recently used going first.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, LazyLock, Mutex, MutexGuard};
+use std::time::SystemTime;
+
+use anyhow::{bail, Result};
+use half::f16;
+use ndarray::{ArrayD, IxDyn};
+use ort::session::Session;
+use ort::tensor::TensorElementType;
+use ort::value::{DynValue, Tensor, ValueType};
+use tracing::{debug, info, warn};
+
+use crate::config::{resolve_relative_to, WarmupModel};
+use crate::job_phase::{report_phase, JobPhase};
+use crate::nodes::backend::{
+    build_session, reserve_session_memory, InferenceBackend, SessionConfig,
+};
+use crate::runtime::memory::MemoryReservation;
+
+/// Idle sessions kept when the config does not say otherwise.
+pub const DEFAULT_SESSION_CACHE_SIZE: usize = 4;
+
+/// A session as held by nodes and their micro-stages.
+pub type SharedSession = Arc<Mutex<Session>>;
+
+static SESSION_POOL: LazyLock<SessionPool> =
+    LazyLock::new(|| SessionPool::new(DEFAULT_SESSION_CACHE_SIZE));
+
+/// The pool shared by every job in the process.
+pub fn session_pool() -> &'static SessionPool {
+    &SESSION_POOL
+}
+
+/// Takes an idle cached session for `config`, or builds one. The session
+/// goes back to the pool when the returned lease is dropped.
+pub fn acquire_session(config: &SessionConfig<'_>) -> Result<(SharedSession, SessionLease)> {
+    report_phase(JobPhase::LoadingModel);
+    session_pool().acquire(SessionKey::new(config), || {
+        let session = build_session(config)?;
+        Ok((session, reserve_session_memory(config.model_path)))
+    })
+}
+
+/// Identifies sessions that can stand in for each other.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SessionKey {
+    model_path: PathBuf,
+    modified: Option<SystemTime>,
+    backend: InferenceBackend,
+    trt_cache_dir: Option<PathBuf>,
+}
+
+impl SessionKey {
+    /// Keys on the canonical model path and its modification time, so a
+    /// replaced model file is not served from a stale session.
+    pub fn new(config: &SessionConfig<'_>) -> Self {
+        let model_path = config
+            .model_path
+            .canonicalize()
+            .unwrap_or_else(|_| config.model_path.to_path_buf());
+        let modified = std::fs::metadata(&model_path)
+            .and_then(|meta| meta.modified())
+            .ok();
+        Self {
+            model_path,
+            modified,
+            backend: config.backend.clone(),
+            trt_cache_dir: config.trt_cache_dir.map(Path::to_path_buf),
+        }
+    }
+}
+
+struct Entry<T> {
+    id: u64,
+    key: SessionKey,
+    value: Arc<Mutex<T>>,
+    leased: bool,
+    last_used: u64,
+    _memory: MemoryReservation,
+}
+
+struct PoolState<T> {
+    capacity: usize,
+    entries: Vec<Entry<T>>,
+    /// Keys with a build in progress; other acquirers wait for it instead
+    /// of building the same engine twice.
+    building: HashSet<SessionKey>,
+    next_id: u64,
+    clock: u64,
+}
+
+impl<T> PoolState<T> {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    /// Drops the least recently used idle entries beyond `capacity`.
+    fn trim(&mut self) {
+        loop {
+            let idle = self.entries.iter().filter(|entry| !entry.leased).count();
+            if idle <= self.capacity {
+                return;
+            }
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| !entry.leased)
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(index, _)| index)
+            else {
+                return;
+            };
+            let entry = self.entries.remove(oldest);
+            debug!(model = %entry.key.model_path.display(), "Evicted cached session");
+        }
+    }
+}
+
+struct PoolShared<T> {
+    state: Mutex<PoolState<T>>,
+    built: Condvar,
+}
+
+impl<T> PoolShared<T> {
+    fn lock(&self) -> MutexGuard<'_, PoolState<T>> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Sessions (or, in tests, any value) cached by [`SessionKey`].
+pub struct SessionPool<T = Session> {
+    shared: Arc<PoolShared<T>>,
+}
+
+impl<T> SessionPool<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            shared: Arc::new(PoolShared {
+                state: Mutex::new(PoolState {
+                    capacity,
+                    entries: Vec::new(),
+                    building: HashSet::new(),
+                    next_id: 0,
+                    clock: 0,
+                }),
+                built: Condvar::new(),
+            }),
+        }
+    }
+
+    /// Sets how many idle sessions are kept and evicts any beyond it.
+    pub fn set_capacity(&self, capacity: usize) {
+        let mut state = self.shared.lock();
+        state.capacity = capacity;
+        state.trim();
+    }
+
+    /// Number of cached sessions, leased or idle.
+    pub fn len(&self) -> usize {
+        self.shared.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Leases an idle entry for `key`, or builds a new one with `build`.
+    pub fn acquire(
+        &self,
+        key: SessionKey,
+        build: impl FnOnce() -> Result<(T, MemoryReservation)>,
+    ) -> Result<(Arc<Mutex<T>>, SessionLease<T>)> {
+        let mut state = self.shared.lock();
+        loop {
+            let clock = state.tick();
+            let idle = state
+                .entries
+                .iter_mut()
+                .filter(|entry| entry.key == key && !entry.leased)
+                .max_by_key(|entry| entry.last_used);
+            if let Some(entry) = idle {
+                entry.leased = true;
+                entry.last_used = clock;
+                debug!(model = %key.model_path.display(), "Reusing cached session");
+                return Ok((Arc::clone(&entry.value), self.lease(entry.id)));
+            }
+            if !state.building.contains(&key) {
+                break;
+            }
+            state = self.wait_for_build(state);
+        }
+        let (value, id) = self.build_entry(state, key, build, true)?;
+        Ok((value, self.lease(id)))
+    }
+
+    /// Builds an entry for `key` and adds it to the idle set, unless one is
+    /// cached or being built already.
+    pub fn preload(
+        &self,
+        key: SessionKey,
+        build: impl FnOnce() -> Result<(T, MemoryReservation)>,
+    ) -> Result<()> {
+        let mut state = self.shared.lock();
+        while state.building.contains(&key) {
+            state = self.wait_for_build(state);
+        }
+        if state.entries.iter().any(|entry| entry.key == key) {
+            return Ok(());
+        }
+        self.build_entry(state, key, build, false)?;
+        Ok(())
+    }
+
+    fn wait_for_build<'a>(
+        &self,
+        state: MutexGuard<'a, PoolState<T>>,
+    ) -> MutexGuard<'a, PoolState<T>> {
+        self.shared
+            .built
+            .wait(state)
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Runs `build` without holding the lock, then adds the result. The
+    /// in-progress mark is cleared in the same critical section that adds
+    /// the entry, so waiters find it.
+    fn build_entry(
+        &self,
+        mut state: MutexGuard<'_, PoolState<T>>,
+        key: SessionKey,
+        build: impl FnOnce() -> Result<(T, MemoryReservation)>,
+        leased: bool,
+    ) -> Result<(Arc<Mutex<T>>, u64)> {
+        state.building.insert(key.clone());
+        drop(state);
+
+        let mut guard = BuildGuard {
+            shared: &self.shared,
+            key: &key,
+            finished: false,
+        };
+        let (value, memory) = build()?;
+
+        let mut state = self.shared.lock();
+        state.building.remove(&key);
+        guard.finished = true;
+        let id = state.next_id;
+        state.next_id += 1;
+        let last_used = state.tick();
+        let value = Arc::new(Mutex::new(value));
+        state.entries.push(Entry {
+            id,
+            key: key.clone(),
+            value: Arc::clone(&value),
+            leased,
+            last_used,
+            _memory: memory,
+        });
+        state.trim();
+        drop(state);
+        drop(guard);
+        Ok((value, id))
+    }
+
+    fn lease(&self, id: u64) -> SessionLease<T> {
+        SessionLease {
+            shared: Arc::clone(&self.shared),
+            id,
+        }
+    }
+}
+
+/// Wakes the acquirers waiting for a build when it ends, and clears the
+/// in-progress mark if the build failed or panicked.
+struct BuildGuard<'a, T> {
+    shared: &'a PoolShared<T>,
+    key: &'a SessionKey,
+    finished: bool,
+}
+
+impl<T> Drop for BuildGuard<'_, T> {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.shared.lock().building.remove(self.key);
+        }
+        self.shared.built.notify_all();
+    }
+}
+
+/// Marks a pooled session as in use; dropping it returns the session to the
+/// pool's idle set.
+pub struct SessionLease<T = Session> {
+    shared: Arc<PoolShared<T>>,
+    id: u64,
+}
+
+impl<T> Drop for SessionLease<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        let clock = state.tick();
+        if let Some(entry) = state.entries.iter_mut().find(|entry| entry.id == self.id) {
+            entry.leased = false;
+            entry.last_used = clock;
+        }
+        state.trim();
+    }
+}
+
+/// Builds sessions for `models` on a background thread and runs each once
+/// at its configured size, so the first job using them starts right away.
+pub fn spawn_warmup(models: Vec<WarmupModel>, models_dir: PathBuf, trt_cache_dir: PathBuf) {
+    if models.is_empty() {
+        return;
+    }
+    std::thread::spawn(move || {
+        for model in models {
+            let model_path = resolve_relative_to(&models_dir, &model.model);
+            let backend = InferenceBackend::from_str_lossy(&model.backend);
+            let config = SessionConfig {
+                model_path: &model_path,
+                backend: &backend,
+                trt_cache_dir: Some(&trt_cache_dir),
+            };
+            let started = std::time::Instant::now();
+            let key = SessionKey::new(&config);
+            let result = session_pool().preload(key, || {
+                let mut session = build_session(&config)?;
+                if let Err(err) = run_dummy_inference(&mut session, model.height, model.width) {
+                    warn!(
+                        model = %model_path.display(),
+                        error = %format!("{err:#}"),
+                        "Warm-up inference failed; keeping the session without it"
+                    );
+                }
+                Ok((session, reserve_session_memory(&model_path)))
+            });
+            match result {
+                Ok(()) => info!(
+                    model = %model_path.display(),
+                    %backend,
+                    elapsed_secs = started.elapsed().as_secs_f64(),
+                    "Warmed up model session"
+                ),
+                Err(err) => warn!(
+                    model = %model_path.display(),
+                    error = %format!("{err:#}"),
+                    "Failed to warm up model session"
+                ),
+            }
+        }
+    });
+}
+
+/// Runs the model once on zeros. Dynamic dimensions of NCHW inputs become
+/// `1×3×height×width`; other dynamic dimensions become 1.
+fn run_dummy_inference(session: &mut Session, height: usize, width: usize) -> Result<()> {
+    let mut feeds: Vec<(String, DynValue)> = Vec::new();
+    for input in session.inputs() {
+        let ValueType::Tensor { ty, shape, .. } = input.dtype() else {
+            bail!("input '{}' is not a tensor", input.name());
+        };
+        let dims: Vec<usize> = shape
+            .iter()
+            .enumerate()
+            .map(|(axis, &dim)| match (dim > 0, shape.len(), axis) {
+                (true, ..) => dim as usize,
+                (false, 4, 1) => 3,
+                (false, 4, 2) => height,
+                (false, 4, 3) => width,
+                _ => 1,
+            })
+            .collect();
+        let value = match ty {
+            TensorElementType::Float32 => {
+                Tensor::from_array(ArrayD::from_elem(IxDyn(&dims), 0.0_f32))?.into_dyn()
+            }
+            TensorElementType::Float16 => {
+                Tensor::from_array(ArrayD::from_elem(IxDyn(&dims), f16::ZERO))?.into_dyn()
+            }
+            other => bail!("cannot warm up input '{}' of type {other:?}", input.name()),
+        };
+        feeds.push((input.name().to_string(), value));
+    }
+    session.run(feeds)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::runtime::memory::{MemoryBudget, MemoryGovernor, MemoryKind};
+
+    fn key(name: &str) -> SessionKey {
+        SessionKey {
+            model_path: PathBuf::from(name),
+            modified: None,
+            backend: InferenceBackend::Cuda,
+            trt_cache_dir: None,
+        }
+    }
+
+    fn builder<'a>(
+        governor: &'a MemoryGovernor,
+        builds: &'a AtomicUsize,
+        value: &'static str,
+    ) -> impl FnOnce() -> Result<(&'static str, MemoryReservation)> + 'a {
+        move || {
+            builds.fetch_add(1, Ordering::SeqCst);
+            Ok((value, governor.reserve(MemoryKind::Vram, 100)))
+        }
+    }
+
+    fn governor() -> MemoryGovernor {
+        MemoryGovernor::new(MemoryBudget {
+            host_bytes: None,
+            vram_bytes: None,
+        })
+    }
+
+    #[test]
+    fn test_released_session_is_reused() {
+        let governor = governor();
+        let builds = AtomicUsize::new(0);
+        let pool = SessionPool::new(2);
+
+        let (first, lease) = pool
+            .acquire(key("a.onnx"), builder(&governor, &builds, "a"))
+            .unwrap();
+        drop(lease);
+        let (second, _lease) = pool
+            .acquire(key("a.onnx"), builder(&governor, &builds, "a"))
+            .unwrap();
+
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(builds.load(Ordering::SeqCst), 1);
+        assert_eq!(governor.usage().vram_used_bytes, 100);
+    }
+
+    #[test]
+    fn test_leased_session_is_not_shared() {
+        let governor = governor();
+        let builds = AtomicUsize::new(0);
+        let pool = SessionPool::new(2);
+
+        let (first, _first_lease) = pool
+            .acquire(key("a.onnx"), builder(&governor, &builds, "a"))
+            .unwrap();
+        let (second, _second_lease) = pool
+            .acquire(key("a.onnx"), builder(&governor, &builds, "a"))
+            .unwrap();
+        let (other, _other_lease) = pool
+            .acquire(key("b.onnx"), builder(&governor, &builds, "b"))
+            .unwrap();
+
+        assert!(!Arc::ptr_eq(&first, &second));
+        assert_eq!(*other.lock().unwrap(), "b");
+        assert_eq!(builds.load(Ordering::SeqCst), 3);
+        assert_eq!(pool.len(), 3);
+    }
+
+    #[test]
+    fn test_idle_sessions_beyond_capacity_are_evicted() {
+        let governor = governor();
+        let builds = AtomicUsize::new(0);
+        let pool = SessionPool::new(1);
+
+        for name in ["a.onnx", "b.onnx"] {
+            let (_, lease) = pool
+                .acquire(key(name), builder(&governor, &builds, "x"))
+                .unwrap();
+            drop(lease);
+        }
+        // `a` was used least recently and is gone with its VRAM.
+        assert_eq!(pool.len(), 1);
+        assert_eq!(governor.usage().vram_used_bytes, 100);
+        pool.acquire(key("a.onnx"), builder(&governor, &builds, "x"))
+            .unwrap();
+        assert_eq!(builds.load(Ordering::SeqCst), 3);
+
+        pool.set_capacity(0);
+        assert_eq!(pool.len(), 0);
+    }
+
+    #[test]
+    fn test_failed_build_is_not_cached() {
+        let pool: SessionPool<&str> = SessionPool::new(2);
+        let err = pool
+            .acquire(key("a.onnx"), || bail!("no such model"))
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("no such model"));
+        assert!(pool.is_empty());
+        assert!(pool.shared.lock().building.is_empty());
+    }
+
+    #[test]
+    fn test_concurrent_acquire_waits_for_build() {
+        let governor = governor();
+        let builds = AtomicUsize::new(0);
+        let pool = SessionPool::new(2);
+
+        std::thread::scope(|scope| {
+            let warmup = scope.spawn(|| {
+                pool.preload(key("a.onnx"), || {
+                    std::thread::sleep(std::time::Duration::from_millis(100));
+                    builder(&governor, &builds, "a")()
+                })
+                .unwrap();
+            });
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            let (_, _lease) = pool
+                .acquire(key("a.onnx"), builder(&governor, &builds, "a"))
+                .unwrap();
+            warmup.join().unwrap();
+        });
+
+        assert_eq!(builds.load(Ordering::SeqCst), 1);
+        // Already cached: preloading again builds nothing.
+        pool.preload(key("a.onnx"), builder(&governor, &builds, "a"))
+            .unwrap();
+        assert_eq!(builds.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,390 @@
+//! Encrypted store for named secrets referenced from workflow params.
+//!
+//! Secrets live in `<data_dir>/secrets.json`, each value sealed with
+//! AES-256-GCM under a per-installation key kept in `<data_dir>/secrets.key`.
+//! Workflows reference them as `{"$secret": "name"}` and the reference is
+//! only swapped for the plaintext on the in-memory copy handed to the
+//! executor, so saved workflows and job records never contain the value.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use chrono::{DateTime, Utc};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+
+use crate::graph::PipelineGraph;
+
+/// Key used in a JSON object to mark a secret reference.
+pub const SECRET_REF_KEY: &str = "$secret";
+
+const SECRETS_FILE_NAME: &str = "secrets.json";
+const SECRETS_KEY_FILE_NAME: &str = "secrets.key";
+const SECRET_NAME_MAX_LEN: usize = 64;
+const KEY_LEN: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecretMetadata {
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SealedSecret {
+    nonce: String,
+    ciphertext: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SecretsFile {
+    #[serde(default)]
+    secrets: BTreeMap<String, SealedSecret>,
+}
+
+#[derive(Debug)]
+pub struct SecretStore {
+    secrets_path: PathBuf,
+    key_path: PathBuf,
+    // Serializes read-modify-write cycles on the secrets file.
+    lock: Mutex<()>,
+}
+
+pub fn validate_secret_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > SECRET_NAME_MAX_LEN {
+        bail!("secret name must be 1-{SECRET_NAME_MAX_LEN} characters");
+    }
+    if !name
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.'))
+    {
+        bail!("secret name may only contain letters, digits, '_', '-' and '.'");
+    }
+    Ok(())
+}
+
+/// Returns the referenced secret name if `value` is exactly `{"$secret": "<name>"}`.
+pub fn secret_ref_name(value: &serde_json::Value) -> Option<&str> {
+    let object = value.as_object()?;
+    if object.len() != 1 {
+        return None;
+    }
+    object.get(SECRET_REF_KEY)?.as_str()
+}
+
+impl SecretStore {
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            secrets_path: data_dir.join(SECRETS_FILE_NAME),
+            key_path: data_dir.join(SECRETS_KEY_FILE_NAME),
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn list(&self) -> Result<Vec<SecretMetadata>> {
+        let _guard = self.lock.lock().unwrap_or_else(|p| p.into_inner());
+        let file = self.read_file()?;
+        Ok(file
+            .secrets
+            .into_iter()
+            .map(|(name, sealed)| SecretMetadata {
+                name,
+                created_at: sealed.created_at,
+                updated_at: sealed.updated_at,
+            })
+            .collect())
+    }
+
+    /// Create or replace a secret.
+    pub fn put(&self, name: &str, value: &str) -> Result<SecretMetadata> {
+        validate_secret_name(name)?;
+
+        let _guard = self.lock.lock().unwrap_or_else(|p| p.into_inner());
+        let key = self.load_or_create_key()?;
+        let mut file = self.read_file()?;
+
+        let rng = SystemRandom::new();
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        rng.fill(&mut nonce_bytes)
+            .map_err(|_| anyhow!("failed to generate secret nonce"))?;
+
+        let mut in_out = value.as_bytes().to_vec();
+        key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce_bytes),
+            Aad::from(name.as_bytes()),
+            &mut in_out,
+        )
+        .map_err(|_| anyhow!("failed to encrypt secret '{name}'"))?;
+
+        let now = Utc::now();
+        let created_at = file
+            .secrets
+            .get(name)
+            .map(|existing| existing.created_at)
+            .unwrap_or(now);
+        file.secrets.insert(
+            name.to_string(),
+            SealedSecret {
+                nonce: BASE64.encode(nonce_bytes),
+                ciphertext: BASE64.encode(&in_out),
+                created_at,
+                updated_at: now,
+            },
+        );
+        self.write_file(&file)?;
+
+        Ok(SecretMetadata {
+            name: name.to_string(),
+            created_at,
+            updated_at: now,
+        })
+    }
+
+    /// Remove a secret; returns whether it existed.
+    pub fn delete(&self, name: &str) -> Result<bool> {
+        let _guard = self.lock.lock().unwrap_or_else(|p| p.into_inner());
+        let mut file = self.read_file()?;
+        if file.secrets.remove(name).is_none() {
+            return Ok(false);
+        }
+        self.write_file(&file)?;
+        Ok(true)
+    }
+
+    /// Decrypt a secret by name.
+    pub fn get(&self, name: &str) -> Result<Option<String>> {
+        let _guard = self.lock.lock().unwrap_or_else(|p| p.into_inner());
+        let file = self.read_file()?;
+        let Some(sealed) = file.secrets.get(name) else {
+            return Ok(None);
+        };
+
+        let key = self.load_key()?;
+        let nonce_bytes: [u8; NONCE_LEN] = BASE64
+            .decode(&sealed.nonce)
+            .context("invalid secret nonce encoding")?
+            .try_into()
+            .map_err(|_| anyhow!("invalid secret nonce length"))?;
+        let mut in_out = BASE64
+            .decode(&sealed.ciphertext)
+            .context("invalid secret ciphertext encoding")?;
+        let plaintext = key
+            .open_in_place(
+                Nonce::assume_unique_for_key(nonce_bytes),
+                Aad::from(name.as_bytes()),
+                &mut in_out,
+            )
+            .map_err(|_| anyhow!("failed to decrypt secret '{name}'"))?;
+
+        String::from_utf8(plaintext.to_vec())
+            .map(Some)
+            .context("secret value is not valid UTF-8")
+    }
+
+    /// Replace every `{"$secret": "<name>"}` in `value` with the decrypted secret.
+    pub fn resolve_refs(&self, value: &mut serde_json::Value) -> Result<()> {
+        if let Some(name) = secret_ref_name(value) {
+            let name = name.to_string();
+            let secret = self
+                .get(&name)?
+                .ok_or_else(|| anyhow!("referenced secret not found: {name}"))?;
+            *value = serde_json::Value::String(secret);
+            return Ok(());
+        }
+
+        match value {
+            serde_json::Value::Object(map) => {
+                for entry in map.values_mut() {
+                    self.resolve_refs(entry)?;
+                }
+            }
+            serde_json::Value::Array(items) => {
+                for item in items {
+                    self.resolve_refs(item)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Resolve secret references in every node's params.
+    pub fn resolve_graph_refs(&self, graph: &mut PipelineGraph) -> Result<()> {
+        for node in graph.nodes_mut() {
+            for value in node.params.values_mut() {
+                self.resolve_refs(value)
+                    .with_context(|| format!("failed to resolve secrets for node '{}'", node.id))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn read_file(&self) -> Result<SecretsFile> {
+        if !self.secrets_path.exists() {
+            return Ok(SecretsFile::default());
+        }
+        let raw = fs::read_to_string(&self.secrets_path).with_context(|| {
+            format!(
+                "failed to read secrets file: {}",
+                self.secrets_path.display()
+            )
+        })?;
+        serde_json::from_str(&raw).with_context(|| {
+            format!(
+                "failed to parse secrets file: {}",
+                self.secrets_path.display()
+            )
+        })
+    }
+
+    fn write_file(&self, file: &SecretsFile) -> Result<()> {
+        if let Some(parent) = self.secrets_path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("failed to create secrets directory: {}", parent.display())
+            })?;
+        }
+        let encoded = serde_json::to_string_pretty(file).context("failed to encode secrets")?;
+        let tmp_path = self.secrets_path.with_extension("json.tmp");
+        fs::write(&tmp_path, encoded)
+            .with_context(|| format!("failed to write secrets file: {}", tmp_path.display()))?;
+        restrict_permissions(&tmp_path)?;
+        fs::rename(&tmp_path, &self.secrets_path).with_context(|| {
+            format!(
+                "failed to replace secrets file: {}",
+                self.secrets_path.display()
+            )
+        })
+    }
+
+    fn load_key(&self) -> Result<LessSafeKey> {
+        let bytes = fs::read(&self.key_path)
+            .with_context(|| format!("failed to read secrets key: {}", self.key_path.display()))?;
+        if bytes.len() != KEY_LEN {
+            bail!("secrets key has unexpected length: {}", bytes.len());
+        }
+        let unbound = UnboundKey::new(&AES_256_GCM, &bytes)
+            .map_err(|_| anyhow!("failed to load secrets key"))?;
+        Ok(LessSafeKey::new(unbound))
+    }
+
+    fn load_or_create_key(&self) -> Result<LessSafeKey> {
+        if self.key_path.exists() {
+            return self.load_key();
+        }
+
+        if let Some(parent) = self.key_path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("failed to create secrets directory: {}", parent.display())
+            })?;
+        }
+        let mut bytes = [0u8; KEY_LEN];
+        SystemRandom::new()
+            .fill(&mut bytes)
+            .map_err(|_| anyhow!("failed to generate secrets key"))?;
+        fs::write(&self.key_path, bytes)
+            .with_context(|| format!("failed to write secrets key: {}", self.key_path.display()))?;
+        restrict_permissions(&self.key_path)?;
+
+        self.load_key()
+    }
+}
+
+#[cfg(unix)]
+fn restrict_permissions(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+        .with_context(|| format!("failed to restrict permissions: {}", path.display()))
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use tempfile::tempdir;
+
+    use crate::graph::NodeInstance;
+
+    #[test]
+    fn put_get_roundtrip_keeps_plaintext_off_disk() {
+        let dir = tempdir().expect("tempdir");
+        let store = SecretStore::new(dir.path());
+
+        store.put("jellyfin_key", "s3cr3t-value").expect("put");
+        assert_eq!(
+            store.get("jellyfin_key").expect("get").as_deref(),
+            Some("s3cr3t-value")
+        );
+
+        let on_disk = fs::read_to_string(dir.path().join(SECRETS_FILE_NAME)).expect("read");
+        assert!(!on_disk.contains("s3cr3t-value"));
+
+        let listed = store.list().expect("list");
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].name, "jellyfin_key");
+
+        assert!(store.delete("jellyfin_key").expect("delete"));
+        assert!(!store.delete("jellyfin_key").expect("delete again"));
+        assert_eq!(store.get("jellyfin_key").expect("get"), None);
+    }
+
+    #[test]
+    fn put_rejects_invalid_names() {
+        let dir = tempdir().expect("tempdir");
+        let store = SecretStore::new(dir.path());
+        assert!(store.put("", "x").is_err());
+        assert!(store.put("../escape", "x").is_err());
+        assert!(store.put("has space", "x").is_err());
+    }
+
+    #[test]
+    fn resolve_graph_refs_replaces_references_on_the_copy() {
+        let dir = tempdir().expect("tempdir");
+        let store = SecretStore::new(dir.path());
+        store.put("jellyfin_key", "abc").expect("put");
+
+        let mut graph = PipelineGraph::new();
+        graph
+            .add_node(NodeInstance {
+                id: "fetch".to_string(),
+                node_type: "HttpRequest".to_string(),
+                params: HashMap::from([
+                    (
+                        "headers".to_string(),
+                        serde_json::json!({"X-Emby-Token": {"$secret": "jellyfin_key"}}),
+                    ),
+                    ("url".to_string(), serde_json::json!("http://host/")),
+                ]),
+            })
+            .expect("add node");
+        let original = serde_json::to_value(&graph).expect("serialize");
+
+        let mut resolved = graph.clone();
+        store.resolve_graph_refs(&mut resolved).expect("resolve");
+
+        let node = resolved.nodes_mut().next().expect("node");
+        assert_eq!(node.params["headers"]["X-Emby-Token"], "abc");
+        assert_eq!(serde_json::to_value(&graph).expect("serialize"), original);
+    }
+
+    #[test]
+    fn resolve_refs_fails_for_missing_secret() {
+        let dir = tempdir().expect("tempdir");
+        let store = SecretStore::new(dir.path());
+        let mut value = serde_json::json!({"$secret": "missing"});
+        let err = store.resolve_refs(&mut value).expect_err("missing secret");
+        assert!(err.to_string().contains("missing"));
+    }
+}
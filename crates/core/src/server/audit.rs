@@ -0,0 +1,132 @@
+//! Audit log of requests that change what the server runs or stores.
+//!
+//! Job creation, config changes, workflow saves and deletes and model
+//! deletions are recorded in the `audit_log` table of the jobs db, with the
+//! caller, the request and the status it got. Callers are told apart by a
+//! short hash of their bearer token; the token itself is never stored.
+
+use axum::http::{HeaderMap, Method};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Entries returned by `GET /api/audit` when no `limit` is given.
+pub(super) const DEFAULT_AUDIT_LIMIT: usize = 100;
+/// Most entries one `GET /api/audit` returns.
+pub(super) const MAX_AUDIT_LIMIT: usize = 1000;
+
+/// Actor of requests made without a bearer token.
+pub const ANONYMOUS_ACTOR: &str = "anonymous";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub seq: i64,
+    pub recorded_at: DateTime<Utc>,
+    /// `token:<hash prefix>` or [`ANONYMOUS_ACTOR`].
+    pub actor: String,
+    /// What was done, e.g. `job.create` or `workflow.delete`.
+    pub action: String,
+    pub method: String,
+    pub path: String,
+    /// HTTP status the request was answered with.
+    pub status: u16,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct AuditQuery {
+    pub action: Option<String>,
+    pub actor: Option<String>,
+    /// Only entries older than this `seq`, for paging.
+    pub before: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+/// The audited action of a request to the route `matched_path`, if any.
+pub(super) fn audited_action(method: &Method, matched_path: &str) -> Option<&'static str> {
+    let action = match (method.as_str(), matched_path) {
+        (
+            "POST",
+            "/api/jobs"
+            | "/api/jobs/chunked"
+            | "/api/jobs/{id}/rerun"
+            | "/api/run"
+            | "/api/batch"
+            | "/api/experiments"
+            | "/api/jellyfin/stream",
+        ) => "job.create",
+        ("PUT", "/api/config")
+        | ("POST", "/api/secrets" | "/api/maintenance/restore")
+        | ("DELETE", "/api/secrets/{name}")
+        | ("PUT" | "DELETE", "/api/jellyfin/servers/{name}") => "config.update",
+        (
+            "POST",
+            "/api/workflows"
+            | "/api/workflows/import"
+            | "/api/workflows/{filename}/versions/{version}/restore",
+        ) => "workflow.save",
+        ("DELETE", "/api/workflows/{filename}") => "workflow.delete",
+        ("DELETE", "/api/models/{filename}") => "model.delete",
+        _ => return None,
+    };
+    Some(action)
+}
+
+/// Who made a request: a hash prefix of its bearer token, or
+/// [`ANONYMOUS_ACTOR`].
+pub(super) fn request_actor(headers: &HeaderMap) -> String {
+    let token = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| !token.is_empty());
+    match token {
+        Some(token) => {
+            let hash = format!("{:x}", Sha256::digest(token.as_bytes()));
+            format!("token:{}", &hash[..12])
+        }
+        None => ANONYMOUS_ACTOR.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audited_actions() {
+        assert_eq!(
+            audited_action(&Method::POST, "/api/jobs/{id}/rerun"),
+            Some("job.create")
+        );
+        assert_eq!(
+            audited_action(&Method::POST, "/api/jellyfin/stream"),
+            Some("job.create")
+        );
+        assert_eq!(
+            audited_action(&Method::PUT, "/api/config"),
+            Some("config.update")
+        );
+        assert_eq!(
+            audited_action(&Method::DELETE, "/api/models/{filename}"),
+            Some("model.delete")
+        );
+        assert_eq!(audited_action(&Method::GET, "/api/jobs"), None);
+        assert_eq!(audited_action(&Method::POST, "/api/workflows/lint"), None);
+    }
+
+    #[test]
+    fn test_request_actor_hashes_bearer_token() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(request_actor(&headers), ANONYMOUS_ACTOR);
+
+        headers.insert(
+            axum::http::header::AUTHORIZATION,
+            "Bearer s3cr3t".parse().unwrap(),
+        );
+        let actor = request_actor(&headers);
+        assert!(actor.starts_with("token:"));
+        assert_eq!(actor.len(), "token:".len() + 12);
+        assert!(!actor.contains("s3cr3t"));
+    }
+}
@@ -0,0 +1,263 @@
+//! Backups of the data directory: a `.tar.gz` with the config, the jobs db,
+//! saved workflows and presets, to keep before an upgrade or to move a
+//! server to another machine.
+//!
+//! ```text
+//! manifest.json
+//! config.toml
+//! jobs.db
+//! workflows/<workflow>.json
+//! presets/<preset>.json
+//! ```
+//!
+//! Models are left out: they are large and can be downloaded again. Secrets
+//! are left out too, as they are sealed with a key that stays on this
+//! machine.
+
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+
+use super::persistence::JobsPersistence;
+
+const MANIFEST_NAME: &str = "manifest.json";
+const CONFIG_NAME: &str = "config.toml";
+const JOBS_DB_NAME: &str = "jobs.db";
+const WORKFLOWS_FOLDER: &str = "workflows";
+const PRESETS_FOLDER: &str = "presets";
+const BACKUP_FORMAT_VERSION: u32 = 1;
+/// Largest backup `POST /api/maintenance/restore` accepts.
+pub(super) const MAX_RESTORE_BYTES: usize = 1024 * 1024 * 1024;
+/// Most bytes a backup may unpack to across all its entries, so a small
+/// gzip bomb can't exhaust memory.
+const MAX_UNPACKED_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub created_at: DateTime<Utc>,
+    pub app_version: String,
+}
+
+/// Where the backed up files are read from.
+pub(super) struct BackupSources {
+    pub config_path: PathBuf,
+    pub jobs_persistence: Option<JobsPersistence>,
+    pub workflows_dir: PathBuf,
+    pub presets_dir: PathBuf,
+}
+
+/// The files of a backup, read back and checked.
+#[derive(Debug)]
+pub(super) struct Backup {
+    pub manifest: BackupManifest,
+    pub config: Option<String>,
+    pub jobs_db: Option<Vec<u8>>,
+    /// `(filename, contents)` of each workflow.
+    pub workflows: Vec<(String, Vec<u8>)>,
+    /// `(filename, contents)` of each preset.
+    pub presets: Vec<(String, Vec<u8>)>,
+}
+
+pub(super) fn build_backup(sources: &BackupSources) -> Result<Vec<u8>> {
+    let mut tar = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+
+    let manifest = BackupManifest {
+        format_version: BACKUP_FORMAT_VERSION,
+        created_at: Utc::now(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+    };
+    append_bytes(
+        &mut tar,
+        MANIFEST_NAME,
+        &serde_json::to_vec_pretty(&manifest)?,
+    )?;
+
+    if sources.config_path.is_file() {
+        tar.append_path_with_name(&sources.config_path, CONFIG_NAME)
+            .context("failed to add config.toml to backup")?;
+    }
+
+    if let Some(persistence) = &sources.jobs_persistence {
+        // The live db may be mid-write; back up a consistent copy instead.
+        let snapshot = persistence
+            .db_path()
+            .with_file_name(format!("jobs.db.backup-{}", uuid::Uuid::new_v4()));
+        let added = persistence.snapshot_to(&snapshot).and_then(|()| {
+            tar.append_path_with_name(&snapshot, JOBS_DB_NAME)
+                .context("failed to add jobs.db to backup")
+        });
+        let _ = fs::remove_file(&snapshot);
+        added?;
+    }
+
+    for (folder, dir) in [
+        (WORKFLOWS_FOLDER, &sources.workflows_dir),
+        (PRESETS_FOLDER, &sources.presets_dir),
+    ] {
+        for path in json_files(dir)? {
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            tar.append_path_with_name(&path, format!("{folder}/{name}"))
+                .with_context(|| format!("failed to add {} to backup", path.display()))?;
+        }
+    }
+
+    let gz = tar.into_inner().context("failed to finalize backup")?;
+    gz.finish().context("failed to compress backup")
+}
+
+/// Reads a backup made by [`build_backup`], rejecting entries outside its
+/// layout.
+pub(super) fn read_backup(bytes: &[u8]) -> Result<Backup> {
+    read_backup_limited(bytes, MAX_UNPACKED_BYTES)
+}
+
+fn read_backup_limited(bytes: &[u8], max_unpacked: u64) -> Result<Backup> {
+    let mut unpacked = 0u64;
+    let mut archive = tar::Archive::new(GzDecoder::new(Cursor::new(bytes)));
+    let mut manifest = None;
+    let mut config = None;
+    let mut jobs_db = None;
+    let mut workflows = Vec::new();
+    let mut presets = Vec::new();
+
+    for entry in archive.entries().context("not a .tar.gz backup")? {
+        let mut entry = entry.context("failed to read backup entry")?;
+        if entry.header().entry_type().is_dir() {
+            continue;
+        }
+        let path = entry.path().context("invalid entry path")?.into_owned();
+        let parts = path
+            .components()
+            .map(|component| match component {
+                Component::Normal(part) => part.to_str(),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .with_context(|| format!("unsafe entry path: {}", path.display()))?;
+        let remaining = max_unpacked - unpacked;
+        let mut contents = Vec::new();
+        (&mut entry)
+            .take(remaining + 1)
+            .read_to_end(&mut contents)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        unpacked += contents.len() as u64;
+        if unpacked > max_unpacked {
+            bail!(
+                "backup unpacks to more than {} MiB",
+                max_unpacked / (1024 * 1024)
+            );
+        }
+
+        match parts.as_slice() {
+            [MANIFEST_NAME] => {
+                manifest = Some(
+                    serde_json::from_slice::<BackupManifest>(&contents)
+                        .context("invalid manifest.json")?,
+                );
+            }
+            [CONFIG_NAME] => {
+                config = Some(String::from_utf8(contents).context("config.toml is not UTF-8")?);
+            }
+            [JOBS_DB_NAME] => jobs_db = Some(contents),
+            [WORKFLOWS_FOLDER, name] if name.ends_with(".json") => {
+                workflows.push((name.to_string(), contents));
+            }
+            [PRESETS_FOLDER, name] if name.ends_with(".json") => {
+                presets.push((name.to_string(), contents));
+            }
+            _ => bail!("unexpected entry in backup: {}", path.display()),
+        }
+    }
+
+    let manifest = manifest.context("backup has no manifest.json")?;
+    if manifest.format_version > BACKUP_FORMAT_VERSION {
+        bail!(
+            "backup format {} is newer than this version supports ({BACKUP_FORMAT_VERSION})",
+            manifest.format_version
+        );
+    }
+    Ok(Backup {
+        manifest,
+        config,
+        jobs_db,
+        workflows,
+        presets,
+    })
+}
+
+fn append_bytes<W: std::io::Write>(
+    tar: &mut tar::Builder<W>,
+    name: &str,
+    contents: &[u8],
+) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(contents.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    tar.append_data(&mut header, name, contents)
+        .with_context(|| format!("failed to add {name} to backup"))
+}
+
+/// The `.json` files directly in `dir`, by name. A missing dir has none.
+fn json_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read {}", dir.display()));
+        }
+    };
+    let mut files = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.is_file() && path.extension().and_then(|ext| ext.to_str()) == Some("json")
+        })
+        .collect::<Vec<_>>();
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backup_with_workflow(size: usize) -> Vec<u8> {
+        let mut tar = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        let manifest = BackupManifest {
+            format_version: BACKUP_FORMAT_VERSION,
+            created_at: Utc::now(),
+            app_version: "test".to_string(),
+        };
+        append_bytes(
+            &mut tar,
+            MANIFEST_NAME,
+            &serde_json::to_vec(&manifest).unwrap(),
+        )
+        .unwrap();
+        append_bytes(&mut tar, "workflows/big.json", &vec![b' '; size]).unwrap();
+        tar.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn test_read_backup_caps_unpacked_size() {
+        let bytes = backup_with_workflow(4 * 1024 * 1024);
+        assert!(bytes.len() < 64 * 1024, "{}", bytes.len());
+
+        let err = read_backup_limited(&bytes, 1024 * 1024).unwrap_err();
+        assert!(err.to_string().contains("more than 1 MiB"), "{err}");
+
+        let backup = read_backup_limited(&bytes, 8 * 1024 * 1024).unwrap();
+        assert_eq!(backup.workflows.len(), 1);
+    }
+}
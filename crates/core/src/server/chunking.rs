@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+use super::JobStatus;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateChunkedJobRequest {
+    pub workflow: serde_json::Value,
+    #[serde(default)]
+    pub workflow_name: Option<String>,
+    pub params: std::collections::HashMap<String, serde_json::Value>,
+    /// Param holding the source video path (default `input`).
+    #[serde(default)]
+    pub input_param: Option<String>,
+    /// Param holding the final output path (default `output`).
+    #[serde(default)]
+    pub output_param: Option<String>,
+    #[serde(default)]
+    pub target_chunk_secs: Option<f64>,
+    #[serde(default)]
+    pub scene_threshold: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChunkInfo {
+    pub index: usize,
+    pub start_secs: f64,
+    pub end_secs: f64,
+    /// Absent for chunks completed by an earlier run of the same output.
+    pub job_id: Option<String>,
+    pub status: JobStatus,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChunkedJobResponse {
+    pub job_id: String,
+    pub chunks: Vec<ChunkInfo>,
+}
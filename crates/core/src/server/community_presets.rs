@@ -0,0 +1,423 @@
+//! Community presets synced from a preset index.
+//!
+//! `presets.community_index_url` points at a JSON index:
+//!
+//! ```json
+//! {"presets": [{"id": "anime-2x", "name": "Anime 2x", "description": "…",
+//!   "version": "3", "author": "…", "vetted": true, "url": "anime-2x.json"}]}
+//! ```
+//!
+//! Each entry carries its workflow inline as `workflow` or names a file
+//! relative to the index as `url`, which must have the index's scheme and
+//! host so that a remote index cannot point at local files. Only `vetted` entries are synced. They
+//! are listed by `/api/presets` as `community/<id>` next to the presets from
+//! `paths.presets_dir` and the API, and every sync that adds, updates or
+//! removes one is announced on `/api/config/events`.
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use url::Url;
+
+use super::{AppState, ConfigEvent, Preset, PresetOrigin};
+
+/// Prefix of the ids of community presets in the preset list.
+pub(super) const COMMUNITY_PRESET_PREFIX: &str = "community/";
+
+/// How often the sync task looks at the config for a changed index URL.
+const SYNC_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Deserialize)]
+struct CommunityIndex {
+    presets: Vec<CommunityIndexEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommunityIndexEntry {
+    id: String,
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    vetted: bool,
+    #[serde(default)]
+    workflow: Option<serde_json::Value>,
+    #[serde(default)]
+    url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PresetChange {
+    Added,
+    Updated,
+    Removed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresetUpdate {
+    pub id: String,
+    pub name: String,
+    pub change: PresetChange,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommunitySyncResponse {
+    pub synced_at: DateTime<Utc>,
+    /// Community presets after the sync.
+    pub presets: usize,
+    pub updates: Vec<PresetUpdate>,
+}
+
+/// Fetches the index at `index_url` and the workflows of its vetted entries.
+/// Entries that cannot be used are skipped with a warning.
+pub(super) fn fetch_community_presets(
+    index_url: &str,
+    synced_at: DateTime<Utc>,
+) -> Result<Vec<(String, Preset)>> {
+    let index_url =
+        Url::parse(index_url).with_context(|| format!("invalid preset index URL {index_url}"))?;
+    let index: CommunityIndex = serde_json::from_str(&fetch_text(&index_url)?)
+        .with_context(|| format!("invalid preset index at {index_url}"))?;
+
+    let mut presets = Vec::new();
+    for entry in index.presets.into_iter().filter(|entry| entry.vetted) {
+        let id = entry.id.clone();
+        match community_preset(&index_url, entry, synced_at) {
+            Ok(preset) => presets.push((format!("{COMMUNITY_PRESET_PREFIX}{id}"), preset)),
+            Err(err) => warn!(id = %id, error = %format!("{err:#}"), "Skipping community preset"),
+        }
+    }
+    Ok(presets)
+}
+
+fn community_preset(
+    index_url: &Url,
+    entry: CommunityIndexEntry,
+    synced_at: DateTime<Utc>,
+) -> Result<Preset> {
+    if entry.id.is_empty()
+        || !entry
+            .id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        bail!("preset id must be letters, digits, '-' or '_'");
+    }
+
+    let workflow = match (entry.workflow, entry.url.as_deref()) {
+        (Some(workflow), _) => workflow,
+        (None, Some(url)) => {
+            let url = index_url
+                .join(url)
+                .with_context(|| format!("invalid preset URL {url}"))?;
+            check_entry_url(index_url, &url)?;
+            let mut doc: serde_json::Value = serde_json::from_str(&fetch_text(&url)?)
+                .with_context(|| format!("invalid preset at {url}"))?;
+            // A saved workflow or preset file wraps the graph.
+            match doc.get_mut("workflow") {
+                Some(workflow) => workflow.take(),
+                None => doc,
+            }
+        }
+        (None, None) => bail!("preset has neither a workflow nor a url"),
+    };
+
+    Ok(Preset {
+        name: entry.name,
+        description: entry.description,
+        workflow,
+        origin: PresetOrigin::Community {
+            source: index_url.to_string(),
+            version: entry.version,
+            author: entry.author,
+            synced_at,
+        },
+    })
+}
+
+/// Entries may only point next to their index: `file:` URLs are followed
+/// only from a `file:` index, and remote entries must be on the index's host.
+fn check_entry_url(index_url: &Url, url: &Url) -> Result<()> {
+    if url.scheme() != index_url.scheme() || url.host_str() != index_url.host_str() {
+        bail!("preset URL {url} is not on the scheme and host of the index");
+    }
+    Ok(())
+}
+
+fn fetch_text(url: &Url) -> Result<String> {
+    if url.scheme() == "file" {
+        let path = url
+            .to_file_path()
+            .map_err(|()| anyhow!("invalid file URL {url}"))?;
+        return fs::read_to_string(&path).with_context(|| format!("failed to read {url}"));
+    }
+
+    let client = reqwest::blocking::Client::builder()
+        .connect_timeout(Duration::from_secs(15))
+        .timeout(Duration::from_secs(60))
+        .build()
+        .context("failed to build HTTP client for preset sync")?;
+    client
+        .get(url.clone())
+        .send()
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.text())
+        .with_context(|| format!("failed to fetch {url}"))
+}
+
+/// Replaces the community presets in `presets` with `fetched` and reports
+/// what changed. Presets of other origins are left alone.
+pub(super) fn replace_community_presets(
+    presets: &DashMap<String, Preset>,
+    fetched: Vec<(String, Preset)>,
+) -> Vec<PresetUpdate> {
+    let mut updates = Vec::new();
+    let fetched_ids: std::collections::HashSet<String> =
+        fetched.iter().map(|(id, _)| id.clone()).collect();
+
+    presets.retain(|id, preset| {
+        let keep = !id.starts_with(COMMUNITY_PRESET_PREFIX) || fetched_ids.contains(id);
+        if !keep {
+            updates.push(PresetUpdate {
+                id: id.clone(),
+                name: preset.name.clone(),
+                change: PresetChange::Removed,
+                previous_version: preset.origin.version().map(str::to_string),
+                version: None,
+            });
+        }
+        keep
+    });
+
+    for (id, preset) in fetched {
+        let version = preset.origin.version().map(str::to_string);
+        let change = match presets.get(&id) {
+            None => Some((PresetChange::Added, None)),
+            Some(current) => {
+                let previous = current.origin.version().map(str::to_string);
+                let changed = previous != version
+                    || current.workflow != preset.workflow
+                    || current.name != preset.name
+                    || current.description != preset.description;
+                changed.then_some((PresetChange::Updated, previous))
+            }
+        };
+        if let Some((change, previous_version)) = change {
+            updates.push(PresetUpdate {
+                id: id.clone(),
+                name: preset.name.clone(),
+                change,
+                previous_version,
+                version,
+            });
+        }
+        presets.insert(id, preset);
+    }
+
+    updates.sort_by(|a, b| a.id.cmp(&b.id));
+    updates
+}
+
+/// Syncs the community presets from `presets.community_index_url` now. An
+/// empty URL removes them.
+pub(super) async fn sync_community_presets(state: &AppState) -> Result<CommunitySyncResponse> {
+    let index_url = state
+        .inner
+        .config
+        .read()
+        .await
+        .presets
+        .community_index_url
+        .clone();
+    let synced_at = Utc::now();
+    let fetched = if index_url.trim().is_empty() {
+        Vec::new()
+    } else {
+        let index_url = index_url.clone();
+        tokio::task::spawn_blocking(move || fetch_community_presets(&index_url, synced_at))
+            .await
+            .context("preset sync task failed")??
+    };
+
+    let updates = replace_community_presets(&state.inner.presets, fetched);
+    let presets = state
+        .inner
+        .presets
+        .iter()
+        .filter(|entry| entry.key().starts_with(COMMUNITY_PRESET_PREFIX))
+        .count();
+    if !updates.is_empty() {
+        info!(
+            index = %index_url,
+            presets,
+            changes = updates.len(),
+            "Community presets changed"
+        );
+        let _ = state.inner.config_events.send(ConfigEvent::PresetsUpdated {
+            changed_at: synced_at,
+            updates: updates.clone(),
+        });
+    }
+    Ok(CommunitySyncResponse {
+        synced_at,
+        presets,
+        updates,
+    })
+}
+
+/// Sync the community presets at start, every `presets.sync_interval_hours`
+/// and whenever `presets.community_index_url` changes.
+pub fn spawn_community_preset_sync(state: &AppState) -> tokio::task::JoinHandle<()> {
+    let state = state.clone();
+    tokio::spawn(async move {
+        let mut last_sync: Option<(String, Instant)> = None;
+        loop {
+            let config = state.inner.config.read().await.presets.clone();
+            let interval = Duration::from_secs(config.sync_interval_hours.max(1) * 3600);
+            let due = match &last_sync {
+                None => true,
+                Some((url, at)) => *url != config.community_index_url || at.elapsed() >= interval,
+            };
+            // With no index configured there is nothing to fetch, only
+            // presets of a previous index to drop.
+            if due && (last_sync.is_some() || !config.community_index_url.trim().is_empty()) {
+                if let Err(err) = sync_community_presets(&state).await {
+                    warn!(
+                        index = %config.community_index_url,
+                        error = %format!("{err:#}"),
+                        "Failed to sync community presets"
+                    );
+                }
+            }
+            if due {
+                last_sync = Some((config.community_index_url, Instant::now()));
+            }
+            tokio::time::sleep(SYNC_POLL_INTERVAL).await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_index(dir: &std::path::Path, version: &str) -> String {
+        fs::write(
+            dir.join("anime.json"),
+            r#"{"name": "Anime", "description": "", "workflow": {"nodes": [], "connections": []}}"#,
+        )
+        .unwrap();
+        let index = serde_json::json!({"presets": [
+            {"id": "anime", "name": "Anime 2x", "version": version, "author": "someone",
+             "vetted": true, "url": "anime.json"},
+            {"id": "inline", "name": "Inline", "vetted": true,
+             "workflow": {"nodes": [], "connections": []}},
+            {"id": "unvetted", "name": "Unvetted", "workflow": {}},
+            {"id": "../bad", "name": "Bad", "vetted": true, "workflow": {}}
+        ]});
+        fs::write(dir.join("index.json"), index.to_string()).unwrap();
+        Url::from_file_path(dir.join("index.json"))
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_fetch_community_presets_keeps_vetted_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let index_url = write_index(dir.path(), "1");
+
+        let mut presets = fetch_community_presets(&index_url, Utc::now()).unwrap();
+        presets.sort_by(|a, b| a.0.cmp(&b.0));
+        let ids: Vec<&str> = presets.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["community/anime", "community/inline"]);
+
+        let anime = &presets[0].1;
+        assert_eq!(
+            anime.workflow,
+            serde_json::json!({"nodes": [], "connections": []})
+        );
+        assert_eq!(anime.origin.version(), Some("1"));
+        assert!(
+            matches!(&anime.origin, PresetOrigin::Community { author: Some(author), .. } if author == "someone")
+        );
+    }
+
+    #[test]
+    fn test_remote_index_entries_stay_on_its_host() {
+        let index_url = Url::parse("https://presets.example.com/v1/index.json").unwrap();
+        let entry = |url: &str| CommunityIndexEntry {
+            id: "anime".to_string(),
+            name: "Anime".to_string(),
+            description: String::new(),
+            version: None,
+            author: None,
+            vetted: true,
+            workflow: None,
+            url: Some(url.to_string()),
+        };
+
+        for url in [
+            "file:///etc/passwd",
+            "http://presets.example.com/v1/anime.json",
+            "https://elsewhere.example.com/anime.json",
+        ] {
+            let err = community_preset(&index_url, entry(url), Utc::now()).unwrap_err();
+            assert!(
+                err.to_string().contains("scheme and host of the index"),
+                "{url}: {err:#}"
+            );
+        }
+        check_entry_url(&index_url, &index_url.join("presets/anime.json").unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_replace_community_presets_reports_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let presets = DashMap::new();
+        presets.insert(
+            "builtin".to_string(),
+            Preset {
+                name: "Builtin".to_string(),
+                description: String::new(),
+                workflow: serde_json::json!({}),
+                origin: PresetOrigin::Builtin,
+            },
+        );
+
+        let fetch = |version| {
+            fetch_community_presets(&write_index(dir.path(), version), Utc::now()).unwrap()
+        };
+        let updates = replace_community_presets(&presets, fetch("1"));
+        assert_eq!(
+            updates.iter().map(|u| u.change).collect::<Vec<_>>(),
+            vec![PresetChange::Added, PresetChange::Added]
+        );
+        assert!(replace_community_presets(&presets, fetch("1")).is_empty());
+
+        let updates = replace_community_presets(&presets, fetch("2"));
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].change, PresetChange::Updated);
+        assert_eq!(updates[0].previous_version.as_deref(), Some("1"));
+        assert_eq!(updates[0].version.as_deref(), Some("2"));
+
+        let updates = replace_community_presets(&presets, Vec::new());
+        assert_eq!(updates.len(), 2);
+        assert!(updates.iter().all(|u| u.change == PresetChange::Removed));
+        assert_eq!(presets.len(), 1);
+        assert!(presets.contains_key("builtin"));
+    }
+}
@@ -0,0 +1,424 @@
+use std::fs;
+use std::net::TcpListener;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::config::{normalize_supported_locale, AppConfig};
+
+/// Encoders the bundled presets rely on; missing ones are reported as warnings.
+const EXPECTED_ENCODERS: [&str; 4] = ["libx265", "libx264", "hevc_nvenc", "h264_nvenc"];
+const DEFAULT_ENCODER: &str = "libx265";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigIssueSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigValidationIssue {
+    pub severity: ConfigIssueSeverity,
+    pub field: String,
+    pub code: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigValidationReport {
+    /// `false` when at least one issue has `error` severity.
+    pub valid: bool,
+    pub issues: Vec<ConfigValidationIssue>,
+    /// Encoders from [`EXPECTED_ENCODERS`] that ffmpeg reports, or `None`
+    /// when ffmpeg could not be queried.
+    pub available_encoders: Option<Vec<String>>,
+}
+
+struct IssueCollector {
+    issues: Vec<ConfigValidationIssue>,
+}
+
+impl IssueCollector {
+    fn push(&mut self, severity: ConfigIssueSeverity, field: &str, code: &str, message: String) {
+        self.issues.push(ConfigValidationIssue {
+            severity,
+            field: field.to_string(),
+            code: code.to_string(),
+            message,
+        });
+    }
+
+    fn error(&mut self, field: &str, code: &str, message: String) {
+        self.push(ConfigIssueSeverity::Error, field, code, message);
+    }
+
+    fn warning(&mut self, field: &str, code: &str, message: String) {
+        self.push(ConfigIssueSeverity::Warning, field, code, message);
+    }
+}
+
+/// Check a proposed config without applying it. `active` is the running
+/// config, used to skip the port probe for the address we already listen on.
+/// `encoders` is the ffmpeg encoder list, or the probe error message.
+pub(super) fn validate_config(
+    proposed: &AppConfig,
+    active: &AppConfig,
+    encoders: Result<Vec<String>, String>,
+) -> ConfigValidationReport {
+    let mut issues = IssueCollector { issues: Vec::new() };
+
+    check_directory(
+        &mut issues,
+        "paths.models_dir",
+        &proposed.paths.models_dir,
+        false,
+    );
+    for (index, dir) in proposed.paths.extra_models_dirs.iter().enumerate() {
+        check_directory(
+            &mut issues,
+            &format!("paths.extra_models_dirs[{index}]"),
+            dir,
+            false,
+        );
+    }
+    check_directory(
+        &mut issues,
+        "paths.trt_cache_dir",
+        &proposed.paths.trt_cache_dir,
+        true,
+    );
+    check_directory(
+        &mut issues,
+        "paths.presets_dir",
+        &proposed.paths.presets_dir,
+        false,
+    );
+    check_directory(
+        &mut issues,
+        "paths.workflows_dir",
+        &proposed.paths.workflows_dir,
+        true,
+    );
+
+    check_listen_address(&mut issues, proposed, active);
+
+    if normalize_supported_locale(&proposed.locale) != proposed.locale {
+        issues.warning(
+            "locale",
+            "unsupported_locale",
+            format!(
+                "locale '{}' is not supported; '{}' will be used",
+                proposed.locale,
+                normalize_supported_locale(&proposed.locale)
+            ),
+        );
+    }
+
+    if let Err(err) = proposed.redaction.compile() {
+        issues.error("redaction.patterns", "invalid_pattern", format!("{err:#}"));
+    }
+
+    check_jellyfin_servers(&mut issues, proposed);
+
+    let available_encoders = match encoders {
+        Ok(encoders) => {
+            let available: Vec<String> = EXPECTED_ENCODERS
+                .iter()
+                .filter(|name| encoders.iter().any(|encoder| encoder == *name))
+                .map(|name| name.to_string())
+                .collect();
+            if !available.iter().any(|name| name == DEFAULT_ENCODER) {
+                issues.warning(
+                    "encoders",
+                    "default_encoder_missing",
+                    format!("ffmpeg does not provide the default encoder '{DEFAULT_ENCODER}'"),
+                );
+            }
+            Some(available)
+        }
+        Err(message) => {
+            issues.error(
+                "encoders",
+                "ffmpeg_unavailable",
+                format!("failed to query ffmpeg encoders: {message}"),
+            );
+            None
+        }
+    };
+
+    let issues = issues.issues;
+    ConfigValidationReport {
+        valid: !issues
+            .iter()
+            .any(|issue| issue.severity == ConfigIssueSeverity::Error),
+        issues,
+        available_encoders,
+    }
+}
+
+fn check_directory(issues: &mut IssueCollector, field: &str, path: &Path, needs_write: bool) {
+    if !path.exists() {
+        issues.warning(
+            field,
+            "missing_directory",
+            format!("directory does not exist: {}", path.display()),
+        );
+        return;
+    }
+
+    if !path.is_dir() {
+        issues.error(
+            field,
+            "not_a_directory",
+            format!("path is not a directory: {}", path.display()),
+        );
+        return;
+    }
+
+    if needs_write {
+        if let Err(err) = probe_writable(path) {
+            issues.error(
+                field,
+                "not_writable",
+                format!("directory is not writable: {} ({err:#})", path.display()),
+            );
+        }
+    }
+}
+
+fn probe_writable(dir: &Path) -> Result<()> {
+    let probe = dir.join(format!(".videnoa-write-probe-{}", Uuid::new_v4()));
+    fs::write(&probe, b"").with_context(|| format!("failed to create {}", probe.display()))?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+fn check_jellyfin_servers(issues: &mut IssueCollector, proposed: &AppConfig) {
+    let default_server = &proposed.jellyfin.default_server;
+    if !default_server.is_empty() && proposed.jellyfin.server(default_server).is_none() {
+        issues.error(
+            "jellyfin.default_server",
+            "unknown_server",
+            format!("default server '{default_server}' is not configured"),
+        );
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for (index, server) in proposed.jellyfin.servers.iter().enumerate() {
+        let field = format!("jellyfin.servers[{index}]");
+        if let Err(err) = crate::jellyfin::validate_server_name(&server.name) {
+            issues.error(&format!("{field}.name"), "invalid_name", err.to_string());
+        } else if !seen.insert(server.name.as_str()) {
+            issues.error(
+                &format!("{field}.name"),
+                "duplicate_name",
+                format!("Jellyfin server '{}' is configured twice", server.name),
+            );
+        }
+        if !url::Url::parse(&server.url).is_ok_and(|url| matches!(url.scheme(), "http" | "https")) {
+            issues.error(
+                &format!("{field}.url"),
+                "invalid_url",
+                format!("url must be an http(s) URL, got '{}'", server.url),
+            );
+        }
+        if crate::secrets::validate_secret_name(&server.api_key_secret).is_err() {
+            issues.error(
+                &format!("{field}.api_key_secret"),
+                "invalid_secret_name",
+                format!("invalid secret name '{}'", server.api_key_secret),
+            );
+        }
+    }
+}
+
+fn check_listen_address(issues: &mut IssueCollector, proposed: &AppConfig, active: &AppConfig) {
+    if proposed.server.port == 0 {
+        issues.error(
+            "server.port",
+            "invalid_port",
+            "port must be between 1 and 65535".to_string(),
+        );
+        return;
+    }
+
+    if proposed.server.host == active.server.host && proposed.server.port == active.server.port {
+        return;
+    }
+
+    let addr = format!("{}:{}", proposed.server.host, proposed.server.port);
+    if let Err(err) = TcpListener::bind(&addr) {
+        issues.error(
+            "server.port",
+            "port_unavailable",
+            format!("cannot listen on {addr}: {err}"),
+        );
+    }
+}
+
+/// Names of all encoders the ffmpeg binary reports via `-encoders`.
+pub(super) fn probe_ffmpeg_encoders() -> Result<Vec<String>> {
+    let output = crate::runtime::command_for("ffmpeg")
+        .args(["-hide_banner", "-encoders"])
+        .output()
+        .context("failed to launch ffmpeg")?;
+    if !output.status.success() {
+        bail!(
+            "ffmpeg -encoders exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(parse_ffmpeg_encoders(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+fn parse_ffmpeg_encoders(stdout: &str) -> Vec<String> {
+    // Encoder rows look like " V....D libx264   libx264 H.264 ..." and follow
+    // a " ------" separator line.
+    stdout
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("---"))
+        .skip(1)
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let _flags = parts.next()?;
+            parts.next().map(str::to_string)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn config_with_dirs(root: &Path) -> AppConfig {
+        let mut config = AppConfig::default();
+        config.paths.models_dir = root.join("models");
+        config.paths.trt_cache_dir = root.join("trt_cache");
+        config.paths.presets_dir = root.join("presets");
+        config.paths.workflows_dir = root.join("workflows");
+        for dir in [
+            &config.paths.models_dir,
+            &config.paths.trt_cache_dir,
+            &config.paths.presets_dir,
+            &config.paths.workflows_dir,
+        ] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        config
+    }
+
+    #[test]
+    fn parse_ffmpeg_encoders_reads_names_after_separator() {
+        let stdout =
+            "Encoders:\n V..... = Video\n ------\n V....D libx264  H.264\n A....D aac  AAC\n";
+        assert_eq!(parse_ffmpeg_encoders(stdout), vec!["libx264", "aac"]);
+    }
+
+    #[test]
+    fn valid_config_reports_available_encoders() {
+        let root = tempdir().unwrap();
+        let config = config_with_dirs(root.path());
+
+        let report = validate_config(
+            &config,
+            &config,
+            Ok(vec!["libx265".to_string(), "aac".to_string()]),
+        );
+        assert!(report.valid, "{:?}", report.issues);
+        assert!(report.issues.is_empty());
+        assert_eq!(report.available_encoders, Some(vec!["libx265".to_string()]));
+    }
+
+    #[test]
+    fn structured_issues_for_bad_paths_port_and_ffmpeg() {
+        let root = tempdir().unwrap();
+        let active = config_with_dirs(root.path());
+        let mut proposed = active.clone();
+        proposed.paths.models_dir = root.path().join("missing");
+        let file_path = root.path().join("file");
+        fs::write(&file_path, b"x").unwrap();
+        proposed.paths.trt_cache_dir = file_path;
+        proposed.server.port = 0;
+
+        let report = validate_config(&proposed, &active, Err("not found".to_string()));
+        assert!(!report.valid);
+
+        let codes: Vec<(&str, &str, ConfigIssueSeverity)> = report
+            .issues
+            .iter()
+            .map(|issue| (issue.field.as_str(), issue.code.as_str(), issue.severity))
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                (
+                    "paths.models_dir",
+                    "missing_directory",
+                    ConfigIssueSeverity::Warning
+                ),
+                (
+                    "paths.trt_cache_dir",
+                    "not_a_directory",
+                    ConfigIssueSeverity::Error
+                ),
+                ("server.port", "invalid_port", ConfigIssueSeverity::Error),
+                ("encoders", "ffmpeg_unavailable", ConfigIssueSeverity::Error),
+            ]
+        );
+        assert_eq!(report.available_encoders, None);
+    }
+
+    #[test]
+    fn jellyfin_servers_need_unique_names_and_http_urls() {
+        let root = tempdir().unwrap();
+        let active = config_with_dirs(root.path());
+        let mut proposed = active.clone();
+        let server = |name: &str, url: &str| crate::config::JellyfinServerConfig {
+            name: name.to_string(),
+            url: url.to_string(),
+            api_key_secret: format!("jellyfin.{name}"),
+        };
+        proposed.jellyfin.servers = vec![
+            server("anime", "http://anime.local:8096"),
+            server("anime", "ftp://anime.local"),
+        ];
+        proposed.jellyfin.default_server = "movies".to_string();
+
+        let report = validate_config(&proposed, &active, Ok(vec!["libx265".to_string()]));
+        let codes: Vec<(&str, &str)> = report
+            .issues
+            .iter()
+            .map(|issue| (issue.field.as_str(), issue.code.as_str()))
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                ("jellyfin.default_server", "unknown_server"),
+                ("jellyfin.servers[1].name", "duplicate_name"),
+                ("jellyfin.servers[1].url", "invalid_url"),
+            ]
+        );
+    }
+
+    #[test]
+    fn occupied_port_is_reported() {
+        let root = tempdir().unwrap();
+        let active = config_with_dirs(root.path());
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut proposed = active.clone();
+        proposed.server.host = "127.0.0.1".to_string();
+        proposed.server.port = listener.local_addr().unwrap().port();
+
+        let report = validate_config(&proposed, &active, Ok(vec!["libx265".to_string()]));
+        assert!(!report.valid);
+        assert_eq!(report.issues[0].code, "port_unavailable");
+    }
+}
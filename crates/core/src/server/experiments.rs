@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::JobStatus;
+use crate::experiment::ExperimentReport;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateExperimentRequest {
+    /// Clip every variant processes.
+    pub source: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Workflow for variants that do not bring their own.
+    #[serde(default)]
+    pub workflow: Option<serde_json::Value>,
+    /// Params shared by all variants.
+    #[serde(default)]
+    pub params: HashMap<String, serde_json::Value>,
+    /// Param holding the source video path (default `input`).
+    #[serde(default)]
+    pub input_param: Option<String>,
+    /// Param holding the output path (default `output`).
+    #[serde(default)]
+    pub output_param: Option<String>,
+    pub variants: Vec<ExperimentVariant>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExperimentVariant {
+    pub name: String,
+    /// Set on every SuperResolution node of the variant's workflow.
+    #[serde(default)]
+    pub model_path: Option<String>,
+    #[serde(default)]
+    pub workflow: Option<serde_json::Value>,
+    /// Added to, and overriding, the shared params.
+    #[serde(default)]
+    pub params: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExperimentResponse {
+    pub job_id: String,
+    pub status: JobStatus,
+    /// Written once every variant has finished and been scored.
+    pub report: Option<ExperimentReport>,
+}
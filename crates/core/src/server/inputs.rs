@@ -0,0 +1,95 @@
+//! Source files registered for later jobs.
+//!
+//! A client registers either a local path, which is checked and
+//! canonicalized, or an uploaded file, which is stored under
+//! `<data_dir>/inputs/<id>/<name>`. Either way it gets back a handle,
+//! `input:<id>`, that can stand in for the path in job params. Handles are
+//! replaced by the file path when the job is created.
+//!
+//! Handles of registered paths live until the server restarts; uploaded
+//! files stay in the inputs directory, so their handles keep working.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::model_inspect::sanitize_model_filename;
+use crate::probe::resolve_in_roots;
+
+pub(super) const INPUTS_DIR_NAME: &str = "inputs";
+const INPUT_HANDLE_PREFIX: &str = "input:";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RegisterInputRequest {
+    pub path: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegisteredInput {
+    /// `input:<id>`; pass it wherever a job param takes a path.
+    pub handle: String,
+    pub name: String,
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    /// `true` for uploads, which are kept in the server's inputs directory.
+    pub managed: bool,
+}
+
+impl RegisteredInput {
+    pub(super) fn new(id: &str, path: PathBuf, managed: bool) -> Result<Self> {
+        let size_bytes = fs::metadata(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?
+            .len();
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Ok(Self {
+            handle: format!("{INPUT_HANDLE_PREFIX}{id}"),
+            name,
+            path,
+            size_bytes,
+            managed,
+        })
+    }
+}
+
+/// The id in `value` when it is an input handle. Ids are UUIDs, so a handle
+/// cannot name a path outside the inputs directory.
+pub(super) fn parse_input_handle(value: &str) -> Option<&str> {
+    let id = value.strip_prefix(INPUT_HANDLE_PREFIX)?;
+    uuid::Uuid::parse_str(id).ok().map(|_| id)
+}
+
+/// Canonical path of a local file to register. With `media_roots` set, the
+/// file must lie inside one of them.
+pub(super) fn check_local_input(path: &Path, media_roots: &[PathBuf]) -> Result<PathBuf> {
+    let path = if media_roots.is_empty() {
+        path.canonicalize()
+            .with_context(|| format!("{} does not exist", path.display()))?
+    } else {
+        resolve_in_roots(path, media_roots)?
+    };
+    if !path.is_file() {
+        bail!("{} is not a file", path.display());
+    }
+    Ok(path)
+}
+
+/// Where an upload named `name` is stored.
+pub(super) fn upload_path(inputs_dir: &Path, id: &str, name: &str) -> Result<PathBuf> {
+    let name = name.trim();
+    sanitize_model_filename(name).map_err(|reason| anyhow::anyhow!("invalid name: {reason}"))?;
+    Ok(inputs_dir.join(id).join(name))
+}
+
+/// The uploaded file stored for `id`, if any.
+pub(super) fn find_upload(inputs_dir: &Path, id: &str) -> Option<PathBuf> {
+    fs::read_dir(inputs_dir.join(id))
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|path| path.is_file())
+}
@@ -1,46 +1,135 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::path::{Path as StdPath, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, OnceLock};
 use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
-use axum::extract::{Path, State};
-use axum::http::StatusCode;
+use axum::extract::{DefaultBodyLimit, Path, State};
+use axum::http::{Method, StatusCode};
 use axum::response::{IntoResponse, Response};
 use axum::routing::{any, delete, get, post};
 use axum::{Json, Router};
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
-use tokio::sync::{broadcast, RwLock, Semaphore};
-use tokio_util::sync::CancellationToken;
+use tokio::sync::{broadcast, AcquireError, Notify, OwnedSemaphorePermit, RwLock, Semaphore};
+use tokio_util::sync::{CancellationToken, DropGuard};
 use tower_http::cors::CorsLayer;
 #[cfg(debug_assertions)]
 use tower_http::services::{ServeDir, ServeFile};
-use tracing::{error, info, warn};
+use tracing::{error, info, warn, Instrument};
 use uuid::Uuid;
 
+mod audit;
+mod backup;
+mod chunking;
+mod community_presets;
+mod config_validation;
+mod experiments;
+mod inputs;
+mod model_files;
+mod model_uploads;
 mod persistence;
-
-use crate::config::AppConfig;
+mod support_bundle;
+mod workers;
+mod workflow_bundle;
+mod workflow_versions;
+
+use crate::artifacts::{collect_artifacts, EncodeStats, JobArtifact};
+use crate::benchmark::{AutoModelSource, BenchmarkRecord, BenchmarkStore, AUTO_MODEL};
+use crate::config::{
+    AppConfig, JellyfinServerConfig, JobDispatchMode, JobsConfig, LoggingConfig, PathsConfig,
+    QueueEmptyAction, UpdateChannel,
+};
 use crate::debug_event::NodeDebugValueEvent;
-use crate::descriptor::{all_node_descriptors, NodeDescriptor};
-use crate::executor::SequentialExecutor;
-use crate::graph::PipelineGraph;
-use crate::jellyfin::{ItemQuery, JellyfinClient};
+use crate::descriptor::{all_node_descriptors, localized_node_descriptors, NodeDescriptor};
+use crate::executor::{port_data_from_json, set_default_node_timeout, SequentialExecutor};
+use crate::experiment::{ExperimentReport, VariantResult};
+use crate::graph::{ParamError, PipelineGraph, WorkflowInterface};
+use crate::jellyfin::{ItemQuery, JellyfinClient, MediaStream, ServerCredentials, SkipRule};
+use crate::job_logs::{self, JobLogLine};
+use crate::job_phase::{with_phase_reporter, JobPhase, PhaseReporter};
+use crate::lint::{lint_graph, LintWarning};
 use crate::model_inspect::{self, ModelInspection};
-use crate::model_registry::{ModelEntry, ModelRegistry};
+use crate::model_registry::{ModelEntry, ModelRegistry, ModelType};
+use crate::monitor::{mjpeg_part, JobMonitor, MJPEG_BOUNDARY};
+use crate::node::{NodeDebugSink, ResourceClass};
+use crate::node_error::NodeError;
+use crate::node_i18n::negotiate_locale;
 use crate::nodes::compile_context::VideoCompileContext;
+use crate::nodes::external_command::set_allowed_commands;
+use crate::nodes::jellyfin_video::JellyfinVideoNode;
+use crate::nodes::preview_tap::{FramePreview, FramePreviewSink};
+use crate::nodes::stream_output::{hls_playlist_url, validate_stream_id, HLS_PLAYLIST_NAME};
+use crate::nodes::workflow_io::collect_workflow_outputs;
+use crate::plugin::{loaded_plugins, PluginInfo};
+use crate::post_actions::{job_files, run_post_actions, PostAction, PostActionResult};
+use crate::power::{run_queue_empty_action, QueueWatch, SleepInhibitor};
+use crate::probe::{probe_media, resolve_in_roots, MediaProbe};
 use crate::registry::{register_all_nodes, NodeRegistry};
-use persistence::JobsPersistence;
+use crate::runtime::memory::{set_memory_budget, MemoryBudget};
+use crate::runtime::session_pool::{session_pool, spawn_warmup};
+use crate::secrets::{SecretMetadata, SecretStore};
+use crate::services::NodeServices;
+use crate::step::{StepGate, StepPause, Stepper};
+use crate::streaming_executor::{stage_progress, StageProgress};
+use crate::trace::{self, JobTrace, JobTraceSnapshot};
+use crate::updates::{check_for_updates, UpdateCheck};
+use crate::vapoursynth_export::export_vapoursynth;
+pub use chunking::{ChunkInfo, ChunkedJobResponse, CreateChunkedJobRequest};
+pub use community_presets::{
+    spawn_community_preset_sync, CommunitySyncResponse, PresetChange, PresetUpdate,
+};
+pub use config_validation::{ConfigIssueSeverity, ConfigValidationIssue, ConfigValidationReport};
+pub use experiments::{CreateExperimentRequest, ExperimentResponse, ExperimentVariant};
+pub use inputs::{RegisterInputRequest, RegisteredInput};
+pub use model_files::{ModelDirUsage, ModelDiskUsage, ModelFileUsage, RenameModelRequest};
+pub use model_uploads::{ModelUpload, StartModelUploadRequest};
+use persistence::{JobCursor, JobQuery, JobsPersistence};
+use workers::WorkerRegistry;
+pub use workers::{
+    CompleteWorkerJobRequest, RegisterWorkerRequest, RegisterWorkerResponse, WorkerInfo,
+    WorkerJobAssignment, WorkerJobOutcome, WorkerListResponse, WorkerProgressResponse,
+    WORKER_HEARTBEAT_INTERVAL_SECS,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Preset {
     pub name: String,
     pub description: String,
     pub workflow: serde_json::Value,
+    #[serde(skip)]
+    pub origin: PresetOrigin,
+}
+
+/// Where a preset in the preset list came from.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PresetOrigin {
+    /// A file in `paths.presets_dir`.
+    #[default]
+    Builtin,
+    /// Created through `POST /api/presets`.
+    User,
+    /// Synced from `presets.community_index_url`.
+    Community {
+        source: String,
+        version: Option<String>,
+        author: Option<String>,
+        synced_at: DateTime<Utc>,
+    },
+}
+
+impl PresetOrigin {
+    pub fn version(&self) -> Option<&str> {
+        match self {
+            Self::Community { version, .. } => version.as_deref(),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Serialize)]
@@ -49,6 +138,7 @@ pub struct PresetResponse {
     pub name: String,
     pub description: String,
     pub workflow: serde_json::Value,
+    pub origin: PresetOrigin,
 }
 
 #[derive(Deserialize)]
@@ -66,16 +156,51 @@ pub struct AppState {
 struct AppStateInner {
     jobs: DashMap<String, Job>,
     jobs_persistence: Option<JobsPersistence>,
-    gpu_semaphore: Arc<Semaphore>,
+    /// Job slots per resource class.
+    class_semaphores: HashMap<ResourceClass, Arc<Semaphore>>,
     node_registry: NodeRegistry,
-    model_registry: ModelRegistry,
+    model_registry: std::sync::RwLock<ModelRegistry>,
     progress_senders: DashMap<String, broadcast::Sender<JobWsEvent>>,
     presets: DashMap<String, Preset>,
     config: RwLock<AppConfig>,
     config_path: PathBuf,
+    config_events: broadcast::Sender<ConfigEvent>,
     data_dir: PathBuf,
+    secrets: Arc<SecretStore>,
     preview_sessions: DashMap<String, PathBuf>,
     performance_series: Mutex<VecDeque<RuntimePerformanceSeriesSample>>,
+    /// Set once model discovery has completed for the active models_dir.
+    models_scanned: AtomicBool,
+    /// Set by `--read-only`; `server.read_only` in the config also applies.
+    read_only: AtomicBool,
+    /// Cached successful runtime probes for `/api/health/ready`; failures are
+    /// retried on the next request.
+    ffmpeg_ready: OnceLock<String>,
+    onnxruntime_ready: OnceLock<PathBuf>,
+    workers: WorkerRegistry,
+    /// Sub-jobs spawned for each chunked job, keyed by the parent job id.
+    chunk_plans: DashMap<String, Vec<ChunkSlot>>,
+    /// Variant jobs spawned for each experiment, keyed by the parent job id.
+    experiment_plans: DashMap<String, Vec<String>>,
+    /// Wakes local job tasks waiting on a paused job.
+    jobs_resumed: Notify,
+    job_events: broadcast::Sender<JobFinishedEvent>,
+    /// Local files registered through `/api/inputs/register`, by handle id.
+    registered_inputs: DashMap<String, PathBuf>,
+    /// Execution traces of running local jobs, written to disk when they end.
+    job_traces: DashMap<String, JobTrace>,
+    /// Latest `PreviewTap` frame of each running local job.
+    frame_previews: DashMap<String, FramePreview>,
+    /// Live monitors of running local jobs, see `GET /api/jobs/{id}/monitor`.
+    job_monitors: DashMap<String, JobMonitor>,
+}
+
+#[derive(Clone)]
+struct ChunkSlot {
+    start_secs: f64,
+    end_secs: f64,
+    /// `None` when the chunk was already completed by an interrupted run.
+    job_id: Option<String>,
 }
 
 const PRINT_PREVIEW_THROTTLE_MS: u64 = 150;
@@ -85,7 +210,29 @@ const WORKFLOW_SOURCE_API_RUN_WORKFLOWS: &str = "api_run_workflows";
 const WORKFLOW_SOURCE_API_RUN_PRESETS: &str = "api_run_presets";
 const DEFAULT_WORKFLOW_NAME_API_JOBS: &str = "ad-hoc workflow";
 const DEFAULT_WORKFLOW_NAME_API_BATCH: &str = "batch workflow";
-const RERUN_COMPLETED_REJECTION: &str = "cannot rerun completed job";
+const PROGRESS_HISTORY_SAMPLE_INTERVAL_MS: u64 = 2000;
+const DEFAULT_JOB_LOGS_TAIL: usize = 500;
+const DEFAULT_LOG_FILE_TAIL: usize = 500;
+const MAX_LOG_FILE_TAIL: usize = 20_000;
+const CONFIG_WATCH_INTERVAL_MS: u64 = 2000;
+const MODELS_WATCH_INTERVAL_MS: u64 = 2000;
+const WORKFLOW_SOURCE_API_CHUNKED: &str = "api_chunked";
+const WORKFLOW_SOURCE_CHUNK: &str = "chunk";
+const CHUNK_POLL_INTERVAL_MS: u64 = 1000;
+const WORKFLOW_SOURCE_API_EXPERIMENT: &str = "api_experiment";
+const WORKFLOW_SOURCE_EXPERIMENT_VARIANT: &str = "experiment_variant";
+const DEFAULT_WORKFLOW_NAME_API_EXPERIMENT: &str = "experiment";
+const MAX_EXPERIMENT_VARIANTS: usize = 16;
+const STREAMS_DIR_NAME: &str = "streams";
+/// Per-job scratch directories handed out by `NodeServices::temp_dir`.
+const SCRATCH_DIR_NAME: &str = "scratch";
+const WORKFLOW_SOURCE_API_JELLYFIN_STREAM: &str = "api_jellyfin_stream";
+const MAX_JOBS_PAGE_SIZE: usize = 500;
+const NEXT_CURSOR_HEADER: &str = "x-next-cursor";
+const JOB_RETENTION_INTERVAL_SECS: u64 = 3600;
+const LOG_SIZE_LIMIT_INTERVAL_SECS: u64 = 600;
+const POWER_POLL_INTERVAL_SECS: u64 = 5;
+const QUEUE_EMPTY_GRACE_SECS: u64 = 60;
 
 impl AppState {
     pub fn new(
@@ -134,24 +281,108 @@ impl AppState {
             }
         }
 
+        match config.redaction.compile() {
+            Ok(rules) => crate::logging::set_redaction_rules(rules),
+            Err(err) => {
+                warn!(error = %format!("{err:#}"), "Ignoring invalid redaction rules from config");
+            }
+        }
+        set_allowed_commands(config.commands.allowed.clone());
+        set_default_node_timeout(config.jobs.node_timeout_secs);
+        set_memory_budget(MemoryBudget::from_config(&config.memory));
+        session_pool().set_capacity(config.performance.session_cache_size);
+        spawn_warmup(
+            config.performance.warmup.clone(),
+            config.paths.models_dir.clone(),
+            config.paths.trt_cache_dir.clone(),
+        );
+
+        enforce_log_size_limit(
+            &data_dir.join(crate::logging::DEFAULT_LOG_DIR_NAME),
+            &config.logging,
+        );
+
         Self {
             inner: Arc::new(AppStateInner {
                 jobs,
                 jobs_persistence,
-                gpu_semaphore: Arc::new(Semaphore::new(1)),
+                class_semaphores: class_semaphores(&config.jobs),
                 node_registry,
-                model_registry,
+                model_registry: std::sync::RwLock::new(model_registry),
                 progress_senders: DashMap::new(),
                 presets,
                 config: RwLock::new(config),
                 config_path,
+                config_events: broadcast::channel(16).0,
+                secrets: Arc::new(SecretStore::new(&data_dir)),
                 data_dir,
                 preview_sessions: DashMap::new(),
                 performance_series: Mutex::new(VecDeque::new()),
+                models_scanned: AtomicBool::new(false),
+                read_only: AtomicBool::new(false),
+                ffmpeg_ready: OnceLock::new(),
+                onnxruntime_ready: OnceLock::new(),
+                workers: WorkerRegistry::default(),
+                chunk_plans: DashMap::new(),
+                experiment_plans: DashMap::new(),
+                jobs_resumed: Notify::new(),
+                job_events: broadcast::channel(16).0,
+                registered_inputs: DashMap::new(),
+                job_traces: DashMap::new(),
+                frame_previews: DashMap::new(),
+                job_monitors: DashMap::new(),
             }),
         }
     }
 
+    /// Completed and failed jobs, for embedders such as the desktop app.
+    /// Chunk and experiment sub-jobs are left out; their parent is reported.
+    pub fn subscribe_job_events(&self) -> broadcast::Receiver<JobFinishedEvent> {
+        self.inner.job_events.subscribe()
+    }
+
+    /// Register a local file as `POST /api/inputs/register` does, e.g. for
+    /// files the desktop app was asked to open.
+    pub async fn register_local_input(&self, path: &StdPath) -> Result<RegisteredInput> {
+        let roots = self.inner.config.read().await.paths.media_roots.clone();
+        let path = inputs::check_local_input(path, &roots)?;
+        let id = Uuid::new_v4().to_string();
+        let registered = RegisteredInput::new(&id, path.clone(), false)?;
+        self.inner.registered_inputs.insert(id, path);
+        Ok(registered)
+    }
+
+    /// Look for a newer release on `channel`, or the configured channel.
+    /// Shared with the desktop app, which installs what it finds.
+    pub async fn check_for_updates(&self, channel: Option<UpdateChannel>) -> Result<UpdateCheck> {
+        let config = self.inner.config.read().await.updates.clone();
+        let channel = channel.unwrap_or(config.channel);
+        tokio::task::spawn_blocking(move || {
+            check_for_updates(&config.releases_url, channel, env!("CARGO_PKG_VERSION"))
+        })
+        .await
+        .context("update check task failed")?
+    }
+
+    /// Rebuild the model registry from the model directories, e.g. after
+    /// files were added or removed.
+    pub async fn rescan_models(&self) {
+        let paths = self.inner.config.read().await.paths.clone();
+        match tokio::task::spawn_blocking(move || discover_models(&paths)).await {
+            Ok((model_registry, scanned)) => self.set_model_registry(model_registry, scanned),
+            Err(err) => warn!(error = %err, "Model rescan task failed"),
+        }
+    }
+
+    fn set_model_registry(&self, model_registry: ModelRegistry, scanned: bool) {
+        *self
+            .inner
+            .model_registry
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = model_registry;
+        self.inner.models_scanned.store(scanned, Ordering::Release);
+    }
+
     fn persist_job_snapshot(&self, job: &Job) -> Result<()> {
         if let Some(persistence) = &self.inner.jobs_persistence {
             persistence.upsert_job(job)?;
@@ -159,6 +390,71 @@ impl AppState {
         Ok(())
     }
 
+    /// Make `next` the active config: re-run model discovery, reload presets
+    /// from disk and notify `/api/config/events` subscribers. Returns `false`
+    /// when `next` matches the active config.
+    pub async fn apply_config(&self, next: AppConfig, source: ConfigChangeSource) -> bool {
+        {
+            let mut config = self.inner.config.write().await;
+            if *config == next {
+                return false;
+            }
+            *config = next.clone();
+        }
+
+        match next.redaction.compile() {
+            Ok(rules) => crate::logging::set_redaction_rules(rules),
+            Err(err) => {
+                warn!(error = %format!("{err:#}"), "Ignoring invalid redaction rules from config");
+            }
+        }
+        set_allowed_commands(next.commands.allowed.clone());
+        set_default_node_timeout(next.jobs.node_timeout_secs);
+        set_memory_budget(MemoryBudget::from_config(&next.memory));
+        session_pool().set_capacity(next.performance.session_cache_size);
+        enforce_log_size_limit(&self.log_dir(), &next.logging);
+
+        let (model_registry, scanned) = discover_models(&next.paths);
+        self.set_model_registry(model_registry, scanned);
+
+        // Presets created through the API or synced from the community index
+        // only live in memory; keep those and replace everything that came
+        // from disk.
+        let disk_presets = load_builtin_presets(&next.paths.presets_dir);
+        self.inner.presets.retain(|id, preset| {
+            preset.origin != PresetOrigin::Builtin && !disk_presets.contains_key(id)
+        });
+        for (id, preset) in disk_presets {
+            self.inner.presets.insert(id, preset);
+        }
+
+        info!(?source, "Configuration applied");
+        let _ = self.inner.config_events.send(ConfigEvent::ConfigChanged {
+            source,
+            changed_at: Utc::now(),
+            config: Box::new(next),
+        });
+        true
+    }
+
+    /// Re-read config.toml and apply it if it differs from the active config.
+    pub async fn reload_config_from_disk(&self) -> Result<bool> {
+        let config_path = self.inner.config_path.clone();
+        let next =
+            tokio::task::spawn_blocking(move || AppConfig::load_from_path(&config_path)).await??;
+        Ok(self.apply_config(next, ConfigChangeSource::File).await)
+    }
+
+    /// Keep the server read-only whatever the config says.
+    pub fn force_read_only(&self) {
+        self.inner.read_only.store(true, Ordering::Release);
+    }
+
+    pub async fn is_read_only(&self) -> bool {
+        self.inner.read_only.load(Ordering::Acquire)
+            || self.inner.config.read().await.server.read_only
+    }
+
     /// Resolve workflows_dir relative to process current working directory.
     pub async fn resolve_workflows_dir(&self) -> PathBuf {
         let config = self.inner.config.read().await;
@@ -245,11 +541,30 @@ pub struct Job {
     pub completed_at: Option<DateTime<Utc>>,
     pub progress: Option<ProgressUpdate>,
     pub error: Option<String>,
+    /// Where and why the job failed, when the error came from a node.
+    pub error_details: Option<NodeError>,
     pub cancel_token: CancellationToken,
     pub params: Option<HashMap<String, serde_json::Value>>,
     pub workflow_name: String,
     pub workflow_source: String,
     pub rerun_of_job_id: Option<String>,
+    /// Values collected by the workflow's WorkflowOutput nodes, set once the
+    /// job completes.
+    pub outputs: Option<HashMap<String, serde_json::Value>>,
+    /// User-assigned labels, e.g. a show or season.
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+    /// Results of the workflow's post actions, set once the job completes.
+    pub post_action_results: Vec<PostActionResult>,
+    /// Why the job was skipped instead of run.
+    pub skip_reason: Option<String>,
+    /// When the job was paused; cleared on resume.
+    pub paused_at: Option<DateTime<Utc>>,
+    /// Files the job wrote, set once it completes.
+    pub artifacts: Vec<JobArtifact>,
+    /// Set for jobs run in step mode or with breakpoints; not kept across
+    /// restarts.
+    pub step_gate: Option<StepGate>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -260,14 +575,63 @@ pub enum JobStatus {
     Completed,
     Failed,
     Cancelled,
+    /// Not run because the source already meets the target, see
+    /// [`crate::jellyfin::SkipRule`].
+    Skipped,
+    /// Held in the queue until resumed; only queued jobs can be paused.
+    Paused,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ProgressUpdate {
     pub current_frame: u64,
     pub total_frames: Option<u64>,
     pub fps: f32,
     pub eta_seconds: Option<f64>,
+    /// What the job is doing, e.g. building a TensorRT engine before the
+    /// first frame.
+    #[serde(default)]
+    pub phase: Option<JobPhase>,
+    /// Per-stage frame counts and queue depths of the running pipeline.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stages: Vec<StageProgress>,
+    /// Machine load while the job runs; only sampled with profiling on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hardware: Option<HardwareUsage>,
+}
+
+/// CPU and GPU load sampled during a job, to tell whether it is held back by
+/// inference on the GPU or by decoding and encoding on the CPU.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct HardwareUsage {
+    pub cpu_util_percent: Option<f64>,
+    pub gpu_util_percent: Option<f64>,
+    pub vram_used_bytes: Option<u64>,
+}
+
+/// A sampled progress point persisted for after-the-fact throughput graphs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgressHistoryPoint {
+    pub recorded_at: DateTime<Utc>,
+    pub current_frame: u64,
+    pub total_frames: Option<u64>,
+    pub fps: f32,
+    pub eta_seconds: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hardware: Option<HardwareUsage>,
+}
+
+impl ProgressHistoryPoint {
+    fn from_update(update: &ProgressUpdate, recorded_at: DateTime<Utc>) -> Self {
+        Self {
+            recorded_at,
+            current_frame: update.current_frame,
+            total_frames: update.total_frames,
+            fps: update.fps,
+            eta_seconds: update.eta_seconds,
+            hardware: update.hardware,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -278,6 +642,19 @@ pub enum JobWsEvent {
         total_frames: Option<u64>,
         fps: f32,
         eta_seconds: Option<f64>,
+        #[serde(default)]
+        phase: Option<JobPhase>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        stages: Vec<StageProgress>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        hardware: Option<HardwareUsage>,
+    },
+    Phase {
+        phase: JobPhase,
+    },
+    /// The job was paused, resumed or skipped.
+    Status {
+        status: JobStatus,
     },
     NodeDebugValue {
         node_id: String,
@@ -286,6 +663,30 @@ pub enum JobWsEvent {
         truncated: bool,
         preview_max_chars: usize,
     },
+    Log {
+        timestamp: DateTime<Utc>,
+        level: String,
+        target: String,
+        message: String,
+    },
+    /// The job ran `node_id` in step mode or hit a breakpoint there, and
+    /// waits for `POST /api/jobs/{id}/step`.
+    StepPaused {
+        node_id: String,
+        node_type: String,
+        outputs: BTreeMap<String, String>,
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        frame_previews: BTreeMap<String, String>,
+    },
+    /// A new frame from a `PreviewTap` node, also served at
+    /// `GET /api/jobs/{id}/preview.jpg`.
+    FramePreview {
+        frame_index: u64,
+        width: u32,
+        height: u32,
+        /// `data:image/jpeg;base64,...`
+        image: String,
+    },
 }
 
 impl From<ProgressUpdate> for JobWsEvent {
@@ -295,6 +696,9 @@ impl From<ProgressUpdate> for JobWsEvent {
             total_frames: value.total_frames,
             fps: value.fps,
             eta_seconds: value.eta_seconds,
+            phase: value.phase,
+            stages: value.stages,
+            hardware: value.hardware,
         }
     }
 }
@@ -311,12 +715,111 @@ impl From<NodeDebugValueEvent> for JobWsEvent {
     }
 }
 
+impl From<StepPause> for JobWsEvent {
+    fn from(value: StepPause) -> Self {
+        Self::StepPaused {
+            node_id: value.node_id,
+            node_type: value.node_type,
+            outputs: value.outputs,
+            frame_previews: value.frame_previews,
+        }
+    }
+}
+
+impl From<&FramePreview> for JobWsEvent {
+    fn from(value: &FramePreview) -> Self {
+        use base64::Engine as _;
+
+        Self::FramePreview {
+            frame_index: value.frame_index,
+            width: value.width,
+            height: value.height,
+            image: format!(
+                "data:image/jpeg;base64,{}",
+                base64::engine::general_purpose::STANDARD.encode(&value.jpeg)
+            ),
+        }
+    }
+}
+
+impl From<JobLogLine> for JobWsEvent {
+    fn from(value: JobLogLine) -> Self {
+        Self::Log {
+            timestamp: value.timestamp,
+            level: value.level,
+            target: value.target,
+            message: value.message,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigChangeSource {
+    Api,
+    File,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConfigEvent {
+    ConfigChanged {
+        source: ConfigChangeSource,
+        changed_at: DateTime<Utc>,
+        config: Box<AppConfig>,
+    },
+    /// A community preset sync added, updated or removed presets.
+    PresetsUpdated {
+        changed_at: DateTime<Utc>,
+        updates: Vec<PresetUpdate>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JobFinishedEvent {
+    pub job_id: String,
+    pub workflow_name: String,
+    /// `Completed` or `Failed`.
+    pub status: JobStatus,
+    pub duration_ms: Option<i64>,
+    pub error: Option<String>,
+}
+
 #[derive(Debug)]
 struct NodeDebugEventThrottle {
     window: Duration,
     last_emit_by_node_id: HashMap<String, Instant>,
 }
 
+#[derive(Debug)]
+struct ProgressHistorySampler {
+    interval: Duration,
+    last_sample_at: Option<Instant>,
+}
+
+impl ProgressHistorySampler {
+    fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_sample_at: None,
+        }
+    }
+
+    fn should_sample(&mut self, now: Instant) -> bool {
+        if let Some(last_sample_at) = self.last_sample_at {
+            if now
+                .checked_duration_since(last_sample_at)
+                .is_some_and(|elapsed| elapsed < self.interval)
+            {
+                return false;
+            }
+        }
+
+        self.last_sample_at = Some(now);
+        true
+    }
+}
+
 #[derive(Clone, Copy)]
 struct ProgressFpsBaseline {
     first_frame: u64,
@@ -398,6 +901,15 @@ pub struct CreateJobRequest {
     pub workflow_name: Option<String>,
     #[serde(default)]
     pub params: Option<HashMap<String, serde_json::Value>>,
+    /// Replaces the workflow's own `post_actions` for this job.
+    #[serde(default)]
+    pub post_actions: Option<Vec<PostAction>>,
+    /// Pause after every node until `POST /api/jobs/{id}/step`.
+    #[serde(default)]
+    pub step_mode: bool,
+    /// Ids of nodes to pause after, like `step_mode` but only at these.
+    #[serde(default)]
+    pub breakpoints: Vec<String>,
 }
 
 #[derive(Deserialize)]
@@ -407,6 +919,8 @@ pub struct RunWorkflowRequest {
     pub workflow_name: Option<String>,
     #[serde(default)]
     pub params: Option<HashMap<String, serde_json::Value>>,
+    #[serde(default)]
+    pub post_actions: Option<Vec<PostAction>>,
 }
 
 #[derive(Serialize)]
@@ -425,11 +939,163 @@ pub struct JobResponse {
     pub completed_at: Option<DateTime<Utc>>,
     pub progress: Option<ProgressUpdate>,
     pub error: Option<String>,
+    pub error_details: Option<NodeError>,
     pub workflow_name: String,
     pub workflow_source: String,
     pub params: Option<HashMap<String, serde_json::Value>>,
     pub rerun_of_job_id: Option<String>,
     pub duration_ms: Option<i64>,
+    pub outputs: Option<HashMap<String, serde_json::Value>>,
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+    pub post_actions: Vec<PostActionResult>,
+    pub skip_reason: Option<String>,
+    pub paused_at: Option<DateTime<Utc>>,
+    pub artifacts: Vec<JobArtifact>,
+    pub encode_stats: Option<EncodeStats>,
+    pub step_mode: bool,
+    pub breakpoints: Vec<String>,
+    /// Where a step mode or breakpoint job is paused, with the node's outputs.
+    pub step_paused: Option<StepPause>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ListJobsQuery {
+    /// Comma-separated statuses, e.g. `failed,cancelled`.
+    pub status: Option<String>,
+    pub workflow_source: Option<String>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    /// Free-text search in the workflow name.
+    pub q: Option<String>,
+    /// Only jobs carrying this exact tag.
+    pub tag: Option<String>,
+    /// `desc` (newest first, default) or `asc`.
+    pub order: Option<String>,
+    pub limit: Option<usize>,
+    pub cursor: Option<String>,
+}
+
+impl ListJobsQuery {
+    fn into_job_query(self) -> Result<JobQuery, AppError> {
+        let statuses = parse_status_list(self.status.as_deref())?;
+        let oldest_first = match self.order.as_deref() {
+            None | Some("desc") => false,
+            Some("asc") => true,
+            Some(other) => {
+                return Err(AppError::BadRequest(format!(
+                    "order must be 'asc' or 'desc', got '{other}'"
+                )))
+            }
+        };
+        if self.limit == Some(0) {
+            return Err(AppError::BadRequest("limit must be at least 1".into()));
+        }
+        let cursor = match self.cursor.as_deref() {
+            Some(raw) => Some(
+                JobCursor::decode(raw)
+                    .ok_or_else(|| AppError::BadRequest("invalid cursor".into()))?,
+            ),
+            None => None,
+        };
+
+        Ok(JobQuery {
+            statuses,
+            workflow_source: self.workflow_source.filter(|source| !source.is_empty()),
+            created_after: self.created_after,
+            created_before: self.created_before,
+            search: self
+                .q
+                .map(|q| q.trim().to_string())
+                .filter(|q| !q.is_empty()),
+            tag: self
+                .tag
+                .map(|tag| tag.trim().to_string())
+                .filter(|tag| !tag.is_empty()),
+            oldest_first,
+            limit: self.limit.map(|limit| limit.min(MAX_JOBS_PAGE_SIZE)),
+            cursor,
+        })
+    }
+}
+
+/// Parse a comma-separated `status` query value.
+fn parse_status_list(raw: Option<&str>) -> Result<Vec<JobStatus>, AppError> {
+    raw.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|status| !status.is_empty())
+        .map(|status| {
+            serde_json::from_value::<JobStatus>(serde_json::Value::String(status.to_string()))
+                .map_err(|_| AppError::BadRequest(format!("unknown job status: {status}")))
+        })
+        .collect()
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BulkDeleteJobsQuery {
+    /// Comma-separated finished statuses, e.g. `failed,cancelled`.
+    pub status: Option<String>,
+    pub workflow_source: Option<String>,
+    /// Only jobs created before this instant.
+    pub before: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+pub struct BulkDeleteJobsResponse {
+    pub deleted: usize,
+}
+
+/// Body of `PATCH /api/jobs/{id}`. Omitted fields are left unchanged; an
+/// empty note clears it.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateJobRequest {
+    pub tags: Option<Vec<String>>,
+    pub note: Option<String>,
+}
+
+const MAX_JOB_TAGS: usize = 32;
+const MAX_JOB_TAG_LEN: usize = 64;
+const MAX_JOB_NOTE_LEN: usize = 4096;
+
+/// Trim, drop empty and de-duplicate tags, keeping their first-seen order.
+fn normalize_job_tags(raw: Vec<String>) -> Result<Vec<String>, AppError> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in raw {
+        let tag = tag.trim();
+        if tag.is_empty() || tags.iter().any(|existing| existing == tag) {
+            continue;
+        }
+        if tag.chars().count() > MAX_JOB_TAG_LEN {
+            return Err(AppError::BadRequest(format!(
+                "tag '{tag}' is longer than {MAX_JOB_TAG_LEN} characters"
+            )));
+        }
+        tags.push(tag.to_string());
+    }
+    if tags.len() > MAX_JOB_TAGS {
+        return Err(AppError::BadRequest(format!(
+            "a job can carry at most {MAX_JOB_TAGS} tags"
+        )));
+    }
+    Ok(tags)
+}
+
+#[derive(Serialize)]
+pub struct JobOutputsResponse {
+    pub job_id: String,
+    pub status: JobStatus,
+    pub outputs: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Serialize)]
+pub struct ProgressHistoryResponse {
+    pub job_id: String,
+    pub sample_interval_ms: u64,
+    pub points: Vec<ProgressHistoryPoint>,
 }
 
 #[derive(Deserialize)]
@@ -447,11 +1113,27 @@ pub struct BatchResponse {
 #[derive(Serialize)]
 pub struct HealthResponse {
     pub status: String,
+    pub read_only: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadinessCheck {
+    pub name: String,
+    pub ready: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadinessResponse {
+    pub ready: bool,
+    pub checks: Vec<ReadinessCheck>,
 }
 
 #[derive(Serialize)]
 pub struct ErrorResponse {
     pub error: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub param_errors: Vec<ParamError>,
 }
 
 #[derive(Deserialize)]
@@ -472,6 +1154,41 @@ pub struct FsEntry {
     pub path: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SuggestParamQuery {
+    pub param: String,
+    #[serde(default)]
+    pub prefix: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionKind {
+    Option,
+    Model,
+    Workflow,
+    Dir,
+    File,
+    /// A configured Jellyfin server.
+    Server,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ParamSuggestion {
+    /// What to put in the param.
+    pub value: String,
+    pub label: String,
+    pub kind: SuggestionKind,
+}
+
+const MAX_PARAM_SUGGESTIONS: usize = 200;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProbeRequest {
+    pub path: String,
+}
+
 #[derive(Deserialize)]
 pub struct ExtractFramesRequest {
     pub video_path: String,
@@ -510,6 +1227,43 @@ pub struct SaveWorkflowRequest {
     pub workflow: serde_json::Value,
 }
 
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExportWorkflowRequest {
+    pub workflow: serde_json::Value,
+    #[serde(default)]
+    pub params: Option<HashMap<String, serde_json::Value>>,
+}
+
+#[derive(Serialize)]
+pub struct ExportWorkflowResponse {
+    pub script: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LintWorkflowRequest {
+    pub workflow: serde_json::Value,
+}
+
+#[derive(Serialize)]
+pub struct LintWorkflowResponse {
+    pub warnings: Vec<LintWarning>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ImportWorkflowBundleQuery {
+    /// Download missing models that have a URL; on unless set to `false`.
+    pub download_models: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct ImportWorkflowBundleResponse {
+    /// The imported workflow first, then its sub-workflows.
+    pub workflows: Vec<WorkflowEntry>,
+    pub models: Vec<workflow_bundle::ModelCheck>,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct WorkflowEntry {
     pub filename: String,
@@ -558,9 +1312,34 @@ pub fn app_router(state: AppState) -> Router {
 }
 
 pub fn app_router_with_static(state: AppState, static_dir: Option<&StdPath>) -> Router {
-    let api = Router::new()
+    let api = api_router(state);
+
+    #[cfg(not(debug_assertions))]
+    {
+        let _ = static_dir;
+        api.fallback(embedded_static_handler)
+    }
+    #[cfg(debug_assertions)]
+    {
+        if let Some(dir) = static_dir {
+            let index = dir.join("index.html");
+            let spa = ServeDir::new(dir).fallback(ServeFile::new(index));
+            api.fallback_service(spa)
+        } else {
+            api
+        }
+    }
+}
+
+/// API routes only, without the frontend fallback (used by `--headless`).
+pub fn api_router(state: AppState) -> Router {
+    Router::new()
         .route("/api/health", get(health))
+        .route("/api/health/live", get(health))
+        .route("/api/health/ready", get(health_ready))
         .route("/api/config", get(get_config).put(update_config))
+        .route("/api/config/validate", post(validate_config))
+        .route("/api/config/events", any(config_events_ws))
         .route("/api/performance/current", get(get_performance_current))
         .route("/api/performance/overview", get(get_performance_overview))
         .route("/api/performance/export", get(get_performance_export))
@@ -568,57 +1347,348 @@ pub fn app_router_with_static(state: AppState, static_dir: Option<&StdPath>) ->
             "/api/performance/capabilities",
             get(get_performance_capabilities),
         )
-        .route("/api/jobs", post(create_job).get(list_jobs))
+        .route("/api/logs", get(list_logs))
+        .route("/api/logs/{name}", get(get_log_file))
+        .route("/api/crashes", get(list_crashes))
+        .route("/api/secrets", get(list_secrets).post(put_secret))
+        .route("/api/secrets/{name}", delete(delete_secret))
+        .route("/api/audit", get(list_audit_entries))
+        .route("/api/support-bundle", post(create_support_bundle))
+        .route("/api/maintenance/backup", post(create_backup))
+        .route(
+            "/api/maintenance/restore",
+            post(restore_backup).layer(DefaultBodyLimit::max(backup::MAX_RESTORE_BYTES)),
+        )
+        .route("/api/updates/check", get(check_updates))
+        .route("/api/workers", get(list_workers))
+        .route("/api/workers/register", post(register_worker))
+        .route("/api/workers/{id}/heartbeat", post(worker_heartbeat))
+        .route("/api/workers/{id}/claim", post(claim_worker_job))
+        .route(
+            "/api/workers/{id}/jobs/{job_id}/progress",
+            post(report_worker_progress),
+        )
+        .route(
+            "/api/workers/{id}/jobs/{job_id}/complete",
+            post(complete_worker_job),
+        )
+        .route(
+            "/api/jobs",
+            post(create_job).get(list_jobs).delete(delete_jobs_bulk),
+        )
+        .route("/api/jobs/chunked", post(create_chunked_job))
+        .route("/api/experiments", post(create_experiment))
+        .route("/api/experiments/{id}", get(get_experiment))
         .route("/api/run", post(run_workflow_by_name))
-        .route("/api/jobs/{id}", get(get_job).delete(delete_job_history))
+        .route(
+            "/api/jobs/{id}",
+            get(get_job).patch(update_job).delete(delete_job_history),
+        )
         .route("/api/jobs/{id}/rerun", post(rerun_job))
+        .route("/api/jobs/{id}/pause", post(pause_job))
+        .route("/api/jobs/{id}/resume", post(resume_job))
+        .route("/api/jobs/{id}/step", post(step_job))
+        .route(
+            "/api/jobs/{id}/progress-history",
+            get(get_job_progress_history),
+        )
+        .route("/api/jobs/{id}/logs", get(get_job_logs))
+        .route("/api/jobs/{id}/trace", get(get_job_trace))
+        .route("/api/jobs/{id}/preview.jpg", get(get_job_preview))
+        .route("/api/jobs/{id}/monitor", get(job_monitor_stream))
+        .route("/api/jobs/{id}/outputs", get(get_job_outputs))
+        .route("/api/jobs/{id}/chunks", get(get_job_chunks))
         .route("/api/jobs/{id}/ws", any(job_ws))
         .route("/api/nodes", get(list_nodes))
+        .route("/api/nodes/{node_type}/suggest", get(suggest_param_values))
+        .route("/api/plugins", get(list_plugins))
         .route("/api/models", get(list_models))
+        .route("/api/models/benchmarks", get(list_model_benchmarks))
+        .route("/api/models/usage", get(model_disk_usage))
+        .route("/api/models/upload", post(start_model_upload))
+        .route(
+            "/api/models/upload/{id}",
+            get(get_model_upload)
+                .post(upload_model_chunk)
+                .delete(cancel_model_upload),
+        )
+        .route("/api/models/{filename}", delete(delete_model))
         .route("/api/models/{filename}/inspect", get(inspect_model))
+        .route("/api/models/{filename}/rename", post(rename_model))
         .route("/api/batch", post(create_batch))
         .route("/api/presets", get(list_presets).post(create_preset))
+        .route("/api/presets/sync", post(sync_presets))
         .route("/api/workflows", get(list_workflows).post(save_workflow))
         .route(
             "/api/workflows/{filename}/interface",
             get(get_workflow_interface),
         )
-        .route("/api/workflows/{filename}", delete(delete_workflow))
-        .route("/api/jellyfin/libraries", get(jellyfin_libraries))
-        .route("/api/jellyfin/items", get(jellyfin_items))
-        .route("/api/fs/list", get(list_fs))
-        .route("/api/fs/browse", get(browse_fs))
-        .route("/api/preview/extract", post(extract_frames))
-        .route("/api/preview/process", post(process_frame))
         .route(
-            "/api/preview/frames/{preview_id}/{filename}",
+            "/api/workflows/export/vapoursynth",
+            post(export_workflow_vapoursynth),
+        )
+        .route("/api/workflows/lint", post(lint_workflow))
+        .route("/api/workflows/import", post(import_workflow_bundle))
+        .route(
+            "/api/workflows/{filename}/export",
+            get(export_workflow_bundle),
+        )
+        .route("/api/workflows/{filename}", delete(delete_workflow))
+        .route(
+            "/api/workflows/{filename}/versions",
+            get(list_workflow_versions),
+        )
+        .route(
+            "/api/workflows/{filename}/versions/{version}",
+            get(get_workflow_version),
+        )
+        .route(
+            "/api/workflows/{filename}/versions/{version}/restore",
+            post(restore_workflow_version),
+        )
+        .route("/api/jellyfin/libraries", get(jellyfin_libraries))
+        .route("/api/jellyfin/items", get(jellyfin_items))
+        .route("/api/jellyfin/image/{item_id}", get(jellyfin_image))
+        .route(
+            "/api/jellyfin/items/{item_id}/probe",
+            get(jellyfin_probe_item),
+        )
+        .route("/api/jellyfin/stream", post(start_jellyfin_stream))
+        .route("/api/jellyfin/servers", get(list_jellyfin_servers))
+        .route(
+            "/api/jellyfin/servers/{name}",
+            delete(delete_jellyfin_server).put(put_jellyfin_server),
+        )
+        .route(
+            "/api/jellyfin/servers/{name}/test",
+            post(test_jellyfin_server),
+        )
+        .route("/api/fs/list", get(list_fs))
+        .route("/api/fs/browse", get(browse_fs))
+        .route("/api/probe", post(probe_media_file))
+        .route("/api/inputs/register", post(register_input))
+        .route("/api/preview/extract", post(extract_frames))
+        .route("/api/preview/process", post(process_frame))
+        .route(
+            "/api/streams/{stream_id}/{filename}",
+            get(serve_stream_file),
+        )
+        .route(
+            "/api/preview/frames/{preview_id}/{filename}",
             get(serve_preview_frame),
         )
         .route("/api/{*path}", any(api_route_not_found))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            record_audit_entry,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            reject_writes_when_read_only,
+        ))
         .layer(CorsLayer::permissive())
-        .with_state(state);
+        .with_state(state)
+}
 
-    #[cfg(not(debug_assertions))]
-    {
-        let _ = static_dir;
-        api.fallback(embedded_static_handler)
-    }
-    #[cfg(debug_assertions)]
-    {
-        if let Some(dir) = static_dir {
-            let index = dir.join("index.html");
-            let spa = ServeDir::new(dir).fallback(ServeFile::new(index));
-            api.fallback_service(spa)
-        } else {
-            api
+/// While the server is read-only, only lets through requests that can't
+/// change anything: `GET`, `HEAD`, `OPTIONS` and the routes in
+/// [`allowed_when_read_only`].
+async fn reject_writes_when_read_only(
+    State(state): State<AppState>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> Response {
+    let allowed = request.method().is_safe()
+        || request
+            .extensions()
+            .get::<axum::extract::MatchedPath>()
+            .is_some_and(|matched| allowed_when_read_only(request.method(), matched.as_str()));
+    if !allowed && state.is_read_only().await {
+        return AppError::Forbidden("server is in read-only mode".to_string()).into_response();
+    }
+    next.run(request).await
+}
+
+/// Non-`GET` routes that change no state, plus the remote worker protocol
+/// so already queued jobs still reach workers.
+fn allowed_when_read_only(method: &Method, matched_path: &str) -> bool {
+    matches!(
+        (method.as_str(), matched_path),
+        (
+            "POST",
+            "/api/config/validate"
+                | "/api/workflows/lint"
+                | "/api/workflows/export/vapoursynth"
+                | "/api/probe"
+                | "/api/workers/register"
+                | "/api/workers/{id}/heartbeat"
+                | "/api/workers/{id}/claim"
+                | "/api/workers/{id}/jobs/{job_id}/progress"
+                | "/api/workers/{id}/jobs/{job_id}/complete"
+        )
+    )
+}
+
+/// Records requests that create jobs or change config, workflows or models
+/// in the audit log.
+async fn record_audit_entry(
+    State(state): State<AppState>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> Response {
+    let action = request
+        .extensions()
+        .get::<axum::extract::MatchedPath>()
+        .and_then(|matched| audit::audited_action(request.method(), matched.as_str()));
+    let Some(action) = action else {
+        return next.run(request).await;
+    };
+    let actor = audit::request_actor(request.headers());
+    let method = request.method().to_string();
+    let path = request.uri().path().to_string();
+
+    let response = next.run(request).await;
+    if let Some(persistence) = &state.inner.jobs_persistence {
+        let entry = audit::AuditEntry {
+            seq: 0,
+            recorded_at: Utc::now(),
+            actor,
+            action: action.to_string(),
+            method,
+            path,
+            status: response.status().as_u16(),
+        };
+        if let Err(err) = persistence.append_audit_entry(&entry) {
+            warn!(error = %err, action, "Failed to record audit entry");
         }
     }
+    response
+}
+
+async fn list_audit_entries(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<audit::AuditQuery>,
+) -> Result<Json<Vec<audit::AuditEntry>>, AppError> {
+    let Some(persistence) = &state.inner.jobs_persistence else {
+        return Ok(Json(Vec::new()));
+    };
+    let limit = query
+        .limit
+        .unwrap_or(audit::DEFAULT_AUDIT_LIMIT)
+        .clamp(1, audit::MAX_AUDIT_LIMIT);
+    persistence
+        .load_audit_entries(&query, limit)
+        .map(Json)
+        .map_err(|e| AppError::Internal(format!("failed to read audit log: {e:#}")))
 }
 
-async fn health() -> Json<HealthResponse> {
+async fn health(State(state): State<AppState>) -> Json<HealthResponse> {
     Json(HealthResponse {
         status: "ok".to_string(),
+        read_only: state.is_read_only().await,
+    })
+}
+
+/// Readiness: ffmpeg runs, ONNX Runtime loads and the models directory has
+/// been scanned. Returns 503 until every check passes.
+async fn health_ready(State(state): State<AppState>) -> (StatusCode, Json<ReadinessResponse>) {
+    let probe_state = state.clone();
+    let (ffmpeg, onnxruntime) = tokio::task::spawn_blocking(move || {
+        let ffmpeg = match probe_state.inner.ffmpeg_ready.get() {
+            Some(version) => Ok(version.clone()),
+            None => probe_ffmpeg_version().map(|version| {
+                probe_state
+                    .inner
+                    .ffmpeg_ready
+                    .get_or_init(|| version)
+                    .clone()
+            }),
+        };
+        let onnxruntime = match probe_state.inner.onnxruntime_ready.get() {
+            Some(path) => Ok(path.clone()),
+            None => crate::runtime::probe_ort_runtime().map(|path| {
+                probe_state
+                    .inner
+                    .onnxruntime_ready
+                    .get_or_init(|| path)
+                    .clone()
+            }),
+        };
+        (ffmpeg, onnxruntime)
     })
+    .await
+    .unwrap_or_else(|e| {
+        let message = format!("task join error: {e}");
+        (
+            Err(anyhow::anyhow!(message.clone())),
+            Err(anyhow::anyhow!(message)),
+        )
+    });
+
+    let models_scanned = state.inner.models_scanned.load(Ordering::Acquire);
+    let models_detail = {
+        let models_dir = state.inner.config.read().await.paths.models_dir.clone();
+        let registry = state
+            .inner
+            .model_registry
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if models_scanned {
+            format!(
+                "{} models registered from {}",
+                registry.list().len(),
+                models_dir.display()
+            )
+        } else {
+            format!("models directory not scanned: {}", models_dir.display())
+        }
+    };
+
+    let checks = vec![
+        ReadinessCheck {
+            name: "ffmpeg".to_string(),
+            ready: ffmpeg.is_ok(),
+            detail: ffmpeg.unwrap_or_else(|err| format!("{err:#}")),
+        },
+        ReadinessCheck {
+            name: "onnxruntime".to_string(),
+            ready: onnxruntime.is_ok(),
+            detail: match onnxruntime {
+                Ok(path) => path.display().to_string(),
+                Err(err) => format!("{err:#}"),
+            },
+        },
+        ReadinessCheck {
+            name: "models".to_string(),
+            ready: models_scanned,
+            detail: models_detail,
+        },
+    ];
+
+    let ready = checks.iter().all(|check| check.ready);
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(ReadinessResponse { ready, checks }))
+}
+
+/// First line of `ffmpeg -version`.
+fn probe_ffmpeg_version() -> Result<String> {
+    let output = crate::runtime::command_for("ffmpeg")
+        .arg("-version")
+        .output()
+        .map_err(|err| anyhow::anyhow!("failed to launch ffmpeg: {err}"))?;
+    if !output.status.success() {
+        anyhow::bail!("ffmpeg -version exited with {}", output.status);
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string())
 }
 
 #[derive(Clone)]
@@ -651,6 +1721,9 @@ struct NvidiaSmiGpuSnapshot {
 
 const BYTES_PER_MIB: u64 = 1024 * 1024;
 const PERFORMANCE_EXPORT_RETENTION_SAMPLES: usize = 180;
+/// Spacing of hardware samples taken while a job runs; each one runs
+/// `nvidia-smi`, so this stays well above the progress callback rate.
+const JOB_HARDWARE_SAMPLE_INTERVAL_MS: u64 = 2000;
 static PREVIOUS_CPU_TIMES: OnceLock<Mutex<Option<CpuTimes>>> = OnceLock::new();
 
 fn read_proc_meminfo_kib(key: &str) -> Option<u64> {
@@ -880,6 +1953,37 @@ fn query_nvidia_smi_process_vram_bytes(pid: u32) -> Option<u64> {
     }
 }
 
+pub(crate) fn sample_hardware_usage() -> HardwareUsage {
+    let gpu_snapshot = query_nvidia_smi_gpu_snapshot();
+    HardwareUsage {
+        cpu_util_percent: read_cpu_util_percent(),
+        gpu_util_percent: gpu_snapshot.map(|snapshot| snapshot.gpu_util_percent),
+        vram_used_bytes: gpu_snapshot.map(|snapshot| snapshot.vram_used_bytes),
+    }
+}
+
+/// Keeps `latest` updated with a fresh [`HardwareUsage`] every
+/// [`JOB_HARDWARE_SAMPLE_INTERVAL_MS`] until the returned guard is dropped.
+fn spawn_hardware_sampler(latest: Arc<Mutex<Option<HardwareUsage>>>) -> DropGuard {
+    let stop = CancellationToken::new();
+    let stopped = stop.clone();
+    tokio::spawn(async move {
+        loop {
+            if let Ok(sample) = tokio::task::spawn_blocking(sample_hardware_usage).await {
+                match latest.lock() {
+                    Ok(mut guard) => *guard = Some(sample),
+                    Err(poisoned) => *poisoned.into_inner() = Some(sample),
+                }
+            }
+            tokio::select! {
+                _ = stopped.cancelled() => return,
+                _ = tokio::time::sleep(Duration::from_millis(JOB_HARDWARE_SAMPLE_INTERVAL_MS)) => {}
+            }
+        }
+    });
+    stop.drop_guard()
+}
+
 fn collect_runtime_performance_sample() -> RuntimePerformanceSample {
     let cpu_util_percent = read_cpu_util_percent();
 
@@ -967,6 +2071,12 @@ fn record_runtime_performance_series_sample(
     timestamp_ms: i64,
     sample: &RuntimePerformanceSample,
 ) {
+    if let Some(persistence) = &state.inner.jobs_persistence {
+        if let Err(err) = persistence.append_performance_sample(timestamp_ms, &sample.metrics) {
+            warn!(error = %err, "Failed to persist performance sample");
+        }
+    }
+
     let mut performance_series = match state.inner.performance_series.lock() {
         Ok(guard) => guard,
         Err(err) => {
@@ -985,7 +2095,33 @@ fn record_runtime_performance_series_sample(
     }
 }
 
-fn export_runtime_performance_series_rows(state: &AppState) -> Vec<serde_json::Value> {
+/// Samples in the query's time range, read from the jobs database when there
+/// is one. Without a range only the newest
+/// [`PERFORMANCE_EXPORT_RETENTION_SAMPLES`] are returned.
+fn load_runtime_performance_series(
+    state: &AppState,
+    query: &PerformanceExportQuery,
+) -> Vec<RuntimePerformanceSeriesSample> {
+    let from_ms = query.from.map(|from| from.timestamp_millis());
+    let to_ms = query.to.map(|to| to.timestamp_millis());
+    let limit =
+        (from_ms.is_none() && to_ms.is_none()).then_some(PERFORMANCE_EXPORT_RETENTION_SAMPLES);
+
+    if let Some(persistence) = &state.inner.jobs_persistence {
+        match persistence.load_performance_samples(from_ms, to_ms, limit) {
+            Ok(rows) => {
+                return rows
+                    .into_iter()
+                    .map(|(timestamp_ms, metrics)| RuntimePerformanceSeriesSample {
+                        timestamp_ms,
+                        metrics,
+                    })
+                    .collect();
+            }
+            Err(err) => warn!(error = %err, "Failed to load performance samples"),
+        }
+    }
+
     let performance_series = match state.inner.performance_series.lock() {
         Ok(guard) => guard,
         Err(err) => {
@@ -993,14 +2129,34 @@ fn export_runtime_performance_series_rows(state: &AppState) -> Vec<serde_json::V
             return Vec::new();
         }
     };
+    performance_series
+        .iter()
+        .filter(|sample| {
+            from_ms.is_none_or(|from| sample.timestamp_ms >= from)
+                && to_ms.is_none_or(|to| sample.timestamp_ms <= to)
+        })
+        .cloned()
+        .collect()
+}
 
-    if performance_series.is_empty() {
-        return Vec::new();
+fn export_runtime_performance_series_rows(
+    state: &AppState,
+    query: &PerformanceExportQuery,
+    current: &RuntimePerformanceSample,
+) -> Vec<serde_json::Value> {
+    let mut samples = load_runtime_performance_series(state, query);
+    if samples.is_empty() {
+        // Nothing sampled yet (e.g. right after enabling profiling): show the
+        // current values rather than an empty chart.
+        if query.from.is_some() || query.to.is_some() {
+            return Vec::new();
+        }
+        samples.push(RuntimePerformanceSeriesSample {
+            timestamp_ms: Utc::now().timestamp_millis(),
+            metrics: current.metrics.clone(),
+        });
     }
 
-    let mut samples: Vec<RuntimePerformanceSeriesSample> =
-        performance_series.iter().cloned().collect();
-
     if samples.len() == 1 {
         let only = samples[0].clone();
         samples.insert(
@@ -1117,7 +2273,65 @@ async fn get_performance_overview(State(state): State<AppState>) -> Json<serde_j
     Json(payload)
 }
 
-async fn get_performance_export(State(state): State<AppState>) -> Json<serde_json::Value> {
+#[derive(Debug, Default, Deserialize)]
+pub struct PerformanceExportQuery {
+    /// Only samples taken at or after this instant.
+    pub from: Option<DateTime<Utc>>,
+    /// Only samples taken at or before this instant.
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Sample the performance series every `performance.sample_interval_secs`
+/// while profiling is enabled, and drop samples older than
+/// `performance.series_retention_hours`.
+pub fn spawn_performance_sampler(state: &AppState) -> tokio::task::JoinHandle<()> {
+    let state = state.clone();
+    tokio::spawn(async move {
+        loop {
+            let config = state.inner.config.read().await.performance.clone();
+            if config.profiling_enabled {
+                if let Ok(sample) =
+                    tokio::task::spawn_blocking(collect_runtime_performance_sample).await
+                {
+                    let now = Utc::now();
+                    record_runtime_performance_series_sample(
+                        &state,
+                        now.timestamp_millis(),
+                        &sample,
+                    );
+                    if config.series_retention_hours > 0 {
+                        let retention = chrono::Duration::hours(
+                            config.series_retention_hours.min(24 * 36_500) as i64,
+                        );
+                        prune_runtime_performance_series(&state, now - retention);
+                    }
+                }
+            }
+            tokio::time::sleep(Duration::from_secs(config.sample_interval_secs.max(1))).await;
+        }
+    })
+}
+
+fn prune_runtime_performance_series(state: &AppState, before: DateTime<Utc>) {
+    if let Some(persistence) = &state.inner.jobs_persistence {
+        if let Err(err) = persistence.prune_performance_samples(before.timestamp_millis()) {
+            warn!(error = %err, "Failed to prune performance samples");
+        }
+    }
+}
+
+async fn get_performance_export(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<PerformanceExportQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    if let (Some(from), Some(to)) = (query.from, query.to) {
+        if from > to {
+            return Err(AppError::BadRequest(
+                "`from` must not be later than `to`".to_string(),
+            ));
+        }
+    }
+
     let profiling_enabled = {
         let config = state.inner.config.read().await;
         config.performance.profiling_enabled
@@ -1128,20 +2342,17 @@ async fn get_performance_export(State(state): State<AppState>) -> Json<serde_jso
         if let serde_json::Value::Object(ref mut object) = payload {
             object.insert("series".to_string(), serde_json::json!([]));
         }
-        return Json(payload);
+        return Ok(Json(payload));
     }
 
     let sample = collect_runtime_performance_sample();
-    let now_ms = Utc::now().timestamp_millis();
-    record_runtime_performance_series_sample(&state, now_ms, &sample);
-
     let mut payload = enabled_performance_envelope(&sample);
     if let serde_json::Value::Object(ref mut object) = payload {
-        let series_rows = export_runtime_performance_series_rows(&state);
+        let series_rows = export_runtime_performance_series_rows(&state, &query, &sample);
         object.insert("series".to_string(), serde_json::Value::Array(series_rows));
     }
 
-    Json(payload)
+    Ok(Json(payload))
 }
 
 async fn get_performance_capabilities(State(state): State<AppState>) -> Json<serde_json::Value> {
@@ -1184,426 +2395,677 @@ async fn update_config(
     State(state): State<AppState>,
     Json(payload): Json<AppConfig>,
 ) -> Result<Json<AppConfig>, AppError> {
-    payload.save_to_path(&state.inner.config_path)?;
+    payload
+        .redaction
+        .compile()
+        .map_err(|e| AppError::BadRequest(format!("{e:#}")))?;
 
-    {
-        let mut config = state.inner.config.write().await;
-        *config = payload.clone();
-    }
+    payload.save_to_path(&state.inner.config_path)?;
+    state
+        .apply_config(payload.clone(), ConfigChangeSource::Api)
+        .await;
 
     Ok(Json(payload))
 }
 
-async fn create_job(
-    State(state): State<AppState>,
-    Json(payload): Json<CreateJobRequest>,
-) -> Result<(StatusCode, Json<CreateJobResponse>), AppError> {
-    let workflow_name = payload
-        .workflow_name
-        .as_deref()
-        .map(str::trim)
-        .filter(|name| !name.is_empty())
-        .map(ToOwned::to_owned)
-        .unwrap_or_else(|| {
-            workflow_name_from_request(&payload.workflow, DEFAULT_WORKFLOW_NAME_API_JOBS)
-        });
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LogListResponse {
+    pub log_dir: String,
+    pub total_size_bytes: u64,
+    pub max_total_size_bytes: Option<u64>,
+    pub retention_files: usize,
+    pub files: Vec<crate::logging::LogFileEntry>,
+}
 
-    let inferred_params = extract_workflow_input_params(&payload.workflow);
-    let params = payload.params.or(inferred_params);
+#[derive(Debug, Deserialize)]
+pub struct LogTailQuery {
+    pub tail: Option<usize>,
+}
 
-    let workflow = parse_and_validate_workflow(&state, payload.workflow)?;
-    let created = create_and_spawn_job(
-        &state,
-        workflow,
-        params,
-        workflow_name,
-        WORKFLOW_SOURCE_API_JOBS.to_string(),
-        None,
-    )?;
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LogTailResponse {
+    pub name: String,
+    pub lines: Vec<String>,
+}
 
-    Ok((StatusCode::CREATED, Json(created)))
+impl AppState {
+    fn log_dir(&self) -> PathBuf {
+        self.inner
+            .data_dir
+            .join(crate::logging::DEFAULT_LOG_DIR_NAME)
+    }
 }
 
-async fn run_workflow_by_name(
-    State(state): State<AppState>,
-    Json(payload): Json<RunWorkflowRequest>,
-) -> Result<(StatusCode, Json<CreateJobResponse>), AppError> {
-    let workflow_name = validate_run_workflow_name(payload.workflow_name.as_deref())?;
-    let resolved = resolve_run_workflow_file(&state, &workflow_name).await?;
+async fn list_logs(State(state): State<AppState>) -> Result<Json<LogListResponse>, AppError> {
+    let log_dir = state.log_dir();
+    let logging_config = state.inner.config.read().await.logging.clone();
+
+    let files = crate::logging::list_log_files(&log_dir)
+        .map_err(|e| AppError::Internal(format!("failed to list log files: {e}")))?;
+    let total_size_bytes = files.iter().map(|file| file.size_bytes).sum();
+
+    Ok(Json(LogListResponse {
+        log_dir: log_dir.display().to_string(),
+        total_size_bytes,
+        max_total_size_bytes: logging_config.max_total_size_bytes(),
+        retention_files: logging_config.retention_files,
+        files,
+    }))
+}
 
-    let workflow_document = std::fs::read_to_string(&resolved.path)
-        .map_err(|e| AppError::Internal(format!("failed to read workflow: {e}")))?;
-    let parsed_document: serde_json::Value = serde_json::from_str(&workflow_document)
-        .map_err(|e| AppError::BadRequest(format!("invalid JSON: {e}")))?;
-    let workflow_value = parsed_document
-        .get("workflow")
-        .cloned()
-        .unwrap_or(parsed_document);
+async fn get_log_file(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    axum::extract::Query(params): axum::extract::Query<LogTailQuery>,
+) -> Result<Json<LogTailResponse>, AppError> {
+    if !crate::logging::is_rotated_log_file_name(&name) {
+        return Err(AppError::BadRequest(format!(
+            "invalid log file name: {name}"
+        )));
+    }
 
-    let workflow = parse_and_validate_workflow(&state, workflow_value)?;
-    let created = create_and_spawn_job(
-        &state,
-        workflow,
-        payload.params,
-        workflow_name,
-        resolved.workflow_source.to_string(),
-        None,
-    )?;
+    let tail = params
+        .tail
+        .unwrap_or(DEFAULT_LOG_FILE_TAIL)
+        .min(MAX_LOG_FILE_TAIL);
+    let log_dir = state.log_dir();
+    let file_name = name.clone();
+    let lines = tokio::task::spawn_blocking(move || {
+        crate::logging::read_log_file_tail(&log_dir, &file_name, tail)
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("task join error: {e}")))?
+    .map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => AppError::NotFound(format!("log file not found: {name}")),
+        _ => AppError::Internal(format!("failed to read log file: {e}")),
+    })?;
 
-    Ok((StatusCode::CREATED, Json(created)))
+    Ok(Json(LogTailResponse { name, lines }))
 }
 
-fn parse_and_validate_workflow(
-    state: &AppState,
-    workflow_json: serde_json::Value,
-) -> Result<PipelineGraph, AppError> {
-    let workflow: PipelineGraph =
-        serde_json::from_value(workflow_json).map_err(|e| AppError::BadRequest(e.to_string()))?;
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CrashListResponse {
+    pub crash_dir: String,
+    pub files: Vec<crate::logging::LogFileEntry>,
+}
 
-    workflow
-        .validate(&state.inner.node_registry)
-        .map_err(|e| AppError::BadRequest(format!("workflow validation failed: {e:#}")))?;
+async fn list_crashes(State(state): State<AppState>) -> Result<Json<CrashListResponse>, AppError> {
+    let crash_dir = crate::logging::crash_dir_for(&state.inner.data_dir);
+    let files = crate::logging::list_crash_artifacts(&crash_dir)
+        .map_err(|e| AppError::Internal(format!("failed to list crash artifacts: {e}")))?;
 
-    Ok(workflow)
+    Ok(Json(CrashListResponse {
+        crash_dir: crash_dir.display().to_string(),
+        files,
+    }))
 }
 
-fn create_and_spawn_job(
-    state: &AppState,
-    workflow: PipelineGraph,
-    params: Option<HashMap<String, serde_json::Value>>,
-    workflow_name: String,
-    workflow_source: String,
-    rerun_of_job_id: Option<String>,
-) -> Result<CreateJobResponse, AppError> {
-    let id = Uuid::new_v4().to_string();
-    let now = Utc::now();
-    let cancel_token = CancellationToken::new();
-
-    let (tx, _rx) = broadcast::channel::<JobWsEvent>(64);
-    state.inner.progress_senders.insert(id.clone(), tx);
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct SupportBundleRequest {
+    /// Job whose workflow and log are attached; defaults to the most recent failed job.
+    pub job_id: Option<String>,
+    pub max_log_files: Option<usize>,
+    pub max_crash_files: Option<usize>,
+}
 
-    let job = Job {
-        id: id.clone(),
-        status: JobStatus::Queued,
-        workflow,
-        created_at: now,
-        started_at: None,
-        completed_at: None,
-        progress: None,
-        error: None,
-        cancel_token: cancel_token.clone(),
-        params,
-        workflow_name,
-        workflow_source: workflow_source.clone(),
-        rerun_of_job_id,
-    };
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct UpdateCheckQuery {
+    /// Check this channel instead of the configured one.
+    channel: Option<UpdateChannel>,
+}
 
+async fn check_updates(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<UpdateCheckQuery>,
+) -> Result<Json<UpdateCheck>, AppError> {
     state
-        .persist_job_snapshot(&job)
-        .map_err(|e| AppError::Internal(format!("failed to persist new job: {e:#}")))?;
+        .check_for_updates(query.channel)
+        .await
+        .map(Json)
+        .map_err(|e| AppError::Internal(format!("update check failed: {e:#}")))
+}
 
-    state.inner.jobs.insert(id.clone(), job);
+async fn create_support_bundle(
+    State(state): State<AppState>,
+    payload: Option<Json<SupportBundleRequest>>,
+) -> Result<Response, AppError> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
 
-    let state_clone = state.clone();
-    let job_id = id.clone();
-    tokio::spawn(async move {
-        run_job(state_clone, job_id).await;
-    });
+    let job = match payload.job_id.as_deref() {
+        Some(id) => {
+            let job = state
+                .inner
+                .jobs
+                .get(id)
+                .ok_or_else(|| AppError::NotFound(format!("job not found: {id}")))?;
+            Some((job_to_response(&job), job.workflow.clone()))
+        }
+        None => state
+            .inner
+            .jobs
+            .iter()
+            .filter(|entry| entry.status == JobStatus::Failed)
+            .max_by_key(|entry| entry.completed_at.unwrap_or(entry.created_at))
+            .map(|entry| (job_to_response(&entry), entry.workflow.clone())),
+    };
 
-    info!(job_id = %id, workflow_source, "Job created");
+    let input = support_bundle::SupportBundleInput {
+        data_dir: state.inner.data_dir.clone(),
+        config: state.inner.config.read().await.clone(),
+        job,
+        max_log_files: payload
+            .max_log_files
+            .unwrap_or(support_bundle::DEFAULT_BUNDLE_LOG_FILES)
+            .min(support_bundle::MAX_BUNDLE_FILES),
+        max_crash_files: payload
+            .max_crash_files
+            .unwrap_or(support_bundle::DEFAULT_BUNDLE_CRASH_FILES)
+            .min(support_bundle::MAX_BUNDLE_FILES),
+    };
 
-    Ok(CreateJobResponse {
-        id,
-        status: JobStatus::Queued,
-        created_at: now,
-    })
+    let bytes = tokio::task::spawn_blocking(move || support_bundle::build_support_bundle(&input))
+        .await
+        .map_err(|e| AppError::Internal(format!("task join error: {e}")))?
+        .map_err(|e| AppError::Internal(format!("failed to build support bundle: {e:#}")))?;
+
+    let filename = format!("videnoa-support-{}.zip", Utc::now().format("%Y%m%d-%H%M%S"));
+    Ok((
+        [
+            (
+                axum::http::header::CONTENT_TYPE,
+                "application/zip".to_string(),
+            ),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        bytes,
+    )
+        .into_response())
 }
 
-struct ResolvedWorkflowFile {
-    path: PathBuf,
-    workflow_source: &'static str,
+/// Tarball of the config, jobs db, saved workflows and presets.
+async fn create_backup(State(state): State<AppState>) -> Result<Response, AppError> {
+    let sources = backup::BackupSources {
+        config_path: state.inner.config_path.clone(),
+        jobs_persistence: state.inner.jobs_persistence.clone(),
+        workflows_dir: state.resolve_workflows_dir().await,
+        presets_dir: state.inner.config.read().await.paths.presets_dir.clone(),
+    };
+    let bytes = tokio::task::spawn_blocking(move || backup::build_backup(&sources))
+        .await
+        .map_err(|e| AppError::Internal(format!("task join error: {e}")))?
+        .map_err(|e| AppError::Internal(format!("failed to build backup: {e:#}")))?;
+
+    let filename = format!(
+        "videnoa-backup-{}.tar.gz",
+        Utc::now().format("%Y%m%d-%H%M%S")
+    );
+    Ok((
+        [
+            (
+                axum::http::header::CONTENT_TYPE,
+                "application/gzip".to_string(),
+            ),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+        ],
+        bytes,
+    )
+        .into_response())
 }
 
-async fn resolve_run_workflow_file(
-    state: &AppState,
-    workflow_name: &str,
-) -> Result<ResolvedWorkflowFile, AppError> {
-    let filename = format!("{workflow_name}.json");
-    sanitize_workflow_filename(&filename)?;
+#[derive(Debug, Serialize)]
+pub struct RestoreBackupResponse {
+    pub backup: backup::BackupManifest,
+    pub config_restored: bool,
+    pub workflows: Vec<String>,
+    pub presets: Vec<String>,
+    /// The backup's jobs db replaces the current one when the server next
+    /// starts.
+    pub restart_required: bool,
+}
 
-    let workflows_dir = state.resolve_workflows_dir().await;
-    let workflows_path = workflows_dir.join(&filename);
-    if workflows_path.exists() {
-        return Ok(ResolvedWorkflowFile {
-            path: workflows_path,
-            workflow_source: WORKFLOW_SOURCE_API_RUN_WORKFLOWS,
-        });
+/// Writes a backup's config, workflows and presets in place, keeping the
+/// workflows they replace as versions, and stages its jobs db for the next
+/// start.
+async fn restore_backup(
+    State(state): State<AppState>,
+    body: axum::body::Bytes,
+) -> Result<Json<RestoreBackupResponse>, AppError> {
+    let restored = tokio::task::spawn_blocking(move || backup::read_backup(&body))
+        .await
+        .map_err(|e| AppError::Internal(format!("task join error: {e}")))?
+        .map_err(|e| AppError::BadRequest(format!("invalid backup: {e:#}")))?;
+    for (filename, _) in restored.workflows.iter().chain(&restored.presets) {
+        sanitize_workflow_filename(filename)?;
     }
+    let config = restored
+        .config
+        .as_deref()
+        .map(|raw| {
+            let config = toml::from_str::<AppConfig>(raw)
+                .map_err(|e| AppError::BadRequest(format!("invalid config.toml: {e}")))?;
+            config
+                .redaction
+                .compile()
+                .map_err(|e| AppError::BadRequest(format!("{e:#}")))?;
+            Ok::<_, AppError>((raw, config))
+        })
+        .transpose()?;
 
-    let config = state.inner.config.read().await;
-    let presets_path = config.paths.presets_dir.join(&filename);
-    if presets_path.exists() {
-        return Ok(ResolvedWorkflowFile {
-            path: presets_path,
-            workflow_source: WORKFLOW_SOURCE_API_RUN_PRESETS,
-        });
+    // Apply the config first: it may move the workflows and presets dirs.
+    if let Some((raw, config)) = config {
+        std::fs::write(&state.inner.config_path, raw)
+            .map_err(|e| AppError::Internal(format!("failed to write config file: {e}")))?;
+        state.apply_config(config, ConfigChangeSource::Api).await;
     }
 
-    Err(AppError::NotFound(format!(
-        "workflow not found: {workflow_name}"
-    )))
+    let workflows_dir = state.resolve_workflows_dir().await;
+    std::fs::create_dir_all(&workflows_dir)
+        .map_err(|e| AppError::Internal(format!("failed to create workflows dir: {e}")))?;
+    let keep_versions = state.inner.config.read().await.workflows.keep_versions;
+    for (filename, contents) in &restored.workflows {
+        workflow_versions::snapshot_workflow(&workflows_dir, filename, keep_versions)
+            .map_err(|e| AppError::Internal(format!("failed to keep previous version: {e:#}")))?;
+        std::fs::write(workflows_dir.join(filename), contents)
+            .map_err(|e| AppError::Internal(format!("failed to write workflow file: {e}")))?;
+    }
+
+    if !restored.presets.is_empty() {
+        let presets_dir = state.inner.config.read().await.paths.presets_dir.clone();
+        std::fs::create_dir_all(&presets_dir)
+            .map_err(|e| AppError::Internal(format!("failed to create presets dir: {e}")))?;
+        for (filename, contents) in &restored.presets {
+            std::fs::write(presets_dir.join(filename), contents)
+                .map_err(|e| AppError::Internal(format!("failed to write preset file: {e}")))?;
+        }
+        for (id, preset) in load_builtin_presets(&presets_dir) {
+            state.inner.presets.insert(id, preset);
+        }
+    }
+
+    if let Some(jobs_db) = &restored.jobs_db {
+        let staged = state
+            .inner
+            .data_dir
+            .join(persistence::PENDING_RESTORE_FILE_NAME);
+        std::fs::write(&staged, jobs_db)
+            .map_err(|e| AppError::Internal(format!("failed to stage jobs db: {e}")))?;
+        info!(path = %staged.display(), "Staged jobs db restore for the next start");
+    }
+
+    Ok(Json(RestoreBackupResponse {
+        backup: restored.manifest,
+        config_restored: restored.config.is_some(),
+        workflows: file_names(restored.workflows),
+        presets: file_names(restored.presets),
+        restart_required: restored.jobs_db.is_some(),
+    }))
 }
 
-fn validate_run_workflow_name(raw_name: Option<&str>) -> Result<String, AppError> {
-    let workflow_name = raw_name
-        .map(str::trim)
-        .filter(|name| !name.is_empty())
-        .ok_or_else(|| AppError::BadRequest("workflow_name is required".to_string()))?;
+fn file_names(files: Vec<(String, Vec<u8>)>) -> Vec<String> {
+    files.into_iter().map(|(name, _)| name).collect()
+}
 
-    if workflow_name.ends_with(".json") {
-        return Err(AppError::BadRequest(
-            "workflow_name must not include .json suffix".to_string(),
-        ));
-    }
+#[derive(Debug, Deserialize)]
+pub struct PutSecretRequest {
+    pub name: String,
+    pub value: String,
+}
 
-    if workflow_name.contains('/') || workflow_name.contains('\\') {
-        return Err(AppError::BadRequest(
-            "workflow_name must not contain path separators".to_string(),
-        ));
-    }
+async fn list_secrets(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<SecretMetadata>>, AppError> {
+    let secrets = state
+        .inner
+        .secrets
+        .list()
+        .map_err(|e| AppError::Internal(format!("failed to list secrets: {e:#}")))?;
+    Ok(Json(secrets))
+}
 
-    if workflow_name.contains("..") {
-        return Err(AppError::BadRequest(
-            "workflow_name must not contain '..'".to_string(),
-        ));
-    }
+async fn put_secret(
+    State(state): State<AppState>,
+    Json(payload): Json<PutSecretRequest>,
+) -> Result<(StatusCode, Json<SecretMetadata>), AppError> {
+    crate::secrets::validate_secret_name(&payload.name)
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
 
-    Ok(workflow_name.to_string())
+    let metadata = state
+        .inner
+        .secrets
+        .put(&payload.name, &payload.value)
+        .map_err(|e| AppError::Internal(format!("failed to store secret: {e:#}")))?;
+
+    info!(secret = %metadata.name, "Secret stored");
+    Ok((StatusCode::CREATED, Json(metadata)))
 }
 
-async fn create_batch(
+async fn delete_secret(
     State(state): State<AppState>,
-    Json(payload): Json<BatchRequest>,
-) -> Result<(StatusCode, Json<BatchResponse>), AppError> {
-    if payload.file_paths.is_empty() {
-        return Err(AppError::BadRequest(
-            "file_paths must not be empty".to_string(),
-        ));
+    Path(name): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let removed = state
+        .inner
+        .secrets
+        .delete(&name)
+        .map_err(|e| AppError::Internal(format!("failed to delete secret: {e:#}")))?;
+    if !removed {
+        return Err(AppError::NotFound(format!("secret not found: {name}")));
     }
 
-    let base_workflow: serde_json::Value = payload.workflow;
-    let workflow_name = workflow_name_from_request(&base_workflow, DEFAULT_WORKFLOW_NAME_API_BATCH);
+    info!(secret = %name, "Secret deleted");
+    Ok(StatusCode::NO_CONTENT)
+}
 
-    let mut job_ids = Vec::with_capacity(payload.file_paths.len());
+/// Check the bearer token on worker protocol requests against the secret
+/// named by `workers.token_secret`. Claims hand out resolved secrets, so the
+/// worker endpoints stay closed until a token is configured.
+async fn authorize_worker(
+    state: &AppState,
+    headers: &axum::http::HeaderMap,
+) -> Result<(), AppError> {
+    let token_secret = state.inner.config.read().await.workers.token_secret.clone();
+    let Some(secret_name) = token_secret else {
+        return Err(AppError::Forbidden(
+            "worker endpoints are disabled until workers.token_secret is set".to_string(),
+        ));
+    };
 
-    for file_path in &payload.file_paths {
-        let mut wf = base_workflow.clone();
-        if let Some(nodes) = wf.get_mut("nodes").and_then(|n| n.as_array_mut()) {
-            for node in nodes.iter_mut() {
-                let node_type = node.get("node_type").and_then(|t| t.as_str());
-                match node_type {
-                    Some("VideoInput") => {
-                        if let Some(params) = node.get_mut("params").and_then(|p| p.as_object_mut())
-                        {
-                            params.insert(
-                                "path".to_string(),
-                                serde_json::Value::String(file_path.clone()),
-                            );
-                        }
-                    }
-                    Some("WorkflowInput") => {
-                        if let Some(params) = node.get_mut("params").and_then(|p| p.as_object_mut())
-                        {
-                            if let Some(ports_arr) =
-                                params.get("ports").and_then(|v| v.as_array()).cloned()
-                            {
-                                for port in ports_arr {
-                                    let port_name = port
-                                        .get("name")
-                                        .and_then(|n| n.as_str())
-                                        .unwrap_or_default();
-                                    let port_type = port
-                                        .get("port_type")
-                                        .and_then(|t| t.as_str())
-                                        .unwrap_or_default();
+    let expected = state
+        .inner
+        .secrets
+        .get(&secret_name)
+        .map_err(|e| AppError::Internal(format!("failed to read worker token: {e:#}")))?
+        .ok_or_else(|| {
+            AppError::Forbidden(format!("worker token secret is not set: {secret_name}"))
+        })?;
+    let presented = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default()
+        .trim();
 
-                                    if port_type != "Path" || port_name.is_empty() {
-                                        continue;
-                                    }
+    if !workers::tokens_match(presented, &expected) {
+        return Err(AppError::Forbidden("invalid worker token".to_string()));
+    }
+    Ok(())
+}
 
-                                    let name_lower = port_name.to_lowercase();
-                                    if name_lower.contains("input")
-                                        || name_lower == "input"
-                                        || name_lower == "path"
-                                    {
-                                        params.insert(
-                                            port_name.to_string(),
-                                            serde_json::Value::String(file_path.clone()),
-                                        );
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    _ => {}
-                }
+/// Drop workers that stopped heartbeating and put their jobs back in the queue.
+async fn expire_workers(state: &AppState) {
+    let timeout = Duration::from_secs(
+        state
+            .inner
+            .config
+            .read()
+            .await
+            .workers
+            .heartbeat_timeout_secs,
+    );
+
+    for job_id in state.inner.workers.expire(timeout) {
+        let requeued = state.inner.jobs.get_mut(&job_id).and_then(|mut job| {
+            if job.status != JobStatus::Running {
+                return None;
+            }
+            job.status = JobStatus::Queued;
+            job.started_at = None;
+            job.progress = None;
+            Some(job.clone())
+        });
+
+        if let Some(snapshot) = requeued {
+            warn!(job_id = %job_id, "Remote worker timed out; job returned to the queue");
+            if let Err(err) = state.persist_job_snapshot(&snapshot) {
+                error!(job_id = %job_id, error = ?err, "Failed to persist requeued job");
             }
         }
+    }
+}
 
-        let workflow: PipelineGraph = parse_and_validate_workflow(&state, wf)?;
+async fn list_workers(State(state): State<AppState>) -> Json<WorkerListResponse> {
+    expire_workers(&state).await;
+    Json(state.inner.workers.snapshot())
+}
 
-        let created = create_and_spawn_job(
-            &state,
-            workflow,
-            None,
-            workflow_name.clone(),
-            WORKFLOW_SOURCE_API_BATCH.to_string(),
-            None,
-        )?;
-        let id = created.id;
+async fn register_worker(
+    State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
+    Json(payload): Json<RegisterWorkerRequest>,
+) -> Result<(StatusCode, Json<RegisterWorkerResponse>), AppError> {
+    authorize_worker(&state, &headers).await?;
 
-        info!(job_id = %id, file_path = %file_path, "Batch job created");
-        job_ids.push(id);
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(AppError::BadRequest("worker name is required".to_string()));
     }
 
-    let total = job_ids.len();
-    Ok((StatusCode::CREATED, Json(BatchResponse { job_ids, total })))
-}
-
-async fn list_jobs(State(state): State<AppState>) -> Json<Vec<JobResponse>> {
-    let jobs: Vec<JobResponse> = state
-        .inner
-        .jobs
-        .iter()
-        .map(|entry| job_to_response(entry.value()))
-        .collect();
-    Json(jobs)
+    let worker_id = state.inner.workers.register(name);
+    info!(worker_id = %worker_id, worker_name = %name, "Remote worker registered");
+    Ok((
+        StatusCode::CREATED,
+        Json(RegisterWorkerResponse {
+            worker_id,
+            heartbeat_interval_secs: WORKER_HEARTBEAT_INTERVAL_SECS,
+        }),
+    ))
 }
 
-async fn get_job(
+async fn worker_heartbeat(
     State(state): State<AppState>,
-    Path(id): Path<String>,
-) -> Result<Json<JobResponse>, AppError> {
-    let job = state
-        .inner
-        .jobs
-        .get(&id)
-        .ok_or_else(|| AppError::NotFound(format!("job not found: {id}")))?;
+    Path(worker_id): Path<String>,
+    headers: axum::http::HeaderMap,
+) -> Result<StatusCode, AppError> {
+    authorize_worker(&state, &headers).await?;
+    expire_workers(&state).await;
 
-    Ok(Json(job_to_response(job.value())))
+    if !state.inner.workers.heartbeat(&worker_id) {
+        return Err(AppError::NotFound(format!(
+            "worker not registered: {worker_id}"
+        )));
+    }
+    Ok(StatusCode::NO_CONTENT)
 }
 
-async fn rerun_job(
+/// Hand the oldest remotely dispatched job to a worker, or 204 when the
+/// queue is empty.
+async fn claim_worker_job(
     State(state): State<AppState>,
-    Path(id): Path<String>,
-) -> Result<(StatusCode, Json<CreateJobResponse>), AppError> {
-    let (workflow, params, workflow_name, workflow_source) = {
-        let source_job = state
-            .inner
-            .jobs
-            .get(&id)
-            .ok_or_else(|| AppError::NotFound(format!("job not found: {id}")))?;
-
-        if source_job.status == JobStatus::Completed {
-            return Err(AppError::BadRequest(format!(
-                "{RERUN_COMPLETED_REJECTION}: {id}"
-            )));
-        }
+    Path(worker_id): Path<String>,
+    headers: axum::http::HeaderMap,
+) -> Result<Response, AppError> {
+    authorize_worker(&state, &headers).await?;
+    expire_workers(&state).await;
 
-        (
-            source_job.workflow.clone(),
-            source_job.params.clone(),
-            source_job.workflow_name.clone(),
-            source_job.workflow_source.clone(),
-        )
-    };
+    if !state.inner.workers.heartbeat(&worker_id) {
+        return Err(AppError::NotFound(format!(
+            "worker not registered: {worker_id}"
+        )));
+    }
 
-    let created = create_and_spawn_job(
-        &state,
-        workflow,
-        params,
-        workflow_name,
-        workflow_source,
-        Some(id),
-    )?;
+    loop {
+        let claimed = state.inner.workers.claim_next(&worker_id, |job_id| {
+            state
+                .inner
+                .jobs
+                .get(job_id)
+                .is_some_and(|job| job.status == JobStatus::Queued)
+        });
+        let Some(job_id) = claimed else {
+            return Ok(StatusCode::NO_CONTENT.into_response());
+        };
 
-    Ok((StatusCode::CREATED, Json(created)))
+        let running_snapshot = state.inner.jobs.get_mut(&job_id).map(|mut job| {
+            job.status = JobStatus::Running;
+            job.started_at = Some(Utc::now());
+            job.clone()
+        });
+        let Some(snapshot) = running_snapshot else {
+            state.inner.workers.release(&job_id);
+            continue;
+        };
+        if let Err(err) = state.persist_job_snapshot(&snapshot) {
+            error!(job_id = %job_id, error = ?err, "Failed to persist running transition");
+        }
+
+        // As in run_job, secrets and Jellyfin servers are resolved on the
+        // copy sent to the worker.
+        let mut workflow = snapshot.workflow;
+        let mut params = snapshot.params;
+        let jellyfin_config = state.inner.config.read().await.jellyfin.clone();
+        let secrets_resolved = state
+            .inner
+            .secrets
+            .resolve_graph_refs(&mut workflow)
+            .and_then(|()| match params.as_mut() {
+                Some(params) => params
+                    .values_mut()
+                    .try_for_each(|value| state.inner.secrets.resolve_refs(value)),
+                None => Ok(()),
+            })
+            .and_then(|()| {
+                crate::jellyfin::resolve_server_refs(
+                    &mut workflow,
+                    &jellyfin_config,
+                    &state.inner.secrets,
+                )
+            })
+            .and_then(|()| {
+                crate::nodes::http_request::resolve_auth_secrets(
+                    &mut workflow,
+                    &state.inner.secrets,
+                )
+            });
+        if let Err(err) = secrets_resolved {
+            state.inner.workers.release(&job_id);
+            finish_job(&state, &job_id, Err(JobFailure::from_error(&err)));
+            continue;
+        }
+        match crate::jellyfin::skip_reason(&workflow, params.as_ref()).await {
+            Ok(Some(reason)) => {
+                state.inner.workers.release(&job_id);
+                skip_job(&state, &job_id, reason);
+                continue;
+            }
+            Ok(None) => {}
+            Err(err) => {
+                warn!(job_id = %job_id, error = %format!("{err:#}"), "Could not check Jellyfin skip rules; running the job");
+            }
+        }
+
+        info!(job_id = %job_id, worker_id = %worker_id, "Job claimed by remote worker");
+        return Ok(Json(WorkerJobAssignment {
+            job_id,
+            workflow,
+            params,
+        })
+        .into_response());
+    }
 }
 
-async fn delete_job_history(
+async fn report_worker_progress(
     State(state): State<AppState>,
-    Path(id): Path<String>,
-) -> Result<StatusCode, AppError> {
-    let (job_id, job) = state
-        .inner
-        .jobs
-        .remove(&id)
-        .ok_or_else(|| AppError::NotFound(format!("job not found: {id}")))?;
-
-    if matches!(job.status, JobStatus::Queued | JobStatus::Running) {
-        job.cancel_token.cancel();
+    Path((worker_id, job_id)): Path<(String, String)>,
+    headers: axum::http::HeaderMap,
+    Json(update): Json<ProgressUpdate>,
+) -> Result<Json<WorkerProgressResponse>, AppError> {
+    authorize_worker(&state, &headers).await?;
+    if !state.inner.workers.is_claimed_by(&job_id, &worker_id) {
+        return Err(AppError::NotFound(format!(
+            "job {job_id} is not claimed by worker {worker_id}"
+        )));
     }
+    state.inner.workers.heartbeat(&worker_id);
 
-    let removed_sender = state.inner.progress_senders.remove(&job_id);
+    let cancel = match state.inner.jobs.get_mut(&job_id) {
+        Some(mut job) if job.status != JobStatus::Cancelled => {
+            job.progress = Some(update.clone());
+            false
+        }
+        _ => true,
+    };
 
-    if let Some(persistence) = &state.inner.jobs_persistence {
-        let persisted_deleted_rows = persistence
-            .delete_job(&job_id)
-            .map_err(|e| AppError::Internal(format!("failed to delete job history row: {e:#}")));
+    if !cancel {
+        record_progress_history_point(&state.inner, &job_id, &update);
+        if let Some(tx) = state.inner.progress_senders.get(&job_id) {
+            let _ = tx.send(JobWsEvent::from(update));
+        }
+    }
 
-        let persisted_deleted_rows = match persisted_deleted_rows {
-            Ok(rows) if rows == 1 => rows,
-            Ok(rows) => {
-                state.inner.jobs.insert(job_id.clone(), job.clone());
-                if let Some((sender_id, sender)) = removed_sender {
-                    state.inner.progress_senders.insert(sender_id, sender);
-                }
-                return Err(AppError::Internal(format!(
-                    "expected exactly one persisted row deleted for job {job_id}, deleted {rows}"
-                )));
-            }
-            Err(err) => {
-                state.inner.jobs.insert(job_id.clone(), job.clone());
-                if let Some((sender_id, sender)) = removed_sender {
-                    state.inner.progress_senders.insert(sender_id, sender);
-                }
-                return Err(err);
-            }
-        };
+    Ok(Json(WorkerProgressResponse { cancel }))
+}
 
-        debug_assert_eq!(persisted_deleted_rows, 1);
+async fn complete_worker_job(
+    State(state): State<AppState>,
+    Path((worker_id, job_id)): Path<(String, String)>,
+    headers: axum::http::HeaderMap,
+    Json(payload): Json<CompleteWorkerJobRequest>,
+) -> Result<StatusCode, AppError> {
+    authorize_worker(&state, &headers).await?;
+    if !state.inner.workers.is_claimed_by(&job_id, &worker_id) {
+        return Err(AppError::NotFound(format!(
+            "job {job_id} is not claimed by worker {worker_id}"
+        )));
     }
+    state.inner.workers.release(&job_id);
+    state.inner.workers.heartbeat(&worker_id);
 
-    info!(job_id = %job_id, "Job history row deleted");
+    if payload.outcome == WorkerJobOutcome::Completed {
+        if let Some(mut job) = state.inner.jobs.get_mut(&job_id) {
+            job.outputs = payload.outputs;
+            job.artifacts = payload.artifacts;
+        }
+    }
+    let outcome = match payload.outcome {
+        WorkerJobOutcome::Completed => Ok(()),
+        WorkerJobOutcome::Failed => Err(JobFailure {
+            message: payload
+                .error
+                .unwrap_or_else(|| "remote worker reported a failure".to_string()),
+            details: payload.error_details,
+        }),
+    };
+    info!(job_id = %job_id, worker_id = %worker_id, outcome = ?payload.outcome, "Remote job finished");
+    finish_job(&state, &job_id, outcome);
     Ok(StatusCode::NO_CONTENT)
 }
 
-async fn job_ws(
-    ws: WebSocketUpgrade,
+async fn validate_config(
     State(state): State<AppState>,
-    Path(id): Path<String>,
-) -> Result<Response, AppError> {
-    if !state.inner.jobs.contains_key(&id) {
-        return Err(AppError::NotFound(format!("job not found: {id}")));
-    }
+    Json(payload): Json<AppConfig>,
+) -> Result<Json<ConfigValidationReport>, AppError> {
+    let active = state.inner.config.read().await.clone();
 
-    let rx = state
-        .inner
-        .progress_senders
-        .get(&id)
-        .map(|sender| sender.subscribe())
-        .ok_or_else(|| AppError::NotFound(format!("no progress channel for job: {id}")))?;
+    let report = tokio::task::spawn_blocking(move || {
+        let encoders = config_validation::probe_ffmpeg_encoders().map_err(|err| format!("{err:#}"));
+        config_validation::validate_config(&payload, &active, encoders)
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("task join error: {e}")))?;
+
+    Ok(Json(report))
+}
 
-    Ok(ws.on_upgrade(move |socket| handle_ws(socket, rx)))
+async fn config_events_ws(ws: WebSocketUpgrade, State(state): State<AppState>) -> Response {
+    let rx = state.inner.config_events.subscribe();
+    ws.on_upgrade(move |socket| handle_config_events_ws(socket, rx))
 }
 
-async fn handle_ws(mut socket: WebSocket, mut rx: broadcast::Receiver<JobWsEvent>) {
+async fn handle_config_events_ws(mut socket: WebSocket, mut rx: broadcast::Receiver<ConfigEvent>) {
     loop {
         tokio::select! {
             result = rx.recv() => {
                 match result {
-                    Ok(update) => {
-                        let json = match serde_json::to_string(&update) {
+                    Ok(event) => {
+                        let json = match serde_json::to_string(&event) {
                             Ok(j) => j,
                             Err(_) => break,
                         };
@@ -1612,11 +3074,9 @@ async fn handle_ws(mut socket: WebSocket, mut rx: broadcast::Receiver<JobWsEvent
                         }
                     }
                     Err(broadcast::error::RecvError::Lagged(n)) => {
-                        warn!("WebSocket receiver lagged by {n} messages");
-                    }
-                    Err(broadcast::error::RecvError::Closed) => {
-                        break;
+                        warn!("Config event receiver lagged by {n} messages");
                     }
+                    Err(broadcast::error::RecvError::Closed) => break,
                 }
             }
             msg = socket.recv() => {
@@ -1629,1557 +3089,8043 @@ async fn handle_ws(mut socket: WebSocket, mut rx: broadcast::Receiver<JobWsEvent
     }
 }
 
-async fn list_nodes() -> Json<Vec<NodeDescriptor>> {
-    Json(all_node_descriptors())
-}
+/// Poll config.toml and hot-apply edits made outside the API.
+pub fn spawn_config_watcher(state: &AppState) -> tokio::task::JoinHandle<()> {
+    let state = state.clone();
+    tokio::spawn(async move {
+        let fingerprint = |path: &StdPath| {
+            std::fs::metadata(path)
+                .ok()
+                .map(|meta| (meta.modified().ok(), meta.len()))
+        };
 
-async fn list_models(State(state): State<AppState>) -> Json<Vec<ModelEntry>> {
-    let models = state.inner.model_registry.list().to_vec();
-    Json(models)
-}
+        let mut last_seen = fingerprint(&state.inner.config_path);
+        let mut interval = tokio::time::interval(Duration::from_millis(CONFIG_WATCH_INTERVAL_MS));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
 
-async fn inspect_model(
-    State(state): State<AppState>,
-    Path(filename): Path<String>,
-) -> Result<Json<ModelInspection>, AppError> {
-    model_inspect::sanitize_model_filename(&filename)
-        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+        loop {
+            interval.tick().await;
+            let current = fingerprint(&state.inner.config_path);
+            if current == last_seen {
+                continue;
+            }
+            last_seen = current;
 
-    let config = state.inner.config.read().await;
-    let models_dir = &config.paths.models_dir;
-    let path = models_dir.join(&filename);
+            if let Err(err) = state.reload_config_from_disk().await {
+                warn!(
+                    error = %format!("{err:#}"),
+                    path = %state.inner.config_path.display(),
+                    "Failed to reload config file; keeping the active config"
+                );
+            }
+        }
+    })
+}
 
-    if !path.exists() {
-        return Err(AppError::NotFound(format!("model not found: {filename}")));
+/// Files the model registry is built from, to notice models being added,
+/// removed or replaced.
+fn models_fingerprint(paths: &PathsConfig) -> Vec<(PathBuf, Option<std::time::SystemTime>, u64)> {
+    let mut files = Vec::new();
+    for dir in paths.models_dirs() {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let relevant = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("onnx") || ext == "json");
+            if !relevant {
+                continue;
+            }
+            if let Ok(meta) = entry.metadata() {
+                files.push((path, meta.modified().ok(), meta.len()));
+            }
+        }
     }
-
-    let inspection = tokio::task::spawn_blocking(move || model_inspect::inspect_onnx(&path))
-        .await
-        .map_err(|e| AppError::Internal(format!("task join error: {e}")))?
-        .map_err(|e| AppError::Internal(format!("failed to inspect model: {e}")))?;
-
-    Ok(Json(inspection))
+    files.sort();
+    files
 }
 
-async fn list_presets(State(state): State<AppState>) -> Json<Vec<PresetResponse>> {
-    let presets: Vec<PresetResponse> = state
-        .inner
-        .presets
-        .iter()
-        .map(|entry| PresetResponse {
-            id: entry.key().clone(),
-            name: entry.value().name.clone(),
-            description: entry.value().description.clone(),
-            workflow: entry.value().workflow.clone(),
-        })
-        .collect();
-    Json(presets)
+/// Rescans the model directories when a model or sidecar file changes, so
+/// new models show up in `/api/models` without a restart.
+pub fn spawn_models_watcher(state: &AppState) -> tokio::task::JoinHandle<()> {
+    let state = state.clone();
+    tokio::spawn(async move {
+        let mut last_seen = None;
+        let mut interval = tokio::time::interval(Duration::from_millis(MODELS_WATCH_INTERVAL_MS));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            let paths = state.inner.config.read().await.paths.clone();
+            let current = models_fingerprint(&paths);
+            if last_seen.as_ref() == Some(&current) {
+                continue;
+            }
+            if last_seen.is_some() {
+                info!("Model files changed; rescanning model directories");
+                state.rescan_models().await;
+            }
+            last_seen = Some(current);
+        }
+    })
 }
 
-async fn create_preset(
+async fn create_job(
     State(state): State<AppState>,
-    Json(payload): Json<CreatePresetRequest>,
-) -> (StatusCode, Json<PresetResponse>) {
-    let id = Uuid::new_v4().to_string();
-    let preset = Preset {
-        name: payload.name,
-        description: payload.description,
-        workflow: payload.workflow,
-    };
+    Json(payload): Json<CreateJobRequest>,
+) -> Result<(StatusCode, Json<CreateJobResponse>), AppError> {
+    let workflow_name = payload
+        .workflow_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| {
+            workflow_name_from_request(&payload.workflow, DEFAULT_WORKFLOW_NAME_API_JOBS)
+        });
 
-    let response = PresetResponse {
-        id: id.clone(),
-        name: preset.name.clone(),
-        description: preset.description.clone(),
-        workflow: preset.workflow.clone(),
-    };
+    let inferred_params = extract_workflow_input_params(&payload.workflow);
+    let params = payload.params.or(inferred_params);
 
-    state.inner.presets.insert(id, preset);
+    let mut workflow = parse_and_validate_workflow(&state, payload.workflow)?;
+    if let Some(actions) = payload.post_actions {
+        set_post_actions(&mut workflow, actions)?;
+    }
+    let step_gate = step_gate_for_request(&workflow, payload.step_mode, payload.breakpoints)?;
+    let created = create_and_spawn_job(
+        &state,
+        workflow,
+        params,
+        workflow_name,
+        WORKFLOW_SOURCE_API_JOBS.to_string(),
+        None,
+        step_gate,
+    )
+    .await?;
 
-    (StatusCode::CREATED, Json(response))
+    Ok((StatusCode::CREATED, Json(created)))
 }
 
-// ---------------------------------------------------------------------------
-// Workflow CRUD (user-saved workflows on disk)
-// ---------------------------------------------------------------------------
+async fn run_workflow_by_name(
+    State(state): State<AppState>,
+    Json(payload): Json<RunWorkflowRequest>,
+) -> Result<(StatusCode, Json<CreateJobResponse>), AppError> {
+    let workflow_name = validate_run_workflow_name(payload.workflow_name.as_deref())?;
+    let resolved = resolve_run_workflow_file(&state, &workflow_name).await?;
 
-/// Sanitize a workflow filename: reject path separators, `..`, and empty names.
-fn sanitize_workflow_filename(filename: &str) -> Result<(), AppError> {
-    let trimmed = filename.trim();
-    if trimmed.is_empty() {
-        return Err(AppError::BadRequest("filename must not be empty".into()));
+    let workflow_document = std::fs::read_to_string(&resolved.path)
+        .map_err(|e| AppError::Internal(format!("failed to read workflow: {e}")))?;
+    let parsed_document: serde_json::Value = serde_json::from_str(&workflow_document)
+        .map_err(|e| AppError::BadRequest(format!("invalid JSON: {e}")))?;
+    let workflow_value = parsed_document
+        .get("workflow")
+        .cloned()
+        .unwrap_or(parsed_document);
+
+    let mut workflow = parse_and_validate_workflow(&state, workflow_value)?;
+    if let Some(actions) = payload.post_actions {
+        set_post_actions(&mut workflow, actions)?;
     }
-    if trimmed.contains('/') || trimmed.contains('\\') {
-        return Err(AppError::BadRequest(
-            "filename must not contain path separators".into(),
-        ));
+    let created = create_and_spawn_job(
+        &state,
+        workflow,
+        payload.params,
+        workflow_name,
+        resolved.workflow_source.to_string(),
+        None,
+        None,
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+/// Every node pauses in step mode; otherwise only the breakpoints do, and
+/// each must name a node of `workflow`.
+fn step_gate_for_request(
+    workflow: &PipelineGraph,
+    step_mode: bool,
+    breakpoints: Vec<String>,
+) -> Result<Option<StepGate>, AppError> {
+    if step_mode {
+        return Ok(Some(StepGate::new()));
     }
-    if trimmed.contains("..") {
-        return Err(AppError::BadRequest(
-            "filename must not contain '..'".into(),
-        ));
+    if breakpoints.is_empty() {
+        return Ok(None);
     }
-    Ok(())
+    if let Some(unknown) = breakpoints
+        .iter()
+        .find(|id| !workflow.nodes().any(|node| node.id == **id))
+    {
+        return Err(AppError::BadRequest(format!(
+            "breakpoint '{unknown}' is not a node of the workflow"
+        )));
+    }
+    Ok(Some(StepGate::with_breakpoints(
+        breakpoints.into_iter().collect(),
+    )))
 }
 
-async fn list_workflows(State(state): State<AppState>) -> Json<Vec<WorkflowEntry>> {
-    let dir = state.resolve_workflows_dir().await;
+fn parse_and_validate_workflow(
+    state: &AppState,
+    workflow_json: serde_json::Value,
+) -> Result<PipelineGraph, AppError> {
+    let workflow: PipelineGraph =
+        serde_json::from_value(workflow_json).map_err(|e| AppError::BadRequest(e.to_string()))?;
 
-    let mut entries = Vec::new();
-    if let Ok(read_dir) = std::fs::read_dir(dir) {
-        for entry in read_dir.flatten() {
-            let path = entry.path();
-            if !path.extension().is_some_and(|e| e == "json") {
-                continue;
-            }
-            if let Ok(contents) = std::fs::read_to_string(&path) {
-                if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&contents) {
-                    let name = parsed
-                        .get("name")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or_default()
-                        .to_string();
-                    let description = parsed
-                        .get("description")
-                        .and_then(|v| v.as_str())
-                        .unwrap_or_default()
-                        .to_string();
-                    let workflow = parsed.get("workflow").cloned().unwrap_or_default();
-                    let has_interface = workflow
-                        .get("interface")
-                        .and_then(|i| i.get("inputs"))
-                        .and_then(|arr| arr.as_array())
-                        .is_some_and(|a| !a.is_empty());
-                    let filename = path
-                        .file_name()
-                        .unwrap_or_default()
-                        .to_string_lossy()
-                        .to_string();
-                    entries.push(WorkflowEntry {
-                        filename,
-                        name,
-                        description,
-                        workflow,
-                        has_interface,
-                    });
-                }
-            }
-        }
-    }
-    entries.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-    Json(entries)
+    workflow
+        .validate(&state.inner.node_registry)
+        .map_err(|e| AppError::BadRequest(format!("workflow validation failed: {e:#}")))?;
+
+    Ok(workflow)
 }
 
-async fn save_workflow(
-    State(state): State<AppState>,
-    Json(payload): Json<SaveWorkflowRequest>,
-) -> Result<(StatusCode, Json<WorkflowEntry>), AppError> {
-    let trimmed = payload.name.trim().to_string();
-    if trimmed.is_empty() {
-        return Err(AppError::BadRequest(
-            "workflow name must not be empty".into(),
-        ));
-    }
-    if trimmed.contains('/') || trimmed.contains('\\') {
-        return Err(AppError::BadRequest(
-            "workflow name must not contain path separators".into(),
-        ));
+/// Replace the post actions a job inherits from its workflow.
+fn set_post_actions(
+    workflow: &mut PipelineGraph,
+    actions: Vec<PostAction>,
+) -> Result<(), AppError> {
+    for action in &actions {
+        action
+            .validate()
+            .map_err(|e| AppError::BadRequest(format!("invalid post action: {e:#}")))?;
+    }
+    workflow.post_actions = actions;
+    Ok(())
+}
+
+async fn create_and_spawn_job(
+    state: &AppState,
+    workflow: PipelineGraph,
+    mut params: Option<HashMap<String, serde_json::Value>>,
+    workflow_name: String,
+    workflow_source: String,
+    rerun_of_job_id: Option<String>,
+    step_gate: Option<StepGate>,
+) -> Result<CreateJobResponse, AppError> {
+    if let Some(params) = params.as_mut() {
+        resolve_input_handles(state, params)?;
     }
-    if trimmed.contains("..") {
+    validate_interface_params(&workflow, params.as_mut())?;
+    let remote = state.inner.config.read().await.workers.dispatch == JobDispatchMode::Remote;
+    if remote && step_gate.is_some() {
         return Err(AppError::BadRequest(
-            "workflow name must not contain '..'".into(),
+            "step mode and breakpoints need jobs to run locally; workers.dispatch is remote"
+                .to_string(),
         ));
     }
 
-    let filename = if trimmed.ends_with(".json") {
-        trimmed.clone()
-    } else {
-        format!("{trimmed}.json")
-    };
-
-    sanitize_workflow_filename(&filename)?;
+    let (id, now) = insert_queued_job(
+        state,
+        workflow,
+        params,
+        workflow_name,
+        workflow_source.clone(),
+        rerun_of_job_id,
+        step_gate,
+    )?;
 
-    let dir = state.resolve_workflows_dir().await;
+    if remote {
+        state.inner.workers.enqueue(&id);
+        info!(job_id = %id, workflow_source, "Job queued for a remote worker");
+        return Ok(CreateJobResponse {
+            id,
+            status: JobStatus::Queued,
+            created_at: now,
+        });
+    }
 
-    std::fs::create_dir_all(&dir)
-        .map_err(|e| AppError::Internal(format!("failed to create workflows dir: {e}")))?;
+    spawn_local_job(state, &id);
 
-    let doc = serde_json::json!({
-        "name": trimmed,
-        "description": payload.description,
-        "workflow": payload.workflow,
-    });
+    info!(job_id = %id, workflow_source, "Job created");
 
-    let path = dir.join(&filename);
-    let bytes = serde_json::to_vec_pretty(&doc)
-        .map_err(|e| AppError::Internal(format!("failed to serialize workflow: {e}")))?;
-    std::fs::write(&path, bytes)
-        .map_err(|e| AppError::Internal(format!("failed to write workflow file: {e}")))?;
+    Ok(CreateJobResponse {
+        id,
+        status: JobStatus::Queued,
+        created_at: now,
+    })
+}
 
-    let has_interface = payload
-        .workflow
-        .get("interface")
-        .and_then(|i| i.get("inputs"))
-        .and_then(|arr| arr.as_array())
-        .is_some_and(|a| !a.is_empty());
+/// Run `id` on this machine, capturing its logs while the task lives.
+fn spawn_local_job(state: &AppState, id: &str) {
+    let log_capture = job_logs::job_log_hub().begin_capture(
+        id,
+        Some(&job_logs::job_log_file_path(&state.inner.data_dir, id)),
+    );
+    let state_clone = state.clone();
+    let job_id = id.to_string();
+    let job_span = tracing::info_span!(parent: None, job_logs::JOB_LOG_SPAN_NAME, job_id = %id);
+    tokio::spawn(
+        async move {
+            let _log_capture = log_capture;
+            run_job(state_clone, job_id).await;
+        }
+        .instrument(job_span),
+    );
+}
 
-    Ok((
-        StatusCode::CREATED,
-        Json(WorkflowEntry {
-            filename,
-            name: trimmed,
-            description: payload.description,
-            workflow: payload.workflow,
-            has_interface,
-        }),
-    ))
+/// Coerce params to the workflow interface types and reject missing or
+/// out-of-range values before the job is queued.
+fn validate_interface_params(
+    workflow: &PipelineGraph,
+    params: Option<&mut HashMap<String, serde_json::Value>>,
+) -> Result<(), AppError> {
+    let Some(interface) = &workflow.interface else {
+        return Ok(());
+    };
+    let mut empty = HashMap::new();
+    let errors = interface.validate_params(
+        params.unwrap_or(&mut empty),
+        &workflow.workflow_input_values(),
+    );
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::InvalidParams(errors))
+    }
 }
 
-async fn delete_workflow(
-    State(state): State<AppState>,
-    Path(filename): Path<String>,
-) -> Result<StatusCode, AppError> {
-    sanitize_workflow_filename(&filename)?;
+/// Persist a new queued job and register its progress channel, without
+/// starting it.
+fn insert_queued_job(
+    state: &AppState,
+    workflow: PipelineGraph,
+    params: Option<HashMap<String, serde_json::Value>>,
+    workflow_name: String,
+    workflow_source: String,
+    rerun_of_job_id: Option<String>,
+    step_gate: Option<StepGate>,
+) -> Result<(String, DateTime<Utc>), AppError> {
+    let id = Uuid::new_v4().to_string();
+    let now = Utc::now();
 
-    if !filename.ends_with(".json") {
-        return Err(AppError::BadRequest(
-            "only .json workflow files can be deleted".into(),
-        ));
-    }
+    let (tx, _rx) = broadcast::channel::<JobWsEvent>(64);
+    state.inner.progress_senders.insert(id.clone(), tx);
 
-    let dir = state.resolve_workflows_dir().await;
-    let path = dir.join(&filename);
+    let job = Job {
+        id: id.clone(),
+        status: JobStatus::Queued,
+        workflow,
+        created_at: now,
+        started_at: None,
+        completed_at: None,
+        progress: None,
+        error: None,
+        error_details: None,
+        cancel_token: CancellationToken::new(),
+        params,
+        workflow_name,
+        workflow_source,
+        rerun_of_job_id,
+        outputs: None,
+        tags: Vec::new(),
+        note: None,
+        post_action_results: Vec::new(),
+        skip_reason: None,
+        paused_at: None,
+        artifacts: Vec::new(),
+        step_gate,
+    };
 
-    if !path.exists() {
-        return Err(AppError::NotFound(format!(
-            "workflow not found: {filename}"
-        )));
-    }
+    state
+        .persist_job_snapshot(&job)
+        .map_err(|e| AppError::Internal(format!("failed to persist new job: {e:#}")))?;
 
-    std::fs::remove_file(&path)
-        .map_err(|e| AppError::Internal(format!("failed to delete workflow: {e}")))?;
+    state.inner.jobs.insert(id.clone(), job);
+    Ok((id, now))
+}
 
-    Ok(StatusCode::NO_CONTENT)
+struct ChunkedRunOptions {
+    workflow: PipelineGraph,
+    workflow_name: String,
+    params: HashMap<String, serde_json::Value>,
+    input_param: String,
+    output_param: String,
+    source: PathBuf,
+    output: PathBuf,
+    target_chunk_secs: f64,
+    scene_threshold: f64,
 }
 
-async fn get_workflow_interface(
+/// Split one long source into scene-aligned chunks, run the workflow on each
+/// chunk as its own job (so remote workers can pick them up in parallel) and
+/// stream-copy the results back together.
+async fn create_chunked_job(
     State(state): State<AppState>,
-    Path(filename): Path<String>,
-) -> Result<Json<serde_json::Value>, AppError> {
-    sanitize_workflow_filename(&filename)?;
+    Json(mut payload): Json<CreateChunkedJobRequest>,
+) -> Result<(StatusCode, Json<CreateJobResponse>), AppError> {
+    resolve_input_handles(&state, &mut payload.params)?;
+    let workflow_name = payload
+        .workflow_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| {
+            workflow_name_from_request(&payload.workflow, DEFAULT_WORKFLOW_NAME_API_JOBS)
+        });
+    let input_param = payload.input_param.unwrap_or_else(|| "input".to_string());
+    let output_param = payload.output_param.unwrap_or_else(|| "output".to_string());
 
-    let workflows_dir = state.resolve_workflows_dir().await;
-    let config = state.inner.config.read().await;
-    let workflows_path = workflows_dir.join(&filename);
-    let presets_path = config.paths.presets_dir.join(&filename);
+    let path_param = |name: &str| {
+        payload
+            .params
+            .get(name)
+            .and_then(|value| value.as_str())
+            .filter(|value| !value.trim().is_empty())
+            .map(PathBuf::from)
+            .ok_or_else(|| AppError::BadRequest(format!("params.{name} must be a path string")))
+    };
+    let source = path_param(&input_param)?;
+    let output = path_param(&output_param)?;
+    if !source.is_file() {
+        return Err(AppError::BadRequest(format!(
+            "input file does not exist: {}",
+            source.display()
+        )));
+    }
 
-    let contents = if workflows_path.exists() {
-        std::fs::read_to_string(&workflows_path)
-    } else if presets_path.exists() {
-        std::fs::read_to_string(&presets_path)
-    } else {
-        return Err(AppError::NotFound(format!(
-            "workflow not found: {filename}"
+    let target_chunk_secs = payload
+        .target_chunk_secs
+        .unwrap_or(crate::chunking::DEFAULT_TARGET_CHUNK_SECS);
+    if target_chunk_secs.is_nan() || target_chunk_secs < crate::chunking::MIN_CHUNK_SECS {
+        return Err(AppError::BadRequest(format!(
+            "target_chunk_secs must be at least {}",
+            crate::chunking::MIN_CHUNK_SECS
         )));
-    };
+    }
+    let scene_threshold = payload
+        .scene_threshold
+        .unwrap_or(crate::chunking::DEFAULT_SCENE_THRESHOLD);
+    if !(scene_threshold > 0.0 && scene_threshold <= 1.0) {
+        return Err(AppError::BadRequest(
+            "scene_threshold must be in (0, 1]".to_string(),
+        ));
+    }
 
-    let contents =
-        contents.map_err(|e| AppError::Internal(format!("failed to read workflow: {e}")))?;
-    let parsed: serde_json::Value = serde_json::from_str(&contents)
-        .map_err(|e| AppError::BadRequest(format!("invalid JSON: {e}")))?;
+    let workflow = parse_and_validate_workflow(&state, payload.workflow)?;
+    let (id, now) = insert_queued_job(
+        &state,
+        workflow.clone(),
+        Some(payload.params.clone()),
+        workflow_name.clone(),
+        WORKFLOW_SOURCE_API_CHUNKED.to_string(),
+        None,
+        None,
+    )?;
 
-    let workflow = parsed.get("workflow").unwrap_or(&parsed);
-    let interface = workflow
-        .get("interface")
-        .cloned()
-        .unwrap_or(serde_json::json!({"inputs": [], "outputs": []}));
+    let options = ChunkedRunOptions {
+        workflow,
+        workflow_name,
+        params: payload.params,
+        input_param,
+        output_param,
+        source,
+        output,
+        target_chunk_secs,
+        scene_threshold,
+    };
 
-    Ok(Json(interface))
-}
+    let log_capture = job_logs::job_log_hub().begin_capture(
+        &id,
+        Some(&job_logs::job_log_file_path(&state.inner.data_dir, &id)),
+    );
+    let state_clone = state.clone();
+    let job_id = id.clone();
+    let job_span = tracing::info_span!(parent: None, job_logs::JOB_LOG_SPAN_NAME, job_id = %id);
+    tokio::spawn(
+        async move {
+            let _log_capture = log_capture;
+            run_chunked_job(state_clone, job_id, options).await;
+        }
+        .instrument(job_span),
+    );
 
-async fn list_fs(
-    State(state): State<AppState>,
-    axum::extract::Query(params): axum::extract::Query<FsListQuery>,
-) -> Result<Json<Vec<FsEntry>>, AppError> {
-    let workflows_resolved = state.resolve_workflows_dir().await;
-    let config = state.inner.config.read().await;
+    info!(job_id = %id, "Chunked job created");
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateJobResponse {
+            id,
+            status: JobStatus::Queued,
+            created_at: now,
+        }),
+    ))
+}
 
-    let base_name = params.base.as_deref().unwrap_or("models");
-    let base_dir: PathBuf = match base_name {
-        "models" => config.paths.models_dir.clone(),
-        "presets" => config.paths.presets_dir.clone(),
-        "workflows" => workflows_resolved,
-        _ => {
-            return Err(AppError::Forbidden(format!(
-                "unknown base directory: {base_name}"
-            )));
-        }
+async fn run_chunked_job(state: AppState, job_id: String, options: ChunkedRunOptions) {
+    let Some(snapshot) = mark_job_running(&state, &job_id) else {
+        return;
     };
 
-    if !base_dir.exists() {
-        return Ok(Json(vec![]));
+    let result = run_chunks(&state, &job_id, snapshot.cancel_token, &options).await;
+    if result.is_err() {
+        cancel_chunk_jobs(&state, &job_id);
     }
 
-    let canonical_base = base_dir.canonicalize().map_err(|e| {
-        AppError::Internal(format!(
-            "failed to canonicalize base dir {}: {e}",
-            base_dir.display()
-        ))
-    })?;
-
-    let (list_dir, name_filter) = if let Some(ref prefix) = params.prefix {
-        let joined = canonical_base.join(prefix);
-        if joined.is_dir() {
-            (joined, None)
-        } else {
-            let parent = joined.parent().unwrap_or(&canonical_base).to_path_buf();
-            let filter = joined.file_name().map(|n| n.to_string_lossy().to_string());
-            (parent, filter)
-        }
-    } else {
-        (canonical_base.clone(), None)
+    let outcome = result.map_err(|err| {
+        error!(job_id = %job_id, error = ?err, "Chunked job failed");
+        JobFailure::from_error(&err)
+    });
+    finish_job(&state, &job_id, outcome);
+}
+
+async fn run_chunks(
+    state: &AppState,
+    job_id: &str,
+    cancel_token: CancellationToken,
+    options: &ChunkedRunOptions,
+) -> Result<()> {
+    let mut manifest = {
+        let source = options.source.clone();
+        let output = options.output.clone();
+        let target_chunk_secs = options.target_chunk_secs;
+        let scene_threshold = options.scene_threshold;
+        tokio::task::spawn_blocking(move || {
+            crate::chunking::prepare_chunked_run(
+                &source,
+                &output,
+                target_chunk_secs,
+                scene_threshold,
+            )
+        })
+        .await??
     };
+    let total = manifest.chunks.len();
+    info!(
+        job_id = %job_id,
+        chunks = total,
+        already_completed = manifest.completed_count(),
+        "Source split into chunks"
+    );
 
-    if !list_dir.exists() {
-        return Ok(Json(vec![]));
-    }
+    let mut slots = Vec::with_capacity(total);
+    for chunk in &manifest.chunks {
+        let slot_job_id = if chunk.is_done() {
+            None
+        } else {
+            let mut params = options.params.clone();
+            params.insert(
+                options.input_param.clone(),
+                serde_json::Value::String(chunk.source.to_string_lossy().into_owned()),
+            );
+            params.insert(
+                options.output_param.clone(),
+                serde_json::Value::String(chunk.output.to_string_lossy().into_owned()),
+            );
 
-    let canonical_list = list_dir.canonicalize().map_err(|e| {
-        AppError::Internal(format!(
-            "failed to canonicalize list dir {}: {e}",
-            list_dir.display()
-        ))
-    })?;
+            let created = create_and_spawn_job(
+                state,
+                options.workflow.clone(),
+                Some(params),
+                format!(
+                    "{} [chunk {}/{total}]",
+                    options.workflow_name,
+                    chunk.index + 1
+                ),
+                WORKFLOW_SOURCE_CHUNK.to_string(),
+                None,
+                None,
+            )
+            .await
+            .map_err(|err| anyhow::anyhow!("failed to create chunk job: {err:?}"))?;
+            Some(created.id)
+        };
 
-    // SECURITY: reject paths that escape the sandboxed base directory
-    if !canonical_list.starts_with(&canonical_base) {
-        return Err(AppError::Forbidden("path traversal detected".to_string()));
+        slots.push(ChunkSlot {
+            start_secs: chunk.start_secs,
+            end_secs: chunk.end_secs,
+            job_id: slot_job_id,
+        });
+        state
+            .inner
+            .chunk_plans
+            .insert(job_id.to_string(), slots.clone());
     }
 
-    let read_dir = match std::fs::read_dir(&canonical_list) {
-        Ok(rd) => rd,
-        Err(_) => return Ok(Json(vec![])),
-    };
+    let ws_tx = state
+        .inner
+        .progress_senders
+        .get(job_id)
+        .map(|tx| tx.clone());
+    while manifest.completed_count() < total {
+        tokio::select! {
+            _ = cancel_token.cancelled() => anyhow::bail!("chunked job cancelled"),
+            _ = tokio::time::sleep(Duration::from_millis(CHUNK_POLL_INTERVAL_MS)) => {}
+        }
 
-    let mut entries: Vec<FsEntry> = Vec::new();
-    for entry in read_dir.flatten() {
-        let file_name = entry.file_name().to_string_lossy().to_string();
+        let mut current_frame = 0;
+        let mut total_frames = Some(0);
+        for (index, slot) in slots.iter().enumerate() {
+            let Some(chunk_job_id) = &slot.job_id else {
+                continue;
+            };
+            let Some(job) = state.inner.jobs.get(chunk_job_id) else {
+                anyhow::bail!("chunk {} job was deleted", index + 1);
+            };
+            match job.status {
+                JobStatus::Completed => {
+                    if !manifest.chunks[index].completed {
+                        manifest.mark_completed(index)?;
+                    }
+                }
+                JobStatus::Failed | JobStatus::Cancelled | JobStatus::Skipped => anyhow::bail!(
+                    "chunk {} failed: {}",
+                    index + 1,
+                    job.error
+                        .as_deref()
+                        .or(job.skip_reason.as_deref())
+                        .unwrap_or("cancelled")
+                ),
+                JobStatus::Queued | JobStatus::Running | JobStatus::Paused => {}
+            }
+            let progress = job.progress.as_ref();
+            current_frame += progress.map_or(0, |p| p.current_frame);
+            total_frames = total_frames
+                .zip(progress.and_then(|p| p.total_frames))
+                .map(|(sum, frames)| sum + frames);
+        }
 
-        if file_name.starts_with('.') {
-            continue;
+        let update = ProgressUpdate {
+            current_frame,
+            total_frames,
+            fps: 0.0,
+            eta_seconds: None,
+            phase: Some(JobPhase::Processing),
+            stages: Vec::new(),
+            hardware: None,
+        };
+        if let Some(mut job) = state.inner.jobs.get_mut(job_id) {
+            job.progress = Some(update.clone());
+        }
+        if let Some(tx) = &ws_tx {
+            let _ = tx.send(JobWsEvent::from(update));
         }
+    }
 
-        if let Some(ref filter) = name_filter {
-            if !file_name.starts_with(filter.as_str()) {
-                continue;
+    job_phase_reporter(Arc::clone(&state.inner), job_id.to_string(), ws_tx)(JobPhase::Muxing);
+    tokio::task::spawn_blocking(move || crate::chunking::finalize_chunked_run(&manifest)).await??;
+    info!(job_id = %job_id, output = %options.output.display(), "Chunks concatenated");
+    Ok(())
+}
+
+/// Records phase changes of `job_id` in its progress and announces them to
+/// websocket subscribers.
+fn job_phase_reporter(
+    inner: Arc<AppStateInner>,
+    job_id: String,
+    ws_tx: Option<broadcast::Sender<JobWsEvent>>,
+) -> PhaseReporter {
+    Arc::new(move |phase| {
+        {
+            let Some(mut job) = inner.jobs.get_mut(&job_id) else {
+                return;
+            };
+            let progress = job.progress.get_or_insert_with(ProgressUpdate::default);
+            if progress.phase == Some(phase) {
+                return;
             }
+            progress.phase = Some(phase);
         }
+        info!(job_id = %job_id, ?phase, "Job phase changed");
+        if let Some(tx) = &ws_tx {
+            let _ = tx.send(JobWsEvent::Phase { phase });
+        }
+    })
+}
 
-        let is_dir = entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
+/// Forwards events nodes emit while running (e.g. download progress) to
+/// websocket subscribers. Nodes rate-limit these themselves.
+fn job_debug_sink(ws_tx: Option<broadcast::Sender<JobWsEvent>>) -> NodeDebugSink {
+    Arc::new(move |event| {
+        if let Some(tx) = &ws_tx {
+            let _ = tx.send(JobWsEvent::from(event));
+        }
+    })
+}
 
-        let rel = canonical_list
-            .join(&file_name)
-            .strip_prefix(&canonical_base)
-            .unwrap_or(StdPath::new(&file_name))
-            .to_string_lossy()
-            .to_string();
+/// Move a parent job (chunked run or experiment) to running and persist it.
+fn mark_job_running(state: &AppState, job_id: &str) -> Option<Job> {
+    let snapshot = state.inner.jobs.get_mut(job_id).map(|mut job| {
+        job.status = JobStatus::Running;
+        job.started_at = Some(Utc::now());
+        job.clone()
+    })?;
+    if let Err(err) = state.persist_job_snapshot(&snapshot) {
+        error!(job_id = %job_id, error = ?err, "Failed to persist running transition");
+    }
+    Some(snapshot)
+}
 
-        let display_path = format!("{base_name}/{rel}");
+/// Cancel any chunk jobs of `parent_id` that have not finished yet.
+fn cancel_chunk_jobs(state: &AppState, parent_id: &str) {
+    let Some(slots) = state.inner.chunk_plans.get(parent_id).map(|s| s.clone()) else {
+        return;
+    };
+    cancel_child_jobs(state, slots.into_iter().filter_map(|slot| slot.job_id));
+}
 
-        entries.push(FsEntry {
-            name: file_name,
-            is_dir,
-            path: display_path,
+/// Cancel the given sub-jobs unless they already finished.
+fn cancel_child_jobs(state: &AppState, job_ids: impl IntoIterator<Item = String>) {
+    for child_job_id in job_ids {
+        let cancelled = state.inner.jobs.get_mut(&child_job_id).and_then(|mut job| {
+            if !matches!(
+                job.status,
+                JobStatus::Queued | JobStatus::Running | JobStatus::Paused
+            ) {
+                return None;
+            }
+            job.cancel_token.cancel();
+            job.status = JobStatus::Cancelled;
+            job.completed_at = Some(Utc::now());
+            Some(job.clone())
         });
+        if let Some(snapshot) = cancelled {
+            if let Err(err) = state.persist_job_snapshot(&snapshot) {
+                error!(job_id = %child_job_id, error = ?err, "Failed to persist cancelled sub-job");
+            }
+        }
     }
+}
 
-    entries.sort_by(|a, b| {
-        b.is_dir
-            .cmp(&a.is_dir)
-            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
-    });
+async fn get_job_chunks(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ChunkedJobResponse>, AppError> {
+    let slots = match state.inner.chunk_plans.get(&id) {
+        Some(slots) => slots.clone(),
+        None => {
+            let is_chunked = state
+                .inner
+                .jobs
+                .get(&id)
+                .is_some_and(|job| job.workflow_source == WORKFLOW_SOURCE_API_CHUNKED);
+            if !is_chunked {
+                return Err(AppError::NotFound(format!("chunked job not found: {id}")));
+            }
+            Vec::new()
+        }
+    };
 
-    Ok(Json(entries))
+    let chunks = slots
+        .into_iter()
+        .enumerate()
+        .map(|(index, slot)| ChunkInfo {
+            index,
+            start_secs: slot.start_secs,
+            end_secs: slot.end_secs,
+            // Chunks finished by an earlier, interrupted run have no job.
+            status: slot
+                .job_id
+                .as_ref()
+                .map_or(JobStatus::Completed, |chunk_job_id| {
+                    state
+                        .inner
+                        .jobs
+                        .get(chunk_job_id)
+                        .map_or(JobStatus::Cancelled, |job| job.status)
+                }),
+            job_id: slot.job_id,
+        })
+        .collect();
+
+    Ok(Json(ChunkedJobResponse { job_id: id, chunks }))
 }
 
-async fn browse_fs(
-    axum::extract::Query(params): axum::extract::Query<FsBrowseQuery>,
-) -> Result<Json<Vec<FsEntry>>, AppError> {
-    let raw_path = params
-        .path
-        .as_deref()
-        .map(str::trim)
-        .filter(|s| !s.is_empty())
-        .unwrap_or(".");
+struct ExperimentPlan {
+    source: PathBuf,
+    name: String,
+    input_param: String,
+    output_param: String,
+    variants: Vec<PlannedVariant>,
+}
 
-    let resolved_path = if raw_path.starts_with('~') {
-        #[cfg(unix)]
-        let home = std::env::var("HOME").unwrap_or_default();
-        #[cfg(windows)]
-        let home = std::env::var("USERPROFILE").unwrap_or_default();
-        format!("{home}{}", &raw_path[1..])
-    } else {
-        raw_path.to_string()
-    };
+struct PlannedVariant {
+    name: String,
+    workflow: PipelineGraph,
+    params: HashMap<String, serde_json::Value>,
+}
 
-    let browse_dir = PathBuf::from(resolved_path);
-    if !browse_dir.exists() || !browse_dir.is_dir() {
-        return Ok(Json(vec![]));
+/// Run one source through several model or workflow variants as separate
+/// jobs, then score every output with VMAF and write a comparison report.
+async fn create_experiment(
+    State(state): State<AppState>,
+    Json(payload): Json<CreateExperimentRequest>,
+) -> Result<(StatusCode, Json<CreateJobResponse>), AppError> {
+    let source = match resolve_input_handle(&state, payload.source.trim())? {
+        Some(path) => path,
+        None => PathBuf::from(payload.source.trim()),
+    };
+    if !source.is_file() {
+        return Err(AppError::BadRequest(format!(
+            "source file does not exist: {}",
+            source.display()
+        )));
+    }
+    if payload.variants.is_empty() || payload.variants.len() > MAX_EXPERIMENT_VARIANTS {
+        return Err(AppError::BadRequest(format!(
+            "variants must list between 1 and {MAX_EXPERIMENT_VARIANTS} entries"
+        )));
     }
 
-    let canonical_browse = browse_dir.canonicalize().map_err(|e| {
-        AppError::Internal(format!(
-            "failed to canonicalize browse dir {}: {e}",
-            browse_dir.display()
-        ))
-    })?;
-
-    #[cfg(unix)]
-    {
-        if canonical_browse.starts_with(StdPath::new("/proc"))
-            || canonical_browse.starts_with(StdPath::new("/sys"))
-            || canonical_browse.starts_with(StdPath::new("/dev"))
-        {
-            return Err(AppError::Forbidden(
-                "browsing this directory is not allowed".to_string(),
+    let mut names = HashSet::new();
+    let mut variants = Vec::with_capacity(payload.variants.len());
+    for variant in payload.variants {
+        let name = variant.name.trim().to_string();
+        if name.is_empty() {
+            return Err(AppError::BadRequest(
+                "variant names must not be empty".to_string(),
             ));
         }
+        if !names.insert(name.clone()) {
+            return Err(AppError::BadRequest(format!(
+                "duplicate variant name: {name}"
+            )));
+        }
+        let mut workflow_json = variant
+            .workflow
+            .or_else(|| payload.workflow.clone())
+            .ok_or_else(|| {
+                AppError::BadRequest(format!(
+                    "variant {name} has no workflow and the request sets none"
+                ))
+            })?;
+        if let Some(model_path) = &variant.model_path {
+            crate::experiment::override_model_path(&mut workflow_json, model_path)
+                .map_err(|e| AppError::BadRequest(format!("variant {name}: {e}")))?;
+        }
+        let workflow = parse_and_validate_workflow(&state, workflow_json)?;
+        let mut params = payload.params.clone();
+        params.extend(variant.params);
+        variants.push(PlannedVariant {
+            name,
+            workflow,
+            params,
+        });
     }
 
-    let read_dir = match std::fs::read_dir(&canonical_browse) {
-        Ok(rd) => rd,
-        Err(_) => return Ok(Json(vec![])),
-    };
+    let name = payload
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| DEFAULT_WORKFLOW_NAME_API_EXPERIMENT.to_string());
+    let input_param = payload.input_param.unwrap_or_else(|| "input".to_string());
+    let mut parent_params = payload.params;
+    parent_params.insert(
+        input_param.clone(),
+        serde_json::Value::String(source.to_string_lossy().into_owned()),
+    );
+    let (id, now) = insert_queued_job(
+        &state,
+        variants[0].workflow.clone(),
+        Some(parent_params),
+        name.clone(),
+        WORKFLOW_SOURCE_API_EXPERIMENT.to_string(),
+        None,
+        None,
+    )?;
 
-    let mut entries: Vec<FsEntry> = Vec::new();
-    for entry in read_dir.flatten() {
-        let file_name = entry.file_name().to_string_lossy().to_string();
+    let plan = ExperimentPlan {
+        source,
+        name,
+        input_param,
+        output_param: payload.output_param.unwrap_or_else(|| "output".to_string()),
+        variants,
+    };
 
-        if file_name.starts_with('.') {
-            continue;
+    let log_capture = job_logs::job_log_hub().begin_capture(
+        &id,
+        Some(&job_logs::job_log_file_path(&state.inner.data_dir, &id)),
+    );
+    let state_clone = state.clone();
+    let job_id = id.clone();
+    let job_span = tracing::info_span!(parent: None, job_logs::JOB_LOG_SPAN_NAME, job_id = %id);
+    tokio::spawn(
+        async move {
+            let _log_capture = log_capture;
+            run_experiment(state_clone, job_id, plan).await;
         }
+        .instrument(job_span),
+    );
 
-        let canonical_entry = match entry.path().canonicalize() {
-            Ok(path) => path,
-            Err(_) => continue,
-        };
+    info!(job_id = %id, "Experiment created");
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateJobResponse {
+            id,
+            status: JobStatus::Queued,
+            created_at: now,
+        }),
+    ))
+}
 
-        let is_dir = entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
+async fn run_experiment(state: AppState, job_id: String, plan: ExperimentPlan) {
+    let Some(snapshot) = mark_job_running(&state, &job_id) else {
+        return;
+    };
 
-        entries.push(FsEntry {
-            name: file_name,
-            is_dir,
-            path: canonical_entry.to_string_lossy().to_string(),
-        });
+    let result = run_variants(&state, &job_id, snapshot.cancel_token, &plan).await;
+    if result.is_err() {
+        cancel_experiment_jobs(&state, &job_id);
     }
 
-    entries.sort_by(|a, b| {
-        b.is_dir
-            .cmp(&a.is_dir)
-            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    let outcome = result.map_err(|err| {
+        error!(job_id = %job_id, error = ?err, "Experiment failed");
+        JobFailure::from_error(&err)
     });
-    entries.truncate(200);
-
-    Ok(Json(entries))
+    finish_job(&state, &job_id, outcome);
 }
 
-async fn extract_frames(
-    State(state): State<AppState>,
-    Json(payload): Json<ExtractFramesRequest>,
-) -> Result<(StatusCode, Json<ExtractFramesResponse>), AppError> {
-    if payload.count == 0 || payload.count > 100 {
-        return Err(AppError::BadRequest(
-            "count must be between 1 and 100".to_string(),
-        ));
+async fn run_variants(
+    state: &AppState,
+    job_id: &str,
+    cancel_token: CancellationToken,
+    plan: &ExperimentPlan,
+) -> Result<()> {
+    let dir = crate::experiment::experiment_dir(&state.inner.data_dir, job_id);
+    std::fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+
+    let mut variant_jobs = Vec::with_capacity(plan.variants.len());
+    for (index, variant) in plan.variants.iter().enumerate() {
+        let output = dir.join(crate::experiment::variant_output_name(index, &variant.name));
+        let mut params = variant.params.clone();
+        params.insert(
+            plan.input_param.clone(),
+            serde_json::Value::String(plan.source.to_string_lossy().into_owned()),
+        );
+        params.insert(
+            plan.output_param.clone(),
+            serde_json::Value::String(output.to_string_lossy().into_owned()),
+        );
+        let created = create_and_spawn_job(
+            state,
+            variant.workflow.clone(),
+            Some(params),
+            format!("{} [{}]", plan.name, variant.name),
+            WORKFLOW_SOURCE_EXPERIMENT_VARIANT.to_string(),
+            None,
+            None,
+        )
+        .await
+        .map_err(|err| anyhow::anyhow!("failed to create job for {}: {err:?}", variant.name))?;
+        variant_jobs.push((created.id, output));
+        state.inner.experiment_plans.insert(
+            job_id.to_string(),
+            variant_jobs.iter().map(|(id, _)| id.clone()).collect(),
+        );
     }
 
-    let video_path = StdPath::new(&payload.video_path);
-    if !video_path.exists() {
-        return Err(AppError::BadRequest(format!(
-            "video file not found: {}",
-            payload.video_path
-        )));
+    loop {
+        tokio::select! {
+            _ = cancel_token.cancelled() => anyhow::bail!("experiment cancelled"),
+            _ = tokio::time::sleep(Duration::from_millis(CHUNK_POLL_INTERVAL_MS)) => {}
+        }
+        let finished = variant_jobs.iter().all(|(variant_job_id, _)| {
+            state
+                .inner
+                .jobs
+                .get(variant_job_id)
+                .is_none_or(|job| is_terminal(job.status))
+        });
+        if finished {
+            break;
+        }
     }
 
-    let preview_id = Uuid::new_v4().to_string();
-    let temp_dir = std::env::temp_dir().join(format!("videnoa-preview-{preview_id}"));
-    std::fs::create_dir_all(&temp_dir)
-        .map_err(|e| AppError::Internal(format!("failed to create temp dir: {e}")))?;
-
-    let probe = crate::runtime::command_for("ffprobe")
-        .args([
-            "-v",
-            "error",
-            "-count_frames",
-            "-select_streams",
-            "v:0",
-            "-show_entries",
-            "stream=nb_read_frames",
-            "-of",
-            "csv=p=0",
-            &payload.video_path,
-        ])
-        .output()
-        .map_err(|e| AppError::Internal(format!("ffprobe failed: {e}")))?;
-
-    let total_frames: u64 = String::from_utf8_lossy(&probe.stdout)
-        .trim()
-        .parse()
-        .unwrap_or(1000);
-    let interval = (total_frames / payload.count as u64).max(1);
-
-    let output_pattern = temp_dir.join("frame_%04d.png");
-    let status = crate::runtime::command_for("ffmpeg")
-        .args([
-            "-i",
-            &payload.video_path,
-            "-vf",
-            &format!("select='not(mod(n\\,{interval}))'"),
-            "-frames:v",
-            &payload.count.to_string(),
-            "-vsync",
-            "vfn",
-            output_pattern
-                .to_str()
-                .ok_or_else(|| AppError::Internal("invalid path encoding".to_string()))?,
-        ])
-        .output()
-        .map_err(|e| AppError::Internal(format!("ffmpeg failed: {e}")))?;
+    let mut results = Vec::with_capacity(variant_jobs.len());
+    for (variant, (variant_job_id, output)) in plan.variants.iter().zip(variant_jobs) {
+        let job = state.inner.jobs.get(&variant_job_id).map(|job| job.clone());
+        let status = job.as_ref().map_or(JobStatus::Cancelled, |job| job.status);
+        let mut result = VariantResult {
+            name: variant.name.clone(),
+            job_id: variant_job_id,
+            output: output.clone(),
+            status: serde_json::to_value(status)?
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            error: job.as_ref().and_then(|job| job.error.clone()),
+            width: None,
+            height: None,
+            elapsed_secs: job.as_ref().and_then(|job| {
+                let started = job.started_at?;
+                let completed = job.completed_at?;
+                Some((completed - started).num_milliseconds() as f64 / 1000.0)
+            }),
+            vmaf: None,
+        };
+        if status == JobStatus::Completed {
+            let source = plan.source.clone();
+            let scored_output = output.clone();
+            match tokio::task::spawn_blocking(move || {
+                crate::experiment::score_variant(&source, &scored_output)
+            })
+            .await?
+            {
+                Ok((width, height, vmaf)) => {
+                    info!(variant = %result.name, vmaf, "Variant scored");
+                    result.width = Some(width);
+                    result.height = Some(height);
+                    result.vmaf = Some(vmaf);
+                    record_artifact_vmaf(state, &result.job_id, &output, vmaf);
+                }
+                Err(err) => {
+                    warn!(variant = %result.name, error = %format!("{err:#}"), "Failed to score variant");
+                    result.error = Some(format!("{err:#}"));
+                }
+            }
+        }
+        results.push(result);
+    }
 
-    if !status.status.success() {
-        let stderr = String::from_utf8_lossy(&status.stderr);
-        return Err(AppError::Internal(format!("ffmpeg error: {stderr}")));
+    let created_at = state
+        .inner
+        .jobs
+        .get(job_id)
+        .map_or_else(Utc::now, |job| job.created_at);
+    let report = ExperimentReport {
+        experiment_id: job_id.to_string(),
+        source: plan.source.clone(),
+        baseline: crate::experiment::BASELINE_SCALER.to_string(),
+        created_at,
+        completed_at: Some(Utc::now()),
+        variants: results,
+    };
+    let report_path = {
+        let report = report.clone();
+        tokio::task::spawn_blocking(move || report.save(&dir)).await??
+    };
+    info!(job_id = %job_id, report = %report_path.display(), "Experiment report written");
+    if let Some(mut job) = state.inner.jobs.get_mut(job_id) {
+        job.outputs = Some(HashMap::from([(
+            "report".to_string(),
+            serde_json::Value::String(report_path.to_string_lossy().into_owned()),
+        )]));
     }
 
-    let mut frames = Vec::new();
-    for i in 1..=payload.count {
-        let filename = format!("frame_{i:04}.png");
-        let frame_path = temp_dir.join(&filename);
-        if frame_path.exists() {
-            frames.push(FrameInfo {
-                index: i - 1,
-                url: format!("/api/preview/frames/{preview_id}/{filename}"),
-            });
-        }
+    if report
+        .variants
+        .iter()
+        .all(|variant| variant.status != "completed")
+    {
+        anyhow::bail!("no variant completed");
     }
+    Ok(())
+}
 
-    if frames.is_empty() {
-        return Err(AppError::Internal("ffmpeg produced no frames".to_string()));
+/// Cancel any variant jobs of `parent_id` that have not finished yet.
+/// Store a VMAF score on the artifact of `job_id` written to `output`.
+fn record_artifact_vmaf(state: &AppState, job_id: &str, output: &StdPath, vmaf: f64) {
+    let snapshot = state.inner.jobs.get_mut(job_id).and_then(|mut job| {
+        let artifact = job
+            .artifacts
+            .iter_mut()
+            .find(|artifact| artifact.path == output)?;
+        artifact.vmaf = Some(vmaf);
+        Some(job.clone())
+    });
+    if let Some(snapshot) = snapshot {
+        if let Err(err) = state.persist_job_snapshot(&snapshot) {
+            error!(job_id = %job_id, error = ?err, "Failed to persist artifact VMAF score");
+        }
     }
+}
 
-    state
+fn cancel_experiment_jobs(state: &AppState, parent_id: &str) {
+    let Some(job_ids) = state
         .inner
-        .preview_sessions
-        .insert(preview_id.clone(), temp_dir);
-
-    info!(preview_id = %preview_id, frame_count = frames.len(), "Extracted preview frames");
-
-    Ok((
-        StatusCode::CREATED,
-        Json(ExtractFramesResponse { preview_id, frames }),
-    ))
+        .experiment_plans
+        .get(parent_id)
+        .map(|ids| ids.clone())
+    else {
+        return;
+    };
+    cancel_child_jobs(state, job_ids);
 }
 
-async fn serve_preview_frame(
+async fn get_experiment(
     State(state): State<AppState>,
-    Path((preview_id, filename)): Path<(String, String)>,
-) -> Result<Response, AppError> {
-    let session_dir = state
+    Path(id): Path<String>,
+) -> Result<Json<ExperimentResponse>, AppError> {
+    let status = state
         .inner
-        .preview_sessions
-        .get(&preview_id)
-        .ok_or_else(|| AppError::NotFound(format!("preview session not found: {preview_id}")))?;
-
-    let file_path = session_dir.join(&filename);
-    if !file_path.exists() {
-        return Err(AppError::NotFound(format!("frame not found: {filename}")));
-    }
-
-    let bytes = tokio::fs::read(&file_path)
-        .await
-        .map_err(|e| AppError::Internal(format!("failed to read frame: {e}")))?;
+        .jobs
+        .get(&id)
+        .filter(|job| job.workflow_source == WORKFLOW_SOURCE_API_EXPERIMENT)
+        .map(|job| job.status)
+        .ok_or_else(|| AppError::NotFound(format!("experiment not found: {id}")))?;
+    let dir = crate::experiment::experiment_dir(&state.inner.data_dir, &id);
+    let report = dir
+        .join(crate::experiment::REPORT_FILE_NAME)
+        .is_file()
+        .then(|| ExperimentReport::load(&dir))
+        .transpose()
+        .map_err(|e| AppError::Internal(format!("{e:#}")))?;
+    Ok(Json(ExperimentResponse {
+        job_id: id,
+        status,
+        report,
+    }))
+}
 
-    Ok((StatusCode::OK, [("content-type", "image/png")], bytes).into_response())
+struct ResolvedWorkflowFile {
+    path: PathBuf,
+    workflow_source: &'static str,
 }
 
-async fn process_frame(
-    State(state): State<AppState>,
-    Json(payload): Json<ProcessFrameRequest>,
-) -> Result<Json<ProcessFrameResponse>, AppError> {
-    let session_dir = state
-        .inner
-        .preview_sessions
-        .get(&payload.preview_id)
-        .ok_or_else(|| {
-            AppError::NotFound(format!("preview session not found: {}", payload.preview_id))
-        })?;
+async fn resolve_run_workflow_file(
+    state: &AppState,
+    workflow_name: &str,
+) -> Result<ResolvedWorkflowFile, AppError> {
+    let filename = format!("{workflow_name}.json");
+    sanitize_workflow_filename(&filename)?;
 
-    let filename = format!("frame_{:04}.png", payload.frame_index + 1);
-    let frame_path = session_dir.join(&filename);
-    if !frame_path.exists() {
-        return Err(AppError::NotFound(format!(
-            "frame not found: index {}",
-            payload.frame_index
-        )));
+    let workflows_dir = state.resolve_workflows_dir().await;
+    let workflows_path = workflows_dir.join(&filename);
+    if workflows_path.exists() {
+        return Ok(ResolvedWorkflowFile {
+            path: workflows_path,
+            workflow_source: WORKFLOW_SOURCE_API_RUN_WORKFLOWS,
+        });
     }
 
-    // TODO(task 4.3): actual frame processing through inference pipeline
-    let processed_url = format!("/api/preview/frames/{}/{}", payload.preview_id, filename);
-
-    Ok(Json(ProcessFrameResponse { processed_url }))
-}
+    let config = state.inner.config.read().await;
+    let presets_path = config.paths.presets_dir.join(&filename);
+    if presets_path.exists() {
+        return Ok(ResolvedWorkflowFile {
+            path: presets_path,
+            workflow_source: WORKFLOW_SOURCE_API_RUN_PRESETS,
+        });
+    }
 
-#[derive(Deserialize)]
-pub struct JellyfinProxyQuery {
-    pub url: String,
-    pub api_key: String,
-    pub library_id: Option<String>,
+    Err(AppError::NotFound(format!(
+        "workflow not found: {workflow_name}"
+    )))
 }
 
-async fn jellyfin_libraries(
-    axum::extract::Query(params): axum::extract::Query<JellyfinProxyQuery>,
-) -> Result<Json<serde_json::Value>, AppError> {
-    let client = JellyfinClient::new(&params.url, &params.api_key)
-        .map_err(|e| AppError::BadRequest(e.to_string()))?;
-
-    let libraries = client
-        .get_libraries()
-        .await
-        .map_err(|e| AppError::Internal(e.to_string()))?;
-
-    Ok(Json(serde_json::to_value(libraries).unwrap_or_default()))
-}
+fn validate_run_workflow_name(raw_name: Option<&str>) -> Result<String, AppError> {
+    let workflow_name = raw_name
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| AppError::BadRequest("workflow_name is required".to_string()))?;
 
-async fn jellyfin_items(
-    axum::extract::Query(params): axum::extract::Query<JellyfinProxyQuery>,
-) -> Result<Json<serde_json::Value>, AppError> {
-    let client = JellyfinClient::new(&params.url, &params.api_key)
-        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    if workflow_name.ends_with(".json") {
+        return Err(AppError::BadRequest(
+            "workflow_name must not include .json suffix".to_string(),
+        ));
+    }
 
-    let query = ItemQuery {
-        parent_id: params.library_id,
-        include_item_types: Some("Movie,Episode".to_string()),
-        fields: Some("Path,Overview".to_string()),
-        recursive: Some(true),
-        ..Default::default()
-    };
+    if workflow_name.contains('/') || workflow_name.contains('\\') {
+        return Err(AppError::BadRequest(
+            "workflow_name must not contain path separators".to_string(),
+        ));
+    }
 
-    let items = client
-        .get_items(&query)
-        .await
-        .map_err(|e| AppError::Internal(e.to_string()))?;
+    if workflow_name.contains("..") {
+        return Err(AppError::BadRequest(
+            "workflow_name must not contain '..'".to_string(),
+        ));
+    }
 
-    Ok(Json(serde_json::to_value(items).unwrap_or_default()))
+    Ok(workflow_name.to_string())
 }
 
-async fn run_job(state: AppState, job_id: String) {
-    let _permit = {
-        let cancel_token = {
-            let job = match state.inner.jobs.get(&job_id) {
-                Some(j) => j,
-                None => return,
-            };
-            job.cancel_token.clone()
-        };
-
-        tokio::select! {
-            permit = state.inner.gpu_semaphore.clone().acquire_owned() => {
-                match permit {
-                    Ok(p) => p,
-                    Err(_) => return,
-                }
-            }
-            _ = cancel_token.cancelled() => {
-                return;
-            }
-        }
-    };
+async fn create_batch(
+    State(state): State<AppState>,
+    Json(payload): Json<BatchRequest>,
+) -> Result<(StatusCode, Json<BatchResponse>), AppError> {
+    if payload.file_paths.is_empty() {
+        return Err(AppError::BadRequest(
+            "file_paths must not be empty".to_string(),
+        ));
+    }
 
-    let running_snapshot = {
-        if let Some(mut job) = state.inner.jobs.get_mut(&job_id) {
-            if job.status == JobStatus::Cancelled {
-                return;
-            }
-            job.status = JobStatus::Running;
-            job.started_at = Some(Utc::now());
-            Some(job.clone())
-        } else {
-            None
-        }
-    };
+    let base_workflow: serde_json::Value = payload.workflow;
+    let workflow_name = workflow_name_from_request(&base_workflow, DEFAULT_WORKFLOW_NAME_API_BATCH);
 
-    if let Some(snapshot) = running_snapshot {
-        if let Err(err) = state.persist_job_snapshot(&snapshot) {
-            error!(job_id = %job_id, error = ?err, "Failed to persist running transition");
-        }
-    }
+    let mut job_ids = Vec::with_capacity(payload.file_paths.len());
 
-    let result = {
-        let (mut workflow, mut job_params, cancel_token) = {
-            let Some(job) = state.inner.jobs.get(&job_id) else {
-                return;
-            };
-            (
-                job.workflow.clone(),
-                job.params.clone(),
-                job.cancel_token.clone(),
-            )
-        };
-        let inner = Arc::clone(&state.inner);
-        let trt_cache_dir = state.inner.config.read().await.paths.trt_cache_dir.clone();
-
-        // Clone the broadcast sender before entering the blocking closure
-        // to avoid holding the DashMap read lock across the block_in_place boundary.
-        let ws_tx = state.inner.progress_senders.get(&job_id).map(|r| r.clone());
-
-        let job_id_for_closure = job_id.clone();
-
-        if workflow.has_video_frames_edges() {
-            if let Some(params) = job_params.as_ref() {
-                workflow.inject_workflow_input_params(params);
-            }
-            job_params = None;
-        }
-
-        if let Some(params) = job_params {
-            tokio::task::block_in_place(move || {
-                let mut debug_throttle =
-                    NodeDebugEventThrottle::new(Duration::from_millis(PRINT_PREVIEW_THROTTLE_MS));
-                let ws_tx_for_debug = ws_tx.clone();
-                let mut node_debug_cb = move |event: NodeDebugValueEvent| {
-                    if !debug_throttle.should_emit(&event.node_id, Instant::now()) {
-                        return;
-                    }
-                    if let Some(tx) = &ws_tx_for_debug {
-                        let _ = tx.send(JobWsEvent::from(event));
+    for file_path in &payload.file_paths {
+        let mut wf = base_workflow.clone();
+        if let Some(nodes) = wf.get_mut("nodes").and_then(|n| n.as_array_mut()) {
+            for node in nodes.iter_mut() {
+                let node_type = node.get("node_type").and_then(|t| t.as_str());
+                match node_type {
+                    Some("VideoInput") => {
+                        if let Some(params) = node.get_mut("params").and_then(|p| p.as_object_mut())
+                        {
+                            params.insert(
+                                "path".to_string(),
+                                serde_json::Value::String(file_path.clone()),
+                            );
+                        }
                     }
-                };
+                    Some("WorkflowInput") => {
+                        if let Some(params) = node.get_mut("params").and_then(|p| p.as_object_mut())
+                        {
+                            if let Some(ports_arr) =
+                                params.get("ports").and_then(|v| v.as_array()).cloned()
+                            {
+                                for port in ports_arr {
+                                    let port_name = port
+                                        .get("name")
+                                        .and_then(|n| n.as_str())
+                                        .unwrap_or_default();
+                                    let port_type = port
+                                        .get("port_type")
+                                        .and_then(|t| t.as_str())
+                                        .unwrap_or_default();
 
-                // Convert JSON params to PortData (infer type from JSON value)
-                let mut port_params = HashMap::new();
-                for (key, value) in &params {
-                    let port_data = if let Some(i) = value.as_i64() {
-                        crate::types::PortData::Int(i)
-                    } else if let Some(f) = value.as_f64() {
-                        crate::types::PortData::Float(f)
-                    } else if let Some(b) = value.as_bool() {
-                        crate::types::PortData::Bool(b)
-                    } else if let Some(s) = value.as_str() {
-                        crate::types::PortData::Str(s.to_string())
-                    } else {
-                        crate::types::PortData::Str(value.to_string())
-                    };
-                    port_params.insert(key.clone(), port_data);
-                }
-                let ctx = crate::node::ExecutionContext::default();
-                SequentialExecutor::execute_with_params_and_debug_hook(
-                    &workflow,
-                    &inner.node_registry,
-                    port_params,
-                    &ctx,
-                    Some(&mut node_debug_cb),
-                )
-            })
-        } else {
-            // No params: use execute_with_context with video compile support
-            // Use block_in_place (NOT spawn_blocking) because the executor internally
-            // calls block_in_place at executor.rs:67. Nesting block_in_place inside
-            // spawn_blocking panics; block_in_place inside block_in_place is a no-op.
-            tokio::task::block_in_place(move || {
-                let compile_ctx = VideoCompileContext::new(trt_cache_dir);
-                let fps_baseline = Mutex::new(None::<ProgressFpsBaseline>);
-                let ws_tx_for_progress = ws_tx.clone();
-                let ws_tx_for_debug = ws_tx.clone();
+                                    if port_type != "Path" || port_name.is_empty() {
+                                        continue;
+                                    }
 
-                let inner_for_cb = Arc::clone(&inner);
-                let progress_cb: Box<dyn Fn(u64, Option<u64>, Option<u64>) + Send> =
-                    Box::new(move |current_frame, total_frames, _hint| {
-                        let now = Instant::now();
-                        let fps = {
-                            let mut baseline_guard = match fps_baseline.lock() {
-                                Ok(guard) => guard,
-                                Err(poisoned) => poisoned.into_inner(),
-                            };
-                            let (next_baseline, next_fps) = estimate_input_fps_from_second_frame(
-                                *baseline_guard,
-                                current_frame,
-                                now,
-                            );
-                            *baseline_guard = next_baseline;
-                            next_fps as f64
-                        };
-                        let eta = total_frames.and_then(|total| {
-                            if fps > 0.0 && current_frame < total {
-                                Some((total - current_frame) as f64 / fps)
-                            } else {
-                                None
+                                    let name_lower = port_name.to_lowercase();
+                                    if name_lower.contains("input")
+                                        || name_lower == "input"
+                                        || name_lower == "path"
+                                    {
+                                        params.insert(
+                                            port_name.to_string(),
+                                            serde_json::Value::String(file_path.clone()),
+                                        );
+                                    }
+                                }
                             }
-                        });
-
-                        let update = ProgressUpdate {
-                            current_frame,
-                            total_frames,
-                            fps: fps as f32,
-                            eta_seconds: eta,
-                        };
-
-                        if let Some(mut job) = inner_for_cb.jobs.get_mut(&job_id_for_closure) {
-                            job.progress = Some(update.clone());
-                        }
-
-                        if let Some(tx) = &ws_tx_for_progress {
-                            let _ = tx.send(JobWsEvent::from(update));
                         }
-                    });
-
-                let mut debug_throttle =
-                    NodeDebugEventThrottle::new(Duration::from_millis(PRINT_PREVIEW_THROTTLE_MS));
-                let mut node_debug_cb = move |event: NodeDebugValueEvent| {
-                    if !debug_throttle.should_emit(&event.node_id, Instant::now()) {
-                        return;
-                    }
-                    if let Some(tx) = &ws_tx_for_debug {
-                        let _ = tx.send(JobWsEvent::from(event));
-                    }
-                };
-
-                let (cancel_watch_tx, cancel_watch_rx) = tokio::sync::watch::channel(false);
-                let _cancel_bridge = tokio::spawn({
-                    let token = cancel_token.clone();
-                    async move {
-                        token.cancelled().await;
-                        let _ = cancel_watch_tx.send(true);
                     }
-                });
-
-                SequentialExecutor::execute_with_context_and_debug_hook(
-                    &workflow,
-                    &inner.node_registry,
-                    Some(&compile_ctx),
-                    Some(progress_cb),
-                    Some(cancel_watch_rx),
-                    Some(&mut node_debug_cb),
-                )
-            })
-        }
-    };
-
-    match result {
-        Ok(_outputs) => {
-            let mut completed_snapshot = None;
-            if let Some(mut job) = state.inner.jobs.get_mut(&job_id) {
-                if job.status == JobStatus::Cancelled {
-                    return;
-                }
-                job.status = JobStatus::Completed;
-                job.completed_at = Some(Utc::now());
-                completed_snapshot = Some(job.clone());
-            }
-
-            if let Some(snapshot) = completed_snapshot {
-                if let Err(err) = state.persist_job_snapshot(&snapshot) {
-                    error!(job_id = %job_id, error = ?err, "Failed to persist completed transition");
+                    _ => {}
                 }
             }
         }
-        Err(err) => {
-            error!(job_id = %job_id, error = ?err, "Job execution failed");
-            let mut failed_snapshot = None;
-            if let Some(mut job) = state.inner.jobs.get_mut(&job_id) {
-                if job.status == JobStatus::Cancelled {
-                    return;
-                }
-                job.status = JobStatus::Failed;
-                job.error = Some(format!("{:#}", err));
-                job.completed_at = Some(Utc::now());
-                failed_snapshot = Some(job.clone());
-            }
 
-            if let Some(snapshot) = failed_snapshot {
-                if let Err(persist_err) = state.persist_job_snapshot(&snapshot) {
-                    error!(
-                        job_id = %job_id,
-                        error = ?persist_err,
-                        "Failed to persist failed transition"
-                    );
-                }
-            }
-        }
-    }
+        let workflow: PipelineGraph = parse_and_validate_workflow(&state, wf)?;
 
-    state.inner.progress_senders.remove(&job_id);
+        let created = create_and_spawn_job(
+            &state,
+            workflow,
+            None,
+            workflow_name.clone(),
+            WORKFLOW_SOURCE_API_BATCH.to_string(),
+            None,
+            None,
+        )
+        .await?;
+        let id = created.id;
 
-    info!(job_id = %job_id, "Job completed");
-}
+        info!(job_id = %id, file_path = %file_path, "Batch job created");
+        job_ids.push(id);
+    }
 
-#[derive(Debug)]
-pub enum AppError {
-    BadRequest(String),
-    Forbidden(String),
-    NotFound(String),
-    Internal(String),
+    let total = job_ids.len();
+    Ok((StatusCode::CREATED, Json(BatchResponse { job_ids, total })))
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        let (status, message) = match self {
-            AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
-            AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
-            AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
-            AppError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
-        };
+/// Job history, newest first. Filters and paging come from the query
+/// string; when a page is cut short the `x-next-cursor` header holds the
+/// value to pass as `cursor` for the next one.
+async fn list_jobs(
+    State(state): State<AppState>,
+    axum::extract::Query(params): axum::extract::Query<ListJobsQuery>,
+) -> Result<Response, AppError> {
+    let query = params.into_job_query()?;
+
+    let page = match &state.inner.jobs_persistence {
+        Some(persistence) => persistence
+            .query_job_ids(&query)
+            .map_err(|e| AppError::Internal(format!("failed to query jobs: {e:#}")))?,
+        None => query.paginate(
+            state
+                .inner
+                .jobs
+                .iter()
+                .filter(|entry| query.matches(entry.value()))
+                .map(|entry| (entry.created_at, entry.id.clone()))
+                .collect(),
+        ),
+    };
 
-        let body = Json(ErrorResponse { error: message });
-        (status, body).into_response()
+    let jobs: Vec<JobResponse> = page
+        .ids
+        .iter()
+        .filter_map(|id| state.inner.jobs.get(id).map(|job| job_to_response(&job)))
+        .collect();
+    let mut response = Json(jobs).into_response();
+    if let Some(cursor) = page.next_cursor {
+        if let Ok(value) = axum::http::HeaderValue::from_str(&cursor.encode()) {
+            response.headers_mut().insert(NEXT_CURSOR_HEADER, value);
+        }
     }
+    Ok(response)
 }
 
-impl From<anyhow::Error> for AppError {
-    fn from(err: anyhow::Error) -> Self {
-        AppError::Internal(format!("{:#}", err))
-    }
+async fn get_job(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<JobResponse>, AppError> {
+    let job = state
+        .inner
+        .jobs
+        .get(&id)
+        .ok_or_else(|| AppError::NotFound(format!("job not found: {id}")))?;
+
+    Ok(Json(job_to_response(job.value())))
 }
 
-fn job_to_response(job: &Job) -> JobResponse {
-    JobResponse {
-        id: job.id.clone(),
-        status: job.status,
-        created_at: job.created_at,
-        started_at: job.started_at,
-        completed_at: job.completed_at,
-        progress: job.progress.clone(),
-        error: job.error.clone(),
-        workflow_name: job.workflow_name.clone(),
-        workflow_source: job.workflow_source.clone(),
-        params: job.params.clone(),
-        rerun_of_job_id: job.rerun_of_job_id.clone(),
-        duration_ms: job_duration_ms(job),
+async fn update_job(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateJobRequest>,
+) -> Result<Json<JobResponse>, AppError> {
+    let tags = request.tags.map(normalize_job_tags).transpose()?;
+    let note = request.note.map(|note| note.trim().to_string());
+    if let Some(note) = &note {
+        if note.chars().count() > MAX_JOB_NOTE_LEN {
+            return Err(AppError::BadRequest(format!(
+                "note is longer than {MAX_JOB_NOTE_LEN} characters"
+            )));
+        }
     }
-}
 
-fn workflow_name_from_request(workflow: &serde_json::Value, fallback: &str) -> String {
-    workflow
-        .get("name")
-        .and_then(|v| v.as_str())
-        .map(str::trim)
-        .filter(|name| !name.is_empty())
-        .unwrap_or(fallback)
-        .to_string()
-}
+    let snapshot = {
+        let mut job = state
+            .inner
+            .jobs
+            .get_mut(&id)
+            .ok_or_else(|| AppError::NotFound(format!("job not found: {id}")))?;
+        if let Some(tags) = tags {
+            job.tags = tags;
+        }
+        if let Some(note) = note {
+            job.note = Some(note).filter(|note| !note.is_empty());
+        }
+        job.clone()
+    };
 
-fn extract_workflow_input_params(
-    workflow: &serde_json::Value,
-) -> Option<HashMap<String, serde_json::Value>> {
-    let nodes = workflow.get("nodes")?.as_array()?;
-    let workflow_input = nodes
-        .iter()
-        .find(|node| node.get("node_type").and_then(|t| t.as_str()) == Some("WorkflowInput"))?;
-    let params = workflow_input.get("params")?.as_object()?;
+    state
+        .persist_job_snapshot(&snapshot)
+        .map_err(|e| AppError::Internal(format!("failed to persist job {id}: {e:#}")))?;
 
-    let mut extracted = HashMap::new();
-    for (key, value) in params {
-        if matches!(
-            key.as_str(),
-            "ports" | "interface_inputs" | "interface_outputs"
-        ) {
-            continue;
-        }
+    Ok(Json(job_to_response(&snapshot)))
+}
 
-        extracted.insert(key.clone(), value.clone());
+/// Hold a queued job in the queue. Running jobs cannot be paused, and
+/// neither can chunked runs or experiments, whose sub-jobs are paused
+/// individually instead.
+async fn pause_job(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<JobResponse>, AppError> {
+    if state.inner.chunk_plans.contains_key(&id) || state.inner.experiment_plans.contains_key(&id) {
+        return Err(AppError::BadRequest(format!(
+            "job {id} runs sub-jobs; pause those instead"
+        )));
     }
+    let snapshot = {
+        let mut job = state
+            .inner
+            .jobs
+            .get_mut(&id)
+            .ok_or_else(|| AppError::NotFound(format!("job not found: {id}")))?;
+        if job.status != JobStatus::Queued {
+            return Err(AppError::BadRequest(format!(
+                "only queued jobs can be paused; job {id} is {}",
+                job_status_name(job.status)
+            )));
+        }
+        job.status = JobStatus::Paused;
+        job.paused_at = Some(Utc::now());
+        job.clone()
+    };
 
-    if extracted.is_empty() {
-        None
-    } else {
-        Some(extracted)
-    }
+    state
+        .persist_job_snapshot(&snapshot)
+        .map_err(|e| AppError::Internal(format!("failed to persist job {id}: {e:#}")))?;
+    announce_job_status(&state, &id, JobStatus::Paused);
+    info!(job_id = %id, "Job paused");
+    Ok(Json(job_to_response(&snapshot)))
 }
 
-fn job_duration_ms(job: &Job) -> Option<i64> {
-    let completed_at = job.completed_at?;
-    let started_at = job.started_at.unwrap_or(job.created_at);
-    Some((completed_at - started_at).num_milliseconds().max(0))
+/// Let a step mode or breakpoint job continue to its next pause. A step sent
+/// before the job pauses is used at its next pause.
+async fn step_job(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<JobResponse>, AppError> {
+    let job = state
+        .inner
+        .jobs
+        .get(&id)
+        .ok_or_else(|| AppError::NotFound(format!("job not found: {id}")))?;
+    let Some(gate) = &job.step_gate else {
+        return Err(AppError::BadRequest(format!(
+            "job {id} was not started in step mode or with breakpoints"
+        )));
+    };
+    if is_terminal(job.status) {
+        return Err(AppError::BadRequest(format!(
+            "job {id} is already {}",
+            job_status_name(job.status)
+        )));
+    }
+    gate.step();
+    Ok(Json(job_to_response(&job)))
 }
 
-pub fn default_app_state() -> AppState {
-    let dd = crate::config::data_dir(None);
-    let cfg_path = crate::config::config_path(&dd);
-    let config = match AppConfig::load_from_path(&cfg_path) {
-        Ok(config) => config,
-        Err(err) => {
-            warn!(error = %err, "Failed to load config file, using defaults");
-            AppConfig::default()
+/// Put a paused job back in the queue at its original position.
+async fn resume_job(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<JobResponse>, AppError> {
+    let snapshot = {
+        let mut job = state
+            .inner
+            .jobs
+            .get_mut(&id)
+            .ok_or_else(|| AppError::NotFound(format!("job not found: {id}")))?;
+        if job.status != JobStatus::Paused {
+            return Err(AppError::BadRequest(format!(
+                "only paused jobs can be resumed; job {id} is {}",
+                job_status_name(job.status)
+            )));
+        }
+        let redacted = job
+            .params
+            .as_ref()
+            .map(redacted_param_names)
+            .unwrap_or_default();
+        if !redacted.is_empty() {
+            return Err(AppError::Conflict(format!(
+                "job {id} was reloaded after a restart and its params {} were redacted; \
+                 rerun it with those params instead",
+                redacted.join(", ")
+            )));
         }
+        job.status = JobStatus::Queued;
+        job.paused_at = None;
+        job.clone()
     };
-    app_state_with_config(config, cfg_path, dd)
+
+    state
+        .persist_job_snapshot(&snapshot)
+        .map_err(|e| AppError::Internal(format!("failed to persist job {id}: {e:#}")))?;
+
+    // Jobs paused before a restart have no task waiting for them.
+    state
+        .inner
+        .progress_senders
+        .entry(id.clone())
+        .or_insert_with(|| broadcast::channel::<JobWsEvent>(64).0);
+    if state.inner.config.read().await.workers.dispatch == JobDispatchMode::Remote {
+        state.inner.workers.enqueue(&id);
+    } else if !job_logs::job_log_hub().is_capturing(&id) {
+        spawn_local_job(&state, &id);
+    }
+    state.inner.jobs_resumed.notify_waiters();
+
+    announce_job_status(&state, &id, JobStatus::Queued);
+    info!(job_id = %id, "Job resumed");
+    Ok(Json(job_to_response(&snapshot)))
 }
 
-pub fn app_state_with_config(
-    config: AppConfig,
-    config_path: PathBuf,
-    data_dir: PathBuf,
-) -> AppState {
-    let mut node_registry = NodeRegistry::new();
-    register_all_nodes(&mut node_registry);
-    let mut model_registry = ModelRegistry::with_builtin_models(config.paths.models_dir.clone());
-    if let Err(e) = model_registry.discover() {
-        tracing::warn!(error = %e, "Failed to discover models on disk");
-    }
-    let presets = load_builtin_presets(&config.paths.presets_dir);
-    AppState::new(
-        node_registry,
-        model_registry,
-        presets,
-        config,
-        config_path,
-        data_dir,
-    )
+fn job_status_name(status: JobStatus) -> String {
+    serde_json::to_value(status)
+        .ok()
+        .and_then(|value| value.as_str().map(str::to_string))
+        .unwrap_or_default()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::debug_event::NodeDebugValueEvent;
-    use crate::types::PortType;
-    use axum::body::Body;
-    use axum::http::Request;
-    use rusqlite::Connection;
-    use tower::{Service, ServiceExt};
+async fn get_job_outputs(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<JobOutputsResponse>, AppError> {
+    let job = state
+        .inner
+        .jobs
+        .get(&id)
+        .ok_or_else(|| AppError::NotFound(format!("job not found: {id}")))?;
 
-    fn test_state() -> AppState {
-        test_state_with_data_dir(test_data_dir())
+    Ok(Json(JobOutputsResponse {
+        job_id: job.id.clone(),
+        status: job.status,
+        outputs: job
+            .outputs
+            .as_ref()
+            .map(redact_job_params)
+            .unwrap_or_default(),
+    }))
+}
+
+async fn get_job_progress_history(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ProgressHistoryResponse>, AppError> {
+    if !state.inner.jobs.contains_key(&id) {
+        return Err(AppError::NotFound(format!("job not found: {id}")));
     }
 
-    fn test_state_with_data_dir(data_dir: PathBuf) -> AppState {
-        let mut node_registry = NodeRegistry::new();
-        node_registry.register("test_source", |_params| {
-            Ok(Box::new(TestNode {
-                node_type: "test_source".to_string(),
-                inputs: vec![],
-                outputs: vec![crate::node::PortDefinition {
-                    name: "output".to_string(),
-                    port_type: PortType::VideoFrames,
-                    required: true,
-                    default_value: None,
-                }],
-            }))
-        });
-        node_registry.register("test_sink", |_params| {
-            Ok(Box::new(TestNode {
-                node_type: "test_sink".to_string(),
-                inputs: vec![crate::node::PortDefinition {
-                    name: "input".to_string(),
-                    port_type: PortType::VideoFrames,
-                    required: true,
-                    default_value: None,
-                }],
-                outputs: vec![],
-            }))
-        });
-        node_registry.register("test_delay", |params| {
-            let sleep_ms = params
-                .get("sleep_ms")
-                .and_then(serde_json::Value::as_u64)
-                .unwrap_or(0);
-            Ok(Box::new(DelayNode { sleep_ms }))
-        });
+    let points = match &state.inner.jobs_persistence {
+        Some(persistence) => persistence
+            .load_progress_history(&id)
+            .map_err(|e| AppError::Internal(format!("failed to load progress history: {e:#}")))?,
+        None => Vec::new(),
+    };
 
-        let model_registry = ModelRegistry::with_builtin_models(test_models_dir());
-        AppState::new(
-            node_registry,
-            model_registry,
-            DashMap::new(),
-            AppConfig::default(),
-            test_config_path(),
-            data_dir,
-        )
-    }
+    Ok(Json(ProgressHistoryResponse {
+        job_id: id,
+        sample_interval_ms: PROGRESS_HISTORY_SAMPLE_INTERVAL_MS,
+        points,
+    }))
+}
 
-    fn test_config_path() -> PathBuf {
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        std::env::temp_dir().join(format!(
-            "videnoa-core-server-test-{}-{timestamp}.toml",
-            std::process::id()
-        ))
-    }
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobTraceResponse {
+    pub job_id: String,
+    pub live: bool,
+    #[serde(flatten)]
+    pub trace: JobTraceSnapshot,
+}
 
-    fn test_models_dir() -> PathBuf {
-        std::env::temp_dir().join("models")
+/// The job's node start and end events, live while it runs. Empty for jobs
+/// that have not started or ran on a remote worker.
+async fn get_job_trace(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<JobTraceResponse>, AppError> {
+    if !state.inner.jobs.contains_key(&id) {
+        return Err(AppError::NotFound(format!("job not found: {id}")));
     }
-
-    fn test_data_dir() -> PathBuf {
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        std::env::temp_dir().join(format!(
-            "videnoa-test-data-{}-{timestamp}",
-            std::process::id()
-        ))
+    if let Some(trace) = state.inner.job_traces.get(&id) {
+        return Ok(Json(JobTraceResponse {
+            job_id: id,
+            live: true,
+            trace: trace.snapshot(),
+        }));
     }
 
-    fn temp_path(path: &str) -> PathBuf {
-        std::env::temp_dir().join(path)
-    }
+    let path = trace::job_trace_file_path(&state.inner.data_dir, &id);
+    let trace = tokio::task::spawn_blocking(move || trace::read_trace_file(&path))
+        .await
+        .map_err(|e| AppError::Internal(format!("task join error: {e}")))?
+        .map_err(|e| AppError::Internal(format!("failed to read job trace: {e:#}")))?
+        .unwrap_or_default();
+
+    Ok(Json(JobTraceResponse {
+        job_id: id,
+        live: false,
+        trace,
+    }))
+}
 
-    fn temp_path_str(path: &str) -> String {
-        temp_path(path).to_string_lossy().to_string()
+/// The latest frame a `PreviewTap` node of the running job published.
+async fn get_job_preview(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Response, AppError> {
+    if !state.inner.jobs.contains_key(&id) {
+        return Err(AppError::NotFound(format!("job not found: {id}")));
     }
+    let jpeg = state
+        .inner
+        .frame_previews
+        .get(&id)
+        .map(|preview| preview.jpeg.clone())
+        .ok_or_else(|| AppError::NotFound(format!("job {id} has no frame preview")))?;
 
-    fn unique_temp_dir(prefix: &str) -> PathBuf {
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        std::env::temp_dir().join(format!("{prefix}-{}-{timestamp}", std::process::id()))
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, "image/jpeg"),
+            (axum::http::header::CACHE_CONTROL, "no-store"),
+        ],
+        jpeg,
+    )
+        .into_response())
+}
+
+/// MJPEG stream of the frames the running job writes, about one per second.
+/// Ends when the job does.
+async fn job_monitor_stream(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Response, AppError> {
+    if !state.inner.jobs.contains_key(&id) {
+        return Err(AppError::NotFound(format!("job not found: {id}")));
+    }
+    let mut frames = state
+        .inner
+        .job_monitors
+        .get(&id)
+        .map(|monitor| monitor.subscribe())
+        .ok_or_else(|| AppError::NotFound(format!("job {id} is not running locally")))?;
+    // Send the frame seen so far right away.
+    frames.mark_changed();
+
+    let parts = futures_util::stream::unfold(frames, |mut frames| async move {
+        loop {
+            frames.changed().await.ok()?;
+            let frame = frames.borrow_and_update().clone();
+            if let Some(frame) = frame {
+                let part = mjpeg_part(&frame.jpeg);
+                return Some((Ok::<_, std::convert::Infallible>(part), frames));
+            }
+        }
+    });
+
+    Ok((
+        [
+            (
+                axum::http::header::CONTENT_TYPE,
+                format!("multipart/x-mixed-replace; boundary={MJPEG_BOUNDARY}"),
+            ),
+            (axum::http::header::CACHE_CONTROL, "no-store".to_string()),
+        ],
+        axum::body::Body::from_stream(parts),
+    )
+        .into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JobLogsQuery {
+    pub tail: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobLogsResponse {
+    pub job_id: String,
+    pub live: bool,
+    pub lines: Vec<JobLogLine>,
+}
+
+async fn get_job_logs(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    axum::extract::Query(params): axum::extract::Query<JobLogsQuery>,
+) -> Result<Json<JobLogsResponse>, AppError> {
+    if !state.inner.jobs.contains_key(&id) {
+        return Err(AppError::NotFound(format!("job not found: {id}")));
+    }
+
+    let tail = params
+        .tail
+        .unwrap_or(DEFAULT_JOB_LOGS_TAIL)
+        .min(job_logs::DEFAULT_JOB_LOG_RING_CAPACITY);
+
+    if let Some(lines) = job_logs::job_log_hub().tail(&id, tail) {
+        return Ok(Json(JobLogsResponse {
+            job_id: id,
+            live: true,
+            lines,
+        }));
+    }
+
+    let path = job_logs::job_log_file_path(&state.inner.data_dir, &id);
+    let lines = if path.exists() {
+        tokio::task::spawn_blocking(move || job_logs::read_job_log_file_tail(&path, tail))
+            .await
+            .map_err(|e| AppError::Internal(format!("task join error: {e}")))?
+            .map_err(|e| AppError::Internal(format!("failed to read job logs: {e:#}")))?
+    } else {
+        Vec::new()
+    };
+
+    Ok(Json(JobLogsResponse {
+        job_id: id,
+        live: false,
+        lines,
+    }))
+}
+
+/// Optional body of `POST /api/jobs/{id}/rerun`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RerunJobRequest {
+    /// Merged over the source job's params; keys given here win.
+    #[serde(default)]
+    pub params: HashMap<String, serde_json::Value>,
+}
+
+async fn rerun_job(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    payload: Option<Json<RerunJobRequest>>,
+) -> Result<(StatusCode, Json<CreateJobResponse>), AppError> {
+    let overrides = payload
+        .map(|Json(request)| request.params)
+        .unwrap_or_default();
+    let (workflow, params, workflow_name, workflow_source) = {
+        let source_job = state
+            .inner
+            .jobs
+            .get(&id)
+            .ok_or_else(|| AppError::NotFound(format!("job not found: {id}")))?;
+
+        let params = if overrides.is_empty() {
+            source_job.params.clone()
+        } else {
+            let mut params = source_job.params.clone().unwrap_or_default();
+            params.extend(overrides);
+            Some(params)
+        };
+        let redacted = params
+            .as_ref()
+            .map(redacted_param_names)
+            .unwrap_or_default();
+        if !redacted.is_empty() {
+            return Err(AppError::Conflict(format!(
+                "job {id} was reloaded after a restart and its params {} were redacted; \
+                 pass them in the rerun request's params",
+                redacted.join(", ")
+            )));
+        }
+
+        (
+            source_job.workflow.clone(),
+            params,
+            source_job.workflow_name.clone(),
+            source_job.workflow_source.clone(),
+        )
+    };
+
+    let created = create_and_spawn_job(
+        &state,
+        workflow,
+        params,
+        workflow_name,
+        workflow_source,
+        Some(id),
+        None,
+    )
+    .await?;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+async fn delete_job_history(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    remove_job(&state, &id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Remove a job from memory and the jobs db, cancelling it if it is still
+/// active, and delete its log file.
+fn remove_job(state: &AppState, id: &str) -> Result<(), AppError> {
+    let (job_id, job) = state
+        .inner
+        .jobs
+        .remove(id)
+        .ok_or_else(|| AppError::NotFound(format!("job not found: {id}")))?;
+
+    if matches!(
+        job.status,
+        JobStatus::Queued | JobStatus::Running | JobStatus::Paused
+    ) {
+        job.cancel_token.cancel();
+    }
+
+    let removed_sender = state.inner.progress_senders.remove(&job_id);
+
+    if let Some(persistence) = &state.inner.jobs_persistence {
+        let persisted_deleted_rows = persistence
+            .delete_job(&job_id)
+            .map_err(|e| AppError::Internal(format!("failed to delete job history row: {e:#}")));
+
+        let persisted_deleted_rows = match persisted_deleted_rows {
+            Ok(rows) if rows == 1 => rows,
+            Ok(rows) => {
+                state.inner.jobs.insert(job_id.clone(), job.clone());
+                if let Some((sender_id, sender)) = removed_sender {
+                    state.inner.progress_senders.insert(sender_id, sender);
+                }
+                return Err(AppError::Internal(format!(
+                    "expected exactly one persisted row deleted for job {job_id}, deleted {rows}"
+                )));
+            }
+            Err(err) => {
+                state.inner.jobs.insert(job_id.clone(), job.clone());
+                if let Some((sender_id, sender)) = removed_sender {
+                    state.inner.progress_senders.insert(sender_id, sender);
+                }
+                return Err(err);
+            }
+        };
+
+        debug_assert_eq!(persisted_deleted_rows, 1);
+    }
+
+    if state.inner.chunk_plans.contains_key(&job_id) {
+        cancel_chunk_jobs(state, &job_id);
+        state.inner.chunk_plans.remove(&job_id);
+    }
+    if state.inner.experiment_plans.contains_key(&job_id) {
+        cancel_experiment_jobs(state, &job_id);
+        state.inner.experiment_plans.remove(&job_id);
+    }
+
+    job_logs::job_log_hub().end_capture(&job_id);
+    let log_path = job_logs::job_log_file_path(&state.inner.data_dir, &job_id);
+    if let Err(err) = std::fs::remove_file(&log_path) {
+        if err.kind() != std::io::ErrorKind::NotFound {
+            warn!(job_id = %job_id, error = %err, "Failed to remove job log file");
+        }
+    }
+    let trace_path = trace::job_trace_file_path(&state.inner.data_dir, &job_id);
+    if let Err(err) = std::fs::remove_file(&trace_path) {
+        if err.kind() != std::io::ErrorKind::NotFound {
+            warn!(job_id = %job_id, error = %err, "Failed to remove job trace file");
+        }
+    }
+    state.inner.frame_previews.remove(&job_id);
+    state.inner.job_monitors.remove(&job_id);
+
+    info!(job_id = %job_id, "Job history row deleted");
+    Ok(())
+}
+
+/// Bulk-delete finished jobs matching the query, e.g.
+/// `DELETE /api/jobs?status=failed&before=2025-01-01T00:00:00Z`.
+async fn delete_jobs_bulk(
+    State(state): State<AppState>,
+    axum::extract::Query(params): axum::extract::Query<BulkDeleteJobsQuery>,
+) -> Result<Json<BulkDeleteJobsResponse>, AppError> {
+    if params.status.is_none() && params.before.is_none() && params.workflow_source.is_none() {
+        return Err(AppError::BadRequest(
+            "bulk delete needs at least one of status, before or workflow_source".into(),
+        ));
+    }
+    let statuses = parse_status_list(params.status.as_deref())?;
+    if statuses.iter().any(|status| !is_terminal(*status)) {
+        return Err(AppError::BadRequest(
+            "only finished jobs can be bulk-deleted".into(),
+        ));
+    }
+    let query = JobQuery {
+        statuses,
+        workflow_source: params.workflow_source,
+        created_before: params.before,
+        ..JobQuery::default()
+    };
+
+    let ids: Vec<String> = state
+        .inner
+        .jobs
+        .iter()
+        .filter(|entry| is_terminal(entry.status) && query.matches(entry.value()))
+        .map(|entry| entry.id.clone())
+        .collect();
+    let mut deleted = 0;
+    for id in &ids {
+        match remove_job(&state, id) {
+            Ok(()) => deleted += 1,
+            Err(AppError::NotFound(_)) => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    info!(deleted, "Bulk-deleted job history");
+    Ok(Json(BulkDeleteJobsResponse { deleted }))
+}
+
+fn is_terminal(status: JobStatus) -> bool {
+    matches!(
+        status,
+        JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled | JobStatus::Skipped
+    )
+}
+
+/// Delete finished jobs beyond `jobs.retention_max_jobs` (newest kept) or
+/// older than `jobs.retention_max_age_days`. Returns how many were removed.
+fn enforce_job_retention(state: &AppState, config: &JobsConfig, now: DateTime<Utc>) -> usize {
+    if config.retention_max_jobs == 0 && config.retention_max_age_days == 0 {
+        return 0;
+    }
+
+    let mut finished: Vec<(DateTime<Utc>, DateTime<Utc>, String)> = state
+        .inner
+        .jobs
+        .iter()
+        .filter(|entry| is_terminal(entry.status))
+        .map(|entry| {
+            (
+                entry.created_at,
+                entry.completed_at.unwrap_or(entry.created_at),
+                entry.id.clone(),
+            )
+        })
+        .collect();
+    finished.sort_by(|a, b| b.cmp(a));
+
+    let max_age = chrono::Duration::days(config.retention_max_age_days.min(36_500) as i64);
+    let expired: Vec<String> = finished
+        .into_iter()
+        .enumerate()
+        .filter(|(rank, (_, finished_at, _))| {
+            (config.retention_max_jobs > 0 && *rank >= config.retention_max_jobs)
+                || (config.retention_max_age_days > 0 && now - *finished_at > max_age)
+        })
+        .map(|(_, (_, _, id))| id)
+        .collect();
+
+    let mut removed = 0;
+    for id in expired {
+        match remove_job(state, &id) {
+            Ok(()) => removed += 1,
+            Err(AppError::NotFound(_)) => {}
+            Err(err) => {
+                warn!(job_id = %id, error = ?err, "Failed to delete job past retention");
+            }
+        }
+    }
+    removed
+}
+
+/// Periodically apply the `[jobs]` retention settings to the job history.
+pub fn spawn_job_retention(state: &AppState) -> tokio::task::JoinHandle<()> {
+    let state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(JOB_RETENTION_INTERVAL_SECS));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            let config = state.inner.config.read().await.jobs.clone();
+            let removed = enforce_job_retention(&state, &config, Utc::now());
+            if removed > 0 {
+                info!(removed, "Deleted jobs past the retention policy");
+            }
+        }
+    })
+}
+
+/// Delete the oldest rotated log files over `logging.max_total_size_mb`.
+fn enforce_log_size_limit(log_dir: &StdPath, logging: &LoggingConfig) {
+    let Some(max_total_bytes) = logging.max_total_size_bytes() else {
+        return;
+    };
+    match crate::logging::enforce_log_dir_size_limit(log_dir, max_total_bytes) {
+        Ok(removed) if !removed.is_empty() => {
+            info!(
+                removed_count = removed.len(),
+                max_total_bytes, "Pruned rotated log files over the size limit"
+            );
+        }
+        Ok(_) => {}
+        Err(err) => {
+            warn!(error = %err, log_dir = %log_dir.display(), "Failed to enforce log size limit");
+        }
+    }
+}
+
+/// Periodically apply `logging.max_total_size_mb`, since the log files keep
+/// growing and rotating while the server runs.
+pub fn spawn_log_size_limit(state: &AppState) -> tokio::task::JoinHandle<()> {
+    let state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(LOG_SIZE_LIMIT_INTERVAL_SECS));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            let logging = state.inner.config.read().await.logging.clone();
+            let log_dir = state.log_dir();
+            let _ = tokio::task::spawn_blocking(move || enforce_log_size_limit(&log_dir, &logging))
+                .await;
+        }
+    })
+}
+
+/// Apply the `[power]` settings: hold off system sleep while jobs are queued
+/// or running, and suspend or shut down once the queue has drained.
+pub fn spawn_power_manager(state: &AppState) -> tokio::task::JoinHandle<()> {
+    let state = state.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(POWER_POLL_INTERVAL_SECS));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        let mut watch = QueueWatch::new(Duration::from_secs(QUEUE_EMPTY_GRACE_SECS));
+        let mut inhibitor: Option<SleepInhibitor> = None;
+        // Skip further attempts until the queue drains, to warn once per batch.
+        let mut inhibit_failed = false;
+
+        loop {
+            interval.tick().await;
+            let config = state.inner.config.read().await.power.clone();
+            let busy = state
+                .inner
+                .jobs
+                .iter()
+                .any(|job| matches!(job.status, JobStatus::Queued | JobStatus::Running));
+
+            if busy && config.prevent_sleep {
+                if inhibitor.is_none() && !inhibit_failed {
+                    match SleepInhibitor::acquire("Processing video jobs") {
+                        Ok(acquired) => {
+                            info!("Preventing system sleep while jobs run");
+                            inhibitor = Some(acquired);
+                        }
+                        Err(err) => {
+                            warn!(error = %format!("{err:#}"), "Could not prevent system sleep");
+                            inhibit_failed = true;
+                        }
+                    }
+                }
+            } else {
+                if inhibitor.take().is_some() {
+                    info!("Allowing system sleep again");
+                }
+                if !busy {
+                    inhibit_failed = false;
+                }
+            }
+
+            if watch.observe(busy, Instant::now())
+                && config.when_queue_empty != QueueEmptyAction::None
+            {
+                let action = config.when_queue_empty;
+                info!(?action, "Job queue is empty, running power action");
+                match tokio::task::spawn_blocking(move || run_queue_empty_action(action)).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(err)) => {
+                        error!(?action, error = %format!("{err:#}"), "Power action failed");
+                    }
+                    Err(err) => error!(?action, error = %err, "Power action panicked"),
+                }
+            }
+        }
+    })
+}
+
+async fn job_ws(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Response, AppError> {
+    if !state.inner.jobs.contains_key(&id) {
+        return Err(AppError::NotFound(format!("job not found: {id}")));
+    }
+
+    let rx = state
+        .inner
+        .progress_senders
+        .get(&id)
+        .map(|sender| sender.subscribe())
+        .ok_or_else(|| AppError::NotFound(format!("no progress channel for job: {id}")))?;
+
+    let log_rx = job_logs::job_log_hub().subscribe(&id);
+
+    Ok(ws.on_upgrade(move |socket| handle_ws(socket, rx, log_rx)))
+}
+
+async fn recv_job_log(
+    log_rx: &mut Option<broadcast::Receiver<JobLogLine>>,
+) -> Result<JobLogLine, broadcast::error::RecvError> {
+    match log_rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn handle_ws(
+    mut socket: WebSocket,
+    mut rx: broadcast::Receiver<JobWsEvent>,
+    mut log_rx: Option<broadcast::Receiver<JobLogLine>>,
+) {
+    loop {
+        tokio::select! {
+            result = recv_job_log(&mut log_rx) => {
+                match result {
+                    Ok(line) => {
+                        let json = match serde_json::to_string(&JobWsEvent::from(line)) {
+                            Ok(j) => j,
+                            Err(_) => break,
+                        };
+                        if socket.send(Message::Text(json.into())).await.is_err() {
+                            break;
+                        }
+                    }
+                    // Dropped log lines remain available via /api/jobs/{id}/logs.
+                    Err(broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(broadcast::error::RecvError::Closed) => {
+                        log_rx = None;
+                    }
+                }
+            }
+            result = rx.recv() => {
+                match result {
+                    Ok(update) => {
+                        let json = match serde_json::to_string(&update) {
+                            Ok(j) => j,
+                            Err(_) => break,
+                        };
+                        if socket.send(Message::Text(json.into())).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("WebSocket receiver lagged by {n} messages");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        break;
+                    }
+                }
+            }
+            msg = socket.recv() => {
+                match msg {
+                    Some(Ok(Message::Close(_))) | None => break,
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// Node descriptors in the locale named by `Accept-Language`, falling back
+/// to the configured locale.
+async fn list_nodes(
+    State(state): State<AppState>,
+    headers: axum::http::HeaderMap,
+) -> Json<Vec<NodeDescriptor>> {
+    let requested = headers
+        .get(axum::http::header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(negotiate_locale);
+    let locale = match requested {
+        Some(locale) => locale,
+        None => state.inner.config.read().await.locale.clone(),
+    };
+    Json(localized_node_descriptors(&locale))
+}
+
+async fn list_plugins() -> Json<Vec<PluginInfo>> {
+    Json(loaded_plugins())
+}
+
+/// Candidate values for one node param, picked from the param's descriptor:
+/// enum options, models of the matching type, workflows with an interface,
+/// or host paths starting with `prefix`.
+async fn suggest_param_values(
+    State(state): State<AppState>,
+    Path(node_type): Path<String>,
+    axum::extract::Query(query): axum::extract::Query<SuggestParamQuery>,
+) -> Result<Json<Vec<ParamSuggestion>>, AppError> {
+    let descriptor = all_node_descriptors()
+        .into_iter()
+        .find(|descriptor| descriptor.node_type == node_type)
+        .ok_or_else(|| AppError::NotFound(format!("unknown node type: {node_type}")))?;
+    let port = descriptor
+        .inputs
+        .into_iter()
+        .find(|port| port.direction == "param" && port.name == query.param)
+        .ok_or_else(|| {
+            AppError::NotFound(format!(
+                "node type {node_type} has no param '{}'",
+                query.param
+            ))
+        })?;
+
+    let prefix = query.prefix.trim();
+    let matches_prefix =
+        |candidate: &str| candidate.to_lowercase().starts_with(&prefix.to_lowercase());
+
+    let mut suggestions: Vec<ParamSuggestion> = if let Some(options) = port.enum_options {
+        options
+            .into_iter()
+            .filter(|option| matches_prefix(option))
+            .map(|option| ParamSuggestion {
+                label: option.clone(),
+                value: option,
+                kind: SuggestionKind::Option,
+            })
+            .collect()
+    } else if port.ui_hint.as_deref() == Some("model_selector") {
+        let model_type: Option<ModelType> =
+            serde_json::from_value(serde_json::Value::String(node_type.clone())).ok();
+        let auto = (node_type == "SuperResolution").then(|| ParamSuggestion {
+            value: AUTO_MODEL.to_string(),
+            label: AUTO_MODEL.to_string(),
+            kind: SuggestionKind::Option,
+        });
+        let models: Vec<ParamSuggestion> = state
+            .inner
+            .model_registry
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .list()
+            .iter()
+            .filter(|model| model_type.as_ref().is_none_or(|ty| model.model_type == *ty))
+            .map(|model| ParamSuggestion {
+                value: format!("models/{}", model.filename),
+                label: model.name.clone(),
+                kind: SuggestionKind::Model,
+            })
+            .collect();
+        auto.into_iter()
+            .chain(models)
+            .filter(|suggestion| {
+                matches_prefix(&suggestion.value)
+                    || matches_prefix(suggestion.value.trim_start_matches("models/"))
+            })
+            .collect()
+    } else if port.ui_hint.as_deref() == Some("workflow_picker") || port.port_type == "WorkflowPath"
+    {
+        let workflows_dir = state.resolve_workflows_dir().await;
+        let presets_dir = state.inner.config.read().await.paths.presets_dir.clone();
+        let mut seen = HashSet::new();
+        let mut workflows: Vec<ParamSuggestion> = read_workflow_entries(&workflows_dir)
+            .into_iter()
+            .chain(read_workflow_entries(&presets_dir))
+            .filter(|entry| entry.has_interface && seen.insert(entry.filename.clone()))
+            .filter(|entry| matches_prefix(&entry.filename) || matches_prefix(&entry.name))
+            .map(|entry| ParamSuggestion {
+                value: entry.filename,
+                label: entry.name,
+                kind: SuggestionKind::Workflow,
+            })
+            .collect();
+        workflows.sort_by_key(|suggestion| suggestion.label.to_lowercase());
+        workflows
+    } else if port.ui_hint.as_deref() == Some("jellyfin_server") {
+        let config = state.inner.config.read().await;
+        config
+            .jellyfin
+            .servers
+            .iter()
+            .filter(|server| matches_prefix(&server.name))
+            .map(|server| ParamSuggestion {
+                value: server.name.clone(),
+                label: format!("{} ({})", server.name, server.url),
+                kind: SuggestionKind::Server,
+            })
+            .collect()
+    } else if port.port_type == "Path" {
+        // "/videos/ep" lists /videos and keeps entries starting with "ep".
+        let (dir, name_prefix) = match prefix.rfind('/') {
+            Some(index) => (&prefix[..=index], &prefix[index + 1..]),
+            None => ("", prefix),
+        };
+        let dir = if dir.is_empty() { "." } else { dir };
+        let name_prefix = name_prefix.to_lowercase();
+        browse_directory(dir)?
+            .into_iter()
+            .filter(|entry| entry.name.to_lowercase().starts_with(&name_prefix))
+            .map(|entry| ParamSuggestion {
+                kind: if entry.is_dir {
+                    SuggestionKind::Dir
+                } else {
+                    SuggestionKind::File
+                },
+                label: entry.name,
+                value: entry.path,
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    suggestions.truncate(MAX_PARAM_SUGGESTIONS);
+    Ok(Json(suggestions))
+}
+
+async fn list_models(State(state): State<AppState>) -> Json<Vec<ModelEntry>> {
+    let models = state
+        .inner
+        .model_registry
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .list()
+        .to_vec();
+    Json(models)
+}
+
+async fn list_model_benchmarks(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<BenchmarkRecord>>, AppError> {
+    let data_dir = state.inner.data_dir.clone();
+    let records = tokio::task::spawn_blocking(move || BenchmarkStore::open(&data_dir)?.list())
+        .await
+        .map_err(|e| AppError::Internal(format!("task join error: {e}")))?
+        .map_err(|e| AppError::Internal(format!("failed to read benchmarks: {e}")))?;
+    Ok(Json(records))
+}
+
+async fn inspect_model(
+    State(state): State<AppState>,
+    Path(filename): Path<String>,
+) -> Result<Json<ModelInspection>, AppError> {
+    model_inspect::sanitize_model_filename(&filename)
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    let path = state
+        .inner
+        .config
+        .read()
+        .await
+        .paths
+        .find_model_file(&filename)
+        .ok_or_else(|| AppError::NotFound(format!("model not found: {filename}")))?;
+
+    let inspection = tokio::task::spawn_blocking(move || model_inspect::inspect_onnx(&path))
+        .await
+        .map_err(|e| AppError::Internal(format!("task join error: {e}")))?
+        .map_err(|e| AppError::Internal(format!("failed to inspect model: {e}")))?;
+
+    Ok(Json(inspection))
+}
+
+async fn model_disk_usage(State(state): State<AppState>) -> Result<Json<ModelDiskUsage>, AppError> {
+    let paths = state.inner.config.read().await.paths.clone();
+    let usage = tokio::task::spawn_blocking(move || model_files::model_disk_usage(&paths))
+        .await
+        .map_err(|e| AppError::Internal(format!("task join error: {e}")))?
+        .map_err(|e| AppError::Internal(format!("failed to measure models: {e:#}")))?;
+    Ok(Json(usage))
+}
+
+/// Deletes a model file and its sidecar from the model directories.
+async fn delete_model(
+    State(state): State<AppState>,
+    Path(filename): Path<String>,
+) -> Result<StatusCode, AppError> {
+    model_files::check_model_filename(&filename)
+        .map_err(|e| AppError::BadRequest(format!("{e:#}")))?;
+
+    let paths = state.inner.config.read().await.paths.clone();
+    let deleted = model_files::delete_model(&paths, &filename)
+        .map_err(|e| AppError::Internal(format!("failed to delete model: {e:#}")))?
+        .ok_or_else(|| AppError::NotFound(format!("model not found: {filename}")))?;
+    info!(path = %deleted.display(), "Deleted model");
+
+    state.rescan_models().await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Renames a model file and its sidecar in place and returns the model under
+/// its new name.
+async fn rename_model(
+    State(state): State<AppState>,
+    Path(filename): Path<String>,
+    Json(payload): Json<RenameModelRequest>,
+) -> Result<Json<ModelEntry>, AppError> {
+    model_files::check_model_filename(&filename)
+        .map_err(|e| AppError::BadRequest(format!("{e:#}")))?;
+    let new_filename = payload.filename.trim().to_string();
+
+    model_files::check_model_filename(&new_filename)
+        .map_err(|e| AppError::BadRequest(format!("{e:#}")))?;
+
+    let paths = state.inner.config.read().await.paths.clone();
+    if paths.find_model_file(&filename).is_none() {
+        return Err(AppError::NotFound(format!("model not found: {filename}")));
+    }
+    if paths.find_model_file(&new_filename).is_some() {
+        return Err(AppError::BadRequest(format!(
+            "a model named {new_filename} already exists"
+        )));
+    }
+    let renamed = model_files::rename_model(&paths, &filename, &new_filename)
+        .map_err(|e| AppError::Internal(format!("failed to rename model: {e:#}")))?
+        .ok_or_else(|| AppError::NotFound(format!("model not found: {filename}")))?;
+    info!(from = %filename, path = %renamed.display(), "Renamed model");
+
+    state.rescan_models().await;
+    state
+        .inner
+        .model_registry
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .list()
+        .iter()
+        .find(|model| model.filename == new_filename)
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| AppError::Internal(format!("renamed model not found: {new_filename}")))
+}
+
+/// Starts a chunked model upload, or returns the unfinished upload of the
+/// same file to resume.
+async fn start_model_upload(
+    State(state): State<AppState>,
+    Json(payload): Json<StartModelUploadRequest>,
+) -> Result<Json<ModelUpload>, AppError> {
+    let filename = payload.filename.trim();
+    if let Some(existing) = state
+        .inner
+        .config
+        .read()
+        .await
+        .paths
+        .find_model_file(filename)
+    {
+        return Err(AppError::BadRequest(format!(
+            "{} already exists",
+            existing.display()
+        )));
+    }
+    let uploads_dir = state
+        .inner
+        .data_dir
+        .join(model_uploads::MODEL_UPLOADS_DIR_NAME);
+    let upload = model_uploads::start_upload(&uploads_dir, payload)
+        .map_err(|e| AppError::BadRequest(format!("{e:#}")))?;
+    Ok(Json(upload))
+}
+
+async fn get_model_upload(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<ModelUpload>, AppError> {
+    let uploads_dir = state
+        .inner
+        .data_dir
+        .join(model_uploads::MODEL_UPLOADS_DIR_NAME);
+    model_uploads::load_upload(&uploads_dir, &id)
+        .map_err(|e| AppError::Internal(format!("{e:#}")))?
+        .map(Json)
+        .ok_or_else(|| AppError::NotFound(format!("upload not found: {id}")))
+}
+
+/// Appends the request body to an upload at `offset`. The chunk that
+/// completes the file also verifies it and moves it into the models
+/// directory.
+async fn upload_model_chunk(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    axum::extract::Query(query): axum::extract::Query<model_uploads::ModelUploadChunkQuery>,
+    body: axum::body::Body,
+) -> Result<Json<ModelUpload>, AppError> {
+    let uploads_dir = state
+        .inner
+        .data_dir
+        .join(model_uploads::MODEL_UPLOADS_DIR_NAME);
+    let mut upload = model_uploads::load_upload(&uploads_dir, &id)
+        .map_err(|e| AppError::Internal(format!("{e:#}")))?
+        .ok_or_else(|| AppError::NotFound(format!("upload not found: {id}")))?;
+    if query.offset != upload.received_bytes {
+        return Err(AppError::BadRequest(format!(
+            "chunk starts at {}, but the upload continues at {}",
+            query.offset, upload.received_bytes
+        )));
+    }
+
+    upload.received_bytes = append_upload_chunk(
+        body,
+        &upload.part_path(&uploads_dir),
+        upload.received_bytes,
+        upload.size_bytes,
+    )
+    .await?;
+    if upload.received_bytes < upload.size_bytes {
+        return Ok(Json(upload));
+    }
+
+    let models_dir = state.inner.config.read().await.paths.models_dir.clone();
+    let finished = upload.clone();
+    let path = tokio::task::spawn_blocking(move || {
+        model_uploads::finish_upload(&uploads_dir, &finished, &models_dir)
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("task join error: {e}")))?
+    .map_err(|e| AppError::BadRequest(format!("{e:#}")))?;
+    info!(path = %path.display(), size_bytes = upload.size_bytes, "Uploaded model");
+
+    state.rescan_models().await;
+    upload.complete = true;
+    Ok(Json(upload))
+}
+
+async fn cancel_model_upload(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let uploads_dir = state
+        .inner
+        .data_dir
+        .join(model_uploads::MODEL_UPLOADS_DIR_NAME);
+    let upload = model_uploads::load_upload(&uploads_dir, &id)
+        .map_err(|e| AppError::Internal(format!("{e:#}")))?
+        .ok_or_else(|| AppError::NotFound(format!("upload not found: {id}")))?;
+    model_uploads::remove_upload(&uploads_dir, &upload);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Appends `body` to the part file, which holds `offset` bytes, and returns
+/// its new length. Bytes that arrived before a dropped connection are kept
+/// so the upload can resume after them.
+async fn append_upload_chunk(
+    body: axum::body::Body,
+    part: &StdPath,
+    offset: u64,
+    size_bytes: u64,
+) -> Result<u64, AppError> {
+    use futures_util::StreamExt;
+    use tokio::io::AsyncWriteExt;
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .append(true)
+        .open(part)
+        .await
+        .map_err(|e| AppError::Internal(format!("failed to open {}: {e}", part.display())))?;
+    let mut received = offset;
+    let mut stream = body.into_data_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                let _ = file.flush().await;
+                return Err(AppError::BadRequest(format!("failed to read upload: {e}")));
+            }
+        };
+        if received + chunk.len() as u64 > size_bytes {
+            let _ = file.set_len(offset).await;
+            return Err(AppError::BadRequest(format!(
+                "upload is larger than its size of {size_bytes} bytes"
+            )));
+        }
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| AppError::Internal(format!("failed to write {}: {e}", part.display())))?;
+        received += chunk.len() as u64;
+    }
+    file.flush()
+        .await
+        .map_err(|e| AppError::Internal(format!("failed to write {}: {e}", part.display())))?;
+    Ok(received)
+}
+
+async fn list_presets(State(state): State<AppState>) -> Json<Vec<PresetResponse>> {
+    let presets: Vec<PresetResponse> = state
+        .inner
+        .presets
+        .iter()
+        .map(|entry| PresetResponse {
+            id: entry.key().clone(),
+            name: entry.value().name.clone(),
+            description: entry.value().description.clone(),
+            workflow: entry.value().workflow.clone(),
+            origin: entry.value().origin.clone(),
+        })
+        .collect();
+    Json(presets)
+}
+
+/// Syncs the community presets now instead of waiting for the next
+/// scheduled sync.
+async fn sync_presets(
+    State(state): State<AppState>,
+) -> Result<Json<CommunitySyncResponse>, AppError> {
+    if state
+        .inner
+        .config
+        .read()
+        .await
+        .presets
+        .community_index_url
+        .trim()
+        .is_empty()
+    {
+        return Err(AppError::BadRequest(
+            "presets.community_index_url is not set".into(),
+        ));
+    }
+    community_presets::sync_community_presets(&state)
+        .await
+        .map(Json)
+        .map_err(|e| AppError::Internal(format!("failed to sync community presets: {e:#}")))
+}
+
+async fn create_preset(
+    State(state): State<AppState>,
+    Json(payload): Json<CreatePresetRequest>,
+) -> (StatusCode, Json<PresetResponse>) {
+    let id = Uuid::new_v4().to_string();
+    let preset = Preset {
+        name: payload.name,
+        description: payload.description,
+        workflow: payload.workflow,
+        origin: PresetOrigin::User,
+    };
+
+    let response = PresetResponse {
+        id: id.clone(),
+        name: preset.name.clone(),
+        description: preset.description.clone(),
+        workflow: preset.workflow.clone(),
+        origin: preset.origin.clone(),
+    };
+
+    state.inner.presets.insert(id, preset);
+
+    (StatusCode::CREATED, Json(response))
+}
+
+// ---------------------------------------------------------------------------
+// Workflow CRUD (user-saved workflows on disk)
+// ---------------------------------------------------------------------------
+
+/// Sanitize a workflow filename: reject path separators, `..`, and empty names.
+fn sanitize_workflow_filename(filename: &str) -> Result<(), AppError> {
+    let trimmed = filename.trim();
+    if trimmed.is_empty() {
+        return Err(AppError::BadRequest("filename must not be empty".into()));
+    }
+    if trimmed.contains('/') || trimmed.contains('\\') {
+        return Err(AppError::BadRequest(
+            "filename must not contain path separators".into(),
+        ));
+    }
+    if trimmed.contains("..") {
+        return Err(AppError::BadRequest(
+            "filename must not contain '..'".into(),
+        ));
+    }
+    Ok(())
+}
+
+async fn list_workflows(State(state): State<AppState>) -> Json<Vec<WorkflowEntry>> {
+    let dir = state.resolve_workflows_dir().await;
+    let mut entries = read_workflow_entries(&dir);
+    entries.sort_by_key(|a| a.name.to_lowercase());
+    Json(entries)
+}
+
+/// Parse every `.json` workflow file in `dir`, skipping unreadable ones.
+fn read_workflow_entries(dir: &StdPath) -> Vec<WorkflowEntry> {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    read_dir
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|e| e == "json"))
+        .filter_map(|path| read_workflow_entry(&path))
+        .collect()
+}
+
+/// Parse one workflow file, or `None` when it is unreadable.
+fn read_workflow_entry(path: &StdPath) -> Option<WorkflowEntry> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    let parsed = serde_json::from_str::<serde_json::Value>(&contents).ok()?;
+    let name = parsed
+        .get("name")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let description = parsed
+        .get("description")
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let workflow = parsed.get("workflow").cloned().unwrap_or_default();
+    let has_interface = workflow
+        .get("interface")
+        .and_then(|i| i.get("inputs"))
+        .and_then(|arr| arr.as_array())
+        .is_some_and(|a| !a.is_empty());
+    let filename = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    Some(WorkflowEntry {
+        filename,
+        name,
+        description,
+        workflow,
+        has_interface,
+    })
+}
+
+async fn save_workflow(
+    State(state): State<AppState>,
+    Json(payload): Json<SaveWorkflowRequest>,
+) -> Result<(StatusCode, Json<WorkflowEntry>), AppError> {
+    let trimmed = payload.name.trim().to_string();
+    if trimmed.is_empty() {
+        return Err(AppError::BadRequest(
+            "workflow name must not be empty".into(),
+        ));
+    }
+    if trimmed.contains('/') || trimmed.contains('\\') {
+        return Err(AppError::BadRequest(
+            "workflow name must not contain path separators".into(),
+        ));
+    }
+    if trimmed.contains("..") {
+        return Err(AppError::BadRequest(
+            "workflow name must not contain '..'".into(),
+        ));
+    }
+
+    let filename = if trimmed.ends_with(".json") {
+        trimmed.clone()
+    } else {
+        format!("{trimmed}.json")
+    };
+
+    sanitize_workflow_filename(&filename)?;
+
+    let dir = state.resolve_workflows_dir().await;
+
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| AppError::Internal(format!("failed to create workflows dir: {e}")))?;
+
+    let doc = serde_json::json!({
+        "name": trimmed,
+        "description": payload.description,
+        "workflow": payload.workflow,
+    });
+
+    let path = dir.join(&filename);
+    let bytes = serde_json::to_vec_pretty(&doc)
+        .map_err(|e| AppError::Internal(format!("failed to serialize workflow: {e}")))?;
+    let keep_versions = state.inner.config.read().await.workflows.keep_versions;
+    workflow_versions::snapshot_workflow(&dir, &filename, keep_versions)
+        .map_err(|e| AppError::Internal(format!("failed to keep previous version: {e:#}")))?;
+    std::fs::write(&path, bytes)
+        .map_err(|e| AppError::Internal(format!("failed to write workflow file: {e}")))?;
+
+    let has_interface = payload
+        .workflow
+        .get("interface")
+        .and_then(|i| i.get("inputs"))
+        .and_then(|arr| arr.as_array())
+        .is_some_and(|a| !a.is_empty());
+
+    Ok((
+        StatusCode::CREATED,
+        Json(WorkflowEntry {
+            filename,
+            name: trimmed,
+            description: payload.description,
+            workflow: payload.workflow,
+            has_interface,
+        }),
+    ))
+}
+
+async fn delete_workflow(
+    State(state): State<AppState>,
+    Path(filename): Path<String>,
+) -> Result<StatusCode, AppError> {
+    sanitize_workflow_filename(&filename)?;
+
+    if !filename.ends_with(".json") {
+        return Err(AppError::BadRequest(
+            "only .json workflow files can be deleted".into(),
+        ));
+    }
+
+    let dir = state.resolve_workflows_dir().await;
+    let path = dir.join(&filename);
+
+    if !path.exists() {
+        return Err(AppError::NotFound(format!(
+            "workflow not found: {filename}"
+        )));
+    }
+
+    // Deleting keeps a version too, so a deleted workflow can be restored.
+    let keep_versions = state.inner.config.read().await.workflows.keep_versions;
+    workflow_versions::snapshot_workflow(&dir, &filename, keep_versions)
+        .map_err(|e| AppError::Internal(format!("failed to keep previous version: {e:#}")))?;
+    std::fs::remove_file(&path)
+        .map_err(|e| AppError::Internal(format!("failed to delete workflow: {e}")))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Zips a workflow with its sub-workflows and a manifest of the models it
+/// needs.
+async fn export_workflow_bundle(
+    State(state): State<AppState>,
+    Path(filename): Path<String>,
+) -> Result<Response, AppError> {
+    sanitize_workflow_filename(&filename)?;
+    let workflows_dir = state.resolve_workflows_dir().await;
+    let paths = state.inner.config.read().await.paths.clone();
+    let sources = workflow_bundle::BundleSources {
+        workflows_dir,
+        presets_dir: paths.presets_dir,
+        models_dir: paths.models_dir,
+        models: state
+            .inner
+            .model_registry
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .list()
+            .to_vec(),
+    };
+    if sources.find_workflow(&filename).is_none() {
+        return Err(AppError::NotFound(format!(
+            "workflow not found: {filename}"
+        )));
+    }
+
+    let bundle_name = format!(
+        "{}.zip",
+        filename.strip_suffix(".json").unwrap_or(&filename)
+    );
+    let bytes = tokio::task::spawn_blocking(move || {
+        workflow_bundle::build_workflow_bundle(&filename, &sources)
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("task join error: {e}")))?
+    .map_err(|e| AppError::BadRequest(format!("failed to export workflow: {e:#}")))?;
+
+    Ok((
+        [
+            (
+                axum::http::header::CONTENT_TYPE,
+                "application/zip".to_string(),
+            ),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{bundle_name}\""),
+            ),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+/// Writes the workflows of a bundle to the workflows directory, keeping the
+/// files they replace as versions, then checks and fetches its models.
+async fn import_workflow_bundle(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<ImportWorkflowBundleQuery>,
+    body: axum::body::Bytes,
+) -> Result<Json<ImportWorkflowBundleResponse>, AppError> {
+    let dir = state.resolve_workflows_dir().await;
+    let keep_versions = state.inner.config.read().await.workflows.keep_versions;
+    let (bundle, workflows) = tokio::task::spawn_blocking(move || {
+        let bundle = workflow_bundle::read_workflow_bundle(&body)
+            .map_err(|e| AppError::BadRequest(format!("invalid workflow bundle: {e:#}")))?;
+        for (filename, _) in &bundle.workflows {
+            sanitize_workflow_filename(filename)?;
+        }
+
+        std::fs::create_dir_all(&dir)
+            .map_err(|e| AppError::Internal(format!("failed to create workflows dir: {e}")))?;
+        let mut workflows = Vec::new();
+        for (filename, contents) in &bundle.workflows {
+            workflow_versions::snapshot_workflow(&dir, filename, keep_versions).map_err(|e| {
+                AppError::Internal(format!("failed to keep previous version: {e:#}"))
+            })?;
+            let path = dir.join(filename);
+            std::fs::write(&path, contents)
+                .map_err(|e| AppError::Internal(format!("failed to write workflow file: {e}")))?;
+            workflows.extend(read_workflow_entry(&path));
+        }
+        Ok::<_, AppError>((bundle, workflows))
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("task join error: {e}")))??;
+
+    let models_dir = state.inner.config.read().await.paths.models_dir.clone();
+    let download = query.download_models.unwrap_or(true);
+    let manifest_models = bundle.manifest.models;
+    let models = tokio::task::spawn_blocking(move || {
+        workflow_bundle::check_bundle_models(&models_dir, &manifest_models, download)
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("task join error: {e}")))?;
+
+    if models
+        .iter()
+        .any(|check| check.status == workflow_bundle::ModelStatus::Downloaded)
+    {
+        let mut registry = state
+            .inner
+            .model_registry
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Err(err) = registry.discover() {
+            warn!(error = %err, "Failed to discover downloaded models");
+        }
+    }
+
+    Ok(Json(ImportWorkflowBundleResponse { workflows, models }))
+}
+
+/// Previous versions of a workflow file, newest first. Deleted workflows
+/// keep their history.
+async fn list_workflow_versions(
+    State(state): State<AppState>,
+    Path(filename): Path<String>,
+) -> Result<Json<Vec<workflow_versions::WorkflowVersion>>, AppError> {
+    sanitize_workflow_filename(&filename)?;
+    let dir = state.resolve_workflows_dir().await;
+    workflow_versions::list_versions(&dir, &filename)
+        .map(Json)
+        .map_err(|e| AppError::Internal(format!("failed to list workflow versions: {e:#}")))
+}
+
+/// Returns `NotFound` unless `version` of `filename` is stored.
+fn ensure_workflow_version(dir: &StdPath, filename: &str, version: i64) -> Result<(), AppError> {
+    let versions = workflow_versions::list_versions(dir, filename)
+        .map_err(|e| AppError::Internal(format!("failed to list workflow versions: {e:#}")))?;
+    if versions.iter().any(|v| v.version == version) {
+        Ok(())
+    } else {
+        Err(AppError::NotFound(format!(
+            "version {version} of workflow {filename} not found"
+        )))
+    }
+}
+
+/// The saved document of one previous version.
+async fn get_workflow_version(
+    State(state): State<AppState>,
+    Path((filename, version)): Path<(String, i64)>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    sanitize_workflow_filename(&filename)?;
+    let dir = state.resolve_workflows_dir().await;
+    ensure_workflow_version(&dir, &filename, version)?;
+    let bytes = workflow_versions::read_version(&dir, &filename, version)
+        .map_err(|e| AppError::Internal(format!("failed to read workflow version: {e:#}")))?;
+    serde_json::from_slice(&bytes)
+        .map(Json)
+        .map_err(|e| AppError::Internal(format!("stored workflow version is not JSON: {e}")))
+}
+
+/// Makes a previous version current again. The replaced contents are kept
+/// as a new version, so restoring can itself be undone.
+async fn restore_workflow_version(
+    State(state): State<AppState>,
+    Path((filename, version)): Path<(String, i64)>,
+) -> Result<Json<WorkflowEntry>, AppError> {
+    sanitize_workflow_filename(&filename)?;
+    let dir = state.resolve_workflows_dir().await;
+    ensure_workflow_version(&dir, &filename, version)?;
+    let keep_versions = state.inner.config.read().await.workflows.keep_versions;
+    workflow_versions::restore_version(&dir, &filename, version, keep_versions)
+        .map_err(|e| AppError::Internal(format!("failed to restore workflow version: {e:#}")))?;
+    read_workflow_entry(&dir.join(&filename))
+        .map(Json)
+        .ok_or_else(|| AppError::Internal(format!("restored workflow {filename} is unreadable")))
+}
+
+/// Converts a workflow into a VapourSynth script. Params fill its
+/// WorkflowInput node as they would for a job.
+async fn export_workflow_vapoursynth(
+    State(state): State<AppState>,
+    Json(payload): Json<ExportWorkflowRequest>,
+) -> Result<Json<ExportWorkflowResponse>, AppError> {
+    let inferred_params = extract_workflow_input_params(&payload.workflow);
+    let mut params = payload.params.or(inferred_params);
+
+    let mut workflow = parse_and_validate_workflow(&state, payload.workflow)?;
+    validate_interface_params(&workflow, params.as_mut())?;
+    if let Some(params) = params.as_ref() {
+        workflow.inject_workflow_input_params(params);
+    }
+
+    let script = export_vapoursynth(&workflow, &state.inner.node_registry)
+        .map_err(|e| AppError::BadRequest(format!("export failed: {e:#}")))?;
+    Ok(Json(ExportWorkflowResponse { script }))
+}
+
+/// Lint warnings for a workflow; only a workflow that fails validation is
+/// rejected.
+async fn lint_workflow(
+    State(state): State<AppState>,
+    Json(payload): Json<LintWorkflowRequest>,
+) -> Result<Json<LintWorkflowResponse>, AppError> {
+    let workflow = parse_and_validate_workflow(&state, payload.workflow)?;
+    let warnings = lint_graph(&workflow, &state.inner.node_registry);
+    Ok(Json(LintWorkflowResponse { warnings }))
+}
+
+async fn get_workflow_interface(
+    State(state): State<AppState>,
+    Path(filename): Path<String>,
+) -> Result<Json<WorkflowInterface>, AppError> {
+    sanitize_workflow_filename(&filename)?;
+
+    let workflows_dir = state.resolve_workflows_dir().await;
+    let config = state.inner.config.read().await;
+    let workflows_path = workflows_dir.join(&filename);
+    let presets_path = config.paths.presets_dir.join(&filename);
+
+    let contents = if workflows_path.exists() {
+        std::fs::read_to_string(&workflows_path)
+    } else if presets_path.exists() {
+        std::fs::read_to_string(&presets_path)
+    } else {
+        return Err(AppError::NotFound(format!(
+            "workflow not found: {filename}"
+        )));
+    };
+
+    let contents =
+        contents.map_err(|e| AppError::Internal(format!("failed to read workflow: {e}")))?;
+    let parsed: serde_json::Value = serde_json::from_str(&contents)
+        .map_err(|e| AppError::BadRequest(format!("invalid JSON: {e}")))?;
+
+    let workflow = parsed.get("workflow").unwrap_or(&parsed);
+    let interface = match workflow.get("interface") {
+        Some(raw) => serde_json::from_value(raw.clone())
+            .map_err(|e| AppError::BadRequest(format!("invalid workflow interface: {e}")))?,
+        None => WorkflowInterface {
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+        },
+    };
+
+    Ok(Json(interface))
+}
+
+async fn list_fs(
+    State(state): State<AppState>,
+    axum::extract::Query(params): axum::extract::Query<FsListQuery>,
+) -> Result<Json<Vec<FsEntry>>, AppError> {
+    let workflows_resolved = state.resolve_workflows_dir().await;
+    let config = state.inner.config.read().await;
+
+    let base_name = params.base.as_deref().unwrap_or("models");
+    let base_dir: PathBuf = match base_name {
+        "models" => config.paths.models_dir.clone(),
+        "presets" => config.paths.presets_dir.clone(),
+        "workflows" => workflows_resolved,
+        _ => {
+            return Err(AppError::Forbidden(format!(
+                "unknown base directory: {base_name}"
+            )));
+        }
+    };
+
+    if !base_dir.exists() {
+        return Ok(Json(vec![]));
+    }
+
+    let canonical_base = base_dir.canonicalize().map_err(|e| {
+        AppError::Internal(format!(
+            "failed to canonicalize base dir {}: {e}",
+            base_dir.display()
+        ))
+    })?;
+
+    let (list_dir, name_filter) = if let Some(ref prefix) = params.prefix {
+        let joined = canonical_base.join(prefix);
+        if joined.is_dir() {
+            (joined, None)
+        } else {
+            let parent = joined.parent().unwrap_or(&canonical_base).to_path_buf();
+            let filter = joined.file_name().map(|n| n.to_string_lossy().to_string());
+            (parent, filter)
+        }
+    } else {
+        (canonical_base.clone(), None)
+    };
+
+    if !list_dir.exists() {
+        return Ok(Json(vec![]));
+    }
+
+    let canonical_list = list_dir.canonicalize().map_err(|e| {
+        AppError::Internal(format!(
+            "failed to canonicalize list dir {}: {e}",
+            list_dir.display()
+        ))
+    })?;
+
+    // SECURITY: reject paths that escape the sandboxed base directory
+    if !canonical_list.starts_with(&canonical_base) {
+        return Err(AppError::Forbidden("path traversal detected".to_string()));
+    }
+
+    let read_dir = match std::fs::read_dir(&canonical_list) {
+        Ok(rd) => rd,
+        Err(_) => return Ok(Json(vec![])),
+    };
+
+    let mut entries: Vec<FsEntry> = Vec::new();
+    for entry in read_dir.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+
+        if file_name.starts_with('.') {
+            continue;
+        }
+
+        if let Some(ref filter) = name_filter {
+            if !file_name.starts_with(filter.as_str()) {
+                continue;
+            }
+        }
+
+        let is_dir = entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
+
+        let rel = canonical_list
+            .join(&file_name)
+            .strip_prefix(&canonical_base)
+            .unwrap_or(StdPath::new(&file_name))
+            .to_string_lossy()
+            .to_string();
+
+        let display_path = format!("{base_name}/{rel}");
+
+        entries.push(FsEntry {
+            name: file_name,
+            is_dir,
+            path: display_path,
+        });
+    }
+
+    entries.sort_by(|a, b| {
+        b.is_dir
+            .cmp(&a.is_dir)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+
+    Ok(Json(entries))
+}
+
+async fn browse_fs(
+    axum::extract::Query(params): axum::extract::Query<FsBrowseQuery>,
+) -> Result<Json<Vec<FsEntry>>, AppError> {
+    let raw_path = params
+        .path
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .unwrap_or(".");
+    browse_directory(raw_path).map(Json)
+}
+
+/// List a host directory for path pickers, expanding `~` and refusing
+/// kernel pseudo-filesystems. Missing directories list as empty.
+fn browse_directory(raw_path: &str) -> Result<Vec<FsEntry>, AppError> {
+    let resolved_path = if raw_path.starts_with('~') {
+        #[cfg(unix)]
+        let home = std::env::var("HOME").unwrap_or_default();
+        #[cfg(windows)]
+        let home = std::env::var("USERPROFILE").unwrap_or_default();
+        format!("{home}{}", &raw_path[1..])
+    } else {
+        raw_path.to_string()
+    };
+
+    let browse_dir = PathBuf::from(resolved_path);
+    if !browse_dir.exists() || !browse_dir.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let canonical_browse = browse_dir.canonicalize().map_err(|e| {
+        AppError::Internal(format!(
+            "failed to canonicalize browse dir {}: {e}",
+            browse_dir.display()
+        ))
+    })?;
+
+    #[cfg(unix)]
+    {
+        if canonical_browse.starts_with(StdPath::new("/proc"))
+            || canonical_browse.starts_with(StdPath::new("/sys"))
+            || canonical_browse.starts_with(StdPath::new("/dev"))
+        {
+            return Err(AppError::Forbidden(
+                "browsing this directory is not allowed".to_string(),
+            ));
+        }
+    }
+
+    let read_dir = match std::fs::read_dir(&canonical_browse) {
+        Ok(rd) => rd,
+        Err(_) => return Ok(vec![]),
+    };
+
+    let mut entries: Vec<FsEntry> = Vec::new();
+    for entry in read_dir.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+
+        if file_name.starts_with('.') {
+            continue;
+        }
+
+        let canonical_entry = match entry.path().canonicalize() {
+            Ok(path) => path,
+            Err(_) => continue,
+        };
+
+        let is_dir = entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
+
+        entries.push(FsEntry {
+            name: file_name,
+            is_dir,
+            path: canonical_entry.to_string_lossy().to_string(),
+        });
+    }
+
+    entries.sort_by(|a, b| {
+        b.is_dir
+            .cmp(&a.is_dir)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    entries.truncate(200);
+
+    Ok(entries)
+}
+
+async fn probe_media_file(
+    State(state): State<AppState>,
+    Json(payload): Json<ProbeRequest>,
+) -> Result<Json<MediaProbe>, AppError> {
+    let roots = state.inner.config.read().await.paths.media_roots.clone();
+    let path = PathBuf::from(payload.path.trim());
+    let path = resolve_in_roots(&path, &roots).map_err(|e| {
+        let missing = e.chain().any(|cause| {
+            cause
+                .downcast_ref::<std::io::Error>()
+                .is_some_and(|err| err.kind() == std::io::ErrorKind::NotFound)
+        });
+        if missing {
+            AppError::NotFound(format!("{e:#}"))
+        } else {
+            AppError::Forbidden(format!("{e:#}"))
+        }
+    })?;
+    if !path.is_file() {
+        return Err(AppError::BadRequest(format!(
+            "{} is not a file",
+            path.display()
+        )));
+    }
+
+    let probe = tokio::task::spawn_blocking(move || probe_media(&path))
+        .await
+        .map_err(|e| AppError::Internal(format!("task join error: {e}")))?
+        .map_err(|e| AppError::BadRequest(format!("{e:#}")))?;
+    Ok(Json(probe))
+}
+
+#[derive(Debug, Deserialize)]
+struct RegisterInputQuery {
+    name: Option<String>,
+}
+
+/// Registers a source file and returns a handle for job params. A JSON body
+/// `{"path": ...}` registers a local file; any other body is stored as an
+/// upload named by the `name` query parameter.
+async fn register_input(
+    State(state): State<AppState>,
+    axum::extract::Query(query): axum::extract::Query<RegisterInputQuery>,
+    headers: axum::http::HeaderMap,
+    body: axum::body::Body,
+) -> Result<(StatusCode, Json<RegisteredInput>), AppError> {
+    let is_json = headers
+        .get(axum::http::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    let registered = if is_json {
+        let bytes = axum::body::to_bytes(body, 64 * 1024)
+            .await
+            .map_err(|e| AppError::BadRequest(format!("failed to read request body: {e}")))?;
+        let payload: RegisterInputRequest = serde_json::from_slice(&bytes)
+            .map_err(|e| AppError::BadRequest(format!("invalid request body: {e}")))?;
+        state
+            .register_local_input(StdPath::new(payload.path.trim()))
+            .await
+            .map_err(|e| AppError::BadRequest(format!("{e:#}")))?
+    } else {
+        let id = Uuid::new_v4().to_string();
+        let name = query.name.unwrap_or_default();
+        let inputs_dir = state.inner.data_dir.join(inputs::INPUTS_DIR_NAME);
+        let path = inputs::upload_path(&inputs_dir, &id, &name)
+            .map_err(|e| AppError::BadRequest(format!("{e:#}")))?;
+        if let Err(err) = write_upload(body, &path).await {
+            let _ = tokio::fs::remove_dir_all(inputs_dir.join(&id)).await;
+            return Err(err);
+        }
+        RegisteredInput::new(&id, path, true).map_err(|e| AppError::Internal(format!("{e:#}")))?
+    };
+
+    info!(handle = %registered.handle, path = %registered.path.display(), managed = registered.managed, "Registered input");
+    Ok((StatusCode::CREATED, Json(registered)))
+}
+
+async fn write_upload(body: axum::body::Body, path: &StdPath) -> Result<(), AppError> {
+    use futures_util::StreamExt;
+    use tokio::io::AsyncWriteExt;
+
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| AppError::Internal(format!("failed to create {}: {e}", dir.display())))?;
+    }
+    let mut file = tokio::fs::File::create(path)
+        .await
+        .map_err(|e| AppError::Internal(format!("failed to create {}: {e}", path.display())))?;
+    let mut stream = body.into_data_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk =
+            chunk.map_err(|e| AppError::BadRequest(format!("failed to read upload: {e}")))?;
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| AppError::Internal(format!("failed to write {}: {e}", path.display())))?;
+    }
+    file.flush()
+        .await
+        .map_err(|e| AppError::Internal(format!("failed to write {}: {e}", path.display())))?;
+    Ok(())
+}
+
+/// The file behind an `input:<id>` handle, or `None` when `value` is not a
+/// handle.
+fn resolve_input_handle(state: &AppState, value: &str) -> Result<Option<PathBuf>, AppError> {
+    let Some(id) = inputs::parse_input_handle(value) else {
+        return Ok(None);
+    };
+    state
+        .inner
+        .registered_inputs
+        .get(id)
+        .map(|path| path.clone())
+        .or_else(|| inputs::find_upload(&state.inner.data_dir.join(inputs::INPUTS_DIR_NAME), id))
+        .map(Some)
+        .ok_or_else(|| AppError::BadRequest(format!("unknown input handle: {value}")))
+}
+
+/// Replace input handles among `params` with the paths they stand for.
+fn resolve_input_handles(
+    state: &AppState,
+    params: &mut HashMap<String, serde_json::Value>,
+) -> Result<(), AppError> {
+    for value in params.values_mut() {
+        let Some(handle) = value.as_str() else {
+            continue;
+        };
+        if let Some(path) = resolve_input_handle(state, handle)? {
+            *value = serde_json::Value::String(path.to_string_lossy().into_owned());
+        }
+    }
+    Ok(())
+}
+
+async fn extract_frames(
+    State(state): State<AppState>,
+    Json(payload): Json<ExtractFramesRequest>,
+) -> Result<(StatusCode, Json<ExtractFramesResponse>), AppError> {
+    if payload.count == 0 || payload.count > 100 {
+        return Err(AppError::BadRequest(
+            "count must be between 1 and 100".to_string(),
+        ));
+    }
+
+    let video_path = StdPath::new(&payload.video_path);
+    if !video_path.exists() {
+        return Err(AppError::BadRequest(format!(
+            "video file not found: {}",
+            payload.video_path
+        )));
+    }
+
+    let preview_id = Uuid::new_v4().to_string();
+    let temp_dir = std::env::temp_dir().join(format!("videnoa-preview-{preview_id}"));
+    std::fs::create_dir_all(&temp_dir)
+        .map_err(|e| AppError::Internal(format!("failed to create temp dir: {e}")))?;
+
+    let probe = crate::runtime::command_for("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-count_frames",
+            "-select_streams",
+            "v:0",
+            "-show_entries",
+            "stream=nb_read_frames",
+            "-of",
+            "csv=p=0",
+            &payload.video_path,
+        ])
+        .output()
+        .map_err(|e| AppError::Internal(format!("ffprobe failed: {e}")))?;
+
+    let total_frames: u64 = String::from_utf8_lossy(&probe.stdout)
+        .trim()
+        .parse()
+        .unwrap_or(1000);
+    let interval = (total_frames / payload.count as u64).max(1);
+
+    let output_pattern = temp_dir.join("frame_%04d.png");
+    let status = crate::runtime::command_for("ffmpeg")
+        .args([
+            "-i",
+            &payload.video_path,
+            "-vf",
+            &format!("select='not(mod(n\\,{interval}))'"),
+            "-frames:v",
+            &payload.count.to_string(),
+            "-vsync",
+            "vfn",
+            output_pattern
+                .to_str()
+                .ok_or_else(|| AppError::Internal("invalid path encoding".to_string()))?,
+        ])
+        .output()
+        .map_err(|e| AppError::Internal(format!("ffmpeg failed: {e}")))?;
+
+    if !status.status.success() {
+        let stderr = String::from_utf8_lossy(&status.stderr);
+        return Err(AppError::Internal(format!("ffmpeg error: {stderr}")));
+    }
+
+    let mut frames = Vec::new();
+    for i in 1..=payload.count {
+        let filename = format!("frame_{i:04}.png");
+        let frame_path = temp_dir.join(&filename);
+        if frame_path.exists() {
+            frames.push(FrameInfo {
+                index: i - 1,
+                url: format!("/api/preview/frames/{preview_id}/{filename}"),
+            });
+        }
+    }
+
+    if frames.is_empty() {
+        return Err(AppError::Internal("ffmpeg produced no frames".to_string()));
+    }
+
+    state
+        .inner
+        .preview_sessions
+        .insert(preview_id.clone(), temp_dir);
+
+    info!(preview_id = %preview_id, frame_count = frames.len(), "Extracted preview frames");
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ExtractFramesResponse { preview_id, frames }),
+    ))
+}
+
+async fn serve_preview_frame(
+    State(state): State<AppState>,
+    Path((preview_id, filename)): Path<(String, String)>,
+) -> Result<Response, AppError> {
+    let session_dir = state
+        .inner
+        .preview_sessions
+        .get(&preview_id)
+        .ok_or_else(|| AppError::NotFound(format!("preview session not found: {preview_id}")))?;
+
+    let file_path = session_dir.join(&filename);
+    if !file_path.exists() {
+        return Err(AppError::NotFound(format!("frame not found: {filename}")));
+    }
+
+    let bytes = tokio::fs::read(&file_path)
+        .await
+        .map_err(|e| AppError::Internal(format!("failed to read frame: {e}")))?;
+
+    Ok((StatusCode::OK, [("content-type", "image/png")], bytes).into_response())
+}
+
+/// Serve the playlist and segments an HLS `StreamOutput` node is writing.
+async fn serve_stream_file(
+    State(state): State<AppState>,
+    Path((stream_id, filename)): Path<(String, String)>,
+) -> Result<Response, AppError> {
+    validate_stream_id(&stream_id).map_err(|err| AppError::BadRequest(err.to_string()))?;
+    let content_type = match filename.rsplit_once('.').map(|(_, ext)| ext) {
+        Some("m3u8") => "application/vnd.apple.mpegurl",
+        Some("ts") => "video/mp2t",
+        Some("m4s") => "video/iso.segment",
+        Some("mp4") => "video/mp4",
+        _ => {
+            return Err(AppError::NotFound(format!(
+                "stream file not found: {filename}"
+            )))
+        }
+    };
+    if filename.contains('/') || filename.contains('\\') || filename.contains("..") {
+        return Err(AppError::BadRequest(
+            "filename must not contain path separators or '..'".into(),
+        ));
+    }
+
+    let file_path = state
+        .inner
+        .data_dir
+        .join(STREAMS_DIR_NAME)
+        .join(&stream_id)
+        .join(&filename);
+    let bytes = match tokio::fs::read(&file_path).await {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Err(AppError::NotFound(format!(
+                "stream file not found: {stream_id}/{filename}"
+            )))
+        }
+        Err(err) => {
+            return Err(AppError::Internal(format!(
+                "failed to read stream file: {err}"
+            )))
+        }
+    };
+
+    // The playlist is rewritten every segment; segments never change.
+    let cache_control = if !filename.ends_with(".m3u8") {
+        "max-age=60"
+    } else {
+        "no-cache"
+    };
+    Ok((
+        StatusCode::OK,
+        [
+            ("content-type", content_type),
+            ("cache-control", cache_control),
+        ],
+        bytes,
+    )
+        .into_response())
+}
+
+async fn process_frame(
+    State(state): State<AppState>,
+    Json(payload): Json<ProcessFrameRequest>,
+) -> Result<Json<ProcessFrameResponse>, AppError> {
+    let session_dir = state
+        .inner
+        .preview_sessions
+        .get(&payload.preview_id)
+        .ok_or_else(|| {
+            AppError::NotFound(format!("preview session not found: {}", payload.preview_id))
+        })?;
+
+    let filename = format!("frame_{:04}.png", payload.frame_index + 1);
+    let frame_path = session_dir.join(&filename);
+    if !frame_path.exists() {
+        return Err(AppError::NotFound(format!(
+            "frame not found: index {}",
+            payload.frame_index
+        )));
+    }
+
+    // TODO(task 4.3): actual frame processing through inference pipeline
+    let processed_url = format!("/api/preview/frames/{}/{}", payload.preview_id, filename);
+
+    Ok(Json(ProcessFrameResponse { processed_url }))
+}
+
+#[derive(Deserialize)]
+pub struct JellyfinProxyQuery {
+    /// Name of a configured server; replaces `url` and `api_key`.
+    pub server: Option<String>,
+    pub url: Option<String>,
+    pub api_key: Option<String>,
+    pub library_id: Option<String>,
+    /// Only items whose name contains this.
+    pub search: Option<String>,
+    pub start_index: Option<u32>,
+    /// Page size; defaults to [`JELLYFIN_DEFAULT_PAGE_SIZE`].
+    pub limit: Option<u32>,
+    /// `name` (default), `date_added`, `premiere_date` or `episode`.
+    pub sort_by: Option<String>,
+    /// `asc` (default) or `desc`.
+    pub sort_order: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct JellyfinImageQuery {
+    pub server: Option<String>,
+    pub url: Option<String>,
+    pub api_key: Option<String>,
+    /// `Primary` (default), `Backdrop`, `Thumb`, `Logo` or `Banner`.
+    #[serde(rename = "type")]
+    pub image_type: Option<String>,
+    pub max_width: Option<u32>,
+}
+
+const JELLYFIN_DEFAULT_PAGE_SIZE: u32 = 100;
+const JELLYFIN_MAX_PAGE_SIZE: u32 = 500;
+const JELLYFIN_IMAGE_TYPES: &[&str] = &["Primary", "Backdrop", "Thumb", "Logo", "Banner"];
+const JELLYFIN_DEFAULT_IMAGE_WIDTH: u32 = 300;
+const JELLYFIN_MAX_IMAGE_WIDTH: u32 = 1920;
+
+/// Credentials of the configured `server`, or the `url` and `api_key` given
+/// with the request, falling back to the default server.
+async fn jellyfin_credentials(
+    state: &AppState,
+    server: Option<&str>,
+    url: Option<&str>,
+    api_key: Option<&str>,
+) -> Result<ServerCredentials, AppError> {
+    let config = state.inner.config.read().await.jellyfin.clone();
+    let server = server
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .or_else(|| {
+            (url.is_none() && !config.default_server.is_empty())
+                .then_some(config.default_server.as_str())
+        });
+    match (server, url, api_key) {
+        (Some(name), _, _) => {
+            crate::jellyfin::server_credentials(&config, &state.inner.secrets, name)
+                .map_err(|e| AppError::BadRequest(format!("{e:#}")))
+        }
+        (None, Some(url), Some(api_key)) => Ok(ServerCredentials {
+            url: url.trim().to_string(),
+            api_key: api_key.trim().to_string(),
+        }),
+        _ => Err(AppError::BadRequest(
+            "either server or url and api_key are required".into(),
+        )),
+    }
+}
+
+async fn jellyfin_client(
+    state: &AppState,
+    server: Option<&str>,
+    url: Option<&str>,
+    api_key: Option<&str>,
+) -> Result<JellyfinClient, AppError> {
+    let credentials = jellyfin_credentials(state, server, url, api_key).await?;
+    JellyfinClient::new(&credentials.url, &credentials.api_key)
+        .map_err(|e| AppError::BadRequest(e.to_string()))
+}
+
+async fn jellyfin_libraries(
+    State(state): State<AppState>,
+    axum::extract::Query(params): axum::extract::Query<JellyfinProxyQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let client = jellyfin_client(
+        &state,
+        params.server.as_deref(),
+        params.url.as_deref(),
+        params.api_key.as_deref(),
+    )
+    .await?;
+
+    let libraries = client
+        .get_libraries()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(serde_json::to_value(libraries).unwrap_or_default()))
+}
+
+async fn jellyfin_items(
+    State(state): State<AppState>,
+    axum::extract::Query(params): axum::extract::Query<JellyfinProxyQuery>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let client = jellyfin_client(
+        &state,
+        params.server.as_deref(),
+        params.url.as_deref(),
+        params.api_key.as_deref(),
+    )
+    .await?;
+
+    let query = jellyfin_item_query(params)?;
+    let items = client
+        .get_items(&query)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(serde_json::to_value(items).unwrap_or_default()))
+}
+
+/// Builds one page of a library listing from the proxy query.
+fn jellyfin_item_query(params: JellyfinProxyQuery) -> Result<ItemQuery, AppError> {
+    let sort_by = match params.sort_by.as_deref().unwrap_or("name") {
+        "name" => "SortName",
+        "date_added" => "DateCreated,SortName",
+        "premiere_date" => "PremiereDate,SortName",
+        "episode" => "SeriesSortName,ParentIndexNumber,IndexNumber,SortName",
+        other => {
+            return Err(AppError::BadRequest(format!(
+                "sort_by must be name, date_added, premiere_date or episode, got '{other}'"
+            )))
+        }
+    };
+    let sort_order = match params.sort_order.as_deref().unwrap_or("asc") {
+        "asc" => "Ascending",
+        "desc" => "Descending",
+        other => {
+            return Err(AppError::BadRequest(format!(
+                "sort_order must be asc or desc, got '{other}'"
+            )))
+        }
+    };
+    let limit = params.limit.unwrap_or(JELLYFIN_DEFAULT_PAGE_SIZE);
+    if limit == 0 || limit > JELLYFIN_MAX_PAGE_SIZE {
+        return Err(AppError::BadRequest(format!(
+            "limit must be between 1 and {JELLYFIN_MAX_PAGE_SIZE}"
+        )));
+    }
+
+    Ok(ItemQuery {
+        parent_id: params.library_id,
+        include_item_types: Some("Movie,Episode".to_string()),
+        search_term: params
+            .search
+            .map(|search| search.trim().to_string())
+            .filter(|search| !search.is_empty()),
+        limit: Some(limit),
+        start_index: Some(params.start_index.unwrap_or(0)),
+        fields: Some("Path,Overview".to_string()),
+        recursive: Some(true),
+        sort_by: Some(sort_by.to_string()),
+        sort_order: Some(sort_order.to_string()),
+    })
+}
+
+/// Proxies an item image, so the browser can show posters without the API
+/// key in an `<img>` URL pointing at the Jellyfin server.
+async fn jellyfin_image(
+    State(state): State<AppState>,
+    Path(item_id): Path<String>,
+    axum::extract::Query(params): axum::extract::Query<JellyfinImageQuery>,
+) -> Result<Response, AppError> {
+    if item_id.is_empty()
+        || !item_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return Err(AppError::BadRequest(format!(
+            "invalid Jellyfin item id: {item_id}"
+        )));
+    }
+    let image_type = params.image_type.as_deref().unwrap_or("Primary");
+    if !JELLYFIN_IMAGE_TYPES.contains(&image_type) {
+        return Err(AppError::BadRequest(format!(
+            "type must be one of {}",
+            JELLYFIN_IMAGE_TYPES.join(", ")
+        )));
+    }
+    let max_width = params
+        .max_width
+        .unwrap_or(JELLYFIN_DEFAULT_IMAGE_WIDTH)
+        .clamp(16, JELLYFIN_MAX_IMAGE_WIDTH);
+
+    let client = jellyfin_client(
+        &state,
+        params.server.as_deref(),
+        params.url.as_deref(),
+        params.api_key.as_deref(),
+    )
+    .await?;
+    let image = client
+        .get_item_image(&item_id, image_type, max_width)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .ok_or_else(|| AppError::NotFound(format!("item {item_id} has no {image_type} image")))?;
+
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, image.content_type),
+            (
+                axum::http::header::CACHE_CONTROL,
+                "private, max-age=3600".to_string(),
+            ),
+        ],
+        image.bytes,
+    )
+        .into_response())
+}
+
+#[derive(Deserialize)]
+pub struct JellyfinProbeQuery {
+    pub server: Option<String>,
+    pub url: Option<String>,
+    pub api_key: Option<String>,
+    #[serde(default)]
+    pub min_width: u32,
+    #[serde(default)]
+    pub min_height: u32,
+    /// Comma-separated, e.g. `hevc,av1`.
+    pub codecs: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JellyfinProbeResponse {
+    pub item_id: String,
+    pub name: String,
+    pub video: Option<MediaStream>,
+    pub streams: Vec<MediaStream>,
+    /// Whether the item already meets the target; `None` without a target.
+    pub meets_target: Option<bool>,
+    pub reason: Option<String>,
+}
+
+/// Report an item's media streams and whether it already meets the given
+/// target, i.e. whether a `JellyfinVideo` node with the same skip rules
+/// would skip it.
+async fn jellyfin_probe_item(
+    State(state): State<AppState>,
+    Path(item_id): Path<String>,
+    axum::extract::Query(params): axum::extract::Query<JellyfinProbeQuery>,
+) -> Result<Json<JellyfinProbeResponse>, AppError> {
+    let client = jellyfin_client(
+        &state,
+        params.server.as_deref(),
+        params.url.as_deref(),
+        params.api_key.as_deref(),
+    )
+    .await?;
+    let item = client
+        .get_item(&item_id)
+        .await
+        .map_err(|e| AppError::Internal(format!("{e:#}")))?;
+
+    let rule = SkipRule {
+        min_width: params.min_width,
+        min_height: params.min_height,
+        codecs: params
+            .codecs
+            .as_deref()
+            .map(crate::jellyfin::parse_codec_list)
+            .unwrap_or_default(),
+    };
+    let reason = rule.check(&item);
+    Ok(Json(JellyfinProbeResponse {
+        item_id: item.id.clone(),
+        name: item.name.clone(),
+        video: item.video_stream().cloned(),
+        meets_target: (!rule.is_empty()).then_some(reason.is_some()),
+        reason,
+        streams: item.media_streams,
+    }))
+}
+
+/// A configured server; the API key itself never leaves the secret store.
+#[derive(Debug, Serialize)]
+pub struct JellyfinServerResponse {
+    pub name: String,
+    pub url: String,
+    pub api_key_secret: String,
+    pub api_key_set: bool,
+    /// Whether this is `jellyfin.default_server`.
+    pub default: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PutJellyfinServerRequest {
+    pub url: String,
+    /// Stored encrypted; may be left out to keep the current key.
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Make this the default server.
+    #[serde(default)]
+    pub default: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JellyfinServerTestResponse {
+    pub name: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+fn jellyfin_server_response(
+    state: &AppState,
+    server: &JellyfinServerConfig,
+    default_server: &str,
+) -> Result<JellyfinServerResponse, AppError> {
+    let api_key_set = state
+        .inner
+        .secrets
+        .get(&server.api_key_secret)
+        .map_err(|e| AppError::Internal(format!("failed to read secrets: {e:#}")))?
+        .is_some();
+    Ok(JellyfinServerResponse {
+        name: server.name.clone(),
+        url: server.url.clone(),
+        api_key_secret: server.api_key_secret.clone(),
+        api_key_set,
+        default: server.name == default_server,
+    })
+}
+
+async fn list_jellyfin_servers(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<JellyfinServerResponse>>, AppError> {
+    let config = state.inner.config.read().await.jellyfin.clone();
+    let servers = config
+        .servers
+        .iter()
+        .map(|server| jellyfin_server_response(&state, server, &config.default_server))
+        .collect::<Result<_, _>>()?;
+    Ok(Json(servers))
+}
+
+/// Add or update a named server. The API key goes into the encrypted secret
+/// store and the config only keeps the secret's name.
+async fn put_jellyfin_server(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+    Json(payload): Json<PutJellyfinServerRequest>,
+) -> Result<Json<JellyfinServerResponse>, AppError> {
+    crate::jellyfin::validate_server_name(&name)
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+    let url = payload.url.trim().to_string();
+    if !url::Url::parse(&url).is_ok_and(|parsed| matches!(parsed.scheme(), "http" | "https")) {
+        return Err(AppError::BadRequest(format!(
+            "url must be an http(s) URL, got '{url}'"
+        )));
+    }
+    let api_key = payload
+        .api_key
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty());
+
+    let mut next = state.inner.config.read().await.clone();
+    let existing = next.jellyfin.server(&name).cloned();
+    if existing.is_none() && api_key.is_none() {
+        return Err(AppError::BadRequest(
+            "api_key is required for a new server".into(),
+        ));
+    }
+    let server = JellyfinServerConfig {
+        name: name.clone(),
+        url,
+        api_key_secret: existing
+            .map(|server| server.api_key_secret)
+            .unwrap_or_else(|| crate::jellyfin::server_api_key_secret(&name)),
+    };
+    if let Some(api_key) = api_key {
+        state
+            .inner
+            .secrets
+            .put(&server.api_key_secret, &api_key)
+            .map_err(|e| AppError::Internal(format!("failed to store API key: {e:#}")))?;
+    }
+
+    match next.jellyfin.servers.iter_mut().find(|s| s.name == name) {
+        Some(slot) => *slot = server.clone(),
+        None => next.jellyfin.servers.push(server.clone()),
+    }
+    if payload.default {
+        next.jellyfin.default_server = name.clone();
+    }
+    let default_server = next.jellyfin.default_server.clone();
+    next.save_to_path(&state.inner.config_path)?;
+    state.apply_config(next, ConfigChangeSource::Api).await;
+
+    info!(server = %name, "Jellyfin server saved");
+    Ok(Json(jellyfin_server_response(
+        &state,
+        &server,
+        &default_server,
+    )?))
+}
+
+async fn delete_jellyfin_server(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let mut next = state.inner.config.read().await.clone();
+    let Some(index) = next.jellyfin.servers.iter().position(|s| s.name == name) else {
+        return Err(AppError::NotFound(format!(
+            "Jellyfin server not found: {name}"
+        )));
+    };
+    let removed = next.jellyfin.servers.remove(index);
+    if next.jellyfin.default_server == name {
+        next.jellyfin.default_server.clear();
+    }
+    next.save_to_path(&state.inner.config_path)?;
+    state.apply_config(next, ConfigChangeSource::Api).await;
+
+    // Only drop keys this endpoint created; a secret the user named in the
+    // config may be shared with other settings.
+    if removed.api_key_secret == crate::jellyfin::server_api_key_secret(&name) {
+        state
+            .inner
+            .secrets
+            .delete(&removed.api_key_secret)
+            .map_err(|e| AppError::Internal(format!("failed to delete API key: {e:#}")))?;
+    }
+
+    info!(server = %name, "Jellyfin server deleted");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Connect to a configured server. Connection failures are reported in the
+/// body rather than as an error status.
+async fn test_jellyfin_server(
+    State(state): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<JellyfinServerTestResponse>, AppError> {
+    let config = state.inner.config.read().await.jellyfin.clone();
+    if config.server(&name).is_none() {
+        return Err(AppError::NotFound(format!(
+            "Jellyfin server not found: {name}"
+        )));
+    }
+
+    let result = match crate::jellyfin::server_credentials(&config, &state.inner.secrets, &name)
+        .and_then(|credentials| JellyfinClient::new(&credentials.url, &credentials.api_key))
+    {
+        Ok(client) => client.get_system_info().await,
+        Err(err) => Err(err),
+    };
+    Ok(Json(match result {
+        Ok(info) => JellyfinServerTestResponse {
+            name,
+            ok: true,
+            server_name: Some(info.server_name),
+            version: Some(info.version),
+            error: None,
+        },
+        Err(err) => JellyfinServerTestResponse {
+            name,
+            ok: false,
+            server_name: None,
+            version: None,
+            error: Some(format!("{err:#}")),
+        },
+    }))
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JellyfinStreamRequest {
+    /// Name of a configured server; replaces `jellyfin_url` and `api_key`.
+    #[serde(default)]
+    pub server: Option<String>,
+    #[serde(default)]
+    pub jellyfin_url: Option<String>,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    pub item_id: String,
+    pub workflow: serde_json::Value,
+    #[serde(default)]
+    pub workflow_name: Option<String>,
+    #[serde(default)]
+    pub params: Option<HashMap<String, serde_json::Value>>,
+    /// Param that receives the Jellyfin download URL (default `input`).
+    #[serde(default)]
+    pub input_param: Option<String>,
+    /// Param that receives the HLS playlist path (default `output`).
+    #[serde(default)]
+    pub output_param: Option<String>,
+    /// Defaults to the item id.
+    #[serde(default)]
+    pub stream_id: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct JellyfinStreamResponse {
+    pub id: String,
+    pub status: JobStatus,
+    pub created_at: DateTime<Utc>,
+    pub stream_id: String,
+    pub playlist_url: String,
+}
+
+/// Experimental: run `workflow` on a Jellyfin item and encode straight into a
+/// growing HLS playlist, so the upscaled episode can be watched while the job
+/// is still running. The workflow's input param gets the item's download URL
+/// and its output param an `.m3u8` path under the streams directory.
+async fn start_jellyfin_stream(
+    State(state): State<AppState>,
+    Json(payload): Json<JellyfinStreamRequest>,
+) -> Result<(StatusCode, Json<JellyfinStreamResponse>), AppError> {
+    let item_id = payload.item_id.trim();
+    if item_id.is_empty() {
+        return Err(AppError::BadRequest("item_id must not be empty".into()));
+    }
+    let stream_id = payload
+        .stream_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .unwrap_or(item_id)
+        .to_string();
+    validate_stream_id(&stream_id).map_err(|err| AppError::BadRequest(err.to_string()))?;
+    let credentials = jellyfin_credentials(
+        &state,
+        payload.server.as_deref(),
+        payload.jellyfin_url.as_deref(),
+        payload.api_key.as_deref(),
+    )
+    .await?;
+    let source_url =
+        JellyfinVideoNode::build_download_url(&credentials.url, item_id, &credentials.api_key)
+            .map_err(|err| AppError::BadRequest(err.to_string()))?;
+
+    let stream_dir = state.inner.data_dir.join(STREAMS_DIR_NAME).join(&stream_id);
+    let playlist = stream_dir.join(HLS_PLAYLIST_NAME);
+    let playlist_value = serde_json::Value::String(playlist.to_string_lossy().into_owned());
+    let stream_busy = state.inner.jobs.iter().any(|job| {
+        !is_terminal(job.status)
+            && job
+                .params
+                .as_ref()
+                .is_some_and(|params| params.values().any(|value| *value == playlist_value))
+    });
+    if stream_busy {
+        return Err(AppError::BadRequest(format!(
+            "stream '{stream_id}' is already being encoded"
+        )));
+    }
+
+    let workflow_name = payload
+        .workflow_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(ToOwned::to_owned)
+        .unwrap_or_else(|| {
+            workflow_name_from_request(&payload.workflow, DEFAULT_WORKFLOW_NAME_API_JOBS)
+        });
+    let mut params = payload.params.unwrap_or_default();
+    params.insert(
+        payload.input_param.unwrap_or_else(|| "input".to_string()),
+        serde_json::Value::String(source_url.to_string()),
+    );
+    params.insert(
+        payload.output_param.unwrap_or_else(|| "output".to_string()),
+        playlist_value,
+    );
+    let workflow = parse_and_validate_workflow(&state, payload.workflow)?;
+
+    // Segments from an earlier run of the same stream would be served
+    // alongside the new playlist.
+    match std::fs::remove_dir_all(&stream_dir) {
+        Ok(()) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => {
+            return Err(AppError::Internal(format!(
+                "failed to clear stream directory: {err}"
+            )))
+        }
+    }
+    std::fs::create_dir_all(&stream_dir)
+        .map_err(|err| AppError::Internal(format!("failed to create stream directory: {err}")))?;
+
+    let created = create_and_spawn_job(
+        &state,
+        workflow,
+        Some(params),
+        workflow_name,
+        WORKFLOW_SOURCE_API_JELLYFIN_STREAM.to_string(),
+        None,
+        None,
+    )
+    .await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(JellyfinStreamResponse {
+            id: created.id,
+            status: created.status,
+            created_at: created.created_at,
+            playlist_url: hls_playlist_url(&stream_id),
+            stream_id,
+        }),
+    ))
+}
+
+/// One semaphore per resource class, sized from `jobs.max_*_jobs`. A class
+/// whose limit is 0 shares the single GPU slot.
+fn class_semaphores(config: &JobsConfig) -> HashMap<ResourceClass, Arc<Semaphore>> {
+    let gpu = Arc::new(Semaphore::new(1));
+    [
+        (ResourceClass::Cpu, config.max_cpu_jobs),
+        (ResourceClass::Network, config.max_network_jobs),
+        (ResourceClass::Disk, config.max_disk_jobs),
+    ]
+    .into_iter()
+    .map(|(class, limit)| {
+        let semaphore = match limit {
+            0 => Arc::clone(&gpu),
+            limit => Arc::new(Semaphore::new(limit)),
+        };
+        (class, semaphore)
+    })
+    .chain([(ResourceClass::Gpu, Arc::clone(&gpu))])
+    .collect()
+}
+
+/// The semaphores a job with `classes` takes a slot from, each once.
+fn job_semaphores(state: &AppState, classes: &BTreeSet<ResourceClass>) -> Vec<Arc<Semaphore>> {
+    let mut semaphores: Vec<Arc<Semaphore>> = Vec::new();
+    for class in classes {
+        let semaphore = &state.inner.class_semaphores[class];
+        if !semaphores.iter().any(|held| Arc::ptr_eq(held, semaphore)) {
+            semaphores.push(Arc::clone(semaphore));
+        }
+    }
+    semaphores
+}
+
+/// Takes a slot from every semaphore or from none. A job never holds one
+/// class's slot while it waits for another, so a job queued for the GPU
+/// does not keep CPU, disk or network jobs waiting behind it.
+async fn acquire_slots(
+    semaphores: &[Arc<Semaphore>],
+) -> Result<Vec<OwnedSemaphorePermit>, AcquireError> {
+    let mut permits: Vec<OwnedSemaphorePermit> = Vec::with_capacity(semaphores.len());
+    loop {
+        let mut blocked = None;
+        for semaphore in semaphores {
+            if permits
+                .iter()
+                .any(|permit| Arc::ptr_eq(permit.semaphore(), semaphore))
+            {
+                continue;
+            }
+            match Arc::clone(semaphore).try_acquire_owned() {
+                Ok(permit) => permits.push(permit),
+                Err(_) => {
+                    blocked = Some(semaphore);
+                    break;
+                }
+            }
+        }
+        let Some(blocked) = blocked else {
+            return Ok(permits);
+        };
+
+        // Give everything back and wait for the contended class alone.
+        permits.clear();
+        permits.push(Arc::clone(blocked).acquire_owned().await?);
+    }
+}
+
+async fn run_job(state: AppState, job_id: String) {
+    let (cancel_token, semaphores) = {
+        let job = match state.inner.jobs.get(&job_id) {
+            Some(j) => j,
+            None => return,
+        };
+        let classes = job
+            .workflow
+            .resource_classes(&state.inner.node_registry)
+            .unwrap_or_else(|_| BTreeSet::from([ResourceClass::Gpu]));
+        (job.cancel_token.clone(), job_semaphores(&state, &classes))
+    };
+
+    // A paused job gives its slots back and waits for a resume; the job may
+    // also have been claimed elsewhere (e.g. by a worker) in the meantime.
+    let (_permits, running_snapshot) = loop {
+        let permit = tokio::select! {
+            permit = acquire_slots(&semaphores) => {
+                match permit {
+                    Ok(p) => p,
+                    Err(_) => return,
+                }
+            }
+            _ = cancel_token.cancelled() => {
+                return;
+            }
+        };
+
+        let resumed = state.inner.jobs_resumed.notified();
+        let Some(mut job) = state.inner.jobs.get_mut(&job_id) else {
+            return;
+        };
+        match job.status {
+            JobStatus::Queued => {
+                job.status = JobStatus::Running;
+                job.started_at = Some(Utc::now());
+                break (permit, job.clone());
+            }
+            JobStatus::Paused => {
+                drop(job);
+                drop(permit);
+                tokio::select! {
+                    _ = resumed => {}
+                    _ = cancel_token.cancelled() => return,
+                }
+            }
+            _ => return,
+        }
+    };
+
+    if let Err(err) = state.persist_job_snapshot(&running_snapshot) {
+        error!(job_id = %job_id, error = ?err, "Failed to persist running transition");
+    }
+
+    let result = 'execute: {
+        let (mut workflow, mut job_params, cancel_token, step_gate) = {
+            let Some(job) = state.inner.jobs.get(&job_id) else {
+                return;
+            };
+            (
+                job.workflow.clone(),
+                job.params.clone(),
+                job.cancel_token.clone(),
+                job.step_gate.clone(),
+            )
+        };
+        let inner = Arc::clone(&state.inner);
+        let (trt_cache_dir, models_dir, profiling_enabled, jellyfin_config, torrents_config) = {
+            let config = state.inner.config.read().await;
+            (
+                config.paths.trt_cache_dir.clone(),
+                config.paths.models_dir.clone(),
+                config.performance.profiling_enabled,
+                config.jellyfin.clone(),
+                config.torrents.clone(),
+            )
+        };
+
+        // Clone the broadcast sender before entering the blocking closure
+        // to avoid holding the DashMap read lock across the block_in_place boundary.
+        let ws_tx = state.inner.progress_senders.get(&job_id).map(|r| r.clone());
+
+        let job_id_for_closure = job_id.clone();
+
+        // Secret references and Jellyfin server names are resolved on this
+        // execution copy only; the stored job keeps the placeholders.
+        let secrets_resolved = state
+            .inner
+            .secrets
+            .resolve_graph_refs(&mut workflow)
+            .and_then(|()| match job_params.as_mut() {
+                Some(params) => params
+                    .values_mut()
+                    .try_for_each(|value| state.inner.secrets.resolve_refs(value)),
+                None => Ok(()),
+            })
+            .and_then(|()| {
+                crate::jellyfin::resolve_server_refs(
+                    &mut workflow,
+                    &jellyfin_config,
+                    &state.inner.secrets,
+                )
+            })
+            .and_then(|()| {
+                crate::nodes::http_request::resolve_auth_secrets(
+                    &mut workflow,
+                    &state.inner.secrets,
+                )
+            });
+        if let Err(err) = secrets_resolved {
+            break 'execute Err(err);
+        }
+
+        match crate::jellyfin::skip_reason(&workflow, job_params.as_ref()).await {
+            Ok(Some(reason)) => {
+                skip_job(&state, &job_id, reason);
+                return;
+            }
+            Ok(None) => {}
+            Err(err) => {
+                warn!(job_id = %job_id, error = %format!("{err:#}"), "Could not check Jellyfin skip rules; running the job");
+            }
+        }
+
+        if workflow.has_video_frames_edges() {
+            if let Some(params) = job_params.as_ref() {
+                workflow.inject_workflow_input_params(params);
+            }
+            job_params = None;
+        }
+
+        let services = NodeServices::default()
+            .with_secrets(state.inner.secrets.clone())
+            .with_scratch_dir(state.inner.data_dir.join(SCRATCH_DIR_NAME))
+            .with_cancel_token(cancel_token.clone())
+            .with_torrents_config(torrents_config);
+        let trace = JobTrace::new();
+        state.inner.job_traces.insert(job_id.clone(), trace.clone());
+        let monitor = JobMonitor::new();
+        state
+            .inner
+            .job_monitors
+            .insert(job_id.clone(), monitor.clone());
+        let stepper = step_gate.map(|gate| {
+            let ws_tx = ws_tx.clone();
+            Stepper::new(
+                gate,
+                Arc::new(move |pause: StepPause| {
+                    if let Some(tx) = &ws_tx {
+                        let _ = tx.send(JobWsEvent::from(pause));
+                    }
+                }),
+            )
+        });
+
+        if let Some(params) = job_params {
+            tokio::task::block_in_place(move || {
+                let mut debug_throttle =
+                    NodeDebugEventThrottle::new(Duration::from_millis(PRINT_PREVIEW_THROTTLE_MS));
+                let ws_tx_for_debug = ws_tx.clone();
+                let mut node_debug_cb = move |event: NodeDebugValueEvent| {
+                    if !debug_throttle.should_emit(&event.node_id, Instant::now()) {
+                        return;
+                    }
+                    if let Some(tx) = &ws_tx_for_debug {
+                        let _ = tx.send(JobWsEvent::from(event));
+                    }
+                };
+
+                let port_params = port_params_from_json(&params, workflow.interface.as_ref());
+                let ctx = crate::node::ExecutionContext {
+                    debug_sink: Some(job_debug_sink(ws_tx.clone())),
+                    services,
+                    trace: Some(trace),
+                    stepper,
+                    ..Default::default()
+                };
+                SequentialExecutor::execute_with_params_and_debug_hook(
+                    &workflow,
+                    &inner.node_registry,
+                    port_params,
+                    &ctx,
+                    Some(&mut node_debug_cb),
+                )
+            })
+        } else {
+            // No params: use execute_with_context with video compile support
+            // Use block_in_place (NOT spawn_blocking) because the executor internally
+            // calls block_in_place at executor.rs:67. Nesting block_in_place inside
+            // spawn_blocking panics; block_in_place inside block_in_place is a no-op.
+            let hardware = Arc::new(Mutex::new(None::<HardwareUsage>));
+            let _stop_hardware_sampler =
+                profiling_enabled.then(|| spawn_hardware_sampler(Arc::clone(&hardware)));
+            tokio::task::block_in_place(move || {
+                let benchmarks = BenchmarkStore::open(&inner.data_dir)
+                    .inspect_err(|err| warn!(error = %err, "Benchmark cache unavailable"))
+                    .ok();
+                let compile_ctx = VideoCompileContext::new(trt_cache_dir)
+                    .with_streams_dir(inner.data_dir.join(STREAMS_DIR_NAME))
+                    .with_auto_models(AutoModelSource {
+                        models_dir,
+                        benchmarks,
+                    })
+                    .with_debug_sink(job_debug_sink(ws_tx.clone()))
+                    .with_services(services)
+                    .with_trace(trace)
+                    .with_monitor(monitor)
+                    .with_frame_preview_sink(job_frame_preview_sink(
+                        Arc::clone(&inner),
+                        job_id_for_closure.clone(),
+                        ws_tx.clone(),
+                    ));
+                let compile_ctx = match stepper {
+                    Some(stepper) => compile_ctx.with_stepper(stepper),
+                    None => compile_ctx,
+                };
+                let fps_baseline = Mutex::new(None::<ProgressFpsBaseline>);
+                let history_sampler = Mutex::new(ProgressHistorySampler::new(
+                    Duration::from_millis(PROGRESS_HISTORY_SAMPLE_INTERVAL_MS),
+                ));
+                let ws_tx_for_progress = ws_tx.clone();
+                let ws_tx_for_debug = ws_tx.clone();
+                let phase_reporter = job_phase_reporter(
+                    Arc::clone(&inner),
+                    job_id_for_closure.clone(),
+                    ws_tx.clone(),
+                );
+
+                let inner_for_cb = Arc::clone(&inner);
+                let progress_cb: Box<dyn Fn(u64, Option<u64>, Option<u64>) + Send> =
+                    Box::new(move |current_frame, total_frames, _hint| {
+                        let now = Instant::now();
+                        let fps = {
+                            let mut baseline_guard = match fps_baseline.lock() {
+                                Ok(guard) => guard,
+                                Err(poisoned) => poisoned.into_inner(),
+                            };
+                            let (next_baseline, next_fps) = estimate_input_fps_from_second_frame(
+                                *baseline_guard,
+                                current_frame,
+                                now,
+                            );
+                            *baseline_guard = next_baseline;
+                            next_fps as f64
+                        };
+                        let eta = total_frames.and_then(|total| {
+                            if fps > 0.0 && current_frame < total {
+                                Some((total - current_frame) as f64 / fps)
+                            } else {
+                                None
+                            }
+                        });
+
+                        let mut update = ProgressUpdate {
+                            current_frame,
+                            total_frames,
+                            fps: fps as f32,
+                            eta_seconds: eta,
+                            phase: None,
+                            stages: stage_progress(),
+                            hardware: match hardware.lock() {
+                                Ok(guard) => *guard,
+                                Err(poisoned) => *poisoned.into_inner(),
+                            },
+                        };
+
+                        if let Some(mut job) = inner_for_cb.jobs.get_mut(&job_id_for_closure) {
+                            update.phase = job.progress.as_ref().and_then(|p| p.phase);
+                            job.progress = Some(update.clone());
+                        }
+
+                        let should_sample = match history_sampler.lock() {
+                            Ok(mut sampler) => sampler.should_sample(now),
+                            Err(poisoned) => poisoned.into_inner().should_sample(now),
+                        };
+                        if should_sample {
+                            record_progress_history_point(
+                                &inner_for_cb,
+                                &job_id_for_closure,
+                                &update,
+                            );
+                        }
+
+                        if let Some(tx) = &ws_tx_for_progress {
+                            let _ = tx.send(JobWsEvent::from(update));
+                        }
+                    });
+
+                let mut debug_throttle =
+                    NodeDebugEventThrottle::new(Duration::from_millis(PRINT_PREVIEW_THROTTLE_MS));
+                let mut node_debug_cb = move |event: NodeDebugValueEvent| {
+                    if !debug_throttle.should_emit(&event.node_id, Instant::now()) {
+                        return;
+                    }
+                    if let Some(tx) = &ws_tx_for_debug {
+                        let _ = tx.send(JobWsEvent::from(event));
+                    }
+                };
+
+                let (cancel_watch_tx, cancel_watch_rx) = tokio::sync::watch::channel(false);
+                let _cancel_bridge = tokio::spawn({
+                    let token = cancel_token.clone();
+                    async move {
+                        token.cancelled().await;
+                        let _ = cancel_watch_tx.send(true);
+                    }
+                });
+
+                with_phase_reporter(Some(phase_reporter), || {
+                    SequentialExecutor::execute_with_context_and_debug_hook(
+                        &workflow,
+                        &inner.node_registry,
+                        Some(&compile_ctx),
+                        Some(progress_cb),
+                        Some(cancel_watch_rx),
+                        Some(&mut node_debug_cb),
+                    )
+                })
+            })
+        }
+    };
+    save_job_trace(&state, &job_id);
+
+    let outcome = match result {
+        Ok(outputs_by_node) => {
+            let workflow = state
+                .inner
+                .jobs
+                .get(&job_id)
+                .map(|job| job.workflow.clone());
+            if let Some(workflow) = workflow {
+                let outputs = collect_workflow_outputs(&workflow, &outputs_by_node);
+                let artifacts =
+                    tokio::task::block_in_place(|| collect_artifacts(&workflow, &outputs_by_node));
+                if let Some(mut job) = state.inner.jobs.get_mut(&job_id) {
+                    job.outputs = outputs;
+                    job.artifacts = artifacts;
+                }
+                if !workflow.post_actions.is_empty() {
+                    let results = run_job_post_actions(workflow, outputs_by_node).await;
+                    if let Some(mut job) = state.inner.jobs.get_mut(&job_id) {
+                        job.post_action_results = results;
+                    }
+                }
+            }
+            Ok(())
+        }
+        Err(err) => {
+            error!(job_id = %job_id, error = ?err, "Job execution failed");
+            Err(JobFailure::from_error(&err))
+        }
+    };
+    finish_job(&state, &job_id, outcome);
+    state.inner.frame_previews.remove(&job_id);
+    state.inner.job_monitors.remove(&job_id);
+}
+
+/// Keeps the job's latest `PreviewTap` frame and sends it to WebSocket
+/// clients.
+fn job_frame_preview_sink(
+    inner: Arc<AppStateInner>,
+    job_id: String,
+    ws_tx: Option<broadcast::Sender<JobWsEvent>>,
+) -> FramePreviewSink {
+    Arc::new(move |preview: FramePreview| {
+        if let Some(tx) = &ws_tx {
+            let _ = tx.send(JobWsEvent::from(&preview));
+        }
+        // An encode may finish after the job did; keep nothing for it then.
+        let running = inner
+            .jobs
+            .get(&job_id)
+            .is_some_and(|job| !is_terminal(job.status));
+        if running {
+            inner.frame_previews.insert(job_id.clone(), preview);
+        }
+    })
+}
+
+/// Move a finished job's trace from memory to its file.
+fn save_job_trace(state: &AppState, job_id: &str) {
+    let Some((_, trace)) = state.inner.job_traces.remove(job_id) else {
+        return;
+    };
+    let path = trace::job_trace_file_path(&state.inner.data_dir, job_id);
+    if let Err(err) = trace::write_trace_file(&path, &trace.snapshot()) {
+        warn!(job_id = %job_id, error = %format!("{err:#}"), "Failed to save job trace");
+    }
+}
+
+/// Run the workflow's post actions on the files the job wrote. Failures are
+/// logged and recorded in the results; they do not fail the job.
+async fn run_job_post_actions(
+    workflow: PipelineGraph,
+    outputs_by_node: HashMap<String, HashMap<String, crate::types::PortData>>,
+) -> Vec<PostActionResult> {
+    let (outputs, source) = job_files(&workflow, &outputs_by_node);
+    let actions = workflow.post_actions;
+    let results = tokio::task::spawn_blocking(move || {
+        run_post_actions(&actions, &outputs, source.as_deref())
+    })
+    .await
+    .unwrap_or_else(|err| {
+        vec![PostActionResult {
+            action: "post_actions".to_string(),
+            path: PathBuf::new(),
+            result_path: None,
+            error: Some(format!("post actions panicked: {err}")),
+        }]
+    });
+    for result in &results {
+        match &result.error {
+            Some(error) => {
+                warn!(action = %result.action, path = %result.path.display(), error = %error, "Post action failed")
+            }
+            None => {
+                info!(action = %result.action, path = %result.path.display(), "Post action done")
+            }
+        }
+    }
+    results
+}
+
+/// Why a job failed: the message stored as its `error`, and the structured
+/// details when there are any.
+struct JobFailure {
+    message: String,
+    details: Option<NodeError>,
+}
+
+impl JobFailure {
+    fn from_error(err: &anyhow::Error) -> Self {
+        Self {
+            message: format!("{err:#}"),
+            details: Some(NodeError::from_error(err)),
+        }
+    }
+}
+
+/// Record the terminal state of a job that ran locally or on a remote
+/// worker. Jobs already marked cancelled keep that status.
+fn finish_job(state: &AppState, job_id: &str, outcome: std::result::Result<(), JobFailure>) {
+    match outcome {
+        Ok(()) => {
+            let mut completed_snapshot = None;
+            if let Some(mut job) = state.inner.jobs.get_mut(job_id) {
+                if job.status == JobStatus::Cancelled {
+                    return;
+                }
+                job.status = JobStatus::Completed;
+                job.completed_at = Some(Utc::now());
+                completed_snapshot = Some(job.clone());
+            }
+
+            if let Some(snapshot) = completed_snapshot {
+                if let Some(progress) = snapshot.progress.as_ref() {
+                    record_progress_history_point(&state.inner, job_id, progress);
+                }
+                if let Err(err) = state.persist_job_snapshot(&snapshot) {
+                    error!(job_id = %job_id, error = ?err, "Failed to persist completed transition");
+                }
+                announce_job_finished(state, &snapshot);
+            }
+        }
+        Err(failure) => {
+            let mut failed_snapshot = None;
+            if let Some(mut job) = state.inner.jobs.get_mut(job_id) {
+                if job.status == JobStatus::Cancelled {
+                    return;
+                }
+                job.status = JobStatus::Failed;
+                job.error = Some(failure.message);
+                job.error_details = failure.details;
+                job.completed_at = Some(Utc::now());
+                failed_snapshot = Some(job.clone());
+            }
+
+            if let Some(snapshot) = failed_snapshot {
+                if let Err(persist_err) = state.persist_job_snapshot(&snapshot) {
+                    error!(
+                        job_id = %job_id,
+                        error = ?persist_err,
+                        "Failed to persist failed transition"
+                    );
+                }
+                announce_job_finished(state, &snapshot);
+            }
+        }
+    }
+
+    state.inner.progress_senders.remove(job_id);
+
+    info!(job_id = %job_id, "Job completed");
+}
+
+/// Mark a job skipped because its source needs no processing. Cancelled jobs
+/// keep that status.
+fn skip_job(state: &AppState, job_id: &str, reason: String) {
+    let snapshot = state.inner.jobs.get_mut(job_id).and_then(|mut job| {
+        if job.status == JobStatus::Cancelled {
+            return None;
+        }
+        job.status = JobStatus::Skipped;
+        job.skip_reason = Some(reason);
+        job.completed_at = Some(Utc::now());
+        Some(job.clone())
+    });
+    if let Some(snapshot) = snapshot {
+        if let Err(err) = state.persist_job_snapshot(&snapshot) {
+            error!(job_id = %job_id, error = ?err, "Failed to persist skipped transition");
+        }
+        info!(job_id = %job_id, reason = ?snapshot.skip_reason, "Job skipped");
+        announce_job_status(state, job_id, JobStatus::Skipped);
+    }
+    state.inner.progress_senders.remove(job_id);
+}
+
+/// Tell websocket subscribers of `job_id` about a status change.
+fn announce_job_status(state: &AppState, job_id: &str, status: JobStatus) {
+    if let Some(tx) = state.inner.progress_senders.get(job_id) {
+        let _ = tx.send(JobWsEvent::Status { status });
+    }
+}
+
+fn announce_job_finished(state: &AppState, job: &Job) {
+    if matches!(
+        job.workflow_source.as_str(),
+        WORKFLOW_SOURCE_CHUNK | WORKFLOW_SOURCE_EXPERIMENT_VARIANT
+    ) {
+        return;
+    }
+    let _ = state.inner.job_events.send(JobFinishedEvent {
+        job_id: job.id.clone(),
+        workflow_name: job.workflow_name.clone(),
+        status: job.status,
+        duration_ms: job_duration_ms(job),
+        error: job.error.clone(),
+    });
+}
+
+/// Convert JSON job params to executor inputs, inferring the port type from
+/// the JSON value.
+/// Convert job params to port data, using the interface input types where
+/// the workflow declares them and guessing from the JSON value otherwise.
+pub(crate) fn port_params_from_json(
+    params: &HashMap<String, serde_json::Value>,
+    interface: Option<&WorkflowInterface>,
+) -> HashMap<String, crate::types::PortData> {
+    let declared_types = interface
+        .map(WorkflowInterface::input_types)
+        .unwrap_or_default();
+    let mut port_params = HashMap::new();
+    for (key, value) in params {
+        let typed = declared_types
+            .get(key)
+            .and_then(|port_type| port_data_from_json(port_type, value).ok());
+        let port_data = if let Some(port_data) = typed {
+            port_data
+        } else if let Some(i) = value.as_i64() {
+            crate::types::PortData::Int(i)
+        } else if let Some(f) = value.as_f64() {
+            crate::types::PortData::Float(f)
+        } else if let Some(b) = value.as_bool() {
+            crate::types::PortData::Bool(b)
+        } else if let Some(s) = value.as_str() {
+            crate::types::PortData::Str(s.to_string())
+        } else {
+            crate::types::PortData::Str(value.to_string())
+        };
+        port_params.insert(key.clone(), port_data);
+    }
+    port_params
+}
+
+fn record_progress_history_point(inner: &AppStateInner, job_id: &str, update: &ProgressUpdate) {
+    let Some(persistence) = &inner.jobs_persistence else {
+        return;
+    };
+
+    let point = ProgressHistoryPoint::from_update(update, Utc::now());
+    if let Err(err) = persistence.append_progress_point(job_id, &point) {
+        warn!(job_id = %job_id, error = %err, "Failed to persist progress history point");
+    }
+}
+
+#[derive(Debug)]
+pub enum AppError {
+    BadRequest(String),
+    Forbidden(String),
+    NotFound(String),
+    /// The request can't be applied to the resource in its current state.
+    Conflict(String),
+    Internal(String),
+    /// Job params that do not fit the workflow interface; answered with 400
+    /// and one entry per param.
+    InvalidParams(Vec<ParamError>),
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let mut param_errors = Vec::new();
+        let (status, message) = match self {
+            AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg),
+            AppError::InvalidParams(errors) => {
+                let message = format!(
+                    "invalid params: {}",
+                    errors
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                );
+                param_errors = errors;
+                (StatusCode::BAD_REQUEST, message)
+            }
+            AppError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
+            AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
+            AppError::Conflict(msg) => (StatusCode::CONFLICT, msg),
+            AppError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+        };
+
+        let body = Json(ErrorResponse {
+            error: message,
+            param_errors,
+        });
+        (status, body).into_response()
+    }
+}
+
+impl From<anyhow::Error> for AppError {
+    fn from(err: anyhow::Error) -> Self {
+        AppError::Internal(format!("{:#}", err))
+    }
+}
+
+/// Copy of job params with sensitive values masked, for API responses and
+/// persisted job records.
+pub(crate) fn redact_job_params(
+    params: &HashMap<String, serde_json::Value>,
+) -> HashMap<String, serde_json::Value> {
+    let mut value = serde_json::Value::Object(
+        params
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+    );
+    crate::logging::redact_json_value(&mut value);
+
+    match value {
+        serde_json::Value::Object(map) => map.into_iter().collect(),
+        _ => params.clone(),
+    }
+}
+
+/// Names of the params whose values were masked by [`redact_job_params`].
+/// Jobs reloaded from the jobs db carry the masked values, which must not be
+/// run as if they were the real ones.
+fn redacted_param_names(params: &HashMap<String, serde_json::Value>) -> Vec<String> {
+    fn contains_placeholder(value: &serde_json::Value) -> bool {
+        match value {
+            serde_json::Value::String(text) => text.contains(crate::logging::REDACTION_PLACEHOLDER),
+            serde_json::Value::Array(items) => items.iter().any(contains_placeholder),
+            serde_json::Value::Object(map) => map.values().any(contains_placeholder),
+            _ => false,
+        }
+    }
+
+    let mut names: Vec<String> = params
+        .iter()
+        .filter(|(_, value)| contains_placeholder(value))
+        .map(|(name, _)| name.clone())
+        .collect();
+    names.sort();
+    names
+}
+
+fn job_to_response(job: &Job) -> JobResponse {
+    let duration_ms = job_duration_ms(job);
+    JobResponse {
+        id: job.id.clone(),
+        status: job.status,
+        created_at: job.created_at,
+        started_at: job.started_at,
+        completed_at: job.completed_at,
+        progress: job.progress.clone(),
+        error: job.error.clone(),
+        error_details: job.error_details.clone(),
+        workflow_name: job.workflow_name.clone(),
+        workflow_source: job.workflow_source.clone(),
+        params: job.params.as_ref().map(redact_job_params),
+        rerun_of_job_id: job.rerun_of_job_id.clone(),
+        duration_ms,
+        outputs: job.outputs.as_ref().map(redact_job_params),
+        tags: job.tags.clone(),
+        note: job.note.clone(),
+        post_actions: job.post_action_results.clone(),
+        skip_reason: job.skip_reason.clone(),
+        paused_at: job.paused_at,
+        artifacts: job.artifacts.clone(),
+        encode_stats: EncodeStats::from_artifacts(
+            &job.artifacts,
+            duration_ms.map(|ms| ms as f64 / 1000.0),
+        ),
+        step_mode: job
+            .step_gate
+            .as_ref()
+            .is_some_and(|gate| gate.breakpoints().is_none()),
+        breakpoints: job
+            .step_gate
+            .as_ref()
+            .and_then(StepGate::breakpoints)
+            .map(|breakpoints| breakpoints.iter().cloned().collect())
+            .unwrap_or_default(),
+        step_paused: job.step_gate.as_ref().and_then(StepGate::paused),
+    }
+}
+
+fn workflow_name_from_request(workflow: &serde_json::Value, fallback: &str) -> String {
+    workflow
+        .get("name")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or(fallback)
+        .to_string()
+}
+
+fn extract_workflow_input_params(
+    workflow: &serde_json::Value,
+) -> Option<HashMap<String, serde_json::Value>> {
+    let nodes = workflow.get("nodes")?.as_array()?;
+    let workflow_input = nodes
+        .iter()
+        .find(|node| node.get("node_type").and_then(|t| t.as_str()) == Some("WorkflowInput"))?;
+    let params = workflow_input.get("params")?.as_object()?;
+
+    let mut extracted = HashMap::new();
+    for (key, value) in params {
+        if matches!(
+            key.as_str(),
+            "ports" | "interface_inputs" | "interface_outputs"
+        ) {
+            continue;
+        }
+
+        extracted.insert(key.clone(), value.clone());
+    }
+
+    if extracted.is_empty() {
+        None
+    } else {
+        Some(extracted)
+    }
+}
+
+fn job_duration_ms(job: &Job) -> Option<i64> {
+    let completed_at = job.completed_at?;
+    let started_at = job.started_at.unwrap_or(job.created_at);
+    Some((completed_at - started_at).num_milliseconds().max(0))
+}
+
+pub fn default_app_state() -> AppState {
+    let dd = crate::config::data_dir(None);
+    let cfg_path = crate::config::config_path(&dd);
+    let config = match AppConfig::load_from_path(&cfg_path) {
+        Ok(config) => config,
+        Err(err) => {
+            warn!(error = %err, "Failed to load config file, using defaults");
+            AppConfig::default()
+        }
+    };
+    app_state_with_config(config, cfg_path, dd)
+}
+
+/// The built-in catalog plus the models found in `paths`' model
+/// directories, and whether the scan succeeded.
+fn discover_models(paths: &PathsConfig) -> (ModelRegistry, bool) {
+    let mut model_registry = ModelRegistry::with_builtin_models(paths.models_dir.clone())
+        .with_extra_dirs(paths.extra_models_dirs.clone());
+    let scanned = match model_registry.discover() {
+        Ok(()) => true,
+        Err(err) => {
+            warn!(error = %err, "Failed to discover models on disk");
+            false
+        }
+    };
+    (model_registry, scanned)
+}
+
+pub fn app_state_with_config(
+    config: AppConfig,
+    config_path: PathBuf,
+    data_dir: PathBuf,
+) -> AppState {
+    let mut node_registry = NodeRegistry::new();
+    register_all_nodes(&mut node_registry);
+    node_registry.load_plugins(&config.paths.plugins_dir);
+    let (model_registry, scanned) = discover_models(&config.paths);
+    let presets = load_builtin_presets(&config.paths.presets_dir);
+    let state = AppState::new(
+        node_registry,
+        model_registry,
+        presets,
+        config,
+        config_path,
+        data_dir,
+    );
+    state.inner.models_scanned.store(scanned, Ordering::Release);
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::debug_event::NodeDebugValueEvent;
+    use crate::types::PortType;
+    use axum::body::Body;
+    use axum::http::Request;
+    use rusqlite::Connection;
+    use tower::{Service, ServiceExt};
+
+    fn test_state() -> AppState {
+        test_state_with_data_dir(test_data_dir())
+    }
+
+    fn test_state_with_data_dir(data_dir: PathBuf) -> AppState {
+        let mut node_registry = NodeRegistry::new();
+        node_registry.register("test_source", |_params| {
+            Ok(Box::new(TestNode {
+                node_type: "test_source".to_string(),
+                inputs: vec![],
+                outputs: vec![crate::node::PortDefinition {
+                    name: "output".to_string(),
+                    port_type: PortType::VideoFrames,
+                    required: true,
+                    default_value: None,
+                }],
+            }))
+        });
+        node_registry.register("test_sink", |_params| {
+            Ok(Box::new(TestNode {
+                node_type: "test_sink".to_string(),
+                inputs: vec![crate::node::PortDefinition {
+                    name: "input".to_string(),
+                    port_type: PortType::VideoFrames,
+                    required: true,
+                    default_value: None,
+                }],
+                outputs: vec![],
+            }))
+        });
+        node_registry.register("test_delay", |params| {
+            let sleep_ms = params
+                .get("sleep_ms")
+                .and_then(serde_json::Value::as_u64)
+                .unwrap_or(0);
+            Ok(Box::new(DelayNode { sleep_ms }))
+        });
+
+        let model_registry = ModelRegistry::with_builtin_models(test_models_dir());
+        AppState::new(
+            node_registry,
+            model_registry,
+            DashMap::new(),
+            AppConfig::default(),
+            test_config_path(),
+            data_dir,
+        )
+    }
+
+    fn test_config_path() -> PathBuf {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!(
+            "videnoa-core-server-test-{}-{timestamp}.toml",
+            std::process::id()
+        ))
+    }
+
+    fn test_models_dir() -> PathBuf {
+        std::env::temp_dir().join("models")
+    }
+
+    fn test_data_dir() -> PathBuf {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!(
+            "videnoa-test-data-{}-{timestamp}",
+            std::process::id()
+        ))
+    }
+
+    fn temp_path(path: &str) -> PathBuf {
+        std::env::temp_dir().join(path)
+    }
+
+    fn temp_path_str(path: &str) -> String {
+        temp_path(path).to_string_lossy().to_string()
+    }
+
+    fn unique_temp_dir(prefix: &str) -> PathBuf {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        std::env::temp_dir().join(format!("{prefix}-{}-{timestamp}", std::process::id()))
+    }
+
+    fn write_json_file(path: &StdPath, value: &serde_json::Value) {
+        let bytes = serde_json::to_vec_pretty(value).expect("serialize test workflow JSON");
+        std::fs::write(path, bytes).expect("write test workflow JSON");
+    }
+
+    async fn set_workflow_lookup_dirs(
+        state: &AppState,
+        workflows_dir: PathBuf,
+        presets_dir: PathBuf,
+    ) {
+        let mut config = state.inner.config.write().await;
+        config.paths.workflows_dir = workflows_dir;
+        config.paths.presets_dir = presets_dir;
+    }
+
+    fn test_router() -> Router {
+        app_router(test_state())
+    }
+
+    fn valid_workflow_json() -> serde_json::Value {
+        serde_json::json!({
+            "nodes": [
+                {"id": "src", "node_type": "test_source", "params": {}},
+                {"id": "dst", "node_type": "test_sink", "params": {}}
+            ],
+            "connections": [
+                {
+                    "from_node": "src",
+                    "from_port": "output",
+                    "to_node": "dst",
+                    "to_port": "input",
+                    "port_type": "VideoFrames"
+                }
+            ]
+        })
+    }
+
+    fn delay_workflow_json(sleep_ms: u64) -> serde_json::Value {
+        serde_json::json!({
+            "nodes": [
+                {
+                    "id": "delay",
+                    "node_type": "test_delay",
+                    "params": {
+                        "sleep_ms": sleep_ms
+                    }
+                }
+            ],
+            "connections": []
+        })
+    }
+
+    fn workflow_input_output_json() -> serde_json::Value {
+        serde_json::json!({
+            "nodes": [
+                {"id": "wi", "node_type": "WorkflowInput", "params": {
+                    "ports": [{"name": "greeting", "port_type": "Str"}]
+                }},
+                {"id": "wo", "node_type": "WorkflowOutput", "params": {
+                    "ports": [{"name": "greeting", "port_type": "Str"}]
+                }}
+            ],
+            "connections": [
+                {
+                    "from_node": "wi",
+                    "from_port": "greeting",
+                    "to_node": "wo",
+                    "to_port": "greeting",
+                    "port_type": "Str"
+                }
+            ],
+            "interface": {
+                "inputs": [{"name": "greeting", "port_type": "Str"}],
+                "outputs": [{"name": "greeting", "port_type": "Str"}]
+            }
+        })
+    }
+
+    fn persisted_job_status(data_dir: &StdPath, job_id: &str) -> Option<String> {
+        let db_path = data_dir.join("jobs.db");
+        let conn = Connection::open(db_path).ok()?;
+        conn.query_row(
+            "SELECT status FROM jobs WHERE id = ?1",
+            rusqlite::params![job_id],
+            |row| row.get(0),
+        )
+        .ok()
+    }
+
+    fn build_test_job(
+        id: String,
+        status: JobStatus,
+        params: Option<HashMap<String, serde_json::Value>>,
+    ) -> Job {
+        let workflow: PipelineGraph =
+            serde_json::from_value(valid_workflow_json()).expect("workflow should deserialize");
+        let created_at = Utc::now() - chrono::Duration::seconds(5);
+        let started_at = if status == JobStatus::Queued {
+            None
+        } else {
+            Some(created_at + chrono::Duration::seconds(1))
+        };
+        let completed_at = if is_terminal(status) {
+            Some(created_at + chrono::Duration::seconds(2))
+        } else {
+            None
+        };
+        let error = match status {
+            JobStatus::Failed => Some("source failed".to_string()),
+            JobStatus::Cancelled => Some("source cancelled".to_string()),
+            _ => None,
+        };
+
+        Job {
+            id,
+            status,
+            workflow,
+            created_at,
+            started_at,
+            completed_at,
+            progress: None,
+            error,
+            error_details: None,
+            cancel_token: CancellationToken::new(),
+            params,
+            workflow_name: "Source Workflow".to_string(),
+            workflow_source: WORKFLOW_SOURCE_API_JOBS.to_string(),
+            rerun_of_job_id: None,
+            outputs: None,
+            tags: Vec::new(),
+            note: None,
+            post_action_results: Vec::new(),
+            skip_reason: None,
+            paused_at: None,
+            artifacts: Vec::new(),
+            step_gate: None,
+        }
+    }
+
+    fn insert_test_job(state: &AppState, job: Job) {
+        state
+            .persist_job_snapshot(&job)
+            .expect("persist source job snapshot");
+        state.inner.jobs.insert(job.id.clone(), job);
+    }
+
+    struct TestNode {
+        node_type: String,
+        inputs: Vec<crate::node::PortDefinition>,
+        outputs: Vec<crate::node::PortDefinition>,
+    }
+
+    struct DelayNode {
+        sleep_ms: u64,
+    }
+
+    impl crate::node::Node for TestNode {
+        fn node_type(&self) -> &str {
+            &self.node_type
+        }
+        fn input_ports(&self) -> Vec<crate::node::PortDefinition> {
+            self.inputs.clone()
+        }
+        fn output_ports(&self) -> Vec<crate::node::PortDefinition> {
+            self.outputs.clone()
+        }
+        fn execute(
+            &mut self,
+            _inputs: &std::collections::HashMap<String, crate::types::PortData>,
+            _ctx: &crate::node::ExecutionContext,
+        ) -> Result<std::collections::HashMap<String, crate::types::PortData>> {
+            Ok(std::collections::HashMap::new())
+        }
+    }
+
+    impl crate::node::Node for DelayNode {
+        fn node_type(&self) -> &str {
+            "test_delay"
+        }
+
+        fn input_ports(&self) -> Vec<crate::node::PortDefinition> {
+            vec![]
+        }
+
+        fn output_ports(&self) -> Vec<crate::node::PortDefinition> {
+            vec![]
+        }
+
+        fn execute(
+            &mut self,
+            _inputs: &std::collections::HashMap<String, crate::types::PortData>,
+            _ctx: &crate::node::ExecutionContext,
+        ) -> Result<std::collections::HashMap<String, crate::types::PortData>> {
+            if self.sleep_ms > 0 {
+                std::thread::sleep(std::time::Duration::from_millis(self.sleep_ms));
+            }
+            Ok(std::collections::HashMap::new())
+        }
+    }
+
+    async fn send_request(router: &mut Router, request: Request<Body>) -> axum::response::Response {
+        router
+            .as_service()
+            .ready()
+            .await
+            .unwrap()
+            .call(request)
+            .await
+            .unwrap()
+    }
+
+    async fn wait_for_job_terminal_status(state: &AppState, job_id: &str) -> JobStatus {
+        const MAX_POLLS: usize = 80;
+        const POLL_INTERVAL_MS: u64 = 50;
+
+        for _ in 0..MAX_POLLS {
+            if let Some(job) = state.inner.jobs.get(job_id) {
+                if is_terminal(job.status) {
+                    return job.status;
+                }
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(POLL_INTERVAL_MS)).await;
+        }
+
+        panic!("job {job_id} did not reach terminal status within timeout");
+    }
+
+    async fn wait_for_persisted_status(data_dir: &StdPath, job_id: &str, expected: &str) -> bool {
+        const MAX_POLLS: usize = 80;
+        const POLL_INTERVAL_MS: u64 = 25;
+
+        for _ in 0..MAX_POLLS {
+            if persisted_job_status(data_dir, job_id).as_deref() == Some(expected) {
+                return true;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(POLL_INTERVAL_MS)).await;
+        }
+
+        false
+    }
+
+    #[tokio::test]
+    async fn test_health_endpoint() {
+        let mut app = test_router();
+        let req = Request::builder()
+            .uri("/api/health")
+            .body(Body::empty())
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["status"], "ok");
+        assert_eq!(json["read_only"], false);
+    }
+
+    #[tokio::test]
+    async fn test_read_only_mode_rejects_writes() {
+        let state = test_state();
+        let mut config = AppConfig::default();
+        config.server.read_only = true;
+        state.apply_config(config, ConfigChangeSource::File).await;
+        let mut app = app_router(state.clone());
+
+        let create_job = || {
+            Request::builder()
+                .method("POST")
+                .uri("/api/jobs")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({ "workflow": valid_workflow_json() }).to_string(),
+                ))
+                .unwrap()
+        };
+        let resp = send_request(&mut app, create_job()).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/api/config")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::to_string(&AppConfig::default()).unwrap(),
+            ))
+            .unwrap();
+        assert_eq!(
+            send_request(&mut app, req).await.status(),
+            StatusCode::FORBIDDEN
+        );
+        let req = Request::builder()
+            .method("DELETE")
+            .uri("/api/workflows/upscale.json")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(
+            send_request(&mut app, req).await.status(),
+            StatusCode::FORBIDDEN
+        );
+        assert!(state.inner.config.read().await.server.read_only);
+
+        // POSTs that change nothing still work.
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/workflows/lint")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::json!({ "workflow": valid_workflow_json() }).to_string(),
+            ))
+            .unwrap();
+        assert_eq!(send_request(&mut app, req).await.status(), StatusCode::OK);
+        // Backups and support bundles hand out the config, jobs db and logs.
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/maintenance/backup")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(
+            send_request(&mut app, req).await.status(),
+            StatusCode::FORBIDDEN
+        );
+        assert!(!allowed_when_read_only(
+            &Method::POST,
+            "/api/support-bundle"
+        ));
+        assert!(allowed_when_read_only(
+            &Method::POST,
+            "/api/workers/{id}/jobs/{job_id}/complete"
+        ));
+        assert!(!allowed_when_read_only(
+            &Method::POST,
+            "/api/maintenance/restore"
+        ));
+
+        let req = Request::builder()
+            .uri("/api/jobs")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(send_request(&mut app, req).await.status(), StatusCode::OK);
+        let req = Request::builder()
+            .uri("/api/health")
+            .body(Body::empty())
+            .unwrap();
+        let body = axum::body::to_bytes(send_request(&mut app, req).await.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["read_only"], true);
+
+        // `--read-only` holds even when the config turns it off.
+        state.force_read_only();
+        state
+            .apply_config(AppConfig::default(), ConfigChangeSource::File)
+            .await;
+        let resp = send_request(&mut app, create_job()).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_health_live_endpoint() {
+        let mut app = test_router();
+        let req = Request::builder()
+            .uri("/api/health/live")
+            .body(Body::empty())
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_health_ready_reports_unscanned_models_as_not_ready() {
+        let mut app = test_router();
+        let req = Request::builder()
+            .uri("/api/health/ready")
+            .body(Body::empty())
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["ready"], false);
+        let checks = json["checks"].as_array().unwrap();
+        let names: Vec<&str> = checks
+            .iter()
+            .map(|check| check["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["ffmpeg", "onnxruntime", "models"]);
+        assert_eq!(checks[2]["ready"], false);
+    }
+
+    #[tokio::test]
+    async fn test_health_ready_marks_models_scanned_after_discovery() {
+        let data_dir = test_data_dir();
+        let mut config = AppConfig::default();
+        config.paths.models_dir = data_dir.join("models");
+        config.paths.presets_dir = data_dir.join("presets");
+        let state = app_state_with_config(config, data_dir.join("config.toml"), data_dir);
+        let mut app = app_router(state);
+
+        let req = Request::builder()
+            .uri("/api/health/ready")
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["checks"][2]["name"], "models");
+        assert_eq!(json["checks"][2]["ready"], true);
+    }
+
+    #[tokio::test]
+    async fn test_get_config_endpoint() {
+        let mut app = test_router();
+        let req = Request::builder()
+            .uri("/api/config")
+            .body(Body::empty())
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let config: AppConfig = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(config.paths.models_dir, PathBuf::from("models"));
+        assert_eq!(config.server.port, 3000);
+    }
+
+    #[tokio::test]
+    async fn test_put_config_broadcasts_config_changed_event() {
+        let state = test_state();
+        let mut events = state.inner.config_events.subscribe();
+        let mut app = app_router(state.clone());
+
+        let mut updated = state.inner.config.read().await.clone();
+        updated.locale = "zh-CN".to_string();
+
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/api/config")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&updated).unwrap()))
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let ConfigEvent::ConfigChanged { source, config, .. } =
+            events.try_recv().expect("config event")
+        else {
+            panic!("expected a config change event");
+        };
+        assert_eq!(source, ConfigChangeSource::Api);
+        assert_eq!(config.locale, "zh-CN");
+
+        let _ = std::fs::remove_file(&state.inner.config_path);
+    }
+
+    #[tokio::test]
+    async fn test_reload_config_from_disk_reloads_presets_and_keeps_api_presets() {
+        let data_dir = test_data_dir();
+        let state = test_state_with_data_dir(data_dir.clone());
+        let mut events = state.inner.config_events.subscribe();
+
+        let api_preset_id = Uuid::new_v4().to_string();
+        state.inner.presets.insert(
+            api_preset_id.clone(),
+            Preset {
+                name: "api".to_string(),
+                description: String::new(),
+                workflow: serde_json::json!({}),
+                origin: PresetOrigin::User,
+            },
+        );
+
+        let presets_dir = data_dir.join("presets-reloaded");
+        std::fs::create_dir_all(&presets_dir).unwrap();
+        std::fs::write(
+            presets_dir.join("disk-preset.json"),
+            r#"{"name":"Disk","description":"from disk","workflow":{}}"#,
+        )
+        .unwrap();
+
+        let mut next = state.inner.config.read().await.clone();
+        next.paths.presets_dir = presets_dir;
+        next.save_to_path(&state.inner.config_path).unwrap();
+
+        assert!(state.reload_config_from_disk().await.unwrap());
+        assert!(!state.reload_config_from_disk().await.unwrap());
+
+        assert!(state.inner.presets.contains_key("disk-preset"));
+        assert!(state.inner.presets.contains_key(&api_preset_id));
+        let ConfigEvent::ConfigChanged { source, .. } = events.try_recv().expect("config event")
+        else {
+            panic!("expected a config change event");
+        };
+        assert_eq!(source, ConfigChangeSource::File);
+        assert!(events.try_recv().is_err());
+
+        let _ = std::fs::remove_file(&state.inner.config_path);
+    }
+
+    #[tokio::test]
+    async fn test_validate_config_does_not_apply_proposed_config() {
+        let state = test_state();
+        let mut app = app_router(state.clone());
+        let before = state.inner.config.read().await.clone();
+
+        let mut proposed = before.clone();
+        proposed.server.port = 0;
+        proposed.locale = "zh-CN".to_string();
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/config/validate")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&proposed).unwrap()))
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let report: ConfigValidationReport = serde_json::from_slice(&body).unwrap();
+        assert!(!report.valid);
+        assert!(report
+            .issues
+            .iter()
+            .any(|issue| issue.field == "server.port" && issue.code == "invalid_port"));
+
+        assert_eq!(*state.inner.config.read().await, before);
+        assert!(!state.inner.config_path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_put_config_rejects_invalid_redaction_pattern() {
+        let state = test_state();
+        let mut app = app_router(state);
+
+        let mut updated = AppConfig::default();
+        updated.redaction.patterns = vec!["(unclosed".to_string()];
+
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/api/config")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&updated).unwrap()))
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_job_params_are_redacted_in_responses_and_persistence() {
+        let data_dir = test_data_dir();
+        let state = test_state_with_data_dir(data_dir.clone());
+        let job_id = Uuid::new_v4().to_string();
+        let params = HashMap::from([
+            ("api_key".to_string(), serde_json::json!("abc123")),
+            (
+                "source".to_string(),
+                serde_json::json!("http://user:pw@jellyfin.local/item"),
+            ),
+            ("scale".to_string(), serde_json::json!(2)),
+        ]);
+        insert_test_job(
+            &state,
+            build_test_job(job_id.clone(), JobStatus::Completed, Some(params.clone())),
+        );
+        let mut app = app_router(state.clone());
+
+        let req = Request::builder()
+            .uri(format!("/api/jobs/{job_id}"))
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            json["params"]["api_key"],
+            crate::logging::REDACTION_PLACEHOLDER
+        );
+        assert_eq!(
+            json["params"]["source"],
+            format!(
+                "http://{}@jellyfin.local/item",
+                crate::logging::REDACTION_PLACEHOLDER
+            )
+        );
+        assert_eq!(json["params"]["scale"], 2);
+
+        let conn = Connection::open(data_dir.join("jobs.db")).expect("open jobs db");
+        let params_json: String = conn
+            .query_row(
+                "SELECT params_json FROM jobs WHERE id = ?1",
+                [&job_id],
+                |row| row.get(0),
+            )
+            .expect("query params_json");
+        assert!(!params_json.contains("abc123"));
+        assert!(!params_json.contains("user:pw"));
+
+        let in_memory = state.inner.jobs.get(&job_id).unwrap().params.clone();
+        assert_eq!(in_memory, Some(params));
+    }
+
+    #[tokio::test]
+    async fn test_put_config_endpoint() {
+        let state = test_state();
+        let config_path = state.inner.config_path.clone();
+        let mut app = app_router(state);
+
+        let updated = AppConfig {
+            paths: crate::config::PathsConfig {
+                models_dir: PathBuf::from("models_custom"),
+                extra_models_dirs: vec![PathBuf::from("models_shared")],
+                trt_cache_dir: PathBuf::from("cache_custom"),
+                presets_dir: PathBuf::from("presets_custom"),
+                workflows_dir: PathBuf::from("workflows_custom"),
+                plugins_dir: PathBuf::from("plugins_custom"),
+                media_roots: vec![PathBuf::from("/media")],
+            },
+            server: crate::config::ServerConfig {
+                port: 4321,
+                host: "127.0.0.1".to_string(),
+                read_only: false,
+            },
+            locale: "zh-CN".to_string(),
+            performance: crate::config::PerformanceConfig {
+                profiling_enabled: true,
+                session_cache_size: 2,
+                warmup: vec![crate::config::WarmupModel {
+                    model: PathBuf::from("2x_AnimeJaNai.onnx"),
+                    backend: "tensorrt".to_string(),
+                    height: 720,
+                    width: 1280,
+                }],
+                sample_interval_secs: 10,
+                series_retention_hours: 72,
+            },
+            logging: crate::config::LoggingConfig {
+                retention_files: 7,
+                max_total_size_mb: 64,
+            },
+            redaction: crate::config::RedactionConfig::default(),
+            workers: crate::config::WorkersConfig {
+                dispatch: crate::config::JobDispatchMode::Remote,
+                token_secret: Some("worker-token".to_string()),
+                heartbeat_timeout_secs: 45,
+            },
+            jobs: crate::config::JobsConfig {
+                retention_max_jobs: 200,
+                retention_max_age_days: 30,
+                max_cpu_jobs: 4,
+                max_network_jobs: 8,
+                max_disk_jobs: 2,
+                node_timeout_secs: 600,
+            },
+            commands: crate::config::CommandsConfig {
+                allowed: vec!["ffmpeg".to_string()],
+            },
+            torrents: crate::config::TorrentsConfig {
+                enabled: true,
+                download_dir: PathBuf::from("/srv/torrents"),
+            },
+            memory: crate::config::MemoryConfig {
+                host_budget_mb: 8192,
+                vram_budget_mb: 6144,
+            },
+            workflows: crate::config::WorkflowsConfig { keep_versions: 5 },
+            presets: crate::config::PresetsConfig {
+                community_index_url: "https://example.com/presets/index.json".to_string(),
+                sync_interval_hours: 6,
+            },
+            jellyfin: crate::config::JellyfinConfig {
+                servers: vec![crate::config::JellyfinServerConfig {
+                    name: "anime".to_string(),
+                    url: "http://anime.local:8096".to_string(),
+                    api_key_secret: "jellyfin.anime".to_string(),
+                }],
+                default_server: "anime".to_string(),
+            },
+            power: crate::config::PowerConfig {
+                prevent_sleep: false,
+                when_queue_empty: QueueEmptyAction::Suspend,
+            },
+            updates: crate::config::UpdatesConfig {
+                channel: UpdateChannel::Beta,
+                releases_url: "https://example.com/releases".to_string(),
+            },
+        };
+
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/api/config")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&updated).unwrap()))
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let returned: AppConfig = serde_json::from_slice(&body).unwrap();
+        assert_eq!(returned, updated);
+
+        let req = Request::builder()
+            .uri("/api/config")
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let reloaded: AppConfig = serde_json::from_slice(&body).unwrap();
+        assert_eq!(reloaded, updated);
+
+        assert!(config_path.exists());
+        let _ = std::fs::remove_file(config_path);
+    }
+
+    #[tokio::test]
+    async fn test_create_job_valid() {
+        let mut app = test_router();
+        let body = serde_json::json!({
+            "workflow": valid_workflow_json()
+        });
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/jobs")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(json["id"].is_string());
+        assert_eq!(json["status"], "queued");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_create_job_video_pipeline() {
+        let mut node_registry = NodeRegistry::new();
+        register_all_nodes(&mut node_registry);
+        let model_registry = ModelRegistry::with_builtin_models(test_models_dir());
+        let state = AppState::new(
+            node_registry,
+            model_registry,
+            DashMap::new(),
+            AppConfig::default(),
+            test_config_path(),
+            test_data_dir(),
+        );
+        let mut app = app_router(state.clone());
+
+        let workflow = serde_json::json!({
+            "nodes": [
+                {"id": "input", "node_type": "VideoInput", "params": {
+                    "path": temp_path_str("nonexistent-video-videnoa-test.mkv")
+                }},
+                {"id": "output", "node_type": "VideoOutput", "params": {}}
+            ],
+            "connections": [
+                {
+                    "from_node": "input",
+                    "from_port": "source_path",
+                    "to_node": "output",
+                    "to_port": "source_path",
+                    "port_type": "Path"
+                },
+                {
+                    "from_node": "input",
+                    "from_port": "frames",
+                    "to_node": "output",
+                    "to_port": "frames",
+                    "port_type": "VideoFrames"
+                }
+            ]
+        });
+        let body = serde_json::json!({ "workflow": workflow });
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/jobs")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let create_json: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
+        let job_id = create_json["id"].as_str().unwrap().to_string();
+
+        let status = wait_for_job_terminal_status(&state, &job_id).await;
+        let job = state.inner.jobs.get(&job_id).unwrap();
+        assert_eq!(status, JobStatus::Failed);
+        let err_msg = job.error.as_deref().unwrap_or("");
+        assert!(
+            !err_msg.contains("CompileContext"),
+            "should not fail due to missing CompileContext, got: {err_msg}"
+        );
+        let details = job
+            .error_details
+            .as_ref()
+            .expect("failed job has error details");
+        assert_eq!(details.node_id.as_deref(), Some("input"), "{details:?}");
+        assert_eq!(details.node_type.as_deref(), Some("VideoInput"));
+        assert!(!details.message.starts_with("execution failed"));
+        assert!(job_to_response(&job).error_details.is_some());
+        drop(job);
+
+        let req = Request::builder()
+            .uri(format!("/api/jobs/{job_id}/trace"))
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let trace: JobTraceResponse = serde_json::from_slice(&body).unwrap();
+        assert!(!trace.live);
+        let events = trace
+            .trace
+            .events
+            .iter()
+            .map(|event| (event.kind, event.node_id.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            vec![
+                (crate::trace::TraceEventKind::NodeStart, "input"),
+                (crate::trace::TraceEventKind::NodeFailed, "input"),
+            ]
+        );
+        assert!(trace.trace.events[0].inputs.as_ref().unwrap()["path"]
+            .contains("nonexistent-video-videnoa-test.mkv"));
+
+        let req = Request::builder()
+            .uri("/api/jobs/missing-job/trace")
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_job_preview_serves_latest_frame() {
+        let state = test_state();
+        let mut app = app_router(state.clone());
+        insert_test_job(
+            &state,
+            build_test_job("running-job".to_string(), JobStatus::Running, None),
+        );
+        insert_test_job(
+            &state,
+            build_test_job("done-job".to_string(), JobStatus::Completed, None),
+        );
+        let (ws_tx, mut ws_rx) = broadcast::channel(4);
+
+        let get_preview = |id: &str| {
+            Request::builder()
+                .uri(format!("/api/jobs/{id}/preview.jpg"))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let resp = send_request(&mut app, get_preview("running-job")).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let resp = send_request(&mut app, get_preview("missing-job")).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let preview = |frame_index| FramePreview {
+            frame_index,
+            width: 2,
+            height: 1,
+            jpeg: vec![0xFF, 0xD8, frame_index as u8],
+        };
+        let sink = job_frame_preview_sink(
+            Arc::clone(&state.inner),
+            "running-job".to_string(),
+            Some(ws_tx),
+        );
+        sink(preview(1));
+        sink(preview(7));
+        match ws_rx.try_recv().unwrap() {
+            JobWsEvent::FramePreview {
+                frame_index, image, ..
+            } => {
+                assert_eq!(frame_index, 1);
+                assert!(image.starts_with("data:image/jpeg;base64,"));
+            }
+            other => panic!("unexpected event: {other:?}"),
+        }
+
+        let resp = send_request(&mut app, get_preview("running-job")).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()[axum::http::header::CONTENT_TYPE],
+            "image/jpeg"
+        );
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(body.as_ref(), &[0xFF, 0xD8, 7]);
+
+        // Previews arriving after a job ended are not kept.
+        job_frame_preview_sink(Arc::clone(&state.inner), "done-job".to_string(), None)(preview(3));
+        let resp = send_request(&mut app, get_preview("done-job")).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_job_monitor_streams_mjpeg() {
+        use futures_util::StreamExt as _;
+
+        let state = test_state();
+        let mut app = app_router(state.clone());
+        insert_test_job(
+            &state,
+            build_test_job("running-job".to_string(), JobStatus::Running, None),
+        );
+        let monitor_uri = |id: &str| {
+            Request::builder()
+                .uri(format!("/api/jobs/{id}/monitor"))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let resp = send_request(&mut app, monitor_uri("missing-job")).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let resp = send_request(&mut app, monitor_uri("running-job")).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let monitor = JobMonitor::new();
+        state
+            .inner
+            .job_monitors
+            .insert("running-job".to_string(), monitor.clone());
+        let resp = send_request(&mut app, monitor_uri("running-job")).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()[axum::http::header::CONTENT_TYPE],
+            format!("multipart/x-mixed-replace; boundary={MJPEG_BOUNDARY}").as_str()
+        );
+        assert!(monitor.is_watched());
+
+        let jpeg = vec![0xFF, 0xD8, 0x01];
+        monitor.publish(FramePreview {
+            frame_index: 5,
+            width: 2,
+            height: 1,
+            jpeg: jpeg.clone(),
+        });
+        let mut parts = resp.into_body().into_data_stream();
+        let part = tokio::time::timeout(Duration::from_secs(5), parts.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(part.as_ref(), mjpeg_part(&jpeg).as_slice());
+
+        // The stream ends with the job.
+        state.inner.job_monitors.remove("running-job");
+        drop(monitor);
+        let end = tokio::time::timeout(Duration::from_secs(5), parts.next())
+            .await
+            .unwrap();
+        assert!(end.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_create_job_with_params() {
+        let mut node_registry = NodeRegistry::new();
+        register_all_nodes(&mut node_registry);
+        let model_registry = ModelRegistry::with_builtin_models(test_models_dir());
+        let state = AppState::new(
+            node_registry,
+            model_registry,
+            DashMap::new(),
+            AppConfig::default(),
+            test_config_path(),
+            test_data_dir(),
+        );
+        let mut app = app_router(state.clone());
+
+        let workflow = workflow_input_output_json();
+        let body = serde_json::json!({
+            "workflow": workflow,
+            "params": {"greeting": "hello world"}
+        });
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/jobs")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let create_json: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
+        let job_id = create_json["id"].as_str().unwrap().to_string();
+
+        let status = wait_for_job_terminal_status(&state, &job_id).await;
+
+        let job = state.inner.jobs.get(&job_id).unwrap();
+        assert_eq!(
+            status,
+            JobStatus::Completed,
+            "expected Completed, got {:?}, error: {:?}",
+            job.status,
+            job.error
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_step_mode_job_pauses_after_each_node() {
+        let mut node_registry = NodeRegistry::new();
+        register_all_nodes(&mut node_registry);
+        let state = AppState::new(
+            node_registry,
+            ModelRegistry::with_builtin_models(test_models_dir()),
+            DashMap::new(),
+            AppConfig::default(),
+            test_config_path(),
+            test_data_dir(),
+        );
+        let mut app = app_router(state.clone());
+
+        let (status, json) = post_json(
+            &mut app,
+            "/api/jobs",
+            None,
+            serde_json::json!({
+                "workflow": workflow_input_output_json(),
+                "params": {"greeting": "hello world"},
+                "step_mode": true
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let job_id = json["id"].as_str().unwrap().to_string();
+        let mut rx = state
+            .inner
+            .progress_senders
+            .get(&job_id)
+            .unwrap()
+            .subscribe();
+
+        // A failed assertion must not leave the job paused forever.
+        let _stop_job = state
+            .inner
+            .jobs
+            .get(&job_id)
+            .unwrap()
+            .cancel_token
+            .clone()
+            .drop_guard();
+
+        // Paused after the first node until stepped.
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(
+            state.inner.jobs.get(&job_id).unwrap().status,
+            JobStatus::Running
+        );
+        while rx.try_recv().is_ok() {}
+
+        let (status, json) = post_json(
+            &mut app,
+            &format!("/api/jobs/{job_id}/step"),
+            None,
+            serde_json::json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["step_mode"], true);
+
+        let pause = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let JobWsEvent::StepPaused {
+                    node_id, outputs, ..
+                } = rx.recv().await.unwrap()
+                {
+                    break (node_id, outputs);
+                }
+            }
+        })
+        .await
+        .expect("second node pauses");
+        assert_eq!(pause.0, "wo");
+        assert_eq!(pause.1["greeting"], "hello world");
+
+        post_json(
+            &mut app,
+            &format!("/api/jobs/{job_id}/step"),
+            None,
+            serde_json::json!({}),
+        )
+        .await;
+        assert_eq!(
+            wait_for_job_terminal_status(&state, &job_id).await,
+            JobStatus::Completed
+        );
+
+        let (status, _) = post_json(
+            &mut app,
+            &format!("/api/jobs/{job_id}/step"),
+            None,
+            serde_json::json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_breakpoint_pauses_job_after_its_node() {
+        let mut node_registry = NodeRegistry::new();
+        register_all_nodes(&mut node_registry);
+        let state = AppState::new(
+            node_registry,
+            ModelRegistry::with_builtin_models(test_models_dir()),
+            DashMap::new(),
+            AppConfig::default(),
+            test_config_path(),
+            test_data_dir(),
+        );
+        let mut app = app_router(state.clone());
+
+        let (status, json) = post_json(
+            &mut app,
+            "/api/jobs",
+            None,
+            serde_json::json!({
+                "workflow": workflow_input_output_json(),
+                "params": {"greeting": "hello world"},
+                "breakpoints": ["missing"]
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(
+            json["error"].as_str().unwrap().contains("'missing'"),
+            "{json}"
+        );
+
+        let (status, json) = post_json(
+            &mut app,
+            "/api/jobs",
+            None,
+            serde_json::json!({
+                "workflow": workflow_input_output_json(),
+                "params": {"greeting": "hello world"},
+                "breakpoints": ["wo"]
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let job_id = json["id"].as_str().unwrap().to_string();
+        let _stop_job = state
+            .inner
+            .jobs
+            .get(&job_id)
+            .unwrap()
+            .cancel_token
+            .clone()
+            .drop_guard();
+
+        let mut paused = serde_json::Value::Null;
+        for _ in 0..100 {
+            let (_, job) = get_json(&mut app, &format!("/api/jobs/{job_id}")).await;
+            if !job["step_paused"].is_null() {
+                paused = job;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert_eq!(paused["step_paused"]["node_id"], "wo", "{paused}");
+        assert_eq!(paused["step_paused"]["outputs"]["greeting"], "hello world");
+        assert_eq!(paused["step_mode"], false);
+        assert_eq!(paused["breakpoints"], serde_json::json!(["wo"]));
+
+        let (status, _) = post_json(
+            &mut app,
+            &format!("/api/jobs/{job_id}/step"),
+            None,
+            serde_json::json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            wait_for_job_terminal_status(&state, &job_id).await,
+            JobStatus::Completed
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_finished_job_is_announced_to_job_event_subscribers() {
+        let mut node_registry = NodeRegistry::new();
+        register_all_nodes(&mut node_registry);
+        let state = AppState::new(
+            node_registry,
+            ModelRegistry::with_builtin_models(test_models_dir()),
+            DashMap::new(),
+            AppConfig::default(),
+            test_config_path(),
+            test_data_dir(),
+        );
+        let mut events = state.subscribe_job_events();
+        let mut app = app_router(state.clone());
+
+        let (status, json) = post_json(
+            &mut app,
+            "/api/jobs",
+            None,
+            serde_json::json!({
+                "workflow": workflow_input_output_json(),
+                "params": {"greeting": "hello world"}
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let job_id = json["id"].as_str().unwrap().to_string();
+        assert_eq!(
+            wait_for_job_terminal_status(&state, &job_id).await,
+            JobStatus::Completed
+        );
+
+        let event = tokio::time::timeout(Duration::from_secs(5), events.recv())
+            .await
+            .expect("job finished event")
+            .unwrap();
+        assert_eq!(event.job_id, job_id);
+        assert_eq!(event.status, JobStatus::Completed);
+        assert!(event.duration_ms.is_some());
+        assert!(event.error.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_completed_job_exposes_workflow_outputs() {
+        let data_dir = test_data_dir();
+        let full_state = |data_dir: PathBuf| {
+            let mut node_registry = NodeRegistry::new();
+            register_all_nodes(&mut node_registry);
+            AppState::new(
+                node_registry,
+                ModelRegistry::with_builtin_models(test_models_dir()),
+                DashMap::new(),
+                AppConfig::default(),
+                test_config_path(),
+                data_dir,
+            )
+        };
+        let state = full_state(data_dir.clone());
+        let mut app = app_router(state.clone());
+
+        let (status, created) = post_json(
+            &mut app,
+            "/api/jobs",
+            None,
+            serde_json::json!({
+                "workflow": workflow_input_output_json(),
+                "params": {"greeting": "hello world"}
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let job_id = created["id"].as_str().unwrap().to_string();
+        assert_eq!(
+            wait_for_job_terminal_status(&state, &job_id).await,
+            JobStatus::Completed
+        );
+
+        let (_, job) = get_json(&mut app, &format!("/api/jobs/{job_id}")).await;
+        assert_eq!(job["outputs"]["greeting"], "hello world");
+
+        let (status, outputs) = get_json(&mut app, &format!("/api/jobs/{job_id}/outputs")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(outputs["status"], "completed");
+        assert_eq!(outputs["outputs"]["greeting"], "hello world");
+
+        // The completed status is persisted just after it is set in memory.
+        assert!(wait_for_persisted_status(&data_dir, &job_id, "completed").await);
+        let restored = full_state(data_dir.clone());
+        let restored_job = restored.inner.jobs.get(&job_id).unwrap();
+        assert_eq!(
+            restored_job.outputs.as_ref().unwrap()["greeting"],
+            "hello world"
+        );
+        drop(restored_job);
+
+        let (status, _) = get_json(&mut app, "/api/jobs/missing/outputs").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let _ = std::fs::remove_dir_all(data_dir);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_create_job_infers_workflow_input_params_when_top_level_params_missing() {
+        let mut node_registry = NodeRegistry::new();
+        register_all_nodes(&mut node_registry);
+        let model_registry = ModelRegistry::with_builtin_models(test_models_dir());
+        let state = AppState::new(
+            node_registry,
+            model_registry,
+            DashMap::new(),
+            AppConfig::default(),
+            test_config_path(),
+            test_data_dir(),
+        );
+        let mut app = app_router(state.clone());
+
+        let body = serde_json::json!({
+            "workflow": {
+                "nodes": [
+                    {"id": "wi", "node_type": "WorkflowInput", "params": {
+                        "ports": [{"name": "greeting", "port_type": "Str"}],
+                        "greeting": "hello from interface"
+                    }},
+                    {"id": "wo", "node_type": "WorkflowOutput", "params": {
+                        "ports": [{"name": "greeting", "port_type": "Str"}]
+                    }}
+                ],
+                "connections": [
+                    {
+                        "from_node": "wi",
+                        "from_port": "greeting",
+                        "to_node": "wo",
+                        "to_port": "greeting",
+                        "port_type": "Str"
+                    }
+                ],
+                "interface": {
+                    "inputs": [{"name": "greeting", "port_type": "Str"}],
+                    "outputs": [{"name": "greeting", "port_type": "Str"}]
+                }
+            }
+        });
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/jobs")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let create_json: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
+        let job_id = create_json["id"].as_str().unwrap().to_string();
+
+        let job = state
+            .inner
+            .jobs
+            .get(&job_id)
+            .expect("job should remain available");
+
+        let params = job
+            .params
+            .as_ref()
+            .expect("params should be inferred from WorkflowInput node params");
+        assert_eq!(
+            params.get("greeting"),
+            Some(&serde_json::json!("hello from interface"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_job_prefers_explicit_workflow_name_over_workflow_payload_name() {
+        let state = test_state();
+        let mut app = app_router(state.clone());
+
+        let body = serde_json::json!({
+            "workflow_name": "Named from request",
+            "workflow": {
+                "name": "Name inside workflow JSON",
+                "nodes": [
+                    {"id": "src", "node_type": "test_source", "params": {}},
+                    {"id": "dst", "node_type": "test_sink", "params": {}}
+                ],
+                "connections": [
+                    {
+                        "from_node": "src",
+                        "from_port": "output",
+                        "to_node": "dst",
+                        "to_port": "input",
+                        "port_type": "VideoFrames"
+                    }
+                ]
+            }
+        });
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/jobs")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let job_id = json["id"]
+            .as_str()
+            .expect("job id should be present")
+            .to_string();
+
+        let job = state
+            .inner
+            .jobs
+            .get(&job_id)
+            .expect("job should exist in memory");
+        assert_eq!(job.workflow_name, "Named from request");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_create_job_jellyfin_video_manual_node_params_execute_successfully() {
+        let mut node_registry = NodeRegistry::new();
+        register_all_nodes(&mut node_registry);
+        let model_registry = ModelRegistry::with_builtin_models(test_models_dir());
+        let state = AppState::new(
+            node_registry,
+            model_registry,
+            DashMap::new(),
+            AppConfig::default(),
+            test_config_path(),
+            test_data_dir(),
+        );
+        let mut app = app_router(state.clone());
+
+        let workflow = serde_json::json!({
+            "nodes": [
+                {"id": "jelly", "node_type": "JellyfinVideo", "params": {
+                    "jellyfin_url": "http://localhost:8096",
+                    "api_key": "test-api-key",
+                    "item_id": "episode-01"
+                }},
+                {"id": "wo", "node_type": "WorkflowOutput", "params": {
+                    "ports": [{"name": "video_url", "port_type": "Str"}]
+                }}
+            ],
+            "connections": [
+                {
+                    "from_node": "jelly",
+                    "from_port": "video_url",
+                    "to_node": "wo",
+                    "to_port": "video_url",
+                    "port_type": "Str"
+                }
+            ],
+            "interface": {
+                "inputs": [],
+                "outputs": [{"name": "video_url", "port_type": "Str"}]
+            }
+        });
+
+        let body = serde_json::json!({ "workflow": workflow });
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/jobs")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let create_json: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
+        let job_id = create_json["id"]
+            .as_str()
+            .expect("job id should be present")
+            .to_string();
+
+        let status = wait_for_job_terminal_status(&state, &job_id).await;
+        let job = state
+            .inner
+            .jobs
+            .get(&job_id)
+            .expect("job should remain queryable in state");
+
+        assert_eq!(
+            status,
+            JobStatus::Completed,
+            "expected Completed, got {:?}, error: {:?}",
+            job.status,
+            job.error
+        );
+        assert!(
+            job.error.is_none(),
+            "manual node params path should not fail JellyfinVideo Str input resolution"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_create_job_constant_str_param_validates_and_executes_across_boundary() {
+        let mut node_registry = NodeRegistry::new();
+        register_all_nodes(&mut node_registry);
+        let model_registry = ModelRegistry::with_builtin_models(test_models_dir());
+        let state = AppState::new(
+            node_registry,
+            model_registry,
+            DashMap::new(),
+            AppConfig::default(),
+            test_config_path(),
+            test_data_dir(),
+        );
+        let mut app = app_router(state.clone());
+
+        let workflow = serde_json::json!({
+            "nodes": [
+                {"id": "constant", "node_type": "Constant", "params": {
+                    "type": "Str",
+                    "value": "hello-from-constant"
+                }},
+                {"id": "wo", "node_type": "WorkflowOutput", "params": {
+                    "ports": [{"name": "value", "port_type": "Str"}]
+                }}
+            ],
+            "connections": [
+                {
+                    "from_node": "constant",
+                    "from_port": "value",
+                    "to_node": "wo",
+                    "to_port": "value",
+                    "port_type": "Str"
+                }
+            ],
+            "interface": {
+                "inputs": [],
+                "outputs": [{"name": "value", "port_type": "Str"}]
+            }
+        });
+
+        let body = serde_json::json!({ "workflow": workflow });
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/jobs")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let create_json: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
+        let job_id = create_json["id"]
+            .as_str()
+            .expect("job id should be present")
+            .to_string();
+
+        let status = wait_for_job_terminal_status(&state, &job_id).await;
+        let job = state
+            .inner
+            .jobs
+            .get(&job_id)
+            .expect("job should remain queryable in state");
+
+        assert_eq!(
+            status,
+            JobStatus::Completed,
+            "expected Completed, got {:?}, error: {:?}",
+            job.status,
+            job.error
+        );
+        assert!(
+            job.error.is_none(),
+            "Constant type=Str should validate and execute without Int/Str boundary mismatch"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_job_invalid_workflow() {
+        let mut app = test_router();
+        let body = serde_json::json!({
+            "workflow": {"invalid": true}
+        });
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/jobs")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_create_job_post_actions_override_workflow() {
+        let state = test_state();
+        let mut app = app_router(state.clone());
+        let mut workflow = valid_workflow_json();
+        workflow["post_actions"] = serde_json::json!([{"action": "delete_source"}]);
+
+        let post = |body: serde_json::Value| {
+            Request::builder()
+                .method("POST")
+                .uri("/api/jobs")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap()
+        };
+
+        let req = post(serde_json::json!({
+            "workflow": workflow,
+            "post_actions": [
+                {"action": "move", "to": "/library/"},
+                {"action": "chmod", "mode": "664"},
+            ]
+        }));
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let id = json["id"].as_str().unwrap();
+        let actions = state
+            .inner
+            .jobs
+            .get(id)
+            .unwrap()
+            .workflow
+            .post_actions
+            .clone();
+        assert_eq!(
+            actions,
+            vec![
+                PostAction::Move {
+                    to: PathBuf::from("/library/")
+                },
+                PostAction::Chmod {
+                    mode: "664".to_string()
+                },
+            ]
+        );
+
+        let req = post(serde_json::json!({
+            "workflow": workflow,
+            "post_actions": [{"action": "chmod", "mode": "rwx"}]
+        }));
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        workflow["post_actions"] = serde_json::json!([{"action": "chown"}]);
+        let resp = send_request(&mut app, post(serde_json::json!({"workflow": workflow}))).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_run_workflow_name_creates_single_job_and_persists_metadata() {
+        let data_dir = test_data_dir();
+        let state = test_state_with_data_dir(data_dir.clone());
+        let mut app = app_router(state.clone());
+
+        let workflows_dir = unique_temp_dir("videnoa-run-workflows");
+        let presets_dir = unique_temp_dir("videnoa-run-presets");
+        std::fs::create_dir_all(&workflows_dir).expect("create workflows dir");
+        std::fs::create_dir_all(&presets_dir).expect("create presets dir");
+        set_workflow_lookup_dirs(&state, workflows_dir.clone(), presets_dir.clone()).await;
+
+        let workflow_doc = serde_json::json!({
+            "name": "Inner Name Should Not Override",
+            "description": "Run API test",
+            "workflow": valid_workflow_json()
+        });
+        write_json_file(&workflows_dir.join("named-run.json"), &workflow_doc);
+
+        let body = serde_json::json!({
+            "workflow_name": "named-run",
+            "params": {
+                "input": "/tmp/input-video.mkv",
+                "seed": 42
+            }
+        });
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/run")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let job_id = json["id"]
+            .as_str()
+            .expect("job id should be present")
+            .to_string();
+        assert_eq!(json["status"], "queued");
+        assert_eq!(
+            state.inner.jobs.len(),
+            1,
+            "run endpoint must create exactly one job"
+        );
+
+        let job = state
+            .inner
+            .jobs
+            .get(&job_id)
+            .expect("job should remain available");
+        assert_eq!(job.workflow_name, "named-run");
+        assert_eq!(job.workflow_source, WORKFLOW_SOURCE_API_RUN_WORKFLOWS);
+        let params = job.params.as_ref().expect("params should be preserved");
+        assert_eq!(
+            params.get("input"),
+            Some(&serde_json::json!("/tmp/input-video.mkv"))
+        );
+        assert_eq!(params.get("seed"), Some(&serde_json::json!(42)));
+
+        let conn = Connection::open(data_dir.join("jobs.db")).expect("open jobs db");
+        let (workflow_name, workflow_source, params_json): (String, String, Option<String>) = conn
+            .query_row(
+                "SELECT workflow_name, workflow_source, params_json FROM jobs WHERE id = ?1",
+                rusqlite::params![job_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .expect("query run job metadata");
+
+        assert_eq!(workflow_name, "named-run");
+        assert_eq!(workflow_source, WORKFLOW_SOURCE_API_RUN_WORKFLOWS);
+        let params_value: serde_json::Value = serde_json::from_str(
+            &params_json.expect("params_json should be persisted for /api/run"),
+        )
+        .expect("params_json should deserialize");
+        assert_eq!(params_value["input"], "/tmp/input-video.mkv");
+        assert_eq!(params_value["seed"], 42);
+
+        let _ = std::fs::remove_dir_all(&workflows_dir);
+        let _ = std::fs::remove_dir_all(&presets_dir);
+    }
+
+    #[tokio::test]
+    async fn test_run_workflow_name_rejects_json_suffix() {
+        let mut app = test_router();
+        let body = serde_json::json!({
+            "workflow_name": "named-run.json"
+        });
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/run")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["error"], "workflow_name must not include .json suffix");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_run_workflow_name_prefers_workflows_dir_over_presets_dir() {
+        let state = test_state();
+        let mut app = app_router(state.clone());
+
+        let workflows_dir = unique_temp_dir("videnoa-run-precedence-workflows");
+        let presets_dir = unique_temp_dir("videnoa-run-precedence-presets");
+        std::fs::create_dir_all(&workflows_dir).expect("create workflows dir");
+        std::fs::create_dir_all(&presets_dir).expect("create presets dir");
+        set_workflow_lookup_dirs(&state, workflows_dir.clone(), presets_dir.clone()).await;
+
+        write_json_file(
+            &workflows_dir.join("shared-name.json"),
+            &serde_json::json!({"workflow": valid_workflow_json()}),
+        );
+        write_json_file(
+            &presets_dir.join("shared-name.json"),
+            &serde_json::json!({"workflow": {"invalid": true}}),
+        );
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/run")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::to_vec(&serde_json::json!({"workflow_name": "shared-name"})).unwrap(),
+            ))
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let job_id = json["id"]
+            .as_str()
+            .expect("job id should be present")
+            .to_string();
+
+        let job = state
+            .inner
+            .jobs
+            .get(&job_id)
+            .expect("job should exist in memory");
+        assert_eq!(job.workflow_source, WORKFLOW_SOURCE_API_RUN_WORKFLOWS);
+
+        let _ = std::fs::remove_dir_all(&workflows_dir);
+        let _ = std::fs::remove_dir_all(&presets_dir);
+    }
+
+    #[tokio::test]
+    async fn test_run_workflow_name_validates_and_coerces_interface_params() {
+        let state = test_state();
+        let mut app = app_router(state.clone());
+
+        let workflows_dir = unique_temp_dir("videnoa-run-interface-workflows");
+        let presets_dir = unique_temp_dir("videnoa-run-interface-presets");
+        std::fs::create_dir_all(&workflows_dir).expect("create workflows dir");
+        std::fs::create_dir_all(&presets_dir).expect("create presets dir");
+        set_workflow_lookup_dirs(&state, workflows_dir.clone(), presets_dir.clone()).await;
+
+        let mut workflow = valid_workflow_json();
+        workflow["interface"] = serde_json::json!({
+            "inputs": [
+                {
+                    "name": "scale",
+                    "port_type": "Int",
+                    "default_value": 2,
+                    "description": "Upscale factor",
+                    "enum_options": [2, 4]
+                },
+                {"name": "crf", "port_type": "Int", "min": 0, "max": 51},
+                {
+                    "name": "input",
+                    "port_type": "Path",
+                    "file_picker": {"mode": "open", "extensions": ["mkv"]}
+                }
+            ],
+            "outputs": []
+        });
+        write_json_file(
+            &workflows_dir.join("constrained.json"),
+            &serde_json::json!({ "workflow": workflow }),
+        );
+
+        let (status, interface) =
+            get_json(&mut app, "/api/workflows/constrained.json/interface").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(interface["inputs"][0]["description"], "Upscale factor");
+        assert_eq!(
+            interface["inputs"][0]["enum_options"],
+            serde_json::json!([2, 4])
+        );
+        assert_eq!(interface["inputs"][1]["max"], 51.0);
+        assert_eq!(interface["inputs"][2]["file_picker"]["mode"], "open");
+
+        let (status, json) = post_json(
+            &mut app,
+            "/api/run",
+            None,
+            serde_json::json!({
+                "workflow_name": "constrained",
+                "params": {"scale": 3, "crf": 60, "input": "/tmp/in.mkv"}
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let error = json["error"].as_str().unwrap();
+        assert!(error.contains("param 'scale'"), "{error}");
+        assert!(error.contains("param 'crf'"), "{error}");
+        assert!(!error.contains("param 'input'"), "{error}");
+        assert!(state.inner.jobs.is_empty());
+
+        let (status, json) = post_json(
+            &mut app,
+            "/api/run",
+            None,
+            serde_json::json!({
+                "workflow_name": "constrained",
+                "params": {"crf": "sharp"}
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            json["param_errors"],
+            serde_json::json!([
+                {"param": "crf", "message": "expected Int, got \"sharp\""},
+                {"param": "input", "message": "a value is required"}
+            ])
+        );
+
+        let (status, json) = post_json(
+            &mut app,
+            "/api/run",
+            None,
+            serde_json::json!({
+                "workflow_name": "constrained",
+                "params": {"scale": "4", "crf": "18", "input": "/tmp/in.mkv"}
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let job = state.inner.jobs.get(json["id"].as_str().unwrap()).unwrap();
+        let params = job.params.as_ref().unwrap();
+        assert_eq!(params["scale"], serde_json::json!(4));
+        assert_eq!(params["crf"], serde_json::json!(18));
+        drop(job);
+
+        let _ = std::fs::remove_dir_all(&workflows_dir);
+        let _ = std::fs::remove_dir_all(&presets_dir);
+    }
+
+    #[tokio::test]
+    async fn test_run_workflow_name_rejects_missing_or_empty_workflow_name() {
+        let mut app = test_router();
+
+        let missing_req = Request::builder()
+            .method("POST")
+            .uri("/api/run")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::to_vec(&serde_json::json!({})).unwrap(),
+            ))
+            .unwrap();
+        let missing_resp = send_request(&mut app, missing_req).await;
+        assert_eq!(missing_resp.status(), StatusCode::BAD_REQUEST);
+
+        let missing_body = axum::body::to_bytes(missing_resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let missing_json: serde_json::Value = serde_json::from_slice(&missing_body).unwrap();
+        assert_eq!(missing_json["error"], "workflow_name is required");
+
+        let empty_req = Request::builder()
+            .method("POST")
+            .uri("/api/run")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::to_vec(&serde_json::json!({"workflow_name": "   "})).unwrap(),
+            ))
+            .unwrap();
+        let empty_resp = send_request(&mut app, empty_req).await;
+        assert_eq!(empty_resp.status(), StatusCode::BAD_REQUEST);
+
+        let empty_body = axum::body::to_bytes(empty_resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let empty_json: serde_json::Value = serde_json::from_slice(&empty_body).unwrap();
+        assert_eq!(empty_json["error"], "workflow_name is required");
+    }
+
+    #[tokio::test]
+    async fn test_run_workflow_name_rejects_batch_file_paths_payload() {
+        let state = test_state();
+        let mut app = app_router(state.clone());
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/run")
+            .header("content-type", "application/json")
+            .body(Body::from(
+                serde_json::to_vec(&serde_json::json!({
+                    "workflow_name": "shared-name",
+                    "file_paths": ["/tmp/a.mkv", "/tmp/b.mkv"]
+                }))
+                .unwrap(),
+            ))
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            state.inner.jobs.len(),
+            0,
+            "batch payload must not create jobs"
+        );
+    }
+
+    async fn assert_legacy_node_rejected(node_id: &str, node_type: &str) {
+        let mut app = test_router();
+        let body = serde_json::json!({
+            "workflow": {
+                "nodes": [
+                    {
+                        "id": node_id,
+                        "node_type": node_type,
+                        "params": {}
+                    }
+                ],
+                "connections": []
+            }
+        });
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/jobs")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let err_json: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
+        let err = err_json["error"]
+            .as_str()
+            .expect("error payload should include message");
+
+        assert!(
+            err.contains("workflow validation failed"),
+            "expected validation failure prefix, got: {err}"
+        );
+        assert!(
+            err.contains(&format!(
+                "failed to instantiate node '{node_id}' of type '{node_type}'"
+            )),
+            "expected node id + type in error, got: {err}"
+        );
+        assert!(
+            err.contains(&format!("unknown node type: {node_type}")),
+            "expected unknown node type detail, got: {err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_job_rejects_legacy_jellyfin_input_node_type() {
+        assert_legacy_node_rejected("legacy_jellyfin", "JellyfinInput").await;
+    }
+
+    #[tokio::test]
+    async fn test_list_jobs_returns_created() {
+        let state = test_state();
+        let mut app = app_router(state.clone());
+
+        let body = serde_json::json!({
+            "workflow": valid_workflow_json()
+        });
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/jobs")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+        let _ = send_request(&mut app, req).await;
+
+        let req = Request::builder()
+            .uri("/api/jobs")
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+        assert!(!json.is_empty());
+        assert!(json[0].get("workflow_name").is_some());
+        assert!(json[0].get("workflow_source").is_some());
+        assert!(json[0].get("params").is_some());
+        assert!(json[0].get("rerun_of_job_id").is_some());
+        assert!(json[0].get("duration_ms").is_some());
     }
 
-    fn write_json_file(path: &StdPath, value: &serde_json::Value) {
-        let bytes = serde_json::to_vec_pretty(value).expect("serialize test workflow JSON");
-        std::fs::write(path, bytes).expect("write test workflow JSON");
+    fn insert_aged_jobs(state: &AppState, jobs: &[(&str, JobStatus, i64)]) {
+        for (id, status, age_days) in jobs {
+            let mut job = build_test_job(id.to_string(), *status, None);
+            job.created_at = Utc::now() - chrono::Duration::days(*age_days);
+            job.completed_at = job.completed_at.map(|_| job.created_at);
+            insert_test_job(state, job);
+        }
     }
 
-    async fn set_workflow_lookup_dirs(
-        state: &AppState,
-        workflows_dir: PathBuf,
-        presets_dir: PathBuf,
-    ) {
-        let mut config = state.inner.config.write().await;
-        config.paths.workflows_dir = workflows_dir;
-        config.paths.presets_dir = presets_dir;
-    }
+    #[tokio::test]
+    async fn test_bulk_delete_jobs_removes_only_matching_finished_jobs() {
+        let data_dir = test_data_dir();
+        let state = test_state_with_data_dir(data_dir.clone());
+        insert_aged_jobs(
+            &state,
+            &[
+                ("old-failed", JobStatus::Failed, 10),
+                ("new-failed", JobStatus::Failed, 0),
+                ("old-done", JobStatus::Completed, 10),
+                ("old-queued", JobStatus::Queued, 10),
+            ],
+        );
+        let mut app = app_router(state.clone());
 
-    fn test_router() -> Router {
-        app_router(test_state())
-    }
+        let before = url::form_urlencoded::byte_serialize(
+            (Utc::now() - chrono::Duration::days(1))
+                .to_rfc3339()
+                .as_bytes(),
+        )
+        .collect::<String>();
+        let req = Request::builder()
+            .method("DELETE")
+            .uri(format!("/api/jobs?status=failed&before={before}"))
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["deleted"], 1);
+        assert!(!state.inner.jobs.contains_key("old-failed"));
+        assert!(persisted_job_status(&data_dir, "old-failed").is_none());
+        for kept in ["new-failed", "old-done", "old-queued"] {
+            assert!(state.inner.jobs.contains_key(kept), "{kept}");
+        }
 
-    fn valid_workflow_json() -> serde_json::Value {
-        serde_json::json!({
-            "nodes": [
-                {"id": "src", "node_type": "test_source", "params": {}},
-                {"id": "dst", "node_type": "test_sink", "params": {}}
-            ],
-            "connections": [
-                {
-                    "from_node": "src",
-                    "from_port": "output",
-                    "to_node": "dst",
-                    "to_port": "input",
-                    "port_type": "VideoFrames"
-                }
-            ]
-        })
+        for uri in ["/api/jobs", "/api/jobs?status=running"] {
+            let req = Request::builder()
+                .method("DELETE")
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap();
+            let resp = send_request(&mut app, req).await;
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST, "{uri}");
+        }
+        let _ = std::fs::remove_dir_all(data_dir);
     }
 
-    fn delay_workflow_json(sleep_ms: u64) -> serde_json::Value {
-        serde_json::json!({
-            "nodes": [
-                {
-                    "id": "delay",
-                    "node_type": "test_delay",
-                    "params": {
-                        "sleep_ms": sleep_ms
-                    }
-                }
+    #[tokio::test]
+    async fn test_job_retention_keeps_newest_and_active_jobs() {
+        let data_dir = test_data_dir();
+        let state = test_state_with_data_dir(data_dir.clone());
+        insert_aged_jobs(
+            &state,
+            &[
+                ("day-0", JobStatus::Completed, 0),
+                ("day-1", JobStatus::Failed, 1),
+                ("day-2", JobStatus::Cancelled, 2),
+                ("day-40", JobStatus::Completed, 40),
+                ("running-40", JobStatus::Running, 40),
             ],
-            "connections": []
-        })
-    }
+        );
 
-    fn workflow_input_output_json() -> serde_json::Value {
-        serde_json::json!({
-            "nodes": [
-                {"id": "wi", "node_type": "WorkflowInput", "params": {
-                    "ports": [{"name": "greeting", "port_type": "Str"}]
-                }},
-                {"id": "wo", "node_type": "WorkflowOutput", "params": {
-                    "ports": [{"name": "greeting", "port_type": "Str"}]
-                }}
-            ],
-            "connections": [
-                {
-                    "from_node": "wi",
-                    "from_port": "greeting",
-                    "to_node": "wo",
-                    "to_port": "greeting",
-                    "port_type": "Str"
-                }
-            ],
-            "interface": {
-                "inputs": [{"name": "greeting", "port_type": "Str"}],
-                "outputs": [{"name": "greeting", "port_type": "Str"}]
-            }
-        })
-    }
+        let disabled = JobsConfig::default();
+        assert_eq!(enforce_job_retention(&state, &disabled, Utc::now()), 0);
 
-    fn persisted_job_status(data_dir: &StdPath, job_id: &str) -> Option<String> {
-        let db_path = data_dir.join("jobs.db");
-        let conn = Connection::open(db_path).ok()?;
-        conn.query_row(
-            "SELECT status FROM jobs WHERE id = ?1",
-            rusqlite::params![job_id],
-            |row| row.get(0),
-        )
-        .ok()
-    }
+        let by_age = JobsConfig {
+            retention_max_age_days: 30,
+            ..JobsConfig::default()
+        };
+        assert_eq!(enforce_job_retention(&state, &by_age, Utc::now()), 1);
+        assert!(!state.inner.jobs.contains_key("day-40"));
 
-    fn build_test_job(
-        id: String,
-        status: JobStatus,
-        params: Option<HashMap<String, serde_json::Value>>,
-    ) -> Job {
-        let workflow: PipelineGraph =
-            serde_json::from_value(valid_workflow_json()).expect("workflow should deserialize");
-        let created_at = Utc::now() - chrono::Duration::seconds(5);
-        let started_at = if status == JobStatus::Queued {
-            None
-        } else {
-            Some(created_at + chrono::Duration::seconds(1))
+        let by_count = JobsConfig {
+            retention_max_jobs: 2,
+            ..JobsConfig::default()
         };
-        let completed_at = if matches!(
-            status,
-            JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled
-        ) {
-            Some(created_at + chrono::Duration::seconds(2))
-        } else {
-            None
+        assert_eq!(enforce_job_retention(&state, &by_count, Utc::now()), 1);
+        assert!(!state.inner.jobs.contains_key("day-2"));
+        assert!(state.inner.jobs.contains_key("day-0"));
+        assert!(state.inner.jobs.contains_key("day-1"));
+        assert!(state.inner.jobs.contains_key("running-40"));
+        assert!(persisted_job_status(&data_dir, "day-2").is_none());
+        let _ = std::fs::remove_dir_all(data_dir);
+    }
+
+    #[tokio::test]
+    async fn test_list_jobs_filters_and_paginates() {
+        let data_dir = test_data_dir();
+        let state = test_state_with_data_dir(data_dir.clone());
+        let base = Utc::now() - chrono::Duration::hours(1);
+        for (index, (status, name, source)) in [
+            (
+                JobStatus::Completed,
+                "Anime 2x Upscale",
+                WORKFLOW_SOURCE_API_JOBS,
+            ),
+            (
+                JobStatus::Failed,
+                "Anime 4x Upscale",
+                WORKFLOW_SOURCE_API_JOBS,
+            ),
+            (
+                JobStatus::Failed,
+                "Interpolation",
+                WORKFLOW_SOURCE_API_BATCH,
+            ),
+            (
+                JobStatus::Cancelled,
+                "anime batch",
+                WORKFLOW_SOURCE_API_BATCH,
+            ),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let mut job = build_test_job(format!("job-{index}"), status, None);
+            job.created_at = base + chrono::Duration::minutes(index as i64);
+            job.workflow_name = name.to_string();
+            job.workflow_source = source.to_string();
+            insert_test_job(&state, job);
+        }
+        let mut app = app_router(state);
+        let ids = |jobs: &serde_json::Value| -> Vec<String> {
+            jobs.as_array()
+                .unwrap()
+                .iter()
+                .map(|job| job["id"].as_str().unwrap().to_string())
+                .collect()
         };
-        let error = match status {
-            JobStatus::Failed => Some("source failed".to_string()),
-            JobStatus::Cancelled => Some("source cancelled".to_string()),
-            _ => None,
+
+        let (_, jobs) = get_json(&mut app, "/api/jobs").await;
+        assert_eq!(ids(&jobs), ["job-3", "job-2", "job-1", "job-0"]);
+
+        let (_, jobs) = get_json(&mut app, "/api/jobs?status=failed,cancelled&order=asc").await;
+        assert_eq!(ids(&jobs), ["job-1", "job-2", "job-3"]);
+
+        let (_, jobs) = get_json(
+            &mut app,
+            &format!("/api/jobs?workflow_source={WORKFLOW_SOURCE_API_BATCH}&q=ANIME"),
+        )
+        .await;
+        assert_eq!(ids(&jobs), ["job-3"]);
+
+        let encode = |ts: DateTime<Utc>| {
+            url::form_urlencoded::byte_serialize(ts.to_rfc3339().as_bytes()).collect::<String>()
         };
+        let (_, jobs) = get_json(
+            &mut app,
+            &format!(
+                "/api/jobs?created_after={}&created_before={}",
+                encode(base + chrono::Duration::seconds(30)),
+                encode(base + chrono::Duration::seconds(150))
+            ),
+        )
+        .await;
+        assert_eq!(ids(&jobs), ["job-2", "job-1"]);
 
-        Job {
-            id,
-            status,
-            workflow,
-            created_at,
-            started_at,
-            completed_at,
-            progress: None,
-            error,
-            cancel_token: CancellationToken::new(),
-            params,
-            workflow_name: "Source Workflow".to_string(),
-            workflow_source: WORKFLOW_SOURCE_API_JOBS.to_string(),
-            rerun_of_job_id: None,
+        let req = Request::builder()
+            .uri("/api/jobs?limit=3")
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        let cursor = resp.headers()[NEXT_CURSOR_HEADER]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let (_, jobs) = get_json(&mut app, &format!("/api/jobs?limit=3&cursor={cursor}")).await;
+        assert_eq!(ids(&jobs), ["job-0"]);
+
+        for uri in [
+            "/api/jobs?status=exploded",
+            "/api/jobs?order=sideways",
+            "/api/jobs?limit=0",
+            "/api/jobs?cursor=not-a-cursor",
+        ] {
+            let (status, _) = get_json(&mut app, uri).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
         }
+        let _ = std::fs::remove_dir_all(data_dir);
     }
 
-    fn insert_test_job(state: &AppState, job: Job) {
-        state
-            .persist_job_snapshot(&job)
-            .expect("persist source job snapshot");
-        state.inner.jobs.insert(job.id.clone(), job);
-    }
+    #[tokio::test]
+    async fn test_update_job_tags_and_note() {
+        let data_dir = test_data_dir();
+        let state = test_state_with_data_dir(data_dir.clone());
+        insert_test_job(
+            &state,
+            build_test_job("tagged".to_string(), JobStatus::Completed, None),
+        );
+        insert_test_job(
+            &state,
+            build_test_job("untagged".to_string(), JobStatus::Completed, None),
+        );
+        let mut app = app_router(state);
+        let patch = |body: serde_json::Value| {
+            Request::builder()
+                .method("PATCH")
+                .uri("/api/jobs/tagged")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap()
+        };
 
-    struct TestNode {
-        node_type: String,
-        inputs: Vec<crate::node::PortDefinition>,
-        outputs: Vec<crate::node::PortDefinition>,
-    }
+        let resp = send_request(
+            &mut app,
+            patch(serde_json::json!({
+                "tags": [" show-a ", "s01", "show-a", ""],
+                "note": "rerun with sharper model"
+            })),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let job: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(job["tags"], serde_json::json!(["show-a", "s01"]));
+        assert_eq!(job["note"], "rerun with sharper model");
 
-    struct DelayNode {
-        sleep_ms: u64,
+        let (_, jobs) = get_json(&mut app, "/api/jobs?tag=s01").await;
+        let ids: Vec<&str> = jobs
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|job| job["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, ["tagged"]);
+
+        let resp = send_request(&mut app, patch(serde_json::json!({ "note": "" }))).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let (_, job) = get_json(&mut app, "/api/jobs/tagged").await;
+        assert_eq!(job["tags"], serde_json::json!(["show-a", "s01"]));
+        assert!(job["note"].is_null());
+
+        let resp = send_request(
+            &mut app,
+            patch(serde_json::json!({ "tags": ["x".repeat(MAX_JOB_TAG_LEN + 1)] })),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let restored = test_state_with_data_dir(data_dir.clone());
+        assert_eq!(
+            restored.inner.jobs.get("tagged").unwrap().tags,
+            ["show-a", "s01"]
+        );
+        let _ = std::fs::remove_dir_all(data_dir);
     }
 
-    impl crate::node::Node for TestNode {
-        fn node_type(&self) -> &str {
-            &self.node_type
-        }
-        fn input_ports(&self) -> Vec<crate::node::PortDefinition> {
-            self.inputs.clone()
-        }
-        fn output_ports(&self) -> Vec<crate::node::PortDefinition> {
-            self.outputs.clone()
-        }
-        fn execute(
-            &mut self,
-            _inputs: &std::collections::HashMap<String, crate::types::PortData>,
-            _ctx: &crate::node::ExecutionContext,
-        ) -> Result<std::collections::HashMap<String, crate::types::PortData>> {
-            Ok(std::collections::HashMap::new())
-        }
+    #[tokio::test]
+    async fn test_get_job_found() {
+        let state = test_state();
+        let mut app = app_router(state.clone());
+
+        let body = serde_json::json!({
+            "workflow": valid_workflow_json()
+        });
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/jobs")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let create_json: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
+        let job_id = create_json["id"].as_str().unwrap();
+
+        let req = Request::builder()
+            .uri(format!("/api/jobs/{job_id}"))
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["id"], job_id);
     }
 
-    impl crate::node::Node for DelayNode {
-        fn node_type(&self) -> &str {
-            "test_delay"
-        }
+    #[tokio::test]
+    async fn test_get_job_includes_metadata_for_ad_hoc_job() {
+        let state = test_state();
+        let mut app = app_router(state.clone());
 
-        fn input_ports(&self) -> Vec<crate::node::PortDefinition> {
-            vec![]
-        }
+        let mut workflow = valid_workflow_json();
+        workflow
+            .as_object_mut()
+            .expect("workflow should be object")
+            .insert(
+                "name".to_string(),
+                serde_json::Value::String("Manual Workflow".to_string()),
+            );
 
-        fn output_ports(&self) -> Vec<crate::node::PortDefinition> {
-            vec![]
-        }
+        let body = serde_json::json!({
+            "workflow": workflow,
+            "params": {"input": "/tmp/input-video.mkv"}
+        });
 
-        fn execute(
-            &mut self,
-            _inputs: &std::collections::HashMap<String, crate::types::PortData>,
-            _ctx: &crate::node::ExecutionContext,
-        ) -> Result<std::collections::HashMap<String, crate::types::PortData>> {
-            if self.sleep_ms > 0 {
-                std::thread::sleep(std::time::Duration::from_millis(self.sleep_ms));
-            }
-            Ok(std::collections::HashMap::new())
-        }
-    }
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/jobs")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
 
-    async fn send_request(router: &mut Router, request: Request<Body>) -> axum::response::Response {
-        router
-            .as_service()
-            .ready()
+        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
-            .unwrap()
-            .call(request)
+            .unwrap();
+        let create_json: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
+        let job_id = create_json["id"].as_str().unwrap();
+
+        let req = Request::builder()
+            .uri(format!("/api/jobs/{job_id}"))
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
-            .unwrap()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["workflow_name"], "Manual Workflow");
+        assert_eq!(json["workflow_source"], WORKFLOW_SOURCE_API_JOBS);
+        assert_eq!(json["params"]["input"], "/tmp/input-video.mkv");
+        assert!(json["rerun_of_job_id"].is_null());
+        assert!(json.get("duration_ms").is_some());
     }
 
-    async fn wait_for_job_terminal_status(state: &AppState, job_id: &str) -> JobStatus {
-        const MAX_POLLS: usize = 80;
-        const POLL_INTERVAL_MS: u64 = 50;
+    #[tokio::test]
+    async fn test_get_batch_job_includes_default_metadata() {
+        let state = test_state();
+        let mut app = app_router(state.clone());
 
-        for _ in 0..MAX_POLLS {
-            if let Some(job) = state.inner.jobs.get(job_id) {
-                if matches!(
-                    job.status,
-                    JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled
-                ) {
-                    return job.status;
-                }
-            }
-            tokio::time::sleep(std::time::Duration::from_millis(POLL_INTERVAL_MS)).await;
-        }
+        let body = serde_json::json!({
+            "file_paths": [temp_path_str("video1.mkv")],
+            "workflow": valid_workflow_json()
+        });
 
-        panic!("job {job_id} did not reach terminal status within timeout");
-    }
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/batch")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
 
-    async fn wait_for_persisted_status(data_dir: &StdPath, job_id: &str, expected: &str) -> bool {
-        const MAX_POLLS: usize = 80;
-        const POLL_INTERVAL_MS: u64 = 25;
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let job_id = json["job_ids"][0].as_str().unwrap();
 
-        for _ in 0..MAX_POLLS {
-            if persisted_job_status(data_dir, job_id).as_deref() == Some(expected) {
-                return true;
-            }
-            tokio::time::sleep(std::time::Duration::from_millis(POLL_INTERVAL_MS)).await;
-        }
+        let req = Request::builder()
+            .uri(format!("/api/jobs/{job_id}"))
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
 
-        false
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["workflow_name"], DEFAULT_WORKFLOW_NAME_API_BATCH);
+        assert_eq!(json["workflow_source"], WORKFLOW_SOURCE_API_BATCH);
+        assert!(json["params"].is_null());
+        assert!(json["rerun_of_job_id"].is_null());
+        assert!(json.get("duration_ms").is_some());
     }
 
     #[tokio::test]
-    async fn test_health_endpoint() {
+    async fn test_get_job_not_found() {
         let mut app = test_router();
         let req = Request::builder()
-            .uri("/api/health")
+            .uri("/api/jobs/nonexistent-id")
+            .body(Body::empty())
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_delete_job_history_removes_only_target_row_and_views() {
+        let data_dir = test_data_dir();
+        let state = test_state_with_data_dir(data_dir.clone());
+        let mut app = app_router(state.clone());
+
+        let target_id = format!("delete-target-{}", Uuid::new_v4());
+        let target_job = build_test_job(target_id.clone(), JobStatus::Completed, None);
+        insert_test_job(&state, target_job);
+
+        let other_id = format!("delete-other-{}", Uuid::new_v4());
+        let other_job = build_test_job(other_id.clone(), JobStatus::Failed, None);
+        insert_test_job(&state, other_job);
+
+        let req = Request::builder()
+            .method("DELETE")
+            .uri(format!("/api/jobs/{target_id}"))
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        assert!(state.inner.jobs.get(&target_id).is_none());
+        assert!(state.inner.jobs.get(&other_id).is_some());
+
+        let req = Request::builder()
+            .uri(format!("/api/jobs/{target_id}"))
             .body(Body::empty())
             .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
 
+        let req = Request::builder()
+            .uri("/api/jobs")
+            .body(Body::empty())
+            .unwrap();
         let resp = send_request(&mut app, req).await;
         assert_eq!(resp.status(), StatusCode::OK);
 
         let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        assert_eq!(json["status"], "ok");
+        let listed_jobs: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+        assert!(!listed_jobs
+            .iter()
+            .any(|job| job["id"].as_str() == Some(target_id.as_str())));
+        assert!(listed_jobs
+            .iter()
+            .any(|job| job["id"].as_str() == Some(other_id.as_str())));
+
+        assert_eq!(persisted_job_status(&data_dir, &target_id), None);
+        assert_eq!(
+            persisted_job_status(&data_dir, &other_id).as_deref(),
+            Some("failed")
+        );
     }
 
     #[tokio::test]
-    async fn test_get_config_endpoint() {
-        let mut app = test_router();
+    async fn test_delete_job_history_cancels_active_job_then_removes_row() {
+        let data_dir = test_data_dir();
+        let state = test_state_with_data_dir(data_dir.clone());
+        let mut app = app_router(state.clone());
+
+        let active_id = format!("delete-active-{}", Uuid::new_v4());
+        let active_job = build_test_job(active_id.clone(), JobStatus::Running, None);
+        let cancel_probe = active_job.cancel_token.clone();
+        insert_test_job(&state, active_job);
+
         let req = Request::builder()
-            .uri("/api/config")
+            .method("DELETE")
+            .uri(format!("/api/jobs/{active_id}"))
             .body(Body::empty())
             .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        assert!(cancel_probe.is_cancelled());
+        assert!(state.inner.jobs.get(&active_id).is_none());
+        assert_eq!(persisted_job_status(&data_dir, &active_id), None);
 
+        let req = Request::builder()
+            .uri(format!("/api/jobs/{active_id}"))
+            .body(Body::empty())
+            .unwrap();
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_rerun_allows_any_status_and_creates_new_job() {
+        let source_statuses = [
+            JobStatus::Queued,
+            JobStatus::Running,
+            JobStatus::Completed,
+            JobStatus::Failed,
+            JobStatus::Cancelled,
+        ];
+
+        for source_status in source_statuses {
+            let state = test_state();
+            let mut app = app_router(state.clone());
+
+            let source_id = format!("rerun-source-{}", Uuid::new_v4());
+            let source_params = Some(HashMap::from([(
+                "seed".to_string(),
+                serde_json::json!(source_status as u8),
+            )]));
+            let source_job =
+                build_test_job(source_id.clone(), source_status, source_params.clone());
+            insert_test_job(&state, source_job.clone());
+
+            let req = Request::builder()
+                .method("POST")
+                .uri(format!("/api/jobs/{source_id}/rerun"))
+                .body(Body::empty())
+                .unwrap();
+            let resp = send_request(&mut app, req).await;
+            assert_eq!(
+                resp.status(),
+                StatusCode::CREATED,
+                "expected rerun to be allowed for status {source_status:?}"
+            );
 
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let config: AppConfig = serde_json::from_slice(&body).unwrap();
+            let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            let rerun_id = json["id"]
+                .as_str()
+                .expect("rerun response should include id")
+                .to_string();
 
-        assert_eq!(config.paths.models_dir, PathBuf::from("models"));
-        assert_eq!(config.server.port, 3000);
+            assert_ne!(rerun_id, source_id);
+            assert_eq!(json["status"], "queued");
+            assert!(json.get("created_at").is_some());
+
+            let rerun_job = state
+                .inner
+                .jobs
+                .get(&rerun_id)
+                .expect("rerun job should exist in state");
+            assert_eq!(
+                rerun_job.rerun_of_job_id.as_deref(),
+                Some(source_id.as_str())
+            );
+            assert_eq!(rerun_job.workflow_name, source_job.workflow_name);
+            assert_eq!(rerun_job.workflow_source, source_job.workflow_source);
+            assert_eq!(rerun_job.params, source_params);
+        }
     }
 
     #[tokio::test]
-    async fn test_put_config_endpoint() {
+    async fn test_rerun_completed_job_with_param_overrides() {
         let state = test_state();
-        let config_path = state.inner.config_path.clone();
-        let mut app = app_router(state);
+        let mut app = app_router(state.clone());
 
-        let updated = AppConfig {
-            paths: crate::config::PathsConfig {
-                models_dir: PathBuf::from("models_custom"),
-                trt_cache_dir: PathBuf::from("cache_custom"),
-                presets_dir: PathBuf::from("presets_custom"),
-                workflows_dir: PathBuf::from("workflows_custom"),
-            },
-            server: crate::config::ServerConfig {
-                port: 4321,
-                host: "127.0.0.1".to_string(),
-            },
-            locale: "zh-CN".to_string(),
-            performance: crate::config::PerformanceConfig {
-                profiling_enabled: true,
-            },
-        };
+        let source_id = format!("rerun-completed-source-{}", Uuid::new_v4());
+        let source_job = build_test_job(
+            source_id.clone(),
+            JobStatus::Completed,
+            Some(HashMap::from([
+                ("input".to_string(), serde_json::json!("/tmp/source.mkv")),
+                ("model".to_string(), serde_json::json!("soft")),
+            ])),
+        );
+        insert_test_job(&state, source_job);
 
-        let req = Request::builder()
-            .method("PUT")
-            .uri("/api/config")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&updated).unwrap()))
-            .unwrap();
+        let (status, json) = post_json(
+            &mut app,
+            &format!("/api/jobs/{source_id}/rerun"),
+            None,
+            serde_json::json!({ "params": { "model": "sharp" } }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let rerun_id = json["id"].as_str().expect("rerun id should exist");
 
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::OK);
+        let rerun_job = state
+            .inner
+            .jobs
+            .get(rerun_id)
+            .expect("rerun job should exist in state");
+        assert_eq!(
+            rerun_job.rerun_of_job_id.as_deref(),
+            Some(source_id.as_str())
+        );
+        assert_eq!(
+            rerun_job.params,
+            Some(HashMap::from([
+                ("input".to_string(), serde_json::json!("/tmp/source.mkv")),
+                ("model".to_string(), serde_json::json!("sharp")),
+            ]))
+        );
 
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let returned: AppConfig = serde_json::from_slice(&body).unwrap();
-        assert_eq!(returned, updated);
+        let source_after = state
+            .inner
+            .jobs
+            .get(&source_id)
+            .expect("source job should remain present");
+        assert_eq!(source_after.status, JobStatus::Completed);
+        assert_eq!(
+            source_after.params.as_ref().unwrap()["model"],
+            serde_json::json!("soft")
+        );
 
         let req = Request::builder()
-            .uri("/api/config")
-            .body(Body::empty())
+            .method("POST")
+            .uri(format!("/api/jobs/{source_id}/rerun"))
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"unknown": true}"#))
             .unwrap();
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::OK);
-
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let reloaded: AppConfig = serde_json::from_slice(&body).unwrap();
-        assert_eq!(reloaded, updated);
-
-        assert!(config_path.exists());
-        let _ = std::fs::remove_file(config_path);
+        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
     }
 
     #[tokio::test]
-    async fn test_create_job_valid() {
-        let mut app = test_router();
-        let body = serde_json::json!({
-            "workflow": valid_workflow_json()
-        });
+    async fn test_rerun_requires_overrides_for_redacted_params() {
+        let state = test_state();
+        let mut app = app_router(state.clone());
 
-        let req = Request::builder()
-            .method("POST")
-            .uri("/api/jobs")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
-            .unwrap();
+        let source_id = format!("rerun-redacted-source-{}", Uuid::new_v4());
+        insert_test_job(
+            &state,
+            build_test_job(
+                source_id.clone(),
+                JobStatus::Completed,
+                Some(HashMap::from([
+                    ("input".to_string(), serde_json::json!("/tmp/source.mkv")),
+                    (
+                        "api_key".to_string(),
+                        serde_json::json!(crate::logging::REDACTION_PLACEHOLDER),
+                    ),
+                ])),
+            ),
+        );
 
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::CREATED);
+        let (status, json) = post_json(
+            &mut app,
+            &format!("/api/jobs/{source_id}/rerun"),
+            None,
+            serde_json::json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT, "{json}");
+        assert!(
+            json["error"].as_str().unwrap().contains("api_key"),
+            "{json}"
+        );
 
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        assert!(json["id"].is_string());
-        assert_eq!(json["status"], "queued");
+        let (status, json) = post_json(
+            &mut app,
+            &format!("/api/jobs/{source_id}/rerun"),
+            None,
+            serde_json::json!({ "params": { "api_key": "secret" } }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{json}");
+        let rerun_id = json["id"].as_str().expect("rerun id should exist");
+        assert_eq!(
+            state
+                .inner
+                .jobs
+                .get(rerun_id)
+                .unwrap()
+                .params
+                .as_ref()
+                .unwrap()["api_key"],
+            serde_json::json!("secret")
+        );
     }
 
-    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-    async fn test_create_job_video_pipeline() {
-        let mut node_registry = NodeRegistry::new();
-        register_all_nodes(&mut node_registry);
-        let model_registry = ModelRegistry::with_builtin_models(test_models_dir());
-        let state = AppState::new(
-            node_registry,
-            model_registry,
-            DashMap::new(),
-            AppConfig::default(),
-            test_config_path(),
-            test_data_dir(),
-        );
+    #[tokio::test]
+    async fn test_rerun_preserves_source_row_immutability() {
+        let data_dir = test_data_dir();
+        let state = test_state_with_data_dir(data_dir.clone());
         let mut app = app_router(state.clone());
 
-        let workflow = serde_json::json!({
-            "nodes": [
-                {"id": "input", "node_type": "VideoInput", "params": {
-                    "path": temp_path_str("nonexistent-video-videnoa-test.mkv")
-                }},
-                {"id": "output", "node_type": "VideoOutput", "params": {}}
-            ],
-            "connections": [
-                {
-                    "from_node": "input",
-                    "from_port": "source_path",
-                    "to_node": "output",
-                    "to_port": "source_path",
-                    "port_type": "Path"
+        let source_id = format!("rerun-row-source-{}", Uuid::new_v4());
+        let mut source_job = build_test_job(
+            source_id.clone(),
+            JobStatus::Failed,
+            Some(HashMap::from([(
+                "input".to_string(),
+                serde_json::json!("/tmp/source.mkv"),
+            )])),
+        );
+        source_job.rerun_of_job_id = Some("older-ancestor-id".to_string());
+        insert_test_job(&state, source_job.clone());
+
+        let conn = Connection::open(data_dir.join("jobs.db")).expect("open jobs db");
+        let source_row_before: (
+            String,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+            String,
+            String,
+        ) = conn
+            .query_row(
+                "SELECT status, error, params_json, rerun_of_job_id, workflow_name, workflow_source
+                 FROM jobs
+                 WHERE id = ?1",
+                rusqlite::params![source_id],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                        row.get(5)?,
+                    ))
                 },
-                {
-                    "from_node": "input",
-                    "from_port": "frames",
-                    "to_node": "output",
-                    "to_port": "frames",
-                    "port_type": "VideoFrames"
-                }
-            ]
-        });
-        let body = serde_json::json!({ "workflow": workflow });
+            )
+            .expect("query source row before rerun");
 
         let req = Request::builder()
             .method("POST")
-            .uri("/api/jobs")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .uri(format!("/api/jobs/{source_id}/rerun"))
+            .body(Body::empty())
             .unwrap();
-
         let resp = send_request(&mut app, req).await;
         assert_eq!(resp.status(), StatusCode::CREATED);
 
-        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let create_json: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
-        let job_id = create_json["id"].as_str().unwrap().to_string();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let rerun_id = json["id"]
+            .as_str()
+            .expect("rerun id should exist")
+            .to_string();
+
+        let source_row_after: (
+            String,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+            String,
+            String,
+        ) = conn
+            .query_row(
+                "SELECT status, error, params_json, rerun_of_job_id, workflow_name, workflow_source
+                 FROM jobs
+                 WHERE id = ?1",
+                rusqlite::params![source_id],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                        row.get(5)?,
+                    ))
+                },
+            )
+            .expect("query source row after rerun");
+        assert_eq!(source_row_before, source_row_after);
+
+        let rerun_row_rerun_of: Option<String> = conn
+            .query_row(
+                "SELECT rerun_of_job_id FROM jobs WHERE id = ?1",
+                rusqlite::params![rerun_id],
+                |row| row.get(0),
+            )
+            .expect("query rerun row linkage");
+        assert_eq!(rerun_row_rerun_of.as_deref(), Some(source_id.as_str()));
 
-        let status = wait_for_job_terminal_status(&state, &job_id).await;
-        let job = state.inner.jobs.get(&job_id).unwrap();
-        assert_eq!(status, JobStatus::Failed);
-        let err_msg = job.error.as_deref().unwrap_or("");
-        assert!(
-            !err_msg.contains("CompileContext"),
-            "should not fail due to missing CompileContext, got: {err_msg}"
+        let source_after = state
+            .inner
+            .jobs
+            .get(&source_id)
+            .expect("source job should remain in state");
+        assert_eq!(source_after.status, JobStatus::Failed);
+        assert_eq!(
+            source_after.rerun_of_job_id.as_deref(),
+            Some("older-ancestor-id")
         );
     }
 
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-    async fn test_create_job_with_params() {
-        let mut node_registry = NodeRegistry::new();
-        register_all_nodes(&mut node_registry);
-        let model_registry = ModelRegistry::with_builtin_models(test_models_dir());
-        let state = AppState::new(
-            node_registry,
-            model_registry,
-            DashMap::new(),
-            AppConfig::default(),
-            test_config_path(),
-            test_data_dir(),
-        );
+    async fn test_job_lifecycle_is_persisted_to_data_dir_jobs_db() {
+        let data_dir = test_data_dir();
+        let state = test_state_with_data_dir(data_dir.clone());
         let mut app = app_router(state.clone());
 
-        let workflow = workflow_input_output_json();
+        let mut workflow = delay_workflow_json(350);
+        workflow
+            .as_object_mut()
+            .expect("workflow should be object")
+            .insert(
+                "name".to_string(),
+                serde_json::Value::String("Persisted Delay Workflow".to_string()),
+            );
+
         let body = serde_json::json!({
             "workflow": workflow,
-            "params": {"greeting": "hello world"}
+            "params": {
+                "seed": 7
+            }
         });
 
         let req = Request::builder()
@@ -3198,952 +11144,1296 @@ mod tests {
         let create_json: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
         let job_id = create_json["id"].as_str().unwrap().to_string();
 
-        let status = wait_for_job_terminal_status(&state, &job_id).await;
+        assert!(
+            data_dir.join("jobs.db").exists(),
+            "expected jobs.db at {}",
+            data_dir.join("jobs.db").display()
+        );
 
-        let job = state.inner.jobs.get(&job_id).unwrap();
-        assert_eq!(
-            status,
-            JobStatus::Completed,
-            "expected Completed, got {:?}, error: {:?}",
-            job.status,
-            job.error
+        assert!(
+            wait_for_persisted_status(&data_dir, &job_id, "running").await,
+            "expected running transition to be persisted"
         );
-    }
 
-    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-    async fn test_create_job_infers_workflow_input_params_when_top_level_params_missing() {
-        let mut node_registry = NodeRegistry::new();
-        register_all_nodes(&mut node_registry);
-        let model_registry = ModelRegistry::with_builtin_models(test_models_dir());
-        let state = AppState::new(
-            node_registry,
-            model_registry,
-            DashMap::new(),
-            AppConfig::default(),
-            test_config_path(),
-            test_data_dir(),
+        let terminal = wait_for_job_terminal_status(&state, &job_id).await;
+        assert_eq!(terminal, JobStatus::Completed);
+
+        assert!(
+            wait_for_persisted_status(&data_dir, &job_id, "completed").await,
+            "expected completed transition to be persisted"
         );
-        let mut app = app_router(state.clone());
 
-        let body = serde_json::json!({
-            "workflow": {
-                "nodes": [
-                    {"id": "wi", "node_type": "WorkflowInput", "params": {
-                        "ports": [{"name": "greeting", "port_type": "Str"}],
-                        "greeting": "hello from interface"
-                    }},
-                    {"id": "wo", "node_type": "WorkflowOutput", "params": {
-                        "ports": [{"name": "greeting", "port_type": "Str"}]
-                    }}
-                ],
-                "connections": [
-                    {
-                        "from_node": "wi",
-                        "from_port": "greeting",
-                        "to_node": "wo",
-                        "to_port": "greeting",
-                        "port_type": "Str"
-                    }
-                ],
-                "interface": {
-                    "inputs": [{"name": "greeting", "port_type": "Str"}],
-                    "outputs": [{"name": "greeting", "port_type": "Str"}]
-                }
-            }
-        });
+        let conn = Connection::open(data_dir.join("jobs.db")).expect("open jobs db");
+        let (workflow_name, workflow_source, params_json, rerun_of_job_id, started_at, completed_at): (
+            String,
+            String,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+            Option<String>,
+        ) = conn
+            .query_row(
+                "SELECT workflow_name, workflow_source, params_json, rerun_of_job_id, started_at, completed_at
+                 FROM jobs
+                 WHERE id = ?1",
+                rusqlite::params![job_id],
+                |row| {
+                    Ok((
+                        row.get(0)?,
+                        row.get(1)?,
+                        row.get(2)?,
+                        row.get(3)?,
+                        row.get(4)?,
+                        row.get(5)?,
+                    ))
+                },
+            )
+            .expect("query persisted metadata");
 
-        let req = Request::builder()
-            .method("POST")
-            .uri("/api/jobs")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
-            .unwrap();
+        assert_eq!(workflow_name, "Persisted Delay Workflow");
+        assert_eq!(workflow_source, WORKFLOW_SOURCE_API_JOBS);
+        assert!(rerun_of_job_id.is_none());
+        assert!(started_at.is_some());
+        assert!(completed_at.is_some());
 
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::CREATED);
+        let params_json = params_json.expect("params_json should be persisted");
+        let params_value: serde_json::Value =
+            serde_json::from_str(&params_json).expect("params_json should be valid JSON");
+        assert_eq!(params_value["seed"], 7);
+    }
 
-        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
+    #[test]
+    fn test_jobs_db_migrates_unversioned_schema() {
+        let data_dir = test_data_dir();
+        std::fs::create_dir_all(&data_dir).unwrap();
+        {
+            // The jobs table as the first releases created it.
+            let conn = Connection::open(data_dir.join("jobs.db")).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE jobs (
+                    id TEXT PRIMARY KEY,
+                    status TEXT NOT NULL,
+                    workflow_json TEXT NOT NULL,
+                    created_at TEXT NOT NULL,
+                    started_at TEXT,
+                    completed_at TEXT,
+                    progress_json TEXT,
+                    error TEXT,
+                    params_json TEXT,
+                    workflow_name TEXT NOT NULL,
+                    workflow_source TEXT NOT NULL,
+                    rerun_of_job_id TEXT,
+                    updated_at TEXT NOT NULL
+                 );",
+            )
             .unwrap();
-        let create_json: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
-        let job_id = create_json["id"].as_str().unwrap().to_string();
+            conn.execute(
+                "INSERT INTO jobs (id, status, workflow_json, created_at, workflow_name, workflow_source, updated_at)
+                 VALUES ('legacy-job', 'completed', ?1, '2025-01-01T00:00:00Z', 'Legacy', ?2, '2025-01-01T00:00:00Z')",
+                rusqlite::params![valid_workflow_json().to_string(), WORKFLOW_SOURCE_API_JOBS],
+            )
+            .unwrap();
+        }
 
+        let state = test_state_with_data_dir(data_dir.clone());
         let job = state
             .inner
             .jobs
-            .get(&job_id)
-            .expect("job should remain available");
-
-        let params = job
-            .params
-            .as_ref()
-            .expect("params should be inferred from WorkflowInput node params");
+            .get("legacy-job")
+            .expect("legacy job should survive the upgrade");
+        assert_eq!(job.status, JobStatus::Completed);
+        assert!(job.tags.is_empty());
+        assert!(job.paused_at.is_none());
+        drop(job);
+
+        let conn = Connection::open(data_dir.join("jobs.db")).unwrap();
         assert_eq!(
-            params.get("greeting"),
-            Some(&serde_json::json!("hello from interface"))
+            persistence::schema_version(&conn).unwrap(),
+            persistence::SCHEMA_VERSION
         );
-    }
 
-    #[tokio::test]
-    async fn test_create_job_prefers_explicit_workflow_name_over_workflow_payload_name() {
-        let state = test_state();
-        let mut app = app_router(state.clone());
+        // A db written by a newer release still loads.
+        conn.execute(
+            "UPDATE schema_version SET version = ?1",
+            [persistence::SCHEMA_VERSION + 1],
+        )
+        .unwrap();
+        drop(conn);
+        let newer = test_state_with_data_dir(data_dir.clone());
+        assert!(newer.inner.jobs.contains_key("legacy-job"));
+        let _ = std::fs::remove_dir_all(data_dir);
+    }
 
-        let body = serde_json::json!({
-            "workflow_name": "Named from request",
-            "workflow": {
-                "name": "Name inside workflow JSON",
-                "nodes": [
-                    {"id": "src", "node_type": "test_source", "params": {}},
-                    {"id": "dst", "node_type": "test_sink", "params": {}}
-                ],
-                "connections": [
-                    {
-                        "from_node": "src",
-                        "from_port": "output",
-                        "to_node": "dst",
-                        "to_port": "input",
-                        "port_type": "VideoFrames"
-                    }
-                ]
-            }
-        });
+    #[test]
+    fn test_startup_restore_reconciles_running_job_to_cancelled() {
+        let data_dir = test_data_dir();
+        let initial_state = test_state_with_data_dir(data_dir.clone());
 
-        let req = Request::builder()
-            .method("POST")
-            .uri("/api/jobs")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
-            .unwrap();
+        let workflow: PipelineGraph =
+            serde_json::from_value(valid_workflow_json()).expect("workflow should deserialize");
+        let created_at = Utc::now() - chrono::Duration::minutes(2);
+        let started_at = Some(created_at + chrono::Duration::seconds(5));
+        let job_id = format!("restore-{}", Uuid::new_v4());
 
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::CREATED);
+        let stale_running_job = Job {
+            id: job_id.clone(),
+            status: JobStatus::Running,
+            workflow,
+            created_at,
+            started_at,
+            completed_at: None,
+            progress: Some(ProgressUpdate {
+                current_frame: 42,
+                total_frames: Some(300),
+                fps: 12.0,
+                eta_seconds: Some(21.5),
+                phase: Some(JobPhase::Processing),
+                stages: Vec::new(),
+                hardware: None,
+            }),
+            error: Some("executor interrupted before shutdown".to_string()),
+            error_details: Some(NodeError {
+                node_id: Some("upscale".to_string()),
+                node_type: Some("SuperResolution".to_string()),
+                category: crate::node_error::ErrorCategory::Model,
+                message: "failed to load model".to_string(),
+                hint: None,
+            }),
+            cancel_token: CancellationToken::new(),
+            params: Some(HashMap::from([(
+                "input".to_string(),
+                serde_json::Value::String("/tmp/input.mkv".to_string()),
+            )])),
+            workflow_name: "Restore Candidate".to_string(),
+            workflow_source: WORKFLOW_SOURCE_API_JOBS.to_string(),
+            rerun_of_job_id: Some("older-job-id".to_string()),
+            outputs: None,
+            tags: vec!["season-1".to_string()],
+            note: Some("rerun with sharper model".to_string()),
+            post_action_results: vec![PostActionResult {
+                action: "move".to_string(),
+                path: PathBuf::from("/out/ep01.mkv"),
+                result_path: Some(PathBuf::from("/library/ep01.mkv")),
+                error: None,
+            }],
+            skip_reason: None,
+            paused_at: None,
+            artifacts: vec![JobArtifact {
+                node_id: "output".to_string(),
+                path: PathBuf::from("/out/ep01.mkv"),
+                size_bytes: Some(734_003_200),
+                duration_secs: Some(1420.5),
+                bit_rate: Some(4_133_000),
+                video_codec: Some("hevc".to_string()),
+                width: Some(3840),
+                height: Some(2160),
+                vmaf: Some(95.2),
+                comparison_frames: Vec::new(),
+            }],
+            step_gate: None,
+        };
 
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        let job_id = json["id"]
-            .as_str()
-            .expect("job id should be present")
-            .to_string();
+        initial_state
+            .persist_job_snapshot(&stale_running_job)
+            .expect("persist running snapshot");
 
-        let job = state
+        let restored_state = test_state_with_data_dir(data_dir.clone());
+        let restored_job = restored_state
             .inner
             .jobs
             .get(&job_id)
-            .expect("job should exist in memory");
-        assert_eq!(job.workflow_name, "Named from request");
-    }
+            .expect("job should be restored from persistence");
 
-    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-    async fn test_create_job_jellyfin_video_manual_node_params_execute_successfully() {
-        let mut node_registry = NodeRegistry::new();
-        register_all_nodes(&mut node_registry);
-        let model_registry = ModelRegistry::with_builtin_models(test_models_dir());
-        let state = AppState::new(
-            node_registry,
-            model_registry,
-            DashMap::new(),
-            AppConfig::default(),
-            test_config_path(),
-            test_data_dir(),
+        assert_eq!(restored_job.status, JobStatus::Cancelled);
+        assert!(restored_job.completed_at.is_some());
+        assert!(restored_job.error.is_some());
+        assert!(restored_job
+            .error
+            .as_deref()
+            .unwrap_or_default()
+            .contains("transitioned to 'cancelled' for retry safety"));
+        assert_eq!(restored_job.workflow_name, "Restore Candidate");
+        assert_eq!(restored_job.workflow_source, WORKFLOW_SOURCE_API_JOBS);
+        assert_eq!(
+            restored_job.rerun_of_job_id.as_deref(),
+            Some("older-job-id")
         );
-        let mut app = app_router(state.clone());
+        assert_eq!(
+            restored_job.post_action_results,
+            stale_running_job.post_action_results
+        );
+        assert_eq!(restored_job.artifacts, stale_running_job.artifacts);
+        assert_eq!(restored_job.error_details, stale_running_job.error_details);
+        let encode_stats = job_to_response(&restored_job).encode_stats.unwrap();
+        assert_eq!(encode_stats.output_bytes, Some(734_003_200));
+        assert_eq!(encode_stats.vmaf, Some(95.2));
 
-        let workflow = serde_json::json!({
-            "nodes": [
-                {"id": "jelly", "node_type": "JellyfinVideo", "params": {
-                    "jellyfin_url": "http://localhost:8096",
-                    "api_key": "test-api-key",
-                    "item_id": "episode-01"
-                }},
-                {"id": "wo", "node_type": "WorkflowOutput", "params": {
-                    "ports": [{"name": "video_url", "port_type": "Str"}]
-                }}
-            ],
-            "connections": [
-                {
-                    "from_node": "jelly",
-                    "from_port": "video_url",
-                    "to_node": "wo",
-                    "to_port": "video_url",
-                    "port_type": "Str"
-                }
-            ],
-            "interface": {
-                "inputs": [],
-                "outputs": [{"name": "video_url", "port_type": "Str"}]
-            }
+        let conn = Connection::open(data_dir.join("jobs.db")).expect("open jobs db");
+        let (status, completed_at_raw, error_raw): (String, Option<String>, Option<String>) = conn
+            .query_row(
+                "SELECT status, completed_at, error FROM jobs WHERE id = ?1",
+                rusqlite::params![job_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .expect("query reconciled row");
+
+        assert_eq!(status, "cancelled");
+        assert!(completed_at_raw.is_some());
+        assert!(error_raw
+            .as_deref()
+            .unwrap_or_default()
+            .contains("transitioned to 'cancelled' for retry safety"));
+    }
+
+    #[tokio::test]
+    async fn test_job_ws_serializes_progress_and_debug_events() {
+        let progress_event = JobWsEvent::from(ProgressUpdate {
+            current_frame: 12,
+            total_frames: Some(240),
+            fps: 23.5,
+            eta_seconds: Some(9.7),
+            phase: Some(JobPhase::Processing),
+            stages: vec![StageProgress {
+                name: "decode".to_string(),
+                frames: 14,
+                queued: 0,
+                queue_capacity: 0,
+            }],
+            hardware: None,
         });
+        let progress_json = serde_json::to_value(&progress_event).unwrap();
+        assert_eq!(progress_json["type"], "progress");
+        assert_eq!(progress_json["current_frame"], 12);
+        assert_eq!(progress_json["total_frames"], 240);
+        assert_eq!(progress_json["fps"], 23.5);
+        assert_eq!(progress_json["eta_seconds"], 9.7);
+        assert_eq!(progress_json["phase"], "processing");
+        assert_eq!(progress_json["stages"][0]["name"], "decode");
+        assert!(progress_json.get("node_id").is_none());
 
-        let body = serde_json::json!({ "workflow": workflow });
-        let req = Request::builder()
-            .method("POST")
-            .uri("/api/jobs")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
-            .unwrap();
+        let parsed_progress: JobWsEvent = serde_json::from_value(progress_json).unwrap();
+        assert_eq!(parsed_progress, progress_event);
 
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::CREATED);
+        let phase_event = JobWsEvent::Phase {
+            phase: JobPhase::BuildingEngine,
+        };
+        let phase_json = serde_json::to_value(&phase_event).unwrap();
+        assert_eq!(
+            phase_json,
+            serde_json::json!({"type": "phase", "phase": "building_engine"})
+        );
 
-        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let create_json: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
-        let job_id = create_json["id"]
-            .as_str()
-            .expect("job id should be present")
-            .to_string();
+        let debug_event = JobWsEvent::from(NodeDebugValueEvent {
+            node_id: "print_1".to_string(),
+            node_type: "Print".to_string(),
+            value_preview: "hello".to_string(),
+            truncated: false,
+            preview_max_chars: 512,
+        });
+        let debug_json = serde_json::to_value(&debug_event).unwrap();
+        assert_eq!(debug_json["type"], "node_debug_value");
+        assert_eq!(debug_json["node_id"], "print_1");
+        assert_eq!(debug_json["node_type"], "Print");
+        assert_eq!(debug_json["value_preview"], "hello");
+        assert_eq!(debug_json["truncated"], false);
+        assert_eq!(debug_json["preview_max_chars"], 512);
+        assert!(debug_json.get("current_frame").is_none());
 
-        let status = wait_for_job_terminal_status(&state, &job_id).await;
-        let job = state
-            .inner
-            .jobs
-            .get(&job_id)
-            .expect("job should remain queryable in state");
+        let parsed_debug: JobWsEvent = serde_json::from_value(debug_json).unwrap();
+        assert_eq!(parsed_debug, debug_event);
+    }
 
-        assert_eq!(
-            status,
-            JobStatus::Completed,
-            "expected Completed, got {:?}, error: {:?}",
-            job.status,
-            job.error
+    #[test]
+    fn test_print_preview_throttle_per_node() {
+        let window = std::time::Duration::from_millis(PRINT_PREVIEW_THROTTLE_MS);
+        let start = std::time::Instant::now();
+
+        let mut job_a_throttle = NodeDebugEventThrottle::new(window);
+        assert!(job_a_throttle.should_emit("node-a", start));
+        assert!(
+            !job_a_throttle.should_emit("node-a", start + std::time::Duration::from_millis(149))
         );
+        assert!(job_a_throttle.should_emit("node-b", start + std::time::Duration::from_millis(149)));
+        assert!(job_a_throttle.should_emit("node-a", start + std::time::Duration::from_millis(150)));
         assert!(
-            job.error.is_none(),
-            "manual node params path should not fail JellyfinVideo Str input resolution"
+            !job_a_throttle.should_emit("node-b", start + std::time::Duration::from_millis(298))
         );
+        assert!(job_a_throttle.should_emit("node-b", start + std::time::Duration::from_millis(299)));
+
+        let mut job_b_throttle = NodeDebugEventThrottle::new(window);
+        assert!(job_b_throttle.should_emit("node-a", start + std::time::Duration::from_millis(1)));
     }
 
-    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-    async fn test_create_job_constant_str_param_validates_and_executes_across_boundary() {
-        let mut node_registry = NodeRegistry::new();
-        register_all_nodes(&mut node_registry);
-        let model_registry = ModelRegistry::with_builtin_models(test_models_dir());
-        let state = AppState::new(
-            node_registry,
-            model_registry,
-            DashMap::new(),
-            AppConfig::default(),
-            test_config_path(),
-            test_data_dir(),
-        );
+    #[test]
+    fn test_job_debug_sink_forwards_node_events() {
+        let (tx, mut rx) = broadcast::channel(4);
+        let ctx = crate::node::ExecutionContext {
+            node_id: "download".to_string(),
+            debug_sink: Some(job_debug_sink(Some(tx))),
+            ..Default::default()
+        };
+        ctx.emit_debug("Downloader", "downloaded 5 of 10 bytes (50%)");
+
+        let JobWsEvent::NodeDebugValue {
+            node_id,
+            value_preview,
+            ..
+        } = rx.try_recv().unwrap()
+        else {
+            panic!("expected a node debug event");
+        };
+        assert_eq!(node_id, "download");
+        assert_eq!(value_preview, "downloaded 5 of 10 bytes (50%)");
+    }
+
+    #[test]
+    fn test_progress_history_sampler_respects_interval() {
+        let interval = std::time::Duration::from_millis(PROGRESS_HISTORY_SAMPLE_INTERVAL_MS);
+        let start = std::time::Instant::now();
+
+        let mut sampler = ProgressHistorySampler::new(interval);
+        assert!(sampler.should_sample(start));
+        assert!(!sampler.should_sample(start + interval - std::time::Duration::from_millis(1)));
+        assert!(sampler.should_sample(start + interval));
+        assert!(!sampler.should_sample(start + interval + std::time::Duration::from_millis(10)));
+    }
+
+    #[tokio::test]
+    async fn test_job_progress_history_replays_persisted_points_in_order() {
+        let data_dir = test_data_dir();
+        let state = test_state_with_data_dir(data_dir);
         let mut app = app_router(state.clone());
 
-        let workflow = serde_json::json!({
-            "nodes": [
-                {"id": "constant", "node_type": "Constant", "params": {
-                    "type": "Str",
-                    "value": "hello-from-constant"
-                }},
-                {"id": "wo", "node_type": "WorkflowOutput", "params": {
-                    "ports": [{"name": "value", "port_type": "Str"}]
-                }}
-            ],
-            "connections": [
-                {
-                    "from_node": "constant",
-                    "from_port": "value",
-                    "to_node": "wo",
-                    "to_port": "value",
-                    "port_type": "Str"
-                }
-            ],
-            "interface": {
-                "inputs": [],
-                "outputs": [{"name": "value", "port_type": "Str"}]
-            }
-        });
+        let job_id = format!("progress-history-{}", Uuid::new_v4());
+        insert_test_job(
+            &state,
+            build_test_job(job_id.clone(), JobStatus::Running, None),
+        );
+
+        let persistence = state
+            .inner
+            .jobs_persistence
+            .as_ref()
+            .expect("test state should have persistence");
+        let base = Utc::now();
+        for (offset_secs, frame) in [(0, 10_u64), (2, 60), (4, 110)] {
+            let point = ProgressHistoryPoint {
+                recorded_at: base + chrono::Duration::seconds(offset_secs),
+                current_frame: frame,
+                total_frames: Some(200),
+                fps: 25.0,
+                eta_seconds: Some(4.0),
+                hardware: (frame == 60).then_some(HardwareUsage {
+                    cpu_util_percent: Some(35.0),
+                    gpu_util_percent: Some(97.5),
+                    vram_used_bytes: None,
+                }),
+            };
+            persistence
+                .append_progress_point(&job_id, &point)
+                .expect("append progress point");
+        }
 
-        let body = serde_json::json!({ "workflow": workflow });
         let req = Request::builder()
-            .method("POST")
-            .uri("/api/jobs")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .uri(format!("/api/jobs/{job_id}/progress-history"))
+            .body(Body::empty())
             .unwrap();
-
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.status(), StatusCode::OK);
 
-        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let create_json: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
-        let job_id = create_json["id"]
-            .as_str()
-            .expect("job id should be present")
-            .to_string();
-
-        let status = wait_for_job_terminal_status(&state, &job_id).await;
-        let job = state
-            .inner
-            .jobs
-            .get(&job_id)
-            .expect("job should remain queryable in state");
-
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["job_id"], job_id);
         assert_eq!(
-            status,
-            JobStatus::Completed,
-            "expected Completed, got {:?}, error: {:?}",
-            job.status,
-            job.error
+            json["sample_interval_ms"],
+            serde_json::json!(PROGRESS_HISTORY_SAMPLE_INTERVAL_MS)
         );
-        assert!(
-            job.error.is_none(),
-            "Constant type=Str should validate and execute without Int/Str boundary mismatch"
+        let frames: Vec<u64> = json["points"]
+            .as_array()
+            .expect("points array")
+            .iter()
+            .map(|point| point["current_frame"].as_u64().unwrap())
+            .collect();
+        assert_eq!(frames, vec![10, 60, 110]);
+        assert!(json["points"][0].get("hardware").is_none());
+        assert_eq!(
+            json["points"][1]["hardware"],
+            serde_json::json!({
+                "cpu_util_percent": 35.0,
+                "gpu_util_percent": 97.5,
+                "vram_used_bytes": null,
+            })
         );
+
+        let req = Request::builder()
+            .method("DELETE")
+            .uri(format!("/api/jobs/{job_id}"))
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        assert!(persistence
+            .load_progress_history(&job_id)
+            .expect("load progress history")
+            .is_empty());
     }
 
     #[tokio::test]
-    async fn test_create_job_invalid_workflow() {
+    async fn test_job_progress_history_unknown_job_returns_not_found() {
         let mut app = test_router();
-        let body = serde_json::json!({
-            "workflow": {"invalid": true}
-        });
-
         let req = Request::builder()
-            .method("POST")
-            .uri("/api/jobs")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .uri("/api/jobs/missing-job/progress-history")
+            .body(Body::empty())
             .unwrap();
-
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
 
-    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-    async fn test_run_workflow_name_creates_single_job_and_persists_metadata() {
+    #[tokio::test]
+    async fn test_job_logs_reads_live_capture_then_persisted_file_tail() {
         let data_dir = test_data_dir();
         let state = test_state_with_data_dir(data_dir.clone());
+        let job_id = Uuid::new_v4().to_string();
+        insert_test_job(
+            &state,
+            build_test_job(job_id.clone(), JobStatus::Completed, None),
+        );
+
+        let log_path = job_logs::job_log_file_path(&data_dir, &job_id);
+        let capture = job_logs::job_log_hub().begin_capture(&job_id, Some(&log_path));
+        for index in 0..3 {
+            job_logs::job_log_hub().append(
+                &job_id,
+                JobLogLine {
+                    timestamp: Utc::now(),
+                    level: "INFO".to_string(),
+                    target: "ffmpeg_stderr".to_string(),
+                    message: format!("frame={index}"),
+                },
+            );
+        }
+
         let mut app = app_router(state.clone());
+        let req = Request::builder()
+            .uri(format!("/api/jobs/{job_id}/logs?tail=2"))
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let live: JobLogsResponse = serde_json::from_slice(&body).unwrap();
+        assert!(live.live);
+        let messages: Vec<&str> = live.lines.iter().map(|l| l.message.as_str()).collect();
+        assert_eq!(messages, vec!["frame=1", "frame=2"]);
 
-        let workflows_dir = unique_temp_dir("videnoa-run-workflows");
-        let presets_dir = unique_temp_dir("videnoa-run-presets");
-        std::fs::create_dir_all(&workflows_dir).expect("create workflows dir");
-        std::fs::create_dir_all(&presets_dir).expect("create presets dir");
-        set_workflow_lookup_dirs(&state, workflows_dir.clone(), presets_dir.clone()).await;
+        drop(capture);
 
-        let workflow_doc = serde_json::json!({
-            "name": "Inner Name Should Not Override",
-            "description": "Run API test",
-            "workflow": valid_workflow_json()
-        });
-        write_json_file(&workflows_dir.join("named-run.json"), &workflow_doc);
+        let req = Request::builder()
+            .uri(format!("/api/jobs/{job_id}/logs"))
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let persisted: JobLogsResponse = serde_json::from_slice(&body).unwrap();
+        assert!(!persisted.live);
+        assert_eq!(persisted.lines.len(), 3);
+        assert_eq!(persisted.lines[0].message, "frame=0");
 
-        let body = serde_json::json!({
-            "workflow_name": "named-run",
-            "params": {
-                "input": "/tmp/input-video.mkv",
-                "seed": 42
-            }
-        });
         let req = Request::builder()
-            .method("POST")
-            .uri("/api/run")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .method("DELETE")
+            .uri(format!("/api/jobs/{job_id}"))
+            .body(Body::empty())
             .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        assert!(!log_path.exists());
+    }
 
+    #[tokio::test]
+    async fn test_logs_list_and_tail_rotated_files() {
+        let data_dir = test_data_dir();
+        let log_dir = data_dir.join(crate::logging::DEFAULT_LOG_DIR_NAME);
+        std::fs::create_dir_all(&log_dir).unwrap();
+        std::fs::write(log_dir.join("videnoa.2026-01-01.log"), "one\ntwo\nthree\n").unwrap();
+        std::fs::write(log_dir.join("notes.txt"), "ignored").unwrap();
+        let mut app = app_router(test_state_with_data_dir(data_dir));
+
+        let req = Request::builder()
+            .uri("/api/logs")
+            .body(Body::empty())
+            .unwrap();
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let listing: LogListResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(listing.files.len(), 1);
+        assert_eq!(listing.files[0].name, "videnoa.2026-01-01.log");
+        assert_eq!(listing.total_size_bytes, 14);
 
+        let req = Request::builder()
+            .uri("/api/logs/videnoa.2026-01-01.log?tail=2")
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
         let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        let job_id = json["id"]
-            .as_str()
-            .expect("job id should be present")
-            .to_string();
-        assert_eq!(json["status"], "queued");
-        assert_eq!(
-            state.inner.jobs.len(),
-            1,
-            "run endpoint must create exactly one job"
-        );
+        let tail: LogTailResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(tail.lines, vec!["two", "three"]);
 
-        let job = state
-            .inner
-            .jobs
-            .get(&job_id)
-            .expect("job should remain available");
-        assert_eq!(job.workflow_name, "named-run");
-        assert_eq!(job.workflow_source, WORKFLOW_SOURCE_API_RUN_WORKFLOWS);
-        let params = job.params.as_ref().expect("params should be preserved");
-        assert_eq!(
-            params.get("input"),
-            Some(&serde_json::json!("/tmp/input-video.mkv"))
-        );
-        assert_eq!(params.get("seed"), Some(&serde_json::json!(42)));
+        let req = Request::builder()
+            .uri("/api/logs/notes.txt")
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
 
-        let conn = Connection::open(data_dir.join("jobs.db")).expect("open jobs db");
-        let (workflow_name, workflow_source, params_json): (String, String, Option<String>) = conn
-            .query_row(
-                "SELECT workflow_name, workflow_source, params_json FROM jobs WHERE id = ?1",
-                rusqlite::params![job_id],
-                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
-            )
-            .expect("query run job metadata");
+        let req = Request::builder()
+            .uri("/api/logs/videnoa.1999-01-01.log")
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
 
-        assert_eq!(workflow_name, "named-run");
-        assert_eq!(workflow_source, WORKFLOW_SOURCE_API_RUN_WORKFLOWS);
-        let params_value: serde_json::Value = serde_json::from_str(
-            &params_json.expect("params_json should be persisted for /api/run"),
-        )
-        .expect("params_json should deserialize");
-        assert_eq!(params_value["input"], "/tmp/input-video.mkv");
-        assert_eq!(params_value["seed"], 42);
+    #[tokio::test]
+    async fn test_config_update_enforces_log_size_limit() {
+        let data_dir = test_data_dir();
+        let log_dir = data_dir.join(crate::logging::DEFAULT_LOG_DIR_NAME);
+        std::fs::create_dir_all(&log_dir).unwrap();
+        for day in 1..=3 {
+            std::fs::write(
+                log_dir.join(format!("videnoa.2026-01-0{day}.log")),
+                vec![b'x'; 600 * 1024],
+            )
+            .unwrap();
+        }
+        let state = test_state_with_data_dir(data_dir);
+        let mut config = state.inner.config.read().await.clone();
+        config.logging.max_total_size_mb = 1;
 
-        let _ = std::fs::remove_dir_all(&workflows_dir);
-        let _ = std::fs::remove_dir_all(&presets_dir);
+        assert!(state.apply_config(config, ConfigChangeSource::Api).await);
+        assert!(!log_dir.join("videnoa.2026-01-01.log").exists());
+        assert!(!log_dir.join("videnoa.2026-01-02.log").exists());
+        assert!(log_dir.join("videnoa.2026-01-03.log").is_file());
     }
 
     #[tokio::test]
-    async fn test_run_workflow_name_rejects_json_suffix() {
-        let mut app = test_router();
-        let body = serde_json::json!({
-            "workflow_name": "named-run.json"
-        });
+    async fn test_crashes_list_panic_artifacts() {
+        let data_dir = test_data_dir();
+        let crash_dir = crate::logging::crash_dir_for(&data_dir);
+        std::fs::create_dir_all(&crash_dir).unwrap();
+        std::fs::write(crash_dir.join("panic-20260101-000000-000000.log"), "boom").unwrap();
+        std::fs::write(crash_dir.join("README"), "ignored").unwrap();
+        let mut app = app_router(test_state_with_data_dir(data_dir));
 
         let req = Request::builder()
-            .method("POST")
-            .uri("/api/run")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .uri("/api/crashes")
+            .body(Body::empty())
             .unwrap();
-
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
-
+        assert_eq!(resp.status(), StatusCode::OK);
         let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        assert_eq!(json["error"], "workflow_name must not include .json suffix");
+        let listing: CrashListResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(listing.files.len(), 1);
+        assert_eq!(listing.files[0].name, "panic-20260101-000000-000000.log");
     }
 
-    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-    async fn test_run_workflow_name_prefers_workflows_dir_over_presets_dir() {
-        let state = test_state();
-        let mut app = app_router(state.clone());
-
-        let workflows_dir = unique_temp_dir("videnoa-run-precedence-workflows");
-        let presets_dir = unique_temp_dir("videnoa-run-precedence-presets");
-        std::fs::create_dir_all(&workflows_dir).expect("create workflows dir");
-        std::fs::create_dir_all(&presets_dir).expect("create presets dir");
-        set_workflow_lookup_dirs(&state, workflows_dir.clone(), presets_dir.clone()).await;
-
-        write_json_file(
-            &workflows_dir.join("shared-name.json"),
-            &serde_json::json!({"workflow": valid_workflow_json()}),
-        );
-        write_json_file(
-            &presets_dir.join("shared-name.json"),
-            &serde_json::json!({"workflow": {"invalid": true}}),
+    #[tokio::test]
+    async fn test_support_bundle_contains_logs_config_and_failed_workflow() {
+        let data_dir = test_data_dir();
+        let log_dir = data_dir.join(crate::logging::DEFAULT_LOG_DIR_NAME);
+        std::fs::create_dir_all(&log_dir).unwrap();
+        std::fs::write(
+            log_dir.join("videnoa.2026-01-01.log"),
+            "request token=abc123 failed\n",
+        )
+        .unwrap();
+        let crash_dir = crate::logging::crash_dir_for(&data_dir);
+        std::fs::create_dir_all(&crash_dir).unwrap();
+        std::fs::write(crash_dir.join("panic-20260101-000000-000000.log"), "boom").unwrap();
+
+        let state = test_state_with_data_dir(data_dir);
+        let failed_id = Uuid::new_v4().to_string();
+        insert_test_job(
+            &state,
+            build_test_job(failed_id.clone(), JobStatus::Failed, None),
         );
+        let mut app = app_router(state);
 
         let req = Request::builder()
             .method("POST")
-            .uri("/api/run")
-            .header("content-type", "application/json")
-            .body(Body::from(
-                serde_json::to_vec(&serde_json::json!({"workflow_name": "shared-name"})).unwrap(),
-            ))
+            .uri("/api/support-bundle")
+            .body(Body::empty())
             .unwrap();
-
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::CREATED);
-
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()[axum::http::header::CONTENT_TYPE],
+            "application/zip"
+        );
         let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        let job_id = json["id"]
-            .as_str()
-            .expect("job id should be present")
-            .to_string();
 
-        let job = state
-            .inner
-            .jobs
-            .get(&job_id)
-            .expect("job should exist in memory");
-        assert_eq!(job.workflow_source, WORKFLOW_SOURCE_API_RUN_WORKFLOWS);
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(body.to_vec())).unwrap();
+        let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "config.toml",
+                "crash/panic-20260101-000000-000000.log",
+                "job/job.json",
+                "job/workflow.json",
+                "logs/videnoa.2026-01-01.log",
+                "system.json",
+            ]
+        );
 
-        let _ = std::fs::remove_dir_all(&workflows_dir);
-        let _ = std::fs::remove_dir_all(&presets_dir);
+        let mut log = String::new();
+        std::io::Read::read_to_string(
+            &mut archive.by_name("logs/videnoa.2026-01-01.log").unwrap(),
+            &mut log,
+        )
+        .unwrap();
+        assert!(!log.contains("abc123"));
+
+        let mut job_json = String::new();
+        std::io::Read::read_to_string(&mut archive.by_name("job/job.json").unwrap(), &mut job_json)
+            .unwrap();
+        assert!(job_json.contains(&failed_id));
     }
 
     #[tokio::test]
-    async fn test_run_workflow_name_rejects_missing_or_empty_workflow_name() {
+    async fn test_support_bundle_unknown_job_returns_not_found() {
         let mut app = test_router();
-
-        let missing_req = Request::builder()
-            .method("POST")
-            .uri("/api/run")
-            .header("content-type", "application/json")
-            .body(Body::from(
-                serde_json::to_vec(&serde_json::json!({})).unwrap(),
-            ))
-            .unwrap();
-        let missing_resp = send_request(&mut app, missing_req).await;
-        assert_eq!(missing_resp.status(), StatusCode::BAD_REQUEST);
-
-        let missing_body = axum::body::to_bytes(missing_resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let missing_json: serde_json::Value = serde_json::from_slice(&missing_body).unwrap();
-        assert_eq!(missing_json["error"], "workflow_name is required");
-
-        let empty_req = Request::builder()
+        let req = Request::builder()
             .method("POST")
-            .uri("/api/run")
+            .uri("/api/support-bundle")
             .header("content-type", "application/json")
-            .body(Body::from(
-                serde_json::to_vec(&serde_json::json!({"workflow_name": "   "})).unwrap(),
-            ))
-            .unwrap();
-        let empty_resp = send_request(&mut app, empty_req).await;
-        assert_eq!(empty_resp.status(), StatusCode::BAD_REQUEST);
-
-        let empty_body = axum::body::to_bytes(empty_resp.into_body(), usize::MAX)
-            .await
+            .body(Body::from(r#"{"job_id":"missing-job"}"#))
             .unwrap();
-        let empty_json: serde_json::Value = serde_json::from_slice(&empty_body).unwrap();
-        assert_eq!(empty_json["error"], "workflow_name is required");
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
 
     #[tokio::test]
-    async fn test_run_workflow_name_rejects_batch_file_paths_payload() {
-        let state = test_state();
+    async fn test_backup_restores_workflows_presets_and_jobs_db() {
+        let data_dir = test_data_dir();
+        let mut config = AppConfig::default();
+        config.paths.models_dir = data_dir.join("models");
+        config.paths.presets_dir = data_dir.join("presets");
+        config.paths.workflows_dir = data_dir.join("workflows");
+        let config_path = data_dir.join("config.toml");
+        config.save_to_path(&config_path).unwrap();
+        std::fs::create_dir_all(&config.paths.workflows_dir).unwrap();
+        std::fs::create_dir_all(&config.paths.presets_dir).unwrap();
+        let workflow_path = config.paths.workflows_dir.join("upscale.json");
+        std::fs::write(&workflow_path, valid_workflow_json().to_string()).unwrap();
+        std::fs::write(
+            config.paths.presets_dir.join("anime.json"),
+            serde_json::json!({"name": "Anime", "description": "", "workflow": {}}).to_string(),
+        )
+        .unwrap();
+
+        let state = app_state_with_config(config, config_path, data_dir.clone());
+        let job_id = Uuid::new_v4().to_string();
+        insert_test_job(
+            &state,
+            build_test_job(job_id.clone(), JobStatus::Completed, None),
+        );
         let mut app = app_router(state.clone());
 
         let req = Request::builder()
             .method("POST")
-            .uri("/api/run")
-            .header("content-type", "application/json")
-            .body(Body::from(
-                serde_json::to_vec(&serde_json::json!({
-                    "workflow_name": "shared-name",
-                    "file_paths": ["/tmp/a.mkv", "/tmp/b.mkv"]
-                }))
-                .unwrap(),
-            ))
+            .uri("/api/maintenance/backup")
+            .body(Body::empty())
             .unwrap();
-
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(resp.status(), StatusCode::OK);
         assert_eq!(
-            state.inner.jobs.len(),
-            0,
-            "batch payload must not create jobs"
+            resp.headers()[axum::http::header::CONTENT_TYPE],
+            "application/gzip"
+        );
+        let tarball = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let mut archive =
+            tar::Archive::new(flate2::read::GzDecoder::new(std::io::Cursor::new(&tarball)));
+        let mut names = archive
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+            .collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "config.toml",
+                "jobs.db",
+                "manifest.json",
+                "presets/anime.json",
+                "workflows/upscale.json",
+            ]
         );
-    }
 
-    async fn assert_legacy_node_rejected(node_id: &str, node_type: &str) {
-        let mut app = test_router();
-        let body = serde_json::json!({
-            "workflow": {
-                "nodes": [
-                    {
-                        "id": node_id,
-                        "node_type": node_type,
-                        "params": {}
-                    }
-                ],
-                "connections": []
-            }
-        });
+        // Lose a workflow and a job, then restore them.
+        std::fs::remove_file(&workflow_path).unwrap();
+        let persistence = state.inner.jobs_persistence.clone().unwrap();
+        persistence.delete_job(&job_id).unwrap();
 
         let req = Request::builder()
             .method("POST")
-            .uri("/api/jobs")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .uri("/api/maintenance/restore")
+            .body(Body::from(tarball))
             .unwrap();
-
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
-
-        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let err_json: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
-        let err = err_json["error"]
-            .as_str()
-            .expect("error payload should include message");
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["config_restored"], true);
+        assert_eq!(json["workflows"], serde_json::json!(["upscale.json"]));
+        assert_eq!(json["presets"], serde_json::json!(["anime.json"]));
+        assert_eq!(json["restart_required"], true);
+        assert!(workflow_path.is_file());
+        assert!(state.inner.presets.contains_key("anime"));
+
+        // The jobs db is swapped in at the next start.
+        let staged = data_dir.join(persistence::PENDING_RESTORE_FILE_NAME);
+        assert!(staged.is_file());
+        let reopened = JobsPersistence::new(&data_dir).unwrap();
+        assert!(!staged.exists());
+        assert!(reopened
+            .load_jobs_for_startup()
+            .unwrap()
+            .iter()
+            .any(|job| job.id == job_id));
 
-        assert!(
-            err.contains("workflow validation failed"),
-            "expected validation failure prefix, got: {err}"
-        );
-        assert!(
-            err.contains(&format!(
-                "failed to instantiate node '{node_id}' of type '{node_type}'"
-            )),
-            "expected node id + type in error, got: {err}"
-        );
-        assert!(
-            err.contains(&format!("unknown node type: {node_type}")),
-            "expected unknown node type detail, got: {err}"
-        );
+        let mut tar = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_size(2);
+        header.set_cksum();
+        tar.append_data(&mut header, "secrets.json", &b"{}"[..])
+            .unwrap();
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        std::io::Write::write_all(&mut gz, &tar.into_inner().unwrap()).unwrap();
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/maintenance/restore")
+            .body(Body::from(gz.finish().unwrap()))
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
     }
 
     #[tokio::test]
-    async fn test_create_job_rejects_legacy_stream_input_node_type() {
-        assert_legacy_node_rejected("legacy_stream", "StreamInput").await;
-    }
+    async fn test_update_check_uses_configured_channel() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("releases.json"),
+            serde_json::json!([
+                {"tag_name": "v999.0.0-beta.1", "prerelease": true},
+                {"tag_name": "v0.0.1"}
+            ])
+            .to_string(),
+        )
+        .unwrap();
+        let state = test_state();
+        state.inner.config.write().await.updates.releases_url =
+            url::Url::from_file_path(dir.path().join("releases.json"))
+                .unwrap()
+                .to_string();
+        let mut app = app_router(state);
 
-    #[tokio::test]
-    async fn test_create_job_rejects_legacy_jellyfin_input_node_type() {
-        assert_legacy_node_rejected("legacy_jellyfin", "JellyfinInput").await;
+        let (status, check) = get_json(&mut app, "/api/updates/check").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(check["channel"], "stable");
+        assert_eq!(check["current_version"], env!("CARGO_PKG_VERSION"));
+        assert_eq!(check["update_available"], false);
+        assert_eq!(check["latest"]["version"], "0.0.1");
+
+        let (status, check) = get_json(&mut app, "/api/updates/check?channel=beta").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(check["channel"], "beta");
+        assert_eq!(check["update_available"], true);
+        assert_eq!(check["latest"]["tag"], "v999.0.0-beta.1");
     }
 
     #[tokio::test]
-    async fn test_list_jobs_returns_created() {
-        let state = test_state();
-        let mut app = app_router(state.clone());
+    async fn test_secrets_endpoints_store_list_and_delete_without_exposing_values() {
+        let data_dir = test_data_dir();
+        let mut app = app_router(test_state_with_data_dir(data_dir.clone()));
 
-        let body = serde_json::json!({
-            "workflow": valid_workflow_json()
-        });
         let req = Request::builder()
             .method("POST")
-            .uri("/api/jobs")
+            .uri("/api/secrets")
             .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .body(Body::from(
+                r#"{"name":"jellyfin_key","value":"plaintext-value"}"#,
+            ))
             .unwrap();
-        let _ = send_request(&mut app, req).await;
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
 
         let req = Request::builder()
-            .uri("/api/jobs")
+            .uri("/api/secrets")
             .body(Body::empty())
             .unwrap();
         let resp = send_request(&mut app, req).await;
         assert_eq!(resp.status(), StatusCode::OK);
-
         let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let json: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
-        assert!(!json.is_empty());
-        assert!(json[0].get("workflow_name").is_some());
-        assert!(json[0].get("workflow_source").is_some());
-        assert!(json[0].get("params").is_some());
-        assert!(json[0].get("rerun_of_job_id").is_some());
-        assert!(json[0].get("duration_ms").is_some());
-    }
-
-    #[tokio::test]
-    async fn test_get_job_found() {
-        let state = test_state();
-        let mut app = app_router(state.clone());
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.contains("jellyfin_key"));
+        assert!(!text.contains("plaintext-value"));
 
-        let body = serde_json::json!({
-            "workflow": valid_workflow_json()
-        });
         let req = Request::builder()
             .method("POST")
-            .uri("/api/jobs")
+            .uri("/api/secrets")
             .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .body(Body::from(r#"{"name":"../bad","value":"x"}"#))
             .unwrap();
         let resp = send_request(&mut app, req).await;
-        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let create_json: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
-        let job_id = create_json["id"].as_str().unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
 
         let req = Request::builder()
-            .uri(format!("/api/jobs/{job_id}"))
+            .method("DELETE")
+            .uri("/api/secrets/jellyfin_key")
             .body(Body::empty())
             .unwrap();
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
 
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
+        let req = Request::builder()
+            .method("DELETE")
+            .uri("/api/secrets/jellyfin_key")
+            .body(Body::empty())
             .unwrap();
-        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        assert_eq!(json["id"], job_id);
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
 
     #[tokio::test]
-    async fn test_get_job_includes_metadata_for_ad_hoc_job() {
-        let state = test_state();
-        let mut app = app_router(state.clone());
-
-        let mut workflow = valid_workflow_json();
-        workflow
-            .as_object_mut()
-            .expect("workflow should be object")
-            .insert(
-                "name".to_string(),
-                serde_json::Value::String("Manual Workflow".to_string()),
-            );
-
-        let body = serde_json::json!({
-            "workflow": workflow,
-            "params": {"input": "/tmp/input-video.mkv"}
-        });
-
+    async fn test_job_logs_unknown_job_returns_not_found() {
+        let mut app = test_router();
         let req = Request::builder()
-            .method("POST")
-            .uri("/api/jobs")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .uri("/api/jobs/missing-job/logs")
+            .body(Body::empty())
             .unwrap();
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
 
-        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let create_json: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
-        let job_id = create_json["id"].as_str().unwrap();
+    #[test]
+    fn test_estimate_input_fps_from_second_frame_ignores_first_frame_delay() {
+        let started_at = Instant::now();
+        let delayed_first_frame = started_at + Duration::from_secs(10);
+        let (baseline, first_fps) =
+            estimate_input_fps_from_second_frame(None, 1, delayed_first_frame);
+        assert_eq!(first_fps, 0.0);
+
+        let (_baseline, second_fps) = estimate_input_fps_from_second_frame(
+            baseline,
+            2,
+            delayed_first_frame + Duration::from_secs(1),
+        );
+        assert!((second_fps - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_estimate_input_fps_from_second_frame_resets_when_frame_counter_rewinds() {
+        let started_at = Instant::now();
+        let (baseline, _) = estimate_input_fps_from_second_frame(None, 5, started_at);
+        let (rewound_baseline, rewound_fps) =
+            estimate_input_fps_from_second_frame(baseline, 2, started_at + Duration::from_secs(1));
+        assert_eq!(rewound_fps, 0.0);
+
+        let (_, resumed_fps) = estimate_input_fps_from_second_frame(
+            rewound_baseline,
+            3,
+            started_at + Duration::from_secs(2),
+        );
+        assert!((resumed_fps - 1.0).abs() < 0.01);
+    }
 
+    #[tokio::test]
+    async fn test_list_nodes() {
+        let mut app = test_router();
         let req = Request::builder()
-            .uri(format!("/api/jobs/{job_id}"))
+            .uri("/api/nodes")
             .body(Body::empty())
             .unwrap();
+
         let resp = send_request(&mut app, req).await;
         assert_eq!(resp.status(), StatusCode::OK);
 
         let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        assert_eq!(json["workflow_name"], "Manual Workflow");
-        assert_eq!(json["workflow_source"], WORKFLOW_SOURCE_API_JOBS);
-        assert_eq!(json["params"]["input"], "/tmp/input-video.mkv");
-        assert!(json["rerun_of_job_id"].is_null());
-        assert!(json.get("duration_ms").is_some());
+        let json: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json.len(), 42);
+        let node_types: Vec<&str> = json
+            .iter()
+            .map(|n| n["node_type"].as_str().unwrap())
+            .collect();
+        assert!(node_types.contains(&"Downloader"));
+        assert!(node_types.contains(&"PathDivider"));
+        assert!(node_types.contains(&"PathJoiner"));
+        assert!(node_types.contains(&"StringReplace"));
+        assert!(node_types.contains(&"StringTemplate"));
+        assert!(node_types.contains(&"TypeConversion"));
+        assert!(node_types.contains(&"HttpRequest"));
+        assert!(node_types.contains(&"Print"));
+        assert!(node_types.contains(&"VideoInput"));
+        assert!(node_types.contains(&"StreamInput"));
+        assert!(node_types.contains(&"SuperResolution"));
+        assert!(node_types.contains(&"VideoOutput"));
+        assert!(node_types.contains(&"Constant"));
+        assert!(node_types.contains(&"WorkflowInput"));
+        assert!(node_types.contains(&"WorkflowOutput"));
+        assert!(node_types.contains(&"Workflow"));
+
+        let downloader = json
+            .iter()
+            .find(|node| node["node_type"] == "Downloader")
+            .expect("Downloader descriptor should be present");
+        let outputs = downloader["outputs"]
+            .as_array()
+            .expect("Downloader outputs should be an array");
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0]["name"], "path");
     }
 
-    #[tokio::test]
-    async fn test_get_batch_job_includes_default_metadata() {
+    #[tokio::test]
+    async fn test_list_nodes_localized_by_header_then_config() {
+        async fn super_res(app: &mut Router, accept_language: Option<&str>) -> serde_json::Value {
+            let mut req = Request::builder().uri("/api/nodes");
+            if let Some(value) = accept_language {
+                req = req.header("accept-language", value);
+            }
+            let resp = send_request(app, req.body(Body::empty()).unwrap()).await;
+            assert_eq!(resp.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let json: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+            json.into_iter()
+                .find(|node| node["node_type"] == "SuperResolution")
+                .unwrap()
+        }
+
         let state = test_state();
         let mut app = app_router(state.clone());
 
-        let body = serde_json::json!({
-            "file_paths": [temp_path_str("video1.mkv")],
-            "workflow": valid_workflow_json()
-        });
+        let en = super_res(&mut app, None).await;
+        assert_eq!(en["display_name"], "Super Resolution");
+        assert_eq!(en["label_key"], "node.SuperResolution.title");
+        assert_eq!(en["inputs"][0]["label_key"], "port.frames");
+        assert_eq!(en["inputs"][0]["label"], "Frames");
 
-        let req = Request::builder()
-            .method("POST")
-            .uri("/api/batch")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
-            .unwrap();
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::CREATED);
+        let zh = super_res(&mut app, Some("zh-CN,zh;q=0.9,en;q=0.8")).await;
+        assert_eq!(zh["display_name"], "超分辨率");
+        assert_eq!(zh["inputs"][0]["label"], "帧");
 
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        let job_id = json["job_ids"][0].as_str().unwrap();
+        state.inner.config.write().await.locale = "zh-CN".to_string();
+        let configured = super_res(&mut app, None).await;
+        assert_eq!(configured["display_name"], "超分辨率");
+        let requested = super_res(&mut app, Some("en-US")).await;
+        assert_eq!(requested["display_name"], "Super Resolution");
+    }
 
+    #[tokio::test]
+    async fn test_list_models() {
+        let mut app = test_router();
         let req = Request::builder()
-            .uri(format!("/api/jobs/{job_id}"))
+            .uri("/api/models")
             .body(Body::empty())
             .unwrap();
+
         let resp = send_request(&mut app, req).await;
         assert_eq!(resp.status(), StatusCode::OK);
 
         let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        assert_eq!(json["workflow_name"], DEFAULT_WORKFLOW_NAME_API_BATCH);
-        assert_eq!(json["workflow_source"], WORKFLOW_SOURCE_API_BATCH);
-        assert!(json["params"].is_null());
-        assert!(json["rerun_of_job_id"].is_null());
-        assert!(json.get("duration_ms").is_some());
+        let json: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json.len(), 3);
     }
 
     #[tokio::test]
-    async fn test_get_job_not_found() {
-        let mut app = test_router();
-        let req = Request::builder()
-            .uri("/api/jobs/nonexistent-id")
-            .body(Body::empty())
+    async fn test_list_model_benchmarks() {
+        let data_dir = unique_temp_dir("videnoa-benchmarks");
+        let store = BenchmarkStore::open(&data_dir).unwrap();
+        store
+            .record(&BenchmarkRecord {
+                model: "2x_Test.onnx".to_string(),
+                backend: "cuda".to_string(),
+                width: 1920,
+                height: 1080,
+                tile_size: 0,
+                fps: 42.5,
+                measured_at: chrono::Utc::now(),
+            })
             .unwrap();
+        let mut app = app_router(test_state_with_data_dir(data_dir.clone()));
 
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let (status, json) = get_json(&mut app, "/api/models/benchmarks").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json[0]["model"], "2x_Test.onnx");
+        assert_eq!(json[0]["fps"], 42.5);
+        let _ = std::fs::remove_dir_all(&data_dir);
     }
 
     #[tokio::test]
-    async fn test_delete_job_history_removes_only_target_row_and_views() {
-        let data_dir = test_data_dir();
-        let state = test_state_with_data_dir(data_dir.clone());
-        let mut app = app_router(state.clone());
+    async fn test_suggest_param_values() {
+        let mut app = test_router();
 
-        let target_id = format!("delete-target-{}", Uuid::new_v4());
-        let target_job = build_test_job(target_id.clone(), JobStatus::Completed, None);
-        insert_test_job(&state, target_job);
+        let (status, json) = get_json(
+            &mut app,
+            "/api/nodes/SuperResolution/suggest?param=backend&prefix=T",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            json,
+            serde_json::json!([{"value": "tensorrt", "label": "tensorrt", "kind": "option"}])
+        );
 
-        let other_id = format!("delete-other-{}", Uuid::new_v4());
-        let other_job = build_test_job(other_id.clone(), JobStatus::Failed, None);
-        insert_test_job(&state, other_job);
+        let (_, json) = get_json(
+            &mut app,
+            "/api/nodes/FrameInterpolation/suggest?param=model_path",
+        )
+        .await;
+        assert_eq!(json[0]["value"], "models/rife_v4.26.onnx");
+        assert_eq!(json[0]["kind"], "model");
+        assert_eq!(json.as_array().unwrap().len(), 1);
+
+        let (_, json) = get_json(
+            &mut app,
+            "/api/nodes/SuperResolution/suggest?param=model_path&prefix=rife",
+        )
+        .await;
+        assert!(json.as_array().unwrap().is_empty());
 
-        let req = Request::builder()
-            .method("DELETE")
-            .uri(format!("/api/jobs/{target_id}"))
-            .body(Body::empty())
-            .unwrap();
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        let (_, json) = get_json(
+            &mut app,
+            "/api/nodes/SuperResolution/suggest?param=model_path",
+        )
+        .await;
+        assert_eq!(json[0]["value"], "auto");
+
+        let dir = unique_temp_dir("videnoa-suggest-paths");
+        std::fs::create_dir_all(dir.join("clips")).unwrap();
+        std::fs::write(dir.join("clip.mkv"), b"").unwrap();
+        std::fs::write(dir.join("other.mkv"), b"").unwrap();
+        let prefix: String =
+            url::form_urlencoded::byte_serialize(format!("{}/CL", dir.display()).as_bytes())
+                .collect();
+        let (_, json) = get_json(
+            &mut app,
+            &format!("/api/nodes/VideoInput/suggest?param=path&prefix={prefix}"),
+        )
+        .await;
+        let kinds: Vec<(&str, &str)> = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| {
+                (
+                    entry["label"].as_str().unwrap(),
+                    entry["kind"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(kinds, [("clips", "dir"), ("clip.mkv", "file")]);
+        let _ = std::fs::remove_dir_all(&dir);
 
-        assert!(state.inner.jobs.get(&target_id).is_none());
-        assert!(state.inner.jobs.get(&other_id).is_some());
+        let (status, _) = get_json(&mut app, "/api/nodes/Nope/suggest?param=path").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = get_json(&mut app, "/api/nodes/VideoInput/suggest?param=nope").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
 
-        let req = Request::builder()
-            .uri(format!("/api/jobs/{target_id}"))
-            .body(Body::empty())
-            .unwrap();
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    #[tokio::test]
+    async fn test_list_presets() {
+        let presets = DashMap::new();
+        presets.insert(
+            "test-preset".to_string(),
+            Preset {
+                name: "Test Preset".to_string(),
+                description: "A test preset".to_string(),
+                workflow: serde_json::json!({"nodes": [], "connections": []}),
+                origin: PresetOrigin::Builtin,
+            },
+        );
+
+        let mut node_registry = NodeRegistry::new();
+        node_registry.register("test_source", |_params| {
+            Ok(Box::new(TestNode {
+                node_type: "test_source".to_string(),
+                inputs: vec![],
+                outputs: vec![],
+            }))
+        });
+        let model_registry = ModelRegistry::with_builtin_models(test_models_dir());
+        let state = AppState::new(
+            node_registry,
+            model_registry,
+            presets,
+            AppConfig::default(),
+            test_config_path(),
+            test_data_dir(),
+        );
+        let mut app = app_router(state);
 
         let req = Request::builder()
-            .uri("/api/jobs")
+            .uri("/api/presets")
             .body(Body::empty())
             .unwrap();
+
         let resp = send_request(&mut app, req).await;
         assert_eq!(resp.status(), StatusCode::OK);
 
         let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let listed_jobs: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
-        assert!(!listed_jobs
-            .iter()
-            .any(|job| job["id"].as_str() == Some(target_id.as_str())));
-        assert!(listed_jobs
+        let json: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json.len(), 1);
+        assert_eq!(json[0]["id"], "test-preset");
+        assert_eq!(json[0]["name"], "Test Preset");
+        assert!(json[0]["workflow"].is_object());
+        assert_eq!(json[0]["origin"]["kind"], "builtin");
+    }
+
+    #[tokio::test]
+    async fn test_sync_presets_lists_community_presets_and_notifies() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("index.json"),
+            serde_json::json!({"presets": [
+                {"id": "anime", "name": "Anime", "version": "1", "vetted": true,
+                 "workflow": {"nodes": [], "connections": []}}
+            ]})
+            .to_string(),
+        )
+        .unwrap();
+
+        let state = test_state();
+        let mut app = app_router(state.clone());
+        let (status, _) =
+            post_json(&mut app, "/api/presets/sync", None, serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        state.inner.config.write().await.presets.community_index_url =
+            url::Url::from_file_path(dir.path().join("index.json"))
+                .unwrap()
+                .to_string();
+        let mut events = state.inner.config_events.subscribe();
+        let (status, synced) =
+            post_json(&mut app, "/api/presets/sync", None, serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(synced["presets"], 1);
+        assert_eq!(synced["updates"][0]["change"], "added");
+        assert!(matches!(
+            events.try_recv().unwrap(),
+            ConfigEvent::PresetsUpdated { updates, .. } if updates[0].id == "community/anime"
+        ));
+
+        let (_, presets) = get_json(&mut app, "/api/presets").await;
+        let community = presets
+            .as_array()
+            .unwrap()
             .iter()
-            .any(|job| job["id"].as_str() == Some(other_id.as_str())));
+            .find(|preset| preset["id"] == "community/anime")
+            .unwrap();
+        assert_eq!(community["origin"]["kind"], "community");
+        assert_eq!(community["origin"]["version"], "1");
 
-        assert_eq!(persisted_job_status(&data_dir, &target_id), None);
-        assert_eq!(
-            persisted_job_status(&data_dir, &other_id).as_deref(),
-            Some("failed")
-        );
+        // Nothing changed, so nothing is announced.
+        let (_, synced) =
+            post_json(&mut app, "/api/presets/sync", None, serde_json::json!({})).await;
+        assert_eq!(synced["updates"], serde_json::json!([]));
+        assert!(events.try_recv().is_err());
     }
 
     #[tokio::test]
-    async fn test_delete_job_history_cancels_active_job_then_removes_row() {
-        let data_dir = test_data_dir();
-        let state = test_state_with_data_dir(data_dir.clone());
+    async fn test_create_batch() {
+        let state = test_state();
         let mut app = app_router(state.clone());
 
-        let active_id = format!("delete-active-{}", Uuid::new_v4());
-        let active_job = build_test_job(active_id.clone(), JobStatus::Running, None);
-        let cancel_probe = active_job.cancel_token.clone();
-        insert_test_job(&state, active_job);
+        let body = serde_json::json!({
+            "file_paths": [temp_path_str("video1.mkv"), temp_path_str("video2.mp4")],
+            "workflow": valid_workflow_json()
+        });
 
         let req = Request::builder()
-            .method("DELETE")
-            .uri(format!("/api/jobs/{active_id}"))
-            .body(Body::empty())
+            .method("POST")
+            .uri("/api/batch")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
             .unwrap();
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
 
-        assert!(cancel_probe.is_cancelled());
-        assert!(state.inner.jobs.get(&active_id).is_none());
-        assert_eq!(persisted_job_status(&data_dir, &active_id), None);
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
 
-        let req = Request::builder()
-            .uri(format!("/api/jobs/{active_id}"))
-            .body(Body::empty())
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
             .unwrap();
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["total"], 2);
+        assert_eq!(json["job_ids"].as_array().unwrap().len(), 2);
     }
 
     #[tokio::test]
-    async fn test_rerun_allows_non_completed_statuses_and_creates_new_job() {
-        let source_statuses = [
-            JobStatus::Queued,
-            JobStatus::Running,
-            JobStatus::Failed,
-            JobStatus::Cancelled,
-        ];
-
-        for source_status in source_statuses {
-            let state = test_state();
-            let mut app = app_router(state.clone());
-
-            let source_id = format!("rerun-source-{}", Uuid::new_v4());
-            let source_params = Some(HashMap::from([(
-                "seed".to_string(),
-                serde_json::json!(source_status as u8),
-            )]));
-            let source_job =
-                build_test_job(source_id.clone(), source_status, source_params.clone());
-            insert_test_job(&state, source_job.clone());
-
-            let req = Request::builder()
-                .method("POST")
-                .uri(format!("/api/jobs/{source_id}/rerun"))
-                .body(Body::empty())
-                .unwrap();
-            let resp = send_request(&mut app, req).await;
-            assert_eq!(
-                resp.status(),
-                StatusCode::CREATED,
-                "expected rerun to be allowed for status {source_status:?}"
-            );
-
-            let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-                .await
-                .unwrap();
-            let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-            let rerun_id = json["id"]
-                .as_str()
-                .expect("rerun response should include id")
-                .to_string();
+    async fn test_create_batch_empty_paths() {
+        let mut app = test_router();
+        let body = serde_json::json!({
+            "file_paths": [],
+            "workflow": valid_workflow_json()
+        });
 
-            assert_ne!(rerun_id, source_id);
-            assert_eq!(json["status"], "queued");
-            assert!(json.get("created_at").is_some());
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/batch")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
 
-            let rerun_job = state
-                .inner
-                .jobs
-                .get(&rerun_id)
-                .expect("rerun job should exist in state");
-            assert_eq!(
-                rerun_job.rerun_of_job_id.as_deref(),
-                Some(source_id.as_str())
-            );
-            assert_eq!(rerun_job.workflow_name, source_job.workflow_name);
-            assert_eq!(rerun_job.workflow_source, source_job.workflow_source);
-            assert_eq!(rerun_job.params, source_params);
-        }
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
     }
 
     #[tokio::test]
-    async fn test_rerun_rejects_completed_source_job() {
-        let state = test_state();
-        let mut app = app_router(state.clone());
-
-        let source_id = format!("rerun-completed-source-{}", Uuid::new_v4());
-        let source_job = build_test_job(source_id.clone(), JobStatus::Completed, None);
-        insert_test_job(&state, source_job.clone());
+    async fn test_extract_frames_missing_file() {
+        let mut app = test_router();
+        let body = serde_json::json!({
+            "video_path": temp_path_str("nonexistent-video-file-12345.mkv"),
+            "count": 3
+        });
 
         let req = Request::builder()
             .method("POST")
-            .uri(format!("/api/jobs/{source_id}/rerun"))
-            .body(Body::empty())
+            .uri("/api/preview/extract")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
             .unwrap();
+
         let resp = send_request(&mut app, req).await;
         assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
 
@@ -4151,71 +12441,107 @@ mod tests {
             .await
             .unwrap();
         let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        assert_eq!(
-            json["error"],
-            format!("{RERUN_COMPLETED_REJECTION}: {source_id}")
-        );
-        assert_eq!(state.inner.jobs.len(), 1);
+        assert!(json["error"].as_str().unwrap().contains("not found"));
+    }
 
-        let source_after = state
-            .inner
-            .jobs
-            .get(&source_id)
-            .expect("source job should remain present");
-        assert_eq!(source_after.status, JobStatus::Completed);
-        assert!(source_after.rerun_of_job_id.is_none());
+    #[tokio::test]
+    async fn test_extract_frames_invalid_count() {
+        let mut app = test_router();
+        let body = serde_json::json!({
+            "video_path": temp_path_str("some_video.mkv"),
+            "count": 0
+        });
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/preview/extract")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
     }
 
     #[tokio::test]
-    async fn test_rerun_preserves_source_row_immutability() {
+    async fn test_serve_preview_frame_not_found() {
+        let mut app = test_router();
+        let req = Request::builder()
+            .uri("/api/preview/frames/nonexistent-session/frame_0001.png")
+            .body(Body::empty())
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_serve_stream_file_serves_hls_playlist() {
         let data_dir = test_data_dir();
-        let state = test_state_with_data_dir(data_dir.clone());
-        let mut app = app_router(state.clone());
+        let stream_dir = data_dir.join(STREAMS_DIR_NAME).join("cam1");
+        std::fs::create_dir_all(&stream_dir).unwrap();
+        std::fs::write(stream_dir.join("playlist.m3u8"), "#EXTM3U\n").unwrap();
+        let mut app = app_router(test_state_with_data_dir(data_dir.clone()));
 
-        let source_id = format!("rerun-row-source-{}", Uuid::new_v4());
-        let mut source_job = build_test_job(
-            source_id.clone(),
-            JobStatus::Failed,
-            Some(HashMap::from([(
-                "input".to_string(),
-                serde_json::json!("/tmp/source.mkv"),
-            )])),
+        let req = Request::builder()
+            .uri("/api/streams/cam1/playlist.m3u8")
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()[axum::http::header::CONTENT_TYPE],
+            "application/vnd.apple.mpegurl"
         );
-        source_job.rerun_of_job_id = Some("older-ancestor-id".to_string());
-        insert_test_job(&state, source_job.clone());
 
-        let conn = Connection::open(data_dir.join("jobs.db")).expect("open jobs db");
-        let source_row_before: (
-            String,
-            Option<String>,
-            Option<String>,
-            Option<String>,
-            String,
-            String,
-        ) = conn
-            .query_row(
-                "SELECT status, error, params_json, rerun_of_job_id, workflow_name, workflow_source
-                 FROM jobs
-                 WHERE id = ?1",
-                rusqlite::params![source_id],
-                |row| {
-                    Ok((
-                        row.get(0)?,
-                        row.get(1)?,
-                        row.get(2)?,
-                        row.get(3)?,
-                        row.get(4)?,
-                        row.get(5)?,
-                    ))
-                },
-            )
-            .expect("query source row before rerun");
+        for (uri, expected) in [
+            ("/api/streams/cam1/segment_00001.ts", StatusCode::NOT_FOUND),
+            ("/api/streams/cam1/secrets.json", StatusCode::NOT_FOUND),
+            ("/api/streams/bad.id/playlist.m3u8", StatusCode::BAD_REQUEST),
+        ] {
+            let req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+            let resp = send_request(&mut app, req).await;
+            assert_eq!(resp.status(), expected, "{uri}");
+        }
+        let _ = std::fs::remove_dir_all(data_dir);
+    }
+
+    #[tokio::test]
+    async fn test_process_frame_session_not_found() {
+        let mut app = test_router();
+        let body = serde_json::json!({
+            "preview_id": "nonexistent-session",
+            "frame_index": 0,
+            "workflow": {"nodes": [], "connections": []}
+        });
 
         let req = Request::builder()
             .method("POST")
-            .uri(format!("/api/jobs/{source_id}/rerun"))
-            .body(Body::empty())
+            .uri("/api/preview/process")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_create_preset() {
+        let mut app = test_router();
+        let body = serde_json::json!({
+            "name": "My Custom Preset",
+            "description": "Custom workflow",
+            "workflow": {"nodes": [], "connections": []}
+        });
+
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/presets")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
             .unwrap();
+
         let resp = send_request(&mut app, req).await;
         assert_eq!(resp.status(), StatusCode::CREATED);
 
@@ -4223,590 +12549,623 @@ mod tests {
             .await
             .unwrap();
         let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        let rerun_id = json["id"]
-            .as_str()
-            .expect("rerun id should exist")
-            .to_string();
+        assert!(json["id"].is_string());
+        assert_eq!(json["name"], "My Custom Preset");
+    }
 
-        let source_row_after: (
-            String,
-            Option<String>,
-            Option<String>,
-            Option<String>,
-            String,
-            String,
-        ) = conn
-            .query_row(
-                "SELECT status, error, params_json, rerun_of_job_id, workflow_name, workflow_source
-                 FROM jobs
-                 WHERE id = ?1",
-                rusqlite::params![source_id],
-                |row| {
-                    Ok((
-                        row.get(0)?,
-                        row.get(1)?,
-                        row.get(2)?,
-                        row.get(3)?,
-                        row.get(4)?,
-                        row.get(5)?,
-                    ))
-                },
-            )
-            .expect("query source row after rerun");
-        assert_eq!(source_row_before, source_row_after);
+    fn fs_test_state(models_dir: PathBuf) -> AppState {
+        let mut node_registry = NodeRegistry::new();
+        node_registry.register("test_source", |_params| {
+            Ok(Box::new(TestNode {
+                node_type: "test_source".to_string(),
+                inputs: vec![],
+                outputs: vec![],
+            }))
+        });
+        let model_registry = ModelRegistry::with_builtin_models(test_models_dir());
+        let config = AppConfig {
+            paths: crate::config::PathsConfig {
+                models_dir,
+                extra_models_dirs: Vec::new(),
+                trt_cache_dir: temp_path("trt_cache"),
+                presets_dir: temp_path("videnoa-test-presets-nonexistent"),
+                workflows_dir: temp_path("videnoa-test-workflows-nonexistent"),
+                plugins_dir: temp_path("videnoa-test-plugins-nonexistent"),
+                media_roots: Vec::new(),
+            },
+            ..AppConfig::default()
+        };
+        AppState::new(
+            node_registry,
+            model_registry,
+            DashMap::new(),
+            config,
+            test_config_path(),
+            test_data_dir(),
+        )
+    }
+
+    // Assembles a minimal ONNX protobuf (Add node, 2 float32[1,3] inputs, 1 output)
+    // using raw prost wire encoding since server crate can't access videnoa's generated proto types.
+    fn build_test_onnx_bytes() -> Vec<u8> {
+        use prost::encoding::*;
+
+        fn embed(field: u32, msg: &[u8], buf: &mut Vec<u8>) {
+            encode_key(field, WireType::LengthDelimited, buf);
+            encode_varint(msg.len() as u64, buf);
+            buf.extend_from_slice(msg);
+        }
+
+        fn encode_value_info(name: &str) -> Vec<u8> {
+            let mut dim1 = Vec::new();
+            int64::encode(1, &1i64, &mut dim1);
+            let mut dim3 = Vec::new();
+            int64::encode(1, &3i64, &mut dim3);
+
+            let mut shape = Vec::new();
+            embed(1, &dim1, &mut shape);
+            embed(1, &dim3, &mut shape);
+
+            let mut tensor = Vec::new();
+            int32::encode(1, &1i32, &mut tensor); // elem_type = FLOAT
+            embed(2, &shape, &mut tensor);
+
+            let mut type_proto = Vec::new();
+            embed(1, &tensor, &mut type_proto); // oneof tensor_type
+
+            let mut vi = Vec::new();
+            string::encode(1, &name.to_string(), &mut vi);
+            embed(2, &type_proto, &mut vi);
+            vi
+        }
+
+        let vi_a = encode_value_info("A");
+        let vi_b = encode_value_info("B");
+        let vi_c = encode_value_info("C");
+
+        let mut node = Vec::new();
+        string::encode(1, &"A".to_string(), &mut node);
+        string::encode(1, &"B".to_string(), &mut node);
+        string::encode(2, &"C".to_string(), &mut node);
+        string::encode(3, &"add_0".to_string(), &mut node);
+        string::encode(4, &"Add".to_string(), &mut node);
+
+        let mut init = Vec::new();
+        let mut dims_packed = Vec::new();
+        encode_varint(1, &mut dims_packed);
+        encode_varint(3, &mut dims_packed);
+        embed(1, &dims_packed, &mut init); // dims: [1, 3]
+        int32::encode(2, &1i32, &mut init); // data_type = FLOAT
+        string::encode(8, &"B".to_string(), &mut init);
 
-        let rerun_row_rerun_of: Option<String> = conn
-            .query_row(
-                "SELECT rerun_of_job_id FROM jobs WHERE id = ?1",
-                rusqlite::params![rerun_id],
-                |row| row.get(0),
-            )
-            .expect("query rerun row linkage");
-        assert_eq!(rerun_row_rerun_of.as_deref(), Some(source_id.as_str()));
+        let mut opset = Vec::new();
+        int64::encode(2, &17i64, &mut opset);
 
-        let source_after = state
-            .inner
-            .jobs
-            .get(&source_id)
-            .expect("source job should remain in state");
-        assert_eq!(source_after.status, JobStatus::Failed);
-        assert_eq!(
-            source_after.rerun_of_job_id.as_deref(),
-            Some("older-ancestor-id")
-        );
+        let mut graph = Vec::new();
+        embed(1, &node, &mut graph);
+        embed(5, &init, &mut graph);
+        embed(11, &vi_a, &mut graph);
+        embed(11, &vi_b, &mut graph);
+        embed(12, &vi_c, &mut graph);
+
+        let mut buf = Vec::new();
+        int64::encode(1, &8i64, &mut buf);
+        string::encode(2, &"test".to_string(), &mut buf);
+        string::encode(3, &"1.0".to_string(), &mut buf);
+        embed(7, &graph, &mut buf);
+        embed(8, &opset, &mut buf);
+
+        buf
     }
 
-    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-    async fn test_job_lifecycle_is_persisted_to_data_dir_jobs_db() {
-        let data_dir = test_data_dir();
-        let state = test_state_with_data_dir(data_dir.clone());
+    #[tokio::test]
+    async fn test_models_watcher_picks_up_new_models() {
+        let dir = unique_temp_dir("videnoa-models-watch");
+        let extra = dir.join("extra");
+        std::fs::create_dir_all(&extra).unwrap();
+        let state = fs_test_state(dir.clone());
+        state.inner.config.write().await.paths.extra_models_dirs = vec![extra.clone()];
         let mut app = app_router(state.clone());
 
-        let mut workflow = delay_workflow_json(350);
-        workflow
-            .as_object_mut()
-            .expect("workflow should be object")
-            .insert(
-                "name".to_string(),
-                serde_json::Value::String("Persisted Delay Workflow".to_string()),
-            );
-
-        let body = serde_json::json!({
-            "workflow": workflow,
-            "params": {
-                "seed": 7
+        let watcher = spawn_models_watcher(&state);
+        // Let the first tick record the empty directories.
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        std::fs::write(extra.join("4x_Dropped.onnx"), b"data").unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            let (_, models) = get_json(&mut app, "/api/models").await;
+            if models
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|model| model["name"] == "4x_Dropped")
+            {
+                break;
             }
-        });
+            assert!(Instant::now() < deadline, "new model never appeared");
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+        watcher.abort();
+        std::fs::remove_dir_all(&dir).ok();
+    }
 
-        let req = Request::builder()
-            .method("POST")
-            .uri("/api/jobs")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
-            .unwrap();
+    #[tokio::test]
+    async fn test_model_rename_delete_and_usage() {
+        let dir = unique_temp_dir("videnoa-model-files");
+        let extra = dir.join("extra");
+        std::fs::create_dir_all(&extra).unwrap();
+        std::fs::write(dir.join("4x_Big.onnx"), vec![0u8; 100]).unwrap();
+        std::fs::write(dir.join("4x_Big.json"), br#"{"description": "big"}"#).unwrap();
+        std::fs::write(extra.join("2x_Small.onnx"), vec![0u8; 10]).unwrap();
+        let state = fs_test_state(dir.clone());
+        state.inner.config.write().await.paths.extra_models_dirs = vec![extra.clone()];
+        let mut app = app_router(state);
 
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::CREATED);
+        let (status, usage) = get_json(&mut app, "/api/models/usage").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(usage["total_bytes"], 132);
+        assert_eq!(usage["dirs"].as_array().unwrap().len(), 2);
+        assert_eq!(usage["dirs"][1]["model_count"], 1);
+        assert_eq!(usage["models"][0]["filename"], "4x_Big.onnx");
+        assert_eq!(usage["models"][0]["size_bytes"], 122);
+
+        for (name, expected) in [
+            ("../4x_Big.onnx", StatusCode::BAD_REQUEST),
+            ("4x_Big.bin", StatusCode::BAD_REQUEST),
+            ("2x_Small.onnx", StatusCode::BAD_REQUEST),
+        ] {
+            let (status, _) = post_json(
+                &mut app,
+                "/api/models/4x_Big.onnx/rename",
+                None,
+                serde_json::json!({"filename": name}),
+            )
+            .await;
+            assert_eq!(status, expected, "{name}");
+        }
+        let (status, renamed) = post_json(
+            &mut app,
+            "/api/models/4x_Big.onnx/rename",
+            None,
+            serde_json::json!({"filename": "4x_Huge.onnx"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(renamed["filename"], "4x_Huge.onnx");
+        assert!(dir.join("4x_Huge.json").is_file());
+        assert!(!dir.join("4x_Big.onnx").exists());
+
+        let delete = |filename: &str| {
+            Request::builder()
+                .method("DELETE")
+                .uri(format!("/api/models/{filename}"))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let resp = send_request(&mut app, delete("4x_Huge.onnx")).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        assert!(!dir.join("4x_Huge.onnx").exists());
+        assert!(!dir.join("4x_Huge.json").exists());
+        let (_, models) = get_json(&mut app, "/api/models").await;
+        assert!(!models
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|model| model["filename"] == "4x_Huge.onnx"));
+        let resp = send_request(&mut app, delete("4x_Huge.onnx")).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
 
-        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let create_json: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
-        let job_id = create_json["id"].as_str().unwrap().to_string();
+        // Only .onnx files can be deleted or renamed through the models API.
+        std::fs::write(dir.join("notes.txt"), b"keep me").unwrap();
+        let resp = send_request(&mut app, delete("notes.txt")).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let (status, _) = post_json(
+            &mut app,
+            "/api/models/notes.txt/rename",
+            None,
+            serde_json::json!({"filename": "notes.onnx"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(dir.join("notes.txt").is_file());
 
-        assert!(
-            data_dir.join("jobs.db").exists(),
-            "expected jobs.db at {}",
-            data_dir.join("jobs.db").display()
-        );
+        std::fs::remove_dir_all(&dir).ok();
+    }
 
-        assert!(
-            wait_for_persisted_status(&data_dir, &job_id, "running").await,
-            "expected running transition to be persisted"
-        );
+    #[tokio::test]
+    async fn test_chunked_model_upload() {
+        use sha2::{Digest, Sha256};
 
-        let terminal = wait_for_job_terminal_status(&state, &job_id).await;
-        assert_eq!(terminal, JobStatus::Completed);
+        let dir = unique_temp_dir("videnoa-model-upload");
+        let state = fs_test_state(dir.clone());
+        let data_dir = state.inner.data_dir.clone();
+        let mut app = app_router(state);
+        let content = b"0123456789abcdef";
+        let sha256 = format!("{:x}", Sha256::digest(content));
 
-        assert!(
-            wait_for_persisted_status(&data_dir, &job_id, "completed").await,
-            "expected completed transition to be persisted"
+        let chunk = |id: &str, offset: usize, bytes: &[u8]| {
+            Request::builder()
+                .method("POST")
+                .uri(format!("/api/models/upload/{id}?offset={offset}"))
+                .header("content-type", "application/octet-stream")
+                .body(Body::from(bytes.to_vec()))
+                .unwrap()
+        };
+        let read_json = |resp: axum::response::Response| async move {
+            let status = resp.status();
+            let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&bytes).unwrap_or_default(),
+            )
+        };
+
+        let start = serde_json::json!({
+            "filename": "4x_Uploaded.onnx",
+            "size_bytes": content.len(),
+            "sha256": sha256,
+        });
+        let (status, upload) = post_json(&mut app, "/api/models/upload", None, start.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+        let id = upload["id"].as_str().unwrap().to_string();
+
+        let (status, upload) =
+            read_json(send_request(&mut app, chunk(&id, 0, &content[..6])).await).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(upload["received_bytes"], 6);
+        assert_eq!(upload["complete"], false);
+
+        // Starting again resumes where the upload left off.
+        let (_, resumed) = post_json(&mut app, "/api/models/upload", None, start.clone()).await;
+        assert_eq!(resumed["id"], id.as_str());
+        assert_eq!(resumed["received_bytes"], 6);
+
+        let (status, _) =
+            read_json(send_request(&mut app, chunk(&id, 0, &content[6..])).await).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "stale offset");
+        let (status, upload) =
+            read_json(send_request(&mut app, chunk(&id, 6, &content[6..])).await).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(upload["complete"], true);
+        assert_eq!(
+            std::fs::read(dir.join("4x_Uploaded.onnx")).unwrap(),
+            content
         );
+        let (_, models) = get_json(&mut app, "/api/models").await;
+        assert!(models
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|model| model["filename"] == "4x_Uploaded.onnx"));
+        let (status, _) = get_json(&mut app, &format!("/api/models/upload/{id}")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
 
-        let conn = Connection::open(data_dir.join("jobs.db")).expect("open jobs db");
-        let (workflow_name, workflow_source, params_json, rerun_of_job_id, started_at, completed_at): (
-            String,
-            String,
-            Option<String>,
-            Option<String>,
-            Option<String>,
-            Option<String>,
-        ) = conn
-            .query_row(
-                "SELECT workflow_name, workflow_source, params_json, rerun_of_job_id, started_at, completed_at
-                 FROM jobs
-                 WHERE id = ?1",
-                rusqlite::params![job_id],
-                |row| {
-                    Ok((
-                        row.get(0)?,
-                        row.get(1)?,
-                        row.get(2)?,
-                        row.get(3)?,
-                        row.get(4)?,
-                        row.get(5)?,
-                    ))
-                },
-            )
-            .expect("query persisted metadata");
+        let (status, _) = post_json(&mut app, "/api/models/upload", None, start).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "model already exists");
 
-        assert_eq!(workflow_name, "Persisted Delay Workflow");
-        assert_eq!(workflow_source, WORKFLOW_SOURCE_API_JOBS);
-        assert!(rerun_of_job_id.is_none());
-        assert!(started_at.is_some());
-        assert!(completed_at.is_some());
+        let (_, upload) = post_json(
+            &mut app,
+            "/api/models/upload",
+            None,
+            serde_json::json!({
+                "filename": "4x_Corrupt.onnx",
+                "size_bytes": content.len(),
+                "sha256": sha256,
+            }),
+        )
+        .await;
+        let id = upload["id"].as_str().unwrap().to_string();
+        let (status, _) =
+            read_json(send_request(&mut app, chunk(&id, 0, b"not the same bytes")).await).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "larger than announced");
+        let (status, _) =
+            read_json(send_request(&mut app, chunk(&id, 0, b"0123456789abcdeX")).await).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "hash mismatch");
+        assert!(!dir.join("4x_Corrupt.onnx").exists());
 
-        let params_json = params_json.expect("params_json should be persisted");
-        let params_value: serde_json::Value =
-            serde_json::from_str(&params_json).expect("params_json should be valid JSON");
-        assert_eq!(params_value["seed"], 7);
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::remove_dir_all(&data_dir).ok();
     }
 
-    #[test]
-    fn test_startup_restore_reconciles_running_job_to_cancelled() {
-        let data_dir = test_data_dir();
-        let initial_state = test_state_with_data_dir(data_dir.clone());
+    #[tokio::test]
+    async fn test_inspect_model_valid() {
+        let dir = std::env::temp_dir().join(format!("videnoa-inspect-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("test_model.onnx"), build_test_onnx_bytes()).unwrap();
 
-        let workflow: PipelineGraph =
-            serde_json::from_value(valid_workflow_json()).expect("workflow should deserialize");
-        let created_at = Utc::now() - chrono::Duration::minutes(2);
-        let started_at = Some(created_at + chrono::Duration::seconds(5));
-        let job_id = format!("restore-{}", Uuid::new_v4());
+        let state = fs_test_state(dir.clone());
+        let mut app = app_router(state);
 
-        let stale_running_job = Job {
-            id: job_id.clone(),
-            status: JobStatus::Running,
-            workflow,
-            created_at,
-            started_at,
-            completed_at: None,
-            progress: Some(ProgressUpdate {
-                current_frame: 42,
-                total_frames: Some(300),
-                fps: 12.0,
-                eta_seconds: Some(21.5),
-            }),
-            error: Some("executor interrupted before shutdown".to_string()),
-            cancel_token: CancellationToken::new(),
-            params: Some(HashMap::from([(
-                "input".to_string(),
-                serde_json::Value::String("/tmp/input.mkv".to_string()),
-            )])),
-            workflow_name: "Restore Candidate".to_string(),
-            workflow_source: WORKFLOW_SOURCE_API_JOBS.to_string(),
-            rerun_of_job_id: Some("older-job-id".to_string()),
-        };
+        let req = Request::builder()
+            .uri("/api/models/test_model.onnx/inspect")
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
 
-        initial_state
-            .persist_job_snapshot(&stale_running_job)
-            .expect("persist running snapshot");
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["ir_version"], 8);
+        assert_eq!(json["opset_version"], 17);
+        assert_eq!(json["producer_name"], "test");
+        assert_eq!(json["op_count"], 1);
+        assert_eq!(json["param_count"], 3);
+        assert!(json["inputs"].as_array().unwrap().len() >= 2);
+        assert!(json["outputs"].as_array().unwrap().len() >= 1);
+        assert_eq!(json["nodes"][0]["op_type"], "Add");
 
-        let restored_state = test_state_with_data_dir(data_dir.clone());
-        let restored_job = restored_state
-            .inner
-            .jobs
-            .get(&job_id)
-            .expect("job should be restored from persistence");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 
-        assert_eq!(restored_job.status, JobStatus::Cancelled);
-        assert!(restored_job.completed_at.is_some());
-        assert!(restored_job.error.is_some());
-        assert!(restored_job
-            .error
-            .as_deref()
-            .unwrap_or_default()
-            .contains("transitioned to 'cancelled' for retry safety"));
-        assert_eq!(restored_job.workflow_name, "Restore Candidate");
-        assert_eq!(restored_job.workflow_source, WORKFLOW_SOURCE_API_JOBS);
-        assert_eq!(
-            restored_job.rerun_of_job_id.as_deref(),
-            Some("older-job-id")
-        );
+    #[tokio::test]
+    async fn test_inspect_model_path_traversal() {
+        let dir = std::env::temp_dir().join(format!("videnoa-inspect-trav-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
 
-        let conn = Connection::open(data_dir.join("jobs.db")).expect("open jobs db");
-        let (status, completed_at_raw, error_raw): (String, Option<String>, Option<String>) = conn
-            .query_row(
-                "SELECT status, completed_at, error FROM jobs WHERE id = ?1",
-                rusqlite::params![job_id],
-                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
-            )
-            .expect("query reconciled row");
+        let state = fs_test_state(dir.clone());
+        let mut app = app_router(state);
+
+        let req = Request::builder()
+            .uri("/api/models/..%2F..%2Fetc%2Fpasswd/inspect")
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
 
-        assert_eq!(status, "cancelled");
-        assert!(completed_at_raw.is_some());
-        assert!(error_raw
-            .as_deref()
-            .unwrap_or_default()
-            .contains("transitioned to 'cancelled' for retry safety"));
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[tokio::test]
-    async fn test_job_ws_serializes_progress_and_debug_events() {
-        let progress_event = JobWsEvent::from(ProgressUpdate {
-            current_frame: 12,
-            total_frames: Some(240),
-            fps: 23.5,
-            eta_seconds: Some(9.7),
-        });
-        let progress_json = serde_json::to_value(&progress_event).unwrap();
-        assert_eq!(progress_json["type"], "progress");
-        assert_eq!(progress_json["current_frame"], 12);
-        assert_eq!(progress_json["total_frames"], 240);
-        assert_eq!(progress_json["fps"], 23.5);
-        assert_eq!(progress_json["eta_seconds"], 9.7);
-        assert!(progress_json.get("node_id").is_none());
+    async fn test_inspect_model_not_found() {
+        let dir = std::env::temp_dir().join(format!("videnoa-inspect-nf-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
 
-        let parsed_progress: JobWsEvent = serde_json::from_value(progress_json).unwrap();
-        assert_eq!(parsed_progress, progress_event);
+        let state = fs_test_state(dir.clone());
+        let mut app = app_router(state);
 
-        let debug_event = JobWsEvent::from(NodeDebugValueEvent {
-            node_id: "print_1".to_string(),
-            node_type: "Print".to_string(),
-            value_preview: "hello".to_string(),
-            truncated: false,
-            preview_max_chars: 512,
-        });
-        let debug_json = serde_json::to_value(&debug_event).unwrap();
-        assert_eq!(debug_json["type"], "node_debug_value");
-        assert_eq!(debug_json["node_id"], "print_1");
-        assert_eq!(debug_json["node_type"], "Print");
-        assert_eq!(debug_json["value_preview"], "hello");
-        assert_eq!(debug_json["truncated"], false);
-        assert_eq!(debug_json["preview_max_chars"], 512);
-        assert!(debug_json.get("current_frame").is_none());
+        let req = Request::builder()
+            .uri("/api/models/nonexistent.onnx/inspect")
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
 
-        let parsed_debug: JobWsEvent = serde_json::from_value(debug_json).unwrap();
-        assert_eq!(parsed_debug, debug_event);
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
-    #[test]
-    fn test_print_preview_throttle_per_node() {
-        let window = std::time::Duration::from_millis(PRINT_PREVIEW_THROTTLE_MS);
-        let start = std::time::Instant::now();
+    #[tokio::test]
+    async fn test_fs_list_models_dir() {
+        let dir = std::env::temp_dir().join(format!("videnoa-fs-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("model_a.onnx"), b"fake").unwrap();
+        std::fs::write(dir.join("model_b.onnx"), b"fake").unwrap();
+        std::fs::create_dir_all(dir.join("subdir")).unwrap();
+        std::fs::write(dir.join(".hidden"), b"secret").unwrap();
 
-        let mut job_a_throttle = NodeDebugEventThrottle::new(window);
-        assert!(job_a_throttle.should_emit("node-a", start));
-        assert!(
-            !job_a_throttle.should_emit("node-a", start + std::time::Duration::from_millis(149))
-        );
-        assert!(job_a_throttle.should_emit("node-b", start + std::time::Duration::from_millis(149)));
-        assert!(job_a_throttle.should_emit("node-a", start + std::time::Duration::from_millis(150)));
-        assert!(
-            !job_a_throttle.should_emit("node-b", start + std::time::Duration::from_millis(298))
-        );
-        assert!(job_a_throttle.should_emit("node-b", start + std::time::Duration::from_millis(299)));
+        let state = fs_test_state(dir.clone());
+        let mut app = app_router(state);
 
-        let mut job_b_throttle = NodeDebugEventThrottle::new(window);
-        assert!(job_b_throttle.should_emit("node-a", start + std::time::Duration::from_millis(1)));
-    }
+        let req = Request::builder()
+            .uri("/api/fs/list?base=models")
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
 
-    #[test]
-    fn test_estimate_input_fps_from_second_frame_ignores_first_frame_delay() {
-        let started_at = Instant::now();
-        let delayed_first_frame = started_at + Duration::from_secs(10);
-        let (baseline, first_fps) =
-            estimate_input_fps_from_second_frame(None, 1, delayed_first_frame);
-        assert_eq!(first_fps, 0.0);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let entries: Vec<FsEntry> = serde_json::from_slice(&body).unwrap();
 
-        let (_baseline, second_fps) = estimate_input_fps_from_second_frame(
-            baseline,
-            2,
-            delayed_first_frame + Duration::from_secs(1),
-        );
-        assert!((second_fps - 1.0).abs() < 0.01);
-    }
+        assert!(entries[0].is_dir, "directories should come first");
+        assert_eq!(entries[0].name, "subdir");
 
-    #[test]
-    fn test_estimate_input_fps_from_second_frame_resets_when_frame_counter_rewinds() {
-        let started_at = Instant::now();
-        let (baseline, _) = estimate_input_fps_from_second_frame(None, 5, started_at);
-        let (rewound_baseline, rewound_fps) =
-            estimate_input_fps_from_second_frame(baseline, 2, started_at + Duration::from_secs(1));
-        assert_eq!(rewound_fps, 0.0);
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert!(names.contains(&"model_a.onnx"));
+        assert!(names.contains(&"model_b.onnx"));
+        assert!(!names.contains(&".hidden"), "hidden files must be excluded");
 
-        let (_, resumed_fps) = estimate_input_fps_from_second_frame(
-            rewound_baseline,
-            3,
-            started_at + Duration::from_secs(2),
-        );
-        assert!((resumed_fps - 1.0).abs() < 0.01);
+        for entry in &entries {
+            assert!(entry.path.starts_with("models/"));
+        }
+
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[tokio::test]
-    async fn test_list_nodes() {
-        let mut app = test_router();
+    async fn test_fs_list_with_prefix() {
+        let dir = std::env::temp_dir().join(format!("videnoa-fs-prefix-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("RealESRGAN_x4.onnx"), b"fake").unwrap();
+        std::fs::write(dir.join("RealCUGAN_x2.onnx"), b"fake").unwrap();
+        std::fs::write(dir.join("RIFE_v4.onnx"), b"fake").unwrap();
+
+        let state = fs_test_state(dir.clone());
+        let mut app = app_router(state);
+
         let req = Request::builder()
-            .uri("/api/nodes")
+            .uri("/api/fs/list?base=models&prefix=Real")
             .body(Body::empty())
             .unwrap();
-
         let resp = send_request(&mut app, req).await;
         assert_eq!(resp.status(), StatusCode::OK);
 
         let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let json: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
-        assert_eq!(json.len(), 22);
-        let node_types: Vec<&str> = json
-            .iter()
-            .map(|n| n["node_type"].as_str().unwrap())
-            .collect();
-        assert!(node_types.contains(&"Downloader"));
-        assert!(node_types.contains(&"PathDivider"));
-        assert!(node_types.contains(&"PathJoiner"));
-        assert!(node_types.contains(&"StringReplace"));
-        assert!(node_types.contains(&"StringTemplate"));
-        assert!(node_types.contains(&"TypeConversion"));
-        assert!(node_types.contains(&"HttpRequest"));
-        assert!(node_types.contains(&"Print"));
-        assert!(node_types.contains(&"VideoInput"));
-        assert!(node_types.contains(&"SuperResolution"));
-        assert!(node_types.contains(&"VideoOutput"));
-        assert!(node_types.contains(&"Constant"));
-        assert!(node_types.contains(&"WorkflowInput"));
-        assert!(node_types.contains(&"WorkflowOutput"));
-        assert!(node_types.contains(&"Workflow"));
+        let entries: Vec<FsEntry> = serde_json::from_slice(&body).unwrap();
 
-        let downloader = json
-            .iter()
-            .find(|node| node["node_type"] == "Downloader")
-            .expect("Downloader descriptor should be present");
-        let outputs = downloader["outputs"]
-            .as_array()
-            .expect("Downloader outputs should be an array");
-        assert_eq!(outputs.len(), 1);
-        assert_eq!(outputs[0]["name"], "path");
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|e| e.name.starts_with("Real")));
+
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[tokio::test]
-    async fn test_list_models() {
+    async fn test_fs_list_unknown_base() {
         let mut app = test_router();
         let req = Request::builder()
-            .uri("/api/models")
+            .uri("/api/fs/list?base=../../etc")
             .body(Body::empty())
             .unwrap();
 
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::OK);
-
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let json: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
-        assert_eq!(json.len(), 3);
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
     }
 
     #[tokio::test]
-    async fn test_list_presets() {
-        let presets = DashMap::new();
-        presets.insert(
-            "test-preset".to_string(),
-            Preset {
-                name: "Test Preset".to_string(),
-                description: "A test preset".to_string(),
-                workflow: serde_json::json!({"nodes": [], "connections": []}),
-            },
-        );
+    async fn test_fs_list_traversal_blocked() {
+        let dir = std::env::temp_dir().join(format!("videnoa-fs-trav-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
 
-        let mut node_registry = NodeRegistry::new();
-        node_registry.register("test_source", |_params| {
-            Ok(Box::new(TestNode {
-                node_type: "test_source".to_string(),
-                inputs: vec![],
-                outputs: vec![],
-            }))
-        });
-        let model_registry = ModelRegistry::with_builtin_models(test_models_dir());
-        let state = AppState::new(
-            node_registry,
-            model_registry,
-            presets,
-            AppConfig::default(),
-            test_config_path(),
-            test_data_dir(),
-        );
+        let state = fs_test_state(dir.clone());
         let mut app = app_router(state);
 
         let req = Request::builder()
-            .uri("/api/presets")
+            .uri("/api/fs/list?base=models&prefix=../../../etc/passwd")
             .body(Body::empty())
             .unwrap();
-
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
 
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let json: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
-        assert_eq!(json.len(), 1);
-        assert_eq!(json[0]["id"], "test-preset");
-        assert_eq!(json[0]["name"], "Test Preset");
-        assert!(json[0]["workflow"].is_object());
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[tokio::test]
-    async fn test_create_batch() {
-        let state = test_state();
-        let mut app = app_router(state.clone());
-
-        let body = serde_json::json!({
-            "file_paths": [temp_path_str("video1.mkv"), temp_path_str("video2.mp4")],
-            "workflow": valid_workflow_json()
-        });
+    async fn test_fs_list_nonexistent_base_dir() {
+        let state = fs_test_state(temp_path("videnoa-nonexistent-dir-xyz"));
+        let mut app = app_router(state);
 
         let req = Request::builder()
-            .method("POST")
-            .uri("/api/batch")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .uri("/api/fs/list?base=models")
+            .body(Body::empty())
             .unwrap();
-
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.status(), StatusCode::OK);
 
         let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        assert_eq!(json["total"], 2);
-        assert_eq!(json["job_ids"].as_array().unwrap().len(), 2);
+        let entries: Vec<FsEntry> = serde_json::from_slice(&body).unwrap();
+        assert!(entries.is_empty());
     }
 
     #[tokio::test]
-    async fn test_create_batch_empty_paths() {
-        let mut app = test_router();
-        let body = serde_json::json!({
-            "file_paths": [],
-            "workflow": valid_workflow_json()
-        });
+    async fn test_fs_list_default_base_is_models() {
+        let dir = std::env::temp_dir().join(format!("videnoa-fs-default-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("test.onnx"), b"fake").unwrap();
+
+        let state = fs_test_state(dir.clone());
+        let mut app = app_router(state);
 
         let req = Request::builder()
-            .method("POST")
-            .uri("/api/batch")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .uri("/api/fs/list")
+            .body(Body::empty())
             .unwrap();
-
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let entries: Vec<FsEntry> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "test.onnx");
+        assert_eq!(entries[0].path, "models/test.onnx");
+
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[tokio::test]
-    async fn test_extract_frames_missing_file() {
+    async fn test_browse_fs_tmp() {
         let mut app = test_router();
-        let body = serde_json::json!({
-            "video_path": temp_path_str("nonexistent-video-file-12345.mkv"),
-            "count": 3
-        });
-
         let req = Request::builder()
-            .method("POST")
-            .uri("/api/preview/extract")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .uri("/api/fs/browse?path=/tmp")
+            .body(Body::empty())
             .unwrap();
 
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::OK);
 
         let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        assert!(json["error"].as_str().unwrap().contains("not found"));
+        let _: Vec<FsEntry> = serde_json::from_slice(&body).unwrap();
     }
 
     #[tokio::test]
-    async fn test_extract_frames_invalid_count() {
+    async fn test_browse_fs_tilde() {
         let mut app = test_router();
-        let body = serde_json::json!({
-            "video_path": temp_path_str("some_video.mkv"),
-            "count": 0
-        });
-
         let req = Request::builder()
-            .method("POST")
-            .uri("/api/preview/extract")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .uri("/api/fs/browse?path=~")
+            .body(Body::empty())
             .unwrap();
 
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let entries: Vec<FsEntry> = serde_json::from_slice(&body).unwrap();
+        assert!(!entries.is_empty());
+
+        if let Some(first) = entries.first() {
+            assert!(first.path.starts_with('/'));
+        }
     }
 
+    #[cfg(unix)]
     #[tokio::test]
-    async fn test_serve_preview_frame_not_found() {
+    async fn test_browse_fs_denied_proc() {
         let mut app = test_router();
         let req = Request::builder()
-            .uri("/api/preview/frames/nonexistent-session/frame_0001.png")
+            .uri("/api/fs/browse?path=/proc")
             .body(Body::empty())
             .unwrap();
 
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
     }
 
+    #[cfg(unix)]
     #[tokio::test]
-    async fn test_process_frame_session_not_found() {
+    async fn test_browse_fs_denied_sys() {
         let mut app = test_router();
-        let body = serde_json::json!({
-            "preview_id": "nonexistent-session",
-            "frame_index": 0,
-            "workflow": {"nodes": [], "connections": []}
-        });
-
         let req = Request::builder()
-            .method("POST")
-            .uri("/api/preview/process")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .uri("/api/fs/browse?path=/sys")
+            .body(Body::empty())
             .unwrap();
 
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
     }
 
     #[tokio::test]
-    async fn test_create_preset() {
+    async fn test_browse_fs_nonexistent() {
         let mut app = test_router();
-        let body = serde_json::json!({
-            "name": "My Custom Preset",
-            "description": "Custom workflow",
-            "workflow": {"nodes": [], "connections": []}
-        });
-
         let req = Request::builder()
-            .method("POST")
-            .uri("/api/presets")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .uri("/api/fs/browse?path=/nonexistent_path_xyz_123")
+            .body(Body::empty())
             .unwrap();
 
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.status(), StatusCode::OK);
 
         let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        assert!(json["id"].is_string());
-        assert_eq!(json["name"], "My Custom Preset");
+        let entries: Vec<FsEntry> = serde_json::from_slice(&body).unwrap();
+        assert!(entries.is_empty());
     }
 
-    fn fs_test_state(models_dir: PathBuf) -> AppState {
+    fn workflow_test_state(workflows_dir: PathBuf) -> AppState {
         let mut node_registry = NodeRegistry::new();
         node_registry.register("test_source", |_params| {
             Ok(Box::new(TestNode {
@@ -4818,10 +13177,13 @@ mod tests {
         let model_registry = ModelRegistry::with_builtin_models(test_models_dir());
         let config = AppConfig {
             paths: crate::config::PathsConfig {
-                models_dir,
+                models_dir: test_models_dir(),
+                extra_models_dirs: Vec::new(),
                 trt_cache_dir: temp_path("trt_cache"),
                 presets_dir: temp_path("videnoa-test-presets-nonexistent"),
-                workflows_dir: temp_path("videnoa-test-workflows-nonexistent"),
+                workflows_dir,
+                plugins_dir: temp_path("videnoa-test-plugins-nonexistent"),
+                media_roots: Vec::new(),
             },
             ..AppConfig::default()
         };
@@ -4835,445 +13197,857 @@ mod tests {
         )
     }
 
-    // Assembles a minimal ONNX protobuf (Add node, 2 float32[1,3] inputs, 1 output)
-    // using raw prost wire encoding since server crate can't access videnoa's generated proto types.
-    fn build_test_onnx_bytes() -> Vec<u8> {
-        use prost::encoding::*;
-
-        fn embed(field: u32, msg: &[u8], buf: &mut Vec<u8>) {
-            encode_key(field, WireType::LengthDelimited, buf);
-            encode_varint(msg.len() as u64, buf);
-            buf.extend_from_slice(msg);
-        }
-
-        fn encode_value_info(name: &str) -> Vec<u8> {
-            let mut dim1 = Vec::new();
-            int64::encode(1, &1i64, &mut dim1);
-            let mut dim3 = Vec::new();
-            int64::encode(1, &3i64, &mut dim3);
-
-            let mut shape = Vec::new();
-            embed(1, &dim1, &mut shape);
-            embed(1, &dim3, &mut shape);
-
-            let mut tensor = Vec::new();
-            int32::encode(1, &1i32, &mut tensor); // elem_type = FLOAT
-            embed(2, &shape, &mut tensor);
-
-            let mut type_proto = Vec::new();
-            embed(1, &tensor, &mut type_proto); // oneof tensor_type
-
-            let mut vi = Vec::new();
-            string::encode(1, &name.to_string(), &mut vi);
-            embed(2, &type_proto, &mut vi);
-            vi
-        }
-
-        let vi_a = encode_value_info("A");
-        let vi_b = encode_value_info("B");
-        let vi_c = encode_value_info("C");
-
-        let mut node = Vec::new();
-        string::encode(1, &"A".to_string(), &mut node);
-        string::encode(1, &"B".to_string(), &mut node);
-        string::encode(2, &"C".to_string(), &mut node);
-        string::encode(3, &"add_0".to_string(), &mut node);
-        string::encode(4, &"Add".to_string(), &mut node);
-
-        let mut init = Vec::new();
-        let mut dims_packed = Vec::new();
-        encode_varint(1, &mut dims_packed);
-        encode_varint(3, &mut dims_packed);
-        embed(1, &dims_packed, &mut init); // dims: [1, 3]
-        int32::encode(2, &1i32, &mut init); // data_type = FLOAT
-        string::encode(8, &"B".to_string(), &mut init);
-
-        let mut opset = Vec::new();
-        int64::encode(2, &17i64, &mut opset);
-
-        let mut graph = Vec::new();
-        embed(1, &node, &mut graph);
-        embed(5, &init, &mut graph);
-        embed(11, &vi_a, &mut graph);
-        embed(11, &vi_b, &mut graph);
-        embed(12, &vi_c, &mut graph);
-
-        let mut buf = Vec::new();
-        int64::encode(1, &8i64, &mut buf);
-        string::encode(2, &"test".to_string(), &mut buf);
-        string::encode(3, &"1.0".to_string(), &mut buf);
-        embed(7, &graph, &mut buf);
-        embed(8, &opset, &mut buf);
-
-        buf
-    }
-
     #[tokio::test]
-    async fn test_inspect_model_valid() {
-        let dir = std::env::temp_dir().join(format!("videnoa-inspect-test-{}", std::process::id()));
-        std::fs::create_dir_all(&dir).unwrap();
-        std::fs::write(dir.join("test_model.onnx"), build_test_onnx_bytes()).unwrap();
-
-        let state = fs_test_state(dir.clone());
+    async fn test_audit_log_records_mutating_requests() {
+        let dir = unique_temp_dir("videnoa-wf-audit");
+        let state = workflow_test_state(dir.clone());
         let mut app = app_router(state);
 
+        let body = serde_json::json!({
+            "name": "Audited",
+            "description": "",
+            "workflow": {"nodes": [], "connections": []}
+        });
         let req = Request::builder()
-            .uri("/api/models/test_model.onnx/inspect")
-            .body(Body::empty())
+            .method("POST")
+            .uri("/api/workflows")
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer s3cr3t")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
             .unwrap();
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::OK);
-
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        assert_eq!(json["ir_version"], 8);
-        assert_eq!(json["opset_version"], 17);
-        assert_eq!(json["producer_name"], "test");
-        assert_eq!(json["op_count"], 1);
-        assert_eq!(json["param_count"], 3);
-        assert!(json["inputs"].as_array().unwrap().len() >= 2);
-        assert!(json["outputs"].as_array().unwrap().len() >= 1);
-        assert_eq!(json["nodes"][0]["op_type"], "Add");
+        let created: WorkflowEntry = serde_json::from_slice(&resp_body).unwrap();
+
+        for (method, uri) in [
+            ("GET", "/api/workflows".to_string()),
+            ("DELETE", format!("/api/workflows/{}", created.filename)),
+            ("DELETE", "/api/models/missing.onnx".to_string()),
+        ] {
+            let req = Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap();
+            send_request(&mut app, req).await;
+        }
+
+        let audit = |app: &mut Router, query: &str| {
+            let req = Request::builder()
+                .uri(format!("/api/audit{query}"))
+                .body(Body::empty())
+                .unwrap();
+            let mut app = app.clone();
+            async move {
+                let resp = send_request(&mut app, req).await;
+                assert_eq!(resp.status(), StatusCode::OK);
+                let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                serde_json::from_slice::<Vec<audit::AuditEntry>>(&body).unwrap()
+            }
+        };
+        let entries = audit(&mut app, "").await;
+        let summary = entries
+            .iter()
+            .map(|entry| (entry.action.as_str(), entry.status))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("model.delete", 404),
+                ("workflow.delete", 204),
+                ("workflow.save", 201),
+            ]
+        );
+        assert_eq!(entries[1].actor, audit::ANONYMOUS_ACTOR);
+        assert_eq!(
+            entries[1].path,
+            format!("/api/workflows/{}", created.filename)
+        );
+        assert!(entries[2].actor.starts_with("token:"));
+        assert!(!entries[2].actor.contains("s3cr3t"));
+
+        let saves = audit(&mut app, "?action=workflow.save").await;
+        assert_eq!(saves, vec![entries[2].clone()]);
+        let page = audit(&mut app, &format!("?limit=1&before={}", entries[0].seq)).await;
+        assert_eq!(page, vec![entries[1].clone()]);
 
         let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[tokio::test]
-    async fn test_inspect_model_path_traversal() {
-        let dir = std::env::temp_dir().join(format!("videnoa-inspect-trav-{}", std::process::id()));
+    async fn test_list_workflows_empty() {
+        let dir = std::env::temp_dir().join(format!("videnoa-wf-empty-{}", std::process::id()));
         std::fs::create_dir_all(&dir).unwrap();
 
-        let state = fs_test_state(dir.clone());
+        let state = workflow_test_state(dir.clone());
         let mut app = app_router(state);
 
         let req = Request::builder()
-            .uri("/api/models/..%2F..%2Fetc%2Fpasswd/inspect")
+            .uri("/api/workflows")
             .body(Body::empty())
             .unwrap();
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let entries: Vec<WorkflowEntry> = serde_json::from_slice(&body).unwrap();
+        assert!(entries.is_empty());
 
         let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[tokio::test]
-    async fn test_inspect_model_not_found() {
-        let dir = std::env::temp_dir().join(format!("videnoa-inspect-nf-{}", std::process::id()));
-        std::fs::create_dir_all(&dir).unwrap();
+    async fn test_save_and_list_workflows() {
+        let dir = std::env::temp_dir().join(format!("videnoa-wf-save-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
 
-        let state = fs_test_state(dir.clone());
+        let state = workflow_test_state(dir.clone());
         let mut app = app_router(state);
 
+        let body = serde_json::json!({
+            "name": "My Workflow",
+            "description": "Test workflow",
+            "workflow": {
+                "nodes": [],
+                "connections": [],
+                "interface": {
+                    "inputs": [{"name": "video", "type": "VideoFrames"}]
+                }
+            }
+        });
+
         let req = Request::builder()
-            .uri("/api/models/nonexistent.onnx/inspect")
+            .method("POST")
+            .uri("/api/workflows")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let created: WorkflowEntry = serde_json::from_slice(&resp_body).unwrap();
+        assert_eq!(created.name, "My Workflow");
+        assert!(created.has_interface);
+        assert!(created.filename.ends_with(".json"));
+
+        let req = Request::builder()
+            .uri("/api/workflows")
             .body(Body::empty())
             .unwrap();
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let entries: Vec<WorkflowEntry> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].name, "My Workflow");
+        assert_eq!(entries[0].description, "Test workflow");
+        assert!(entries[0].has_interface);
 
         let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[tokio::test]
-    async fn test_fs_list_models_dir() {
-        let dir = std::env::temp_dir().join(format!("videnoa-fs-test-{}", std::process::id()));
-        std::fs::create_dir_all(&dir).unwrap();
-        std::fs::write(dir.join("model_a.onnx"), b"fake").unwrap();
-        std::fs::write(dir.join("model_b.onnx"), b"fake").unwrap();
-        std::fs::create_dir_all(dir.join("subdir")).unwrap();
-        std::fs::write(dir.join(".hidden"), b"secret").unwrap();
+    async fn test_save_and_delete_workflow() {
+        let dir = std::env::temp_dir().join(format!("videnoa-wf-del-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
 
-        let state = fs_test_state(dir.clone());
+        let state = workflow_test_state(dir.clone());
         let mut app = app_router(state);
 
+        let body = serde_json::json!({
+            "name": "Deletable",
+            "description": "Will be deleted",
+            "workflow": {"nodes": [], "connections": []}
+        });
+
         let req = Request::builder()
-            .uri("/api/fs/list?base=models")
-            .body(Body::empty())
+            .method("POST")
+            .uri("/api/workflows")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
             .unwrap();
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.status(), StatusCode::CREATED);
 
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let entries: Vec<FsEntry> = serde_json::from_slice(&body).unwrap();
-
-        assert!(entries[0].is_dir, "directories should come first");
-        assert_eq!(entries[0].name, "subdir");
+        let created: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
+        let filename = created["filename"].as_str().unwrap();
 
-        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
-        assert!(names.contains(&"model_a.onnx"));
-        assert!(names.contains(&"model_b.onnx"));
-        assert!(!names.contains(&".hidden"), "hidden files must be excluded");
+        let req = Request::builder()
+            .method("DELETE")
+            .uri(format!("/api/workflows/{filename}"))
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
 
-        for entry in &entries {
-            assert!(entry.path.starts_with("models/"));
-        }
+        let req = Request::builder()
+            .uri("/api/workflows")
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let entries: Vec<WorkflowEntry> = serde_json::from_slice(&body).unwrap();
+        assert!(entries.is_empty());
 
         let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[tokio::test]
-    async fn test_fs_list_with_prefix() {
-        let dir = std::env::temp_dir().join(format!("videnoa-fs-prefix-{}", std::process::id()));
-        std::fs::create_dir_all(&dir).unwrap();
-        std::fs::write(dir.join("RealESRGAN_x4.onnx"), b"fake").unwrap();
-        std::fs::write(dir.join("RealCUGAN_x2.onnx"), b"fake").unwrap();
-        std::fs::write(dir.join("RIFE_v4.onnx"), b"fake").unwrap();
-
-        let state = fs_test_state(dir.clone());
-        let mut app = app_router(state);
+    async fn test_workflow_bundle_export_and_import() {
+        let source_dir =
+            std::env::temp_dir().join(format!("videnoa-wf-bundle-src-{}", std::process::id()));
+        let target_dir =
+            std::env::temp_dir().join(format!("videnoa-wf-bundle-dst-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&source_dir);
+        let _ = std::fs::remove_dir_all(&target_dir);
+
+        let mut app = app_router(workflow_test_state(source_dir.clone()));
+        let sub = serde_json::json!({
+            "name": "sub",
+            "description": "",
+            "workflow": {"nodes": [
+                {"id": "fi", "node_type": "FrameInterpolation", "params": {"model_path": "models/custom-rife.onnx"}}
+            ], "connections": []}
+        });
+        let main = serde_json::json!({
+            "name": "main",
+            "description": "uses sub",
+            "workflow": {"nodes": [
+                {"id": "sr", "node_type": "SuperResolution", "params": {"model_path": "models/RealESRGAN_x4plus_anime_6B.onnx"}},
+                {"id": "nested", "node_type": "Workflow", "params": {"workflow_path": "/elsewhere/sub.json"}}
+            ], "connections": []}
+        });
+        for body in [sub, main] {
+            let (status, _) = post_json(&mut app, "/api/workflows", None, body).await;
+            assert_eq!(status, StatusCode::CREATED);
+        }
 
         let req = Request::builder()
-            .uri("/api/fs/list?base=models&prefix=Real")
+            .uri("/api/workflows/main.json/export")
             .body(Body::empty())
             .unwrap();
         let resp = send_request(&mut app, req).await;
         assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()["content-type"], "application/zip");
+        let bundle = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bundle.to_vec())).unwrap();
+        let manifest: serde_json::Value =
+            serde_json::from_reader(archive.by_name("manifest.json").unwrap()).unwrap();
+        assert_eq!(manifest["workflow"], "main.json");
+        assert_eq!(manifest["sub_workflows"], serde_json::json!(["sub.json"]));
+        let models = manifest["models"].as_array().unwrap();
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0]["name"], "RealESRGAN_x4plus_anime_6B");
+        assert!(models[0]["url"].is_string());
+        assert_eq!(models[1]["filename"], "custom-rife.onnx");
+        let bundled_main: serde_json::Value =
+            serde_json::from_reader(archive.by_name("workflows/main.json").unwrap()).unwrap();
+        assert_eq!(
+            bundled_main["workflow"]["nodes"][1]["params"]["workflow_path"],
+            "sub.json"
+        );
 
+        let mut app = app_router(workflow_test_state(target_dir.clone()));
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/workflows/import?download_models=false")
+            .body(Body::from(bundle))
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
         let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let entries: Vec<FsEntry> = serde_json::from_slice(&body).unwrap();
+        let imported: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let filenames: Vec<&str> = imported["workflows"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["filename"].as_str().unwrap())
+            .collect();
+        assert_eq!(filenames, vec!["main.json", "sub.json"]);
+        assert_eq!(imported["models"][1]["status"], "missing");
+        assert!(target_dir.join("sub.json").is_file());
 
-        assert_eq!(entries.len(), 2);
-        assert!(entries.iter().all(|e| e.name.starts_with("Real")));
+        let req = Request::builder()
+            .method("POST")
+            .uri("/api/workflows/import")
+            .body(Body::from("not a zip"))
+            .unwrap();
+        assert_eq!(
+            send_request(&mut app, req).await.status(),
+            StatusCode::BAD_REQUEST
+        );
+        let (status, _) = get_json(&mut app, "/api/workflows/absent.json/export").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
 
-        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::remove_dir_all(&source_dir);
+        let _ = std::fs::remove_dir_all(&target_dir);
     }
 
     #[tokio::test]
-    async fn test_fs_list_unknown_base() {
-        let mut app = test_router();
+    async fn test_workflow_versions_list_and_restore() {
+        let dir = std::env::temp_dir().join(format!("videnoa-wf-versions-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let state = workflow_test_state(dir.clone());
+        let mut app = app_router(state);
+
+        let save = |description: &str| {
+            serde_json::json!({
+                "name": "history",
+                "description": description,
+                "workflow": {"nodes": [], "connections": []}
+            })
+        };
+        let (status, _) = post_json(&mut app, "/api/workflows", None, save("first")).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (_, versions) = get_json(&mut app, "/api/workflows/history.json/versions").await;
+        assert!(versions.as_array().unwrap().is_empty());
+
+        let (status, _) = post_json(&mut app, "/api/workflows", None, save("second")).await;
+        assert_eq!(status, StatusCode::CREATED);
+        let (status, versions) = get_json(&mut app, "/api/workflows/history.json/versions").await;
+        assert_eq!(status, StatusCode::OK);
+        let versions = versions.as_array().unwrap().clone();
+        assert_eq!(versions.len(), 1);
+        let version = versions[0]["version"].as_i64().unwrap();
+
+        let (status, doc) = get_json(
+            &mut app,
+            &format!("/api/workflows/history.json/versions/{version}"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(doc["description"], "first");
+
+        let (status, restored) = post_json(
+            &mut app,
+            &format!("/api/workflows/history.json/versions/{version}/restore"),
+            None,
+            serde_json::json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(restored["description"], "first");
+        // The overwritten "second" save is now a version as well.
+        let (_, versions) = get_json(&mut app, "/api/workflows/history.json/versions").await;
+        assert_eq!(versions.as_array().unwrap().len(), 2);
+
         let req = Request::builder()
-            .uri("/api/fs/list?base=../../etc")
+            .method("DELETE")
+            .uri("/api/workflows/history.json")
             .body(Body::empty())
             .unwrap();
+        assert_eq!(
+            send_request(&mut app, req).await.status(),
+            StatusCode::NO_CONTENT
+        );
+        let (_, versions) = get_json(&mut app, "/api/workflows/history.json/versions").await;
+        let latest = versions[0]["version"].as_i64().unwrap();
+        let (status, restored) = post_json(
+            &mut app,
+            &format!("/api/workflows/history.json/versions/{latest}/restore"),
+            None,
+            serde_json::json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(restored["description"], "first");
+        assert!(dir.join("history.json").is_file());
 
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        let (status, _) = get_json(&mut app, "/api/workflows/history.json/versions/1").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = get_json(&mut app, "/api/workflows/..%2Fescape.json/versions").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let _ = std::fs::remove_dir_all(&dir);
     }
 
     #[tokio::test]
-    async fn test_fs_list_traversal_blocked() {
-        let dir = std::env::temp_dir().join(format!("videnoa-fs-trav-{}", std::process::id()));
-        std::fs::create_dir_all(&dir).unwrap();
+    async fn test_save_workflow_path_traversal() {
+        let dir = std::env::temp_dir().join(format!("videnoa-wf-trav-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
 
-        let state = fs_test_state(dir.clone());
+        let state = workflow_test_state(dir.clone());
         let mut app = app_router(state);
 
+        let body = serde_json::json!({
+            "name": "../etc/passwd",
+            "description": "malicious",
+            "workflow": {"nodes": [], "connections": []}
+        });
+
         let req = Request::builder()
-            .uri("/api/fs/list?base=models&prefix=../../../etc/passwd")
-            .body(Body::empty())
+            .method("POST")
+            .uri("/api/workflows")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
             .unwrap();
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
 
         let _ = std::fs::remove_dir_all(&dir);
     }
 
-    #[tokio::test]
-    async fn test_fs_list_nonexistent_base_dir() {
-        let state = fs_test_state(temp_path("videnoa-nonexistent-dir-xyz"));
-        let mut app = app_router(state);
+    #[tokio::test]
+    async fn test_probe_media_sandboxed_to_media_roots() {
+        let state = test_state();
+        let mut app = app_router(state.clone());
+        let root = unique_temp_dir("videnoa-probe-root");
+        let other = unique_temp_dir("videnoa-probe-other");
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::create_dir_all(&other).unwrap();
+        let inside = root.join("notes.txt");
+        let outside = other.join("secret.mkv");
+        std::fs::write(&inside, b"not a video").unwrap();
+        std::fs::write(&outside, b"").unwrap();
+
+        let probe = |path: &StdPath| {
+            Request::builder()
+                .method("POST")
+                .uri("/api/probe")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    serde_json::to_vec(&serde_json::json!({"path": path})).unwrap(),
+                ))
+                .unwrap()
+        };
+
+        // No media roots configured: the endpoint is disabled.
+        let resp = send_request(&mut app, probe(&inside)).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        state.inner.config.write().await.paths.media_roots = vec![root.clone()];
+        let resp = send_request(&mut app, probe(&outside)).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        let resp = send_request(&mut app, probe(&root.join("missing.mkv"))).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let resp = send_request(&mut app, probe(&other.join("missing.mkv"))).await;
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        let resp = send_request(&mut app, probe(&root)).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let resp = send_request(&mut app, probe(&inside)).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        std::fs::remove_dir_all(&root).ok();
+        std::fs::remove_dir_all(&other).ok();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_registered_input_handles_resolve_in_job_params() {
+        let data_dir = test_data_dir();
+        let state = test_state_with_data_dir(data_dir.clone());
+        let mut app = app_router(state.clone());
+        let source_dir = unique_temp_dir("videnoa-input-source");
+        std::fs::create_dir_all(&source_dir).unwrap();
+        let source = source_dir.join("ep01.mkv");
+        std::fs::write(&source, b"episode").unwrap();
+
+        let (status, json) = post_json(
+            &mut app,
+            "/api/inputs/register",
+            None,
+            serde_json::json!({"path": source}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let registered: RegisteredInput = serde_json::from_value(json).unwrap();
+        assert!(registered.handle.starts_with("input:"));
+        assert_eq!(registered.name, "ep01.mkv");
+        assert_eq!(registered.path, source.canonicalize().unwrap());
+        assert_eq!(registered.size_bytes, 7);
+        assert!(!registered.managed);
+
+        let resp = send_request(
+            &mut app,
+            Request::builder()
+                .method("POST")
+                .uri("/api/inputs/register?name=clip.mkv")
+                .header("content-type", "application/octet-stream")
+                .body(Body::from(&b"uploaded clip"[..]))
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let uploaded: RegisteredInput = serde_json::from_slice(&body).unwrap();
+        assert!(uploaded.managed);
+        assert!(uploaded.path.starts_with(data_dir.join("inputs")));
+        assert_eq!(std::fs::read(&uploaded.path).unwrap(), b"uploaded clip");
+
+        let (status, json) = post_json(
+            &mut app,
+            "/api/jobs",
+            None,
+            serde_json::json!({
+                "workflow": valid_workflow_json(),
+                "params": {"input": registered.handle, "extra": uploaded.handle}
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let job_id = json["id"].as_str().unwrap().to_string();
+        let params = state
+            .inner
+            .jobs
+            .get(&job_id)
+            .unwrap()
+            .params
+            .clone()
+            .unwrap();
+        assert_eq!(params["input"], registered.path.to_string_lossy().as_ref());
+        assert_eq!(params["extra"], uploaded.path.to_string_lossy().as_ref());
+        wait_for_job_terminal_status(&state, &job_id).await;
+
+        // Uploads survive a restart; registered paths do not.
+        state.inner.registered_inputs.clear();
+        assert_eq!(
+            resolve_input_handle(&state, &uploaded.handle).unwrap(),
+            Some(uploaded.path.clone())
+        );
+        assert!(resolve_input_handle(&state, &registered.handle).is_err());
+        assert_eq!(
+            resolve_input_handle(&state, "input:../secrets").unwrap(),
+            None
+        );
+
+        let (status, _) = post_json(
+            &mut app,
+            "/api/jobs",
+            None,
+            serde_json::json!({
+                "workflow": valid_workflow_json(),
+                "params": {"input": registered.handle}
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
 
-        let req = Request::builder()
-            .uri("/api/fs/list?base=models")
-            .body(Body::empty())
-            .unwrap();
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::OK);
+        let (status, _) = post_json(
+            &mut app,
+            "/api/inputs/register",
+            None,
+            serde_json::json!({"path": source_dir}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let resp = send_request(
+            &mut app,
+            Request::builder()
+                .method("POST")
+                .uri("/api/inputs/register?name=..%2Fescape.mkv")
+                .body(Body::from(&b"x"[..]))
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
 
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let entries: Vec<FsEntry> = serde_json::from_slice(&body).unwrap();
-        assert!(entries.is_empty());
+        std::fs::remove_dir_all(&source_dir).ok();
+        std::fs::remove_dir_all(&data_dir).ok();
     }
 
     #[tokio::test]
-    async fn test_fs_list_default_base_is_models() {
-        let dir = std::env::temp_dir().join(format!("videnoa-fs-default-{}", std::process::id()));
-        std::fs::create_dir_all(&dir).unwrap();
-        std::fs::write(dir.join("test.onnx"), b"fake").unwrap();
-
-        let state = fs_test_state(dir.clone());
+    async fn test_export_workflow_vapoursynth() {
+        let mut node_registry = NodeRegistry::new();
+        register_all_nodes(&mut node_registry);
+        let state = AppState::new(
+            node_registry,
+            ModelRegistry::with_builtin_models(test_models_dir()),
+            DashMap::new(),
+            AppConfig::default(),
+            test_config_path(),
+            test_data_dir(),
+        );
         let mut app = app_router(state);
 
-        let req = Request::builder()
-            .uri("/api/fs/list")
-            .body(Body::empty())
-            .unwrap();
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::OK);
+        let workflow = |sr_params: serde_json::Value| {
+            serde_json::json!({
+                "nodes": [
+                    {"id": "wi", "node_type": "WorkflowInput", "params": {
+                        "ports": [{"name": "input", "port_type": "Path"}]
+                    }},
+                    {"id": "input", "node_type": "VideoInput", "params": {}},
+                    {"id": "sr", "node_type": "SuperResolution", "params": sr_params},
+                    {"id": "output", "node_type": "VideoOutput", "params": {"output_path": "/media/out.mkv"}}
+                ],
+                "connections": [
+                    {"from_node": "wi", "from_port": "input", "to_node": "input", "to_port": "path", "port_type": "Path"},
+                    {"from_node": "input", "from_port": "frames", "to_node": "sr", "to_port": "frames", "port_type": "VideoFrames"},
+                    {"from_node": "sr", "from_port": "frames", "to_node": "output", "to_port": "frames", "port_type": "VideoFrames"},
+                    {"from_node": "input", "from_port": "source_path", "to_node": "output", "to_port": "source_path", "port_type": "Path"}
+                ]
+            })
+        };
+        let export = |body: serde_json::Value| {
+            Request::builder()
+                .method("POST")
+                .uri("/api/workflows/export/vapoursynth")
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap()
+        };
 
+        let resp = send_request(
+            &mut app,
+            export(serde_json::json!({
+                "workflow": workflow(serde_json::json!({"model_path": "/models/x2.onnx", "scale": 2})),
+                "params": {"input": "/media/ep01.mkv"}
+            })),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
         let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let entries: Vec<FsEntry> = serde_json::from_slice(&body).unwrap();
-        assert_eq!(entries.len(), 1);
-        assert_eq!(entries[0].name, "test.onnx");
-        assert_eq!(entries[0].path, "models/test.onnx");
-
-        let _ = std::fs::remove_dir_all(&dir);
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let script = json["script"].as_str().unwrap();
+        assert!(script.contains("core.lsmas.LWLibavSource(\"/media/ep01.mkv\")"));
+        assert!(script.contains("network_path=\"/models/x2.onnx\""));
+
+        let resp = send_request(
+            &mut app,
+            export(serde_json::json!({
+                "workflow": workflow(serde_json::json!({"model_path": "auto"})),
+                "params": {"input": "/media/ep01.mkv"}
+            })),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
     }
 
-    #[tokio::test]
-    async fn test_browse_fs_tmp() {
-        let mut app = test_router();
-        let req = Request::builder()
-            .uri("/api/fs/browse?path=/tmp")
-            .body(Body::empty())
-            .unwrap();
-
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::OK);
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_create_job_video_pipeline_with_params() {
+        let mut node_registry = NodeRegistry::new();
+        register_all_nodes(&mut node_registry);
+        let model_registry = ModelRegistry::with_builtin_models(test_models_dir());
+        let state = AppState::new(
+            node_registry,
+            model_registry,
+            DashMap::new(),
+            AppConfig::default(),
+            test_config_path(),
+            test_data_dir(),
+        );
+        let mut app = app_router(state.clone());
 
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let _: Vec<FsEntry> = serde_json::from_slice(&body).unwrap();
-    }
+        let body = serde_json::json!({
+            "workflow": {
+                "nodes": [
+                    {"id": "input", "node_type": "VideoInput", "params": {"path": temp_path_str("nonexistent.mp4")}},
+                    {"id": "sr", "node_type": "SuperResolution", "params": {"model_path": temp_path_str("model.onnx"), "scale": 2, "tile_size": 0}},
+                    {"id": "output", "node_type": "VideoOutput", "params": {
+                        "output_path": temp_path_str("out.mp4"), "codec": "libx265", "crf": 18,
+                        "pixel_format": "yuv420p10le", "width": 1920, "height": 1080, "fps": "24"
+                    }}
+                ],
+                "connections": [
+                    {"from_node": "input", "from_port": "frames", "to_node": "sr", "to_port": "frames", "port_type": "VideoFrames"},
+                    {"from_node": "sr", "from_port": "frames", "to_node": "output", "to_port": "frames", "port_type": "VideoFrames"},
+                    {"from_node": "input", "from_port": "source_path", "to_node": "output", "to_port": "source_path", "port_type": "Path"}
+                ]
+            },
+            "params": {"greeting": "hello"}
+        });
 
-    #[tokio::test]
-    async fn test_browse_fs_tilde() {
-        let mut app = test_router();
         let req = Request::builder()
-            .uri("/api/fs/browse?path=~")
-            .body(Body::empty())
+            .method("POST")
+            .uri("/api/jobs")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
             .unwrap();
 
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.status(), StatusCode::CREATED);
 
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let entries: Vec<FsEntry> = serde_json::from_slice(&body).unwrap();
-        assert!(!entries.is_empty());
+        let create_json: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
+        let job_id = create_json["id"].as_str().unwrap().to_string();
 
-        if let Some(first) = entries.first() {
-            assert!(first.path.starts_with('/'));
-        }
+        let status = wait_for_job_terminal_status(&state, &job_id).await;
+        let job = state.inner.jobs.get(&job_id).unwrap();
+        assert_eq!(
+            status,
+            JobStatus::Failed,
+            "expected Failed, got {:?}",
+            job.status
+        );
+        assert_eq!(
+            job.params
+                .as_ref()
+                .and_then(|params| params.get("greeting")),
+            Some(&serde_json::json!("hello")),
+            "job metadata should preserve submitted params"
+        );
+        assert!(
+            !job.error
+                .as_deref()
+                .unwrap_or("")
+                .contains("do not support workflow parameters"),
+            "video workflow should no longer fail on params guard, got: {:?}",
+            job.error,
+        );
     }
 
-    #[cfg(unix)]
     #[tokio::test]
-    async fn test_browse_fs_denied_proc() {
-        let mut app = test_router();
-        let req = Request::builder()
-            .uri("/api/fs/browse?path=/proc")
-            .body(Body::empty())
-            .unwrap();
-
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
-    }
+    async fn test_lint_workflow_reports_warnings_without_rejecting() {
+        let mut node_registry = NodeRegistry::new();
+        register_all_nodes(&mut node_registry);
+        let state = AppState::new(
+            node_registry,
+            ModelRegistry::with_builtin_models(test_models_dir()),
+            DashMap::new(),
+            AppConfig::default(),
+            test_config_path(),
+            test_data_dir(),
+        );
+        let mut app = app_router(state);
 
-    #[cfg(unix)]
-    #[tokio::test]
-    async fn test_browse_fs_denied_sys() {
-        let mut app = test_router();
-        let req = Request::builder()
-            .uri("/api/fs/browse?path=/sys")
-            .body(Body::empty())
-            .unwrap();
+        let (status, body) = post_json(
+            &mut app,
+            "/api/workflows/lint",
+            None,
+            serde_json::json!({"workflow": {
+                "nodes": [
+                    {"id": "input", "node_type": "VideoInput", "params": {"path": "/media/in.mkv"}},
+                    {"id": "output", "node_type": "VideoOutput", "params": {"output_path": "/media/out.mkv", "crf": 0}},
+                    {"id": "orphan", "node_type": "Constant", "params": {"type": "Int", "value": 1}}
+                ],
+                "connections": [
+                    {"from_node": "input", "from_port": "frames", "to_node": "output", "to_port": "frames", "port_type": "VideoFrames"},
+                    {"from_node": "input", "from_port": "source_path", "to_node": "output", "to_port": "source_path", "port_type": "Path"}
+                ]
+            }}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let mut codes: Vec<(&str, &str)> = body["warnings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|w| (w["code"].as_str().unwrap(), w["node_id"].as_str().unwrap()))
+            .collect();
+        codes.sort();
+        assert_eq!(
+            codes,
+            vec![("dead_node", "orphan"), ("suspicious_param", "output")]
+        );
 
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+        let (status, _) = post_json(
+            &mut app,
+            "/api/workflows/lint",
+            None,
+            serde_json::json!({"workflow": {"nodes": [
+                {"id": "x", "node_type": "NoSuchNode", "params": {}}
+            ], "connections": []}}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
     }
 
     #[tokio::test]
-    async fn test_browse_fs_nonexistent() {
+    async fn test_update_config_invalid_json() {
         let mut app = test_router();
+
         let req = Request::builder()
-            .uri("/api/fs/browse?path=/nonexistent_path_xyz_123")
-            .body(Body::empty())
+            .method("PUT")
+            .uri("/api/config")
+            .header("content-type", "application/json")
+            .body(Body::from(b"not valid json".to_vec()))
             .unwrap();
 
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::OK);
-
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let entries: Vec<FsEntry> = serde_json::from_slice(&body).unwrap();
-        assert!(entries.is_empty());
-    }
-
-    fn workflow_test_state(workflows_dir: PathBuf) -> AppState {
-        let mut node_registry = NodeRegistry::new();
-        node_registry.register("test_source", |_params| {
-            Ok(Box::new(TestNode {
-                node_type: "test_source".to_string(),
-                inputs: vec![],
-                outputs: vec![],
-            }))
-        });
-        let model_registry = ModelRegistry::with_builtin_models(test_models_dir());
-        let config = AppConfig {
-            paths: crate::config::PathsConfig {
-                models_dir: test_models_dir(),
-                trt_cache_dir: temp_path("trt_cache"),
-                presets_dir: temp_path("videnoa-test-presets-nonexistent"),
-                workflows_dir,
-            },
-            ..AppConfig::default()
-        };
-        AppState::new(
-            node_registry,
-            model_registry,
-            DashMap::new(),
-            config,
-            test_config_path(),
-            test_data_dir(),
-        )
+        assert!(
+            resp.status().is_client_error(),
+            "expected client error, got {}",
+            resp.status()
+        );
     }
 
     #[tokio::test]
-    async fn test_list_workflows_empty() {
-        let dir = std::env::temp_dir().join(format!("videnoa-wf-empty-{}", std::process::id()));
-        std::fs::create_dir_all(&dir).unwrap();
-
-        let state = workflow_test_state(dir.clone());
-        let mut app = app_router(state);
+    async fn test_update_config_roundtrip() {
+        let mut app = test_router();
 
         let req = Request::builder()
-            .uri("/api/workflows")
+            .uri("/api/config")
             .body(Body::empty())
             .unwrap();
         let resp = send_request(&mut app, req).await;
         assert_eq!(resp.status(), StatusCode::OK);
-
         let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let entries: Vec<WorkflowEntry> = serde_json::from_slice(&body).unwrap();
-        assert!(entries.is_empty());
+        let mut config: serde_json::Value = serde_json::from_slice(&body).unwrap();
 
-        let _ = std::fs::remove_dir_all(&dir);
+        let custom_models = temp_path_str("custom_models");
+        config["paths"]["models_dir"] = serde_json::json!(custom_models);
+
+        let req = Request::builder()
+            .method("PUT")
+            .uri("/api/config")
+            .header("content-type", "application/json")
+            .body(Body::from(serde_json::to_vec(&config).unwrap()))
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let req = Request::builder()
+            .uri("/api/config")
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let updated: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(updated["paths"]["models_dir"], custom_models);
     }
 
     #[tokio::test]
-    async fn test_save_and_list_workflows() {
-        let dir = std::env::temp_dir().join(format!("videnoa-wf-save-{}", std::process::id()));
-        let _ = std::fs::remove_dir_all(&dir);
+    async fn test_relative_workflows_resolution_uses_current_dir() {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let workflows_rel = PathBuf::from(format!(
+            "target/videnoa-wf-cwd-{}-{timestamp}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&workflows_rel);
 
-        let state = workflow_test_state(dir.clone());
+        let state = workflow_test_state(workflows_rel.clone());
         let mut app = app_router(state);
 
         let body = serde_json::json!({
-            "name": "My Workflow",
-            "description": "Test workflow",
-            "workflow": {
-                "nodes": [],
-                "connections": [],
-                "interface": {
-                    "inputs": [{"name": "video", "type": "VideoFrames"}]
-                }
-            }
+            "name": "Resolved Workflow",
+            "description": "Tests current-dir resolution",
+            "workflow": {"nodes": [], "connections": []}
         });
 
         let req = Request::builder()
@@ -5285,865 +14059,1711 @@ mod tests {
         let resp = send_request(&mut app, req).await;
         assert_eq!(resp.status(), StatusCode::CREATED);
 
-        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
+        let expected_file = workflows_rel.join("Resolved Workflow.json");
+        assert!(
+            expected_file.exists(),
+            "Workflow file should be at {}",
+            expected_file.display()
+        );
+
+        let _ = std::fs::remove_dir_all(&workflows_rel);
+    }
+
+    #[tokio::test]
+    async fn test_get_memory_endpoints_are_replaced_by_performance_routes() {
+        let mut app = test_router();
+
+        for endpoint in [
+            "/api/performance/current",
+            "/api/performance/overview",
+            "/api/performance/export",
+            "/api/performance/capabilities",
+        ] {
+            let req = Request::builder()
+                .uri(endpoint)
+                .body(Body::empty())
+                .unwrap();
+            let resp = send_request(&mut app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK, "expected 200 for {endpoint}");
+
+            let content_type = resp
+                .headers()
+                .get(axum::http::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default();
+            assert!(
+                content_type.contains("application/json"),
+                "expected JSON content-type for {endpoint}, got {content_type}"
+            );
+        }
+
+        for endpoint in [
+            "/api/memory/current",
+            "/api/memory/overview",
+            "/api/memory/export",
+            "/api/memory/capabilities",
+        ] {
+            let req = Request::builder()
+                .uri(endpoint)
+                .body(Body::empty())
+                .unwrap();
+            let resp = send_request(&mut app, req).await;
+            assert_eq!(
+                resp.status(),
+                StatusCode::NOT_FOUND,
+                "expected deterministic API 404 for {endpoint}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_memory_endpoints_do_not_fallback_to_spa() {
+        let static_dir = unique_temp_dir("videnoa-spa-memory-fallback");
+        std::fs::create_dir_all(&static_dir).expect("create static dir");
+        std::fs::write(
+            static_dir.join("index.html"),
+            "<html><body>SPA</body></html>",
+        )
+        .expect("write index.html");
+
+        let state = test_state();
+        let mut app = app_router_with_static(state, Some(&static_dir));
+
+        let req = Request::builder()
+            .uri("/api/memory/current")
+            .body(Body::empty())
             .unwrap();
-        let created: WorkflowEntry = serde_json::from_slice(&resp_body).unwrap();
-        assert_eq!(created.name, "My Workflow");
-        assert!(created.has_interface);
-        assert!(created.filename.ends_with(".json"));
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let content_type = resp
+            .headers()
+            .get(axum::http::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        assert!(
+            !content_type.contains("text/html"),
+            "legacy API route must not fall through to SPA HTML"
+        );
+
+        let _ = std::fs::remove_dir_all(&static_dir);
+    }
+
+    #[tokio::test]
+    async fn test_performance_route_still_returns_json_with_spa_static_fallback_enabled() {
+        let static_dir = unique_temp_dir("videnoa-spa-performance-route");
+        std::fs::create_dir_all(&static_dir).expect("create static dir");
+        std::fs::write(
+            static_dir.join("index.html"),
+            "<html><body>SPA</body></html>",
+        )
+        .expect("write index.html");
+
+        let state = test_state();
+        let mut app = app_router_with_static(state, Some(&static_dir));
 
         let req = Request::builder()
-            .uri("/api/workflows")
+            .uri("/api/performance/current")
             .body(Body::empty())
             .unwrap();
         let resp = send_request(&mut app, req).await;
         assert_eq!(resp.status(), StatusCode::OK);
 
+        let content_type = resp
+            .headers()
+            .get(axum::http::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        assert!(
+            content_type.contains("application/json"),
+            "performance route must resolve to API JSON even when SPA fallback is enabled"
+        );
+
         let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let entries: Vec<WorkflowEntry> = serde_json::from_slice(&body).unwrap();
-        assert_eq!(entries.len(), 1);
-        assert_eq!(entries[0].name, "My Workflow");
-        assert_eq!(entries[0].description, "Test workflow");
-        assert!(entries[0].has_interface);
+        let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(payload["status"], "disabled");
+        assert_eq!(payload["enabled"], false);
 
-        let _ = std::fs::remove_dir_all(&dir);
+        let req = Request::builder().uri("/").body(Body::empty()).unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let content_type = resp
+            .headers()
+            .get(axum::http::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        assert!(
+            content_type.contains("text/html"),
+            "non-API route should still use SPA fallback"
+        );
+
+        let _ = std::fs::remove_dir_all(&static_dir);
     }
 
     #[tokio::test]
-    async fn test_save_and_delete_workflow() {
-        let dir = std::env::temp_dir().join(format!("videnoa-wf-del-{}", std::process::id()));
-        let _ = std::fs::remove_dir_all(&dir);
+    async fn test_performance_routes_use_enabled_envelopes_when_profiling_is_enabled() {
+        let state = test_state();
+        {
+            let mut config = state.inner.config.write().await;
+            config.performance.profiling_enabled = true;
+        }
 
-        let state = workflow_test_state(dir.clone());
         let mut app = app_router(state);
 
-        let body = serde_json::json!({
-            "name": "Deletable",
-            "description": "Will be deleted",
-            "workflow": {"nodes": [], "connections": []}
-        });
-
-        let req = Request::builder()
-            .method("POST")
-            .uri("/api/workflows")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
-            .unwrap();
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::CREATED);
+        for endpoint in [
+            "/api/performance/current",
+            "/api/performance/overview",
+            "/api/performance/export",
+            "/api/performance/capabilities",
+        ] {
+            let req = Request::builder()
+                .uri(endpoint)
+                .body(Body::empty())
+                .unwrap();
+            let resp = send_request(&mut app, req).await;
+            assert_eq!(resp.status(), StatusCode::OK, "expected 200 for {endpoint}");
 
-        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let created: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
-        let filename = created["filename"].as_str().unwrap();
+            let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(
+                payload["enabled"], true,
+                "enabled flag mismatch for {endpoint}"
+            );
+            assert_ne!(
+                payload["status"],
+                serde_json::json!("disabled"),
+                "status should not be disabled for {endpoint}",
+            );
+        }
 
         let req = Request::builder()
-            .method("DELETE")
-            .uri(format!("/api/workflows/{filename}"))
+            .uri("/api/performance/overview")
             .body(Body::empty())
             .unwrap();
         let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(payload["metrics"].is_object());
+        assert!(payload["metrics"].get("ram_used_bytes").is_some());
+        assert!(payload["metrics"].get("ram_total_bytes").is_some());
 
         let req = Request::builder()
-            .uri("/api/workflows")
+            .uri("/api/performance/export")
             .body(Body::empty())
             .unwrap();
         let resp = send_request(&mut app, req).await;
         let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let entries: Vec<WorkflowEntry> = serde_json::from_slice(&body).unwrap();
-        assert!(entries.is_empty());
-
-        let _ = std::fs::remove_dir_all(&dir);
+        let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(payload["series"].as_array().map(|rows| rows.len()), Some(2));
     }
 
     #[tokio::test]
-    async fn test_save_workflow_path_traversal() {
-        let dir = std::env::temp_dir().join(format!("videnoa-wf-trav-{}", std::process::id()));
-        let _ = std::fs::remove_dir_all(&dir);
+    async fn test_performance_export_reads_persisted_series_by_time_range() {
+        let data_dir = test_data_dir();
+        let state = test_state_with_data_dir(data_dir.clone());
+        {
+            let mut config = state.inner.config.write().await;
+            config.performance.profiling_enabled = true;
+        }
 
-        let state = workflow_test_state(dir.clone());
-        let mut app = app_router(state);
+        let base = Utc::now() - chrono::Duration::minutes(10);
+        for (offset_secs, cpu) in [(0, 10.0), (5, 20.0), (10, 30.0)] {
+            let mut metrics = serde_json::Map::new();
+            metrics.insert("cpu_util_percent".to_string(), serde_json::json!(cpu));
+            let sample = RuntimePerformanceSample {
+                metrics,
+                has_cpu_metrics: true,
+                has_memory_metrics: false,
+                has_gpu_metrics: false,
+                has_vram_metrics: false,
+            };
+            let at = base + chrono::Duration::seconds(offset_secs);
+            record_runtime_performance_series_sample(&state, at.timestamp_millis(), &sample);
+        }
+        prune_runtime_performance_series(&state, base + chrono::Duration::seconds(1));
 
-        let body = serde_json::json!({
-            "name": "../etc/passwd",
-            "description": "malicious",
-            "workflow": {"nodes": [], "connections": []}
-        });
+        // A fresh state on the same data dir reads the samples back from disk.
+        let restarted = test_state_with_data_dir(data_dir);
+        restarted
+            .inner
+            .config
+            .write()
+            .await
+            .performance
+            .profiling_enabled = true;
+        let mut app = app_router(restarted);
+        let export = |query: String| {
+            Request::builder()
+                .uri(format!("/api/performance/export{query}"))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let resp = send_request(&mut app, export(String::new())).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let cpu: Vec<f64> = payload["series"]
+            .as_array()
+            .expect("performance export should include a series array")
+            .iter()
+            .map(|row| row["metrics"]["cpu_util_percent"].as_f64().unwrap())
+            .collect();
+        assert_eq!(cpu, vec![20.0, 30.0]);
 
-        let req = Request::builder()
-            .method("POST")
-            .uri("/api/workflows")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+        let from = (base + chrono::Duration::seconds(8))
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        let query = format!("?from={from}");
+        let resp = send_request(&mut app, export(query)).await;
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
             .unwrap();
-        let resp = send_request(&mut app, req).await;
+        let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        // A single row is padded with a copy one second earlier.
+        assert_eq!(payload["series"].as_array().map(Vec::len), Some(2));
+        assert_eq!(payload["series"][1]["metrics"]["cpu_util_percent"], 30.0);
+
+        let to = (base - chrono::Duration::seconds(1))
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        let query = format!("?to={to}");
+        let resp = send_request(&mut app, export(query)).await;
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(payload["series"], serde_json::json!([]));
+
+        let query = format!("?from={from}&to={to}");
+        let resp = send_request(&mut app, export(query)).await;
         assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
 
-        let _ = std::fs::remove_dir_all(&dir);
+    fn performance_contract_router(
+        current_payload: serde_json::Value,
+        overview_payload: serde_json::Value,
+        export_payload: serde_json::Value,
+        capabilities_payload: serde_json::Value,
+    ) -> Router {
+        Router::new()
+            .route(
+                "/api/performance/current",
+                axum::routing::get(move || {
+                    let payload = current_payload.clone();
+                    async move { Json(payload) }
+                }),
+            )
+            .route(
+                "/api/performance/overview",
+                axum::routing::get(move || {
+                    let payload = overview_payload.clone();
+                    async move { Json(payload) }
+                }),
+            )
+            .route(
+                "/api/performance/export",
+                axum::routing::get(move || {
+                    let payload = export_payload.clone();
+                    async move { Json(payload) }
+                }),
+            )
+            .route(
+                "/api/performance/capabilities",
+                axum::routing::get(move || {
+                    let payload = capabilities_payload.clone();
+                    async move { Json(payload) }
+                }),
+            )
     }
 
-    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
-    async fn test_create_job_video_pipeline_with_params() {
-        let mut node_registry = NodeRegistry::new();
-        register_all_nodes(&mut node_registry);
-        let model_registry = ModelRegistry::with_builtin_models(test_models_dir());
-        let state = AppState::new(
-            node_registry,
-            model_registry,
-            DashMap::new(),
-            AppConfig::default(),
-            test_config_path(),
-            test_data_dir(),
+    async fn get_performance_contract_json(router: &mut Router, uri: &str) -> serde_json::Value {
+        let req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let resp = send_request(router, req).await;
+        assert_eq!(resp.status(), StatusCode::OK, "unexpected status for {uri}");
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_performance_contract_routes_use_deterministic_off_envelopes() {
+        let mut app = performance_contract_router(
+            serde_json::json!({
+                "status": "disabled",
+                "enabled": false,
+                "reason": "disabled_by_config",
+                "message": "telemetry disabled",
+                "metrics": null,
+            }),
+            serde_json::json!({
+                "status": "disabled",
+                "enabled": false,
+                "reason": "disabled_by_config",
+                "message": "telemetry disabled",
+                "metrics": null,
+            }),
+            serde_json::json!({
+                "status": "disabled",
+                "enabled": false,
+                "reason": "disabled_by_config",
+                "message": "telemetry disabled",
+                "series": [],
+            }),
+            serde_json::json!({
+                "status": "disabled",
+                "enabled": false,
+                "reason": "disabled_by_config",
+                "message": "telemetry disabled",
+                "supported_statuses": ["disabled", "enabled", "degraded", "partial"],
+            }),
         );
-        let mut app = app_router(state.clone());
 
-        let body = serde_json::json!({
-            "workflow": {
-                "nodes": [
-                    {"id": "input", "node_type": "VideoInput", "params": {"path": temp_path_str("nonexistent.mp4")}},
-                    {"id": "sr", "node_type": "SuperResolution", "params": {"model_path": temp_path_str("model.onnx"), "scale": 2, "tile_size": 0}},
-                    {"id": "output", "node_type": "VideoOutput", "params": {
-                        "output_path": temp_path_str("out.mp4"), "codec": "libx265", "crf": 18,
-                        "pixel_format": "yuv420p10le", "width": 1920, "height": 1080, "fps": "24"
-                    }}
-                ],
-                "connections": [
-                    {"from_node": "input", "from_port": "frames", "to_node": "sr", "to_port": "frames", "port_type": "VideoFrames"},
-                    {"from_node": "sr", "from_port": "frames", "to_node": "output", "to_port": "frames", "port_type": "VideoFrames"},
-                    {"from_node": "input", "from_port": "source_path", "to_node": "output", "to_port": "source_path", "port_type": "Path"}
-                ]
-            },
-            "params": {"greeting": "hello"}
-        });
+        let current = get_performance_contract_json(&mut app, "/api/performance/current").await;
+        let overview = get_performance_contract_json(&mut app, "/api/performance/overview").await;
+        let exported = get_performance_contract_json(&mut app, "/api/performance/export").await;
+        let capabilities =
+            get_performance_contract_json(&mut app, "/api/performance/capabilities").await;
 
-        let req = Request::builder()
-            .method("POST")
-            .uri("/api/jobs")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
-            .unwrap();
+        for payload in [&current, &overview, &exported, &capabilities] {
+            assert_eq!(payload["status"], "disabled");
+            assert_eq!(payload["enabled"], false);
+        }
 
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert!(current["metrics"].is_null());
+        assert!(overview["metrics"].is_null());
+        assert_eq!(exported["series"], serde_json::json!([]));
+        assert_eq!(
+            capabilities["supported_statuses"],
+            serde_json::json!(["disabled", "enabled", "degraded", "partial"]),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_performance_contract_routes_keep_partial_enabled_with_sparse_metrics() {
+        let mut app = performance_contract_router(
+            serde_json::json!({
+                "status": "partial",
+                "enabled": true,
+                "reason": "gpu_missing",
+                "message": "partial telemetry",
+                "metrics": {
+                    "cpu_util_percent": 24.0,
+                    "gpu_util_percent": null,
+                },
+            }),
+            serde_json::json!({
+                "status": "partial",
+                "enabled": true,
+                "reason": "gpu_missing",
+                "message": "partial telemetry",
+                "metrics": {
+                    "cpu_util_percent": 24.0,
+                    "ram_used_bytes": 2147483648u64,
+                },
+            }),
+            serde_json::json!({
+                "status": "partial",
+                "enabled": true,
+                "reason": "cpu_only",
+                "message": "partial export",
+                "series": [
+                    {
+                        "timestamp_ms": 1700000000000u64,
+                        "metrics": {
+                            "cpu_util_percent": 21.0,
+                        },
+                    },
+                ],
+            }),
+            serde_json::json!({
+                "status": "partial",
+                "enabled": true,
+                "reason": "cpu_only",
+                "message": "partial capabilities",
+                "supported_statuses": ["disabled", "enabled", "degraded", "partial"],
+            }),
+        );
+
+        let current = get_performance_contract_json(&mut app, "/api/performance/current").await;
+        let overview = get_performance_contract_json(&mut app, "/api/performance/overview").await;
+        let exported = get_performance_contract_json(&mut app, "/api/performance/export").await;
+        let capabilities =
+            get_performance_contract_json(&mut app, "/api/performance/capabilities").await;
 
-        let resp_body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let create_json: serde_json::Value = serde_json::from_slice(&resp_body).unwrap();
-        let job_id = create_json["id"].as_str().unwrap().to_string();
+        for payload in [&current, &overview, &exported, &capabilities] {
+            assert_eq!(payload["status"], "partial");
+            assert_eq!(payload["enabled"], true);
+        }
 
-        let status = wait_for_job_terminal_status(&state, &job_id).await;
-        let job = state.inner.jobs.get(&job_id).unwrap();
         assert_eq!(
-            status,
-            JobStatus::Failed,
-            "expected Failed, got {:?}",
-            job.status
+            current["metrics"]["gpu_util_percent"],
+            serde_json::Value::Null
         );
+        assert!(overview["metrics"].get("gpu_util_percent").is_none());
         assert_eq!(
-            job.params
-                .as_ref()
-                .and_then(|params| params.get("greeting")),
-            Some(&serde_json::json!("hello")),
-            "job metadata should preserve submitted params"
+            exported["series"].as_array().map(|rows| rows.len()),
+            Some(1)
         );
-        assert!(
-            !job.error
-                .as_deref()
-                .unwrap_or("")
-                .contains("do not support workflow parameters"),
-            "video workflow should no longer fail on params guard, got: {:?}",
-            job.error,
+        assert_eq!(
+            exported["series"][0]["metrics"]["cpu_util_percent"],
+            serde_json::json!(21.0),
         );
+        assert_eq!(capabilities["supported_statuses"][3], "partial");
     }
 
     #[tokio::test]
-    async fn test_update_config_invalid_json() {
-        let mut app = test_router();
+    async fn test_performance_contract_routes_keep_degraded_enabled_with_fallback_series() {
+        let mut app = performance_contract_router(
+            serde_json::json!({
+                "status": "degraded",
+                "enabled": true,
+                "reason": "sampler_stale",
+                "message": "degraded telemetry",
+                "metrics": {
+                    "cpu_util_percent": 12.0,
+                    "gpu_util_percent": null,
+                },
+            }),
+            serde_json::json!({
+                "status": "degraded",
+                "enabled": true,
+                "reason": "sampler_stale",
+                "message": "degraded telemetry",
+                "metrics": {
+                    "cpu_util_percent": 12.0,
+                    "ram_used_bytes": 3221225472u64,
+                    "ram_total_bytes": 8589934592u64,
+                },
+            }),
+            serde_json::json!({
+                "status": "degraded",
+                "enabled": true,
+                "reason": "export_missing_keys",
+                "message": "degraded export",
+                "series": [
+                    {
+                        "timestamp_ms": 1700000000000u64,
+                        "metrics": {
+                            "temperature_celsius": 61.0,
+                        },
+                    },
+                    {
+                        "timestamp_ms": 1700000001000u64,
+                        "metrics": {
+                            "temperature_celsius": 62.0,
+                        },
+                    },
+                ],
+            }),
+            serde_json::json!({
+                "status": "degraded",
+                "enabled": true,
+                "reason": "sampler_stale",
+                "message": "degraded capabilities",
+                "supported_statuses": ["disabled", "enabled", "degraded", "partial"],
+            }),
+        );
 
-        let req = Request::builder()
-            .method("PUT")
-            .uri("/api/config")
-            .header("content-type", "application/json")
-            .body(Body::from(b"not valid json".to_vec()))
-            .unwrap();
+        let current = get_performance_contract_json(&mut app, "/api/performance/current").await;
+        let overview = get_performance_contract_json(&mut app, "/api/performance/overview").await;
+        let exported = get_performance_contract_json(&mut app, "/api/performance/export").await;
+        let capabilities =
+            get_performance_contract_json(&mut app, "/api/performance/capabilities").await;
 
-        let resp = send_request(&mut app, req).await;
+        for payload in [&current, &overview, &exported, &capabilities] {
+            assert_eq!(payload["status"], "degraded");
+            assert_eq!(payload["enabled"], true);
+        }
+
+        assert_eq!(
+            current["metrics"]["gpu_util_percent"],
+            serde_json::Value::Null
+        );
+        assert_eq!(
+            exported["series"].as_array().map(|rows| rows.len()),
+            Some(2),
+            "degraded export should keep deterministic fallback rows",
+        );
         assert!(
-            resp.status().is_client_error(),
-            "expected client error, got {}",
-            resp.status()
+            overview["metrics"].get("gpu_util_percent").is_none(),
+            "degraded overview allows sparse/missing fields for deterministic fallback",
         );
+        assert_eq!(capabilities["supported_statuses"][2], "degraded");
     }
 
-    #[tokio::test]
-    async fn test_update_config_roundtrip() {
-        let mut app = test_router();
-
-        let req = Request::builder()
-            .uri("/api/config")
-            .body(Body::empty())
-            .unwrap();
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::OK);
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let mut config: serde_json::Value = serde_json::from_slice(&body).unwrap();
-
-        let custom_models = temp_path_str("custom_models");
-        config["paths"]["models_dir"] = serde_json::json!(custom_models);
+    #[test]
+    fn test_parse_proc_stat_cpu_line_extracts_total_and_idle_ticks() {
+        let parsed = parse_proc_stat_cpu_line("cpu  100 20 30 400 50 0 10 0 0 0")
+            .expect("cpu line should parse");
+        assert_eq!(parsed.total_ticks, 610);
+        assert_eq!(parsed.idle_ticks, 450);
+    }
 
-        let req = Request::builder()
-            .method("PUT")
-            .uri("/api/config")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&config).unwrap()))
-            .unwrap();
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::OK);
+    #[test]
+    fn test_compute_cpu_util_percent_uses_tick_deltas() {
+        let previous = CpuTimes {
+            total_ticks: 1_000,
+            idle_ticks: 400,
+        };
+        let current = CpuTimes {
+            total_ticks: 1_200,
+            idle_ticks: 460,
+        };
 
-        let req = Request::builder()
-            .uri("/api/config")
-            .body(Body::empty())
-            .unwrap();
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::OK);
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let updated: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        assert_eq!(updated["paths"]["models_dir"], custom_models);
+        let percent =
+            compute_cpu_util_percent(previous, current).expect("deltas should produce percent");
+        assert!((percent - 70.0).abs() < 1e-6);
     }
 
-    #[tokio::test]
-    async fn test_relative_workflows_resolution_uses_current_dir() {
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        let workflows_rel = PathBuf::from(format!(
-            "target/videnoa-wf-cwd-{}-{timestamp}",
-            std::process::id()
-        ));
-        let _ = std::fs::remove_dir_all(&workflows_rel);
+    #[test]
+    fn test_parse_nvidia_smi_gpu_snapshot_parses_util_and_vram() {
+        let snapshot = parse_nvidia_smi_gpu_snapshot("45, 1024, 8192\n")
+            .expect("nvidia-smi gpu row should parse");
+        assert_eq!(snapshot.gpu_util_percent, 45.0);
+        assert_eq!(snapshot.vram_used_bytes, 1024 * BYTES_PER_MIB);
+        assert_eq!(snapshot.vram_total_bytes, 8192 * BYTES_PER_MIB);
+    }
 
-        let state = workflow_test_state(workflows_rel.clone());
-        let mut app = app_router(state);
+    #[test]
+    fn test_parse_nvidia_smi_compute_apps_vram_sums_matching_pid_rows() {
+        let stdout = "111, 32\n222, 64\n111, 128\n111, N/A\n";
+        let vram_bytes = parse_nvidia_smi_compute_apps_vram(stdout, 111)
+            .expect("matching pid rows should produce a sum");
+        assert_eq!(vram_bytes, (32 + 128) * BYTES_PER_MIB);
+    }
 
-        let body = serde_json::json!({
-            "name": "Resolved Workflow",
-            "description": "Tests current-dir resolution",
-            "workflow": {"nodes": [], "connections": []}
-        });
+    #[test]
+    fn test_enabled_performance_envelope_status_transitions_match_metric_coverage() {
+        let empty_sample = RuntimePerformanceSample {
+            metrics: serde_json::Map::new(),
+            has_cpu_metrics: false,
+            has_memory_metrics: false,
+            has_gpu_metrics: false,
+            has_vram_metrics: false,
+        };
+        assert_eq!(
+            enabled_performance_envelope(&empty_sample)["status"],
+            "degraded"
+        );
 
-        let req = Request::builder()
-            .method("POST")
-            .uri("/api/workflows")
-            .header("content-type", "application/json")
-            .body(Body::from(serde_json::to_vec(&body).unwrap()))
-            .unwrap();
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::CREATED);
+        let partial_sample = RuntimePerformanceSample {
+            metrics: serde_json::Map::new(),
+            has_cpu_metrics: true,
+            has_memory_metrics: true,
+            has_gpu_metrics: false,
+            has_vram_metrics: false,
+        };
+        assert_eq!(
+            enabled_performance_envelope(&partial_sample)["status"],
+            "partial"
+        );
 
-        let expected_file = workflows_rel.join("Resolved Workflow.json");
-        assert!(
-            expected_file.exists(),
-            "Workflow file should be at {}",
-            expected_file.display()
+        let full_sample = RuntimePerformanceSample {
+            metrics: serde_json::Map::new(),
+            has_cpu_metrics: true,
+            has_memory_metrics: true,
+            has_gpu_metrics: true,
+            has_vram_metrics: true,
+        };
+        assert_eq!(
+            enabled_performance_envelope(&full_sample)["status"],
+            "enabled"
         );
-
-        let _ = std::fs::remove_dir_all(&workflows_rel);
     }
 
-    #[tokio::test]
-    async fn test_get_memory_endpoints_are_replaced_by_performance_routes() {
-        let mut app = test_router();
-
-        for endpoint in [
-            "/api/performance/current",
-            "/api/performance/overview",
-            "/api/performance/export",
-            "/api/performance/capabilities",
-        ] {
-            let req = Request::builder()
-                .uri(endpoint)
-                .body(Body::empty())
-                .unwrap();
-            let resp = send_request(&mut app, req).await;
-            assert_eq!(resp.status(), StatusCode::OK, "expected 200 for {endpoint}");
-
-            let content_type = resp
-                .headers()
-                .get(axum::http::header::CONTENT_TYPE)
-                .and_then(|v| v.to_str().ok())
-                .unwrap_or_default();
-            assert!(
-                content_type.contains("application/json"),
-                "expected JSON content-type for {endpoint}, got {content_type}"
-            );
+    async fn post_json(
+        app: &mut Router,
+        uri: &str,
+        token: Option<&str>,
+        body: serde_json::Value,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut builder = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(token) = token {
+            builder = builder.header("authorization", format!("Bearer {token}"));
         }
+        let req = builder
+            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+            .unwrap();
+        let resp = send_request(app, req).await;
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).unwrap()
+        };
+        (status, json)
+    }
 
-        for endpoint in [
-            "/api/memory/current",
-            "/api/memory/overview",
-            "/api/memory/export",
-            "/api/memory/capabilities",
-        ] {
-            let req = Request::builder()
-                .uri(endpoint)
-                .body(Body::empty())
-                .unwrap();
-            let resp = send_request(&mut app, req).await;
-            assert_eq!(
-                resp.status(),
-                StatusCode::NOT_FOUND,
-                "expected deterministic API 404 for {endpoint}"
-            );
-        }
+    async fn get_json(app: &mut Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let req = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let resp = send_request(app, req).await;
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes).unwrap()
+        };
+        (status, json)
     }
 
     #[tokio::test]
-    async fn test_memory_endpoints_do_not_fallback_to_spa() {
-        let static_dir = unique_temp_dir("videnoa-spa-memory-fallback");
-        std::fs::create_dir_all(&static_dir).expect("create static dir");
-        std::fs::write(
-            static_dir.join("index.html"),
-            "<html><body>SPA</body></html>",
+    async fn test_remote_worker_claims_reports_and_completes_job() {
+        let state = test_state();
+        state
+            .inner
+            .secrets
+            .put("worker-token", "s3cret")
+            .expect("store worker token");
+        {
+            let mut config = state.inner.config.write().await;
+            config.workers.dispatch = JobDispatchMode::Remote;
+            config.workers.token_secret = Some("worker-token".to_string());
+        }
+        let mut app = app_router(state.clone());
+
+        let (status, created) = post_json(
+            &mut app,
+            "/api/jobs",
+            None,
+            serde_json::json!({ "workflow": valid_workflow_json() }),
         )
-        .expect("write index.html");
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let job_id = created["id"].as_str().unwrap().to_string();
+
+        let (status, registered) = post_json(
+            &mut app,
+            "/api/workers/register",
+            Some("s3cret"),
+            serde_json::json!({ "name": "gpu-box" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let worker_id = registered["worker_id"].as_str().unwrap().to_string();
+
+        let (status, assignment) = post_json(
+            &mut app,
+            &format!("/api/workers/{worker_id}/claim"),
+            Some("s3cret"),
+            serde_json::Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(assignment["job_id"], job_id.as_str());
+        assert_eq!(
+            state.inner.jobs.get(&job_id).unwrap().status,
+            JobStatus::Running
+        );
 
-        let state = test_state();
-        let mut app = app_router_with_static(state, Some(&static_dir));
+        let (status, _) = post_json(
+            &mut app,
+            &format!("/api/workers/{worker_id}/claim"),
+            Some("s3cret"),
+            serde_json::Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
 
-        let req = Request::builder()
-            .uri("/api/memory/current")
-            .body(Body::empty())
-            .unwrap();
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let (status, progress) = post_json(
+            &mut app,
+            &format!("/api/workers/{worker_id}/jobs/{job_id}/progress"),
+            Some("s3cret"),
+            serde_json::json!({
+                "current_frame": 12,
+                "total_frames": 100,
+                "fps": 24.0,
+                "eta_seconds": 3.6
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(progress["cancel"], false);
+        assert_eq!(
+            state
+                .inner
+                .jobs
+                .get(&job_id)
+                .unwrap()
+                .progress
+                .as_ref()
+                .map(|p| p.current_frame),
+            Some(12)
+        );
 
-        let content_type = resp
-            .headers()
-            .get(axum::http::header::CONTENT_TYPE)
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or_default();
-        assert!(
-            !content_type.contains("text/html"),
-            "legacy API route must not fall through to SPA HTML"
+        let (status, _) = post_json(
+            &mut app,
+            &format!("/api/workers/{worker_id}/jobs/{job_id}/complete"),
+            Some("s3cret"),
+            serde_json::json!({ "outcome": "completed", "error": null }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert_eq!(
+            state.inner.jobs.get(&job_id).unwrap().status,
+            JobStatus::Completed
         );
 
-        let _ = std::fs::remove_dir_all(&static_dir);
+        let (status, _) = post_json(
+            &mut app,
+            &format!("/api/workers/{worker_id}/jobs/{job_id}/complete"),
+            Some("s3cret"),
+            serde_json::json!({ "outcome": "completed", "error": null }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
     }
 
     #[tokio::test]
-    async fn test_performance_route_still_returns_json_with_spa_static_fallback_enabled() {
-        let static_dir = unique_temp_dir("videnoa-spa-performance-route");
-        std::fs::create_dir_all(&static_dir).expect("create static dir");
-        std::fs::write(
-            static_dir.join("index.html"),
-            "<html><body>SPA</body></html>",
-        )
-        .expect("write index.html");
-
+    async fn test_worker_endpoints_require_configured_token() {
         let state = test_state();
-        let mut app = app_router_with_static(state, Some(&static_dir));
+        let mut app = app_router(state.clone());
+        let body = serde_json::json!({ "name": "gpu-box" });
 
-        let req = Request::builder()
-            .uri("/api/performance/current")
-            .body(Body::empty())
-            .unwrap();
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::OK);
+        // Without a configured token the worker endpoints stay closed.
+        let (status, _) = post_json(&mut app, "/api/workers/register", None, body.clone()).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
 
-        let content_type = resp
-            .headers()
-            .get(axum::http::header::CONTENT_TYPE)
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or_default();
-        assert!(
-            content_type.contains("application/json"),
-            "performance route must resolve to API JSON even when SPA fallback is enabled"
+        state
+            .inner
+            .secrets
+            .put("worker-token", "s3cret")
+            .expect("store worker token");
+        state.inner.config.write().await.workers.token_secret = Some("worker-token".to_string());
+
+        let (status, _) = post_json(&mut app, "/api/workers/register", None, body.clone()).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, _) = post_json(
+            &mut app,
+            "/api/workers/register",
+            Some("wrong"),
+            body.clone(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, registered) =
+            post_json(&mut app, "/api/workers/register", Some("s3cret"), body).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(
+            registered["heartbeat_interval_secs"],
+            WORKER_HEARTBEAT_INTERVAL_SECS
         );
+    }
 
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        assert_eq!(payload["status"], "disabled");
-        assert_eq!(payload["enabled"], false);
+    #[tokio::test]
+    async fn test_create_chunked_job_validates_paths_and_chunk_size() {
+        let mut app = test_router();
 
-        let req = Request::builder().uri("/").body(Body::empty()).unwrap();
-        let resp = send_request(&mut app, req).await;
-        assert_eq!(resp.status(), StatusCode::OK);
-        let content_type = resp
-            .headers()
-            .get(axum::http::header::CONTENT_TYPE)
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or_default();
-        assert!(
-            content_type.contains("text/html"),
-            "non-API route should still use SPA fallback"
-        );
+        let (status, body) = post_json(
+            &mut app,
+            "/api/jobs/chunked",
+            None,
+            serde_json::json!({
+                "workflow": valid_workflow_json(),
+                "params": { "output": "/tmp/out.mkv" }
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("params.input"));
 
-        let _ = std::fs::remove_dir_all(&static_dir);
+        let (status, body) = post_json(
+            &mut app,
+            "/api/jobs/chunked",
+            None,
+            serde_json::json!({
+                "workflow": valid_workflow_json(),
+                "params": {
+                    "input": "/definitely/missing/source.mkv",
+                    "output": "/tmp/out.mkv"
+                }
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"]
+            .as_str()
+            .unwrap()
+            .contains("input file does not exist"));
+
+        let source = test_data_dir().join("source.mkv");
+        std::fs::create_dir_all(source.parent().unwrap()).unwrap();
+        std::fs::write(&source, b"not a real video").unwrap();
+        let (status, _) = post_json(
+            &mut app,
+            "/api/jobs/chunked",
+            None,
+            serde_json::json!({
+                "workflow": valid_workflow_json(),
+                "params": {
+                    "input": source.to_string_lossy(),
+                    "output": "/tmp/out.mkv"
+                },
+                "target_chunk_secs": 1.0
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let _ = std::fs::remove_dir_all(source.parent().unwrap());
     }
 
     #[tokio::test]
-    async fn test_performance_routes_use_enabled_envelopes_when_profiling_is_enabled() {
-        let state = test_state();
-        {
-            let mut config = state.inner.config.write().await;
-            config.performance.profiling_enabled = true;
-        }
-
-        let mut app = app_router(state);
+    async fn test_create_experiment_validates_request() {
+        let mut app = test_router();
+        let source = unique_temp_dir("videnoa-experiment-source").join("clip.mkv");
+        std::fs::create_dir_all(source.parent().unwrap()).unwrap();
+        std::fs::write(&source, b"clip").unwrap();
 
-        for endpoint in [
-            "/api/performance/current",
-            "/api/performance/overview",
-            "/api/performance/export",
-            "/api/performance/capabilities",
+        for (body, expected) in [
+            (
+                serde_json::json!({
+                    "source": "/definitely/missing/clip.mkv",
+                    "workflow": valid_workflow_json(),
+                    "variants": [{"name": "a"}]
+                }),
+                "source file does not exist",
+            ),
+            (
+                serde_json::json!({
+                    "source": source.to_string_lossy(),
+                    "workflow": valid_workflow_json(),
+                    "variants": [{"name": "a"}, {"name": " a "}]
+                }),
+                "duplicate variant name",
+            ),
+            (
+                serde_json::json!({
+                    "source": source.to_string_lossy(),
+                    "variants": [{"name": "a"}]
+                }),
+                "has no workflow",
+            ),
+            (
+                serde_json::json!({
+                    "source": source.to_string_lossy(),
+                    "workflow": valid_workflow_json(),
+                    "variants": [{"name": "a", "model_path": "models/a.onnx"}]
+                }),
+                "no SuperResolution node",
+            ),
         ] {
-            let req = Request::builder()
-                .uri(endpoint)
-                .body(Body::empty())
-                .unwrap();
-            let resp = send_request(&mut app, req).await;
-            assert_eq!(resp.status(), StatusCode::OK, "expected 200 for {endpoint}");
-
-            let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-                .await
-                .unwrap();
-            let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
-            assert_eq!(
-                payload["enabled"], true,
-                "enabled flag mismatch for {endpoint}"
-            );
-            assert_ne!(
-                payload["status"],
-                serde_json::json!("disabled"),
-                "status should not be disabled for {endpoint}",
+            let (status, json) = post_json(&mut app, "/api/experiments", None, body).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{json}");
+            assert!(
+                json["error"].as_str().unwrap().contains(expected),
+                "{expected}: {json}"
             );
         }
 
-        let req = Request::builder()
-            .uri("/api/performance/overview")
-            .body(Body::empty())
-            .unwrap();
-        let resp = send_request(&mut app, req).await;
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        assert!(payload["metrics"].is_object());
-        assert!(payload["metrics"].get("ram_used_bytes").is_some());
-        assert!(payload["metrics"].get("ram_total_bytes").is_some());
+        let (status, _) = get_json(&mut app, "/api/experiments/missing").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let _ = std::fs::remove_dir_all(source.parent().unwrap());
+    }
 
-        let req = Request::builder()
-            .uri("/api/performance/export")
-            .body(Body::empty())
-            .unwrap();
-        let resp = send_request(&mut app, req).await;
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
-            .await
-            .unwrap();
-        let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        assert_eq!(payload["series"].as_array().map(|rows| rows.len()), Some(2));
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_experiment_runs_variants_and_writes_report() {
+        let data_dir = test_data_dir();
+        let state = test_state_with_data_dir(data_dir.clone());
+        let mut app = app_router(state.clone());
+        let source = data_dir.join("clip.mkv");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        std::fs::write(&source, b"clip").unwrap();
+
+        let (status, created) = post_json(
+            &mut app,
+            "/api/experiments",
+            None,
+            serde_json::json!({
+                "source": source.to_string_lossy(),
+                "workflow": valid_workflow_json(),
+                "variants": [{"name": "first"}, {"name": "second"}]
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{created}");
+        let id = created["id"].as_str().unwrap().to_string();
+        // The test workflow has no video source, so every variant fails and
+        // the report records why.
+        assert_eq!(
+            wait_for_job_terminal_status(&state, &id).await,
+            JobStatus::Failed
+        );
+
+        let (status, json) = get_json(&mut app, &format!("/api/experiments/{id}")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["status"], "failed");
+        let variants = json["report"]["variants"].as_array().unwrap();
+        let names: Vec<&str> = variants
+            .iter()
+            .map(|variant| variant["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["first", "second"]);
+        assert_eq!(variants[0]["status"], "failed");
+        assert!(variants[0]["error"].is_string());
+        assert!(variants[1]["output"]
+            .as_str()
+            .unwrap()
+            .ends_with("02_second.mkv"));
+        assert_eq!(
+            state.inner.jobs.get(&id).unwrap().error.as_deref(),
+            Some("no variant completed")
+        );
+        let _ = std::fs::remove_dir_all(&data_dir);
     }
 
     #[tokio::test]
-    async fn test_performance_export_accumulates_time_series_samples_over_requests() {
-        let state = test_state();
-        {
-            let mut config = state.inner.config.write().await;
-            config.performance.profiling_enabled = true;
-        }
-
-        let mut app = app_router(state);
+    async fn test_jellyfin_items_pages_and_proxies_images() {
+        // Echoes the item query back and serves one poster.
+        let mock = Router::new()
+            .route(
+                "/Items",
+                get(
+                    |axum::extract::Query(query): axum::extract::Query<HashMap<String, String>>| async move {
+                        Json(serde_json::json!({
+                            "Items": [{"Id": "ep1", "Name": serde_json::to_string(&query).unwrap(),
+                                       "Type": "Episode", "ImageTags": {"Primary": "t"}}],
+                            "TotalRecordCount": 1234,
+                            "StartIndex": query.get("StartIndex").and_then(|v| v.parse::<u64>().ok())
+                        }))
+                    },
+                ),
+            )
+            .route(
+                "/Items/{id}/Images/{kind}",
+                get(|Path((id, _kind)): Path<(String, String)>| async move {
+                    if id == "ep1" {
+                        ([(axum::http::header::CONTENT_TYPE, "image/jpeg")], vec![0xFF, 0xD8])
+                            .into_response()
+                    } else {
+                        StatusCode::NOT_FOUND.into_response()
+                    }
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, mock).await.unwrap() });
+
+        let mut app = app_router(test_state());
+        let (status, body) = get_json(
+            &mut app,
+            &format!(
+                "/api/jellyfin/items?url={base}&api_key=key&library_id=lib&search=%20naruto%20&start_index=200&limit=50&sort_by=episode&sort_order=desc"
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body["TotalRecordCount"], 1234);
+        assert_eq!(body["StartIndex"], 200);
+        assert_eq!(body["Items"][0]["ImageTags"]["Primary"], "t");
+        let forwarded: HashMap<String, String> =
+            serde_json::from_str(body["Items"][0]["Name"].as_str().unwrap()).unwrap();
+        assert_eq!(forwarded["ParentId"], "lib");
+        assert_eq!(forwarded["SearchTerm"], "naruto");
+        assert_eq!(forwarded["Limit"], "50");
+        assert_eq!(forwarded["StartIndex"], "200");
+        assert_eq!(
+            forwarded["SortBy"],
+            "SeriesSortName,ParentIndexNumber,IndexNumber,SortName"
+        );
+        assert_eq!(forwarded["SortOrder"], "Descending");
 
-        for _ in 0..2 {
-            let req = Request::builder()
-                .uri("/api/performance/export")
-                .body(Body::empty())
-                .unwrap();
-            let _ = send_request(&mut app, req).await;
-            tokio::time::sleep(Duration::from_millis(2)).await;
-        }
+        let (status, _) = get_json(
+            &mut app,
+            &format!("/api/jellyfin/items?url={base}&api_key=key&limit=5000"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
 
         let req = Request::builder()
-            .uri("/api/performance/export")
+            .uri(format!("/api/jellyfin/image/ep1?url={base}&api_key=key"))
             .body(Body::empty())
             .unwrap();
         let resp = send_request(&mut app, req).await;
         assert_eq!(resp.status(), StatusCode::OK);
-
-        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+        assert_eq!(resp.headers()["content-type"], "image/jpeg");
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        let payload: serde_json::Value = serde_json::from_slice(&body).unwrap();
-        let series = payload["series"]
-            .as_array()
-            .expect("performance export should include a series array");
-        assert_eq!(series.len(), 3);
-
-        let ordered_timestamps = series
-            .iter()
-            .map(|entry| {
-                entry["timestamp_ms"]
-                    .as_i64()
-                    .expect("series entry should include timestamp_ms")
-            })
-            .collect::<Vec<_>>();
-
-        let mut sorted_unique_timestamps = ordered_timestamps.clone();
-        sorted_unique_timestamps.sort_unstable();
-        sorted_unique_timestamps.dedup();
+        assert_eq!(bytes.as_ref(), &[0xFF, 0xD8]);
 
-        assert_eq!(sorted_unique_timestamps.len(), 3);
-        assert_eq!(
-            ordered_timestamps, sorted_unique_timestamps,
-            "series entries should be ordered by timestamp",
-        );
+        let (status, _) = get_json(
+            &mut app,
+            &format!("/api/jellyfin/image/missing?url={base}&api_key=key"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = get_json(
+            &mut app,
+            &format!("/api/jellyfin/image/ep1?url={base}&api_key=key&type=Other"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
     }
 
-    fn performance_contract_router(
-        current_payload: serde_json::Value,
-        overview_payload: serde_json::Value,
-        export_payload: serde_json::Value,
-        capabilities_payload: serde_json::Value,
-    ) -> Router {
-        Router::new()
-            .route(
-                "/api/performance/current",
-                axum::routing::get(move || {
-                    let payload = current_payload.clone();
-                    async move { Json(payload) }
-                }),
-            )
-            .route(
-                "/api/performance/overview",
-                axum::routing::get(move || {
-                    let payload = overview_payload.clone();
-                    async move { Json(payload) }
-                }),
-            )
+    #[tokio::test]
+    async fn test_jellyfin_servers_keep_key_encrypted_and_resolve_by_name() {
+        let mock = Router::new()
             .route(
-                "/api/performance/export",
-                axum::routing::get(move || {
-                    let payload = export_payload.clone();
-                    async move { Json(payload) }
+                "/System/Info",
+                get(|headers: axum::http::HeaderMap| async move {
+                    if headers.get("X-Emby-Token").is_some_and(|v| v == "anime-key") {
+                        Json(serde_json::json!({"ServerName": "Anime", "Version": "10.9.0", "Id": "a"}))
+                            .into_response()
+                    } else {
+                        StatusCode::UNAUTHORIZED.into_response()
+                    }
                 }),
             )
             .route(
-                "/api/performance/capabilities",
-                axum::routing::get(move || {
-                    let payload = capabilities_payload.clone();
-                    async move { Json(payload) }
-                }),
-            )
-    }
+                "/Library/VirtualFolders",
+                get(|| async { Json(serde_json::json!([])) }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, mock).await.unwrap() });
 
-    async fn get_performance_contract_json(router: &mut Router, uri: &str) -> serde_json::Value {
-        let req = Request::builder().uri(uri).body(Body::empty()).unwrap();
-        let resp = send_request(router, req).await;
-        assert_eq!(resp.status(), StatusCode::OK, "unexpected status for {uri}");
+        let state = test_state();
+        let mut app = app_router(state.clone());
+        let put = |body: serde_json::Value| {
+            Request::builder()
+                .method("PUT")
+                .uri("/api/jellyfin/servers/anime")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let resp = send_request(&mut app, put(serde_json::json!({"url": base}))).await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let resp = send_request(
+            &mut app,
+            put(serde_json::json!({"url": base, "api_key": "anime-key"})),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
         let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        serde_json::from_slice(&body).unwrap()
+        let saved: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(saved["api_key_secret"], "jellyfin.anime");
+        assert_eq!(saved["api_key_set"], true);
+
+        let config = std::fs::read_to_string(&state.inner.config_path).unwrap();
+        assert!(config.contains("jellyfin.anime"), "{config}");
+        assert!(!config.contains("anime-key"), "{config}");
+
+        let (status, body) = post_json(
+            &mut app,
+            "/api/jellyfin/servers/anime/test",
+            None,
+            serde_json::json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["ok"], true);
+        assert_eq!(body["version"], "10.9.0");
+
+        let (status, _) = get_json(&mut app, "/api/jellyfin/libraries?server=anime").await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, body) = get_json(&mut app, "/api/jellyfin/libraries?server=movies").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"]
+            .as_str()
+            .unwrap()
+            .contains("unknown Jellyfin server"));
+
+        // Changing only the URL keeps the stored key.
+        let resp = send_request(
+            &mut app,
+            put(serde_json::json!({"url": "http://127.0.0.1:1"})),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let (_, body) = post_json(
+            &mut app,
+            "/api/jellyfin/servers/anime/test",
+            None,
+            serde_json::json!({}),
+        )
+        .await;
+        assert_eq!(body["ok"], false);
+        assert!(body["error"].is_string());
+
+        let req = Request::builder()
+            .method("DELETE")
+            .uri("/api/jellyfin/servers/anime")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(
+            send_request(&mut app, req).await.status(),
+            StatusCode::NO_CONTENT
+        );
+        assert_eq!(state.inner.secrets.get("jellyfin.anime").unwrap(), None);
+        let (_, body) = get_json(&mut app, "/api/jellyfin/servers").await;
+        assert_eq!(body, serde_json::json!([]));
+        let _ = std::fs::remove_file(&state.inner.config_path);
     }
 
     #[tokio::test]
-    async fn test_performance_contract_routes_use_deterministic_off_envelopes() {
-        let mut app = performance_contract_router(
-            serde_json::json!({
-                "status": "disabled",
-                "enabled": false,
-                "reason": "disabled_by_config",
-                "message": "telemetry disabled",
-                "metrics": null,
-            }),
-            serde_json::json!({
-                "status": "disabled",
-                "enabled": false,
-                "reason": "disabled_by_config",
-                "message": "telemetry disabled",
-                "metrics": null,
-            }),
-            serde_json::json!({
-                "status": "disabled",
-                "enabled": false,
-                "reason": "disabled_by_config",
-                "message": "telemetry disabled",
-                "series": [],
-            }),
-            serde_json::json!({
-                "status": "disabled",
-                "enabled": false,
-                "reason": "disabled_by_config",
-                "message": "telemetry disabled",
-                "supported_statuses": ["disabled", "enabled", "degraded", "partial"],
+    async fn test_jellyfin_default_server_and_server_suggestions() {
+        // Each library is named after the token it was fetched with.
+        let mock = Router::new().route(
+            "/Library/VirtualFolders",
+            get(|headers: axum::http::HeaderMap| async move {
+                let token = headers["X-Emby-Token"].to_str().unwrap().to_string();
+                Json(serde_json::json!([{"Name": token, "ItemId": "lib", "Locations": []}]))
             }),
         );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, mock).await.unwrap() });
 
-        let current = get_performance_contract_json(&mut app, "/api/performance/current").await;
-        let overview = get_performance_contract_json(&mut app, "/api/performance/overview").await;
-        let exported = get_performance_contract_json(&mut app, "/api/performance/export").await;
-        let capabilities =
-            get_performance_contract_json(&mut app, "/api/performance/capabilities").await;
-
-        for payload in [&current, &overview, &exported, &capabilities] {
-            assert_eq!(payload["status"], "disabled");
-            assert_eq!(payload["enabled"], false);
+        let state = test_state();
+        let mut app = app_router(state.clone());
+        for (name, body) in [
+            (
+                "anime",
+                serde_json::json!({"url": base, "api_key": "anime-key"}),
+            ),
+            (
+                "movies",
+                serde_json::json!({"url": base, "api_key": "movie-key", "default": true}),
+            ),
+        ] {
+            let req = Request::builder()
+                .method("PUT")
+                .uri(format!("/api/jellyfin/servers/{name}"))
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            assert_eq!(send_request(&mut app, req).await.status(), StatusCode::OK);
         }
 
-        assert!(current["metrics"].is_null());
-        assert!(overview["metrics"].is_null());
-        assert_eq!(exported["series"], serde_json::json!([]));
+        let (_, servers) = get_json(&mut app, "/api/jellyfin/servers").await;
+        let defaults: Vec<(&str, bool)> = servers
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|server| {
+                (
+                    server["name"].as_str().unwrap(),
+                    server["default"].as_bool().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(defaults, [("anime", false), ("movies", true)]);
+
+        let (_, libraries) = get_json(&mut app, "/api/jellyfin/libraries").await;
+        assert_eq!(libraries[0]["Name"], "movie-key");
+        let (_, libraries) = get_json(&mut app, "/api/jellyfin/libraries?server=anime").await;
+        assert_eq!(libraries[0]["Name"], "anime-key");
+
+        let (status, suggestions) = get_json(
+            &mut app,
+            "/api/nodes/JellyfinVideo/suggest?param=server&prefix=mo",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
         assert_eq!(
-            capabilities["supported_statuses"],
-            serde_json::json!(["disabled", "enabled", "degraded", "partial"]),
+            suggestions,
+            serde_json::json!([{"value": "movies", "label": format!("movies ({base})"), "kind": "server"}])
+        );
+
+        let req = Request::builder()
+            .method("DELETE")
+            .uri("/api/jellyfin/servers/movies")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(
+            send_request(&mut app, req).await.status(),
+            StatusCode::NO_CONTENT
         );
+        assert_eq!(state.inner.config.read().await.jellyfin.default_server, "");
+        let (status, _) = get_json(&mut app, "/api/jellyfin/libraries").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let _ = std::fs::remove_file(&state.inner.config_path);
     }
 
-    #[tokio::test]
-    async fn test_performance_contract_routes_keep_partial_enabled_with_sparse_metrics() {
-        let mut app = performance_contract_router(
-            serde_json::json!({
-                "status": "partial",
-                "enabled": true,
-                "reason": "gpu_missing",
-                "message": "partial telemetry",
-                "metrics": {
-                    "cpu_util_percent": 24.0,
-                    "gpu_util_percent": null,
-                },
-            }),
-            serde_json::json!({
-                "status": "partial",
-                "enabled": true,
-                "reason": "gpu_missing",
-                "message": "partial telemetry",
-                "metrics": {
-                    "cpu_util_percent": 24.0,
-                    "ram_used_bytes": 2147483648u64,
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_jellyfin_skip_rules_probe_and_skip_jobs() {
+        // ep1 is already 1080p HEVC, ep2 is 720p H.264.
+        let mock = Router::new().route(
+            "/Items",
+            get(
+                |axum::extract::Query(query): axum::extract::Query<HashMap<String, String>>| async move {
+                    let (width, height, codec) = match query["Ids"].as_str() {
+                        "ep1" => (1920, 1080, "hevc"),
+                        _ => (1280, 720, "h264"),
+                    };
+                    Json(serde_json::json!({
+                        "Items": [{
+                            "Id": query["Ids"], "Name": "Episode", "Type": "Episode",
+                            "MediaStreams": [
+                                {"Type": "Video", "Codec": codec, "Width": width, "Height": height},
+                                {"Type": "Audio", "Codec": "aac"}
+                            ]
+                        }],
+                        "TotalRecordCount": 1
+                    }))
                 },
-            }),
-            serde_json::json!({
-                "status": "partial",
-                "enabled": true,
-                "reason": "cpu_only",
-                "message": "partial export",
-                "series": [
-                    {
-                        "timestamp_ms": 1700000000000u64,
-                        "metrics": {
-                            "cpu_util_percent": 21.0,
-                        },
-                    },
-                ],
-            }),
-            serde_json::json!({
-                "status": "partial",
-                "enabled": true,
-                "reason": "cpu_only",
-                "message": "partial capabilities",
-                "supported_statuses": ["disabled", "enabled", "degraded", "partial"],
-            }),
+            ),
         );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, mock).await.unwrap() });
 
-        let current = get_performance_contract_json(&mut app, "/api/performance/current").await;
-        let overview = get_performance_contract_json(&mut app, "/api/performance/overview").await;
-        let exported = get_performance_contract_json(&mut app, "/api/performance/export").await;
-        let capabilities =
-            get_performance_contract_json(&mut app, "/api/performance/capabilities").await;
+        let data_dir = test_data_dir();
+        let mut node_registry = NodeRegistry::new();
+        register_all_nodes(&mut node_registry);
+        let state = AppState::new(
+            node_registry,
+            ModelRegistry::with_builtin_models(test_models_dir()),
+            DashMap::new(),
+            AppConfig::default(),
+            test_config_path(),
+            data_dir.clone(),
+        );
+        let mut app = app_router(state.clone());
 
-        for payload in [&current, &overview, &exported, &capabilities] {
-            assert_eq!(payload["status"], "partial");
-            assert_eq!(payload["enabled"], true);
+        let (status, probe) = get_json(
+            &mut app,
+            &format!("/api/jellyfin/items/ep1/probe?url={base}&api_key=key&min_height=1080&codecs=hevc,av1"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{probe}");
+        assert_eq!(probe["video"]["Codec"], "hevc");
+        assert_eq!(probe["streams"].as_array().unwrap().len(), 2);
+        assert_eq!(probe["meets_target"], true);
+        let (_, probe) = get_json(
+            &mut app,
+            &format!("/api/jellyfin/items/ep2/probe?url={base}&api_key=key&min_height=1080"),
+        )
+        .await;
+        assert_eq!(probe["meets_target"], false);
+        let (_, probe) = get_json(
+            &mut app,
+            &format!("/api/jellyfin/items/ep2/probe?url={base}&api_key=key"),
+        )
+        .await;
+        assert!(probe["meets_target"].is_null());
+
+        let mut statuses = Vec::new();
+        let mut skipped_id = String::new();
+        for item_id in ["ep1", "ep2"] {
+            let workflow = serde_json::json!({
+                "nodes": [{"id": "source", "node_type": "JellyfinVideo", "params": {
+                    "jellyfin_url": base, "api_key": "key", "item_id": item_id,
+                    "skip_min_height": 1080, "skip_codecs": "hevc"
+                }}],
+                "connections": []
+            });
+            let (status, body) = post_json(
+                &mut app,
+                "/api/jobs",
+                None,
+                serde_json::json!({ "workflow": workflow }),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED, "{body}");
+            let job_id = body["id"].as_str().unwrap().to_string();
+            statuses.push(wait_for_job_terminal_status(&state, &job_id).await);
+            let persisted = if item_id == "ep1" {
+                "skipped"
+            } else {
+                "completed"
+            };
+            assert!(wait_for_persisted_status(&data_dir, &job_id, persisted).await);
+            let (_, job) = get_json(&mut app, &format!("/api/jobs/{job_id}")).await;
+            if item_id == "ep1" {
+                skipped_id = job_id;
+                assert_eq!(job["status"], "skipped");
+                assert_eq!(
+                    job["skip_reason"],
+                    "Jellyfin item ep1: source is 1920x1080 hevc, which already meets the target"
+                );
+            } else {
+                assert!(job["skip_reason"].is_null());
+            }
         }
+        assert_eq!(statuses, [JobStatus::Skipped, JobStatus::Completed]);
+
+        let (_, jobs) = get_json(&mut app, "/api/jobs?status=skipped").await;
+        assert_eq!(jobs.as_array().unwrap().len(), 1);
+
+        let restored = test_state_with_data_dir(data_dir.clone());
+        let job = restored.inner.jobs.get(&skipped_id).unwrap();
+        assert_eq!(job.status, JobStatus::Skipped);
+        assert!(job.skip_reason.as_deref().unwrap().contains("1920x1080"));
+        drop(job);
+        let _ = std::fs::remove_dir_all(data_dir);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_jobs_take_a_slot_per_resource_class() {
+        let cpu_workflow = serde_json::json!({
+            "nodes": [
+                {"id": "constant", "node_type": "Constant", "params": {
+                    "type": "Str",
+                    "value": "remux"
+                }},
+                {"id": "print", "node_type": "Print", "params": {}}
+            ],
+            "connections": [
+                {
+                    "from_node": "constant",
+                    "from_port": "value",
+                    "to_node": "print",
+                    "to_port": "value",
+                    "port_type": "Str"
+                }
+            ]
+        });
+        let state_with = |max_cpu_jobs: usize| {
+            let mut node_registry = NodeRegistry::new();
+            register_all_nodes(&mut node_registry);
+            let mut config = AppConfig::default();
+            config.jobs.max_cpu_jobs = max_cpu_jobs;
+            AppState::new(
+                node_registry,
+                ModelRegistry::with_builtin_models(test_models_dir()),
+                DashMap::new(),
+                config,
+                test_config_path(),
+                test_data_dir(),
+            )
+        };
 
+        let state = state_with(2);
+        let mut app = app_router(state.clone());
+        let _gpu = state.inner.class_semaphores[&ResourceClass::Gpu]
+            .clone()
+            .acquire_owned()
+            .await
+            .unwrap();
+        let (status, body) = post_json(
+            &mut app,
+            "/api/jobs",
+            None,
+            serde_json::json!({ "workflow": cpu_workflow }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+        let job_id = body["id"].as_str().unwrap();
         assert_eq!(
-            current["metrics"]["gpu_util_percent"],
-            serde_json::Value::Null
+            wait_for_job_terminal_status(&state, job_id).await,
+            JobStatus::Completed
         );
-        assert!(overview["metrics"].get("gpu_util_percent").is_none());
+
+        let gpu_and_cpu = BTreeSet::from([ResourceClass::Cpu, ResourceClass::Gpu]);
+        assert_eq!(job_semaphores(&state, &gpu_and_cpu).len(), 2);
+
+        // With no CPU slots the job waits for the GPU slot like any other.
+        let state = state_with(0);
+        assert_eq!(job_semaphores(&state, &gpu_and_cpu).len(), 1);
+        let mut app = app_router(state.clone());
+        let gpu = state.inner.class_semaphores[&ResourceClass::Gpu]
+            .clone()
+            .acquire_owned()
+            .await
+            .unwrap();
+        let (status, body) = post_json(
+            &mut app,
+            "/api/jobs",
+            None,
+            serde_json::json!({ "workflow": cpu_workflow }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+        let job_id = body["id"].as_str().unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
         assert_eq!(
-            exported["series"].as_array().map(|rows| rows.len()),
-            Some(1)
+            state.inner.jobs.get(job_id).unwrap().status,
+            JobStatus::Queued
         );
+        drop(gpu);
         assert_eq!(
-            exported["series"][0]["metrics"]["cpu_util_percent"],
-            serde_json::json!(21.0),
+            wait_for_job_terminal_status(&state, job_id).await,
+            JobStatus::Completed
         );
-        assert_eq!(capabilities["supported_statuses"][3], "partial");
     }
 
-    #[tokio::test]
-    async fn test_performance_contract_routes_keep_degraded_enabled_with_fallback_series() {
-        let mut app = performance_contract_router(
-            serde_json::json!({
-                "status": "degraded",
-                "enabled": true,
-                "reason": "sampler_stale",
-                "message": "degraded telemetry",
-                "metrics": {
-                    "cpu_util_percent": 12.0,
-                    "gpu_util_percent": null,
-                },
-            }),
-            serde_json::json!({
-                "status": "degraded",
-                "enabled": true,
-                "reason": "sampler_stale",
-                "message": "degraded telemetry",
-                "metrics": {
-                    "cpu_util_percent": 12.0,
-                    "ram_used_bytes": 3221225472u64,
-                    "ram_total_bytes": 8589934592u64,
-                },
-            }),
-            serde_json::json!({
-                "status": "degraded",
-                "enabled": true,
-                "reason": "export_missing_keys",
-                "message": "degraded export",
-                "series": [
-                    {
-                        "timestamp_ms": 1700000000000u64,
-                        "metrics": {
-                            "temperature_celsius": 61.0,
-                        },
-                    },
-                    {
-                        "timestamp_ms": 1700000001000u64,
-                        "metrics": {
-                            "temperature_celsius": 62.0,
-                        },
-                    },
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_job_waiting_for_gpu_holds_no_other_slot() {
+        let mut node_registry = NodeRegistry::new();
+        register_all_nodes(&mut node_registry);
+        let mut config = AppConfig::default();
+        config.jobs.max_cpu_jobs = 1;
+        let state = AppState::new(
+            node_registry,
+            ModelRegistry::with_builtin_models(test_models_dir()),
+            DashMap::new(),
+            config,
+            test_config_path(),
+            test_data_dir(),
+        );
+        let mut app = app_router(state.clone());
+
+        let gpu = state.inner.class_semaphores[&ResourceClass::Gpu]
+            .clone()
+            .acquire_owned()
+            .await
+            .unwrap();
+        let semaphores = job_semaphores(
+            &state,
+            &BTreeSet::from([ResourceClass::Cpu, ResourceClass::Gpu]),
+        );
+        let gpu_job = tokio::spawn(async move { acquire_slots(&semaphores).await.map(|_| ()) });
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert!(!gpu_job.is_finished());
+
+        let (status, body) = post_json(
+            &mut app,
+            "/api/jobs",
+            None,
+            serde_json::json!({ "workflow": {
+                "nodes": [
+                    {"id": "constant", "node_type": "Constant", "params": {
+                        "type": "Str",
+                        "value": "remux"
+                    }},
+                    {"id": "print", "node_type": "Print", "params": {}}
                 ],
-            }),
-            serde_json::json!({
-                "status": "degraded",
-                "enabled": true,
-                "reason": "sampler_stale",
-                "message": "degraded capabilities",
-                "supported_statuses": ["disabled", "enabled", "degraded", "partial"],
-            }),
+                "connections": [
+                    {
+                        "from_node": "constant",
+                        "from_port": "value",
+                        "to_node": "print",
+                        "to_port": "value",
+                        "port_type": "Str"
+                    }
+                ]
+            }}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+        let job_id = body["id"].as_str().unwrap();
+        assert_eq!(
+            wait_for_job_terminal_status(&state, job_id).await,
+            JobStatus::Completed
         );
 
-        let current = get_performance_contract_json(&mut app, "/api/performance/current").await;
-        let overview = get_performance_contract_json(&mut app, "/api/performance/overview").await;
-        let exported = get_performance_contract_json(&mut app, "/api/performance/export").await;
-        let capabilities =
-            get_performance_contract_json(&mut app, "/api/performance/capabilities").await;
+        drop(gpu);
+        tokio::time::timeout(std::time::Duration::from_secs(5), gpu_job)
+            .await
+            .expect("GPU job should get its slots once the GPU is free")
+            .unwrap()
+            .unwrap();
+    }
 
-        for payload in [&current, &overview, &exported, &capabilities] {
-            assert_eq!(payload["status"], "degraded");
-            assert_eq!(payload["enabled"], true);
-        }
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_pause_and_resume_queued_job() {
+        let data_dir = test_data_dir();
+        let state = test_state_with_data_dir(data_dir.clone());
+        let mut app = app_router(state.clone());
 
+        // Hold the only execution slot so the job stays queued.
+        let slot = state.inner.class_semaphores[&ResourceClass::Gpu]
+            .clone()
+            .acquire_owned()
+            .await
+            .unwrap();
+        let (status, body) = post_json(
+            &mut app,
+            "/api/jobs",
+            None,
+            serde_json::json!({ "workflow": valid_workflow_json() }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+        let job_id = body["id"].as_str().unwrap().to_string();
+        let mut events = state
+            .inner
+            .progress_senders
+            .get(&job_id)
+            .unwrap()
+            .subscribe();
+
+        let pause_uri = format!("/api/jobs/{job_id}/pause");
+        let resume_uri = format!("/api/jobs/{job_id}/resume");
+        let (status, _) = post_json(&mut app, &resume_uri, None, serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, job) = post_json(&mut app, &pause_uri, None, serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::OK, "{job}");
+        assert_eq!(job["status"], "paused");
+        assert!(job["paused_at"].is_string());
         assert_eq!(
-            current["metrics"]["gpu_util_percent"],
-            serde_json::Value::Null
+            events.recv().await.unwrap(),
+            JobWsEvent::Status {
+                status: JobStatus::Paused
+            }
         );
+        let (status, _) = post_json(&mut app, &pause_uri, None, serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (_, jobs) = get_json(&mut app, "/api/jobs?status=paused").await;
+        assert_eq!(jobs.as_array().unwrap().len(), 1);
+
+        // A free slot does not start a paused job.
+        drop(slot);
+        tokio::time::sleep(Duration::from_millis(200)).await;
         assert_eq!(
-            exported["series"].as_array().map(|rows| rows.len()),
-            Some(2),
-            "degraded export should keep deterministic fallback rows",
-        );
-        assert!(
-            overview["metrics"].get("gpu_util_percent").is_none(),
-            "degraded overview allows sparse/missing fields for deterministic fallback",
+            state.inner.jobs.get(&job_id).unwrap().status,
+            JobStatus::Paused
         );
-        assert_eq!(capabilities["supported_statuses"][2], "degraded");
-    }
 
-    #[test]
-    fn test_parse_proc_stat_cpu_line_extracts_total_and_idle_ticks() {
-        let parsed = parse_proc_stat_cpu_line("cpu  100 20 30 400 50 0 10 0 0 0")
-            .expect("cpu line should parse");
-        assert_eq!(parsed.total_ticks, 610);
-        assert_eq!(parsed.idle_ticks, 450);
+        let restored = test_state_with_data_dir(data_dir.clone());
+        let restored_job = restored.inner.jobs.get(&job_id).unwrap();
+        assert_eq!(restored_job.status, JobStatus::Paused);
+        assert!(restored_job.paused_at.is_some());
+        drop(restored_job);
+
+        let (status, job) = post_json(&mut app, &resume_uri, None, serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::OK, "{job}");
+        assert_eq!(job["status"], "queued");
+        assert!(job["paused_at"].is_null());
+        assert_eq!(
+            events.recv().await.unwrap(),
+            JobWsEvent::Status {
+                status: JobStatus::Queued
+            }
+        );
+        wait_for_job_terminal_status(&state, &job_id).await;
+        assert!(state.inner.jobs.get(&job_id).unwrap().started_at.is_some());
+        let _ = std::fs::remove_dir_all(data_dir);
     }
 
-    #[test]
-    fn test_compute_cpu_util_percent_uses_tick_deltas() {
-        let previous = CpuTimes {
-            total_ticks: 1_000,
-            idle_ticks: 400,
-        };
-        let current = CpuTimes {
-            total_ticks: 1_200,
-            idle_ticks: 460,
-        };
+    #[tokio::test]
+    async fn test_resume_refuses_job_with_redacted_params() {
+        let state = test_state();
+        let mut app = app_router(state.clone());
 
-        let percent =
-            compute_cpu_util_percent(previous, current).expect("deltas should produce percent");
-        assert!((percent - 70.0).abs() < 1e-6);
-    }
+        let job_id = format!("paused-redacted-{}", Uuid::new_v4());
+        insert_test_job(
+            &state,
+            build_test_job(
+                job_id.clone(),
+                JobStatus::Paused,
+                Some(HashMap::from([
+                    ("input".to_string(), serde_json::json!("/tmp/source.mkv")),
+                    (
+                        "api_key".to_string(),
+                        serde_json::json!(crate::logging::REDACTION_PLACEHOLDER),
+                    ),
+                ])),
+            ),
+        );
 
-    #[test]
-    fn test_parse_nvidia_smi_gpu_snapshot_parses_util_and_vram() {
-        let snapshot = parse_nvidia_smi_gpu_snapshot("45, 1024, 8192\n")
-            .expect("nvidia-smi gpu row should parse");
-        assert_eq!(snapshot.gpu_util_percent, 45.0);
-        assert_eq!(snapshot.vram_used_bytes, 1024 * BYTES_PER_MIB);
-        assert_eq!(snapshot.vram_total_bytes, 8192 * BYTES_PER_MIB);
+        let (status, body) = post_json(
+            &mut app,
+            &format!("/api/jobs/{job_id}/resume"),
+            None,
+            serde_json::json!({}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT, "{body}");
+        assert!(
+            body["error"].as_str().unwrap().contains("api_key"),
+            "{body}"
+        );
+        assert_eq!(
+            state.inner.jobs.get(&job_id).unwrap().status,
+            JobStatus::Paused
+        );
     }
 
-    #[test]
-    fn test_parse_nvidia_smi_compute_apps_vram_sums_matching_pid_rows() {
-        let stdout = "111, 32\n222, 64\n111, 128\n111, N/A\n";
-        let vram_bytes = parse_nvidia_smi_compute_apps_vram(stdout, 111)
-            .expect("matching pid rows should produce a sum");
-        assert_eq!(vram_bytes, (32 + 128) * BYTES_PER_MIB);
-    }
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_jellyfin_stream_starts_hls_job() {
+        let data_dir = test_data_dir();
+        let state = test_state_with_data_dir(data_dir.clone());
+        let mut app = app_router(state.clone());
 
-    #[test]
-    fn test_enabled_performance_envelope_status_transitions_match_metric_coverage() {
-        let empty_sample = RuntimePerformanceSample {
-            metrics: serde_json::Map::new(),
-            has_cpu_metrics: false,
-            has_memory_metrics: false,
-            has_gpu_metrics: false,
-            has_vram_metrics: false,
-        };
+        let (status, body) = post_json(
+            &mut app,
+            "/api/jellyfin/stream",
+            None,
+            serde_json::json!({
+                "jellyfin_url": "http://jellyfin.local:8096",
+                "api_key": "key",
+                "item_id": "abc123",
+                "workflow": valid_workflow_json()
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+        assert_eq!(body["stream_id"], "abc123");
+        assert_eq!(body["playlist_url"], "/api/streams/abc123/playlist.m3u8");
+
+        let stream_dir = data_dir.join(STREAMS_DIR_NAME).join("abc123");
+        assert!(stream_dir.is_dir());
+        let job_id = body["id"].as_str().unwrap();
+        let params = state
+            .inner
+            .jobs
+            .get(job_id)
+            .unwrap()
+            .params
+            .clone()
+            .unwrap();
         assert_eq!(
-            enabled_performance_envelope(&empty_sample)["status"],
-            "degraded"
+            params["input"],
+            "http://jellyfin.local:8096/Items/abc123/Download?ApiKey=key"
         );
-
-        let partial_sample = RuntimePerformanceSample {
-            metrics: serde_json::Map::new(),
-            has_cpu_metrics: true,
-            has_memory_metrics: true,
-            has_gpu_metrics: false,
-            has_vram_metrics: false,
-        };
         assert_eq!(
-            enabled_performance_envelope(&partial_sample)["status"],
-            "partial"
+            params["output"],
+            stream_dir.join("playlist.m3u8").to_string_lossy().as_ref()
         );
+        wait_for_job_terminal_status(&state, job_id).await;
 
-        let full_sample = RuntimePerformanceSample {
-            metrics: serde_json::Map::new(),
-            has_cpu_metrics: true,
-            has_memory_metrics: true,
-            has_gpu_metrics: true,
-            has_vram_metrics: true,
-        };
-        assert_eq!(
-            enabled_performance_envelope(&full_sample)["status"],
-            "enabled"
+        let (status, body) = post_json(
+            &mut app,
+            "/api/jellyfin/stream",
+            None,
+            serde_json::json!({
+                "jellyfin_url": "http://jellyfin.local:8096",
+                "api_key": "key",
+                "item_id": "season 1/ep 2",
+                "workflow": valid_workflow_json()
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("stream_id"));
+        let _ = std::fs::remove_dir_all(data_dir);
+    }
+
+    #[tokio::test]
+    async fn test_job_chunks_endpoint_only_serves_chunked_jobs() {
+        let state = test_state();
+        let job_id = Uuid::new_v4().to_string();
+        insert_test_job(
+            &state,
+            build_test_job(job_id.clone(), JobStatus::Completed, None),
         );
+        let mut app = app_router(state);
+
+        let req = Request::builder()
+            .uri(format!("/api/jobs/{job_id}/chunks"))
+            .body(Body::empty())
+            .unwrap();
+        let resp = send_request(&mut app, req).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
     }
 }
@@ -0,0 +1,196 @@
+//! Deleting, renaming and measuring the model files in the model
+//! directories.
+//!
+//! A model's sidecar `<stem>.json` is renamed and deleted with it. Files
+//! are looked up in `paths.models_dir` first and then
+//! `paths.extra_models_dirs`, the order model discovery uses.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::config::PathsConfig;
+use crate::model_inspect::sanitize_model_filename;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RenameModelRequest {
+    pub filename: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelDiskUsage {
+    pub total_bytes: u64,
+    pub dirs: Vec<ModelDirUsage>,
+    /// Largest first.
+    pub models: Vec<ModelFileUsage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelDirUsage {
+    pub path: PathBuf,
+    pub model_count: usize,
+    pub total_bytes: u64,
+    /// Free space on the directory's disk; unset when it can't be read.
+    pub available_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelFileUsage {
+    pub filename: String,
+    pub path: PathBuf,
+    /// Size of the model and its sidecar.
+    pub size_bytes: u64,
+}
+
+/// Sizes of the `.onnx` files in every model directory. A model that is in
+/// more than one directory is counted where discovery finds it.
+pub(super) fn model_disk_usage(paths: &PathsConfig) -> Result<ModelDiskUsage> {
+    let mut dirs = Vec::new();
+    let mut models: Vec<ModelFileUsage> = Vec::new();
+    for dir in paths.models_dirs() {
+        let mut usage = ModelDirUsage {
+            path: dir.clone(),
+            model_count: 0,
+            total_bytes: 0,
+            available_bytes: available_space(dir),
+        };
+        if dir.is_dir() {
+            let read_dir =
+                fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))?;
+            for path in read_dir
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+            {
+                if !is_model_file(&path) {
+                    continue;
+                }
+                let Some(filename) = path.file_name().and_then(|name| name.to_str()) else {
+                    continue;
+                };
+                if models.iter().any(|model| model.filename == filename) {
+                    continue;
+                }
+                let size_bytes = file_size(&path) + file_size(&path.with_extension("json"));
+                usage.model_count += 1;
+                usage.total_bytes += size_bytes;
+                models.push(ModelFileUsage {
+                    filename: filename.to_string(),
+                    path,
+                    size_bytes,
+                });
+            }
+        }
+        dirs.push(usage);
+    }
+    models.sort_by(|a, b| {
+        b.size_bytes
+            .cmp(&a.size_bytes)
+            .then_with(|| a.filename.cmp(&b.filename))
+    });
+    Ok(ModelDiskUsage {
+        total_bytes: dirs.iter().map(|dir| dir.total_bytes).sum(),
+        dirs,
+        models,
+    })
+}
+
+/// Removes the model file `filename` and its sidecar. Returns the path of
+/// the removed model, or `None` when no model directory has it.
+pub(super) fn delete_model(paths: &PathsConfig, filename: &str) -> Result<Option<PathBuf>> {
+    check_model_filename(filename)?;
+    let Some(path) = paths.find_model_file(filename) else {
+        return Ok(None);
+    };
+    fs::remove_file(&path).with_context(|| format!("failed to delete {}", path.display()))?;
+    let sidecar = path.with_extension("json");
+    if sidecar.is_file() {
+        fs::remove_file(&sidecar)
+            .with_context(|| format!("failed to delete {}", sidecar.display()))?;
+    }
+    Ok(Some(path))
+}
+
+/// Renames the model file `filename` and its sidecar to `new_filename` in
+/// the directory it is in. Returns the new path, or `None` when no model
+/// directory has the model.
+pub(super) fn rename_model(
+    paths: &PathsConfig,
+    filename: &str,
+    new_filename: &str,
+) -> Result<Option<PathBuf>> {
+    check_model_filename(filename)?;
+    check_model_filename(new_filename)?;
+    let Some(path) = paths.find_model_file(filename) else {
+        return Ok(None);
+    };
+    if let Some(existing) = paths.find_model_file(new_filename) {
+        bail!("{} already exists", existing.display());
+    }
+    let new_path = path.with_file_name(new_filename);
+    fs::rename(&path, &new_path).with_context(|| format!("failed to rename {}", path.display()))?;
+    let sidecar = path.with_extension("json");
+    if sidecar.is_file() {
+        let new_sidecar = new_path.with_extension("json");
+        fs::rename(&sidecar, &new_sidecar)
+            .with_context(|| format!("failed to rename {}", sidecar.display()))?;
+    }
+    Ok(Some(new_path))
+}
+
+/// Checks that `filename` can name a model file.
+pub(super) fn check_model_filename(filename: &str) -> Result<()> {
+    sanitize_model_filename(filename)
+        .map_err(|reason| anyhow::anyhow!("invalid name: {reason}"))?;
+    if !is_model_file(Path::new(filename)) {
+        bail!("invalid name: model files must end in .onnx");
+    }
+    Ok(())
+}
+
+fn is_model_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("onnx"))
+}
+
+fn file_size(path: &Path) -> u64 {
+    fs::metadata(path).map(|meta| meta.len()).unwrap_or(0)
+}
+
+#[cfg(unix)]
+fn available_space(dir: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(dir.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    #[allow(clippy::unnecessary_cast)]
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+#[cfg(windows)]
+fn available_space(dir: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let path: Vec<u16> = dir.as_os_str().encode_wide().chain([0]).collect();
+    let mut available = 0u64;
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            path.as_ptr(),
+            &mut available,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    (ok != 0).then_some(available)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn available_space(_dir: &Path) -> Option<u64> {
+    None
+}
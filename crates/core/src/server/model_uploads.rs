@@ -0,0 +1,220 @@
+//! Resumable model uploads.
+//!
+//! An upload is started with the model's file name, size and, optionally,
+//! SHA-256, and then sent in chunks, each at the offset the server has
+//! received so far. Parts are kept under `<data_dir>/model_uploads/` as
+//! `<id>.part` with the upload's details in `<id>.json`, so an interrupted
+//! upload can continue after a reload or a server restart: starting the
+//! same file again returns the unfinished upload and its offset.
+//!
+//! Once every byte is in, the part is checked against the hash and moved
+//! into `paths.models_dir`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::model_files::check_model_filename;
+use crate::model_registry::sha256_file;
+
+pub(super) const MODEL_UPLOADS_DIR_NAME: &str = "model_uploads";
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StartModelUploadRequest {
+    pub filename: String,
+    pub size_bytes: u64,
+    /// Hex SHA-256 of the whole file; the upload is rejected when the
+    /// received bytes don't match it.
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelUpload {
+    pub id: String,
+    pub filename: String,
+    pub size_bytes: u64,
+    pub sha256: Option<String>,
+    /// Where the next chunk starts.
+    #[serde(default)]
+    pub received_bytes: u64,
+    /// Set once the model was verified and moved into the models directory.
+    #[serde(default)]
+    pub complete: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct ModelUploadChunkQuery {
+    pub offset: u64,
+}
+
+impl ModelUpload {
+    pub(super) fn part_path(&self, uploads_dir: &Path) -> PathBuf {
+        uploads_dir.join(format!("{}.part", self.id))
+    }
+
+    fn meta_path(&self, uploads_dir: &Path) -> PathBuf {
+        uploads_dir.join(format!("{}.json", self.id))
+    }
+}
+
+/// Start an upload, or return the unfinished one for the same file so the
+/// client can resume at its `received_bytes`.
+pub(super) fn start_upload(
+    uploads_dir: &Path,
+    request: StartModelUploadRequest,
+) -> Result<ModelUpload> {
+    let filename = request.filename.trim().to_string();
+    check_model_filename(&filename)?;
+    if request.size_bytes == 0 {
+        bail!("size_bytes must be greater than 0");
+    }
+    let sha256 = match request.sha256.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(hash) if hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()) => {
+            Some(hash.to_ascii_lowercase())
+        }
+        Some(_) => bail!("sha256 must be 64 hex digits"),
+    };
+
+    if let Some(upload) = list_uploads(uploads_dir)?.into_iter().find(|upload| {
+        upload.filename == filename
+            && upload.size_bytes == request.size_bytes
+            && upload.sha256 == sha256
+    }) {
+        return Ok(upload);
+    }
+
+    fs::create_dir_all(uploads_dir)
+        .with_context(|| format!("failed to create {}", uploads_dir.display()))?;
+    let upload = ModelUpload {
+        id: uuid::Uuid::new_v4().to_string(),
+        filename,
+        size_bytes: request.size_bytes,
+        sha256,
+        received_bytes: 0,
+        complete: false,
+    };
+    fs::File::create(upload.part_path(uploads_dir))
+        .with_context(|| format!("failed to create upload {}", upload.id))?;
+    let meta = serde_json::to_vec_pretty(&upload)?;
+    fs::write(upload.meta_path(uploads_dir), meta)
+        .with_context(|| format!("failed to save upload {}", upload.id))?;
+    Ok(upload)
+}
+
+/// The unfinished upload `id`, with the bytes received so far.
+pub(super) fn load_upload(uploads_dir: &Path, id: &str) -> Result<Option<ModelUpload>> {
+    // Ids are UUIDs, so they cannot name a file outside the uploads directory.
+    if uuid::Uuid::parse_str(id).is_err() {
+        return Ok(None);
+    }
+    let meta_path = uploads_dir.join(format!("{id}.json"));
+    let meta = match fs::read(&meta_path) {
+        Ok(meta) => meta,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read {}", meta_path.display()))
+        }
+    };
+    let mut upload: ModelUpload = serde_json::from_slice(&meta)
+        .with_context(|| format!("invalid upload {}", meta_path.display()))?;
+    upload.received_bytes = fs::metadata(upload.part_path(uploads_dir))
+        .map(|meta| meta.len())
+        .unwrap_or(0);
+    Ok(Some(upload))
+}
+
+fn list_uploads(uploads_dir: &Path) -> Result<Vec<ModelUpload>> {
+    let Ok(read_dir) = fs::read_dir(uploads_dir) else {
+        return Ok(Vec::new());
+    };
+    let mut uploads = Vec::new();
+    for path in read_dir
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+    {
+        if path.extension().is_some_and(|ext| ext == "json") {
+            let Some(id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            if let Some(upload) = load_upload(uploads_dir, id)? {
+                uploads.push(upload);
+            }
+        }
+    }
+    Ok(uploads)
+}
+
+/// Check the received file against the upload's hash and move it to
+/// `models_dir`. The upload is removed either way, since a file with the
+/// wrong hash can't be fixed by sending more chunks.
+pub(super) fn finish_upload(
+    uploads_dir: &Path,
+    upload: &ModelUpload,
+    models_dir: &Path,
+) -> Result<PathBuf> {
+    let part = upload.part_path(uploads_dir);
+    let result = verify_and_move(&part, upload, models_dir);
+    remove_upload(uploads_dir, upload);
+    result
+}
+
+fn verify_and_move(part: &Path, upload: &ModelUpload, models_dir: &Path) -> Result<PathBuf> {
+    if let Some(expected) = &upload.sha256 {
+        let actual = sha256_file(part)?;
+        if actual != *expected {
+            bail!("upload does not match sha256 {expected} (got {actual})");
+        }
+    }
+
+    fs::create_dir_all(models_dir)
+        .with_context(|| format!("failed to create {}", models_dir.display()))?;
+    let target = models_dir.join(&upload.filename);
+    if target.exists() {
+        bail!("{} already exists", target.display());
+    }
+    // The data and models directories may be on different disks.
+    if fs::rename(part, &target).is_err() {
+        fs::copy(part, &target).with_context(|| format!("failed to write {}", target.display()))?;
+    }
+    Ok(target)
+}
+
+/// Drop an unfinished upload and what it received.
+pub(super) fn remove_upload(uploads_dir: &Path, upload: &ModelUpload) {
+    let _ = fs::remove_file(upload.part_path(uploads_dir));
+    let _ = fs::remove_file(upload.meta_path(uploads_dir));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_upload_resumes_matching_upload() {
+        let dir = tempfile::tempdir().unwrap();
+        let request = |filename: &str, sha256: Option<&str>| StartModelUploadRequest {
+            filename: filename.to_string(),
+            size_bytes: 10,
+            sha256: sha256.map(str::to_string),
+        };
+
+        let upload = start_upload(dir.path(), request("4x_New.onnx", None)).unwrap();
+        assert_eq!(upload.received_bytes, 0);
+        fs::write(upload.part_path(dir.path()), b"12345").unwrap();
+
+        let resumed = start_upload(dir.path(), request("4x_New.onnx", None)).unwrap();
+        assert_eq!(resumed.id, upload.id);
+        assert_eq!(resumed.received_bytes, 5);
+        let other = start_upload(dir.path(), request("4x_Other.onnx", None)).unwrap();
+        assert_ne!(other.id, upload.id);
+
+        assert!(start_upload(dir.path(), request("../4x.onnx", None)).is_err());
+        assert!(start_upload(dir.path(), request("4x.bin", None)).is_err());
+        assert!(start_upload(dir.path(), request("4x.onnx", Some("abc"))).is_err());
+        assert!(load_upload(dir.path(), "../escape").unwrap().is_none());
+    }
+}
@@ -2,18 +2,31 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection};
 use tokio_util::sync::CancellationToken;
-use tracing::warn;
+use tracing::{info, warn};
 
-use super::{Job, JobStatus, PipelineGraph, ProgressUpdate};
+use super::audit::{AuditEntry, AuditQuery};
+use super::{HardwareUsage, Job, JobStatus, PipelineGraph, ProgressHistoryPoint, ProgressUpdate};
+use crate::artifacts::JobArtifact;
+use crate::node_error::NodeError;
+use crate::post_actions::PostActionResult;
 
 const STATUS_QUEUED: &str = "queued";
 const STATUS_RUNNING: &str = "running";
 const STATUS_COMPLETED: &str = "completed";
 const STATUS_FAILED: &str = "failed";
 const STATUS_CANCELLED: &str = "cancelled";
+const STATUS_SKIPPED: &str = "skipped";
+const STATUS_PAUSED: &str = "paused";
+
+const JOBS_DB_FILE_NAME: &str = "jobs.db";
+/// A restored jobs db, moved over `jobs.db` the next time the server starts.
+pub(super) const PENDING_RESTORE_FILE_NAME: &str = "jobs.db.restore";
 
 #[derive(Debug)]
 struct PersistedJobRow {
@@ -25,10 +38,121 @@ struct PersistedJobRow {
     completed_at: Option<DateTime<Utc>>,
     progress_json: Option<String>,
     error: Option<String>,
+    error_details_json: Option<String>,
     params_json: Option<String>,
     workflow_name: String,
     workflow_source: String,
     rerun_of_job_id: Option<String>,
+    outputs_json: Option<String>,
+    tags_json: String,
+    note: Option<String>,
+    post_actions_json: String,
+    skip_reason: Option<String>,
+    paused_at: Option<DateTime<Utc>>,
+    artifacts_json: String,
+}
+
+/// Filters, order and page position for job history listings.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct JobQuery {
+    /// Empty matches every status.
+    pub statuses: Vec<JobStatus>,
+    pub workflow_source: Option<String>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    /// Case-insensitive substring of the workflow name.
+    pub search: Option<String>,
+    /// Exact tag the job must carry.
+    pub tag: Option<String>,
+    pub oldest_first: bool,
+    /// `None` returns every matching job.
+    pub limit: Option<usize>,
+    pub cursor: Option<JobCursor>,
+}
+
+/// Position of the last job on a page: jobs are ordered by `created_at`,
+/// then `id`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct JobCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: String,
+}
+
+impl JobCursor {
+    pub(crate) fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}\n{}", self.created_at.to_rfc3339(), self.id))
+    }
+
+    pub(crate) fn decode(encoded: &str) -> Option<Self> {
+        let raw = String::from_utf8(URL_SAFE_NO_PAD.decode(encoded).ok()?).ok()?;
+        let (created_at, id) = raw.split_once('\n')?;
+        Some(Self {
+            created_at: parse_timestamp(created_at).ok()?,
+            id: id.to_string(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct JobPage {
+    pub ids: Vec<String>,
+    pub next_cursor: Option<JobCursor>,
+}
+
+impl JobQuery {
+    /// In-memory equivalent of the SQL filter, for runs without a jobs db.
+    pub(crate) fn matches(&self, job: &Job) -> bool {
+        (self.statuses.is_empty() || self.statuses.contains(&job.status))
+            && self
+                .workflow_source
+                .as_ref()
+                .is_none_or(|source| *source == job.workflow_source)
+            && self
+                .created_after
+                .is_none_or(|after| job.created_at >= after)
+            && self
+                .created_before
+                .is_none_or(|before| job.created_at < before)
+            && self.search.as_ref().is_none_or(|needle| {
+                job.workflow_name
+                    .to_lowercase()
+                    .contains(&needle.to_lowercase())
+            })
+            && self.tag.as_ref().is_none_or(|tag| job.tags.contains(tag))
+    }
+
+    /// Sort, apply the cursor and cut a page out of already-filtered
+    /// `(created_at, id)` keys.
+    pub(crate) fn paginate(&self, mut keys: Vec<(DateTime<Utc>, String)>) -> JobPage {
+        keys.sort();
+        if !self.oldest_first {
+            keys.reverse();
+        }
+        if let Some(cursor) = &self.cursor {
+            let position = (cursor.created_at, cursor.id.clone());
+            keys.retain(|key| {
+                if self.oldest_first {
+                    *key > position
+                } else {
+                    *key < position
+                }
+            });
+        }
+        let mut next_cursor = None;
+        if let Some(limit) = self.limit {
+            if keys.len() > limit {
+                keys.truncate(limit);
+                next_cursor = keys.last().map(|(created_at, id)| JobCursor {
+                    created_at: *created_at,
+                    id: id.clone(),
+                });
+            }
+        }
+        JobPage {
+            ids: keys.into_iter().map(|(_, id)| id).collect(),
+            next_cursor,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -46,8 +170,12 @@ impl JobsPersistence {
         })?;
 
         let persistence = Self {
-            db_path: data_dir.join("jobs.db"),
+            db_path: data_dir.join(JOBS_DB_FILE_NAME),
         };
+        apply_pending_restore(
+            &persistence.db_path,
+            &data_dir.join(PENDING_RESTORE_FILE_NAME),
+        )?;
         persistence.initialize_schema()?;
         Ok(persistence)
     }
@@ -56,6 +184,18 @@ impl JobsPersistence {
         &self.db_path
     }
 
+    /// Writes a consistent copy of the db to `path`, which must not exist.
+    pub(crate) fn snapshot_to(&self, path: &Path) -> Result<()> {
+        let target = path
+            .to_str()
+            .with_context(|| format!("non UTF-8 snapshot path: {}", path.display()))?;
+        self.with_connection(|conn| {
+            conn.execute("VACUUM INTO ?1", params![target])
+                .map(|_| ())
+                .context("failed to snapshot jobs db")
+        })
+    }
+
     pub(crate) fn upsert_job(&self, job: &Job) -> Result<()> {
         let row = Self::row_from_job(job)?;
         self.with_connection(|conn| self.upsert_row(conn, &row))
@@ -76,7 +216,15 @@ impl JobsPersistence {
                     params_json,
                     workflow_name,
                     workflow_source,
-                    rerun_of_job_id
+                    rerun_of_job_id,
+                    outputs_json,
+                    tags_json,
+                    note,
+                    post_actions_json,
+                    skip_reason,
+                    paused_at,
+                    artifacts_json,
+                    error_details_json
                  FROM jobs
                  ORDER BY created_at ASC, id ASC",
             )?;
@@ -138,6 +286,24 @@ impl JobsPersistence {
                     workflow_name: row.get(9)?,
                     workflow_source: row.get(10)?,
                     rerun_of_job_id: row.get(11)?,
+                    outputs_json: row.get(12)?,
+                    tags_json: row.get(13)?,
+                    note: row.get(14)?,
+                    post_actions_json: row.get(15)?,
+                    skip_reason: row.get(16)?,
+                    paused_at: parse_optional_timestamp(row.get::<_, Option<String>>(17)?)
+                        .map_err(|e| {
+                            rusqlite::Error::FromSqlConversionFailure(
+                                17,
+                                rusqlite::types::Type::Text,
+                                Box::new(std::io::Error::new(
+                                    std::io::ErrorKind::InvalidData,
+                                    e.to_string(),
+                                )),
+                            )
+                        })?,
+                    artifacts_json: row.get(18)?,
+                    error_details_json: row.get(19)?,
                 })
             })?;
 
@@ -153,6 +319,7 @@ impl JobsPersistence {
                     }
                 };
 
+                // Paused jobs never started, so they stay paused until resumed.
                 if matches!(row.status, JobStatus::Queued | JobStatus::Running) {
                     let previous_status = row.status;
                     row.status = JobStatus::Cancelled;
@@ -195,6 +362,55 @@ impl JobsPersistence {
                     None => None,
                 };
 
+                let outputs: Option<HashMap<String, serde_json::Value>> =
+                    match row.outputs_json.as_deref() {
+                        Some(encoded) => match serde_json::from_str(encoded) {
+                            Ok(parsed) => Some(parsed),
+                            Err(err) => {
+                                warn!(job_id = %row.id, error = %err, "Dropping invalid persisted outputs snapshot");
+                                None
+                            }
+                        },
+                        None => None,
+                    };
+
+                let tags: Vec<String> = match serde_json::from_str(&row.tags_json) {
+                    Ok(parsed) => parsed,
+                    Err(err) => {
+                        warn!(job_id = %row.id, error = %err, "Dropping invalid persisted job tags");
+                        Vec::new()
+                    }
+                };
+
+                let post_action_results: Vec<PostActionResult> =
+                    match serde_json::from_str(&row.post_actions_json) {
+                        Ok(parsed) => parsed,
+                        Err(err) => {
+                            warn!(job_id = %row.id, error = %err, "Dropping invalid persisted post action results");
+                            Vec::new()
+                        }
+                    };
+
+                let error_details: Option<NodeError> = match row.error_details_json.as_deref() {
+                    Some(encoded) => match serde_json::from_str(encoded) {
+                        Ok(parsed) => Some(parsed),
+                        Err(err) => {
+                            warn!(job_id = %row.id, error = %err, "Dropping invalid persisted error details");
+                            None
+                        }
+                    },
+                    None => None,
+                };
+
+                let artifacts: Vec<JobArtifact> = match serde_json::from_str(&row.artifacts_json)
+                {
+                    Ok(parsed) => parsed,
+                    Err(err) => {
+                        warn!(job_id = %row.id, error = %err, "Dropping invalid persisted job artifacts");
+                        Vec::new()
+                    }
+                };
+
                 jobs.push(Job {
                     id: row.id,
                     status: row.status,
@@ -204,11 +420,20 @@ impl JobsPersistence {
                     completed_at: row.completed_at,
                     progress,
                     error: row.error,
+                    error_details,
                     cancel_token: CancellationToken::new(),
                     params,
                     workflow_name: row.workflow_name,
                     workflow_source: row.workflow_source,
                     rerun_of_job_id: row.rerun_of_job_id,
+                    outputs,
+                    tags,
+                    note: row.note,
+                    post_action_results,
+                    skip_reason: row.skip_reason,
+                    paused_at: row.paused_at,
+                    artifacts,
+                    step_gate: None,
                 });
             }
 
@@ -216,8 +441,94 @@ impl JobsPersistence {
         })
     }
 
+    /// Ids of the persisted jobs matching `query`, in page order.
+    pub(crate) fn query_job_ids(&self, query: &JobQuery) -> Result<JobPage> {
+        let mut clauses: Vec<String> = Vec::new();
+        let mut values: Vec<SqlValue> = Vec::new();
+
+        if !query.statuses.is_empty() {
+            clauses.push(format!(
+                "status IN ({})",
+                vec!["?"; query.statuses.len()].join(", ")
+            ));
+            values.extend(
+                query
+                    .statuses
+                    .iter()
+                    .map(|status| SqlValue::Text(status_to_str(*status).to_string())),
+            );
+        }
+        if let Some(source) = &query.workflow_source {
+            clauses.push("workflow_source = ?".into());
+            values.push(SqlValue::Text(source.clone()));
+        }
+        if let Some(after) = query.created_after {
+            clauses.push("created_at >= ?".into());
+            values.push(SqlValue::Text(after.to_rfc3339()));
+        }
+        if let Some(before) = query.created_before {
+            clauses.push("created_at < ?".into());
+            values.push(SqlValue::Text(before.to_rfc3339()));
+        }
+        if let Some(search) = &query.search {
+            clauses.push("workflow_name LIKE ? ESCAPE '\\'".into());
+            let escaped = search
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            values.push(SqlValue::Text(format!("%{escaped}%")));
+        }
+        if let Some(tag) = &query.tag {
+            clauses.push("EXISTS (SELECT 1 FROM json_each(jobs.tags_json) WHERE value = ?)".into());
+            values.push(SqlValue::Text(tag.clone()));
+        }
+        if let Some(cursor) = &query.cursor {
+            let op = if query.oldest_first { ">" } else { "<" };
+            clauses.push(format!(
+                "(created_at {op} ? OR (created_at = ? AND id {op} ?))"
+            ));
+            let created_at = cursor.created_at.to_rfc3339();
+            values.push(SqlValue::Text(created_at.clone()));
+            values.push(SqlValue::Text(created_at));
+            values.push(SqlValue::Text(cursor.id.clone()));
+        }
+
+        let mut sql = String::from("SELECT id, created_at FROM jobs");
+        if !clauses.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&clauses.join(" AND "));
+        }
+        sql.push_str(if query.oldest_first {
+            " ORDER BY created_at ASC, id ASC"
+        } else {
+            " ORDER BY created_at DESC, id DESC"
+        });
+        if let Some(limit) = query.limit {
+            // One extra row tells whether another page follows.
+            sql.push_str(&format!(" LIMIT {}", limit + 1));
+        }
+
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map(params_from_iter(values), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+            let mut keys = Vec::new();
+            for row in rows {
+                let (id, created_at) = row.context("failed to read job listing row")?;
+                keys.push((parse_timestamp(&created_at)?, id));
+            }
+            Ok(query.paginate(keys))
+        })
+    }
+
     pub(crate) fn delete_job(&self, job_id: &str) -> Result<usize> {
         self.with_connection(|conn| {
+            conn.execute(
+                "DELETE FROM job_progress_history WHERE job_id = ?1",
+                params![job_id],
+            )
+            .with_context(|| format!("failed to delete progress history for job {job_id}"))?;
             let deleted_rows = conn
                 .execute("DELETE FROM jobs WHERE id = ?1", params![job_id])
                 .with_context(|| format!("failed to delete persisted job {job_id}"))?;
@@ -225,35 +536,248 @@ impl JobsPersistence {
         })
     }
 
-    fn initialize_schema(&self) -> Result<()> {
+    pub(crate) fn append_progress_point(
+        &self,
+        job_id: &str,
+        point: &ProgressHistoryPoint,
+    ) -> Result<()> {
         self.with_connection(|conn| {
-            conn.execute_batch(
-                "PRAGMA journal_mode = WAL;
-                 CREATE TABLE IF NOT EXISTS jobs (
-                    id TEXT PRIMARY KEY,
-                    status TEXT NOT NULL,
-                    workflow_json TEXT NOT NULL,
-                    created_at TEXT NOT NULL,
-                    started_at TEXT,
-                    completed_at TEXT,
-                    progress_json TEXT,
-                    error TEXT,
-                    params_json TEXT,
-                    workflow_name TEXT NOT NULL,
-                    workflow_source TEXT NOT NULL,
-                    rerun_of_job_id TEXT,
-                    updated_at TEXT NOT NULL
-                 );
-                 CREATE INDEX IF NOT EXISTS idx_jobs_created_at ON jobs(created_at DESC);
-                 CREATE INDEX IF NOT EXISTS idx_jobs_status ON jobs(status);",
+            conn.execute(
+                "INSERT INTO job_progress_history (
+                    job_id,
+                    recorded_at,
+                    current_frame,
+                    total_frames,
+                    fps,
+                    eta_seconds,
+                    cpu_util_percent,
+                    gpu_util_percent,
+                    vram_used_bytes
+                 ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    job_id,
+                    point.recorded_at.to_rfc3339(),
+                    point.current_frame as i64,
+                    point.total_frames.map(|total| total as i64),
+                    f64::from(point.fps),
+                    point.eta_seconds,
+                    point.hardware.and_then(|usage| usage.cpu_util_percent),
+                    point.hardware.and_then(|usage| usage.gpu_util_percent),
+                    point
+                        .hardware
+                        .and_then(|usage| usage.vram_used_bytes)
+                        .map(|bytes| bytes as i64),
+                ],
+            )
+            .with_context(|| format!("failed to append progress point for job {job_id}"))?;
+            Ok(())
+        })
+    }
+
+    pub(crate) fn load_progress_history(&self, job_id: &str) -> Result<Vec<ProgressHistoryPoint>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT recorded_at, current_frame, total_frames, fps, eta_seconds,
+                        cpu_util_percent, gpu_util_percent, vram_used_bytes
+                 FROM job_progress_history
+                 WHERE job_id = ?1
+                 ORDER BY recorded_at ASC, seq ASC",
+            )?;
+
+            let raw_rows = stmt.query_map(params![job_id], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, Option<i64>>(2)?,
+                    row.get::<_, f64>(3)?,
+                    row.get::<_, Option<f64>>(4)?,
+                    HardwareUsage {
+                        cpu_util_percent: row.get(5)?,
+                        gpu_util_percent: row.get(6)?,
+                        vram_used_bytes: row.get::<_, Option<i64>>(7)?.map(|bytes| bytes.max(0) as u64),
+                    },
+                ))
+            })?;
+
+            let mut points = Vec::new();
+            for row_result in raw_rows {
+                let (recorded_at, current_frame, total_frames, fps, eta_seconds, hardware) =
+                    row_result.with_context(|| {
+                        format!("failed to read progress history for job {job_id}")
+                    })?;
+                let recorded_at = match parse_timestamp(&recorded_at) {
+                    Ok(ts) => ts,
+                    Err(err) => {
+                        warn!(job_id = %job_id, error = %err, "Skipping progress point with invalid timestamp");
+                        continue;
+                    }
+                };
+
+                points.push(ProgressHistoryPoint {
+                    recorded_at,
+                    current_frame: current_frame.max(0) as u64,
+                    total_frames: total_frames.map(|total| total.max(0) as u64),
+                    fps: fps as f32,
+                    eta_seconds,
+                    hardware: (hardware != HardwareUsage::default()).then_some(hardware),
+                });
+            }
+
+            Ok(points)
+        })
+    }
+
+    pub(crate) fn append_performance_sample(
+        &self,
+        timestamp_ms: i64,
+        metrics: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<()> {
+        let metrics_json =
+            serde_json::to_string(metrics).context("failed to serialize performance sample")?;
+        self.with_connection(|conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO performance_samples (timestamp_ms, metrics_json)
+                 VALUES (?1, ?2)",
+                params![timestamp_ms, metrics_json],
             )
-            .with_context(|| {
+            .context("failed to append performance sample")?;
+            Ok(())
+        })
+    }
+
+    /// Samples with `from_ms <= timestamp_ms <= to_ms`, oldest first. With
+    /// `limit`, only the newest `limit` of them.
+    pub(crate) fn load_performance_samples(
+        &self,
+        from_ms: Option<i64>,
+        to_ms: Option<i64>,
+        limit: Option<usize>,
+    ) -> Result<Vec<(i64, serde_json::Map<String, serde_json::Value>)>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT timestamp_ms, metrics_json FROM (
+                    SELECT timestamp_ms, metrics_json
+                    FROM performance_samples
+                    WHERE timestamp_ms >= ?1 AND timestamp_ms <= ?2
+                    ORDER BY timestamp_ms DESC
+                    LIMIT ?3
+                 ) ORDER BY timestamp_ms ASC",
+            )?;
+            let limit = limit.map_or(-1, |limit| limit as i64);
+            let rows = stmt.query_map(
+                params![
+                    from_ms.unwrap_or(i64::MIN),
+                    to_ms.unwrap_or(i64::MAX),
+                    limit
+                ],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)),
+            )?;
+
+            let mut samples = Vec::new();
+            for row in rows {
+                let (timestamp_ms, metrics_json) =
+                    row.context("failed to read performance sample")?;
+                match serde_json::from_str(&metrics_json) {
+                    Ok(metrics) => samples.push((timestamp_ms, metrics)),
+                    Err(err) => {
+                        warn!(timestamp_ms, error = %err, "Skipping unreadable performance sample")
+                    }
+                }
+            }
+            Ok(samples)
+        })
+    }
+
+    pub(crate) fn prune_performance_samples(&self, before_ms: i64) -> Result<usize> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "DELETE FROM performance_samples WHERE timestamp_ms < ?1",
+                params![before_ms],
+            )
+            .context("failed to prune performance samples")
+        })
+    }
+
+    /// Appends `entry` to the audit log; its `seq` is assigned by the db.
+    pub(crate) fn append_audit_entry(&self, entry: &AuditEntry) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute(
+                "INSERT INTO audit_log (recorded_at, actor, action, method, path, status)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    entry.recorded_at.to_rfc3339(),
+                    entry.actor,
+                    entry.action,
+                    entry.method,
+                    entry.path,
+                    entry.status,
+                ],
+            )
+            .context("failed to append audit entry")?;
+            Ok(())
+        })
+    }
+
+    /// Audit entries matching `query`, newest first.
+    pub(crate) fn load_audit_entries(
+        &self,
+        query: &AuditQuery,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>> {
+        self.with_connection(|conn| {
+            let mut stmt = conn.prepare(
+                "SELECT seq, recorded_at, actor, action, method, path, status
+                 FROM audit_log
+                 WHERE (?1 IS NULL OR action = ?1)
+                   AND (?2 IS NULL OR actor = ?2)
+                   AND (?3 IS NULL OR seq < ?3)
+                 ORDER BY seq DESC
+                 LIMIT ?4",
+            )?;
+            let rows = stmt.query_map(
+                params![query.action, query.actor, query.before, limit as i64],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(1)?,
+                        AuditEntry {
+                            seq: row.get(0)?,
+                            recorded_at: DateTime::<Utc>::UNIX_EPOCH,
+                            actor: row.get(2)?,
+                            action: row.get(3)?,
+                            method: row.get(4)?,
+                            path: row.get(5)?,
+                            status: row.get(6)?,
+                        },
+                    ))
+                },
+            )?;
+
+            let mut entries = Vec::new();
+            for row in rows {
+                let (recorded_at, mut entry) = row.context("failed to read audit entry")?;
+                entry.recorded_at = match parse_timestamp(&recorded_at) {
+                    Ok(ts) => ts,
+                    Err(err) => {
+                        warn!(seq = entry.seq, error = %err, "Skipping audit entry with invalid timestamp");
+                        continue;
+                    }
+                };
+                entries.push(entry);
+            }
+            Ok(entries)
+        })
+    }
+
+    fn initialize_schema(&self) -> Result<()> {
+        self.with_connection(|conn| {
+            conn.execute_batch("PRAGMA journal_mode = WAL;")
+                .context("failed to enable WAL for jobs db")?;
+            run_migrations(conn).with_context(|| {
                 format!(
                     "failed to initialize jobs persistence schema: {}",
                     self.db_path.display()
                 )
-            })?;
-            Ok(())
+            })
         })
     }
 
@@ -282,8 +806,16 @@ impl JobsPersistence {
                 workflow_name,
                 workflow_source,
                 rerun_of_job_id,
-                updated_at
-             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+                updated_at,
+                outputs_json,
+                tags_json,
+                note,
+                post_actions_json,
+                skip_reason,
+                paused_at,
+                artifacts_json,
+                error_details_json
+             ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)
              ON CONFLICT(id) DO UPDATE SET
                 status = excluded.status,
                 workflow_json = excluded.workflow_json,
@@ -296,7 +828,15 @@ impl JobsPersistence {
                 workflow_name = excluded.workflow_name,
                 workflow_source = excluded.workflow_source,
                 rerun_of_job_id = excluded.rerun_of_job_id,
-                updated_at = excluded.updated_at",
+                updated_at = excluded.updated_at,
+                outputs_json = excluded.outputs_json,
+                tags_json = excluded.tags_json,
+                note = excluded.note,
+                post_actions_json = excluded.post_actions_json,
+                skip_reason = excluded.skip_reason,
+                paused_at = excluded.paused_at,
+                artifacts_json = excluded.artifacts_json,
+                error_details_json = excluded.error_details_json",
             params![
                 row.id,
                 status_to_str(row.status),
@@ -311,6 +851,14 @@ impl JobsPersistence {
                 row.workflow_source,
                 row.rerun_of_job_id,
                 updated_at,
+                row.outputs_json,
+                row.tags_json,
+                row.note,
+                row.post_actions_json,
+                row.skip_reason,
+                row.paused_at.map(|ts| ts.to_rfc3339()),
+                row.artifacts_json,
+                row.error_details_json,
             ],
         )
         .with_context(|| format!("failed to upsert persisted job {}", row.id))?;
@@ -330,13 +878,264 @@ impl JobsPersistence {
             progress_json: encode_optional_json(job.progress.as_ref())
                 .context("failed to serialize progress snapshot")?,
             error: job.error.clone(),
-            params_json: encode_optional_json(job.params.as_ref())
-                .context("failed to serialize params snapshot")?,
+            error_details_json: encode_optional_json(job.error_details.as_ref())
+                .context("failed to serialize error details")?,
+            params_json: encode_optional_json(
+                job.params.as_ref().map(super::redact_job_params).as_ref(),
+            )
+            .context("failed to serialize params snapshot")?,
             workflow_name: job.workflow_name.clone(),
             workflow_source: job.workflow_source.clone(),
             rerun_of_job_id: job.rerun_of_job_id.clone(),
+            outputs_json: encode_optional_json(
+                job.outputs.as_ref().map(super::redact_job_params).as_ref(),
+            )
+            .context("failed to serialize outputs snapshot")?,
+            tags_json: serde_json::to_string(&job.tags).context("failed to serialize job tags")?,
+            note: job.note.clone(),
+            post_actions_json: serde_json::to_string(&job.post_action_results)
+                .context("failed to serialize post action results")?,
+            skip_reason: job.skip_reason.clone(),
+            paused_at: job.paused_at,
+            artifacts_json: serde_json::to_string(&job.artifacts)
+                .context("failed to serialize job artifacts")?,
+        })
+    }
+}
+
+/// Replaces `db_path` and its WAL files with a staged restore, if there is
+/// one.
+fn apply_pending_restore(db_path: &Path, staged: &Path) -> Result<()> {
+    if !staged.is_file() {
+        return Ok(());
+    }
+    let wal_files = ["-wal", "-shm"].map(|suffix| {
+        let mut name = db_path.as_os_str().to_owned();
+        name.push(suffix);
+        PathBuf::from(name)
+    });
+    for path in &wal_files {
+        match std::fs::remove_file(path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to remove {}", path.display()));
+            }
+        }
+    }
+    std::fs::rename(staged, db_path).with_context(|| {
+        format!(
+            "failed to move restored jobs db {} into place",
+            staged.display()
+        )
+    })?;
+    info!(db_path = %db_path.display(), "Restored jobs db from backup");
+    Ok(())
+}
+
+/// One step of the jobs db schema.
+struct Migration {
+    version: u32,
+    description: &'static str,
+    apply: fn(&Connection) -> Result<()>,
+}
+
+/// Schema steps in the order they are applied. Append new steps with the
+/// next version; never change a step that has shipped.
+///
+/// Databases from before `schema_version` existed start at version 0 and
+/// may already have some of the columns added by steps 2 to 5, so those use
+/// [`ensure_column`]. Later steps can use plain `ALTER TABLE`.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "jobs, progress history and performance tables",
+        apply: migrate_base_tables,
+    },
+    Migration {
+        version: 2,
+        description: "job outputs, tags and notes",
+        apply: |conn| {
+            ensure_column(conn, "jobs", "outputs_json", "TEXT")?;
+            ensure_column(conn, "jobs", "tags_json", "TEXT NOT NULL DEFAULT '[]'")?;
+            ensure_column(conn, "jobs", "note", "TEXT")
+        },
+    },
+    Migration {
+        version: 3,
+        description: "hardware usage in progress history",
+        apply: |conn| {
+            ensure_column(conn, "job_progress_history", "cpu_util_percent", "REAL")?;
+            ensure_column(conn, "job_progress_history", "gpu_util_percent", "REAL")?;
+            ensure_column(conn, "job_progress_history", "vram_used_bytes", "INTEGER")
+        },
+    },
+    Migration {
+        version: 4,
+        description: "post action results",
+        apply: |conn| {
+            ensure_column(
+                conn,
+                "jobs",
+                "post_actions_json",
+                "TEXT NOT NULL DEFAULT '[]'",
+            )
+        },
+    },
+    Migration {
+        version: 5,
+        description: "skipped and paused jobs",
+        apply: |conn| {
+            ensure_column(conn, "jobs", "skip_reason", "TEXT")?;
+            ensure_column(conn, "jobs", "paused_at", "TEXT")
+        },
+    },
+    Migration {
+        version: 6,
+        description: "job artifacts",
+        apply: |conn| {
+            conn.execute_batch(
+                "ALTER TABLE jobs ADD COLUMN artifacts_json TEXT NOT NULL DEFAULT '[]';",
+            )
+            .context("failed to add jobs.artifacts_json")
+        },
+    },
+    Migration {
+        version: 7,
+        description: "structured job errors",
+        apply: |conn| {
+            conn.execute_batch("ALTER TABLE jobs ADD COLUMN error_details_json TEXT;")
+                .context("failed to add jobs.error_details_json")
+        },
+    },
+    Migration {
+        version: 8,
+        description: "request audit log",
+        apply: |conn| {
+            conn.execute_batch(
+                "CREATE TABLE audit_log (
+                    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+                    recorded_at TEXT NOT NULL,
+                    actor TEXT NOT NULL,
+                    action TEXT NOT NULL,
+                    method TEXT NOT NULL,
+                    path TEXT NOT NULL,
+                    status INTEGER NOT NULL
+                 );
+                 CREATE INDEX idx_audit_log_action ON audit_log(action, seq DESC);",
+            )
+            .context("failed to create audit_log table")
+        },
+    },
+];
+
+/// Version of the newest schema this build knows.
+pub(super) const SCHEMA_VERSION: u32 = MIGRATIONS[MIGRATIONS.len() - 1].version;
+
+fn migrate_base_tables(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS jobs (
+            id TEXT PRIMARY KEY,
+            status TEXT NOT NULL,
+            workflow_json TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            started_at TEXT,
+            completed_at TEXT,
+            progress_json TEXT,
+            error TEXT,
+            params_json TEXT,
+            workflow_name TEXT NOT NULL,
+            workflow_source TEXT NOT NULL,
+            rerun_of_job_id TEXT,
+            updated_at TEXT NOT NULL
+         );
+         CREATE INDEX IF NOT EXISTS idx_jobs_created_at ON jobs(created_at DESC);
+         CREATE INDEX IF NOT EXISTS idx_jobs_status ON jobs(status);
+         CREATE INDEX IF NOT EXISTS idx_jobs_source_created_at
+            ON jobs(workflow_source, created_at DESC);
+         CREATE TABLE IF NOT EXISTS job_progress_history (
+            seq INTEGER PRIMARY KEY AUTOINCREMENT,
+            job_id TEXT NOT NULL,
+            recorded_at TEXT NOT NULL,
+            current_frame INTEGER NOT NULL,
+            total_frames INTEGER,
+            fps REAL NOT NULL,
+            eta_seconds REAL
+         );
+         CREATE INDEX IF NOT EXISTS idx_job_progress_history_job_id
+            ON job_progress_history(job_id, recorded_at);
+         CREATE TABLE IF NOT EXISTS performance_samples (
+            timestamp_ms INTEGER PRIMARY KEY,
+            metrics_json TEXT NOT NULL
+         );",
+    )
+    .context("failed to create base tables")
+}
+
+/// Bring the db up to [`SCHEMA_VERSION`], one transaction per step, so an
+/// interrupted upgrade resumes from the last finished step. A db written by
+/// a newer release is used as is.
+fn run_migrations(conn: &Connection) -> Result<()> {
+    conn.execute_batch("CREATE TABLE IF NOT EXISTS schema_version (version INTEGER NOT NULL);")
+        .context("failed to create schema_version table")?;
+    let current = schema_version(conn)?;
+    if current > SCHEMA_VERSION {
+        warn!(
+            version = current,
+            supported = SCHEMA_VERSION,
+            "Jobs db was written by a newer release; newer columns are ignored"
+        );
+        return Ok(());
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let tx = conn.unchecked_transaction()?;
+        (migration.apply)(&tx).with_context(|| {
+            format!(
+                "jobs db migration {} ({}) failed",
+                migration.version, migration.description
+            )
+        })?;
+        tx.execute("DELETE FROM schema_version", [])?;
+        tx.execute(
+            "INSERT INTO schema_version (version) VALUES (?1)",
+            params![migration.version],
+        )?;
+        tx.commit()?;
+        info!(
+            version = migration.version,
+            description = migration.description,
+            "Applied jobs db migration"
+        );
+    }
+    Ok(())
+}
+
+/// Schema version recorded in the db; 0 for a new db or one from before
+/// versioning.
+pub(super) fn schema_version(conn: &Connection) -> Result<u32> {
+    let version: Option<u32> = conn
+        .query_row("SELECT MAX(version) FROM schema_version", [], |row| {
+            row.get(0)
         })
+        .context("failed to read jobs db schema version")?;
+    Ok(version.unwrap_or(0))
+}
+
+/// Add `column` to an existing `table` created by an older release.
+fn ensure_column(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table})"))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|name| name.ok())
+        .any(|name| name == column);
+    if !exists {
+        conn.execute_batch(&format!(
+            "ALTER TABLE {table} ADD COLUMN {column} {definition};"
+        ))
+        .with_context(|| format!("failed to add column {table}.{column}"))?;
     }
+    Ok(())
 }
 
 fn encode_optional_json<T: serde::Serialize>(value: Option<&T>) -> Result<Option<String>> {
@@ -367,6 +1166,8 @@ fn status_to_str(status: JobStatus) -> &'static str {
         JobStatus::Completed => STATUS_COMPLETED,
         JobStatus::Failed => STATUS_FAILED,
         JobStatus::Cancelled => STATUS_CANCELLED,
+        JobStatus::Skipped => STATUS_SKIPPED,
+        JobStatus::Paused => STATUS_PAUSED,
     }
 }
 
@@ -377,6 +1178,8 @@ fn parse_status(value: &str) -> Option<JobStatus> {
         STATUS_COMPLETED => Some(JobStatus::Completed),
         STATUS_FAILED => Some(JobStatus::Failed),
         STATUS_CANCELLED => Some(JobStatus::Cancelled),
+        STATUS_SKIPPED => Some(JobStatus::Skipped),
+        STATUS_PAUSED => Some(JobStatus::Paused),
         _ => None,
     }
 }
@@ -0,0 +1,152 @@
+use std::fs;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::Serialize;
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::config::AppConfig;
+use crate::graph::PipelineGraph;
+use crate::job_logs;
+use crate::logging::{self, redact_sensitive_text};
+
+use super::JobResponse;
+
+pub(super) const DEFAULT_BUNDLE_LOG_FILES: usize = 3;
+pub(super) const DEFAULT_BUNDLE_CRASH_FILES: usize = 5;
+pub(super) const MAX_BUNDLE_FILES: usize = 20;
+
+/// Everything that goes into a support bundle, gathered up front so the
+/// archive itself can be written off the async runtime.
+pub(super) struct SupportBundleInput {
+    pub data_dir: PathBuf,
+    pub config: AppConfig,
+    pub job: Option<(JobResponse, PipelineGraph)>,
+    pub max_log_files: usize,
+    pub max_crash_files: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct SystemInfo {
+    generated_at: String,
+    app_version: &'static str,
+    os: &'static str,
+    os_family: &'static str,
+    arch: &'static str,
+    available_parallelism: Option<usize>,
+    data_dir: String,
+}
+
+pub(super) fn build_support_bundle(input: &SupportBundleInput) -> Result<Vec<u8>> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    let system_info = SystemInfo {
+        generated_at: Utc::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        os_family: std::env::consts::FAMILY,
+        arch: std::env::consts::ARCH,
+        available_parallelism: std::thread::available_parallelism().ok().map(|n| n.get()),
+        data_dir: input.data_dir.display().to_string(),
+    };
+    write_entry(
+        &mut zip,
+        options,
+        "system.json",
+        &serde_json::to_vec_pretty(&system_info)?,
+    )?;
+
+    let config_toml =
+        toml::to_string_pretty(&input.config).context("failed to serialize config TOML")?;
+    write_entry(
+        &mut zip,
+        options,
+        "config.toml",
+        redact_sensitive_text(&config_toml).as_bytes(),
+    )?;
+
+    let log_dir = input.data_dir.join(logging::DEFAULT_LOG_DIR_NAME);
+    for entry in logging::list_log_files(&log_dir)?
+        .into_iter()
+        .take(input.max_log_files)
+    {
+        add_file(
+            &mut zip,
+            options,
+            &log_dir.join(&entry.name),
+            "logs",
+            &entry.name,
+        )?;
+    }
+
+    let crash_dir = logging::crash_dir_for(&input.data_dir);
+    for entry in logging::list_crash_artifacts(&crash_dir)?
+        .into_iter()
+        .take(input.max_crash_files)
+    {
+        add_file(
+            &mut zip,
+            options,
+            &crash_dir.join(&entry.name),
+            "crash",
+            &entry.name,
+        )?;
+    }
+
+    if let Some((job, workflow)) = &input.job {
+        write_entry(
+            &mut zip,
+            options,
+            "job/job.json",
+            redact_sensitive_text(&serde_json::to_string_pretty(job)?).as_bytes(),
+        )?;
+        write_entry(
+            &mut zip,
+            options,
+            "job/workflow.json",
+            redact_sensitive_text(&serde_json::to_string_pretty(workflow)?).as_bytes(),
+        )?;
+
+        let job_log = job_logs::job_log_file_path(&input.data_dir, &job.id);
+        if job_log.is_file() {
+            add_file(&mut zip, options, &job_log, "job", "job.log.jsonl")?;
+        }
+    }
+
+    let cursor = zip.finish().context("failed to finalize support bundle")?;
+    Ok(cursor.into_inner())
+}
+
+fn add_file(
+    zip: &mut ZipWriter<Cursor<Vec<u8>>>,
+    options: SimpleFileOptions,
+    path: &Path,
+    folder: &str,
+    name: &str,
+) -> Result<()> {
+    let contents = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let redacted = redact_sensitive_text(&String::from_utf8_lossy(&contents));
+    write_entry(
+        zip,
+        options,
+        &format!("{folder}/{name}"),
+        redacted.as_bytes(),
+    )
+}
+
+fn write_entry(
+    zip: &mut ZipWriter<Cursor<Vec<u8>>>,
+    options: SimpleFileOptions,
+    name: &str,
+    contents: &[u8],
+) -> Result<()> {
+    zip.start_file(name, options)
+        .with_context(|| format!("failed to add {name} to support bundle"))?;
+    zip.write_all(contents)
+        .with_context(|| format!("failed to write {name} to support bundle"))?;
+    Ok(())
+}
@@ -0,0 +1,286 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::artifacts::JobArtifact;
+use crate::graph::PipelineGraph;
+use crate::node_error::NodeError;
+
+/// Interval workers are asked to heartbeat at; well below the default
+/// `workers.heartbeat_timeout_secs`.
+pub const WORKER_HEARTBEAT_INTERVAL_SECS: u64 = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterWorkerRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterWorkerResponse {
+    pub worker_id: String,
+    pub heartbeat_interval_secs: u64,
+}
+
+/// A job handed to a worker by `POST /api/workers/{id}/claim`. Secret
+/// references are already resolved.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerJobAssignment {
+    pub job_id: String,
+    pub workflow: PipelineGraph,
+    pub params: Option<HashMap<String, serde_json::Value>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerProgressResponse {
+    /// `true` once the job was cancelled or deleted on the server; the worker
+    /// should stop and report completion.
+    pub cancel: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkerJobOutcome {
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompleteWorkerJobRequest {
+    pub outcome: WorkerJobOutcome,
+    pub error: Option<String>,
+    /// Structured form of `error`.
+    #[serde(default)]
+    pub error_details: Option<NodeError>,
+    /// WorkflowOutput values of a completed job.
+    #[serde(default)]
+    pub outputs: Option<HashMap<String, serde_json::Value>>,
+    /// Files a completed job wrote, as paths on the worker.
+    #[serde(default)]
+    pub artifacts: Vec<JobArtifact>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerInfo {
+    pub id: String,
+    pub name: String,
+    pub registered_at: DateTime<Utc>,
+    pub last_heartbeat_at: DateTime<Utc>,
+    pub current_job_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorkerListResponse {
+    pub workers: Vec<WorkerInfo>,
+    pub queued_job_ids: Vec<String>,
+}
+
+struct WorkerEntry {
+    name: String,
+    registered_at: DateTime<Utc>,
+    last_heartbeat: Instant,
+    last_heartbeat_at: DateTime<Utc>,
+}
+
+/// Registered workers, the queue of jobs waiting for a remote worker, and
+/// which worker holds each claimed job.
+#[derive(Default)]
+pub(super) struct WorkerRegistry {
+    workers: DashMap<String, WorkerEntry>,
+    claims: DashMap<String, String>,
+    pending: Mutex<VecDeque<String>>,
+}
+
+impl WorkerRegistry {
+    pub(super) fn register(&self, name: &str) -> String {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now();
+        self.workers.insert(
+            id.clone(),
+            WorkerEntry {
+                name: name.to_string(),
+                registered_at: now,
+                last_heartbeat: Instant::now(),
+                last_heartbeat_at: now,
+            },
+        );
+        id
+    }
+
+    /// Returns `false` for unknown (or already expired) workers.
+    pub(super) fn heartbeat(&self, worker_id: &str) -> bool {
+        match self.workers.get_mut(worker_id) {
+            Some(mut entry) => {
+                entry.last_heartbeat = Instant::now();
+                entry.last_heartbeat_at = Utc::now();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drop workers silent for longer than `timeout` and return the jobs they
+    /// held, which have been put back at the front of the queue.
+    pub(super) fn expire(&self, timeout: Duration) -> Vec<String> {
+        let now = Instant::now();
+        let mut expired = Vec::new();
+        self.workers.retain(|id, entry| {
+            let alive = now.duration_since(entry.last_heartbeat) <= timeout;
+            if !alive {
+                expired.push(id.clone());
+            }
+            alive
+        });
+        if expired.is_empty() {
+            return Vec::new();
+        }
+
+        let mut released = Vec::new();
+        self.claims.retain(|job_id, worker_id| {
+            let keep = !expired.contains(worker_id);
+            if !keep {
+                released.push(job_id.clone());
+            }
+            keep
+        });
+
+        let mut pending = self.pending.lock().unwrap_or_else(|p| p.into_inner());
+        for job_id in released.iter().rev() {
+            pending.push_front(job_id.clone());
+        }
+        released
+    }
+
+    pub(super) fn enqueue(&self, job_id: &str) {
+        self.pending
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .push_back(job_id.to_string());
+    }
+
+    /// Pop queued job ids until `accept` takes one, and record the claim.
+    /// Rejected ids are dropped from the queue.
+    pub(super) fn claim_next(
+        &self,
+        worker_id: &str,
+        mut accept: impl FnMut(&str) -> bool,
+    ) -> Option<String> {
+        let mut pending = self.pending.lock().unwrap_or_else(|p| p.into_inner());
+        while let Some(job_id) = pending.pop_front() {
+            if accept(&job_id) {
+                self.claims.insert(job_id.clone(), worker_id.to_string());
+                return Some(job_id);
+            }
+        }
+        None
+    }
+
+    pub(super) fn is_claimed_by(&self, job_id: &str, worker_id: &str) -> bool {
+        self.claims
+            .get(job_id)
+            .is_some_and(|owner| owner.as_str() == worker_id)
+    }
+
+    pub(super) fn release(&self, job_id: &str) {
+        self.claims.remove(job_id);
+    }
+
+    pub(super) fn snapshot(&self) -> WorkerListResponse {
+        let mut workers: Vec<WorkerInfo> = self
+            .workers
+            .iter()
+            .map(|entry| WorkerInfo {
+                id: entry.key().clone(),
+                name: entry.name.clone(),
+                registered_at: entry.registered_at,
+                last_heartbeat_at: entry.last_heartbeat_at,
+                current_job_id: self
+                    .claims
+                    .iter()
+                    .find(|claim| claim.value() == entry.key())
+                    .map(|claim| claim.key().clone()),
+            })
+            .collect();
+        workers.sort_by_key(|worker| worker.registered_at);
+
+        let queued_job_ids = self
+            .pending
+            .lock()
+            .unwrap_or_else(|p| p.into_inner())
+            .iter()
+            .cloned()
+            .collect();
+
+        WorkerListResponse {
+            workers,
+            queued_job_ids,
+        }
+    }
+}
+
+/// Compare a presented bearer token with the expected one without an early
+/// exit on the first differing byte.
+pub(super) fn tokens_match(presented: &str, expected: &str) -> bool {
+    let presented = presented.as_bytes();
+    let expected = expected.as_bytes();
+    if presented.len() != expected.len() {
+        return false;
+    }
+    presented
+        .iter()
+        .zip(expected)
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expired_worker_returns_claimed_job_to_front_of_queue() {
+        let registry = WorkerRegistry::default();
+        let stale = registry.register("stale");
+        registry.enqueue("job-a");
+        registry.enqueue("job-b");
+
+        assert_eq!(
+            registry.claim_next(&stale, |_| true),
+            Some("job-a".to_string())
+        );
+        assert!(registry.is_claimed_by("job-a", &stale));
+
+        std::thread::sleep(Duration::from_millis(5));
+        let released = registry.expire(Duration::ZERO);
+        assert_eq!(released, vec!["job-a".to_string()]);
+        assert!(!registry.heartbeat(&stale));
+        assert_eq!(registry.snapshot().queued_job_ids, vec!["job-a", "job-b"]);
+    }
+
+    #[test]
+    fn claim_next_skips_rejected_jobs() {
+        let registry = WorkerRegistry::default();
+        let worker = registry.register("gpu-1");
+        registry.enqueue("deleted");
+        registry.enqueue("job-b");
+
+        let claimed = registry.claim_next(&worker, |job_id| job_id != "deleted");
+        assert_eq!(claimed, Some("job-b".to_string()));
+        assert!(registry.snapshot().queued_job_ids.is_empty());
+        assert_eq!(
+            registry.snapshot().workers[0].current_job_id.as_deref(),
+            Some("job-b")
+        );
+    }
+
+    #[test]
+    fn tokens_match_requires_exact_value() {
+        assert!(tokens_match("s3cret", "s3cret"));
+        assert!(!tokens_match("s3cret", "s3cre"));
+        assert!(!tokens_match("s3creT", "s3cret"));
+    }
+}
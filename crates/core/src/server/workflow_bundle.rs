@@ -0,0 +1,519 @@
+//! Workflow bundles: a zip with a workflow, the sub-workflows its `Workflow`
+//! nodes run and a manifest of the models it needs, so it can be moved to
+//! another machine in one file.
+//!
+//! ```text
+//! manifest.json
+//! workflows/<workflow>.json
+//! workflows/<sub-workflow>.json
+//! ```
+//!
+//! Models are listed, not packed: the manifest carries each model's file
+//! name, SHA-256 and download URL, and the importing side checks its models
+//! directory and fetches what is missing.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+use std::fs;
+use std::io::{Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::benchmark::AUTO_MODEL;
+use crate::descriptor::all_node_descriptors;
+use crate::model_inspect::sanitize_model_filename;
+use crate::model_registry::{download_model_file, sha256_file, ModelEntry};
+
+const MANIFEST_NAME: &str = "manifest.json";
+const WORKFLOWS_FOLDER: &str = "workflows";
+const BUNDLE_FORMAT_VERSION: u32 = 1;
+/// Most bytes one bundle entry may unpack to.
+const MAX_ENTRY_BYTES: u64 = 16 * 1024 * 1024;
+/// Most bytes a bundle may unpack to across all its entries, so a small
+/// zip bomb can't exhaust memory.
+const MAX_UNPACKED_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy)]
+struct UnpackLimits {
+    entry: u64,
+    total: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    /// File name of the exported workflow.
+    pub workflow: String,
+    /// File names of the workflows run by `Workflow` nodes, at any depth.
+    #[serde(default)]
+    pub sub_workflows: Vec<String>,
+    #[serde(default)]
+    pub models: Vec<BundleModel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundleModel {
+    pub name: String,
+    pub filename: String,
+    #[serde(default)]
+    pub sha256: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelStatus {
+    /// Already in the models directory with a matching hash.
+    Present,
+    Downloaded,
+    /// Not in the models directory and not downloaded.
+    Missing,
+    /// In the models directory, but a different file than the bundle names.
+    HashMismatch,
+    DownloadFailed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelCheck {
+    #[serde(flatten)]
+    pub model: BundleModel,
+    pub status: ModelStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Where `build_workflow_bundle` looks up workflows and models.
+pub(super) struct BundleSources {
+    pub workflows_dir: PathBuf,
+    pub presets_dir: PathBuf,
+    pub models_dir: PathBuf,
+    pub models: Vec<ModelEntry>,
+}
+
+impl BundleSources {
+    /// Saved workflows shadow presets of the same name, as they do when
+    /// resolving a workflow interface.
+    pub fn find_workflow(&self, filename: &str) -> Option<PathBuf> {
+        [&self.workflows_dir, &self.presets_dir]
+            .into_iter()
+            .map(|dir| dir.join(filename))
+            .find(|path| path.is_file())
+    }
+}
+
+/// Workflows read out of a bundle, ready to be written to the workflows
+/// directory; the exported workflow comes first.
+pub(super) struct ImportedBundle {
+    pub manifest: BundleManifest,
+    pub workflows: Vec<(String, Vec<u8>)>,
+}
+
+pub(super) fn build_workflow_bundle(filename: &str, sources: &BundleSources) -> Result<Vec<u8>> {
+    let model_ports = model_ports_by_node_type();
+    let mut workflows: Vec<(String, serde_json::Value)> = Vec::new();
+    let mut model_files = BTreeSet::new();
+    let mut seen = HashSet::from([filename.to_string()]);
+    let mut queue = VecDeque::from([filename.to_string()]);
+
+    while let Some(name) = queue.pop_front() {
+        let path = sources
+            .find_workflow(&name)
+            .with_context(|| format!("workflow {name} not found"))?;
+        let contents =
+            fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+        let mut doc: serde_json::Value = serde_json::from_slice(&contents)
+            .with_context(|| format!("{name} is not valid JSON"))?;
+
+        let graph = if doc.get("workflow").is_some() {
+            &mut doc["workflow"]
+        } else {
+            &mut doc
+        };
+        let nodes = graph
+            .get_mut("nodes")
+            .and_then(|nodes| nodes.as_array_mut())
+            .map(|nodes| nodes.as_mut_slice())
+            .unwrap_or_default();
+        for node in nodes {
+            let node_type = node["node_type"].as_str().unwrap_or_default().to_string();
+            let Some(params) = node.get_mut("params").and_then(|p| p.as_object_mut()) else {
+                continue;
+            };
+
+            if node_type == "Workflow" {
+                let Some(sub) = params
+                    .get("workflow_path")
+                    .and_then(|v| v.as_str())
+                    .filter(|v| !v.is_empty())
+                    .and_then(|v| Path::new(v).file_name())
+                    .map(|v| v.to_string_lossy().to_string())
+                else {
+                    continue;
+                };
+                // The bundled copy sits next to the workflow that uses it.
+                params.insert("workflow_path".to_string(), sub.clone().into());
+                if seen.insert(sub.clone()) {
+                    queue.push_back(sub);
+                }
+            }
+
+            for port in model_ports.get(&node_type).into_iter().flatten() {
+                let model_file = params
+                    .get(port)
+                    .and_then(|v| v.as_str())
+                    .filter(|v| !v.is_empty() && *v != AUTO_MODEL)
+                    .and_then(|v| Path::new(v).file_name());
+                if let Some(model_file) = model_file {
+                    model_files.insert(model_file.to_string_lossy().to_string());
+                }
+            }
+        }
+        workflows.push((name, doc));
+    }
+
+    let mut models = Vec::new();
+    for model_file in model_files {
+        let entry = sources.models.iter().find(|e| e.filename == model_file);
+        let local = sources.models_dir.join(&model_file);
+        let sha256 = match entry.and_then(|e| e.sha256.clone()) {
+            Some(hash) => Some(hash),
+            None if local.is_file() => Some(sha256_file(&local)?),
+            None => None,
+        };
+        models.push(BundleModel {
+            name: entry.map_or_else(
+                || {
+                    Path::new(&model_file)
+                        .file_stem()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string()
+                },
+                |e| e.name.clone(),
+            ),
+            url: entry.and_then(|e| e.url.clone()),
+            filename: model_file,
+            sha256,
+        });
+    }
+
+    let manifest = BundleManifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        workflow: filename.to_string(),
+        sub_workflows: workflows
+            .iter()
+            .skip(1)
+            .map(|(name, _)| name.clone())
+            .collect(),
+        models,
+    };
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+    write_entry(
+        &mut zip,
+        options,
+        MANIFEST_NAME,
+        &serde_json::to_vec_pretty(&manifest)?,
+    )?;
+    for (name, doc) in &workflows {
+        write_entry(
+            &mut zip,
+            options,
+            &format!("{WORKFLOWS_FOLDER}/{name}"),
+            &serde_json::to_vec_pretty(doc)?,
+        )?;
+    }
+    let cursor = zip.finish().context("failed to finalize workflow bundle")?;
+    Ok(cursor.into_inner())
+}
+
+/// Reads and checks a bundle without touching the disk.
+pub(super) fn read_workflow_bundle(bytes: &[u8]) -> Result<ImportedBundle> {
+    read_workflow_bundle_limited(
+        bytes,
+        UnpackLimits {
+            entry: MAX_ENTRY_BYTES,
+            total: MAX_UNPACKED_BYTES,
+        },
+    )
+}
+
+fn read_workflow_bundle_limited(bytes: &[u8], limits: UnpackLimits) -> Result<ImportedBundle> {
+    let mut unpacked = 0u64;
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).context("not a zip archive")?;
+    let manifest: BundleManifest = serde_json::from_slice(&read_entry(
+        &mut archive,
+        MANIFEST_NAME,
+        limits,
+        &mut unpacked,
+    )?)
+    .context("invalid bundle manifest")?;
+    if manifest.format_version > BUNDLE_FORMAT_VERSION {
+        bail!(
+            "bundle format {} is newer than the supported format {BUNDLE_FORMAT_VERSION}",
+            manifest.format_version
+        );
+    }
+
+    let mut workflows = Vec::new();
+    for name in std::iter::once(&manifest.workflow).chain(&manifest.sub_workflows) {
+        if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+            bail!("invalid workflow file name in bundle: '{name}'");
+        }
+        let contents = read_entry(
+            &mut archive,
+            &format!("{WORKFLOWS_FOLDER}/{name}"),
+            limits,
+            &mut unpacked,
+        )?;
+        serde_json::from_slice::<serde_json::Value>(&contents)
+            .with_context(|| format!("{name} in bundle is not valid JSON"))?;
+        workflows.push((name.clone(), contents));
+    }
+    for model in &manifest.models {
+        sanitize_model_filename(&model.filename).map_err(|e| {
+            anyhow::anyhow!(
+                "invalid model file name '{}' in bundle: {e}",
+                model.filename
+            )
+        })?;
+    }
+
+    Ok(ImportedBundle {
+        manifest,
+        workflows,
+    })
+}
+
+/// Checks each bundle model against `models_dir`, downloading the missing
+/// ones that have a URL when `download` is set.
+pub(super) fn check_bundle_models(
+    models_dir: &Path,
+    models: &[BundleModel],
+    download: bool,
+) -> Vec<ModelCheck> {
+    models
+        .iter()
+        .map(|model| {
+            let path = models_dir.join(&model.filename);
+            let (status, error) = if path.is_file() {
+                match (&model.sha256, sha256_file(&path)) {
+                    (Some(expected), Ok(actual)) if *expected != actual => {
+                        (ModelStatus::HashMismatch, None)
+                    }
+                    (Some(_), Err(err)) => (ModelStatus::HashMismatch, Some(format!("{err:#}"))),
+                    _ => (ModelStatus::Present, None),
+                }
+            } else {
+                match model.url.as_deref().filter(|_| download) {
+                    Some(url) => match download_model_file(
+                        models_dir,
+                        &model.name,
+                        &model.filename,
+                        url,
+                        model.sha256.as_deref(),
+                    ) {
+                        Ok(_) => (ModelStatus::Downloaded, None),
+                        Err(err) => (ModelStatus::DownloadFailed, Some(format!("{err:#}"))),
+                    },
+                    None => (ModelStatus::Missing, None),
+                }
+            };
+            ModelCheck {
+                model: model.clone(),
+                status,
+                error,
+            }
+        })
+        .collect()
+}
+
+/// Names of the params that pick a model, per node type.
+fn model_ports_by_node_type() -> BTreeMap<String, Vec<String>> {
+    all_node_descriptors()
+        .into_iter()
+        .map(|descriptor| {
+            let ports = descriptor
+                .inputs
+                .into_iter()
+                .filter(|port| port.ui_hint.as_deref() == Some("model_selector"))
+                .map(|port| port.name)
+                .collect();
+            (descriptor.node_type, ports)
+        })
+        .collect()
+}
+
+/// Reads `name`, adding its size to `unpacked` and failing once either
+/// limit is passed.
+fn read_entry(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    name: &str,
+    limits: UnpackLimits,
+    unpacked: &mut u64,
+) -> Result<Vec<u8>> {
+    let file = archive
+        .by_name(name)
+        .with_context(|| format!("bundle has no {name}"))?;
+    let limit = limits.entry.min(limits.total - *unpacked);
+    let mut contents = Vec::new();
+    file.take(limit + 1)
+        .read_to_end(&mut contents)
+        .with_context(|| format!("failed to read {name} from bundle"))?;
+    if contents.len() as u64 > limits.entry {
+        bail!(
+            "{name} in bundle unpacks to more than {} MiB",
+            limits.entry / (1024 * 1024)
+        );
+    }
+    *unpacked += contents.len() as u64;
+    if *unpacked > limits.total {
+        bail!(
+            "bundle unpacks to more than {} MiB",
+            limits.total / (1024 * 1024)
+        );
+    }
+    Ok(contents)
+}
+
+fn write_entry(
+    zip: &mut ZipWriter<Cursor<Vec<u8>>>,
+    options: SimpleFileOptions,
+    name: &str,
+    contents: &[u8],
+) -> Result<()> {
+    zip.start_file(name, options)
+        .with_context(|| format!("failed to add {name} to workflow bundle"))?;
+    zip.write_all(contents)
+        .with_context(|| format!("failed to write {name} to workflow bundle"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(filename: &str, sha256: Option<&str>) -> BundleModel {
+        BundleModel {
+            name: filename.trim_end_matches(".onnx").to_string(),
+            filename: filename.to_string(),
+            sha256: sha256.map(str::to_string),
+            url: None,
+        }
+    }
+
+    #[test]
+    fn test_check_bundle_models_compares_hashes() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.onnx"), b"model a").unwrap();
+        fs::write(dir.path().join("b.onnx"), b"model b").unwrap();
+        let hash_a = sha256_file(&dir.path().join("a.onnx")).unwrap();
+
+        let checks = check_bundle_models(
+            dir.path(),
+            &[
+                model("a.onnx", Some(&hash_a)),
+                model("b.onnx", Some(&hash_a)),
+                model("c.onnx", None),
+            ],
+            true,
+        );
+        let statuses: Vec<_> = checks.iter().map(|c| c.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ModelStatus::Present,
+                ModelStatus::HashMismatch,
+                ModelStatus::Missing
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_workflow_bundle_rejects_unsafe_names() {
+        let bundle = |workflow: &str| {
+            let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+            let options = SimpleFileOptions::default();
+            let manifest = BundleManifest {
+                format_version: BUNDLE_FORMAT_VERSION,
+                workflow: workflow.to_string(),
+                sub_workflows: Vec::new(),
+                models: Vec::new(),
+            };
+            write_entry(
+                &mut zip,
+                options,
+                MANIFEST_NAME,
+                &serde_json::to_vec(&manifest).unwrap(),
+            )
+            .unwrap();
+            write_entry(
+                &mut zip,
+                options,
+                &format!("{WORKFLOWS_FOLDER}/{workflow}"),
+                b"{}",
+            )
+            .unwrap();
+            zip.finish().unwrap().into_inner()
+        };
+
+        let imported = read_workflow_bundle(&bundle("ok.json")).unwrap();
+        assert_eq!(
+            imported.workflows,
+            vec![("ok.json".to_string(), b"{}".to_vec())]
+        );
+        assert!(read_workflow_bundle(&bundle("../escape.json")).is_err());
+        assert!(read_workflow_bundle(b"not a zip").is_err());
+    }
+
+    #[test]
+    fn test_read_workflow_bundle_caps_unpacked_size() {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        let manifest = BundleManifest {
+            format_version: BUNDLE_FORMAT_VERSION,
+            workflow: "big.json".to_string(),
+            sub_workflows: Vec::new(),
+            models: Vec::new(),
+        };
+        write_entry(
+            &mut zip,
+            options,
+            MANIFEST_NAME,
+            &serde_json::to_vec(&manifest).unwrap(),
+        )
+        .unwrap();
+        let workflow = format!("{{\"pad\": \"{}\"}}", " ".repeat(4 * 1024 * 1024));
+        write_entry(
+            &mut zip,
+            options,
+            &format!("{WORKFLOWS_FOLDER}/big.json"),
+            workflow.as_bytes(),
+        )
+        .unwrap();
+        let bytes = zip.finish().unwrap().into_inner();
+        assert!(bytes.len() < 64 * 1024, "{}", bytes.len());
+
+        let limits = |entry, total| UnpackLimits { entry, total };
+        let rejected = |limits| match read_workflow_bundle_limited(&bytes, limits) {
+            Ok(_) => panic!("bundle should be rejected"),
+            Err(err) => err.to_string(),
+        };
+        let err = rejected(limits(1024 * 1024, 8 * 1024 * 1024));
+        assert!(
+            err.contains("big.json in bundle unpacks to more than 1 MiB"),
+            "{err}"
+        );
+        let err = rejected(limits(8 * 1024 * 1024, 2 * 1024 * 1024));
+        assert!(err.contains("bundle unpacks to more than 2 MiB"), "{err}");
+
+        let imported =
+            read_workflow_bundle_limited(&bytes, limits(8 * 1024 * 1024, 8 * 1024 * 1024)).unwrap();
+        assert_eq!(imported.workflows.len(), 1);
+    }
+}
@@ -0,0 +1,114 @@
+//! Previous versions of saved workflows.
+//!
+//! Before a workflow file is overwritten, deleted or restored over, its
+//! current contents are copied to `.versions/<filename>/<millis>.json` in
+//! the workflows directory. Only the newest `workflows.keep_versions` copies
+//! are kept per file.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+const VERSIONS_DIR_NAME: &str = ".versions";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorkflowVersion {
+    /// Milliseconds since the epoch at which this version was replaced.
+    pub version: i64,
+    pub saved_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+fn versions_dir(workflows_dir: &Path, filename: &str) -> PathBuf {
+    workflows_dir.join(VERSIONS_DIR_NAME).join(filename)
+}
+
+fn version_path(workflows_dir: &Path, filename: &str, version: i64) -> PathBuf {
+    versions_dir(workflows_dir, filename).join(format!("{version}.json"))
+}
+
+/// Copies the current contents of `filename` into its history and prunes
+/// the history to `keep` versions. Does nothing when the file does not
+/// exist yet or `keep` is `0`.
+pub(super) fn snapshot_workflow(workflows_dir: &Path, filename: &str, keep: usize) -> Result<()> {
+    let current = workflows_dir.join(filename);
+    if keep == 0 || !current.is_file() {
+        return Ok(());
+    }
+
+    let dir = versions_dir(workflows_dir, filename);
+    fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    // Saves within the same millisecond get consecutive numbers.
+    let mut version = Utc::now().timestamp_millis();
+    while version_path(workflows_dir, filename, version).exists() {
+        version += 1;
+    }
+    let target = version_path(workflows_dir, filename, version);
+    fs::copy(&current, &target).with_context(|| {
+        format!(
+            "failed to copy {} to {}",
+            current.display(),
+            target.display()
+        )
+    })?;
+
+    for stale in list_versions(workflows_dir, filename)?.iter().skip(keep) {
+        let path = version_path(workflows_dir, filename, stale.version);
+        fs::remove_file(&path).with_context(|| format!("failed to remove {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// The stored versions of `filename`, newest first.
+pub(super) fn list_versions(workflows_dir: &Path, filename: &str) -> Result<Vec<WorkflowVersion>> {
+    let dir = versions_dir(workflows_dir, filename);
+    let read_dir = match fs::read_dir(&dir) {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("failed to read {}", dir.display())),
+    };
+
+    let mut versions: Vec<WorkflowVersion> = read_dir
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().is_none_or(|e| e != "json") {
+                return None;
+            }
+            let version: i64 = path.file_stem()?.to_str()?.parse().ok()?;
+            Some(WorkflowVersion {
+                version,
+                saved_at: DateTime::from_timestamp_millis(version)?,
+                size_bytes: entry.metadata().ok()?.len(),
+            })
+        })
+        .collect();
+    versions.sort_by_key(|v| std::cmp::Reverse(v.version));
+    Ok(versions)
+}
+
+/// Contents of one stored version.
+pub(super) fn read_version(workflows_dir: &Path, filename: &str, version: i64) -> Result<Vec<u8>> {
+    let path = version_path(workflows_dir, filename, version);
+    if !path.is_file() {
+        bail!("version {version} of {filename} not found");
+    }
+    fs::read(&path).with_context(|| format!("failed to read {}", path.display()))
+}
+
+/// Puts `version` back as the current contents of `filename`. The contents
+/// it replaces become a version themselves, so a restore can be undone.
+pub(super) fn restore_version(
+    workflows_dir: &Path,
+    filename: &str,
+    version: i64,
+    keep: usize,
+) -> Result<()> {
+    let contents = read_version(workflows_dir, filename, version)?;
+    snapshot_workflow(workflows_dir, filename, keep)?;
+    let current = workflows_dir.join(filename);
+    fs::write(&current, contents).with_context(|| format!("failed to write {}", current.display()))
+}
@@ -0,0 +1,249 @@
+//! Services nodes reach through [`ExecutionContext::services`]: one shared
+//! HTTP client, the secret store, the model session pool, scratch
+//! directories, the job's cancellation token and the torrent settings.
+//!
+//! The server builds one [`NodeServices`] per job. Scratch directories live
+//! under a per-job root that is removed once the last clone is dropped, so
+//! a node that fails halfway leaves nothing behind. A default
+//! `NodeServices` (e.g. from `ExecutionContext::default()`) has no secrets,
+//! is never cancelled and leaves torrents disabled.
+//!
+//! [`ExecutionContext::services`]: crate::node::ExecutionContext::services
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, bail, Context, Result};
+use tokio_util::sync::CancellationToken;
+
+use crate::config::TorrentsConfig;
+use crate::runtime::session_pool::{session_pool, SessionPool};
+use crate::secrets::SecretStore;
+
+/// Connect timeout of the shared HTTP client. Nodes set their own total
+/// timeout per request.
+const HTTP_CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How often [`NodeServices::sleep`] checks for cancellation.
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+static HTTP_CLIENT: OnceLock<reqwest::blocking::Client> = OnceLock::new();
+
+/// Looks up stored secrets by name.
+pub trait SecretResolver: Send + Sync {
+    fn resolve_secret(&self, name: &str) -> Result<Option<String>>;
+}
+
+impl SecretResolver for SecretStore {
+    fn resolve_secret(&self, name: &str) -> Result<Option<String>> {
+        self.get(name)
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct NodeServices {
+    secrets: Option<Arc<dyn SecretResolver>>,
+    scratch: Arc<ScratchRoot>,
+    cancel: CancellationToken,
+    torrents: TorrentsConfig,
+}
+
+impl NodeServices {
+    pub fn with_secrets(mut self, secrets: Arc<dyn SecretResolver>) -> Self {
+        self.secrets = Some(secrets);
+        self
+    }
+
+    /// Create scratch directories under `parent` instead of the system temp
+    /// directory.
+    pub fn with_scratch_dir(mut self, parent: PathBuf) -> Self {
+        self.scratch = Arc::new(ScratchRoot::new(parent));
+        self
+    }
+
+    pub fn with_cancel_token(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    pub fn with_torrents_config(mut self, torrents: TorrentsConfig) -> Self {
+        self.torrents = torrents;
+        self
+    }
+
+    /// The blocking HTTP client every node shares, so connections are
+    /// pooled. Set a total timeout on each request.
+    pub fn http_client(&self) -> Result<&'static reqwest::blocking::Client> {
+        if let Some(client) = HTTP_CLIENT.get() {
+            return Ok(client);
+        }
+        let client = reqwest::blocking::Client::builder()
+            .connect_timeout(HTTP_CONNECT_TIMEOUT)
+            .timeout(None)
+            .build()
+            .context("failed to build the shared HTTP client")?;
+        Ok(HTTP_CLIENT.get_or_init(|| client))
+    }
+
+    /// The stored secret called `name`.
+    pub fn secret(&self, name: &str) -> Result<String> {
+        let secrets = self
+            .secrets
+            .as_ref()
+            .ok_or_else(|| anyhow!("no secret store is available to look up '{name}'"))?;
+        secrets
+            .resolve_secret(name)?
+            .with_context(|| format!("secret '{name}' is not set"))
+    }
+
+    /// The pool of loaded model sessions shared by every job.
+    pub fn session_pool(&self) -> &'static SessionPool {
+        session_pool()
+    }
+
+    /// A new empty directory named after `prefix`, removed with the rest of
+    /// the job's scratch space when the job ends.
+    pub fn temp_dir(&self, prefix: &str) -> Result<PathBuf> {
+        let dir = self
+            .scratch
+            .root()?
+            .join(format!("{prefix}-{}", uuid::Uuid::new_v4().simple()));
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("failed to create scratch dir {}", dir.display()))?;
+        Ok(dir)
+    }
+
+    /// The `[torrents]` settings the `TorrentInput` node runs with.
+    pub fn torrents_config(&self) -> &TorrentsConfig {
+        &self.torrents
+    }
+
+    pub fn cancel_token(&self) -> &CancellationToken {
+        &self.cancel
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+
+    /// Fails once the job has been cancelled, for nodes that poll.
+    pub fn check_cancelled(&self) -> Result<()> {
+        if self.is_cancelled() {
+            bail!("job was cancelled");
+        }
+        Ok(())
+    }
+
+    /// Blocks for `duration`, failing early if the job is cancelled in the
+    /// meantime. Use it for retry backoffs and throttling.
+    pub fn sleep(&self, duration: Duration) -> Result<()> {
+        let deadline = Instant::now() + duration;
+        loop {
+            self.check_cancelled()?;
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                return Ok(());
+            }
+            std::thread::sleep(left.min(CANCEL_POLL_INTERVAL));
+        }
+    }
+}
+
+/// A directory created on first use and removed on drop.
+struct ScratchRoot {
+    parent: PathBuf,
+    dir: OnceLock<PathBuf>,
+}
+
+impl ScratchRoot {
+    fn new(parent: PathBuf) -> Self {
+        Self {
+            parent,
+            dir: OnceLock::new(),
+        }
+    }
+
+    fn root(&self) -> Result<&Path> {
+        if let Some(dir) = self.dir.get() {
+            return Ok(dir);
+        }
+        let dir = self
+            .parent
+            .join(format!("job-{}", uuid::Uuid::new_v4().simple()));
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("failed to create scratch dir {}", dir.display()))?;
+        Ok(self.dir.get_or_init(|| dir))
+    }
+}
+
+impl Default for ScratchRoot {
+    fn default() -> Self {
+        Self::new(std::env::temp_dir().join("videnoa").join("scratch"))
+    }
+}
+
+impl Drop for ScratchRoot {
+    fn drop(&mut self) {
+        if let Some(dir) = self.dir.get() {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_temp_dirs_are_removed_with_the_last_clone() {
+        let parent = tempfile::tempdir().unwrap();
+        let services = NodeServices::default().with_scratch_dir(parent.path().to_path_buf());
+        let clone = services.clone();
+
+        let first = services.temp_dir("cmd").unwrap();
+        let second = clone.temp_dir("cmd").unwrap();
+        assert_ne!(first, second);
+        assert_eq!(first.parent(), second.parent());
+        assert!(first.is_dir() && second.is_dir());
+
+        drop(services);
+        assert!(second.is_dir());
+        drop(clone);
+        assert!(!first.exists() && !second.exists());
+        assert_eq!(std::fs::read_dir(parent.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_secrets_and_cancellation() {
+        struct Fixed;
+        impl SecretResolver for Fixed {
+            fn resolve_secret(&self, name: &str) -> Result<Option<String>> {
+                Ok((name == "token").then(|| "s3cr3t".to_string()))
+            }
+        }
+
+        assert!(NodeServices::default().secret("token").is_err());
+        let cancel = CancellationToken::new();
+        let services = NodeServices::default()
+            .with_secrets(Arc::new(Fixed))
+            .with_cancel_token(cancel.clone());
+        assert_eq!(services.secret("token").unwrap(), "s3cr3t");
+        let err = services.secret("other").unwrap_err();
+        assert!(err.to_string().contains("'other' is not set"), "{err}");
+
+        assert!(services.check_cancelled().is_ok());
+        assert!(services.sleep(Duration::from_millis(1)).is_ok());
+
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            cancel.cancel();
+        });
+        let started = Instant::now();
+        assert!(services.sleep(Duration::from_secs(60)).is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+        canceller.join().unwrap();
+        assert!(services.is_cancelled());
+        assert!(services.check_cancelled().is_err());
+    }
+}
@@ -0,0 +1,325 @@
+//! Step-through execution for debugging workflows.
+//!
+//! A job started in step mode pauses after every node; a job with
+//! breakpoints pauses only after the nodes they name. The executor hands
+//! the node's outputs to a [`Stepper`], which publishes a [`StepPause`]
+//! (redacted value previews, plus a small JPEG of the first frame of any
+//! image or video file the node produced) and then blocks until
+//! [`StepGate::step`] lets the job continue, or the job is cancelled.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use serde::{Deserialize, Serialize};
+
+use crate::graph::NodeInstance;
+use crate::services::NodeServices;
+use crate::trace::summarize_ports;
+use crate::types::PortData;
+
+/// Width frame previews are scaled down to, in pixels.
+pub const FRAME_PREVIEW_WIDTH: u32 = 320;
+
+/// How often a paused job checks whether it was cancelled.
+const STEP_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+const FRAME_PREVIEW_EXTENSIONS: [&str; 14] = [
+    "mkv", "mp4", "m4v", "avi", "mov", "webm", "ts", "m2ts", "png", "jpg", "jpeg", "webp", "bmp",
+    "tif",
+];
+
+/// What a paused job shows about the node it just ran.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepPause {
+    pub node_id: String,
+    pub node_type: String,
+    /// Output previews by port.
+    pub outputs: BTreeMap<String, String>,
+    /// `data:image/jpeg;base64,...` previews of image and video outputs, by
+    /// port.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub frame_previews: BTreeMap<String, String>,
+}
+
+/// Receives a [`StepPause`] each time the job pauses.
+pub type StepSink = Arc<dyn Fn(StepPause) + Send + Sync>;
+
+/// Where a job pauses and how many steps it may take. Steps granted before
+/// the job pauses are kept, so a client may step ahead.
+#[derive(Clone, Default)]
+pub struct StepGate {
+    /// Node ids to pause after; `None` pauses after every node.
+    breakpoints: Option<Arc<BTreeSet<String>>>,
+    permits: Arc<(Mutex<u64>, Condvar)>,
+    paused: Arc<Mutex<Option<StepPause>>>,
+}
+
+impl StepGate {
+    /// Pause after every node.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pause only after the nodes in `breakpoints`.
+    pub fn with_breakpoints(breakpoints: BTreeSet<String>) -> Self {
+        Self {
+            breakpoints: Some(Arc::new(breakpoints)),
+            ..Self::default()
+        }
+    }
+
+    pub fn breakpoints(&self) -> Option<&BTreeSet<String>> {
+        self.breakpoints.as_deref()
+    }
+
+    pub fn pauses_after(&self, node_id: &str) -> bool {
+        self.breakpoints
+            .as_ref()
+            .is_none_or(|breakpoints| breakpoints.contains(node_id))
+    }
+
+    /// The pause the job is waiting in, if any.
+    pub fn paused(&self) -> Option<StepPause> {
+        self.paused
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    /// Let the job continue to its next pause.
+    pub fn step(&self) {
+        let (permits, ready) = &*self.permits;
+        *permits
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) += 1;
+        ready.notify_all();
+    }
+
+    fn set_paused(&self, pause: Option<StepPause>) {
+        *self
+            .paused
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = pause;
+    }
+
+    /// Block until a step is granted, failing if the job is cancelled first.
+    fn wait(&self, services: &NodeServices) -> Result<()> {
+        let (permits, ready) = &*self.permits;
+        let mut permits = permits
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        while *permits == 0 {
+            services.check_cancelled()?;
+            permits = ready
+                .wait_timeout(permits, STEP_POLL_INTERVAL)
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .0;
+        }
+        *permits -= 1;
+        Ok(())
+    }
+}
+
+/// Pauses a job after each node, see the module docs.
+#[derive(Clone)]
+pub struct Stepper {
+    gate: StepGate,
+    sink: StepSink,
+}
+
+impl Stepper {
+    pub fn new(gate: StepGate, sink: StepSink) -> Self {
+        Self { gate, sink }
+    }
+
+    /// Publish `instance`'s outputs and wait for the next step, if the job
+    /// pauses after it.
+    pub fn pause_after(
+        &self,
+        instance: &NodeInstance,
+        outputs: &HashMap<String, PortData>,
+        services: &NodeServices,
+    ) -> Result<()> {
+        if !self.gate.pauses_after(&instance.id) {
+            return Ok(());
+        }
+        let frame_previews = outputs
+            .iter()
+            .filter_map(|(port, data)| match data {
+                PortData::Path(path) => Some((port.clone(), frame_preview(path)?)),
+                _ => None,
+            })
+            .collect();
+        let pause = StepPause {
+            node_id: instance.id.clone(),
+            node_type: instance.node_type.clone(),
+            outputs: summarize_ports(outputs),
+            frame_previews,
+        };
+        self.gate.set_paused(Some(pause.clone()));
+        (self.sink)(pause);
+        let result = self.gate.wait(services);
+        self.gate.set_paused(None);
+        result
+    }
+}
+
+/// The first frame of the image or video at `path`, scaled down to
+/// [`FRAME_PREVIEW_WIDTH`] as a JPEG data URL. `None` for other files or if
+/// ffmpeg can't read it.
+fn frame_preview(path: &Path) -> Option<String> {
+    let is_media = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            FRAME_PREVIEW_EXTENSIONS
+                .iter()
+                .any(|media| media.eq_ignore_ascii_case(ext))
+        });
+    if !is_media || !path.is_file() {
+        return None;
+    }
+
+    let output = crate::runtime::command_for("ffmpeg")
+        .arg("-v")
+        .arg("error")
+        .arg("-i")
+        .arg(path)
+        .args([
+            "-frames:v",
+            "1",
+            "-vf",
+            &format!("scale='min({FRAME_PREVIEW_WIDTH},iw)':-2"),
+            "-f",
+            "image2pipe",
+            "-c:v",
+            "mjpeg",
+            "-",
+        ])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() || output.stdout.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "data:image/jpeg;base64,{}",
+        BASE64.encode(&output.stdout)
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+    use std::time::Instant;
+
+    use tokio_util::sync::CancellationToken;
+
+    use super::*;
+
+    #[test]
+    fn test_stepper_publishes_and_waits_for_step() {
+        let (tx, rx) = mpsc::channel();
+        let tx = Mutex::new(tx);
+        let gate = StepGate::new();
+        let stepper = Stepper::new(
+            gate.clone(),
+            Arc::new(move |pause| {
+                let _ = tx.lock().unwrap().send(pause);
+            }),
+        );
+        let instance = NodeInstance {
+            id: "count".to_string(),
+            node_type: "Constant".to_string(),
+            params: HashMap::new(),
+        };
+        let outputs = HashMap::from([
+            ("value".to_string(), PortData::Int(3)),
+            (
+                "notes".to_string(),
+                PortData::Path("/nonexistent/notes.txt".into()),
+            ),
+        ]);
+
+        let runner = {
+            let stepper = stepper.clone();
+            let instance = instance.clone();
+            std::thread::spawn(move || {
+                stepper.pause_after(&instance, &outputs, &NodeServices::default())
+            })
+        };
+        let pause = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(pause.node_id, "count");
+        assert_eq!(pause.outputs["value"], "3");
+        assert!(pause.frame_previews.is_empty());
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(!runner.is_finished());
+
+        gate.step();
+        runner.join().unwrap().unwrap();
+
+        // A step granted ahead of time is used by the next pause.
+        gate.step();
+        stepper
+            .pause_after(&instance, &HashMap::new(), &NodeServices::default())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_breakpoints_pause_only_at_their_nodes() {
+        let gate = StepGate::with_breakpoints(BTreeSet::from(["upscale".to_string()]));
+        let stepper = Stepper::new(gate.clone(), Arc::new(|_| {}));
+        let node = |id: &str| NodeInstance {
+            id: id.to_string(),
+            node_type: "Print".to_string(),
+            params: HashMap::new(),
+        };
+        assert!(!gate.pauses_after("input"));
+        assert!(StepGate::new().pauses_after("input"));
+
+        // Not a breakpoint: runs straight through.
+        stepper
+            .pause_after(&node("input"), &HashMap::new(), &NodeServices::default())
+            .unwrap();
+
+        let runner = std::thread::spawn(move || {
+            let outputs = HashMap::from([("value".to_string(), PortData::Int(4))]);
+            stepper.pause_after(&node("upscale"), &outputs, &NodeServices::default())
+        });
+        let started = Instant::now();
+        let pause = loop {
+            if let Some(pause) = gate.paused() {
+                break pause;
+            }
+            assert!(started.elapsed() < Duration::from_secs(5));
+            std::thread::sleep(Duration::from_millis(5));
+        };
+        assert_eq!(pause.node_id, "upscale");
+        assert_eq!(pause.outputs["value"], "4");
+
+        gate.step();
+        runner.join().unwrap().unwrap();
+        assert_eq!(gate.paused(), None);
+    }
+
+    #[test]
+    fn test_paused_job_stops_on_cancel() {
+        let cancel = CancellationToken::new();
+        let services = NodeServices::default().with_cancel_token(cancel.clone());
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            cancel.cancel();
+        });
+        let started = Instant::now();
+        assert!(StepGate::new().wait(&services).is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+        canceller.join().unwrap();
+    }
+}
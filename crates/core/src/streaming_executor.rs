@@ -1,11 +1,18 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
 use tokio::sync::{mpsc, watch};
 
+use crate::job_phase::{
+    phase_reporter, report_phase, with_phase_reporter, JobPhase, PhaseReporter,
+};
 use crate::node::{ExecutionContext, FrameProcessor};
+use crate::node_error::NodeFailure;
+use crate::runtime::memory::{memory_governor, MemoryReservation};
 use crate::types::Frame;
 
 pub const DEFAULT_BUFFER_SIZE: usize = 4;
@@ -15,6 +22,8 @@ pub struct IndexedFrame {
     pub timestamp: Option<Duration>,
     pub frame: Frame,
     pub is_scene_change: bool,
+    /// Host memory recorded for `frame` with the memory governor.
+    pub memory: Option<MemoryReservation>,
 }
 
 impl IndexedFrame {
@@ -24,10 +33,111 @@ impl IndexedFrame {
             timestamp: None,
             frame,
             is_scene_change: false,
+            memory: None,
         }
     }
 }
 
+/// Frame counts of one pipeline stage. The stage with a full input queue
+/// and an empty output queue is the one holding the pipeline back.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StageProgress {
+    /// `decode`, `encode`, or the node type of a processing stage.
+    pub name: String,
+    /// Frames the stage has passed on (or written, for `encode`).
+    pub frames: u64,
+    /// Frames waiting in the stage's input queue.
+    pub queued: u64,
+    /// Size of the input queue; 0 for `decode`, which has none.
+    pub queue_capacity: u64,
+}
+
+#[derive(Default)]
+struct StageCounter {
+    name: String,
+    received: AtomicU64,
+    sent: AtomicU64,
+}
+
+impl StageCounter {
+    fn new(name: &str) -> Arc<Self> {
+        Arc::new(Self {
+            name: name.to_string(),
+            ..Default::default()
+        })
+    }
+}
+
+struct PipelineStats {
+    stages: Vec<Arc<StageCounter>>,
+    queue_capacity: u64,
+}
+
+impl PipelineStats {
+    fn snapshot(&self) -> Vec<StageProgress> {
+        let mut upstream_sent = None;
+        self.stages
+            .iter()
+            .map(|stage| {
+                let sent = stage.sent.load(Ordering::Relaxed);
+                let progress = StageProgress {
+                    name: stage.name.clone(),
+                    frames: sent,
+                    // The counters are bumped around the channel operations,
+                    // so the difference can briefly overshoot by one.
+                    queued: upstream_sent.map_or(0, |upstream: u64| {
+                        upstream
+                            .saturating_sub(stage.received.load(Ordering::Relaxed))
+                            .min(self.queue_capacity)
+                    }),
+                    queue_capacity: upstream_sent.map_or(0, |_| self.queue_capacity),
+                };
+                upstream_sent = Some(sent);
+                progress
+            })
+            .collect()
+    }
+}
+
+/// Channels of a stage between decoder and encoder, counting what passes.
+struct StageIo {
+    input: mpsc::Receiver<IndexedFrame>,
+    output: mpsc::Sender<IndexedFrame>,
+    counter: Arc<StageCounter>,
+}
+
+impl StageIo {
+    fn recv(&mut self) -> Option<IndexedFrame> {
+        let frame = self.input.blocking_recv()?;
+        self.counter.received.fetch_add(1, Ordering::Relaxed);
+        Some(frame)
+    }
+
+    /// Returns false once downstream has gone away.
+    fn send(&self, frame: IndexedFrame) -> bool {
+        if self.output.blocking_send(frame).is_err() {
+            return false;
+        }
+        self.counter.sent.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+}
+
+thread_local! {
+    static REPORTING_PIPELINE: RefCell<Option<Arc<PipelineStats>>> = const { RefCell::new(None) };
+}
+
+/// Per-stage counts of the pipeline whose progress callback is running on
+/// this thread. Empty when called from anywhere else.
+pub fn stage_progress() -> Vec<StageProgress> {
+    REPORTING_PIPELINE.with(|slot| {
+        slot.borrow()
+            .as_ref()
+            .map(|stats| stats.snapshot())
+            .unwrap_or_default()
+    })
+}
+
 pub trait FrameSink: Send + 'static {
     fn write_frame(&mut self, frame: &Frame) -> Result<()>;
     fn finish(&mut self) -> Result<()>;
@@ -133,12 +243,19 @@ impl StreamingExecutor {
         let external_cancel_handle =
             spawn_external_cancel_watcher(cancel, cancel_state.clone(), cancel_tx.clone());
 
+        report_phase(JobPhase::Decoding);
+        let reporter = phase_reporter();
         let mut handles = Vec::new();
+        let mut counters = Vec::new();
 
         let (first_tx, first_rx) = mpsc::channel(self.buffer_size);
+        let decoder_counter = StageCounter::new("decode");
+        counters.push(Arc::clone(&decoder_counter));
         handles.push(spawn_decoder_stage(
             decoder,
             first_tx,
+            decoder_counter,
+            reporter.clone(),
             cancel_state.clone(),
             cancel_tx.clone(),
             error_tx.clone(),
@@ -150,10 +267,16 @@ impl StreamingExecutor {
 
             match stage {
                 PipelineStage::Processor(processor) => {
+                    let counter = StageCounter::new(processor.node_type());
+                    counters.push(Arc::clone(&counter));
+                    let io = StageIo {
+                        input: upstream_rx,
+                        output: next_tx,
+                        counter,
+                    };
                     handles.push(spawn_processor_stage(
                         processor,
-                        upstream_rx,
-                        next_tx,
+                        io,
                         total_frames,
                         cancel_state.clone(),
                         cancel_tx.clone(),
@@ -161,10 +284,16 @@ impl StreamingExecutor {
                     ));
                 }
                 PipelineStage::Interpolator(interpolator) => {
+                    let counter = StageCounter::new(interpolator.stage_name());
+                    counters.push(Arc::clone(&counter));
+                    let io = StageIo {
+                        input: upstream_rx,
+                        output: next_tx,
+                        counter,
+                    };
                     handles.push(spawn_interpolator_stage(
                         interpolator,
-                        upstream_rx,
-                        next_tx,
+                        io,
                         total_frames,
                         cancel_state.clone(),
                         cancel_tx.clone(),
@@ -176,12 +305,19 @@ impl StreamingExecutor {
             upstream_rx = next_rx;
         }
 
+        counters.push(StageCounter::new("encode"));
+        let stats = Arc::new(PipelineStats {
+            stages: counters,
+            queue_capacity: self.buffer_size as u64,
+        });
         handles.push(spawn_encoder_stage(
             encoder,
             upstream_rx,
+            stats,
             total_output_frames,
             total_frames,
             progress_callback,
+            reporter,
             cancel_state.clone(),
             cancel_tx.clone(),
             error_tx.clone(),
@@ -261,6 +397,8 @@ fn spawn_external_cancel_watcher(
 fn spawn_decoder_stage<D>(
     mut decoder: D,
     output: mpsc::Sender<IndexedFrame>,
+    counter: Arc<StageCounter>,
+    reporter: Option<PhaseReporter>,
     cancel_state: Arc<AtomicBool>,
     cancel_tx: watch::Sender<bool>,
     error_tx: mpsc::UnboundedSender<anyhow::Error>,
@@ -268,8 +406,12 @@ fn spawn_decoder_stage<D>(
 where
     D: Iterator<Item = Result<Frame>> + Send + 'static,
 {
+    let stage_span = tracing::Span::current();
     tokio::task::spawn_blocking(move || {
-        let result = run_decoder_loop(&mut decoder, output, cancel_state.clone());
+        let _entered = stage_span.enter();
+        let result = with_phase_reporter(reporter, || {
+            run_decoder_loop(&mut decoder, output, &counter, cancel_state.clone())
+        });
         if let Err(error) = result {
             report_task_error(
                 &error_tx,
@@ -283,19 +425,19 @@ where
 
 fn spawn_processor_stage(
     mut processor: Box<dyn FrameProcessor>,
-    input: mpsc::Receiver<IndexedFrame>,
-    output: mpsc::Sender<IndexedFrame>,
+    io: StageIo,
     total_frames: Option<u64>,
     cancel_state: Arc<AtomicBool>,
     cancel_tx: watch::Sender<bool>,
     error_tx: mpsc::UnboundedSender<anyhow::Error>,
 ) -> tokio::task::JoinHandle<()> {
     let stage_name = processor.node_type().to_string();
+    let stage_span = tracing::Span::current();
     tokio::task::spawn_blocking(move || {
+        let _entered = stage_span.enter();
         let result = run_processor_loop(
             &mut processor,
-            input,
-            output,
+            io,
             total_frames,
             cancel_state.clone(),
             &stage_name,
@@ -305,7 +447,7 @@ fn spawn_processor_stage(
                 &error_tx,
                 &cancel_state,
                 &cancel_tx,
-                error.context(format!("processor stage '{stage_name}' failed")),
+                error.context(NodeFailure::stage("processor", &stage_name)),
             );
         }
     })
@@ -313,19 +455,19 @@ fn spawn_processor_stage(
 
 fn spawn_interpolator_stage(
     mut interpolator: Box<dyn FrameInterpolator>,
-    input: mpsc::Receiver<IndexedFrame>,
-    output: mpsc::Sender<IndexedFrame>,
+    io: StageIo,
     total_frames: Option<u64>,
     cancel_state: Arc<AtomicBool>,
     cancel_tx: watch::Sender<bool>,
     error_tx: mpsc::UnboundedSender<anyhow::Error>,
 ) -> tokio::task::JoinHandle<()> {
     let stage_name = interpolator.stage_name().to_string();
+    let stage_span = tracing::Span::current();
     tokio::task::spawn_blocking(move || {
+        let _entered = stage_span.enter();
         let result = run_interpolator_loop(
             &mut interpolator,
-            input,
-            output,
+            io,
             total_frames,
             cancel_state.clone(),
             &stage_name,
@@ -335,7 +477,7 @@ fn spawn_interpolator_stage(
                 &error_tx,
                 &cancel_state,
                 &cancel_tx,
-                error.context(format!("interpolator stage '{stage_name}' failed")),
+                error.context(NodeFailure::stage("interpolator", &stage_name)),
             );
         }
     })
@@ -344,9 +486,11 @@ fn spawn_interpolator_stage(
 fn spawn_encoder_stage<E>(
     mut encoder: E,
     input: mpsc::Receiver<IndexedFrame>,
+    stats: Arc<PipelineStats>,
     total_output_frames: Option<u64>,
     total_input_frames: Option<u64>,
     progress_callback: Option<Box<dyn Fn(u64, Option<u64>, Option<u64>) + Send>>,
+    reporter: Option<PhaseReporter>,
     cancel_state: Arc<AtomicBool>,
     cancel_tx: watch::Sender<bool>,
     error_tx: mpsc::UnboundedSender<anyhow::Error>,
@@ -354,47 +498,56 @@ fn spawn_encoder_stage<E>(
 where
     E: FrameSink,
 {
+    let stage_span = tracing::Span::current();
     tokio::task::spawn_blocking(move || {
-        let result = run_encoder_loop(
-            &mut encoder,
-            input,
-            total_output_frames,
-            total_input_frames,
-            progress_callback,
-            cancel_state.clone(),
-        );
+        let _entered = stage_span.enter();
+        with_phase_reporter(reporter, move || {
+            let result = run_encoder_loop(
+                &mut encoder,
+                input,
+                &stats,
+                total_output_frames,
+                total_input_frames,
+                progress_callback,
+                cancel_state.clone(),
+            );
 
-        match result {
-            Ok(()) => {
-                let finish_result = encoder.finish().context("encoder finish failed");
-                if let Err(error) = finish_result {
-                    if cancel_state.load(Ordering::SeqCst) {
-                        return;
+            match result {
+                Ok(()) => {
+                    if !cancel_state.load(Ordering::SeqCst) {
+                        report_phase(JobPhase::Encoding);
                     }
-
+                    let finish_result = encoder.finish().context("encoder finish failed");
+                    if let Err(error) = finish_result {
+                        if cancel_state.load(Ordering::SeqCst) {
+                            return;
+                        }
+
+                        report_task_error(
+                            &error_tx,
+                            &cancel_state,
+                            &cancel_tx,
+                            error.context("encoder stage failed while finalizing"),
+                        );
+                    }
+                }
+                Err(error) => {
                     report_task_error(
                         &error_tx,
                         &cancel_state,
                         &cancel_tx,
-                        error.context("encoder stage failed while finalizing"),
+                        error.context("encoder stage failed"),
                     );
                 }
             }
-            Err(error) => {
-                report_task_error(
-                    &error_tx,
-                    &cancel_state,
-                    &cancel_tx,
-                    error.context("encoder stage failed"),
-                );
-            }
-        }
+        })
     })
 }
 
 fn run_decoder_loop<D>(
     decoder: &mut D,
     output: mpsc::Sender<IndexedFrame>,
+    counter: &StageCounter,
     cancel_state: Arc<AtomicBool>,
 ) -> Result<()>
 where
@@ -412,14 +565,24 @@ where
         let t_decode = std::time::Instant::now();
         let frame = frame_result.with_context(|| format!("failed to decode frame {index}"))?;
         total_decode_ms += t_decode.elapsed().as_secs_f64() * 1000.0;
+        if index == 0 {
+            report_phase(JobPhase::Processing);
+        }
 
-        let indexed_frame = IndexedFrame::new(index, frame);
+        // Waits here while host memory is over budget and earlier frames
+        // are still being processed downstream.
+        let Some(memory) = memory_governor().admit_frame(frame.byte_len(), &cancel_state) else {
+            break;
+        };
+        let mut indexed_frame = IndexedFrame::new(index, frame);
+        indexed_frame.memory = Some(memory);
 
         let t_send = std::time::Instant::now();
         if output.blocking_send(indexed_frame).is_err() {
             break;
         }
         total_send_ms += t_send.elapsed().as_secs_f64() * 1000.0;
+        counter.sent.fetch_add(1, Ordering::Relaxed);
 
         index = index.saturating_add(1);
     }
@@ -440,8 +603,7 @@ where
 
 fn run_processor_loop(
     processor: &mut Box<dyn FrameProcessor>,
-    mut input: mpsc::Receiver<IndexedFrame>,
-    output: mpsc::Sender<IndexedFrame>,
+    mut io: StageIo,
     total_frames: Option<u64>,
     cancel_state: Arc<AtomicBool>,
     stage_name: &str,
@@ -462,7 +624,7 @@ fn run_processor_loop(
         }
 
         let t_recv = std::time::Instant::now();
-        let Some(mut indexed_frame) = input.blocking_recv() else {
+        let Some(mut indexed_frame) = io.recv() else {
             break;
         };
         total_recv_ms += t_recv.elapsed().as_secs_f64() * 1000.0;
@@ -474,10 +636,13 @@ fn run_processor_loop(
         indexed_frame.frame = processor
             .process_frame(indexed_frame.frame, &ctx)
             .with_context(|| format!("processor '{stage_name}' failed on frame {frame_index}"))?;
+        if let Some(memory) = indexed_frame.memory.as_mut() {
+            memory.resize(indexed_frame.frame.byte_len());
+        }
         total_process_ms += t_process.elapsed().as_secs_f64() * 1000.0;
 
         let t_send = std::time::Instant::now();
-        if output.blocking_send(indexed_frame).is_err() {
+        if !io.send(indexed_frame) {
             break;
         }
         total_send_ms += t_send.elapsed().as_secs_f64() * 1000.0;
@@ -504,8 +669,7 @@ fn run_processor_loop(
 
 fn run_interpolator_loop(
     interpolator: &mut Box<dyn FrameInterpolator>,
-    mut input: mpsc::Receiver<IndexedFrame>,
-    output: mpsc::Sender<IndexedFrame>,
+    mut io: StageIo,
     total_frames: Option<u64>,
     cancel_state: Arc<AtomicBool>,
     stage_name: &str,
@@ -528,7 +692,7 @@ fn run_interpolator_loop(
         }
 
         let t_recv = std::time::Instant::now();
-        let Some(current) = input.blocking_recv() else {
+        let Some(current) = io.recv() else {
             break;
         };
         total_recv_ms += t_recv.elapsed().as_secs_f64() * 1000.0;
@@ -556,10 +720,11 @@ fn run_interpolator_loop(
                 timestamp: prev_timestamp,
                 frame: prev.frame,
                 is_scene_change: prev.is_scene_change,
+                memory: prev.memory,
             };
 
             let t_send = std::time::Instant::now();
-            if output.blocking_send(previous_output).is_err() {
+            if !io.send(previous_output) {
                 return Ok(());
             }
             total_send_ms += t_send.elapsed().as_secs_f64() * 1000.0;
@@ -575,15 +740,17 @@ fn run_interpolator_loop(
                     interpolation_count + 1,
                 );
 
+                let memory = memory_governor().reserve_frame(frame.byte_len());
                 let interpolated = IndexedFrame {
                     index: output_index,
                     timestamp,
                     frame,
                     is_scene_change: current.is_scene_change,
+                    memory: Some(memory),
                 };
 
                 let t_send2 = std::time::Instant::now();
-                if output.blocking_send(interpolated).is_err() {
+                if !io.send(interpolated) {
                     return Ok(());
                 }
                 total_send_ms += t_send2.elapsed().as_secs_f64() * 1000.0;
@@ -604,8 +771,9 @@ fn run_interpolator_loop(
                 timestamp: last.timestamp,
                 frame: last.frame,
                 is_scene_change: last.is_scene_change,
+                memory: last.memory,
             };
-            let _ = output.blocking_send(final_frame);
+            io.send(final_frame);
         }
     }
 
@@ -630,6 +798,7 @@ fn run_interpolator_loop(
 fn run_encoder_loop<E>(
     encoder: &mut E,
     mut input: mpsc::Receiver<IndexedFrame>,
+    stats: &Arc<PipelineStats>,
     total_output_frames: Option<u64>,
     total_input_frames: Option<u64>,
     progress_callback: Option<Box<dyn Fn(u64, Option<u64>, Option<u64>) + Send>>,
@@ -638,6 +807,7 @@ fn run_encoder_loop<E>(
 where
     E: FrameSink,
 {
+    let counter = stats.stages.last().expect("pipeline ends with the encoder");
     let mut written = 0_u64;
     let mut total_recv_ms = 0.0_f64;
     let mut total_encode_ms = 0.0_f64;
@@ -652,6 +822,7 @@ where
             break;
         };
         total_recv_ms += t_recv.elapsed().as_secs_f64() * 1000.0;
+        counter.received.fetch_add(1, Ordering::Relaxed);
 
         let t_enc = std::time::Instant::now();
         encoder
@@ -660,9 +831,12 @@ where
         total_encode_ms += t_enc.elapsed().as_secs_f64() * 1000.0;
 
         written = written.saturating_add(1);
+        counter.sent.fetch_add(1, Ordering::Relaxed);
 
         if let Some(callback) = progress_callback.as_ref() {
+            REPORTING_PIPELINE.with(|slot| *slot.borrow_mut() = Some(Arc::clone(stats)));
             callback(written, total_output_frames, total_input_frames);
+            REPORTING_PIPELINE.with(|slot| *slot.borrow_mut() = None);
         }
     }
 
@@ -1159,6 +1333,43 @@ mod tests {
         assert_eq!(progress.last(), Some(&(6, Some(6), Some(6))));
     }
 
+    #[tokio::test]
+    async fn test_progress_callback_sees_stage_counts() {
+        let executor = StreamingExecutor::new(2);
+        let frames = (0_u8..5).map(sample_frame).map(Ok);
+        let processors: Vec<Box<dyn FrameProcessor>> = vec![Box::new(AddProcessor::new("add", 1))];
+
+        let snapshots = Arc::new(Mutex::new(Vec::new()));
+        let snapshots_clone = snapshots.clone();
+        let callback = Box::new(move |_, _, _| {
+            snapshots_clone
+                .lock()
+                .expect("progress mutex poisoned")
+                .push(stage_progress());
+        });
+
+        let sink = CollectingSink::new(SharedSinkState::new());
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        executor
+            .execute_pipeline(frames, processors, sink, Some(5), cancel_rx, Some(callback))
+            .await
+            .expect("pipeline should complete");
+
+        let snapshots = snapshots.lock().expect("progress mutex poisoned");
+        let last = snapshots.last().expect("callback should have run");
+        let names: Vec<&str> = last.iter().map(|stage| stage.name.as_str()).collect();
+        assert_eq!(names, ["decode", "add", "encode"]);
+        assert_eq!(last[2].frames, 5);
+        assert_eq!(last[0].queue_capacity, 0);
+        assert_eq!(last[1].queue_capacity, 2);
+        for snapshot in snapshots.iter() {
+            assert!(snapshot
+                .iter()
+                .all(|stage| stage.queued <= stage.queue_capacity));
+        }
+        assert!(stage_progress().is_empty());
+    }
+
     #[test]
     fn test_interpolated_timestamp_is_linear() {
         let interpolated = interpolate_timestamp(
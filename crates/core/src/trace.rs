@@ -0,0 +1,322 @@
+//! Per-job execution trace.
+//!
+//! Executors record a [`TraceEvent`] when a node starts and when it finishes,
+//! with short, redacted previews of its inputs and outputs and how long it
+//! ran. Nodes inside a Workflow node are recorded under that node's id in
+//! [`TraceEvent::scope`], so a trace of nested graphs still reads in order.
+//! The server keeps the trace of a running job in memory and writes it to
+//! `<data_dir>/traces/<job_id>.json` when the job ends.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::debug_event::format_port_data_preview;
+use crate::graph::NodeInstance;
+use crate::logging::{redact_json_value, redact_sensitive_text};
+use crate::node_error::NodeError;
+use crate::types::PortData;
+
+/// Sub-directory of the data directory holding finished jobs' traces.
+pub const TRACE_DIR_NAME: &str = "traces";
+/// Events kept per job; later ones are counted in [`JobTraceSnapshot::dropped`].
+pub const MAX_TRACE_EVENTS: usize = 10_000;
+/// Characters kept of each input or output preview.
+pub const TRACE_PREVIEW_MAX_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceEventKind {
+    NodeStart,
+    NodeEnd,
+    NodeFailed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceEvent {
+    pub seq: u64,
+    pub timestamp: DateTime<Utc>,
+    pub kind: TraceEventKind,
+    pub node_id: String,
+    pub node_type: String,
+    /// Ids of the Workflow nodes this node ran inside, outermost first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scope: Vec<String>,
+    /// Input previews by port, on `node_start`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inputs: Option<BTreeMap<String, String>>,
+    /// Output previews by port, on `node_end`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outputs: Option<BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobTraceSnapshot {
+    pub events: Vec<TraceEvent>,
+    /// Events not kept because the trace was full.
+    pub dropped: u64,
+}
+
+#[derive(Default)]
+struct TraceLog {
+    events: Vec<TraceEvent>,
+    next_seq: u64,
+    dropped: u64,
+}
+
+/// Shared recorder for one job. Clones append to the same trace.
+#[derive(Clone, Default)]
+pub struct JobTrace {
+    log: Arc<Mutex<TraceLog>>,
+    scope: Vec<String>,
+}
+
+impl JobTrace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A recorder for the graph run by the Workflow node `node_id`.
+    pub fn scoped(&self, node_id: &str) -> Self {
+        let mut scope = self.scope.clone();
+        scope.push(node_id.to_string());
+        Self {
+            log: Arc::clone(&self.log),
+            scope,
+        }
+    }
+
+    /// Record that `instance` is starting. Call [`NodeSpan::finish`] with its
+    /// result.
+    pub fn start_node(
+        &self,
+        instance: &NodeInstance,
+        inputs: &HashMap<String, PortData>,
+    ) -> NodeSpan {
+        let mut event = self.event(TraceEventKind::NodeStart, &instance.id, &instance.node_type);
+        event.inputs = Some(summarize_ports(inputs));
+        self.push(event);
+        NodeSpan {
+            trace: self.clone(),
+            node_id: instance.id.clone(),
+            node_type: instance.node_type.clone(),
+            started: Instant::now(),
+        }
+    }
+
+    pub fn snapshot(&self) -> JobTraceSnapshot {
+        let log = self.lock();
+        JobTraceSnapshot {
+            events: log.events.clone(),
+            dropped: log.dropped,
+        }
+    }
+
+    fn event(&self, kind: TraceEventKind, node_id: &str, node_type: &str) -> TraceEvent {
+        TraceEvent {
+            seq: 0,
+            timestamp: Utc::now(),
+            kind,
+            node_id: node_id.to_string(),
+            node_type: node_type.to_string(),
+            scope: self.scope.clone(),
+            inputs: None,
+            outputs: None,
+            duration_ms: None,
+            error: None,
+        }
+    }
+
+    fn push(&self, mut event: TraceEvent) {
+        let mut log = self.lock();
+        if log.events.len() >= MAX_TRACE_EVENTS {
+            log.dropped += 1;
+            return;
+        }
+        event.seq = log.next_seq;
+        log.next_seq += 1;
+        log.events.push(event);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, TraceLog> {
+        self.log
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// A node that has started but not yet finished.
+pub struct NodeSpan {
+    trace: JobTrace,
+    node_id: String,
+    node_type: String,
+    started: Instant,
+}
+
+impl NodeSpan {
+    pub fn finish(self, result: Result<&HashMap<String, PortData>, &anyhow::Error>) {
+        let kind = match result {
+            Ok(_) => TraceEventKind::NodeEnd,
+            Err(_) => TraceEventKind::NodeFailed,
+        };
+        let mut event = self.trace.event(kind, &self.node_id, &self.node_type);
+        match result {
+            Ok(outputs) => event.outputs = Some(summarize_ports(outputs)),
+            Err(err) => {
+                event.error = Some(redact_sensitive_text(&NodeError::from_error(err).message));
+            }
+        }
+        event.duration_ms = Some(self.started.elapsed().as_millis() as u64);
+        self.trace.push(event);
+    }
+}
+
+/// Truncated previews of `ports`, with values of secret-looking ports and
+/// credentials inside values masked.
+pub(crate) fn summarize_ports(ports: &HashMap<String, PortData>) -> BTreeMap<String, String> {
+    let mut value = serde_json::Value::Object(
+        ports
+            .iter()
+            .map(|(name, data)| {
+                let (preview, truncated) = format_port_data_preview(data, TRACE_PREVIEW_MAX_CHARS);
+                let preview = if truncated {
+                    format!("{preview}…")
+                } else {
+                    preview
+                };
+                (name.clone(), serde_json::Value::String(preview))
+            })
+            .collect(),
+    );
+    redact_json_value(&mut value);
+
+    match value {
+        serde_json::Value::Object(map) => map
+            .into_iter()
+            .map(|(name, value)| match value {
+                serde_json::Value::String(text) => (name, text),
+                other => (name, other.to_string()),
+            })
+            .collect(),
+        _ => BTreeMap::new(),
+    }
+}
+
+pub fn job_trace_file_path(data_dir: &Path, job_id: &str) -> PathBuf {
+    data_dir.join(TRACE_DIR_NAME).join(format!("{job_id}.json"))
+}
+
+pub fn write_trace_file(path: &Path, trace: &JobTraceSnapshot) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let json = serde_json::to_vec(trace).context("failed to serialize job trace")?;
+    fs::write(path, json).with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// The trace stored at `path`, or `None` if there is none.
+pub fn read_trace_file(path: &Path) -> Result<Option<JobTraceSnapshot>> {
+    let json = match fs::read(path) {
+        Ok(json) => json,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read {}", path.display()));
+        }
+    };
+    serde_json::from_slice(&json)
+        .map(Some)
+        .with_context(|| format!("failed to parse {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node_error::NodeFailure;
+
+    fn instance(id: &str, node_type: &str) -> NodeInstance {
+        NodeInstance {
+            id: id.to_string(),
+            node_type: node_type.to_string(),
+            params: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_trace_records_nodes_in_order() {
+        let trace = JobTrace::new();
+        let inputs = HashMap::from([
+            (
+                "url".to_string(),
+                PortData::Str("https://example.com".into()),
+            ),
+            ("api_key".to_string(), PortData::Str("s3cr3t".into())),
+        ]);
+        let span = trace.start_node(&instance("fetch", "HttpRequest"), &inputs);
+        let outputs = HashMap::from([("status".to_string(), PortData::Int(200))]);
+        span.finish(Ok(&outputs));
+
+        let inner = trace.scoped("sub");
+        let span = inner.start_node(&instance("print", "Print"), &HashMap::new());
+        let err = Err::<(), _>(anyhow::anyhow!("boom"))
+            .with_context(|| NodeFailure::new("node", &instance("print", "Print")))
+            .unwrap_err();
+        span.finish(Err(&err));
+
+        let snapshot = trace.snapshot();
+        let kinds = snapshot
+            .events
+            .iter()
+            .map(|event| (event.seq, event.kind, event.node_id.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                (0, TraceEventKind::NodeStart, "fetch"),
+                (1, TraceEventKind::NodeEnd, "fetch"),
+                (2, TraceEventKind::NodeStart, "print"),
+                (3, TraceEventKind::NodeFailed, "print"),
+            ]
+        );
+
+        let start_inputs = snapshot.events[0].inputs.as_ref().unwrap();
+        assert_eq!(start_inputs["url"], "https://example.com");
+        assert_eq!(
+            start_inputs["api_key"],
+            crate::logging::REDACTION_PLACEHOLDER
+        );
+        assert_eq!(
+            snapshot.events[1].outputs.as_ref().unwrap()["status"],
+            "200"
+        );
+        assert!(snapshot.events[1].duration_ms.is_some());
+        assert!(snapshot.events[0].scope.is_empty());
+        assert_eq!(snapshot.events[3].scope, vec!["sub".to_string()]);
+        assert_eq!(snapshot.events[3].error.as_deref(), Some("boom"));
+    }
+
+    #[test]
+    fn test_trace_file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = job_trace_file_path(dir.path(), "job-1");
+        assert_eq!(read_trace_file(&path).unwrap(), None);
+
+        let trace = JobTrace::new();
+        trace
+            .start_node(&instance("a", "Print"), &HashMap::new())
+            .finish(Ok(&HashMap::new()));
+        let snapshot = trace.snapshot();
+        write_trace_file(&path, &snapshot).unwrap();
+        assert_eq!(read_trace_file(&path).unwrap(), Some(snapshot));
+    }
+}
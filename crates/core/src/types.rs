@@ -35,6 +35,28 @@ pub enum Frame {
     // GpuTensor variant will be added later when ort is integrated into core.
 }
 
+impl Frame {
+    /// Size of the pixel buffer in bytes.
+    pub fn byte_len(&self) -> u64 {
+        let bytes = match self {
+            Frame::CpuRgb { data, .. } => data.len(),
+            Frame::CpuTensor { data, .. } | Frame::NchwF32 { data, .. } => data.len() * 4,
+            Frame::NchwF16 { data, .. } => data.len() * 2,
+        };
+        bytes as u64
+    }
+
+    /// Width and height in pixels.
+    pub fn dimensions(&self) -> (u32, u32) {
+        match self {
+            Frame::CpuRgb { width, height, .. }
+            | Frame::CpuTensor { width, height, .. }
+            | Frame::NchwF32 { width, height, .. }
+            | Frame::NchwF16 { width, height, .. } => (*width, *height),
+        }
+    }
+}
+
 /// Stream info for non-video streams.
 pub struct StreamInfo {
     pub index: usize,
@@ -55,6 +77,8 @@ pub struct Chapter {
 /// Media metadata passthrough.
 pub struct MediaMetadata {
     pub source_path: PathBuf,
+    /// Every video stream in the source, including the one being decoded.
+    pub video_streams: Vec<StreamInfo>,
     pub audio_streams: Vec<StreamInfo>,
     pub subtitle_streams: Vec<StreamInfo>,
     pub attachment_streams: Vec<StreamInfo>,
@@ -75,6 +99,8 @@ pub enum PortType {
     Bool,
     Path,
     WorkflowPath,
+    /// Structured data, e.g. an API response or an analysis report.
+    Json,
 }
 
 impl PortType {
@@ -93,6 +119,10 @@ pub enum PortData {
     Str(String),
     Bool(bool),
     Path(PathBuf),
+    Json(serde_json::Value),
+    /// Values of every connection into a list port, in connection-index
+    /// order.
+    List(Vec<PortData>),
 }
 
 #[cfg(test)]
@@ -142,6 +172,7 @@ mod tests {
         let source_path = std::env::temp_dir().join("input.mkv");
         let media_metadata = MediaMetadata {
             source_path: source_path.clone(),
+            video_streams: vec![],
             audio_streams: vec![audio_stream],
             subtitle_streams: vec![],
             attachment_streams: vec![],
@@ -0,0 +1,220 @@
+//! Checks the project's GitHub releases for a newer version.
+//!
+//! The stable channel only looks at full releases; beta also considers
+//! pre-releases. Tags are read as semver with an optional leading `v`, and
+//! releases whose tag doesn't parse are skipped. A release that ships a
+//! `latest.json` asset can be installed by the desktop app's updater.
+
+use std::fs;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::config::UpdateChannel;
+
+/// Asset the desktop updater reads to find the signed bundles of a release.
+const UPDATER_MANIFEST_ASSET: &str = "latest.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UpdateCheck {
+    pub current_version: String,
+    pub channel: UpdateChannel,
+    pub update_available: bool,
+    /// Newest release on the channel, even when it isn't newer than the
+    /// running version.
+    pub latest: Option<ReleaseInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReleaseInfo {
+    pub version: String,
+    pub tag: String,
+    pub name: String,
+    pub url: String,
+    pub prerelease: bool,
+    pub published_at: Option<String>,
+    /// Release notes, as markdown.
+    pub notes: String,
+    /// `latest.json` for the desktop updater, when the release has one.
+    pub updater_manifest_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    html_url: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    published_at: Option<String>,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// Fetch the releases at `releases_url` and compare the newest one on
+/// `channel` with `current_version`. Blocks on the network.
+pub fn check_for_updates(
+    releases_url: &str,
+    channel: UpdateChannel,
+    current_version: &str,
+) -> Result<UpdateCheck> {
+    let url = Url::parse(releases_url).with_context(|| format!("invalid URL {releases_url}"))?;
+    let text = fetch_releases(&url)?;
+    let releases: Vec<GithubRelease> =
+        serde_json::from_str(&text).with_context(|| format!("{url} is not a releases list"))?;
+    evaluate_releases(releases, channel, current_version)
+}
+
+fn evaluate_releases(
+    releases: Vec<GithubRelease>,
+    channel: UpdateChannel,
+    current_version: &str,
+) -> Result<UpdateCheck> {
+    let current = parse_tag(current_version)
+        .ok_or_else(|| anyhow!("invalid current version {current_version}"))?;
+    let latest = releases
+        .into_iter()
+        .filter(|release| !release.draft)
+        .filter(|release| channel == UpdateChannel::Beta || !release.prerelease)
+        .filter_map(|release| parse_tag(&release.tag_name).map(|version| (version, release)))
+        .max_by(|(a, _), (b, _)| a.cmp(b));
+
+    let update_available = latest
+        .as_ref()
+        .is_some_and(|(version, _)| *version > current);
+    Ok(UpdateCheck {
+        current_version: current.to_string(),
+        channel,
+        update_available,
+        latest: latest.map(|(version, release)| release_info(version, release)),
+    })
+}
+
+fn release_info(version: Version, release: GithubRelease) -> ReleaseInfo {
+    let updater_manifest_url = release
+        .assets
+        .into_iter()
+        .find(|asset| asset.name == UPDATER_MANIFEST_ASSET)
+        .map(|asset| asset.browser_download_url);
+    ReleaseInfo {
+        version: version.to_string(),
+        name: release
+            .name
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| release.tag_name.clone()),
+        tag: release.tag_name,
+        url: release.html_url,
+        prerelease: release.prerelease,
+        published_at: release.published_at,
+        notes: release.body.unwrap_or_default(),
+        updater_manifest_url,
+    }
+}
+
+fn parse_tag(tag: &str) -> Option<Version> {
+    let tag = tag.trim();
+    Version::parse(tag.strip_prefix('v').unwrap_or(tag)).ok()
+}
+
+fn fetch_releases(url: &Url) -> Result<String> {
+    if url.scheme() == "file" {
+        let path = url
+            .to_file_path()
+            .map_err(|()| anyhow!("invalid file URL {url}"))?;
+        return fs::read_to_string(&path).with_context(|| format!("failed to read {url}"));
+    }
+
+    // The GitHub API rejects requests without a user agent.
+    let client = reqwest::blocking::Client::builder()
+        .connect_timeout(Duration::from_secs(15))
+        .timeout(Duration::from_secs(30))
+        .user_agent(concat!("videnoa/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .context("failed to build HTTP client for update check")?;
+    client
+        .get(url.clone())
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.text())
+        .with_context(|| format!("failed to fetch {url}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn releases() -> Vec<GithubRelease> {
+        serde_json::from_value(serde_json::json!([
+            {
+                "tag_name": "v0.4.0-beta.1",
+                "name": "0.4.0 beta 1",
+                "html_url": "https://example.com/v0.4.0-beta.1",
+                "prerelease": true,
+                "assets": [
+                    {"name": "latest.json", "browser_download_url": "https://example.com/beta/latest.json"}
+                ]
+            },
+            {"tag_name": "v0.5.0", "draft": true},
+            {"tag_name": "nightly", "prerelease": true},
+            {
+                "tag_name": "v0.3.1",
+                "name": "",
+                "html_url": "https://example.com/v0.3.1",
+                "body": "Fixes.",
+                "assets": [
+                    {"name": "videnoa.msi", "browser_download_url": "https://example.com/videnoa.msi"}
+                ]
+            },
+            {"tag_name": "v0.3.0"}
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_stable_channel_skips_prereleases_and_drafts() {
+        let check = evaluate_releases(releases(), UpdateChannel::Stable, "0.3.0").unwrap();
+        assert!(check.update_available);
+        let latest = check.latest.unwrap();
+        assert_eq!(latest.version, "0.3.1");
+        assert_eq!(latest.name, "v0.3.1");
+        assert_eq!(latest.notes, "Fixes.");
+        assert!(latest.updater_manifest_url.is_none());
+
+        let current = evaluate_releases(releases(), UpdateChannel::Stable, "0.3.1").unwrap();
+        assert!(!current.update_available);
+        assert_eq!(current.latest.unwrap().version, "0.3.1");
+    }
+
+    #[test]
+    fn test_beta_channel_includes_prereleases() {
+        let check = evaluate_releases(releases(), UpdateChannel::Beta, "0.3.1").unwrap();
+        assert!(check.update_available);
+        let latest = check.latest.unwrap();
+        assert_eq!(latest.version, "0.4.0-beta.1");
+        assert!(latest.prerelease);
+        assert_eq!(
+            latest.updater_manifest_url.as_deref(),
+            Some("https://example.com/beta/latest.json")
+        );
+
+        let ahead = evaluate_releases(releases(), UpdateChannel::Beta, "0.4.0").unwrap();
+        assert!(!ahead.update_available, "0.4.0 is newer than its beta");
+    }
+}
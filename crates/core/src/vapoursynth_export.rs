@@ -0,0 +1,535 @@
+//! VapourSynth script export.
+//!
+//! Converts the video chain of a workflow into a `.vpy` script that runs the
+//! same models through [vs-mlrt](https://github.com/AmusementClub/vs-mlrt),
+//! for users moving to VapourSynth or checking that both produce the same
+//! frames. Only nodes with a direct VapourSynth equivalent are supported:
+//! `VideoInput`, `SuperResolution`, `FrameInterpolation` and `VideoOutput`.
+//! Params may be literal or come from nodes that only compute values
+//! (`WorkflowInput`, `Constant`, `PathJoiner`, ...), which are evaluated here.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Context, Result};
+use petgraph::stable_graph::NodeIndex;
+
+use crate::executor::{clone_port_data, port_data_from_json};
+use crate::graph::{NodeInstance, PipelineGraph};
+use crate::node::ExecutionContext;
+use crate::nodes::video_input::segments_from_inputs;
+use crate::registry::NodeRegistry;
+use crate::types::{PortData, PortType};
+
+pub const SCRIPT_FILE_EXTENSION: &str = "vpy";
+
+/// Nodes without side effects, run during export to resolve params.
+const EVALUATED_NODE_TYPES: &[&str] = &[
+    "WorkflowInput",
+    "Constant",
+    "PathJoiner",
+    "PathDivider",
+    "StringTemplate",
+    "StringReplace",
+    "TypeConversion",
+    "Script",
+];
+
+const SUPPORTED_NODE_TYPES: &str = "VideoInput, SuperResolution, FrameInterpolation, VideoOutput";
+
+/// Builds a VapourSynth script equivalent to `graph`'s video chain.
+pub fn export_vapoursynth(graph: &PipelineGraph, registry: &NodeRegistry) -> Result<String> {
+    let order = graph.execution_order()?;
+
+    let mut evaluated: HashMap<String, HashMap<String, PortData>> = HashMap::new();
+    for &idx in &order {
+        let instance = graph.node(idx);
+        if !EVALUATED_NODE_TYPES.contains(&instance.node_type.as_str()) {
+            continue;
+        }
+        let params = resolve_params(graph, registry, idx, &evaluated)?;
+        let mut node = registry.create(&instance.node_type, instance.params.clone())?;
+        let outputs = node
+            .execute(&params.values, &ExecutionContext::default())
+            .with_context(|| format!("failed to evaluate node '{}'", instance.id))?;
+        evaluated.insert(instance.id.clone(), outputs);
+    }
+
+    let chain = video_chain(graph, &order)?;
+    let mut script = ScriptBuilder::default();
+    for &idx in &chain {
+        let instance = graph.node(idx);
+        let params = resolve_params(graph, registry, idx, &evaluated)?;
+        script.node_header(instance);
+        match instance.node_type.as_str() {
+            "VideoInput" => script.video_input(&params)?,
+            "SuperResolution" => script.super_resolution(&params)?,
+            "FrameInterpolation" => script.frame_interpolation(&params)?,
+            "VideoOutput" => script.video_output(&params)?,
+            other => bail!(
+                "node '{}' ({other}) has no VapourSynth equivalent; export supports {SUPPORTED_NODE_TYPES}",
+                instance.id
+            ),
+        }
+    }
+    if !script.has_output {
+        bail!("workflow has no VideoOutput node to export");
+    }
+    Ok(script.finish())
+}
+
+/// The nodes linked by `VideoFrames` connections, source first.
+fn video_chain(graph: &PipelineGraph, order: &[NodeIndex]) -> Result<Vec<NodeIndex>> {
+    let video_edges = |idx: NodeIndex, outgoing: bool| -> Vec<NodeIndex> {
+        let edges = if outgoing {
+            graph.connections_from(idx)
+        } else {
+            graph.connections_to(idx)
+        };
+        edges
+            .into_iter()
+            .filter(|(_, conn)| conn.port_type == PortType::VideoFrames)
+            .map(|(other, _)| other)
+            .collect()
+    };
+
+    let video_nodes: Vec<NodeIndex> = order
+        .iter()
+        .copied()
+        .filter(|&idx| !video_edges(idx, true).is_empty() || !video_edges(idx, false).is_empty())
+        .collect();
+    let Some(&source) = video_nodes
+        .iter()
+        .find(|&&idx| video_edges(idx, false).is_empty())
+    else {
+        bail!("workflow has no video pipeline to export");
+    };
+
+    let mut chain = vec![source];
+    loop {
+        let next = video_edges(*chain.last().expect("chain is not empty"), true);
+        match next.as_slice() {
+            [] => break,
+            [next] => chain.push(*next),
+            _ => bail!("export only supports linear video pipelines"),
+        }
+    }
+    if chain.len() != video_nodes.len() {
+        bail!("export only supports linear video pipelines");
+    }
+    Ok(chain)
+}
+
+/// Input values of one node, with the ports whose value only a full run
+/// could produce kept apart so they fail when read.
+struct NodeParams<'a> {
+    node: &'a NodeInstance,
+    values: HashMap<String, PortData>,
+    computed_by: HashMap<String, String>,
+}
+
+impl NodeParams<'_> {
+    fn value(&self, name: &str) -> Result<&PortData> {
+        if let Some(source) = self.computed_by.get(name) {
+            bail!(
+                "{}.{name} comes from node '{source}', which only produces a value when the workflow runs",
+                self.node.id
+            );
+        }
+        self.values
+            .get(name)
+            .with_context(|| format!("{}.{name} is not set", self.node.id))
+    }
+
+    fn str(&self, name: &str) -> Result<String> {
+        match self.value(name)? {
+            PortData::Str(value) => Ok(value.clone()),
+            PortData::Path(path) => Ok(path.to_string_lossy().into_owned()),
+            _ => bail!("{}.{name} must be a string", self.node.id),
+        }
+    }
+
+    fn int(&self, name: &str) -> Result<i64> {
+        match self.value(name)? {
+            PortData::Int(value) => Ok(*value),
+            _ => bail!("{}.{name} must be an integer", self.node.id),
+        }
+    }
+
+    /// Rejects a setting vs-mlrt cannot reproduce.
+    fn expect_one_of(&self, name: &str, supported: &[&str]) -> Result<()> {
+        let value = self.str(name)?;
+        if !supported.contains(&value.as_str()) {
+            bail!(
+                "{}.{name} '{value}' has no vs-mlrt equivalent (supported: {})",
+                self.node.id,
+                supported.join(", ")
+            );
+        }
+        Ok(())
+    }
+}
+
+fn resolve_params<'a>(
+    graph: &'a PipelineGraph,
+    registry: &NodeRegistry,
+    idx: NodeIndex,
+    evaluated: &HashMap<String, HashMap<String, PortData>>,
+) -> Result<NodeParams<'a>> {
+    let instance = graph.node(idx);
+    let node = registry.create(&instance.node_type, instance.params.clone())?;
+    let mut values = HashMap::new();
+    let mut computed_by = HashMap::new();
+
+    for (source_idx, connection) in graph.connections_to(idx) {
+        if connection.port_type == PortType::VideoFrames {
+            continue;
+        }
+        let source = graph.node(source_idx);
+        match evaluated
+            .get(&source.id)
+            .and_then(|outputs| outputs.get(&connection.source_port))
+        {
+            Some(data) => {
+                values.insert(connection.target_port.clone(), clone_port_data(data));
+            }
+            None => {
+                computed_by.insert(connection.target_port.clone(), source.id.clone());
+            }
+        }
+    }
+
+    for port in node.input_ports() {
+        if values.contains_key(&port.name) || computed_by.contains_key(&port.name) {
+            continue;
+        }
+        let Some(value) = instance
+            .params
+            .get(&port.name)
+            .or(port.default_value.as_ref())
+        else {
+            continue;
+        };
+        let data = port_data_from_json(&port.port_type, value)
+            .with_context(|| format!("invalid value for '{}.{}'", instance.id, port.name))?;
+        values.insert(port.name, data);
+    }
+
+    Ok(NodeParams {
+        node: instance,
+        values,
+        computed_by,
+    })
+}
+
+#[derive(Default)]
+struct ScriptBuilder {
+    lines: Vec<String>,
+    encode_command: Option<String>,
+    uses_inference: bool,
+    uses_rife: bool,
+    has_output: bool,
+}
+
+impl ScriptBuilder {
+    fn node_header(&mut self, node: &NodeInstance) {
+        self.lines.push(String::new());
+        self.lines
+            .push(format!("# {} ({})", node.id, node.node_type));
+    }
+
+    fn video_input(&mut self, params: &NodeParams) -> Result<()> {
+        let path = params.str("path")?;
+        let stream = params.int("video_stream")?;
+        if stream >= 0 {
+            self.lines.push(format!(
+                "clip = core.lsmas.LWLibavSource({}, stream_index={stream})",
+                py_str(&path)
+            ));
+        } else {
+            self.lines.push(format!(
+                "clip = core.lsmas.LWLibavSource({})",
+                py_str(&path)
+            ));
+        }
+
+        let segments = segments_from_inputs(&params.values)
+            .with_context(|| format!("invalid frame range on '{}'", params.node.id))?;
+        if !segments.is_empty() {
+            let slices: Vec<String> = segments
+                .iter()
+                .map(|range| match range.end {
+                    Some(end) => format!("clip[{}:{end}]", range.start),
+                    None => format!("clip[{}:]", range.start),
+                })
+                .collect();
+            self.lines.push(format!("clip = {}", slices.join(" + ")));
+        }
+        self.lines.push(
+            "clip = core.resize.Bicubic(clip, format=vs.RGBS, matrix_in_s=\"709\")".to_string(),
+        );
+        Ok(())
+    }
+
+    fn super_resolution(&mut self, params: &NodeParams) -> Result<()> {
+        let model = params.str("model_path")?;
+        if model == crate::benchmark::AUTO_MODEL {
+            bail!(
+                "{}.model_path is 'auto'; choose a model before exporting",
+                params.node.id
+            );
+        }
+        params.expect_one_of("value_range", &["auto", "0-1"])?;
+        params.expect_one_of("output_range", &["same", "0-1"])?;
+        params.expect_one_of("channel_order", &["rgb"])?;
+        if params.int("pad_align")? > 0 {
+            bail!(
+                "{}.pad_align has no vs-mlrt equivalent; set it to 0",
+                params.node.id
+            );
+        }
+
+        let mut args = vec![
+            "clip".to_string(),
+            format!("network_path={}", py_str(&model)),
+        ];
+        let tile_size = params.int("tile_size")?;
+        if tile_size > 0 {
+            args.push(format!("tilesize=[{tile_size}, {tile_size}]"));
+        }
+        args.push(format!("backend={}", vsmlrt_backend(params)?));
+        self.lines
+            .push(format!("clip = inference({})", args.join(", ")));
+        self.uses_inference = true;
+        Ok(())
+    }
+
+    fn frame_interpolation(&mut self, params: &NodeParams) -> Result<()> {
+        let multiplier = params.int("multiplier")?;
+        if multiplier < 2 {
+            bail!(
+                "{}.multiplier must be at least 2, got {multiplier}",
+                params.node.id
+            );
+        }
+        if let Ok(model) = params.str("model_path") {
+            self.lines.push(format!(
+                "# vs-mlrt's RIFE runs its bundled model, not {model}"
+            ));
+        }
+        self.lines.push(format!(
+            "clip = RIFE(clip, multi={multiplier}, backend={})",
+            vsmlrt_backend(params)?
+        ));
+        self.uses_rife = true;
+        Ok(())
+    }
+
+    fn video_output(&mut self, params: &NodeParams) -> Result<()> {
+        let pixel_format = params.str("pixel_format")?;
+        let format = vs_format(&pixel_format).with_context(|| {
+            format!(
+                "{}.pixel_format '{pixel_format}' has no VapourSynth format",
+                params.node.id
+            )
+        })?;
+        self.lines.push(format!(
+            "clip = core.resize.Bicubic(clip, format=vs.{format}, matrix_s=\"709\")"
+        ));
+        self.lines.push("clip.set_output()".to_string());
+
+        // The output path may be built by a node that only runs with the
+        // workflow; the command is a hint, so fall back to a placeholder.
+        let output = params
+            .str("output_path")
+            .unwrap_or_else(|_| "output.mkv".to_string());
+        self.encode_command = Some(format!(
+            "vspipe -c y4m script.{SCRIPT_FILE_EXTENSION} - | ffmpeg -i - -c:v {} -crf {} -pix_fmt {pixel_format} {}",
+            params.str("codec")?,
+            params.int("crf")?,
+            py_str(&output)
+        ));
+        self.has_output = true;
+        Ok(())
+    }
+
+    fn finish(self) -> String {
+        let mut out = vec![
+            "# Exported from a videnoa workflow.".to_string(),
+            "# Needs L-SMASH-Works and vs-mlrt (https://github.com/AmusementClub/vs-mlrt)."
+                .to_string(),
+        ];
+        if let Some(command) = &self.encode_command {
+            out.push("# Encode with:".to_string());
+            out.push(format!("#   {command}"));
+        }
+        out.push("import vapoursynth as vs".to_string());
+        let mut imports = Vec::new();
+        if self.uses_inference || self.uses_rife {
+            imports.push("Backend");
+        }
+        if self.uses_rife {
+            imports.push("RIFE");
+        }
+        if self.uses_inference {
+            imports.push("inference");
+        }
+        if !imports.is_empty() {
+            out.push(format!("from vsmlrt import {}", imports.join(", ")));
+        }
+        out.push(String::new());
+        out.push("core = vs.core".to_string());
+        out.extend(self.lines);
+        out.push(String::new());
+        out.join("\n")
+    }
+}
+
+fn vsmlrt_backend(params: &NodeParams) -> Result<&'static str> {
+    match params.str("backend")?.as_str() {
+        "cuda" => Ok("Backend.ORT_CUDA()"),
+        "tensorrt" => Ok("Backend.TRT()"),
+        other => bail!(
+            "{}.backend '{other}' has no vs-mlrt equivalent",
+            params.node.id
+        ),
+    }
+}
+
+/// VapourSynth preset for an FFmpeg `pixel_format`.
+fn vs_format(pixel_format: &str) -> Option<&'static str> {
+    Some(match pixel_format {
+        "yuv420p" => "YUV420P8",
+        "yuv420p10le" => "YUV420P10",
+        "yuv422p" => "YUV422P8",
+        "yuv422p10le" => "YUV422P10",
+        "yuv444p" => "YUV444P8",
+        "yuv444p10le" => "YUV444P10",
+        _ => return None,
+    })
+}
+
+/// A Python string literal; JSON string escapes are valid Python.
+fn py_str(value: &str) -> String {
+    serde_json::to_string(value).expect("strings always serialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::build_default_registry;
+
+    fn workflow(nodes: serde_json::Value, connections: serde_json::Value) -> PipelineGraph {
+        serde_json::from_value(serde_json::json!({
+            "nodes": nodes,
+            "connections": connections,
+        }))
+        .unwrap()
+    }
+
+    fn frames(from: &str, to: &str) -> serde_json::Value {
+        serde_json::json!({
+            "from_node": from, "from_port": "frames",
+            "to_node": to, "to_port": "frames",
+            "port_type": "VideoFrames"
+        })
+    }
+
+    #[test]
+    fn test_export_linear_workflow() {
+        let graph = workflow(
+            serde_json::json!([
+                {"id": "wi", "node_type": "WorkflowInput", "params": {
+                    "ports": [{"name": "input", "port_type": "Path"}],
+                    "input": "/media/ep01.mkv"
+                }},
+                {"id": "in", "node_type": "VideoInput", "params": {"start_frame": 24, "end_frame": 48}},
+                {"id": "sr", "node_type": "SuperResolution", "params": {
+                    "model_path": "/models/x4.onnx", "tile_size": 512, "backend": "tensorrt"
+                }},
+                {"id": "fi", "node_type": "FrameInterpolation", "params": {
+                    "model_path": "/models/rife.onnx", "multiplier": 2
+                }},
+                {"id": "out", "node_type": "VideoOutput", "params": {"output_path": "/media/ep01_4k.mkv"}}
+            ]),
+            serde_json::json!([
+                {"from_node": "wi", "from_port": "input", "to_node": "in", "to_port": "path", "port_type": "Path"},
+                frames("in", "sr"),
+                frames("sr", "fi"),
+                frames("fi", "out")
+            ]),
+        );
+
+        let script = export_vapoursynth(&graph, &build_default_registry()).unwrap();
+        assert!(script.contains("from vsmlrt import Backend, RIFE, inference"));
+        assert!(script.contains("clip = core.lsmas.LWLibavSource(\"/media/ep01.mkv\")"));
+        assert!(script.contains("clip = clip[24:48]"));
+        assert!(script.contains(
+            "clip = inference(clip, network_path=\"/models/x4.onnx\", tilesize=[512, 512], backend=Backend.TRT())"
+        ));
+        assert!(script.contains("clip = RIFE(clip, multi=2, backend=Backend.ORT_CUDA())"));
+        assert!(script.contains("format=vs.YUV420P10"));
+        assert!(script.contains("-c:v libx265 -crf 18 -pix_fmt yuv420p10le \"/media/ep01_4k.mkv\""));
+        assert!(script.trim_end().ends_with("clip.set_output()"));
+    }
+
+    #[test]
+    fn test_export_rejects_unsupported_nodes_and_settings() {
+        let registry = build_default_registry();
+        let nodes = |sr_params: serde_json::Value| {
+            serde_json::json!([
+                {"id": "in", "node_type": "VideoInput", "params": {"path": "/media/a.mkv"}},
+                {"id": "sr", "node_type": "SuperResolution", "params": sr_params},
+                {"id": "out", "node_type": "VideoOutput", "params": {"output_path": "/media/b.mkv"}}
+            ])
+        };
+        let connections = serde_json::json!([frames("in", "sr"), frames("sr", "out")]);
+
+        let auto = workflow(
+            nodes(serde_json::json!({"model_path": "auto"})),
+            connections.clone(),
+        );
+        let err = export_vapoursynth(&auto, &registry).unwrap_err();
+        assert!(err.to_string().contains("'auto'"), "{err:#}");
+
+        let bgr = workflow(
+            nodes(serde_json::json!({"model_path": "/m.onnx", "channel_order": "bgr"})),
+            connections,
+        );
+        let err = export_vapoursynth(&bgr, &registry).unwrap_err();
+        assert!(err.to_string().contains("channel_order"), "{err:#}");
+
+        let filter = workflow(
+            serde_json::json!([
+                {"id": "in", "node_type": "VideoInput", "params": {"path": "/media/a.mkv"}},
+                {"id": "vf", "node_type": "FfmpegFilter", "params": {"filter": "hqdn3d"}},
+                {"id": "out", "node_type": "VideoOutput", "params": {"output_path": "/media/b.mkv"}}
+            ]),
+            serde_json::json!([frames("in", "vf"), frames("vf", "out")]),
+        );
+        let err = export_vapoursynth(&filter, &registry).unwrap_err();
+        assert!(
+            err.to_string().contains("no VapourSynth equivalent"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn test_export_reports_params_computed_at_run_time() {
+        let graph = workflow(
+            serde_json::json!([
+                {"id": "dl", "node_type": "Downloader", "params": {"url": "https://example.com/a.mkv"}},
+                {"id": "in", "node_type": "VideoInput", "params": {}},
+                {"id": "out", "node_type": "VideoOutput", "params": {"output_path": "/media/b.mkv"}}
+            ]),
+            serde_json::json!([
+                {"from_node": "dl", "from_port": "path", "to_node": "in", "to_port": "path", "port_type": "Path"},
+                frames("in", "out")
+            ]),
+        );
+        let err = export_vapoursynth(&graph, &build_default_registry()).unwrap_err();
+        assert!(
+            err.to_string().contains("in.path comes from node 'dl'"),
+            "{err:#}"
+        );
+    }
+}
@@ -0,0 +1,469 @@
+//! Remote execution worker.
+//!
+//! A worker registers with a videnoa server running with
+//! `workers.dispatch = "remote"`, heartbeats, claims queued jobs and runs
+//! them locally, reporting progress and the final outcome back over HTTP.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use reqwest::StatusCode;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn, Instrument};
+use url::Url;
+
+use crate::artifacts::{collect_artifacts, JobArtifact};
+use crate::benchmark::AutoModelSource;
+use crate::config::TorrentsConfig;
+use crate::executor::SequentialExecutor;
+use crate::job_logs;
+use crate::job_phase::{with_phase_reporter, PhaseReporter};
+use crate::node_error::NodeError;
+use crate::nodes::compile_context::VideoCompileContext;
+use crate::nodes::workflow_io::collect_workflow_outputs;
+use crate::registry::{register_all_nodes, NodeRegistry};
+use crate::server::{
+    port_params_from_json, sample_hardware_usage, CompleteWorkerJobRequest, ProgressUpdate,
+    RegisterWorkerRequest, RegisterWorkerResponse, WorkerJobAssignment, WorkerJobOutcome,
+    WorkerProgressResponse,
+};
+use crate::services::NodeServices;
+use crate::streaming_executor::stage_progress;
+
+/// Minimum spacing between progress reports sent to the server.
+const PROGRESS_REPORT_INTERVAL_MS: u64 = 2000;
+
+type ProgressCallback = Box<dyn Fn(u64, Option<u64>, Option<u64>) + Send>;
+
+#[derive(Debug, Clone)]
+pub struct WorkerOptions {
+    /// Base URL of the API server, e.g. `http://nas.local:3000`.
+    pub server_url: String,
+    pub name: String,
+    /// Bearer token matching the server's `workers.token_secret`.
+    pub token: Option<String>,
+    pub trt_cache_dir: PathBuf,
+    /// Models and benchmark cache used for `model_path: auto`.
+    pub auto_models: AutoModelSource,
+    /// Directory scanned for node plugin libraries.
+    pub plugins_dir: PathBuf,
+    /// Attach CPU/GPU load to progress reports (`performance.profiling_enabled`).
+    pub profiling_enabled: bool,
+    /// The worker's own `[torrents]` settings, for `TorrentInput` nodes.
+    pub torrents: TorrentsConfig,
+    /// Delay between claim attempts while the queue is empty.
+    pub poll_interval: Duration,
+}
+
+/// Result of a claim attempt.
+#[derive(Debug)]
+pub enum ClaimOutcome {
+    Assigned(Box<WorkerJobAssignment>),
+    /// Nothing is queued.
+    Empty,
+    /// The server dropped this worker (e.g. after missed heartbeats).
+    Unregistered,
+}
+
+/// HTTP client for the `/api/workers` protocol.
+#[derive(Debug, Clone)]
+pub struct WorkerClient {
+    base_url: Url,
+    token: Option<String>,
+    client: reqwest::Client,
+}
+
+impl WorkerClient {
+    pub fn new(server_url: &str, token: Option<String>) -> Result<Self> {
+        let base_url =
+            Url::parse(server_url).with_context(|| format!("invalid server URL: {server_url}"))?;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .context("failed to build HTTP client")?;
+        Ok(Self {
+            base_url,
+            token,
+            client,
+        })
+    }
+
+    fn post(&self, path: &str) -> Result<reqwest::RequestBuilder> {
+        let url = self
+            .base_url
+            .join(path)
+            .with_context(|| format!("invalid worker endpoint: {path}"))?;
+        let request = self.client.post(url);
+        Ok(match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        })
+    }
+
+    pub async fn register(&self, name: &str) -> Result<RegisterWorkerResponse> {
+        let resp = self
+            .post("/api/workers/register")?
+            .json(&RegisterWorkerRequest {
+                name: name.to_string(),
+            })
+            .send()
+            .await
+            .context("failed to reach server")?;
+        if !resp.status().is_success() {
+            bail!("worker registration failed: HTTP {}", resp.status());
+        }
+        resp.json()
+            .await
+            .context("failed to parse registration response")
+    }
+
+    /// Returns `false` when the server no longer knows this worker.
+    pub async fn heartbeat(&self, worker_id: &str) -> Result<bool> {
+        let resp = self
+            .post(&format!("/api/workers/{worker_id}/heartbeat"))?
+            .send()
+            .await
+            .context("failed to reach server")?;
+        match resp.status() {
+            StatusCode::NOT_FOUND => Ok(false),
+            status if status.is_success() => Ok(true),
+            status => bail!("heartbeat failed: HTTP {status}"),
+        }
+    }
+
+    pub async fn claim(&self, worker_id: &str) -> Result<ClaimOutcome> {
+        let resp = self
+            .post(&format!("/api/workers/{worker_id}/claim"))?
+            .send()
+            .await
+            .context("failed to reach server")?;
+        match resp.status() {
+            StatusCode::NO_CONTENT => Ok(ClaimOutcome::Empty),
+            StatusCode::NOT_FOUND => Ok(ClaimOutcome::Unregistered),
+            status if status.is_success() => Ok(ClaimOutcome::Assigned(Box::new(
+                resp.json()
+                    .await
+                    .context("failed to parse job assignment")?,
+            ))),
+            status => bail!("claim failed: HTTP {status}"),
+        }
+    }
+
+    pub async fn report_progress(
+        &self,
+        worker_id: &str,
+        job_id: &str,
+        update: &ProgressUpdate,
+    ) -> Result<WorkerProgressResponse> {
+        let resp = self
+            .post(&format!("/api/workers/{worker_id}/jobs/{job_id}/progress"))?
+            .json(update)
+            .send()
+            .await
+            .context("failed to reach server")?;
+        if !resp.status().is_success() {
+            bail!("progress report failed: HTTP {}", resp.status());
+        }
+        resp.json()
+            .await
+            .context("failed to parse progress response")
+    }
+
+    pub async fn complete(
+        &self,
+        worker_id: &str,
+        job_id: &str,
+        request: &CompleteWorkerJobRequest,
+    ) -> Result<()> {
+        let resp = self
+            .post(&format!("/api/workers/{worker_id}/jobs/{job_id}/complete"))?
+            .json(request)
+            .send()
+            .await
+            .context("failed to reach server")?;
+        if !resp.status().is_success() {
+            bail!("job completion report failed: HTTP {}", resp.status());
+        }
+        Ok(())
+    }
+}
+
+/// Register with the server and process claimed jobs until the process is
+/// stopped. Connection failures are logged and retried.
+pub async fn run_worker(options: WorkerOptions) -> Result<()> {
+    let client = WorkerClient::new(&options.server_url, options.token.clone())?;
+    let mut node_registry = NodeRegistry::new();
+    register_all_nodes(&mut node_registry);
+    node_registry.load_plugins(&options.plugins_dir);
+    let node_registry = Arc::new(node_registry);
+
+    loop {
+        let registration = match client.register(&options.name).await {
+            Ok(registration) => registration,
+            Err(err) => {
+                warn!(error = %format!("{err:#}"), server = %options.server_url, "Worker registration failed; retrying");
+                tokio::time::sleep(options.poll_interval).await;
+                continue;
+            }
+        };
+        let worker_id = registration.worker_id;
+        info!(worker_id = %worker_id, server = %options.server_url, "Registered as remote worker");
+
+        let registered = Arc::new(AtomicBool::new(true));
+        let heartbeat = tokio::spawn({
+            let client = client.clone();
+            let worker_id = worker_id.clone();
+            let registered = Arc::clone(&registered);
+            let interval = Duration::from_secs(registration.heartbeat_interval_secs.max(1));
+            async move {
+                loop {
+                    tokio::time::sleep(interval).await;
+                    match client.heartbeat(&worker_id).await {
+                        Ok(true) => {}
+                        Ok(false) => {
+                            registered.store(false, Ordering::Release);
+                            return;
+                        }
+                        Err(err) => {
+                            warn!(error = %format!("{err:#}"), "Worker heartbeat failed");
+                        }
+                    }
+                }
+            }
+        });
+
+        while registered.load(Ordering::Acquire) {
+            match client.claim(&worker_id).await {
+                Ok(ClaimOutcome::Assigned(assignment)) => {
+                    let job_span = tracing::info_span!(
+                        parent: None,
+                        job_logs::JOB_LOG_SPAN_NAME,
+                        job_id = %assignment.job_id
+                    );
+                    process_assignment(&client, &worker_id, *assignment, &node_registry, &options)
+                        .instrument(job_span)
+                        .await;
+                }
+                Ok(ClaimOutcome::Empty) => tokio::time::sleep(options.poll_interval).await,
+                Ok(ClaimOutcome::Unregistered) => break,
+                Err(err) => {
+                    warn!(error = %format!("{err:#}"), "Failed to claim a job");
+                    tokio::time::sleep(options.poll_interval).await;
+                }
+            }
+        }
+
+        heartbeat.abort();
+        warn!(worker_id = %worker_id, "Worker registration lost; registering again");
+    }
+}
+
+async fn process_assignment(
+    client: &WorkerClient,
+    worker_id: &str,
+    assignment: WorkerJobAssignment,
+    node_registry: &Arc<NodeRegistry>,
+    options: &WorkerOptions,
+) {
+    let profiling_enabled = options.profiling_enabled;
+    let job_id = assignment.job_id.clone();
+    info!(job_id = %job_id, "Running claimed job");
+
+    let (progress_tx, mut progress_rx) = tokio::sync::watch::channel(None::<ProgressUpdate>);
+    let (cancel_tx, cancel_rx) = tokio::sync::watch::channel(false);
+    let cancel_token = CancellationToken::new();
+
+    // Forward the latest progress at most every PROGRESS_REPORT_INTERVAL_MS
+    // and trip the cancel flag when the server asks the job to stop.
+    let reporter = tokio::spawn({
+        let client = client.clone();
+        let worker_id = worker_id.to_string();
+        let job_id = job_id.clone();
+        let cancel_token = cancel_token.clone();
+        async move {
+            while progress_rx.changed().await.is_ok() {
+                let update = progress_rx.borrow_and_update().clone();
+                if let Some(mut update) = update {
+                    if profiling_enabled {
+                        update.hardware = tokio::task::spawn_blocking(sample_hardware_usage)
+                            .await
+                            .ok();
+                    }
+                    match client.report_progress(&worker_id, &job_id, &update).await {
+                        Ok(resp) if resp.cancel => {
+                            info!(job_id = %job_id, "Server cancelled the job");
+                            cancel_token.cancel();
+                            let _ = cancel_tx.send(true);
+                            return;
+                        }
+                        Ok(_) => {}
+                        Err(err) => {
+                            warn!(error = %format!("{err:#}"), "Failed to report progress");
+                        }
+                    }
+                }
+                tokio::time::sleep(Duration::from_millis(PROGRESS_REPORT_INTERVAL_MS)).await;
+            }
+        }
+    });
+
+    let registry = Arc::clone(node_registry);
+    let trt_cache_dir = options.trt_cache_dir.clone();
+    let auto_models = options.auto_models.clone();
+    let services = NodeServices::default()
+        .with_cancel_token(cancel_token)
+        .with_torrents_config(options.torrents.clone());
+    let cancelled = cancel_rx.clone();
+    // block_in_place rather than spawn_blocking: the executor itself calls
+    // block_in_place, which panics inside spawn_blocking.
+    let result = tokio::task::block_in_place(move || {
+        execute_assignment(
+            assignment,
+            &registry,
+            trt_cache_dir,
+            auto_models,
+            progress_tx,
+            cancel_rx,
+            services,
+        )
+    });
+    reporter.abort();
+
+    let request = match result {
+        Ok(_) if *cancelled.borrow() => CompleteWorkerJobRequest {
+            outcome: WorkerJobOutcome::Failed,
+            error: Some("cancelled".to_string()),
+            error_details: None,
+            outputs: None,
+            artifacts: Vec::new(),
+        },
+        Ok((outputs, artifacts)) => CompleteWorkerJobRequest {
+            outcome: WorkerJobOutcome::Completed,
+            error: None,
+            error_details: None,
+            outputs,
+            artifacts,
+        },
+        Err(err) => {
+            error!(job_id = %job_id, error = ?err, "Job execution failed");
+            CompleteWorkerJobRequest {
+                outcome: WorkerJobOutcome::Failed,
+                error: Some(format!("{err:#}")),
+                error_details: Some(NodeError::from_error(&err)),
+                outputs: None,
+                artifacts: Vec::new(),
+            }
+        }
+    };
+
+    if let Err(err) = client.complete(worker_id, &job_id, &request).await {
+        warn!(job_id = %job_id, error = %format!("{err:#}"), "Failed to report job completion");
+    } else {
+        info!(job_id = %job_id, outcome = ?request.outcome, "Job finished");
+    }
+}
+
+/// Values collected by the workflow's WorkflowOutput nodes.
+type WorkflowOutputs = Option<HashMap<String, serde_json::Value>>;
+
+/// Mirrors the server's local `run_job`: graphs with video-frame edges get
+/// the params injected and run with progress/cancellation, others run via
+/// the param-driven executor.
+fn execute_assignment(
+    assignment: WorkerJobAssignment,
+    registry: &NodeRegistry,
+    trt_cache_dir: PathBuf,
+    auto_models: AutoModelSource,
+    progress_tx: tokio::sync::watch::Sender<Option<ProgressUpdate>>,
+    cancel_rx: tokio::sync::watch::Receiver<bool>,
+    services: NodeServices,
+) -> Result<(WorkflowOutputs, Vec<JobArtifact>)> {
+    let mut workflow = assignment.workflow;
+    let mut params = assignment.params;
+
+    if workflow.has_video_frames_edges() {
+        if let Some(params) = params.as_ref() {
+            workflow.inject_workflow_input_params(params);
+        }
+        params = None;
+    }
+
+    if let Some(params) = params {
+        let ctx = crate::node::ExecutionContext {
+            services,
+            ..Default::default()
+        };
+        let outputs_by_node = SequentialExecutor::execute_with_params(
+            &workflow,
+            registry,
+            port_params_from_json(&params, workflow.interface.as_ref()),
+            &ctx,
+        )?;
+        return Ok((
+            collect_workflow_outputs(&workflow, &outputs_by_node),
+            collect_artifacts(&workflow, &outputs_by_node),
+        ));
+    }
+
+    let compile_ctx = VideoCompileContext::new(trt_cache_dir)
+        .with_auto_models(auto_models)
+        .with_services(services);
+    let started = Mutex::new(None::<(u64, Instant)>);
+    let progress_tx = Arc::new(progress_tx);
+    let progress_for_frames = Arc::clone(&progress_tx);
+    let progress_cb: ProgressCallback = Box::new(move |current_frame, total_frames, _hint| {
+        let now = Instant::now();
+        let fps = {
+            let mut started = started.lock().unwrap_or_else(|p| p.into_inner());
+            let (first_frame, first_at) = *started.get_or_insert((current_frame, now));
+            let elapsed = now.duration_since(first_at).as_secs_f64();
+            if elapsed > 0.0 {
+                current_frame.saturating_sub(first_frame) as f64 / elapsed
+            } else {
+                0.0
+            }
+        };
+        let eta_seconds = total_frames.and_then(|total| {
+            (fps > 0.0 && current_frame < total).then(|| (total - current_frame) as f64 / fps)
+        });
+        progress_for_frames.send_modify(|slot| {
+            let phase = slot.as_ref().and_then(|p| p.phase);
+            *slot = Some(ProgressUpdate {
+                current_frame,
+                total_frames,
+                fps: fps as f32,
+                eta_seconds,
+                phase,
+                stages: stage_progress(),
+                hardware: None,
+            });
+        });
+    });
+    // Phases ride along with the next progress report.
+    let phase_reporter: PhaseReporter = Arc::new(move |phase| {
+        progress_tx.send_if_modified(|slot| {
+            let progress = slot.get_or_insert_with(ProgressUpdate::default);
+            let changed = progress.phase != Some(phase);
+            progress.phase = Some(phase);
+            changed
+        });
+    });
+
+    let outputs_by_node = with_phase_reporter(Some(phase_reporter), || {
+        SequentialExecutor::execute_with_context(
+            &workflow,
+            registry,
+            Some(&compile_ctx),
+            Some(progress_cb),
+            Some(cancel_rx),
+        )
+    })?;
+    Ok((
+        collect_workflow_outputs(&workflow, &outputs_by_node),
+        collect_artifacts(&workflow, &outputs_by_node),
+    ))
+}
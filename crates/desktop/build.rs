@@ -6,5 +6,11 @@ fn main() {
     println!("cargo:rerun-if-changed=icons/icon-128.png");
     println!("cargo:rerun-if-changed=icons/icon-256.png");
 
-    tauri_build::build();
+    // The window loads the embedded server over http, a remote origin, so
+    // app commands must be listed here and granted in a capability.
+    tauri_build::try_build(
+        tauri_build::Attributes::new()
+            .app_manifest(tauri_build::AppManifest::new().commands(&["install_update"])),
+    )
+    .expect("failed to run tauri build script");
 }
@@ -1,18 +1,36 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use std::net::TcpListener;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
-use tauri::{webview::WebviewWindowBuilder, WebviewUrl};
+use tauri::{webview::WebviewWindowBuilder, AppHandle, Manager, WebviewUrl, WindowEvent};
+use tauri_plugin_notification::NotificationExt;
+use tauri_plugin_updater::UpdaterExt;
+use tokio::sync::broadcast;
 use tracing::{error, info, warn};
 use tracing_subscriber::prelude::*;
 
-use videnoa_core::config::{config_path, data_dir, initialize_data_dir, AppConfig};
+use videnoa_core::config::{
+    config_path, data_dir, initialize_data_dir, portable_data_dir, portable_root, AppConfig,
+    UpdateChannel,
+};
 use videnoa_core::logging::{
     compose_logging_init_plan, install_panic_hook, FileSinkPlan, LoggingInitOptions,
     PanicHookInstallPlan, RuntimeLogMode, DEFAULT_LOG_FILTER,
 };
-use videnoa_core::server::{app_router_with_static, app_state_with_config};
+use videnoa_core::server::{
+    app_router_with_static, app_state_with_config, spawn_community_preset_sync,
+    spawn_config_watcher, spawn_job_retention, spawn_log_size_limit, spawn_models_watcher,
+    spawn_performance_sampler, spawn_power_manager, AppState, JobFinishedEvent, JobStatus,
+};
+
+/// Base URL of the embedded server, kept for handlers that run after setup.
+struct LocalServerUrl(String);
+
+/// Key the updater checks release bundles against, set when building
+/// releases. Builds without it can check for updates but not install them.
+const UPDATER_PUBKEY: Option<&str> = option_env!("VIDENOA_UPDATER_PUBKEY");
 
 fn init_logging(data_dir: std::path::PathBuf) {
     let panic_hook_plan = install_panic_hook(Some(data_dir.as_path()));
@@ -31,10 +49,15 @@ fn init_logging(data_dir: std::path::PathBuf) {
         );
     }
 
+    let retention_files = AppConfig::load_from_path(&config_path(&data_dir))
+        .map(|config| config.logging.retention_files)
+        .unwrap_or(videnoa_core::logging::DEFAULT_LOG_RETENTION_FILES);
+
     let init_plan = compose_logging_init_plan(&LoggingInitOptions {
         mode: RuntimeLogMode::Desktop,
         data_dir: Some(data_dir),
         rust_log_env: std::env::var("RUST_LOG").ok(),
+        retention_files,
         ..Default::default()
     });
 
@@ -58,13 +81,20 @@ fn init_logging(data_dir: std::path::PathBuf) {
                         ready_file_sink.appender,
                     ))
                     .with_filter(parse_env_filter_with_fallback(&file_filter, "file")),
+            )
+            .with(
+                videnoa_core::job_logs::job_log_layer()
+                    .with_filter(videnoa_core::job_logs::job_log_targets()),
             );
             tracing::subscriber::set_global_default(subscriber)
                 .expect("failed to install desktop tracing subscriber");
         }
         FileSinkPlan::Fallback(fallback_file_sink) => {
             fallback_warning = Some(fallback_file_sink);
-            let subscriber = tracing_subscriber::registry().with(console_layer);
+            let subscriber = tracing_subscriber::registry().with(console_layer).with(
+                videnoa_core::job_logs::job_log_layer()
+                    .with_filter(videnoa_core::job_logs::job_log_targets()),
+            );
             tracing::subscriber::set_global_default(subscriber)
                 .expect("failed to install desktop tracing subscriber");
         }
@@ -163,6 +193,216 @@ fn select_startup_window_size<R: tauri::Runtime>(app: &tauri::App<R>) -> (f64, f
     (window_width, window_height)
 }
 
+fn format_duration_ms(duration_ms: i64) -> String {
+    let secs = duration_ms.max(0) / 1000;
+    match (secs / 3600, secs / 60 % 60, secs % 60) {
+        (0, 0, s) => format!("{s}s"),
+        (0, m, s) => format!("{m}m {s}s"),
+        (h, m, _) => format!("{h}h {m}m"),
+    }
+}
+
+fn job_notification_text(event: &JobFinishedEvent) -> (String, String) {
+    let duration = event.duration_ms.map(format_duration_ms);
+    match event.status {
+        JobStatus::Completed => (
+            "Job completed".to_string(),
+            match duration {
+                Some(duration) => format!("{} finished in {duration}", event.workflow_name),
+                None => format!("{} finished", event.workflow_name),
+            },
+        ),
+        _ => {
+            let mut body = match duration {
+                Some(duration) => format!("{} failed after {duration}", event.workflow_name),
+                None => format!("{} failed", event.workflow_name),
+            };
+            if let Some(error) = &event.error {
+                body.push_str(": ");
+                body.push_str(error);
+            }
+            ("Job failed".to_string(), body)
+        }
+    }
+}
+
+/// Show a native notification for every finished job. Desktop notifications
+/// have no click callback, so a job announced while the window is in the
+/// background is opened in the jobs view once the window regains focus, which
+/// is what clicking the notification does.
+fn spawn_job_notifications(
+    app: AppHandle,
+    mut events: broadcast::Receiver<JobFinishedEvent>,
+    local_server_url: String,
+) {
+    let pending_job: Arc<Mutex<Option<String>>> = Arc::default();
+
+    if let Some(window) = app.get_webview_window("main") {
+        let pending_job = pending_job.clone();
+        let focused_window = window.clone();
+        window.on_window_event(move |event| {
+            if !matches!(event, WindowEvent::Focused(true)) {
+                return;
+            }
+            let Some(job_id) = pending_job
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .take()
+            else {
+                return;
+            };
+            let target = format!("{local_server_url}/jobs?job={job_id}");
+            match url::Url::parse(&target) {
+                Ok(url) => {
+                    if let Err(err) = focused_window.navigate(url) {
+                        warn!(error = %err, job_id = %job_id, "Failed to open finished job");
+                    }
+                }
+                Err(err) => warn!(error = %err, target = %target, "Invalid job view URL"),
+            }
+        });
+    }
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    warn!(skipped = n, "Dropped job notifications");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+
+            let (title, body) = job_notification_text(&event);
+            if let Err(err) = app.notification().builder().title(title).body(body).show() {
+                warn!(error = %err, job_id = %event.job_id, "Failed to show job notification");
+                continue;
+            }
+
+            let in_background = app
+                .get_webview_window("main")
+                .and_then(|window| window.is_focused().ok())
+                .is_some_and(|focused| !focused);
+            if in_background {
+                *pending_job
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(event.job_id);
+            }
+        }
+    });
+}
+
+/// Files named on a command line, resolved against `cwd`. Flags and paths
+/// that are not files are skipped.
+fn launch_files(args: impl IntoIterator<Item = String>, cwd: &Path) -> Vec<PathBuf> {
+    args.into_iter()
+        .filter(|arg| !arg.starts_with('-'))
+        .map(|arg| cwd.join(arg))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// The jobs page with the run dialog open on `files`, or `None` when none
+/// of them could be registered.
+async fn run_dialog_url(
+    state: &AppState,
+    local_server_url: &str,
+    files: &[PathBuf],
+) -> Option<url::Url> {
+    let mut url = url::Url::parse(&format!("{local_server_url}/jobs")).ok()?;
+    let mut registered_any = false;
+    for file in files {
+        match state.register_local_input(file).await {
+            Ok(input) => {
+                url.query_pairs_mut()
+                    .append_pair("input", &input.path.to_string_lossy());
+                registered_any = true;
+            }
+            Err(err) => {
+                warn!(error = %format!("{err:#}"), path = %file.display(), "Ignoring file passed to the desktop app");
+            }
+        }
+    }
+    registered_any.then_some(url)
+}
+
+/// A second launch ends right after handing its arguments over; bring this
+/// window forward and open the files it was given in the run dialog.
+fn open_forwarded_launch(app: &AppHandle, argv: Vec<String>, cwd: String) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    let _ = window.unminimize();
+    let _ = window.set_focus();
+
+    let files = launch_files(argv.into_iter().skip(1), Path::new(&cwd));
+    if files.is_empty() {
+        return;
+    }
+    let (Some(state), Some(local_server_url)) = (
+        app.try_state::<AppState>(),
+        app.try_state::<LocalServerUrl>(),
+    ) else {
+        return;
+    };
+    let state = state.inner().clone();
+    let local_server_url = local_server_url.0.clone();
+    info!(
+        count = files.len(),
+        "Opening files forwarded by a second launch"
+    );
+    tauri::async_runtime::spawn(async move {
+        if let Some(url) = run_dialog_url(&state, &local_server_url, &files).await {
+            if let Err(err) = window.navigate(url) {
+                warn!(error = %err, "Failed to open the run dialog for forwarded files");
+            }
+        }
+    });
+}
+
+/// Download the newest release on `channel`, or the configured channel,
+/// install it and restart into it. Invoked from the settings page.
+#[tauri::command]
+async fn install_update(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    channel: Option<UpdateChannel>,
+) -> Result<(), String> {
+    if UPDATER_PUBKEY.is_none() {
+        return Err("this build cannot install updates".to_string());
+    }
+    let check = state
+        .check_for_updates(channel)
+        .await
+        .map_err(|err| format!("{err:#}"))?;
+    let release = match check.latest {
+        Some(release) if check.update_available => release,
+        _ => return Err("no update available".to_string()),
+    };
+    let manifest_url = release
+        .updater_manifest_url
+        .as_deref()
+        .ok_or_else(|| format!("release {} has no desktop update", release.tag))?;
+    let manifest_url = url::Url::parse(manifest_url).map_err(|err| err.to_string())?;
+
+    let update = app
+        .updater_builder()
+        .endpoints(vec![manifest_url])
+        .and_then(|builder| builder.build())
+        .map_err(|err| err.to_string())?
+        .check()
+        .await
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "no update available".to_string())?;
+    info!(version = %update.version, "Installing update");
+    update
+        .download_and_install(|_, _| {}, || {})
+        .await
+        .map_err(|err| err.to_string())?;
+    app.restart();
+}
+
 fn detect_startup_locale() -> String {
     sys_locale::get_locale()
         .map(|locale| videnoa_core::config::normalize_supported_locale(&locale))
@@ -170,12 +410,39 @@ fn detect_startup_locale() -> String {
 }
 
 fn main() {
+    // Files on the command line are relative to where the app was started,
+    // which portable mode changes.
+    let launch_cwd = std::env::current_dir().unwrap_or_default();
+    let portable = std::env::current_exe()
+        .ok()
+        .and_then(|exe| portable_root(&exe));
+    let (startup_data_dir, chdir_result) = match &portable {
+        // Relative config paths such as `models` and the runtime libraries
+        // are looked up from the working directory, so run from the install.
+        Some(root) => (portable_data_dir(root), std::env::set_current_dir(root)),
+        None => (data_dir(None), Ok(())),
+    };
+
     videnoa_core::runtime::setup_runtime_libs();
-    let startup_data_dir = data_dir(None);
     init_logging(startup_data_dir.clone());
+    if let Err(err) = chdir_result {
+        warn!(error = %err, "Failed to switch to the portable install directory");
+    }
     videnoa_core::runtime::log_runtime_lib_status();
 
-    tauri::Builder::default()
+    let mut builder = tauri::Builder::default()
+        // Must be registered first so a second launch exits before it starts
+        // its own server.
+        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            open_forwarded_launch(app, argv, cwd);
+        }))
+        .plugin(tauri_plugin_notification::init());
+    if let Some(pubkey) = UPDATER_PUBKEY {
+        builder = builder.plugin(tauri_plugin_updater::Builder::new().pubkey(pubkey).build());
+    }
+
+    builder
+        .invoke_handler(tauri::generate_handler![install_update])
         .setup(move |app| {
             let data_dir = startup_data_dir.clone();
             let cfg_path = config_path(&data_dir);
@@ -228,13 +495,22 @@ fn main() {
             #[cfg(not(debug_assertions))]
             let static_path: Option<&Path> = None;
 
-            let router = app_router_with_static(state, static_path);
+            let router = app_router_with_static(state.clone(), static_path);
+            let job_events = state.subscribe_job_events();
+            app.manage(state.clone());
 
             let listener = TcpListener::bind("127.0.0.1:0")?;
             listener.set_nonblocking(true)?;
             let port = listener.local_addr()?.port();
 
             tauri::async_runtime::spawn(async move {
+                let _config_watcher = spawn_config_watcher(&state);
+                let _models_watcher = spawn_models_watcher(&state);
+                let _job_retention = spawn_job_retention(&state);
+                let _log_size_limit = spawn_log_size_limit(&state);
+                let _performance_sampler = spawn_performance_sampler(&state);
+                let _community_preset_sync = spawn_community_preset_sync(&state);
+                let _power_manager = spawn_power_manager(&state);
                 let listener = match tokio::net::TcpListener::from_std(listener) {
                     Ok(listener) => listener,
                     Err(err) => {
@@ -253,11 +529,25 @@ fn main() {
                 mode = "desktop",
                 pid = std::process::id(),
                 data_dir = %data_dir.display(),
+                portable = portable.is_some(),
                 local_server_url = %local_server_url,
                 local_server_port = port,
                 "Desktop runtime startup metadata"
             );
-            let url = url::Url::parse(&local_server_url)?;
+            app.manage(LocalServerUrl(local_server_url.clone()));
+
+            // Files passed on the first launch, e.g. through "Open with".
+            let files = launch_files(std::env::args().skip(1), &launch_cwd);
+            let run_dialog = if files.is_empty() {
+                None
+            } else {
+                let state = app.state::<AppState>().inner().clone();
+                tauri::async_runtime::block_on(run_dialog_url(&state, &local_server_url, &files))
+            };
+            let url = match run_dialog {
+                Some(url) => url,
+                None => url::Url::parse(&local_server_url)?,
+            };
             let (window_width, window_height) = select_startup_window_size(app);
 
             WebviewWindowBuilder::new(app, "main".to_string(), WebviewUrl::External(url))
@@ -267,6 +557,8 @@ fn main() {
                 .title("Videnoa")
                 .build()?;
 
+            spawn_job_notifications(app.handle().clone(), job_events, local_server_url);
+
             Ok(())
         })
         .run(tauri::generate_context!())
@@ -0,0 +1,120 @@
+"""Python side of the videnoa `Python` node frame protocol.
+
+A script for the `Python` node imports this module and calls `serve`:
+
+    import videnoa_frames
+
+    def setup(params):
+        return load_model(params["weights"])
+
+    def process(frame, model):
+        image = frame.to_numpy()  # (height, width, 3) uint8
+        return videnoa_frames.Frame.from_numpy(model(image))
+
+    videnoa_frames.serve(process, setup=setup)
+
+`process` gets each frame and either the value returned by `setup` or, when no
+`setup` is given, the node's `params_json`. Only the standard library is needed;
+`to_numpy` and `from_numpy` import numpy on first use. Use stderr for logging,
+since stdout carries the frames.
+"""
+
+from __future__ import annotations
+
+import dataclasses
+import json
+import struct
+import sys
+from dataclasses import dataclass
+from typing import Any, Callable, Optional
+
+PROTOCOL_NAME = "videnoa-frames"
+PROTOCOL_VERSION = 1
+HEADER = struct.Struct("<III")
+CHANNELS = 3
+
+
+@dataclass(frozen=True)
+class Frame:
+    width: int
+    height: int
+    data: bytes
+
+    def replace(self, **changes: Any) -> "Frame":
+        return dataclasses.replace(self, **changes)
+
+    def to_numpy(self):
+        import numpy as np
+
+        return np.frombuffer(self.data, dtype=np.uint8).reshape(
+            self.height, self.width, CHANNELS
+        )
+
+    @classmethod
+    def from_numpy(cls, array) -> "Frame":
+        import numpy as np
+
+        array = np.ascontiguousarray(array, dtype=np.uint8)
+        if array.ndim != 3 or array.shape[2] != CHANNELS:
+            raise ValueError(f"expected a (height, width, 3) array, got {array.shape}")
+        height, width, _ = array.shape
+        return cls(width=width, height=height, data=array.tobytes())
+
+
+def _read_exact(stream, size: int) -> Optional[bytes]:
+    chunks = []
+    remaining = size
+    while remaining:
+        chunk = stream.read(remaining)
+        if not chunk:
+            if remaining == size:
+                return None
+            raise EOFError("stream ended inside a frame")
+        chunks.append(chunk)
+        remaining -= len(chunk)
+    return b"".join(chunks)
+
+
+def _reply(stdout, message: dict) -> None:
+    stdout.write(json.dumps(message).encode() + b"\n")
+    stdout.flush()
+
+
+def serve(
+    process: Callable[[Frame, Any], Frame],
+    setup: Optional[Callable[[dict], Any]] = None,
+) -> None:
+    stdin = sys.stdin.buffer
+    stdout = sys.stdout.buffer
+
+    hello = json.loads(stdin.readline() or b"{}")
+    if hello.get("protocol") != PROTOCOL_NAME or hello.get("version") != PROTOCOL_VERSION:
+        found = f"{hello.get('protocol')} v{hello.get('version')}"
+        _reply(stdout, {"error": f"unsupported protocol: {found}"})
+        sys.exit(1)
+
+    params = hello.get("params") or {}
+    try:
+        context = setup(params) if setup is not None else params
+    except Exception as exc:  # report setup failures to the host
+        _reply(stdout, {"error": f"setup failed: {exc}"})
+        sys.exit(1)
+    _reply(stdout, {"ready": True})
+
+    while True:
+        header = _read_exact(stdin, HEADER.size)
+        if header is None:
+            return
+        width, height, channels = HEADER.unpack(header)
+        if channels != CHANNELS:
+            raise ValueError(f"expected {CHANNELS} channels, got {channels}")
+        data = _read_exact(stdin, width * height * channels)
+        if data is None:
+            raise EOFError("stream ended inside a frame")
+
+        result = process(Frame(width=width, height=height, data=data), context)
+        if len(result.data) != result.width * result.height * CHANNELS:
+            raise ValueError("frame data does not match its width and height")
+        stdout.write(HEADER.pack(result.width, result.height, CHANNELS))
+        stdout.write(result.data)
+        stdout.flush()
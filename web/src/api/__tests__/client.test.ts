@@ -18,6 +18,7 @@ import {
   saveWorkflow,
   submitJob,
   submitJobWithParams,
+  uploadModelFile,
 } from '../client';
 
 beforeEach(() => {
@@ -567,3 +568,40 @@ describe('cancelJob()', () => {
     );
   });
 });
+
+describe('uploadModelFile()', () => {
+  it('resumes an unfinished upload at its offset', async () => {
+    const mockFetch = vi.mocked(fetch);
+    const upload = {
+      id: 'u1',
+      filename: '4x_New.onnx',
+      size_bytes: 10,
+      sha256: null,
+      received_bytes: 4,
+      complete: false,
+    };
+    mockFetch
+      .mockResolvedValueOnce(new Response(JSON.stringify(upload), { status: 200 }))
+      .mockResolvedValueOnce(
+        new Response(JSON.stringify({ ...upload, received_bytes: 10, complete: true }), {
+          status: 200,
+        }),
+      );
+    const progress: number[] = [];
+
+    const file = new File(['0123456789'], '4x_New.onnx');
+    const result = await uploadModelFile(file, (received) => progress.push(received));
+
+    expect(result.complete).toBe(true);
+    expect(progress).toEqual([4, 10]);
+    const [startUrl, startInit] = mockFetch.mock.calls[0];
+    expect(startUrl).toBe('/api/models/upload');
+    expect(JSON.parse(startInit?.body as string)).toMatchObject({
+      filename: '4x_New.onnx',
+      size_bytes: 10,
+    });
+    const [chunkUrl, chunkInit] = mockFetch.mock.calls[1];
+    expect(chunkUrl).toBe('/api/models/upload/u1?offset=4');
+    expect((chunkInit?.body as Blob).size).toBe(6);
+  });
+});
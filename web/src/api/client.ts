@@ -4,9 +4,13 @@ import type {
   BatchResponse,
   CreateJobResponse,
   ExtractResponse,
+  HardwareUsage,
   JobResponse,
+  JobPhase,
+  JobStatus,
   JobWsEvent,
   JobWsNodeDebugValueEvent,
+  MediaProbe,
   PerformanceCapabilitiesResponse,
   PerformanceCurrentResponse,
   PerformanceExportResponse,
@@ -14,6 +18,9 @@ import type {
   Preset,
   ProcessResponse,
   ProgressUpdate,
+  StageProgress,
+  UpdateChannel,
+  UpdateCheck,
   Workflow,
   WorkflowInterface,
 } from '../types';
@@ -42,6 +49,39 @@ export interface ModelEntry {
   description: string;
   is_fp16: boolean;
   input_format: string;
+  architecture: string | null;
+  training_domain: string | null;
+  license: string | null;
+  source_url: string | null;
+  recommended_tile_size: number | null;
+}
+
+export interface ModelDirUsage {
+  path: string;
+  model_count: number;
+  total_bytes: number;
+  available_bytes: number | null;
+}
+
+export interface ModelFileUsage {
+  filename: string;
+  path: string;
+  size_bytes: number;
+}
+
+export interface ModelDiskUsage {
+  total_bytes: number;
+  dirs: ModelDirUsage[];
+  models: ModelFileUsage[];
+}
+
+export interface ModelUpload {
+  id: string;
+  filename: string;
+  size_bytes: number;
+  sha256: string | null;
+  received_bytes: number;
+  complete: boolean;
 }
 
 // ─── API Error ───────────────────────────────────────────────────────────────
@@ -103,8 +143,8 @@ function jsonBody(data: unknown): RequestInit {
 
 // ─── Health ──────────────────────────────────────────────────────────────────
 
-export function healthCheck(): Promise<{ status: string }> {
-  return request<{ status: string }>('/api/health');
+export function healthCheck(): Promise<{ status: string; read_only: boolean }> {
+  return request<{ status: string; read_only: boolean }>('/api/health');
 }
 
 // ─── Nodes ───────────────────────────────────────────────────────────────────
@@ -119,6 +159,83 @@ export function listModels(): Promise<ModelEntry[]> {
   return request<ModelEntry[]>('/api/models');
 }
 
+export function getModelDiskUsage(): Promise<ModelDiskUsage> {
+  return request<ModelDiskUsage>('/api/models/usage');
+}
+
+export function renameModel(filename: string, newFilename: string): Promise<ModelEntry> {
+  return request<ModelEntry>(
+    `/api/models/${encodeURIComponent(filename)}/rename`,
+    jsonBody({ filename: newFilename }),
+  );
+}
+
+/** Bytes sent per request by {@link uploadModelFile}. */
+export const MODEL_UPLOAD_CHUNK_BYTES = 8 * 1024 * 1024;
+
+export function startModelUpload(
+  filename: string,
+  sizeBytes: number,
+  sha256: string | null,
+): Promise<ModelUpload> {
+  return request<ModelUpload>(
+    '/api/models/upload',
+    jsonBody({ filename, size_bytes: sizeBytes, sha256 }),
+  );
+}
+
+export function uploadModelChunk(id: string, offset: number, chunk: Blob): Promise<ModelUpload> {
+  return request<ModelUpload>(
+    `/api/models/upload/${encodeURIComponent(id)}?offset=${String(offset)}`,
+    {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/octet-stream' },
+      body: chunk,
+    },
+  );
+}
+
+async function sha256Hex(file: Blob): Promise<string | null> {
+  // Only available in secure contexts (HTTPS or localhost); the server then
+  // accepts the upload without a hash.
+  const subtle = globalThis.crypto?.subtle as SubtleCrypto | undefined;
+  if (!subtle) {
+    return null;
+  }
+  const digest = await subtle.digest('SHA-256', await file.arrayBuffer());
+  return Array.from(new Uint8Array(digest), (b) => b.toString(16).padStart(2, '0')).join('');
+}
+
+/**
+ * Uploads a model file in chunks. An unfinished upload of the same file is
+ * resumed where it stopped.
+ */
+export async function uploadModelFile(
+  file: File,
+  onProgress?: (receivedBytes: number, totalBytes: number) => void,
+): Promise<ModelUpload> {
+  const sha256 = await sha256Hex(file);
+  let upload = await startModelUpload(file.name, file.size, sha256);
+  onProgress?.(upload.received_bytes, file.size);
+  while (!upload.complete) {
+    const offset = upload.received_bytes;
+    const chunk = file.slice(offset, offset + MODEL_UPLOAD_CHUNK_BYTES);
+    upload = await uploadModelChunk(upload.id, offset, chunk);
+    onProgress?.(upload.received_bytes, file.size);
+  }
+  return upload;
+}
+
+export async function deleteModel(filename: string): Promise<void> {
+  const resp = await fetch(`/api/models/${encodeURIComponent(filename)}`, {
+    method: 'DELETE',
+  });
+  if (!resp.ok) {
+    const text = await resp.text().catch(() => '');
+    throw new ApiError(resp.status, text || resp.statusText);
+  }
+}
+
 export function getPerformanceCurrent(): Promise<PerformanceCurrentResponse> {
   return requestValidated('/api/performance/current', parsePerformanceCurrentResponse);
 }
@@ -127,8 +244,20 @@ export function getPerformanceOverview(): Promise<PerformanceOverviewResponse> {
   return requestValidated('/api/performance/overview', parsePerformanceOverviewResponse);
 }
 
-export function getPerformanceExport(): Promise<PerformanceExportResponse> {
-  return requestValidated('/api/performance/export', parsePerformanceExportResponse);
+/** `from`/`to` are RFC 3339 instants; without them the newest samples are returned. */
+export function getPerformanceExport(
+  range: { from?: string; to?: string } = {},
+): Promise<PerformanceExportResponse> {
+  const query = new URLSearchParams();
+  if (range.from) {
+    query.set('from', range.from);
+  }
+  if (range.to) {
+    query.set('to', range.to);
+  }
+  const search = query.toString();
+  const suffix = search ? `?${search}` : '';
+  return requestValidated(`/api/performance/export${suffix}`, parsePerformanceExportResponse);
 }
 
 export function getPerformanceCapabilities(): Promise<PerformanceCapabilitiesResponse> {
@@ -203,7 +332,13 @@ export function listJobs(): Promise<JobResponse[]> {
   return request<JobResponse[]>('/api/jobs');
 }
 
-export function rerunJob(id: string): Promise<CreateJobResponse> {
+export function rerunJob(
+  id: string,
+  params?: Record<string, unknown>,
+): Promise<CreateJobResponse> {
+  if (params && Object.keys(params).length > 0) {
+    return request<CreateJobResponse>(`/api/jobs/${id}/rerun`, jsonBody({ params }));
+  }
   return request<CreateJobResponse>(`/api/jobs/${id}/rerun`, { method: 'POST' });
 }
 
@@ -230,6 +365,14 @@ export function cancelJob(id: string): Promise<void> {
   return deleteJobHistory(id);
 }
 
+export function pauseJob(id: string): Promise<JobResponse> {
+  return request<JobResponse>(`/api/jobs/${id}/pause`, { method: 'POST' });
+}
+
+export function resumeJob(id: string): Promise<JobResponse> {
+  return request<JobResponse>(`/api/jobs/${id}/resume`, { method: 'POST' });
+}
+
 // ─── WebSocket progress ──────────────────────────────────────────────────────
 
 function isRecord(value: unknown): value is Record<string, unknown> {
@@ -237,7 +380,7 @@ function isRecord(value: unknown): value is Record<string, unknown> {
 }
 
 function parseProgressPayload(value: Record<string, unknown>): ProgressUpdate | null {
-  const { current_frame, total_frames, fps, eta_seconds } = value;
+  const { current_frame, total_frames, fps, eta_seconds, phase, stages, hardware } = value;
   if (typeof current_frame !== 'number' || typeof fps !== 'number') {
     return null;
   }
@@ -253,9 +396,68 @@ function parseProgressPayload(value: Record<string, unknown>): ProgressUpdate |
     total_frames: total_frames ?? null,
     fps,
     eta_seconds: eta_seconds ?? null,
+    phase: parsePhase(phase),
+    stages: parseStages(stages),
+    hardware: parseHardware(hardware),
   };
 }
 
+function optionalNumber(value: unknown): number | null {
+  return typeof value === 'number' && Number.isFinite(value) ? value : null;
+}
+
+function parseHardware(value: unknown): HardwareUsage | null {
+  if (!isRecord(value)) {
+    return null;
+  }
+  return {
+    cpu_util_percent: optionalNumber(value.cpu_util_percent),
+    gpu_util_percent: optionalNumber(value.gpu_util_percent),
+    vram_used_bytes: optionalNumber(value.vram_used_bytes),
+  };
+}
+
+function parseStages(value: unknown): StageProgress[] {
+  if (!Array.isArray(value)) {
+    return [];
+  }
+  return value.filter(
+    (stage): stage is StageProgress =>
+      isRecord(stage) &&
+      typeof stage.name === 'string' &&
+      typeof stage.frames === 'number' &&
+      typeof stage.queued === 'number' &&
+      typeof stage.queue_capacity === 'number',
+  );
+}
+
+const JOB_PHASES: readonly JobPhase[] = [
+  'loading_model',
+  'building_engine',
+  'decoding',
+  'processing',
+  'encoding',
+  'muxing',
+];
+
+function parsePhase(value: unknown): JobPhase | null {
+  return JOB_PHASES.find((phase) => phase === value) ?? null;
+}
+
+const JOB_STATUSES: readonly JobStatus[] = [
+  'queued',
+  'running',
+  'completed',
+  'failed',
+  'cancelled',
+  'skipped',
+  'paused',
+];
+
+function parseJobStatus(value: unknown): JobStatus | null {
+  return JOB_STATUSES.find((status) => status === value) ?? null;
+}
+
 function parseNodeDebugValuePayload(
   value: Record<string, unknown>,
 ): JobWsNodeDebugValueEvent | null {
@@ -295,6 +497,16 @@ function parseJobWsEvent(data: unknown): JobWsEvent | null {
     return parseNodeDebugValuePayload(data);
   }
 
+  if (eventType === 'phase') {
+    const phase = parsePhase(data.phase);
+    return phase ? { type: 'phase', phase } : null;
+  }
+
+  if (eventType === 'status') {
+    const status = parseJobStatus(data.status);
+    return status ? { type: 'status', status } : null;
+  }
+
   const progress = parseProgressPayload(data);
   return progress ? { type: 'progress', ...progress } : null;
 }
@@ -304,12 +516,23 @@ export function subscribeToProgress(
   onProgress: (update: ProgressUpdate) => void,
   onClose: () => void,
   onNodeDebugValue?: (event: JobWsNodeDebugValueEvent) => void,
+  onStatus?: (status: JobStatus) => void,
 ): { close: () => void } {
   let retries = 0;
   const maxRetries = 3;
   const retryDelay = 2000;
   let ws: WebSocket | null = null;
   let closed = false;
+  // Phase events carry no frame counts; merge them into the last update.
+  let lastProgress: ProgressUpdate = {
+    current_frame: 0,
+    total_frames: null,
+    fps: 0,
+    eta_seconds: null,
+    phase: null,
+    stages: [],
+    hardware: null,
+  };
 
   function connect() {
     const proto = window.location.protocol === 'https:' ? 'wss:' : 'ws:';
@@ -323,12 +546,21 @@ export function subscribeToProgress(
         }
 
         if (parsed.type === 'progress') {
-          onProgress({
+          lastProgress = {
             current_frame: parsed.current_frame,
             total_frames: parsed.total_frames,
             fps: parsed.fps,
             eta_seconds: parsed.eta_seconds,
-          });
+            phase: parsed.phase ?? lastProgress.phase,
+            stages: parsed.stages,
+            hardware: parsed.hardware,
+          };
+          onProgress(lastProgress);
+        } else if (parsed.type === 'phase') {
+          lastProgress = { ...lastProgress, phase: parsed.phase };
+          onProgress(lastProgress);
+        } else if (parsed.type === 'status') {
+          onStatus?.(parsed.status);
         } else {
           onNodeDebugValue?.(parsed);
         }
@@ -380,6 +612,24 @@ export function createPreset(
   return request<Preset>('/api/presets', jsonBody({ name, description, workflow }));
 }
 
+export interface PresetUpdate {
+  id: string;
+  name: string;
+  change: 'added' | 'updated' | 'removed';
+  previous_version?: string;
+  version?: string;
+}
+
+export interface CommunitySyncResponse {
+  synced_at: string;
+  presets: number;
+  updates: PresetUpdate[];
+}
+
+export function syncCommunityPresets(): Promise<CommunitySyncResponse> {
+  return request<CommunitySyncResponse>('/api/presets/sync', { method: 'POST' });
+}
+
 // ─── Workflows ────────────────────────────────────────────────────────────────
 
 export interface WorkflowEntry {
@@ -402,6 +652,10 @@ export function saveWorkflow(
   return request<WorkflowEntry>('/api/workflows', jsonBody({ name, description, workflow }));
 }
 
+export function exportVapourSynthScript(workflow: Workflow): Promise<{ script: string }> {
+  return request<{ script: string }>('/api/workflows/export/vapoursynth', jsonBody({ workflow }));
+}
+
 export function getWorkflowInterface(filename: string): Promise<WorkflowInterface> {
   return request<WorkflowInterface>(`/api/workflows/${encodeURIComponent(filename)}/interface`);
 }
@@ -416,6 +670,51 @@ export async function deleteWorkflow(filename: string): Promise<void> {
   }
 }
 
+export interface WorkflowVersion {
+  version: number;
+  saved_at: string;
+  size_bytes: number;
+}
+
+export function listWorkflowVersions(filename: string): Promise<WorkflowVersion[]> {
+  return request<WorkflowVersion[]>(`/api/workflows/${encodeURIComponent(filename)}/versions`);
+}
+
+export function restoreWorkflowVersion(filename: string, version: number): Promise<WorkflowEntry> {
+  return request<WorkflowEntry>(
+    `/api/workflows/${encodeURIComponent(filename)}/versions/${String(version)}/restore`,
+    { method: 'POST' },
+  );
+}
+
+export interface BundleModelCheck {
+  name: string;
+  filename: string;
+  sha256: string | null;
+  url: string | null;
+  status: 'present' | 'downloaded' | 'missing' | 'hash_mismatch' | 'download_failed';
+  error?: string;
+}
+
+export interface ImportWorkflowBundleResponse {
+  workflows: WorkflowEntry[];
+  models: BundleModelCheck[];
+}
+
+export function workflowBundleUrl(filename: string): string {
+  return `/api/workflows/${encodeURIComponent(filename)}/export`;
+}
+
+export function importWorkflowBundle(
+  bundle: Blob,
+  downloadModels = true,
+): Promise<ImportWorkflowBundleResponse> {
+  return request<ImportWorkflowBundleResponse>(
+    `/api/workflows/import?download_models=${String(downloadModels)}`,
+    { method: 'POST', headers: { 'Content-Type': 'application/zip' }, body: bundle },
+  );
+}
+
 // ─── Batch ───────────────────────────────────────────────────────────────────
 
 export function submitBatch(
@@ -428,6 +727,56 @@ export function submitBatch(
   );
 }
 
+// ─── Jellyfin servers ────────────────────────────────────────────────────────
+
+export interface JellyfinServer {
+  name: string;
+  url: string;
+  api_key_secret: string;
+  api_key_set: boolean;
+  default: boolean;
+}
+
+export interface JellyfinServerTest {
+  name: string;
+  ok: boolean;
+  server_name?: string;
+  version?: string;
+  error?: string;
+}
+
+export function listJellyfinServers(): Promise<JellyfinServer[]> {
+  return request<JellyfinServer[]>('/api/jellyfin/servers');
+}
+
+export function saveJellyfinServer(
+  name: string,
+  server: { url: string; api_key?: string; default?: boolean },
+): Promise<JellyfinServer> {
+  return request<JellyfinServer>(`/api/jellyfin/servers/${encodeURIComponent(name)}`, {
+    method: 'PUT',
+    headers: { 'Content-Type': 'application/json' },
+    body: JSON.stringify(server),
+  });
+}
+
+export async function deleteJellyfinServer(name: string): Promise<void> {
+  const resp = await fetch(`/api/jellyfin/servers/${encodeURIComponent(name)}`, {
+    method: 'DELETE',
+  });
+  if (!resp.ok) {
+    const text = await resp.text().catch(() => '');
+    throw new ApiError(resp.status, text || resp.statusText);
+  }
+}
+
+export function testJellyfinServer(name: string): Promise<JellyfinServerTest> {
+  return request<JellyfinServerTest>(
+    `/api/jellyfin/servers/${encodeURIComponent(name)}/test`,
+    { method: 'POST' },
+  );
+}
+
 // ─── Config ──────────────────────────────────────────────────────────────────
 
 export function getConfig(): Promise<AppConfig> {
@@ -442,6 +791,19 @@ export function updateConfig(config: AppConfig): Promise<AppConfig> {
   });
 }
 
+// ─── Updates ─────────────────────────────────────────────────────────────────
+
+export function checkForUpdates(channel?: UpdateChannel): Promise<UpdateCheck> {
+  const query = channel ? `?channel=${encodeURIComponent(channel)}` : '';
+  return request<UpdateCheck>(`/api/updates/check${query}`);
+}
+
+// ─── Probe ───────────────────────────────────────────────────────────────────
+
+export function probeMedia(path: string): Promise<MediaProbe> {
+  return request<MediaProbe>('/api/probe', jsonBody({ path }));
+}
+
 // ─── Preview ─────────────────────────────────────────────────────────────────
 
 export function extractFrames(
@@ -493,6 +855,7 @@ export interface ModelInspection {
   nodes: GraphNodeInfo[];
   param_count: number;
   op_count: number;
+  metadata: Record<string, string>;
 }
 
 export async function inspectModel(filename: string): Promise<ModelInspection> {
@@ -518,6 +881,26 @@ export async function listDirectory(
   return res.json();
 }
 
+export interface ParamSuggestion {
+  value: string;
+  label: string;
+  kind: 'option' | 'model' | 'workflow' | 'dir' | 'file' | 'server';
+}
+
+export async function suggestParamValues(
+  nodeType: string,
+  param: string,
+  prefix: string = '',
+): Promise<ParamSuggestion[]> {
+  const params = new URLSearchParams({ param });
+  if (prefix) params.set('prefix', prefix);
+  const res = await fetch(
+    `/api/nodes/${encodeURIComponent(nodeType)}/suggest?${params.toString()}`,
+  );
+  if (!res.ok) return [];
+  return res.json();
+}
+
 export async function browseDirectory(path: string = ''): Promise<FsEntry[]> {
   const params = new URLSearchParams();
   if (path) params.set('path', path);
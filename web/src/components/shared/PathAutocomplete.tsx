@@ -2,7 +2,7 @@ import { FileText, FolderOpen, Loader2 } from "lucide-react";
 import { useCallback, useEffect, useRef, useState } from "react";
 import { useTranslation } from "react-i18next";
 import type { FsEntry } from "@/api/client";
-import { browseDirectory } from "@/api/client";
+import { browseDirectory, suggestParamValues } from "@/api/client";
 import { Input } from "@/components/ui/input";
 import {
 	Popover,
@@ -15,14 +15,47 @@ interface PathAutocompleteProps {
 	value: string;
 	onChange: (path: string) => void;
 	className?: string;
+	/** Ask the server for suggestions for this node param instead of browsing. */
+	suggestFor?: { nodeType: string; param: string };
+}
+
+function fetchEntries(
+	input: string,
+	suggestFor: PathAutocompleteProps["suggestFor"],
+): Promise<FsEntry[]> {
+	if (suggestFor) {
+		return suggestParamValues(suggestFor.nodeType, suggestFor.param, input).then(
+			(suggestions) =>
+				suggestions.map((s) => ({
+					name: s.label,
+					is_dir: s.kind === "dir",
+					path: s.value,
+				})),
+		);
+	}
+
+	// Split input into directory part + filename prefix for partial matching.
+	// e.g. "/home/user/Do" → dir="/home/user/", namePrefix="do"
+	const lastSlash = input.lastIndexOf("/");
+	const dir = lastSlash >= 0 ? input.slice(0, lastSlash + 1) : input;
+	const namePrefix =
+		lastSlash >= 0 ? input.slice(lastSlash + 1).toLowerCase() : "";
+	return browseDirectory(dir).then((entries) =>
+		namePrefix
+			? entries.filter((e) => e.name.toLowerCase().startsWith(namePrefix))
+			: entries,
+	);
 }
 
 export function PathAutocomplete({
 	value,
 	onChange,
 	className,
+	suggestFor,
 }: PathAutocompleteProps) {
 	const { t } = useTranslation("common");
+	const suggestNodeType = suggestFor?.nodeType;
+	const suggestParam = suggestFor?.param;
 	const [open, setOpen] = useState(false);
 	const [suggestions, setSuggestions] = useState<FsEntry[]>([]);
 	const [loading, setLoading] = useState(false);
@@ -34,21 +67,16 @@ export function PathAutocomplete({
 		debounceRef.current = setTimeout(() => {
 			const id = ++fetchIdRef.current;
 
-			// Split input into directory part + filename prefix for partial matching.
-			// e.g. "/home/user/Do" → dir="/home/user/", namePrefix="do"
-			const lastSlash = input.lastIndexOf("/");
-			const dir = lastSlash >= 0 ? input.slice(0, lastSlash + 1) : input;
-			const namePrefix =
-				lastSlash >= 0 ? input.slice(lastSlash + 1).toLowerCase() : "";
-
 			setLoading(true);
-			browseDirectory(dir)
+			fetchEntries(
+				input,
+				suggestNodeType && suggestParam
+					? { nodeType: suggestNodeType, param: suggestParam }
+					: undefined,
+			)
 				.then((entries) => {
 					if (id !== fetchIdRef.current) return;
-					const filtered = namePrefix
-						? entries.filter((e) => e.name.toLowerCase().startsWith(namePrefix))
-						: entries;
-					setSuggestions(filtered);
+					setSuggestions(entries);
 					setLoading(false);
 					setOpen(true);
 				})
@@ -58,7 +86,7 @@ export function PathAutocomplete({
 					setLoading(false);
 				});
 		}, 300);
-	}, []);
+	}, [suggestNodeType, suggestParam]);
 
 	useEffect(() => {
 		return () => {
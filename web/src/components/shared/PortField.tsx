@@ -1,9 +1,27 @@
 import type { WorkflowPort } from '@/types'
 import { Input } from '@/components/ui/input'
 import { Checkbox } from '@/components/ui/checkbox'
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select'
 import { PathAutocomplete } from './PathAutocomplete'
 import type { ParamValue } from './port-field-utils'
 
+function PortLabel({ port }: { port: WorkflowPort }) {
+  return (
+    <div>
+      <span className="text-sm text-foreground">{port.name}</span>
+      {port.description && (
+        <p className="text-xs text-muted-foreground">{port.description}</p>
+      )}
+    </div>
+  )
+}
+
 export function PortField({
   port,
   value,
@@ -13,10 +31,37 @@ export function PortField({
   value: ParamValue
   onChange: (value: ParamValue) => void
 }) {
+  if (port.enum_options && port.enum_options.length > 0) {
+    const options = port.enum_options
+    return (
+      <div className="space-y-1">
+        <PortLabel port={port} />
+        <Select
+          value={String(value)}
+          onValueChange={(v) => {
+            const option = options.find((o) => String(o) === v)
+            onChange(typeof option === 'number' || typeof option === 'boolean' ? option : v)
+          }}
+        >
+          <SelectTrigger>
+            <SelectValue />
+          </SelectTrigger>
+          <SelectContent>
+            {options.map((option) => (
+              <SelectItem key={String(option)} value={String(option)}>
+                {String(option)}
+              </SelectItem>
+            ))}
+          </SelectContent>
+        </Select>
+      </div>
+    )
+  }
+
   if (port.port_type === 'Bool') {
     return (
       <div className="flex items-center justify-between gap-3">
-        <span className="text-sm text-foreground">{port.name}</span>
+        <PortLabel port={port} />
         <Checkbox
           checked={Boolean(value)}
           onCheckedChange={(v) => { onChange(!!v) }}
@@ -28,10 +73,12 @@ export function PortField({
   if (port.port_type === 'Int') {
     return (
       <div className="space-y-1">
-        <span className="text-sm text-foreground">{port.name}</span>
+        <PortLabel port={port} />
         <Input
           type="number"
           step={1}
+          min={port.min}
+          max={port.max}
           value={String(value)}
           onChange={(e) => {
             const parsed = parseInt(e.target.value, 10)
@@ -45,10 +92,12 @@ export function PortField({
   if (port.port_type === 'Float') {
     return (
       <div className="space-y-1">
-        <span className="text-sm text-foreground">{port.name}</span>
+        <PortLabel port={port} />
         <Input
           type="number"
           step={0.01}
+          min={port.min}
+          max={port.max}
           value={String(value)}
           onChange={(e) => {
             const parsed = parseFloat(e.target.value)
@@ -62,7 +111,7 @@ export function PortField({
   if (port.port_type === 'Path') {
     return (
       <div className="space-y-1">
-        <span className="text-sm text-foreground">{port.name}</span>
+        <PortLabel port={port} />
         <PathAutocomplete
           value={String(value)}
           onChange={(v) => { onChange(v) }}
@@ -73,7 +122,7 @@ export function PortField({
 
   return (
     <div className="space-y-1">
-      <span className="text-sm text-foreground">{port.name}</span>
+      <PortLabel port={port} />
       <Input
         type="text"
         value={String(value)}
@@ -12,9 +12,10 @@ import { PathAutocomplete } from "../PathAutocomplete";
 
 vi.mock("@/api/client", () => ({
 	browseDirectory: vi.fn(),
+	suggestParamValues: vi.fn(),
 }));
 
-import { browseDirectory } from "@/api/client";
+import { browseDirectory, suggestParamValues } from "@/api/client";
 
 const MOCK_ENTRIES: FsEntry[] = [
 	{ name: "Documents", is_dir: true, path: "/home/Documents" },
@@ -25,6 +26,7 @@ beforeEach(async () => {
 	initializeI18n();
 	await i18n.changeLanguage("en");
 	vi.mocked(browseDirectory).mockReset();
+	vi.mocked(suggestParamValues).mockReset();
 	vi.useFakeTimers({ shouldAdvanceTime: true });
 });
 
@@ -62,6 +64,36 @@ describe("PathAutocomplete", () => {
 		});
 	});
 
+	it("uses node param suggestions when suggestFor is set", async () => {
+		vi.mocked(suggestParamValues).mockResolvedValue([
+			{ value: "/videos/ep01.mkv", label: "ep01.mkv", kind: "file" },
+		]);
+
+		render(
+			<PathAutocomplete
+				value="/videos/ep"
+				onChange={vi.fn()}
+				suggestFor={{ nodeType: "VideoInput", param: "path" }}
+			/>,
+		);
+		const input = screen.getByPlaceholderText("path/to/file");
+
+		await act(async () => {
+			fireEvent.focus(input);
+			await vi.advanceTimersByTimeAsync(300);
+		});
+
+		await waitFor(() => {
+			expect(screen.getByText("ep01.mkv")).toBeInTheDocument();
+		});
+		expect(suggestParamValues).toHaveBeenCalledWith(
+			"VideoInput",
+			"path",
+			"/videos/ep",
+		);
+		expect(browseDirectory).not.toHaveBeenCalled();
+	});
+
 	it("shows folder and file icons", async () => {
 		vi.mocked(browseDirectory).mockResolvedValue(MOCK_ENTRIES);
 
@@ -102,6 +102,8 @@ export const enResources: NamespaceResources = {
 		"jobs.page.actions.cancel": "Cancel",
 		"jobs.page.actions.retry": "Retry",
 		"jobs.page.actions.delete": "Delete",
+		"jobs.page.actions.pause": "Pause",
+		"jobs.page.actions.resume": "Resume",
 
 		"jobs.page.active.title": "Active Job",
 		"jobs.page.active.progressFrames": "{{current}} / {{total}} frames",
@@ -109,6 +111,19 @@ export const enResources: NamespaceResources = {
 		"jobs.page.active.stats.eta": "ETA",
 		"jobs.page.active.stats.elapsed": "Elapsed",
 		"jobs.page.active.stats.status": "Status",
+		"jobs.page.active.phase.loading_model": "Loading model…",
+		"jobs.page.active.phase.building_engine": "Building TensorRT engine…",
+		"jobs.page.active.phase.decoding": "Decoding…",
+		"jobs.page.active.phase.processing": "Processing",
+		"jobs.page.active.phase.encoding": "Encoding…",
+		"jobs.page.active.phase.muxing": "Muxing…",
+		"jobs.page.active.stages.title": "Pipeline stages",
+		"jobs.page.active.stages.bottleneck": "bottleneck",
+		"jobs.page.active.stages.frames": "{{count}} frames",
+		"jobs.page.active.stages.queued": "{{queued}} / {{capacity}} frames queued",
+		"jobs.page.active.hardware.gpu": "GPU {{value}}%",
+		"jobs.page.active.hardware.vram": "VRAM {{value}} GiB",
+		"jobs.page.active.hardware.cpu": "CPU {{value}}%",
 
 		"jobs.page.empty.title": "No jobs yet",
 		"jobs.page.empty.description":
@@ -136,6 +151,16 @@ export const enResources: NamespaceResources = {
 		"jobs.page.history.details.duration": "Duration:",
 		"jobs.page.history.details.params": "Params JSON:",
 		"jobs.page.history.details.error": "Error:",
+		"jobs.page.history.details.errorNode": "Failed node:",
+		"jobs.page.history.details.errorHint": "Hint:",
+		"jobs.page.history.details.errorCategory.io": "File access",
+		"jobs.page.history.details.errorCategory.model": "Model",
+		"jobs.page.history.details.errorCategory.encode": "Encoding",
+		"jobs.page.history.details.errorCategory.network": "Network",
+		"jobs.page.history.details.errorCategory.other": "Other",
+		"jobs.page.history.details.skipReason": "Skipped because:",
+		"jobs.page.history.details.artifacts": "Output files:",
+		"jobs.page.history.details.encodeStats": "Encode:",
 
 		"jobs.dialog.title": "Run Workflow",
 		"jobs.dialog.description":
@@ -144,9 +169,13 @@ export const enResources: NamespaceResources = {
 		"jobs.dialog.empty.noWorkflowWithInputs":
 			"No workflows with input ports found. Create a workflow with WorkflowInput/Output nodes in the Editor first.",
 		"jobs.dialog.parameters.title": "Parameters",
+		"jobs.dialog.parameters.moreFiles_one": "{{count}} more file will run with the same parameters.",
+		"jobs.dialog.parameters.moreFiles_other": "{{count}} more files will run with the same parameters.",
 		"jobs.dialog.actions.run": "Run",
 		"jobs.dialog.actions.submitting": "Submitting…",
 		"jobs.dialog.success.submitted": "Job submitted successfully",
+		"jobs.dialog.success.submittedMany_one": "{{count}} job submitted",
+		"jobs.dialog.success.submittedMany_other": "{{count}} jobs submitted",
 		"jobs.dialog.errors.loadWorkflows": "Failed to load workflows",
 		"jobs.dialog.errors.submitPrefix": "Failed to submit job",
 
@@ -155,24 +184,40 @@ export const enResources: NamespaceResources = {
 		"jobs.status.completed": "Completed",
 		"jobs.status.failed": "Failed",
 		"jobs.status.cancelled": "Cancelled",
+		"jobs.status.skipped": "Skipped",
+		"jobs.status.paused": "Paused",
 	},
 	models: {
 		"card.inputFormat": "Input format: {{format}}",
+		"card.license": "License: {{license}}",
 		"card.noDescription": "No description",
 		"card.range": "Range: {{min}}–{{max}}",
 		"detail.error.inspectFailed": "Failed to inspect model",
 		"detail.graph.tooLarge": "Graph too large to render ({{count}} operations)",
 		"detail.graph.tooLargeHint":
 			"Use the Metadata and I/O Schema tabs instead.",
+		"detail.manage.confirmDelete": "Click again to delete",
+		"detail.manage.delete": "Delete",
+		"detail.manage.deleteFailed": "Failed to delete model",
+		"detail.manage.deleted": "Deleted {{filename}}",
+		"detail.manage.filenameLabel": "Model filename",
+		"detail.manage.rename": "Rename",
+		"detail.manage.renameFailed": "Failed to rename model",
+		"detail.manage.renamed": "Renamed to {{filename}}",
+		"detail.manage.title": "Manage",
+		"detail.metadata.architecture": "Architecture",
 		"detail.metadata.domain": "Domain",
 		"detail.metadata.filename": "Filename",
 		"detail.metadata.irVersion": "IR Version",
+		"detail.metadata.license": "License",
 		"detail.metadata.modelVersion": "Model Version",
 		"detail.metadata.name": "Name",
 		"detail.metadata.opsetVersion": "Opset Version",
 		"detail.metadata.operations": "Operations",
 		"detail.metadata.parameters": "Parameters",
 		"detail.metadata.producer": "Producer",
+		"detail.metadata.recommendedTileSize": "Recommended Tile Size",
+		"detail.metadata.trainingDomain": "Training Domain",
 		"detail.schema.dataType": "Data Type",
 		"detail.schema.inputs": "Inputs",
 		"detail.schema.name": "Name",
@@ -200,6 +245,13 @@ export const enResources: NamespaceResources = {
 		"search.placeholder": "Search models…",
 		"typeBadge.frameInterpolation": "Frame Interpolation",
 		"typeBadge.superResolution": "Super-Resolution",
+		"upload.button": "Upload model",
+		"upload.done": "Uploaded {{filename}}",
+		"upload.failed": "Failed to upload model",
+		"upload.progress": "Uploading… {{percent}}%",
+		"usage.free": "{{size}} free",
+		"usage.summary_one": "{{size}} on disk in {{count}} model file",
+		"usage.summary_other": "{{size}} on disk in {{count}} model files",
 		"view.grid": "Grid view",
 		"view.list": "List view",
 	},
@@ -258,6 +310,8 @@ export const enResources: NamespaceResources = {
 		"batch.workflow.nodes_zero": "no nodes",
 		"batch.workflow.nodes_one": "{{count}} node",
 		"batch.workflow.nodes_other": "{{count}} nodes",
+		"errors.checkUpdates": "Failed to check for updates",
+		"errors.installUpdate": "Failed to install the update",
 		"errors.loadConfig": "Failed to load config",
 		"errors.saveConfig": "Failed to save config",
 		"footer.saveSuccess": "Settings saved",
@@ -278,12 +332,40 @@ export const enResources: NamespaceResources = {
 		"sections.performance.fields.profilingEnabled": "Profiling telemetry",
 		"sections.performance.fields.profilingEnabledHint":
 			"Changes apply immediately after saving.",
+		"sections.performance.fields.sampleIntervalSecs": "Sample interval (seconds)",
+		"sections.performance.fields.seriesRetentionHours": "Keep samples (hours)",
+		"sections.performance.fields.seriesRetentionHint":
+			"Samples are stored in the jobs database; 0 keeps them forever.",
 		"sections.performance.title": "Performance Profiling",
+		"sections.power.description": "Keep the machine awake for long batches.",
+		"sections.power.fields.disabled": "Disabled",
+		"sections.power.fields.enabled": "Enabled",
+		"sections.power.fields.preventSleep": "Prevent sleep while jobs run",
+		"sections.power.fields.whenQueueEmpty": "When the queue is empty",
+		"sections.power.fields.whenQueueEmptyHint":
+			"Runs one minute after the last job finishes, unless another job is queued.",
+		"sections.power.queueEmptyActions.none": "Do nothing",
+		"sections.power.queueEmptyActions.shutdown": "Shut down",
+		"sections.power.queueEmptyActions.suspend": "Suspend",
+		"sections.power.title": "Power",
 		"sections.server.description": "Server settings (read-only, requires restart)",
 		"sections.server.fields.host": "host",
 		"sections.server.fields.port": "port",
 		"sections.server.readOnlyBadge": "Read-only",
 		"sections.server.title": "Server",
+		"sections.updates.actions.check": "Check for updates",
+		"sections.updates.actions.install": "Install and restart",
+		"sections.updates.channels.beta": "Beta",
+		"sections.updates.channels.stable": "Stable",
+		"sections.updates.description": "Look for new releases on GitHub.",
+		"sections.updates.fields.channel": "Release channel",
+		"sections.updates.fields.channelHint": "Beta also offers pre-releases.",
+		"sections.updates.releaseNotes": "Release notes",
+		"sections.updates.status.available":
+			"Version {{version}} is available (you have {{current}}).",
+		"sections.updates.status.upToDate":
+			"You are on the latest version ({{current}}).",
+		"sections.updates.title": "Updates",
 		"toast.saveSuccess": "Settings saved",
 	},
 	editor: {
@@ -300,6 +382,17 @@ export const enResources: NamespaceResources = {
 		"nodeTitle.Rescale": "Rescale",
 		"nodeTitle.ColorSpace": "Color Space",
 		"nodeTitle.SceneDetect": "Scene Detect",
+		"nodeTitle.AnalyzeSource": "Analyze Source",
+		"nodeTitle.Script": "Script",
+		"nodeTitle.Python": "Python",
+		"nodeTitle.FfmpegFilter": "FFmpeg Filter",
+		"nodeTitle.GrainSynthesis": "Grain Synthesis",
+		"nodeTitle.PreviewTap": "Preview Tap",
+		"nodeTitle.RegionEnhance": "Region Enhance",
+		"nodeTitle.ModelInference": "Model Inference",
+		"nodeTitle.ExternalCommand": "External Command",
+		"nodeTitle.StreamInput": "Stream Input",
+		"nodeTitle.VapourSynthInput": "VapourSynth Input",
 		"nodeTitle.StreamOutput": "Stream Output",
 		"nodeTitle.Constant": "Constant",
 		"nodeTitle.PathDivider": "Path Divider",
@@ -309,6 +402,10 @@ export const enResources: NamespaceResources = {
 		"nodeTitle.TypeConversion": "Type Conversion",
 		"nodeTitle.HttpRequest": "HTTP Request",
 		"nodeTitle.Print": "Print",
+		"modelSelector.auto": "Auto (from benchmarks)",
+		"palette.experimental": "Beta",
+		"node.deprecated": "Deprecated",
+		"node.deprecatedReplacedBy": "Deprecated, use {{replacement}} instead",
 		"toolbar.undo": "Undo (Ctrl+Z)",
 		"toolbar.redo": "Redo (Ctrl+Shift+Z)",
 		"toolbar.autoLayout": "Auto-Layout",
@@ -316,6 +413,7 @@ export const enResources: NamespaceResources = {
 		"toolbar.clear": "Clear",
 		"toolbar.save": "Save",
 		"toolbar.download": "Download",
+		"toolbar.exportVapourSynth": "Export VapourSynth Script",
 		"toolbar.load": "Load",
 		"toolbar.runWorkflow": "Run Workflow",
 		"toolbar.batchProcessing": "Batch Processing",
@@ -330,6 +428,8 @@ export const enResources: NamespaceResources = {
 		"toolbar.toast.workflowSaved": "Workflow saved",
 		"toolbar.toast.saveFailed": "Failed to save workflow",
 		"toolbar.toast.workflowDownloaded": "Workflow downloaded",
+		"toolbar.toast.scriptExported": "VapourSynth script exported",
+		"toolbar.toast.exportFailed": "Failed to export workflow",
 		"runDialog.title": "Run Workflow",
 		"runDialog.description": "Configure input parameters before running.",
 		"runDialog.parameters.title": "Parameters",
@@ -341,6 +441,9 @@ export const enResources: NamespaceResources = {
 		"gallery.description": "Load a preset pipeline or a saved workflow.",
 		"gallery.sections.builtInPresets": "Built-in Presets",
 		"gallery.sections.savedWorkflows": "Saved Workflows",
+		"gallery.sections.communityPresets": "Community Presets",
+		"gallery.presetVersion": "v{{version}}",
+		"gallery.presetAuthor": "by {{author}}",
 		"gallery.empty.noPresets": "No presets available",
 		"gallery.empty.noSavedWorkflows": "No saved workflows yet",
 		"gallery.toast.presetLoaded": "Preset loaded",
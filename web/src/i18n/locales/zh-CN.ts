@@ -99,6 +99,8 @@ export const zhCNResources: NamespaceResources = {
 		"jobs.page.actions.cancel": "取消",
 		"jobs.page.actions.retry": "重试",
 		"jobs.page.actions.delete": "删除",
+		"jobs.page.actions.pause": "暂停",
+		"jobs.page.actions.resume": "继续",
 
 		"jobs.page.active.title": "当前任务",
 		"jobs.page.active.progressFrames": "{{current}} / {{total}} 帧",
@@ -106,6 +108,19 @@ export const zhCNResources: NamespaceResources = {
 		"jobs.page.active.stats.eta": "预计剩余",
 		"jobs.page.active.stats.elapsed": "已用时长",
 		"jobs.page.active.stats.status": "状态",
+		"jobs.page.active.phase.loading_model": "正在加载模型…",
+		"jobs.page.active.phase.building_engine": "正在构建 TensorRT 引擎…",
+		"jobs.page.active.phase.decoding": "正在解码…",
+		"jobs.page.active.phase.processing": "处理中",
+		"jobs.page.active.phase.encoding": "正在编码…",
+		"jobs.page.active.phase.muxing": "正在封装…",
+		"jobs.page.active.stages.title": "流水线阶段",
+		"jobs.page.active.stages.bottleneck": "瓶颈",
+		"jobs.page.active.stages.frames": "{{count}} 帧",
+		"jobs.page.active.stages.queued": "队列 {{queued}} / {{capacity}} 帧",
+		"jobs.page.active.hardware.gpu": "GPU {{value}}%",
+		"jobs.page.active.hardware.vram": "显存 {{value}} GiB",
+		"jobs.page.active.hardware.cpu": "CPU {{value}}%",
 
 		"jobs.page.empty.title": "暂无任务",
 		"jobs.page.empty.description": "请先在编辑器中提交一个工作流。",
@@ -132,6 +147,16 @@ export const zhCNResources: NamespaceResources = {
 		"jobs.page.history.details.duration": "时长：",
 		"jobs.page.history.details.params": "参数 JSON：",
 		"jobs.page.history.details.error": "错误：",
+		"jobs.page.history.details.errorNode": "失败节点：",
+		"jobs.page.history.details.errorHint": "建议：",
+		"jobs.page.history.details.errorCategory.io": "文件访问",
+		"jobs.page.history.details.errorCategory.model": "模型",
+		"jobs.page.history.details.errorCategory.encode": "编码",
+		"jobs.page.history.details.errorCategory.network": "网络",
+		"jobs.page.history.details.errorCategory.other": "其他",
+		"jobs.page.history.details.skipReason": "跳过原因：",
+		"jobs.page.history.details.artifacts": "输出文件：",
+		"jobs.page.history.details.encodeStats": "编码：",
 
 		"jobs.dialog.title": "运行工作流",
 		"jobs.dialog.description": "选择一个工作流并配置其输入参数。",
@@ -139,9 +164,13 @@ export const zhCNResources: NamespaceResources = {
 		"jobs.dialog.empty.noWorkflowWithInputs":
 			"未找到包含输入端口的工作流。请先在编辑器中创建带有 WorkflowInput/Output 节点的工作流。",
 		"jobs.dialog.parameters.title": "参数",
+		"jobs.dialog.parameters.moreFiles_one": "另有 {{count}} 个文件将使用相同参数运行。",
+		"jobs.dialog.parameters.moreFiles_other": "另有 {{count}} 个文件将使用相同参数运行。",
 		"jobs.dialog.actions.run": "运行",
 		"jobs.dialog.actions.submitting": "提交中…",
 		"jobs.dialog.success.submitted": "任务提交成功",
+		"jobs.dialog.success.submittedMany_one": "已提交 {{count}} 个任务",
+		"jobs.dialog.success.submittedMany_other": "已提交 {{count}} 个任务",
 		"jobs.dialog.errors.loadWorkflows": "加载工作流失败",
 		"jobs.dialog.errors.submitPrefix": "提交任务失败",
 
@@ -150,23 +179,39 @@ export const zhCNResources: NamespaceResources = {
 		"jobs.status.completed": "已完成",
 		"jobs.status.failed": "失败",
 		"jobs.status.cancelled": "已取消",
+		"jobs.status.skipped": "已跳过",
+		"jobs.status.paused": "已暂停",
 	},
 	models: {
 		"card.inputFormat": "输入格式：{{format}}",
+		"card.license": "许可证：{{license}}",
 		"card.noDescription": "无描述",
 		"card.range": "范围：{{min}}–{{max}}",
 		"detail.error.inspectFailed": "模型解析失败",
 		"detail.graph.tooLarge": "图过大，无法渲染（{{count}} 个算子）",
 		"detail.graph.tooLargeHint": "请改用“元数据”和“I/O 结构”标签页。",
+		"detail.manage.confirmDelete": "再次点击以删除",
+		"detail.manage.delete": "删除",
+		"detail.manage.deleteFailed": "删除模型失败",
+		"detail.manage.deleted": "已删除 {{filename}}",
+		"detail.manage.filenameLabel": "模型文件名",
+		"detail.manage.rename": "重命名",
+		"detail.manage.renameFailed": "重命名模型失败",
+		"detail.manage.renamed": "已重命名为 {{filename}}",
+		"detail.manage.title": "管理",
+		"detail.metadata.architecture": "架构",
 		"detail.metadata.domain": "域",
 		"detail.metadata.filename": "文件名",
 		"detail.metadata.irVersion": "IR 版本",
+		"detail.metadata.license": "许可证",
 		"detail.metadata.modelVersion": "模型版本",
 		"detail.metadata.name": "名称",
 		"detail.metadata.opsetVersion": "Opset 版本",
 		"detail.metadata.operations": "算子数",
 		"detail.metadata.parameters": "参数量",
 		"detail.metadata.producer": "生成器",
+		"detail.metadata.recommendedTileSize": "推荐分块大小",
+		"detail.metadata.trainingDomain": "训练领域",
 		"detail.schema.dataType": "数据类型",
 		"detail.schema.inputs": "输入",
 		"detail.schema.name": "名称",
@@ -194,6 +239,13 @@ export const zhCNResources: NamespaceResources = {
 		"search.placeholder": "搜索模型…",
 		"typeBadge.frameInterpolation": "帧插值",
 		"typeBadge.superResolution": "超分辨率",
+		"upload.button": "上传模型",
+		"upload.done": "已上传 {{filename}}",
+		"upload.failed": "上传模型失败",
+		"upload.progress": "正在上传… {{percent}}%",
+		"usage.free": "剩余 {{size}}",
+		"usage.summary_one": "{{count}} 个模型文件，共占用 {{size}}",
+		"usage.summary_other": "{{count}} 个模型文件，共占用 {{size}}",
 		"view.grid": "网格视图",
 		"view.list": "列表视图",
 	},
@@ -244,6 +296,8 @@ export const zhCNResources: NamespaceResources = {
 		"batch.workflow.nodes_zero": "无节点",
 		"batch.workflow.nodes_one": "{{count}} 个节点",
 		"batch.workflow.nodes_other": "{{count}} 个节点",
+		"errors.checkUpdates": "检查更新失败",
+		"errors.installUpdate": "安装更新失败",
 		"errors.loadConfig": "加载配置失败",
 		"errors.saveConfig": "保存配置失败",
 		"footer.saveSuccess": "设置已保存",
@@ -261,12 +315,36 @@ export const zhCNResources: NamespaceResources = {
 		"sections.performance.fields.enabled": "已开启",
 		"sections.performance.fields.profilingEnabled": "Profiling 遥测",
 		"sections.performance.fields.profilingEnabledHint": "保存后立即生效。",
+		"sections.performance.fields.sampleIntervalSecs": "采样间隔（秒）",
+		"sections.performance.fields.seriesRetentionHours": "样本保留时长（小时）",
+		"sections.performance.fields.seriesRetentionHint": "样本保存在任务数据库中；0 表示永久保留。",
 		"sections.performance.title": "性能 Profiling",
+		"sections.power.description": "在长时间批处理时保持计算机唤醒。",
+		"sections.power.fields.disabled": "已关闭",
+		"sections.power.fields.enabled": "已开启",
+		"sections.power.fields.preventSleep": "任务运行时阻止睡眠",
+		"sections.power.fields.whenQueueEmpty": "队列清空后",
+		"sections.power.fields.whenQueueEmptyHint": "最后一个任务结束一分钟后执行，期间有新任务排队则取消。",
+		"sections.power.queueEmptyActions.none": "不执行操作",
+		"sections.power.queueEmptyActions.shutdown": "关机",
+		"sections.power.queueEmptyActions.suspend": "睡眠",
+		"sections.power.title": "电源",
 		"sections.server.description": "服务器设置（只读，需重启生效）",
 		"sections.server.fields.host": "host",
 		"sections.server.fields.port": "port",
 		"sections.server.readOnlyBadge": "只读",
 		"sections.server.title": "服务器",
+		"sections.updates.actions.check": "检查更新",
+		"sections.updates.actions.install": "安装并重启",
+		"sections.updates.channels.beta": "测试版",
+		"sections.updates.channels.stable": "稳定版",
+		"sections.updates.description": "在 GitHub 上检查新版本。",
+		"sections.updates.fields.channel": "更新通道",
+		"sections.updates.fields.channelHint": "测试版通道也会提供预发布版本。",
+		"sections.updates.releaseNotes": "更新说明",
+		"sections.updates.status.available": "新版本 {{version}} 可用（当前 {{current}}）。",
+		"sections.updates.status.upToDate": "已是最新版本（{{current}}）。",
+		"sections.updates.title": "更新",
 		"toast.saveSuccess": "设置已保存",
 	},
 	editor: {
@@ -283,6 +361,17 @@ export const zhCNResources: NamespaceResources = {
 		"nodeTitle.Rescale": "重缩放",
 		"nodeTitle.ColorSpace": "色彩空间",
 		"nodeTitle.SceneDetect": "场景检测",
+		"nodeTitle.AnalyzeSource": "片源分析",
+		"nodeTitle.Script": "脚本",
+		"nodeTitle.Python": "Python",
+		"nodeTitle.FfmpegFilter": "FFmpeg 滤镜",
+		"nodeTitle.GrainSynthesis": "颗粒合成",
+		"nodeTitle.PreviewTap": "预览抽帧",
+		"nodeTitle.RegionEnhance": "区域增强",
+		"nodeTitle.ModelInference": "模型推理",
+		"nodeTitle.ExternalCommand": "外部命令",
+		"nodeTitle.StreamInput": "流输入",
+		"nodeTitle.VapourSynthInput": "VapourSynth 输入",
 		"nodeTitle.StreamOutput": "流输出",
 		"nodeTitle.Constant": "常量",
 		"nodeTitle.PathDivider": "路径拆分",
@@ -292,6 +381,10 @@ export const zhCNResources: NamespaceResources = {
 		"nodeTitle.TypeConversion": "类型转换",
 		"nodeTitle.HttpRequest": "HTTP 请求",
 		"nodeTitle.Print": "打印",
+		"modelSelector.auto": "自动（按测速结果）",
+		"palette.experimental": "实验性",
+		"node.deprecated": "已弃用",
+		"node.deprecatedReplacedBy": "已弃用，请改用{{replacement}}",
 		"toolbar.undo": "撤销（Ctrl+Z）",
 		"toolbar.redo": "重做（Ctrl+Shift+Z）",
 		"toolbar.autoLayout": "自动布局",
@@ -299,6 +392,7 @@ export const zhCNResources: NamespaceResources = {
 		"toolbar.clear": "清空",
 		"toolbar.save": "保存",
 		"toolbar.download": "下载",
+		"toolbar.exportVapourSynth": "导出 VapourSynth 脚本",
 		"toolbar.load": "加载",
 		"toolbar.runWorkflow": "运行工作流",
 		"toolbar.batchProcessing": "批量处理",
@@ -313,6 +407,8 @@ export const zhCNResources: NamespaceResources = {
 		"toolbar.toast.workflowSaved": "工作流已保存",
 		"toolbar.toast.saveFailed": "保存工作流失败",
 		"toolbar.toast.workflowDownloaded": "工作流已下载",
+		"toolbar.toast.scriptExported": "VapourSynth 脚本已导出",
+		"toolbar.toast.exportFailed": "导出工作流失败",
 		"runDialog.title": "运行工作流",
 		"runDialog.description": "运行前请先配置输入参数。",
 		"runDialog.parameters.title": "参数",
@@ -324,6 +420,9 @@ export const zhCNResources: NamespaceResources = {
 		"gallery.description": "加载一个预设管线或已保存的工作流。",
 		"gallery.sections.builtInPresets": "内置预设",
 		"gallery.sections.savedWorkflows": "已保存工作流",
+		"gallery.sections.communityPresets": "社区预设",
+		"gallery.presetVersion": "v{{version}}",
+		"gallery.presetAuthor": "作者：{{author}}",
 		"gallery.empty.noPresets": "暂无可用预设",
 		"gallery.empty.noSavedWorkflows": "暂无已保存工作流",
 		"gallery.toast.presetLoaded": "预设已加载",
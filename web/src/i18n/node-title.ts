@@ -14,6 +14,16 @@ const NODE_TITLE_KEY_BY_TYPE: Record<string, string> = {
 	Rescale: "nodeTitle.Rescale",
 	ColorSpace: "nodeTitle.ColorSpace",
 	SceneDetect: "nodeTitle.SceneDetect",
+	AnalyzeSource: "nodeTitle.AnalyzeSource",
+	Script: "nodeTitle.Script",
+	Python: "nodeTitle.Python",
+	FfmpegFilter: "nodeTitle.FfmpegFilter",
+	GrainSynthesis: "nodeTitle.GrainSynthesis",
+	PreviewTap: "nodeTitle.PreviewTap",
+	RegionEnhance: "nodeTitle.RegionEnhance",
+	ModelInference: "nodeTitle.ModelInference",
+	ExternalCommand: "nodeTitle.ExternalCommand",
+	StreamInput: "nodeTitle.StreamInput",
 	StreamOutput: "nodeTitle.StreamOutput",
 	Constant: "nodeTitle.Constant",
 	PathDivider: "nodeTitle.PathDivider",
@@ -1,5 +1,9 @@
 import { describe, expect, it, vi } from 'vitest'
-import { createDesktopWindowController, isDesktopRuntime } from '../runtime-desktop'
+import {
+  createDesktopWindowController,
+  installDesktopUpdate,
+  isDesktopRuntime,
+} from '../runtime-desktop'
 
 describe('isDesktopRuntime()', () => {
   it('returns false in browser-like runtime without Tauri globals', () => {
@@ -100,3 +104,17 @@ describe('createDesktopWindowController()', () => {
     ])
   })
 })
+
+describe('installDesktopUpdate()', () => {
+  it('invokes the desktop install command', async () => {
+    const invoke = vi.fn().mockResolvedValue(undefined)
+
+    await installDesktopUpdate('beta', { __TAURI__: { core: { invoke } } })
+
+    expect(invoke).toHaveBeenCalledWith('install_update', { channel: 'beta' })
+  })
+
+  it('rejects in browser runtime', async () => {
+    await expect(installDesktopUpdate('stable', {})).rejects.toThrow()
+  })
+})
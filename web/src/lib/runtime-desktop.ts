@@ -10,6 +10,9 @@ interface DesktopWindowApi {
 
 interface DesktopGlobalLike {
   __TAURI__?: {
+    core?: {
+      invoke?: (command: string, args?: Record<string, unknown>) => Promise<unknown>
+    }
     window?: {
       getCurrentWindow?: () => DesktopWindowApi
       appWindow?: DesktopWindowApi
@@ -59,3 +62,14 @@ export function createDesktopWindowController(source: unknown = globalThis) {
     close: () => invokeWindowControl('close', source),
   }
 }
+
+export async function installDesktopUpdate(
+  channel: string,
+  source: unknown = globalThis,
+): Promise<void> {
+  const invoke = (source as DesktopGlobalLike).__TAURI__?.core?.invoke
+  if (typeof invoke !== 'function') {
+    throw new Error('Updates can only be installed from the desktop app')
+  }
+  await invoke('install_update', { channel })
+}
@@ -1,29 +1,52 @@
 import { Handle, type NodeProps, Position, useUpdateNodeInternals } from '@xyflow/react';
 import {
+  Activity,
   ArrowDownToLine,
   ArrowLeftRight,
   ArrowUpFromLine,
+  Brain,
   Braces,
+  Calculator,
+  Clock,
+  CloudDownload,
+  CloudUpload,
+  Code,
   Download,
+  Eye,
+  FileCode,
   FileVideo,
   Film,
+  Fingerprint,
   Globe,
   HardDrive,
   Hash,
+  Magnet,
   Microscope,
   Palette,
   Plus,
+  Puzzle,
   Radio,
   Scaling,
+  ScanFace,
   Scissors,
+  Server,
+  SlidersHorizontal,
+  Sparkles,
   Split,
+  Terminal,
   Trash2,
   Workflow,
   X,
 } from 'lucide-react';
 import { memo, useCallback, useEffect, useMemo, useState } from 'react';
 import { useTranslation } from 'react-i18next';
-import { getWorkflowInterface, listModels, type ModelEntry } from '@/api/client';
+import {
+  getWorkflowInterface,
+  inspectModel,
+  listModels,
+  type ModelEntry,
+  type ModelInspection,
+} from '@/api/client';
 import { JellyfinLogo } from '@/components/shared/JellyfinLogo';
 import { PathAutocomplete } from '@/components/shared/PathAutocomplete';
 import { WorkflowPathPicker } from '@/components/shared/WorkflowPathPicker';
@@ -58,23 +81,40 @@ import {
 
 const ICON_REGISTRY: Record<string, React.ComponentType<{ className?: string }>> = {
   'file-video': FileVideo,
+  'file-code': FileCode,
   'microscope': Microscope,
   'film': Film,
   'hard-drive': HardDrive,
   'globe': Globe,
   'radio': Radio,
   'scaling': Scaling,
+  'scan-face': ScanFace,
   'palette': Palette,
   'scissors': Scissors,
+  'activity': Activity,
   'hash': Hash,
   'tv': JellyfinLogo,
   'arrow-down-to-line': ArrowDownToLine,
   'arrow-up-from-line': ArrowUpFromLine,
   'download': Download,
+  'cloud-download': CloudDownload,
+  'cloud-upload': CloudUpload,
+  'magnet': Magnet,
   'workflow': Workflow,
   'split': Split,
   'braces': Braces,
   'arrow-left-right': ArrowLeftRight,
+  'puzzle': Puzzle,
+  'code': Code,
+  'calculator': Calculator,
+  'clock': Clock,
+  'fingerprint': Fingerprint,
+  'server': Server,
+  'terminal': Terminal,
+  'sliders-horizontal': SlidersHorizontal,
+  'sparkles': Sparkles,
+  'eye': Eye,
+  'brain': Brain,
 };
 
 let cachedModels: ModelEntry[] | null = null;
@@ -157,19 +197,31 @@ function InlineHandle({
   );
 }
 
+const AUTO_MODEL = 'auto';
+
 function ModelSelector({
   nodeType,
   value,
+  scale,
   onChange,
 }: {
   nodeType: NodeTypeName;
   value: string;
+  scale?: number;
   onChange: (v: string) => void;
 }) {
+  const { t } = useTranslation('editor');
   const { models } = useModelCache();
 
   const modelType = nodeType === 'FrameInterpolation' ? 'FrameInterpolation' : 'SuperResolution';
-  const filtered = models.filter((m) => m.model_type === modelType);
+  // Upscalers only list models whose declared scale matches the node; models
+  // without a known scale and the current selection always stay visible.
+  const fitsScale = (m: ModelEntry) =>
+    modelType !== 'SuperResolution' || scale == null || m.scale == null || m.scale === scale
+    || value.endsWith(m.filename);
+  const filtered = nodeType === 'ModelInference'
+    ? models
+    : models.filter((m) => m.model_type === modelType && fitsScale(m));
 
   if (filtered.length === 0) {
     return (
@@ -185,25 +237,38 @@ function ModelSelector({
 
   const currentFilename = value.split('/').pop() ?? value;
   const selectedModel = filtered.find((m) => m.filename === currentFilename || value.endsWith(m.filename));
+  // Upscalers can defer the choice to the per-machine benchmark cache.
+  const allowAuto = nodeType === 'SuperResolution';
+  const selectValue = allowAuto && value === AUTO_MODEL
+    ? AUTO_MODEL
+    : selectedModel?.filename ?? '__custom__';
 
   return (
     <Select
-      value={selectedModel?.filename ?? '__custom__'}
+      value={selectValue}
       onValueChange={(v) => {
         if (v === '__custom__') return;
-        onChange(`models/${v}`);
+        onChange(v === AUTO_MODEL ? AUTO_MODEL : `models/${v}`);
       }}
     >
       <SelectTrigger className="h-6 w-[150px] text-[10px] bg-background/50 border-border/50">
         <SelectValue placeholder="Select model" />
       </SelectTrigger>
       <SelectContent>
+        {allowAuto && (
+          <SelectItem value={AUTO_MODEL} className="text-[10px]">
+            {t('modelSelector.auto')}
+          </SelectItem>
+        )}
         {filtered.map((m) => (
           <SelectItem key={m.filename} value={m.filename} className="text-[10px]">
             <span>{m.name}</span>
             {m.scale != null && (
               <span className="ml-1 opacity-60">{m.scale}x</span>
             )}
+            {m.architecture && (
+              <span className="ml-1 opacity-60">{m.architecture}</span>
+            )}
           </SelectItem>
         ))}
       </SelectContent>
@@ -239,6 +304,7 @@ function ParamEditorControl({
       <ModelSelector
         nodeType={nodeType}
         value={String(value ?? '')}
+        scale={typeof nodeParams.scale === 'number' ? nodeParams.scale : undefined}
         onChange={handleChange}
       />
     );
@@ -303,12 +369,24 @@ function ParamEditorControl({
     );
   }
 
+  if (port.ui_hint === 'jellyfin_server') {
+    return (
+      <PathAutocomplete
+        value={String(value ?? port.default_value ?? '')}
+        onChange={handleChange}
+        className="h-6 w-[130px] text-[10px]"
+        suggestFor={{ nodeType, param: port.name }}
+      />
+    );
+  }
+
   if (port.port_type === 'Path' && port.name !== 'model_path') {
     return (
       <PathAutocomplete
         value={String(value ?? port.default_value ?? '')}
         onChange={handleChange}
         className="h-6 w-[150px] text-[10px]"
+        suggestFor={{ nodeType, param: port.name }}
       />
     );
   }
@@ -352,6 +430,7 @@ const PORT_TYPE_VALUES = new Set<PortType>([
   'Bool',
   'Path',
   'WorkflowPath',
+  'Json',
 ]);
 
 function toPortType(value: unknown): PortType | undefined {
@@ -517,6 +596,26 @@ function parseInterfacePorts(params: Record<string, string | number | boolean>,
   }
 }
 
+const MODEL_INPUT_PORT_TYPES: Record<string, PortType> = {
+  float32: 'Float',
+  float16: 'Float',
+  float64: 'Float',
+  int32: 'Int',
+  int64: 'Int',
+  bool: 'Bool',
+};
+
+// Mirrors `ModelIo::from_inspection`: the first 4-D float input takes the
+// frames, every other model input becomes a param port.
+function modelInferencePorts(inspection: ModelInspection): WorkflowPort[] {
+  const image = inspection.inputs.find(
+    (t) => t.shape.length === 4 && (t.data_type === 'float32' || t.data_type === 'float16'),
+  );
+  return inspection.inputs
+    .filter((t) => t !== image && t.data_type in MODEL_INPUT_PORT_TYPES)
+    .map((t) => ({ name: t.name, port_type: MODEL_INPUT_PORT_TYPES[t.data_type] }));
+}
+
 function CustomNodeComponent({ id, data }: NodeProps) {
   const { t } = useTranslation('editor');
   const nodeType = data.nodeType as NodeTypeName;
@@ -566,6 +665,28 @@ function CustomNodeComponent({ id, data }: NodeProps) {
     return () => { cancelled = true; };
   }, [params.workflow_path, isWorkflowNode, id, updateNodeParams]);
 
+  const isModelInferenceNode = nodeType === 'ModelInference';
+
+  useEffect(() => {
+    if (!isModelInferenceNode) return;
+    const modelPath = params.model_path;
+    if (!modelPath || typeof modelPath !== 'string') return;
+    const filename = modelPath.split('/').pop() ?? modelPath;
+
+    let cancelled = false;
+    inspectModel(filename)
+      .then((inspection) => {
+        if (cancelled) return;
+        updateNodeParams(id, { model_inputs: JSON.stringify(modelInferencePorts(inspection)) });
+      })
+      .catch(() => {
+        if (cancelled) return;
+        updateNodeParams(id, { model_inputs: '[]' });
+      });
+
+    return () => { cancelled = true; };
+  }, [params.model_path, isModelInferenceNode, id, updateNodeParams]);
+
   const isParamConnected = useCallback(
     (portName: string) => edges.some((e) => e.target === id && e.targetHandle === portName),
     [edges, id],
@@ -595,7 +716,19 @@ function CustomNodeComponent({ id, data }: NodeProps) {
       dynamic_type_param: null,
     }))
     : [];
-  const effectiveParamInputs = [...paramInputs, ...stringTemplateInputs];
+  const modelInputs = isModelInferenceNode
+    ? parseInterfacePorts(params, 'model_inputs').map<PortDescriptor>((modelPort) => ({
+      name: modelPort.name,
+      port_type: modelPort.port_type,
+      direction: 'param',
+      required: true,
+      default_value: null,
+      ui_hint: null,
+      enum_options: null,
+      dynamic_type_param: null,
+    }))
+    : [];
+  const effectiveParamInputs = [...paramInputs, ...stringTemplateInputs, ...modelInputs];
   const hasStreamPorts = streamInputs.length > 0
     || desc.outputs.length > 0
     || interfaceInputs.length > 0
@@ -622,6 +755,15 @@ function CustomNodeComponent({ id, data }: NodeProps) {
           <X className="size-3" />
         </button>
       </div>
+      {desc.deprecated && (
+        <div className="border-b border-border/40 bg-amber-500/10 px-3 py-1 text-[10px] text-amber-500">
+          {desc.replaced_by
+            ? t('node.deprecatedReplacedBy', {
+              replacement: getLocalizedNodeTitle(t, desc.replaced_by, desc.replaced_by),
+            })
+            : t('node.deprecated')}
+        </div>
+      )}
 
       {hasStreamPorts && (
         <div className="py-1.5">
@@ -630,8 +772,8 @@ function CustomNodeComponent({ id, data }: NodeProps) {
               {streamInputs.map((port) => (
                 <div key={port.name} className="flex items-center gap-1.5 h-[22px] pl-1.5">
                   <InlineHandle port={port} direction="input" />
-                  <span className="text-[10px] text-muted-foreground truncate">
-                    {port.name}
+                  <span className="text-[10px] text-muted-foreground truncate" title={port.name}>
+                    {port.label || port.name}
                   </span>
                 </div>
               ))}
@@ -660,8 +802,8 @@ function CustomNodeComponent({ id, data }: NodeProps) {
                 const effectivePort = resolvedType ? { ...port, port_type: resolvedType } : port;
                 return (
                   <div key={port.name} className="flex items-center gap-1.5 h-[22px] pr-1.5">
-                    <span className="text-[10px] text-muted-foreground truncate">
-                      {port.name}
+                    <span className="text-[10px] text-muted-foreground truncate" title={port.name}>
+                      {port.label || port.name}
                     </span>
                     <InlineHandle port={effectivePort} direction="output" />
                   </div>
@@ -699,8 +841,11 @@ function CustomNodeComponent({ id, data }: NodeProps) {
             return (
               <div key={port.name} className="flex items-center gap-2 min-h-7 pl-1.5 pr-2">
                 <InlineHandle port={effectivePort} direction="input" />
-                <span className="text-[10px] leading-none text-muted-foreground truncate flex-1 min-w-0">
-                  {port.name}
+                <span
+                  className="text-[10px] leading-none text-muted-foreground truncate flex-1 min-w-0"
+                  title={port.name}
+                >
+                  {port.label || port.name}
                 </span>
                 {connected ? (
                   null
@@ -770,6 +915,10 @@ export function getPortType(
     const match = ifacePorts.find((p) => p.name === handleId);
     if (match) return match.port_type as PortType;
   }
+  if (nodeType === 'ModelInference' && direction === 'input' && nodeParams) {
+    const match = parseInterfacePorts(nodeParams, 'model_inputs').find((p) => p.name === handleId);
+    if (match) return match.port_type as PortType;
+  }
   if (nodeType === 'StringTemplate' && direction === 'input' && nodeParams) {
     const match = parseStringTemplateDynamicInputs(nodeParams).find((p) => p.name === handleId);
     if (match) return match.port_type as PortType;
@@ -782,3 +931,9 @@ export function getPortType(
   if (!port) return undefined;
   return resolveDescriptorPortType(port, nodeParams);
 }
+
+/** Whether the input takes any number of connections, e.g. Concat's segments. */
+export function isListPort(nodeType: NodeTypeName, handleId: string): boolean {
+  const desc = useNodeDefinitions.getState().descriptors.find((d) => d.node_type === nodeType);
+  return desc?.inputs.some((p) => p.name === handleId && p.list === true) ?? false;
+}
@@ -17,6 +17,7 @@ import {
 } from "@xyflow/react";
 import { Layers, Sparkles } from "lucide-react";
 import { useCallback, useEffect, useMemo, useRef } from "react";
+import { useTranslation } from "react-i18next";
 import { v4 as uuidv4 } from "uuid";
 import { Button } from "@/components/ui/button";
 import { TooltipProvider } from "@/components/ui/tooltip";
@@ -31,7 +32,12 @@ import {
 	type PortType,
 } from "@/types";
 import { computeLayout } from "./auto-layout";
-import { CustomNode, getDefaultBackend, getPortType } from "./CustomNode";
+import {
+	CustomNode,
+	getDefaultBackend,
+	getPortType,
+	isListPort,
+} from "./CustomNode";
 import { DeletableEdge } from "./DeletableEdge";
 import { EditorToolbar } from "./EditorToolbar";
 import { NodePalette } from "./NodePalette";
@@ -99,10 +105,11 @@ function isTensorPassthrough(
 function EditorCanvas() {
 	const reactFlowWrapper = useRef<HTMLDivElement>(null);
 	const { screenToFlowPosition, fitView } = useReactFlow();
+	const { i18n } = useTranslation();
 
 	useEffect(() => {
-		useNodeDefinitions.getState().fetch();
-	}, []);
+		useNodeDefinitions.getState().fetch(i18n.language);
+	}, [i18n.language]);
 
 	const nodes = useWorkflowStore((s) => s.nodes);
 	const edges = useWorkflowStore((s) => s.edges);
@@ -202,9 +209,23 @@ function EditorCanvas() {
 				targetNode.data.params as Record<string, string | number | boolean>,
 			);
 
-			return sourcePortType !== undefined && sourcePortType === targetPortType;
+			if (sourcePortType === undefined || sourcePortType !== targetPortType) {
+				return false;
+			}
+			// Only list ports take more than one connection.
+			return (
+				isListPort(
+					targetNode.data.nodeType as NodeTypeName,
+					connection.targetHandle,
+				) ||
+				!edges.some(
+					(e) =>
+						e.target === connection.target &&
+						e.targetHandle === connection.targetHandle,
+				)
+			);
 		},
-		[nodes],
+		[nodes, edges],
 	);
 
 	const onConnect = useCallback(
@@ -223,13 +244,33 @@ function EditorCanvas() {
 				) ?? "Str";
 
 			const targetNode = nodes.find((n) => n.id === connection.target);
+			// New segments go after the ones already connected.
+			const toIndex =
+				targetNode &&
+				isListPort(
+					targetNode.data.nodeType as NodeTypeName,
+					connection.targetHandle,
+				)
+					? Math.max(
+							-1,
+							...edges
+								.filter(
+									(e) =>
+										e.target === connection.target &&
+										e.targetHandle === connection.targetHandle,
+								)
+								.map((e) =>
+									typeof e.data?.to_index === "number" ? e.data.to_index : 0,
+								),
+						) + 1
+					: undefined;
 			const tensor =
 				sourceNode && targetNode
 					? isTensorPassthrough(sourceNode, targetNode, portType)
 					: false;
 
 			const edge: Edge = {
-				id: `e-${connection.source}-${connection.sourceHandle}-${connection.target}-${connection.targetHandle}`,
+				id: `e-${connection.source}-${connection.sourceHandle}-${connection.target}-${connection.targetHandle}${toIndex === undefined ? "" : `-${String(toIndex)}`}`,
 				source: connection.source,
 				sourceHandle: connection.sourceHandle,
 				target: connection.target,
@@ -240,12 +281,15 @@ function EditorCanvas() {
 							style: resolveEdgeStyle(portType),
 							animated: portType === "VideoFrames",
 						}),
-				data: { port_type: portType },
+				data:
+					toIndex === undefined
+						? { port_type: portType }
+						: { port_type: portType, to_index: toIndex },
 			};
 
 			addEdgeAction(edge);
 		},
-		[nodes, addEdgeAction],
+		[nodes, edges, addEdgeAction],
 	);
 
 	const onDragOver = useCallback((event: React.DragEvent) => {
@@ -1,6 +1,7 @@
 import {
 	Download,
 	Eye,
+	FileCode,
 	FolderInput,
 	FolderOpen,
 	LayoutDashboard,
@@ -14,7 +15,7 @@ import {
 import { useState } from "react";
 import { useTranslation } from "react-i18next";
 import { useNavigate } from "react-router";
-import { saveWorkflow } from "@/api/client";
+import { exportVapourSynthScript, saveWorkflow } from "@/api/client";
 import { toast } from "@/components/shared/Toaster";
 import { Button } from "@/components/ui/button";
 import { Input } from "@/components/ui/input";
@@ -159,6 +160,25 @@ export function EditorToolbar({ onAutoLayout, onFitView }: EditorToolbarProps) {
 		toast.success(t("toolbar.toast.workflowDownloaded"));
 	};
 
+	const handleExportVapourSynth = async () => {
+		const workflow = useWorkflowStore.getState().exportWorkflow();
+		try {
+			const { script } = await exportVapourSynthScript(workflow);
+			const blob = new Blob([script], { type: "text/x-python" });
+			const url = URL.createObjectURL(blob);
+			const a = document.createElement("a");
+			a.href = url;
+			a.download = `${(currentFile?.filename ?? "workflow.json").replace(/\.json$/, "")}.vpy`;
+			a.click();
+			URL.revokeObjectURL(url);
+			toast.success(t("toolbar.toast.scriptExported"));
+		} catch (err) {
+			toast.error(
+				err instanceof Error ? err.message : t("toolbar.toast.exportFailed"),
+			);
+		}
+	};
+
 	return (
 		<>
 			<div className="absolute top-3 left-1/2 -translate-x-1/2 z-10 flex items-center gap-1 bg-card/90 backdrop-blur-md border border-border/50 rounded-lg px-2 py-1 shadow-lg">
@@ -260,6 +280,14 @@ export function EditorToolbar({ onAutoLayout, onFitView }: EditorToolbarProps) {
 					onClick={handleDownload}
 					disabled={nodeCount === 0}
 				/>
+				<ToolbarButton
+					icon={FileCode}
+					label={t("toolbar.exportVapourSynth")}
+					onClick={() => {
+						void handleExportVapourSynth();
+					}}
+					disabled={nodeCount === 0}
+				/>
 				<ToolbarButton
 					icon={FolderOpen}
 					label={t("toolbar.load")}
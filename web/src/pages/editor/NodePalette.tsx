@@ -1,23 +1,40 @@
 import {
+	Activity,
 	ArrowDownToLine,
 	ArrowLeftRight,
 	ArrowUpFromLine,
+	Brain,
 	Braces,
+	Calculator,
+	Clock,
+	CloudDownload,
+	CloudUpload,
+	Code,
 	Download,
+	Eye,
+	FileCode,
 	FileVideo,
 	Film,
+	Fingerprint,
 	Globe,
 	HardDrive,
 	Hash,
+	Magnet,
 	Microscope,
 	Palette,
 	PanelLeftClose,
 	PanelLeftOpen,
+	Puzzle,
 	Radio,
 	Replace,
 	Scaling,
+	ScanFace,
 	Scissors,
+	Server,
+	SlidersHorizontal,
+	Sparkles,
 	Split,
+	Terminal,
 	Workflow,
 } from "lucide-react";
 import { type DragEvent, useMemo } from "react";
@@ -37,24 +54,41 @@ const ICON_REGISTRY: Record<
 	React.ComponentType<{ className?: string }>
 > = {
 	"file-video": FileVideo,
+	"file-code": FileCode,
 	microscope: Microscope,
 	film: Film,
 	"hard-drive": HardDrive,
 	globe: Globe,
 	radio: Radio,
 	scaling: Scaling,
+	"scan-face": ScanFace,
 	palette: Palette,
 	scissors: Scissors,
+	activity: Activity,
 	hash: Hash,
 	tv: JellyfinLogo,
 	"arrow-down-to-line": ArrowDownToLine,
 	"arrow-up-from-line": ArrowUpFromLine,
 	download: Download,
+	"cloud-download": CloudDownload,
+	"cloud-upload": CloudUpload,
+	magnet: Magnet,
 	workflow: Workflow,
 	split: Split,
 	braces: Braces,
 	replace: Replace,
 	"arrow-left-right": ArrowLeftRight,
+	puzzle: Puzzle,
+	code: Code,
+	calculator: Calculator,
+	clock: Clock,
+	fingerprint: Fingerprint,
+	server: Server,
+	terminal: Terminal,
+	"sliders-horizontal": SlidersHorizontal,
+	sparkles: Sparkles,
+	eye: Eye,
+	brain: Brain,
 };
 
 const CATEGORY_ORDER = ["input", "processing", "output", "utility", "workflow"];
@@ -80,6 +114,7 @@ function NodeItem({ descriptor }: { descriptor: NodeDescriptor }) {
 		<button
 			type="button"
 			className="flex items-center gap-2.5 px-3 py-2 rounded-md cursor-grab border border-transparent hover:border-border/60 hover:bg-secondary/40 transition-colors group w-full text-left"
+			title={descriptor.description || undefined}
 			draggable
 			onDragStart={(e) => {
 				onDragStart(e, descriptor.node_type);
@@ -98,6 +133,11 @@ function NodeItem({ descriptor }: { descriptor: NodeDescriptor }) {
 					descriptor.display_name,
 				)}
 			</span>
+			{descriptor.experimental && (
+				<span className="shrink-0 rounded border border-amber-500/40 px-1 text-[9px] font-medium text-amber-500">
+					{t("palette.experimental")}
+				</span>
+			)}
 		</button>
 	);
 }
@@ -110,6 +150,7 @@ export function NodePalette() {
 	const categories = useMemo(() => {
 		const groups: Record<string, NodeDescriptor[]> = {};
 		for (const d of descriptors) {
+			if (d.deprecated) continue;
 			const cat = d.category;
 			if (!groups[cat]) groups[cat] = [];
 			groups[cat].push(d);
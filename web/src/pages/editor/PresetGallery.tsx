@@ -123,6 +123,44 @@ export function PresetGallery() {
 		[closeModal, loadWorkflow, t],
 	);
 
+	const renderPreset = (preset: Preset) => {
+		const meta =
+			preset.origin.kind === "community"
+				? [
+						preset.origin.version &&
+							t("gallery.presetVersion", { version: preset.origin.version }),
+						preset.origin.author &&
+							t("gallery.presetAuthor", { author: preset.origin.author }),
+					]
+						.filter(Boolean)
+						.join(" · ")
+				: "";
+		return (
+			<Card
+				key={preset.id}
+				className="cursor-pointer hover:bg-secondary/40 transition-colors"
+				onClick={() => {
+					handleLoadPreset(preset);
+				}}
+			>
+				<CardHeader className="p-4">
+					<CardTitle className="text-sm">{preset.name}</CardTitle>
+					{preset.description && (
+						<CardDescription className="text-xs">
+							{preset.description}
+						</CardDescription>
+					)}
+					{meta && (
+						<CardDescription className="text-xs">{meta}</CardDescription>
+					)}
+				</CardHeader>
+			</Card>
+		);
+	};
+
+	const localPresets = presets.filter((p) => p.origin.kind !== "community");
+	const communityPresets = presets.filter((p) => p.origin.kind === "community");
+
 	const handleDelete = useCallback(
 		async (filename: string, e: React.MouseEvent) => {
 			e.stopPropagation();
@@ -160,30 +198,22 @@ export function PresetGallery() {
 							<p className="text-xs font-medium text-muted-foreground uppercase tracking-wide px-1">
 								{t("gallery.sections.builtInPresets")}
 							</p>
-							{presets.map((preset) => (
-								<Card
-									key={preset.id}
-									className="cursor-pointer hover:bg-secondary/40 transition-colors"
-									onClick={() => {
-										handleLoadPreset(preset);
-									}}
-								>
-									<CardHeader className="p-4">
-										<CardTitle className="text-sm">{preset.name}</CardTitle>
-										{preset.description && (
-											<CardDescription className="text-xs">
-												{preset.description}
-											</CardDescription>
-										)}
-									</CardHeader>
-								</Card>
-							))}
-							{presets.length === 0 && (
+							{localPresets.map(renderPreset)}
+							{localPresets.length === 0 && (
 								<p className="text-sm text-muted-foreground text-center py-4">
 									{t("gallery.empty.noPresets")}
 								</p>
 							)}
 
+							{communityPresets.length > 0 && (
+								<>
+									<p className="text-xs font-medium text-muted-foreground uppercase tracking-wide px-1 mt-3">
+										{t("gallery.sections.communityPresets")}
+									</p>
+									{communityPresets.map(renderPreset)}
+								</>
+							)}
+
 							<p className="text-xs font-medium text-muted-foreground uppercase tracking-wide px-1 mt-3">
 								{t("gallery.sections.savedWorkflows")}
 							</p>
@@ -290,6 +290,19 @@ describe('NodePalette', () => {
     expect(screen.getByText('processing')).toBeInTheDocument();
   });
 
+  it('hides deprecated nodes and badges experimental ones', () => {
+    useNodeDefinitions.setState({
+      descriptors: [
+        { ...MOCK_DESCRIPTORS[0], experimental: true },
+        { ...MOCK_DESCRIPTORS[1], deprecated: true, replaced_by: 'VideoInput' },
+      ],
+    });
+    render(<NodePalette />);
+
+    expect(screen.getByText('Video Input').closest('button')).toHaveTextContent('Beta');
+    expect(screen.queryByText('Super Resolution')).not.toBeInTheDocument();
+  });
+
   it('does not show legacy count badges', () => {
     render(<NodePalette />);
     expect(screen.queryAllByText('2')).toHaveLength(0);
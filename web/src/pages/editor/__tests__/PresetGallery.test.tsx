@@ -66,6 +66,7 @@ const PRESET_FIXTURE: Preset = {
 			},
 		],
 	},
+	origin: { kind: "builtin" },
 };
 
 const WORKFLOW_FIXTURE: WorkflowEntry = {
@@ -136,6 +137,35 @@ describe("PresetGallery", () => {
 		expect(screen.getByText("Upscale anime by 2x")).toBeInTheDocument();
 	});
 
+	it("lists community presets in their own section with version and author", async () => {
+		useUIStore.setState({ activeModal: "presets" });
+		vi.mocked(listPresets).mockResolvedValue([
+			PRESET_FIXTURE,
+			{
+				...PRESET_FIXTURE,
+				id: "community/grain",
+				name: "Film Grain",
+				description: "",
+				origin: {
+					kind: "community",
+					source: "https://example.com/index.json",
+					version: "3",
+					author: "someone",
+					synced_at: "2026-01-01T00:00:00Z",
+				},
+			},
+		]);
+		vi.mocked(listWorkflows).mockResolvedValue([]);
+
+		render(<PresetGallery />);
+
+		await waitFor(() => {
+			expect(screen.getByText("Community Presets")).toBeInTheDocument();
+		});
+		expect(screen.getByText("Film Grain")).toBeInTheDocument();
+		expect(screen.getByText("v3 · by someone")).toBeInTheDocument();
+	});
+
 	it("shows workflow names and delete button", async () => {
 		useUIStore.setState({ activeModal: "presets" });
 		vi.mocked(listPresets).mockResolvedValue([]);
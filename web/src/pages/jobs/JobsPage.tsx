@@ -5,6 +5,7 @@ import {
 	Clock,
 	Gauge,
 	Loader2,
+	Pause,
 	Play,
 	RotateCcw,
 	Timer,
@@ -12,7 +13,7 @@ import {
 	X,
 } from "lucide-react";
 import type { ReactNode } from "react";
-import { useCallback, useEffect, useState } from "react";
+import { useCallback, useEffect, useRef, useState } from "react";
 import { useTranslation } from "react-i18next";
 import { PageContainer } from "@/components/layout/PageContainer";
 import { Badge } from "@/components/ui/badge";
@@ -25,7 +26,15 @@ import {
 	formatRelativeTime,
 } from "@/lib/presentation-format";
 import { useJobStore } from "@/stores/job-store";
-import type { Job, JobStatus, NodeRuntimePreview } from "@/types";
+import type {
+	EncodeStats,
+	HardwareUsage,
+	Job,
+	JobArtifact,
+	JobStatus,
+	NodeRuntimePreview,
+	StageProgress,
+} from "@/types";
 import { RunWorkflowDialog } from "./RunWorkflowDialog";
 import { formatDuration, formatETA } from "./time-utils";
 
@@ -37,6 +46,8 @@ const STATUS_STYLES: Record<JobStatus, string> = {
 	completed: "bg-green-500/20 text-green-400 border-green-500/30",
 	failed: "bg-red-500/20 text-red-400 border-red-500/30",
 	cancelled: "bg-gray-500/20 text-gray-400 border-gray-500/30",
+	skipped: "bg-teal-500/20 text-teal-400 border-teal-500/30",
+	paused: "bg-orange-500/20 text-orange-400 border-orange-500/30",
 };
 
 function getParamsSummary(
@@ -63,6 +74,66 @@ function getParamsJson(params: Job["params"]): string {
 	}
 }
 
+// One line per output file, e.g. "3840x2160 hevc · 700.0 MiB · 4.13 Mb/s".
+function getArtifactSummary(artifact: JobArtifact): string {
+	const parts: string[] = [];
+	if (artifact.width != null && artifact.height != null) {
+		parts.push(
+			[`${String(artifact.width)}x${String(artifact.height)}`, artifact.video_codec]
+				.filter(Boolean)
+				.join(" "),
+		);
+	} else if (artifact.video_codec) {
+		parts.push(artifact.video_codec);
+	}
+	if (artifact.size_bytes != null) {
+		parts.push(`${(artifact.size_bytes / 1024 ** 2).toFixed(1)} MiB`);
+	}
+	if (artifact.duration_secs != null) {
+		const total = Math.round(artifact.duration_secs);
+		parts.push(
+			[Math.floor(total / 3600), Math.floor((total % 3600) / 60), total % 60]
+				.map((value) => String(value).padStart(2, "0"))
+				.join(":"),
+		);
+	}
+	if (artifact.bit_rate != null) {
+		parts.push(`${(artifact.bit_rate / 1_000_000).toFixed(2)} Mb/s`);
+	}
+	return parts.join(" · ");
+}
+
+// e.g. "400.0 MiB · 2.67 Mb/s · 2.5x · VMAF 93.0".
+function getEncodeStatsSummary(stats: EncodeStats): string {
+	const parts: string[] = [];
+	if (stats.output_bytes != null) {
+		parts.push(`${(stats.output_bytes / 1024 ** 2).toFixed(1)} MiB`);
+	}
+	if (stats.average_bit_rate != null) {
+		parts.push(`${(stats.average_bit_rate / 1_000_000).toFixed(2)} Mb/s`);
+	}
+	if (stats.speed != null) {
+		parts.push(`${stats.speed.toFixed(2)}x`);
+	}
+	if (stats.vmaf != null) {
+		parts.push(`VMAF ${stats.vmaf.toFixed(1)}`);
+	}
+	return parts.join(" · ");
+}
+
+// Frames pile up in front of the slowest stage and every queue after it runs
+// dry, so the bottleneck is the last stage with a filled-up input queue. With
+// all queues near empty, decoding is what holds the pipeline back.
+function findBottleneck(stages: StageProgress[]): number {
+	for (let index = stages.length - 1; index > 0; index--) {
+		const stage = stages[index];
+		if (stage.queue_capacity > 0 && stage.queued * 2 >= stage.queue_capacity) {
+			return index;
+		}
+	}
+	return 0;
+}
+
 // ─── Active Job Card ──────────────────────────────────────────────────────────
 
 function ActiveJobCard({ job }: { job: Job }) {
@@ -83,6 +154,9 @@ function ActiveJobCard({ job }: { job: Job }) {
 				? formatETA(progress.eta_seconds)
 				: "00:00:00"
 			: "00:00:00";
+	const phase = progress?.phase ?? null;
+	const stages = progress?.stages ?? [];
+	const hardware = progress?.hardware ?? null;
 	const percentage =
 		totalFrames != null && totalFrames > 0
 			? Math.min(Math.round((currentFrame / totalFrames) * 100), 100)
@@ -172,9 +246,17 @@ function ActiveJobCard({ job }: { job: Job }) {
 					<StatItem
 						icon={<Activity className="h-3.5 w-3.5" />}
 						label={t("jobs.page.active.stats.status")}
-						value={t(`jobs.status.${job.status}`)}
+						value={
+							phase
+								? t(`jobs.page.active.phase.${phase}`)
+								: t(`jobs.status.${job.status}`)
+						}
+						testId="jobs-active-stat-status"
 					/>
 				</div>
+
+				{stages.length > 0 && <StageBreakdown stages={stages} />}
+				{hardware && <HardwareLoad hardware={hardware} />}
 			</CardContent>
 		</Card>
 	);
@@ -206,24 +288,121 @@ function StatItem({
 	);
 }
 
+function StageBreakdown({ stages }: { stages: StageProgress[] }) {
+	const { t } = useTranslation("jobs");
+	const bottleneck = findBottleneck(stages);
+
+	return (
+		<div className="space-y-1.5" data-testid="jobs-active-stages">
+			<p className="text-[10px] uppercase tracking-wider text-muted-foreground">
+				{t("jobs.page.active.stages.title")}
+			</p>
+			{stages.map((stage, index) => (
+				<div
+					key={`${String(index)}-${stage.name}`}
+					className="grid grid-cols-[minmax(0,1fr)_auto_6rem] items-center gap-3 text-xs"
+				>
+					<span
+						className={
+							index === bottleneck
+								? "truncate font-medium text-amber-400"
+								: "truncate text-muted-foreground"
+						}
+					>
+						{stage.name}
+						{index === bottleneck && ` · ${t("jobs.page.active.stages.bottleneck")}`}
+					</span>
+					<span className="font-mono text-muted-foreground">
+						{t("jobs.page.active.stages.frames", { count: stage.frames })}
+					</span>
+					{stage.queue_capacity > 0 ? (
+						<Progress
+							value={stage.queued}
+							max={stage.queue_capacity}
+							className="h-1.5"
+							title={t("jobs.page.active.stages.queued", {
+								queued: stage.queued,
+								capacity: stage.queue_capacity,
+							})}
+						/>
+					) : (
+						<span />
+					)}
+				</div>
+			))}
+		</div>
+	);
+}
+
+function HardwareLoad({ hardware }: { hardware: HardwareUsage }) {
+	const { t } = useTranslation("jobs");
+	const parts: string[] = [];
+	if (hardware.gpu_util_percent !== null) {
+		parts.push(
+			t("jobs.page.active.hardware.gpu", { value: hardware.gpu_util_percent.toFixed(0) }),
+		);
+	}
+	if (hardware.vram_used_bytes !== null) {
+		parts.push(
+			t("jobs.page.active.hardware.vram", {
+				value: (hardware.vram_used_bytes / 1024 ** 3).toFixed(1),
+			}),
+		);
+	}
+	if (hardware.cpu_util_percent !== null) {
+		parts.push(
+			t("jobs.page.active.hardware.cpu", { value: hardware.cpu_util_percent.toFixed(0) }),
+		);
+	}
+	if (parts.length === 0) {
+		return null;
+	}
+
+	return (
+		<p className="text-xs text-muted-foreground" data-testid="jobs-active-hardware">
+			{parts.join(" · ")}
+		</p>
+	);
+}
+
 // ─── Job History Row ──────────────────────────────────────────────────────────
 
 function JobRow({
 	job,
 	printRuntimePreviews,
+	focused = false,
 }: {
 	job: Job;
 	printRuntimePreviews: NodeRuntimePreview[];
+	/** Opened from a `/jobs?job=<id>` link, e.g. a desktop notification. */
+	focused?: boolean;
 }) {
 	const { t } = useTranslation("jobs");
-	const { rerunJob, deleteJobHistory } = useJobStore();
-	const [expanded, setExpanded] = useState(false);
+	const { rerunJob, deleteJobHistory, pauseJob, resumeJob } = useJobStore();
+	const [expanded, setExpanded] = useState(focused);
+	const rowRef = useRef<HTMLDivElement>(null);
 	const [retrying, setRetrying] = useState(false);
 	const [deleting, setDeleting] = useState(false);
+	const [toggling, setToggling] = useState(false);
 
 	const paramsSummary = getParamsSummary(job.params, t);
-	const isCompleted = job.status === "completed";
-	const actionsDisabled = retrying || deleting;
+	const actionsDisabled = retrying || deleting || toggling;
+	const pausable = job.status === "queued" || job.status === "paused";
+
+	const handleTogglePause = useCallback(async () => {
+		setToggling(true);
+		try {
+			if (job.status === "paused") {
+				await resumeJob(job.id);
+			} else {
+				await pauseJob(job.id);
+			}
+		} catch {
+			// store handles error state
+		} finally {
+			setToggling(false);
+		}
+	}, [job.id, job.status, pauseJob, resumeJob]);
 
 	const handleRetry = useCallback(async () => {
 		setRetrying(true);
@@ -247,8 +426,15 @@ function JobRow({
 		}
 	}, [deleteJobHistory, job.id]);
 
+	useEffect(() => {
+		if (focused) {
+			setExpanded(true);
+			rowRef.current?.scrollIntoView({ block: "nearest" });
+		}
+	}, [focused]);
+
 	return (
-		<div className="group">
+		<div className="group" ref={rowRef} data-focused={focused || undefined}>
 			<div className="flex w-full items-center px-4 py-2 text-sm transition-colors hover:bg-secondary/40">
 				<button
 					type="button"
@@ -302,8 +488,8 @@ function JobRow({
 					</span>
 				</button>
 
-				<div className="flex w-36 shrink-0 items-center justify-end gap-1 pl-3">
-					{!isCompleted && (
+				<div className="flex w-56 shrink-0 items-center justify-end gap-1 pl-3">
+					{pausable && (
 						<Button
 							type="button"
 							variant="outline"
@@ -311,17 +497,38 @@ function JobRow({
 							disabled={actionsDisabled}
 							onClick={(event) => {
 								event.stopPropagation();
-								void handleRetry();
+								void handleTogglePause();
 							}}
 						>
-							{retrying ? (
+							{toggling ? (
 								<Loader2 className="h-3.5 w-3.5 animate-spin" />
+							) : job.status === "paused" ? (
+								<Play className="h-3.5 w-3.5" />
 							) : (
-								<RotateCcw className="h-3.5 w-3.5" />
+								<Pause className="h-3.5 w-3.5" />
 							)}
-							{t("jobs.page.actions.retry")}
+							{job.status === "paused"
+								? t("jobs.page.actions.resume")
+								: t("jobs.page.actions.pause")}
 						</Button>
 					)}
+					<Button
+						type="button"
+						variant="outline"
+						size="sm"
+						disabled={actionsDisabled}
+						onClick={(event) => {
+							event.stopPropagation();
+							void handleRetry();
+						}}
+					>
+						{retrying ? (
+							<Loader2 className="h-3.5 w-3.5 animate-spin" />
+						) : (
+							<RotateCcw className="h-3.5 w-3.5" />
+						)}
+						{t("jobs.page.actions.retry")}
+					</Button>
 					<Button
 						type="button"
 						variant="outline"
@@ -420,6 +627,68 @@ function JobRow({
 								<span className="text-red-400">{job.error}</span>
 							</div>
 						)}
+						{job.error_details && (
+							<div className="col-span-2">
+								<span className="text-muted-foreground">
+									{t("jobs.page.history.details.errorNode")}
+								</span>{" "}
+								<span>
+									{[job.error_details.node_id, job.error_details.node_type]
+										.filter(Boolean)
+										.join(" · ") || "—"}{" "}
+									(
+									{t(
+										`jobs.page.history.details.errorCategory.${job.error_details.category}`,
+									)}
+									)
+								</span>
+								{job.error_details.hint && (
+									<p className="mt-1">
+										<span className="text-muted-foreground">
+											{t("jobs.page.history.details.errorHint")}
+										</span>{" "}
+										<span className="text-amber-400">
+											{job.error_details.hint}
+										</span>
+									</p>
+								)}
+							</div>
+						)}
+						{job.skip_reason && (
+							<div className="col-span-2">
+								<span className="text-muted-foreground">
+									{t("jobs.page.history.details.skipReason")}
+								</span>{" "}
+								<span className="text-teal-400">{job.skip_reason}</span>
+							</div>
+						)}
+						{job.encode_stats && (
+							<div className="col-span-2">
+								<span className="text-muted-foreground">
+									{t("jobs.page.history.details.encodeStats")}
+								</span>{" "}
+								<span>{getEncodeStatsSummary(job.encode_stats)}</span>
+							</div>
+						)}
+						{job.artifacts && job.artifacts.length > 0 && (
+							<div className="col-span-2">
+								<p className="text-muted-foreground">
+									{t("jobs.page.history.details.artifacts")}
+								</p>
+								<ul className="mt-1 space-y-1">
+									{job.artifacts.map((artifact) => (
+										<li key={`${artifact.node_id}:${artifact.path}`}>
+											<span className="font-mono break-all">
+												{artifact.path}
+											</span>{" "}
+											<span className="text-muted-foreground">
+												{getArtifactSummary(artifact)}
+											</span>
+										</li>
+									))}
+								</ul>
+							</div>
+						)}
 						{printRuntimePreviews.length > 0 && (
 							<div className="col-span-2 mt-2 space-y-2">
 								<p className="text-muted-foreground">Print output</p>
@@ -480,7 +749,16 @@ export function JobsPage() {
 		unsubscribeFromJob,
 		runtimePreviewsByJobId,
 	} = useJobStore();
-	const [runDialogOpen, setRunDialogOpen] = useState(false);
+	const [focusedJobId] = useState(() =>
+		new URLSearchParams(window.location.search).get("job"),
+	);
+	// `/jobs?input=<path>` opens the run dialog on those files.
+	const [inputFiles, setInputFiles] = useState(() =>
+		new URLSearchParams(window.location.search).getAll("input"),
+	);
+	const [runDialogOpen, setRunDialogOpen] = useState(
+		() => inputFiles.length > 0,
+	);
 
 	useEffect(() => {
 		void fetchJobs();
@@ -594,7 +872,7 @@ export function JobsPage() {
 								<span className="min-w-0 flex-1">
 									{t("jobs.page.history.columns.error")}
 								</span>
-								<span className="w-36 shrink-0 text-right">
+								<span className="w-56 shrink-0 text-right">
 									{t("jobs.page.history.columns.actions")}
 								</span>
 							</div>
@@ -608,6 +886,7 @@ export function JobsPage() {
 										key={job.id}
 										job={job}
 										printRuntimePreviews={getPrintRuntimePreviews(job.id)}
+										focused={job.id === focusedJobId}
 									/>
 								))}
 							</div>
@@ -617,7 +896,13 @@ export function JobsPage() {
 			)}
 			<RunWorkflowDialog
 				open={runDialogOpen}
-				onOpenChange={setRunDialogOpen}
+				onOpenChange={(open) => {
+					setRunDialogOpen(open);
+					if (!open) {
+						setInputFiles([]);
+					}
+				}}
+				inputFiles={inputFiles}
 				onSubmitted={() => {
 					void fetchJobs();
 				}}
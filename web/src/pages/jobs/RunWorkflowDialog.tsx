@@ -27,11 +27,19 @@ import {
 	DialogTitle,
 } from "@/components/ui/dialog";
 import { formatErrorWithPrefix } from "@/lib/presentation-error";
+import type { Workflow, WorkflowPort } from "@/types";
 
 interface RunWorkflowDialogProps {
 	open: boolean;
 	onOpenChange: (open: boolean) => void;
 	onSubmitted: () => void;
+	/** Files to run, e.g. passed to the desktop app; one job per file. */
+	inputFiles?: string[];
+}
+
+// The first Path input receives the files handed to the dialog.
+function findFileInput(inputs: WorkflowPort[]): WorkflowPort | undefined {
+	return inputs.find((port) => port.port_type === "Path");
 }
 
 // ─── Component ──────────────────────────────────────────────────────────────
@@ -40,6 +48,7 @@ export function RunWorkflowDialog({
 	open,
 	onOpenChange,
 	onSubmitted,
+	inputFiles = [],
 }: RunWorkflowDialogProps) {
 	const { t } = useTranslation("jobs");
 	const [workflows, setWorkflows] = useState<WorkflowEntry[]>([]);
@@ -88,11 +97,19 @@ export function RunWorkflowDialog({
 		}
 	}, [open]);
 
-	const handleSelect = useCallback((entry: WorkflowEntry) => {
-		setSelectedWorkflow(entry);
-		const inputs = entry.workflow.interface?.inputs ?? [];
-		setParamValues(buildDefaults(inputs));
-	}, []);
+	const handleSelect = useCallback(
+		(entry: WorkflowEntry) => {
+			setSelectedWorkflow(entry);
+			const inputs = entry.workflow.interface?.inputs ?? [];
+			const defaults = buildDefaults(inputs);
+			const fileInput = findFileInput(inputs);
+			if (fileInput && inputFiles.length > 0) {
+				defaults[fileInput.name] = inputFiles[0];
+			}
+			setParamValues(defaults);
+		},
+		[inputFiles],
+	);
 
 	const handleParamChange = useCallback((name: string, value: ParamValue) => {
 		setParamValues((prev) => ({ ...prev, [name]: value }));
@@ -103,7 +120,9 @@ export function RunWorkflowDialog({
 		setSubmitting(true);
 		try {
 			const inputs = selectedWorkflow.workflow.interface?.inputs ?? [];
-			const modifiedWorkflow = {
+			const withParams = (
+				values: Record<string, ParamValue>,
+			): Workflow => ({
 				...selectedWorkflow.workflow,
 				nodes: selectedWorkflow.workflow.nodes.map((node) => {
 					if (node.node_type === "WorkflowInput") {
@@ -116,7 +135,7 @@ export function RunWorkflowDialog({
 										port.name,
 										convertParam(
 											port,
-											paramValues[port.name] ?? getDefaultValue(port),
+											values[port.name] ?? getDefaultValue(port),
 										),
 									]),
 								),
@@ -125,11 +144,29 @@ export function RunWorkflowDialog({
 					}
 					return node;
 				}),
-			};
-			await submitJob(modifiedWorkflow, {
-				workflowName: selectedWorkflow.name,
 			});
-			toast.success(t("jobs.dialog.success.submitted"));
+			// The first file is already in the form; the rest reuse its other
+			// values.
+			const fileInput = findFileInput(inputs);
+			const runs =
+				fileInput && inputFiles.length > 1
+					? [
+							paramValues,
+							...inputFiles
+								.slice(1)
+								.map((file) => ({ ...paramValues, [fileInput.name]: file })),
+						]
+					: [paramValues];
+			for (const values of runs) {
+				await submitJob(withParams(values), {
+					workflowName: selectedWorkflow.name,
+				});
+			}
+			toast.success(
+				runs.length > 1
+					? t("jobs.dialog.success.submittedMany", { count: runs.length })
+					: t("jobs.dialog.success.submitted"),
+			);
 			onOpenChange(false);
 			onSubmitted();
 		} catch (err: unknown) {
@@ -139,7 +176,7 @@ export function RunWorkflowDialog({
 		} finally {
 			setSubmitting(false);
 		}
-	}, [selectedWorkflow, paramValues, onOpenChange, onSubmitted, t]);
+	}, [selectedWorkflow, paramValues, inputFiles, onOpenChange, onSubmitted, t]);
 
 	const inputs = selectedWorkflow?.workflow.interface?.inputs ?? [];
 
@@ -216,6 +253,13 @@ export function RunWorkflowDialog({
 								<p className="text-xs font-medium text-muted-foreground uppercase tracking-wider">
 									{t("jobs.dialog.parameters.title")}
 								</p>
+								{inputFiles.length > 1 && findFileInput(inputs) && (
+									<p className="text-xs text-muted-foreground">
+										{t("jobs.dialog.parameters.moreFiles", {
+											count: inputFiles.length - 1,
+										})}
+									</p>
+								)}
 								<div className="space-y-3">
 									{inputs.map((port) => (
 										<PortField
@@ -247,7 +247,7 @@ describe("JobsPage print debug visibility", () => {
 });
 
 describe("JobsPage history actions", () => {
-	it("shows delete for completed rows and hides retry", () => {
+	it("shows retry and delete for completed rows", () => {
 		useJobStore.setState({
 			jobs: [makeJob({ id: "completed-job", status: "completed" })],
 		});
@@ -255,7 +255,7 @@ describe("JobsPage history actions", () => {
 		render(<JobsPage />);
 
 		expect(screen.getByRole("button", { name: "Delete" })).toBeInTheDocument();
-		expect(screen.queryByRole("button", { name: "Retry" })).not.toBeInTheDocument();
+		expect(screen.getByRole("button", { name: "Retry" })).toBeInTheDocument();
 	});
 
 	it("shows retry for non-completed rows", () => {
@@ -327,6 +327,33 @@ describe("JobsPage history actions", () => {
 		});
 	});
 
+	it("expands the job named in the job query parameter", async () => {
+		const scrollIntoView = vi.fn();
+		Element.prototype.scrollIntoView = scrollIntoView;
+		window.history.replaceState(null, "", "/jobs?job=linked-job");
+		useJobStore.setState({
+			jobs: [
+				makeJob({
+					id: "linked-job",
+					workflow_source: "api_run_presets",
+				}),
+				makeJob({ id: "other-job", workflow_source: "api_batch" }),
+			],
+		});
+
+		try {
+			render(<JobsPage />);
+
+			await waitFor(() => {
+				expect(screen.getByText("api_run_presets")).toBeInTheDocument();
+			});
+			expect(screen.queryByText("api_batch")).not.toBeInTheDocument();
+			expect(scrollIntoView).toHaveBeenCalledTimes(1);
+		} finally {
+			window.history.replaceState(null, "", "/");
+		}
+	});
+
 	it("renders safe params summary for null params", () => {
 		useJobStore.setState({
 			jobs: [makeJob({ id: "null-params-job", status: "completed", params: null })],
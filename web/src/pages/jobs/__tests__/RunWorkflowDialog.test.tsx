@@ -206,6 +206,63 @@ describe("RunWorkflowDialog", () => {
 		expect(onSubmitted).toHaveBeenCalled();
 	});
 
+	it("runs one job per input file in the first Path input", async () => {
+		vi.mocked(listWorkflows).mockResolvedValue([
+			makeEntry({
+				filename: "upscale.json",
+				name: "Upscale",
+				workflow: {
+					nodes: [{ id: "wi", node_type: "WorkflowInput", params: {} }],
+					connections: [],
+					interface: {
+						inputs: [
+							{ name: "scale", port_type: "Int", default_value: 2 },
+							{ name: "input", port_type: "Path" },
+						],
+						outputs: [],
+					},
+				},
+			}),
+		]);
+		vi.mocked(submitJob).mockResolvedValue({
+			id: "job-1",
+			status: "queued",
+			created_at: new Date().toISOString(),
+		});
+
+		render(
+			<RunWorkflowDialog
+				open={true}
+				onOpenChange={vi.fn()}
+				onSubmitted={vi.fn()}
+				inputFiles={["/media/ep01.mkv", "/media/ep02.mkv"]}
+			/>,
+		);
+
+		await waitFor(() => {
+			expect(screen.getByText("Upscale")).toBeInTheDocument();
+		});
+		fireEvent.click(screen.getByText("Upscale"));
+
+		expect(screen.getByDisplayValue("/media/ep01.mkv")).toBeInTheDocument();
+		expect(
+			screen.getByText("1 more file will run with the same parameters."),
+		).toBeInTheDocument();
+
+		fireEvent.click(screen.getByRole("button", { name: /Run/i }));
+
+		await waitFor(() => {
+			expect(submitJob).toHaveBeenCalledTimes(2);
+		});
+		const inputsOf = (call: number) =>
+			vi
+				.mocked(submitJob)
+				.mock.calls[call][0].nodes.find((n) => n.node_type === "WorkflowInput")
+				?.params;
+		expect(inputsOf(0)).toEqual({ scale: 2, input: "/media/ep01.mkv" });
+		expect(inputsOf(1)).toEqual({ scale: 2, input: "/media/ep02.mkv" });
+	});
+
 	it("shows loading spinner while fetching", async () => {
 		vi.mocked(listWorkflows).mockReturnValue(new Promise(() => {}));
 
@@ -8,12 +8,14 @@ import {
 	ReactFlow,
 	ReactFlowProvider,
 } from "@xyflow/react";
-import { Loader2 } from "lucide-react";
+import { Loader2, Pencil, Trash2 } from "lucide-react";
 import { useCallback, useEffect, useMemo, useState } from "react";
 import { useTranslation } from "react-i18next";
 import type { ModelEntry, ModelInspection } from "@/api/client";
-import { inspectModel } from "@/api/client";
+import { deleteModel, inspectModel, renameModel } from "@/api/client";
+import { toast } from "@/components/shared/Toaster";
 import { Badge } from "@/components/ui/badge";
+import { Button } from "@/components/ui/button";
 import {
 	Dialog,
 	DialogContent,
@@ -21,6 +23,7 @@ import {
 	DialogHeader,
 	DialogTitle,
 } from "@/components/ui/dialog";
+import { Input } from "@/components/ui/input";
 import { Tabs, TabsContent, TabsList, TabsTrigger } from "@/components/ui/tabs";
 import {
 	formatErrorWithPrefix,
@@ -227,6 +230,24 @@ function MetadataSection({
 	if (inspection.domain) {
 		fields.push([t("detail.metadata.domain"), inspection.domain]);
 	}
+	if (model.architecture) {
+		fields.push([t("detail.metadata.architecture"), model.architecture]);
+	}
+	if (model.training_domain) {
+		fields.push([t("detail.metadata.trainingDomain"), model.training_domain]);
+	}
+	if (model.license) {
+		fields.push([t("detail.metadata.license"), model.license]);
+	}
+	if (model.recommended_tile_size != null) {
+		fields.push([
+			t("detail.metadata.recommendedTileSize"),
+			String(model.recommended_tile_size),
+		]);
+	}
+	for (const [key, value] of Object.entries(inspection.metadata ?? {})) {
+		fields.push([key, value]);
+	}
 
 	return (
 		<div className="space-y-3">
@@ -240,6 +261,16 @@ function MetadataSection({
 					</div>
 				))}
 			</div>
+			{model.source_url && (
+				<a
+					href={model.source_url}
+					target="_blank"
+					rel="noreferrer"
+					className="block text-xs text-primary hover:underline truncate"
+				>
+					{model.source_url}
+				</a>
+			)}
 			{inspection.doc_string && (
 				<p className="text-xs text-muted-foreground border-t border-border pt-2 mt-2">
 					{inspection.doc_string}
@@ -419,15 +450,116 @@ function GraphSection({ inspection }: { inspection: ModelInspection }) {
 	);
 }
 
+// ─── Manage section ───────────────────────────────────────────────────────────
+
+function ManageSection({
+	model,
+	onChanged,
+}: {
+	model: ModelEntry;
+	onChanged: (renamed: ModelEntry | null) => void;
+}) {
+	const { t } = useTranslation("models");
+	const [filename, setFilename] = useState(model.filename);
+	const [confirmingDelete, setConfirmingDelete] = useState(false);
+	const [busy, setBusy] = useState(false);
+
+	const trimmed = filename.trim();
+
+	const handleRename = async () => {
+		setBusy(true);
+		try {
+			const renamed = await renameModel(model.filename, trimmed);
+			toast.success(t("detail.manage.renamed", { filename: renamed.filename }));
+			onChanged(renamed);
+		} catch (err: unknown) {
+			toast.error(
+				formatErrorWithPrefix(
+					t("detail.manage.renameFailed"),
+					getErrorMessage(err),
+				),
+			);
+		} finally {
+			setBusy(false);
+		}
+	};
+
+	const handleDelete = async () => {
+		if (!confirmingDelete) {
+			setConfirmingDelete(true);
+			return;
+		}
+		setBusy(true);
+		try {
+			await deleteModel(model.filename);
+			toast.success(t("detail.manage.deleted", { filename: model.filename }));
+			onChanged(null);
+		} catch (err: unknown) {
+			toast.error(
+				formatErrorWithPrefix(
+					t("detail.manage.deleteFailed"),
+					getErrorMessage(err),
+				),
+			);
+		} finally {
+			setBusy(false);
+			setConfirmingDelete(false);
+		}
+	};
+
+	return (
+		<div className="mt-4 space-y-2 border-t border-border/50 pt-4">
+			<h4 className="text-xs font-medium text-muted-foreground uppercase tracking-wider">
+				{t("detail.manage.title")}
+			</h4>
+			<div className="flex items-center gap-2">
+				<Input
+					aria-label={t("detail.manage.filenameLabel")}
+					value={filename}
+					onChange={(e) => setFilename(e.target.value)}
+					className="font-mono text-xs"
+				/>
+				<Button
+					variant="outline"
+					size="sm"
+					disabled={busy || !trimmed || trimmed === model.filename}
+					onClick={() => void handleRename()}
+				>
+					<Pencil className="size-3.5 mr-1" />
+					{t("detail.manage.rename")}
+				</Button>
+				<Button
+					variant="destructive"
+					size="sm"
+					disabled={busy}
+					onClick={() => void handleDelete()}
+				>
+					<Trash2 className="size-3.5 mr-1" />
+					{confirmingDelete
+						? t("detail.manage.confirmDelete")
+						: t("detail.manage.delete")}
+				</Button>
+			</div>
+		</div>
+	);
+}
+
 // ─── Main dialog ──────────────────────────────────────────────────────────────
 
 interface ModelDetailProps {
 	model: ModelEntry | null;
 	open: boolean;
 	onOpenChange: (open: boolean) => void;
+	/** Set when the model's file is in a model directory and can be managed. */
+	onChanged?: (renamed: ModelEntry | null) => void;
 }
 
-export function ModelDetail({ model, open, onOpenChange }: ModelDetailProps) {
+export function ModelDetail({
+	model,
+	open,
+	onOpenChange,
+	onChanged,
+}: ModelDetailProps) {
 	const { t } = useTranslation("models");
 	const [inspection, setInspection] = useState<ModelInspection | null>(null);
 	const [errorDetail, setErrorDetail] = useState<string | null>(null);
@@ -531,6 +663,14 @@ export function ModelDetail({ model, open, onOpenChange }: ModelDetailProps) {
 						</TabsContent>
 					</Tabs>
 				)}
+
+				{model && onChanged && (
+					<ManageSection
+						key={model.filename}
+						model={model}
+						onChanged={onChanged}
+					/>
+				)}
 			</DialogContent>
 		</Dialog>
 	);
@@ -1,9 +1,22 @@
-import { Layers, LayoutGrid, List, Search, Sparkles } from "lucide-react";
-import { useEffect, useMemo, useState } from "react";
+import {
+	Layers,
+	LayoutGrid,
+	List,
+	Loader2,
+	Search,
+	Sparkles,
+	Upload,
+} from "lucide-react";
+import { useEffect, useMemo, useRef, useState } from "react";
 import { useTranslation } from "react-i18next";
-import type { ModelEntry, ModelType } from "@/api/client";
-import { listModels } from "@/api/client";
+import type { ModelDiskUsage, ModelEntry, ModelType } from "@/api/client";
+import {
+	getModelDiskUsage,
+	listModels,
+	uploadModelFile,
+} from "@/api/client";
 import { PageContainer } from "@/components/layout/PageContainer";
+import { toast } from "@/components/shared/Toaster";
 import { Badge } from "@/components/ui/badge";
 import { Button } from "@/components/ui/button";
 import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
@@ -14,7 +27,10 @@ import {
 	TooltipProvider,
 	TooltipTrigger,
 } from "@/components/ui/tooltip";
-import { getErrorMessage } from "@/lib/presentation-error";
+import {
+	formatErrorWithPrefix,
+	getErrorMessage,
+} from "@/lib/presentation-error";
 import { ModelDetail } from "./ModelDetail";
 
 // ─── Constants ───────────────────────────────────────────────────────────────
@@ -41,6 +57,13 @@ const TYPE_FILTERS: {
 
 const FALLBACK_MODEL_LOAD_ERROR = "Failed to load models";
 
+function formatSize(bytes: number): string {
+	const gib = bytes / 1024 ** 3;
+	return gib >= 1
+		? `${gib.toFixed(1)} GiB`
+		: `${(bytes / 1024 ** 2).toFixed(1)} MiB`;
+}
+
 // ─── Badge styles ────────────────────────────────────────────────────────────
 
 function typeBadgeClass(type: ModelType): string {
@@ -128,6 +151,12 @@ function ModelCard({
 							FP16
 						</Badge>
 					)}
+					{model.architecture && (
+						<Badge variant="outline">{model.architecture}</Badge>
+					)}
+					{model.training_domain && (
+						<Badge variant="outline">{model.training_domain}</Badge>
+					)}
 				</div>
 			</CardHeader>
 			<CardContent className="space-y-2 text-xs">
@@ -161,6 +190,11 @@ function ModelCard({
 				<p className="text-muted-foreground/70">
 					{t("card.inputFormat", { format: model.input_format })}
 				</p>
+				{model.license && (
+					<p className="text-muted-foreground/70">
+						{t("card.license", { license: model.license })}
+					</p>
+				)}
 			</CardContent>
 		</Card>
 	);
@@ -255,6 +289,10 @@ export function ModelsPage() {
 	const [view, setView] = useState<"grid" | "list">("grid");
 	const [selectedModel, setSelectedModel] = useState<ModelEntry | null>(null);
 	const [detailOpen, setDetailOpen] = useState(false);
+	const [usage, setUsage] = useState<ModelDiskUsage | null>(null);
+	const [reloadKey, setReloadKey] = useState(0);
+	const [uploadPercent, setUploadPercent] = useState<number | null>(null);
+	const uploadInputRef = useRef<HTMLInputElement>(null);
 
 	useEffect(() => {
 		let cancelled = false;
@@ -273,10 +311,50 @@ export function ModelsPage() {
 			.finally(() => {
 				if (!cancelled) setLoading(false);
 			});
+		// Usage is extra; the page works without it.
+		getModelDiskUsage()
+			.then((data) => {
+				if (!cancelled) setUsage(data);
+			})
+			.catch(() => {
+				if (!cancelled) setUsage(null);
+			});
 		return () => {
 			cancelled = true;
 		};
-	}, []);
+	}, [reloadKey]);
+
+	const selectedOnDisk =
+		selectedModel !== null &&
+		(usage?.models.some((f) => f.filename === selectedModel.filename) ?? false);
+
+	const handleModelChanged = (renamed: ModelEntry | null) => {
+		if (renamed) {
+			setSelectedModel(renamed);
+		} else {
+			setDetailOpen(false);
+		}
+		setReloadKey((key) => key + 1);
+	};
+
+	const availableBytes = usage?.dirs[0]?.available_bytes ?? null;
+
+	const handleUpload = async (file: File) => {
+		setUploadPercent(0);
+		try {
+			await uploadModelFile(file, (received, total) => {
+				setUploadPercent(Math.floor((received / total) * 100));
+			});
+			toast.success(t("upload.done", { filename: file.name }));
+			setReloadKey((key) => key + 1);
+		} catch (err: unknown) {
+			toast.error(
+				formatErrorWithPrefix(t("upload.failed"), getErrorMessage(err)),
+			);
+		} finally {
+			setUploadPercent(null);
+		}
+	};
 
 	const filtered = useMemo(() => {
 		const q = searchQuery.toLowerCase();
@@ -309,6 +387,19 @@ export function ModelsPage() {
 					{t("page.title")}
 				</h2>
 				<p className="text-sm text-muted-foreground mt-0.5">{subtitle}</p>
+				{usage && (
+					<p
+						className="text-xs text-muted-foreground mt-0.5"
+						data-testid="model-usage"
+					>
+						{t("usage.summary", {
+							count: usage.models.length,
+							size: formatSize(usage.total_bytes),
+						})}
+						{availableBytes !== null &&
+							` · ${t("usage.free", { size: formatSize(availableBytes) })}`}
+					</p>
+				)}
 			</div>
 
 			{/* Filter bar */}
@@ -344,8 +435,36 @@ export function ModelsPage() {
 					))}
 				</div>
 
-				{/* View toggle */}
+				{/* Upload and view toggle */}
 				<div className="flex items-center gap-1 sm:ml-auto">
+					<input
+						ref={uploadInputRef}
+						type="file"
+						accept=".onnx"
+						className="hidden"
+						data-testid="model-upload-input"
+						onChange={(e) => {
+							const file = e.target.files?.[0];
+							e.target.value = "";
+							if (file) void handleUpload(file);
+						}}
+					/>
+					<Button
+						variant="outline"
+						size="sm"
+						disabled={uploadPercent !== null}
+						onClick={() => uploadInputRef.current?.click()}
+						className="mr-2"
+					>
+						{uploadPercent !== null ? (
+							<Loader2 className="size-3.5 mr-1 animate-spin" />
+						) : (
+							<Upload className="size-3.5 mr-1" />
+						)}
+						{uploadPercent !== null
+							? t("upload.progress", { percent: uploadPercent })
+							: t("upload.button")}
+					</Button>
 					<Button
 						variant={view === "grid" ? "secondary" : "ghost"}
 						size="icon"
@@ -427,6 +546,7 @@ export function ModelsPage() {
 				model={selectedModel}
 				open={detailOpen}
 				onOpenChange={setDetailOpen}
+				onChanged={selectedOnDisk ? handleModelChanged : undefined}
 			/>
 		</PageContainer>
 	);
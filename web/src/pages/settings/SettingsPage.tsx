@@ -1,15 +1,18 @@
 import {
 	Check,
+	Download,
 	FolderOpen,
 	Loader2,
 	Lock,
+	Power,
+	RefreshCw,
 	RotateCcw,
 	Save,
 	Server,
 } from "lucide-react";
 import { useCallback, useEffect, useMemo, useState } from "react";
 import { useTranslation } from "react-i18next";
-import { getConfig, updateConfig } from "@/api/client";
+import { checkForUpdates, getConfig, updateConfig } from "@/api/client";
 import { PageContainer } from "@/components/layout/PageContainer";
 import { toast } from "@/components/shared/Toaster";
 import { Badge } from "@/components/ui/badge";
@@ -22,7 +25,36 @@ import {
 	CardTitle,
 } from "@/components/ui/card";
 import { Input } from "@/components/ui/input";
-import type { AppConfig } from "@/types";
+import {
+	Select,
+	SelectContent,
+	SelectItem,
+	SelectTrigger,
+	SelectValue,
+} from "@/components/ui/select";
+import { installDesktopUpdate, isDesktopRuntime } from "@/lib/runtime-desktop";
+import type {
+	AppConfig,
+	PowerSettings,
+	QueueEmptyAction,
+	UpdateChannel,
+	UpdateCheck,
+	UpdateSettings,
+} from "@/types";
+
+const QUEUE_EMPTY_ACTIONS: QueueEmptyAction[] = ["none", "suspend", "shutdown"];
+
+const DEFAULT_POWER_SETTINGS: PowerSettings = {
+	prevent_sleep: true,
+	when_queue_empty: "none",
+};
+
+const UPDATE_CHANNELS: UpdateChannel[] = ["stable", "beta"];
+
+const DEFAULT_UPDATE_SETTINGS: UpdateSettings = {
+	channel: "stable",
+	releases_url: "https://api.github.com/repos/ControlNet/videnoa/releases",
+};
 
 // ─── Field helpers ───────────────────────────────────────────────────────────
 
@@ -50,13 +82,21 @@ export function SettingsPage() {
 	const [saving, setSaving] = useState(false);
 	const [error, setError] = useState<string | null>(null);
 	const [saveSuccess, setSaveSuccess] = useState(false);
+	const [updateCheck, setUpdateCheck] = useState<UpdateCheck | null>(null);
+	const [checkingUpdates, setCheckingUpdates] = useState(false);
+	const [installingUpdate, setInstallingUpdate] = useState(false);
+	const [updateError, setUpdateError] = useState<string | null>(null);
+	const isDesktop = useMemo(() => isDesktopRuntime(), []);
 
 	const normalizeConfig = useCallback((data: AppConfig): AppConfig => {
 		return {
 			...data,
 			performance: {
+				...data.performance,
 				profiling_enabled: data.performance?.profiling_enabled ?? false,
 			},
+			power: { ...DEFAULT_POWER_SETTINGS, ...data.power },
+			updates: { ...DEFAULT_UPDATE_SETTINGS, ...data.updates },
 		};
 	}, []);
 
@@ -120,6 +160,42 @@ export function SettingsPage() {
 		}
 	}, [normalizeConfig, t]);
 
+	const updateChannel = formState?.updates?.channel ?? "stable";
+
+	const handleCheckUpdates = useCallback(async () => {
+		setCheckingUpdates(true);
+		setUpdateError(null);
+		try {
+			setUpdateCheck(await checkForUpdates(updateChannel));
+		} catch (err) {
+			setUpdateCheck(null);
+			setUpdateError(
+				err instanceof Error ? err.message : t("errors.checkUpdates"),
+			);
+		} finally {
+			setCheckingUpdates(false);
+		}
+	}, [t, updateChannel]);
+
+	const handleInstallUpdate = useCallback(async () => {
+		setInstallingUpdate(true);
+		setUpdateError(null);
+		try {
+			// The app restarts into the new version when this succeeds.
+			await installDesktopUpdate(updateChannel);
+		} catch (err) {
+			setUpdateError(
+				err instanceof Error
+					? err.message
+					: typeof err === "string"
+						? err
+						: t("errors.installUpdate"),
+			);
+		} finally {
+			setInstallingUpdate(false);
+		}
+	}, [t, updateChannel]);
+
 	// Updater helpers
 	function updatePaths<K extends keyof AppConfig["paths"]>(
 		key: K,
@@ -144,6 +220,35 @@ export function SettingsPage() {
 		);
 	}
 
+	function updatePower<K extends keyof PowerSettings>(
+		key: K,
+		value: PowerSettings[K],
+	) {
+		setFormState((prev) =>
+			prev
+				? {
+						...prev,
+						power: { ...DEFAULT_POWER_SETTINGS, ...prev.power, [key]: value },
+				  }
+				: prev,
+		);
+	}
+
+	function updateUpdates<K extends keyof UpdateSettings>(
+		key: K,
+		value: UpdateSettings[K],
+	) {
+		setUpdateCheck(null);
+		setFormState((prev) =>
+			prev
+				? {
+						...prev,
+						updates: { ...DEFAULT_UPDATE_SETTINGS, ...prev.updates, [key]: value },
+				  }
+				: prev,
+		);
+	}
+
 	// ─── Loading state ───────────────────────────────────────────────────────────
 
 	if (loading || !formState) {
@@ -309,6 +414,206 @@ export function SettingsPage() {
 						<p className="text-xs text-muted-foreground">
 							{t("sections.performance.fields.profilingEnabledHint")}
 						</p>
+						<div className="grid grid-cols-2 gap-3 pt-2">
+							<div className="space-y-2">
+								<FieldLabel htmlFor="performance-sample-interval">
+									{t("sections.performance.fields.sampleIntervalSecs")}
+								</FieldLabel>
+								<Input
+									id="performance-sample-interval"
+									type="number"
+									min={1}
+									value={formState.performance.sample_interval_secs ?? 5}
+									onChange={(event) =>
+										updatePerformance(
+											"sample_interval_secs",
+											Math.max(1, Number(event.target.value) || 1),
+										)
+									}
+									className="font-mono text-xs"
+								/>
+							</div>
+							<div className="space-y-2">
+								<FieldLabel htmlFor="performance-series-retention">
+									{t("sections.performance.fields.seriesRetentionHours")}
+								</FieldLabel>
+								<Input
+									id="performance-series-retention"
+									type="number"
+									min={0}
+									value={formState.performance.series_retention_hours ?? 24}
+									onChange={(event) =>
+										updatePerformance(
+											"series_retention_hours",
+											Math.max(0, Number(event.target.value) || 0),
+										)
+									}
+									className="font-mono text-xs"
+								/>
+							</div>
+						</div>
+						<p className="text-xs text-muted-foreground">
+							{t("sections.performance.fields.seriesRetentionHint")}
+						</p>
+					</CardContent>
+				</Card>
+
+				{/* ── Power Section ──────────────────────────────────────────────────── */}
+				<Card>
+					<CardHeader>
+						<div className="flex items-center gap-2">
+							<Power className="h-4 w-4 text-muted-foreground" />
+							<CardTitle className="text-base">
+								{t("sections.power.title")}
+							</CardTitle>
+						</div>
+						<CardDescription>{t("sections.power.description")}</CardDescription>
+					</CardHeader>
+					<CardContent className="space-y-2">
+						<FieldLabel htmlFor="power-prevent-sleep">
+							{t("sections.power.fields.preventSleep")}
+						</FieldLabel>
+						<label
+							htmlFor="power-prevent-sleep"
+							className="flex items-center gap-3 rounded-md border border-border/60 bg-background/50 px-3 py-2"
+						>
+							<input
+								id="power-prevent-sleep"
+								type="checkbox"
+								checked={formState.power?.prevent_sleep ?? true}
+								onChange={(event) =>
+									updatePower("prevent_sleep", event.target.checked)
+								}
+								className="h-4 w-4 rounded border-border"
+							/>
+							<span className="text-sm text-foreground">
+								{(formState.power?.prevent_sleep ?? true)
+									? t("sections.power.fields.enabled")
+									: t("sections.power.fields.disabled")}
+							</span>
+						</label>
+						<div className="space-y-2 pt-2">
+							<FieldLabel htmlFor="power-when-queue-empty">
+								{t("sections.power.fields.whenQueueEmpty")}
+							</FieldLabel>
+							<Select
+								value={formState.power?.when_queue_empty ?? "none"}
+								onValueChange={(value) =>
+									updatePower("when_queue_empty", value as QueueEmptyAction)
+								}
+							>
+								<SelectTrigger id="power-when-queue-empty" className="text-xs">
+									<SelectValue />
+								</SelectTrigger>
+								<SelectContent>
+									{QUEUE_EMPTY_ACTIONS.map((action) => (
+										<SelectItem key={action} value={action} className="text-xs">
+											{t(`sections.power.queueEmptyActions.${action}`)}
+										</SelectItem>
+									))}
+								</SelectContent>
+							</Select>
+						</div>
+						<p className="text-xs text-muted-foreground">
+							{t("sections.power.fields.whenQueueEmptyHint")}
+						</p>
+					</CardContent>
+				</Card>
+
+				{/* ── Updates Section ────────────────────────────────────────────────── */}
+				<Card>
+					<CardHeader>
+						<div className="flex items-center gap-2">
+							<Download className="h-4 w-4 text-muted-foreground" />
+							<CardTitle className="text-base">
+								{t("sections.updates.title")}
+							</CardTitle>
+						</div>
+						<CardDescription>{t("sections.updates.description")}</CardDescription>
+					</CardHeader>
+					<CardContent className="space-y-2">
+						<FieldLabel htmlFor="updates-channel">
+							{t("sections.updates.fields.channel")}
+						</FieldLabel>
+						<Select
+							value={updateChannel}
+							onValueChange={(value) =>
+								updateUpdates("channel", value as UpdateChannel)
+							}
+						>
+							<SelectTrigger id="updates-channel" className="text-xs">
+								<SelectValue />
+							</SelectTrigger>
+							<SelectContent>
+								{UPDATE_CHANNELS.map((channel) => (
+									<SelectItem key={channel} value={channel} className="text-xs">
+										{t(`sections.updates.channels.${channel}`)}
+									</SelectItem>
+								))}
+							</SelectContent>
+						</Select>
+						<p className="text-xs text-muted-foreground">
+							{t("sections.updates.fields.channelHint")}
+						</p>
+						<div className="flex flex-wrap items-center gap-3 pt-2">
+							<Button
+								variant="secondary"
+								size="sm"
+								onClick={() => void handleCheckUpdates()}
+								disabled={checkingUpdates || installingUpdate}
+							>
+								{checkingUpdates ? (
+									<Loader2 className="h-3.5 w-3.5 animate-spin" />
+								) : (
+									<RefreshCw className="h-3.5 w-3.5" />
+								)}
+								{t("sections.updates.actions.check")}
+							</Button>
+							{updateCheck?.update_available &&
+								updateCheck.latest?.updater_manifest_url &&
+								isDesktop && (
+									<Button
+										size="sm"
+										onClick={() => void handleInstallUpdate()}
+										disabled={installingUpdate}
+									>
+										{installingUpdate ? (
+											<Loader2 className="h-3.5 w-3.5 animate-spin" />
+										) : (
+											<Download className="h-3.5 w-3.5" />
+										)}
+										{t("sections.updates.actions.install")}
+									</Button>
+								)}
+						</div>
+						{updateCheck && (
+							<p className="text-sm text-foreground" data-testid="update-status">
+								{updateCheck.update_available && updateCheck.latest
+									? t("sections.updates.status.available", {
+											version: updateCheck.latest.version,
+											current: updateCheck.current_version,
+										})
+									: t("sections.updates.status.upToDate", {
+											current: updateCheck.current_version,
+										})}
+								{updateCheck.update_available && updateCheck.latest && (
+									<>
+										{" "}
+										<a
+											href={updateCheck.latest.url}
+											target="_blank"
+											rel="noreferrer"
+											className="text-primary underline-offset-2 hover:underline"
+										>
+											{t("sections.updates.releaseNotes")}
+										</a>
+									</>
+								)}
+							</p>
+						)}
+						{updateError && (
+							<p className="text-xs text-destructive">{updateError}</p>
+						)}
 					</CardContent>
 				</Card>
 			</div>
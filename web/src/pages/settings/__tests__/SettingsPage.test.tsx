@@ -1,12 +1,13 @@
 import { fireEvent, render, screen, waitFor } from "@testing-library/react";
 import { MemoryRouter } from "react-router";
 import { beforeEach, describe, expect, it, vi } from "vitest";
-import { getConfig, updateConfig } from "@/api/client";
+import { checkForUpdates, getConfig, updateConfig } from "@/api/client";
 import { i18n, initializeI18n } from "@/i18n";
 import type { AppConfig } from "@/types";
 import { SettingsPage } from "../SettingsPage";
 
 vi.mock("@/api/client", () => ({
+	checkForUpdates: vi.fn(),
 	getConfig: vi.fn(),
 	updateConfig: vi.fn(),
 }));
@@ -111,3 +112,84 @@ describe("SettingsPage profiling toggle", () => {
 		expect(screen.queryByText("Something went wrong")).not.toBeInTheDocument();
 	});
 });
+
+describe("SettingsPage power settings", () => {
+	it("defaults prevent sleep on and saves it with the power section", async () => {
+		vi.mocked(getConfig).mockResolvedValue(makeConfig());
+		vi.mocked(updateConfig).mockResolvedValue(
+			makeConfig({ power: { prevent_sleep: false, when_queue_empty: "none" } }),
+		);
+
+		render(
+			<MemoryRouter>
+				<SettingsPage />
+			</MemoryRouter>,
+		);
+
+		await waitFor(() => {
+			expect(screen.getByLabelText("Prevent sleep while jobs run")).toBeChecked();
+		});
+
+		fireEvent.click(screen.getByLabelText("Prevent sleep while jobs run"));
+		fireEvent.click(screen.getByRole("button", { name: "Save" }));
+
+		await waitFor(() => {
+			expect(updateConfig).toHaveBeenCalledWith(
+				expect.objectContaining({
+					power: { prevent_sleep: false, when_queue_empty: "none" },
+				}),
+			);
+		});
+	});
+});
+
+describe("SettingsPage updates", () => {
+	it("checks the configured channel and reports a newer release", async () => {
+		vi.mocked(getConfig).mockResolvedValue(
+			makeConfig({
+				updates: {
+					channel: "beta",
+					releases_url: "https://example.com/releases",
+				},
+			}),
+		);
+		vi.mocked(checkForUpdates).mockResolvedValue({
+			current_version: "0.3.0",
+			channel: "beta",
+			update_available: true,
+			latest: {
+				version: "0.4.0-beta.1",
+				tag: "v0.4.0-beta.1",
+				name: "0.4.0 beta 1",
+				url: "https://example.com/v0.4.0-beta.1",
+				prerelease: true,
+				published_at: null,
+				notes: "",
+				updater_manifest_url: "https://example.com/latest.json",
+			},
+		});
+
+		render(
+			<MemoryRouter>
+				<SettingsPage />
+			</MemoryRouter>,
+		);
+
+		fireEvent.click(
+			await screen.findByRole("button", { name: "Check for updates" }),
+		);
+
+		expect(await screen.findByTestId("update-status")).toHaveTextContent(
+			"Version 0.4.0-beta.1 is available (you have 0.3.0).",
+		);
+		expect(checkForUpdates).toHaveBeenCalledWith("beta");
+		expect(screen.getByRole("link", { name: "Release notes" })).toHaveAttribute(
+			"href",
+			"https://example.com/v0.4.0-beta.1",
+		);
+		// Installing needs the desktop app.
+		expect(
+			screen.queryByRole("button", { name: "Install and restart" }),
+		).not.toBeInTheDocument();
+	});
+});
@@ -17,6 +17,9 @@ function makeJobResponse(overrides: Partial<JobResponse> = {}): JobResponse {
 		params: null,
 		rerun_of_job_id: null,
 		duration_ms: null,
+		outputs: null,
+		tags: [],
+		note: null,
 		...overrides,
 	};
 }
@@ -266,6 +269,35 @@ describe("cancelJob", () => {
 	});
 });
 
+describe("pauseJob / resumeJob", () => {
+	it("posts to the pause and resume endpoints and refreshes jobs", async () => {
+		const mockFetch = vi.mocked(fetch);
+
+		mockFetch.mockResolvedValueOnce(
+			jsonResponse(makeJobResponse({ id: "j1", status: "paused" })),
+		);
+		mockFetch.mockResolvedValueOnce(
+			jsonResponse([makeJobResponse({ id: "j1", status: "paused" })]),
+		);
+		await useJobStore.getState().pauseJob("j1");
+
+		expect(mockFetch.mock.calls[0][0]).toBe("/api/jobs/j1/pause");
+		expect(mockFetch.mock.calls[0][1]).toMatchObject({ method: "POST" });
+		expect(useJobStore.getState().jobs[0].status).toBe("paused");
+
+		mockFetch.mockResolvedValueOnce(
+			jsonResponse(makeJobResponse({ id: "j1", status: "queued" })),
+		);
+		mockFetch.mockResolvedValueOnce(
+			jsonResponse([makeJobResponse({ id: "j1", status: "queued" })]),
+		);
+		await useJobStore.getState().resumeJob("j1");
+
+		expect(mockFetch.mock.calls[2][0]).toBe("/api/jobs/j1/resume");
+		expect(useJobStore.getState().jobs[0].status).toBe("queued");
+	});
+});
+
 describe("subscribeToJob", () => {
 	it("updates active progress for the subscribed job", () => {
 		const cleanup = vi.fn();
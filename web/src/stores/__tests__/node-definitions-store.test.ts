@@ -42,6 +42,7 @@ beforeEach(() => {
     descriptors: [],
     loading: false,
     error: null,
+    locale: null,
   });
 });
 
@@ -80,6 +81,20 @@ describe('fetch', () => {
     expect(fetch).not.toHaveBeenCalled();
   });
 
+  it('re-fetches with Accept-Language when the locale changes', async () => {
+    useNodeDefinitions.setState({ descriptors: MOCK_DESCRIPTORS });
+    vi.mocked(fetch).mockResolvedValueOnce(jsonResponse(MOCK_DESCRIPTORS));
+
+    await useNodeDefinitions.getState().fetch('zh-CN');
+    await useNodeDefinitions.getState().fetch('zh-CN');
+
+    expect(fetch).toHaveBeenCalledTimes(1);
+    expect(fetch).toHaveBeenCalledWith('/api/nodes', {
+      headers: { 'Accept-Language': 'zh-CN' },
+    });
+    expect(useNodeDefinitions.getState().locale).toBe('zh-CN');
+  });
+
   it('sets error on non-ok response', async () => {
     vi.mocked(fetch).mockResolvedValueOnce(
       new Response('Not Found', { status: 404, statusText: 'Not Found' }),
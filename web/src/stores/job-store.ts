@@ -22,6 +22,8 @@ interface JobState {
 	rerunJob: (jobId: string) => Promise<string>;
 	deleteJobHistory: (jobId: string) => Promise<void>;
 	cancelJob: (jobId: string) => Promise<void>;
+	pauseJob: (jobId: string) => Promise<void>;
+	resumeJob: (jobId: string) => Promise<void>;
 	subscribeToJob: (jobId: string) => void;
 	unsubscribeFromJob: () => void;
 }
@@ -44,11 +46,19 @@ export const useJobStore = create<JobState>((set, get) => ({
 			started_at: r.started_at,
 			completed_at: r.completed_at,
 			error: r.error,
+			error_details: r.error_details,
 			workflow_name: r.workflow_name,
 			workflow_source: r.workflow_source,
 			params: r.params,
 			rerun_of_job_id: r.rerun_of_job_id,
 			duration_ms: r.duration_ms,
+			outputs: r.outputs,
+			tags: r.tags,
+			note: r.note,
+			skip_reason: r.skip_reason,
+			paused_at: r.paused_at,
+			artifacts: r.artifacts,
+			encode_stats: r.encode_stats,
 		}));
 		set({ jobs });
 	},
@@ -80,6 +90,16 @@ export const useJobStore = create<JobState>((set, get) => ({
 		await get().deleteJobHistory(jobId);
 	},
 
+	pauseJob: async (jobId) => {
+		await api.pauseJob(jobId);
+		await get().fetchJobs();
+	},
+
+	resumeJob: async (jobId) => {
+		await api.resumeJob(jobId);
+		await get().fetchJobs();
+	},
+
 	subscribeToJob: (jobId) => {
 		const state = get();
 		if (state.activeJobId === jobId && state.wsCleanup) {
@@ -143,6 +163,13 @@ export const useJobStore = create<JobState>((set, get) => ({
 					},
 				}));
 			},
+			(status) => {
+				set((prev) => ({
+					jobs: prev.jobs.map((job) =>
+						job.id === jobId ? { ...job, status } : job,
+					),
+				}));
+			},
 		);
 
 		set({
@@ -2,21 +2,33 @@ import { create } from 'zustand';
 
 export interface PortDescriptor {
   name: string;
+  /** Localized label; falls back to `name`. */
+  label?: string;
+  label_key?: string;
   port_type: string;
   direction: string;
   required: boolean;
   default_value: unknown;
   ui_hint: string | null;
   enum_options: string[] | null;
+  /** Accepts any number of connections, ordered by their `to_index`. */
+  list?: boolean;
   dynamic_type_param: string | null;
 }
 
 export interface NodeDescriptor {
   node_type: string;
   display_name: string;
+  description?: string;
+  label_key?: string;
+  description_key?: string;
   category: string;
   accent_color: string;
   icon: string;
+  experimental?: boolean;
+  /** Hidden from the palette; kept so existing workflows still run. */
+  deprecated?: boolean;
+  replaced_by?: string | null;
   inputs: PortDescriptor[];
   outputs: PortDescriptor[];
 }
@@ -25,21 +37,28 @@ interface NodeDefinitionsState {
   descriptors: NodeDescriptor[];
   loading: boolean;
   error: string | null;
-  fetch: () => Promise<void>;
+  /** Locale the loaded descriptors were requested in. */
+  locale: string | null;
+  fetch: (locale?: string) => Promise<void>;
 }
 
 export const useNodeDefinitions = create<NodeDefinitionsState>((set, get) => ({
   descriptors: [],
   loading: false,
   error: null,
-  fetch: async () => {
-    if (get().descriptors.length > 0) return;
+  locale: null,
+  fetch: async (locale) => {
+    const requested = locale ?? null;
+    if (get().descriptors.length > 0 && get().locale === requested) return;
     set({ loading: true, error: null });
     try {
-      const resp = await fetch('/api/nodes');
+      const resp = await fetch(
+        '/api/nodes',
+        requested ? { headers: { 'Accept-Language': requested } } : undefined,
+      );
       if (!resp.ok) throw new Error(`HTTP ${resp.status}`);
       const data: NodeDescriptor[] = await resp.json();
-      set({ descriptors: data, loading: false });
+      set({ descriptors: data, locale: requested, loading: false });
     } catch (e) {
       set({ error: String(e), loading: false });
     }
@@ -80,12 +80,14 @@ const PORT_TYPE_VALUES = new Set<PortType>([
   'Bool',
   'Path',
   'WorkflowPath',
+  'Json',
 ]);
 
 const STRUCTURED_EXPORT_PARAM_KEYS = new Set<string>([
   'ports',
   'interface_inputs',
   'interface_outputs',
+  'model_inputs',
 ]);
 
 function parseStructuredExportParam(key: string, value: string): unknown {
@@ -189,6 +191,11 @@ function resolvePortType(
         .find((p) => p.name === edge.targetHandle);
       if (match) return match.port_type as PortType;
     }
+    if (targetType === 'ModelInference') {
+      const match = parseIfacePorts(targetNode.data.params, 'model_inputs')
+        .find((p) => p.name === edge.targetHandle);
+      if (match) return match.port_type as PortType;
+    }
     if (targetType === 'StringTemplate') {
       const match = parseStringTemplateDynamicInputs(targetNode.data.params)
         .find((p) => p.name === edge.targetHandle);
@@ -487,7 +494,10 @@ export const useWorkflowStore = create<WorkflowState>((set, get) => ({
       sourceHandle: conn.from_port,
       target: conn.to_node,
       targetHandle: conn.to_port,
-      data: { port_type: conn.port_type },
+      data:
+        conn.to_index === undefined
+          ? { port_type: conn.port_type }
+          : { port_type: conn.port_type, to_index: conn.to_index },
     }));
 
     set({
@@ -557,6 +567,7 @@ export const useWorkflowStore = create<WorkflowState>((set, get) => ({
       to_node: edge.target,
       to_port: edge.targetHandle ?? '',
       port_type: resolvePortType(nodes, edge),
+      ...(typeof edge.data?.to_index === 'number' ? { to_index: edge.data.to_index } : {}),
     }));
 
     const wfInterface = extractWorkflowInterface(nodes);
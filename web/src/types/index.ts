@@ -9,7 +9,8 @@ export type PortType =
   | 'Str'
   | 'Bool'
   | 'Path'
-  | 'WorkflowPath';
+  | 'WorkflowPath'
+  | 'Json';
 
 export const PORT_COLORS: Record<PortType, string> = {
   VideoFrames: '#8B5CF6',
@@ -21,6 +22,7 @@ export const PORT_COLORS: Record<PortType, string> = {
   Bool: '#EF4444',
   Path: '#6B7280',
   WorkflowPath: '#A855F7',
+  Json: '#EC4899',
 };
 
 // ─── Port & Node Definitions ─────────────────────────────────────────────────
@@ -53,12 +55,24 @@ export interface WorkflowConnection {
   to_node: string;
   to_port: string;
   port_type: PortType;
+  /** Position among the connections into a list port. */
+  to_index?: number;
+}
+
+export interface FilePickerHint {
+  mode: 'open' | 'save' | 'directory';
+  extensions?: string[];
 }
 
 export interface WorkflowPort {
   name: string;
   port_type: string;
   default_value?: unknown;
+  description?: string;
+  enum_options?: unknown[];
+  min?: number;
+  max?: number;
+  file_picker?: FilePickerHint;
 }
 
 export interface WorkflowInterface {
@@ -82,15 +96,46 @@ export interface PipelineNodeData {
 
 // ─── Job types ───────────────────────────────────────────────────────────────
 
-export type JobStatus = 'queued' | 'running' | 'completed' | 'failed' | 'cancelled';
+export type JobStatus =
+  | 'queued'
+  | 'running'
+  | 'completed'
+  | 'failed'
+  | 'cancelled'
+  | 'skipped'
+  | 'paused';
 
 export interface ProgressUpdate {
   current_frame: number;
   total_frames: number | null;
   fps: number;
   eta_seconds: number | null;
+  phase?: JobPhase | null;
+  stages?: StageProgress[];
+  hardware?: HardwareUsage | null;
+}
+
+export interface HardwareUsage {
+  cpu_util_percent: number | null;
+  gpu_util_percent: number | null;
+  vram_used_bytes: number | null;
+}
+
+export interface StageProgress {
+  name: string;
+  frames: number;
+  queued: number;
+  queue_capacity: number;
 }
 
+export type JobPhase =
+  | 'loading_model'
+  | 'building_engine'
+  | 'decoding'
+  | 'processing'
+  | 'encoding'
+  | 'muxing';
+
 export interface JobWsProgressEvent extends ProgressUpdate {
   type: 'progress';
 }
@@ -104,7 +149,21 @@ export interface JobWsNodeDebugValueEvent {
   preview_max_chars: number;
 }
 
-export type JobWsEvent = JobWsProgressEvent | JobWsNodeDebugValueEvent;
+export interface JobWsPhaseEvent {
+  type: 'phase';
+  phase: JobPhase;
+}
+
+export interface JobWsStatusEvent {
+  type: 'status';
+  status: JobStatus;
+}
+
+export type JobWsEvent =
+  | JobWsProgressEvent
+  | JobWsPhaseEvent
+  | JobWsStatusEvent
+  | JobWsNodeDebugValueEvent;
 
 export interface NodeRuntimePreview {
   node_id: string;
@@ -123,11 +182,62 @@ export interface Job {
   started_at: string | null;
   completed_at: string | null;
   error: string | null;
+  error_details?: NodeError | null;
   workflow_name?: string;
   workflow_source?: string;
   params?: Record<string, unknown> | null;
   rerun_of_job_id?: string | null;
   duration_ms?: number | null;
+  outputs?: Record<string, unknown> | null;
+  tags?: string[];
+  note?: string | null;
+  post_actions?: PostActionResult[];
+  skip_reason?: string | null;
+  paused_at?: string | null;
+  artifacts?: JobArtifact[];
+  encode_stats?: EncodeStats | null;
+}
+
+export interface EncodeStats {
+  output_bytes: number | null;
+  duration_secs: number | null;
+  average_bit_rate: number | null;
+  speed: number | null;
+  vmaf: number | null;
+}
+
+export interface JobArtifact {
+  node_id: string;
+  path: string;
+  size_bytes: number | null;
+  duration_secs: number | null;
+  bit_rate: number | null;
+  video_codec: string | null;
+  width: number | null;
+  height: number | null;
+  vmaf?: number | null;
+  comparison_frames?: ComparisonFrame[];
+}
+
+export interface ComparisonFrame {
+  time_secs: number;
+  original: string;
+  processed: string;
+}
+
+export interface NodeError {
+  node_id: string | null;
+  node_type: string | null;
+  category: 'io' | 'model' | 'encode' | 'network' | 'other';
+  message: string;
+  hint: string | null;
+}
+
+export interface PostActionResult {
+  action: string;
+  path: string;
+  result_path: string | null;
+  error: string | null;
 }
 
 // ─── API response types (matching backend JSON) ─────────────────────────────
@@ -146,18 +256,39 @@ export interface JobResponse {
   completed_at: string | null;
   progress: ProgressUpdate | null;
   error: string | null;
+  error_details?: NodeError | null;
   workflow_name: string;
   workflow_source: string;
   params: Record<string, unknown> | null;
   rerun_of_job_id: string | null;
   duration_ms: number | null;
+  outputs: Record<string, unknown> | null;
+  tags: string[];
+  note: string | null;
+  post_actions: PostActionResult[];
+  skip_reason?: string | null;
+  paused_at?: string | null;
+  artifacts?: JobArtifact[];
+  encode_stats?: EncodeStats | null;
 }
 
+export type PresetOrigin =
+  | { kind: 'builtin' }
+  | { kind: 'user' }
+  | {
+    kind: 'community';
+    source: string;
+    version: string | null;
+    author: string | null;
+    synced_at: string;
+  };
+
 export interface Preset {
   id: string;
   name: string;
   description: string;
   workflow: Workflow;
+  origin: PresetOrigin;
 }
 
 export interface BatchResponse {
@@ -168,6 +299,7 @@ export interface BatchResponse {
 export interface AppConfig {
   paths: {
     models_dir: string;
+    extra_models_dirs?: string[];
     trt_cache_dir: string;
     presets_dir: string;
     workflows_dir: string;
@@ -175,11 +307,57 @@ export interface AppConfig {
   server: {
     port: number;
     host: string;
+    read_only?: boolean;
   };
   locale: string;
   performance: {
     profiling_enabled: boolean;
+    session_cache_size?: number;
+    warmup?: WarmupModel[];
+    sample_interval_secs?: number;
+    series_retention_hours?: number;
   };
+  power?: PowerSettings;
+  updates?: UpdateSettings;
+}
+
+export type QueueEmptyAction = 'none' | 'suspend' | 'shutdown';
+
+export interface PowerSettings {
+  prevent_sleep: boolean;
+  when_queue_empty: QueueEmptyAction;
+}
+
+export type UpdateChannel = 'stable' | 'beta';
+
+export interface UpdateSettings {
+  channel: UpdateChannel;
+  releases_url: string;
+}
+
+export interface ReleaseInfo {
+  version: string;
+  tag: string;
+  name: string;
+  url: string;
+  prerelease: boolean;
+  published_at: string | null;
+  notes: string;
+  updater_manifest_url: string | null;
+}
+
+export interface UpdateCheck {
+  current_version: string;
+  channel: UpdateChannel;
+  update_available: boolean;
+  latest: ReleaseInfo | null;
+}
+
+export interface WarmupModel {
+  model: string;
+  backend: string;
+  height: number;
+  width: number;
 }
 
 // ─── Preview / Before-After Comparison ───────────────────────────────────────
@@ -198,6 +376,58 @@ export interface ProcessResponse {
   processed_url: string;
 }
 
+export interface MediaProbe {
+  path: string;
+  format: {
+    format_name: string | null;
+    format_long_name: string | null;
+    duration_secs: number | null;
+    size_bytes: number | null;
+    bit_rate: number | null;
+    tags: Record<string, string>;
+  };
+  streams: ProbeStream[];
+  chapters: { start_secs: number | null; end_secs: number | null; title: string | null }[];
+}
+
+export interface ProbeStream {
+  index: number;
+  codec_type: string;
+  codec_name: string | null;
+  profile: string | null;
+  bit_rate: number | null;
+  language: string | null;
+  title: string | null;
+  default: boolean;
+  forced: boolean;
+  video: {
+    width: number;
+    height: number;
+    pix_fmt: string | null;
+    bit_depth: number | null;
+    frame_rate: number | null;
+    frame_count: number | null;
+    interlaced: boolean;
+    attached_pic: boolean;
+    color_range: string | null;
+    color_space: string | null;
+    color_primaries: string | null;
+    color_transfer: string | null;
+    hdr: {
+      format: 'hdr10' | 'hlg' | 'dolby_vision';
+      mastering_display: { min_luminance: number | null; max_luminance: number | null } | null;
+      max_cll: number | null;
+      max_fall: number | null;
+      dolby_vision_profile: number | null;
+    } | null;
+  } | null;
+  audio: {
+    channels: number | null;
+    channel_layout: string | null;
+    sample_rate: number | null;
+  } | null;
+}
+
 export type PerformanceStatus = 'disabled' | 'enabled' | 'degraded' | 'partial';
 
 export interface PerformanceEnvelope {